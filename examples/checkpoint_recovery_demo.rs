@@ -11,7 +11,7 @@
 //! ```
 
 use anyhow::Result;
-use ironbeam::{AverageF64, Count, ExecMode, Pipeline, Runner, Sum, from_vec};
+use ironbeam::{AverageF64, Count, DirectRunner, ExecMode, Pipeline, Sum, from_vec};
 use std::env::args;
 
 #[cfg(feature = "checkpointing")]
@@ -36,12 +36,13 @@ fn run_with_time_based_checkpoints() -> Result<()> {
         policy: CheckpointPolicy::TimeInterval(2), // Every 2 seconds
         auto_recover: true,
         max_checkpoints: Some(3),
+        ..Default::default()
     };
 
     println!("Policy: Checkpoint every 2 seconds");
     println!("Max checkpoints: 3 (oldest will be deleted)\n");
 
-    let runner = Runner {
+    let runner = DirectRunner {
         mode: ExecMode::Sequential,
         checkpoint_config: Some(checkpoint_config),
         ..Default::default()
@@ -74,12 +75,13 @@ fn run_with_node_based_checkpoints() -> Result<()> {
         policy: CheckpointPolicy::EveryNNodes(2), // Every 2 nodes
         auto_recover: true,
         max_checkpoints: Some(5),
+        ..Default::default()
     };
 
     println!("Policy: Checkpoint every 2 nodes");
     println!("This creates more frequent checkpoints for fine-grained recovery\n");
 
-    let runner = Runner {
+    let runner = DirectRunner {
         mode: ExecMode::Sequential,
         checkpoint_config: Some(checkpoint_config),
         ..Default::default()
@@ -121,12 +123,13 @@ fn run_with_hybrid_checkpoints() -> Result<()> {
         },
         auto_recover: true,
         max_checkpoints: Some(10),
+        ..Default::default()
     };
 
     println!("Policy: Checkpoint after barriers OR every 3 seconds");
     println!("This provides the most aggressive checkpointing\n");
 
-    let runner = Runner {
+    let runner = DirectRunner {
         mode: ExecMode::Sequential,
         checkpoint_config: Some(checkpoint_config),
         ..Default::default()
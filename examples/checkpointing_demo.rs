@@ -11,7 +11,7 @@
 //! ```
 
 use anyhow::Result;
-use ironbeam::{ExecMode, Pipeline, Runner, Sum, from_vec};
+use ironbeam::{DirectRunner, ExecMode, Pipeline, Sum, from_vec};
 
 #[cfg(feature = "checkpointing")]
 use ironbeam::checkpoint::{CheckpointConfig, CheckpointPolicy};
@@ -41,6 +41,7 @@ fn main() -> Result<()> {
         policy: CheckpointPolicy::AfterEveryBarrier,
         auto_recover: true,
         max_checkpoints: Some(5),
+        ..Default::default()
     };
 
     println!("\nCheckpoint Configuration:");
@@ -50,7 +51,7 @@ fn main() -> Result<()> {
     println!("  Max checkpoints: 5");
 
     // Create a runner with checkpointing
-    let runner = Runner {
+    let runner = DirectRunner {
         mode: ExecMode::Sequential,
         checkpoint_config: Some(checkpoint_config),
         ..Default::default()
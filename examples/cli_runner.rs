@@ -0,0 +1,59 @@
+//! Example of a pipeline binary built on `ironbeam::cli`.
+//!
+//! Registers two pipelines behind one command line. Try:
+//!
+//! ```text
+//! cargo run --example cli_runner --features cli -- --list
+//! cargo run --example cli_runner --features cli -- squares --param n=20 --partitions 4
+//! cargo run --example cli_runner --features cli -- totals --input orders.jsonl --output totals.csv
+//! cargo run --example cli_runner --features cli -- totals --input orders.jsonl --dry-run
+//! ```
+//!
+//! where `orders.jsonl` holds lines such as `{"region":"eu","cents":1200}`.
+
+#[cfg(feature = "cli")]
+use ironbeam::cli::{Cli, PipelineOutput};
+#[cfg(feature = "cli")]
+use ironbeam::{from_vec, read_jsonl};
+#[cfg(feature = "cli")]
+use serde::{Deserialize, Serialize};
+use std::process::ExitCode;
+
+#[cfg(feature = "cli")]
+#[derive(Clone, Serialize, Deserialize)]
+struct Order {
+    region: String,
+    cents: u64,
+}
+
+#[cfg(feature = "cli")]
+fn main() -> ExitCode {
+    Cli::new("cli_runner")
+        .register(
+            "squares",
+            "Square the numbers 1..=n (--param n=N)",
+            |p, args| {
+                let n: u64 = args.param("n").map_or(Ok(10), str::parse)?;
+                let squares = from_vec(p, (1..=n).collect::<Vec<_>>()).map(|x: &u64| x * x);
+                Ok(PipelineOutput::jsonl(squares))
+            },
+        )
+        .register(
+            "totals",
+            "Sum order value per region of --input",
+            |p, args| {
+                let totals = read_jsonl::<Order>(p, args.input()?)?
+                    .map(|o: &Order| (o.region.clone(), o.cents))
+                    .sum_per_key();
+                Ok(PipelineOutput::csv(totals, false))
+            },
+        )
+        .main()
+}
+
+#[cfg(not(feature = "cli"))]
+fn main() -> ExitCode {
+    println!("This example requires the 'cli' feature.");
+    println!("Run with: cargo run --example cli_runner --features cli -- --list");
+    ExitCode::SUCCESS
+}
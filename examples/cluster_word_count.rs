@@ -0,0 +1,78 @@
+//! Word counting across worker processes with the experimental `ClusterRunner`.
+//!
+//! The binary is its own worker: `ClusterRunner::spawn` re-runs it with the
+//! coordinator's address in the environment, and `worker_main` turns those copies
+//! into workers before `main` builds anything.
+//!
+//! Run with:
+//! ```bash
+//! cargo run --example cluster_word_count --features cluster
+//! ```
+
+use anyhow::Result;
+
+#[cfg(feature = "cluster")]
+use ironbeam::cluster::{ClusterRunner, worker_main};
+#[cfg(feature = "cluster")]
+use ironbeam::spec::DoFnRegistry;
+#[cfg(feature = "cluster")]
+use ironbeam::{Count, PCollection, Pipeline, from_vec};
+#[cfg(feature = "cluster")]
+use serde_json::json;
+
+#[cfg(feature = "cluster")]
+fn registry() -> DoFnRegistry {
+    let mut registry = DoFnRegistry::new();
+    registry
+        .register_source("lines", |p, cfg| {
+            let copies = cfg["copies"].as_u64().unwrap_or(1);
+            let text = [
+                "the quick brown fox jumps over the lazy dog",
+                "the dog barks and the fox runs",
+                "a lazy afternoon for a quick nap",
+            ];
+            let lines = (0..copies)
+                .flat_map(|_| text.iter().map(ToString::to_string))
+                .collect::<Vec<String>>();
+            Ok(from_vec(p, lines))
+        })
+        .register_transform("count_words", |lines: PCollection<String>, _| {
+            Ok(lines
+                .flat_map(|line: &String| {
+                    line.split_whitespace()
+                        .map(str::to_string)
+                        .collect::<Vec<_>>()
+                })
+                .key_by(|word: &String| word.clone())
+                .combine_values(Count))
+        });
+    registry
+}
+
+#[cfg(feature = "cluster")]
+fn main() -> Result<()> {
+    let registry = registry();
+    if worker_main(&registry)? {
+        return Ok(());
+    }
+
+    let p = Pipeline::default();
+    let lines = registry.source::<String>(&p, "lines", json!({ "copies": 10_000 }))?;
+    let counts = registry.apply::<String, (String, u64)>(lines, "count_words", ())?;
+
+    let runner = ClusterRunner::spawn(registry, 3)?;
+    println!("Started {} worker processes", runner.workers());
+
+    let mut out = counts.collect_with(&runner)?;
+    out.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    for (word, n) in out.iter().take(5) {
+        println!("{word:>10} {n}");
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "cluster"))]
+fn main() {
+    println!("This example requires the 'cluster' feature.");
+    println!("Run with: cargo run --example cluster_word_count --features cluster");
+}
@@ -105,6 +105,14 @@ fn example_with_optimizations() -> Result<()> {
             OptimizationDecision::LiftedGBKCombine { removed_barrier } => {
                 println!("✓ Lifted GroupByKey→CombineValues (removed_barrier={removed_barrier})");
             }
+            OptimizationDecision::LiftedGroupAggregation {
+                combines,
+                aggregations,
+            } => {
+                println!(
+                    "✓ Lifted {combines} combine(s) and {aggregations} map_values aggregation(s) into partial aggregation"
+                );
+            }
             OptimizationDecision::DroppedMidMaterialized { count } => {
                 println!("✓ Dropped {count} mid-pipeline materialized nodes");
             }
@@ -159,11 +167,27 @@ fn example_with_optimizations() -> Result<()> {
                     "✓ Bloom semi-join pre-filter: build side={smaller_side}, estimated probe-side reduction={estimated_reduction_pct}%"
                 );
             }
+            OptimizationDecision::CollapsedJoinChain {
+                joins_collapsed,
+                inputs,
+            } => {
+                println!(
+                    "✓ Collapsed {joins_collapsed} nested join(s) into one {inputs}-way co-group"
+                );
+            }
             OptimizationDecision::AdaptivePartitionCount { barrier_count } => {
                 println!(
                     "✓ Adaptive inter-stage partition count: {barrier_count} barrier stage(s) will rescale partition count by cardinality ratio"
                 );
             }
+            OptimizationDecision::HoistedKeyFilters { filters } => {
+                println!(
+                    "✓ Hoisted {filters} key-only filter(s) ahead of costlier key-preserving ops"
+                );
+            }
+            OptimizationDecision::DroppedNoOps { count } => {
+                println!("✓ Dropped {count} pass-through op(s)");
+            }
             OptimizationDecision::EmptySourceShortCircuit => {
                 println!("✓ Empty source short-circuit: runner returns Vec::new() immediately");
             }
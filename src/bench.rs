@@ -0,0 +1,431 @@
+//! Micro-benchmarks for pipelines.
+//!
+//! [`PipelineBench`] builds a pipeline once and runs it repeatedly on the parallel
+//! runner, once per point of a thread-count × partition-count matrix. For each point it
+//! reports wall-clock times, throughput in elements and megabytes per second, and the
+//! mean time of every plan step, taken from the per-transform [metrics](crate::metrics).
+//! It is meant for comparing settings such as `map_batches` sizes or partition counts on
+//! one machine, not as a replacement for a statistics-heavy harness like criterion.
+//!
+//! # Throughput
+//! By default a run processes the elements produced by the first plan step (the source),
+//! and its bytes are that step's estimated output size, `elements × size_of::<T>()` (see
+//! [`TransformMetrics::bytes_out`]). Heap data such as string contents is not counted, so
+//! for pipelines over strings or vectors set the real input size with
+//! [`PipelineBench::input_size`].
+//!
+//! # Thread counts
+//! Each thread count gets its own rayon pool for the duration of its runs; the global
+//! pool is left alone.
+//!
+//! # Example
+//! ```no_run
+//! use ironbeam::*;
+//! use ironbeam::bench::PipelineBench;
+//! # use anyhow::Result;
+//!
+//! # fn main() -> Result<()> {
+//! let report = PipelineBench::new("square batches", |p: &Pipeline| {
+//!     from_vec(p, (0u64..1_000_000).collect::<Vec<_>>())
+//!         .map_batches(4_096, |xs: &[u64]| xs.iter().map(|x| x * x).collect())
+//! })
+//! .iterations(10)
+//! .threads([1, 4])
+//! .partitions([4, 16])
+//! .run()?;
+//!
+//! println!("{report}");
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::StepRef;
+use crate::metrics::{MetricsCollector, TransformMetrics};
+use crate::{DirectRunner, Element, ExecMode, PCollection, Pipeline};
+use anyhow::{Context, Result};
+use rayon::ThreadPoolBuilder;
+use std::fmt::{Display, Formatter, Result as FormatResult};
+use std::hint::black_box;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+type BuildFn<T> = Arc<dyn Fn(&Pipeline) -> PCollection<T> + Send + Sync>;
+
+/// Runs a pipeline repeatedly across thread and partition counts and reports timings.
+///
+/// Built with [`new`](Self::new), configured with the builder methods, and executed by
+/// [`run`](Self::run). See the [module docs](self) for what is measured.
+pub struct PipelineBench<T> {
+    name: String,
+    build: BuildFn<T>,
+    iterations: usize,
+    warmup: usize,
+    threads: Vec<Option<usize>>,
+    partitions: Vec<Option<usize>>,
+    input_size: Option<(u64, u64)>,
+}
+
+impl<T: Element> PipelineBench<T> {
+    /// Benchmark the pipeline that `build` adds to the given [`Pipeline`], ending in the
+    /// returned collection.
+    #[must_use]
+    pub fn new<F>(name: impl Into<String>, build: F) -> Self
+    where
+        F: Fn(&Pipeline) -> PCollection<T> + Send + Sync + 'static,
+    {
+        Self {
+            name: name.into(),
+            build: Arc::new(build),
+            iterations: 5,
+            warmup: 1,
+            threads: vec![None],
+            partitions: vec![None],
+            input_size: None,
+        }
+    }
+
+    /// Set the number of timed runs per matrix point (default `5`, minimum `1`).
+    #[must_use]
+    pub fn iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations.max(1);
+        self
+    }
+
+    /// Set the number of untimed runs before the timed ones at each matrix point
+    /// (default `1`).
+    #[must_use]
+    pub const fn warmup(mut self, warmup: usize) -> Self {
+        self.warmup = warmup;
+        self
+    }
+
+    /// Set the thread counts to measure. By default the runs use rayon's global pool.
+    /// Zeros are treated as `1`.
+    #[must_use]
+    pub fn threads(mut self, threads: impl IntoIterator<Item = usize>) -> Self {
+        self.threads = threads.into_iter().map(|t| Some(t.max(1))).collect();
+        if self.threads.is_empty() {
+            self.threads.push(None);
+        }
+        self
+    }
+
+    /// Set the partition counts to measure. By default the runner picks the count as it
+    /// would for [`collect_par`](crate::PCollection::collect_par). Zeros are treated as
+    /// `1`.
+    #[must_use]
+    pub fn partitions(mut self, partitions: impl IntoIterator<Item = usize>) -> Self {
+        self.partitions = partitions.into_iter().map(|p| Some(p.max(1))).collect();
+        if self.partitions.is_empty() {
+            self.partitions.push(None);
+        }
+        self
+    }
+
+    /// Set the elements and bytes one run processes, used for throughput instead of the
+    /// source step's output (see [Throughput](self#throughput)).
+    #[must_use]
+    pub const fn input_size(mut self, elements: u64, bytes: u64) -> Self {
+        self.input_size = Some((elements, bytes));
+        self
+    }
+
+    /// Run every point of the matrix and return the timings, in matrix order (thread
+    /// counts outermost).
+    ///
+    /// # Errors
+    /// Returns an error if a thread pool cannot be built or a run fails.
+    pub fn run(&self) -> Result<BenchReport> {
+        let p = Pipeline::default();
+        let out = (self.build)(&p);
+        let metrics = MetricsCollector::new();
+        p.set_metrics(metrics.clone());
+
+        let mut results = Vec::with_capacity(self.threads.len() * self.partitions.len());
+        for &threads in &self.threads {
+            let pool = match threads {
+                Some(n) => Some(
+                    ThreadPoolBuilder::new()
+                        .num_threads(n)
+                        .build()
+                        .with_context(|| format!("build a {n}-thread pool"))?,
+                ),
+                None => None,
+            };
+            for &partitions in &self.partitions {
+                let point = || self.measure(&p, &out, &metrics, threads, partitions);
+                let result = match &pool {
+                    Some(pool) => pool.install(point),
+                    None => point(),
+                }?;
+                results.push(result);
+            }
+        }
+
+        Ok(BenchReport {
+            name: self.name.clone(),
+            iterations: self.iterations,
+            results,
+        })
+    }
+
+    /// Warm up and time one matrix point.
+    fn measure(
+        &self,
+        p: &Pipeline,
+        out: &PCollection<T>,
+        metrics: &MetricsCollector,
+        threads: Option<usize>,
+        partitions: Option<usize>,
+    ) -> Result<BenchResult> {
+        let runner = DirectRunner {
+            mode: ExecMode::Parallel {
+                threads: None,
+                partitions,
+            },
+            ..Default::default()
+        };
+        let describe = || {
+            format!(
+                "benchmark run ({} threads, {} partitions)",
+                count(threads),
+                count(partitions)
+            )
+        };
+
+        for _ in 0..self.warmup {
+            black_box(runner.run_collect::<T>(p, out.id).with_context(describe)?);
+        }
+
+        let mut times = Vec::with_capacity(self.iterations);
+        let mut steps: Vec<Vec<TransformMetrics>> = Vec::with_capacity(self.iterations);
+        for _ in 0..self.iterations {
+            let start = Instant::now();
+            let result = runner.run_collect::<T>(p, out.id).with_context(describe)?;
+            times.push(start.elapsed());
+            black_box(result);
+            steps.push(metrics.report().per_transform().to_vec());
+        }
+
+        let (elements, bytes) = self.input_size.unwrap_or_else(|| {
+            steps[0].first().map_or((0, 0), |source| {
+                (
+                    source.elements_out.unwrap_or(0),
+                    source.bytes_out.unwrap_or(0),
+                )
+            })
+        });
+
+        Ok(BenchResult {
+            threads,
+            partitions,
+            times,
+            elements,
+            bytes,
+            stages: stage_timings(&steps),
+        })
+    }
+}
+
+/// Average each plan step's wall time over the iterations. Every iteration runs the same
+/// plan, so steps line up by position.
+fn stage_timings(steps: &[Vec<TransformMetrics>]) -> Vec<StageTiming> {
+    let Some(first) = steps.first() else {
+        return Vec::new();
+    };
+    first
+        .iter()
+        .enumerate()
+        .map(|(i, step)| {
+            let times: Vec<Duration> = steps
+                .iter()
+                .filter_map(|run| run.get(i).map(|t| t.wall_time))
+                .collect();
+            StageTiming {
+                step: step.node.clone(),
+                mean: mean(&times),
+                min: times.iter().min().copied().unwrap_or_default(),
+                max: times.iter().max().copied().unwrap_or_default(),
+            }
+        })
+        .collect()
+}
+
+fn mean(times: &[Duration]) -> Duration {
+    let total: Duration = times.iter().sum();
+    u32::try_from(times.len())
+        .ok()
+        .filter(|&n| n > 0)
+        .map_or(Duration::ZERO, |n| total / n)
+}
+
+/// `"default"` for an unset thread or partition count.
+fn count(n: Option<usize>) -> String {
+    n.map_or_else(|| "default".to_string(), |n| n.to_string())
+}
+
+fn millis(d: Duration) -> f64 {
+    d.as_secs_f64() * 1_000.0
+}
+
+/// Timings of every matrix point of one [`PipelineBench::run`].
+///
+/// Its [`Display`] form is a table with one row per point followed by each point's
+/// per-step times.
+#[derive(Debug, Clone)]
+pub struct BenchReport {
+    /// The benchmark name.
+    pub name: String,
+    /// Timed runs per matrix point.
+    pub iterations: usize,
+    /// One result per matrix point, thread counts outermost.
+    pub results: Vec<BenchResult>,
+}
+
+impl BenchReport {
+    /// The point with the shortest median time.
+    #[must_use]
+    pub fn fastest(&self) -> Option<&BenchResult> {
+        self.results.iter().min_by_key(|r| r.median())
+    }
+
+    /// The result for a thread and partition count (`None` for the defaults).
+    #[must_use]
+    pub fn result(
+        &self,
+        threads: Option<usize>,
+        partitions: Option<usize>,
+    ) -> Option<&BenchResult> {
+        self.results
+            .iter()
+            .find(|r| r.threads == threads && r.partitions == partitions)
+    }
+}
+
+impl Display for BenchReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        writeln!(f, "bench '{}' ({} iterations)", self.name, self.iterations)?;
+        writeln!(
+            f,
+            "{:>8} {:>10} {:>12} {:>12} {:>14} {:>10}",
+            "threads", "partitions", "median ms", "min ms", "elems/s", "MB/s"
+        )?;
+        for r in &self.results {
+            writeln!(
+                f,
+                "{:>8} {:>10} {:>12.3} {:>12.3} {:>14.0} {:>10.2}",
+                count(r.threads),
+                count(r.partitions),
+                millis(r.median()),
+                millis(r.min()),
+                r.elements_per_sec(),
+                r.mb_per_sec()
+            )?;
+        }
+        for r in &self.results {
+            writeln!(
+                f,
+                "stages ({} threads, {} partitions):",
+                count(r.threads),
+                count(r.partitions)
+            )?;
+            for stage in &r.stages {
+                writeln!(f, "  {stage}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Timings of one matrix point.
+#[derive(Debug, Clone)]
+pub struct BenchResult {
+    /// Thread count, or `None` for rayon's global pool.
+    pub threads: Option<usize>,
+    /// Partition count, or `None` for the runner's default.
+    pub partitions: Option<usize>,
+    /// Wall-clock time of each timed run, in run order.
+    pub times: Vec<Duration>,
+    /// Elements processed per run (see [Throughput](self#throughput)).
+    pub elements: u64,
+    /// Bytes processed per run (see [Throughput](self#throughput)).
+    pub bytes: u64,
+    /// Per-step times of the optimized plan, in execution order.
+    pub stages: Vec<StageTiming>,
+}
+
+impl BenchResult {
+    /// Mean run time.
+    #[must_use]
+    pub fn mean(&self) -> Duration {
+        mean(&self.times)
+    }
+
+    /// Median run time (the lower middle for an even number of runs).
+    #[must_use]
+    pub fn median(&self) -> Duration {
+        let mut sorted = self.times.clone();
+        sorted.sort_unstable();
+        sorted
+            .get(sorted.len().saturating_sub(1) / 2)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Fastest run time.
+    #[must_use]
+    pub fn min(&self) -> Duration {
+        self.times.iter().min().copied().unwrap_or_default()
+    }
+
+    /// Slowest run time.
+    #[must_use]
+    pub fn max(&self) -> Duration {
+        self.times.iter().max().copied().unwrap_or_default()
+    }
+
+    /// Elements per second at the median run time.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn elements_per_sec(&self) -> f64 {
+        per_sec(self.elements as f64, self.median())
+    }
+
+    /// Megabytes (10⁶ bytes) per second at the median run time.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn mb_per_sec(&self) -> f64 {
+        per_sec(self.bytes as f64 / 1_000_000.0, self.median())
+    }
+}
+
+fn per_sec(amount: f64, time: Duration) -> f64 {
+    let secs = time.as_secs_f64();
+    if secs > 0.0 { amount / secs } else { 0.0 }
+}
+
+/// Time spent in one step of the optimized plan, over a point's timed runs.
+#[derive(Debug, Clone)]
+pub struct StageTiming {
+    /// The plan step.
+    pub step: StepRef,
+    /// Mean wall time.
+    pub mean: Duration,
+    /// Shortest wall time.
+    pub min: Duration,
+    /// Longest wall time.
+    pub max: Duration,
+}
+
+impl Display for StageTiming {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        write!(
+            f,
+            "{}: mean {:.3} ms (min {:.3}, max {:.3})",
+            self.step,
+            millis(self.mean),
+            millis(self.min),
+            millis(self.max)
+        )
+    }
+}
@@ -1,7 +1,7 @@
 //! In-tree Bloom filter for the Bloom semi-join optimization.
 //!
-//! This module provides a lightweight, ephemeral Bloom filter designed for
-//! one-shot use during [`crate::node::Node::CoGroup`] execution:
+//! This module provides a lightweight Bloom filter. Internally it is used
+//! one-shot during [`crate::node::Node::CoGroup`] execution:
 //!
 //! 1. **Build** from the smaller (or semantically-required) join side's keys.
 //! 2. **Query** for each element of the other side before the hash-join phase.
@@ -22,11 +22,20 @@
 //! false positives only mean a few extra elements reach the hash-join step;
 //! false negatives are impossible, so join correctness is guaranteed.
 //!
-//! ## Non-persistence guarantee
+//! Pipelines can also build one explicitly with the
+//! [`BloomFilterBuilder`](crate::combiners::BloomFilterBuilder) combiner and
+//! pre-filter a keyed collection with
+//! [`filter_with_bloom`](crate::PCollection::filter_with_bloom).
 //!
-//! The filter is not serializable and must not be stored across pipeline runs.
-//! It is valid only for the duration of a single `exec` call.
+//! ## Persistence
+//!
+//! The filter is `Serialize + Deserialize` and filters of equal size merge by
+//! bitwise OR. Positions come from the zero-keyed std `DefaultHasher`, so a
+//! stored filter is only meaningful to binaries built with the same Rust
+//! toolchain.
 
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
 use std::array::from_fn;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
@@ -48,6 +57,7 @@ const MIN_BITS: usize = 64;
 ///   inserted** — safe to discard the element.
 /// - [`BloomFilter::might_contain`] returning `true` means the key *probably* was
 ///   inserted (false-positive rate ≈ 1 %).
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct BloomFilter {
     /// Bit array packed into 64-bit words.
     bits: Vec<u64>,
@@ -91,6 +101,32 @@ impl BloomFilter {
             .iter()
             .all(|&pos| self.bits[pos / 64] & (1u64 << (pos % 64)) != 0)
     }
+
+    /// Number of addressable bits in the filter.
+    #[must_use]
+    pub const fn num_bits(&self) -> usize {
+        self.m
+    }
+
+    /// Union `other` into `self`, so the result might contain every key
+    /// inserted into either filter.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the filters were sized differently.
+    pub fn merge(&mut self, other: &Self) -> Result<()> {
+        if self.m != other.m {
+            bail!(
+                "cannot merge Bloom filters of {} and {} bits",
+                self.m,
+                other.m
+            );
+        }
+        for (a, &b) in self.bits.iter_mut().zip(&other.bits) {
+            *a |= b;
+        }
+        Ok(())
+    }
 }
 
 /// Compute `K` independent bit positions for `key` in a bit array of size `m`.
@@ -0,0 +1,115 @@
+//! Cooperative cancellation and timeouts for pipeline runs.
+//!
+//! A run started with [`DirectRunner::run_with_cancellation`](crate::DirectRunner::run_with_cancellation)
+//! stops once its [`CancellationToken`] is cancelled, and a run on a runner with a
+//! [`timeout`](crate::DirectRunner::timeout) stops once the timeout has elapsed. Either way the
+//! run returns [`IronbeamError::Cancelled`](crate::IronbeamError::Cancelled) naming the
+//! step it stopped at.
+//!
+//! Cancellation is cooperative. The runner checks before every plan step and, within a
+//! fused run of stateless transforms, before each transform is applied to each
+//! partition. Work already inside a single transform call or a barrier (a group-by-key
+//! merge, a sort, a join) finishes before the check sees the cancellation.
+//!
+//! # Example
+//! ```no_run
+//! use ironbeam::*;
+//! use std::thread;
+//! use std::time::Duration;
+//!
+//! let p = Pipeline::default();
+//! let out = from_vec(&p, (0u64..1_000_000).collect::<Vec<_>>()).map(|x: &u64| x * 2);
+//!
+//! let token = CancellationToken::new();
+//! let canceller = token.clone();
+//! thread::spawn(move || {
+//!     thread::sleep(Duration::from_millis(10));
+//!     canceller.cancel();
+//! });
+//!
+//! match DirectRunner::default().run_with_cancellation::<u64>(&p, out.node_id(), &token) {
+//!     Ok(values) => println!("finished with {} values", values.len()),
+//!     Err(err) => println!("stopped: {err}"),
+//! }
+//! ```
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+/// A shareable flag that asks a running pipeline to stop.
+///
+/// Clones share the flag, so one clone can be handed to the run and another kept by
+/// whoever decides to cancel it (a request handler, a job scheduler, a signal handler).
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancellationToken {
+    /// A token that has not been cancelled.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask every run using this token to stop. Cancelling twice has no further effect.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether [`cancel`](Self::cancel) has been called on this token or a clone.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst)
+    }
+}
+
+/// Why a run was cancelled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CancelReason {
+    /// The run's [`CancellationToken`] was cancelled.
+    Requested,
+    /// The runner's [`timeout`](crate::DirectRunner::timeout), given here, elapsed.
+    TimedOut(Duration),
+}
+
+/// The token and deadline one run checks.
+pub(crate) struct CancelCheck {
+    token: Option<CancellationToken>,
+    deadline: Option<(Instant, Duration)>,
+}
+
+impl CancelCheck {
+    /// A check for `token` and a `timeout` counted from now, or `None` if there is
+    /// neither.
+    pub(crate) fn new(
+        token: Option<&CancellationToken>,
+        timeout: Option<Duration>,
+    ) -> Option<Self> {
+        if token.is_none() && timeout.is_none() {
+            return None;
+        }
+        Some(Self {
+            token: token.cloned(),
+            deadline: timeout.map(|t| (Instant::now() + t, t)),
+        })
+    }
+
+    /// Why the run should stop, if it should.
+    pub(crate) fn reason(&self) -> Option<CancelReason> {
+        if self
+            .token
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+        {
+            return Some(CancelReason::Requested);
+        }
+        match self.deadline {
+            Some((deadline, timeout)) if Instant::now() >= deadline => {
+                Some(CancelReason::TimedOut(timeout))
+            }
+            _ => None,
+        }
+    }
+}
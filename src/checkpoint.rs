@@ -29,9 +29,10 @@
 //!     policy: CheckpointPolicy::AfterEveryBarrier,
 //!     auto_recover: true,
 //!     max_checkpoints: Some(5),
+//!     compression: Compression::Zstd { level: 3 },
 //! };
 //!
-//! let runner = Runner {
+//! let runner = DirectRunner {
 //!     mode: ExecMode::Parallel { threads: None, partitions: None },
 //!     checkpoint_config: Some(checkpoint_config),
 //!     ..Default::default()
@@ -42,9 +43,38 @@
 //! # Ok(())
 //! # }
 //! ```
-
+//!
+//! # Resuming after a barrier
+//!
+//! With the `coders` feature, a checkpoint taken right after a barrier (`GroupByKey`,
+//! `CombineValues`, a join, …) also stores that barrier's output partitions, encoded
+//! with the step's [`ElementCoder`]. When `auto_recover`
+//! finds such a checkpoint for the same plan, the runner decodes the partitions and
+//! resumes with the step after the barrier instead of rerunning the pipeline.
+//! Checkpoints without data (non-barrier steps, or builds without `coders`) still
+//! record progress but are never resumed from.
+//!
+//! Checkpoint files start with a format header (see [`CHECKPOINT_FORMAT_VERSION`]).
+//! The body after it is compressed with [`CheckpointConfig::compression`] (zstd by
+//! default when the `compression-zstd` feature is on), which keeps large barrier
+//! outputs small on disk. Both the progress metadata and the stored partitions carry SHA-256 checksums
+//! that [`CheckpointManager::load_checkpoint_with_data`] verifies before anything is
+//! restored. A checkpoint that fails validation is skipped.
+
+#[cfg(all(feature = "checkpointing", feature = "coders"))]
+use crate::coders::ElementCoder;
+#[cfg(feature = "checkpointing")]
+use crate::io::compression::{Compression, compress_to_vec, decompress_to_vec};
+#[cfg(feature = "checkpointing")]
+use crate::node::Node;
 #[cfg(feature = "checkpointing")]
-use anyhow::{Context, Result, anyhow};
+use crate::pipeline::Pipeline;
+#[cfg(feature = "checkpointing")]
+use crate::planner::Plan;
+#[cfg(feature = "checkpointing")]
+use crate::type_token::Partition;
+#[cfg(feature = "checkpointing")]
+use anyhow::{Context, Result, anyhow, bail};
 #[cfg(feature = "checkpointing")]
 use postcard;
 #[cfg(feature = "checkpointing")]
@@ -52,14 +82,30 @@ use serde::{Deserialize, Serialize};
 #[cfg(feature = "checkpointing")]
 use sha2::{Digest, Sha256};
 #[cfg(feature = "checkpointing")]
-use std::fs::{DirEntry, File, create_dir_all, read_dir, remove_file};
+use std::fs::{DirEntry, File, create_dir_all, read_dir, remove_file, rename};
 #[cfg(feature = "checkpointing")]
 use std::io::{Read, Write};
 #[cfg(feature = "checkpointing")]
 use std::path::{Path, PathBuf};
 #[cfg(feature = "checkpointing")]
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "checkpointing")]
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
+/// Version of the checkpoint file format written by [`CheckpointManager`].
+///
+/// Files start with the magic bytes `IBCK` followed by this version as a little-endian
+/// `u32`, then a flag byte that is `1` when the postcard body is compressed (the codec
+/// is recognized from its magic bytes on load) and `0` otherwise. Version 2 files (no
+/// flag byte, uncompressed body) and version 1 files (a bare postcard-encoded
+/// [`CheckpointState`], written before the header existed) are still readable; files
+/// from a newer version are rejected.
+#[cfg(feature = "checkpointing")]
+pub const CHECKPOINT_FORMAT_VERSION: u32 = 3;
+
+#[cfg(feature = "checkpointing")]
+const CHECKPOINT_MAGIC: &[u8; 4] = b"IBCK";
+
 /// Configuration for checkpoint behavior.
 ///
 /// Controls when, where, and how checkpoints are created during pipeline execution.
@@ -77,6 +123,10 @@ pub struct CheckpointConfig {
     /// Maximum number of checkpoints to retain (oldest are deleted first).
     /// None means keep all checkpoints.
     pub max_checkpoints: Option<usize>,
+    /// Codec used for checkpoint files. [`Compression::None`] and
+    /// [`Compression::Auto`] store them uncompressed. Defaults to zstd level 3 when
+    /// the `compression-zstd` feature is enabled, and to no compression otherwise.
+    pub compression: Compression,
 }
 
 #[cfg(feature = "checkpointing")]
@@ -88,6 +138,11 @@ impl Default for CheckpointConfig {
             policy: CheckpointPolicy::AfterEveryBarrier,
             auto_recover: true,
             max_checkpoints: Some(10),
+            compression: if cfg!(feature = "compression-zstd") {
+                Compression::Zstd { level: 3 }
+            } else {
+                Compression::None
+            },
         }
     }
 }
@@ -109,8 +164,8 @@ pub enum CheckpointPolicy {
 /// Checkpoint state containing execution progress and intermediate results.
 ///
 /// This is the serializable snapshot of a running pipeline at a specific point.
-/// Due to type-erasure in Partition buffers, checkpoints track only progress
-/// markers; the pipeline re-executes from the last completed barrier on recovery.
+/// The output partitions of the completed step, when they were persisted, travel
+/// alongside it as [`CheckpointData`].
 #[derive(Serialize, Deserialize)]
 #[cfg(feature = "checkpointing")]
 pub struct CheckpointState {
@@ -142,6 +197,53 @@ pub struct CheckpointMetadata {
     pub progress_percent: u8,
 }
 
+/// Serialized output partitions of the step a checkpoint was taken after.
+///
+/// Each partition is one blob produced by
+/// [`ElementCoder::encode_partition`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[cfg(feature = "checkpointing")]
+pub struct CheckpointData {
+    /// Encoded partitions, in partition order.
+    pub partitions: Vec<Vec<u8>>,
+    /// SHA-256 checksum over every partition, for integrity verification.
+    pub checksum: String,
+}
+
+#[cfg(feature = "checkpointing")]
+impl CheckpointData {
+    /// Wrap encoded partitions, computing their checksum.
+    #[must_use]
+    pub fn new(partitions: Vec<Vec<u8>>) -> Self {
+        let checksum = data_checksum(&partitions);
+        Self {
+            partitions,
+            checksum,
+        }
+    }
+
+    /// Whether the stored checksum matches the partitions.
+    #[must_use]
+    pub fn verify(&self) -> bool {
+        data_checksum(&self.partitions) == self.checksum
+    }
+}
+
+/// On-disk body of a checkpoint file, after the format header.
+#[derive(Serialize)]
+#[cfg(feature = "checkpointing")]
+struct CheckpointFileRef<'a> {
+    state: &'a CheckpointState,
+    data: Option<&'a CheckpointData>,
+}
+
+#[derive(Deserialize)]
+#[cfg(feature = "checkpointing")]
+struct CheckpointFile {
+    state: CheckpointState,
+    data: Option<CheckpointData>,
+}
+
 /// Manages checkpoint creation, persistence, and recovery.
 #[cfg(feature = "checkpointing")]
 pub struct CheckpointManager {
@@ -210,16 +312,46 @@ impl CheckpointManager {
     ///
     /// Returns an error if the checkpoint file cannot be created or written to.
     pub fn save_checkpoint(&mut self, state: &CheckpointState) -> Result<PathBuf> {
+        self.save_checkpoint_with_data(state, None)
+    }
+
+    /// Save a checkpoint to disk together with the output partitions of the completed
+    /// step, so a later run can resume from it.
+    ///
+    /// The file is written under a temporary name and renamed into place, so a crash
+    /// mid-write never leaves a truncated checkpoint behind.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the checkpoint file cannot be created or written to.
+    pub fn save_checkpoint_with_data(
+        &mut self,
+        state: &CheckpointState,
+        data: Option<&CheckpointData>,
+    ) -> Result<PathBuf> {
         let filename = format!("checkpoint_{}_{}.bin", state.pipeline_id, state.timestamp);
         let path = self.config.directory.join(&filename);
+        let tmp_path = path.with_extension("tmp");
 
-        let encoded = postcard::to_allocvec(state).context("Failed to serialize checkpoint")?;
-
-        let mut file = File::create(&path).context("Failed to create checkpoint file")?;
+        let compressed = !matches!(
+            self.config.compression,
+            Compression::None | Compression::Auto
+        );
+        let mut encoded = Vec::from(*CHECKPOINT_MAGIC);
+        encoded.extend_from_slice(&CHECKPOINT_FORMAT_VERSION.to_le_bytes());
+        encoded.push(u8::from(compressed));
+        let body = postcard::to_allocvec(&CheckpointFileRef { state, data })
+            .context("Failed to serialize checkpoint")?;
+        let body = compress_to_vec(&body, self.config.compression)
+            .context("Failed to compress checkpoint")?;
+        encoded.extend_from_slice(&body);
+
+        let mut file = File::create(&tmp_path).context("Failed to create checkpoint file")?;
         file.write_all(&encoded)
             .context("Failed to write checkpoint")?;
         file.sync_all()
             .context("Failed to sync checkpoint to disk")?;
+        rename(&tmp_path, &path).context("Failed to move checkpoint into place")?;
 
         self.last_checkpoint_time = Some(SystemTime::now());
 
@@ -234,15 +366,40 @@ impl CheckpointManager {
     ///
     /// Returns an error if the checkpoint directory cannot be read or if no valid checkpoint is found.
     pub fn find_latest_checkpoint(&self, pipeline_id: &str) -> Result<Option<PathBuf>> {
-        if !self.config.enabled {
-            return Ok(None);
-        }
+        Ok(self.list_checkpoints(pipeline_id)?.pop())
+    }
 
-        if !self.config.directory.exists() {
-            return Ok(None);
+    /// List every checkpoint for a given pipeline, oldest first.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the checkpoint directory cannot be read.
+    pub fn list_checkpoints(&self, pipeline_id: &str) -> Result<Vec<PathBuf>> {
+        if !self.config.enabled || !self.config.directory.exists() {
+            return Ok(Vec::new());
         }
+        Ok(self
+            .checkpoint_entries(pipeline_id)?
+            .iter()
+            .map(DirEntry::path)
+            .collect())
+    }
 
+    /// Checkpoint files of `pipeline_id` in the checkpoint directory, sorted by the
+    /// timestamp encoded in their names.
+    fn checkpoint_entries(&self, pipeline_id: &str) -> Result<Vec<DirEntry>> {
         let prefix = format!("checkpoint_{pipeline_id}_");
+        let timestamp = |entry: &DirEntry| {
+            entry
+                .file_name()
+                .to_str()
+                .and_then(|name| {
+                    name.strip_prefix(&prefix)
+                        .and_then(|s| s.strip_suffix(".bin"))
+                        .and_then(|s| s.parse::<u64>().ok())
+                })
+                .unwrap_or(0)
+        };
         let mut checkpoints: Vec<_> = read_dir(&self.config.directory)
             .context("Failed to read checkpoint directory")?
             .filter_map(Result::ok)
@@ -255,40 +412,61 @@ impl CheckpointManager {
                 })
             })
             .collect();
-
-        if checkpoints.is_empty() {
-            return Ok(None);
-        }
-
-        // Sort by timestamp (encoded in filename)
-        checkpoints.sort_by_key(|entry| {
-            entry
-                .file_name()
-                .to_str()
-                .and_then(|name| {
-                    name.strip_prefix(&prefix)
-                        .and_then(|s| s.strip_suffix(".bin"))
-                        .and_then(|s| s.parse::<u64>().ok())
-                })
-                .unwrap_or(0)
-        });
-
-        Ok(checkpoints.last().map(DirEntry::path))
+        checkpoints.sort_by_key(timestamp);
+        Ok(checkpoints)
     }
 
     /// Load and verify a checkpoint from persistent storage.
     ///
     /// # Errors
     ///
-    /// Returns an error if the checkpoint file cannot be read or if the checksum verification fails.
+    /// Returns an error if the checkpoint file cannot be read, uses an unsupported
+    /// format version, or if checksum verification fails.
     pub fn load_checkpoint(&self, path: &Path) -> Result<CheckpointState> {
+        self.load_checkpoint_with_data(path).map(|(state, _)| state)
+    }
+
+    /// Load and verify a checkpoint along with its stored output partitions, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the checkpoint file cannot be read, uses an unsupported
+    /// format version, or if the metadata or data checksum does not match.
+    pub fn load_checkpoint_with_data(
+        &self,
+        path: &Path,
+    ) -> Result<(CheckpointState, Option<CheckpointData>)> {
         let mut file = File::open(path).context("Failed to open checkpoint file")?;
         let mut encoded = Vec::new();
         file.read_to_end(&mut encoded)
             .context("Failed to read checkpoint")?;
 
-        let state: CheckpointState =
-            postcard::from_bytes(&encoded).context("Failed to deserialize checkpoint")?;
+        let (state, data) = if let Some(rest) = encoded.strip_prefix(CHECKPOINT_MAGIC) {
+            let (version, body) = rest
+                .split_first_chunk::<4>()
+                .ok_or_else(|| anyhow!("Checkpoint header is truncated"))?;
+            let body = match u32::from_le_bytes(*version) {
+                2 => body.to_vec(),
+                CHECKPOINT_FORMAT_VERSION => match body.split_first() {
+                    Some((0, body)) => body.to_vec(),
+                    Some((1, body)) => decompress_to_vec(body.to_vec())
+                        .context("Failed to decompress checkpoint")?,
+                    Some((flag, _)) => bail!("Unknown checkpoint compression flag {flag}"),
+                    None => bail!("Checkpoint header is truncated"),
+                },
+                version => bail!(
+                    "Unsupported checkpoint format version {version} (expected {CHECKPOINT_FORMAT_VERSION})"
+                ),
+            };
+            let file: CheckpointFile =
+                postcard::from_bytes(&body).context("Failed to deserialize checkpoint")?;
+            (file.state, file.data)
+        } else {
+            // Version 1: a bare `CheckpointState`, written before the header existed.
+            let state: CheckpointState =
+                postcard::from_bytes(&encoded).context("Failed to deserialize checkpoint")?;
+            (state, None)
+        };
 
         let metadata_str = format!(
             "{}:{}:{}:{}",
@@ -300,8 +478,20 @@ impl CheckpointManager {
                 "Checkpoint integrity check failed: checksum mismatch"
             ));
         }
+        if let Some(data) = &data {
+            if data.partitions.len() != state.partition_count {
+                bail!(
+                    "Checkpoint integrity check failed: {} partitions stored, {} expected",
+                    data.partitions.len(),
+                    state.partition_count
+                );
+            }
+            if !data.verify() {
+                bail!("Checkpoint integrity check failed: data checksum mismatch");
+            }
+        }
 
-        Ok(state)
+        Ok((state, data))
     }
 
     /// Delete old checkpoints beyond the retention limit.
@@ -310,36 +500,11 @@ impl CheckpointManager {
             return Ok(());
         };
 
-        let prefix = format!("checkpoint_{pipeline_id}_");
-        let mut checkpoints: Vec<_> = read_dir(&self.config.directory)
-            .context("Failed to read checkpoint directory")?
-            .filter_map(Result::ok)
-            .filter(|entry| {
-                entry.file_name().to_str().is_some_and(|name| {
-                    name.starts_with(&prefix)
-                        && Path::new(name)
-                            .extension()
-                            .is_some_and(|ext| ext.eq_ignore_ascii_case("bin"))
-                })
-            })
-            .collect();
-
+        let checkpoints = self.checkpoint_entries(pipeline_id)?;
         if checkpoints.len() <= max_checkpoints {
             return Ok(());
         }
 
-        checkpoints.sort_by_key(|entry| {
-            entry
-                .file_name()
-                .to_str()
-                .and_then(|name| {
-                    name.strip_prefix(&prefix)
-                        .and_then(|s| s.strip_suffix(".bin"))
-                        .and_then(|s| s.parse::<u64>().ok())
-                })
-                .unwrap_or(0)
-        });
-
         let to_delete = checkpoints.len() - max_checkpoints;
         for entry in checkpoints.iter().take(to_delete) {
             remove_file(entry.path()).ok(); // Ignore errors
@@ -354,21 +519,7 @@ impl CheckpointManager {
     ///
     /// Returns an error if the checkpoint directory cannot be read or if any checkpoint file cannot be deleted.
     pub fn clear_checkpoints(&self, pipeline_id: &str) -> Result<()> {
-        let prefix = format!("checkpoint_{pipeline_id}_");
-        let checkpoints: Vec<_> = read_dir(&self.config.directory)
-            .context("Failed to read checkpoint directory")?
-            .filter_map(Result::ok)
-            .filter(|entry| {
-                entry.file_name().to_str().is_some_and(|name| {
-                    name.starts_with(&prefix)
-                        && Path::new(name)
-                            .extension()
-                            .is_some_and(|ext| ext.eq_ignore_ascii_case("bin"))
-                })
-            })
-            .collect();
-
-        for entry in checkpoints {
+        for entry in self.checkpoint_entries(pipeline_id)? {
             remove_file(entry.path()).ok();
         }
 
@@ -389,6 +540,21 @@ pub fn compute_checksum(data: &[u8]) -> String {
     })
 }
 
+/// SHA-256 over length-prefixed partitions, so moving bytes between partitions changes it.
+#[cfg(feature = "checkpointing")]
+fn data_checksum(partitions: &[Vec<u8>]) -> String {
+    let mut hasher = Sha256::new();
+    for part in partitions {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part);
+    }
+    hasher.finalize().iter().fold(String::new(), |mut s, b| {
+        use std::fmt::Write;
+        let _ = write!(s, "{b:02x}");
+        s
+    })
+}
+
 /// Generate a stable pipeline ID from the pipeline structure.
 #[cfg(feature = "checkpointing")]
 pub(crate) fn generate_pipeline_id(pipeline_hash: &str) -> String {
@@ -412,3 +578,234 @@ pub fn current_timestamp_ms() -> u64 {
         .unwrap_or_default()
         .as_millis() as u64
 }
+
+/// What one run saved to and resumed from checkpoints, reported on
+/// [`PipelineResult::checkpoints`](crate::PipelineResult::checkpoints).
+#[cfg(feature = "checkpointing")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CheckpointSummary {
+    /// Number of checkpoints the run saved.
+    pub saved: usize,
+    /// Index of the completed plan step the run resumed after, if it recovered from a
+    /// checkpoint.
+    pub resumed_after: Option<usize>,
+}
+
+/// Checkpointing state for one [`DirectRunner`](crate::DirectRunner) execution: decides when to
+/// save, persists barrier outputs, and restores them on recovery.
+#[cfg(feature = "checkpointing")]
+pub(crate) struct CheckpointRun {
+    manager: CheckpointManager,
+    pipeline_id: String,
+    exec_mode: &'static str,
+    /// Kind and barrier flag of every chain step.
+    steps: Vec<(&'static str, bool)>,
+    /// Coder for every chain step's output, when one is known.
+    #[cfg(feature = "coders")]
+    coders: Vec<Option<Arc<dyn ElementCoder>>>,
+    /// Checkpoints saved and restored so far, shared with the caller of the run.
+    summary: Arc<Mutex<CheckpointSummary>>,
+}
+
+#[cfg(feature = "checkpointing")]
+impl CheckpointRun {
+    /// Checkpointing for `plan`, collecting `Vec<T>` at the terminal.
+    ///
+    /// The pipeline id hashes the plan's step kinds, origin node ids, source size, and
+    /// terminal type, so checkpoints of a different pipeline are never restored.
+    pub(crate) fn new<T: 'static>(
+        config: CheckpointConfig,
+        p: &Pipeline,
+        plan: &Plan,
+        exec_mode: &'static str,
+    ) -> Result<Self> {
+        use std::fmt::Write;
+
+        let mut fingerprint = String::from(std::any::type_name::<T>());
+        for (idx, node) in plan.chain.iter().enumerate() {
+            let _ = write!(fingerprint, "|{}", node.kind());
+            if let Node::Source {
+                payload, vec_ops, ..
+            } = node
+            {
+                let _ = write!(fingerprint, ":{:?}", vec_ops.len(payload.as_ref()));
+            }
+            for id in plan.chain_origin_ids.get(idx).into_iter().flatten() {
+                let _ = write!(fingerprint, ",{}", id.raw());
+            }
+        }
+        #[cfg(not(feature = "coders"))]
+        let _ = p;
+        #[cfg(feature = "coders")]
+        let coders = plan.step_coders(p);
+        Ok(Self {
+            manager: CheckpointManager::new(config)?,
+            pipeline_id: generate_pipeline_id(&fingerprint),
+            exec_mode,
+            steps: plan
+                .chain
+                .iter()
+                .map(|node| (node.kind(), node.is_barrier()))
+                .collect(),
+            #[cfg(feature = "coders")]
+            coders,
+            summary: Arc::default(),
+        })
+    }
+
+    /// Handle on the run's [`CheckpointSummary`], updated as checkpoints are saved and
+    /// restored.
+    pub(crate) fn summary(&self) -> Arc<Mutex<CheckpointSummary>> {
+        Arc::clone(&self.summary)
+    }
+
+    /// With `auto_recover`, find the newest valid checkpoint that stored its output and
+    /// decode it. Returns the index of the completed step and its output partitions.
+    pub(crate) fn recover(&self) -> Option<(usize, Vec<Partition>)> {
+        if !self.manager.config.auto_recover {
+            return None;
+        }
+        let paths = self
+            .manager
+            .list_checkpoints(&self.pipeline_id)
+            .unwrap_or_default();
+        for path in paths.iter().rev() {
+            match self.manager.load_checkpoint_with_data(path) {
+                Ok((state, Some(data))) => match self.restore(&state, &data) {
+                    Ok(parts) => {
+                        eprintln!(
+                            "[Checkpoint] Resuming after node {} ({:.0}% complete)",
+                            state.completed_node_index, state.metadata.progress_percent
+                        );
+                        self.summary.lock().unwrap().resumed_after =
+                            Some(state.completed_node_index);
+                        return Some((state.completed_node_index, parts));
+                    }
+                    Err(e) => eprintln!(
+                        "[Checkpoint] Cannot restore checkpoint {}: {e}",
+                        path.display()
+                    ),
+                },
+                Ok((_, None)) => {}
+                Err(e) => eprintln!(
+                    "[Checkpoint] Failed to load checkpoint {}: {e}",
+                    path.display()
+                ),
+            }
+        }
+        if !paths.is_empty() {
+            eprintln!("[Checkpoint] No restorable checkpoint found, running from the start");
+        }
+        None
+    }
+
+    /// Decode `data`, checking that `state` describes a step of this plan.
+    fn restore(&self, state: &CheckpointState, data: &CheckpointData) -> Result<Vec<Partition>> {
+        let idx = state.completed_node_index;
+        if state.metadata.total_nodes != self.steps.len()
+            || self.steps.get(idx).map(|(kind, _)| *kind) != Some(&*state.metadata.last_node_type)
+        {
+            bail!("checkpoint does not match the current plan");
+        }
+        if self.exec_mode == "sequential" && data.partitions.len() != 1 {
+            bail!(
+                "{} partitions cannot resume in sequential mode",
+                data.partitions.len()
+            );
+        }
+        #[cfg(feature = "coders")]
+        {
+            let coder = self.coders[idx]
+                .as_ref()
+                .ok_or_else(|| anyhow!("no coder for node {idx}"))?;
+            data.partitions
+                .iter()
+                .map(|bytes| coder.decode_partition(bytes))
+                .collect()
+        }
+        #[cfg(not(feature = "coders"))]
+        bail!("restoring partitions requires the `coders` feature")
+    }
+
+    /// Called after chain step `idx` produced `parts`; saves a checkpoint if the policy
+    /// asks for one, storing `parts` when the step is a barrier with a known coder.
+    pub(crate) fn after_step(&mut self, idx: usize, parts: &[Partition]) {
+        let total_nodes = self.steps.len();
+        let (kind, is_barrier) = self.steps[idx];
+        if !self.manager.should_checkpoint(idx, is_barrier, total_nodes) {
+            return;
+        }
+        let data = if is_barrier {
+            self.encode(idx, parts)
+        } else {
+            None
+        };
+
+        let timestamp = current_timestamp_ms();
+        #[allow(
+            clippy::cast_possible_truncation,
+            clippy::cast_sign_loss,
+            clippy::cast_precision_loss
+        )]
+        let progress_percent = ((idx as f64 / total_nodes as f64) * 100.0) as u8;
+        let partition_count = parts.len();
+        let metadata_str = format!("{}:{idx}:{timestamp}:{partition_count}", self.pipeline_id);
+        let state = CheckpointState {
+            pipeline_id: self.pipeline_id.clone(),
+            completed_node_index: idx,
+            timestamp,
+            partition_count,
+            checksum: compute_checksum(metadata_str.as_bytes()),
+            exec_mode: self.exec_mode.to_string(),
+            metadata: CheckpointMetadata {
+                total_nodes,
+                last_node_type: kind.to_string(),
+                progress_percent,
+            },
+        };
+
+        match self
+            .manager
+            .save_checkpoint_with_data(&state, data.as_ref())
+        {
+            Ok(path) => {
+                self.summary.lock().unwrap().saved += 1;
+                eprintln!(
+                    "[Checkpoint] Saved checkpoint at node {idx} ({progress_percent:.0}% complete) to {:?}",
+                    path.display()
+                );
+            }
+            Err(e) => {
+                eprintln!("[Checkpoint] Warning: Failed to save checkpoint: {e}");
+            }
+        }
+    }
+
+    /// Encode the output of step `idx`; `None` when it has no coder or fails to encode.
+    #[cfg_attr(not(feature = "coders"), allow(clippy::unused_self, unused_variables))]
+    fn encode(&self, idx: usize, parts: &[Partition]) -> Option<CheckpointData> {
+        #[cfg(feature = "coders")]
+        {
+            let coder = self.coders[idx].as_ref()?;
+            match parts
+                .iter()
+                .map(|part| coder.encode_partition(part.as_ref()))
+                .collect::<Result<Vec<_>>>()
+            {
+                Ok(encoded) => Some(CheckpointData::new(encoded)),
+                Err(e) => {
+                    eprintln!("[Checkpoint] Not storing output of node {idx}: {e}");
+                    None
+                }
+            }
+        }
+        #[cfg(not(feature = "coders"))]
+        None
+    }
+
+    /// Remove this pipeline's checkpoints after a successful run.
+    pub(crate) fn finish(&self) {
+        self.manager.clear_checkpoints(&self.pipeline_id).ok();
+        eprintln!("[Checkpoint] Pipeline completed successfully, checkpoints cleared");
+    }
+}
@@ -0,0 +1,637 @@
+//! A command-line runner for pipeline binaries.
+//!
+//! Teams shipping pipelines built on this crate tend to write the same `main` over and
+//! over: parse a few flags, pick an execution mode, set up checkpointing and metrics,
+//! print the plan, run, and report. [`Cli`] does this once. A binary registers its
+//! pipelines by name, each as a function that builds the pipeline from the parsed
+//! [`CliArgs`] and returns its [`PipelineOutput`]; [`Cli::main`] does the rest.
+//!
+//! ```text
+//! usage: etl [PIPELINE] [OPTIONS]
+//!
+//!   --input PATH            input file or glob (repeatable)
+//!   --output PATH           output file; stdout when omitted
+//!   --mode MODE             sequential or parallel (default: parallel)
+//!   --partitions N          source partitions for a parallel run
+//!   --threads N             worker threads for a parallel run
+//!   --checkpoint-dir DIR    checkpoint after every barrier, resuming from DIR
+//!   --param KEY=VALUE       pipeline parameter (repeatable)
+//!   --metrics-json PATH     save the run's metrics as JSON
+//!   --dry-run               print the estimated plan without running it
+//!   --describe              print the pipeline's JSON manifest without running it
+//!   --quiet                 only print errors
+//!   --list                  list the registered pipelines
+//!   --help                  print this help
+//! ```
+//!
+//! The pipeline name may be omitted when only one is registered. Progress, the
+//! [execution explanation](crate::ExecutionExplanation), and with the `metrics`
+//! feature the per-step metrics are written to standard error, so standard output
+//! carries only the records when `--output` is omitted. Spans from the `otel` feature
+//! go to whatever `tracing` subscriber the binary installs.
+//!
+//! # Example
+//! ```no_run
+//! use ironbeam::cli::{Cli, PipelineOutput};
+//! use ironbeam::*;
+//! use serde::{Deserialize, Serialize};
+//! use std::process::ExitCode;
+//!
+//! #[derive(Clone, Serialize, Deserialize)]
+//! struct Order {
+//!     region: String,
+//!     cents: u64,
+//! }
+//!
+//! fn main() -> ExitCode {
+//!     Cli::new("orders")
+//!         .register("totals", "Sum order value per region", |p, args| {
+//!             let orders = read_jsonl::<Order>(p, args.input()?)?;
+//!             let totals = orders
+//!                 .key_by(|o: &Order| o.region.clone())
+//!                 .map_values(|o: &Order| o.cents)
+//!                 .combine_values(Sum::<u64>::default());
+//!             Ok(PipelineOutput::jsonl(totals))
+//!         })
+//!         .main()
+//! }
+//! ```
+//!
+//! `orders totals --input 'orders/*.jsonl' --output totals.jsonl --partitions 16` then
+//! runs the pipeline in parallel over 16 partitions.
+
+use crate::io::csv::{encode_csv_part, write_csv_vec};
+use crate::io::jsonl::{encode_jsonl_part, write_jsonl_vec};
+use crate::manifest::PipelineManifest;
+use crate::{
+    Element, ExecMode, ExecutionExplanation, NodeId, PCollection, Pipeline, RunnerConfig,
+    RunnerConfigBuilder,
+};
+use anyhow::{Context, Result, anyhow, bail};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "checkpointing")]
+use crate::checkpoint::CheckpointConfig;
+#[cfg(feature = "metrics")]
+use crate::metrics::MetricsCollector;
+
+/// Execution mode chosen with `--mode`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CliMode {
+    /// Run on the calling thread.
+    Sequential,
+    /// Run on the rayon pool over several partitions.
+    #[default]
+    Parallel,
+}
+
+/// Options of one pipeline run, parsed from the command line.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CliArgs {
+    /// Name of the registered pipeline to run; empty when none was given.
+    pub pipeline: String,
+    /// `--input` paths or globs, in order.
+    pub inputs: Vec<PathBuf>,
+    /// `--output` path; records go to standard output when `None`.
+    pub output: Option<PathBuf>,
+    /// `--mode`.
+    pub mode: CliMode,
+    /// `--partitions`.
+    pub partitions: Option<usize>,
+    /// `--threads`.
+    pub threads: Option<usize>,
+    /// `--checkpoint-dir`.
+    pub checkpoint_dir: Option<PathBuf>,
+    /// `--param` values, by key.
+    pub params: BTreeMap<String, String>,
+    /// `--metrics-json` path.
+    pub metrics_json: Option<PathBuf>,
+    /// `--dry-run`.
+    pub dry_run: bool,
+    /// `--describe`.
+    pub describe: bool,
+    /// `--quiet`.
+    pub quiet: bool,
+}
+
+/// What the command line asks for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CliCommand {
+    /// Run a pipeline.
+    Run(CliArgs),
+    /// List the registered pipelines.
+    List,
+    /// Print the usage.
+    Help,
+}
+
+impl CliArgs {
+    /// Parse command-line arguments, without the program name.
+    ///
+    /// Options take their value as the next argument or after `=`
+    /// (`--partitions 8` or `--partitions=8`).
+    ///
+    /// # Errors
+    /// Returns an error for an unknown option, a missing or malformed value, a second
+    /// pipeline name, or options that contradict each other (`--partitions` or
+    /// `--threads` with `--mode sequential`).
+    pub fn parse<I, S>(args: I) -> Result<CliCommand>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter().map(Into::into);
+        while let Some(arg) = args.next() {
+            let Some(flag) = arg.strip_prefix("--") else {
+                if !parsed.pipeline.is_empty() {
+                    bail!(
+                        "unexpected argument {arg:?}: pipeline {:?} already given",
+                        parsed.pipeline
+                    );
+                }
+                parsed.pipeline = arg;
+                continue;
+            };
+            let (flag, inline) = match flag.split_once('=') {
+                Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+                None => (flag.to_string(), None),
+            };
+            let mut value = || {
+                inline
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| anyhow!("--{flag} needs a value"))
+            };
+            match flag.as_str() {
+                "help" => return Ok(CliCommand::Help),
+                "list" => return Ok(CliCommand::List),
+                "input" => parsed.inputs.push(value()?.into()),
+                "output" => parsed.output = Some(value()?.into()),
+                "mode" => {
+                    parsed.mode = match value()?.as_str() {
+                        "sequential" => CliMode::Sequential,
+                        "parallel" => CliMode::Parallel,
+                        other => bail!("--mode must be sequential or parallel, not {other:?}"),
+                    }
+                }
+                "partitions" => parsed.partitions = Some(positive(&flag, &value()?)?),
+                "threads" => parsed.threads = Some(positive(&flag, &value()?)?),
+                "checkpoint-dir" => parsed.checkpoint_dir = Some(value()?.into()),
+                "param" => {
+                    let param = value()?;
+                    let (key, val) = param
+                        .split_once('=')
+                        .ok_or_else(|| anyhow!("--param must be KEY=VALUE, not {param:?}"))?;
+                    parsed.params.insert(key.to_string(), val.to_string());
+                }
+                "metrics-json" => parsed.metrics_json = Some(value()?.into()),
+                "dry-run" | "describe" | "quiet" if inline.is_some() => {
+                    bail!("--{flag} takes no value")
+                }
+                "dry-run" => parsed.dry_run = true,
+                "describe" => parsed.describe = true,
+                "quiet" => parsed.quiet = true,
+                _ => bail!("unknown option --{flag}"),
+            }
+        }
+        if parsed.mode == CliMode::Sequential
+            && (parsed.partitions.is_some() || parsed.threads.is_some())
+        {
+            bail!("--partitions and --threads only apply to --mode parallel");
+        }
+        Ok(CliCommand::Run(parsed))
+    }
+
+    /// The single `--input`.
+    ///
+    /// # Errors
+    /// Returns an error unless exactly one `--input` was given.
+    pub fn input(&self) -> Result<&Path> {
+        match self.inputs.as_slice() {
+            [input] => Ok(input),
+            [] => bail!("--input is required"),
+            _ => bail!("expected one --input, got {}", self.inputs.len()),
+        }
+    }
+
+    /// The `--param` value for `key`, if given.
+    #[must_use]
+    pub fn param(&self, key: &str) -> Option<&str> {
+        self.params.get(key).map(String::as_str)
+    }
+
+    /// The `--param` value for `key`, parsed.
+    ///
+    /// # Errors
+    /// Returns an error if the parameter is missing or does not parse.
+    pub fn require_param<T>(&self, key: &str) -> Result<T>
+    where
+        T: std::str::FromStr,
+        T::Err: std::error::Error + Send + Sync + 'static,
+    {
+        let raw = self
+            .param(key)
+            .ok_or_else(|| anyhow!("--param {key}=... is required"))?;
+        raw.parse()
+            .with_context(|| format!("--param {key}={raw} is invalid"))
+    }
+
+    /// Runner configuration for these options, without metrics.
+    ///
+    /// # Errors
+    /// Returns an error if `--checkpoint-dir` is given without the `checkpointing`
+    /// feature.
+    pub fn runner_config(&self) -> Result<RunnerConfigBuilder> {
+        let mut builder = RunnerConfig::builder();
+        builder = match self.mode {
+            CliMode::Sequential => builder.mode(ExecMode::Sequential),
+            CliMode::Parallel => builder.mode(ExecMode::Parallel {
+                threads: self.threads,
+                partitions: self.partitions,
+            }),
+        };
+        if let Some(dir) = &self.checkpoint_dir {
+            #[cfg(feature = "checkpointing")]
+            {
+                builder = builder.checkpointing(CheckpointConfig {
+                    enabled: true,
+                    directory: dir.clone(),
+                    ..CheckpointConfig::default()
+                });
+            }
+            #[cfg(not(feature = "checkpointing"))]
+            bail!(
+                "--checkpoint-dir {} requires the `checkpointing` feature",
+                dir.display()
+            );
+        }
+        Ok(builder)
+    }
+}
+
+fn positive(flag: &str, value: &str) -> Result<usize> {
+    match value.parse::<usize>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => bail!("--{flag} must be a positive integer, not {value:?}"),
+    }
+}
+
+type WriteFn = Box<dyn FnOnce(&RunnerConfig, Option<&Path>) -> Result<usize>>;
+
+/// The terminal collection of a registered pipeline, and how to write it.
+pub struct PipelineOutput {
+    pipeline: Pipeline,
+    terminal: NodeId,
+    format: Option<&'static str>,
+    write: WriteFn,
+}
+
+impl PipelineOutput {
+    /// Write the collection as JSON Lines to `--output`, or to standard output.
+    #[must_use]
+    pub fn jsonl<T: Element + Serialize>(collection: PCollection<T>) -> Self {
+        Self::with_writer(collection, |rows: Vec<T>, output| match output {
+            Some(path) => write_jsonl_vec(path, &rows),
+            None => write_stdout(&encode_jsonl_part(&rows)?).map(|()| rows.len()),
+        })
+        .with_format("jsonl")
+    }
+
+    /// Write the collection as CSV to `--output`, or to standard output.
+    #[must_use]
+    pub fn csv<T: Element + Serialize>(collection: PCollection<T>, has_headers: bool) -> Self {
+        Self::with_writer(collection, move |rows: Vec<T>, output| match output {
+            Some(path) => write_csv_vec(path, has_headers, &rows),
+            None => write_stdout(&encode_csv_part(&rows, has_headers)?.1).map(|()| rows.len()),
+        })
+        .with_format("csv")
+    }
+
+    /// Hand the collected records and the `--output` path to `write`, which returns the
+    /// number of records written.
+    #[must_use]
+    pub fn with_writer<T, F>(collection: PCollection<T>, write: F) -> Self
+    where
+        T: Element,
+        F: FnOnce(Vec<T>, Option<&Path>) -> Result<usize> + 'static,
+    {
+        let terminal = collection.id;
+        let pipeline = collection.pipeline;
+        let run_on = pipeline.clone();
+        Self {
+            pipeline,
+            terminal,
+            format: None,
+            write: Box::new(move |config, output| {
+                let rows = config.run_collect::<T>(&run_on, terminal)?;
+                write(rows, output)
+            }),
+        }
+    }
+
+    /// Set the format the output is described with in `--describe` manifests.
+    #[must_use]
+    pub const fn with_format(mut self, format: &'static str) -> Self {
+        self.format = Some(format);
+        self
+    }
+}
+
+fn write_stdout(bytes: &[u8]) -> Result<()> {
+    let mut out = std::io::stdout().lock();
+    out.write_all(bytes).context("write to standard output")?;
+    out.flush().context("write to standard output")
+}
+
+/// Summary of a finished [`Cli`] run.
+#[derive(Clone, Debug)]
+pub struct RunReport {
+    /// Name of the pipeline that ran.
+    pub pipeline: String,
+    /// The plan, with estimates.
+    pub explanation: ExecutionExplanation,
+    /// Records written; `0` for a dry run.
+    pub rows: usize,
+    /// Where the records went; `None` for standard output.
+    pub output: Option<PathBuf>,
+    /// Wall-clock time of the run and write.
+    pub elapsed: Duration,
+    /// The manifest printed for `--describe`.
+    pub manifest: Option<PipelineManifest>,
+}
+
+type BuildFn = Box<dyn Fn(&Pipeline, &CliArgs) -> Result<PipelineOutput>>;
+
+struct Registered {
+    about: String,
+    build: BuildFn,
+}
+
+/// A set of named pipelines behind a command line. See the
+/// [module documentation](self).
+pub struct Cli {
+    name: String,
+    pipelines: BTreeMap<String, Registered>,
+}
+
+impl Cli {
+    /// A runner for the program called `name` in its usage text.
+    #[must_use]
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            pipelines: BTreeMap::new(),
+        }
+    }
+
+    /// Register the pipeline `name`, described by `about` in `--list`. `build` adds the
+    /// pipeline's steps to a fresh [`Pipeline`] and returns its output.
+    ///
+    /// # Panics
+    /// Panics if a pipeline called `name` is already registered.
+    #[must_use]
+    pub fn register<F>(
+        mut self,
+        name: impl Into<String>,
+        about: impl Into<String>,
+        build: F,
+    ) -> Self
+    where
+        F: Fn(&Pipeline, &CliArgs) -> Result<PipelineOutput> + 'static,
+    {
+        let name = name.into();
+        let previous = self.pipelines.insert(
+            name.clone(),
+            Registered {
+                about: about.into(),
+                build: Box::new(build),
+            },
+        );
+        assert!(previous.is_none(), "pipeline {name:?} is registered twice");
+        self
+    }
+
+    /// Names of the registered pipelines, sorted.
+    pub fn pipelines(&self) -> impl Iterator<Item = &str> {
+        self.pipelines.keys().map(String::as_str)
+    }
+
+    /// Run with the process's arguments, writing progress to standard error.
+    ///
+    /// Returns exit code 0 on success, 2 for a usage error, and 1 if the pipeline fails.
+    #[must_use]
+    pub fn main(&self) -> ExitCode {
+        let command = match CliArgs::parse(std::env::args().skip(1)) {
+            Ok(command) => command,
+            Err(err) => {
+                eprintln!("{}: {err:#}\n\n{}", self.name, self.usage());
+                return ExitCode::from(2);
+            }
+        };
+        let mut stderr = std::io::stderr();
+        match self.dispatch(&command, &mut stderr) {
+            Ok(_) => ExitCode::SUCCESS,
+            Err(err) => {
+                eprintln!("{}: {err:#}", self.name);
+                ExitCode::FAILURE
+            }
+        }
+    }
+
+    /// Parse `args` (without the program name) and carry them out, writing help,
+    /// listings and progress to `log`. Returns the report of a pipeline run.
+    ///
+    /// # Errors
+    /// Returns the errors of [`CliArgs::parse`] and [`Cli::execute`].
+    pub fn run<I, S>(&self, args: I, log: &mut dyn Write) -> Result<Option<RunReport>>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.dispatch(&CliArgs::parse(args)?, log)
+    }
+
+    fn dispatch(&self, command: &CliCommand, log: &mut dyn Write) -> Result<Option<RunReport>> {
+        match command {
+            CliCommand::Help => {
+                log.write_all(self.usage().as_bytes())?;
+                Ok(None)
+            }
+            CliCommand::List => {
+                for (name, registered) in &self.pipelines {
+                    writeln!(log, "{name:<24}{}", registered.about)?;
+                }
+                Ok(None)
+            }
+            CliCommand::Run(args) => self.execute(args, log).map(Some),
+        }
+    }
+
+    /// Build and run the pipeline `args` names, writing progress to `log` unless
+    /// `args.quiet`.
+    ///
+    /// # Errors
+    /// Returns an error if the pipeline is not registered (or none is named while
+    /// several are), building or running it fails, or the output or metrics cannot be
+    /// written.
+    pub fn execute(&self, args: &CliArgs, log: &mut dyn Write) -> Result<RunReport> {
+        let name = if args.pipeline.is_empty() {
+            let mut names = self.pipelines.keys();
+            match (names.next(), names.next()) {
+                (Some(only), None) => only.clone(),
+                (None, _) => bail!("no pipelines are registered"),
+                (Some(_), Some(_)) => {
+                    bail!("name a pipeline to run: {}", self.name_list())
+                }
+            }
+        } else {
+            args.pipeline.clone()
+        };
+        let registered = self.pipelines.get(&name).ok_or_else(|| {
+            anyhow!(
+                "unknown pipeline {name:?}; registered: {}",
+                self.name_list()
+            )
+        })?;
+
+        let runner = args.runner_config()?;
+        #[cfg(feature = "metrics")]
+        let metrics = MetricsCollector::new();
+        #[cfg(feature = "metrics")]
+        let runner = runner.metrics(metrics.clone());
+        let config = runner.build();
+
+        let p = Pipeline::default();
+        let output = (registered.build)(&p, args).with_context(|| format!("build {name}"))?;
+        let location = args
+            .output
+            .as_ref()
+            .map_or_else(|| "stdout".to_string(), |p| p.display().to_string());
+        output.pipeline.record_output(
+            output.terminal,
+            output.format.unwrap_or("custom"),
+            &location,
+        );
+        let explanation = config
+            .runner()
+            .dry_run(&output.pipeline, output.terminal)
+            .with_context(|| format!("plan {name}"))?;
+        if args.describe {
+            let manifest = output
+                .pipeline
+                .describe()
+                .with_context(|| format!("describe {name}"))?;
+            write_stdout(format!("{}\n", manifest.to_json()?).as_bytes())?;
+            return Ok(RunReport {
+                pipeline: name,
+                explanation,
+                rows: 0,
+                output: None,
+                elapsed: Duration::ZERO,
+                manifest: Some(manifest),
+            });
+        }
+        if !args.quiet {
+            writeln!(log, "{explanation}")?;
+        }
+        if args.dry_run {
+            return Ok(RunReport {
+                pipeline: name,
+                explanation,
+                rows: 0,
+                output: None,
+                elapsed: Duration::ZERO,
+                manifest: None,
+            });
+        }
+
+        if !args.quiet {
+            writeln!(log, "running {name}")?;
+        }
+        let start = Instant::now();
+        let rows = (output.write)(&config, args.output.as_deref())
+            .with_context(|| format!("run {name}"))?;
+        let elapsed = start.elapsed();
+
+        #[cfg(feature = "metrics")]
+        {
+            if !args.quiet {
+                for step in metrics.report().per_transform() {
+                    writeln!(log, "  {step}")?;
+                }
+            }
+            if let Some(path) = &args.metrics_json {
+                let path = path
+                    .to_str()
+                    .ok_or_else(|| anyhow!("--metrics-json path is not valid UTF-8"))?;
+                metrics
+                    .save_to_file(path)
+                    .with_context(|| format!("write metrics to {path}"))?;
+            }
+        }
+        #[cfg(not(feature = "metrics"))]
+        if args.metrics_json.is_some() {
+            bail!("--metrics-json requires the `metrics` feature");
+        }
+
+        if !args.quiet {
+            let target = args.output.as_ref().map_or_else(
+                || "standard output".to_string(),
+                |p| p.display().to_string(),
+            );
+            writeln!(
+                log,
+                "{name}: wrote {rows} records to {target} in {:.3}s",
+                elapsed.as_secs_f64()
+            )?;
+        }
+        Ok(RunReport {
+            pipeline: name,
+            explanation,
+            rows,
+            output: args.output.clone(),
+            elapsed,
+            manifest: None,
+        })
+    }
+
+    fn name_list(&self) -> String {
+        self.pipelines().collect::<Vec<_>>().join(", ")
+    }
+
+    /// The usage text printed by `--help`.
+    #[must_use]
+    pub fn usage(&self) -> String {
+        let mut usage = format!(
+            "usage: {} [PIPELINE] [OPTIONS]\n\n\
+             \x20 --input PATH            input file or glob (repeatable)\n\
+             \x20 --output PATH           output file; stdout when omitted\n\
+             \x20 --mode MODE             sequential or parallel (default: parallel)\n\
+             \x20 --partitions N          source partitions for a parallel run\n\
+             \x20 --threads N             worker threads for a parallel run\n\
+             \x20 --checkpoint-dir DIR    checkpoint after every barrier, resuming from DIR\n\
+             \x20 --param KEY=VALUE       pipeline parameter (repeatable)\n\
+             \x20 --metrics-json PATH     save the run's metrics as JSON\n\
+             \x20 --dry-run               print the estimated plan without running it\n\
+             \x20 --describe              print the pipeline's JSON manifest without running it\n\
+             \x20 --quiet                 only print errors\n\
+             \x20 --list                  list the registered pipelines\n\
+             \x20 --help                  print this help\n",
+            self.name
+        );
+        if !self.pipelines.is_empty() {
+            usage.push_str("\npipelines:\n");
+            for (name, registered) in &self.pipelines {
+                let _ = writeln!(usage, "  {name:<24}{}", registered.about);
+            }
+        }
+        usage
+    }
+}
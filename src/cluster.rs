@@ -0,0 +1,633 @@
+//! Experimental multi-process execution over TCP.
+//!
+//! A [`ClusterRunner`] is a [`PipelineRunner`] that splits a pipeline's source across
+//! worker processes, either spawned on this machine ([`ClusterRunner::spawn`]) or already
+//! listening elsewhere ([`ClusterRunner::connect`], with [`serve`] on the remote side).
+//! Closures cannot cross a process boundary, so the pipeline must be built through a
+//! [`DoFnRegistry`]: the coordinator ships its
+//! [`PipelineSpec`], and each worker rebuilds the same plan
+//! from its own copy of the registry.
+//!
+//! Execution proceeds stage by stage:
+//!
+//! 1. Every worker takes its share of the source partitions and runs the stateless steps
+//!    up to the next barrier.
+//! 2. Before a `GroupByKey` or `CombineValues` whose input carries a KV coder, workers
+//!    encode their pairs, hash each key's bytes into one bucket per worker, and exchange
+//!    the buckets through the coordinator. Each worker then runs the barrier on the keys
+//!    it owns and continues with the next stage.
+//! 3. The terminal output is encoded with its coder and collected by the coordinator.
+//!
+//! Any other barrier (`Sort`, `CombineGlobal`, `Reshuffle`, joins, flattens) ends the
+//! distributed part of the run: the coordinator gathers the partitions and finishes the
+//! plan in-process, as [`DirectRunner`] would.
+//!
+//! Keys are routed by their postcard bytes, so key types must encode deterministically
+//! (no `HashMap`/`HashSet` keys). Shuffled data is relayed through the coordinator and
+//! held in memory, and a worker that fails mid-run leaves its connection unusable; this
+//! runner is meant for spreading one machine's allocator and memory pressure across
+//! processes or for small clusters, not as a fault-tolerant scheduler.
+//!
+//! ```no_run
+//! use ironbeam::*;
+//! use ironbeam::cluster::{ClusterRunner, worker_main};
+//! use ironbeam::spec::DoFnRegistry;
+//! use anyhow::Result;
+//! use serde_json::json;
+//!
+//! fn registry() -> DoFnRegistry {
+//!     let mut registry = DoFnRegistry::new();
+//!     registry
+//!         .register_source("numbers", |p, cfg| {
+//!             let n = cfg["n"].as_u64().unwrap_or(0);
+//!             Ok(from_vec(p, (0..n).collect::<Vec<u64>>()))
+//!         })
+//!         .register_transform("sum_by_mod", |input: PCollection<u64>, cfg| {
+//!             let m = cfg.as_u64().unwrap_or(1);
+//!             Ok(input.key_by(move |x: &u64| x % m).combine_values(Sum::<u64>::default()))
+//!         });
+//!     registry
+//! }
+//!
+//! fn main() -> Result<()> {
+//!     let registry = registry();
+//!     // Spawned workers re-run this binary; serve the coordinator and exit.
+//!     if worker_main(&registry)? {
+//!         return Ok(());
+//!     }
+//!
+//!     let p = Pipeline::default();
+//!     let nums = registry.source::<u64>(&p, "numbers", json!({ "n": 1_000_000 }))?;
+//!     let sums = registry.apply::<u64, (u64, u64)>(nums, "sum_by_mod", 10)?;
+//!
+//!     let runner = ClusterRunner::spawn(registry, 4)?;
+//!     let out = sums.collect_with(&runner)?;
+//!     assert_eq!(out.len(), 10);
+//!     Ok(())
+//! }
+//! ```
+
+use crate::coders::ElementCoder;
+use crate::collection::Element;
+use crate::node::Node;
+use crate::planner::build_plan;
+use crate::runner::{DirectRunner, PipelineRunner, finish_steps, panic_message, run_steps};
+use crate::spec::{DoFnRegistry, PipelineSpec};
+use crate::type_token::Partition;
+use crate::{NodeId, Pipeline};
+use anyhow::{Context, Result, anyhow, bail};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Environment variable carrying the coordinator address to a spawned worker.
+pub const WORKER_ENV: &str = "IRONBEAM_CLUSTER_COORDINATOR";
+
+/// Version of the coordinator/worker protocol; both sides must agree.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// How long [`ClusterRunner::spawn`] waits for its workers to connect.
+const SPAWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Coordinator-to-worker messages.
+#[derive(Serialize, Deserialize)]
+enum Request {
+    /// Rebuild the pipeline from `spec` and plan it up to spec step `terminal`.
+    Plan {
+        spec: String,
+        terminal: usize,
+        worker: usize,
+        workers: usize,
+        partitions: usize,
+    },
+    /// Run the steps after the held output up to (excluding) chain step `to`.
+    Run { to: usize },
+    /// Hash the held pairs by key into one bucket per worker.
+    Shuffle,
+    /// Replace the held output with `count` encoded pairs (the worker's bucket).
+    Load { count: u64, bytes: Vec<u8> },
+    /// Encode and return the held partitions.
+    Collect,
+}
+
+/// Worker-to-coordinator messages.
+#[derive(Serialize, Deserialize)]
+enum Response {
+    /// Sent once when a connection opens.
+    Ready {
+        protocol: u32,
+    },
+    Done,
+    /// One `(count, concatenated element bytes)` bucket per worker.
+    Buckets(Vec<(u64, Vec<u8>)>),
+    Partitions(Vec<Vec<u8>>),
+    Failed(String),
+}
+
+/// Write `msg` as one length-prefixed postcard frame.
+fn send<M: Serialize>(stream: &mut TcpStream, msg: &M) -> Result<()> {
+    let body = postcard::to_allocvec(msg).context("encode cluster message")?;
+    let mut frame = Vec::with_capacity(8 + body.len());
+    frame.extend_from_slice(&(body.len() as u64).to_le_bytes());
+    frame.extend_from_slice(&body);
+    stream.write_all(&frame)?;
+    stream.flush()?;
+    Ok(())
+}
+
+/// Read one frame, or `None` if the peer closed the connection between frames.
+fn recv<M: DeserializeOwned>(stream: &mut TcpStream) -> Result<Option<M>> {
+    let mut header = [0u8; 8];
+    match stream.read_exact(&mut header) {
+        Ok(()) => {}
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.into()),
+    }
+    let len = usize::try_from(u64::from_le_bytes(header)).context("cluster frame too large")?;
+    let mut body = vec![0u8; len];
+    stream.read_exact(&mut body)?;
+    Ok(Some(
+        postcard::from_bytes(&body).context("decode cluster message")?,
+    ))
+}
+
+/// FNV-1a, so every worker routes a key to the same bucket regardless of build.
+fn key_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |h, b| {
+        (h ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Whether a barrier can run on hash-partitioned keys.
+fn is_keyed(node: &Node) -> bool {
+    matches!(node, Node::GroupByKey { .. } | Node::CombineValues { .. })
+}
+
+/// A connection to one worker.
+struct Worker {
+    stream: TcpStream,
+    label: String,
+}
+
+impl Worker {
+    fn open(mut stream: TcpStream) -> Result<Self> {
+        stream.set_nodelay(true)?;
+        let label = stream
+            .peer_addr()
+            .map_or_else(|_| "worker".to_string(), |a| a.to_string());
+        match recv::<Response>(&mut stream)? {
+            Some(Response::Ready { protocol }) if protocol == PROTOCOL_VERSION => {}
+            Some(Response::Ready { protocol }) => bail!(
+                "worker {label} speaks cluster protocol {protocol} (expected {PROTOCOL_VERSION})"
+            ),
+            _ => bail!("worker {label} did not complete the handshake"),
+        }
+        Ok(Self { stream, label })
+    }
+}
+
+/// Send one request to every worker, then read every response.
+///
+/// All responses are read even when one fails, so the connections stay in step.
+fn round(
+    workers: &mut [Worker],
+    mut request: impl FnMut(usize) -> Request,
+) -> Result<Vec<Response>> {
+    for (idx, worker) in workers.iter_mut().enumerate() {
+        send(&mut worker.stream, &request(idx))
+            .with_context(|| format!("send to worker {}", worker.label))?;
+    }
+    let mut responses = Vec::with_capacity(workers.len());
+    let mut failure = None;
+    for worker in workers.iter_mut() {
+        match recv::<Response>(&mut worker.stream) {
+            Ok(Some(Response::Failed(msg))) => {
+                failure.get_or_insert_with(|| anyhow!("worker {}: {msg}", worker.label));
+            }
+            Ok(Some(response)) => responses.push(response),
+            Ok(None) => {
+                failure.get_or_insert_with(|| anyhow!("worker {} disconnected", worker.label));
+            }
+            Err(e) => {
+                failure.get_or_insert_with(|| e.context(format!("worker {}", worker.label)));
+            }
+        }
+    }
+    failure.map_or(Ok(responses), Err)
+}
+
+/// Experimental [`PipelineRunner`] that distributes a pipeline across worker processes.
+///
+/// See the [module docs](self) for how a run is staged and what it requires.
+pub struct ClusterRunner {
+    /// Number of partitions each worker splits its share of the source into.
+    pub partitions_per_worker: usize,
+    registry: DoFnRegistry,
+    workers: Mutex<Vec<Worker>>,
+    children: Vec<Child>,
+}
+
+impl ClusterRunner {
+    /// Start `workers` copies of the current executable as worker processes.
+    ///
+    /// Each child finds the coordinator through [`WORKER_ENV`], so the program's `main`
+    /// must call [`worker_main`] with an equivalent registry before doing anything else.
+    /// The children exit when the runner is dropped.
+    ///
+    /// # Errors
+    /// Returns an error if `workers` is zero, if called from inside a worker process, or
+    /// if a child cannot be started or does not connect within 30 seconds.
+    pub fn spawn(registry: DoFnRegistry, workers: usize) -> Result<Self> {
+        if workers == 0 {
+            bail!("a cluster needs at least one worker");
+        }
+        if std::env::var_os(WORKER_ENV).is_some() {
+            bail!(
+                "ClusterRunner::spawn called inside a worker process; call cluster::worker_main first"
+            );
+        }
+        let exe = std::env::current_exe().context("locate the current executable")?;
+        let listener = TcpListener::bind("127.0.0.1:0").context("bind cluster coordinator")?;
+        let addr = listener.local_addr()?;
+        let mut runner = Self::new(registry, Vec::new());
+        for _ in 0..workers {
+            let child = Command::new(&exe)
+                .env(WORKER_ENV, addr.to_string())
+                .stdin(Stdio::null())
+                .spawn()
+                .with_context(|| format!("spawn worker {}", exe.display()))?;
+            runner.children.push(child);
+        }
+
+        listener.set_nonblocking(true)?;
+        let deadline = Instant::now() + SPAWN_TIMEOUT;
+        let mut conns = Vec::with_capacity(workers);
+        while conns.len() < workers {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    stream.set_nonblocking(false)?;
+                    conns.push(Worker::open(stream)?);
+                }
+                Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                    for child in &mut runner.children {
+                        if let Some(status) = child.try_wait()? {
+                            bail!("worker process exited before connecting ({status})");
+                        }
+                    }
+                    if Instant::now() > deadline {
+                        bail!("timed out waiting for {workers} workers to connect");
+                    }
+                    std::thread::sleep(Duration::from_millis(10));
+                }
+                Err(e) => return Err(e).context("accept worker connection"),
+            }
+        }
+        runner.workers = Mutex::new(conns);
+        Ok(runner)
+    }
+
+    /// Connect to workers already running [`serve`] at `addrs`.
+    ///
+    /// # Errors
+    /// Returns an error if `addrs` is empty, or if a worker cannot be reached or speaks
+    /// another protocol version.
+    pub fn connect<A: ToSocketAddrs>(
+        registry: DoFnRegistry,
+        addrs: impl IntoIterator<Item = A>,
+    ) -> Result<Self> {
+        let workers = addrs
+            .into_iter()
+            .map(|addr| {
+                let stream = TcpStream::connect(addr).context("connect to cluster worker")?;
+                Worker::open(stream)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if workers.is_empty() {
+            bail!("a cluster needs at least one worker");
+        }
+        Ok(Self::new(registry, workers))
+    }
+
+    fn new(registry: DoFnRegistry, workers: Vec<Worker>) -> Self {
+        Self {
+            partitions_per_worker: num_cpus::get().max(1),
+            registry,
+            workers: Mutex::new(workers),
+            children: Vec::new(),
+        }
+    }
+
+    /// Number of connected workers.
+    ///
+    /// # Panics
+    /// If the worker list mutex is poisoned.
+    #[must_use]
+    pub fn workers(&self) -> usize {
+        self.workers.lock().unwrap().len()
+    }
+}
+
+impl Drop for ClusterRunner {
+    fn drop(&mut self) {
+        // Closing the connections ends each worker's session.
+        if let Ok(workers) = self.workers.get_mut() {
+            workers.clear();
+        }
+        for child in &mut self.children {
+            let _ = child.wait();
+        }
+    }
+}
+
+impl PipelineRunner for ClusterRunner {
+    fn run_collect<T: Element>(&self, p: &Pipeline, terminal: NodeId) -> Result<Vec<T>> {
+        let spec = p
+            .spec()
+            .context("ClusterRunner runs pipelines built through a DoFnRegistry")?;
+        let terminal_step = p.spec_step(terminal).ok_or_else(|| {
+            anyhow!(
+                "terminal node #{} was not built through a DoFnRegistry",
+                terminal.raw()
+            )
+        })?;
+        let (local, nodes) = Pipeline::from_spec(&spec, &self.registry)?;
+        let plan = build_plan(&local, nodes[terminal_step])?;
+        if plan.is_empty {
+            return Ok(Vec::new());
+        }
+        let coders = plan.step_coders(&local);
+        let chain = &plan.chain;
+
+        let mut workers = self
+            .workers
+            .lock()
+            .map_err(|_| anyhow!("cluster connections are poisoned"))?;
+        let n = workers.len();
+        let spec_json = spec.to_json()?;
+        round(&mut workers, |worker| Request::Plan {
+            spec: spec_json.clone(),
+            terminal: terminal_step,
+            worker,
+            workers: n,
+            partitions: self.partitions_per_worker,
+        })?;
+
+        // `held` is the chain step whose output the workers hold; steps from `next` on
+        // have not been scheduled yet.
+        let mut held: Option<usize> = None;
+        let mut next = 1;
+        let gather = loop {
+            match (next..chain.len()).find(|&i| chain[i].is_barrier()) {
+                None => {
+                    round(&mut workers, |_| Request::Run { to: chain.len() })?;
+                    break Some(chain.len() - 1);
+                }
+                Some(b)
+                    if is_keyed(&chain[b]) && coders[b - 1].as_ref().is_some_and(|c| c.is_kv()) =>
+                {
+                    round(&mut workers, |_| Request::Run { to: b })?;
+                    let mut buckets = vec![(0u64, Vec::new()); n];
+                    for response in round(&mut workers, |_| Request::Shuffle)? {
+                        let Response::Buckets(parts) = response else {
+                            bail!("unexpected response to a shuffle");
+                        };
+                        for ((count, bytes), (c, b)) in buckets.iter_mut().zip(parts) {
+                            *count += c;
+                            bytes.extend_from_slice(&b);
+                        }
+                    }
+                    let mut buckets = buckets.into_iter();
+                    round(&mut workers, |_| {
+                        let (count, bytes) = buckets.next().unwrap_or_default();
+                        Request::Load { count, bytes }
+                    })?;
+                    held = Some(b - 1);
+                    next = b + 1;
+                }
+                Some(b) if coders[b - 1].is_some() => {
+                    round(&mut workers, |_| Request::Run { to: b })?;
+                    break Some(b - 1);
+                }
+                Some(_) => break held,
+            }
+        };
+
+        let Some(step) = gather else {
+            // Nothing can be distributed before the first barrier.
+            drop(workers);
+            return DirectRunner::default().run_collect::<T>(&local, nodes[terminal_step]);
+        };
+        let coder = coders[step]
+            .as_ref()
+            .ok_or_else(|| anyhow!("step {} has no coder to collect with", step + 1))?;
+        let mut parts = Vec::new();
+        for response in round(&mut workers, |_| Request::Collect)? {
+            let Response::Partitions(encoded) = response else {
+                bail!("unexpected response to a collect");
+            };
+            for bytes in encoded {
+                parts.push(coder.decode_partition(&bytes)?);
+            }
+        }
+        drop(workers);
+        finish_steps::<T>(
+            chain,
+            step,
+            parts,
+            self.partitions_per_worker * n,
+            plan.limit,
+        )
+    }
+}
+
+/// If this process was started by [`ClusterRunner::spawn`], serve the coordinator until
+/// it disconnects and return `true`; otherwise return `false` immediately.
+///
+/// Call it first thing in `main`, with a registry equivalent to the coordinator's, and
+/// exit when it returns `true`.
+///
+/// # Errors
+/// Returns an error if the coordinator cannot be reached or the connection fails.
+pub fn worker_main(registry: &DoFnRegistry) -> Result<bool> {
+    let Some(addr) = std::env::var_os(WORKER_ENV) else {
+        return Ok(false);
+    };
+    let addr = addr.to_string_lossy().into_owned();
+    let stream =
+        TcpStream::connect(&addr).with_context(|| format!("connect to coordinator at {addr}"))?;
+    serve_connection(stream, registry)?;
+    Ok(true)
+}
+
+/// Serve coordinators connecting to `listener`, one at a time, until accepting fails.
+///
+/// This is the remote side of [`ClusterRunner::connect`]. A broken connection ends only
+/// its own session.
+///
+/// # Errors
+/// Returns an error if accepting a connection fails.
+pub fn serve(listener: &TcpListener, registry: &DoFnRegistry) -> Result<()> {
+    for stream in listener.incoming() {
+        let _ = serve_connection(stream?, registry);
+    }
+    Ok(())
+}
+
+/// Answer requests on `stream` until the coordinator disconnects.
+fn serve_connection(mut stream: TcpStream, registry: &DoFnRegistry) -> Result<()> {
+    stream.set_nodelay(true)?;
+    send(
+        &mut stream,
+        &Response::Ready {
+            protocol: PROTOCOL_VERSION,
+        },
+    )?;
+    let mut job = None;
+    while let Some(request) = recv::<Request>(&mut stream)? {
+        let response = catch_unwind(AssertUnwindSafe(|| handle(&mut job, registry, request)))
+            .unwrap_or_else(|payload| {
+                Err(anyhow!(
+                    "worker panicked: {}",
+                    panic_message(payload.as_ref())
+                ))
+            })
+            .unwrap_or_else(|e| Response::Failed(format!("{e:#}")));
+        send(&mut stream, &response)?;
+    }
+    Ok(())
+}
+
+/// A worker's share of one run.
+struct Job {
+    chain: Vec<Node>,
+    coders: Vec<Option<Arc<dyn ElementCoder>>>,
+    worker: usize,
+    workers: usize,
+    partitions: usize,
+    /// The chain step whose output this worker holds, and its partitions.
+    held: Option<(usize, Vec<Partition>)>,
+}
+
+impl Job {
+    /// This worker's partitions of the source (chain step 0).
+    fn shard(&self) -> Result<Vec<Partition>> {
+        let Node::Source {
+            payload, vec_ops, ..
+        } = &self.chain[0]
+        else {
+            bail!("plan does not start with a source");
+        };
+        let total = vec_ops.len(payload.as_ref()).unwrap_or(0);
+        let pieces = (self.workers * self.partitions).min(total.max(1));
+        Ok(match vec_ops.split(payload.as_ref(), pieces) {
+            Some(parts) => parts
+                .into_iter()
+                .enumerate()
+                .filter(|(idx, _)| idx % self.workers == self.worker)
+                .map(|(_, part)| part)
+                .collect(),
+            None if self.worker == 0 => vec![
+                vec_ops
+                    .clone_any(payload.as_ref())
+                    .ok_or_else(|| anyhow!("source cannot be split or cloned"))?,
+            ],
+            None => Vec::new(),
+        })
+    }
+
+    fn coder(&self, step: usize) -> Result<&Arc<dyn ElementCoder>> {
+        self.coders[step]
+            .as_ref()
+            .ok_or_else(|| anyhow!("step {} has no coder", step + 1))
+    }
+}
+
+fn handle(job: &mut Option<Job>, registry: &DoFnRegistry, request: Request) -> Result<Response> {
+    if let Request::Plan {
+        spec,
+        terminal,
+        worker,
+        workers,
+        partitions,
+    } = request
+    {
+        let spec = PipelineSpec::from_json(&spec)?;
+        let (p, nodes) = Pipeline::from_spec(&spec, registry)?;
+        let id = *nodes
+            .get(terminal)
+            .ok_or_else(|| anyhow!("spec has no step {terminal}"))?;
+        let plan = build_plan(&p, id)?;
+        *job = Some(Job {
+            coders: plan.step_coders(&p),
+            chain: plan.chain,
+            worker,
+            workers,
+            partitions,
+            held: None,
+        });
+        return Ok(Response::Done);
+    }
+    let job = job.as_mut().ok_or_else(|| anyhow!("no pipeline planned"))?;
+    match request {
+        Request::Plan { .. } => unreachable!("handled above"),
+        Request::Run { to } => {
+            let (from, parts) = match job.held.take() {
+                Some(held) => held,
+                None => (0, job.shard()?),
+            };
+            if to <= from || to > job.chain.len() {
+                bail!("cannot run to step {to} from step {from}");
+            }
+            let parts = run_steps(&job.chain, from, parts, to, job.partitions)?;
+            job.held = Some((to - 1, parts));
+            Ok(Response::Done)
+        }
+        Request::Shuffle => {
+            let (step, parts) = job
+                .held
+                .take()
+                .ok_or_else(|| anyhow!("nothing to shuffle"))?;
+            let coder = job.coder(step)?;
+            let mut buckets = vec![(0u64, Vec::new()); job.workers];
+            for part in parts {
+                for (key, value) in coder.encode_kv_pairs(part)? {
+                    let (count, bytes) =
+                        &mut buckets[(key_hash(&key) % job.workers as u64) as usize];
+                    *count += 1;
+                    bytes.extend_from_slice(&key);
+                    bytes.extend_from_slice(&value);
+                }
+            }
+            job.held = Some((step, Vec::new()));
+            Ok(Response::Buckets(buckets))
+        }
+        Request::Load { count, bytes } => {
+            let step = job.held.as_ref().map(|(step, _)| *step);
+            let step = step.ok_or_else(|| anyhow!("nothing was shuffled"))?;
+            // A postcard `Vec<T>` is its varint length followed by the elements, and a
+            // pair encodes as its key bytes followed by its value bytes.
+            let mut blob = postcard::to_allocvec(&count)?;
+            blob.extend_from_slice(&bytes);
+            let part = job.coder(step)?.decode_partition(&blob)?;
+            job.held = Some((step, vec![part]));
+            Ok(Response::Done)
+        }
+        Request::Collect => {
+            let (step, parts) = job
+                .held
+                .take()
+                .ok_or_else(|| anyhow!("nothing to collect"))?;
+            let coder = job.coder(step)?;
+            let encoded = parts
+                .iter()
+                .map(|part| coder.encode_partition(part.as_ref()))
+                .collect::<Result<Vec<_>>>()?;
+            Ok(Response::Partitions(encoded))
+        }
+    }
+}
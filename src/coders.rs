@@ -15,7 +15,7 @@
 //! KV-aware coder so the value can be emitted as two independently
 //! length-prefixed halves, mirroring Beam's `kv<lp, lp>` coder concept.
 
-use std::any::type_name;
+use std::any::{Any, type_name};
 use std::marker::PhantomData;
 
 use anyhow::{Context, Result, anyhow};
@@ -70,6 +70,51 @@ pub trait ElementCoder: Send + Sync {
             self.type_name()
         ))
     }
+
+    /// Postcard-encode a whole `Vec<T>` partition as one blob, without consuming it.
+    /// Used to persist barrier outputs in checkpoints. The default errors; both
+    /// built-in coders implement it.
+    ///
+    /// # Errors
+    /// Returns an error if `partition` is not the expected `Vec<T>`, if it fails to
+    /// postcard-encode, or if the coder does not support whole-partition encoding.
+    fn encode_partition(&self, _partition: &dyn Any) -> Result<Vec<u8>> {
+        Err(anyhow!(
+            "coder for {} does not support whole-partition encoding",
+            self.type_name()
+        ))
+    }
+
+    /// Inverse of [`encode_partition`](Self::encode_partition): decode one blob back
+    /// into a `Vec<T>` partition.
+    ///
+    /// # Errors
+    /// Returns an error if `bytes` is not a valid postcard encoding of `Vec<T>`, or if
+    /// the coder does not support whole-partition decoding.
+    fn decode_partition(&self, _bytes: &[u8]) -> Result<Partition> {
+        Err(anyhow!(
+            "coder for {} does not support whole-partition decoding",
+            self.type_name()
+        ))
+    }
+}
+
+/// Postcard-encode `partition` as a `Vec<T>`.
+fn encode_vec<T: Serialize + 'static>(partition: &dyn Any) -> Result<Vec<u8>> {
+    let vec = partition.downcast_ref::<Vec<T>>().ok_or_else(|| {
+        anyhow!(
+            "partition downcast failed: expected Vec<{}>",
+            type_name::<T>()
+        )
+    })?;
+    to_allocvec(vec).with_context(|| format!("postcard encode Vec<{}>", type_name::<T>()))
+}
+
+/// Postcard-decode `bytes` into a `Vec<T>` partition.
+fn decode_vec<T: DeserializeOwned + Send + Sync + 'static>(bytes: &[u8]) -> Result<Partition> {
+    let vec: Vec<T> = from_bytes(bytes)
+        .with_context(|| format!("postcard decode into Vec<{}>", type_name::<T>()))?;
+    Ok(Box::new(vec))
 }
 
 /// Default coder: pairs `T` with postcard.
@@ -122,6 +167,14 @@ where
     fn type_name(&self) -> &'static str {
         type_name::<T>()
     }
+
+    fn encode_partition(&self, partition: &dyn Any) -> Result<Vec<u8>> {
+        encode_vec::<T>(partition)
+    }
+
+    fn decode_partition(&self, bytes: &[u8]) -> Result<Partition> {
+        decode_vec::<T>(bytes)
+    }
 }
 
 /// KV-aware coder.
@@ -183,6 +236,14 @@ where
         true
     }
 
+    fn encode_partition(&self, partition: &dyn Any) -> Result<Vec<u8>> {
+        encode_vec::<(K, V)>(partition)
+    }
+
+    fn decode_partition(&self, bytes: &[u8]) -> Result<Partition> {
+        decode_vec::<(K, V)>(bytes)
+    }
+
     fn encode_kv_pairs(&self, partition: Partition) -> Result<Vec<(Vec<u8>, Vec<u8>)>> {
         let vec = partition.downcast::<Vec<(K, V)>>().map_err(|_| {
             anyhow!(
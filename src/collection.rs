@@ -15,9 +15,10 @@
 //! windowing). This module provides the core types those helpers build upon.
 
 use crate::NodeId;
-use crate::node::DynOp;
+use crate::error::fail_step;
+use crate::node::{DynOp, KeyPredicate};
 use crate::pipeline::Pipeline;
-use crate::type_token::Partition;
+use crate::type_token::{LazyPartition, Partition};
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 use std::collections::HashMap;
@@ -179,6 +180,11 @@ where
         let out: Vec<O> = v.iter().map(|i| self.0(i)).collect();
         Box::new(out) as Partition
     }
+
+    fn apply_lazy(self: Arc<Self>, input: LazyPartition) -> LazyPartition {
+        let it = input.into_elems::<I>().expect("MapOp input type");
+        LazyPartition::stream(it.map(move |i| self.0(&i)))
+    }
 }
 
 /// Internal dynamic implementation for `map_values`.
@@ -200,6 +206,13 @@ where
         Box::new(out) as Partition
     }
 
+    fn apply_lazy(self: Arc<Self>, input: LazyPartition) -> LazyPartition {
+        let it = input
+            .into_elems::<(K, V)>()
+            .expect("MapValuesOp: expected Vec<(K,V)>");
+        LazyPartition::stream(it.map(move |(k, v)| (k, self.0(&v))))
+    }
+
     // Planner capability flags:
     fn key_preserving(&self) -> bool {
         true
@@ -213,6 +226,9 @@ where
     fn cost_hint(&self) -> u8 {
         3
     } // cheap, but keep filters before it
+    fn key_filter_before(&self, pred: &KeyPredicate) -> Option<Arc<dyn DynOp>> {
+        Some(Arc::new(KeyFilterOp::<K, V>::new(Arc::clone(pred))))
+    }
 }
 
 /// Internal dynamic implementation for `filter`.
@@ -229,6 +245,11 @@ where
         Box::new(out) as Partition
     }
 
+    fn apply_lazy(self: Arc<Self>, input: LazyPartition) -> LazyPartition {
+        let it = input.into_elems::<T>().expect("FilterOp input type");
+        LazyPartition::stream(it.filter(move |t| self.0(t)))
+    }
+
     fn cardinality_reducing(&self) -> bool {
         true
     }
@@ -252,6 +273,13 @@ where
         Box::new(out) as Partition
     }
 
+    fn apply_lazy(self: Arc<Self>, input: LazyPartition) -> LazyPartition {
+        let it = input
+            .into_elems::<(K, V)>()
+            .expect("FilterValuesOp: expected Vec<(K,V)>");
+        LazyPartition::stream(it.filter(move |(_, v)| self.0(v)))
+    }
+
     // Planner capability flags:
     fn key_preserving(&self) -> bool {
         true
@@ -270,6 +298,54 @@ where
     }
 }
 
+/// Internal dynamic implementation for `filter_keys`.
+///
+/// The predicate is stored with the key type erased so that the planner can rebuild the
+/// filter for a different value type when it moves it ahead of a `map_values`.
+pub(crate) struct KeyFilterOp<K, V>(pub KeyPredicate, pub PhantomData<(K, V)>);
+
+impl<K, V> KeyFilterOp<K, V> {
+    pub(crate) const fn new(pred: KeyPredicate) -> Self {
+        Self(pred, PhantomData)
+    }
+}
+
+impl<K, V> DynOp for KeyFilterOp<K, V>
+where
+    K: Element,
+    V: Element,
+{
+    fn apply(&self, p: Partition) -> Partition {
+        let pred = &self.0;
+        let kv = *p
+            .downcast::<Vec<(K, V)>>()
+            .expect("KeyFilterOp: expected Vec<(K,V)>");
+        let out: Vec<(K, V)> = kv.into_iter().filter(|(k, _)| pred(k)).collect();
+        Box::new(out) as Partition
+    }
+
+    fn apply_lazy(self: Arc<Self>, input: LazyPartition) -> LazyPartition {
+        let it = input
+            .into_elems::<(K, V)>()
+            .expect("KeyFilterOp: expected Vec<(K,V)>");
+        LazyPartition::stream(it.filter(move |(k, _)| self.0(k)))
+    }
+
+    // Planner capability flags:
+    fn key_preserving(&self) -> bool {
+        true
+    }
+    fn cost_hint(&self) -> u8 {
+        1
+    }
+    fn cardinality_reducing(&self) -> bool {
+        true
+    }
+    fn key_predicate(&self) -> Option<KeyPredicate> {
+        Some(Arc::clone(&self.0))
+    }
+}
+
 /// Internal dynamic implementation for `flat_map`.
 pub(crate) struct FlatMapOp<I, O, F>(pub F, pub PhantomData<(I, O)>);
 
@@ -287,6 +363,15 @@ where
         }
         Box::new(out) as Partition
     }
+
+    fn apply_lazy(self: Arc<Self>, input: LazyPartition) -> LazyPartition {
+        let it = input.into_elems::<I>().expect("FlatMapOp input type");
+        LazyPartition::stream(it.flat_map(move |i| self.0(&i)))
+    }
+
+    fn expanding(&self) -> bool {
+        true
+    }
 }
 
 /// Internal dynamic implementation for `take(N)` / `first()`.
@@ -308,9 +393,18 @@ impl<T: Element> DynOp for TakeOp<T> {
         Box::new(v) as Partition
     }
 
+    fn apply_lazy(self: Arc<Self>, input: LazyPartition) -> LazyPartition {
+        let it = input.into_elems::<T>().expect("TakeOp input type");
+        LazyPartition::stream(it.take(self.n))
+    }
+
     fn limit_n(&self) -> Option<usize> {
         Some(self.n)
     }
+
+    fn is_noop(&self) -> bool {
+        self.n == usize::MAX
+    }
 }
 
 // |----------------|
@@ -357,6 +451,33 @@ pub trait CombineFn<V, A, O>: Send + Sync + 'static {
     fn is_associative_commutative(&self) -> bool {
         false
     }
+
+    /// Returns `true` if adding a group of values in pieces gives the same accumulator as
+    /// adding it whole.
+    ///
+    /// This only matters for combiners whose input `V` is itself a group, such as the
+    /// `Vec<T>` values produced by `group_by_key`. For them it means
+    /// `add_input(acc, a ++ b)` is equivalent to `add_input(acc, a)` followed by
+    /// `add_input(acc, b)` (and so to merging two accumulators built from `a` and `b`).
+    ///
+    /// When `true`, the planner lifts `group_by_key().combine_values(self)` into
+    /// per-partition partial aggregation: each partition's values are grouped and
+    /// combined locally, and only accumulators are merged across partitions. The default
+    /// is `false`, which keeps the full `group_by_key` shuffle.
+    ///
+    /// The associative built-in combiners [`Sum`](crate::combiners::Sum),
+    /// [`Count<T>`](crate::combiners::Count), [`Min`](crate::combiners::Min),
+    /// [`Max`](crate::combiners::Max), [`DistinctCount`](crate::combiners::DistinctCount)
+    /// and [`DistinctSet`](crate::combiners::DistinctSet) also combine `Vec<T>` groups
+    /// value by value and return `true` there, so
+    /// `group_by_key().combine_values(Sum::new())` is lifted. Name the value type for
+    /// the others (`Count::<u64>::new()`), since `Count<Vec<u64>>`, counting whole
+    /// groups, fits too. Combiners that see a whole
+    /// group as one element, such as the untyped [`Count`] or `TopK` over groups, keep
+    /// the default.
+    fn accepts_partial_groups(&self) -> bool {
+        false
+    }
 }
 
 /// Built-in combiner that **counts** values per key.
@@ -466,6 +587,32 @@ where
     }
 }
 
+/// `TryPartitionMapOp`: `Vec<T> -> Result<Vec<O>>`, applied once to the whole partition.
+/// An error fails the step (see [`fail_step`]).
+/// Used by the service-backed transforms that batch their calls per partition.
+pub(crate) struct TryPartitionMapOp<T, O, F>(pub F, pub PhantomData<(T, O)>)
+where
+    T: 'static + Send + Sync + Clone,
+    O: 'static + Send + Sync + Clone,
+    F: 'static + Send + Sync + Fn(Vec<T>) -> anyhow::Result<Vec<O>>;
+
+impl<T, O, F> DynOp for TryPartitionMapOp<T, O, F>
+where
+    T: 'static + Send + Sync + Clone,
+    O: 'static + Send + Sync + Clone,
+    F: 'static + Send + Sync + Fn(Vec<T>) -> anyhow::Result<Vec<O>>,
+{
+    fn apply(&self, input: Partition) -> Partition {
+        let v = *input
+            .downcast::<Vec<T>>()
+            .expect("TryPartitionMapOp: expected Vec<T> input");
+        match (self.0)(v) {
+            Ok(out) => Box::new(out) as Partition,
+            Err(e) => fail_step(&e),
+        }
+    }
+}
+
 /// `BatchMapValuesOp`: `&[V] -> Vec<O>`, preserves keys, applies per contiguous value slice.
 /// IMPORTANT: f must output exactly as many items as the input slice length.
 /// Used by `map_values_batches`.
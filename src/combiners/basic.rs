@@ -1,4 +1,5 @@
-//! Basic arithmetic combiners: Sum, Min, Max
+//! Basic arithmetic combiners: Sum, Min, Max, and the payload-preserving
+//! MinBy / MaxBy.
 
 use crate::Element;
 use crate::collection::CombineFn;
@@ -50,6 +51,41 @@ where
     }
 }
 
+/// Combines `group_by_key` output value by value: each group's values are added up. A group split across
+/// partitions gives the same result, so the planner lifts
+/// `group_by_key().combine_values(Sum::new())` (see
+/// [`CombineFn::accepts_partial_groups`]).
+impl<T> CombineFn<Vec<T>, T, T> for Sum<T>
+where
+    T: Element + Add<Output = T> + Default,
+{
+    fn create(&self) -> T {
+        T::default()
+    }
+
+    fn add_input(&self, acc: &mut T, group: Vec<T>) {
+        for v in group {
+            CombineFn::<T, T, T>::add_input(self, acc, v);
+        }
+    }
+
+    fn merge(&self, acc: &mut T, other: T) {
+        CombineFn::<T, T, T>::merge(self, acc, other);
+    }
+
+    fn finish(&self, acc: T) -> T {
+        acc
+    }
+
+    fn is_associative_commutative(&self) -> bool {
+        true
+    }
+
+    fn accepts_partial_groups(&self) -> bool {
+        true
+    }
+}
+
 /* ===================== Min<T> ===================== */
 
 /// Minimum value per key (requires `Ord`).
@@ -107,6 +143,41 @@ where
     }
 }
 
+/// Combines `group_by_key` output value by value: the smallest value of each group. A group split across
+/// partitions gives the same result, so the planner lifts
+/// `group_by_key().combine_values(Min::<T>::new())` (see
+/// [`CombineFn::accepts_partial_groups`]).
+impl<T> CombineFn<Vec<T>, Option<T>, T> for Min<T>
+where
+    T: Element + Ord,
+{
+    fn create(&self) -> Option<T> {
+        None
+    }
+
+    fn add_input(&self, acc: &mut Option<T>, group: Vec<T>) {
+        for v in group {
+            CombineFn::<T, Option<T>, T>::add_input(self, acc, v);
+        }
+    }
+
+    fn merge(&self, acc: &mut Option<T>, other: Option<T>) {
+        CombineFn::<T, Option<T>, T>::merge(self, acc, other);
+    }
+
+    fn finish(&self, acc: Option<T>) -> T {
+        acc.expect("Min::finish called on empty group")
+    }
+
+    fn is_associative_commutative(&self) -> bool {
+        true
+    }
+
+    fn accepts_partial_groups(&self) -> bool {
+        true
+    }
+}
+
 /* ===================== Max<T> ===================== */
 
 /// Maximum value per key (requires `Ord`).
@@ -163,3 +234,141 @@ where
         true
     }
 }
+
+/// Combines `group_by_key` output value by value: the largest value of each group. A group split across
+/// partitions gives the same result, so the planner lifts
+/// `group_by_key().combine_values(Max::<T>::new())` (see
+/// [`CombineFn::accepts_partial_groups`]).
+impl<T> CombineFn<Vec<T>, Option<T>, T> for Max<T>
+where
+    T: Element + Ord,
+{
+    fn create(&self) -> Option<T> {
+        None
+    }
+
+    fn add_input(&self, acc: &mut Option<T>, group: Vec<T>) {
+        for v in group {
+            CombineFn::<T, Option<T>, T>::add_input(self, acc, v);
+        }
+    }
+
+    fn merge(&self, acc: &mut Option<T>, other: Option<T>) {
+        CombineFn::<T, Option<T>, T>::merge(self, acc, other);
+    }
+
+    fn finish(&self, acc: Option<T>) -> T {
+        acc.expect("Max::finish called on empty group")
+    }
+
+    fn is_associative_commutative(&self) -> bool {
+        true
+    }
+
+    fn accepts_partial_groups(&self) -> bool {
+        true
+    }
+}
+
+/* ===================== MinBy<F> / MaxBy<F> ===================== */
+
+/// The value with the **smallest** derived key per key, returned whole.
+///
+/// `key_fn` projects each value onto an `Ord` key; unlike [`Min`], the value
+/// itself need not be `Ord`, so payloads ride along without tuple tricks.
+/// When several values share the smallest key, an arbitrary one is kept.
+///
+/// - Accumulator: `Option<T>`
+/// - Output: `T`
+///
+/// # Examples
+/// ```no_run
+/// # use anyhow::Result;
+/// use ironbeam::*;
+/// use ironbeam::combiners::MinBy;
+/// # fn main() -> Result<()> {
+/// let p = Pipeline::default();
+/// let cheapest = from_vec(&p, vec![
+///     ("fruit".to_string(), ("apple".to_string(), 3u32)),
+///     ("fruit".to_string(), ("pear".to_string(), 2)),
+/// ])
+///     .combine_values(MinBy::new(|(_, price): &(String, u32)| *price))
+///     .collect_seq()?;
+/// assert_eq!(cheapest[0].1.0, "pear");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy)]
+pub struct MinBy<F> {
+    key_fn: F,
+}
+
+impl<F> MinBy<F> {
+    /// Select by the key `key_fn` derives from each value.
+    #[must_use]
+    pub const fn new(key_fn: F) -> Self {
+        Self { key_fn }
+    }
+}
+
+/// The value with the **largest** derived key per key, returned whole.
+///
+/// The mirror image of [`MinBy`]; see it for details.
+///
+/// - Accumulator: `Option<T>`
+/// - Output: `T`
+#[derive(Clone, Copy)]
+pub struct MaxBy<F> {
+    key_fn: F,
+}
+
+impl<F> MaxBy<F> {
+    /// Select by the key `key_fn` derives from each value.
+    #[must_use]
+    pub const fn new(key_fn: F) -> Self {
+        Self { key_fn }
+    }
+}
+
+macro_rules! impl_select_by {
+    ($name:ident, $label:literal, $replace:expr) => {
+        impl<T, K, F> CombineFn<T, Option<T>, T> for $name<F>
+        where
+            T: Element,
+            K: Ord,
+            F: Fn(&T) -> K + Send + Sync + 'static,
+        {
+            fn create(&self) -> Option<T> {
+                None
+            }
+
+            fn add_input(&self, acc: &mut Option<T>, v: T) {
+                self.merge(acc, Some(v));
+            }
+
+            fn merge(&self, acc: &mut Option<T>, other: Option<T>) {
+                let Some(b) = other else { return };
+                match acc {
+                    Some(a) => {
+                        let replace: fn(&K, &K) -> bool = $replace;
+                        if replace(&(self.key_fn)(&b), &(self.key_fn)(a)) {
+                            *a = b;
+                        }
+                    }
+                    None => *acc = Some(b),
+                }
+            }
+
+            fn finish(&self, acc: Option<T>) -> T {
+                acc.expect(concat!($label, "::finish called on empty group"))
+            }
+
+            fn is_associative_commutative(&self) -> bool {
+                true
+            }
+        }
+    };
+}
+
+impl_select_by!(MinBy, "MinBy", |new, cur| new < cur);
+impl_select_by!(MaxBy, "MaxBy", |new, cur| new > cur);
@@ -0,0 +1,77 @@
+//! Bloom filter combiner: `BloomFilterBuilder`.
+
+use crate::Element;
+use crate::bloom_filter::BloomFilter;
+use crate::collection::CombineFn;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+/* ===================== BloomFilterBuilder<T> ===================== */
+
+/// Build a [`BloomFilter`] over every value.
+///
+/// The filter is sized for `expected_items` insertions at a ~1 % false-positive
+/// rate; inserting more than that still works but raises the rate. Partial
+/// filters are merged by bitwise OR, so the result is identical under the
+/// sequential and parallel runners.
+///
+/// - Accumulator: [`BloomFilter`]
+/// - Output: [`BloomFilter`]
+///
+/// # Examples
+/// ```no_run
+/// # use anyhow::Result;
+/// use ironbeam::*;
+/// use ironbeam::combiners::BloomFilterBuilder;
+/// # fn main() -> Result<()> {
+/// let p = Pipeline::default();
+/// let filter = from_vec(&p, vec![1u32, 2, 3])
+///     .combine_globally(BloomFilterBuilder::new(3), None)
+///     .collect_seq()?
+///     .remove(0);
+/// assert!(filter.might_contain(&2u32));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct BloomFilterBuilder<T> {
+    expected_items: usize,
+    _m: PhantomData<T>,
+}
+
+impl<T> BloomFilterBuilder<T> {
+    /// Create a builder sized for `expected_items` distinct values.
+    #[must_use]
+    pub const fn new(expected_items: usize) -> Self {
+        Self {
+            expected_items,
+            _m: PhantomData,
+        }
+    }
+}
+
+impl<T> CombineFn<T, BloomFilter, BloomFilter> for BloomFilterBuilder<T>
+where
+    T: Element + Hash,
+{
+    fn create(&self) -> BloomFilter {
+        BloomFilter::new(self.expected_items)
+    }
+
+    fn add_input(&self, acc: &mut BloomFilter, v: T) {
+        acc.insert(&v);
+    }
+
+    fn merge(&self, acc: &mut BloomFilter, other: BloomFilter) {
+        acc.merge(&other)
+            .expect("matching size (both filters built by this combiner)");
+    }
+
+    fn finish(&self, acc: BloomFilter) -> BloomFilter {
+        acc
+    }
+
+    fn is_associative_commutative(&self) -> bool {
+        true
+    }
+}
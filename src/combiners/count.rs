@@ -73,3 +73,38 @@ where
         true
     }
 }
+
+/// Combines `group_by_key` output value by value: the values of each group are counted. A group split across
+/// partitions gives the same result, so the planner lifts
+/// `group_by_key().combine_values(Count::<T>::new())` (see
+/// [`CombineFn::accepts_partial_groups`]).
+impl<T> CombineFn<Vec<T>, u64, u64> for Count<T>
+where
+    T: Element,
+{
+    fn create(&self) -> u64 {
+        0
+    }
+
+    fn add_input(&self, acc: &mut u64, group: Vec<T>) {
+        for v in group {
+            CombineFn::<T, u64, u64>::add_input(self, acc, v);
+        }
+    }
+
+    fn merge(&self, acc: &mut u64, other: u64) {
+        CombineFn::<T, u64, u64>::merge(self, acc, other);
+    }
+
+    fn finish(&self, acc: u64) -> u64 {
+        acc
+    }
+
+    fn is_associative_commutative(&self) -> bool {
+        true
+    }
+
+    fn accepts_partial_groups(&self) -> bool {
+        true
+    }
+}
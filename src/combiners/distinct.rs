@@ -1,10 +1,13 @@
 //! Distinct value combiners: `DistinctCount`, `DistinctSet`,
-//! `KMVApproxDistinctCount`, `HllApproxDistinctCount`.
+//! `KMVApproxDistinctCount`, `HllApproxDistinctCount`, and the mergeable
+//! `HyperLogLog` sketch combiner.
 
 use crate::Element;
 use crate::collection::CombineFn;
-use hyperloglogplus::{HyperLogLog, HyperLogLogPlus};
+use anyhow::{Result, bail};
+use hyperloglogplus::{HyperLogLog as _, HyperLogLogPlus};
 use ordered_float::NotNan;
+use serde::{Deserialize, Serialize};
 use std::collections::{BinaryHeap, HashSet};
 use std::hash::{BuildHasherDefault, DefaultHasher, Hash, Hasher};
 use std::marker::PhantomData;
@@ -56,6 +59,41 @@ where
     }
 }
 
+/// Combines `group_by_key` output value by value: the distinct values of each group are counted. A group split across
+/// partitions gives the same result, so the planner lifts
+/// `group_by_key().combine_values(DistinctCount::<T>::new())` (see
+/// [`CombineFn::accepts_partial_groups`]).
+impl<T> CombineFn<Vec<T>, HashSet<T>, u64> for DistinctCount<T>
+where
+    T: Element + Eq + Hash,
+{
+    fn create(&self) -> HashSet<T> {
+        HashSet::new()
+    }
+
+    fn add_input(&self, acc: &mut HashSet<T>, group: Vec<T>) {
+        for v in group {
+            CombineFn::<T, HashSet<T>, u64>::add_input(self, acc, v);
+        }
+    }
+
+    fn merge(&self, acc: &mut HashSet<T>, other: HashSet<T>) {
+        CombineFn::<T, HashSet<T>, u64>::merge(self, acc, other);
+    }
+
+    fn finish(&self, acc: HashSet<T>) -> u64 {
+        acc.len() as u64
+    }
+
+    fn is_associative_commutative(&self) -> bool {
+        true
+    }
+
+    fn accepts_partial_groups(&self) -> bool {
+        true
+    }
+}
+
 /* ===================== DistinctSet<T> (exact set) ===================== */
 
 /// Get the distinct elements over a stream: accumulates a `HashSet<T>` and outputs a `Vec<T>`.
@@ -102,6 +140,41 @@ where
     }
 }
 
+/// Combines `group_by_key` output value by value: the distinct values of each group are collected. A group split across
+/// partitions gives the same result, so the planner lifts
+/// `group_by_key().combine_values(DistinctSet::<T>::new())` (see
+/// [`CombineFn::accepts_partial_groups`]).
+impl<T> CombineFn<Vec<T>, HashSet<T>, Vec<T>> for DistinctSet<T>
+where
+    T: Element + Eq + Hash,
+{
+    fn create(&self) -> HashSet<T> {
+        HashSet::new()
+    }
+
+    fn add_input(&self, acc: &mut HashSet<T>, group: Vec<T>) {
+        for v in group {
+            CombineFn::<T, HashSet<T>, Vec<T>>::add_input(self, acc, v);
+        }
+    }
+
+    fn merge(&self, acc: &mut HashSet<T>, other: HashSet<T>) {
+        CombineFn::<T, HashSet<T>, Vec<T>>::merge(self, acc, other);
+    }
+
+    fn finish(&self, acc: HashSet<T>) -> Vec<T> {
+        acc.into_iter().collect()
+    }
+
+    fn is_associative_commutative(&self) -> bool {
+        true
+    }
+
+    fn accepts_partial_groups(&self) -> bool {
+        true
+    }
+}
+
 /* ===================== KMVApproxDistinctCount<T> (approximate count) ===================== */
 
 /// Approximate distinct count via the KMV (K-Minimum Values) estimator.
@@ -387,3 +460,361 @@ where
         true
     }
 }
+
+/* ===================== HyperLogLog<T> (mergeable, serializable sketch) ===================== */
+
+const HLL_MIN_PRECISION: u8 = 4;
+const HLL_MAX_PRECISION: u8 = 18;
+
+/// A dense `HyperLogLog` register array that can be serialized, stored, and
+/// merged with sketches produced by other pipeline runs.
+///
+/// Unlike the accumulator behind [`HllApproxDistinctCount`], this sketch
+/// owns a plain `Vec<u8>` of registers, so it round-trips through serde or
+/// the compact [`to_bytes`](Self::to_bytes) / [`from_bytes`](Self::from_bytes)
+/// encoding. Two sketches with the same precision merge by taking the
+/// register-wise maximum, which makes "yesterday's sketch + today's data"
+/// style incremental aggregation exact with respect to a single pass.
+///
+/// Values are hashed with a fixed algorithm and seed (64-bit FNV-1a with a
+/// `MurmurHash3` finalizer, integers fed little-endian), so sketches built by different binaries, toolchains and platforms merge,
+/// as long as the value type's `Hash` implementation stays the same.
+/// Deserializing checks the sketch as [`from_bytes`](Self::from_bytes) does.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "HllSketchParts")]
+pub struct HllSketch {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+impl HllSketch {
+    /// Build an empty sketch with `2^precision` registers; `precision` is
+    /// clamped to `[4, 18]`.
+    #[must_use]
+    pub fn new(precision: u8) -> Self {
+        let p = precision.clamp(HLL_MIN_PRECISION, HLL_MAX_PRECISION);
+        Self {
+            precision: p,
+            registers: vec![0; 1 << p],
+        }
+    }
+
+    /// Return the sketch precision (4..=18).
+    #[must_use]
+    pub const fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    /// True if no value has been inserted (every register is zero).
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.registers.iter().all(|&r| r == 0)
+    }
+
+    /// Insert one value.
+    pub fn insert<T: Hash + ?Sized>(&mut self, v: &T) {
+        let mut h = SketchHasher::default();
+        v.hash(&mut h);
+        let hash = h.finish();
+        let p = u32::from(self.precision);
+        let idx = usize::try_from(hash >> (64 - p)).expect("register index fits in usize");
+        // Sentinel bit bounds the rank at `64 - p + 1` when the tail is all zeros.
+        let tail = (hash << p) | (1 << (p - 1));
+        #[allow(clippy::cast_possible_truncation)]
+        let rank = (tail.leading_zeros() + 1) as u8;
+        if rank > self.registers[idx] {
+            self.registers[idx] = rank;
+        }
+    }
+
+    /// Merge `other` into `self` (register-wise maximum).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the two sketches have different precisions.
+    pub fn merge(&mut self, other: &Self) -> Result<()> {
+        if self.precision != other.precision {
+            bail!(
+                "cannot merge HLL sketches with precision {} and {}",
+                self.precision,
+                other.precision
+            );
+        }
+        for (a, &b) in self.registers.iter_mut().zip(&other.registers) {
+            *a = (*a).max(b);
+        }
+        Ok(())
+    }
+
+    /// Estimated number of distinct values inserted so far.
+    ///
+    /// Uses the classic `HyperLogLog` estimator with linear counting for
+    /// the small-cardinality range; the 64-bit hash makes the large-range
+    /// correction unnecessary.
+    #[must_use]
+    #[allow(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss
+    )]
+    pub fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / m),
+        };
+        let mut sum = 0.0;
+        let mut zeros = 0usize;
+        for &r in &self.registers {
+            sum += 2f64.powi(-i32::from(r));
+            if r == 0 {
+                zeros += 1;
+            }
+        }
+        let raw = alpha * m * m / sum;
+        let est = if raw <= 2.5 * m && zeros > 0 {
+            m * (m / zeros as f64).ln()
+        } else {
+            raw
+        };
+        est.round() as u64
+    }
+
+    /// Encode as `[precision, registers...]`.
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = Vec::with_capacity(1 + self.registers.len());
+        out.push(self.precision);
+        out.extend_from_slice(&self.registers);
+        out
+    }
+
+    /// Decode a sketch produced by [`to_bytes`](Self::to_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the precision byte is out of range or the
+    /// register count does not match it.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let Some((&precision, registers)) = bytes.split_first() else {
+            bail!("empty HLL sketch encoding");
+        };
+        Self::try_from(HllSketchParts {
+            precision,
+            registers: registers.to_vec(),
+        })
+    }
+}
+
+/// The serialized fields of an [`HllSketch`], checked before they become one.
+#[derive(Deserialize)]
+struct HllSketchParts {
+    precision: u8,
+    registers: Vec<u8>,
+}
+
+impl TryFrom<HllSketchParts> for HllSketch {
+    type Error = anyhow::Error;
+
+    fn try_from(parts: HllSketchParts) -> Result<Self> {
+        let HllSketchParts {
+            precision,
+            registers,
+        } = parts;
+        if !(HLL_MIN_PRECISION..=HLL_MAX_PRECISION).contains(&precision) {
+            bail!("HLL sketch precision {precision} outside [4, 18]");
+        }
+        if registers.len() != 1 << precision {
+            bail!(
+                "HLL sketch with precision {precision} needs {} registers, got {}",
+                1usize << precision,
+                registers.len()
+            );
+        }
+        Ok(Self {
+            precision,
+            registers,
+        })
+    }
+}
+
+/// The hash behind [`HllSketch`]: 64-bit FNV-1a from the standard offset
+/// basis, finished with the `MurmurHash3` 64-bit mixer so every output bit
+/// depends on every input bit. Integers are fed little-endian and `usize`
+/// lengths as `u64`, so a value hashes the same on every platform.
+struct SketchHasher(u64);
+
+impl Default for SketchHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for SketchHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            self.0 = (self.0 ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn write_u16(&mut self, n: u16) {
+        self.write(&n.to_le_bytes());
+    }
+
+    fn write_u32(&mut self, n: u32) {
+        self.write(&n.to_le_bytes());
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        self.write(&n.to_le_bytes());
+    }
+
+    fn write_u128(&mut self, n: u128) {
+        self.write(&n.to_le_bytes());
+    }
+
+    fn write_usize(&mut self, n: usize) {
+        self.write_u64(n as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        let mut h = self.0;
+        h ^= h >> 33;
+        h = h.wrapping_mul(0xff51_afd7_ed55_8ccd);
+        h ^= h >> 33;
+        h = h.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+        h ^ (h >> 33)
+    }
+}
+
+/// Output of the [`HyperLogLog`] combiner: the cardinality estimate plus,
+/// when requested via [`HyperLogLog::with_sketch`], the sketch it came from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApproxDistinct {
+    estimate: u64,
+    sketch: Option<HllSketch>,
+}
+
+impl ApproxDistinct {
+    /// Estimated distinct count.
+    #[must_use]
+    pub const fn estimate(&self) -> u64 {
+        self.estimate
+    }
+
+    /// The underlying sketch, if the combiner was built with
+    /// [`HyperLogLog::with_sketch`].
+    #[must_use]
+    pub const fn sketch(&self) -> Option<&HllSketch> {
+        self.sketch.as_ref()
+    }
+
+    /// Consume the output and return the sketch, if any.
+    #[must_use]
+    pub fn into_sketch(self) -> Option<HllSketch> {
+        self.sketch
+    }
+}
+
+/// Approximate distinct count backed by a mergeable [`HllSketch`].
+///
+/// Works with `combine_values`, `combine_values_lifted`, and
+/// `combine_globally`. By default only the estimate is emitted; call
+/// [`with_sketch`](Self::with_sketch) to also emit the sketch so it can be
+/// persisted and merged with later runs:
+///
+/// ```no_run
+/// # use anyhow::Result;
+/// use ironbeam::*;
+/// use ironbeam::combiners::HyperLogLog;
+///
+/// # fn main() -> Result<()> {
+/// let p = Pipeline::default();
+/// let out = from_vec(&p, (0u32..5_000).collect::<Vec<_>>())
+///     .combine_globally(HyperLogLog::<u32>::with_precision(14).with_sketch(), None)
+///     .collect_seq()?;
+/// let bytes = out[0].sketch().unwrap().to_bytes(); // store for tomorrow
+/// # let _ = bytes;
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug)]
+pub struct HyperLogLog<T> {
+    precision: u8,
+    emit_sketch: bool,
+    _m: PhantomData<T>,
+}
+
+impl<T> HyperLogLog<T> {
+    /// Build a combiner with `precision = 12` (~1.6 % relative error).
+    #[must_use]
+    pub const fn new() -> Self {
+        Self::with_precision(12)
+    }
+
+    /// Build a combiner with the given precision, clamped to `[4, 18]`.
+    #[must_use]
+    pub const fn with_precision(precision: u8) -> Self {
+        let p = if precision < HLL_MIN_PRECISION {
+            HLL_MIN_PRECISION
+        } else if precision > HLL_MAX_PRECISION {
+            HLL_MAX_PRECISION
+        } else {
+            precision
+        };
+        Self {
+            precision: p,
+            emit_sketch: false,
+            _m: PhantomData,
+        }
+    }
+
+    /// Also emit the sketch in each [`ApproxDistinct`] output.
+    #[must_use]
+    pub const fn with_sketch(mut self) -> Self {
+        self.emit_sketch = true;
+        self
+    }
+
+    /// Return the configured precision (4..=18).
+    #[must_use]
+    pub const fn precision(&self) -> u8 {
+        self.precision
+    }
+}
+
+impl<T> Default for HyperLogLog<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> CombineFn<T, HllSketch, ApproxDistinct> for HyperLogLog<T>
+where
+    T: Element + Hash,
+{
+    fn create(&self) -> HllSketch {
+        HllSketch::new(self.precision)
+    }
+
+    fn add_input(&self, acc: &mut HllSketch, v: T) {
+        acc.insert(&v);
+    }
+
+    fn merge(&self, acc: &mut HllSketch, other: HllSketch) {
+        acc.merge(&other)
+            .expect("matching precision (both sketches built by this combiner)");
+    }
+
+    fn finish(&self, acc: HllSketch) -> ApproxDistinct {
+        ApproxDistinct {
+            estimate: acc.estimate(),
+            sketch: self.emit_sketch.then_some(acc),
+        }
+    }
+
+    fn is_associative_commutative(&self) -> bool {
+        true
+    }
+}
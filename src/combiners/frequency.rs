@@ -0,0 +1,362 @@
+//! Approximate frequency combiners: `CountMinSketch`, `TopKHeavyHitters`.
+//!
+//! Both track per-value frequencies in a fixed-size Count-Min table, so memory
+//! is bounded by the sketch dimensions rather than the number of distinct
+//! values in the stream.
+
+use crate::Element;
+use crate::collection::CombineFn;
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::marker::PhantomData;
+
+/* ===================== FrequencySketch<T> ===================== */
+
+/// A Count-Min table of `depth` rows by `width` counters.
+///
+/// [`estimate`](Self::estimate) never under-counts; with `width = ⌈e/ε⌉` and
+/// `depth = ⌈ln(1/δ)⌉` it over-counts by at most `ε · total()` with
+/// probability `1 - δ`. Sketches with the same dimensions merge by adding
+/// their tables, so per-run sketches can be persisted and combined later.
+///
+/// Values are hashed with the zero-keyed std `DefaultHasher`; merged sketches
+/// must come from binaries built with the same Rust toolchain.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "")]
+pub struct FrequencySketch<T> {
+    width: usize,
+    depth: usize,
+    total: u64,
+    table: Vec<u64>,
+    _m: PhantomData<T>,
+}
+
+impl<T: Hash> FrequencySketch<T> {
+    /// Build an empty sketch; both dimensions are raised to at least 1.
+    #[must_use]
+    pub fn new(width: usize, depth: usize) -> Self {
+        let (width, depth) = (width.max(1), depth.max(1));
+        Self {
+            width,
+            depth,
+            total: 0,
+            table: vec![0; width * depth],
+            _m: PhantomData,
+        }
+    }
+
+    /// Number of counters per row.
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Number of rows (independent hash functions).
+    #[must_use]
+    pub const fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Total weight of everything added.
+    #[must_use]
+    pub const fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Add `count` occurrences of `v`.
+    pub fn add(&mut self, v: &T, count: u64) {
+        for slot in self.slots(v) {
+            self.table[slot] = self.table[slot].saturating_add(count);
+        }
+        self.total = self.total.saturating_add(count);
+    }
+
+    /// Estimated number of occurrences of `v` (an upper bound).
+    #[must_use]
+    pub fn estimate(&self, v: &T) -> u64 {
+        self.slots(v)
+            .map(|slot| self.table[slot])
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Add `other`'s counts into `self`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the two sketches have different dimensions.
+    pub fn merge(&mut self, other: &Self) -> Result<()> {
+        if (self.width, self.depth) != (other.width, other.depth) {
+            bail!(
+                "cannot merge count-min sketches of {}x{} and {}x{}",
+                self.depth,
+                self.width,
+                other.depth,
+                other.width
+            );
+        }
+        for (a, &b) in self.table.iter_mut().zip(&other.table) {
+            *a = a.saturating_add(b);
+        }
+        self.total = self.total.saturating_add(other.total);
+        Ok(())
+    }
+
+    /// Table index of `v` in each row, via double hashing of one 64-bit hash.
+    fn slots(&self, v: &T) -> impl Iterator<Item = usize> + use<T> {
+        let mut h = DefaultHasher::new();
+        v.hash(&mut h);
+        let hash = h.finish();
+        let (h1, h2) = (hash & 0xFFFF_FFFF, (hash >> 32) | 1);
+        let width = self.width;
+        (0..self.depth).map(move |row| {
+            let col = h1.wrapping_add((row as u64).wrapping_mul(h2)) % width as u64;
+            #[allow(clippy::cast_possible_truncation)]
+            let col = col as usize;
+            row * width + col
+        })
+    }
+}
+
+/// Sketch dimensions for a target error `epsilon` and failure probability `delta`.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::suboptimal_flops
+)]
+fn dims_for(epsilon: f64, delta: f64) -> (usize, usize) {
+    assert!(
+        epsilon.is_finite() && epsilon > 0.0 && epsilon < 1.0,
+        "count-min epsilon must be in (0, 1), got {epsilon}"
+    );
+    assert!(
+        delta.is_finite() && delta > 0.0 && delta < 1.0,
+        "count-min delta must be in (0, 1), got {delta}"
+    );
+    let width = (std::f64::consts::E / epsilon).ceil() as usize;
+    let depth = (1.0 / delta).ln().ceil() as usize;
+    (width, depth.max(1))
+}
+
+/* ===================== CountMinSketch<T> ===================== */
+
+/// Approximate per-value frequencies with bounded memory.
+///
+/// Emits the whole [`FrequencySketch`], which answers "how often did `v`
+/// occur?" for any `v` after the fact.
+///
+/// - Accumulator: [`FrequencySketch<T>`]
+/// - Output: [`FrequencySketch<T>`]
+///
+/// # Examples
+/// ```no_run
+/// # use anyhow::Result;
+/// use ironbeam::*;
+/// use ironbeam::combiners::CountMinSketch;
+/// # fn main() -> Result<()> {
+/// let p = Pipeline::default();
+/// let sketch = from_vec(&p, vec![1u32, 2, 1, 3, 1])
+///     .combine_globally(CountMinSketch::with_error(0.001, 0.01), None)
+///     .collect_seq()?
+///     .remove(0);
+/// assert!(sketch.estimate(&1) >= 3);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct CountMinSketch<T> {
+    width: usize,
+    depth: usize,
+    _m: PhantomData<T>,
+}
+
+impl<T> CountMinSketch<T> {
+    /// Create a combiner with explicit dimensions.
+    #[must_use]
+    pub const fn new(width: usize, depth: usize) -> Self {
+        Self {
+            width,
+            depth,
+            _m: PhantomData,
+        }
+    }
+
+    /// Size the sketch so estimates exceed the true count by at most
+    /// `epsilon · total` with probability `1 - delta`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `epsilon` or `delta` is not in `(0, 1)`.
+    #[must_use]
+    pub fn with_error(epsilon: f64, delta: f64) -> Self {
+        let (width, depth) = dims_for(epsilon, delta);
+        Self::new(width, depth)
+    }
+}
+
+impl<T> CombineFn<T, FrequencySketch<T>, FrequencySketch<T>> for CountMinSketch<T>
+where
+    T: Element + Hash,
+{
+    fn create(&self) -> FrequencySketch<T> {
+        FrequencySketch::new(self.width, self.depth)
+    }
+
+    fn add_input(&self, acc: &mut FrequencySketch<T>, v: T) {
+        acc.add(&v, 1);
+    }
+
+    fn merge(&self, acc: &mut FrequencySketch<T>, other: FrequencySketch<T>) {
+        acc.merge(&other)
+            .expect("matching dimensions (both sketches built by this combiner)");
+    }
+
+    fn finish(&self, acc: FrequencySketch<T>) -> FrequencySketch<T> {
+        acc
+    }
+
+    fn is_associative_commutative(&self) -> bool {
+        true
+    }
+}
+
+/* ===================== TopKHeavyHitters<T> ===================== */
+
+/// Accumulator for [`TopKHeavyHitters`]: a Count-Min table plus a bounded
+/// pool of candidate values and their latest estimates.
+#[derive(Clone, Debug)]
+pub struct HeavyHittersAcc<T> {
+    sketch: FrequencySketch<T>,
+    candidates: HashMap<T, u64>,
+}
+
+/// The `k` most frequent values, estimated with a Count-Min sketch.
+///
+/// Each accumulator keeps at most `4k` candidate values, evicting the one with
+/// the lowest estimate when full; on merge, candidates from both sides are
+/// re-estimated against the merged table. Counts are Count-Min estimates and
+/// may over-count by up to `epsilon · total`.
+///
+/// - Accumulator: [`HeavyHittersAcc<T>`]
+/// - Output: `Vec<(T, u64)>` sorted by estimated count, descending.
+///
+/// # Examples
+/// ```no_run
+/// # use anyhow::Result;
+/// use ironbeam::*;
+/// use ironbeam::combiners::TopKHeavyHitters;
+/// # fn main() -> Result<()> {
+/// let p = Pipeline::default();
+/// let top = from_vec(&p, vec![1u32, 2, 1, 3, 1, 2])
+///     .combine_globally(TopKHeavyHitters::new(2), None)
+///     .collect_seq()?;
+/// assert_eq!(top[0][0].0, 1);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct TopKHeavyHitters<T> {
+    k: usize,
+    width: usize,
+    depth: usize,
+    _m: PhantomData<T>,
+}
+
+impl<T> TopKHeavyHitters<T> {
+    /// Track the top `k` values with a sketch sized for `epsilon = 0.001`,
+    /// `delta = 0.01`.
+    #[must_use]
+    pub fn new(k: usize) -> Self {
+        Self::with_error(k, 0.001, 0.01)
+    }
+
+    /// Track the top `k` values with a sketch sized for the given error
+    /// bounds (see [`CountMinSketch::with_error`]).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `epsilon` or `delta` is not in `(0, 1)`.
+    #[must_use]
+    pub fn with_error(k: usize, epsilon: f64, delta: f64) -> Self {
+        let (width, depth) = dims_for(epsilon, delta);
+        Self {
+            k,
+            width,
+            depth,
+            _m: PhantomData,
+        }
+    }
+
+    const fn capacity(&self) -> usize {
+        self.k.saturating_mul(4)
+    }
+}
+
+impl<T: Element + Eq + Hash> HeavyHittersAcc<T> {
+    fn offer(&mut self, v: T, estimate: u64, capacity: usize) {
+        if let Some(c) = self.candidates.get_mut(&v) {
+            *c = estimate;
+            return;
+        }
+        if self.candidates.len() < capacity {
+            self.candidates.insert(v, estimate);
+            return;
+        }
+        let Some((weakest, &min)) = self.candidates.iter().min_by_key(|(_, c)| **c) else {
+            return;
+        };
+        if estimate > min {
+            let weakest = weakest.clone();
+            self.candidates.remove(&weakest);
+            self.candidates.insert(v, estimate);
+        }
+    }
+}
+
+impl<T> CombineFn<T, HeavyHittersAcc<T>, Vec<(T, u64)>> for TopKHeavyHitters<T>
+where
+    T: Element + Eq + Hash,
+{
+    fn create(&self) -> HeavyHittersAcc<T> {
+        HeavyHittersAcc {
+            sketch: FrequencySketch::new(self.width, self.depth),
+            candidates: HashMap::new(),
+        }
+    }
+
+    fn add_input(&self, acc: &mut HeavyHittersAcc<T>, v: T) {
+        acc.sketch.add(&v, 1);
+        let est = acc.sketch.estimate(&v);
+        acc.offer(v, est, self.capacity());
+    }
+
+    fn merge(&self, acc: &mut HeavyHittersAcc<T>, other: HeavyHittersAcc<T>) {
+        acc.sketch
+            .merge(&other.sketch)
+            .expect("matching dimensions (both sketches built by this combiner)");
+        let pool: Vec<T> = acc
+            .candidates
+            .drain()
+            .map(|(v, _)| v)
+            .chain(other.candidates.into_keys())
+            .collect();
+        for v in pool {
+            let est = acc.sketch.estimate(&v);
+            acc.offer(v, est, self.capacity());
+        }
+    }
+
+    fn finish(&self, acc: HeavyHittersAcc<T>) -> Vec<(T, u64)> {
+        let mut out: Vec<(T, u64)> = acc.candidates.into_iter().collect();
+        out.sort_by_key(|e| Reverse(e.1));
+        out.truncate(self.k);
+        out
+    }
+
+    fn is_associative_commutative(&self) -> bool {
+        true
+    }
+}
@@ -6,18 +6,32 @@
 //! - [`Sum<T>`] -- sum of values.
 //! - [`Min<T>`] -- minimum value.
 //! - [`Max<T>`] -- maximum value.
+//! - [`MinBy<F>`] / [`MaxBy<F>`] -- the whole value with the smallest / largest derived key.
 //! - [`Count<T>`] -- count of values.
 //! - [`AverageF64`] -- average as `f64` (values convertible to `f64`).
 //! - [`Mean<O>`] -- arithmetic mean with caller-chosen floating-point output (`f32` or `f64`).
+//! - [`VarianceF64`] / [`StdDevF64`] -- sample or population variance / standard deviation.
+//! - [`MomentsF64`] -- count, mean, and higher central moments ([`Moments`]) in one pass.
 //! - [`DistinctCount<T>`] -- count of distinct values.
+//! - [`HyperLogLog<T>`] -- approximate distinct count with an optional mergeable [`HllSketch`].
+//! - [`MultiCombine`] -- several combiners fused into one pass, producing a tuple of results.
 //! - [`ToList<T>`] -- collect all values into a `Vec<T>`.
 //! - [`ToSet<T>`] -- collect unique values into a `HashSet<T>`.
 //! - [`ToDict<K, V>`] -- collect `(K, V)` pairs into a `HashMap<K, V>`.
 //! - [`Latest<T>`] -- select the value with the latest timestamp.
+//! - [`BloomFilterBuilder<T>`] -- a serializable [`BloomFilter`](crate::bloom_filter::BloomFilter) of all values.
+//! - [`CountMinSketch<T>`] -- approximate per-value frequencies as a mergeable [`FrequencySketch`].
+//! - [`TopKHeavyHitters<T>`] -- the K most frequent values, estimated with bounded memory.
 //! - [`TopK<T>`] -- the top-K largest values.
 //! - [`BottomK<T>`] -- the bottom-K smallest values.
+//! - [`TopKBy<F>`] -- the K whole values with the largest derived key.
+//! - [`WeightedReservoir<F>`] -- a random sample of K values, weighted by a derived weight.
 //! - [`ApproxQuantiles<T>`] -- approximate quantiles/percentiles using t-digest.
 //! - [`ApproxMedian<T>`] -- approximate median using t-digest.
+//! - [`Percentiles<T>`] -- exact quantiles up to a per-group cap, t-digest above it.
+//! - [`QuantileSketch<T>`] -- the mergeable, serializable t-digest ([`TDigestSketch`]) itself.
+//! - [`Ewma`] / [`RollingMean`] / [`RollingMax<V>`] -- ordered running statistics ([`RollingFn`]), applied per key with [`PCollection::rolling`](crate::PCollection::rolling).
+//! - [`DataProfiler<T>`] -- per-column statistics of serializable records as a [`DataProfile`].
 //!
 //! Each combiner specifies its accumulator type (`A`) and output type (`O`).
 //!
@@ -113,22 +127,39 @@
 //! ```
 
 mod basic;
+mod bloom;
 mod collect;
 mod count;
 mod distinct;
+mod frequency;
 mod latest;
+mod multi;
+mod profile;
 mod quantiles;
+mod rolling;
 mod sampling;
 mod statistical;
 mod topk;
 
 // Re-export all public combiners
-pub use basic::{Max, Min, Sum};
+pub use basic::{Max, MaxBy, Min, MinBy, Sum};
+pub use bloom::BloomFilterBuilder;
 pub use collect::{ToDict, ToList, ToSet};
 pub use count::Count;
-pub use distinct::{DistinctCount, DistinctSet, HllApproxDistinctCount, KMVApproxDistinctCount};
+pub use distinct::{
+    ApproxDistinct, DistinctCount, DistinctSet, HllApproxDistinctCount, HllSketch, HyperLogLog,
+    KMVApproxDistinctCount,
+};
+pub use frequency::{CountMinSketch, FrequencySketch, HeavyHittersAcc, TopKHeavyHitters};
 pub use latest::Latest;
-pub use quantiles::{ApproxMedian, ApproxQuantiles, TDigest};
-pub use sampling::PriorityReservoir;
-pub use statistical::{AverageF64, Mean};
-pub use topk::{BottomK, TopK};
+pub use multi::MultiCombine;
+pub use profile::{ColumnProfile, DataProfile, DataProfiler, ProfileAcc};
+pub use quantiles::{
+    ApproxMedian, ApproxQuantiles, Percentiles, PercentilesAcc, QuantileSketch, TDigest,
+    TDigestSketch,
+};
+pub use rolling::{Ewma, RollingFn, RollingMax, RollingMean};
+pub(crate) use sampling::SplitMix64;
+pub use sampling::{PriorityReservoir, WRAcc, WeightedReservoir};
+pub use statistical::{AverageF64, Mean, Moments, MomentsF64, StdDevF64, VarianceF64};
+pub use topk::{BottomK, TopK, TopKBy};
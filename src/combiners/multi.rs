@@ -0,0 +1,143 @@
+//! Composite combiner: `MultiCombine`, which runs several combiners in one pass.
+
+use crate::Element;
+use crate::collection::CombineFn;
+use std::marker::PhantomData;
+
+/* ===================== MultiCombine<V, C> ===================== */
+
+/// Fuse up to eight combiners over the same values into a single
+/// [`CombineFn`], so several aggregations share one shuffle.
+///
+/// Combiners are added with [`add`](Self::add); the accumulator and output
+/// are tuples in the order the combiners were added. Each value is cloned
+/// once per combiner. Names are only labels (see [`names`](Self::names)).
+///
+/// - Accumulator: `(A1, A2, ...)`
+/// - Output: `(O1, O2, ...)`
+///
+/// # Examples
+/// ```no_run
+/// # use anyhow::Result;
+/// use ironbeam::*;
+/// use ironbeam::combiners::{Count, Max, Min, MultiCombine, Sum};
+/// # fn main() -> Result<()> {
+/// let p = Pipeline::default();
+/// let stats = from_vec(&p, vec![("a".to_string(), 3u64), ("a".to_string(), 5), ("b".to_string(), 1)])
+///     .combine_values(
+///         MultiCombine::new()
+///             .add("sum", Sum::default())
+///             .add("min", Min::default())
+///             .add("max", Max::default())
+///             .add("count", Count::new()),
+///     )
+///     .collect_seq_sorted()?;
+/// assert_eq!(stats[0], ("a".to_string(), (8, 3, 5, 2)));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct MultiCombine<V, C> {
+    names: Vec<String>,
+    combiners: C,
+    _m: PhantomData<V>,
+}
+
+impl<V> MultiCombine<V, ()> {
+    /// Start an empty composite; add combiners with [`add`](Self::add).
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            names: Vec::new(),
+            combiners: (),
+            _m: PhantomData,
+        }
+    }
+}
+
+impl<V> Default for MultiCombine<V, ()> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V, C> MultiCombine<V, C> {
+    /// Labels of the fused combiners, in output order.
+    #[must_use]
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+}
+
+macro_rules! impl_multi_add {
+    ($($c:ident $i:tt),*) => {
+        impl<V, $($c),*> MultiCombine<V, ($($c,)*)> {
+            /// Append a combiner; its result becomes the next element of the
+            /// output tuple.
+            #[must_use]
+            #[allow(clippy::missing_const_for_fn)]
+            pub fn add<N>(self, name: impl Into<String>, comb: N) -> MultiCombine<V, ($($c,)* N,)> {
+                let mut names = self.names;
+                names.push(name.into());
+                MultiCombine {
+                    names,
+                    combiners: ($(self.combiners.$i,)* comb,),
+                    _m: PhantomData,
+                }
+            }
+        }
+    };
+}
+
+impl_multi_add!();
+impl_multi_add!(C0 0);
+impl_multi_add!(C0 0, C1 1);
+impl_multi_add!(C0 0, C1 1, C2 2);
+impl_multi_add!(C0 0, C1 1, C2 2, C3 3);
+impl_multi_add!(C0 0, C1 1, C2 2, C3 3, C4 4);
+impl_multi_add!(C0 0, C1 1, C2 2, C3 3, C4 4, C5 5);
+impl_multi_add!(C0 0, C1 1, C2 2, C3 3, C4 4, C5 5, C6 6);
+
+macro_rules! impl_multi_combine {
+    ($($c:ident $a:ident $o:ident $i:tt),*) => {
+        impl<V, $($c, $a, $o),*> CombineFn<V, ($($a,)*), ($($o,)*)> for MultiCombine<V, ($($c,)*)>
+        where
+            V: Element,
+            $($c: CombineFn<V, $a, $o>,)*
+        {
+            fn create(&self) -> ($($a,)*) {
+                ($(self.combiners.$i.create(),)*)
+            }
+
+            fn add_input(&self, acc: &mut ($($a,)*), v: V) {
+                $(self.combiners.$i.add_input(&mut acc.$i, v.clone());)*
+            }
+
+            fn merge(&self, acc: &mut ($($a,)*), other: ($($a,)*)) {
+                $(self.combiners.$i.merge(&mut acc.$i, other.$i);)*
+            }
+
+            fn finish(&self, acc: ($($a,)*)) -> ($($o,)*) {
+                ($(self.combiners.$i.finish(acc.$i),)*)
+            }
+
+            fn is_associative_commutative(&self) -> bool {
+                $(self.combiners.$i.is_associative_commutative())&&*
+            }
+        }
+    };
+}
+
+impl_multi_combine!(C0 A0 O0 0);
+impl_multi_combine!(C0 A0 O0 0, C1 A1 O1 1);
+impl_multi_combine!(C0 A0 O0 0, C1 A1 O1 1, C2 A2 O2 2);
+impl_multi_combine!(C0 A0 O0 0, C1 A1 O1 1, C2 A2 O2 2, C3 A3 O3 3);
+impl_multi_combine!(C0 A0 O0 0, C1 A1 O1 1, C2 A2 O2 2, C3 A3 O3 3, C4 A4 O4 4);
+impl_multi_combine!(C0 A0 O0 0, C1 A1 O1 1, C2 A2 O2 2, C3 A3 O3 3, C4 A4 O4 4, C5 A5 O5 5);
+impl_multi_combine!(
+    C0 A0 O0 0, C1 A1 O1 1, C2 A2 O2 2, C3 A3 O3 3, C4 A4 O4 4, C5 A5 O5 5, C6 A6 O6 6
+);
+impl_multi_combine!(
+    C0 A0 O0 0, C1 A1 O1 1, C2 A2 O2 2, C3 A3 O3 3, C4 A4 O4 4, C5 A5 O5 5, C6 A6 O6 6,
+    C7 A7 O7 7
+);
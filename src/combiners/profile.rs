@@ -0,0 +1,316 @@
+//! Per-column data profiling: `DataProfiler` and its `DataProfile` report.
+
+use crate::Element;
+use crate::collection::CombineFn;
+use crate::combiners::distinct::HllSketch;
+use crate::combiners::frequency::{HeavyHittersAcc, TopKHeavyHitters};
+use crate::combiners::statistical::Moments;
+use crate::row::Row;
+use serde::{Deserialize, Serialize};
+use serde_json::{Error as JsonError, Value, to_string_pretty};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter, Result as FormatResult};
+use std::fs::write;
+use std::io::Result as IoResult;
+use std::marker::PhantomData;
+use std::path::Path;
+
+/// Statistics for one column of a [`DataProfile`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ColumnProfile {
+    /// Column name.
+    pub name: String,
+    /// Rows where the column is missing or `null`.
+    pub null_count: u64,
+    /// Approximate number of distinct non-null values (`HyperLogLog`).
+    pub distinct_estimate: u64,
+    /// Smallest scalar value; booleans sort before numbers, numbers before strings.
+    #[serde(with = "crate::row::json_text")]
+    pub min: Option<Value>,
+    /// Largest scalar value, ordered like `min`.
+    #[serde(with = "crate::row::json_text")]
+    pub max: Option<Value>,
+    /// Mean of the numeric values, if any.
+    pub mean: Option<f64>,
+    /// Sample standard deviation of the numeric values, if there are at least two.
+    pub stddev: Option<f64>,
+    /// Most frequent non-null values with their estimated counts, most frequent first.
+    #[serde(with = "crate::row::json_text")]
+    pub top_values: Vec<(Value, u64)>,
+}
+
+/// Column statistics for a whole collection, produced by [`DataProfiler`].
+///
+/// Serializes to JSON with [`to_json`](Self::to_json) or
+/// [`write_to_file`](Self::write_to_file); `Display` prints one line per column.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct DataProfile {
+    /// Number of records profiled.
+    pub rows: u64,
+    /// One entry per column seen in any record, in name order.
+    pub columns: Vec<ColumnProfile>,
+}
+
+impl DataProfile {
+    /// The profile of column `name`, if it was seen.
+    #[must_use]
+    pub fn column(&self, name: &str) -> Option<&ColumnProfile> {
+        self.columns.iter().find(|c| c.name == name)
+    }
+
+    /// Export the profile as pretty-printed JSON.
+    ///
+    /// # Errors
+    ///
+    /// If serialization fails, a JSON error is returned.
+    pub fn to_json(&self) -> Result<String, JsonError> {
+        to_string_pretty(self)
+    }
+
+    /// Write the profile to a JSON file.
+    ///
+    /// # Errors
+    ///
+    /// If writing to the file fails, an I/O error is returned.
+    pub fn write_to_file<P: AsRef<Path>>(&self, path: P) -> IoResult<()> {
+        write(path, self.to_json()?)
+    }
+}
+
+impl Display for DataProfile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        writeln!(f, "{} rows, {} columns", self.rows, self.columns.len())?;
+        for c in &self.columns {
+            write!(
+                f,
+                "{}: {} null, ~{} distinct",
+                c.name, c.null_count, c.distinct_estimate
+            )?;
+            if let (Some(min), Some(max)) = (&c.min, &c.max) {
+                write!(f, ", min {min}, max {max}")?;
+            }
+            if let Some(mean) = c.mean {
+                write!(f, ", mean {mean:.4}")?;
+            }
+            if let Some(stddev) = c.stddev {
+                write!(f, ", stddev {stddev:.4}")?;
+            }
+            let top: Vec<String> = c
+                .top_values
+                .iter()
+                .map(|(v, n)| format!("{v} ({n})"))
+                .collect();
+            writeln!(f, ", top [{}]", top.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+/// Running statistics for one column.
+#[derive(Clone, Debug)]
+struct ColumnAcc {
+    non_null: u64,
+    distinct: HllSketch,
+    min: Option<Value>,
+    max: Option<Value>,
+    moments: Moments,
+    top: HeavyHittersAcc<String>,
+}
+
+/// Accumulator for [`DataProfiler`]: the record count and per-column statistics.
+#[derive(Clone, Debug, Default)]
+pub struct ProfileAcc {
+    rows: u64,
+    columns: BTreeMap<String, ColumnAcc>,
+}
+
+/// Profile every column of a collection in one pass.
+///
+/// Each element is converted to columns like [`Row::from_serialize`]. Per column it
+/// tracks null counts, a [`HyperLogLog`](crate::combiners::HyperLogLog)-style distinct
+/// estimate, min/max, the [`Moments`] of numeric values, and the
+/// [`TopKHeavyHitters`] among values.
+///
+/// - Accumulator: [`ProfileAcc`]
+/// - Output: [`DataProfile`]
+///
+/// Usually used through [`PCollection::profile`](crate::PCollection::profile);
+/// construct it directly to change the number of top values or the sketch precision.
+///
+/// # Panics
+///
+/// The pipeline fails at execution time if an element cannot be serialized to JSON.
+#[derive(Clone, Debug)]
+pub struct DataProfiler<T> {
+    top: TopKHeavyHitters<String>,
+    precision: u8,
+    _t: PhantomData<T>,
+}
+
+impl<T> DataProfiler<T> {
+    /// Five top values per column and `HyperLogLog` precision 12 (about 1.6% error).
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            top: TopKHeavyHitters::new(5),
+            precision: 12,
+            _t: PhantomData,
+        }
+    }
+
+    /// Report the `k` most frequent values per column.
+    #[must_use]
+    pub fn with_top_k(mut self, k: usize) -> Self {
+        self.top = TopKHeavyHitters::new(k);
+        self
+    }
+
+    /// Set the distinct-count sketch precision; clamped to `[4, 18]` like
+    /// [`HllSketch::new`].
+    #[must_use]
+    pub const fn with_precision(mut self, precision: u8) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    fn column(&self) -> ColumnAcc {
+        ColumnAcc {
+            non_null: 0,
+            distinct: HllSketch::new(self.precision),
+            min: None,
+            max: None,
+            moments: Moments::default(),
+            top: self.top.create(),
+        }
+    }
+
+    fn merge_column(&self, acc: &mut ColumnAcc, other: ColumnAcc) {
+        acc.non_null += other.non_null;
+        acc.distinct
+            .merge(&other.distinct)
+            .expect("matching precision (both sketches built by this combiner)");
+        if let Some(min) = other.min {
+            keep_if(&mut acc.min, min, Ordering::Less);
+        }
+        if let Some(max) = other.max {
+            keep_if(&mut acc.max, max, Ordering::Greater);
+        }
+        acc.moments.merge(&other.moments);
+        self.top.merge(&mut acc.top, other.top);
+    }
+}
+
+impl<T> Default for DataProfiler<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Sort rank of a scalar JSON type for min/max; `None` for null, arrays, and objects.
+const fn scalar_rank(v: &Value) -> Option<u8> {
+    match v {
+        Value::Bool(_) => Some(0),
+        Value::Number(_) => Some(1),
+        Value::String(_) => Some(2),
+        _ => None,
+    }
+}
+
+fn scalar_cmp(a: &Value, b: &Value) -> Ordering {
+    match (a, b) {
+        (Value::Bool(x), Value::Bool(y)) => x.cmp(y),
+        (Value::Number(x), Value::Number(y)) => x
+            .as_f64()
+            .partial_cmp(&y.as_f64())
+            .unwrap_or(Ordering::Equal),
+        (Value::String(x), Value::String(y)) => x.cmp(y),
+        _ => scalar_rank(a).cmp(&scalar_rank(b)),
+    }
+}
+
+/// Replace `slot` with `v` if it is empty or `v` compares as `want` against it.
+fn keep_if(slot: &mut Option<Value>, v: Value, want: Ordering) {
+    if slot.as_ref().is_none_or(|cur| scalar_cmp(&v, cur) == want) {
+        *slot = Some(v);
+    }
+}
+
+impl<T> CombineFn<T, ProfileAcc, DataProfile> for DataProfiler<T>
+where
+    T: Element + Serialize,
+{
+    fn create(&self) -> ProfileAcc {
+        ProfileAcc::default()
+    }
+
+    fn add_input(&self, acc: &mut ProfileAcc, v: T) {
+        let row = Row::from_serialize(&v).expect("DataProfiler: element is not serializable");
+        acc.rows += 1;
+        for (name, value) in row.columns() {
+            let col = acc
+                .columns
+                .entry(name.to_string())
+                .or_insert_with(|| self.column());
+            if value.is_null() {
+                continue;
+            }
+            col.non_null += 1;
+            let text = value.to_string();
+            col.distinct.insert(&text);
+            self.top.add_input(&mut col.top, text);
+            if let Some(x) = value.as_f64() {
+                col.moments.push(x);
+            }
+            if scalar_rank(value).is_some() {
+                keep_if(&mut col.min, value.clone(), Ordering::Less);
+                keep_if(&mut col.max, value.clone(), Ordering::Greater);
+            }
+        }
+    }
+
+    fn merge(&self, acc: &mut ProfileAcc, other: ProfileAcc) {
+        acc.rows += other.rows;
+        for (name, col) in other.columns {
+            match acc.columns.get_mut(&name) {
+                Some(existing) => self.merge_column(existing, col),
+                None => {
+                    acc.columns.insert(name, col);
+                }
+            }
+        }
+    }
+
+    fn finish(&self, acc: ProfileAcc) -> DataProfile {
+        let columns = acc
+            .columns
+            .into_iter()
+            .map(|(name, col)| {
+                let m = col.moments;
+                ColumnProfile {
+                    name,
+                    null_count: acc.rows - col.non_null,
+                    distinct_estimate: col.distinct.estimate(),
+                    min: col.min,
+                    max: col.max,
+                    mean: (m.count > 0).then_some(m.mean),
+                    stddev: (m.count > 1).then(|| m.sample_variance().sqrt()),
+                    top_values: self
+                        .top
+                        .finish(col.top)
+                        .into_iter()
+                        .map(|(text, n)| (serde_json::from_str(&text).unwrap_or(Value::Null), n))
+                        .collect(),
+                }
+            })
+            .collect();
+        DataProfile {
+            rows: acc.rows,
+            columns,
+        }
+    }
+
+    fn is_associative_commutative(&self) -> bool {
+        true
+    }
+}
@@ -1,16 +1,18 @@
-//! Approximate quantiles combiners using t-digest algorithm.
+//! Quantile combiners: approximate ones using the t-digest algorithm, and exact
+//! `Percentiles` for groups small enough to hold in memory.
 //!
-//! Provides memory-efficient approximate quantile estimation suitable for large-scale
-//! distributed streaming data processing.
+//! The t-digest combiners provide memory-efficient approximate quantile estimation
+//! suitable for large-scale distributed streaming data processing.
 
 use crate::Element;
 use crate::collection::CombineFn;
+use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::marker::PhantomData;
 /* ===================== TDigest ===================== */
 
 /// A centroid in the t-digest: a weighted point representing a cluster of values.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 struct Centroid {
     mean: f64,
     weight: f64,
@@ -30,7 +32,10 @@ impl Centroid {
 /// estimates at the extreme quantiles (0.0 and 1.0) than at the median.
 ///
 /// Based on "Computing Extremely Accurate Quantiles Using t-Digests" by Ted Dunning.
-#[derive(Clone, Debug)]
+///
+/// The digest is `Serialize + Deserialize`, so it can be persisted (see
+/// [`TDigestSketch`]) and merged with digests from later runs.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct TDigest {
     /// Compression parameter: controls accuracy vs. memory tradeoff.
     /// Higher values = more accuracy but more memory.
@@ -263,6 +268,12 @@ impl TDigest {
         cumulative / self.total_weight
     }
 
+    /// Return the compression parameter this digest was built with.
+    #[must_use]
+    pub const fn compression(&self) -> f64 {
+        self.compression
+    }
+
     /// Get the total count of values added.
     #[must_use]
     pub const fn count(&self) -> f64 {
@@ -276,6 +287,19 @@ impl TDigest {
     }
 }
 
+/// A persisted t-digest: the accumulator behind [`ApproxQuantiles`] exposed
+/// as a value that can be serialized, stored per run, and merged later with
+/// [`TDigest::merge`].
+///
+/// Produced by the [`QuantileSketch`] combiner. Quantiles computed from the
+/// merge of several sketches are as accurate as a single pass over the union
+/// of their inputs, up to the digest's compression.
+///
+/// Empty sketches keep `±inf` as their min/max, which self-describing formats
+/// such as JSON cannot represent; skip empty sketches before persisting them
+/// to such formats.
+pub type TDigestSketch = TDigest;
+
 /* ===================== ApproxQuantiles ===================== */
 
 /// Approximate quantiles combiner using t-digest.
@@ -439,3 +463,246 @@ where
         acc.quantile(0.5)
     }
 }
+
+/* ===================== QuantileSketch ===================== */
+
+/// T-digest combiner that emits the sketch itself instead of quantiles.
+///
+/// Use this when results must be combined across runs (for example, daily
+/// sketches rolled up into a weekly p99): persist each [`TDigestSketch`],
+/// then [`merge`](TDigest::merge) and query them later.
+///
+/// - Accumulator: `TDigest`
+/// - Output: [`TDigestSketch`]
+///
+/// # Examples
+/// ```
+/// # use anyhow::Result;
+/// use ironbeam::*;
+/// use ironbeam::combiners::QuantileSketch;
+/// # fn main() -> Result<()> {
+/// let p = Pipeline::default();
+/// let mut days = Vec::new();
+/// for day in 0..2u32 {
+///     let vals: Vec<f64> = (0..500).map(|i| f64::from(day * 500 + i)).collect();
+///     days.push(from_vec(&p, vals).combine_globally(QuantileSketch::new(100.0), None).collect_seq()?);
+/// }
+/// let mut total = days[0][0].clone();
+/// total.merge(&days[1][0]);
+/// assert!((total.quantile(0.5) - 500.0).abs() < 25.0);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct QuantileSketch<V> {
+    compression: f64,
+    _phantom: PhantomData<V>,
+}
+
+impl<V> QuantileSketch<V> {
+    /// Create a sketch combiner.
+    ///
+    /// # Arguments
+    /// * `compression` - T-digest compression parameter (typical: 20-1000, recommended: 100)
+    #[must_use]
+    pub const fn new(compression: f64) -> Self {
+        Self {
+            compression,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<V> Default for QuantileSketch<V> {
+    fn default() -> Self {
+        Self::new(100.0)
+    }
+}
+
+impl<V> CombineFn<V, TDigest, TDigestSketch> for QuantileSketch<V>
+where
+    V: Element + Into<f64>,
+{
+    fn create(&self) -> TDigest {
+        TDigest::new(self.compression)
+    }
+
+    fn add_input(&self, acc: &mut TDigest, v: V) {
+        acc.add(v.into());
+    }
+
+    fn merge(&self, acc: &mut TDigest, other: TDigest) {
+        acc.merge(&other);
+    }
+
+    fn finish(&self, mut acc: TDigest) -> TDigestSketch {
+        // Persist the compact form rather than the raw buffered centroids.
+        acc.compress();
+        acc
+    }
+}
+
+/* ===================== Percentiles (exact) ===================== */
+
+/// Accumulator for [`Percentiles`]: every value while under the cap, a t-digest after.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum PercentilesAcc {
+    /// All values seen so far, unsorted.
+    Exact(Vec<f64>),
+    /// A t-digest, once the values outgrew the cap.
+    Approx(TDigest),
+}
+
+/// Exact quantiles for groups of modest size, approximate beyond a cap.
+///
+/// While a group holds at most `cap` values they are all kept, and the quantiles are
+/// computed exactly by sorting them, interpolating linearly between the two closest
+/// ranks (`q * (n - 1)`, as in `numpy.quantile`). Once a group exceeds the cap its
+/// values are folded into a [`TDigest`] and the result is approximate, like
+/// [`ApproxQuantiles`]; memory stays bounded by the cap either way. Non-finite values
+/// are skipped.
+///
+/// - Accumulator: [`PercentilesAcc`]
+/// - Output: `Vec<f64>`, one value per requested quantile (`NaN` for empty groups)
+///
+/// # Example
+/// ```no_run
+/// # use anyhow::Result;
+/// use ironbeam::*;
+/// use ironbeam::combiners::Percentiles;
+/// # fn main() -> Result<()> {
+/// let p = Pipeline::default();
+/// let qs = from_vec(&p, vec![1.0f64, 2.0, 3.0, 4.0])
+///     .combine_globally(Percentiles::new(vec![0.5, 1.0]), None)
+///     .collect_seq()?;
+/// assert_eq!(qs[0], vec![2.5, 4.0]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Percentiles<V> {
+    quantiles: Vec<f64>,
+    cap: usize,
+    compression: f64,
+    _phantom: PhantomData<V>,
+}
+
+impl<V> Percentiles<V> {
+    /// Exact `quantiles` (each in `0.0..=1.0`) for up to 100,000 values per group,
+    /// falling back to a t-digest with compression 100 above that.
+    #[must_use]
+    pub const fn new(quantiles: Vec<f64>) -> Self {
+        Self {
+            quantiles,
+            cap: 100_000,
+            compression: 100.0,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Exact median.
+    #[must_use]
+    pub fn median() -> Self {
+        Self::new(vec![0.5])
+    }
+
+    /// Keep at most `cap` values per group before switching to a t-digest.
+    #[must_use]
+    pub const fn with_cap(mut self, cap: usize) -> Self {
+        self.cap = cap;
+        self
+    }
+
+    /// Set the compression of the fallback t-digest.
+    #[must_use]
+    pub const fn with_compression(mut self, compression: f64) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Fold exact values into a t-digest once there are more than `cap` of them.
+    fn spill_if_needed(&self, acc: &mut PercentilesAcc) {
+        if let PercentilesAcc::Exact(values) = acc
+            && values.len() > self.cap
+        {
+            let mut digest = TDigest::new(self.compression);
+            for &v in values.iter() {
+                digest.add(v);
+            }
+            *acc = PercentilesAcc::Approx(digest);
+        }
+    }
+}
+
+/// Linearly interpolated quantile `q` of sorted, non-empty `values`.
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn exact_quantile(values: &[f64], q: f64) -> f64 {
+    let pos = q.clamp(0.0, 1.0) * (values.len() - 1) as f64;
+    let lo = pos.floor() as usize;
+    let hi = pos.ceil() as usize;
+    let frac = pos - lo as f64;
+    frac.mul_add(values[hi] - values[lo], values[lo])
+}
+
+impl<V> CombineFn<V, PercentilesAcc, Vec<f64>> for Percentiles<V>
+where
+    V: Element + Into<f64>,
+{
+    fn create(&self) -> PercentilesAcc {
+        PercentilesAcc::Exact(Vec::new())
+    }
+
+    fn add_input(&self, acc: &mut PercentilesAcc, v: V) {
+        let v = v.into();
+        if !v.is_finite() {
+            return;
+        }
+        match acc {
+            PercentilesAcc::Exact(values) => values.push(v),
+            PercentilesAcc::Approx(digest) => digest.add(v),
+        }
+        self.spill_if_needed(acc);
+    }
+
+    fn merge(&self, acc: &mut PercentilesAcc, other: PercentilesAcc) {
+        match (&mut *acc, other) {
+            (PercentilesAcc::Exact(values), PercentilesAcc::Exact(more)) => values.extend(more),
+            (PercentilesAcc::Approx(digest), PercentilesAcc::Exact(more)) => {
+                for v in more {
+                    digest.add(v);
+                }
+            }
+            (PercentilesAcc::Exact(values), PercentilesAcc::Approx(mut digest)) => {
+                for &v in values.iter() {
+                    digest.add(v);
+                }
+                *acc = PercentilesAcc::Approx(digest);
+            }
+            (PercentilesAcc::Approx(digest), PercentilesAcc::Approx(more)) => digest.merge(&more),
+        }
+        self.spill_if_needed(acc);
+    }
+
+    fn finish(&self, acc: PercentilesAcc) -> Vec<f64> {
+        match acc {
+            PercentilesAcc::Exact(values) if values.is_empty() => {
+                vec![f64::NAN; self.quantiles.len()]
+            }
+            PercentilesAcc::Exact(mut values) => {
+                values.sort_by(f64::total_cmp);
+                self.quantiles
+                    .iter()
+                    .map(|&q| exact_quantile(&values, q))
+                    .collect()
+            }
+            PercentilesAcc::Approx(mut digest) => {
+                digest.compress();
+                digest.quantiles(&self.quantiles)
+            }
+        }
+    }
+}
@@ -0,0 +1,185 @@
+//! Rolling and decayed statistics over ordered values: `Ewma`, `RollingMean`,
+//! `RollingMax`.
+//!
+//! Unlike a [`CombineFn`](crate::collection::CombineFn), these depend on the order of
+//! their inputs and produce one output per input, so they implement [`RollingFn`]
+//! instead. Apply them per key, in timestamp order, with
+//! [`PCollection::rolling`](crate::PCollection::rolling).
+
+use std::collections::VecDeque;
+use std::marker::PhantomData;
+
+/// A statistic updated value by value, in order, emitting its current result after
+/// each value.
+pub trait RollingFn<V>: Send + Sync + 'static {
+    /// Running state for one sequence (one key).
+    type State: Send;
+    /// Result emitted after each value.
+    type Output;
+
+    /// Fresh state for a new sequence.
+    fn create(&self) -> Self::State;
+    /// Add `v` to the state and return the statistic including it.
+    fn push(&self, state: &mut Self::State, v: V) -> Self::Output;
+}
+
+/* ===================== Ewma ===================== */
+
+/// Exponentially weighted moving average.
+///
+/// The first value seeds the average; each later value `x` updates it to
+/// `alpha * x + (1 - alpha) * previous`. A larger `alpha` reacts faster.
+///
+/// - State: `Option<f64>`
+/// - Output: `f64`
+#[derive(Clone, Copy, Debug)]
+pub struct Ewma {
+    /// Smoothing factor in `(0, 1]`.
+    pub alpha: f64,
+}
+
+impl Ewma {
+    /// Moving average with smoothing factor `alpha`.
+    ///
+    /// # Panics
+    /// Panics if `alpha` is not in `(0, 1]`.
+    #[must_use]
+    pub fn new(alpha: f64) -> Self {
+        assert!(
+            alpha > 0.0 && alpha <= 1.0,
+            "Ewma::new: alpha must be in (0, 1]"
+        );
+        Self { alpha }
+    }
+}
+
+impl<V: Into<f64>> RollingFn<V> for Ewma {
+    type State = Option<f64>;
+    type Output = f64;
+
+    fn create(&self) -> Option<f64> {
+        None
+    }
+
+    fn push(&self, state: &mut Option<f64>, v: V) -> f64 {
+        let x = v.into();
+        let avg = state.map_or(x, |prev| self.alpha.mul_add(x - prev, prev));
+        *state = Some(avg);
+        avg
+    }
+}
+
+/* ===================== RollingMean ===================== */
+
+/// Mean of the last `window` values (fewer at the start of a sequence).
+///
+/// - State: the values in the window and their sum
+/// - Output: `f64`
+#[derive(Clone, Copy, Debug)]
+pub struct RollingMean {
+    /// Number of values averaged.
+    pub window: usize,
+}
+
+impl RollingMean {
+    /// Mean over a sliding window of `window` values.
+    ///
+    /// # Panics
+    /// Panics if `window == 0`.
+    #[must_use]
+    pub fn new(window: usize) -> Self {
+        assert!(window > 0, "RollingMean::new: window must be positive");
+        Self { window }
+    }
+}
+
+impl<V: Into<f64>> RollingFn<V> for RollingMean {
+    type State = (VecDeque<f64>, f64);
+    type Output = f64;
+
+    fn create(&self) -> Self::State {
+        (VecDeque::with_capacity(self.window), 0.0)
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn push(&self, state: &mut Self::State, v: V) -> f64 {
+        let (values, sum) = state;
+        let x = v.into();
+        values.push_back(x);
+        *sum += x;
+        if values.len() > self.window
+            && let Some(old) = values.pop_front()
+        {
+            *sum -= old;
+        }
+        *sum / values.len() as f64
+    }
+}
+
+/* ===================== RollingMax ===================== */
+
+/// Largest of the last `window` values (fewer at the start of a sequence).
+///
+/// Keeps a monotonic queue, so each value is compared an amortized constant number of
+/// times. A `NaN` ranks below every value that follows it.
+///
+/// - State: candidate maxima with their positions
+/// - Output: `V`
+#[derive(Clone, Copy, Debug)]
+pub struct RollingMax<V> {
+    /// Number of values considered.
+    pub window: usize,
+    _m: PhantomData<V>,
+}
+
+impl<V> RollingMax<V> {
+    /// Maximum over a sliding window of `window` values.
+    ///
+    /// # Panics
+    /// Panics if `window == 0`.
+    #[must_use]
+    pub fn new(window: usize) -> Self {
+        assert!(window > 0, "RollingMax::new: window must be positive");
+        Self {
+            window,
+            _m: PhantomData,
+        }
+    }
+}
+
+impl<V> RollingFn<V> for RollingMax<V>
+where
+    V: PartialOrd + Clone + Send + Sync + 'static,
+{
+    type State = (VecDeque<(u64, V)>, u64);
+    type Output = V;
+
+    fn create(&self) -> Self::State {
+        (VecDeque::new(), 0)
+    }
+
+    fn push(&self, state: &mut Self::State, v: V) -> V {
+        let (queue, seen) = state;
+        let pos = *seen;
+        *seen += 1;
+        // Drop candidates that can never be the maximum again; a value that is not
+        // comparable with itself (`NaN`) never is.
+        while queue
+            .back()
+            .is_some_and(|(_, back)| back <= &v || back.partial_cmp(back).is_none())
+        {
+            queue.pop_back();
+        }
+        queue.push_back((pos, v));
+        while queue
+            .front()
+            .is_some_and(|&(p, _)| p + self.window as u64 <= pos)
+        {
+            queue.pop_front();
+        }
+        queue
+            .front()
+            .map(|(_, max)| max.clone())
+            .expect("just pushed")
+    }
+}
@@ -1,4 +1,4 @@
-//! Priority-based reservoir sampling combiner
+//! Reservoir sampling combiners: uniform priority sampling and weighted A-Res sampling.
 
 use crate::Element;
 use crate::collection::CombineFn;
@@ -12,17 +12,17 @@ use std::marker::PhantomData;
 // ======================================================================
 
 #[derive(Clone, Copy, Debug)]
-struct SplitMix64 {
+pub(crate) struct SplitMix64 {
     state: u64,
 }
 
 impl SplitMix64 {
-    const fn new(seed: u64) -> Self {
+    pub(crate) const fn new(seed: u64) -> Self {
         Self { state: seed }
     }
 
     #[inline]
-    const fn next_u64(&mut self) -> u64 {
+    pub(crate) const fn next_u64(&mut self) -> u64 {
         let mut z = {
             self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
             self.state
@@ -34,7 +34,7 @@ impl SplitMix64 {
 
     #[inline]
     #[allow(clippy::cast_precision_loss)]
-    fn next_f64(&mut self) -> f64 {
+    pub(crate) fn next_f64(&mut self) -> f64 {
         const SCALE: f64 = 1.0 / ((1u64 << 53) as f64);
         ((self.next_u64() >> 11) as f64) * SCALE
     }
@@ -186,3 +186,86 @@ impl<T: Element> CombineFn<T, PRAcc<T>, Vec<T>> for PriorityReservoir<T> {
         items.into_iter().map(|(_, _, v)| v).collect()
     }
 }
+
+// ======================================================================
+// Weighted Reservoir Sampling (Efraimidis–Spirakis A-Res)
+// ======================================================================
+
+/// Accumulator for [`WeightedReservoir`]: the PRNG and candidate `(key, value)` pairs.
+#[derive(Clone, Debug)]
+pub struct WRAcc<T> {
+    rng: SplitMix64,
+    items: Vec<(OrdF64, T)>,
+}
+
+/// Weighted reservoir sampling without replacement (Efraimidis–Spirakis A-Res).
+///
+/// Each value with weight `w > 0` gets the key `u^(1/w)` for `u ~ U(0,1)`, and the
+/// `k` values with the largest keys are kept, so heavier values are proportionally
+/// more likely to be chosen. Keys are compared as `ln(u) / w` to avoid underflow
+/// for tiny weights. Values whose weight is zero, negative, or not finite are never
+/// sampled.
+///
+/// - Accumulator: [`WRAcc<T>`] (bounded to `2k` candidates between compactions)
+/// - Output: `Vec<T>` of at most `k` values, highest key first.
+///
+/// Determinism follows [`PriorityReservoir`]: the PRNG is seeded from `seed`, so
+/// runs in the same execution mode over the same input pick the same sample.
+#[derive(Clone, Copy)]
+pub struct WeightedReservoir<F> {
+    /// Number of values to sample.
+    pub k: usize,
+    /// Seed for the internal PRNG.
+    pub seed: u64,
+    weight_fn: F,
+}
+
+impl<F> WeightedReservoir<F> {
+    /// Sample `k` values, weighting each by `weight_fn(&value)`.
+    #[must_use]
+    pub const fn new(k: usize, seed: u64, weight_fn: F) -> Self {
+        Self { k, seed, weight_fn }
+    }
+
+    fn compact<T>(&self, acc: &mut WRAcc<T>) {
+        acc.items.sort_by_key(|item| Reverse(item.0));
+        acc.items.truncate(self.k);
+    }
+}
+
+impl<T, F> CombineFn<T, WRAcc<T>, Vec<T>> for WeightedReservoir<F>
+where
+    T: Element,
+    F: Fn(&T) -> f64 + Send + Sync + 'static,
+{
+    fn create(&self) -> WRAcc<T> {
+        WRAcc {
+            rng: SplitMix64::new(self.seed.wrapping_mul(0xA24B_AED4_0B9C_497C)),
+            items: Vec::new(),
+        }
+    }
+
+    fn add_input(&self, acc: &mut WRAcc<T>, v: T) {
+        let w = (self.weight_fn)(&v);
+        if self.k == 0 || !w.is_finite() || w <= 0.0 {
+            return;
+        }
+        let u = acc.rng.next_f64().max(f64::from_bits(1));
+        acc.items.push((OrdF64(u.ln() / w), v));
+        if acc.items.len() >= self.k.saturating_mul(2) {
+            self.compact(acc);
+        }
+    }
+
+    fn merge(&self, acc: &mut WRAcc<T>, other: WRAcc<T>) {
+        acc.items.extend(other.items);
+        if acc.items.len() >= self.k.saturating_mul(2) {
+            self.compact(acc);
+        }
+    }
+
+    fn finish(&self, mut acc: WRAcc<T>) -> Vec<T> {
+        self.compact(&mut acc);
+        acc.items.into_iter().map(|(_, v)| v).collect()
+    }
+}
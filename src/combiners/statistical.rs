@@ -1,7 +1,9 @@
-//! Statistical combiners: `AverageF64`, `Mean<O>`.
+//! Statistical combiners: `AverageF64`, `Mean<O>`, `VarianceF64`, `StdDevF64`,
+//! `MomentsF64`.
 
 use crate::Element;
 use crate::collection::CombineFn;
+use serde::{Deserialize, Serialize};
 use std::marker::PhantomData;
 
 /* ===================== AverageF64 ===================== */
@@ -126,3 +128,245 @@ macro_rules! impl_mean_for_float {
 
 impl_mean_for_float!(f64);
 impl_mean_for_float!(f32);
+
+/* ===================== Moments ===================== */
+
+/// Running central moments of a stream of `f64` values.
+///
+/// Holds the count, mean, and the sums of squared, cubed, and fourth-power
+/// deviations from the mean (`M2`, `M3`, `M4`). Values are folded in with
+/// Welford's online update and partial results are combined with the
+/// pairwise formulas of Pébay (2008), so per-partition accumulators merge
+/// without loss of precision and in any order.
+///
+/// This is both the accumulator of [`VarianceF64`], [`StdDevF64`], and
+/// [`MomentsF64`], and the output of [`MomentsF64`]. Derived statistics
+/// return `NaN` when they are undefined for the current count.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Moments {
+    /// Number of values observed.
+    pub count: u64,
+    /// Arithmetic mean of the values.
+    pub mean: f64,
+    /// Sum of squared deviations from the mean.
+    pub m2: f64,
+    /// Sum of cubed deviations from the mean.
+    pub m3: f64,
+    /// Sum of fourth-power deviations from the mean.
+    pub m4: f64,
+}
+
+impl Moments {
+    /// Fold one value into the moments.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn push(&mut self, x: f64) {
+        let n1 = self.count as f64;
+        self.count += 1;
+        let n = self.count as f64;
+        let delta = x - self.mean;
+        let delta_n = delta / n;
+        let delta_n2 = delta_n * delta_n;
+        let term1 = delta * delta_n * n1;
+        self.mean += delta_n;
+        self.m4 += (6.0 * delta_n2)
+            .mul_add(self.m2, term1 * delta_n2 * n.mul_add(n, -3.0 * n + 3.0))
+            - 4.0 * delta_n * self.m3;
+        self.m3 += (term1 * delta_n).mul_add(n - 2.0, -3.0 * delta_n * self.m2);
+        self.m2 += term1;
+    }
+
+    /// Merge another set of moments into this one.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn merge(&mut self, other: &Self) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = *other;
+            return;
+        }
+        let (na, nb) = (self.count as f64, other.count as f64);
+        let n = na + nb;
+        let delta = other.mean - self.mean;
+        let d2 = delta * delta;
+        let d3 = d2 * delta;
+        let d4 = d2 * d2;
+
+        let m2 = self.m2 + other.m2 + d2 * na * nb / n;
+        let m3 = self.m3
+            + other.m3
+            + d3 * na * nb * (na - nb) / (n * n)
+            + 3.0 * delta * na.mul_add(other.m2, -nb * self.m2) / n;
+        let m4 = self.m4
+            + other.m4
+            + d4 * na * nb * na.mul_add(na, -na * nb + nb * nb) / (n * n * n)
+            + 6.0 * d2 * (na * na).mul_add(other.m2, nb * nb * self.m2) / (n * n)
+            + 4.0 * delta * na.mul_add(other.m3, -nb * self.m3) / n;
+
+        self.count += other.count;
+        self.mean += delta * nb / n;
+        self.m2 = m2;
+        self.m3 = m3;
+        self.m4 = m4;
+    }
+
+    /// Population variance (`M2 / n`); `NaN` when empty.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn population_variance(&self) -> f64 {
+        if self.count == 0 {
+            f64::NAN
+        } else {
+            self.m2 / self.count as f64
+        }
+    }
+
+    /// Sample variance (`M2 / (n - 1)`); `NaN` when fewer than two values.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn sample_variance(&self) -> f64 {
+        if self.count < 2 {
+            f64::NAN
+        } else {
+            self.m2 / (self.count - 1) as f64
+        }
+    }
+
+    /// Sample skewness (`sqrt(n) * M3 / M2^1.5`); `NaN` when empty or constant.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn skewness(&self) -> f64 {
+        if self.count == 0 || self.m2 == 0.0 {
+            f64::NAN
+        } else {
+            (self.count as f64).sqrt() * self.m3 / self.m2.powf(1.5)
+        }
+    }
+
+    /// Excess kurtosis (`n * M4 / M2^2 - 3`); `NaN` when empty or constant.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn excess_kurtosis(&self) -> f64 {
+        if self.count == 0 || self.m2 == 0.0 {
+            f64::NAN
+        } else {
+            (self.count as f64) * self.m4 / (self.m2 * self.m2) - 3.0
+        }
+    }
+}
+
+/* ===================== VarianceF64 / StdDevF64 ===================== */
+
+/// Variance of values per key as `f64`, computed in a single pass.
+///
+/// Defaults to the **sample** variance (divides by `n - 1`); use
+/// [`VarianceF64::population`] to divide by `n` instead.
+///
+/// - Accumulator: [`Moments`]
+/// - Output: `f64` (`NaN` when undefined, e.g. a single sample value)
+///
+/// ## Example
+/// ```no_run
+/// # use anyhow::Result;
+/// use ironbeam::*;
+/// use ironbeam::combiners::VarianceF64;
+/// # fn main() -> Result<()> {
+/// let p = Pipeline::default();
+/// let out = from_vec(&p, vec![("a".to_string(), 2.0), ("a".to_string(), 4.0), ("a".to_string(), 6.0)])
+///     .combine_values(VarianceF64::sample())
+///     .collect_seq()?;
+/// assert!((out[0].1 - 4.0).abs() < 1e-12);
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct VarianceF64 {
+    population: bool,
+}
+
+impl VarianceF64 {
+    /// Sample variance (Bessel-corrected, divides by `n - 1`).
+    #[must_use]
+    pub const fn sample() -> Self {
+        Self { population: false }
+    }
+
+    /// Population variance (divides by `n`).
+    #[must_use]
+    pub const fn population() -> Self {
+        Self { population: true }
+    }
+
+    fn of(self, m: &Moments) -> f64 {
+        if self.population {
+            m.population_variance()
+        } else {
+            m.sample_variance()
+        }
+    }
+}
+
+impl Default for VarianceF64 {
+    fn default() -> Self {
+        Self::sample()
+    }
+}
+
+/// Standard deviation of values per key as `f64`: the square root of
+/// [`VarianceF64`], with the same sample/population choice.
+///
+/// - Accumulator: [`Moments`]
+/// - Output: `f64` (`NaN` when undefined)
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StdDevF64(VarianceF64);
+
+impl StdDevF64 {
+    /// Sample standard deviation (Bessel-corrected).
+    #[must_use]
+    pub const fn sample() -> Self {
+        Self(VarianceF64::sample())
+    }
+
+    /// Population standard deviation.
+    #[must_use]
+    pub const fn population() -> Self {
+        Self(VarianceF64::population())
+    }
+}
+
+/// All central moments of values per key, emitted as a [`Moments`] so the
+/// caller can read count, mean, variance, skewness, and kurtosis from one
+/// aggregation.
+///
+/// - Accumulator: [`Moments`]
+/// - Output: [`Moments`]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MomentsF64;
+
+macro_rules! impl_moments_combiner {
+    ($name:ty, $out:ty, |$self_:ident, $acc:ident| $finish:expr) => {
+        impl<V> CombineFn<V, Moments, $out> for $name
+        where
+            V: Element + Into<f64>,
+        {
+            fn create(&self) -> Moments {
+                Moments::default()
+            }
+
+            fn add_input(&self, acc: &mut Moments, v: V) {
+                acc.push(v.into());
+            }
+
+            fn merge(&self, acc: &mut Moments, other: Moments) {
+                acc.merge(&other);
+            }
+
+            fn finish(&$self_, $acc: Moments) -> $out {
+                $finish
+            }
+        }
+    };
+}
+
+impl_moments_combiner!(VarianceF64, f64, |self, acc| self.of(&acc));
+impl_moments_combiner!(StdDevF64, f64, |self, acc| self.0.of(&acc).sqrt());
+impl_moments_combiner!(MomentsF64, Moments, |self, acc| acc);
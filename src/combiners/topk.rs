@@ -1,4 +1,5 @@
-//! Top-K and Bottom-K combiners for selecting the largest or smallest values
+//! Top-K and Bottom-K combiners for selecting the largest or smallest values,
+//! plus `TopKBy` for ranking whole values by a derived key.
 
 use crate::Element;
 use crate::collection::CombineFn;
@@ -203,3 +204,75 @@ where
         true
     }
 }
+
+/* ===================== TopKBy<F> ===================== */
+
+/// The **K** values with the largest derived key per key, returned whole.
+///
+/// `key_fn` projects each value onto an `Ord` key, so values need not be
+/// `Ord` themselves. Values with equal keys are kept in arbitrary order.
+///
+/// - Accumulator: `Vec<T>` (bounded to `2k` between compactions)
+/// - Output: `Vec<T>` sorted by key, descending.
+///
+/// # Notes
+/// - `k == 0` will always produce an empty vector.
+#[derive(Clone, Copy)]
+pub struct TopKBy<F> {
+    /// Number of values to keep.
+    pub k: usize,
+    key_fn: F,
+}
+
+impl<F> TopKBy<F> {
+    /// Keep the `k` values with the largest `key_fn(&value)`.
+    #[must_use]
+    pub const fn new(k: usize, key_fn: F) -> Self {
+        Self { k, key_fn }
+    }
+
+    /// Sort `acc` by key (descending) and drop everything past `k`.
+    fn compact<T, K>(&self, acc: &mut Vec<T>)
+    where
+        K: Ord,
+        F: Fn(&T) -> K,
+    {
+        acc.sort_by_cached_key(|v| Reverse((self.key_fn)(v)));
+        acc.truncate(self.k);
+    }
+}
+
+impl<T, K, F> CombineFn<T, Vec<T>, Vec<T>> for TopKBy<F>
+where
+    T: Element,
+    K: Ord,
+    F: Fn(&T) -> K + Send + Sync + 'static,
+{
+    fn create(&self) -> Vec<T> {
+        Vec::new()
+    }
+
+    fn add_input(&self, acc: &mut Vec<T>, v: T) {
+        acc.push(v);
+        // Compact lazily so each value costs amortised O(log k).
+        if acc.len() > self.k.saturating_mul(2).max(1) {
+            self.compact(acc);
+        }
+    }
+
+    fn merge(&self, acc: &mut Vec<T>, other: Vec<T>) {
+        acc.extend(other);
+        if acc.len() > self.k {
+            self.compact(acc);
+        }
+    }
+
+    fn finish(&self, mut acc: Vec<T>) -> Vec<T> {
+        self.compact(&mut acc);
+        acc
+    }
+
+    fn is_associative_commutative(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,207 @@
+//! Typed errors raised by the execution engine.
+//!
+//! DirectRunner entry points such as [`DirectRunner::run_collect`](crate::DirectRunner::run_collect) return
+//! [`anyhow::Result`], so existing `?`-based call sites keep working. Failures that
+//! originate in the engine itself carry an [`IronbeamError`], which callers can recover
+//! for programmatic handling:
+//!
+//! ```no_run
+//! use ironbeam::*;
+//!
+//! let p = Pipeline::default();
+//! let parsed = from_vec(&p, vec!["1".to_string(), "x".to_string()])
+//!     .map(|s: &String| s.parse::<u32>().expect("not a number"))
+//!     .with_name("parse");
+//!
+//! let err = parsed.collect_par(None, Some(2)).unwrap_err();
+//! if let Some(IronbeamError::PanicInTransform { node, partition, payload }) = err.downcast_ref() {
+//!     eprintln!("{node} failed in partition {partition:?}: {payload}");
+//! }
+//! ```
+//!
+//! # Panics in transforms
+//!
+//! A panic inside a stateless transform (`map`, `filter`, a custom [`DynOp`](crate::DynOp),
+//! …) of the main execution chain is caught and reported as
+//! [`IronbeamError::PanicInTransform`], naming the plan step (and, in parallel execution,
+//! the partition) that failed. Panics raised elsewhere — inside barrier closures, join
+//! subplans, or while draining a lazy stream in
+//! [`ExecMode::Streaming`](crate::ExecMode::Streaming) — still propagate as panics.
+//!
+//! Built-in steps that can fail without a bug, such as the file writers' encode step or
+//! the service-backed transforms, report their errors as
+//! [`IronbeamError::TransformFailed`] in the same places instead.
+
+use crate::cancel::CancelReason;
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FormatResult};
+use std::panic::resume_unwind;
+
+/// Identifies one step of an execution plan in error reports.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepRef {
+    /// 1-based position of the step in the optimized chain, matching
+    /// [`ExplainStep::step`](crate::planner::ExplainStep::step).
+    pub step: usize,
+    /// Node kind of the step (e.g. `"Stateless"`), see [`Node::kind`](crate::node::Node::kind).
+    pub kind: &'static str,
+    /// User-supplied name of the step, if any (see
+    /// [`PCollection::with_name`](crate::PCollection::with_name)).
+    pub name: Option<String>,
+}
+
+impl Display for StepRef {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        match &self.name {
+            Some(name) => write!(f, "step {} ({} '{name}')", self.step, self.kind),
+            None => write!(f, "step {} ({})", self.step, self.kind),
+        }
+    }
+}
+
+/// Errors raised while executing a plan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IronbeamError {
+    /// A partition did not hold the type its consumer expected.
+    TypeMismatch {
+        /// Expected Rust type (e.g. `alloc::vec::Vec<u32>`).
+        expected: &'static str,
+        /// Step whose output was checked; `None` for the collected terminal output.
+        node: Option<StepRef>,
+    },
+    /// The plan or one of its subplans does not start with a `Source` node.
+    MissingSource,
+    /// A `Source` payload could not be cloned or split by its `VecOps`.
+    UnsupportedSource,
+    /// A node appeared where the executor cannot run it.
+    UnsupportedNode {
+        /// Node kind (e.g. `"Flatten"`).
+        kind: &'static str,
+        /// Where it appeared (e.g. `"inside a join subplan"`).
+        context: &'static str,
+    },
+    /// A stateless transform panicked.
+    PanicInTransform {
+        /// Plan step whose fused ops panicked.
+        node: StepRef,
+        /// Index of the failing partition in parallel execution; `None` when the
+        /// step ran over a single partition.
+        partition: Option<usize>,
+        /// Panic message (`"non-string panic payload"` when it was not a string).
+        payload: String,
+    },
+    /// A built-in stateless step returned an error.
+    TransformFailed {
+        /// Plan step whose fused ops failed.
+        node: StepRef,
+        /// Index of the failing partition in parallel execution; `None` when the
+        /// step ran over a single partition.
+        partition: Option<usize>,
+        /// The error, with its causes (`{:#}` format).
+        message: String,
+    },
+    /// A step's output partition outgrew
+    /// [`DirectRunner::max_memory_bytes`](crate::DirectRunner::max_memory_bytes) (see
+    /// [`crate::memory`]).
+    MemoryBudgetExceeded {
+        /// Plan step whose output was too large.
+        node: StepRef,
+        /// Index of the offending partition in parallel execution; `None` when the
+        /// step produced a single partition.
+        partition: Option<usize>,
+        /// Estimated size of the partition in bytes.
+        estimated_bytes: usize,
+        /// The configured budget in bytes.
+        budget: usize,
+    },
+    /// The run was cancelled through its
+    /// [`CancellationToken`](crate::cancel::CancellationToken) or ran past
+    /// [`DirectRunner::timeout`](crate::DirectRunner::timeout) (see [`crate::cancel`]).
+    Cancelled {
+        /// Plan step at which the run stopped.
+        node: StepRef,
+        /// Whether the token was cancelled or the timeout elapsed.
+        reason: CancelReason,
+    },
+}
+
+impl Display for IronbeamError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        match self {
+            Self::TypeMismatch {
+                expected,
+                node: Some(node),
+            } => write!(f, "type mismatch at {node}: expected {expected}"),
+            Self::TypeMismatch {
+                expected,
+                node: None,
+            } => write!(f, "terminal type mismatch: expected {expected}"),
+            Self::MissingSource => write!(f, "execution plan must start with a Source node"),
+            Self::UnsupportedSource => write!(f, "unsupported source vec type"),
+            Self::UnsupportedNode { kind, context } => {
+                write!(f, "{kind} is not supported {context}")
+            }
+            Self::PanicInTransform {
+                node,
+                partition,
+                payload,
+            } => {
+                write!(f, "{node} panicked")?;
+                if let Some(partition) = partition {
+                    write!(f, " in partition {partition}")?;
+                }
+                write!(f, ": {payload}")
+            }
+            Self::TransformFailed {
+                node,
+                partition,
+                message,
+            } => {
+                write!(f, "{node} failed")?;
+                if let Some(partition) = partition {
+                    write!(f, " in partition {partition}")?;
+                }
+                write!(f, ": {message}")
+            }
+            Self::MemoryBudgetExceeded {
+                node,
+                partition,
+                estimated_bytes,
+                budget,
+            } => {
+                write!(f, "{node} exceeded the memory budget")?;
+                if let Some(partition) = partition {
+                    write!(f, " in partition {partition}")?;
+                }
+                write!(
+                    f,
+                    ": ~{estimated_bytes} bytes estimated, budget is {budget} bytes"
+                )
+            }
+            Self::Cancelled {
+                node,
+                reason: CancelReason::Requested,
+            } => write!(f, "run cancelled at {node}"),
+            Self::Cancelled {
+                node,
+                reason: CancelReason::TimedOut(timeout),
+            } => write!(f, "run timed out after {timeout:?} at {node}"),
+        }
+    }
+}
+
+impl Error for IronbeamError {}
+
+/// Unwind payload raised by [`fail_step`], which the runner turns into
+/// [`IronbeamError::TransformFailed`] for the step it was raised in.
+pub(crate) struct StepFailure(pub(crate) String);
+
+/// Fail the step running this op with `err`.
+///
+/// [`DynOp::apply`](crate::DynOp::apply) cannot return an error, so this unwinds with a
+/// [`StepFailure`] payload. Unlike a panic it does not run the panic hook, so nothing is
+/// printed; the error reaches the caller of the run as
+/// [`IronbeamError::TransformFailed`].
+pub(crate) fn fail_step(err: &anyhow::Error) -> ! {
+    resume_unwind(Box::new(StepFailure(format!("{err:#}"))))
+}
@@ -4,11 +4,16 @@
 //! functionality:
 //!
 //! - [`CompositeTransform`]: Package multiple transforms into a reusable component
+//! - [`Sink`]: Write a collection to a custom destination (database, HTTP endpoint,
+//!   message queue, …) via [`PCollection::write_to`]
 //!
 //! These extension points allow you to build higher-level abstractions on top of
 //! the core pipeline API without modifying the framework itself.
 
+use crate::runner::{ExecMode, PipelineRunner, RunnerConfig};
 use crate::{Element, PCollection};
+use anyhow::Result;
+use std::sync::{Mutex, MutexGuard, PoisonError};
 
 /// A reusable, packaged sequence of transformations.
 ///
@@ -151,3 +156,256 @@ impl<T: Element> PCollection<T> {
         transform.expand(self.clone())
     }
 }
+
+/// A custom output destination driven by [`PCollection::write_to`].
+///
+/// This is the write-side counterpart of [`VecOps`](crate::VecOps): implement it to send
+/// a collection somewhere the built-in writers do not cover. The runner drives a sink
+/// through a fixed lifecycle:
+///
+/// 1. The pipeline is executed, and each of its `n` output partitions is handed to the
+///    sink as soon as the worker computing it finishes.
+/// 2. For each partition `i`: [`open(i, n)`](Self::open), then
+///    [`write_batch`](Self::write_batch) for every chunk of at most
+///    [`batch_size`](Self::batch_size) elements, then [`flush`](Self::flush).
+///    Empty partitions are still opened and flushed, and a run with no partitions at
+///    all drives a single empty partition `0` of `1`.
+/// 3. [`close`](Self::close) exactly once — also when execution or an earlier step
+///    failed, so connections and handles are always released.
+///
+/// Partitions may arrive in any order, from any worker thread, but each is driven whole
+/// under a lock: `open`/`write_batch`/`flush` of two partitions never interleave, so
+/// implementations may hold plain (non-`Sync`) mutable state. After the first error no
+/// further partitions are opened.
+///
+/// # Example
+/// ```
+/// use ironbeam::*;
+/// use anyhow::Result;
+///
+/// #[derive(Default)]
+/// struct Collect(Vec<u32>);
+///
+/// impl Sink<u32> for Collect {
+///     fn write_batch(&mut self, batch: &[u32]) -> Result<()> {
+///         self.0.extend_from_slice(batch);
+///         Ok(())
+///     }
+/// }
+///
+/// # fn main() -> Result<()> {
+/// let p = Pipeline::default();
+/// let mut sink = Collect::default();
+/// let written = from_vec(&p, vec![1u32, 2, 3]).write_to(&mut sink)?;
+/// assert_eq!(written, 3);
+/// assert_eq!(sink.0, vec![1, 2, 3]);
+/// # Ok(())
+/// # }
+/// ```
+pub trait Sink<T>: Send {
+    /// Prepare to receive partition `partition` (0-based) of `num_partitions`.
+    ///
+    /// # Errors
+    /// An error aborts the write; [`close`](Self::close) is still called.
+    fn open(&mut self, partition: usize, num_partitions: usize) -> Result<()> {
+        let _ = (partition, num_partitions);
+        Ok(())
+    }
+
+    /// Write one batch of elements belonging to the currently open partition.
+    ///
+    /// # Errors
+    /// An error aborts the write; [`close`](Self::close) is still called.
+    fn write_batch(&mut self, batch: &[T]) -> Result<()>;
+
+    /// Make every batch of the current partition durable.
+    ///
+    /// # Errors
+    /// An error aborts the write; [`close`](Self::close) is still called.
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Release all resources. Called exactly once, after the last partition or
+    /// after the first failure.
+    ///
+    /// # Errors
+    /// Returned to the caller unless an earlier step already failed.
+    fn close(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Number of partitions [`PCollection::write_to`] executes the pipeline with
+    /// (minimum 1). Defaults to 1, which runs sequentially; runners passed to
+    /// [`PCollection::write_to_with`] use their own partitioning instead.
+    fn partitions(&self) -> usize {
+        1
+    }
+
+    /// Maximum number of elements per [`write_batch`](Self::write_batch) call
+    /// (minimum 1). Defaults to 1024.
+    fn batch_size(&self) -> usize {
+        1024
+    }
+}
+
+impl<T, S: Sink<T> + ?Sized> Sink<T> for &mut S {
+    fn open(&mut self, partition: usize, num_partitions: usize) -> Result<()> {
+        (**self).open(partition, num_partitions)
+    }
+
+    fn write_batch(&mut self, batch: &[T]) -> Result<()> {
+        (**self).write_batch(batch)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        (**self).flush()
+    }
+
+    fn close(&mut self) -> Result<()> {
+        (**self).close()
+    }
+
+    fn partitions(&self) -> usize {
+        (**self).partitions()
+    }
+
+    fn batch_size(&self) -> usize {
+        (**self).batch_size()
+    }
+}
+
+impl<T: Element> PCollection<T> {
+    /// Execute the pipeline and write its output to a custom [`Sink`].
+    ///
+    /// The pipeline runs with its [`RunnerConfig`] (see
+    /// [`Pipeline::set_runner_config`](crate::Pipeline::set_runner_config)) over the
+    /// sink's [`partitions`](Sink::partitions): sequentially (one partition, in collection
+    /// order) for the default of 1, otherwise in parallel with each partition written by
+    /// the worker that produced it, as described on [`Sink`]. Pass `&mut sink` to keep
+    /// ownership of the sink after writing.
+    ///
+    /// Returns the number of elements written.
+    ///
+    /// # Errors
+    /// Returns the first error raised by pipeline execution or by any sink method.
+    pub fn write_to<S: Sink<T>>(self, sink: S) -> Result<usize> {
+        let config = sink_runner(self.pipeline.runner_config(), sink.partitions());
+        self.write_to_with(&config, sink)
+    }
+
+    /// Like [`write_to`](Self::write_to), but executes the pipeline on `runner`.
+    ///
+    /// # Errors
+    /// Returns an error if execution fails or the sink reports one.
+    pub fn write_to_with<R: PipelineRunner, S: Sink<T>>(
+        self,
+        runner: &R,
+        sink: S,
+    ) -> Result<usize> {
+        runner.run_to_sink(&self.pipeline, self.id, sink)
+    }
+}
+
+/// `config` with the mode [`PCollection::write_to`] drives a sink with `partitions`
+/// partitions in.
+pub(crate) fn sink_runner(config: RunnerConfig, partitions: usize) -> RunnerConfig {
+    let mode = match partitions {
+        0 | 1 => ExecMode::Sequential,
+        n => ExecMode::Parallel {
+            threads: None,
+            partitions: Some(n),
+        },
+    };
+    config.with_mode(mode)
+}
+
+/// Drives a [`Sink`] through its lifecycle from whichever threads produce partitions.
+pub(crate) struct SinkDriver<S> {
+    state: Mutex<DriverState<S>>,
+    batch_size: usize,
+}
+
+struct DriverState<S> {
+    sink: S,
+    written: usize,
+    driven: bool,
+    error: Option<anyhow::Error>,
+}
+
+impl<S> SinkDriver<S> {
+    pub(crate) fn new<T>(sink: S) -> Self
+    where
+        S: Sink<T>,
+    {
+        Self {
+            batch_size: sink.batch_size().max(1),
+            state: Mutex::new(DriverState {
+                sink,
+                written: 0,
+                driven: false,
+                error: None,
+            }),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, DriverState<S>> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Open, write and flush one whole partition, unless an earlier one failed.
+    pub(crate) fn write<T>(&self, partition: usize, num_partitions: usize, data: &[T])
+    where
+        S: Sink<T>,
+    {
+        let mut state = self.state();
+        if state.error.is_some() {
+            return;
+        }
+        state.driven = true;
+        let batch_size = self.batch_size;
+        let sink = &mut state.sink;
+        let result = sink.open(partition, num_partitions).and_then(|()| {
+            for batch in data.chunks(batch_size) {
+                sink.write_batch(batch)?;
+            }
+            sink.flush()
+        });
+        match result {
+            Ok(()) => state.written += data.len(),
+            Err(e) => state.error = Some(e),
+        }
+    }
+
+    /// Stop driving partitions, reporting `error` from [`finish`](Self::finish) unless
+    /// an earlier one is already recorded.
+    pub(crate) fn fail(&self, error: anyhow::Error) {
+        self.state().error.get_or_insert(error);
+    }
+
+    /// Close the sink once `run` — the execution feeding it — has ended, returning the
+    /// number of elements written. A successful run that produced no partitions still
+    /// drives one empty partition.
+    ///
+    /// Errors are reported in lifecycle order: a sink error first, then the run's, then
+    /// the one from [`close`](Sink::close).
+    pub(crate) fn finish<T>(self, run: Result<()>) -> Result<usize>
+    where
+        S: Sink<T>,
+    {
+        if run.is_ok() && !self.state().driven {
+            self.write::<T>(0, 1, &[]);
+        }
+        let mut state = self
+            .state
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner);
+        let closed = state.sink.close();
+        if let Some(e) = state.error {
+            return Err(e);
+        }
+        run?;
+        closed?;
+        Ok(state.written)
+    }
+}
@@ -0,0 +1,178 @@
+//! Async transforms and collection on a tokio runtime (feature: `async`).
+//!
+//! Pipelines dominated by network calls — HTTP enrichment, lookups against the
+//! [cloud traits](crate::io::cloud::traits) — waste rayon threads when every call
+//! blocks one. This module adds:
+//!
+//! - [`PCollection::map_async`] -- maps each element through an `async` function,
+//!   keeping up to `concurrency` calls in flight across all partitions. Futures run on
+//!   a shared multi-threaded tokio runtime owned by ironbeam, so they may use tokio
+//!   I/O and timers (and clients built on them) regardless of how the pipeline is run.
+//! - [`PCollection::collect_async`] -- runs the pipeline from `async` code without
+//!   blocking the caller's executor.
+//!
+//! `map_async` blocks the executing thread while its futures run, so inside `async`
+//! code, run pipelines that contain it with `collect_async` rather than the blocking
+//! `collect_*` methods, which would block a runtime worker (and panic when they
+//! reach `map_async` on one).
+//!
+//! ```no_run
+//! use ironbeam::*;
+//! use anyhow::Result;
+//! use std::time::Duration;
+//!
+//! # async fn run() -> Result<()> {
+//! let p = Pipeline::default();
+//! let ids = from_vec(&p, (0..1_000u32).collect::<Vec<_>>());
+//! let enriched = ids.map_async(64, |id| async move {
+//!     // e.g. an HTTP lookup
+//!     tokio::time::sleep(Duration::from_millis(10)).await;
+//!     format!("user-{id}")
+//! });
+//! let out = enriched.collect_async().await?;
+//! assert_eq!(out.len(), 1_000);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::node::{DynOp, Node};
+use crate::type_token::Partition;
+use crate::{Element, PCollection};
+use anyhow::{Result, anyhow};
+use std::future::Future;
+use std::marker::PhantomData;
+use std::panic::resume_unwind;
+use std::sync::{Arc, OnceLock};
+use tokio::runtime::{Builder, Runtime};
+use tokio::sync::Semaphore;
+use tokio::task::{JoinError, JoinSet};
+
+/// The runtime every [`MapAsyncOp`] drives its futures on.
+fn runtime() -> &'static Runtime {
+    static RUNTIME: OnceLock<Runtime> = OnceLock::new();
+    RUNTIME.get_or_init(|| {
+        Builder::new_multi_thread()
+            .thread_name("ironbeam-async")
+            .enable_all()
+            .build()
+            .expect("failed to start the ironbeam async runtime")
+    })
+}
+
+/// Re-raise a task's panic on the executing thread, so it surfaces like a panic in
+/// any other transform.
+fn propagate<O>(result: Result<(usize, O), JoinError>) -> (usize, O) {
+    match result {
+        Ok(done) => done,
+        Err(err) if err.is_panic() => resume_unwind(err.into_panic()),
+        Err(err) => panic!("map_async task failed: {err}"),
+    }
+}
+
+/// `MapAsyncOp`: `T -> Future<Output = O>`, preserving element order. `limit` is shared
+/// by every partition, bounding in-flight calls for the whole transform.
+struct MapAsyncOp<T, O, F> {
+    f: Arc<F>,
+    limit: Arc<Semaphore>,
+    _t: PhantomData<fn(T) -> O>,
+}
+
+impl<T, O, F, Fut> DynOp for MapAsyncOp<T, O, F>
+where
+    T: Element,
+    O: Element,
+    F: Fn(T) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = O> + Send + 'static,
+{
+    fn apply(&self, input: Partition) -> Partition {
+        let input = *input
+            .downcast::<Vec<T>>()
+            .expect("MapAsyncOp: expected Vec<T> input");
+        let len = input.len();
+        let out = runtime().block_on(async {
+            let mut slots: Vec<Option<O>> = (0..len).map(|_| None).collect();
+            let mut tasks = JoinSet::new();
+            for (idx, item) in input.into_iter().enumerate() {
+                let permit = Arc::clone(&self.limit)
+                    .acquire_owned()
+                    .await
+                    .expect("map_async semaphore is never closed");
+                let call = (self.f)(item);
+                tasks.spawn(async move {
+                    let out = call.await;
+                    drop(permit);
+                    (idx, out)
+                });
+                while let Some(done) = tasks.try_join_next() {
+                    let (idx, out) = propagate(done);
+                    slots[idx] = Some(out);
+                }
+            }
+            while let Some(done) = tasks.join_next().await {
+                let (idx, out) = propagate(done);
+                slots[idx] = Some(out);
+            }
+            slots
+        });
+        let out: Vec<O> = out
+            .into_iter()
+            .map(|slot| slot.expect("every map_async task completed"))
+            .collect();
+        Box::new(out) as Partition
+    }
+}
+
+impl<T: Element> PCollection<T> {
+    /// Map each element through an `async` function, with at most `concurrency` calls
+    /// in flight at once across all partitions.
+    ///
+    /// `f` takes the element by value and returns a `'static` future (typically an
+    /// `async move` block). Futures run on ironbeam's shared tokio runtime; output order
+    /// matches input order within each partition. A `concurrency` of `0` is treated as
+    /// `1`.
+    ///
+    /// See the [module docs](crate::helpers::async_exec) for running pipelines that use
+    /// it from `async` code.
+    ///
+    /// # Panics
+    /// When the pipeline runs, a panic inside a future is re-raised on the thread
+    /// executing the partition.
+    #[must_use]
+    pub fn map_async<O, F, Fut>(self, concurrency: usize, f: F) -> PCollection<O>
+    where
+        O: Element,
+        F: Fn(T) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = O> + Send + 'static,
+    {
+        let op: Arc<dyn DynOp> = Arc::new(MapAsyncOp::<T, O, F> {
+            f: Arc::new(f),
+            limit: Arc::new(Semaphore::new(concurrency.max(1))),
+            _t: PhantomData,
+        });
+        let id = self.pipeline.insert_node(Node::Stateless(vec![op]));
+        self.pipeline.connect(self.id, id);
+        self.pipeline.set_coder::<O>(id);
+        PCollection {
+            pipeline: self.pipeline,
+            id,
+            _t: PhantomData,
+        }
+    }
+
+    /// Collect elements **in parallel** from `async` code.
+    ///
+    /// Runs the pipeline like [`collect_par(None, None)`](Self::collect_par) on the
+    /// calling runtime's blocking thread pool, so the caller's executor keeps making
+    /// progress. Must be awaited within a tokio runtime.
+    ///
+    /// # Errors
+    /// Returns an error if execution fails (see [`collect_par`](Self::collect_par)) or
+    /// the blocking task is cancelled.
+    pub async fn collect_async(self) -> Result<Vec<T>> {
+        match tokio::task::spawn_blocking(move || self.collect_par(None, None)).await {
+            Ok(result) => result,
+            Err(err) if err.is_panic() => resume_unwind(err.into_panic()),
+            Err(err) => Err(anyhow!("pipeline task failed: {err}")),
+        }
+    }
+}
@@ -227,6 +227,7 @@ pub fn read_avro<T>(p: &Pipeline, path: impl AsRef<Path>) -> Result<PCollection<
 where
     T: Element + DeserializeOwned,
 {
+    let location = path.as_ref().display().to_string();
     let path_str = path
         .as_ref()
         .to_str()
@@ -247,10 +248,10 @@ where
                 read_avro_vec(&file).with_context(|| format!("reading {}", file.display()))?;
             all_data.extend(data);
         }
-        Ok(from_vec(p, all_data))
+        Ok(from_vec(p, all_data).record_input("avro", location))
     } else {
         let v = read_avro_vec::<T>(path)?;
-        Ok(from_vec(p, v))
+        Ok(from_vec(p, v).record_input("avro", location))
     }
 }
 
@@ -294,6 +295,7 @@ pub fn read_avro_streaming<T>(
 where
     T: Element + DeserializeOwned,
 {
+    let location = path.as_ref().display().to_string();
     let shards: AvroShards = build_avro_shards(path, records_per_shard)?;
     let id = p.insert_node(Node::Source {
         payload: Arc::new(shards),
@@ -301,6 +303,7 @@ where
         elem_tag: TypeTag::of::<T>(),
     });
     p.set_coder::<T>(id);
+    p.record_input(id, "avro", location);
     Ok(PCollection {
         pipeline: p.clone(),
         id,
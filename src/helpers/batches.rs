@@ -20,7 +20,9 @@
 //! costs, vectorize operations, or reuse buffers while preserving deterministic
 //! ordering within partitions.
 
-use crate::collection::{BatchBySizeOp, BatchElementsOp, BatchMapOp, BatchMapValuesOp};
+use crate::collection::{
+    BatchBySizeOp, BatchElementsOp, BatchMapOp, BatchMapValuesOp, TryPartitionMapOp,
+};
 use crate::node::{DynOp, Node};
 use crate::{Element, PCollection};
 use std::hash::Hash;
@@ -77,6 +79,28 @@ impl<T: Element> PCollection<T> {
         }
     }
 
+    /// Apply `f` to each whole partition in a stateless node.
+    ///
+    /// This backs the transforms that call an external service once per partition
+    /// rather than once per element. An `Err` from `f` fails the step, reported as
+    /// [`IronbeamError::TransformFailed`](crate::IronbeamError::TransformFailed) with
+    /// the error's message.
+    pub(crate) fn try_map_partitions<O, F>(self, f: F) -> PCollection<O>
+    where
+        O: Element,
+        F: 'static + Send + Sync + Fn(Vec<T>) -> anyhow::Result<Vec<O>>,
+    {
+        let op: Arc<dyn DynOp> = Arc::new(TryPartitionMapOp::<T, O, F>(f, PhantomData));
+        let id = self.pipeline.insert_node(Node::Stateless(vec![op]));
+        self.pipeline.connect(self.id, id);
+        self.pipeline.set_coder::<O>(id);
+        PCollection {
+            pipeline: self.pipeline,
+            id,
+            _t: PhantomData,
+        }
+    }
+
     /// Group consecutive elements within each partition into `Vec<T>` batches
     /// of at most `batch_size` elements.
     ///
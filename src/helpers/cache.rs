@@ -0,0 +1,203 @@
+//! Explicit caching of intermediate collections.
+//!
+//! Every terminal `collect_*` plans and runs the pipeline from its sources, and every
+//! join or flatten input replays its own copy of the upstream chain. When several
+//! branches share an expensive prefix (a diamond, or one parsed dataset feeding two
+//! joins and a sink), that prefix is recomputed once per consumer.
+//!
+//! [`PCollection::cache`] marks a collection whose output should be computed once and
+//! reused: it returns a new collection that reads the materialized elements. The
+//! upstream chain runs the first time any plan reads the cached collection, as part of
+//! that run: on the same [`DirectRunner`](crate::DirectRunner) (mode, memory limits,
+//! spill settings, timeout) and under the same cancellation token. Every later
+//! consumer, in the same run or a later one, reads the stored result instead.
+//!
+//! Unlike [`DirectRunner::run_collect_cached`](crate::DirectRunner::run_collect_cached), which picks
+//! a cache point automatically and only helps calls that share one
+//! [`SharedCSECache`](crate::SharedCSECache), `cache` is placed explicitly and applies
+//! to every consumer, including join and flatten inputs.
+//!
+//! The cached elements stay in memory for as long as the pipeline (or any plan built
+//! from it) is alive.
+
+use crate::node::Node;
+use crate::pipeline::PipelineInner;
+#[cfg(feature = "result-cache")]
+use crate::result_cache::ResultCache;
+use crate::runner::PrepareContext;
+use crate::type_token::{Partition, TypeTag, VecOps, vec_ops_for};
+use crate::{Element, NodeId, PCollection, Pipeline};
+use anyhow::{Result, anyhow};
+use std::any::Any;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex, PoisonError, Weak};
+
+/// The `Source` payload of a cached collection: the upstream node to run, and its
+/// output once it has been computed.
+///
+/// Holds the pipeline weakly, since the pipeline owns this payload through its node.
+struct CachedPayload<T> {
+    pipeline: Weak<Mutex<PipelineInner>>,
+    upstream: NodeId,
+    data: Mutex<Option<Arc<Vec<T>>>>,
+    /// Where to persist the result across runs, for
+    /// [`cache_persistent`](PCollection::cache_persistent).
+    #[cfg(feature = "result-cache")]
+    store: Option<ResultCache>,
+}
+
+impl<T: Element> CachedPayload<T> {
+    fn get(&self) -> Option<Arc<Vec<T>>> {
+        self.data
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Run the upstream node as part of the run in `ctx`, or load its stored result
+    /// when persisted.
+    fn compute(&self, p: &Pipeline, ctx: &PrepareContext<'_>) -> Result<Vec<T>> {
+        #[cfg(feature = "result-cache")]
+        if let Some(store) = &self.store {
+            return store.load_or_compute::<T>(p, self.upstream, ctx);
+        }
+        ctx.run_collect::<T>(p, self.upstream)
+    }
+}
+
+/// `VecOps` for a [`CachedPayload<T>`], delegating to the `Vec<T>` ops once the payload
+/// has been computed.
+struct CachedVecOps<T>(PhantomData<T>);
+
+impl<T: Element> CachedVecOps<T> {
+    fn with_data<R>(data: &dyn Any, f: impl FnOnce(&Vec<T>) -> Option<R>) -> Option<R> {
+        let cached = data.downcast_ref::<CachedPayload<T>>()?.get()?;
+        f(&cached)
+    }
+}
+
+impl<T: Element> VecOps for CachedVecOps<T> {
+    fn len(&self, data: &dyn Any) -> Option<usize> {
+        Self::with_data(data, |v| Some(v.len()))
+    }
+
+    fn split(&self, data: &dyn Any, n: usize) -> Option<Vec<Partition>> {
+        Self::with_data(data, |v| vec_ops_for::<T>().split(v, n))
+    }
+
+    fn clone_any(&self, data: &dyn Any) -> Option<Partition> {
+        Self::with_data(data, |v| Some(Box::new(v.clone()) as Partition))
+    }
+
+    fn prepare(&self, data: &dyn Any, ctx: &PrepareContext<'_>) -> Result<bool> {
+        let payload = data
+            .downcast_ref::<CachedPayload<T>>()
+            .ok_or_else(|| anyhow!("cache: unexpected source payload"))?;
+        // Holding the lock while computing makes concurrent readers wait for the one
+        // computation instead of starting their own.
+        let mut slot = payload.data.lock().unwrap_or_else(PoisonError::into_inner);
+        if slot.is_some() {
+            return Ok(false);
+        }
+        let inner = payload
+            .pipeline
+            .upgrade()
+            .ok_or_else(|| anyhow!("cache: the pipeline was dropped"))?;
+        let out = payload.compute(&Pipeline { inner }, ctx)?;
+        *slot = Some(Arc::new(out));
+        Ok(true)
+    }
+}
+
+impl<T: Element> PCollection<T> {
+    /// Compute this collection once and reuse the result for every consumer.
+    ///
+    /// Returns a collection with the same elements. Its upstream chain runs the first
+    /// time a plan reads it; all later reads -- other branches, join and flatten inputs,
+    /// and later `collect_*` calls -- reuse the stored elements. See the
+    /// [module docs](crate::helpers::cache).
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ironbeam::*;
+    /// # use anyhow::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let parsed = from_vec(&p, vec!["a=1".to_string(), "b=2".to_string(), "a=3".to_string()])
+    ///     .map(|s: &String| {
+    ///         let (k, v) = s.split_once('=').unwrap();
+    ///         (k.to_string(), v.parse::<u32>().unwrap())
+    ///     })
+    ///     .cache();
+    ///
+    /// // Both branches read the parsed rows; parsing runs once.
+    /// let totals = parsed.clone().combine_values(Sum::<u32>::default());
+    /// let joined = parsed.join_inner(&totals).collect_seq_sorted()?;
+    /// assert_eq!(joined.len(), 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn cache(self) -> Self {
+        let node = cached_source::<T>(&self.pipeline, self.id);
+        self.insert_cached(node)
+    }
+
+    /// Insert `node`, a cached source reading this collection, and return its
+    /// collection.
+    pub(crate) fn insert_cached(self, node: Node) -> Self {
+        let id = self.pipeline.insert_node(node);
+        self.pipeline.set_coder::<T>(id);
+        self.pipeline
+            .inner
+            .lock()
+            .unwrap()
+            .cached_from
+            .insert(id, self.id);
+        Self {
+            pipeline: self.pipeline,
+            id,
+            _t: PhantomData,
+        }
+    }
+}
+
+/// Builds a `Source` node that computes `upstream` of the given pipeline on first read.
+pub(crate) type CacheSourceFn = fn(&Pipeline, NodeId) -> Node;
+
+/// A [`CacheSourceFn`] for a node producing `T`.
+pub(crate) fn cached_source<T: Element>(p: &Pipeline, upstream: NodeId) -> Node {
+    let payload = CachedPayload::<T> {
+        pipeline: Arc::downgrade(&p.inner),
+        upstream,
+        data: Mutex::new(None),
+        #[cfg(feature = "result-cache")]
+        store: None,
+    };
+    Node::Source {
+        payload: Arc::new(payload),
+        vec_ops: Arc::new(CachedVecOps::<T>(PhantomData)),
+        elem_tag: TypeTag::of::<T>(),
+    }
+}
+
+/// A cached source for `upstream` whose result is persisted in `store`.
+#[cfg(feature = "result-cache")]
+pub(crate) fn persisted_source<T: Element>(
+    p: &Pipeline,
+    upstream: NodeId,
+    store: ResultCache,
+) -> Node {
+    let payload = CachedPayload::<T> {
+        pipeline: Arc::downgrade(&p.inner),
+        upstream,
+        data: Mutex::new(None),
+        store: Some(store),
+    };
+    Node::Source {
+        payload: Arc::new(payload),
+        vec_ops: Arc::new(CachedVecOps::<T>(PhantomData)),
+        elem_tag: TypeTag::of::<T>(),
+    }
+}
@@ -0,0 +1,289 @@
+//! Enrichment lookups through a [`CacheIO`] cache.
+//!
+//! [`map_with_cached_lookup`](PCollection::map_with_cached_lookup) pairs every element
+//! with the value for `key_fn(element)`, found in three places in turn:
+//!
+//! 1. an in-process LRU of recently used values, so hot keys do not reach the remote
+//!    cache once per element;
+//! 2. the remote cache, where values are stored as JSON;
+//! 3. the `loader`, e.g. a [`DatabaseIO`](crate::io::cloud::DatabaseIO) query, whose
+//!    result is written back to the remote cache with the given TTL.
+//!
+//! A cached value that no longer deserializes, e.g. after the value type changed, is
+//! treated as a miss and overwritten.
+//!
+//! This takes the cache by reference, so it executes the pipeline up to this point when
+//! called, as the file writers do, and returns the results as a new collection in the
+//! same pipeline.
+//!
+//! [`map_with_cached_lookup_shared`](PCollection::map_with_cached_lookup_shared) takes
+//! the cache as an `Arc` and runs as a lazy step of the pipeline instead. Every
+//! partition looks its keys up on the worker running it, through one LRU shared by all
+//! partitions.
+//!
+//! ```
+//! use ironbeam::io::cloud::{CacheIO, FakeCacheIO};
+//! use ironbeam::*;
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let cache = FakeCacheIO::new();
+//! let p = Pipeline::default();
+//! let orders = from_vec(&p, vec![("u1".to_string(), 30u32), ("u1".to_string(), 5)]);
+//! let named = orders
+//!     .map_with_cached_lookup(
+//!         &cache,
+//!         |(user, _): &(String, u32)| format!("user-name:{user}"),
+//!         |key| Ok(key.trim_start_matches("user-name:").to_uppercase()),
+//!         Some(3600),
+//!     )?
+//!     .collect_seq()?;
+//! assert_eq!(named[1].1, "U1");
+//! assert_eq!(cache.get("user-name:u1")?, Some(b"\"U1\"".to_vec()));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::io::cloud::traits::CacheIO;
+use crate::{Element, PCollection, from_vec};
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+/// Default number of values kept in the in-process LRU.
+pub const LOOKUP_LRU_CAPACITY: usize = 10_000;
+
+/// Options for [`PCollection::map_with_cached_lookup_options`].
+#[derive(Debug, Clone, Copy)]
+pub struct CachedLookupOptions {
+    /// TTL of values written back to the remote cache, in seconds (default: none).
+    pub ttl_secs: Option<u64>,
+    /// Number of values kept in the in-process LRU; `0` disables it (default:
+    /// [`LOOKUP_LRU_CAPACITY`]).
+    pub lru_capacity: usize,
+}
+
+impl Default for CachedLookupOptions {
+    fn default() -> Self {
+        Self {
+            ttl_secs: None,
+            lru_capacity: LOOKUP_LRU_CAPACITY,
+        }
+    }
+}
+
+impl CachedLookupOptions {
+    /// Set the TTL of values written back to the remote cache.
+    #[must_use]
+    pub const fn with_ttl_secs(mut self, ttl_secs: Option<u64>) -> Self {
+        self.ttl_secs = ttl_secs;
+        self
+    }
+
+    /// Set the number of values kept in the in-process LRU.
+    #[must_use]
+    pub const fn with_lru_capacity(mut self, lru_capacity: usize) -> Self {
+        self.lru_capacity = lru_capacity;
+        self
+    }
+}
+
+/// Least-recently-used map from keys to values, evicting past `capacity` entries.
+struct Lru<V> {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<String, (V, u64)>,
+    /// Keys by the tick of their last use, oldest first.
+    order: BTreeMap<u64, String>,
+}
+
+impl<V: Clone> Lru<V> {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            tick: 0,
+            entries: HashMap::new(),
+            order: BTreeMap::new(),
+        }
+    }
+
+    fn get(&mut self, key: &str) -> Option<V> {
+        let (value, used) = self.entries.get_mut(key)?;
+        self.order.remove(used);
+        self.tick += 1;
+        *used = self.tick;
+        self.order.insert(self.tick, key.to_string());
+        Some(value.clone())
+    }
+
+    fn put(&mut self, key: String, value: V) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.tick += 1;
+        if let Some((_, used)) = self.entries.insert(key.clone(), (value, self.tick)) {
+            self.order.remove(&used);
+        }
+        self.order.insert(self.tick, key);
+        if self.entries.len() > self.capacity
+            && let Some((_, oldest)) = self.order.pop_first()
+        {
+            self.entries.remove(&oldest);
+        }
+    }
+}
+
+impl<T: Element> PCollection<T> {
+    /// Execute the collection and pair every element with the value for
+    /// `key_fn(element)`, from the in-process LRU, `cache`, or `loader`, writing loaded
+    /// values back to `cache` with `ttl_secs`.
+    ///
+    /// See the [module documentation](crate::helpers::cached_lookup).
+    ///
+    /// # Errors
+    /// See [`map_with_cached_lookup_options`](PCollection::map_with_cached_lookup_options).
+    pub fn map_with_cached_lookup<V, K, L>(
+        self,
+        cache: &dyn CacheIO,
+        key_fn: K,
+        loader: L,
+        ttl_secs: Option<u64>,
+    ) -> Result<PCollection<(T, V)>>
+    where
+        V: Element + Serialize + DeserializeOwned,
+        K: Fn(&T) -> String,
+        L: Fn(&str) -> Result<V>,
+    {
+        let opts = CachedLookupOptions::default().with_ttl_secs(ttl_secs);
+        self.map_with_cached_lookup_options(cache, key_fn, loader, &opts)
+    }
+
+    /// Like [`map_with_cached_lookup`](PCollection::map_with_cached_lookup), with
+    /// explicit [`CachedLookupOptions`].
+    ///
+    /// # Errors
+    /// Returns an error if the pipeline fails, a cache `get` or `set` call fails, the
+    /// loader fails, or a loaded value does not serialize.
+    pub fn map_with_cached_lookup_options<V, K, L>(
+        self,
+        cache: &dyn CacheIO,
+        key_fn: K,
+        loader: L,
+        opts: &CachedLookupOptions,
+    ) -> Result<PCollection<(T, V)>>
+    where
+        V: Element + Serialize + DeserializeOwned,
+        K: Fn(&T) -> String,
+        L: Fn(&str) -> Result<V>,
+    {
+        let pipeline = self.pipeline.clone();
+        let elements = self.collect_seq()?;
+        let lru = Mutex::new(Lru::new(opts.lru_capacity));
+        let out = lookup_all(cache, &key_fn, &loader, opts.ttl_secs, &lru, elements)?;
+        Ok(from_vec(&pipeline, out))
+    }
+
+    /// Pair every element with the value for `key_fn(element)`, from the in-process LRU,
+    /// `cache`, or `loader`, as a lazy step of the pipeline.
+    ///
+    /// See [`map_with_cached_lookup_options_shared`](PCollection::map_with_cached_lookup_options_shared).
+    pub fn map_with_cached_lookup_shared<V, K, L>(
+        self,
+        cache: Arc<dyn CacheIO>,
+        key_fn: K,
+        loader: L,
+        ttl_secs: Option<u64>,
+    ) -> PCollection<(T, V)>
+    where
+        V: Element + Serialize + DeserializeOwned,
+        K: 'static + Send + Sync + Fn(&T) -> String,
+        L: 'static + Send + Sync + Fn(&str) -> Result<V>,
+    {
+        let opts = CachedLookupOptions::default().with_ttl_secs(ttl_secs);
+        self.map_with_cached_lookup_options_shared(cache, key_fn, loader, &opts)
+    }
+
+    /// Like [`map_with_cached_lookup_options`](PCollection::map_with_cached_lookup_options),
+    /// as a lazy step of the pipeline.
+    ///
+    /// Nothing runs until the pipeline does. Each partition is looked up on the worker
+    /// running it, through one LRU shared by every partition of every run of this step.
+    /// The LRU is not locked during remote calls, so two partitions missing the same key
+    /// at once may both load it. Element order within a partition is preserved.
+    ///
+    /// A failed cache call or load, or a loaded value that does not serialize, fails the
+    /// step with [`IronbeamError::TransformFailed`](crate::IronbeamError::TransformFailed).
+    pub fn map_with_cached_lookup_options_shared<V, K, L>(
+        self,
+        cache: Arc<dyn CacheIO>,
+        key_fn: K,
+        loader: L,
+        opts: &CachedLookupOptions,
+    ) -> PCollection<(T, V)>
+    where
+        V: Element + Serialize + DeserializeOwned,
+        K: 'static + Send + Sync + Fn(&T) -> String,
+        L: 'static + Send + Sync + Fn(&str) -> Result<V>,
+    {
+        let ttl_secs = opts.ttl_secs;
+        let lru = Arc::new(Mutex::new(Lru::new(opts.lru_capacity)));
+        self.try_map_partitions(move |elements| {
+            lookup_all(cache.as_ref(), &key_fn, &loader, ttl_secs, &lru, elements)
+        })
+    }
+}
+
+/// Pair `elements` with their values, trying `lru` before `cache` and `loader`.
+fn lookup_all<T, V, K, L>(
+    cache: &dyn CacheIO,
+    key_fn: &K,
+    loader: &L,
+    ttl_secs: Option<u64>,
+    lru: &Mutex<Lru<V>>,
+    elements: Vec<T>,
+) -> Result<Vec<(T, V)>>
+where
+    V: Clone + Serialize + DeserializeOwned,
+    K: Fn(&T) -> String,
+    L: Fn(&str) -> Result<V>,
+{
+    let mut out = Vec::with_capacity(elements.len());
+    for element in elements {
+        let key = key_fn(&element);
+        let hit = lru.lock().expect("lookup LRU mutex poisoned").get(&key);
+        let value = match hit {
+            Some(value) => value,
+            None => {
+                let value = lookup(cache, &key, loader, ttl_secs)?;
+                lru.lock()
+                    .expect("lookup LRU mutex poisoned")
+                    .put(key, value.clone());
+                value
+            }
+        };
+        out.push((element, value));
+    }
+    Ok(out)
+}
+
+/// Read `key` from `cache`, or load it and write it back.
+fn lookup<V, L>(cache: &dyn CacheIO, key: &str, loader: &L, ttl_secs: Option<u64>) -> Result<V>
+where
+    V: Serialize + DeserializeOwned,
+    L: Fn(&str) -> Result<V>,
+{
+    let cached = cache
+        .get(key)
+        .with_context(|| format!("map_with_cached_lookup: get {key}"))?;
+    if let Some(value) = cached.and_then(|bytes| serde_json::from_slice(&bytes).ok()) {
+        return Ok(value);
+    }
+    let value = loader(key).with_context(|| format!("map_with_cached_lookup: load {key}"))?;
+    let bytes = serde_json::to_vec(&value)
+        .with_context(|| format!("map_with_cached_lookup: serialize value of {key}"))?;
+    cache
+        .set(key, &bytes, ttl_secs)
+        .with_context(|| format!("map_with_cached_lookup: set {key}"))?;
+    Ok(value)
+}
@@ -0,0 +1,362 @@
+//! Columnar execution over Arrow record batches (feature `io-parquet`).
+//!
+//! [`read_parquet_streaming`](crate::read_parquet_streaming) deserializes every row into
+//! a struct through `serde_arrow`, and [`write_parquet`](PCollection::write_parquet)
+//! serializes them back. For numeric-heavy pipelines that round trip can cost more than
+//! the work itself. The helpers here keep the data in Arrow form instead:
+//!
+//! - [`read_parquet_batches`] - read a Parquet file as a `PCollection<ArrowBatch>`,
+//!   one partition per row-group shard, without deserializing rows
+//! - [`PCollection::map_batches_arrow`] - apply a function to each
+//!   [`RecordBatch`], e.g. with `arrow::compute` kernels
+//! - [`PCollection::into_rows`] - convert batches to typed rows, for stages that need them
+//! - [`PCollection::into_arrow_batches`] - convert typed rows back to batches
+//! - [`PCollection::write_parquet_batches`] - write batches to a Parquet file as they are
+//!
+//! Batch stages are ordinary stateless stages, so the planner fuses them with their
+//! neighbours like any other map. Rows are only materialized where a pipeline asks for
+//! them with `into_rows`; `into_rows` and `into_arrow_batches` convert one whole
+//! partition at a time.
+//!
+//! ### Example
+//! ```no_run
+//! use ironbeam::*;
+//! use anyhow::Result;
+//! use arrow::array::Float64Array;
+//! use arrow::compute::filter_record_batch;
+//! use arrow::compute::kernels::cmp::gt;
+//!
+//! # fn main() -> Result<()> {
+//! #[derive(serde::Serialize, serde::Deserialize, Clone)]
+//! struct Reading { sensor: String, value: f64 }
+//!
+//! let p = Pipeline::default();
+//! let high = read_parquet_batches(&p, "data/readings.parquet", 1)?
+//!     .map_batches_arrow(|batch| {
+//!         let values = batch.column_by_name("value").unwrap();
+//!         let mask = gt(values, &Float64Array::new_scalar(100.0))?;
+//!         Ok(filter_record_batch(batch, &mask)?)
+//!     });
+//!
+//! // Stay columnar all the way to the output...
+//! high.clone().write_parquet_batches("data/high.parquet")?;
+//! // ...or switch to rows for the stages that need them.
+//! let sensors = high.into_rows::<Reading>().map(|r: &Reading| r.sensor.clone());
+//! # Ok(()) }
+//! ```
+
+use crate::io::atomic::write_atomically;
+use crate::io::parquet::{ParquetShards, ParquetWriteOptions, build_parquet_shards};
+use crate::node::{DynOp, Node};
+use crate::type_token::{Partition, TypeTag, VecOps};
+use crate::{Element, PCollection, Pipeline};
+use anyhow::{Context, Result, bail};
+use arrow::datatypes::FieldRef;
+use arrow::ipc::reader::StreamReader;
+use arrow::ipc::writer::StreamWriter;
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use parquet::arrow::arrow_writer::ArrowWriter;
+use serde::de::{DeserializeOwned, Error as _};
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_arrow::schema::{SchemaLike, TracingOptions};
+use serde_arrow::{from_record_batch, to_record_batch};
+use std::any::Any;
+use std::fs::File;
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::path::Path;
+use std::sync::Arc;
+
+/// One Arrow [`RecordBatch`] as a pipeline element.
+///
+/// A newtype so batches can cross shuffles, spills, and the cluster wire: it serializes
+/// as an Arrow IPC stream holding the single batch.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArrowBatch(pub RecordBatch);
+
+impl ArrowBatch {
+    /// The wrapped batch.
+    #[must_use]
+    pub fn into_inner(self) -> RecordBatch {
+        self.0
+    }
+}
+
+impl From<RecordBatch> for ArrowBatch {
+    fn from(batch: RecordBatch) -> Self {
+        Self(batch)
+    }
+}
+
+impl Deref for ArrowBatch {
+    type Target = RecordBatch;
+
+    fn deref(&self) -> &RecordBatch {
+        &self.0
+    }
+}
+
+impl Serialize for ArrowBatch {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut buf = Vec::new();
+        let mut writer =
+            StreamWriter::try_new(&mut buf, &self.0.schema()).map_err(S::Error::custom)?;
+        writer.write(&self.0).map_err(S::Error::custom)?;
+        writer.finish().map_err(S::Error::custom)?;
+        drop(writer);
+        buf.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for ArrowBatch {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let buf = Vec::<u8>::deserialize(deserializer)?;
+        let mut reader = StreamReader::try_new(buf.as_slice(), None).map_err(D::Error::custom)?;
+        match reader.next() {
+            Some(batch) => Ok(Self(batch.map_err(D::Error::custom)?)),
+            None => Err(D::Error::custom("Arrow IPC stream holds no batch")),
+        }
+    }
+}
+
+/// Read the row groups `[start_group, end_group)` of `src` as Arrow batches.
+fn read_batch_range(
+    src: &ParquetShards,
+    start_group: usize,
+    end_group: usize,
+) -> Result<Vec<ArrowBatch>> {
+    let f = File::open(&src.path).with_context(|| format!("open {}", src.path.display()))?;
+    let reader = ParquetRecordBatchReaderBuilder::try_new(f)
+        .context("open ParquetRecordBatchReader")?
+        .with_row_groups((start_group..end_group).collect())
+        .build()
+        .context("build row-group reader")?;
+    reader
+        .map(|batch| batch.map(ArrowBatch).context("read batch"))
+        .collect()
+}
+
+/// `VecOps` over [`ParquetShards`] that yields each shard as its `Vec<ArrowBatch>`.
+///
+/// `len` reports the row count: the number of batches is not known until the file is
+/// read, and rows are the better size hint for partitioning anyway.
+struct ParquetBatchVecOps;
+
+impl VecOps for ParquetBatchVecOps {
+    fn len(&self, data: &dyn Any) -> Option<usize> {
+        let s = data.downcast_ref::<ParquetShards>()?;
+        usize::try_from(s.total_rows).ok()
+    }
+
+    fn split(&self, data: &dyn Any, _n: usize) -> Option<Vec<Partition>> {
+        let s = data.downcast_ref::<ParquetShards>()?;
+        let mut parts: Vec<Partition> = Vec::with_capacity(s.group_ranges.len());
+        for &(start, end) in &s.group_ranges {
+            parts.push(Box::new(read_batch_range(s, start, end).ok()?) as Partition);
+        }
+        Some(parts)
+    }
+
+    fn clone_any(&self, data: &dyn Any) -> Option<Partition> {
+        let s = data.downcast_ref::<ParquetShards>()?;
+        let end = s.group_ranges.last().map_or(0, |&(_, e)| e);
+        Some(Box::new(read_batch_range(s, 0, end).ok()?) as Partition)
+    }
+}
+
+/// Read a Parquet file as a streaming source of Arrow batches.
+///
+/// Shards the file by row groups like
+/// [`read_parquet_streaming`](crate::read_parquet_streaming), but each partition holds
+/// the shard's [`RecordBatch`]es as decoded by the Parquet reader, with no conversion to
+/// rows. Note that the element count of the collection is the number of batches, not
+/// rows.
+///
+/// - `groups_per_shard`: how many row groups each shard/partition should read (minimum 1).
+///
+/// # Errors
+/// Returns an error if the file cannot be opened or its metadata cannot be read.
+pub fn read_parquet_batches(
+    p: &Pipeline,
+    path: impl AsRef<Path>,
+    groups_per_shard: usize,
+) -> Result<PCollection<ArrowBatch>> {
+    let location = path.as_ref().display().to_string();
+    let shards = build_parquet_shards(path, groups_per_shard)?;
+    let id = p.insert_node(Node::Source {
+        payload: Arc::new(shards),
+        vec_ops: Arc::new(ParquetBatchVecOps),
+        elem_tag: TypeTag::of::<ArrowBatch>(),
+    });
+    p.set_coder::<ArrowBatch>(id);
+    p.record_input(id, "parquet", location);
+    Ok(PCollection {
+        pipeline: p.clone(),
+        id,
+        _t: PhantomData,
+    })
+}
+
+/// Deserializes every batch of a `Vec<ArrowBatch>` partition into one `Vec<T>`.
+struct IntoRowsOp<T>(PhantomData<T>);
+
+impl<T: Element + DeserializeOwned> DynOp for IntoRowsOp<T> {
+    fn apply(&self, input: Partition) -> Partition {
+        let batches = *input
+            .downcast::<Vec<ArrowBatch>>()
+            .expect("IntoRowsOp input type");
+        let mut out: Vec<T> = Vec::new();
+        for batch in &batches {
+            let mut rows: Vec<T> = from_record_batch(&batch.0)
+                .unwrap_or_else(|e| panic!("into_rows: deserialize RecordBatch rows: {e}"));
+            out.append(&mut rows);
+        }
+        Box::new(out) as Partition
+    }
+}
+
+/// Serializes a whole `Vec<T>` partition into a single `ArrowBatch`.
+struct IntoBatchesOp<T> {
+    fields: Vec<FieldRef>,
+    _t: PhantomData<T>,
+}
+
+impl<T: Element + Serialize> DynOp for IntoBatchesOp<T> {
+    fn apply(&self, input: Partition) -> Partition {
+        let rows = *input
+            .downcast::<Vec<T>>()
+            .expect("IntoBatchesOp input type");
+        let out: Vec<ArrowBatch> = if rows.is_empty() {
+            Vec::new()
+        } else {
+            let batch = to_record_batch(&self.fields, &rows)
+                .unwrap_or_else(|e| panic!("into_arrow_batches: convert rows: {e}"));
+            vec![ArrowBatch(batch)]
+        };
+        Box::new(out) as Partition
+    }
+}
+
+impl<T: Element> PCollection<T> {
+    /// Append one stateless `op` producing `O` and return its collection.
+    fn push_stateless<O: Element>(self, op: Arc<dyn DynOp>) -> PCollection<O> {
+        let id = self.pipeline.insert_node(Node::Stateless(vec![op]));
+        self.pipeline.connect(self.id, id);
+        self.pipeline.set_coder::<O>(id);
+        PCollection {
+            pipeline: self.pipeline,
+            id,
+            _t: PhantomData,
+        }
+    }
+}
+
+impl PCollection<ArrowBatch> {
+    /// Apply `f` to every [`RecordBatch`] without converting it to rows.
+    ///
+    /// `f` may change the schema and the row count (filters, projections, computed
+    /// columns). An error from `f` fails the run, like a panic in any other stage.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ironbeam::*;
+    /// # use anyhow::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let ids_only = read_parquet_batches(&p, "data/in.parquet", 1)?
+    ///     .map_batches_arrow(|batch| Ok(batch.project(&[0])?));
+    /// # Ok(()) }
+    /// ```
+    #[must_use]
+    pub fn map_batches_arrow<F>(self, f: F) -> Self
+    where
+        F: 'static + Send + Sync + Fn(&RecordBatch) -> Result<RecordBatch>,
+    {
+        self.map(move |batch: &ArrowBatch| match f(&batch.0) {
+            Ok(out) => ArrowBatch(out),
+            Err(e) => panic!("map_batches_arrow: {e:#}"),
+        })
+    }
+
+    /// Convert the batches to typed rows with `serde_arrow`.
+    ///
+    /// Each partition is converted as a whole, and row order is preserved.
+    #[must_use]
+    pub fn into_rows<T: Element + DeserializeOwned>(self) -> PCollection<T> {
+        self.push_stateless(Arc::new(IntoRowsOp::<T>(PhantomData)))
+    }
+
+    /// Total number of rows across all batches, computed on the batch metadata.
+    ///
+    /// # Errors
+    /// Returns an error if the pipeline fails to execute.
+    pub fn count_rows(self) -> Result<usize> {
+        let batches = self.collect_seq()?;
+        Ok(batches.iter().map(|b| b.num_rows()).sum())
+    }
+
+    /// Execute the pipeline and write the batches to a **single Parquet file**.
+    ///
+    /// Batches are collected sequentially (deterministic order) and written as they
+    /// are; the file schema is the schema of the first batch. Returns the number of rows
+    /// written.
+    ///
+    /// # Errors
+    /// Returns an error if the pipeline fails, the collection is empty (there is no
+    /// schema to write), the batches have different schemas, or writing fails.
+    pub fn write_parquet_batches(self, path: impl AsRef<Path>) -> Result<usize> {
+        self.write_parquet_batches_with(path, &ParquetWriteOptions::default())
+    }
+
+    /// Write the batches to a single Parquet file as
+    /// [`write_parquet_batches`](Self::write_parquet_batches) does, with the row groups,
+    /// compression and encodings set by `opts`.
+    ///
+    /// # Errors
+    /// Returns an error if `opts` is invalid, or for the reasons of
+    /// [`write_parquet_batches`](Self::write_parquet_batches).
+    pub fn write_parquet_batches_with(
+        self,
+        path: impl AsRef<Path>,
+        opts: &ParquetWriteOptions,
+    ) -> Result<usize> {
+        let path = path.as_ref();
+        let props = opts.writer_properties()?;
+        let batches = self.collect_seq()?;
+        let Some(first) = batches.first() else {
+            bail!("write_parquet_batches: no batches, so no schema to write");
+        };
+        write_atomically(path, |file| {
+            let mut writer = ArrowWriter::try_new(file, first.schema(), Some(props))
+                .context("create ArrowWriter")?;
+            let mut rows = 0;
+            for batch in &batches {
+                writer.write(batch).context("write batch to parquet")?;
+                rows += batch.num_rows();
+            }
+            writer.close().context("close ArrowWriter")?;
+            Ok(rows)
+        })
+    }
+}
+
+impl<T: Element + Serialize + DeserializeOwned> PCollection<T> {
+    /// Convert typed rows to Arrow batches, one batch per partition.
+    ///
+    /// The schema is inferred from `T` with `serde_arrow`, as for
+    /// [`write_parquet`](PCollection::write_parquet). Empty partitions produce no batch.
+    ///
+    /// # Errors
+    /// Returns an error if no Arrow schema can be inferred for `T`.
+    pub fn into_arrow_batches(self) -> Result<PCollection<ArrowBatch>> {
+        let fields = Vec::<FieldRef>::from_type::<T>(TracingOptions::default())
+            .context("infer Arrow schema from type T")?;
+        Ok(self.push_stateless(Arc::new(IntoBatchesOp::<T> {
+            fields,
+            _t: PhantomData,
+        })))
+    }
+}
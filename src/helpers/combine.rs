@@ -8,6 +8,21 @@
 //!   via `add_input`.
 //!
 //! Both forms ultimately produce a `(K, O)` stream by aggregating values per key.
+//!
+//! ## Automatic lifting
+//! A `group_by_key` shuffles every value before anything is aggregated. The planner
+//! rewrites the following patterns into per-partition partial aggregation, so only one
+//! partial result per key and partition crosses the barrier:
+//!
+//! - `group_by_key().combine_values_lifted(c)` -- always.
+//! - `group_by_key().combine_values(c)` -- when `c` reports
+//!   [`CombineFn::accepts_partial_groups`].
+//! - `group_by_key().map_values(f)` -- when the result is marked with
+//!   [`PCollection::with_partial_merge`], declaring how two partial results for a key
+//!   combine.
+//!
+//! The rewrites are reported in
+//! [`ExecutionExplanation::optimizations`](crate::planner::ExecutionExplanation::optimizations).
 
 use crate::node::Node;
 use crate::{CombineFn, Element, PCollection, Partition};
@@ -139,12 +154,19 @@ impl<K: Element + Eq + Hash, V: Element> PCollection<(K, V)> {
             })
         };
 
+        let splits_groups = comb.accepts_partial_groups();
         let id = self.pipeline.insert_node(Node::CombineValues {
             local_pairs: local, // classic local for Vec<(K, V)>
             local_groups: None, // no lifted local; not grouped input
             merge,              // shared merge logic
         });
         self.pipeline.connect(self.id, id);
+        if splits_groups {
+            self.pipeline.set_group_lift(id, GroupLift::Combine);
+        }
+        // Like the pre-GBK edge, the combine input is keyed; upgrade the
+        // predecessor to a KV coder so a wire backend can route pairs by key.
+        self.pipeline.set_kv_coder::<K, V>(self.id);
         self.pipeline.set_coder::<(K, O)>(id);
         PCollection {
             pipeline: self.pipeline,
@@ -152,6 +174,72 @@ impl<K: Element + Eq + Hash, V: Element> PCollection<(K, V)> {
             _t: PhantomData,
         }
     }
+
+    /// Declare how two partial results for the same key combine, so the planner can lift
+    /// the `group_by_key().map_values(f)` that produced this collection.
+    ///
+    /// `merge` must satisfy `f(a ++ b) == merge(f(a), f(b))` for any split of a group,
+    /// in any order: `f` is then computed on each partition's share of every group and
+    /// only the partial results are shuffled and merged. Sums, counts, minima and maxima
+    /// qualify; means and medians do not.
+    ///
+    /// The hint has no effect unless this collection is a `map_values` applied directly
+    /// to `group_by_key` output; the collection is returned unchanged either way.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ironbeam::*;
+    ///
+    /// let p = Pipeline::default();
+    /// let kv = from_vec(&p, vec![("a".to_string(), 1u64), ("a".to_string(), 2), ("b".to_string(), 3)]);
+    ///
+    /// let totals = kv
+    ///     .group_by_key()
+    ///     .map_values(|vs: &Vec<u64>| vs.iter().sum::<u64>())
+    ///     .with_partial_merge(|a, b| a + b);
+    /// assert_eq!(
+    ///     totals.collect_seq_sorted()?,
+    ///     vec![("a".to_string(), 3), ("b".to_string(), 3)]
+    /// );
+    /// # use anyhow::Ok; Ok::<()>(())
+    /// ```
+    #[must_use]
+    pub fn with_partial_merge<G>(self, merge: G) -> Self
+    where
+        G: Fn(V, V) -> V + Send + Sync + 'static,
+    {
+        let merge_parts = Arc::new(move |parts: Vec<Partition>| -> Partition {
+            let mut merged: HashMap<K, V> = HashMap::new();
+            for p in parts {
+                let kv = *p
+                    .downcast::<Vec<(K, V)>>()
+                    .expect("partial merge: expected Vec<(K, V)>");
+                for (k, v) in kv {
+                    let v = match merged.remove(&k) {
+                        Some(prev) => merge(prev, v),
+                        None => v,
+                    };
+                    merged.insert(k, v);
+                }
+            }
+            Box::new(merged.into_iter().collect::<Vec<(K, V)>>()) as Partition
+        });
+        self.pipeline
+            .set_group_lift(self.id, GroupLift::MergeOutputs(merge_parts));
+        self
+    }
+}
+
+/// How the planner may lift a node that reads `group_by_key` output into per-partition
+/// partial aggregation. Recorded per node on the pipeline.
+#[derive(Clone)]
+pub(crate) enum GroupLift {
+    /// A classic `combine_values` whose combiner
+    /// [accepts partial groups](CombineFn::accepts_partial_groups).
+    Combine,
+    /// A `map_values` whose per-partition outputs `Vec<(K, O)>` are merged by key with
+    /// this closure.
+    MergeOutputs(Arc<dyn Fn(Vec<Partition>) -> Partition + Send + Sync>),
 }
 
 impl<K, V> PCollection<(K, Vec<V>)>
@@ -11,13 +11,16 @@
 //! - [`PCollection::collect`] -- collects sequentially by default.
 //! - [`PCollection::collect_seq`] -- explicit sequential collection.
 //! - [`PCollection::collect_par`] -- parallel collection with configurable concurrency.
+//! - [`PCollection::collect_seq_result`] and [`PCollection::collect_par_result`] -- the
+//!   same, returning a [`PipelineResult`] with run metadata.
 //!
 //! These operations form the foundation of the dataflow API, similar to Apache Beam's
 //! elementwise transforms (`Map`, `Filter`, `FlatMap`).
 
 use crate::collection::{FilterOp, FlatMapOp, MapOp, TakeOp};
 use crate::node::{DynOp, Node};
-use crate::{Element, ExecMode, PCollection, Runner};
+use crate::runner::PipelineRunner;
+use crate::{DirectRunner, Element, ExecMode, PCollection, PipelineResult};
 use anyhow::Result;
 use std::marker::PhantomData;
 use std::sync::Arc;
@@ -218,13 +221,67 @@ impl<T: Element> PCollection<T> {
     ///
     /// Returns any errors in a [`Result`] container.
     pub fn collect_seq(self) -> Result<Vec<T>> {
-        Runner {
+        DirectRunner {
             mode: ExecMode::Sequential,
             ..Default::default()
         }
         .run_collect::<T>(&self.pipeline, self.id)
     }
 
+    /// Collect elements **sequentially**, streaming through stateless runs.
+    ///
+    /// Runs the pipeline in [`ExecMode::Streaming`]: like [`collect_seq`](Self::collect_seq),
+    /// but chained stateless transforms (`map`, `filter`, `flat_map`, `take`, ...) pull
+    /// elements through one at a time instead of materializing a full partition after each
+    /// step, which lowers peak memory for long stateless chains.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ironbeam::*;
+    ///
+    /// let p = Pipeline::default();
+    /// let out = from_vec(&p, (0..1_000u32).collect::<Vec<_>>())
+    ///     .map(|x| x * 2)
+    ///     .filter(|x| x % 3 == 0)
+    ///     .collect_streaming()
+    ///     .unwrap();
+    /// assert_eq!(out.len(), 334);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns any errors in a [`Result`] container.
+    pub fn collect_streaming(self) -> Result<Vec<T>> {
+        DirectRunner {
+            mode: ExecMode::Streaming,
+            ..Default::default()
+        }
+        .run_collect::<T>(&self.pipeline, self.id)
+    }
+
+    /// Collect elements by executing the pipeline on `runner`, which may be any
+    /// [`PipelineRunner`] backend.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ironbeam::*;
+    ///
+    /// let p = Pipeline::default();
+    /// let runner = DirectRunner {
+    ///     mode: ExecMode::Sequential,
+    ///     ..DirectRunner::default()
+    /// };
+    /// let out = from_vec(&p, vec![1, 2, 3]).collect_with(&runner).unwrap();
+    /// assert_eq!(out, vec![1, 2, 3]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns any error raised by the runner.
+    pub fn collect_with<R: PipelineRunner>(self, runner: &R) -> Result<Vec<T>> {
+        runner.run_collect::<T>(&self.pipeline, self.id)
+    }
+
     /// Collect elements **in parallel** using the specified number of threads and partitions.
     ///
     /// This executes the pipeline with [`ExecMode::Parallel`], splitting data across partitions
@@ -249,7 +306,7 @@ impl<T: Element> PCollection<T> {
     ///
     /// Errors are returned in a [`Result`] wrapper.
     pub fn collect_par(self, threads: Option<usize>, partitions: Option<usize>) -> Result<Vec<T>> {
-        Runner {
+        DirectRunner {
             mode: ExecMode::Parallel {
                 threads,
                 partitions,
@@ -258,4 +315,50 @@ impl<T: Element> PCollection<T> {
         }
         .run_collect::<T>(&self.pipeline, self.id)
     }
+
+    /// Like [`collect_seq`](Self::collect_seq), but returns a [`PipelineResult`] with the
+    /// run's wall time, per-stage element counts, and planner warnings alongside the data.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ironbeam::*;
+    ///
+    /// let p = Pipeline::default();
+    /// let result = from_vec(&p, vec![1, 2, 3]).collect_seq_result().unwrap();
+    /// assert_eq!(result.data, vec![1, 2, 3]);
+    /// println!("took {:?}", result.wall_time);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns any errors in a [`Result`] container.
+    pub fn collect_seq_result(self) -> Result<PipelineResult<T>> {
+        DirectRunner {
+            mode: ExecMode::Sequential,
+            ..Default::default()
+        }
+        .run_with_result::<T>(&self.pipeline, self.id)
+    }
+
+    /// Like [`collect_par`](Self::collect_par), but returns a [`PipelineResult`] with the
+    /// run's wall time, partition count, per-stage element counts, and planner warnings
+    /// alongside the data.
+    ///
+    /// # Errors
+    ///
+    /// Errors are returned in a [`Result`] wrapper.
+    pub fn collect_par_result(
+        self,
+        threads: Option<usize>,
+        partitions: Option<usize>,
+    ) -> Result<PipelineResult<T>> {
+        DirectRunner {
+            mode: ExecMode::Parallel {
+                threads,
+                partitions,
+            },
+            ..Default::default()
+        }
+        .run_with_result::<T>(&self.pipeline, self.id)
+    }
 }
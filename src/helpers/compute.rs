@@ -0,0 +1,345 @@
+//! Delegating per-element work to a serverless function through [`ComputeIO`].
+//!
+//! [`map_via_compute`](PCollection::map_via_compute) serializes every element, invokes
+//! `function_name` with the bytes, and deserializes the function's output. Up to
+//! `concurrency` invocations run at once, and the output keeps the input order.
+//!
+//! A failed element does not fail the transform. Each element becomes a
+//! `Result<O, ComputeError>`, so the try machinery applies:
+//! [`collect_fail_fast`](PCollection::collect_fail_fast) stops at the first error.
+//! [`map_via_compute_catching`](PCollection::map_via_compute_catching) instead splits
+//! the failures off as [`DeadLetter`]s. An element fails when:
+//!
+//! - it does not serialize,
+//! - the invocation itself fails (unknown function, permissions, network),
+//! - the function answers with a status code outside `200..300`, or
+//! - the function's output does not deserialize.
+//!
+//! To process elements per batch rather than one at a time, batch them first with
+//! [`batch_elements`](PCollection::batch_elements) and serialize each `Vec<T>`.
+//!
+//! Like [`predict_with`](PCollection::predict_with), this takes the service by
+//! reference, so it executes the pipeline up to this point when called and returns the
+//! results as a new collection in the same pipeline.
+//!
+//! [`map_via_compute_shared`](PCollection::map_via_compute_shared) and
+//! [`map_via_compute_catching_shared`](PCollection::map_via_compute_catching_shared)
+//! take the service as an `Arc` and run as a lazy step of the pipeline instead. Every
+//! partition is invoked on the worker running it, through one pool of `concurrency`
+//! threads shared by all partitions.
+//!
+//! ```
+//! use ironbeam::io::cloud::FakeComputeIO;
+//! use ironbeam::*;
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let compute = FakeComputeIO::new();
+//! compute.register_function("upper", <[u8]>::to_ascii_uppercase);
+//!
+//! let p = Pipeline::default();
+//! let words = from_vec(&p, vec!["a".to_string(), "bc".to_string()]);
+//! let upper = words
+//!     .map_via_compute(
+//!         &compute,
+//!         "upper",
+//!         |w: &String| Ok(w.clone().into_bytes()),
+//!         |out| Ok(String::from_utf8(out.to_vec())?),
+//!         4,
+//!     )?
+//!     .collect_fail_fast()?;
+//! assert_eq!(upper, vec!["A".to_string(), "BC".to_string()]);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::helpers::dead_letter::DeadLetter;
+use crate::io::cloud::traits::ComputeIO;
+use crate::{Element, PCollection, Pipeline, from_vec};
+use anyhow::{Context, Result, ensure};
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::fmt;
+use std::sync::Arc;
+
+/// Why [`map_via_compute`](PCollection::map_via_compute) could not produce an output
+/// for an element.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "coders", derive(serde::Serialize, serde::Deserialize))]
+pub struct ComputeError {
+    /// The status code the function answered with, or `None` if the element failed to
+    /// serialize or the invocation did not complete.
+    pub status_code: Option<u16>,
+    /// What went wrong. For an error status this is the function's output, read as
+    /// UTF-8.
+    pub message: String,
+    /// The function's logs, if the service returned any.
+    pub logs: Option<String>,
+}
+
+impl ComputeError {
+    const fn new(status_code: Option<u16>, message: String, logs: Option<String>) -> Self {
+        Self {
+            status_code,
+            message,
+            logs,
+        }
+    }
+}
+
+impl fmt::Display for ComputeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.status_code {
+            Some(code) => write!(f, "status {code}: {}", self.message),
+            None => f.write_str(&self.message),
+        }
+    }
+}
+
+impl std::error::Error for ComputeError {}
+
+impl<T: Element> PCollection<T> {
+    /// Execute the collection and map every element through the function
+    /// `function_name`, running up to `concurrency` invocations at once.
+    ///
+    /// The output has one `Result` per input element, in the same order. See the
+    /// [module documentation](crate::helpers::compute) for what counts as a failure.
+    ///
+    /// # Errors
+    /// Returns an error if `concurrency` is zero, the pipeline fails, or the thread
+    /// pool cannot be started. Per-element failures are returned as [`ComputeError`]s.
+    pub fn map_via_compute<O, S, D>(
+        self,
+        compute: &dyn ComputeIO,
+        function_name: &str,
+        serialize: S,
+        deserialize: D,
+        concurrency: usize,
+    ) -> Result<PCollection<Result<O, ComputeError>>>
+    where
+        O: Element,
+        S: Fn(&T) -> Result<Vec<u8>> + Sync,
+        D: Fn(&[u8]) -> Result<O> + Sync,
+    {
+        let (pipeline, _, results) = self.invoke_compute(
+            compute,
+            function_name,
+            &serialize,
+            &deserialize,
+            concurrency,
+        )?;
+        Ok(from_vec(&pipeline, results))
+    }
+
+    /// Like [`map_via_compute`](PCollection::map_via_compute), but returns the outputs
+    /// and the failed elements as two collections. Each [`DeadLetter`] holds the input
+    /// element and its [`ComputeError`]'s message.
+    ///
+    /// # Errors
+    /// See [`map_via_compute`](PCollection::map_via_compute).
+    pub fn map_via_compute_catching<O, S, D>(
+        self,
+        compute: &dyn ComputeIO,
+        function_name: &str,
+        serialize: S,
+        deserialize: D,
+        concurrency: usize,
+    ) -> Result<(PCollection<O>, PCollection<DeadLetter<T>>)>
+    where
+        O: Element,
+        S: Fn(&T) -> Result<Vec<u8>> + Sync,
+        D: Fn(&[u8]) -> Result<O> + Sync,
+    {
+        let (pipeline, elements, results) = self.invoke_compute(
+            compute,
+            function_name,
+            &serialize,
+            &deserialize,
+            concurrency,
+        )?;
+        let mut good = Vec::new();
+        let mut dead = Vec::new();
+        for (element, result) in elements.into_iter().zip(results) {
+            match result {
+                Ok(out) => good.push(out),
+                Err(err) => dead.push(DeadLetter::new(element, err.to_string())),
+            }
+        }
+        Ok((from_vec(&pipeline, good), from_vec(&pipeline, dead)))
+    }
+
+    /// Map every element through the function `function_name` as a lazy step of the
+    /// pipeline, running up to `concurrency` invocations at once.
+    ///
+    /// Nothing runs until the pipeline does. Each partition invokes the function for its
+    /// elements on the worker running it, and all partitions share one pool of
+    /// `concurrency` threads. The output has one `Result` per input element, in the
+    /// order of the elements within each partition.
+    ///
+    /// # Errors
+    /// Returns an error if `concurrency` is zero or the thread pool cannot be started.
+    /// Per-element failures are returned as [`ComputeError`]s.
+    pub fn map_via_compute_shared<O, S, D>(
+        self,
+        compute: Arc<dyn ComputeIO>,
+        function_name: &str,
+        serialize: S,
+        deserialize: D,
+        concurrency: usize,
+    ) -> Result<PCollection<Result<O, ComputeError>>>
+    where
+        O: Element,
+        S: 'static + Send + Sync + Fn(&T) -> Result<Vec<u8>>,
+        D: 'static + Send + Sync + Fn(&[u8]) -> Result<O>,
+    {
+        let pool = compute_pool(concurrency)?;
+        let function_name = function_name.to_string();
+        Ok(self.try_map_partitions(move |elements| {
+            Ok(invoke_all(
+                &pool,
+                compute.as_ref(),
+                &function_name,
+                &serialize,
+                &deserialize,
+                &elements,
+            ))
+        }))
+    }
+
+    /// Like [`map_via_compute_shared`](PCollection::map_via_compute_shared), but
+    /// returns the outputs and the failed elements as two collections, as
+    /// [`map_via_compute_catching`](PCollection::map_via_compute_catching) does.
+    ///
+    /// Both collections read one [cached](PCollection::cache) pass of the step, so each
+    /// element is invoked once however many of them are run.
+    ///
+    /// # Errors
+    /// See [`map_via_compute_shared`](PCollection::map_via_compute_shared).
+    pub fn map_via_compute_catching_shared<O, S, D>(
+        self,
+        compute: Arc<dyn ComputeIO>,
+        function_name: &str,
+        serialize: S,
+        deserialize: D,
+        concurrency: usize,
+    ) -> Result<(PCollection<O>, PCollection<DeadLetter<T>>)>
+    where
+        O: Element,
+        S: 'static + Send + Sync + Fn(&T) -> Result<Vec<u8>>,
+        D: 'static + Send + Sync + Fn(&[u8]) -> Result<O>,
+    {
+        let pool = compute_pool(concurrency)?;
+        let function_name = function_name.to_string();
+        let invoked: PCollection<(T, Result<O, ComputeError>)> =
+            self.try_map_partitions(move |elements| {
+                let results = invoke_all(
+                    &pool,
+                    compute.as_ref(),
+                    &function_name,
+                    &serialize,
+                    &deserialize,
+                    &elements,
+                );
+                Ok(elements.into_iter().zip(results).collect())
+            });
+        Ok(
+            invoked.flat_map_multi(|(element, result), out| match result {
+                Ok(o) => out.emit(o.clone()),
+                Err(err) => out.emit_side(DeadLetter::new(element.clone(), err.to_string())),
+            }),
+        )
+    }
+
+    /// Collect the elements and invoke the function for each of them on a pool of
+    /// `concurrency` threads.
+    #[allow(clippy::type_complexity)]
+    fn invoke_compute<O, S, D>(
+        self,
+        compute: &dyn ComputeIO,
+        function_name: &str,
+        serialize: &S,
+        deserialize: &D,
+        concurrency: usize,
+    ) -> Result<(Pipeline, Vec<T>, Vec<Result<O, ComputeError>>)>
+    where
+        O: Element,
+        S: Fn(&T) -> Result<Vec<u8>> + Sync,
+        D: Fn(&[u8]) -> Result<O> + Sync,
+    {
+        let pool = compute_pool(concurrency)?;
+        let pipeline = self.pipeline.clone();
+        let elements = self.collect_seq()?;
+        let results = invoke_all(
+            &pool,
+            compute,
+            function_name,
+            serialize,
+            deserialize,
+            &elements,
+        );
+        Ok((pipeline, elements, results))
+    }
+}
+
+/// Start the pool of `concurrency` threads the invocations run on.
+fn compute_pool(concurrency: usize) -> Result<Arc<ThreadPool>> {
+    ensure!(
+        concurrency > 0,
+        "map_via_compute: concurrency must be positive"
+    );
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(concurrency)
+        .thread_name(|i| format!("ironbeam-compute-{i}"))
+        .build()
+        .context("map_via_compute: failed to start the thread pool")?;
+    Ok(Arc::new(pool))
+}
+
+/// Invoke the function for each of `elements` on `pool`, keeping their order.
+fn invoke_all<T, O, S, D>(
+    pool: &ThreadPool,
+    compute: &dyn ComputeIO,
+    function_name: &str,
+    serialize: &S,
+    deserialize: &D,
+    elements: &[T],
+) -> Vec<Result<O, ComputeError>>
+where
+    T: Sync,
+    O: Send,
+    S: Fn(&T) -> Result<Vec<u8>> + Sync,
+    D: Fn(&[u8]) -> Result<O> + Sync,
+{
+    pool.install(|| {
+        elements
+            .par_iter()
+            .with_max_len(1)
+            .map(|element| invoke_one(compute, function_name, serialize, deserialize, element))
+            .collect()
+    })
+}
+
+/// Invoke the function for one element and map the outcome to a `Result`.
+fn invoke_one<T, O, S, D>(
+    compute: &dyn ComputeIO,
+    function_name: &str,
+    serialize: &S,
+    deserialize: &D,
+    element: &T,
+) -> Result<O, ComputeError>
+where
+    S: Fn(&T) -> Result<Vec<u8>>,
+    D: Fn(&[u8]) -> Result<O>,
+{
+    let payload = serialize(element)
+        .map_err(|e| ComputeError::new(None, format!("serialize: {e:#}"), None))?;
+    let result = compute
+        .invoke(function_name, &payload)
+        .map_err(|e| ComputeError::new(None, format!("invoke {function_name}: {e}"), None))?;
+    let status = Some(result.status_code);
+    if !(200..300).contains(&result.status_code) {
+        let message = String::from_utf8_lossy(&result.output).into_owned();
+        return Err(ComputeError::new(status, message, result.logs));
+    }
+    deserialize(&result.output)
+        .map_err(|e| ComputeError::new(status, format!("deserialize: {e:#}"), result.logs))
+}
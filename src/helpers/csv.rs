@@ -5,12 +5,25 @@
 //!
 //! - **Vector I/O** -- read the whole file into memory or write an in-memory collection:
 //!   - [`read_csv`] -> `PCollection<T>`
+//!   - [`read_csv_timestamped`] -> `PCollection<Timestamped<T>>`, stamped by an [`EventTime`]
 //!   - [`PCollection::write_csv`](PCollection::write_csv) / [`PCollection::write_csv_par`](PCollection::write_csv_par)
+//!   - [`PCollection::write_csv_with`](PCollection::write_csv_with) takes [`WriteOptions`]
+//!     to force a compression codec and level
 //!
 //! - **Streaming I/O** -- build a source that shards a CSV file by row count and
 //!   parses each shard lazily in the runner:
 //!   - [`read_csv_streaming`] -> `PCollection<T>`
 //!
+//! - **Configured reads** -- [`read_csv_with`] and [`read_csv_streaming_with`] take a
+//!   [`CsvReadOptions`] (delimiter, quote and comment characters, flexible rows,
+//!   trimming, Latin-1 input, malformed-row policy) and return the parsed rows along
+//!   with a collection of [`DeadLetter`] rows that failed to parse.
+//!
+//! - **Glob reads** -- before reading a glob's files, [`read_csv`] and [`read_csv_with`]
+//!   check that they all share the first file's header. Drift (added, missing or
+//!   reordered columns) fails the read unless the options' [`SchemaDriftPolicy`]
+//!   aligns columns by name or ignores it.
+//!
 //! All functions are serde-driven: your record type `T` should `#[derive(serde::Deserialize)]`
 //! for reads and `#[derive(serde::Serialize)]` for writes.
 //!
@@ -55,20 +68,116 @@
 //! let out = stream.collect_seq()?; // materialize after transforms
 //! # Ok(()) }
 //! ```
+//!
+//! Semicolon-separated Latin-1 export, quarantining bad rows:
+//! ```no_run
+//! use ironbeam::*;
+//! use ironbeam::io::csv::{CsvEncoding, CsvReadOptions, MalformedRowPolicy};
+//! use serde::{Deserialize, Serialize};
+//! use anyhow::{Result, Ok};
+//!
+//! #[derive(Clone, Serialize, Deserialize)]
+//! struct Row { k: String, v: u64 }
+//!
+//! # fn main() -> Result<()> {
+//! let p = Pipeline::default();
+//! let opts = CsvReadOptions::new()
+//!     .with_delimiter(b';')
+//!     .with_encoding(CsvEncoding::Latin1)
+//!     .with_malformed_policy(MalformedRowPolicy::DeadLetter);
+//! let (rows, bad) = read_csv_with::<Row>(&p, "export.csv", &opts)?;
+//! bad.write_jsonl("quarantine.jsonl")?;
+//! # Ok(()) }
+//! ```
 
-use crate::io::csv::{CsvShards, CsvVecOps, build_csv_shards, read_csv_vec, write_csv_vec};
+use crate::helpers::DeadLetter;
+use crate::helpers::file_sink::EncodedPart;
+use crate::helpers::timestamped::EventTime;
+use crate::io::compression::{Compression, WriteOptions};
+use crate::io::csv::{
+    CsvDeadLetterVecOps, CsvReadOptions, CsvShards, CsvVecOps, MalformedRowPolicy,
+    build_csv_shards_with, encode_csv_part, read_csv_header, read_csv_vec_aligned,
+    read_csv_vec_with,
+};
+use crate::io::drift::{SchemaDriftPolicy, check_drift};
 use crate::io::glob::expand_glob;
 use crate::node::Node;
-use crate::type_token::TypeTag;
-use crate::{Element, PCollection, Pipeline, from_vec};
+use crate::type_token::{TypeTag, VecOps};
+use crate::{Element, PCollection, Pipeline, Timestamped, from_vec};
 use anyhow::{Context, Result, anyhow, bail};
 use regex::Regex;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
+use std::any::Any;
 use std::marker::PhantomData;
 use std::path::Path;
 use std::sync::Arc;
 
+/// Read a CSV file or glob pattern into memory, concatenating matches in sorted order.
+///
+/// With a header row, the headers of all matches are checked against the first
+/// file's under `opts.on_drift` before any row is read.
+fn read_csv_files<T: DeserializeOwned>(
+    path: &Path,
+    opts: &CsvReadOptions,
+) -> Result<(Vec<T>, Vec<DeadLetter<String>>)> {
+    let path_str = path
+        .to_str()
+        .ok_or_else(|| anyhow!("path contains invalid UTF-8"))?;
+
+    let glob_regex = Regex::new(r"[*?\[]").expect("valid glob regex");
+    if !glob_regex.is_match(path_str) {
+        return read_csv_vec_with(path, opts);
+    }
+    let files =
+        expand_glob(path_str).with_context(|| format!("expanding glob pattern: {path_str}"))?;
+    if files.is_empty() {
+        bail!("no files found matching pattern: {path_str}");
+    }
+
+    let columns = if opts.has_headers && opts.on_drift != SchemaDriftPolicy::Ignore {
+        let headers = files
+            .iter()
+            .map(|file| Ok((file.clone(), read_csv_header(file, opts)?)))
+            .collect::<Result<Vec<_>>>()?;
+        check_drift(&headers, opts.on_drift)?
+    } else {
+        None
+    };
+
+    let mut rows = Vec::new();
+    let mut malformed = Vec::new();
+    for file in files {
+        let (r, m) = match &columns {
+            Some(columns) => read_csv_vec_aligned(&file, opts, columns),
+            None => read_csv_vec_with(&file, opts),
+        }
+        .with_context(|| format!("reading {}", file.display()))?;
+        rows.extend(r);
+        malformed.extend(m);
+    }
+    Ok((rows, malformed))
+}
+
+/// Insert a source node over `shards` and wrap it as a typed collection.
+fn shard_source<T: Element>(
+    p: &Pipeline,
+    shards: CsvShards,
+    vec_ops: Arc<dyn VecOps>,
+) -> PCollection<T> {
+    let id = p.insert_node(Node::Source {
+        payload: Arc::new(shards) as Arc<dyn Any + Send + Sync>,
+        vec_ops,
+        elem_tag: TypeTag::of::<T>(),
+    });
+    p.set_coder::<T>(id);
+    PCollection {
+        pipeline: p.clone(),
+        id,
+        _t: PhantomData,
+    }
+}
+
 /// Read CSV file(s) into a typed `PCollection<T>` (vector mode).
 ///
 /// This eagerly parses the entire file(s) into memory using `serde` and returns
@@ -81,7 +190,9 @@ use std::sync::Arc;
 /// - A glob pattern: `"data/*.csv"` or `"data/year=2024/month=*/day=*/*.csv"`
 ///
 /// When a glob pattern is provided, all matching files are read and concatenated
-/// in sorted (lexicographic) order for deterministic results.
+/// in sorted (lexicographic) order for deterministic results. With headers, every
+/// file must have the first file's header; use [`read_csv_with`] with a
+/// [`SchemaDriftPolicy`] to align differing headers by name instead.
 ///
 /// *Enabled when the `io-csv` feature is on.*
 ///
@@ -91,7 +202,9 @@ use std::sync::Arc;
 /// - `has_headers`: Whether the input CSV includes a header row.
 ///
 /// # Errors
-/// An error is returned if the file cannot be opened or if any row fails to deserialize.
+/// An error is returned if the file cannot be opened, if any row fails to deserialize,
+/// or if the headers of a glob's files differ (a
+/// [`SchemaDriftError`](crate::io::drift::SchemaDriftError)).
 ///
 /// # Panics
 /// The code panics if the `path` parameter is invalid UTF-8 or the regex engine fails.
@@ -138,38 +251,116 @@ pub fn read_csv<T>(
 where
     T: Element + DeserializeOwned,
 {
-    let path_str = path
-        .as_ref()
-        .to_str()
-        .ok_or_else(|| anyhow!("path contains invalid UTF-8"))?;
-
-    let glob_regex = Regex::new(r"[*?\[]").expect("valid glob regex");
-    if glob_regex.is_match(path_str) {
-        let files =
-            expand_glob(path_str).with_context(|| format!("expanding glob pattern: {path_str}"))?;
+    let opts = CsvReadOptions::new().with_headers(has_headers);
+    let path = p.resolve_path(path.as_ref())?;
+    let (rows, _) = read_csv_files(&path, &opts)?;
+    Ok(from_vec(p, rows).record_input("csv", path.display()))
+}
 
-        if files.is_empty() {
-            bail!("no files found matching pattern: {path_str}");
-        }
+/// Read CSV file(s) like [`read_csv`], stamping each row with its event time as it
+/// is read, so windowing can follow without an
+/// [`attach_timestamps`](PCollection::attach_timestamps) step. With
+/// [`EventTime::Field`], a timestamp column that `T` stores as a string is parsed as
+/// a number of milliseconds.
+///
+/// # Errors
+/// Returns an error for the reasons of [`read_csv`], or if an [`EventTime::Field`]
+/// is missing from a row or does not hold a millisecond timestamp.
+///
+/// # Example
+/// ```no_run
+/// use ironbeam::*;
+/// use serde::{Serialize, Deserialize};
+/// use anyhow::Result;
+///
+/// #[derive(Clone, Serialize, Deserialize)]
+/// struct Reading { ts_ms: u64, sensor: String, value: f64 }
+///
+/// # fn main() -> Result<()> {
+/// let p = Pipeline::default();
+/// let readings =
+///     read_csv_timestamped::<Reading>(&p, "sensors/*.csv", true, &EventTime::field("ts_ms"))?;
+/// # Ok(()) }
+/// ```
+pub fn read_csv_timestamped<T>(
+    p: &Pipeline,
+    path: impl AsRef<Path>,
+    has_headers: bool,
+    time: &EventTime<T>,
+) -> Result<PCollection<Timestamped<T>>>
+where
+    T: Element + DeserializeOwned + Serialize,
+{
+    let opts = CsvReadOptions::new().with_headers(has_headers);
+    let path = p.resolve_path(path.as_ref())?;
+    let (rows, _) = read_csv_files(&path, &opts)?;
+    let rows = time
+        .stamp(rows)
+        .with_context(|| format!("reading {}", path.display()))?;
+    Ok(from_vec(p, rows).record_input("csv", path.display()))
+}
 
-        let mut all_data = Vec::new();
-        for file in files {
-            let data: Vec<T> = read_csv_vec(&file, has_headers)
-                .with_context(|| format!("reading {}", file.display()))?;
-            all_data.extend(data);
-        }
-        Ok(from_vec(p, all_data))
-    } else {
-        let v = read_csv_vec::<T>(path, has_headers)?;
-        Ok(from_vec(p, v))
-    }
+/// Read CSV file(s) into a typed `PCollection<T>` using [`CsvReadOptions`].
+///
+/// Like [`read_csv`] (including glob support), but with a configurable dialect and
+/// malformed-row handling. Returns `(rows, malformed)`: rows that failed to parse are
+/// collected as [`DeadLetter`]s holding the raw row text under
+/// [`MalformedRowPolicy::DeadLetter`], and `malformed` is empty under the other
+/// policies.
+///
+/// For a glob pattern with headers, `opts.on_drift` decides what happens when the
+/// files' headers differ (see [`io::drift`](crate::io::drift)): the read fails before
+/// any row is read, rows are aligned to the union of the headers by column name, or
+/// each file is read with its own header.
+///
+/// # Errors
+/// An error is returned if a file cannot be opened, no file matches a glob pattern,
+/// headers drift under [`SchemaDriftPolicy::Fail`], or, under
+/// [`MalformedRowPolicy::Fail`], any row is malformed.
+///
+/// # Example
+/// ```no_run
+/// use ironbeam::*;
+/// use ironbeam::io::csv::{CsvReadOptions, MalformedRowPolicy};
+/// use serde::{Serialize, Deserialize};
+/// use anyhow::{Result, Ok};
+///
+/// #[derive(Clone, Serialize, Deserialize)]
+/// struct Row { k: String, v: u64 }
+///
+/// # fn main() -> Result<()> {
+/// let p = Pipeline::default();
+/// let opts = CsvReadOptions::new()
+///     .with_delimiter(b'\t')
+///     .with_comment(Some(b'#'))
+///     .with_malformed_policy(MalformedRowPolicy::DeadLetter);
+/// let (rows, bad) = read_csv_with::<Row>(&p, "logs/*.tsv", &opts)?;
+/// # Ok(()) }
+/// ```
+pub fn read_csv_with<T>(
+    p: &Pipeline,
+    path: impl AsRef<Path>,
+    opts: &CsvReadOptions,
+) -> Result<(PCollection<T>, PCollection<DeadLetter<String>>)>
+where
+    T: Element + DeserializeOwned,
+{
+    let path = p.resolve_path(path.as_ref())?;
+    let (rows, malformed) = read_csv_files(&path, opts)?;
+    Ok((
+        from_vec(p, rows).record_input("csv", path.display()),
+        from_vec(p, malformed).record_input("csv", path.display()),
+    ))
 }
 
 impl<T: Element + Serialize> PCollection<T> {
-    /// Execute the pipeline sequentially and write the result as CSV (vector mode).
+    /// Execute the pipeline and write the result as CSV.
     ///
-    /// This collects the entire result into memory and writes it to `path` using `serde`.
-    /// For parallel writing, see [`PCollection::write_csv_par`] (requires `parallel-io`).
+    /// Each partition is serialized with `serde` by the runner as the last step of the
+    /// pipeline (see [`file_sink`](crate::helpers::file_sink)), and the parts are
+    /// concatenated in partition order into `path`, with the header row written once.
+    /// To choose the number of partitions, see [`PCollection::write_csv_par`] (requires
+    /// `parallel-io`).
     ///
     /// # Arguments
     /// - `path`: Destination path.
@@ -194,26 +385,58 @@ impl<T: Element + Serialize> PCollection<T> {
     /// # Ok(()) }
     /// ```
     pub fn write_csv(self, path: impl AsRef<Path>, has_headers: bool) -> Result<usize> {
-        let v = self.collect_seq()?;
-        write_csv_vec(path, has_headers, &v)
+        self.write_csv_with(path, has_headers, &WriteOptions::default())
+    }
+
+    /// Like [`PCollection::write_csv`], with explicit [`WriteOptions`] such as a
+    /// forced compression codec and level.
+    ///
+    /// # Errors
+    /// An error is returned if compression setup, writing, or serialization fails.
+    pub fn write_csv_with(
+        self,
+        path: impl AsRef<Path>,
+        has_headers: bool,
+        opts: &WriteOptions,
+    ) -> Result<usize> {
+        self.write_csv_parts(path.as_ref(), None, has_headers, *opts)
+    }
+
+    /// Write through a `write_csv` encode step over `partitions`.
+    fn write_csv_parts(
+        self,
+        path: &Path,
+        partitions: Option<usize>,
+        has_headers: bool,
+        opts: WriteOptions,
+    ) -> Result<usize> {
+        self.write_encoded("write_csv", path, partitions, opts, move |rows| {
+            let (header, bytes) = encode_csv_part(rows, has_headers)?;
+            Ok(EncodedPart {
+                rows: rows.len(),
+                header,
+                bytes,
+            })
+        })
     }
 }
 
 #[cfg_attr(docsrs, doc(cfg(feature = "parallel-io")))]
 #[cfg(feature = "parallel-io")]
 impl<T: Element + Serialize> PCollection<T> {
-    /// Execute the pipeline in parallel and write the result as CSV.
+    /// Like [`write_csv`](Self::write_csv), with the run split into `shards` partitions,
+    /// each serialized in parallel.
     ///
-    /// This collects the result in parallel (respecting the runner's partition settings),
-    /// then writes a single CSV file in deterministic order.
+    /// The parts are concatenated in partition order, so the file is the same for any
+    /// shard count when the pipeline preserves element order.
     ///
     /// # Arguments
     /// - `path`: Destination path.
-    /// - `shards`: Optional parallelism hint for collection (partitions); `None` lets the runner decide.
+    /// - `shards`: Number of partitions; `None` lets the runner decide.
     /// - `has_headers`: Whether to write a header row.
     ///
     /// # Errors
-    /// An error is returned if collection or CSV serialization fails.
+    /// An error is returned if execution or CSV serialization fails.
     ///
     /// # Example
     /// ```no_run
@@ -236,8 +459,7 @@ impl<T: Element + Serialize> PCollection<T> {
         shards: Option<usize>,
         has_headers: bool,
     ) -> Result<usize> {
-        let data = self.collect_par(shards, None)?;
-        write_csv_vec(path, has_headers, &data)
+        self.write_csv_parts(path.as_ref(), shards, has_headers, WriteOptions::default())
     }
 }
 
@@ -282,16 +504,58 @@ pub fn read_csv_streaming<T>(
 where
     T: Element + DeserializeOwned,
 {
-    let shards: CsvShards = build_csv_shards(path, has_headers, rows_per_shard)?;
-    let id = p.insert_node(Node::Source {
-        payload: Arc::new(shards),
-        vec_ops: CsvVecOps::<T>::new(),
-        elem_tag: TypeTag::of::<T>(),
-    });
-    p.set_coder::<T>(id);
-    Ok(PCollection {
-        pipeline: p.clone(),
-        id,
-        _t: PhantomData,
-    })
+    let opts = CsvReadOptions::new().with_headers(has_headers);
+    let location = path.as_ref().display().to_string();
+    let shards: CsvShards = build_csv_shards_with(path, &opts, rows_per_shard)?;
+    Ok(shard_source(p, shards, CsvVecOps::<T>::new()).record_input("csv", location))
+}
+
+/// Create a **streaming** CSV source using [`CsvReadOptions`].
+///
+/// Like [`read_csv_streaming`], but with a configurable dialect and malformed-row
+/// handling. Returns `(rows, malformed)`. Under [`MalformedRowPolicy::DeadLetter`],
+/// `malformed` is a second streaming source over the same shards that yields the rows
+/// failing to parse as `T`; executing both collections reads the file twice. Under the
+/// other policies it is empty.
+///
+/// # Errors
+/// Returns an error if the file cannot be scanned or opened by the CSV reader.
+///
+/// # Example
+/// ```no_run
+/// use ironbeam::*;
+/// use ironbeam::io::csv::{CsvReadOptions, MalformedRowPolicy};
+/// use serde::{Serialize, Deserialize};
+/// use anyhow::{Result, Ok};
+///
+/// #[derive(Clone, Serialize, Deserialize)]
+/// struct Row { k: String, v: u64 }
+///
+/// # fn main() -> Result<()> {
+/// let p = Pipeline::default();
+/// let opts = CsvReadOptions::new().with_malformed_policy(MalformedRowPolicy::Skip);
+/// let (rows, _) = read_csv_streaming_with::<Row>(&p, "big.csv", &opts, 50_000)?;
+/// let out = rows.collect_seq()?;
+/// # Ok(()) }
+/// ```
+pub fn read_csv_streaming_with<T>(
+    p: &Pipeline,
+    path: impl AsRef<Path>,
+    opts: &CsvReadOptions,
+    rows_per_shard: usize,
+) -> Result<(PCollection<T>, PCollection<DeadLetter<String>>)>
+where
+    T: Element + DeserializeOwned,
+{
+    let location = path.as_ref().display().to_string();
+    let shards: CsvShards = build_csv_shards_with(path, opts, rows_per_shard)?;
+    let malformed = if opts.on_malformed == MalformedRowPolicy::DeadLetter {
+        shard_source(p, shards.clone(), CsvDeadLetterVecOps::<T>::new())
+    } else {
+        from_vec(p, Vec::new())
+    };
+    Ok((
+        shard_source(p, shards, CsvVecOps::<T>::new()).record_input("csv", &location),
+        malformed.record_input("csv", location),
+    ))
 }
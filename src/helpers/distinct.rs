@@ -3,6 +3,8 @@
 //! # Overview
 //! - [`PCollection::distinct`](PCollection::distinct) - Remove duplicates globally (exact)
 //! - [`PCollection::distinct_by`](PCollection::distinct_by) - Remove duplicates by a computed projection
+//! - [`PCollection::distinct_within_window`](crate::PCollection::distinct_within_window) - Suppress repeats of a timestamped element that fall within an event-time window
+//! - [`PCollection::distinct_by_within_window`](crate::PCollection::distinct_by_within_window) - The same, comparing a computed key instead of the whole element
 //! - [`PCollection::distinct_per_key`](crate::PCollection::distinct_per_key) - Remove duplicate values per key (exact)
 //! - [`PCollection::distinct_count_globally`] - Exact count of distinct elements (global)
 //! - [`PCollection::distinct_count_per_key`] - Exact count of distinct values per key
@@ -18,11 +20,14 @@
 //! `DistinctCount<T>` (returns `u64`). Two approximate-count families are
 //! available: KMV-based (returns `f64`, exact when cardinality `< k`) and
 //! HyperLogLog++-based (returns `u64`, bounded relative error at all scales).
+//!
+//! The event-time variants group by key, sort each group by timestamp, and keep
+//! an occurrence only if it lands at least `window_ms` after the last one kept.
 
 use crate::combiners::{
     DistinctCount, DistinctSet, HllApproxDistinctCount, KMVApproxDistinctCount,
 };
-use crate::{Element, PCollection};
+use crate::{Element, PCollection, Timestamped};
 use std::hash::Hash;
 
 impl<T: Element + Eq + Hash> PCollection<T> {
@@ -208,6 +213,91 @@ impl<T: Element> PCollection<T> {
     }
 }
 
+impl<T: Element + Eq + Hash> PCollection<Timestamped<T>> {
+    /// Drop repeats of an element that occur within `window_ms` of event time.
+    ///
+    /// Shorthand for [`distinct_by_within_window`](Self::distinct_by_within_window)
+    /// keyed on the whole value.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use anyhow::Result;
+    /// use ironbeam::*;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let out = from_vec(&p, vec![(0u64, 'a'), (400, 'a'), (1_500, 'a'), (450, 'b')])
+    ///     .to_timestamped()
+    ///     .distinct_within_window(1_000)
+    ///     .reify_timestamps()
+    ///     .collect_seq_sorted()?;
+    /// assert_eq!(out, vec![(0, 'a'), (450, 'b'), (1_500, 'a')]);
+    /// # Ok(()) }
+    /// ```
+    #[must_use]
+    pub fn distinct_within_window(self, window_ms: u64) -> Self {
+        self.distinct_by_within_window(window_ms, T::clone)
+    }
+}
+
+impl<T: Element> PCollection<Timestamped<T>> {
+    /// Deduplicate by a computed key, suppressing only occurrences close in event time.
+    ///
+    /// For each key, elements are ordered by timestamp. The earliest is kept, and each
+    /// later one is dropped if its timestamp is less than `window_ms` after the last
+    /// element kept for that key; otherwise it is kept and starts a new window. With
+    /// `window_ms == 0` nothing is dropped. Among elements with the same key and
+    /// timestamp the one kept is arbitrary.
+    ///
+    /// Unlike [`distinct_by`](PCollection::distinct_by), a key that reappears after a
+    /// quiet period is emitted again, which suits retried or replayed events.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use anyhow::Result;
+    /// use ironbeam::*;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// // (ts, (request_id, attempt))
+    /// let requests = from_vec(&p, vec![
+    ///     (100u64, (1u32, 1u32)),
+    ///     (250, (1, 2)),
+    ///     (9_000, (1, 3)),
+    ///     (300, (2, 1)),
+    /// ]);
+    /// let kept = requests
+    ///     .to_timestamped()
+    ///     .distinct_by_within_window(5_000, |&(id, _)| id)
+    ///     .reify_timestamps()
+    ///     .collect_seq_sorted()?;
+    /// assert_eq!(kept, vec![(100, (1, 1)), (300, (2, 1)), (9_000, (1, 3))]);
+    /// # Ok(()) }
+    /// ```
+    #[must_use]
+    pub fn distinct_by_within_window<K, F>(self, window_ms: u64, key_fn: F) -> Self
+    where
+        K: Element + Eq + Hash,
+        F: 'static + Send + Sync + Fn(&T) -> K,
+    {
+        self.key_by(move |t: &Timestamped<T>| key_fn(&t.value))
+            .group_by_key()
+            .flat_map(move |kv: &(K, Vec<Timestamped<T>>)| {
+                let mut events = kv.1.clone();
+                events.sort_by_key(|t| t.ts);
+                let mut last_kept = None;
+                events.retain(|t| {
+                    let keep = last_kept.is_none_or(|last| t.ts - last >= window_ms);
+                    if keep {
+                        last_kept = Some(t.ts);
+                    }
+                    keep
+                });
+                events
+            })
+    }
+}
+
 impl<K, V> PCollection<(K, V)>
 where
     K: Element + Eq + Hash,
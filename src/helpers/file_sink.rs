@@ -0,0 +1,593 @@
+//! File writes executed by the runner.
+//!
+//! The file writers ([`write_jsonl`](PCollection::write_jsonl),
+//! [`write_csv`](PCollection::write_csv), [`write_parquet`](PCollection::write_parquet)
+//! and their `_with`/`_par` variants) do not collect the collection to the caller and
+//! serialize it there. Each appends an *encode* step to the pipeline, which serializes
+//! every partition on the worker that produced it and writes it to a part file in a
+//! temporary directory beside the destination. The runner returns the part files in
+//! partition order, and they are concatenated into the destination file, so the
+//! encoded rows never have to fit in memory together. The part files are removed once
+//! the write finishes or fails.
+//!
+//! Serialization therefore runs in parallel with the rest of the final stage, and the
+//! encode step is an ordinary plan step: it is fused with the stateless transforms
+//! before it, and it appears in metrics and traces under the writer's name (for
+//! example `write_jsonl`). A row that fails to serialize fails that step with
+//! [`IronbeamError::TransformFailed`](crate::IronbeamError::TransformFailed). The
+//! file's contents depend only on the order of the
+//! collection's partitions, never on thread scheduling. The file is written atomically
+//! (see [`crate::io::atomic`]): nothing appears at the destination unless the whole run
+//! and the write succeed.
+//!
+//! # Sinks
+//! [`JsonlSink`], [`CsvSink`] and [`ParquetSink`] write the same files as
+//! [`Sink`]s, for [`write_to`](PCollection::write_to) and for
+//! [`write_to_sink`](PCollection::write_to_sink), so one
+//! [`run_all`](crate::Pipeline::run_all) can write several files. A sink receives the
+//! run's partitions in the order they finish: the text sinks encode each one to a part
+//! file as it arrives (in the same way as the encode step, but one partition at a time)
+//! and concatenate the parts in partition order once every partition has arrived. The
+//! Parquet sink keeps each partition as an Arrow batch until then. Nothing is written
+//! if the run fails.
+//!
+//! Writes run with the pipeline's [`RunnerConfig`] (see
+//! [`Pipeline::set_runner_config`](crate::Pipeline::set_runner_config)), so they spill,
+//! time out and are cancelled as its other runs are, and always in parallel. The
+//! exception is a collection that ends in [`take`](PCollection::take) or
+//! [`first`](PCollection::first): it runs sequentially, so the limit applies to the
+//! whole collection, as it does for [`collect_seq`](PCollection::collect_seq).
+
+use crate::error::fail_step;
+use crate::extensions::Sink;
+#[cfg(feature = "io-parquet")]
+use crate::helpers::ArrowBatch;
+use crate::io::atomic::{create_parent_dir, write_atomically, write_success_marker};
+use crate::io::compression::{WriteOptions, compressed_writer};
+use crate::io::csv::encode_csv_part;
+use crate::io::jsonl::encode_jsonl_part;
+#[cfg(feature = "io-parquet")]
+use crate::io::parquet::{ParquetWriteOptions, write_parquet_batches_with_schema};
+use crate::node::{DynOp, Node};
+use crate::planner::build_plan;
+use crate::type_token::Partition;
+use crate::{Element, ExecMode, PCollection, RunnerConfig};
+use anyhow::{Context, Result, bail};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{File, read, write};
+use std::io::{Seek, SeekFrom, Write, copy};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tempfile::TempDir;
+
+/// One partition of a text file, serialized by an encode step.
+///
+/// The first `header` bytes of `bytes` are a header row that every part repeats; only
+/// the first part with rows keeps it when the parts are concatenated.
+pub(crate) struct EncodedPart {
+    pub(crate) rows: usize,
+    pub(crate) header: usize,
+    pub(crate) bytes: Vec<u8>,
+}
+
+/// An [`EncodedPart`] written to a part file by the worker that encoded it.
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct PartFile {
+    pub(crate) rows: usize,
+    pub(crate) header: usize,
+    pub(crate) path: PathBuf,
+}
+
+impl PartFile {
+    /// Read the part's bytes back.
+    pub(crate) fn read(&self) -> Result<Vec<u8>> {
+        read(&self.path).with_context(|| format!("read {}", self.path.display()))
+    }
+}
+
+/// Writes the parts of one encode step to files in its temporary directory.
+pub(crate) struct PartWriter {
+    dir: PathBuf,
+    next: AtomicUsize,
+}
+
+impl PartWriter {
+    /// Write `part` to a new part file.
+    pub(crate) fn write(&self, part: EncodedPart) -> Result<PartFile> {
+        let n = self.next.fetch_add(1, Ordering::Relaxed);
+        let path = self.dir.join(format!("part-{n:05}"));
+        write(&path, &part.bytes).with_context(|| format!("write {}", path.display()))?;
+        Ok(PartFile {
+            rows: part.rows,
+            header: part.header,
+            path,
+        })
+    }
+}
+
+/// `EncodePartOp`: serializes a whole `Vec<T>` partition into a single part `P`,
+/// usually a [`PartFile`].
+struct EncodePartOp<T, P, F> {
+    writer: PartWriter,
+    encode: F,
+    _t: PhantomData<fn(T) -> P>,
+}
+
+impl<T, P, F> DynOp for EncodePartOp<T, P, F>
+where
+    T: Element,
+    P: Element,
+    F: 'static + Send + Sync + Fn(&[T], &PartWriter) -> Result<P>,
+{
+    fn apply(&self, input: Partition) -> Partition {
+        let rows = *input
+            .downcast::<Vec<T>>()
+            .expect("EncodePartOp: expected Vec<T> input");
+        let part = (self.encode)(&rows, &self.writer).unwrap_or_else(|e| fail_step(&e));
+        Box::new(vec![part]) as Partition
+    }
+}
+
+impl<T: Element> PCollection<T> {
+    /// Runner configuration for a write of this collection: the pipeline's
+    /// [`RunnerConfig`], run in parallel over `partitions` (or its own partition count),
+    /// or sequentially if the collection ends in a `take`.
+    pub(crate) fn write_runner(&self, partitions: Option<usize>) -> Result<RunnerConfig> {
+        let limited = build_plan(&self.pipeline, self.id)?.limit.is_some();
+        let config = self.pipeline.runner_config();
+        let mode = match config.runner().mode {
+            _ if limited => ExecMode::Sequential,
+            ExecMode::Parallel {
+                threads,
+                partitions: configured,
+            } => ExecMode::Parallel {
+                threads,
+                partitions: partitions.or(configured),
+            },
+            ExecMode::Sequential | ExecMode::Streaming => ExecMode::Parallel {
+                threads: None,
+                partitions,
+            },
+        };
+        Ok(config.with_mode(mode))
+    }
+
+    /// Serialize each partition with `encode` in a step named `name`, run the pipeline
+    /// over `partitions`, and write the parts to `path` in partition order with `opts`.
+    ///
+    /// Returns the number of rows written.
+    pub(crate) fn write_encoded<F>(
+        self,
+        name: &'static str,
+        path: &Path,
+        partitions: Option<usize>,
+        opts: WriteOptions,
+        encode: F,
+    ) -> Result<usize>
+    where
+        F: 'static + Send + Sync + Fn(&[T]) -> Result<EncodedPart>,
+    {
+        let path = self.pipeline.resolve_path(path)?;
+        create_parent_dir(&path)?;
+        let parts_in = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."));
+        let (_part_dir, parts) =
+            self.encode_parts(name, partitions, parts_in, move |rows, parts| {
+                parts.write(encode(rows)?)
+            })?;
+        write_parts(&path, &parts, opts)
+    }
+
+    /// Serialize each partition with `encode` in a step named `name`, run the pipeline
+    /// over `partitions`, and return the parts in partition order.
+    ///
+    /// `encode` writes its bytes with the [`PartWriter`] it is given, to part files in a
+    /// new temporary directory under `parts_in`. The directory is returned alongside the
+    /// parts and removes them when dropped.
+    pub(crate) fn encode_parts<P, F>(
+        self,
+        name: &'static str,
+        partitions: Option<usize>,
+        parts_in: &Path,
+        encode: F,
+    ) -> Result<(TempDir, Vec<P>)>
+    where
+        P: Element,
+        F: 'static + Send + Sync + Fn(&[T], &PartWriter) -> Result<P>,
+    {
+        let runner = self.write_runner(partitions)?;
+        let dir = tempfile::Builder::new()
+            .prefix(".ironbeam-parts-")
+            .tempdir_in(parts_in)
+            .with_context(|| {
+                format!("{name}: create a part directory in {}", parts_in.display())
+            })?;
+        let op: Arc<dyn DynOp> = Arc::new(EncodePartOp {
+            writer: PartWriter {
+                dir: dir.path().to_path_buf(),
+                next: AtomicUsize::new(0),
+            },
+            encode,
+            _t: PhantomData::<fn(T) -> P>,
+        });
+        let id = self.pipeline.insert_node(Node::Stateless(vec![op]));
+        self.pipeline.connect(self.id, id);
+        self.pipeline.set_coder::<P>(id);
+        let encoded = PCollection::<P> {
+            pipeline: self.pipeline,
+            id,
+            _t: PhantomData,
+        }
+        .with_name(name);
+        let parts = runner.run_collect(&encoded.pipeline, encoded.id)?;
+        Ok((dir, parts))
+    }
+}
+
+/// Concatenate `parts` into `path`, keeping the header of the first part with rows.
+pub(crate) fn write_parts(path: &Path, parts: &[PartFile], opts: WriteOptions) -> Result<usize> {
+    create_parent_dir(path)?;
+    write_atomically(path, |f| {
+        let mut w = compressed_writer(f, path, opts.compression)
+            .with_context(|| format!("setup compression for {}", path.display()))?;
+        let mut header_written = false;
+        for part in parts.iter().filter(|part| part.rows > 0) {
+            let skip = if header_written { part.header } else { 0 };
+            header_written = true;
+            let mut file =
+                File::open(&part.path).with_context(|| format!("open {}", part.path.display()))?;
+            file.seek(SeekFrom::Start(skip as u64))
+                .with_context(|| format!("read {}", part.path.display()))?;
+            copy(&mut file, &mut w).with_context(|| format!("write {}", path.display()))?;
+        }
+        w.flush()?;
+        Ok(())
+    })?;
+    if opts.success_marker {
+        write_success_marker(path)?;
+    }
+    Ok(parts.iter().map(|part| part.rows).sum())
+}
+
+/// The parts a sink has received, by partition and then by batch, and which partitions
+/// are complete.
+struct Received<P> {
+    path: PathBuf,
+    partitions: Option<usize>,
+    flushed: usize,
+    current: usize,
+    batches: usize,
+    parts: BTreeMap<(usize, usize), P>,
+}
+
+impl<P> Received<P> {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            partitions: None,
+            flushed: 0,
+            current: 0,
+            batches: 0,
+            parts: BTreeMap::new(),
+        }
+    }
+
+    fn open(&mut self, partition: usize, num_partitions: usize) -> Result<()> {
+        match self.partitions {
+            Some(n) if n != num_partitions => bail!(
+                "{}: partition {partition} of {num_partitions}, but the run has {n}",
+                self.path.display()
+            ),
+            _ => self.partitions = Some(num_partitions),
+        }
+        self.current = partition;
+        self.batches = 0;
+        Ok(())
+    }
+
+    fn push(&mut self, part: P) {
+        self.parts.insert((self.current, self.batches), part);
+        self.batches += 1;
+    }
+
+    /// The parts in partition order, or `None` if some partition never arrived (the
+    /// run failed).
+    fn complete(&mut self) -> Option<Vec<P>> {
+        (Some(self.flushed) == self.partitions).then(|| {
+            std::mem::take(&mut self.parts)
+                .into_values()
+                .collect::<Vec<_>>()
+        })
+    }
+}
+
+/// The part files of a text sink, in a temporary directory beside the destination.
+struct TextParts {
+    opts: WriteOptions,
+    dir: Option<(TempDir, PartWriter)>,
+    received: Received<PartFile>,
+}
+
+impl TextParts {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            opts: WriteOptions::default(),
+            dir: None,
+            received: Received::new(path),
+        }
+    }
+
+    fn write(&mut self, part: EncodedPart) -> Result<()> {
+        if self.dir.is_none() {
+            let path = &self.received.path;
+            create_parent_dir(path)?;
+            let parts_in = path
+                .parent()
+                .filter(|parent| !parent.as_os_str().is_empty())
+                .unwrap_or_else(|| Path::new("."));
+            let dir = tempfile::Builder::new()
+                .prefix(".ironbeam-parts-")
+                .tempdir_in(parts_in)
+                .with_context(|| format!("create a part directory in {}", parts_in.display()))?;
+            let writer = PartWriter {
+                dir: dir.path().to_path_buf(),
+                next: AtomicUsize::new(0),
+            };
+            self.dir = Some((dir, writer));
+        }
+        let (_, writer) = self.dir.as_ref().expect("created above");
+        let part = writer.write(part)?;
+        self.received.push(part);
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<()> {
+        let parts = self.received.complete();
+        let written = match &parts {
+            Some(parts) => write_parts(&self.received.path, parts, self.opts).map(drop),
+            None => Ok(()),
+        };
+        self.dir = None;
+        written
+    }
+}
+
+/// A [`Sink`] writing a collection to one JSON Lines file, like
+/// [`write_jsonl`](PCollection::write_jsonl); see the [module docs](self).
+///
+/// # Example
+/// ```no_run
+/// use ironbeam::*;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let p = Pipeline::default();
+/// let words = from_vec(&p, vec!["a".to_string(), "b".to_string()]);
+/// let jsonl = words.clone().write_to_sink(JsonlSink::new("out/words.jsonl"));
+/// let csv = words.write_to_sink(CsvSink::new("out/words.csv", false));
+/// p.run_all(&[jsonl, csv])?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct JsonlSink<T> {
+    parts: TextParts,
+    partitions: usize,
+    _t: PhantomData<fn(&T)>,
+}
+
+impl<T> JsonlSink<T> {
+    /// A sink writing to `path`.
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            parts: TextParts::new(path.as_ref().to_path_buf()),
+            partitions: 1,
+            _t: PhantomData,
+        }
+    }
+
+    /// Write with `opts`, e.g. to force a compression codec.
+    #[must_use]
+    pub const fn with_options(mut self, opts: WriteOptions) -> Self {
+        self.parts.opts = opts;
+        self
+    }
+
+    /// Run [`write_to`](PCollection::write_to) over `partitions` partitions (see
+    /// [`Sink::partitions`]).
+    #[must_use]
+    pub const fn with_partitions(mut self, partitions: usize) -> Self {
+        self.partitions = partitions;
+        self
+    }
+}
+
+impl<T: Serialize> Sink<T> for JsonlSink<T> {
+    fn open(&mut self, partition: usize, num_partitions: usize) -> Result<()> {
+        self.parts.received.open(partition, num_partitions)
+    }
+
+    fn write_batch(&mut self, batch: &[T]) -> Result<()> {
+        self.parts.write(EncodedPart {
+            rows: batch.len(),
+            header: 0,
+            bytes: encode_jsonl_part(batch)?,
+        })
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.parts.received.flushed += 1;
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<()> {
+        self.parts.close()
+    }
+
+    fn partitions(&self) -> usize {
+        self.partitions
+    }
+
+    /// A whole partition, encoded to one part file.
+    fn batch_size(&self) -> usize {
+        usize::MAX
+    }
+}
+
+/// A [`Sink`] writing a collection to one CSV file, like
+/// [`write_csv`](PCollection::write_csv); see the [module docs](self).
+pub struct CsvSink<T> {
+    parts: TextParts,
+    has_headers: bool,
+    partitions: usize,
+    _t: PhantomData<fn(&T)>,
+}
+
+impl<T> CsvSink<T> {
+    /// A sink writing to `path`, with a header row if `has_headers`.
+    pub fn new(path: impl AsRef<Path>, has_headers: bool) -> Self {
+        Self {
+            parts: TextParts::new(path.as_ref().to_path_buf()),
+            has_headers,
+            partitions: 1,
+            _t: PhantomData,
+        }
+    }
+
+    /// Write with `opts`, e.g. to force a compression codec.
+    #[must_use]
+    pub const fn with_options(mut self, opts: WriteOptions) -> Self {
+        self.parts.opts = opts;
+        self
+    }
+
+    /// Run [`write_to`](PCollection::write_to) over `partitions` partitions (see
+    /// [`Sink::partitions`]).
+    #[must_use]
+    pub const fn with_partitions(mut self, partitions: usize) -> Self {
+        self.partitions = partitions;
+        self
+    }
+}
+
+impl<T: Serialize> Sink<T> for CsvSink<T> {
+    fn open(&mut self, partition: usize, num_partitions: usize) -> Result<()> {
+        self.parts.received.open(partition, num_partitions)
+    }
+
+    fn write_batch(&mut self, batch: &[T]) -> Result<()> {
+        let (header, bytes) = encode_csv_part(batch, self.has_headers)?;
+        self.parts.write(EncodedPart {
+            rows: batch.len(),
+            header,
+            bytes,
+        })
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.parts.received.flushed += 1;
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<()> {
+        self.parts.close()
+    }
+
+    fn partitions(&self) -> usize {
+        self.partitions
+    }
+
+    /// A whole partition, encoded to one part file.
+    fn batch_size(&self) -> usize {
+        usize::MAX
+    }
+}
+
+/// A [`Sink`] writing a collection to one Parquet file, like
+/// [`write_parquet_with`](PCollection::write_parquet_with); see the
+/// [module docs](self).
+#[cfg(feature = "io-parquet")]
+pub struct ParquetSink<T> {
+    opts: ParquetWriteOptions,
+    partitions: usize,
+    received: Received<ArrowBatch>,
+    _t: PhantomData<fn(&T)>,
+}
+
+#[cfg(feature = "io-parquet")]
+impl<T> ParquetSink<T> {
+    /// A sink writing to `path` with the default [`ParquetWriteOptions`].
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            opts: ParquetWriteOptions::default(),
+            partitions: 1,
+            received: Received::new(path.as_ref().to_path_buf()),
+            _t: PhantomData,
+        }
+    }
+
+    /// Write with `opts`, e.g. to set the row group size or compression.
+    #[must_use]
+    pub fn with_options(mut self, opts: ParquetWriteOptions) -> Self {
+        self.opts = opts;
+        self
+    }
+
+    /// Run [`write_to`](PCollection::write_to) over `partitions` partitions (see
+    /// [`Sink::partitions`]).
+    #[must_use]
+    pub const fn with_partitions(mut self, partitions: usize) -> Self {
+        self.partitions = partitions;
+        self
+    }
+}
+
+#[cfg(feature = "io-parquet")]
+impl<T: Serialize + DeserializeOwned> Sink<T> for ParquetSink<T> {
+    fn open(&mut self, partition: usize, num_partitions: usize) -> Result<()> {
+        self.received.open(partition, num_partitions)
+    }
+
+    fn write_batch(&mut self, batch: &[T]) -> Result<()> {
+        use serde_arrow::schema::{SchemaLike, TracingOptions};
+        use serde_arrow::to_record_batch;
+
+        if !batch.is_empty() {
+            let fields =
+                Vec::<arrow::datatypes::FieldRef>::from_type::<T>(TracingOptions::default())
+                    .context("infer Arrow schema from type T")?;
+            let batch = to_record_batch(&fields, &batch).context("convert rows to RecordBatch")?;
+            self.received.push(ArrowBatch(batch));
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.received.flushed += 1;
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<()> {
+        match self.received.complete() {
+            Some(batches) => {
+                let path = &self.received.path;
+                create_parent_dir(path)?;
+                write_parquet_batches_with_schema::<T>(path, &batches, &self.opts).map(drop)
+            }
+            None => Ok(()),
+        }
+    }
+
+    fn partitions(&self) -> usize {
+        self.partitions
+    }
+
+    /// A whole partition, converted to one Arrow batch.
+    fn batch_size(&self) -> usize {
+        usize::MAX
+    }
+}
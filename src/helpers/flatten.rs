@@ -1,10 +1,15 @@
 //! Flatten transform for merging multiple `PCollection`s into one.
 //!
 //! This module provides the [`flatten`] function that merges multiple
-//! `PCollection<T>` of the same type into a single `PCollection<T>`.
+//! `PCollection<T>` of the same type into a single `PCollection<T>`, and the
+//! two-input shorthand [`PCollection::union`].
 //!
 //! This is analogous to Apache Beam's `Flatten.pCollections()` transform.
 //!
+//! Flattening the output of another flatten (e.g. `a.union(&b).union(&c)`) does not
+//! nest subplans: the inner flatten's inputs are spliced into the new node, so the
+//! result is a single N-way `Flatten`.
+//!
 //! ## Example
 //! ```no_run
 //! use ironbeam::*;
@@ -19,6 +24,10 @@
 //! let merged = flatten(&[&pc1, &pc2, &pc3]);
 //! let result = merged.collect_seq_sorted()?;
 //! assert_eq!(result, vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
+//!
+//! // Same result, built pairwise.
+//! let unioned = pc1.union(&pc2).union(&pc3);
+//! assert_eq!(unioned.collect_seq_sorted()?, vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
 //! # Ok(()) }
 //! ```
 
@@ -69,6 +78,35 @@ fn insert_dummy_source(p: &Pipeline) -> NodeId {
     })
 }
 
+/// Expand a captured input chain into the subplans it should contribute to a new
+/// `Flatten`.
+///
+/// A chain of the shape `[Source, Flatten, Stateless*]` is the output of an earlier
+/// flatten (possibly followed by element-wise ops). Nested flattens cannot run inside a
+/// subplan, so its input subplans are spliced in directly, with any trailing stateless
+/// ops appended to each of them. Stateless ops already run independently per partition,
+/// so replaying them per input is equivalent. Chains ending in a `take(N)`-style limit are
+/// kept as-is because the limit must apply to the combined output.
+fn splice_nested_flatten(chain: Vec<Node>) -> Vec<Vec<Node>> {
+    if let [Node::Source { .. }, Node::Flatten { chains, .. }, rest @ ..] = chain.as_slice() {
+        let trailing_ok = rest.iter().all(|n| match n {
+            Node::Stateless(ops) => ops.iter().all(|op| op.limit_n().is_none()),
+            _ => false,
+        });
+        if trailing_ok {
+            return chains
+                .iter()
+                .map(|inner| {
+                    let mut inner = inner.clone();
+                    inner.extend(rest.iter().cloned());
+                    inner
+                })
+                .collect();
+        }
+    }
+    vec![chain]
+}
+
 /// Flatten multiple `PCollection<T>` into a single `PCollection<T>`.
 ///
 /// Takes a slice of references to `PCollection<T>` and merges them into one
@@ -120,7 +158,7 @@ where
 
     let chains: Vec<Vec<Node>> = collections
         .iter()
-        .map(|pc| chain_from(&pc.pipeline, pc.id).expect("chain build"))
+        .flat_map(|pc| splice_nested_flatten(chain_from(&pc.pipeline, pc.id).expect("chain build")))
         .collect();
 
     let coalesce = Arc::new(|parts: Vec<Partition>| -> Partition {
@@ -148,6 +186,7 @@ where
         merge,
     });
     pipeline.connect(source_id, id);
+    pipeline.record_upstream(id, collections.iter().map(|pc| pc.id).collect());
     pipeline.set_coder::<T>(id);
 
     PCollection {
@@ -156,3 +195,31 @@ where
         _t: PhantomData,
     }
 }
+
+impl<T: Element> PCollection<T> {
+    /// Concatenate this collection with `other` (a two-input [`flatten`]).
+    ///
+    /// Both collections must belong to the same pipeline. Elements of both inputs
+    /// appear in the output; no de-duplication is performed (see
+    /// [`distinct`](crate::PCollection::distinct) for set semantics). Chained unions
+    /// collapse into a single N-way flatten.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ironbeam::*;
+    /// use anyhow::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let a = from_vec(&p, vec![1, 2]);
+    /// let b = from_vec(&p, vec![2, 3]);
+    ///
+    /// let all = a.union(&b).collect_seq_sorted()?;
+    /// assert_eq!(all, vec![1, 2, 2, 3]);
+    /// # Ok(()) }
+    /// ```
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        flatten(&[self, other])
+    }
+}
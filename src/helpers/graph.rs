@@ -0,0 +1,225 @@
+//! [`GraphIO`] bulk loads and neighbor lookups.
+//!
+//! - [`write_graph_nodes`](PCollection::write_graph_nodes) adds one node per element
+//!   with [`batch_add_nodes`](GraphIO::batch_add_nodes) and pairs each element with the
+//!   ID the graph assigned it, so edges between the new nodes can be loaded next.
+//! - [`write_graph_edges`](PCollection::write_graph_edges) adds one edge per element
+//!   with [`batch_add_edges`](GraphIO::batch_add_edges).
+//! - [`expand_neighbors`](PCollection::expand_neighbors) pairs every element with the
+//!   neighbors of its node, fetched with [`get_neighbors`](GraphIO::get_neighbors).
+//!
+//! - [`expand_neighbors_shared`](PCollection::expand_neighbors_shared) does the same
+//!   lookup lazily, as a step of the pipeline that looks up each partition's nodes on
+//!   the worker running it.
+//!
+//! The first three take the graph by reference, so they execute the pipeline up to this
+//! point when called, as the other cloud sinks do. Requests go out [`GRAPH_BATCH_SIZE`]
+//! nodes or edges at a time.
+//!
+//! ```
+//! use ironbeam::io::cloud::{EdgeDirection, FakeGraphIO};
+//! use ironbeam::*;
+//! use std::collections::HashMap;
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let graph = FakeGraphIO::new();
+//! let p = Pipeline::default();
+//! let people = from_vec(&p, vec!["ada".to_string(), "grace".to_string()]).write_graph_nodes(
+//!     &graph,
+//!     |_: &String| vec!["Person".to_string()],
+//!     |name: &String| HashMap::from([("name".to_string(), name.clone())]),
+//! )?;
+//! let ids: Vec<String> = people.collect_seq()?.into_iter().map(|(_, id)| id).collect();
+//!
+//! from_vec(&p, vec![(ids[0].clone(), ids[1].clone())]).write_graph_edges(
+//!     &graph,
+//!     |(from, to): &(String, String)| (from.clone(), to.clone()),
+//!     |_: &(String, String)| "KNOWS".to_string(),
+//!     |_: &(String, String)| HashMap::new(),
+//! )?;
+//!
+//! let known = from_vec(&p, vec![ids[0].clone()])
+//!     .expand_neighbors(&graph, |id: &String| id.clone(), EdgeDirection::Outgoing)?
+//!     .collect_seq()?;
+//! assert_eq!(known[0].1[0].properties["name"], "grace");
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::io::cloud::traits::{EdgeDirection, GraphIO, GraphNode, NewGraphEdge, NewGraphNode};
+use crate::{Element, PCollection, from_vec};
+use anyhow::{Context, Result, ensure};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Number of nodes or edges per [`batch_add_nodes`](GraphIO::batch_add_nodes) or
+/// [`batch_add_edges`](GraphIO::batch_add_edges) call.
+pub const GRAPH_BATCH_SIZE: usize = 100;
+
+/// A node's or edge's properties, as [`GraphIO`] reads and writes them.
+pub type GraphProperties = HashMap<String, String>;
+
+impl<T: Element> PCollection<T> {
+    /// Execute the collection and add a node per element, labeled `label_fn(element)`
+    /// with properties `props_fn(element)`.
+    ///
+    /// Returns a new collection in the same pipeline pairing every element with the ID
+    /// of its node, in the order of this collection.
+    ///
+    /// # Errors
+    /// Returns an error if the pipeline fails, a `batch_add_nodes` call fails, or the
+    /// graph returns a different number of IDs than nodes sent. Batches before the
+    /// failing one have already been added.
+    pub fn write_graph_nodes<L, P>(
+        self,
+        graph: &dyn GraphIO,
+        label_fn: L,
+        props_fn: P,
+    ) -> Result<PCollection<(T, String)>>
+    where
+        L: Fn(&T) -> Vec<String>,
+        P: Fn(&T) -> GraphProperties,
+    {
+        let pipeline = self.pipeline.clone();
+        let elements = self.collect_seq()?;
+        let mut ids = Vec::with_capacity(elements.len());
+        for (i, batch) in elements.chunks(GRAPH_BATCH_SIZE).enumerate() {
+            let nodes = batch
+                .iter()
+                .map(|element| NewGraphNode {
+                    labels: label_fn(element),
+                    properties: props_fn(element),
+                })
+                .collect();
+            let added = graph
+                .batch_add_nodes(nodes)
+                .with_context(|| format!("write_graph_nodes: batch #{i}"))?;
+            ensure!(
+                added.len() == batch.len(),
+                "write_graph_nodes: graph returned {} IDs for {} nodes",
+                added.len(),
+                batch.len()
+            );
+            ids.extend(added);
+        }
+        Ok(from_vec(&pipeline, elements.into_iter().zip(ids).collect()))
+    }
+
+    /// Execute the collection and add an edge per element, from and to the node IDs
+    /// returned by `endpoints_fn(element)`, labeled `label_fn(element)` with properties
+    /// `props_fn(element)`.
+    ///
+    /// Returns the number of edges added.
+    ///
+    /// # Errors
+    /// Returns an error if the pipeline fails or a `batch_add_edges` call fails, e.g.
+    /// because an endpoint does not exist. Batches before the failing one have already
+    /// been added.
+    pub fn write_graph_edges<E, L, P>(
+        self,
+        graph: &dyn GraphIO,
+        endpoints_fn: E,
+        label_fn: L,
+        props_fn: P,
+    ) -> Result<usize>
+    where
+        E: Fn(&T) -> (String, String),
+        L: Fn(&T) -> String,
+        P: Fn(&T) -> GraphProperties,
+    {
+        let elements = self.collect_seq()?;
+        for (i, batch) in elements.chunks(GRAPH_BATCH_SIZE).enumerate() {
+            let edges = batch
+                .iter()
+                .map(|element| {
+                    let (from, to) = endpoints_fn(element);
+                    NewGraphEdge {
+                        from,
+                        to,
+                        label: label_fn(element),
+                        properties: props_fn(element),
+                    }
+                })
+                .collect();
+            graph
+                .batch_add_edges(edges)
+                .with_context(|| format!("write_graph_edges: batch #{i}"))?;
+        }
+        Ok(elements.len())
+    }
+
+    /// Execute the collection and pair every element with the neighbors of node
+    /// `node_id_fn(element)` in `direction`.
+    ///
+    /// Each distinct node is looked up once. The result is a new collection in the same
+    /// pipeline, in the order of this collection.
+    ///
+    /// # Errors
+    /// Returns an error if the pipeline fails or a `get_neighbors` call fails, e.g.
+    /// because the node does not exist.
+    pub fn expand_neighbors<F>(
+        self,
+        graph: &dyn GraphIO,
+        node_id_fn: F,
+        direction: EdgeDirection,
+    ) -> Result<PCollection<(T, Vec<GraphNode>)>>
+    where
+        F: Fn(&T) -> String,
+    {
+        let pipeline = self.pipeline.clone();
+        let elements = self.collect_seq()?;
+        let expanded = expand(graph, &node_id_fn, direction, elements)?;
+        Ok(from_vec(&pipeline, expanded))
+    }
+
+    /// Pair every element with the neighbors of node `node_id_fn(element)` in
+    /// `direction`, as a lazy step of the pipeline.
+    ///
+    /// Unlike [`expand_neighbors`](Self::expand_neighbors), nothing runs until the
+    /// pipeline does. Each partition looks up its own distinct nodes on the worker running
+    /// it. Element order within a partition is preserved.
+    ///
+    /// A failed `get_neighbors` call, e.g. for a node that does not exist, fails the step
+    /// with [`IronbeamError::TransformFailed`](crate::IronbeamError::TransformFailed).
+    pub fn expand_neighbors_shared<F>(
+        self,
+        graph: Arc<dyn GraphIO>,
+        node_id_fn: F,
+        direction: EdgeDirection,
+    ) -> PCollection<(T, Vec<GraphNode>)>
+    where
+        F: 'static + Send + Sync + Fn(&T) -> String,
+    {
+        self.try_map_partitions(move |elements| {
+            expand(graph.as_ref(), &node_id_fn, direction, elements)
+        })
+    }
+}
+
+/// Pair `elements` with their nodes' neighbors, looking up each distinct node once.
+fn expand<T, F>(
+    graph: &dyn GraphIO,
+    node_id_fn: &F,
+    direction: EdgeDirection,
+    elements: Vec<T>,
+) -> Result<Vec<(T, Vec<GraphNode>)>>
+where
+    F: Fn(&T) -> String,
+{
+    let ids: Vec<String> = elements.iter().map(node_id_fn).collect();
+
+    let mut seen = HashSet::new();
+    let mut neighbors: HashMap<&str, Vec<GraphNode>> = HashMap::new();
+    for id in ids.iter().filter(|id| seen.insert(*id)) {
+        let found = graph
+            .get_neighbors(id, direction)
+            .with_context(|| format!("expand_neighbors: neighbors of {id}"))?;
+        neighbors.insert(id.as_str(), found);
+    }
+
+    Ok(elements
+        .into_iter()
+        .zip(&ids)
+        .map(|(element, id)| (element, neighbors[id.as_str()].clone()))
+        .collect())
+}
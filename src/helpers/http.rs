@@ -0,0 +1,87 @@
+//! JSON REST sources and sinks for [`PCollection`].
+//!
+//! - [`read_http_json`] pages through an API into a `PCollection<T>`, following the
+//!   config's [`Pagination`](crate::io::http::Pagination).
+//! - [`PCollection::write_http_json`] POSTs a collection as JSON arrays, in batches.
+//!
+//! Both run eagerly: the read fetches every page before returning, and the write
+//! executes the pipeline up to this point, as the file writers do. Requests are retried
+//! with backoff and can be rate limited; see [`io::http`](crate::io::http).
+//!
+//! The URL and header values may hold `config://` references, resolved through the
+//! pipeline's [`ConfigResolver`](crate::io::cloud::ConfigResolver), so API tokens do
+//! not have to appear in pipeline code.
+//!
+//! ## Feature flags
+//! - `io-http`: enables this module. It is **not** part of the default feature set;
+//!   opt in explicitly to pull in the HTTP client.
+//!
+//! ## Example
+//! ```no_run
+//! use ironbeam::io::http::{HttpSinkConfig, HttpSourceConfig, Pagination};
+//! use ironbeam::*;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Clone, Serialize, Deserialize)]
+//! struct Order {
+//!     id: u64,
+//!     total_cents: u64,
+//! }
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let p = Pipeline::default();
+//! let source = HttpSourceConfig::new("https://shop.example.com/api/orders")
+//!     .with_header("Authorization", "Bearer ${config://shop/token}")
+//!     .with_pagination(Pagination::PageNumber { param: "page".into(), start: 1 })
+//!     .with_records("/orders");
+//! let orders = read_http_json::<Order>(&p, &source)?;
+//!
+//! let large = orders.filter(|o: &Order| o.total_cents > 100_000);
+//! large.write_http_json(&HttpSinkConfig::new("https://crm.example.com/api/leads"))?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::io::http::{HttpSinkConfig, HttpSourceConfig, read_http_json_vec, write_http_json_vec};
+use crate::{Element, PCollection, Pipeline, from_vec};
+use anyhow::Result;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+/// Read every record of every page described by `config` into a `PCollection<T>`.
+///
+/// # Errors
+/// Returns an error if a `config://` reference does not resolve, or for the reasons
+/// of [`read_http_json_vec`].
+pub fn read_http_json<T>(p: &Pipeline, config: &HttpSourceConfig) -> Result<PCollection<T>>
+where
+    T: Element + DeserializeOwned,
+{
+    let config_url = config.url.clone();
+    let mut config = config.clone();
+    config.url = p.resolve_config(&config.url)?;
+    for (_, value) in &mut config.headers {
+        *value = p.resolve_config(value)?;
+    }
+    let records = read_http_json_vec(&config)?;
+    // The unresolved URL, so a `config://` reference is not expanded into the manifest.
+    Ok(from_vec(p, records).record_input("http-json", &config_url))
+}
+
+impl<T: Element + Serialize> PCollection<T> {
+    /// Execute the collection and POST it to `config.url` as JSON arrays of up to
+    /// `config.batch_size` elements. Returns the number of elements sent.
+    ///
+    /// # Errors
+    /// Returns an error if a `config://` reference does not resolve, the pipeline
+    /// fails, or for the reasons of [`write_http_json_vec`].
+    pub fn write_http_json(self, config: &HttpSinkConfig) -> Result<usize> {
+        let mut config = config.clone();
+        config.url = self.pipeline.resolve_config(&config.url)?;
+        for (_, value) in &mut config.headers {
+            *value = self.pipeline.resolve_config(value)?;
+        }
+        let data = self.collect_seq()?;
+        write_http_json_vec(&config, &data)
+    }
+}
@@ -0,0 +1,317 @@
+//! Batched model inference through [`IntelligenceIO`].
+//!
+//! [`predict_with`](PCollection::predict_with) scores every element of a collection with
+//! a hosted model: elements are encoded into [`InferenceInput`]s, sent
+//! `batch_size` at a time with [`predict_batch`](IntelligenceIO::predict_batch), and each
+//! [`InferenceOutput`] is decoded together with the element it belongs to.
+//!
+//! Failed batches are retried with [`retry_with_backoff`], so only transient errors
+//! (network, timeout, throttling) are retried. [`PredictOptions`] sets the retry policy
+//! and an optional limit on the number of batch requests per second.
+//!
+//! This takes the service by reference, so it executes the pipeline up to this point
+//! when called, as the file writers do, and returns the predictions as a new collection
+//! in the same pipeline.
+//!
+//! [`predict_with_shared`](PCollection::predict_with_shared) takes the service as an
+//! `Arc` and runs as a lazy step of the pipeline instead. Every partition is scored in
+//! its own batches on the worker running it, and all partitions share one rate limit.
+//!
+//! ```
+//! use ironbeam::io::cloud::{FakeIntelligenceIO, InferenceInput};
+//! use ironbeam::*;
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let model = FakeIntelligenceIO::new();
+//! model.register_model("length", |input| input.len().to_string().into_bytes());
+//!
+//! let p = Pipeline::default();
+//! let words = from_vec(&p, vec!["a".to_string(), "abc".to_string()]);
+//! let scored = words
+//!     .predict_with(
+//!         &model,
+//!         "length",
+//!         16,
+//!         |w: &String| Ok(InferenceInput { data: w.clone().into_bytes(), content_type: "text/plain".into() }),
+//!         |w: String, out| Ok((w, String::from_utf8(out.data)?.parse::<usize>()?)),
+//!     )?
+//!     .collect_seq()?;
+//! assert_eq!(scored, vec![("a".to_string(), 1), ("abc".to_string(), 3)]);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::io::cloud::traits::{InferenceInput, InferenceOutput, IntelligenceIO};
+use crate::io::cloud::utils::{RetryConfig, retry_with_backoff};
+use crate::{Element, PCollection, from_vec};
+use anyhow::{Context, Result, ensure};
+use std::sync::{Arc, Mutex};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+/// Options for [`PCollection::predict_with_options`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PredictOptions {
+    /// Retry policy for each batch request (default: [`RetryConfig::default`]).
+    pub retry: RetryConfig,
+    /// Maximum number of batch requests started per second, retries included (default:
+    /// unlimited).
+    pub max_requests_per_second: Option<f64>,
+}
+
+impl PredictOptions {
+    /// Set the retry policy.
+    #[must_use]
+    pub const fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Limit the number of batch requests started per second.
+    #[must_use]
+    pub const fn with_max_requests_per_second(mut self, rate: f64) -> Self {
+        self.max_requests_per_second = Some(rate);
+        self
+    }
+}
+
+/// Spaces calls at least `interval` apart.
+pub(crate) struct RateLimiter {
+    interval: Duration,
+    next: Option<Instant>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(max_per_second: Option<f64>) -> Self {
+        let interval = max_per_second
+            .filter(|rate| *rate > 0.0)
+            .map_or(Duration::ZERO, |rate| Duration::from_secs_f64(1.0 / rate));
+        Self {
+            interval,
+            next: None,
+        }
+    }
+
+    pub(crate) fn wait(&mut self) {
+        let now = Instant::now();
+        if let Some(next) = self.next
+            && next > now
+        {
+            sleep(next - now);
+        }
+        self.next = Some(Instant::now() + self.interval);
+    }
+}
+
+impl<T: Element> PCollection<T> {
+    /// Execute the collection and score it with `model_name`, `batch_size` elements per
+    /// [`predict_batch`](IntelligenceIO::predict_batch) call, using the default
+    /// [`PredictOptions`].
+    ///
+    /// See the [module documentation](crate::helpers::inference).
+    ///
+    /// # Errors
+    /// See [`predict_with_options`](PCollection::predict_with_options).
+    pub fn predict_with<O, E, D>(
+        self,
+        service: &dyn IntelligenceIO,
+        model_name: &str,
+        batch_size: usize,
+        encode: E,
+        decode: D,
+    ) -> Result<PCollection<O>>
+    where
+        O: Element,
+        E: Fn(&T) -> Result<InferenceInput>,
+        D: Fn(T, InferenceOutput) -> Result<O>,
+    {
+        self.predict_with_options(
+            service,
+            model_name,
+            batch_size,
+            encode,
+            decode,
+            &PredictOptions::default(),
+        )
+    }
+
+    /// Like [`predict_with`](PCollection::predict_with), with explicit
+    /// [`PredictOptions`] for retries and rate limiting.
+    ///
+    /// The output has one element per input element, in the same order.
+    ///
+    /// # Errors
+    /// Returns an error if `batch_size` is zero, the pipeline fails, an element fails
+    /// to encode or its output to decode, a batch request still fails after its retries,
+    /// or the service returns a different number of outputs than inputs.
+    pub fn predict_with_options<O, E, D>(
+        self,
+        service: &dyn IntelligenceIO,
+        model_name: &str,
+        batch_size: usize,
+        encode: E,
+        decode: D,
+        opts: &PredictOptions,
+    ) -> Result<PCollection<O>>
+    where
+        O: Element,
+        E: Fn(&T) -> Result<InferenceInput>,
+        D: Fn(T, InferenceOutput) -> Result<O>,
+    {
+        ensure!(batch_size > 0, "predict_with: batch_size must be positive");
+        let pipeline = self.pipeline.clone();
+        let elements = self.collect_seq()?;
+        let limiter = Mutex::new(RateLimiter::new(opts.max_requests_per_second));
+        let batches = Batches {
+            service,
+            model_name,
+            batch_size,
+            retry: &opts.retry,
+            limiter: &limiter,
+        };
+        let predictions = batches.predict(&encode, &decode, elements)?;
+        Ok(from_vec(&pipeline, predictions))
+    }
+
+    /// Score the collection with `model_name` as a lazy step of the pipeline, using the
+    /// default [`PredictOptions`].
+    ///
+    /// # Errors
+    /// See [`predict_with_options_shared`](PCollection::predict_with_options_shared).
+    pub fn predict_with_shared<O, E, D>(
+        self,
+        service: Arc<dyn IntelligenceIO>,
+        model_name: &str,
+        batch_size: usize,
+        encode: E,
+        decode: D,
+    ) -> Result<PCollection<O>>
+    where
+        O: Element,
+        E: 'static + Send + Sync + Fn(&T) -> Result<InferenceInput>,
+        D: 'static + Send + Sync + Fn(T, InferenceOutput) -> Result<O>,
+    {
+        self.predict_with_options_shared(
+            service,
+            model_name,
+            batch_size,
+            encode,
+            decode,
+            &PredictOptions::default(),
+        )
+    }
+
+    /// Like [`predict_with_options`](PCollection::predict_with_options), as a lazy step
+    /// of the pipeline.
+    ///
+    /// Nothing runs until the pipeline does. Each partition is sent in its own batches of
+    /// up to `batch_size` elements, on the worker running it, and
+    /// `max_requests_per_second` limits the requests of all partitions together. The
+    /// output keeps the order of the elements within each partition; element numbers in
+    /// error messages count from the start of the partition.
+    ///
+    /// An element that fails to encode or decode, a batch request that still fails after
+    /// its retries, or a miscounted response fails the step with
+    /// [`IronbeamError::TransformFailed`](crate::IronbeamError::TransformFailed).
+    ///
+    /// # Errors
+    /// Returns an error if `batch_size` is zero.
+    pub fn predict_with_options_shared<O, E, D>(
+        self,
+        service: Arc<dyn IntelligenceIO>,
+        model_name: &str,
+        batch_size: usize,
+        encode: E,
+        decode: D,
+        opts: &PredictOptions,
+    ) -> Result<PCollection<O>>
+    where
+        O: Element,
+        E: 'static + Send + Sync + Fn(&T) -> Result<InferenceInput>,
+        D: 'static + Send + Sync + Fn(T, InferenceOutput) -> Result<O>,
+    {
+        ensure!(batch_size > 0, "predict_with: batch_size must be positive");
+        let model_name = model_name.to_string();
+        let retry = opts.retry;
+        let limiter = Arc::new(Mutex::new(RateLimiter::new(opts.max_requests_per_second)));
+        Ok(self.try_map_partitions(move |elements| {
+            let batches = Batches {
+                service: service.as_ref(),
+                model_name: &model_name,
+                batch_size,
+                retry: &retry,
+                limiter: &limiter,
+            };
+            batches.predict(&encode, &decode, elements)
+        }))
+    }
+}
+
+/// The service, model, and request policy one run of a predict step sends batches with.
+struct Batches<'a> {
+    service: &'a dyn IntelligenceIO,
+    model_name: &'a str,
+    batch_size: usize,
+    retry: &'a RetryConfig,
+    limiter: &'a Mutex<RateLimiter>,
+}
+
+impl Batches<'_> {
+    /// Score `elements` `batch_size` at a time, in order.
+    fn predict<T, O, E, D>(&self, encode: &E, decode: &D, elements: Vec<T>) -> Result<Vec<O>>
+    where
+        E: Fn(&T) -> Result<InferenceInput>,
+        D: Fn(T, InferenceOutput) -> Result<O>,
+    {
+        let Self {
+            service,
+            model_name,
+            batch_size,
+            retry,
+            limiter,
+        } = *self;
+        let mut elements = elements.into_iter();
+        let mut predictions = Vec::with_capacity(elements.len());
+
+        let mut batch_index = 0;
+        loop {
+            let batch: Vec<T> = elements.by_ref().take(batch_size).collect();
+            if batch.is_empty() {
+                break;
+            }
+            let inputs = batch
+                .iter()
+                .enumerate()
+                .map(|(i, element)| {
+                    encode(element).with_context(|| {
+                        format!(
+                            "predict_with: encode element #{}",
+                            batch_index * batch_size + i
+                        )
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let outputs = retry_with_backoff(retry, || {
+                limiter.lock().expect("rate limiter mutex poisoned").wait();
+                service.predict_batch(model_name, inputs.clone())
+            })
+            .with_context(|| format!("predict_with: batch #{batch_index} on {model_name}"))?;
+            ensure!(
+                outputs.len() == batch.len(),
+                "predict_with: {model_name} returned {} outputs for {} inputs",
+                outputs.len(),
+                batch.len()
+            );
+            for (i, (element, output)) in batch.into_iter().zip(outputs).enumerate() {
+                predictions.push(decode(element, output).with_context(|| {
+                    format!(
+                        "predict_with: decode output #{}",
+                        batch_index * batch_size + i
+                    )
+                })?);
+            }
+            batch_index += 1;
+        }
+        Ok(predictions)
+    }
+}
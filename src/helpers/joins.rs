@@ -30,6 +30,24 @@
 //! - [`PCollection::join_left`](crate::PCollection::join_left) - Left outer join on the key
 //! - [`PCollection::join_right`](crate::PCollection::join_right) - Right outer join on the key
 //! - [`PCollection::join_full`](crate::PCollection::join_full) - Full outer join on the key
+//! - [`PCollection::join_many`](crate::PCollection::join_many) - N-way inner join on the key
+//! - [`PCollection::cross_join`](crate::PCollection::cross_join) - Cartesian product of two
+//!   collections, guarded against accidentally huge outputs
+//!
+//! ## Multi-way joins
+//! `join_many` builds a single n-ary `CoGroupN` node, so every input is materialized and
+//! shuffled exactly once. Chaining binary joins (`a.join_inner(&b).join_inner(&c)`) is also
+//! supported: the planner collapses the nested joins into one `CoGroupN`, replaying any
+//! stateless ops placed between them (see `OptimizationDecision::CollapsedJoinChain`).
+//!
+//! ## Cross joins
+//! `cross_join` pairs every element of one collection with every element of another, for
+//! small-table products such as parameter sweeps. Because the output grows as the product of
+//! the input sizes, the planner estimates it before running and fails with an error when it
+//! exceeds [`CROSS_JOIN_MAX_PAIRS`]; call
+//! [`allow_large`](crate::PCollection::allow_large) on the result to opt out. At run time
+//! the smaller side is broadcast: the larger side is split across threads and each thread
+//! pairs its elements with the whole smaller side.
 //!
 //! ### Notes
 //! - The co-group strategy avoids materializing the entire pipeline at once; each subplan is run
@@ -64,15 +82,20 @@
 //! ```
 
 use crate::bloom_filter::BloomFilter;
-use crate::node::Node;
+use crate::node::{CoGroupKind, Node};
 use crate::type_token::{TypeTag, vec_ops_for};
 use crate::{Element, NodeId, PCollection, Partition, Pipeline};
 use anyhow::{Result, anyhow};
+use rayon::prelude::*;
 use std::collections::{HashMap, HashSet};
 use std::hash::Hash;
 use std::marker::PhantomData;
 use std::sync::Arc;
 
+/// The largest estimated output, in pairs, that [`PCollection::cross_join`] accepts
+/// without [`PCollection::allow_large`].
+pub const CROSS_JOIN_MAX_PAIRS: usize = 10_000_000;
+
 /// Build a linear execution chain ending at `terminal` by snapshotting the pipeline
 /// and walking backwards through single-input edges.
 ///
@@ -237,8 +260,10 @@ where
             coalesce_right,
             exec,
             uses_bloom_semi_join: true,
+            kind: CoGroupKind::Keyed,
         });
         self.pipeline.connect(source_id, id);
+        self.pipeline.record_upstream(id, vec![self.id, right.id]);
         // CoGroup inputs are read as `kv<lp, lp>`; upgrade both predecessors
         // (mirrors `group_by_key`). The join's own output is the joined tuple.
         self.pipeline.set_kv_coder::<K, V>(self.id);
@@ -367,8 +392,10 @@ where
             coalesce_right,
             exec,
             uses_bloom_semi_join: true,
+            kind: CoGroupKind::Keyed,
         });
         self.pipeline.connect(source_id, id);
+        self.pipeline.record_upstream(id, vec![self.id, right.id]);
         // CoGroup inputs are read as `kv<lp, lp>`; upgrade both predecessors
         // (mirrors `group_by_key`). The join's own output is the joined tuple.
         self.pipeline.set_kv_coder::<K, V>(self.id);
@@ -498,8 +525,10 @@ where
             coalesce_right,
             exec,
             uses_bloom_semi_join: true,
+            kind: CoGroupKind::Keyed,
         });
         self.pipeline.connect(source_id, id);
+        self.pipeline.record_upstream(id, vec![self.id, right.id]);
         // CoGroup inputs are read as `kv<lp, lp>`; upgrade both predecessors
         // (mirrors `group_by_key`). The join's own output is the joined tuple.
         self.pipeline.set_kv_coder::<K, V>(self.id);
@@ -630,8 +659,10 @@ where
             coalesce_right,
             exec,
             uses_bloom_semi_join: false,
+            kind: CoGroupKind::Keyed,
         });
         self.pipeline.connect(source_id, id);
+        self.pipeline.record_upstream(id, vec![self.id, right.id]);
         // CoGroup inputs are read as `kv<lp, lp>`; upgrade both predecessors
         // (mirrors `group_by_key`). The join's own output is the joined tuple.
         self.pipeline.set_kv_coder::<K, V>(self.id);
@@ -643,4 +674,244 @@ where
             _t: PhantomData,
         }
     }
+
+    /// N-way inner join with other `(K, V)` collections -> `(K, Vec<V>)`.
+    ///
+    /// Emits one row for every combination of values that share a key across `self` and
+    /// **all** of `others`. The `Vec<V>` holds one value per input, in input order
+    /// (`self` first, then `others` in the order given). Keys missing from any input are
+    /// dropped.
+    ///
+    /// All inputs are co-grouped by a single n-ary node, so each one is shuffled exactly
+    /// once instead of re-shuffling intermediate join results.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ironbeam::*;
+    /// use anyhow::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let a = from_vec(&p, vec![("k".to_string(), 1u32), ("x".to_string(), 9)]);
+    /// let b = from_vec(&p, vec![("k".to_string(), 2u32)]);
+    /// let c = from_vec(&p, vec![("k".to_string(), 3u32), ("k".to_string(), 4)]);
+    ///
+    /// let joined = a.join_many(vec![&b, &c]).collect_seq_sorted()?;
+    /// assert_eq!(
+    ///     joined,
+    ///     vec![("k".to_string(), vec![1, 2, 3]), ("k".to_string(), vec![1, 2, 4])]
+    /// );
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if types are mismatched or if the chain building operation fails.
+    #[must_use]
+    pub fn join_many(&self, others: Vec<&Self>) -> PCollection<(K, Vec<V>)> {
+        let mut chains = vec![chain_from(&self.pipeline, self.id).expect("input chain build")];
+        for other in &others {
+            chains.push(chain_from(&other.pipeline, other.id).expect("input chain build"));
+        }
+
+        let coalesce_one = Arc::new(|parts: Vec<Partition>| -> Partition {
+            let mut out: Vec<(K, V)> = Vec::new();
+            for p in parts {
+                let mut v = *p.downcast::<Vec<(K, V)>>().expect("coalesce: wrong type");
+                out.append(&mut v);
+            }
+            Box::new(out) as Partition
+        }) as Arc<dyn Fn(Vec<Partition>) -> Partition + Send + Sync>;
+        let coalesce = vec![coalesce_one; chains.len()];
+
+        let exec = Arc::new(|inputs: Vec<Partition>| -> Partition {
+            let mut grouped: Vec<HashMap<K, Vec<V>>> = inputs
+                .into_iter()
+                .map(|part| {
+                    let rows = *part
+                        .downcast::<Vec<(K, V)>>()
+                        .expect("cogroupN exec: input type Vec<(K,V)>");
+                    let mut m: HashMap<K, Vec<V>> = HashMap::new();
+                    for (k, v) in rows {
+                        m.entry(k).or_default().push(v);
+                    }
+                    m
+                })
+                .collect();
+
+            // Drive the join from the input with the fewest distinct keys.
+            let Some(driver) = (0..grouped.len()).min_by_key(|&i| grouped[i].len()) else {
+                return Box::new(Vec::<(K, Vec<V>)>::new()) as Partition;
+            };
+            let driver_keys: Vec<K> = grouped[driver].keys().cloned().collect();
+
+            let mut out: Vec<(K, Vec<V>)> = Vec::new();
+            'keys: for k in driver_keys {
+                let mut combos: Vec<Vec<V>> = vec![Vec::with_capacity(grouped.len())];
+                for m in &mut grouped {
+                    let Some(vs) = m.remove(&k) else {
+                        continue 'keys;
+                    };
+                    combos = combos
+                        .into_iter()
+                        .flat_map(|prefix| {
+                            vs.iter().map(move |v| {
+                                let mut next = prefix.clone();
+                                next.push(v.clone());
+                                next
+                            })
+                        })
+                        .collect();
+                }
+                out.extend(combos.into_iter().map(|vs| (k.clone(), vs)));
+            }
+            Box::new(out) as Partition
+        });
+
+        let source_id = insert_dummy_source(&self.pipeline);
+        let id = self.pipeline.insert_node(Node::CoGroupN {
+            chains: Arc::new(chains),
+            coalesce,
+            exec,
+        });
+        self.pipeline.connect(source_id, id);
+        self.pipeline.record_upstream(
+            id,
+            std::iter::once(self.id)
+                .chain(others.iter().map(|o| o.id))
+                .collect(),
+        );
+        // Every input is read as `kv<lp, lp>` (mirrors the binary joins).
+        self.pipeline.set_kv_coder::<K, V>(self.id);
+        for other in &others {
+            self.pipeline.set_kv_coder::<K, V>(other.id);
+        }
+        self.pipeline.set_coder::<(K, Vec<V>)>(id);
+        PCollection {
+            pipeline: self.pipeline.clone(),
+            id,
+            _t: PhantomData,
+        }
+    }
+}
+
+impl<A: Element> PCollection<A> {
+    /// Cartesian product with another collection: every `(a, b)` pair, `|self| × |other|`
+    /// elements in all.
+    ///
+    /// Planning fails with an error when the estimated number of pairs exceeds
+    /// [`CROSS_JOIN_MAX_PAIRS`], unless [`allow_large`](Self::allow_large) is called on
+    /// the result. Estimates come from source sizes carried through the plan (see
+    /// [`Plan::explain_estimated`](crate::Plan::explain_estimated)); when a side's size
+    /// cannot be estimated, the guard does not apply.
+    ///
+    /// Both sides are materialized; the smaller one is then broadcast to parallel workers
+    /// that each take a slice of the larger one. Ordering of output rows is not guaranteed.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ironbeam::*;
+    /// use anyhow::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let learning_rates = from_vec(&p, vec![0.1f64, 0.01]);
+    /// let depths = from_vec(&p, vec![4u32, 8, 16]);
+    ///
+    /// let grid = learning_rates.cross_join(&depths).collect_seq()?;
+    /// assert_eq!(grid.len(), 6);
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if types are mismatched or if the chain building operation fails.
+    #[must_use]
+    pub fn cross_join<B: Element>(&self, other: &PCollection<B>) -> PCollection<(A, B)> {
+        let left_chain = chain_from(&self.pipeline, self.id).expect("left chain build");
+        let right_chain = chain_from(&other.pipeline, other.id).expect("right chain build");
+
+        let exec = Arc::new(|left_part: Partition, right_part: Partition| {
+            let left = *left_part
+                .downcast::<Vec<A>>()
+                .expect("cross join exec: left type Vec<A>");
+            let right = *right_part
+                .downcast::<Vec<B>>()
+                .expect("cross join exec: right type Vec<B>");
+
+            // Broadcast the smaller side to every worker over the larger side.
+            let out: Vec<(A, B)> = if left.len() >= right.len() {
+                left.par_iter()
+                    .flat_map_iter(|a| right.iter().map(move |b| (a.clone(), b.clone())))
+                    .collect()
+            } else {
+                right
+                    .par_iter()
+                    .flat_map_iter(|b| left.iter().map(move |a| (a.clone(), b.clone())))
+                    .collect()
+            };
+            Box::new(out) as Partition
+        });
+
+        let source_id = insert_dummy_source(&self.pipeline);
+        let id = self.pipeline.insert_node(Node::CoGroup {
+            left_chain: left_chain.into(),
+            right_chain: right_chain.into(),
+            coalesce_left: Arc::new(coalesce_vec::<A>),
+            coalesce_right: Arc::new(coalesce_vec::<B>),
+            exec,
+            uses_bloom_semi_join: false,
+            kind: CoGroupKind::Cross {
+                max_pairs: Some(CROSS_JOIN_MAX_PAIRS),
+            },
+        });
+        self.pipeline.connect(source_id, id);
+        self.pipeline.record_upstream(id, vec![self.id, other.id]);
+        self.pipeline.set_coder::<(A, B)>(id);
+        PCollection {
+            pipeline: self.pipeline.clone(),
+            id,
+            _t: PhantomData,
+        }
+    }
+
+    /// Lift the size guard of the [`cross_join`](Self::cross_join) that produced this
+    /// collection, so it runs however many pairs it is estimated to produce.
+    ///
+    /// Call it directly on the cross join's output, before deriving other collections
+    /// from it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this collection is not the output of `cross_join`.
+    #[must_use]
+    pub fn allow_large(self) -> Self {
+        let lifted = self.pipeline.update_node(self.id, |node| match node {
+            Node::CoGroup {
+                kind: CoGroupKind::Cross { max_pairs },
+                ..
+            } => {
+                *max_pairs = None;
+                true
+            }
+            _ => false,
+        });
+        assert!(
+            lifted == Some(true),
+            "allow_large: the collection is not the output of `cross_join`"
+        );
+        self
+    }
+}
+
+/// Concatenate the partitions of one cross join input into a single `Vec<T>`.
+fn coalesce_vec<T: Element>(parts: Vec<Partition>) -> Partition {
+    let mut out: Vec<T> = Vec::new();
+    for p in parts {
+        let mut v = *p
+            .downcast::<Vec<T>>()
+            .expect("cross join coalesce: wrong type");
+        out.append(&mut v);
+    }
+    Box::new(out) as Partition
 }
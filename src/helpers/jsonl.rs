@@ -5,9 +5,14 @@
 //!
 //! ## Available operations
 //! - [`read_jsonl`] - Read the entire file into memory as typed `PCollection<T>`
+//! - [`read_jsonl_lenient`] - Like `read_jsonl`, but bad lines are collected instead of failing the read
+//! - [`read_jsonl_timestamped`] - Like `read_jsonl`, but stamps each record with its [`EventTime`]
+//! - [`read_jsonl_checked`] - Like `read_jsonl`, but a glob's files must share their top-level keys
 //! - [`read_jsonl_streaming`] - Build a streaming source with pre-scanned line ranges
-//! - [`PCollection::write_jsonl`](PCollection::write_jsonl) - Execute and write sequentially
-//! - [`PCollection::write_jsonl_par`](PCollection::write_jsonl_par) - Execute sequentially, write in parallel (feature: `parallel-io`)
+//! - [`PCollection::write_jsonl`](PCollection::write_jsonl) - Execute and write, serializing each partition in the run
+//! - [`PCollection::write_jsonl_with`](PCollection::write_jsonl_with) - Same, with explicit
+//!   [`WriteOptions`] (e.g., compression codec and level)
+//! - [`PCollection::write_jsonl_par`](PCollection::write_jsonl_par) - Same, with an explicit number of partitions (feature: `parallel-io`)
 //!
 //! ### Feature gates
 //! - These helpers are **always available in the ABI**. When the `io-jsonl`
@@ -65,15 +70,22 @@
 //! # Ok(()) }
 //! ```
 
+use crate::helpers::file_sink::EncodedPart;
+use crate::helpers::timestamped::EventTime;
+use crate::io::compression::{Compression, WriteOptions};
+use crate::io::drift::{SchemaDriftPolicy, check_drift};
 use crate::io::glob::expand_glob;
+use crate::io::jsonl::{JsonlLineError, encode_jsonl_part, read_jsonl_vec_lenient};
 pub use crate::io::jsonl::{JsonlShards, JsonlVecOps, build_jsonl_shards, write_jsonl_vec};
 use crate::node::Node;
 use crate::type_token::TypeTag;
-use crate::{Element, PCollection, Pipeline, from_vec, read_jsonl_vec};
+use crate::{Element, PCollection, Pipeline, Timestamped, from_vec, read_jsonl_vec};
 use anyhow::{Context, Result, anyhow, bail};
 use regex::Regex;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::BTreeSet;
 use std::marker::PhantomData;
 use std::path::Path;
 use std::sync::Arc;
@@ -141,44 +153,281 @@ pub fn read_jsonl<T>(p: &Pipeline, path: impl AsRef<Path>) -> Result<PCollection
 where
     T: Element + DeserializeOwned,
 {
+    let path = p.resolve_path(path.as_ref())?;
+    let data: Vec<T> = read_jsonl_files(&path)?;
+    Ok(from_vec(p, data).record_input("jsonl", path.display()))
+}
+
+/// Read JSONL file(s) like [`read_jsonl`], stamping each record with its event time
+/// as it is read, so windowing can follow without an
+/// [`attach_timestamps`](PCollection::attach_timestamps) step.
+///
+/// # Panics
+///
+/// Panics if the internal glob-detection regex cannot be compiled — this is
+/// not reachable in practice because the pattern is a compile-time constant.
+///
+/// # Errors
+///
+/// Returns an error for the reasons of [`read_jsonl`], or if an
+/// [`EventTime::Field`] is missing from a record or does not hold a millisecond
+/// timestamp.
+///
+/// # Example
+/// ```no_run
+/// use ironbeam::*;
+/// use serde::{Deserialize, Serialize};
+/// use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// #[derive(Serialize, Deserialize, Clone)]
+/// struct Click { ts_ms: u64, user: String }
+///
+/// let p = Pipeline::default();
+/// let per_minute = read_jsonl_timestamped::<Click>(&p, "clicks/*.jsonl", &EventTime::field("ts_ms"))?
+///     .count_per_window(60_000, 0);
+/// # Ok(()) }
+/// ```
+pub fn read_jsonl_timestamped<T>(
+    p: &Pipeline,
+    path: impl AsRef<Path>,
+    time: &EventTime<T>,
+) -> Result<PCollection<Timestamped<T>>>
+where
+    T: Element + DeserializeOwned + Serialize,
+{
+    let path = p.resolve_path(path.as_ref())?;
+    let data = time
+        .stamp(read_jsonl_files(&path)?)
+        .with_context(|| format!("reading {}", path.display()))?;
+    Ok(from_vec(p, data).record_input("jsonl", path.display()))
+}
+
+/// Read a JSONL file, or every file a glob pattern matches, in path order.
+fn read_jsonl_files<T: DeserializeOwned>(path: &Path) -> Result<Vec<T>> {
     let path_str = path
-        .as_ref()
         .to_str()
         .ok_or_else(|| anyhow!("path contains invalid UTF-8"))?;
 
     let glob_regex = Regex::new(r"[*?\[]").expect("valid glob regex");
-    if glob_regex.is_match(path_str) {
-        let files =
-            expand_glob(path_str).with_context(|| format!("expanding glob pattern: {path_str}"))?;
+    if !glob_regex.is_match(path_str) {
+        return read_jsonl_vec(path);
+    }
+    let files =
+        expand_glob(path_str).with_context(|| format!("expanding glob pattern: {path_str}"))?;
+    if files.is_empty() {
+        bail!("no files found matching pattern: {path_str}");
+    }
 
-        if files.is_empty() {
-            bail!("no files found matching pattern: {path_str}");
-        }
+    let mut all_data = Vec::new();
+    for file in files {
+        let data: Vec<T> =
+            read_jsonl_vec(&file).with_context(|| format!("reading {}", file.display()))?;
+        all_data.extend(data);
+    }
+    Ok(all_data)
+}
+
+/// Read one or more JSONL files, collecting lines that fail to parse instead of
+/// aborting.
+///
+/// Returns `(rows, errors)`. Each error is `(line_number, raw_line, error)`, with
+/// 1-based line numbers; filter or ignore `errors` to skip bad lines, or write them
+/// out for inspection. Glob patterns are supported as in [`read_jsonl`]; there, each
+/// error message is prefixed with the path of the file it came from.
+///
+/// # Panics
+///
+/// Panics if the internal glob-detection regex cannot be compiled — this is
+/// not reachable in practice because the pattern is a compile-time constant.
+///
+/// # Errors
+///
+/// Returns an error if `path` contains invalid UTF-8, if a glob pattern does
+/// not match any files, or if any matched file cannot be opened or read.
+///
+/// # Example
+/// ```no_run
+/// use ironbeam::*;
+/// use serde::{Deserialize, Serialize};
+/// use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// #[derive(Serialize, Deserialize, Clone)]
+/// struct Row { k: String, v: u64 }
+///
+/// let p = Pipeline::default();
+/// let (rows, errors) = read_jsonl_lenient::<Row>(&p, "vendor/feed.jsonl")?;
+/// for (line, _raw, err) in errors.collect_seq()? {
+///     eprintln!("skipped line {line}: {err}");
+/// }
+/// # Ok(()) }
+/// ```
+pub fn read_jsonl_lenient<T>(
+    p: &Pipeline,
+    path: impl AsRef<Path>,
+) -> Result<(PCollection<T>, PCollection<JsonlLineError>)>
+where
+    T: Element + DeserializeOwned,
+{
+    let path = p.resolve_path(path.as_ref())?;
+    let path_str = path
+        .to_str()
+        .ok_or_else(|| anyhow!("path contains invalid UTF-8"))?;
 
-        let mut all_data = Vec::new();
-        for file in files {
-            let data: Vec<T> =
-                read_jsonl_vec(&file).with_context(|| format!("reading {}", file.display()))?;
-            all_data.extend(data);
+    let glob_regex = Regex::new(r"[*?\[]").expect("valid glob regex");
+    if !glob_regex.is_match(path_str) {
+        let (rows, errors) = read_jsonl_vec_lenient::<T>(&path)?;
+        return Ok((
+            from_vec(p, rows).record_input("jsonl", path.display()),
+            from_vec(p, errors).record_input("jsonl", path.display()),
+        ));
+    }
+    let files =
+        expand_glob(path_str).with_context(|| format!("expanding glob pattern: {path_str}"))?;
+    if files.is_empty() {
+        bail!("no files found matching pattern: {path_str}");
+    }
+
+    let mut all_rows = Vec::new();
+    let mut all_errors = Vec::new();
+    for file in files {
+        let (rows, errors) = read_jsonl_vec_lenient::<T>(&file)
+            .with_context(|| format!("reading {}", file.display()))?;
+        all_rows.extend(rows);
+        all_errors.extend(
+            errors
+                .into_iter()
+                .map(|(line, raw, err)| (line, raw, format!("{}: {err}", file.display()))),
+        );
+    }
+    Ok((
+        from_vec(p, all_rows).record_input("jsonl", path.display()),
+        from_vec(p, all_errors).record_input("jsonl", path.display()),
+    ))
+}
+
+/// Read one or more JSONL files like [`read_jsonl`], checking that a glob's files share
+/// a schema before deserializing any record.
+///
+/// A file's schema is the set of top-level keys across all its records, so key order
+/// and keys that only some records carry do not count as drift; a key that no record
+/// of a file has, but the first file's records do (or the other way round), does.
+/// Under [`SchemaDriftPolicy::Fail`] such a file fails the read with a
+/// [`SchemaDriftError`](crate::io::drift::SchemaDriftError) naming the added and
+/// missing keys. Records are matched to fields by name either way, so
+/// [`SchemaDriftPolicy::AlignByName`] and [`SchemaDriftPolicy::Ignore`] both read
+/// every file as it is. A single path is read without a check.
+///
+/// # Panics
+///
+/// Panics if the internal glob-detection regex cannot be compiled — this is
+/// not reachable in practice because the pattern is a compile-time constant.
+///
+/// # Errors
+///
+/// Returns an error if `path` contains invalid UTF-8, if a glob pattern does
+/// not match any files, if any matched file cannot be read or parsed, or if the
+/// files' keys drift under [`SchemaDriftPolicy::Fail`].
+///
+/// # Example
+/// ```no_run
+/// use ironbeam::*;
+/// use ironbeam::io::drift::SchemaDriftPolicy;
+/// use serde::{Deserialize, Serialize};
+/// use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// #[derive(Serialize, Deserialize, Clone)]
+/// struct Row { k: String, v: u64 }
+///
+/// let p = Pipeline::default();
+/// let rows = read_jsonl_checked::<Row>(&p, "exports/*.jsonl", SchemaDriftPolicy::Fail)?;
+/// # Ok(()) }
+/// ```
+pub fn read_jsonl_checked<T>(
+    p: &Pipeline,
+    path: impl AsRef<Path>,
+    policy: SchemaDriftPolicy,
+) -> Result<PCollection<T>>
+where
+    T: Element + DeserializeOwned,
+{
+    let path = p.resolve_path(path.as_ref())?;
+    let path_str = path
+        .to_str()
+        .ok_or_else(|| anyhow!("path contains invalid UTF-8"))?;
+
+    let glob_regex = Regex::new(r"[*?\[]").expect("valid glob regex");
+    if !glob_regex.is_match(path_str) {
+        return Ok(from_vec(p, read_jsonl_vec::<T>(&path)?).record_input("jsonl", path.display()));
+    }
+    let files =
+        expand_glob(path_str).with_context(|| format!("expanding glob pattern: {path_str}"))?;
+    if files.is_empty() {
+        bail!("no files found matching pattern: {path_str}");
+    }
+
+    let mut records = Vec::with_capacity(files.len());
+    let mut schemas = Vec::with_capacity(files.len());
+    for file in files {
+        let values: Vec<Value> =
+            read_jsonl_vec(&file).with_context(|| format!("reading {}", file.display()))?;
+        let keys: BTreeSet<&String> = values
+            .iter()
+            .filter_map(Value::as_object)
+            .flat_map(|record| record.keys())
+            .collect();
+        schemas.push((file.clone(), keys.into_iter().cloned().collect()));
+        records.push((file, values));
+    }
+    check_drift(&schemas, policy)?;
+
+    let mut all_data = Vec::new();
+    for (file, values) in records {
+        for (i, value) in values.into_iter().enumerate() {
+            let row: T = serde_json::from_value(value).with_context(|| {
+                format!("deserialize JSONL record #{} in {}", i + 1, file.display())
+            })?;
+            all_data.push(row);
         }
-        Ok(from_vec(p, all_data))
-    } else {
-        let data: Vec<T> = read_jsonl_vec(path)?;
-        Ok(from_vec(p, data))
     }
+    Ok(from_vec(p, all_data).record_input("jsonl", path.display()))
 }
 
 impl<T: Element + Serialize> PCollection<T> {
-    /// Execute the collection and write it to a JSONL file (sequential).
+    /// Execute the collection and write it to a JSONL file.
     ///
-    /// Returns the number of records written. The output order matches the
-    /// collection's sequential execution order.
+    /// Each partition is serialized by the runner as the last step of the pipeline
+    /// (see [`file_sink`](crate::helpers::file_sink)), and the parts are concatenated
+    /// in partition order. Returns the number of records written.
     ///
     /// ### Errors
-    /// Propagates I/O and serialization errors.
+    /// Propagates execution, I/O and serialization errors.
     pub fn write_jsonl(self, path: impl AsRef<Path>) -> Result<usize> {
-        let data = self.collect_seq()?;
-        write_jsonl_vec(path, &data)
+        self.write_jsonl_with(path, &WriteOptions::default())
+    }
+
+    /// Execute the collection and write it to a JSONL file with explicit
+    /// [`WriteOptions`], e.g., to force a compression codec and level.
+    ///
+    /// ### Errors
+    /// Propagates execution, I/O, compression-setup and serialization errors.
+    pub fn write_jsonl_with(self, path: impl AsRef<Path>, opts: &WriteOptions) -> Result<usize> {
+        self.write_jsonl_parts(path.as_ref(), None, *opts)
+    }
+
+    /// Write through a `write_jsonl` encode step over `partitions`.
+    fn write_jsonl_parts(
+        self,
+        path: &Path,
+        partitions: Option<usize>,
+        opts: WriteOptions,
+    ) -> Result<usize> {
+        self.write_encoded("write_jsonl", path, partitions, opts, |rows| {
+            Ok(EncodedPart {
+                rows: rows.len(),
+                header: 0,
+                bytes: encode_jsonl_part(rows)?,
+            })
+        })
     }
 }
 
@@ -214,6 +463,7 @@ pub fn read_jsonl_streaming<T>(
 where
     T: Element + DeserializeOwned,
 {
+    let location = path.as_ref().display().to_string();
     let shards: JsonlShards = build_jsonl_shards(path, lines_per_shard)?;
     let id = p.insert_node(Node::Source {
         payload: Arc::new(shards),
@@ -221,6 +471,7 @@ where
         elem_tag: TypeTag::of::<T>(),
     });
     p.set_coder::<T>(id);
+    p.record_input(id, "jsonl", location);
     Ok(PCollection {
         pipeline: p.clone(),
         id,
@@ -231,17 +482,17 @@ where
 #[cfg_attr(docsrs, doc(cfg(feature = "parallel-io")))]
 #[cfg(feature = "parallel-io")]
 impl<T: Element + Serialize> PCollection<T> {
-    /// Execute the collection sequentially (to lock in a deterministic order),
-    /// then write JSONL **in parallel** while preserving that order.
+    /// Like [`write_jsonl`](Self::write_jsonl), with the run split into `shards`
+    /// partitions, each serialized in parallel; `None` uses the runner's default.
     ///
-    /// `shards = Some(n)` sets the number of writer shards; `None` uses a sensible default.
+    /// The parts are concatenated in partition order, so the file is the same for any
+    /// shard count when the pipeline preserves element order.
     ///
     /// Returns the number of records written.
     ///
     /// ### Errors
-    /// Propagates I/O and serialization errors.
+    /// Propagates execution, I/O and serialization errors.
     pub fn write_jsonl_par(self, path: impl AsRef<Path>, shards: Option<usize>) -> Result<usize> {
-        let data = self.collect_seq()?; // deterministic order of elements
-        crate::io::jsonl::write_jsonl_par(path, &data, shards)
+        self.write_jsonl_parts(path.as_ref(), shards, WriteOptions::default())
     }
 }
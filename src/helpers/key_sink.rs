@@ -0,0 +1,341 @@
+//! File writes that split a keyed collection into one file per key.
+//!
+//! [`write_jsonl_per_key`](PCollection::write_jsonl_per_key) and
+//! [`write_csv_per_key`](PCollection::write_csv_per_key) write a `PCollection<(K, V)>`
+//! as one file per distinct key under a directory (one export per tenant, say), with
+//! the values as rows. Like the single-file writers (see
+//! [`file_sink`](crate::helpers::file_sink)), each partition is grouped by key and
+//! serialized in an encode step of the pipeline, to part files in the system's
+//! temporary directory; every key's rows are then written in partition order, so a
+//! file's contents do not depend on thread scheduling. Each file is written atomically.
+//!
+//! # File names
+//! A key's file is `{dir}/{name}.{extension}`, where `name` is the key's `Display`
+//! form made safe for any filesystem: characters other than ASCII letters, digits,
+//! `-`, `_` and `.` become `_`, names are cut to [`MAX_FILE_STEM_LEN`] bytes, and a
+//! name that is empty, starts with `.` (including `.` and `..`) or is a reserved
+//! Windows device name such as `CON` gets a leading `_`. A key therefore never writes
+//! outside `dir`. If two keys map to the same name (ignoring case, for
+//! case-insensitive filesystems) the write fails before any file is created.
+//!
+//! # Open files
+//! Files are written in parallel, at most [`PerKeyWriteOptions::max_open_files`] at a
+//! time, so a collection with many thousands of keys stays within the process's file
+//! handle limit.
+//!
+//! Files already in `dir` are left alone: a key missing from this run keeps the file
+//! of an earlier one.
+//!
+//! ```no_run
+//! use ironbeam::*;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Clone, Serialize, Deserialize)]
+//! struct Invoice {
+//!     number: u64,
+//!     cents: i64,
+//! }
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let p = Pipeline::default();
+//! let invoices = from_vec(
+//!     &p,
+//!     vec![
+//!         ("acme".to_string(), Invoice { number: 1, cents: 1200 }),
+//!         ("globex".to_string(), Invoice { number: 2, cents: 800 }),
+//!     ],
+//! );
+//! let opts = PerKeyWriteOptions::default().with_max_open_files(8);
+//! let files = invoices.write_csv_per_key("exports/invoices", true, &opts)?;
+//! assert_eq!(files[0].path.file_name().unwrap(), "acme.csv");
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::helpers::file_sink::{EncodedPart, PartFile, PartWriter, write_parts};
+use crate::io::atomic::SUCCESS_MARKER;
+use crate::io::compression::{Compression, WriteOptions};
+use crate::io::csv::encode_csv_part;
+use crate::io::jsonl::encode_jsonl_part;
+use crate::{Element, PCollection};
+use anyhow::{Context, Result, bail};
+use rayon::ThreadPoolBuilder;
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::env::temp_dir;
+use std::fmt::Display;
+use std::fs::{File, create_dir_all};
+use std::path::{Path, PathBuf};
+
+/// Default [`PerKeyWriteOptions::max_open_files`].
+pub const DEFAULT_MAX_OPEN_FILES: usize = 16;
+
+/// Longest file name, in bytes and without the extension, that a key maps to.
+pub const MAX_FILE_STEM_LEN: usize = 200;
+
+/// Device names Windows reserves in every directory, with or without an extension.
+const RESERVED_STEMS: [&str; 22] = [
+    "CON", "PRN", "AUX", "NUL", "COM1", "COM2", "COM3", "COM4", "COM5", "COM6", "COM7", "COM8",
+    "COM9", "LPT1", "LPT2", "LPT3", "LPT4", "LPT5", "LPT6", "LPT7", "LPT8", "LPT9",
+];
+
+/// Options for the per-key file writers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PerKeyWriteOptions {
+    /// Most output files open at once (default: [`DEFAULT_MAX_OPEN_FILES`]).
+    pub max_open_files: usize,
+    /// File extension, without the leading dot (default: the format's, `jsonl` or
+    /// `csv`). With [`Compression::Auto`], an extension such as `csv.gz` also picks the
+    /// codec.
+    pub extension: Option<String>,
+    /// Output compression (default: [`Compression::Auto`]).
+    pub compression: Compression,
+    /// Number of partitions the collection is encoded in (default: the runner's
+    /// default partition count).
+    pub shards: Option<usize>,
+    /// Create an empty `_SUCCESS` file in the directory once every file is in place
+    /// (default: `false`).
+    pub success_marker: bool,
+}
+
+impl Default for PerKeyWriteOptions {
+    fn default() -> Self {
+        Self {
+            max_open_files: DEFAULT_MAX_OPEN_FILES,
+            extension: None,
+            compression: Compression::Auto,
+            shards: None,
+            success_marker: false,
+        }
+    }
+}
+
+impl PerKeyWriteOptions {
+    /// Set the most output files open at once (at least 1).
+    #[must_use]
+    pub fn with_max_open_files(mut self, max_open_files: usize) -> Self {
+        self.max_open_files = max_open_files.max(1);
+        self
+    }
+
+    /// Set the file extension, without the leading dot, e.g. `"csv.gz"`.
+    #[must_use]
+    pub fn with_extension(mut self, extension: impl Into<String>) -> Self {
+        self.extension = Some(extension.into());
+        self
+    }
+
+    /// Set the output compression.
+    #[must_use]
+    pub const fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Set the number of partitions the collection is encoded in.
+    #[must_use]
+    pub const fn with_shards(mut self, shards: usize) -> Self {
+        self.shards = Some(shards);
+        self
+    }
+
+    /// Set whether to create a `_SUCCESS` marker once every file is in place.
+    #[must_use]
+    pub const fn with_success_marker(mut self, success_marker: bool) -> Self {
+        self.success_marker = success_marker;
+        self
+    }
+}
+
+/// One file written by a per-key writer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyedFile {
+    /// The key, in its `Display` form.
+    pub key: String,
+    /// Where the key's rows were written.
+    pub path: PathBuf,
+    /// Number of rows written.
+    pub rows: usize,
+}
+
+/// The rows of one key within one partition, serialized by a per-key write node.
+#[derive(Clone, Serialize, Deserialize)]
+struct KeyedPart {
+    key: String,
+    part: PartFile,
+}
+
+impl<K, V> PCollection<(K, V)>
+where
+    K: Element + Display,
+    V: Element + Serialize,
+{
+    /// Execute the collection and write each key's values to its own JSONL file under
+    /// `dir`. See the [module documentation](crate::helpers::key_sink) for file naming.
+    ///
+    /// Returns one [`KeyedFile`] per key, ordered by key.
+    ///
+    /// # Errors
+    /// Returns an error if the pipeline fails, a value fails to serialize, two keys map
+    /// to the same file name, or a file cannot be written.
+    pub fn write_jsonl_per_key(
+        self,
+        dir: impl AsRef<Path>,
+        opts: &PerKeyWriteOptions,
+    ) -> Result<Vec<KeyedFile>> {
+        let dir = self.pipeline.resolve_path(dir.as_ref())?;
+        let (_part_dir, parts) = self.encode_parts(
+            "write_jsonl_per_key",
+            opts.shards,
+            &temp_dir(),
+            |rows, parts| {
+                encode_by_key(rows, parts, |values| {
+                    Ok(EncodedPart {
+                        rows: values.len(),
+                        header: 0,
+                        bytes: encode_jsonl_part(values)?,
+                    })
+                })
+            },
+        )?;
+        write_key_files(&dir, "jsonl", parts, opts)
+    }
+
+    /// Execute the collection and write each key's values to its own CSV file under
+    /// `dir`, each starting with a header row if `has_headers`. See the
+    /// [module documentation](crate::helpers::key_sink) for file naming.
+    ///
+    /// Returns one [`KeyedFile`] per key, ordered by key.
+    ///
+    /// # Errors
+    /// Returns an error if the pipeline fails, a value fails to serialize, two keys map
+    /// to the same file name, or a file cannot be written.
+    pub fn write_csv_per_key(
+        self,
+        dir: impl AsRef<Path>,
+        has_headers: bool,
+        opts: &PerKeyWriteOptions,
+    ) -> Result<Vec<KeyedFile>> {
+        let dir = self.pipeline.resolve_path(dir.as_ref())?;
+        let (_part_dir, parts) = self.encode_parts(
+            "write_csv_per_key",
+            opts.shards,
+            &temp_dir(),
+            move |rows, parts| {
+                encode_by_key(rows, parts, |values| {
+                    let (header, bytes) = encode_csv_part(values, has_headers)?;
+                    Ok(EncodedPart {
+                        rows: values.len(),
+                        header,
+                        bytes,
+                    })
+                })
+            },
+        )?;
+        write_key_files(&dir, "csv", parts, opts)
+    }
+}
+
+/// Group a partition's rows by key, in order of first appearance, and encode each
+/// key's values to a part file.
+fn encode_by_key<K: Display, V>(
+    rows: &[(K, V)],
+    parts: &PartWriter,
+    encode: impl Fn(&[&V]) -> Result<EncodedPart>,
+) -> Result<Vec<KeyedPart>> {
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut groups: Vec<(String, Vec<&V>)> = Vec::new();
+    for (key, value) in rows {
+        let key = key.to_string();
+        let i = *index.entry(key.clone()).or_insert_with(|| {
+            groups.push((key, Vec::new()));
+            groups.len() - 1
+        });
+        groups[i].1.push(value);
+    }
+    groups
+        .into_iter()
+        .map(|(key, values)| {
+            let part = encode(&values).with_context(|| format!("key {key:?}"))?;
+            let part = parts.write(part)?;
+            Ok(KeyedPart { key, part })
+        })
+        .collect()
+}
+
+/// Write every key's parts, in partition order, to its file under `dir`.
+fn write_key_files(
+    dir: &Path,
+    default_extension: &str,
+    parts: Vec<Vec<KeyedPart>>,
+    opts: &PerKeyWriteOptions,
+) -> Result<Vec<KeyedFile>> {
+    let mut by_key: BTreeMap<String, Vec<PartFile>> = BTreeMap::new();
+    for KeyedPart { key, part } in parts.into_iter().flatten() {
+        by_key.entry(key).or_default().push(part);
+    }
+
+    let extension = opts.extension.as_deref().unwrap_or(default_extension);
+    let mut claimed: HashMap<String, &str> = HashMap::new();
+    let mut files = Vec::with_capacity(by_key.len());
+    for (key, key_parts) in &by_key {
+        let stem = file_stem(key);
+        if let Some(other) = claimed.insert(stem.to_lowercase(), key) {
+            bail!("keys {other:?} and {key:?} both map to the file name {stem:?}");
+        }
+        files.push((key, dir.join(format!("{stem}.{extension}")), key_parts));
+    }
+
+    create_dir_all(dir).with_context(|| format!("mkdir -p {}", dir.display()))?;
+    let write = WriteOptions::default().with_compression(opts.compression);
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(opts.max_open_files.max(1))
+        .thread_name(|i| format!("ironbeam-key-sink-{i}"))
+        .build()
+        .context("per-key write: failed to start the thread pool")?;
+    let written = pool.install(|| {
+        files
+            .par_iter()
+            .with_max_len(1)
+            .map(|(key, path, key_parts)| {
+                let rows = write_parts(path, key_parts, write)?;
+                Ok(KeyedFile {
+                    key: (*key).clone(),
+                    path: path.clone(),
+                    rows,
+                })
+            })
+            .collect::<Result<Vec<_>>>()
+    })?;
+    if opts.success_marker {
+        let marker = dir.join(SUCCESS_MARKER);
+        File::create(&marker).with_context(|| format!("create {}", marker.display()))?;
+    }
+    Ok(written)
+}
+
+/// File name, without extension, for `key`.
+fn file_stem(key: &str) -> String {
+    let mut stem: String = key
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    stem.truncate(MAX_FILE_STEM_LEN);
+    let device = stem.split('.').next().unwrap_or_default();
+    if stem.is_empty()
+        || stem.starts_with('.')
+        || RESERVED_STEMS
+            .iter()
+            .any(|r| r.eq_ignore_ascii_case(device))
+    {
+        stem.insert(0, '_');
+    }
+    stem
+}
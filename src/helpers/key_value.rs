@@ -0,0 +1,158 @@
+//! [`KeyValueIO`] sinks and lookups.
+//!
+//! - [`write_key_value`](PCollection::write_key_value) stores a collection of
+//!   `(key, document)` pairs in a key-value collection with
+//!   [`batch_put`](KeyValueIO::batch_put).
+//! - [`enrich_from_key_value`](PCollection::enrich_from_key_value) pairs every element
+//!   with the stored document under its key, fetched with
+//!   [`batch_get`](KeyValueIO::batch_get).
+//!
+//! `write_key_value` takes the store by reference and executes the pipeline up to this
+//! point when called, as the file writers do. `enrich_from_key_value` takes the store as
+//! an `Arc` and adds a lazy step to the pipeline instead: nothing is fetched until the
+//! pipeline runs, and then every partition fetches its keys on the worker running it.
+//! Both resolve `config://` references in `collection` through the pipeline's
+//! [`ConfigResolver`](crate::io::cloud::ConfigResolver) when called.
+//!
+//! ```
+//! use ironbeam::io::cloud::{FakeKeyValueIO, KeyValueIO};
+//! use ironbeam::*;
+//! use std::collections::HashMap;
+//! use std::sync::Arc;
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let store = FakeKeyValueIO::new();
+//! let p = Pipeline::default();
+//! let users = from_vec(&p, vec![
+//!     ("u1".to_string(), HashMap::from([("name".to_string(), "Ada".to_string())])),
+//! ]);
+//! users.write_key_value(&store, "users", 100)?;
+//!
+//! let orders = from_vec(&p, vec![("u1".to_string(), 30u32), ("u2".to_string(), 5)]);
+//! let enriched = orders
+//!     .enrich_from_key_value(Arc::new(store), "users", |(user, _): &(String, u32)| {
+//!         user.clone()
+//!     })?
+//!     .collect_seq()?;
+//! assert_eq!(enriched[0].1.as_ref().unwrap()["name"], "Ada");
+//! assert!(enriched[1].1.is_none());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::io::cloud::traits::KeyValueIO;
+use crate::{Element, PCollection};
+use anyhow::{Context, Result, ensure};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Number of keys [`enrich_from_key_value`](PCollection::enrich_from_key_value) fetches
+/// per [`batch_get`](KeyValueIO::batch_get) call.
+pub const ENRICH_BATCH_SIZE: usize = 100;
+
+/// A stored document's fields, as [`KeyValueIO`] reads and writes them.
+pub type KeyValueDocument = HashMap<String, String>;
+
+impl PCollection<(String, KeyValueDocument)> {
+    /// Execute the collection and store every `(key, document)` pair in `collection`,
+    /// `batch_size` documents per [`batch_put`](KeyValueIO::batch_put) call.
+    ///
+    /// Batches are written in collection order, so when a key appears more than once the
+    /// last document wins. Returns the number of documents written.
+    ///
+    /// # Errors
+    /// Returns an error if `batch_size` is zero, the pipeline fails, or a `batch_put`
+    /// call fails. Batches before the failing one have already been written.
+    pub fn write_key_value(
+        self,
+        store: &dyn KeyValueIO,
+        collection: &str,
+        batch_size: usize,
+    ) -> Result<usize> {
+        ensure!(
+            batch_size > 0,
+            "write_key_value: batch_size must be positive"
+        );
+        let collection = self.pipeline.resolve_config(collection)?;
+        let documents = self.collect_seq()?;
+        for (i, batch) in documents.chunks(batch_size).enumerate() {
+            store
+                .batch_put(&collection, batch.to_vec())
+                .with_context(|| format!("write_key_value: batch #{i} to {collection}"))?;
+        }
+        Ok(documents.len())
+    }
+}
+
+impl<T: Element> PCollection<T> {
+    /// Pair every element with the document stored under `key_fn(element)` in
+    /// `collection`, or `None` if there is none.
+    ///
+    /// This is a lazy step of the pipeline: nothing is fetched until the pipeline runs,
+    /// on whatever runner executes it. Each partition then fetches its own distinct
+    /// keys, in [`ENRICH_BATCH_SIZE`] keys per [`batch_get`](KeyValueIO::batch_get) call,
+    /// on the worker running it. Element order within a partition is preserved.
+    ///
+    /// A failed `batch_get`, or a store returning the wrong number of documents, fails
+    /// the step with [`IronbeamError::TransformFailed`](crate::IronbeamError::TransformFailed).
+    ///
+    /// # Errors
+    /// Returns an error if `collection` is a `config://` reference that cannot be
+    /// resolved.
+    pub fn enrich_from_key_value<F>(
+        self,
+        store: Arc<dyn KeyValueIO>,
+        collection: &str,
+        key_fn: F,
+    ) -> Result<PCollection<(T, Option<KeyValueDocument>)>>
+    where
+        F: 'static + Send + Sync + Fn(&T) -> String,
+    {
+        let collection = self.pipeline.resolve_config(collection)?;
+        Ok(self.try_map_partitions(move |elements| {
+            enrich(store.as_ref(), &collection, &key_fn, elements)
+        }))
+    }
+}
+
+/// Pair `elements` with their documents, fetching each distinct key once.
+fn enrich<T, F>(
+    store: &dyn KeyValueIO,
+    collection: &str,
+    key_fn: &F,
+    elements: Vec<T>,
+) -> Result<Vec<(T, Option<KeyValueDocument>)>>
+where
+    F: Fn(&T) -> String,
+{
+    let keys: Vec<String> = elements.iter().map(key_fn).collect();
+
+    let mut seen = HashSet::new();
+    let distinct: Vec<&String> = keys.iter().filter(|k| seen.insert(*k)).collect();
+    let mut documents: HashMap<&str, KeyValueDocument> = HashMap::new();
+    for batch in distinct.chunks(ENRICH_BATCH_SIZE) {
+        let found = store
+            .batch_get(collection, batch.iter().map(|k| (*k).clone()).collect())
+            .with_context(|| format!("enrich_from_key_value: batch_get from {collection}"))?;
+        ensure!(
+            found.len() == batch.len(),
+            "enrich_from_key_value: {collection} returned {} documents for {} keys",
+            found.len(),
+            batch.len()
+        );
+        for (key, doc) in batch.iter().zip(found) {
+            if let Some(doc) = doc {
+                documents.insert(key.as_str(), doc.data);
+            }
+        }
+    }
+
+    Ok(elements
+        .into_iter()
+        .zip(&keys)
+        .map(|(element, key)| {
+            let doc = documents.get(key.as_str()).cloned();
+            (element, doc)
+        })
+        .collect())
+}
@@ -3,6 +3,9 @@
 //! - [`PCollection::key_by`] maps each element to a `(K, T)` pair by deriving a key.
 //! - [`PCollection<(K, V)>::group_by_key`] performs a local/merge aggregation to produce
 //!   `(K, Vec<V>)` per key across the entire dataset.
+//! - [`PCollection<(K, V)>::group_by_key_sorted`] and
+//!   [`PCollection<(K, V)>::group_by_key_sorted_by_key`] do the same and sort each
+//!   group's values during the merge.
 //! - [`PCollection<(K, V)>::keys`] extracts only the key component, producing `PCollection<K>`.
 //! - [`PCollection<(K, V)>::values`] extracts only the value component, producing `PCollection<V>`.
 //! - [`PCollection<(K, V)>::kv_swap`] swaps the key and value, producing `PCollection<(V, K)>`.
@@ -14,6 +17,7 @@
 
 use crate::node::Node;
 use crate::{Element, PCollection, Partition};
+use std::cmp::Ordering;
 use std::collections::HashMap;
 use std::hash::Hash;
 use std::marker::PhantomData;
@@ -211,6 +215,69 @@ impl<K: Element + Eq + Hash, V: Element> PCollection<(K, V)> {
     /// Panics if the input partition cannot be downcast to `Vec<(K, V)>`.
     #[must_use]
     pub fn group_by_key(self) -> PCollection<(K, Vec<V>)> {
+        self.group_by_key_then(|_| {})
+    }
+
+    /// Group values by key, sorting each group with `cmp`.
+    ///
+    /// Identical to [`group_by_key`](Self::group_by_key), except the merge stage
+    /// sorts every group once all partitions have been combined. The sort is
+    /// stable, so values that compare equal keep their arrival order.
+    ///
+    /// The ordering is a property of the emitted `Vec<V>`; transforms such as
+    /// [`map_values`](Self::map_values) see it, but a following
+    /// `combine_values_lifted` may be lifted past the grouping by the planner,
+    /// so only order-insensitive combiners should be chained that way.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// use ironbeam::*;
+    /// use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let events = from_vec(&p, vec![("u1".to_string(), 30u64), ("u1".into(), 10), ("u1".into(), 20)]);
+    /// let newest_first = events.group_by_key_sorted(|a, b| b.cmp(a)).collect_seq()?;
+    /// assert_eq!(newest_first[0].1, vec![30, 20, 10]);
+    /// # Ok(()) }
+    /// ```
+    #[must_use]
+    pub fn group_by_key_sorted<F>(self, cmp: F) -> PCollection<(K, Vec<V>)>
+    where
+        F: Fn(&V, &V) -> Ordering + Send + Sync + 'static,
+    {
+        self.group_by_key_then(move |vs| vs.sort_by(&cmp))
+    }
+
+    /// Group values by key, sorting each group ascending by `key_fn(&value)`.
+    ///
+    /// See [`group_by_key_sorted`](Self::group_by_key_sorted); the sort key is
+    /// computed once per value.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// use ironbeam::*;
+    /// use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let clicks = from_vec(&p, vec![("u1".to_string(), ("b".to_string(), 2u64)), ("u1".into(), ("a".into(), 1))]);
+    /// let by_time = clicks.group_by_key_sorted_by_key(|(_, ts)| *ts).collect_seq()?;
+    /// assert_eq!(by_time[0].1[0].0, "a");
+    /// # Ok(()) }
+    /// ```
+    #[must_use]
+    pub fn group_by_key_sorted_by_key<S, F>(self, key_fn: F) -> PCollection<(K, Vec<V>)>
+    where
+        S: Ord,
+        F: Fn(&V) -> S + Send + Sync + 'static,
+    {
+        self.group_by_key_then(move |vs| vs.sort_by_cached_key(&key_fn))
+    }
+
+    /// Shared `GroupByKey` construction; `finish_group` runs on every merged group.
+    fn group_by_key_then<G>(self, finish_group: G) -> PCollection<(K, Vec<V>)>
+    where
+        G: Fn(&mut Vec<V>) + Send + Sync + 'static,
+    {
         // Local stage: Vec<(K, V)> -> HashMap<K, Vec<V>>
         let local = Arc::new(|p: Partition| -> Partition {
             let kv = *p.downcast::<Vec<(K, V)>>().expect("GBK local: bad input");
@@ -221,8 +288,11 @@ impl<K: Element + Eq + Hash, V: Element> PCollection<(K, V)> {
             Box::new(m) as Partition
         });
 
+        let finish_group = Arc::new(finish_group);
+
         // Merge stage: Vec<HashMap<K, Vec<V>>> -> Vec<(K, Vec<V>)>
-        let merge = Arc::new(|parts: Vec<Partition>| -> Partition {
+        let finish = Arc::clone(&finish_group);
+        let merge = Arc::new(move |parts: Vec<Partition>| -> Partition {
             let mut acc: HashMap<K, Vec<V>> = HashMap::new();
             for p in parts {
                 let m = *p
@@ -232,10 +302,53 @@ impl<K: Element + Eq + Hash, V: Element> PCollection<(K, V)> {
                     acc.entry(k).or_default().extend(vs);
                 }
             }
+            acc.values_mut().for_each(finish.as_ref());
             Box::new(acc.into_iter().collect::<Vec<(K, Vec<V>)>>()) as Partition
         });
 
-        let id = self.pipeline.insert_node(Node::GroupByKey { local, merge });
+        // External stages (memory budget set): each partition is scattered to a
+        // `ScatteredPart<K, V>` (in memory or spilled), then gathered into
+        // Vec<(K, Vec<V>)>. Spill I/O errors fail the step.
+        #[cfg(all(feature = "spilling", feature = "coders"))]
+        let spill_group: Option<crate::node::SpillGroupFn> = Some(Arc::new(
+            move |budget: usize, dir: &std::path::Path| -> crate::node::SpillStages {
+                use crate::error::fail_step;
+                use crate::spill_group::{ExternalGroup, ScatteredPart};
+
+                let group = Arc::new(ExternalGroup::new(budget, dir));
+                let scatter = Arc::clone(&group);
+                let local = Arc::new(move |p: Partition| -> Partition {
+                    let kv = *p.downcast::<Vec<(K, V)>>().expect("GBK spill: bad input");
+                    let part = scatter.scatter(kv).unwrap_or_else(|e| fail_step(&e));
+                    Box::new(part) as Partition
+                });
+                let finish = Arc::clone(&finish_group);
+                let merge = Arc::new(move |parts: Vec<Partition>| -> Partition {
+                    let parts = parts
+                        .into_iter()
+                        .map(|p| {
+                            *p.downcast::<ScatteredPart<K, V>>()
+                                .expect("GBK spill: bad part")
+                        })
+                        .collect();
+                    let mut groups = group.gather(parts).unwrap_or_else(|e| fail_step(&e));
+                    groups.iter_mut().for_each(|(_, vs)| finish(vs));
+                    Box::new(groups) as Partition
+                });
+                (local, merge)
+            },
+        ));
+        #[cfg(not(all(feature = "spilling", feature = "coders")))]
+        let spill_group = {
+            drop(finish_group);
+            None
+        };
+
+        let id = self.pipeline.insert_node(Node::GroupByKey {
+            local,
+            merge,
+            spill_group,
+        });
         self.pipeline.connect(self.id, id);
         // The pre-GBK edge is emitted as `kv<lp, lp>`, so upgrade the
         // predecessor's coder to split each `(K, V)` into independently
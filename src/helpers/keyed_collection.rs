@@ -0,0 +1,234 @@
+//! [`KeyedPCollection`]: a key–value collection whose key structure is checked at
+//! compile time.
+//!
+//! A `PCollection<(K, V)>` is an ordinary collection of tuples, so an accidental
+//! [`map`](PCollection::map) can drop or rewrite the key and the mistake only shows up
+//! as wrong groups further down. A [`KeyedPCollection<K, V>`] wraps the same collection
+//! but only exposes operations that keep every element's key:
+//!
+//! - value-only transforms ([`map_values`](KeyedPCollection::map_values),
+//!   [`filter_values`](KeyedPCollection::filter_values)),
+//! - grouping and combining ([`group_by_key`](KeyedPCollection::group_by_key),
+//!   [`combine_values`](KeyedPCollection::combine_values),
+//!   [`count_per_key`](KeyedPCollection::count_per_key)),
+//! - joins against another keyed collection
+//!   ([`join_inner`](KeyedPCollection::join_inner) and friends),
+//!
+//! each of which returns a `KeyedPCollection` again. Leaving the keyed world is explicit:
+//! [`keys`](KeyedPCollection::keys) and [`values`](KeyedPCollection::values) project
+//! one side, and [`into_pcollection`](KeyedPCollection::into_pcollection) hands back the
+//! underlying `PCollection<(K, V)>` for everything else.
+//!
+//! Create one with [`PCollection::keyed_by`] (the keyed counterpart of
+//! [`key_by`](PCollection::key_by)) or [`PCollection::keyed`] on an existing
+//! `PCollection<(K, V)>`. The wrapper adds no nodes of its own; it compiles to the same
+//! plan as the equivalent `PCollection` calls.
+//!
+//! ```compile_fail
+//! use ironbeam::*;
+//! let p = Pipeline::default();
+//! let words = from_vec(&p, vec!["apple".to_string(), "avocado".to_string()])
+//!     .keyed_by(|w: &String| w.chars().next().unwrap());
+//! // Not available: `map` could rewrite the key.
+//! let lengths = words.map(|(_, w)| w.len());
+//! ```
+
+use crate::combiners::Count;
+use crate::{CombineFn, Element, NodeId, PCollection};
+use anyhow::Result;
+use std::hash::Hash;
+
+/// A `PCollection<(K, V)>` restricted to key-preserving operations.
+///
+/// See the [module documentation](self) for the operations it offers.
+pub struct KeyedPCollection<K, V> {
+    inner: PCollection<(K, V)>,
+}
+
+impl<K: Element, V: Element> Clone for KeyedPCollection<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<K, V> From<KeyedPCollection<K, V>> for PCollection<(K, V)> {
+    fn from(keyed: KeyedPCollection<K, V>) -> Self {
+        keyed.inner
+    }
+}
+
+impl<T: Element> PCollection<T> {
+    /// Derive a key for each element, like [`key_by`](Self::key_by), and return the
+    /// result as a [`KeyedPCollection`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ironbeam::*;
+    /// # fn main() -> anyhow::Result<()> {
+    /// let p = Pipeline::default();
+    /// let counts = from_vec(&p, vec!["apple".to_string(), "avocado".to_string(), "kiwi".to_string()])
+    ///     .keyed_by(|w: &String| w.chars().next().unwrap())
+    ///     .count_per_key()
+    ///     .collect_seq()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn keyed_by<K, F>(self, key_fn: F) -> KeyedPCollection<K, T>
+    where
+        K: Element + Eq + Hash,
+        F: 'static + Send + Sync + Fn(&T) -> K,
+    {
+        KeyedPCollection {
+            inner: self.key_by(key_fn),
+        }
+    }
+}
+
+impl<K: Element + Eq + Hash, V: Element> PCollection<(K, V)> {
+    /// View this collection of pairs as a [`KeyedPCollection`], treating the first
+    /// tuple field as the key.
+    #[must_use]
+    pub const fn keyed(self) -> KeyedPCollection<K, V> {
+        KeyedPCollection { inner: self }
+    }
+}
+
+impl<K: Element + Eq + Hash, V: Element> KeyedPCollection<K, V> {
+    /// The underlying `PCollection<(K, V)>`, for operations not offered on keyed
+    /// collections.
+    #[must_use]
+    pub fn into_pcollection(self) -> PCollection<(K, V)> {
+        self.inner
+    }
+
+    /// Borrow the underlying `PCollection<(K, V)>`.
+    #[must_use]
+    pub const fn as_pcollection(&self) -> &PCollection<(K, V)> {
+        &self.inner
+    }
+
+    /// Identifier of the node producing this collection (see [`PCollection::node_id`]).
+    #[must_use]
+    pub const fn node_id(&self) -> NodeId {
+        self.inner.node_id()
+    }
+
+    /// Name the step producing this collection (see [`PCollection::with_name`]).
+    #[must_use]
+    pub fn with_name(self, name: impl Into<String>) -> Self {
+        self.inner.with_name(name).keyed()
+    }
+
+    /// Transform each value, keeping its key (see [`PCollection::map_values`]).
+    #[must_use]
+    pub fn map_values<O, F>(self, f: F) -> KeyedPCollection<K, O>
+    where
+        O: Element,
+        F: 'static + Send + Sync + Fn(&V) -> O,
+    {
+        self.inner.map_values(f).keyed()
+    }
+
+    /// Keep the pairs whose value matches `pred` (see [`PCollection::filter_values`]).
+    #[must_use]
+    pub fn filter_values<F>(self, pred: F) -> Self
+    where
+        F: 'static + Send + Sync + Fn(&V) -> bool,
+    {
+        self.inner.filter_values(pred).keyed()
+    }
+
+    /// Group all values per key (see [`PCollection::group_by_key`]).
+    #[must_use]
+    pub fn group_by_key(self) -> KeyedPCollection<K, Vec<V>> {
+        self.inner.group_by_key().keyed()
+    }
+
+    /// Combine the values per key with `comb` (see [`PCollection::combine_values`]).
+    #[must_use]
+    pub fn combine_values<C, A, O>(self, comb: C) -> KeyedPCollection<K, O>
+    where
+        C: CombineFn<V, A, O> + 'static,
+        A: Send + Sync + 'static,
+        O: Element,
+    {
+        self.inner.combine_values(comb).keyed()
+    }
+
+    /// Count the values per key (see [`PCollection::count_per_key`]).
+    #[must_use]
+    pub fn count_per_key(self) -> KeyedPCollection<K, u64> {
+        self.inner.combine_values(Count::new()).keyed()
+    }
+
+    /// Inner join with another keyed collection (see [`PCollection::join_inner`]).
+    #[must_use]
+    pub fn join_inner<W: Element>(
+        &self,
+        right: &KeyedPCollection<K, W>,
+    ) -> KeyedPCollection<K, (V, W)> {
+        self.inner.join_inner(&right.inner).keyed()
+    }
+
+    /// Left outer join with another keyed collection (see [`PCollection::join_left`]).
+    #[must_use]
+    pub fn join_left<W: Element>(
+        &self,
+        right: &KeyedPCollection<K, W>,
+    ) -> KeyedPCollection<K, (V, Option<W>)> {
+        self.inner.join_left(&right.inner).keyed()
+    }
+
+    /// Right outer join with another keyed collection (see [`PCollection::join_right`]).
+    #[must_use]
+    pub fn join_right<W: Element>(
+        &self,
+        right: &KeyedPCollection<K, W>,
+    ) -> KeyedPCollection<K, (Option<V>, W)> {
+        self.inner.join_right(&right.inner).keyed()
+    }
+
+    /// Full outer join with another keyed collection (see [`PCollection::join_full`]).
+    #[must_use]
+    pub fn join_full<W: Element>(
+        &self,
+        right: &KeyedPCollection<K, W>,
+    ) -> KeyedPCollection<K, (Option<V>, Option<W>)> {
+        self.inner.join_full(&right.inner).keyed()
+    }
+
+    /// The keys, one per pair (see [`PCollection::keys`]).
+    #[must_use]
+    pub fn keys(self) -> PCollection<K> {
+        self.inner.keys()
+    }
+
+    /// The values, one per pair (see [`PCollection::values`]).
+    #[must_use]
+    pub fn values(self) -> PCollection<V> {
+        self.inner.values()
+    }
+
+    /// Execute sequentially and collect the pairs (see [`PCollection::collect_seq`]).
+    ///
+    /// # Errors
+    /// Returns an error if the run fails.
+    pub fn collect_seq(self) -> Result<Vec<(K, V)>> {
+        self.inner.collect_seq()
+    }
+
+    /// Execute in parallel and collect the pairs (see [`PCollection::collect_par`]).
+    ///
+    /// # Errors
+    /// Returns an error if the run fails.
+    pub fn collect_par(
+        self,
+        threads: Option<usize>,
+        partitions: Option<usize>,
+    ) -> Result<Vec<(K, V)>> {
+        self.inner.collect_par(threads, partitions)
+    }
+}
@@ -0,0 +1,78 @@
+//! Lakehouse table sources for [`PCollection`].
+//!
+//! [`read_delta_table`] reads a snapshot of a Delta Lake table as a sharded source: the
+//! transaction log is replayed up front to find the active Parquet files, partition
+//! filters prune them without opening them, and each remaining file becomes one
+//! partition, read when the pipeline runs. See [`io::lakehouse`](crate::io::lakehouse)
+//! for the log format and what is supported.
+//!
+//! ## Example
+//! ```no_run
+//! use ironbeam::io::lakehouse::{DeltaReadOptions, PartitionFilter};
+//! use ironbeam::*;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Clone, Serialize, Deserialize)]
+//! struct Order {
+//!     region: String,
+//!     cents: i64,
+//! }
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let p = Pipeline::default();
+//! let opts = DeltaReadOptions::default()
+//!     .with_version(42)
+//!     .with_filter(PartitionFilter::In("region".into(), vec!["eu".into(), "uk".into()]));
+//! let total = read_delta_table::<Order>(&p, "lake/orders", &opts)?
+//!     .map(|o: &Order| o.cents)
+//!     .collect_seq()?
+//!     .into_iter()
+//!     .sum::<i64>();
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::io::lakehouse::{DeltaReadOptions, DeltaSnapshot, DeltaVecOps};
+use crate::node::Node;
+use crate::type_token::TypeTag;
+use crate::{Element, PCollection, Pipeline};
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Read the Delta table at `table` as a `PCollection<T>`, one partition per active data
+/// file matching `opts.filters`.
+///
+/// The log is read when this is called; data files are read when the pipeline runs.
+/// Partition columns are filled into each row, so `T` may declare them.
+///
+/// # Errors
+/// Returns an error if a `config://` path does not resolve, or for the reasons of
+/// [`DeltaSnapshot::load`].
+pub fn read_delta_table<T>(
+    p: &Pipeline,
+    table: impl AsRef<Path>,
+    opts: &DeltaReadOptions,
+) -> Result<PCollection<T>>
+where
+    T: Element + DeserializeOwned,
+{
+    let table = p.resolve_path(table.as_ref())?;
+    let location = table.display().to_string();
+    let mut snapshot = DeltaSnapshot::load(table, opts.version)?;
+    snapshot.retain(&opts.filters);
+    let id = p.insert_node(Node::Source {
+        payload: Arc::new(snapshot),
+        vec_ops: DeltaVecOps::<T>::new(),
+        elem_tag: TypeTag::of::<T>(),
+    });
+    p.set_coder::<T>(id);
+    p.record_input(id, "delta", location);
+    Ok(PCollection {
+        pipeline: p.clone(),
+        id,
+        _t: PhantomData,
+    })
+}
@@ -0,0 +1,291 @@
+//! Per-record lineage: provenance tags that travel with each element.
+//!
+//! When a bad row turns up in an output file, the question is usually "which input
+//! line did this come from?". Lineage mode answers it by reading sources as
+//! [`Traced<T>`] values: each element carries the [`Provenance`] of the input
+//! records it was built from (file, shard, and line or row number). The traced
+//! transforms below keep that metadata attached, grouping unions it, and the
+//! dead-letter branch of [`map_traced_catching`](PCollection::map_traced_catching)
+//! keeps it too, so both sinks and errors can point back to the input.
+//!
+//! Lineage is opt-in: only the `*_with_lineage` sources produce traced collections,
+//! and [`strip_lineage`](PCollection::strip_lineage) drops the tags again.
+//!
+//! ## Available operations
+//! - [`read_jsonl_with_lineage`] - Read JSONL file(s), tagging values with their line number
+//! - [`read_csv_with_lineage`] - Read CSV file(s), tagging rows with their record number
+//! - [`PCollection::map_traced`] / [`PCollection::filter_traced`] /
+//!   [`PCollection::flat_map_traced`] - Transform the values, keeping their provenance
+//! - [`PCollection::map_traced_catching`] - Fallible map whose dead letters keep their provenance
+//! - [`PCollection::group_by_key_traced`] - Group traced values per key, merging their provenance
+//! - [`PCollection::strip_lineage`] - Drop the tags
+//! - [`PCollection::write_jsonl_with_lineage`] - Write `{"value": ..., "lineage": [...]}` lines
+//!
+//! ### Example
+//! ```no_run
+//! use ironbeam::*;
+//! use serde::{Deserialize, Serialize};
+//! use anyhow::Result;
+//! # fn main() -> Result<()> {
+//! #[derive(Clone, Serialize, Deserialize)]
+//! struct Order { region: String, cents: i64 }
+//!
+//! let p = Pipeline::default();
+//! let orders = read_jsonl_with_lineage::<Order>(&p, "orders/*.jsonl")?;
+//! let (refunds, bad) = orders
+//!     .filter_traced(|o| o.cents < 0)
+//!     .map_traced_catching(|o| u64::try_from(-o.cents).map_err(|e| e.to_string()));
+//! refunds.write_jsonl_with_lineage("out/refunds.jsonl")?;
+//! for dead in bad.collect_seq()? {
+//!     eprintln!("{}: {}", dead.element.lineage[0], dead.error);
+//! }
+//! # Ok(()) }
+//! ```
+
+use crate::helpers::dead_letter::DeadLetter;
+use crate::io::glob::expand_glob;
+use crate::io::jsonl::read_jsonl_vec_numbered;
+use crate::{Element, PCollection, Pipeline, from_vec, read_csv_vec};
+use anyhow::{Context, Result, anyhow, bail};
+use regex::Regex;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Display};
+use std::hash::Hash;
+use std::path::{Path, PathBuf};
+
+/// Where one input record came from.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Provenance {
+    /// Path of the file the record was read from.
+    pub source: String,
+    /// Index of that file among the files a glob pattern matched (in path order);
+    /// always `0` for a single-file read.
+    pub shard: usize,
+    /// 1-based position of the record in the file: the line number for JSONL, the
+    /// data record number (not counting the header) for CSV.
+    pub record: u64,
+}
+
+impl Provenance {
+    /// Construct a new [`Provenance`].
+    pub fn new(source: impl Into<String>, shard: usize, record: u64) -> Self {
+        Self {
+            source: source.into(),
+            shard,
+            record,
+        }
+    }
+}
+
+impl Display for Provenance {
+    /// Formats as `path:record`, e.g. `orders/2024-01.jsonl:17`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.source, self.record)
+    }
+}
+
+/// A value together with the provenance of the input records it was built from.
+///
+/// Elements read by a `*_with_lineage` source carry exactly one [`Provenance`];
+/// [`group_by_key_traced`](PCollection::group_by_key_traced) concatenates the
+/// lineage of everything it groups. Serializes as `{"value": ..., "lineage": [...]}`.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Traced<T> {
+    /// The element itself.
+    pub value: T,
+    /// The input records this element was derived from.
+    pub lineage: Vec<Provenance>,
+}
+
+impl<T> Traced<T> {
+    /// Construct a new [`Traced`] value.
+    pub const fn new(value: T, lineage: Vec<Provenance>) -> Self {
+        Self { value, lineage }
+    }
+
+    /// Replace the value, keeping the lineage.
+    pub fn with_value<O>(self, value: O) -> Traced<O> {
+        Traced {
+            value,
+            lineage: self.lineage,
+        }
+    }
+}
+
+/// The files `path` names: the sorted matches of a glob pattern, or `path` itself.
+fn lineage_inputs(p: &Pipeline, path: &Path) -> Result<(PathBuf, Vec<PathBuf>)> {
+    let path = p.resolve_path(path)?;
+    let path_str = path
+        .to_str()
+        .ok_or_else(|| anyhow!("path contains invalid UTF-8"))?;
+    let glob_regex = Regex::new(r"[*?\[]").expect("valid glob regex");
+    if !glob_regex.is_match(path_str) {
+        return Ok((path.clone(), vec![path]));
+    }
+    let files =
+        expand_glob(path_str).with_context(|| format!("expanding glob pattern: {path_str}"))?;
+    if files.is_empty() {
+        bail!("no files found matching pattern: {path_str}");
+    }
+    Ok((path, files))
+}
+
+/// Read JSONL file(s) into a `PCollection<Traced<T>>`, tagging each value with the
+/// file, shard and 1-based line number it came from.
+///
+/// Glob patterns are supported as in [`read_jsonl`](crate::read_jsonl); the shard of a
+/// value is the index of its file among the matches.
+///
+/// # Panics
+///
+/// Panics if the internal glob-detection regex cannot be compiled — this is
+/// not reachable in practice because the pattern is a compile-time constant.
+///
+/// # Errors
+/// Returns an error if `path` contains invalid UTF-8, a glob pattern matches no
+/// files, or any file cannot be read or has a line that does not parse into `T`.
+pub fn read_jsonl_with_lineage<T>(
+    p: &Pipeline,
+    path: impl AsRef<Path>,
+) -> Result<PCollection<Traced<T>>>
+where
+    T: Element + DeserializeOwned,
+{
+    let (path, files) = lineage_inputs(p, path.as_ref())?;
+    let mut all_data = Vec::new();
+    for (shard, file) in files.iter().enumerate() {
+        let rows = read_jsonl_vec_numbered::<T>(file)
+            .with_context(|| format!("reading {}", file.display()))?;
+        let source = file.display().to_string();
+        all_data.extend(rows.into_iter().map(|(line, value)| {
+            Traced::new(value, vec![Provenance::new(&source, shard, line as u64)])
+        }));
+    }
+    Ok(from_vec(p, all_data).record_input("jsonl", path.display()))
+}
+
+/// Read CSV file(s) into a `PCollection<Traced<T>>`, tagging each row with the file,
+/// shard and 1-based data record number it came from.
+///
+/// Glob patterns are supported as in [`read_csv`](crate::read_csv). Record numbers
+/// count data rows only, so with `has_headers` record 1 is the second line of a file
+/// whose fields contain no newlines.
+///
+/// # Panics
+///
+/// Panics if the internal glob-detection regex cannot be compiled — this is
+/// not reachable in practice because the pattern is a compile-time constant.
+///
+/// # Errors
+/// Returns an error if `path` contains invalid UTF-8, a glob pattern matches no
+/// files, or any file cannot be read or has a row that does not deserialize into `T`.
+pub fn read_csv_with_lineage<T>(
+    p: &Pipeline,
+    path: impl AsRef<Path>,
+    has_headers: bool,
+) -> Result<PCollection<Traced<T>>>
+where
+    T: Element + DeserializeOwned,
+{
+    let (path, files) = lineage_inputs(p, path.as_ref())?;
+    let mut all_data = Vec::new();
+    for (shard, file) in files.iter().enumerate() {
+        let rows = read_csv_vec::<T>(file, has_headers)
+            .with_context(|| format!("reading {}", file.display()))?;
+        let source = file.display().to_string();
+        all_data.extend(rows.into_iter().zip(1..).map(|(value, record)| {
+            Traced::new(value, vec![Provenance::new(&source, shard, record)])
+        }));
+    }
+    Ok(from_vec(p, all_data).record_input("csv", path.display()))
+}
+
+impl<T: Element> PCollection<Traced<T>> {
+    /// Map each value with `f`, keeping its lineage.
+    #[must_use]
+    pub fn map_traced<O, F>(self, f: F) -> PCollection<Traced<O>>
+    where
+        O: Element,
+        F: 'static + Send + Sync + Fn(&T) -> O,
+    {
+        self.map(move |t| Traced::new(f(&t.value), t.lineage.clone()))
+    }
+
+    /// Keep the values matching `pred`, with their lineage.
+    #[must_use]
+    pub fn filter_traced<F>(self, pred: F) -> Self
+    where
+        F: 'static + Send + Sync + Fn(&T) -> bool,
+    {
+        self.filter(move |t| pred(&t.value))
+    }
+
+    /// Expand each value into zero or more outputs, each inheriting its lineage.
+    #[must_use]
+    pub fn flat_map_traced<O, F>(self, f: F) -> PCollection<Traced<O>>
+    where
+        O: Element,
+        F: 'static + Send + Sync + Fn(&T) -> Vec<O>,
+    {
+        self.flat_map(move |t| {
+            f(&t.value)
+                .into_iter()
+                .map(|o| Traced::new(o, t.lineage.clone()))
+                .collect::<Vec<_>>()
+        })
+    }
+
+    /// Fallible map that routes failures to a dead-letter collection, like
+    /// [`map_catching`](PCollection::map_catching). Each [`DeadLetter`] holds the
+    /// traced input, so a failure can be traced back to the input records behind it.
+    #[must_use]
+    pub fn map_traced_catching<O, E, F>(
+        self,
+        f: F,
+    ) -> (PCollection<Traced<O>>, PCollection<DeadLetter<Traced<T>>>)
+    where
+        O: Element,
+        E: Display,
+        F: 'static + Send + Sync + Fn(&T) -> Result<O, E>,
+    {
+        self.map_catching(move |t| f(&t.value).map(|o| Traced::new(o, t.lineage.clone())))
+    }
+
+    /// Drop the lineage, leaving the plain values.
+    #[must_use]
+    pub fn strip_lineage(self) -> PCollection<T> {
+        self.map(|t| t.value.clone())
+    }
+}
+
+impl<T: Element + Serialize> PCollection<Traced<T>> {
+    /// Execute the collection and write it as JSONL, one
+    /// `{"value": ..., "lineage": [...]}` object per line (see
+    /// [`write_jsonl`](PCollection::write_jsonl)). Returns the number of records
+    /// written.
+    ///
+    /// ### Errors
+    /// Propagates execution, I/O and serialization errors.
+    pub fn write_jsonl_with_lineage(self, path: impl AsRef<Path>) -> Result<usize> {
+        self.write_jsonl(path)
+    }
+}
+
+impl<K: Element + Eq + Hash, V: Element> PCollection<(K, Traced<V>)> {
+    /// Group traced values per key (see [`group_by_key`](PCollection::group_by_key)).
+    /// Each group's lineage is the concatenation of its members' lineage, so an
+    /// aggregate computed from the group can still name every input behind it.
+    #[must_use]
+    pub fn group_by_key_traced(self) -> PCollection<(K, Traced<Vec<V>>)> {
+        self.group_by_key().map(|(k, group)| {
+            let mut values = Vec::with_capacity(group.len());
+            let mut lineage = Vec::new();
+            for traced in group {
+                values.push(traced.value.clone());
+                lineage.extend(traced.lineage.iter().cloned());
+            }
+            (k.clone(), Traced::new(values, lineage))
+        })
+    }
+}
@@ -0,0 +1,165 @@
+//! Parsing `key=value` log lines.
+//!
+//! [`parse_key_value`](PCollection::parse_key_value) turns lines in
+//! [logfmt](https://brandur.org/logfmt) style, or another `key=value` layout described
+//! by a [`KeyValueFormat`], into [`Row`]s with one column per key. Together with
+//! [`extract_regex`](PCollection::extract_regex) it covers most semi-structured log
+//! formats without a hand-written parser.
+//!
+//! Parsing rules:
+//! - Pairs are separated by the pair delimiter; repeated delimiters are skipped.
+//! - A value may be quoted, in which case it can contain delimiters, and `\"`, `\\`,
+//!   `\n` and `\t` inside the quotes are unescaped.
+//! - A key with no value (`debug` in `level=info debug`) becomes `true`.
+//! - Keys and unquoted values are trimmed of surrounding whitespace; a repeated key
+//!   keeps its last value.
+//! - Lines without any key are dropped.
+//!
+//! ```no_run
+//! use ironbeam::*;
+//! # use anyhow::Result;
+//! # fn main() -> Result<()> {
+//! let p = Pipeline::default();
+//! let lines = from_vec(&p, vec![
+//!     r#"level=warn msg="disk almost full" pct=91"#.to_string(),
+//! ]);
+//! let rows = lines.parse_key_value(KeyValueFormat::logfmt()).collect_seq()?;
+//! assert_eq!(rows[0].get("msg"), Some(&serde_json::json!("disk almost full")));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{PCollection, Row};
+use serde_json::Value;
+
+/// The delimiters of a `key=value` line format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyValueFormat {
+    /// Separates one pair from the next (a space for logfmt).
+    pub pair_delimiter: char,
+    /// Separates a key from its value (`=` for logfmt).
+    pub kv_delimiter: char,
+    /// Encloses values that contain delimiters, or `None` for no quoting.
+    pub quote: Option<char>,
+}
+
+impl Default for KeyValueFormat {
+    fn default() -> Self {
+        Self::logfmt()
+    }
+}
+
+impl KeyValueFormat {
+    /// logfmt: `key=value` pairs separated by spaces, with `"`-quoted values.
+    #[must_use]
+    pub const fn logfmt() -> Self {
+        Self::new(' ', '=')
+    }
+
+    /// Pairs separated by `pair_delimiter`, keys from values by `kv_delimiter`, with
+    /// `"`-quoted values.
+    #[must_use]
+    pub const fn new(pair_delimiter: char, kv_delimiter: char) -> Self {
+        Self {
+            pair_delimiter,
+            kv_delimiter,
+            quote: Some('"'),
+        }
+    }
+
+    /// Set the quote character, or disable quoting with `None`.
+    #[must_use]
+    pub const fn with_quote(mut self, quote: Option<char>) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    /// Split one line into `(key, value)` pairs in line order; a key without a value
+    /// has `None`.
+    #[must_use]
+    pub fn parse(&self, line: &str) -> Vec<(String, Option<String>)> {
+        let mut pairs = Vec::new();
+        let mut chars = line.chars().peekable();
+        loop {
+            while chars.next_if_eq(&self.pair_delimiter).is_some() {}
+            if chars.peek().is_none() {
+                break;
+            }
+            let mut key = String::new();
+            while let Some(c) =
+                chars.next_if(|&c| c != self.kv_delimiter && c != self.pair_delimiter)
+            {
+                key.push(c);
+            }
+            let value = chars.next_if_eq(&self.kv_delimiter).map(|_| {
+                if let Some(quote) = self.quote
+                    && chars.next_if_eq(&quote).is_some()
+                {
+                    let mut value = String::new();
+                    while let Some(c) = chars.next() {
+                        match c {
+                            '\\' => match chars.next() {
+                                Some('n') => value.push('\n'),
+                                Some('t') => value.push('\t'),
+                                Some(c) => value.push(c),
+                                None => value.push('\\'),
+                            },
+                            c if c == quote => break,
+                            c => value.push(c),
+                        }
+                    }
+                    // Anything between the closing quote and the next pair is ignored.
+                    while chars.next_if(|&c| c != self.pair_delimiter).is_some() {}
+                    value
+                } else {
+                    let mut value = String::new();
+                    while let Some(c) = chars.next_if(|&c| c != self.pair_delimiter) {
+                        value.push(c);
+                    }
+                    value.trim().to_string()
+                }
+            });
+            let key = key.trim();
+            if !key.is_empty() {
+                pairs.push((key.to_string(), value));
+            }
+        }
+        pairs
+    }
+}
+
+impl PCollection<String> {
+    /// Parse each `key=value` line into a [`Row`] with one string column per key, in
+    /// line order (see the [module docs](self) for the rules). Lines with no keys are
+    /// dropped.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ironbeam::*;
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let lines = from_vec(&p, vec!["user=ada; plan=pro".to_string()]);
+    /// let rows = lines
+    ///     .parse_key_value(KeyValueFormat::new(';', '=').with_quote(None))
+    ///     .collect_seq()?;
+    /// assert_eq!(rows[0].get("plan"), Some(&serde_json::json!("pro")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn parse_key_value(self, format: KeyValueFormat) -> PCollection<Row> {
+        self.filter_map(move |line: &String| {
+            let pairs = format.parse(line);
+            if pairs.is_empty() {
+                return None;
+            }
+            Some(
+                pairs
+                    .into_iter()
+                    .map(|(key, value)| (key, value.map_or(Value::Bool(true), Value::String)))
+                    .collect(),
+            )
+        })
+    }
+}
@@ -0,0 +1,143 @@
+//! Bounded-concurrency transforms for blocking, I/O-heavy per-element calls.
+//!
+//! A blocking call (an HTTP request, a database lookup, a file read) inside
+//! [`map`](PCollection::map) occupies one rayon thread per call, so throughput is
+//! capped at the number of CPU threads however long each call spends waiting. The
+//! operators here run the call on a **dedicated** thread pool sized for the I/O
+//! rather than the CPU:
+//!
+//! - [`PCollection::map_io`] -- runs `f` for up to `concurrency` elements at once and
+//!   keeps each partition's results in input order.
+//! - [`PCollection::map_io_unordered`] -- same, but emits each partition's results in
+//!   completion order, so one slow call does not hold back the rest of the partition.
+//!
+//! The pool belongs to the transform and is shared by every partition, so
+//! `concurrency` bounds the calls in flight for the whole transform. It is created
+//! the first time the transform runs.
+//!
+//! Unlike [`map_batches`](PCollection::map_batches), which hands contiguous slices to
+//! one CPU-bound call, these call `f` once per element.
+
+use crate::node::{DynOp, Node};
+use crate::type_token::Partition;
+use crate::{Element, PCollection};
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::marker::PhantomData;
+use std::sync::mpsc;
+use std::sync::{Arc, OnceLock};
+
+/// `MapIoOp`: `&T -> O` on a dedicated pool of `concurrency` threads.
+struct MapIoOp<T, O, F> {
+    f: F,
+    concurrency: usize,
+    ordered: bool,
+    pool: OnceLock<ThreadPool>,
+    _t: PhantomData<fn(T) -> O>,
+}
+
+impl<T, O, F> MapIoOp<T, O, F> {
+    fn pool(&self) -> &ThreadPool {
+        self.pool.get_or_init(|| {
+            ThreadPoolBuilder::new()
+                .num_threads(self.concurrency)
+                .thread_name(|i| format!("ironbeam-io-{i}"))
+                .build()
+                .expect("failed to start the map_io thread pool")
+        })
+    }
+}
+
+impl<T, O, F> DynOp for MapIoOp<T, O, F>
+where
+    T: Element,
+    O: Element,
+    F: Fn(&T) -> O + Send + Sync + 'static,
+{
+    fn apply(&self, input: Partition) -> Partition {
+        let v = *input
+            .downcast::<Vec<T>>()
+            .expect("MapIoOp: expected Vec<T> input");
+        let f = &self.f;
+        let out: Vec<O> = if self.ordered {
+            self.pool()
+                .install(|| v.par_iter().with_max_len(1).map(f).collect())
+        } else {
+            let (tx, rx) = mpsc::channel();
+            self.pool().scope(|s| {
+                for item in &v {
+                    let tx = tx.clone();
+                    s.spawn(move |_| {
+                        // The receiver outlives the scope, so sending cannot fail.
+                        let _ = tx.send(f(item));
+                    });
+                }
+            });
+            drop(tx);
+            rx.into_iter().collect()
+        };
+        Box::new(out) as Partition
+    }
+}
+
+impl<T: Element> PCollection<T> {
+    /// Apply a blocking, I/O-heavy function to each element, running up to
+    /// `concurrency` calls at once on a dedicated thread pool.
+    ///
+    /// Results keep the input order within each partition. A `concurrency` of `0` is
+    /// treated as `1`. See the [module docs](crate::helpers::map_io) for how this
+    /// differs from [`map`](Self::map) and [`map_batches`](Self::map_batches).
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ironbeam::*;
+    /// use std::time::Duration;
+    ///
+    /// let p = Pipeline::default();
+    /// let ids = from_vec(&p, (0..100u32).collect::<Vec<_>>());
+    /// let names = ids.map_io(32, |id: &u32| {
+    ///     std::thread::sleep(Duration::from_millis(20)); // e.g. a blocking HTTP call
+    ///     format!("user-{id}")
+    /// });
+    /// assert_eq!(names.collect_seq().unwrap()[3], "user-3");
+    /// ```
+    pub fn map_io<O, F>(self, concurrency: usize, f: F) -> PCollection<O>
+    where
+        O: Element,
+        F: Fn(&T) -> O + Send + Sync + 'static,
+    {
+        self.map_io_with(concurrency, true, f)
+    }
+
+    /// Like [`map_io`](Self::map_io), but emits each partition's results in the order
+    /// the calls complete.
+    pub fn map_io_unordered<O, F>(self, concurrency: usize, f: F) -> PCollection<O>
+    where
+        O: Element,
+        F: Fn(&T) -> O + Send + Sync + 'static,
+    {
+        self.map_io_with(concurrency, false, f)
+    }
+
+    fn map_io_with<O, F>(self, concurrency: usize, ordered: bool, f: F) -> PCollection<O>
+    where
+        O: Element,
+        F: Fn(&T) -> O + Send + Sync + 'static,
+    {
+        let op: Arc<dyn DynOp> = Arc::new(MapIoOp::<T, O, F> {
+            f,
+            concurrency: concurrency.max(1),
+            ordered,
+            pool: OnceLock::new(),
+            _t: PhantomData,
+        });
+        let id = self.pipeline.insert_node(Node::Stateless(vec![op]));
+        self.pipeline.connect(self.id, id);
+        self.pipeline.set_coder::<O>(id);
+        PCollection {
+            pipeline: self.pipeline,
+            id,
+            _t: PhantomData,
+        }
+    }
+}
@@ -22,6 +22,11 @@
 //!   - [`PCollection::keys`](crate::PCollection::keys)
 //!   - [`PCollection::values`](crate::PCollection::values)
 //!   - [`PCollection::kv_swap`](crate::PCollection::kv_swap)
+//!   - [`PCollection::group_by_key_sorted`](crate::PCollection::group_by_key_sorted)
+//!   - [`PCollection::group_by_key_sorted_by_key`](crate::PCollection::group_by_key_sorted_by_key)
+//! - [`keyed_collection`] - [`KeyedPCollection`], which only exposes key-preserving operations
+//!   - [`PCollection::keyed_by`](crate::PCollection::keyed_by)
+//!   - [`PCollection::keyed`](crate::PCollection::keyed)
 //! - [`values`] - Value-only transformations on keyed collections
 //!   - [`PCollection::map_values`](crate::PCollection::map_values)
 //!   - [`PCollection::filter_values`](crate::PCollection::filter_values)
@@ -30,6 +35,10 @@
 //! - [`combine`] - Per-key aggregations with combiners
 //!   - [`PCollection::combine_values`](crate::PCollection::combine_values)
 //!   - [`PCollection::combine_values_lifted`](crate::PCollection::combine_values_lifted)
+//! - [`skewed_combine`] - Salted two-phase combine for hot keys
+//!   - [`PCollection::combine_values_skewed`](crate::PCollection::combine_values_skewed)
+//!   - [`PCollection::combine_values_with_hint`](crate::PCollection::combine_values_with_hint)
+//!   - [`SkewHint`]
 //! - [`combine_global`] - Global aggregations across the entire collection
 //!   - [`PCollection::combine_globally`](crate::PCollection::combine_globally)
 //! - [`basic`] - Arithmetic aggregate convenience methods
@@ -48,6 +57,9 @@
 //!   - [`PCollection::approx_median_per_key`](crate::PCollection::approx_median_per_key)
 //!   - [`PCollection::approx_quantiles_globally`](crate::PCollection::approx_quantiles_globally)
 //!   - [`PCollection::approx_quantiles_per_key`](crate::PCollection::approx_quantiles_per_key)
+//!   - [`PCollection::exact_quantiles_globally`](crate::PCollection::exact_quantiles_globally)
+//!   - [`PCollection::exact_quantiles_per_key`](crate::PCollection::exact_quantiles_per_key)
+//!   - [`PCollection::profile`](crate::PCollection::profile)
 //!
 //! ### Joins
 //! - [`joins`] - Join operations for keyed collections
@@ -55,8 +67,25 @@
 //!   - [`PCollection::join_left`](crate::PCollection::join_left)
 //!   - [`PCollection::join_right`](crate::PCollection::join_right)
 //!   - [`PCollection::join_full`](crate::PCollection::join_full)
+//!   - [`PCollection::cross_join`](crate::PCollection::cross_join)
 //! - [`co_gbk`] - Multi-way `CoGroupByKey` for N-way joins (2-10 collections)
 //!   - [`cogroup_by_key!`](crate::cogroup_by_key) - Macro for N-way full outer joins
+//! - [`similarity`] - Approximate nearest-neighbor joins over vector embeddings
+//!   - [`PCollection::similarity_join`](crate::PCollection::similarity_join)
+//!
+//! ### I/O-Bound Transforms
+//! - [`map_io`] - Blocking per-element calls on a dedicated, bounded thread pool
+//!   - [`PCollection::map_io`](crate::PCollection::map_io)
+//!   - [`PCollection::map_io_unordered`](crate::PCollection::map_io_unordered)
+//!
+//! ### Per-Partition Resources
+//! - [`resource`] - Setup/teardown of an expensive resource once per partition
+//!   - [`PCollection::map_with_resource`](crate::PCollection::map_with_resource)
+//!
+//! ### Async Transforms
+//! - [`async_exec`] - `async` maps with bounded concurrency on tokio (feature: `async`, opt-in)
+//!   - [`PCollection::map_async`](crate::PCollection::map_async)
+//!   - [`PCollection::collect_async`](crate::PCollection::collect_async)
 //!
 //! ### Side Inputs
 //! - [`side_inputs`] - Enrich streams with auxiliary data
@@ -72,6 +101,11 @@
 //!   - [`PCollection::filter_with_singleton`](crate::PCollection::filter_with_singleton)
 //!   - [`PCollection::map_with_side_multimap`](crate::PCollection::map_with_side_multimap)
 //!   - [`PCollection::filter_with_side_multimap`](crate::PCollection::filter_with_side_multimap)
+//!   - [`PCollection::filter_with_bloom`](crate::PCollection::filter_with_bloom)
+//!   - [`side_from_jsonl`] / [`side_from_csv`] - Lookup tables read from a file when the pipeline runs
+//!   - [`RefreshableSideInput`] - A side input loaded at execution time and reloaded on a schedule
+//!   - [`PCollection::map_with_refreshable`](crate::PCollection::map_with_refreshable)
+//!   - [`PCollection::filter_with_refreshable`](crate::PCollection::filter_with_refreshable)
 //!
 //! ### Distinct Operations
 //! - [`distinct`] - Remove duplicate elements and count distinct values
@@ -116,6 +150,7 @@
 //! - [`topk`] - Convenience API for selecting top or bottom K values globally or per key
 //!   - [`PCollection::top_k_globally`](crate::PCollection::top_k_globally)
 //!   - [`PCollection::top_k_per_key`](crate::PCollection::top_k_per_key)
+//!   - [`PCollection::top_k_per_key_by`](crate::PCollection::top_k_per_key_by)
 //!   - [`PCollection::bottom_k_globally`](crate::PCollection::bottom_k_globally)
 //!   - [`PCollection::bottom_k_per_key`](crate::PCollection::bottom_k_per_key)
 //!
@@ -123,30 +158,85 @@
 //! - [`try_process`] - Fallible transformations (Result-typed stream)
 //!   - [`PCollection::try_map`](crate::PCollection::try_map)
 //!   - [`PCollection::try_flat_map`](crate::PCollection::try_flat_map)
+//!   - [`PCollection::try_map_with_errors`](crate::PCollection::try_map_with_errors)
+//!   - [`PCollection::try_flat_map_with_errors`](crate::PCollection::try_flat_map_with_errors)
+//!   - [`PCollection::try_map_with_retry`](crate::PCollection::try_map_with_retry)
+//!   - [`RetryPolicy`]
 //!   - [`PCollection::collect_fail_fast`](crate::PCollection::collect_fail_fast)
 //! - [`dead_letter`] - Fallible transforms that split good vs. errors
 //!   - [`DeadLetter`]
 //!   - [`PCollection::map_catching`](crate::PCollection::map_catching)
 //!   - [`PCollection::flat_map_catching`](crate::PCollection::flat_map_catching)
+//! - [`lineage`] - Provenance tags (file, shard, line) carried with each record
+//!   - [`read_jsonl_with_lineage`] / [`read_csv_with_lineage`]
+//!   - [`Traced`] / [`Provenance`]
+//!   - [`PCollection::map_traced_catching`](crate::PCollection::map_traced_catching)
+//!   - [`PCollection::write_jsonl_with_lineage`](crate::PCollection::write_jsonl_with_lineage)
+//!
+//! ### Metrics
+//! - [`with_metrics`] - Transforms that update user-defined metrics (feature: `metrics`)
+//!   - [`PCollection::map_with_metrics`](crate::PCollection::map_with_metrics)
+//!   - [`PCollection::filter_with_metrics`](crate::PCollection::filter_with_metrics)
+//!
+//! ### Keyed State
+//! - [`stateful`] - Per-key mutable state for custom stateful processing
+//!   - [`PCollection::map_values_stateful`](crate::PCollection::map_values_stateful)
+//!   - [`PCollection::map_values_stateful_sorted_by_key`](crate::PCollection::map_values_stateful_sorted_by_key)
+//!   - [`PCollection::process_with_timers`](crate::PCollection::process_with_timers) with a [`TimerContext`]
+//!
+//! ### Multi-Output
+//! - [`partition`] - Side outputs via enums, the `partition!` macro, and tagged emitters
+//!   - [`MultiOutput`]
+//!   - [`PCollection::flat_map_multi`](crate::PCollection::flat_map_multi)
+//!   - [`PCollection::partition_by`](crate::PCollection::partition_by)
+//!
+//! ### Pattern Detection
+//! - [`pattern`] - Ordered event sequences per key (lightweight CEP)
+//!   - [`Pattern`] / [`PatternMatch`]
+//!   - [`PCollection::match_pattern`](crate::PCollection::match_pattern)
 //!
 //! ### Sorting
 //! - [`collect_sorted`] - Collect results in sorted order
 //!   - [`PCollection::collect_seq_sorted`](crate::PCollection::collect_seq_sorted)
 //!   - [`PCollection::collect_par_sorted`](crate::PCollection::collect_par_sorted)
 //!   - [`PCollection::collect_par_sorted_by_key`](crate::PCollection::collect_par_sorted_by_key)
+//! - [`sort`] - Global sort as a pipeline barrier
+//!   - [`PCollection::sort_by`](crate::PCollection::sort_by)
+//!   - [`PCollection::sort_by_key`](crate::PCollection::sort_by_key)
+//!
+//! ### Partitioning
+//! - [`repartition`] - Control partitioning mid-pipeline
+//!   - [`PCollection::repartition`](crate::PCollection::repartition)
+//!   - [`PCollection::repartition_by_key`](crate::PCollection::repartition_by_key)
+//!   - [`PCollection::repartition_by_range`](crate::PCollection::repartition_by_range)
 //!
 //! ### I/O Helpers
 //! - [`jsonl`] - JSON Lines I/O utilities (feature: `io-jsonl`)
 //!   - [`read_jsonl`]
+//!   - [`read_jsonl_lenient`]
+//!   - [`read_jsonl_checked`]
+//!   - [`read_jsonl_timestamped`] with an [`EventTime`]
 //!   - [`read_jsonl_streaming`]
 //!   - [`PCollection::write_jsonl`](crate::PCollection::write_jsonl)
 //! - [`csv`] - CSV I/O utilities (feature: `io-csv`)
 //!   - [`read_csv`]
 //!   - [`read_csv_streaming`]
+//!   - [`read_csv_timestamped`]
+//!   - [`read_csv_with`] / [`read_csv_streaming_with`] with a
+//!     [`CsvReadOptions`](crate::io::csv::CsvReadOptions)
 //!   - [`PCollection::write_csv`](crate::PCollection::write_csv)
 //! - [`parquet`] - Parquet I/O utilities (feature: `io-parquet`)
 //!   - [`read_parquet_streaming`]
+//!   - [`read_parquet_timestamped`]
 //!   - [`PCollection::write_parquet`](crate::PCollection::write_parquet)
+//! - [`columnar`] - Arrow record-batch stages over Parquet (feature: `io-parquet`)
+//!   - [`read_parquet_batches`]
+//!   - [`PCollection::map_batches_arrow`](crate::PCollection::map_batches_arrow)
+//!   - [`PCollection::into_rows`](crate::PCollection::into_rows)
+//!   - [`PCollection::into_arrow_batches`](crate::PCollection::into_arrow_batches)
+//! - `lakehouse` - Delta Lake table snapshots as sharded sources (feature: `io-parquet`)
+//!   - `read_delta_table`
+//!   - [`PCollection::write_parquet_batches`](crate::PCollection::write_parquet_batches)
 //! - [`avro`] - Avro I/O utilities (feature: `io-avro`)
 //!   - [`read_avro`]
 //!   - [`read_avro_streaming`]
@@ -157,6 +247,28 @@
 //!   - [`read_msgpack_streaming`]
 //!   - [`PCollection::write_msgpack`](crate::PCollection::write_msgpack)
 //!   - [`PCollection::write_msgpack_par`](crate::PCollection::write_msgpack_par)
+//! - [`xml`] - XML I/O utilities (feature: `io-xml`)
+//!   - [`read_xml`]
+//!   - [`read_xml_streaming`]
+//!   - [`read_xml_records`] / [`read_xml_records_streaming`] - One record per element at a tag path
+//!   - [`PCollection::write_xml`](crate::PCollection::write_xml)
+//! - `proto` - Length-delimited protobuf I/O (feature: `io-proto`, opt-in)
+//!   - `read_proto`
+//!   - `read_proto_streaming`
+//!   - `PCollection::write_proto`
+//! - `http` - Paginated JSON REST sources and batched POST sinks (feature: `io-http`,
+//!   opt-in)
+//!   - `read_http_json`
+//!   - `PCollection::write_http_json`
+//! - `sqlite` - Local SQLite query sources and table sinks (feature: `io-sqlite`, opt-in)
+//!   - `read_sql_query`
+//!   - `PCollection::write_table`
+//! - [`file_sink`] - How `write_jsonl`, `write_csv` and `write_parquet` serialize each
+//!   partition inside the run
+//!   - [`JsonlSink`], [`CsvSink`], `ParquetSink` - The same files as [`Sink`](crate::Sink)s
+//! - [`key_sink`] - Write a keyed collection as one file per key
+//!   - [`PCollection::write_jsonl_per_key`](crate::PCollection::write_jsonl_per_key)
+//!   - [`PCollection::write_csv_per_key`](crate::PCollection::write_csv_per_key)
 //!
 //! ### Cloud Operations
 //! - [`cloud`] - Helpers for running custom cloud operations
@@ -167,6 +279,28 @@
 //!   - [`run_paginated_operation`] - Handle paginated API responses
 //!   - [`OperationBuilder`] - Fluent API for operation configuration
 //!   - [`run_with_context`] - Track execution metadata
+//! - [`cached_lookup`] - Enrich a collection through a remote cache and a loader
+//!   - [`PCollection::map_with_cached_lookup`](crate::PCollection::map_with_cached_lookup)
+//! - [`compute`] - Map a collection through a serverless function
+//!   - [`PCollection::map_via_compute`](crate::PCollection::map_via_compute)
+//!   - [`PCollection::map_via_compute_catching`](crate::PCollection::map_via_compute_catching)
+//! - [`graph`] - Bulk-load a collection into, or expand it from, a graph database
+//!   - [`PCollection::write_graph_nodes`](crate::PCollection::write_graph_nodes)
+//!   - [`PCollection::write_graph_edges`](crate::PCollection::write_graph_edges)
+//!   - [`PCollection::expand_neighbors`](crate::PCollection::expand_neighbors)
+//! - [`inference`] - Score a collection with a hosted model in batches
+//!   - [`PCollection::predict_with`](crate::PCollection::predict_with)
+//! - [`key_value`] - Store a collection in, or enrich it from, a key-value store
+//!   - [`PCollection::write_key_value`](crate::PCollection::write_key_value)
+//!   - [`PCollection::enrich_from_key_value`](crate::PCollection::enrich_from_key_value)
+//! - [`object_sink`] - Write a collection to object storage, one object per shard
+//!   - [`PCollection::write_jsonl_object`](crate::PCollection::write_jsonl_object)
+//!   - [`PCollection::write_csv_object`](crate::PCollection::write_csv_object)
+//!   - [`PCollection::write_parquet_object`](crate::PCollection::write_parquet_object)
+//! - [`queue`] - Drain a work queue through a pipeline
+//!   - [`read_queue`](crate::read_queue)
+//! - [`search_index`] - Publish a collection to a search index
+//!   - [`PCollection::write_search_index`](crate::PCollection::write_search_index)
 //!
 //! ### Display / String Conversion
 //! - [`display`] - `Display`-based string conversion
@@ -189,6 +323,12 @@
 //! - [`tee`] - Duplicate a collection for multiple downstream branches
 //!   - [`PCollection::tee`](crate::PCollection::tee)
 //!   - [`PCollection::tee_n`](crate::PCollection::tee_n)
+//! - [`cache`] - Compute a shared collection once and reuse it for every consumer
+//!   - [`PCollection::cache`](crate::PCollection::cache)
+//! - [`run_all`] - Run several outputs of a pipeline in one execution
+//!   - [`PCollection::materialize`](crate::PCollection::materialize)
+//!   - [`PCollection::write_to_sink`](crate::PCollection::write_to_sink)
+//!   - [`Pipeline::run_all`](crate::Pipeline::run_all)
 //!
 //! ### Regex Transforms
 //! - [`regex`] - Regex-based transforms for `PCollection<String>`
@@ -198,13 +338,31 @@
 //!   - [`PCollection::regex_find`](crate::PCollection::regex_find)
 //!   - [`PCollection::regex_replace_all`](crate::PCollection::regex_replace_all)
 //!   - [`PCollection::regex_split`](crate::PCollection::regex_split)
+//!   - [`PCollection::extract_regex`](crate::PCollection::extract_regex) into tuples or
+//!     [`Row`](crate::Row)s via [`FromCaptures`]
+//! - [`logfmt`] - Parse `key=value` log lines into rows
+//!   - [`PCollection::parse_key_value`](crate::PCollection::parse_key_value) with a
+//!     [`KeyValueFormat`]
 //!
 //! ### Windowing
 //! - [`tumbling`] - Tumbling window operations
 //!   - [`PCollection::key_by_window`](crate::PCollection::key_by_window)
 //!   - [`PCollection::group_by_window`](crate::PCollection::group_by_window)
 //!   - [`PCollection::group_by_key_and_window`](crate::PCollection::group_by_key_and_window)
+//! - [`sessions`] - Inactivity-gap sessions per key
+//!   - [`PCollection::sessionize`](crate::PCollection::sessionize)
+//! - [`time_series`] - Lag, deltas, rates, and running totals per key
+//!   - [`PCollection::map_values_with_previous`](crate::PCollection::map_values_with_previous)
+//!   - [`PCollection::deltas`](crate::PCollection::deltas)
+//!   - [`PCollection::rates_per_second`](crate::PCollection::rates_per_second)
+//!   - [`PCollection::cumulative_sum`](crate::PCollection::cumulative_sum)
+//! - `time` - Calendar-aware timestamp parsing, truncation and bucketing (feature: `time`,
+//!   opt-in)
+//!   - `PCollection::parse_timestamp`
+//!   - `PCollection::truncate_to`
+//!   - `PCollection::key_by_time_bucket`
 //! - [`timestamped`] - Timestamp utilities for windowed data
+//!   - [`EventTime`] - Event time extracted by the `read_*_timestamped` sources
 //! - [`windowed_combine`] - One-call windowed aggregation helpers
 //!   - [`PCollection::combine_per_window`](crate::PCollection::combine_per_window)
 //!   - [`PCollection::sum_per_window`](crate::PCollection::sum_per_window)
@@ -281,39 +439,78 @@
 //! - [`combiners`](crate::combiners) - Built-in aggregation functions
 //! - [`Pipeline`](crate::Pipeline) - Pipeline construction
 
+#[cfg(feature = "async")]
+pub mod async_exec;
 pub mod avro;
 pub mod basic;
 pub mod batches;
+pub mod cache;
+pub mod cached_lookup;
 pub mod cloud;
 pub mod co_gbk;
 pub mod collect_sorted;
 pub mod collect_values;
+#[cfg(feature = "io-parquet")]
+pub mod columnar;
 pub mod combine;
 pub mod combine_global;
 pub mod common;
+pub mod compute;
 pub mod count;
 pub mod csv;
 pub mod dead_letter;
 pub mod display;
 pub mod distinct;
+pub mod file_sink;
 pub mod filter;
 pub mod flatten;
+pub mod graph;
+#[cfg(feature = "io-http")]
+pub mod http;
+pub mod inference;
 pub mod joins;
 pub mod jsonl;
+pub mod key_sink;
+pub mod key_value;
 pub mod keyed;
+pub mod keyed_collection;
+#[cfg(feature = "io-parquet")]
+pub mod lakehouse;
 pub mod latest;
+pub mod lineage;
 pub mod log_elements;
+pub mod logfmt;
+pub mod map_io;
 pub mod msgpack;
 pub mod named;
+pub mod object_sink;
 pub mod parquet;
 pub mod partition;
+pub mod pattern;
+#[cfg(feature = "io-proto")]
+pub mod proto;
+pub mod queue;
 pub mod regex;
+pub mod repartition;
 pub mod reshuffle;
+pub mod resource;
+pub mod run_all;
 pub mod sampling;
+pub mod search_index;
+pub mod sessions;
 pub mod side_inputs;
+pub mod similarity;
+pub mod skewed_combine;
+pub mod sort;
+#[cfg(feature = "io-sqlite")]
+pub mod sqlite;
+pub mod stateful;
 pub mod statistical;
 pub mod stdlib;
 pub mod tee;
+#[cfg(feature = "time")]
+pub mod time;
+pub mod time_series;
 pub mod timestamped;
 pub mod topk;
 pub mod try_process;
@@ -322,19 +519,47 @@ pub mod validation;
 pub mod values;
 pub mod wait_on;
 pub mod windowed_combine;
+#[cfg(feature = "metrics")]
+pub mod with_metrics;
 pub mod xml;
 
 // Only re-export files with top-level functions
 pub use avro::*;
 pub use cloud::*;
+#[cfg(feature = "io-parquet")]
+pub use columnar::{ArrowBatch, read_parquet_batches};
 pub use csv::*;
 pub use flatten::*;
+#[cfg(feature = "io-http")]
+pub use http::*;
 pub use jsonl::*;
+#[cfg(feature = "io-parquet")]
+pub use lakehouse::*;
+pub use lineage::{read_csv_with_lineage, read_jsonl_with_lineage};
 pub use msgpack::*;
 pub use parquet::*;
+#[cfg(feature = "io-proto")]
+pub use proto::*;
 pub use side_inputs::*;
+#[cfg(feature = "io-sqlite")]
+pub use sqlite::*;
 pub use stdlib::*;
 pub use xml::*;
 
 // Type re-exports from helpers that aren't free-function modules.
 pub use dead_letter::DeadLetter;
+#[cfg(feature = "io-parquet")]
+pub use file_sink::ParquetSink;
+pub use file_sink::{CsvSink, JsonlSink};
+pub use key_sink::{KeyedFile, PerKeyWriteOptions};
+pub use keyed_collection::KeyedPCollection;
+pub use lineage::{Provenance, Traced};
+pub use logfmt::KeyValueFormat;
+pub use partition::MultiOutput;
+pub use pattern::{Pattern, PatternMatch};
+pub use regex::FromCaptures;
+pub use run_all::Materialized;
+pub use skewed_combine::SkewHint;
+pub use stateful::TimerContext;
+pub use timestamped::EventTime;
+pub use try_process::RetryPolicy;
@@ -141,6 +141,7 @@ pub fn read_msgpack<T>(p: &Pipeline, path: impl AsRef<Path>) -> Result<PCollecti
 where
     T: Element + DeserializeOwned,
 {
+    let location = path.as_ref().display().to_string();
     let path_str = path
         .as_ref()
         .to_str()
@@ -161,10 +162,10 @@ where
                 read_msgpack_vec(&file).with_context(|| format!("reading {}", file.display()))?;
             all_data.extend(data);
         }
-        Ok(from_vec(p, all_data))
+        Ok(from_vec(p, all_data).record_input("msgpack", location))
     } else {
         let v = read_msgpack_vec::<T>(path)?;
-        Ok(from_vec(p, v))
+        Ok(from_vec(p, v).record_input("msgpack", location))
     }
 }
 
@@ -209,6 +210,7 @@ pub fn read_msgpack_streaming<T>(
 where
     T: Element + DeserializeOwned,
 {
+    let location = path.as_ref().display().to_string();
     let shards: MsgpackShards = build_msgpack_shards(path, records_per_shard)?;
     let id = p.insert_node(Node::Source {
         payload: Arc::new(shards),
@@ -216,6 +218,7 @@ where
         elem_tag: TypeTag::of::<T>(),
     });
     p.set_coder::<T>(id);
+    p.record_input(id, "msgpack", location);
     Ok(PCollection {
         pipeline: p.clone(),
         id,
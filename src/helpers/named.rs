@@ -1,9 +1,9 @@
 //! Fluent labeling helper for [`PCollection`].
 //!
 //! [`PCollection::with_name`] attaches a human-readable name to the node that
-//! produced this collection. The name is pure metadata: planning and execution
-//! are completely unaffected, so adding (or omitting) names never changes the
-//! result of a pipeline.
+//! produced this collection. The name is metadata: planning is unaffected and
+//! execution only uses it for attribution, so adding (or omitting) names never
+//! changes the result of a pipeline.
 //!
 //! Names are intended for **observability and external backends**. Apache Beam
 //! lets every applied transform carry a label (`data | "Split" >>
@@ -15,8 +15,13 @@
 //!   can forward meaningful step names to the remote runner instead of the
 //!   generic op category;
 //! - The local
-//!   [`ExecutionExplanation`](crate::planner::ExecutionExplanation) view can
-//!   render the user-chosen label next to each step.
+//!   [`ExecutionExplanation`](crate::planner::ExecutionExplanation) view and the
+//!   [DOT / Mermaid exports](crate::visualize) can render the user-chosen label
+//!   next to each step;
+//! - A panic inside a named stateless stage is reported as an
+//!   [`IronbeamError::PanicInTransform`](crate::IronbeamError::PanicInTransform)
+//!   carrying the name, and with the `metrics` feature the time spent in the
+//!   stage is accumulated into a `stage.<name>.micros` counter.
 //!
 //! ## Why "post-fix"?
 //!
@@ -0,0 +1,180 @@
+//! Pipeline writes to object storage.
+//!
+//! [`write_jsonl_object`](PCollection::write_jsonl_object),
+//! [`write_csv_object`](PCollection::write_csv_object) and
+//! [`write_parquet_object`](PCollection::write_parquet_object) push a collection to an
+//! [`ObjectIO`] store.
+//!
+//! Like the file writers (see [`file_sink`](crate::helpers::file_sink)), each serializes
+//! every partition in an encode step of the pipeline, to a part file in the system's
+//! temporary directory that is removed once the upload finishes. Every non-empty
+//! partition then becomes one self-contained object: `{key_prefix}part-00000.jsonl`,
+//! `{key_prefix}part-00001.jsonl`, and so on in partition order. CSV objects each start
+//! with their own header row. The objects are uploaded in parallel with
+//! [`upload_object`], which splits large objects into a multipart upload and retries
+//! every request with backoff. The number of objects is set by
+//! [`ObjectWriteOptions::shards`].
+//!
+//! ```
+//! use ironbeam::io::cloud::upload::ObjectWriteOptions;
+//! use ironbeam::io::cloud::{FakeObjectIO, ObjectIO};
+//! use ironbeam::*;
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let storage = FakeObjectIO::new();
+//! let p = Pipeline::default();
+//! let opts = ObjectWriteOptions::default().with_shards(2);
+//! let n = from_vec(&p, vec![1u32, 2, 3, 4]).write_jsonl_object(&storage, "bucket", "out/", &opts)?;
+//! assert_eq!(n, 4);
+//! assert!(storage.object_exists("bucket", "out/part-00001.jsonl")?);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::Element;
+use crate::PCollection;
+use crate::helpers::file_sink::{EncodedPart, PartFile};
+use crate::io::atomic::SUCCESS_MARKER;
+use crate::io::cloud::traits::ObjectIO;
+use crate::io::cloud::upload::{ObjectWriteOptions, upload_object};
+use crate::io::cloud::utils::retry_with_backoff;
+use crate::io::csv::encode_csv_part;
+use crate::io::jsonl::encode_jsonl_part;
+use crate::io::parquet::encode_parquet_part;
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::env::temp_dir;
+
+impl<T: Element + Serialize> PCollection<T> {
+    /// Execute the collection and upload it as JSONL objects under `key_prefix`, one per
+    /// non-empty shard.
+    ///
+    /// Returns the number of rows written.
+    ///
+    /// # Errors
+    /// Propagates execution and serialization errors, and the error of any upload that
+    /// still fails after its retries.
+    pub fn write_jsonl_object(
+        self,
+        storage: &dyn ObjectIO,
+        bucket: &str,
+        key_prefix: &str,
+        opts: &ObjectWriteOptions,
+    ) -> Result<usize> {
+        let bucket = self.pipeline.resolve_config(bucket)?;
+        let key_prefix = self.pipeline.resolve_config(key_prefix)?;
+        let (_part_dir, parts) = self.encode_parts(
+            "write_jsonl_object",
+            opts.shards,
+            &temp_dir(),
+            |rows, parts| {
+                parts.write(EncodedPart {
+                    rows: rows.len(),
+                    header: 0,
+                    bytes: encode_jsonl_part(rows)?,
+                })
+            },
+        )?;
+        upload_parts(storage, &bucket, &key_prefix, "jsonl", &parts, opts)
+    }
+
+    /// Execute the collection and upload it as CSV objects under `key_prefix`, one per
+    /// non-empty shard. With `has_headers`, every object starts with a header row.
+    ///
+    /// Returns the number of rows written.
+    ///
+    /// # Errors
+    /// Propagates execution and serialization errors, and the error of any upload that
+    /// still fails after its retries.
+    pub fn write_csv_object(
+        self,
+        storage: &dyn ObjectIO,
+        bucket: &str,
+        key_prefix: &str,
+        has_headers: bool,
+        opts: &ObjectWriteOptions,
+    ) -> Result<usize> {
+        let bucket = self.pipeline.resolve_config(bucket)?;
+        let key_prefix = self.pipeline.resolve_config(key_prefix)?;
+        let (_part_dir, parts) = self.encode_parts(
+            "write_csv_object",
+            opts.shards,
+            &temp_dir(),
+            move |rows, parts| {
+                let (header, bytes) = encode_csv_part(rows, has_headers)?;
+                parts.write(EncodedPart {
+                    rows: rows.len(),
+                    header,
+                    bytes,
+                })
+            },
+        )?;
+        upload_parts(storage, &bucket, &key_prefix, "csv", &parts, opts)
+    }
+}
+
+impl<T: Element + Serialize + DeserializeOwned> PCollection<T> {
+    /// Execute the collection and upload it as Parquet objects under `key_prefix`, one
+    /// per non-empty shard, each with the schema inferred from `T`.
+    ///
+    /// Returns the number of rows written.
+    ///
+    /// # Errors
+    /// Propagates execution and conversion errors, and the error of any upload that still
+    /// fails after its retries. When the `io-parquet` feature is disabled, always returns
+    /// an error.
+    pub fn write_parquet_object(
+        self,
+        storage: &dyn ObjectIO,
+        bucket: &str,
+        key_prefix: &str,
+        opts: &ObjectWriteOptions,
+    ) -> Result<usize> {
+        let bucket = self.pipeline.resolve_config(bucket)?;
+        let key_prefix = self.pipeline.resolve_config(key_prefix)?;
+        let (_part_dir, parts) = self.encode_parts(
+            "write_parquet_object",
+            opts.shards,
+            &temp_dir(),
+            |rows, parts| {
+                parts.write(EncodedPart {
+                    rows: rows.len(),
+                    header: 0,
+                    bytes: encode_parquet_part(rows)?,
+                })
+            },
+        )?;
+        upload_parts(storage, &bucket, &key_prefix, "parquet", &parts, opts)
+    }
+}
+
+/// Upload each non-empty part as `{key_prefix}part-NNNNN.{extension}`, then the
+/// `_SUCCESS` marker if requested.
+fn upload_parts(
+    storage: &dyn ObjectIO,
+    bucket: &str,
+    key_prefix: &str,
+    extension: &str,
+    parts: &[PartFile],
+    opts: &ObjectWriteOptions,
+) -> Result<usize> {
+    let objects: Vec<(String, &PartFile)> = parts
+        .iter()
+        .filter(|part| part.rows > 0)
+        .enumerate()
+        .map(|(i, part)| (format!("{key_prefix}part-{i:05}.{extension}"), part))
+        .collect();
+    objects.par_iter().try_for_each(|(key, part)| {
+        let bytes = part.read()?;
+        upload_object(storage, bucket, key, &bytes, opts)
+            .with_context(|| format!("upload {bucket}/{key}"))
+    })?;
+    if opts.success_marker {
+        let marker = format!("{key_prefix}{SUCCESS_MARKER}");
+        retry_with_backoff(&opts.retry, || storage.put_object(bucket, &marker, &[]))
+            .with_context(|| format!("upload {bucket}/{marker}"))?;
+    }
+    Ok(parts.iter().map(|part| part.rows).sum())
+}
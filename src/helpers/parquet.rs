@@ -5,7 +5,13 @@
 //!
 //! ## Available operations
 //! - [`read_parquet_streaming`] - Read Parquet file(s) as a streaming source
+//! - [`read_parquet_streaming_with`] - Same, with a [`SchemaCoercion`] policy for files
+//!   written with an older schema
+//! - [`read_parquet_timestamped`] - Read Parquet file(s) eagerly, stamping rows with an
+//!   [`EventTime`]
 //! - [`PCollection::write_parquet`](PCollection::write_parquet) - Write a collection to a Parquet file
+//! - [`PCollection::write_parquet_with`](PCollection::write_parquet_with) - Same, with
+//!   [`ParquetWriteOptions`] for row group size, compression and encodings
 //!
 //! ### Notes
 //! - Requires the `io-parquet` feature (Arrow/Parquet + serde-arrow integration).
@@ -13,21 +19,28 @@
 //!   `Serialize` for writing and `Deserialize` for reading.
 //! - The streaming reader divides the file by **row groups** (not by bytes/rows).
 //!   Each partition reads its assigned row-group range and deserializes into `Vec<T>`.
-//! - Writing collects results **sequentially** first (deterministic order), then
-//!   writes a single Parquet file.
+//! - Writing converts each partition to an Arrow batch inside the run (see
+//!   [`file_sink`](crate::helpers::file_sink)), then writes the batches to a single
+//!   Parquet file in partition order.
 //!
 //! ### When to use
 //! - Use `write_parquet` to export final results in a columnar, analytics-friendly format.
 //! - Use `read_parquet_streaming` for large datasets where loading the entire file
 //!   would be too expensive; processing happens partition-by-partition.
 
+#[cfg(feature = "io-parquet")]
+use crate::helpers::ArrowBatch;
+use crate::helpers::timestamped::EventTime;
 use crate::io::glob::expand_glob;
+#[cfg(feature = "io-parquet")]
+use crate::io::parquet::write_parquet_batches_with_schema;
 use crate::io::parquet::{
-    ParquetShards, ParquetVecOps, build_parquet_shards, read_parquet_vec, write_parquet_vec,
+    ParquetShards, ParquetVecOps, ParquetWriteOptions, SchemaCoercion, build_parquet_shards,
+    check_parquet_coercion, read_parquet_vec, read_parquet_vec_with, write_parquet_vec_with,
 };
 use crate::node::Node;
 use crate::type_token::TypeTag;
-use crate::{Element, PCollection, Pipeline, from_vec};
+use crate::{Element, PCollection, Pipeline, Timestamped, from_vec};
 use anyhow::{Context, Result, anyhow, bail};
 use regex::Regex;
 use serde::Serialize;
@@ -39,9 +52,9 @@ use std::sync::Arc;
 impl<T: Element + DeserializeOwned + Serialize> PCollection<T> {
     /// Execute the pipeline, collect results, and write them to a **single Parquet file**.
     ///
-    /// The Arrow schema is inferred from `T` (via `serde-arrow`). The entire collection
-    /// is first collected into memory (sequentially) to preserve deterministic ordering
-    /// and then written as one Parquet file.
+    /// The Arrow schema is inferred from `T` (via `serde-arrow`). Each partition is
+    /// converted to an Arrow batch by the runner as the last step of the pipeline, and
+    /// the batches are written as one Parquet file in partition order.
     ///
     /// Returns the number of rows written.
     ///
@@ -71,8 +84,48 @@ impl<T: Element + DeserializeOwned + Serialize> PCollection<T> {
     ///
     /// If an error is encountered while writing the Parquet file, a [`Result`] is returned.
     pub fn write_parquet(self, path: impl AsRef<Path>) -> Result<usize> {
-        let rows: Vec<T> = self.collect_seq()?;
-        write_parquet_vec(path, &rows)
+        self.write_parquet_with(path, &ParquetWriteOptions::default())
+    }
+
+    /// Write the collection to a single Parquet file as
+    /// [`write_parquet`](Self::write_parquet) does, with the row groups, compression and
+    /// encodings set by `opts`.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// use ironbeam::*;
+    /// use ironbeam::io::parquet::{ParquetCompression, ParquetWriteOptions};
+    /// use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// #[derive(serde::Serialize, serde::Deserialize, Clone)]
+    /// struct Row { k: String, v: u64 }
+    ///
+    /// let p = Pipeline::default();
+    /// let opts = ParquetWriteOptions::default()
+    ///     .with_row_group_size(50_000)
+    ///     .with_compression(ParquetCompression::Snappy);
+    /// from_vec(&p, vec![Row { k: "a".into(), v: 1 }]).write_parquet_with("data/out.parquet", &opts)?;
+    /// # Ok(()) }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `opts` is invalid, or for the reasons of
+    /// [`write_parquet`](Self::write_parquet).
+    pub fn write_parquet_with(
+        self,
+        path: impl AsRef<Path>,
+        opts: &ParquetWriteOptions,
+    ) -> Result<usize> {
+        #[cfg(feature = "io-parquet")]
+        {
+            let runner = self.write_runner(None)?;
+            let batches = self.into_arrow_batches()?.with_name("write_parquet");
+            let batches = runner.run_collect::<ArrowBatch>(&batches.pipeline, batches.id)?;
+            write_parquet_batches_with_schema::<T>(path, &batches, opts)
+        }
+        #[cfg(not(feature = "io-parquet"))]
+        write_parquet_vec_with::<T>(path, &Vec::new(), opts)
     }
 }
 
@@ -146,11 +199,131 @@ pub fn read_parquet_streaming<T>(
     path: impl AsRef<Path>,
     groups_per_shard: usize,
 ) -> Result<PCollection<T>>
+where
+    T: Element + DeserializeOwned,
+{
+    read_parquet_source(p, path.as_ref(), groups_per_shard, None)
+}
+
+/// Read Parquet file(s) as a streaming source as [`read_parquet_streaming`] does,
+/// reconciling each file's columns with the schema of `T` under `coercion`, so files
+/// written before a field was added or widened can be read together with newer ones.
+///
+/// ### Example
+/// ```no_run
+/// use ironbeam::*;
+/// use ironbeam::io::parquet::SchemaCoercion;
+/// use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// #[derive(serde::Serialize, serde::Deserialize, Clone)]
+/// struct Rec { k: String, v: i64, region: Option<String> }
+///
+/// let p = Pipeline::default();
+/// // Older files store `v` as i32 and have no `region` column.
+/// let rows = read_parquet_streaming_with::<Rec>(
+///     &p,
+///     "data/year=*/*.parquet",
+///     1,
+///     &SchemaCoercion::default(),
+/// )?;
+/// # Ok(()) }
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if a file's columns cannot be coerced under the policy (checked
+/// when the source is built), or for the reasons of [`read_parquet_streaming`].
+pub fn read_parquet_streaming_with<T>(
+    p: &Pipeline,
+    path: impl AsRef<Path>,
+    groups_per_shard: usize,
+    coercion: &SchemaCoercion,
+) -> Result<PCollection<T>>
+where
+    T: Element + DeserializeOwned,
+{
+    read_parquet_source(p, path.as_ref(), groups_per_shard, Some(coercion))
+}
+
+/// Read Parquet file(s) into memory, stamping each row with its event time as it is
+/// read, so windowing can follow without an
+/// [`attach_timestamps`](PCollection::attach_timestamps) step. Glob patterns are
+/// supported as in [`read_parquet_streaming`]. Unlike that source, the rows are read
+/// eagerly when this is called.
+///
+/// ### Example
+/// ```no_run
+/// use ironbeam::*;
+/// use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// #[derive(serde::Serialize, serde::Deserialize, Clone)]
+/// struct Trade { ts_ms: u64, symbol: String, qty: u32 }
+///
+/// let p = Pipeline::default();
+/// let trades = read_parquet_timestamped::<Trade>(
+///     &p,
+///     "trades/day=*/*.parquet",
+///     &EventTime::extract(|t: &Trade| t.ts_ms),
+/// )?;
+/// # Ok(()) }
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if `path` contains invalid UTF-8, a glob pattern matches no
+/// files, a file cannot be read into `T`, or an [`EventTime::Field`] is missing from a
+/// row or does not hold a millisecond timestamp.
+///
+/// # Panics
+///
+/// Panics if the regex engine fails.
+pub fn read_parquet_timestamped<T>(
+    p: &Pipeline,
+    path: impl AsRef<Path>,
+    time: &EventTime<T>,
+) -> Result<PCollection<Timestamped<T>>>
+where
+    T: Element + DeserializeOwned + Serialize,
+{
+    let path = path.as_ref();
+    let path_str = path
+        .to_str()
+        .ok_or_else(|| anyhow!("path contains invalid UTF-8"))?;
+
+    let glob_regex = Regex::new(r"[*?\[]").expect("valid glob regex");
+    let files = if glob_regex.is_match(path_str) {
+        let files =
+            expand_glob(path_str).with_context(|| format!("expanding glob pattern: {path_str}"))?;
+        if files.is_empty() {
+            bail!("no files found matching pattern: {path_str}");
+        }
+        files
+    } else {
+        vec![path.to_path_buf()]
+    };
+
+    let mut all_data = Vec::new();
+    for file in files {
+        let data: Vec<T> =
+            read_parquet_vec(&file).with_context(|| format!("reading {}", file.display()))?;
+        all_data.extend(data);
+    }
+    let data = time
+        .stamp(all_data)
+        .with_context(|| format!("reading {}", path.display()))?;
+    Ok(from_vec(p, data).record_input("parquet", path.display()))
+}
+
+fn read_parquet_source<T>(
+    p: &Pipeline,
+    path: &Path,
+    groups_per_shard: usize,
+    coercion: Option<&SchemaCoercion>,
+) -> Result<PCollection<T>>
 where
     T: Element + DeserializeOwned,
 {
     let path_str = path
-        .as_ref()
         .to_str()
         .ok_or_else(|| anyhow!("path contains invalid UTF-8"))?;
 
@@ -165,19 +338,30 @@ where
 
         let mut all_data = Vec::new();
         for file in files {
-            let data: Vec<T> =
-                read_parquet_vec(&file).with_context(|| format!("reading {}", file.display()))?;
+            let data: Vec<T> = match coercion {
+                Some(coercion) => read_parquet_vec_with(&file, coercion),
+                None => read_parquet_vec(&file),
+            }
+            .with_context(|| format!("reading {}", file.display()))?;
             all_data.extend(data);
         }
-        Ok(from_vec(p, all_data))
+        Ok(from_vec(p, all_data).record_input("parquet", path.display()))
     } else {
         let shards: ParquetShards = build_parquet_shards(path, groups_per_shard)?;
+        let vec_ops = match coercion {
+            Some(coercion) => {
+                check_parquet_coercion::<T>(path, coercion)?;
+                ParquetVecOps::<T>::with_coercion(*coercion)
+            }
+            None => ParquetVecOps::<T>::new(),
+        };
         let id = p.insert_node(Node::Source {
             payload: Arc::new(shards),
-            vec_ops: ParquetVecOps::<T>::new(),
+            vec_ops,
             elem_tag: TypeTag::of::<T>(),
         });
         p.set_coder::<T>(id);
+        p.record_input(id, "parquet", path.display());
         Ok(PCollection {
             pipeline: p.clone(),
             id,
@@ -15,6 +15,14 @@
 //! 3. Use [`filter_map`](crate::PCollection::filter_map) to extract each variant
 //! 4. Or use the [`partition!`](macro@crate::partition) macro for convenience
 //!
+//! Two methods package the most common shapes of this pattern:
+//! - [`PCollection::flat_map_multi`] -- one transform writes to a **main** and a **side**
+//!   output of independent types through a [`MultiOutput`] emitter (Beam's `TaggedOutput`).
+//! - [`PCollection::partition_by`] -- split into `n` same-typed collections by an index
+//!   function (Beam's `Partition`).
+//! - [`PCollection::split_randomly`] -- split into disjoint, reproducible random subsets
+//!   with given proportions, such as train/validation/test sets.
+//!
 //! This approach is:
 //! - **Type-safe**: Enum variants are checked at compile time
 //! - **Explicit**: No runtime tag registration needed
@@ -127,33 +135,265 @@
 //!
 //! ## Design Rationale
 //!
-//! ### Why Not True Multi-Output?
-//!
-//! A true multi-output transform (like Apache Beam's `ParDo` with `MultiOutputReceiver`)
-//! would require:
-//! - Runtime type erasure and downcasting of multiple output types
-//! - Complex graph modifications to support multiple output edges from one node
-//! - Loss of type safety at compile time
-//! - More complex implementation and maintenance
+//! ### How the Outputs Share Work
 //!
-//! ### Benefits of the Enum Approach
+//! The graph has no node with several typed output edges. Multi-output is built from
+//! ordinary nodes instead:
 //!
-//! - **Type Safety**: All output types are known at compile time
-//! - **Simplicity**: Uses existing `flat_map` and `filter_map` primitives
-//! - **Clarity**: Explicit enum variants make the code self-documenting
-//! - **Flexibility**: Easy to add new output types by adding enum variants
-//! - **Performance**: Efficient filtering via pattern matching
+//! - With [`partition!`](macro@crate::partition) or a hand-written enum, every output
+//!   is a plain `filter_map` branch of the classifying collection. Each terminal plans
+//!   and runs its own branch (the planner prunes the branches it does not need), so
+//!   consuming several outputs in separate runs classifies the input once per run.
+//! - [`flat_map_multi`](PCollection::flat_map_multi) and
+//!   [`partition_by`](PCollection::partition_by) route every element once into a
+//!   [cached](PCollection::cache) collection of tagged elements and derive each output
+//!   from it. The routing pass runs the first time any output is read, as part of that
+//!   run: on the caller's runner, with its mode, memory limits, spill settings and
+//!   cancellation token. Every other output, in the same run or a later one, reads the
+//!   stored elements.
 //!
-//! ## Performance Considerations
+//! The enum keeps every output type known at compile time, and the cached routing pass
+//! keeps user code from running more than once per element.
 //!
-//! The enum approach creates intermediate collections, but this is efficient because:
-//! - Enums are stack-allocated and have minimal overhead
-//! - The compiler optimizes pattern matching
-//! - The planner can fuse operations where possible
-//! - Memory usage is proportional to the largest output, not the sum
+//! ## Memory Considerations
 //!
-//! For most use cases, the performance difference compared to a true multi-output
-//! transform is negligible, while the code clarity and type safety benefits are significant.
+//! The routed elements of `flat_map_multi` and `partition_by` stay in memory for as long
+//! as the pipeline (or any plan built from it) is alive, like any cached collection.
+//! For large inputs where recomputing is cheaper than holding the routed data, use
+//! [`partition!`](macro@crate::partition) or `filter_map` branches instead.
+
+use crate::{Element, PCollection};
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// Emitter handed to the closure of [`PCollection::flat_map_multi`].
+///
+/// Each call to [`emit`](Self::emit) adds an element to the main output and each call
+/// to [`emit_side`](Self::emit_side) adds one to the side output. A single input
+/// element may emit any number of values to either output (including none).
+pub struct MultiOutput<M, S> {
+    routed: Vec<Routed<M, S>>,
+}
+
+impl<M, S> MultiOutput<M, S> {
+    /// Emit `value` to the main output.
+    pub fn emit(&mut self, value: M) {
+        self.routed.push(Routed::Main(value));
+    }
+
+    /// Emit `value` to the side output.
+    pub fn emit_side(&mut self, value: S) {
+        self.routed.push(Routed::Side(value));
+    }
+}
+
+/// Internal carrier between the user's closure and the two output `filter_map`s, which
+/// both read the cached routing pass.
+/// Never appears in any public type signature.
+#[derive(Clone)]
+#[cfg_attr(feature = "coders", derive(serde::Serialize, serde::Deserialize))]
+enum Routed<M, S> {
+    Main(M),
+    Side(S),
+}
+
+impl<T: Element> PCollection<T> {
+    /// 1→N transform with a main and a side output.
+    ///
+    /// `f` is called once per element with a [`MultiOutput`] emitter; values passed to
+    /// [`MultiOutput::emit`] flow into the first returned collection and values passed
+    /// to [`MultiOutput::emit_side`] flow into the second. The two outputs are
+    /// independent collections and may have different element types.
+    ///
+    /// Both outputs read one [cached](PCollection::cache) routing pass, so the upstream
+    /// and `f` run once however many outputs are consumed; the routed elements stay in
+    /// memory while the pipeline is alive.
+    ///
+    /// For more than two outputs, make the side type an enum and split it further with
+    /// [`partition!`](macro@crate::partition).
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use anyhow::Result;
+    /// use ironbeam::*;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let lines = from_vec(&p, vec!["1,2".to_string(), "x".into(), "3".into()]);
+    ///
+    /// let (numbers, errors) = lines.flat_map_multi(|line: &String, out| {
+    ///     for tok in line.split(',') {
+    ///         match tok.parse::<u32>() {
+    ///             Ok(n) => out.emit(n),
+    ///             Err(e) => out.emit_side(format!("{tok}: {e}")),
+    ///         }
+    ///     }
+    /// });
+    /// assert_eq!(numbers.collect_seq_sorted()?, vec![1, 2, 3]);
+    /// assert_eq!(errors.collect_seq()?.len(), 1);
+    /// # Ok(()) }
+    /// ```
+    #[must_use]
+    pub fn flat_map_multi<M, S, F>(self, f: F) -> (PCollection<M>, PCollection<S>)
+    where
+        M: Element,
+        S: Element,
+        F: 'static + Send + Sync + Fn(&T, &mut MultiOutput<M, S>),
+    {
+        let routed: PCollection<Routed<M, S>> = self
+            .flat_map(move |t| {
+                let mut out = MultiOutput { routed: Vec::new() };
+                f(t, &mut out);
+                out.routed
+            })
+            .cache();
+
+        let main = routed.filter_map(|r: &Routed<M, S>| match r {
+            Routed::Main(m) => Some(m.clone()),
+            Routed::Side(_) => None,
+        });
+        let side = routed.filter_map(|r: &Routed<M, S>| match r {
+            Routed::Side(s) => Some(s.clone()),
+            Routed::Main(_) => None,
+        });
+
+        (main, side)
+    }
+
+    /// Split into `n` collections by an index function.
+    ///
+    /// `f` returns the output index (`0..n`) for each element; the element is routed to
+    /// the collection at that position of the returned `Vec`. Every element lands in
+    /// exactly one output.
+    ///
+    /// As with [`flat_map_multi`](PCollection::flat_map_multi), the outputs read one
+    /// [cached](PCollection::cache) routing pass, which stays in memory while the
+    /// pipeline is alive.
+    ///
+    /// # Panics
+    /// Panics if `n == 0`. The pipeline panics at execution time if `f` returns an
+    /// index `>= n`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use anyhow::Result;
+    /// use ironbeam::*;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let parts = from_vec(&p, (1u32..=6).collect::<Vec<_>>()).partition_by(3, |x| (*x % 3) as usize);
+    /// assert_eq!(parts[0].clone().collect_seq()?, vec![3, 6]);
+    /// assert_eq!(parts[1].clone().collect_seq()?, vec![1, 4]);
+    /// assert_eq!(parts[2].clone().collect_seq()?, vec![2, 5]);
+    /// # Ok(()) }
+    /// ```
+    #[must_use]
+    pub fn partition_by<F>(self, n: usize, f: F) -> Vec<Self>
+    where
+        F: 'static + Send + Sync + Fn(&T) -> usize,
+    {
+        assert!(n > 0, "partition_by requires at least one output");
+        let indexed: PCollection<(usize, T)> = self
+            .map(move |t| {
+                let idx = f(t);
+                assert!(
+                    idx < n,
+                    "partition_by: index {idx} out of range for {n} outputs"
+                );
+                (idx, t.clone())
+            })
+            .cache();
+        (0..n)
+            .map(|i| {
+                indexed.filter_map(move |(idx, t): &(usize, T)| (*idx == i).then(|| t.clone()))
+            })
+            .collect()
+    }
+}
+
+impl<T: Element + Hash> PCollection<T> {
+    /// Split into disjoint random subsets with the given relative `weights`.
+    ///
+    /// Returns one collection per weight; each element lands in exactly one of them,
+    /// with probability proportional to its weight (`&[0.8, 0.1, 0.1]` gives an
+    /// 80/10/10 train/validation/test split). The assignment is a seeded hash of the
+    /// element, so it is reproducible across runs, execution modes, and partitionings,
+    /// and equal elements always land in the same output.
+    ///
+    /// # Panics
+    /// Panics if `weights` is empty, contains a negative or non-finite weight, or sums
+    /// to zero.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use anyhow::Result;
+    /// use ironbeam::*;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let splits = from_vec(&p, (0u32..1_000).collect::<Vec<_>>()).split_randomly(&[0.8, 0.1, 0.1], 42);
+    /// let [train, validation, test] = &splits[..] else { unreachable!() };
+    /// let sizes = [train, validation, test].map(|s| s.clone().count_globally().collect_seq().unwrap()[0]);
+    /// assert_eq!(sizes.iter().sum::<u64>(), 1_000);
+    /// # Ok(()) }
+    /// ```
+    #[must_use]
+    pub fn split_randomly(self, weights: &[f64], seed: u64) -> Vec<Self> {
+        self.split_randomly_by(T::clone, weights, seed)
+    }
+}
+
+impl<T: Element> PCollection<T> {
+    /// Like [`split_randomly`](PCollection::split_randomly), but assigns each element by
+    /// the hash of `key_fn(&element)`.
+    ///
+    /// Elements with the same key always land in the same output, which keeps related
+    /// records (all events of one user, say) out of more than one split. It also works
+    /// for element types that are not `Hash`.
+    ///
+    /// # Panics
+    /// Panics if `weights` is empty, contains a negative or non-finite weight, or sums
+    /// to zero.
+    #[must_use]
+    pub fn split_randomly_by<K, F>(self, key_fn: F, weights: &[f64], seed: u64) -> Vec<Self>
+    where
+        K: Hash,
+        F: 'static + Send + Sync + Fn(&T) -> K,
+    {
+        assert!(
+            !weights.is_empty(),
+            "split_randomly requires at least one weight"
+        );
+        assert!(
+            weights.iter().all(|w| w.is_finite() && *w >= 0.0),
+            "split_randomly: weights must be finite and non-negative"
+        );
+        let total: f64 = weights.iter().sum();
+        assert!(total > 0.0, "split_randomly: weights must not all be zero");
+
+        // Cumulative upper bounds in [0, 1]. The bound of the last positive weight is
+        // pinned to 1 so rounding can never leave `u` unassigned.
+        let mut bounds: Vec<f64> = weights
+            .iter()
+            .scan(0.0, |acc, w| {
+                *acc += w / total;
+                Some(*acc)
+            })
+            .collect();
+        let last_positive = weights.iter().rposition(|w| *w > 0.0).unwrap_or(0);
+        bounds[last_positive..].fill(1.0);
+        let n = bounds.len();
+
+        self.partition_by(n, move |t| {
+            let mut hasher = DefaultHasher::new();
+            seed.hash(&mut hasher);
+            key_fn(t).hash(&mut hasher);
+            #[allow(clippy::cast_precision_loss)]
+            let u = (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64;
+            // Zero-weight outputs have empty ranges and are never chosen.
+            bounds.iter().position(|&b| u < b).unwrap_or(last_positive)
+        })
+    }
+}
 
 /// Partition a `PCollection` of enum values into separate collections by variant.
 ///
@@ -0,0 +1,299 @@
+//! Sequence pattern detection (lightweight CEP) over keyed, timestamped streams.
+//!
+//! A [`Pattern`] is an ordered list of predicate **steps**, optionally guarded by
+//! **absences** ("not followed by") and bounded by a time limit. For example, the
+//! abandoned-cart funnel "view, then add to cart, then no purchase within 30 minutes":
+//!
+//! ```text
+//! Pattern::begin("view", is_view)
+//!     .followed_by("cart", is_cart)
+//!     .not_followed_by("purchase", is_purchase)
+//!     .within(30 * 60 * 1_000)
+//! ```
+//!
+//! [`PCollection::match_pattern`] groups a `(K, Timestamped<V>)` stream by key, sorts
+//! each key's events by timestamp, and runs the pattern as a small NFA over them,
+//! emitting one `(K, PatternMatch<V>)` per match.
+//!
+//! ## Matching rules
+//! - Steps need not be adjacent: unrelated events between two steps are skipped.
+//! - Every event that satisfies the first step starts a new attempt, but each event is
+//!   consumed by at most one attempt; older attempts take events first.
+//! - An absence cancels an attempt if a matching event arrives after the previous step
+//!   and before the next one. A trailing absence must hold until the time limit runs out
+//!   (or, without a limit, until the end of the key's events).
+//! - With [`within`](Pattern::within), the time from the first step to any later step or
+//!   absence check is at most the limit, inclusive.
+//!
+//! The input is bounded, so a trailing absence that has not been violated by the end of
+//! the data counts as satisfied.
+//!
+//! ## Example
+//! ```
+//! use ironbeam::*;
+//! use anyhow::Result;
+//! # fn main() -> Result<()> {
+//! let p = Pipeline::default();
+//! let events = from_vec(&p, vec![
+//!     ("u1".to_string(), Timestamped::new(0, "view".to_string())),
+//!     ("u1".to_string(), Timestamped::new(60_000, "cart".to_string())),
+//!     ("u1".to_string(), Timestamped::new(120_000, "purchase".to_string())),
+//!     ("u2".to_string(), Timestamped::new(0, "view".to_string())),
+//!     ("u2".to_string(), Timestamped::new(30_000, "cart".to_string())),
+//! ]);
+//!
+//! let abandoned = Pattern::begin("view", |e: &String| e == "view")
+//!     .followed_by("cart", |e: &String| e == "cart")
+//!     .not_followed_by("purchase", |e: &String| e == "purchase")
+//!     .within(30 * 60 * 1_000);
+//!
+//! let matches = events.match_pattern(&abandoned).collect_seq()?;
+//! assert_eq!(matches.len(), 1);
+//! assert_eq!(matches[0].0, "u2");
+//! assert_eq!((matches[0].1.start, matches[0].1.end), (0, 30_000));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{Element, PCollection, TimestampMs, Timestamped};
+use serde::{Deserialize, Serialize};
+use std::fmt::{Debug, Formatter, Result as FormatResult};
+use std::hash::Hash;
+use std::sync::Arc;
+
+type Predicate<V> = Arc<dyn Fn(&V) -> bool + Send + Sync>;
+
+/// One named predicate of a [`Pattern`].
+struct Step<V> {
+    name: String,
+    pred: Predicate<V>,
+}
+
+impl<V> Clone for Step<V> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            pred: Arc::clone(&self.pred),
+        }
+    }
+}
+
+/// An ordered sequence of event predicates, matched per key by
+/// [`PCollection::match_pattern`].
+///
+/// Built with [`begin`](Self::begin), then [`followed_by`](Self::followed_by) and
+/// [`not_followed_by`](Self::not_followed_by) in order, and optionally
+/// [`within`](Self::within). See the [module docs](self) for the matching rules.
+pub struct Pattern<V> {
+    steps: Vec<Step<V>>,
+    // absences[i] must not occur before steps[i]; absences[steps.len()] is trailing.
+    absences: Vec<Vec<Step<V>>>,
+    within_ms: Option<u64>,
+}
+
+impl<V> Clone for Pattern<V> {
+    fn clone(&self) -> Self {
+        Self {
+            steps: self.steps.clone(),
+            absences: self.absences.clone(),
+            within_ms: self.within_ms,
+        }
+    }
+}
+
+impl<V> Debug for Pattern<V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        let names = |steps: &[Step<V>]| steps.iter().map(|s| s.name.clone()).collect::<Vec<_>>();
+        f.debug_struct("Pattern")
+            .field("steps", &names(&self.steps))
+            .field(
+                "absences",
+                &self.absences.iter().map(|a| names(a)).collect::<Vec<_>>(),
+            )
+            .field("within_ms", &self.within_ms)
+            .finish()
+    }
+}
+
+impl<V> Pattern<V> {
+    /// Start a pattern whose first step is an event satisfying `pred`.
+    pub fn begin<F>(name: impl Into<String>, pred: F) -> Self
+    where
+        F: Fn(&V) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            steps: vec![Step {
+                name: name.into(),
+                pred: Arc::new(pred),
+            }],
+            absences: vec![Vec::new(), Vec::new()],
+            within_ms: None,
+        }
+    }
+
+    /// Add a step: a later event satisfying `pred`.
+    #[must_use]
+    pub fn followed_by<F>(mut self, name: impl Into<String>, pred: F) -> Self
+    where
+        F: Fn(&V) -> bool + Send + Sync + 'static,
+    {
+        self.steps.push(Step {
+            name: name.into(),
+            pred: Arc::new(pred),
+        });
+        self.absences.push(Vec::new());
+        self
+    }
+
+    /// Require that no event satisfying `pred` occurs between the previous step and the
+    /// next one, or, as the last call, after the final step.
+    #[must_use]
+    pub fn not_followed_by<F>(mut self, name: impl Into<String>, pred: F) -> Self
+    where
+        F: Fn(&V) -> bool + Send + Sync + 'static,
+    {
+        let guards = self
+            .absences
+            .last_mut()
+            .expect("a pattern always has a trailing absence list");
+        guards.push(Step {
+            name: name.into(),
+            pred: Arc::new(pred),
+        });
+        self
+    }
+
+    /// Limit a match to `ms` milliseconds from its first step.
+    #[must_use]
+    pub const fn within(mut self, ms: u64) -> Self {
+        self.within_ms = Some(ms);
+        self
+    }
+
+    /// Names of the steps, in order; [`PatternMatch::events`] follows the same order.
+    #[must_use]
+    pub fn step_names(&self) -> Vec<&str> {
+        self.steps.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    /// Run the pattern over one key's events, which must be sorted by timestamp.
+    fn find(&self, events: Vec<Timestamped<V>>) -> Vec<PatternMatch<V>> {
+        let n = self.steps.len();
+        let trailing = &self.absences[n];
+        let expired = |run: &[Timestamped<V>], ts: TimestampMs| {
+            self.within_ms.is_some_and(|w| ts - run[0].ts > w)
+        };
+        let hits = |guards: &[Step<V>], v: &V| guards.iter().any(|g| (g.pred)(v));
+
+        let mut runs: Vec<Vec<Timestamped<V>>> = Vec::new();
+        let mut out = Vec::new();
+        for event in events {
+            // Attempts past the time limit end here: complete ones had their trailing
+            // absence hold for the whole limit; incomplete ones are dropped.
+            runs.retain_mut(|run| {
+                if !expired(run, event.ts) {
+                    return true;
+                }
+                if run.len() == n {
+                    out.push(PatternMatch::new(std::mem::take(run)));
+                }
+                false
+            });
+            runs.retain(|run| !hits(&self.absences[run.len()], &event.value));
+
+            let taker = runs
+                .iter()
+                .position(|run| run.len() < n && (self.steps[run.len()].pred)(&event.value));
+            if let Some(i) = taker {
+                runs[i].push(event);
+            } else if (self.steps[0].pred)(&event.value) {
+                runs.push(vec![event]);
+            }
+
+            if trailing.is_empty() {
+                runs.retain_mut(|run| {
+                    if run.len() < n {
+                        return true;
+                    }
+                    out.push(PatternMatch::new(std::mem::take(run)));
+                    false
+                });
+            }
+        }
+        // End of data: pending trailing absences were never violated.
+        out.extend(
+            runs.into_iter()
+                .filter(|run| run.len() == n)
+                .map(PatternMatch::new),
+        );
+        out
+    }
+}
+
+/// One occurrence of a [`Pattern`], produced by [`PCollection::match_pattern`].
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PatternMatch<V> {
+    /// Timestamp of the event that matched the first step.
+    pub start: TimestampMs,
+    /// Timestamp of the event that matched the last step.
+    pub end: TimestampMs,
+    /// The matched events, one per step, in step order.
+    pub events: Vec<Timestamped<V>>,
+}
+
+impl<V> PatternMatch<V> {
+    fn new(events: Vec<Timestamped<V>>) -> Self {
+        Self {
+            start: events.first().map_or(0, |e| e.ts),
+            end: events.last().map_or(0, |e| e.ts),
+            events,
+        }
+    }
+}
+
+impl<K: Element + Eq + Hash, V: Element> PCollection<(K, Timestamped<V>)> {
+    /// Detect occurrences of `pattern` in each key's events.
+    ///
+    /// Groups by key, orders each key's events by timestamp, and emits
+    /// `(K, PatternMatch<V>)` for every match, in order of completion per key. Events
+    /// with equal timestamps are visited in an arbitrary relative order.
+    ///
+    /// ### Example
+    /// ```
+    /// use ironbeam::*;
+    /// use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// // (device, reading): alert when a spike is followed by another within 10s.
+    /// let readings = from_vec(&p, vec![
+    ///     (1u32, Timestamped::new(0, 95u32)),
+    ///     (1, Timestamped::new(4_000, 20)),
+    ///     (1, Timestamped::new(8_000, 97)),
+    ///     (2, Timestamped::new(0, 99)),
+    ///     (2, Timestamped::new(60_000, 99)),
+    /// ]);
+    /// let double_spike = Pattern::begin("spike", |r: &u32| *r > 90)
+    ///     .followed_by("again", |r: &u32| *r > 90)
+    ///     .within(10_000);
+    /// let alerts = readings.match_pattern(&double_spike).collect_seq()?;
+    /// assert_eq!(alerts.len(), 1);
+    /// assert_eq!(alerts[0].0, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn match_pattern(self, pattern: &Pattern<V>) -> PCollection<(K, PatternMatch<V>)> {
+        let pattern = pattern.clone();
+        self.group_by_key()
+            .flat_map(move |kv: &(K, Vec<Timestamped<V>>)| {
+                let (key, events) = kv;
+                let mut events = events.clone();
+                events.sort_by_key(|e| e.ts);
+                pattern
+                    .find(events)
+                    .into_iter()
+                    .map(|m| (key.clone(), m))
+                    .collect::<Vec<_>>()
+            })
+    }
+}
@@ -0,0 +1,174 @@
+//! Length-delimited protobuf sources and sinks for [`PCollection`].
+//!
+//! Reads and writes files of varint-length-prefixed protobuf messages, the framing
+//! used by `writeDelimitedTo` and by many Kafka topic dumps:
+//!
+//! - **Vector I/O** -- read the whole file into memory or write an in-memory collection:
+//!   - [`read_proto`] -> `PCollection<M>`
+//!   - [`PCollection::write_proto`]
+//!
+//! - **Streaming I/O** -- build a source that shards a file by message count and
+//!   decodes each shard lazily in the runner:
+//!   - [`read_proto_streaming`] -> `PCollection<M>`
+//!
+//! Message types implement [`prost::Message`]. As pipeline elements they must also
+//! satisfy [`Element`]; with the `coders` feature that includes serde, so derive
+//! `serde::Serialize` and `serde::Deserialize` next to `prost::Message`.
+//!
+//! ## Feature flags
+//! - `io-proto`: enables this module. It is **not** part of the default feature set;
+//!   opt in explicitly to pull in `prost`.
+//!
+//! ## Example
+//! ```no_run
+//! use ironbeam::*;
+//! use anyhow::Result;
+//!
+//! #[derive(Clone, PartialEq, prost::Message, serde::Serialize, serde::Deserialize)]
+//! struct Click {
+//!     #[prost(string, tag = "1")]
+//!     user: String,
+//!     #[prost(uint64, tag = "2")]
+//!     ts: u64,
+//! }
+//!
+//! # fn main() -> Result<()> {
+//! let p = Pipeline::default();
+//! let clicks = read_proto_streaming::<Click>(&p, "dumps/clicks.pb", 100_000)?;
+//! let per_user = clicks.key_by(|c: &Click| c.user.clone()).count_per_key();
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::io::glob::expand_glob;
+use crate::io::proto::{
+    ProtoShards, ProtoVecOps, build_proto_shards, read_proto_vec, write_proto_vec,
+};
+use crate::node::Node;
+use crate::type_token::TypeTag;
+use crate::{Element, PCollection, Pipeline, from_vec};
+use anyhow::{Context, Result, anyhow, bail};
+use prost::Message;
+use regex::Regex;
+use std::marker::PhantomData;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Read length-delimited protobuf file(s) into a typed `PCollection<M>`.
+///
+/// This eagerly decodes the entire file(s) into memory. For very large files, prefer
+/// [`read_proto_streaming`]. The `path` may be a glob pattern; matching files are
+/// read and concatenated in sorted (lexicographic) order.
+///
+/// *Enabled when the `io-proto` feature is on.*
+///
+/// # Errors
+/// Returns an error if no files match, any file cannot be read, or any message fails
+/// to decode.
+///
+/// # Panics
+/// If the `path` parameter is invalid UTF-8 or the regex engine fails.
+///
+/// # Example
+/// ```no_run
+/// use ironbeam::*;
+/// use anyhow::Result;
+///
+/// #[derive(Clone, PartialEq, prost::Message, serde::Serialize, serde::Deserialize)]
+/// struct Event {
+///     #[prost(string, tag = "1")]
+///     id: String,
+/// }
+///
+/// # fn main() -> Result<()> {
+/// let p = Pipeline::default();
+/// let events = read_proto::<Event>(&p, "dumps/events-*.pb")?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn read_proto<M>(p: &Pipeline, path: impl AsRef<Path>) -> Result<PCollection<M>>
+where
+    M: Element + Message + Default,
+{
+    let location = path.as_ref().display().to_string();
+    let path_str = path
+        .as_ref()
+        .to_str()
+        .ok_or_else(|| anyhow!("path contains invalid UTF-8"))?;
+
+    let glob_regex = Regex::new(r"[*?\[]").expect("valid glob regex");
+    if glob_regex.is_match(path_str) {
+        let files =
+            expand_glob(path_str).with_context(|| format!("expanding glob pattern: {path_str}"))?;
+
+        if files.is_empty() {
+            bail!("no files found matching pattern: {path_str}");
+        }
+
+        let mut all_data = Vec::new();
+        for file in files {
+            let data: Vec<M> =
+                read_proto_vec(&file).with_context(|| format!("reading {}", file.display()))?;
+            all_data.extend(data);
+        }
+        Ok(from_vec(p, all_data).record_input("proto", location))
+    } else {
+        let v = read_proto_vec::<M>(path)?;
+        Ok(from_vec(p, v).record_input("proto", location))
+    }
+}
+
+/// Create a **streaming** protobuf source, sharded by a fixed number of messages.
+///
+/// This builds a [`ProtoShards`] descriptor (counting messages up front by their
+/// length prefixes) and inserts a `Source` node that decodes only its shard when
+/// executed by the runner.
+///
+/// *Enabled when the `io-proto` feature is on.*
+///
+/// # Arguments
+/// - `p`: Pipeline to attach the source to.
+/// - `path`: Protobuf file path.
+/// - `records_per_shard`: Target number of messages per shard (minimum 1).
+///
+/// # Errors
+/// Returns an error if the file cannot be scanned or opened.
+pub fn read_proto_streaming<M>(
+    p: &Pipeline,
+    path: impl AsRef<Path>,
+    records_per_shard: usize,
+) -> Result<PCollection<M>>
+where
+    M: Element + Message + Default,
+{
+    let location = path.as_ref().display().to_string();
+    let shards: ProtoShards = build_proto_shards(path, records_per_shard)?;
+    let id = p.insert_node(Node::Source {
+        payload: Arc::new(shards),
+        vec_ops: ProtoVecOps::<M>::new(),
+        elem_tag: TypeTag::of::<M>(),
+    });
+    p.set_coder::<M>(id);
+    p.record_input(id, "proto", location);
+    Ok(PCollection {
+        pipeline: p.clone(),
+        id,
+        _t: PhantomData,
+    })
+}
+
+impl<M: Element + Message> PCollection<M> {
+    /// Execute the collection and write it as a length-delimited protobuf file.
+    ///
+    /// The entire collection is first collected into memory (sequentially) to
+    /// preserve deterministic ordering, then written as one file.
+    ///
+    /// Returns the number of messages written.
+    ///
+    /// # Errors
+    /// Propagates execution and I/O errors.
+    pub fn write_proto(self, path: impl AsRef<Path>) -> Result<usize> {
+        let rows: Vec<M> = self.collect_seq()?;
+        write_proto_vec(path, &rows)
+    }
+}
@@ -0,0 +1,192 @@
+//! Draining a [`QueueIO`] work queue through a pipeline.
+//!
+//! [`read_queue`] receives up to `max_messages` messages, deserializes their bodies, and
+//! returns them as a [`QueueBatch`]: the collection of work items plus the receipts
+//! needed to acknowledge them. Received messages are hidden from other consumers for
+//! the visibility timeout, so several workers can drain the same queue.
+//!
+//! [`QueueBatch::run`] hands the collection to the rest of the pipeline and settles the
+//! messages with the outcome:
+//!
+//! - if the pipeline succeeds, the messages are deleted from the queue;
+//! - if it fails, they are left in flight and come back once their visibility timeout
+//!   expires, or, with [`with_redelivery_on_failure`](QueueBatch::with_redelivery_on_failure),
+//!   are made visible again at once through [`QueueIO::change_visibility`].
+//!
+//! Delivery is at least once: a message whose work succeeded may still be redelivered if
+//! deleting it fails, so the work should be idempotent.
+//!
+//! ```
+//! use ironbeam::io::cloud::{FakeQueueIO, QueueIO};
+//! use ironbeam::*;
+//! use std::collections::HashMap;
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let queue = FakeQueueIO::new();
+//! for n in 1..=3 {
+//!     queue.send("jobs", &n.to_string(), HashMap::new())?;
+//! }
+//!
+//! let p = Pipeline::default();
+//! let batch = read_queue(&p, &queue, "jobs", 100, 60, |body| Ok(body.parse::<u32>()?))?;
+//! let total = batch.run(|jobs| {
+//!     let squares = jobs.map(|n: &u32| n * n).collect_seq()?;
+//!     Ok(squares.iter().sum::<u32>())
+//! })?;
+//! assert_eq!(total, 14);
+//! assert_eq!(queue.queue_size("jobs")?, 0);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::io::cloud::traits::QueueIO;
+use crate::{Element, PCollection, Pipeline, from_vec};
+use anyhow::{Context, Result};
+
+/// Most messages requested per [`receive`](QueueIO::receive) call, and most receipts
+/// deleted per [`delete_batch`](QueueIO::delete_batch) call.
+pub const QUEUE_BATCH_SIZE: u32 = 10;
+
+/// Messages received by [`read_queue`], waiting to be acknowledged.
+///
+/// Dropping a batch without calling [`run`](Self::run), [`ack`](Self::ack), or
+/// [`release`](Self::release) leaves its messages in flight until their visibility
+/// timeout expires.
+pub struct QueueBatch<'a, T> {
+    queue_io: &'a dyn QueueIO,
+    queue: String,
+    receipts: Vec<String>,
+    collection: PCollection<T>,
+    redeliver_on_failure: bool,
+}
+
+/// Receive up to `max_messages` messages from `queue` and deserialize their bodies into
+/// a collection in `p`.
+///
+/// Messages are received [`QUEUE_BATCH_SIZE`] at a time until `max_messages` have
+/// arrived or the queue returns no more. Each stays hidden from other consumers for
+/// `visibility_timeout_secs`, which should cover the time the pipeline takes to run.
+///
+/// # Errors
+/// Returns an error if a `receive` call fails or a message body fails to deserialize.
+/// Messages received before the error stay in flight until their visibility timeout
+/// expires.
+pub fn read_queue<'a, T, F>(
+    p: &Pipeline,
+    queue_io: &'a dyn QueueIO,
+    queue: &str,
+    max_messages: usize,
+    visibility_timeout_secs: u32,
+    deserializer: F,
+) -> Result<QueueBatch<'a, T>>
+where
+    T: Element,
+    F: Fn(&str) -> Result<T>,
+{
+    let mut receipts = Vec::new();
+    let mut elements = Vec::new();
+    while receipts.len() < max_messages {
+        let wanted = u32::try_from(max_messages - receipts.len())
+            .map_or(QUEUE_BATCH_SIZE, |n| n.min(QUEUE_BATCH_SIZE));
+        let messages = queue_io
+            .receive(queue, wanted, visibility_timeout_secs)
+            .with_context(|| format!("read_queue: receive from {queue}"))?;
+        if messages.is_empty() {
+            break;
+        }
+        for message in messages {
+            let element = deserializer(&message.body)
+                .with_context(|| format!("read_queue: deserialize message {}", message.id))?;
+            elements.push(element);
+            receipts.push(message.receipt_handle);
+        }
+    }
+    Ok(QueueBatch {
+        queue_io,
+        queue: queue.to_string(),
+        receipts,
+        collection: from_vec(p, elements),
+        redeliver_on_failure: false,
+    })
+}
+
+impl<T: Element> QueueBatch<'_, T> {
+    /// Number of messages in the batch.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.receipts.len()
+    }
+
+    /// Whether the queue had no messages to deliver.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.receipts.is_empty()
+    }
+
+    /// The deserialized messages, in the order they were received.
+    #[must_use]
+    pub fn collection(&self) -> PCollection<T> {
+        self.collection.clone()
+    }
+
+    /// Make the messages visible again at once if [`run`](Self::run) fails, instead of
+    /// waiting for their visibility timeout to expire.
+    #[must_use]
+    pub const fn with_redelivery_on_failure(mut self, redeliver: bool) -> Self {
+        self.redeliver_on_failure = redeliver;
+        self
+    }
+
+    /// Run `f` on the collection, then delete the messages if it succeeds.
+    ///
+    /// If `f` fails, its error is returned and the messages are redelivered: at once
+    /// with [`with_redelivery_on_failure`](Self::with_redelivery_on_failure), otherwise
+    /// when their visibility timeout expires.
+    ///
+    /// # Errors
+    /// Returns the error from `f`, or an error if deleting the messages fails.
+    pub fn run<R>(self, f: impl FnOnce(PCollection<T>) -> Result<R>) -> Result<R> {
+        match f(self.collection()) {
+            Ok(out) => {
+                self.ack()?;
+                Ok(out)
+            }
+            Err(err) if self.redeliver_on_failure => match self.release() {
+                Ok(()) => Err(err),
+                Err(release_err) => Err(err.context(format!(
+                    "read_queue: messages could not be released for redelivery ({release_err:#})"
+                ))),
+            },
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Delete the messages from the queue, [`QUEUE_BATCH_SIZE`] receipts per
+    /// [`delete_batch`](QueueIO::delete_batch) call.
+    ///
+    /// # Errors
+    /// Returns an error if a `delete_batch` call fails. Messages in earlier calls have
+    /// already been deleted.
+    pub fn ack(self) -> Result<()> {
+        for chunk in self.receipts.chunks(QUEUE_BATCH_SIZE as usize) {
+            self.queue_io
+                .delete_batch(&self.queue, chunk.to_vec())
+                .with_context(|| format!("read_queue: delete messages from {}", self.queue))?;
+        }
+        Ok(())
+    }
+
+    /// Make the messages visible to consumers again at once.
+    ///
+    /// # Errors
+    /// Returns an error if the queue does not support
+    /// [`change_visibility`](QueueIO::change_visibility) or a call fails.
+    pub fn release(self) -> Result<()> {
+        for receipt in &self.receipts {
+            self.queue_io
+                .change_visibility(&self.queue, receipt, 0)
+                .with_context(|| format!("read_queue: release message on {}", self.queue))?;
+        }
+        Ok(())
+    }
+}
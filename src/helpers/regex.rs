@@ -16,6 +16,12 @@
 //! | [`regex_find`](PCollection::regex_find) | Return the first match substring; non-matching lines are dropped |
 //! | [`regex_replace_all`](PCollection::regex_replace_all) | Replace every non-overlapping match |
 //! | [`regex_split`](PCollection::regex_split) | Split each line on the pattern → `Vec<String>` |
+//! | [`extract_regex`](PCollection::extract_regex) | Extract the capture groups as a tuple or [`Row`]; non-matching lines are dropped |
+//!
+//! [`extract_regex`](PCollection::extract_regex) compiles its pattern once, when the
+//! transform is added, and hands each partition its own copy of the matcher through
+//! the setup hook of [`map_with_resource`](PCollection::map_with_resource), so parallel
+//! workers do not contend for the matcher's scratch space.
 //!
 //! ## Examples
 //!
@@ -47,10 +53,83 @@
 //! # }
 //! ```
 
-use crate::{Element, PCollection};
-use regex::Regex;
+use crate::{Element, PCollection, Row};
+use regex::{Captures, Regex};
+use serde_json::Value;
 use std::sync::Arc;
 
+/// An element type [`extract_regex`](PCollection::extract_regex) can build from the
+/// capture groups of a match.
+///
+/// Implemented for tuples of one to six `String`s, filled from groups `1..=N`, and
+/// for [`Row`], which gets one column per capture group.
+pub trait FromCaptures: Element {
+    /// Check that `re` has the groups this type needs.
+    ///
+    /// # Errors
+    /// Returns a description of the mismatch.
+    fn check(re: &Regex) -> Result<(), String>;
+
+    /// Build a value from one match, or `None` to drop the line.
+    fn from_captures(re: &Regex, caps: &Captures<'_>) -> Option<Self>;
+}
+
+macro_rules! impl_from_captures_for_tuple {
+    ($n:expr; $($group:tt),+) => {
+        impl FromCaptures for ($(impl_from_captures_for_tuple!(@string $group),)+) {
+            fn check(re: &Regex) -> Result<(), String> {
+                let groups = re.captures_len() - 1;
+                if groups < $n {
+                    return Err(format!(
+                        "pattern has {groups} capture groups, but {} are needed",
+                        $n
+                    ));
+                }
+                Ok(())
+            }
+
+            fn from_captures(_re: &Regex, caps: &Captures<'_>) -> Option<Self> {
+                Some(($(caps.get($group)?.as_str().to_string(),)+))
+            }
+        }
+    };
+    (@string $group:tt) => { String };
+}
+
+impl_from_captures_for_tuple!(1; 1);
+impl_from_captures_for_tuple!(2; 1, 2);
+impl_from_captures_for_tuple!(3; 1, 2, 3);
+impl_from_captures_for_tuple!(4; 1, 2, 3, 4);
+impl_from_captures_for_tuple!(5; 1, 2, 3, 4, 5);
+impl_from_captures_for_tuple!(6; 1, 2, 3, 4, 5, 6);
+
+/// One column per capture group, named after the group or, for unnamed groups, its
+/// index (`"1"`, `"2"`, ...). Groups that did not participate in the match are `null`.
+impl FromCaptures for Row {
+    fn check(re: &Regex) -> Result<(), String> {
+        if re.captures_len() < 2 {
+            return Err("pattern has no capture groups".to_string());
+        }
+        Ok(())
+    }
+
+    fn from_captures(re: &Regex, caps: &Captures<'_>) -> Option<Self> {
+        Some(
+            re.capture_names()
+                .enumerate()
+                .skip(1)
+                .map(|(i, name)| {
+                    let column = name.map_or_else(|| i.to_string(), str::to_string);
+                    let value = caps
+                        .get(i)
+                        .map_or(Value::Null, |m| Value::String(m.as_str().to_string()));
+                    (column, value)
+                })
+                .collect(),
+        )
+    }
+}
+
 impl PCollection<String> {
     /// Keep only lines where the pattern is found anywhere in the string.
     ///
@@ -306,4 +385,55 @@ impl PCollection<String> {
         let re = Arc::new(Regex::new(pattern).expect("regex_split: invalid pattern"));
         self.map(move |line: &String| re.split(line).map(String::from).collect())
     }
+
+    /// Extract the capture groups of the first match in each line as a tuple of
+    /// strings or a [`Row`]; lines with no match are dropped.
+    ///
+    /// A tuple of `N` strings takes groups `1..=N`, and lines where any of them did not
+    /// participate in the match are dropped too. A [`Row`] gets a column per group (see
+    /// [`FromCaptures`]), which suits named groups like `(?P<status>\d{3})`.
+    ///
+    /// The pattern is compiled once; each partition matches with its own copy.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pattern` is not a valid regular expression or has too few capture
+    /// groups for `C`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use ironbeam::*;
+    /// use anyhow::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let lines = from_vec(&p, vec![
+    ///     "GET /index.html 200".to_string(),
+    ///     "POST /login 401".to_string(),
+    ///     "garbage".to_string(),
+    /// ]);
+    ///
+    /// let requests = lines
+    ///     .clone()
+    ///     .extract_regex::<(String, String, String)>(r"^(\w+) (\S+) (\d{3})$");
+    /// assert_eq!(requests.collect_seq()?.len(), 2);
+    ///
+    /// let rows = lines.extract_regex::<Row>(r"^(?P<method>\w+) (?P<path>\S+)");
+    /// let first = &rows.collect_seq()?[0];
+    /// assert_eq!(first.get("method"), Some(&serde_json::json!("GET")));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn extract_regex<C: FromCaptures>(self, pattern: &str) -> PCollection<C> {
+        let re = Regex::new(pattern).expect("extract_regex: invalid pattern");
+        if let Err(e) = C::check(&re) {
+            panic!("extract_regex: {e}: {pattern}");
+        }
+        self.filter_map_with_resource(
+            move || re.clone(),
+            |re: &Regex, line: &String| C::from_captures(re, &re.captures(line)?),
+        )
+    }
 }
@@ -0,0 +1,314 @@
+//! Explicit repartitioning: control partition counts mid-pipeline.
+//!
+//! # Overview
+//!
+//! Partitioning is otherwise decided once at the source (and adaptively after barriers).
+//! The transforms here let a pipeline pick the partition count explicitly:
+//!
+//! - [`PCollection::repartition`] — split evenly into exactly `n` partitions, e.g. fan out
+//!   after a heavily filtering step, or fan in before writing a fixed number of shards.
+//! - [`PCollection::repartition_by_key`] — route `(K, V)` pairs by a caller-supplied key
+//!   hash, so all pairs with the same key share a partition.
+//! - [`PCollection::repartition_by_range`] — balanced, ordered partitions (below).
+//!
+//! All three are barriers backed by [`Node::Reshuffle`] and only take effect in parallel
+//! execution; sequential execution always collects into a single partition.
+//!
+//! [`PCollection::with_partitions`] is the non-shuffling counterpart: a per-stage hint
+//! that changes how many partitions an existing split (the source, a reshuffle, or a
+//! sort) produces, without adding a barrier of its own.
+//!
+//! # Range partitioning
+//!
+//! [`PCollection::repartition_by_range`] re-distributes elements so that every output
+//! partition covers a **contiguous key range**: each element of partition `i` sorts
+//! before (or equal to) each element of partition `i + 1`. Split points are chosen by
+//! sampling the data, so partitions come out roughly equal in size even when the key
+//! distribution is skewed — unlike a hash partitioner, which balances keys rather than
+//! elements, or an even chunking, which ignores order entirely.
+//!
+//! The same range partitioner backs [`PCollection::sort_by`] in parallel mode: elements
+//! are routed into sampled ranges, then every range is sorted independently, giving a
+//! total order without a single-threaded merge.
+//!
+//! # Sampling
+//!
+//! Sampling is deterministic: a fixed stride over the input yields roughly
+//! [`SAMPLES_PER_RANGE`] samples per requested range. Split points are the sample
+//! quantiles at `1/n, 2/n, …`. Keys equal to a split point land in the range to its
+//! right, so a heavily repeated key always stays within a single partition.
+
+use crate::node::Node;
+use crate::{Element, PCollection, Partition};
+use std::cmp::Ordering;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// Number of samples drawn per output range when computing split points.
+pub const SAMPLES_PER_RANGE: usize = 32;
+
+impl<T: Element> PCollection<T> {
+    /// Repartition evenly into exactly `n` partitions (fewer if there are fewer elements).
+    ///
+    /// Unlike [`reshuffle`](Self::reshuffle), which follows the runner's adaptive partition
+    /// count, the output partition count is fixed by the caller. Element order is preserved
+    /// when the partitions are concatenated. `n = 0` is treated as `1`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a partition holds a type other than `Vec<T>`. This cannot occur in
+    /// normal usage because the closure is constructed from a typed `PCollection<T>`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ironbeam::*;
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let rare = from_vec(&p, (0..10_000u32).collect::<Vec<_>>()).filter(|x| x % 100 == 0);
+    /// // Fan in to two shards before an expensive per-partition step.
+    /// let out = rare.repartition(2).collect_par(None, Some(8))?;
+    /// assert_eq!(out.len(), 100);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn repartition(self, n: usize) -> Self {
+        self.insert_repartition(n, |parts, target| {
+            let all: Vec<T> = downcast_parts::<T>(parts).into_iter().flatten().collect();
+            if all.is_empty() || target <= 1 {
+                return vec![Box::new(all) as Partition];
+            }
+            let chunk_size = all.len().div_ceil(target);
+            all.chunks(chunk_size)
+                .map(|c| Box::new(c.to_vec()) as Partition)
+                .collect()
+        })
+    }
+
+    /// Hint that the stage producing this collection should run with `n` partitions.
+    ///
+    /// Unlike [`repartition`](Self::repartition) this adds no barrier; it overrides the
+    /// partition count the parallel runner would otherwise choose at the point where the
+    /// stage's partitions are formed:
+    ///
+    /// - on a source, or on stateless transforms fed directly by one, it sets how many
+    ///   partitions the source is split into;
+    /// - on a [`reshuffle`](Self::reshuffle), a `repartition*`, or a sort (or on stateless
+    ///   transforms right after one), it sets that barrier's output partition count.
+    ///
+    /// Keyed and global aggregations and joins merge into a single partition, so a hint
+    /// on them (or on stateless transforms after them) cannot be honored: the planner
+    /// drops it and records a [`DiagnosticKind::IgnoredPartitionHint`] warning on
+    /// [`Plan::diagnostics`](crate::planner::Plan::diagnostics); add a
+    /// [`repartition`](Self::repartition) instead. When several hints reach the same
+    /// split, the one closest to it wins. Hints are honored by the [`DirectRunner`](crate::DirectRunner)
+    /// in parallel mode and ignored in sequential execution. `n = 0` is treated as `1`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ironbeam::*;
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// // An expensive per-element step: split the source finer than the default.
+    /// let scored = from_vec(&p, (0..1_000u32).collect::<Vec<_>>())
+    ///     .map(|x| x * 2)
+    ///     .with_partitions(64);
+    /// let out = scored.collect_par(None, None)?;
+    /// assert_eq!(out.len(), 1_000);
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`DiagnosticKind::IgnoredPartitionHint`]: crate::validation::DiagnosticKind::IgnoredPartitionHint
+    #[must_use]
+    pub fn with_partitions(self, n: usize) -> Self {
+        self.pipeline.set_partition_hint(self.id, n);
+        self
+    }
+
+    /// Insert a [`Node::Reshuffle`] barrier targeting `n` partitions that runs
+    /// `f(partitions, n)` (`n = 1` in sequential execution).
+    fn insert_repartition<F>(self, n: usize, f: F) -> Self
+    where
+        F: Fn(Vec<Partition>, usize) -> Vec<Partition> + Send + Sync + 'static,
+    {
+        let id = self.pipeline.insert_node(Node::Reshuffle {
+            reshuffle: Arc::new(f),
+            target_partitions: Some(n.max(1)),
+        });
+        self.pipeline.connect(self.id, id);
+        self.pipeline.set_coder::<T>(id);
+        Self {
+            pipeline: self.pipeline,
+            id,
+            _t: PhantomData,
+        }
+    }
+}
+
+impl<K: Element, V: Element> PCollection<(K, V)> {
+    /// Repartition `(K, V)` pairs into `n` partitions by `hash(&key) % n`.
+    ///
+    /// Every pair with the same key lands in the same partition, and pairs keep their
+    /// relative input order within a partition. Empty partitions are dropped, so fewer
+    /// than `n` partitions may be produced. `n = 0` is treated as `1`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a partition holds a type other than `Vec<(K, V)>`. This cannot occur in
+    /// normal usage because the closure is constructed from a typed `PCollection<(K, V)>`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ironbeam::*;
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let kv = from_vec(&p, vec![(1u64, "a".to_string()), (2, "b".into()), (1, "c".into())]);
+    /// let out = kv.repartition_by_key(4, |k| *k).collect_par(None, Some(4))?;
+    /// assert_eq!(out.len(), 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn repartition_by_key<H>(self, n: usize, hash: H) -> Self
+    where
+        H: Fn(&K) -> u64 + Send + Sync + 'static,
+    {
+        self.insert_repartition(n, move |parts, target| {
+            let mut buckets: Vec<Vec<(K, V)>> = (0..target).map(|_| Vec::new()).collect();
+            for (k, v) in downcast_parts::<(K, V)>(parts).into_iter().flatten() {
+                #[allow(clippy::cast_possible_truncation)]
+                let idx = (hash(&k) % target as u64) as usize;
+                buckets[idx].push((k, v));
+            }
+            buckets.retain(|b| !b.is_empty());
+            if buckets.is_empty() {
+                buckets.push(Vec::new());
+            }
+            buckets
+                .into_iter()
+                .map(|b| Box::new(b) as Partition)
+                .collect()
+        })
+    }
+}
+
+impl<T: Element + Ord> PCollection<T> {
+    /// Repartition into (at most) `n` balanced, contiguous key ranges.
+    ///
+    /// This is a barrier backed by [`Node::Reshuffle`]: elements are fully materialized,
+    /// split points are sampled, and every element is routed to the range containing it.
+    /// Element order *within* a range follows input order; ranges are not sorted.
+    ///
+    /// In sequential execution all elements are collected into a single partition, as
+    /// with [`reshuffle`](Self::reshuffle). Fewer than `n` partitions are produced when
+    /// the input has fewer distinct split points than requested (e.g., few distinct keys).
+    ///
+    /// # Panics
+    ///
+    /// Panics if a partition holds a type other than `Vec<T>`. This cannot occur in
+    /// normal usage because the closure is constructed from a typed `PCollection<T>`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ironbeam::*;
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let ranged = from_vec(&p, (0..1_000u32).rev().collect::<Vec<_>>()).repartition_by_range(4);
+    /// let mut out = ranged.collect_par(None, Some(4))?;
+    /// out.sort_unstable();
+    /// assert_eq!(out.len(), 1_000);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn repartition_by_range(self, n: usize) -> Self {
+        self.insert_repartition(n, |parts, target| {
+            range_partition_erased::<T>(parts, target, &T::cmp)
+        })
+    }
+}
+
+/// Downcast type-erased partitions back to `Vec<T>`.
+fn downcast_parts<T: Element>(parts: Vec<Partition>) -> Vec<Vec<T>> {
+    parts
+        .into_iter()
+        .map(|p| {
+            #[allow(clippy::expect_used)]
+            let v = *p
+                .downcast::<Vec<T>>()
+                .expect("repartition: partition held unexpected element type");
+            v
+        })
+        .collect()
+}
+
+/// Downcast type-erased `Vec<T>` partitions, range-partition them, and re-box the ranges.
+pub(crate) fn range_partition_erased<T: Element>(
+    parts: Vec<Partition>,
+    n: usize,
+    cmp: &(dyn Fn(&T, &T) -> Ordering + Send + Sync),
+) -> Vec<Partition> {
+    range_partition(downcast_parts(parts), n, cmp)
+        .into_iter()
+        .map(|r| Box::new(r) as Partition)
+        .collect()
+}
+
+/// Route every element into one of at most `n` contiguous ranges under `cmp`.
+///
+/// Returns the non-empty ranges in ascending order (always at least one, possibly
+/// empty, range). Elements keep their input order (partition by partition) within
+/// each range, so a stable sort of each range yields a globally stable sort.
+pub(crate) fn range_partition<T: Clone>(
+    parts: Vec<Vec<T>>,
+    n: usize,
+    cmp: &(dyn Fn(&T, &T) -> Ordering + Send + Sync),
+) -> Vec<Vec<T>> {
+    let total: usize = parts.iter().map(Vec::len).sum();
+    if n <= 1 || total <= 1 {
+        return vec![parts.into_iter().flatten().collect()];
+    }
+
+    let splits = split_points(&parts, total, n, cmp);
+    let mut ranges: Vec<Vec<T>> = (0..=splits.len())
+        .map(|_| Vec::with_capacity(total / n + 1))
+        .collect();
+    for x in parts.into_iter().flatten() {
+        let idx = splits.partition_point(|s| cmp(s, &x) != Ordering::Greater);
+        ranges[idx].push(x);
+    }
+    ranges.retain(|r| !r.is_empty());
+    if ranges.is_empty() {
+        ranges.push(Vec::new());
+    }
+    ranges
+}
+
+/// Sample the input with a fixed stride and return up to `n - 1` distinct split points.
+fn split_points<T: Clone>(
+    parts: &[Vec<T>],
+    total: usize,
+    n: usize,
+    cmp: &(dyn Fn(&T, &T) -> Ordering + Send + Sync),
+) -> Vec<T> {
+    let stride = (total / n.saturating_mul(SAMPLES_PER_RANGE)).max(1);
+    let mut sample: Vec<&T> = parts.iter().flatten().step_by(stride).collect();
+    sample.sort_by(|a, b| cmp(a, b));
+
+    let mut splits: Vec<T> = Vec::with_capacity(n - 1);
+    for i in 1..n {
+        let candidate = sample[i * sample.len() / n];
+        if splits
+            .last()
+            .is_none_or(|last| cmp(last, candidate) == Ordering::Less)
+        {
+            splits.push(candidate.clone());
+        }
+    }
+    splits
+}
@@ -94,6 +94,7 @@ impl<T: Element> PCollection<T> {
             });
         let id = self.pipeline.insert_node(Node::Reshuffle {
             reshuffle: reshuffle_fn,
+            target_partitions: None,
         });
         self.pipeline.connect(self.id, id);
         self.pipeline.set_coder::<T>(id);
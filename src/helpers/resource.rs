@@ -0,0 +1,241 @@
+//! Per-partition setup and teardown for transforms that need an expensive resource.
+//!
+//! [`PCollection::map_with_resource`] is the counterpart of a Beam `DoFn`'s
+//! `@Setup`/`@Teardown`: `init` builds a resource (a database connection, a loaded
+//! model, a compiled template) once for each partition the transform processes, every
+//! element of that partition is mapped with a shared reference to it, and the resource
+//! is dropped as soon as the partition is done. Put cleanup in the resource's [`Drop`]
+//! impl; it runs at the end of the partition whether the partition finishes normally,
+//! panics, or (in [`ExecMode::Streaming`](crate::ExecMode::Streaming)) is abandoned
+//! early by a downstream `take`.
+//!
+//! The resource is only ever used by the partition that built it, so it must be `Send`
+//! (a streamed partition may move between threads) but need not be `Sync`. Resources
+//! that should outlive a partition, such as a connection pool, belong in the closure's
+//! captured state instead.
+//!
+//! ```no_run
+//! use ironbeam::*;
+//! use std::collections::HashMap;
+//! # use anyhow::Result;
+//! # fn main() -> Result<()> {
+//! let p = Pipeline::default();
+//! let names = from_vec(&p, vec![1u32, 2, 3]).map_with_resource(
+//!     || HashMap::from([(1u32, "one"), (2, "two")]),
+//!     |lookup, id: &u32| lookup.get(id).copied().unwrap_or("?").to_string(),
+//! );
+//! names.collect_seq()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::node::{DynOp, Node};
+use crate::type_token::{LazyPartition, Partition};
+use crate::{Element, PCollection};
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+impl<T: Element> PCollection<T> {
+    /// Map each element with a resource built once per partition by `init`.
+    ///
+    /// `init` runs once for every partition of this transform's input, before its first
+    /// element; `f` receives that resource alongside each element; and the resource is
+    /// dropped when the partition ends. An empty partition still builds (and drops) a
+    /// resource.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the input partition is not a `Vec<T>`. This cannot occur in normal
+    /// usage because the op is constructed from a typed `PCollection<T>`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ironbeam::*;
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// struct Model { scale: f64 }
+    ///
+    /// let p = Pipeline::default();
+    /// let scores = from_vec(&p, vec![1.0f64, 2.5, 4.0])
+    ///     .map_with_resource(|| Model { scale: 10.0 }, |m, x: &f64| x * m.scale)
+    ///     .collect_seq()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn map_with_resource<R, O, I, F>(self, init: I, f: F) -> PCollection<O>
+    where
+        R: Send + 'static,
+        O: Element,
+        I: 'static + Send + Sync + Fn() -> R,
+        F: 'static + Send + Sync + Fn(&R, &T) -> O,
+    {
+        let op: Arc<dyn DynOp> = Arc::new(MapWithResourceOp {
+            init,
+            f,
+            _t: PhantomData::<fn(T, R) -> O>,
+        });
+        self.with_resource_op(op)
+    }
+
+    /// Keep the elements matching `pred`, with a resource built once per partition by
+    /// `init`.
+    pub(crate) fn filter_with_resource<R, I, F>(self, init: I, pred: F) -> Self
+    where
+        R: Send + 'static,
+        I: 'static + Send + Sync + Fn() -> R,
+        F: 'static + Send + Sync + Fn(&R, &T) -> bool,
+    {
+        let op: Arc<dyn DynOp> = Arc::new(FilterWithResourceOp {
+            init,
+            pred,
+            _t: PhantomData::<fn(T, R)>,
+        });
+        self.with_resource_op(op)
+    }
+
+    /// Map each element to zero or one output with a resource built once per partition
+    /// by `init`, keeping the `Some` results.
+    pub(crate) fn filter_map_with_resource<R, O, I, F>(self, init: I, f: F) -> PCollection<O>
+    where
+        R: Send + 'static,
+        O: Element,
+        I: 'static + Send + Sync + Fn() -> R,
+        F: 'static + Send + Sync + Fn(&R, &T) -> Option<O>,
+    {
+        let op: Arc<dyn DynOp> = Arc::new(FilterMapWithResourceOp {
+            init,
+            f,
+            _t: PhantomData::<fn(T, R) -> O>,
+        });
+        self.with_resource_op(op)
+    }
+
+    /// Append a stateless node running `op`.
+    fn with_resource_op<O: Element>(self, op: Arc<dyn DynOp>) -> PCollection<O> {
+        let id = self.pipeline.insert_node(Node::Stateless(vec![op]));
+        self.pipeline.connect(self.id, id);
+        self.pipeline.set_coder::<O>(id);
+        PCollection {
+            pipeline: self.pipeline,
+            id,
+            _t: PhantomData,
+        }
+    }
+}
+
+/// `MapWithResourceOp`: builds a resource with `init` per partition and maps each
+/// element with `f(&resource, &elem)`.
+struct MapWithResourceOp<T, R, O, I, F> {
+    init: I,
+    f: F,
+    _t: PhantomData<fn(T, R) -> O>,
+}
+
+impl<T, R, O, I, F> DynOp for MapWithResourceOp<T, R, O, I, F>
+where
+    T: Element,
+    R: Send + 'static,
+    O: Element,
+    I: 'static + Send + Sync + Fn() -> R,
+    F: 'static + Send + Sync + Fn(&R, &T) -> O,
+{
+    fn apply(&self, input: Partition) -> Partition {
+        let v = *input
+            .downcast::<Vec<T>>()
+            .expect("MapWithResourceOp: expected Vec<T> input");
+        let resource = (self.init)();
+        let out: Vec<O> = v.iter().map(|x| (self.f)(&resource, x)).collect();
+        drop(resource);
+        Box::new(out) as Partition
+    }
+
+    fn apply_lazy(self: Arc<Self>, input: LazyPartition) -> LazyPartition {
+        let it = input
+            .into_elems::<T>()
+            .expect("MapWithResourceOp: expected Vec<T> input");
+        // The iterator owns the resource, so it is dropped with the stream.
+        let resource = (self.init)();
+        LazyPartition::stream(it.map(move |x| (self.f)(&resource, &x)))
+    }
+}
+
+/// `FilterWithResourceOp`: builds a resource with `init` per partition and keeps the
+/// elements for which `pred(&resource, &elem)` holds.
+struct FilterWithResourceOp<T, R, I, F> {
+    init: I,
+    pred: F,
+    _t: PhantomData<fn(T, R)>,
+}
+
+impl<T, R, I, F> DynOp for FilterWithResourceOp<T, R, I, F>
+where
+    T: Element,
+    R: Send + 'static,
+    I: 'static + Send + Sync + Fn() -> R,
+    F: 'static + Send + Sync + Fn(&R, &T) -> bool,
+{
+    fn apply(&self, input: Partition) -> Partition {
+        let v = *input
+            .downcast::<Vec<T>>()
+            .expect("FilterWithResourceOp: expected Vec<T> input");
+        let resource = (self.init)();
+        let out: Vec<T> = v
+            .into_iter()
+            .filter(|x| (self.pred)(&resource, x))
+            .collect();
+        drop(resource);
+        Box::new(out) as Partition
+    }
+
+    fn apply_lazy(self: Arc<Self>, input: LazyPartition) -> LazyPartition {
+        let it = input
+            .into_elems::<T>()
+            .expect("FilterWithResourceOp: expected Vec<T> input");
+        let resource = (self.init)();
+        LazyPartition::stream(it.filter(move |x| (self.pred)(&resource, x)))
+    }
+
+    fn cardinality_reducing(&self) -> bool {
+        true
+    }
+}
+
+/// `FilterMapWithResourceOp`: builds a resource with `init` per partition and keeps
+/// the `Some` results of `f(&resource, &elem)`.
+struct FilterMapWithResourceOp<T, R, O, I, F> {
+    init: I,
+    f: F,
+    _t: PhantomData<fn(T, R) -> O>,
+}
+
+impl<T, R, O, I, F> DynOp for FilterMapWithResourceOp<T, R, O, I, F>
+where
+    T: Element,
+    R: Send + 'static,
+    O: Element,
+    I: 'static + Send + Sync + Fn() -> R,
+    F: 'static + Send + Sync + Fn(&R, &T) -> Option<O>,
+{
+    fn apply(&self, input: Partition) -> Partition {
+        let v = *input
+            .downcast::<Vec<T>>()
+            .expect("FilterMapWithResourceOp: expected Vec<T> input");
+        let resource = (self.init)();
+        let out: Vec<O> = v.iter().filter_map(|x| (self.f)(&resource, x)).collect();
+        drop(resource);
+        Box::new(out) as Partition
+    }
+
+    fn apply_lazy(self: Arc<Self>, input: LazyPartition) -> LazyPartition {
+        let it = input
+            .into_elems::<T>()
+            .expect("FilterMapWithResourceOp: expected Vec<T> input");
+        let resource = (self.init)();
+        LazyPartition::stream(it.filter_map(move |x| (self.f)(&resource, &x)))
+    }
+
+    fn cardinality_reducing(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,227 @@
+//! Running several terminals of one pipeline in a single execution.
+//!
+//! Each `collect_*` or `write_*` call plans and runs the pipeline on its own, so a
+//! pipeline with three outputs reads its inputs and runs the shared prefix three
+//! times. Instead, register each output without running it and execute them together:
+//!
+//! - [`PCollection::materialize`] -- registers the collection as an output whose
+//!   elements are delivered to the returned [`Materialized`] handle.
+//! - [`PCollection::write_to_sink`] -- registers the collection to be written to a
+//!   [`Sink`] (as [`write_to`](PCollection::write_to) would), such as a
+//!   [`JsonlSink`](crate::JsonlSink), [`CsvSink`](crate::CsvSink) or `ParquetSink`
+//!   file.
+//! - [`Pipeline::run_all`] -- runs the given outputs together with the pipeline's
+//!   [`RunnerConfig`] (see [`Pipeline::set_runner_config`]), and
+//!   [`Pipeline::run_all_with`] with another one. Every node that feeds more than one of
+//!   them is computed once (as if it had been [`cache`](PCollection::cache)d) and its
+//!   result reused by each consumer.
+//!
+//! Sharing follows the pipeline's edges, so it covers fan-outs such as
+//! `parsed -> {jsonl, parquet, totals}`. Join and flatten inputs capture their upstream
+//! chain when they are built; call [`cache`](PCollection::cache) before joining to share
+//! that work too.
+//!
+//! ```no_run
+//! use ironbeam::*;
+//! # use anyhow::Result;
+//!
+//! # fn main() -> Result<()> {
+//! let p = Pipeline::default();
+//! let parsed = from_vec(&p, vec!["a b".to_string(), "b c".to_string()])
+//!     .flat_map(|line: &String| line.split(' ').map(str::to_string).collect::<Vec<_>>());
+//!
+//! let words = parsed.clone().write_to_sink(JsonlSink::new("words.jsonl"));
+//! let total = parsed.count_globally().materialize();
+//!
+//! p.run_all(&[words, total.node_id()])?;
+//! assert_eq!(total.take()?, vec![4]);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::extensions::{Sink, sink_runner};
+use crate::node::Node;
+use crate::runner::{PipelineRunner, RunnerConfig};
+use crate::{Element, NodeId, PCollection, Pipeline};
+use anyhow::{Result, anyhow};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Work registered for an output node, run by [`Pipeline::run_all`] against the
+/// pipeline it executes, with the configuration passed to
+/// [`Pipeline::run_all_with`] (or `None` for the pipeline's own); returns the number of
+/// elements delivered.
+pub(crate) type OutputAction =
+    Box<dyn FnOnce(&Pipeline, NodeId, Option<&RunnerConfig>) -> Result<usize> + Send>;
+
+/// Handle to the elements of a collection registered with
+/// [`PCollection::materialize`], filled in by [`Pipeline::run_all`].
+pub struct Materialized<T> {
+    id: NodeId,
+    data: Arc<Mutex<Option<Vec<T>>>>,
+}
+
+impl<T> Materialized<T> {
+    /// The output node to pass to [`Pipeline::run_all`].
+    #[must_use]
+    pub const fn node_id(&self) -> NodeId {
+        self.id
+    }
+
+    /// Take the elements delivered by [`Pipeline::run_all`].
+    ///
+    /// # Errors
+    /// Returns an error if the output has not been run yet, or was already taken.
+    pub fn take(&self) -> Result<Vec<T>> {
+        self.data
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+            .ok_or_else(|| anyhow!("output {:?} has not been run by run_all", self.id))
+    }
+}
+
+impl<T: Element> PCollection<T> {
+    /// Register this collection as an output of the next [`Pipeline::run_all`],
+    /// without running anything.
+    ///
+    /// The elements are collected with the run's configuration (in parallel by
+    /// default) and delivered to the returned handle.
+    #[must_use]
+    pub fn materialize(self) -> Materialized<T> {
+        let data = Arc::new(Mutex::new(None));
+        let slot = Arc::clone(&data);
+        let id = self.register_output(Box::new(move |p, id, config| {
+            let out = match config {
+                Some(config) => config.run_collect::<T>(p, id)?,
+                None => p.runner_config().run_collect::<T>(p, id)?,
+            };
+            let len = out.len();
+            *slot.lock().unwrap_or_else(PoisonError::into_inner) = Some(out);
+            Ok(len)
+        }));
+        Materialized { id, data }
+    }
+
+    /// Register this collection to be written to `sink` by the next
+    /// [`Pipeline::run_all`], without running anything.
+    ///
+    /// The sink is driven exactly as by [`write_to`](Self::write_to) for
+    /// [`Pipeline::run_all`], and as by [`write_to_with`](Self::write_to_with) for
+    /// [`Pipeline::run_all_with`]. Returns the output node to pass to `run_all`.
+    pub fn write_to_sink<S: Sink<T> + 'static>(self, sink: S) -> NodeId {
+        self.register_output(Box::new(move |p, id, config| match config {
+            Some(config) => config.run_to_sink(p, id, sink),
+            None => {
+                let config = sink_runner(p.runner_config(), sink.partitions());
+                config.run_to_sink(p, id, sink)
+            }
+        }))
+    }
+
+    /// Attach a pass-through output node after this collection and register `action`
+    /// for it.
+    fn register_output(self, action: OutputAction) -> NodeId {
+        let id = self.pipeline.insert_node(Node::Stateless(Vec::new()));
+        self.pipeline.connect(self.id, id);
+        self.pipeline.set_coder::<T>(id);
+        self.pipeline
+            .inner
+            .lock()
+            .unwrap()
+            .outputs
+            .insert(id, action);
+        id
+    }
+}
+
+impl Pipeline {
+    /// Run the outputs registered with [`PCollection::materialize`] and
+    /// [`PCollection::write_to_sink`] in one execution, sharing upstream work, with the
+    /// pipeline's [`RunnerConfig`].
+    ///
+    /// Every node that feeds more than one of `outputs` is computed once and reused.
+    /// Outputs run in the given order and each runs at most once; returns the number of
+    /// elements each delivered. See the [module docs](crate::helpers::run_all).
+    ///
+    /// # Errors
+    /// Returns an error if an id is not a registered output (or was already run), or
+    /// if computing a shared node or running an output fails. Outputs before the
+    /// failing one have already been delivered.
+    ///
+    /// # Panics
+    /// If the pipeline is in an inconsistent state, such as during concurrent modifications.
+    pub fn run_all(&self, outputs: &[NodeId]) -> Result<Vec<usize>> {
+        self.run_outputs(outputs, None)
+    }
+
+    /// Like [`run_all`](Self::run_all), but every output and shared node runs with
+    /// `config`, including its execution mode.
+    ///
+    /// # Errors
+    /// Returns the errors of [`run_all`](Self::run_all).
+    pub fn run_all_with(&self, config: &RunnerConfig, outputs: &[NodeId]) -> Result<Vec<usize>> {
+        self.run_outputs(outputs, Some(config))
+    }
+
+    /// Run `outputs` together, with `config` or the pipeline's own configuration.
+    fn run_outputs(&self, outputs: &[NodeId], config: Option<&RunnerConfig>) -> Result<Vec<usize>> {
+        let actions = {
+            let mut g = self.inner.lock().unwrap();
+            if let Some(id) = outputs.iter().find(|id| !g.outputs.contains_key(id)) {
+                return Err(anyhow!("{id:?} is not a registered run_all output"));
+            }
+            outputs
+                .iter()
+                .map(|id| (*id, g.outputs.remove(id).expect("checked above")))
+                .collect::<Vec<_>>()
+        };
+
+        let view = self.execution_copy();
+        share_forks(&view, outputs);
+        actions
+            .into_iter()
+            .map(|(id, action)| action(&view, id, config))
+            .collect()
+    }
+}
+
+/// Reroute every node feeding more than one of `outputs` through a cached source, so
+/// it is computed once for all of them.
+fn share_forks(view: &Pipeline, outputs: &[NodeId]) {
+    let (nodes, edges) = view.snapshot();
+
+    // Nodes from which at least one output is reachable.
+    let mut feeds: HashSet<NodeId> = outputs.iter().copied().collect();
+    let mut stack: Vec<NodeId> = outputs.to_vec();
+    while let Some(cur) = stack.pop() {
+        for &(from, _) in edges.iter().filter(|(_, to)| *to == cur) {
+            if feeds.insert(from) {
+                stack.push(from);
+            }
+        }
+    }
+
+    let mut successors: HashMap<NodeId, Vec<NodeId>> = HashMap::new();
+    for &(from, to) in &edges {
+        if feeds.contains(&to) {
+            successors.entry(from).or_default().push(to);
+        }
+    }
+
+    let cachers = view.inner.lock().unwrap().cachers.clone();
+    for (fork, succ) in successors {
+        // Sources are already materialized; caching them would only copy them.
+        if succ.len() < 2 || matches!(nodes.get(&fork), Some(Node::Source { .. })) {
+            continue;
+        }
+        let Some(cacher) = cachers.get(&fork) else {
+            continue;
+        };
+        let cached = view.insert_node(cacher(view, fork));
+        let mut g = view.inner.lock().unwrap();
+        for edge in g.edges.iter_mut().filter(|(from, _)| *from == fork) {
+            edge.0 = cached;
+        }
+    }
+}
@@ -12,6 +12,9 @@
 //! - Global (unkeyed), Beam-compatible (default seed):
 //!   - [`PCollection<T>::sample_globally`](#method.sample_globally)
 //!   - [`PCollection<T>::sample_globally_with_seed`](#method.sample_globally_with_seed)
+//! - Stratified and weighted, seeded:
+//!   - [`PCollection<T>::sample_stratified`](#method.sample_stratified)
+//!   - [`PCollection<T>::sample_weighted`](#method.sample_weighted)
 //! - Per-key (keyed), seeded:
 //!   - [`PCollection<(K,V)>::sample_values_reservoir_vec`](#method.sample_values_reservoir_vec)
 //!   - [`PCollection<(K,V)>::sample_values_reservoir`](#method.sample_values_reservoir)
@@ -19,21 +22,24 @@
 //!   - [`PCollection<(K,V)>::sample_per_key`](#method.sample_per_key)
 //!   - [`PCollection<(K,V)>::sample_per_key_with_seed`](#method.sample_per_key_with_seed)
 //!
-//! The Beam-style `sample_globally` / `sample_per_key` helpers use a fixed
-//! default seed so two runs over the same input produce the same sample;
-//! pass an explicit seed via the `_with_seed` variants to vary the choice.
+//! The Beam-style `sample_globally` / `sample_per_key` helpers use the
+//! pipeline's sample seed (a fixed default unless changed with
+//! [`Pipeline::set_sample_seed`](crate::Pipeline::set_sample_seed)) so two
+//! runs over the same input produce the same sample; pass an explicit seed
+//! via the `_with_seed` variants to vary the choice.
 
-use crate::combiners::PriorityReservoir;
+use crate::combiners::{PriorityReservoir, SplitMix64, WeightedReservoir};
 use crate::{Element, PCollection};
-use core::hash::Hash;
+use core::hash::{Hash, Hasher};
+use std::hash::DefaultHasher;
 
-/// Default seed used by [`PCollection::sample_globally`] and
-/// [`PCollection::sample_per_key`]. The constant is the `SplitMix64` golden
+/// Default pipeline sample seed, used by [`PCollection::sample_globally`] and
+/// [`PCollection::sample_per_key`] unless the pipeline sets another. The constant is the `SplitMix64` golden
 /// ratio (`(sqrt(5) - 1) / 2 * 2^64`); it scrambles well under the
 /// combiner's seed-mixing multiply, giving a high-entropy starting state
 /// for the internal PRNG. Being fixed means two runs in the same
 /// execution mode pick the same sample.
-const DEFAULT_SAMPLE_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+pub(crate) const DEFAULT_SAMPLE_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
 
 impl<T: Element> PCollection<T> {
     /// Sample **k** elements globally using a priority reservoir and return a single `Vec<T>`.
@@ -59,8 +65,8 @@ impl<T: Element> PCollection<T> {
     /// without replacement.
     ///
     /// This is the Ironbeam equivalent of Beam's
-    /// `Sample.FixedSizeGlobally(n)`. The combiner uses a fixed default
-    /// seed, so two runs in the **same execution mode** (both sequential,
+    /// `Sample.FixedSizeGlobally(n)`. The combiner is seeded with the
+    /// pipeline's [`sample_seed`](crate::Pipeline::sample_seed), so two runs in the **same execution mode** (both sequential,
     /// or both parallel with the same partition count) over the same
     /// input multiset return the same sample.
     /// Sequential and parallel runs may pick different elements — every
@@ -88,7 +94,8 @@ impl<T: Element> PCollection<T> {
     /// ```
     #[must_use]
     pub fn sample_globally(self, n: usize) -> PCollection<Vec<T>> {
-        self.sample_reservoir_vec(n, DEFAULT_SAMPLE_SEED)
+        let seed = self.pipeline.sample_seed();
+        self.sample_reservoir_vec(n, seed)
     }
 
     /// Beam-compatible global fixed-size sample with a user-supplied seed.
@@ -100,6 +107,102 @@ impl<T: Element> PCollection<T> {
     pub fn sample_globally_with_seed(self, n: usize, seed: u64) -> PCollection<Vec<T>> {
         self.sample_reservoir_vec(n, seed)
     }
+
+    /// Stratified sample: keep a fixed fraction of the elements of each stratum.
+    ///
+    /// Elements are grouped by `stratum_fn`, and from a stratum of `n` elements
+    /// exactly `round(fraction * n)` are drawn without replacement, where `fraction`
+    /// is `fraction_per_stratum(&stratum)` clamped to `[0, 1]`. Giving rare strata a
+    /// larger fraction than common ones builds a balanced training set.
+    ///
+    /// The draw is a seeded shuffle of each stratum, with the seed mixed with the
+    /// stratum's hash. As with [`sample_globally`](Self::sample_globally), runs in
+    /// the same execution mode over the same input pick the same sample.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use anyhow::Result;
+    /// use ironbeam::*;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// // 900 negatives, 100 positives: keep 10% of negatives and every positive.
+    /// let labels: Vec<(u32, bool)> = (0..1_000).map(|i| (i, i % 10 == 0)).collect();
+    /// let balanced = from_vec(&p, labels)
+    ///     .sample_stratified(|&(_, positive)| positive, |&positive| if positive { 1.0 } else { 0.1 }, 7)
+    ///     .collect_seq()?;
+    /// assert_eq!(balanced.iter().filter(|(_, positive)| *positive).count(), 100);
+    /// assert_eq!(balanced.len(), 190);
+    /// # Ok(()) }
+    /// ```
+    #[must_use]
+    pub fn sample_stratified<S, F, R>(
+        self,
+        stratum_fn: F,
+        fraction_per_stratum: R,
+        seed: u64,
+    ) -> Self
+    where
+        S: Element + Eq + Hash,
+        F: 'static + Send + Sync + Fn(&T) -> S,
+        R: 'static + Send + Sync + Fn(&S) -> f64,
+    {
+        self.key_by(stratum_fn)
+            .group_by_key()
+            .flat_map(move |kv: &(S, Vec<T>)| {
+                let (stratum, values) = kv;
+                let fraction = fraction_per_stratum(stratum).clamp(0.0, 1.0);
+                #[allow(
+                    clippy::cast_possible_truncation,
+                    clippy::cast_precision_loss,
+                    clippy::cast_sign_loss
+                )]
+                let take = ((fraction * values.len() as f64).round() as usize).min(values.len());
+
+                let mut hasher = DefaultHasher::new();
+                stratum.hash(&mut hasher);
+                let mut rng = SplitMix64::new(seed ^ hasher.finish());
+                // Partial Fisher-Yates: the first `take` slots end up a uniform sample.
+                let mut values = values.clone();
+                for i in 0..take {
+                    #[allow(clippy::cast_possible_truncation)]
+                    let j = i + (rng.next_u64() % (values.len() - i) as u64) as usize;
+                    values.swap(i, j);
+                }
+                values.truncate(take);
+                values
+            })
+    }
+
+    /// Weighted sample of **k** elements without replacement, flattened into a stream.
+    ///
+    /// Each element's chance of selection is proportional to `weight_fn(&element)`,
+    /// using the [`WeightedReservoir`] (A-Res) combiner. Elements with a zero,
+    /// negative, or non-finite weight are never sampled, so fewer than `k` elements
+    /// come back if too few have a positive weight.
+    ///
+    /// # Example
+    /// ```no_run
+    /// # use anyhow::Result;
+    /// use ironbeam::*;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// // (item, popularity): popular items are picked more often.
+    /// let items = from_vec(&p, vec![(1u32, 50.0), (2, 1.0), (3, 0.0), (4, 10.0)]);
+    /// let picked = items.sample_weighted(|&(_, w)| w, 2, 42).collect_seq()?;
+    /// assert_eq!(picked.len(), 2);
+    /// assert!(picked.iter().all(|&(id, _)| id != 3));
+    /// # Ok(()) }
+    /// ```
+    #[must_use]
+    pub fn sample_weighted<F>(self, weight_fn: F, k: usize, seed: u64) -> Self
+    where
+        F: 'static + Send + Sync + Fn(&T) -> f64,
+    {
+        self.combine_globally(WeightedReservoir::new(k, seed, weight_fn), None)
+            .flat_map(|v: &Vec<T>| v.clone())
+    }
 }
 
 impl<K: Element + Eq + Hash, V: Element> PCollection<(K, V)> {
@@ -162,7 +265,8 @@ impl<K: Element + Eq + Hash, V: Element> PCollection<(K, V)> {
     /// ```
     #[must_use]
     pub fn sample_per_key(self, n: usize) -> PCollection<(K, Vec<V>)> {
-        self.sample_values_reservoir_vec(n, DEFAULT_SAMPLE_SEED)
+        let seed = self.pipeline.sample_seed();
+        self.sample_values_reservoir_vec(n, seed)
     }
 
     /// Beam-compatible per-key fixed-size sample with a user-supplied seed.
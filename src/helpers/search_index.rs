@@ -0,0 +1,107 @@
+//! [`SearchIO`] indexing sink.
+//!
+//! [`write_search_index`](PCollection::write_search_index) publishes a collection to a
+//! search index (Elasticsearch, OpenSearch, or any other [`SearchIO`] store), `batch_size`
+//! documents per [`batch_index`](SearchIO::batch_index) call.
+//!
+//! [`SearchIO`] documents are flat string maps, so every element must serialize to a map
+//! (a struct or a map type), and each of its top-level fields becomes one document field:
+//!
+//! - strings are stored as they are,
+//! - numbers, booleans, arrays and nested objects are stored as compact JSON,
+//! - `null` fields (such as `None` options) are left out.
+//!
+//! Like [`write_key_value`](PCollection::write_key_value), this takes the store by
+//! reference, so it executes the pipeline up to this point when called.
+//!
+//! ```
+//! use ironbeam::io::cloud::{FakeSearchIO, SearchIO};
+//! use ironbeam::*;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Clone, Serialize, Deserialize)]
+//! struct Product {
+//!     sku: String,
+//!     price: u32,
+//! }
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let search = FakeSearchIO::new();
+//! let p = Pipeline::default();
+//! let products = from_vec(&p, vec![Product { sku: "p-1".into(), price: 250 }]);
+//! products.write_search_index(&search, "products", |p: &Product| p.sku.clone(), 500)?;
+//!
+//! let doc = search.get("products", "p-1")?.unwrap();
+//! assert_eq!(doc["price"], "250");
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::io::cloud::traits::SearchIO;
+use crate::{Element, PCollection};
+use anyhow::{Context, Result, bail, ensure};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+impl<T: Element + Serialize> PCollection<T> {
+    /// Execute the collection and index every element in `index` under the id
+    /// `id_fn(element)`, `batch_size` documents per [`batch_index`](SearchIO::batch_index)
+    /// call.
+    ///
+    /// Batches are written in collection order, so when an id appears more than once the
+    /// last document wins. Returns the number of documents indexed.
+    ///
+    /// # Errors
+    /// Returns an error if `batch_size` is zero, the pipeline fails, an element does not
+    /// serialize to a map, or a `batch_index` call fails. Batches before the failing one
+    /// have already been indexed.
+    pub fn write_search_index<F>(
+        self,
+        search: &dyn SearchIO,
+        index: &str,
+        id_fn: F,
+        batch_size: usize,
+    ) -> Result<usize>
+    where
+        F: Fn(&T) -> String,
+    {
+        ensure!(
+            batch_size > 0,
+            "write_search_index: batch_size must be positive"
+        );
+        let index = self.pipeline.resolve_config(index)?;
+        let elements = self.collect_seq()?;
+        for (i, batch) in elements.chunks(batch_size).enumerate() {
+            let documents = batch
+                .iter()
+                .enumerate()
+                .map(|(j, element)| {
+                    let document = to_search_document(element).with_context(|| {
+                        format!("write_search_index: element #{}", i * batch_size + j)
+                    })?;
+                    Ok((id_fn(element), document))
+                })
+                .collect::<Result<Vec<_>>>()?;
+            search
+                .batch_index(&index, documents)
+                .with_context(|| format!("write_search_index: batch #{i} to {index}"))?;
+        }
+        Ok(elements.len())
+    }
+}
+
+/// Flatten an element into a search document, one field per top-level key.
+fn to_search_document<T: Serialize>(element: &T) -> Result<HashMap<String, String>> {
+    let Value::Object(fields) = serde_json::to_value(element)? else {
+        bail!("a search document must serialize to a map");
+    };
+    Ok(fields
+        .into_iter()
+        .filter_map(|(name, value)| match value {
+            Value::Null => None,
+            Value::String(s) => Some((name, s)),
+            other => Some((name, other.to_string())),
+        })
+        .collect())
+}
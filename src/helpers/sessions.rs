@@ -0,0 +1,107 @@
+//! Session windows for keyed, timestamped streams.
+//!
+//! A session is a run of one key's events in which each event follows the previous one
+//! by less than an inactivity gap. Sessions are the usual unit of user activity: page
+//! views per visit, requests per connection, and so on.
+//!
+//! ## Overview
+//! - **`sessionize(gap_ms)`**: groups a `(K, Timestamped<V>)` stream by key, orders
+//!   each key's events by timestamp, and splits them wherever two consecutive events
+//!   are `gap_ms` or more apart, emitting `(K, Session<V>)`.
+//!
+//! Unlike tumbling windows, session boundaries depend on the data, so each key's
+//! events are gathered in one place (a `group_by_key` barrier) before splitting.
+//!
+//! ## Example
+//! ```
+//! use ironbeam::*;
+//! use anyhow::Result;
+//! # fn main() -> Result<()> {
+//! let p = Pipeline::default();
+//! let clicks = from_vec(&p, vec![
+//!     ("alice".to_string(), Timestamped::new(1_000, "home".to_string())),
+//!     ("alice".to_string(), Timestamped::new(4_000, "cart".to_string())),
+//!     ("alice".to_string(), Timestamped::new(90_000, "home".to_string())),
+//!     ("bob".to_string(), Timestamped::new(2_000, "home".to_string())),
+//! ]);
+//!
+//! // A new session starts after 30 seconds of inactivity.
+//! let spans = clicks
+//!     .sessionize(30_000)
+//!     .map(|(user, s)| (user.clone(), s.start, s.end, s.len()))
+//!     .collect_seq_sorted()?;
+//! assert_eq!(spans, vec![
+//!     ("alice".to_string(), 1_000, 4_000, 2),
+//!     ("alice".to_string(), 90_000, 90_000, 1),
+//!     ("bob".to_string(), 2_000, 2_000, 1),
+//! ]);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{Element, PCollection, Session, Timestamped};
+use std::hash::Hash;
+
+impl<K: Element + Eq + Hash, V: Element> PCollection<(K, Timestamped<V>)> {
+    /// Split each key's events into sessions separated by at least `gap_ms` of
+    /// inactivity.
+    ///
+    /// Two consecutive events of a key belong to the same session when the later one
+    /// is less than `gap_ms` after the earlier one. Each session is emitted as
+    /// `(K, Session<V>)`, with its events in timestamp order; a key with `n` sessions
+    /// produces `n` output elements, in no particular order. Events with equal
+    /// timestamps keep an arbitrary relative order.
+    ///
+    /// # Panics
+    /// Panics if `gap_ms == 0`.
+    ///
+    /// ### Example
+    /// ```
+    /// use ironbeam::*;
+    /// use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let events = from_vec(&p, vec![
+    ///     (7u32, Timestamped::new(100, 1.5f64)),
+    ///     (7, Timestamped::new(150, 2.5)),
+    ///     (7, Timestamped::new(400, 4.0)),
+    /// ]);
+    ///
+    /// let totals = events
+    ///     .sessionize(100)
+    ///     .map(|(k, s)| (*k, s.start, s.events.iter().map(|e| e.value).sum::<f64>()))
+    ///     .collect_seq()?;
+    /// assert_eq!(totals.len(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn sessionize(self, gap_ms: u64) -> PCollection<(K, Session<V>)> {
+        assert!(gap_ms > 0, "sessionize: gap_ms must be positive");
+        self.group_by_key()
+            .flat_map(move |kv: &(K, Vec<Timestamped<V>>)| {
+                let (key, events) = kv;
+                let mut events = events.clone();
+                events.sort_by_key(|e| e.ts);
+
+                let mut sessions: Vec<(K, Session<V>)> = Vec::new();
+                for event in events {
+                    match sessions.last_mut() {
+                        Some((_, s)) if event.ts - s.end < gap_ms => {
+                            s.end = event.ts;
+                            s.events.push(event);
+                        }
+                        _ => sessions.push((
+                            key.clone(),
+                            Session {
+                                start: event.ts,
+                                end: event.ts,
+                                events: vec![event],
+                            },
+                        )),
+                    }
+                }
+                sessions
+            })
+    }
+}
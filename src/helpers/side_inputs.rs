@@ -10,11 +10,25 @@
 //! - Keyed enrichment joins (`side_hashmap`, `side_multimap`).
 //! - Scalar broadcast values (`side_singleton`).
 //! - Conditional filters using external lists or maps.
+//! - Pre-filtering keyed data against a broadcast Bloom filter (`filter_with_bloom`).
+//! - Reference tables read from a file when the pipeline runs (`side_from_jsonl`,
+//!   `side_from_csv`) or reloaded periodically ([`RefreshableSideInput`]).
 //!
 //! Side inputs are designed for **low-volume, high-fanout** data that would be
 //! inefficient to materialize as a full join. They should comfortably fit in
 //! memory and remain immutable during execution.
 //!
+//! ### Loading at execution time
+//! The constructors above take their data up front, so it is embedded in the pipeline
+//! as it is built. A [`RefreshableSideInput`] instead holds a loader that runs the
+//! first time a partition of a consuming transform needs the data, and again whenever
+//! the loaded value is older than its [refresh interval](RefreshableSideInput::refresh_every).
+//! This suits pipelines that are built once and executed repeatedly by a long-running
+//! process. Each partition reads one snapshot, so a reload never changes the data
+//! part-way through a partition. A loader error fails the run with
+//! [`IronbeamError::PanicInTransform`](crate::IronbeamError::PanicInTransform) at the
+//! consuming step.
+//!
 //! ### Example
 //! ```no_run
 //! use ironbeam::*;
@@ -47,11 +61,19 @@
 //!     });
 //! ```
 
+use crate::bloom_filter::BloomFilter;
 use crate::collection::{SideInput, SideMap, SideMultimap, SideSingleton};
+use crate::io::csv::read_csv_vec;
+use crate::io::jsonl::read_jsonl_vec;
+use crate::window::{Clock, SystemClock, TimestampMs};
 use crate::{Element, PCollection};
+use anyhow::Result;
+use serde::de::DeserializeOwned;
 use std::collections::HashMap;
 use std::hash::Hash;
-use std::sync::Arc;
+use std::path::Path;
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::Duration;
 
 /// Create a read-only side input backed by a plain `Vec<T>`.
 ///
@@ -395,3 +417,291 @@ impl<T: Element> PCollection<T> {
         self.filter(move |t: &T| pred(t, &arc))
     }
 }
+
+impl<K, V> PCollection<(K, V)>
+where
+    K: Element + Hash,
+    V: Element,
+{
+    /// Drop elements whose key is **definitely absent** from a broadcast
+    /// [`BloomFilter`].
+    ///
+    /// Elements whose key might be present (including ~1 % false positives)
+    /// pass through unchanged. Build the filter from the other side of a
+    /// join with [`BloomFilterBuilder`](crate::combiners::BloomFilterBuilder)
+    /// to shrink the probe side before an expensive join or lookup.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use ironbeam::*;
+    /// use ironbeam::combiners::BloomFilterBuilder;
+    ///
+    /// # fn main() -> anyhow::Result<()> {
+    /// let p = Pipeline::default();
+    /// let vip = from_vec(&p, vec!["alice".to_string()])
+    ///     .combine_globally(BloomFilterBuilder::new(1), None)
+    ///     .collect_seq()?
+    ///     .remove(0);
+    /// let clicks = from_vec(&p, vec![("alice".to_string(), 1u32), ("bob".to_string(), 2)]);
+    /// let vip_clicks = clicks.filter_with_bloom(&side_singleton(vip));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn filter_with_bloom(self, filter: &SideSingleton<BloomFilter>) -> Self {
+        let arc = filter.0.clone();
+        self.filter(move |(k, _): &(K, V)| arc.might_contain(k))
+    }
+}
+
+type Loader<T> = dyn Fn() -> Result<T> + Send + Sync;
+
+/// The loaded value and when it was loaded.
+type Cached<T> = Option<(Arc<T>, TimestampMs)>;
+
+/// A side input whose value is produced by a loader when the pipeline runs, and
+/// optionally reloaded once it is older than a refresh interval.
+///
+/// The loader runs lazily, the first time a consuming transform
+/// ([`map_with_refreshable`](PCollection::map_with_refreshable),
+/// [`filter_with_refreshable`](PCollection::filter_with_refreshable)) processes a
+/// partition. The value is then cached and shared by every partition and every later
+/// run of the pipeline, until [`refresh_every`](Self::refresh_every) makes it stale or
+/// [`refresh`](Self::refresh) reloads it explicitly. Clones share the cache.
+///
+/// Staleness is measured with the side input's [`Clock`] ([`SystemClock`] by default),
+/// so tests can drive refreshes with a fake clock.
+///
+/// # Examples
+/// ```no_run
+/// use ironbeam::*;
+/// use std::time::Duration;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let rates = RefreshableSideInput::new(|| {
+///     let text = std::fs::read_to_string("rate.txt")?;
+///     Ok(text.trim().parse::<f64>()?)
+/// })
+/// .refresh_every(Duration::from_secs(300));
+///
+/// let p = Pipeline::default();
+/// let converted = from_vec(&p, vec![10.0f64, 25.0]).map_with_refreshable(&rates, |x, r| x * r);
+/// # Ok(())
+/// # }
+/// ```
+pub struct RefreshableSideInput<T: Element> {
+    loader: Arc<Loader<T>>,
+    every: Option<Duration>,
+    clock: Arc<dyn Clock>,
+    cache: Arc<RwLock<Cached<T>>>,
+}
+
+impl<T: Element> Clone for RefreshableSideInput<T> {
+    fn clone(&self) -> Self {
+        Self {
+            loader: Arc::clone(&self.loader),
+            every: self.every,
+            clock: Arc::clone(&self.clock),
+            cache: Arc::clone(&self.cache),
+        }
+    }
+}
+
+impl<T: Element> RefreshableSideInput<T> {
+    /// A side input loaded once by `loader`, on first use.
+    pub fn new<L>(loader: L) -> Self
+    where
+        L: 'static + Send + Sync + Fn() -> Result<T>,
+    {
+        Self {
+            loader: Arc::new(loader),
+            every: None,
+            clock: Arc::new(SystemClock),
+            cache: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Reload the value on the first use after it has been cached for `interval`.
+    #[must_use]
+    pub const fn refresh_every(mut self, interval: Duration) -> Self {
+        self.every = Some(interval);
+        self
+    }
+
+    /// Measure the refresh interval with `clock` instead of the system clock.
+    #[must_use]
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// The current value, loading it first if it has never been loaded or is stale.
+    ///
+    /// # Errors
+    /// Returns the loader's error. A failed reload leaves the previous value cached,
+    /// and the next call tries again.
+    pub fn get(&self) -> Result<Arc<T>> {
+        let now = self.clock.now_ms();
+        if let Some((value, loaded_at)) =
+            &*self.cache.read().unwrap_or_else(PoisonError::into_inner)
+            && !self.is_stale(*loaded_at, now)
+        {
+            return Ok(Arc::clone(value));
+        }
+        let mut cache = self.cache.write().unwrap_or_else(PoisonError::into_inner);
+        // Another caller may have reloaded while we waited for the lock.
+        if let Some((value, loaded_at)) = &*cache
+            && !self.is_stale(*loaded_at, now)
+        {
+            return Ok(Arc::clone(value));
+        }
+        let value = Arc::new((self.loader)()?);
+        *cache = Some((Arc::clone(&value), now));
+        Ok(value)
+    }
+
+    /// Reload the value now, regardless of its age.
+    ///
+    /// # Errors
+    /// Returns the loader's error, leaving the previous value cached.
+    pub fn refresh(&self) -> Result<()> {
+        let value = Arc::new((self.loader)()?);
+        *self.cache.write().unwrap_or_else(PoisonError::into_inner) =
+            Some((value, self.clock.now_ms()));
+        Ok(())
+    }
+
+    fn is_stale(&self, loaded_at: TimestampMs, now: TimestampMs) -> bool {
+        self.every.is_some_and(|every| {
+            let every = u64::try_from(every.as_millis()).unwrap_or(u64::MAX);
+            now.saturating_sub(loaded_at) >= every
+        })
+    }
+
+    /// Per-partition snapshot for a consuming transform.
+    fn snapshot(&self) -> Arc<T> {
+        self.get()
+            .unwrap_or_else(|e| panic!("failed to load side input: {e:#}"))
+    }
+}
+
+/// Create a hash map side input read from a JSON Lines file when the pipeline runs.
+///
+/// Each line is a two-element array `[key, value]`. The file is read on first use (see
+/// [`RefreshableSideInput`]), so the pipeline does not hold the data until it executes;
+/// chain [`refresh_every`](RefreshableSideInput::refresh_every) to pick up changes to
+/// the file between runs. Later lines win over earlier ones with the same key.
+///
+/// # Examples
+/// ```no_run
+/// use ironbeam::*;
+///
+/// let p = Pipeline::default();
+/// let names = side_from_jsonl::<u32, String>("users.jsonl");
+/// let labelled = from_vec(&p, vec![1u32, 2])
+///     .map_with_refreshable(&names, |id, m| m.get(id).cloned().unwrap_or_default());
+/// ```
+pub fn side_from_jsonl<K, V>(path: impl AsRef<Path>) -> RefreshableSideInput<HashMap<K, V>>
+where
+    K: Element + Eq + Hash + DeserializeOwned,
+    V: Element + DeserializeOwned,
+{
+    let path = path.as_ref().to_path_buf();
+    RefreshableSideInput::new(move || Ok(read_jsonl_vec::<(K, V)>(&path)?.into_iter().collect()))
+}
+
+/// Create a hash map side input read from a two-column CSV file when the pipeline runs.
+///
+/// Each row is deserialized as `(key, value)`; set `has_headers` if the first row is
+/// a header. Loading and refreshing work as for [`side_from_jsonl`].
+///
+/// # Examples
+/// ```no_run
+/// use ironbeam::*;
+///
+/// let p = Pipeline::default();
+/// let prices = side_from_csv::<String, f64>("prices.csv", true);
+/// let totals = from_vec(&p, vec![("apple".to_string(), 3u32)])
+///     .map_with_refreshable(&prices, |(item, n), m| m.get(item).unwrap_or(&0.0) * f64::from(*n));
+/// ```
+pub fn side_from_csv<K, V>(
+    path: impl AsRef<Path>,
+    has_headers: bool,
+) -> RefreshableSideInput<HashMap<K, V>>
+where
+    K: Element + Eq + Hash + DeserializeOwned,
+    V: Element + DeserializeOwned,
+{
+    let path = path.as_ref().to_path_buf();
+    RefreshableSideInput::new(move || {
+        Ok(read_csv_vec::<(K, V)>(&path, has_headers)?
+            .into_iter()
+            .collect())
+    })
+}
+
+impl<T: Element> PCollection<T> {
+    /// Map with a [`RefreshableSideInput`].
+    ///
+    /// Each partition takes one snapshot of the side input before its first element, so
+    /// all of its elements see the same value even if a refresh is due mid-partition.
+    ///
+    /// # Panics
+    ///
+    /// The consuming step fails with
+    /// [`IronbeamError::PanicInTransform`](crate::IronbeamError::PanicInTransform) if the
+    /// side input's loader returns an error.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use ironbeam::*;
+    ///
+    /// let p = Pipeline::default();
+    /// let factor = RefreshableSideInput::new(|| Ok(3u32));
+    /// let scaled = from_vec(&p, vec![1u32, 2, 3]).map_with_refreshable(&factor, |x, f| x * f);
+    /// ```
+    #[must_use]
+    pub fn map_with_refreshable<O, S, F>(
+        self,
+        side: &RefreshableSideInput<S>,
+        f: F,
+    ) -> PCollection<O>
+    where
+        O: Element,
+        S: Element,
+        F: 'static + Send + Sync + Fn(&T, &S) -> O,
+    {
+        let side = side.clone();
+        self.map_with_resource(move || side.snapshot(), move |s: &Arc<S>, t: &T| f(t, s))
+    }
+
+    /// Filter with a [`RefreshableSideInput`].
+    ///
+    /// Snapshots are taken per partition, as in
+    /// [`map_with_refreshable`](Self::map_with_refreshable).
+    ///
+    /// # Panics
+    ///
+    /// The consuming step fails with
+    /// [`IronbeamError::PanicInTransform`](crate::IronbeamError::PanicInTransform) if the
+    /// side input's loader returns an error.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use ironbeam::*;
+    /// use std::collections::HashSet;
+    ///
+    /// let p = Pipeline::default();
+    /// let blocked = RefreshableSideInput::new(|| Ok(HashSet::from([2u32])));
+    /// let allowed = from_vec(&p, vec![1u32, 2, 3]).filter_with_refreshable(&blocked, |x, b| !b.contains(x));
+    /// ```
+    #[must_use]
+    pub fn filter_with_refreshable<S, F>(self, side: &RefreshableSideInput<S>, pred: F) -> Self
+    where
+        S: Element,
+        F: 'static + Send + Sync + Fn(&T, &S) -> bool,
+    {
+        let side = side.clone();
+        self.filter_with_resource(move || side.snapshot(), move |s: &Arc<S>, t: &T| pred(t, s))
+    }
+}
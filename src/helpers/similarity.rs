@@ -0,0 +1,95 @@
+//! Nearest-neighbor joins over vector embeddings.
+//!
+//! [`similarity_join`](PCollection::similarity_join) matches two collections of
+//! `(id, embedding)` pairs by vector distance rather than by key, the core step of
+//! embedding-based deduplication and entity matching. Instead of scoring every pair
+//! (a cross join), it materializes both sides, builds an [`HnswIndex`] over the
+//! smaller one, and queries it once per element of the larger one, in parallel.
+//!
+//! ## Available operations
+//! - [`PCollection::similarity_join`] - Approximate k-nearest-neighbor join
+//!
+//! ### Example
+//! ```no_run
+//! use ironbeam::*;
+//! use anyhow::Result;
+//!
+//! # fn main() -> Result<()> {
+//! let p = Pipeline::default();
+//! let products = from_vec(&p, vec![("p1".to_string(), vec![0.9f32, 0.1]), ("p2".into(), vec![0.1, 0.9])]);
+//! let listings = from_vec(&p, vec![(17u64, vec![0.8f32, 0.2])]);
+//!
+//! // (listing id, (product id, cosine distance)) for each product and its closest
+//! // listing: the listing side is the smaller one, so it is the one indexed
+//! let matches = listings
+//!     .similarity_join(&products, 1, VectorMetric::Cosine)
+//!     .filter(|(_, (_, distance))| *distance < 0.05);
+//! # Ok(()) }
+//! ```
+
+use crate::hnsw::{HnswIndex, VectorMetric};
+use crate::{Element, PCollection};
+use rayon::prelude::*;
+
+impl<I: Element> PCollection<(I, Vec<f32>)> {
+    /// Approximate k-nearest-neighbor join with another collection of embeddings:
+    /// `(i, vi)` and `(j, wj)` -> `(i, (j, distance(vi, wj)))`.
+    ///
+    /// The smaller side is indexed (`other` if both are the same size), and each
+    /// element of the larger side is paired with its `k` nearest neighbors in it under
+    /// `metric`, fewer if the indexed side has fewer than `k` elements. Pairs are always
+    /// emitted as `(i, (j, distance))` with `i` from `self`, so the output holds up to
+    /// `k` pairs per element of `self` when `other` is the smaller side, and up to `k`
+    /// pairs per element of `other` otherwise. Smaller distances are closer.
+    ///
+    /// Results are approximate once the indexed side has more than a few hundred
+    /// vectors: a true neighbor is occasionally missed. For deduplication, join a
+    /// collection with itself and drop the pairs where the ids are equal.
+    ///
+    /// # Panics
+    ///
+    /// Panics during execution if the embeddings do not all have the same length.
+    #[must_use]
+    pub fn similarity_join<J: Element>(
+        &self,
+        other: &PCollection<(J, Vec<f32>)>,
+        k: usize,
+        metric: VectorMetric,
+    ) -> PCollection<(I, (J, f32))> {
+        let left = self.clone().key_by(|_| ()).group_by_key();
+        let right = other.clone().key_by(|_| ()).group_by_key();
+        left.join_inner(&right)
+            .flat_map(move |((), (left, right))| {
+                if left.len() < right.len() {
+                    let index = build_index(left, metric);
+                    right
+                        .par_iter()
+                        .flat_map_iter(|(j, w)| {
+                            index
+                                .search(w, k)
+                                .into_iter()
+                                .map(|(i, d)| (i.clone(), (j.clone(), d)))
+                        })
+                        .collect::<Vec<_>>()
+                } else {
+                    let index = build_index(right, metric);
+                    left.par_iter()
+                        .flat_map_iter(|(i, v)| {
+                            index
+                                .search(v, k)
+                                .into_iter()
+                                .map(|(j, d)| (i.clone(), (j.clone(), d)))
+                        })
+                        .collect::<Vec<_>>()
+                }
+            })
+    }
+}
+
+fn build_index<T: Clone>(side: &[(T, Vec<f32>)], metric: VectorMetric) -> HnswIndex<T> {
+    let mut index = HnswIndex::new(metric);
+    for (id, vector) in side {
+        index.insert(id.clone(), vector.clone());
+    }
+    index
+}
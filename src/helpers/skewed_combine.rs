@@ -0,0 +1,202 @@
+//! Skew-tolerant combine-by-key for [`PCollection`].
+//!
+//! A plain [`combine_values`](crate::PCollection::combine_values) builds one accumulator
+//! per key and merges all of a key's partial accumulators in a single step. With
+//! power-law key distributions a handful of **hot keys** carry most of the data, and
+//! whichever reducer owns them becomes the bottleneck.
+//!
+//! [`PCollection::combine_values_skewed`] spreads each key over `salt_factor` sub-keys:
+//!
+//! 1. **Salt** — every `(K, V)` becomes `((K, salt), V)`, with the salt assigned
+//!    round-robin within each partition.
+//! 2. **Pre-aggregate** — a first combine builds one accumulator per `(K, salt)`, so a
+//!    hot key's values are reduced by up to `salt_factor` independent accumulators.
+//! 3. **Final aggregate** — salts are dropped and the (at most `salt_factor`) partial
+//!    accumulators per key are merged and finished.
+//!
+//! The result equals `combine_values(comb)` for any combiner whose `merge` is
+//! associative and commutative. [`PCollection::combine_values_with_hint`] takes a
+//! [`SkewHint`] instead, so call sites can switch strategies without restructuring.
+
+use crate::collection::CombineFn;
+use crate::node::DynOp;
+use crate::{Element, PCollection, Partition};
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// How a keyed combine should treat the key distribution.
+///
+/// Passed to [`PCollection::combine_values_with_hint`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SkewHint {
+    /// Keys are roughly evenly loaded; use the single-phase combine.
+    #[default]
+    Uniform,
+    /// A few keys are hot; use the salted two-phase combine with `salt_factor` sub-keys.
+    HotKeys {
+        /// Number of sub-keys each key is spread across (`0` and `1` disable salting).
+        salt_factor: u32,
+    },
+}
+
+impl<K: Element + Eq + Hash, V: Element> PCollection<(K, V)> {
+    /// Combine-by-key with a salted two-phase aggregation for hot keys.
+    ///
+    /// Equivalent to [`combine_values`](Self::combine_values) for combiners with an
+    /// associative and commutative `merge`, but each key is first pre-aggregated under
+    /// `salt_factor` sub-keys so no single accumulator absorbs all of a hot key's values.
+    /// A `salt_factor` of `0` or `1` falls back to the single-phase combine.
+    ///
+    /// The accumulator `A` crosses a barrier between the two phases, so it must be an
+    /// [`Element`].
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ironbeam::*;
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let hits = from_vec(&p, (0..10_000u64).map(|i| (if i % 10 == 0 { i % 7 } else { 0 }, 1u64)).collect());
+    /// let counts = hits.combine_values_skewed(Sum::<u64>::default(), 8).collect_par_sorted_by_key(None, None)?;
+    /// assert_eq!(counts.iter().map(|(_, c)| c).sum::<u64>(), 10_000);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn combine_values_skewed<C, A, O>(self, comb: C, salt_factor: u32) -> PCollection<(K, O)>
+    where
+        C: CombineFn<V, A, O> + 'static,
+        A: Element,
+        O: Element,
+    {
+        if salt_factor <= 1 {
+            return self.combine_values(comb);
+        }
+        let comb = Arc::new(comb);
+        let salted: PCollection<((K, u32), V)> = self.apply_transform(Arc::new(SaltOp::<K, V> {
+            salt_factor,
+            _t: PhantomData,
+        }));
+        salted
+            .combine_values(PartialCombine {
+                comb: Arc::clone(&comb),
+                _t: PhantomData,
+            })
+            .map(|((k, _salt), acc): &((K, u32), A)| (k.clone(), acc.clone()))
+            .combine_values(FinalCombine {
+                comb,
+                _t: PhantomData,
+            })
+    }
+
+    /// Combine-by-key, choosing the strategy from a [`SkewHint`].
+    ///
+    /// [`SkewHint::Uniform`] runs [`combine_values`](Self::combine_values);
+    /// [`SkewHint::HotKeys`] runs [`combine_values_skewed`](Self::combine_values_skewed).
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ironbeam::*;
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let kv = from_vec(&p, vec![("a".to_string(), 1u64), ("a".into(), 2), ("b".into(), 3)]);
+    /// let hint = SkewHint::HotKeys { salt_factor: 4 };
+    /// let sums = kv.combine_values_with_hint(Sum::<u64>::default(), hint).collect_seq_sorted()?;
+    /// assert_eq!(sums, vec![("a".to_string(), 3), ("b".to_string(), 3)]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn combine_values_with_hint<C, A, O>(self, comb: C, hint: SkewHint) -> PCollection<(K, O)>
+    where
+        C: CombineFn<V, A, O> + 'static,
+        A: Element,
+        O: Element,
+    {
+        match hint {
+            SkewHint::Uniform => self.combine_values(comb),
+            SkewHint::HotKeys { salt_factor } => self.combine_values_skewed(comb, salt_factor),
+        }
+    }
+}
+
+/// `Vec<(K, V)> -> Vec<((K, salt), V)>`, assigning salts round-robin within the partition.
+struct SaltOp<K, V> {
+    salt_factor: u32,
+    _t: PhantomData<fn() -> (K, V)>,
+}
+
+impl<K: Element, V: Element> DynOp for SaltOp<K, V> {
+    fn apply(&self, input: Partition) -> Partition {
+        let kv = *input
+            .downcast::<Vec<(K, V)>>()
+            .expect("SaltOp: expected Vec<(K, V)> input");
+        let out: Vec<((K, u32), V)> = kv
+            .into_iter()
+            .zip((0..self.salt_factor).cycle())
+            .map(|((k, v), salt)| ((k, salt), v))
+            .collect();
+        Box::new(out) as Partition
+    }
+}
+
+/// Phase one: the wrapped combiner with `finish` replaced by the identity, emitting `A`.
+struct PartialCombine<C, O> {
+    comb: Arc<C>,
+    _t: PhantomData<fn() -> O>,
+}
+
+impl<V, A, O, C> CombineFn<V, A, A> for PartialCombine<C, O>
+where
+    C: CombineFn<V, A, O>,
+    O: 'static,
+{
+    fn create(&self) -> A {
+        self.comb.create()
+    }
+    fn add_input(&self, acc: &mut A, v: V) {
+        self.comb.add_input(acc, v);
+    }
+    fn merge(&self, acc: &mut A, other: A) {
+        self.comb.merge(acc, other);
+    }
+    fn finish(&self, acc: A) -> A {
+        acc
+    }
+    fn is_associative_commutative(&self) -> bool {
+        self.comb.is_associative_commutative()
+    }
+    fn accepts_partial_groups(&self) -> bool {
+        self.comb.accepts_partial_groups()
+    }
+}
+
+/// Phase two: merges partial accumulators (as inputs) and applies the real `finish`.
+struct FinalCombine<C, V> {
+    comb: Arc<C>,
+    _t: PhantomData<fn() -> V>,
+}
+
+impl<V, A, O, C> CombineFn<A, A, O> for FinalCombine<C, V>
+where
+    C: CombineFn<V, A, O>,
+    V: 'static,
+{
+    fn create(&self) -> A {
+        self.comb.create()
+    }
+    fn add_input(&self, acc: &mut A, partial: A) {
+        self.comb.merge(acc, partial);
+    }
+    fn merge(&self, acc: &mut A, other: A) {
+        self.comb.merge(acc, other);
+    }
+    fn finish(&self, acc: A) -> O {
+        self.comb.finish(acc)
+    }
+    fn is_associative_commutative(&self) -> bool {
+        self.comb.is_associative_commutative()
+    }
+}
@@ -0,0 +1,116 @@
+//! Global sort: a graph-level ordering barrier for [`PCollection`].
+//!
+//! # Overview
+//!
+//! [`PCollection::sort_by`] and [`PCollection::sort_by_key`] insert a [`Node::Sort`]
+//! barrier that produces a **totally ordered** collection mid-pipeline, rather than
+//! only at the very end via the `collect_*_sorted` helpers.
+//!
+//! Execution strategy:
+//!
+//! 1. **Range partition** — in parallel mode, split points are sampled from the data and
+//!    every element is routed to the contiguous key range that contains it (see
+//!    [`repartition`](crate::helpers::repartition)). Ranges come out roughly balanced
+//!    even for skewed inputs. Sequential execution uses a single range.
+//! 2. **Per-range sort** — each range is sorted independently (in parallel under the
+//!    parallel runner) with a stable sort. Elements are routed in input order, so the
+//!    overall sort is stable with respect to partition order.
+//!
+//! Range `i` holds only elements that sort before those of range `i + 1`, so downstream
+//! stateless transforms stay parallel and the collected output remains ordered.
+//!
+//! Order-preserving transforms after the sort (`map`, `filter`, `take`, …) see and keep
+//! the ordering. Keyed barriers such as [`group_by_key`](crate::PCollection::group_by_key)
+//! do not preserve it.
+
+use crate::helpers::repartition::range_partition_erased;
+use crate::node::Node;
+use crate::{Element, PCollection, Partition};
+use std::cmp::Ordering;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+impl<T: Element> PCollection<T> {
+    /// Sort the collection globally with the comparator `cmp`.
+    ///
+    /// This is a barrier backed by [`Node::Sort`]: elements are range-partitioned by
+    /// sampled split points and every range is sorted independently. The sort is stable:
+    /// elements that compare equal keep their relative input order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a partition holds a type other than `Vec<T>`. This cannot occur in
+    /// normal usage because the closures are constructed from a typed `PCollection<T>`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ironbeam::*;
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let words = from_vec(&p, vec!["pear".to_string(), "fig".into(), "banana".into()]);
+    /// let by_len = words.sort_by(|a, b| a.len().cmp(&b.len())).collect_par(None, None)?;
+    /// assert_eq!(by_len, vec!["fig", "pear", "banana"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn sort_by<F>(self, cmp: F) -> Self
+    where
+        F: Fn(&T, &T) -> Ordering + Send + Sync + 'static,
+    {
+        let cmp = Arc::new(cmp);
+
+        let cmp_run = Arc::clone(&cmp);
+        let sort_run = Arc::new(move |p: Partition| -> Partition {
+            #[allow(clippy::expect_used)]
+            let mut v = *p
+                .downcast::<Vec<T>>()
+                .expect("Sort: partition held unexpected element type");
+            v.sort_by(|a, b| cmp_run(a, b));
+            Box::new(v) as Partition
+        });
+
+        let range_partition = Arc::new(move |parts: Vec<Partition>, n: usize| {
+            range_partition_erased::<T>(parts, n, cmp.as_ref())
+        });
+
+        let id = self.pipeline.insert_node(Node::Sort {
+            range_partition,
+            sort_run,
+        });
+        self.pipeline.connect(self.id, id);
+        self.pipeline.set_coder::<T>(id);
+        Self {
+            pipeline: self.pipeline,
+            id,
+            _t: PhantomData,
+        }
+    }
+
+    /// Sort the collection globally, ascending by `key_fn(&element)`.
+    ///
+    /// Convenience wrapper over [`sort_by`](Self::sort_by); wrap the key in
+    /// [`std::cmp::Reverse`] for descending order.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ironbeam::*;
+    /// # use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let scores = from_vec(&p, vec![("b".to_string(), 7u32), ("a".into(), 9), ("c".into(), 1)]);
+    /// let best_first = scores.sort_by_key(|(_, s)| std::cmp::Reverse(*s)).collect_seq()?;
+    /// assert_eq!(best_first[0].0, "a");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn sort_by_key<S, F>(self, key_fn: F) -> Self
+    where
+        S: Ord,
+        F: Fn(&T) -> S + Send + Sync + 'static,
+    {
+        self.sort_by(move |a, b| key_fn(a).cmp(&key_fn(b)))
+    }
+}
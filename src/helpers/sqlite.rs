@@ -0,0 +1,77 @@
+//! SQLite sources and sinks for [`PCollection`].
+//!
+//! - [`read_sql_query`] runs a query against a local database into a `PCollection<T>`.
+//! - [`PCollection::write_table`] inserts a collection into a table, creating it if
+//!   needed.
+//!
+//! Both run eagerly: the read executes the query before returning, and the write
+//! executes the pipeline up to this point, as the file writers do. Column mapping is
+//! described in [`io::sqlite`](crate::io::sqlite).
+//!
+//! Database paths may be `config://` references, resolved through the pipeline's
+//! [`ConfigResolver`](crate::io::cloud::ConfigResolver).
+//!
+//! ## Feature flags
+//! - `io-sqlite`: enables this module. It is **not** part of the default feature set;
+//!   opt in explicitly to compile SQLite into the crate.
+//!
+//! ## Example
+//! ```no_run
+//! use ironbeam::*;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Clone, Serialize, Deserialize)]
+//! struct Visit {
+//!     page: String,
+//!     ms: i64,
+//! }
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let p = Pipeline::default();
+//! let visits = read_sql_query::<Visit>(&p, "analytics.db", "SELECT page, ms FROM visits")?;
+//! visits
+//!     .filter(|v: &Visit| v.ms > 1_000)
+//!     .write_table("analytics.db", "slow_visits")?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::io::sqlite::{read_sql_query_vec, write_table_vec};
+use crate::{Element, PCollection, Pipeline, from_vec};
+use anyhow::Result;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::path::Path;
+
+/// Run `query` against the SQLite database at `path` into a `PCollection<T>`, one
+/// element per row.
+///
+/// # Errors
+/// Returns an error if a `config://` path does not resolve, or for the reasons of
+/// [`read_sql_query_vec`].
+pub fn read_sql_query<T>(
+    p: &Pipeline,
+    path: impl AsRef<Path>,
+    query: &str,
+) -> Result<PCollection<T>>
+where
+    T: Element + DeserializeOwned,
+{
+    let path = p.resolve_path(path.as_ref())?;
+    let rows = read_sql_query_vec(&path, query)?;
+    Ok(from_vec(p, rows).record_input("sqlite", path.display()))
+}
+
+impl<T: Element + Serialize> PCollection<T> {
+    /// Execute the collection and insert it into `table` of the SQLite database at
+    /// `path`, in one transaction. Returns the number of rows inserted.
+    ///
+    /// # Errors
+    /// Returns an error if a `config://` path does not resolve, the pipeline fails, or
+    /// for the reasons of [`write_table_vec`].
+    pub fn write_table(self, path: impl AsRef<Path>, table: &str) -> Result<usize> {
+        let path = self.pipeline.resolve_path(path.as_ref())?;
+        let data = self.collect_seq()?;
+        write_table_vec(&path, table, &data)
+    }
+}
@@ -0,0 +1,284 @@
+//! Keyed state: per-key stateful processing for keyed collections.
+//!
+//! Some per-key logic does not fit a [`CombineFn`](crate::collection::CombineFn): it
+//! emits a result for every value, and that result depends on what came before
+//! (running counters, "first time seen" flags, change detection, custom
+//! accumulations). [`PCollection::map_values_stateful`] runs such logic with one
+//! state instance per key.
+//!
+//! ## Guarantees
+//! - All values of a key are routed through **one** state instance, created with
+//!   `S::default()`, and visited one at a time. Keys are processed independently.
+//! - [`map_values_stateful`](PCollection::map_values_stateful) visits a key's values
+//!   in arrival order, which can vary between execution modes and partitionings.
+//! - [`map_values_stateful_sorted_by_key`](PCollection::map_values_stateful_sorted_by_key)
+//!   first sorts them by a derived key (stably), so the result is deterministic.
+//!
+//! State lives only for the duration of one key's processing; it is not persisted
+//! between pipeline runs. Each key's values are gathered by a `group_by_key` barrier,
+//! so a key's values must fit in memory.
+//!
+//! ## Timers
+//! For keyed, timestamped streams, [`PCollection::process_with_timers`] adds an
+//! event-time **timer** per key. The event handler can set it through the
+//! [`TimerContext`]; when event time reaches it, a timer handler runs with the same
+//! state. This expresses timeouts such as "emit an abandoned cart after 30 minutes of
+//! inactivity" without a manual group + sort + scan.
+//!
+//! ## Example
+//! ```
+//! use ironbeam::*;
+//! use std::collections::HashSet;
+//! use anyhow::Result;
+//! # fn main() -> Result<()> {
+//! let p = Pipeline::default();
+//! // (user, (ts, page)): flag each user's first visit to a page.
+//! let visits = from_vec(&p, vec![
+//!     ("u1".to_string(), (1u64, "home".to_string())),
+//!     ("u1".to_string(), (2, "docs".to_string())),
+//!     ("u1".to_string(), (3, "home".to_string())),
+//! ]);
+//!
+//! let first_visits = visits
+//!     .map_values_stateful_sorted_by_key(
+//!         |(ts, _)| *ts,
+//!         |(_, page), seen: &mut HashSet<String>| seen.insert(page.clone()),
+//!     )
+//!     .collect_seq()?;
+//! let flags: Vec<bool> = first_visits.into_iter().map(|(_, first)| first).collect();
+//! assert_eq!(flags, vec![true, true, false]);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{Element, PCollection, TimestampMs, Timestamped};
+use std::hash::Hash;
+
+/// Run `f` over one key's values with a fresh state.
+fn run_with_state<K, V, S, O, F>(key: &K, values: &[V], f: &F) -> Vec<(K, O)>
+where
+    K: Clone,
+    S: Default,
+    F: Fn(&V, &mut S) -> O,
+{
+    let mut state = S::default();
+    values
+        .iter()
+        .map(|v| (key.clone(), f(v, &mut state)))
+        .collect()
+}
+
+impl<K: Element + Eq + Hash, V: Element> PCollection<(K, V)> {
+    /// Map every value with access to mutable per-key state.
+    ///
+    /// `f(value, state)` is called once per value, and its result is emitted as
+    /// `(K, O)`. All values of a key share one `S`, starting from `S::default()`, and
+    /// are visited in arrival order. Use
+    /// [`map_values_stateful_sorted_by_key`](Self::map_values_stateful_sorted_by_key)
+    /// when the order matters.
+    ///
+    /// ### Example
+    /// ```
+    /// use ironbeam::*;
+    /// use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// // Number each key's values: 1, 2, 3, ...
+    /// let numbered = from_vec(&p, vec![('a', 10u32), ('b', 20), ('a', 30)])
+    ///     .map_values_stateful(|_, count: &mut u32| {
+    ///         *count += 1;
+    ///         *count
+    ///     })
+    ///     .collect_seq_sorted()?;
+    /// assert_eq!(numbered, vec![('a', 1), ('a', 2), ('b', 1)]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn map_values_stateful<S, O, F>(self, f: F) -> PCollection<(K, O)>
+    where
+        S: Default + 'static,
+        O: Element,
+        F: 'static + Send + Sync + Fn(&V, &mut S) -> O,
+    {
+        self.group_by_key()
+            .flat_map(move |kv: &(K, Vec<V>)| run_with_state(&kv.0, &kv.1, &f))
+    }
+
+    /// Like [`map_values_stateful`](Self::map_values_stateful), but visits each key's
+    /// values in ascending order of `sort_key(&value)`.
+    ///
+    /// The sort is stable, so values with equal sort keys keep their arrival order.
+    /// With a sort key that is unique per value (a timestamp plus a sequence number,
+    /// say), the output is the same in every execution mode.
+    #[must_use]
+    pub fn map_values_stateful_sorted_by_key<SK, S, O, G, F>(
+        self,
+        sort_key: G,
+        f: F,
+    ) -> PCollection<(K, O)>
+    where
+        SK: Ord,
+        G: 'static + Send + Sync + Fn(&V) -> SK,
+        S: Default + 'static,
+        O: Element,
+        F: 'static + Send + Sync + Fn(&V, &mut S) -> O,
+    {
+        self.group_by_key_sorted_by_key(sort_key)
+            .flat_map(move |kv: &(K, Vec<V>)| run_with_state(&kv.0, &kv.1, &f))
+    }
+}
+
+/// Per-key context handed to the handlers of [`PCollection::process_with_timers`].
+///
+/// Holds the key's state, its one event-time timer, and the outputs emitted so far.
+#[derive(Debug)]
+pub struct TimerContext<S, O> {
+    /// The key's state, starting from `S::default()`.
+    pub state: S,
+    now: TimestampMs,
+    timer: Option<TimestampMs>,
+    out: Vec<Timestamped<O>>,
+}
+
+impl<S: Default, O> TimerContext<S, O> {
+    fn new() -> Self {
+        Self {
+            state: S::default(),
+            now: 0,
+            timer: None,
+            out: Vec::new(),
+        }
+    }
+}
+
+impl<S, O> TimerContext<S, O> {
+    /// Current event time: the timestamp of the event being handled, or the time the
+    /// firing timer was set for.
+    #[must_use]
+    pub const fn now(&self) -> TimestampMs {
+        self.now
+    }
+
+    /// Set the key's timer to fire at event time `at`, replacing any pending timer.
+    /// A time that has already passed fires right after the current handler returns.
+    pub const fn set_timer(&mut self, at: TimestampMs) {
+        self.timer = Some(at);
+    }
+
+    /// Cancel the pending timer, if any.
+    pub const fn clear_timer(&mut self) {
+        self.timer = None;
+    }
+
+    /// The pending timer, if any.
+    #[must_use]
+    pub const fn timer(&self) -> Option<TimestampMs> {
+        self.timer
+    }
+
+    /// Emit `value` at the current event time.
+    pub fn emit(&mut self, value: O) {
+        self.out.push(Timestamped::new(self.now, value));
+    }
+
+    /// Fire the pending timer while it is due before `until`; `None` means the end of
+    /// the input, where every remaining timer is due.
+    fn fire_due<T>(&mut self, until: Option<TimestampMs>, on_timer: &T)
+    where
+        T: Fn(&mut Self),
+    {
+        while let Some(at) = self.timer
+            && until.is_none_or(|ts| at <= ts)
+        {
+            self.timer = None;
+            self.now = self.now.max(at);
+            on_timer(self);
+        }
+    }
+}
+
+impl<K: Element + Eq + Hash, V: Element> PCollection<(K, Timestamped<V>)> {
+    /// Process each key's events in timestamp order with per-key state and a timer.
+    ///
+    /// For every event, `on_event(event, ctx)` runs with the key's [`TimerContext`].
+    /// It may update `ctx.state`, [`emit`](TimerContext::emit) outputs, and
+    /// [`set_timer`](TimerContext::set_timer). A pending timer fires, by calling
+    /// `on_timer(ctx)`, before the first event whose timestamp is at or after the
+    /// timer's time. Timers still pending after a key's last event fire at the end,
+    /// since the input is bounded. `on_timer` may set the timer again.
+    ///
+    /// Outputs are emitted as `(K, Timestamped<O>)` stamped with the event time at
+    /// which they were emitted. Events with equal timestamps are handled in an
+    /// arbitrary relative order.
+    ///
+    /// ### Example
+    /// ```
+    /// use ironbeam::*;
+    /// use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// const TIMEOUT: u64 = 30 * 60 * 1_000;
+    /// let p = Pipeline::default();
+    /// let events = from_vec(&p, vec![
+    ///     ("u1".to_string(), Timestamped::new(0, "cart".to_string())),
+    ///     ("u1".to_string(), Timestamped::new(60_000, "purchase".to_string())),
+    ///     ("u2".to_string(), Timestamped::new(0, "cart".to_string())),
+    ///     ("u2".to_string(), Timestamped::new(40 * 60 * 1_000, "view".to_string())),
+    /// ]);
+    ///
+    /// // Emit the cart time when 30 minutes pass without a purchase.
+    /// let abandoned = events
+    ///     .process_with_timers(
+    ///         |e, ctx: &mut TimerContext<Option<u64>, u64>| match e.value.as_str() {
+    ///             "cart" => {
+    ///                 ctx.state = Some(e.ts);
+    ///                 ctx.set_timer(e.ts + TIMEOUT);
+    ///             }
+    ///             "purchase" => {
+    ///                 ctx.state = None;
+    ///                 ctx.clear_timer();
+    ///             }
+    ///             _ => {}
+    ///         },
+    ///         |ctx| {
+    ///             if let Some(cart_ts) = ctx.state.take() {
+    ///                 ctx.emit(cart_ts);
+    ///             }
+    ///         },
+    ///     )
+    ///     .collect_seq()?;
+    /// assert_eq!(abandoned.len(), 1);
+    /// assert_eq!(abandoned[0].0, "u2");
+    /// assert_eq!(abandoned[0].1.ts, TIMEOUT);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn process_with_timers<S, O, F, T>(
+        self,
+        on_event: F,
+        on_timer: T,
+    ) -> PCollection<(K, Timestamped<O>)>
+    where
+        S: Default + 'static,
+        O: Element,
+        F: 'static + Send + Sync + Fn(&Timestamped<V>, &mut TimerContext<S, O>),
+        T: 'static + Send + Sync + Fn(&mut TimerContext<S, O>),
+    {
+        self.group_by_key_sorted_by_key(|e: &Timestamped<V>| e.ts)
+            .flat_map(move |kv: &(K, Vec<Timestamped<V>>)| {
+                let (key, events) = kv;
+                let mut ctx = TimerContext::new();
+                for event in events {
+                    ctx.fire_due(Some(event.ts), &on_timer);
+                    ctx.now = event.ts;
+                    on_event(event, &mut ctx);
+                }
+                ctx.fire_due(None, &on_timer);
+                ctx.out
+                    .into_iter()
+                    .map(|o| (key.clone(), o))
+                    .collect::<Vec<_>>()
+            })
+    }
+}
@@ -4,20 +4,33 @@
 //! [`combine_values`](PCollection::combine_values) for approximate-quantile operations,
 //! so callers never need to import or construct combiner structs directly.
 //!
-//! All methods are backed by the t-digest algorithm via [`ApproxMedian`] and
-//! [`ApproxQuantiles`]. The `compression` parameter controls the accuracy/memory
-//! trade-off (typical range: 20–1000; recommended default: `100.0`).
+//! The approximate methods are backed by the t-digest algorithm via [`ApproxMedian`]
+//! and [`ApproxQuantiles`]. The `compression` parameter controls the accuracy/memory
+//! trade-off (typical range: 20–1000; recommended default: `100.0`). The exact methods
+//! use [`Percentiles`], which sorts each group's values and falls back to a t-digest
+//! only for groups above 100,000 values.
 //!
 //! ## Unkeyed (global) operations — `PCollection<T>`
 //! - [`PCollection::approx_median_globally`] — approximate median → `PCollection<f64>`
 //! - [`PCollection::approx_quantiles_globally`] — approximate quantile set → `PCollection<Vec<f64>>`
+//! - [`PCollection::quantile_sketch_globally`] — mergeable t-digest → `PCollection<TDigestSketch>`
+//! - [`PCollection::exact_quantiles_globally`] — exact quantile set → `PCollection<Vec<f64>>`
 //!
 //! ## Per-key operations — `PCollection<(K, V)>`
 //! - [`PCollection::approx_median_per_key`] — approximate median per key → `PCollection<(K, f64)>`
 //! - [`PCollection::approx_quantiles_per_key`] — approximate quantile set per key → `PCollection<(K, Vec<f64>)>`
+//! - [`PCollection::quantile_sketch_per_key`] — mergeable t-digest per key → `PCollection<(K, TDigestSketch)>`
+//! - [`PCollection::exact_quantiles_per_key`] — exact quantile set per key → `PCollection<(K, Vec<f64>)>`
+//!
+//! ## Profiling — `PCollection<T: Serialize>`
+//! - [`PCollection::profile`] — per-column statistics in one pass → `PCollection<DataProfile>`
 
-use crate::combiners::{ApproxMedian, ApproxQuantiles};
+use crate::combiners::{
+    ApproxMedian, ApproxQuantiles, DataProfile, DataProfiler, Percentiles, QuantileSketch,
+    TDigestSketch,
+};
 use crate::{Element, PCollection};
+use serde::Serialize;
 use std::hash::Hash;
 
 /* ─────────────────────────────── Unkeyed (global) ─────────────────────────────── */
@@ -90,6 +103,58 @@ where
     ) -> PCollection<Vec<f64>> {
         self.combine_globally(ApproxQuantiles::<T>::new(quantiles, compression), None)
     }
+
+    /// Build a t-digest over all elements and emit the sketch itself.
+    ///
+    /// The resulting [`TDigestSketch`] is serializable and can be merged with
+    /// sketches from other runs via [`TDigest::merge`](crate::combiners::TDigest::merge)
+    /// before querying quantiles.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use anyhow::Result;
+    /// use ironbeam::*;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let sketch = from_vec(&p, (1u32..=100).collect::<Vec<_>>())
+    ///     .quantile_sketch_globally(100.0)
+    ///     .collect_seq()?;
+    /// assert_eq!(sketch[0].count(), 100.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn quantile_sketch_globally(self, compression: f64) -> PCollection<TDigestSketch> {
+        self.combine_globally(QuantileSketch::<T>::new(compression), None)
+    }
+
+    /// Compute exact quantiles of all elements globally.
+    ///
+    /// Returns a single `Vec<f64>` with one value per entry of `quantiles`, linearly
+    /// interpolated between the closest ranks. Collections above 100,000 elements fall
+    /// back to a t-digest; use [`Percentiles`] directly to change that cap.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use anyhow::Result;
+    /// use ironbeam::*;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let qs = from_vec(&p, (1u32..=5).collect::<Vec<_>>())
+    ///     .exact_quantiles_globally(vec![0.0, 0.5, 0.75])
+    ///     .collect_seq()?;
+    /// assert_eq!(qs[0], vec![1.0, 3.0, 4.0]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn exact_quantiles_globally(self, quantiles: Vec<f64>) -> PCollection<Vec<f64>> {
+        self.combine_globally(Percentiles::<T>::new(quantiles), None)
+    }
 }
 
 /* ─────────────────────────────── Per-key ─────────────────────────────── */
@@ -161,4 +226,93 @@ where
     ) -> PCollection<(K, Vec<f64>)> {
         self.combine_values(ApproxQuantiles::<V>::new(quantiles, compression))
     }
+
+    /// Build a t-digest of values per key and emit the sketch itself.
+    ///
+    /// See [`quantile_sketch_globally`](PCollection::quantile_sketch_globally).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use anyhow::Result;
+    /// use ironbeam::*;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let sketches = from_vec(&p, vec![("a".to_string(), 1.0f64), ("a".to_string(), 3.0)])
+    ///     .quantile_sketch_per_key(100.0)
+    ///     .collect_seq()?;
+    /// assert_eq!(sketches[0].1.count(), 2.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn quantile_sketch_per_key(self, compression: f64) -> PCollection<(K, TDigestSketch)> {
+        self.combine_values(QuantileSketch::<V>::new(compression))
+    }
+
+    /// Compute exact quantiles of values per key.
+    ///
+    /// Returns `(K, Vec<f64>)`; see
+    /// [`exact_quantiles_globally`](PCollection::exact_quantiles_globally).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use anyhow::Result;
+    /// use ironbeam::*;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let medians = from_vec(&p, vec![("a".to_string(), 1.0f64), ("a".to_string(), 4.0)])
+    ///     .exact_quantiles_per_key(vec![0.5])
+    ///     .collect_seq()?;
+    /// assert_eq!(medians[0].1, vec![2.5]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn exact_quantiles_per_key(self, quantiles: Vec<f64>) -> PCollection<(K, Vec<f64>)> {
+        self.combine_values(Percentiles::<V>::new(quantiles))
+    }
+}
+
+/* ─────────────────────────────── Profiling ─────────────────────────────── */
+
+impl<T: Element + Serialize> PCollection<T> {
+    /// Profile every column of the collection in a single pass.
+    ///
+    /// Returns one [`DataProfile`] with, per column, the null count, an approximate
+    /// distinct count, min/max, mean and standard deviation of numeric values, and the
+    /// most frequent values. Elements become columns like
+    /// [`Row::from_serialize`](crate::Row::from_serialize). Use
+    /// [`DataProfiler`] with [`combine_globally`](PCollection::combine_globally) to
+    /// tune the number of top values or the sketch precision.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use anyhow::Result;
+    /// use ironbeam::*;
+    /// use serde::{Deserialize, Serialize};
+    ///
+    /// #[derive(Clone, Serialize, Deserialize)]
+    /// struct Trip { city: String, miles: f64 }
+    ///
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let trips = from_vec(&p, vec![
+    ///     Trip { city: "Oslo".into(), miles: 3.0 },
+    ///     Trip { city: "Oslo".into(), miles: 5.0 },
+    /// ]);
+    /// let profile = trips.profile().collect_seq()?.remove(0);
+    /// println!("{profile}");
+    /// assert_eq!(profile.column("miles").unwrap().mean, Some(4.0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn profile(self) -> PCollection<DataProfile> {
+        self.combine_globally(DataProfiler::<T>::new(), None)
+    }
 }
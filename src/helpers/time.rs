@@ -0,0 +1,307 @@
+//! Calendar-aware timestamp helpers (feature `time`).
+//!
+//! Windowing works on [`TimestampMs`] (milliseconds since the Unix epoch), which leaves
+//! two chores to every pipeline: turning timestamp strings into milliseconds, and
+//! bucketing by calendar units whose length varies (a month, a week starting on
+//! Monday). This module does both with [`chrono`], always in UTC:
+//!
+//! - [`TimestampFormat`] / [`parse_timestamp`] - Parse RFC 3339, RFC 2822, Unix
+//!   seconds or milliseconds, or a `strftime` pattern into a [`TimestampMs`]
+//! - [`PCollection::parse_timestamp`] - Stamp each element from one of its fields,
+//!   routing unparsable elements to a dead-letter collection
+//! - [`TimeUnit`] / [`truncate_timestamp`] - Round down to the start of a second,
+//!   minute, hour, day, ISO week, month or year
+//! - [`PCollection::truncate_to`] - Truncate the timestamps of a timestamped collection
+//! - [`TimeBucket`] / [`PCollection::key_by_time_bucket`] - Key elements by the
+//!   [`Window`] of a fixed [`Duration`] or a calendar unit
+//!
+//! ### Example
+//! ```no_run
+//! use ironbeam::*;
+//! use ironbeam::helpers::time::{TimeUnit, TimestampFormat};
+//! use serde::{Deserialize, Serialize};
+//! # use anyhow::Result;
+//! # fn main() -> Result<()> {
+//! #[derive(Clone, Serialize, Deserialize)]
+//! struct Order { placed_at: String, cents: u64 }
+//!
+//! let p = Pipeline::default();
+//! let orders = read_jsonl::<Order>(&p, "orders.jsonl")?;
+//! let (stamped, unparsable) =
+//!     orders.parse_timestamp(TimestampFormat::Rfc3339, |o: &Order| o.placed_at.as_str());
+//! let per_month = stamped
+//!     .key_by_time_bucket(TimeUnit::Month)
+//!     .map_values(|o: &Order| o.cents)
+//!     .sum_per_key();
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::helpers::dead_letter::DeadLetter;
+use crate::{Element, PCollection, TimestampMs, Timestamped, Window};
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Datelike, NaiveDate, NaiveDateTime, Utc};
+use std::fmt::{self, Display};
+use std::time::Duration;
+
+const SECOND_MS: u64 = 1_000;
+const MINUTE_MS: u64 = 60 * SECOND_MS;
+const HOUR_MS: u64 = 60 * MINUTE_MS;
+const DAY_MS: u64 = 24 * HOUR_MS;
+
+/// How timestamp text is written.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TimestampFormat {
+    /// RFC 3339 / ISO 8601 with an offset, e.g. `2024-03-01T12:30:00Z`.
+    Rfc3339,
+    /// RFC 2822, e.g. `Fri, 01 Mar 2024 12:30:00 +0000`.
+    Rfc2822,
+    /// Seconds since the epoch, optionally with a fraction, e.g. `1709296200.25`;
+    /// digits past milliseconds are truncated.
+    UnixSeconds,
+    /// Whole milliseconds since the epoch.
+    UnixMillis,
+    /// A `strftime`-style pattern (see [`chrono::format::strftime`]). Without an
+    /// offset specifier (`%z`) the text is read as UTC; a pattern with only date
+    /// fields reads as midnight UTC.
+    Pattern(String),
+}
+
+impl TimestampFormat {
+    /// A `strftime`-style [`TimestampFormat::Pattern`].
+    pub fn pattern(pattern: impl Into<String>) -> Self {
+        Self::Pattern(pattern.into())
+    }
+
+    /// Parse `text` into milliseconds since the epoch.
+    ///
+    /// # Errors
+    /// Returns an error if `text` does not match the format or lies before the epoch.
+    pub fn parse(&self, text: &str) -> Result<TimestampMs> {
+        let text = text.trim();
+        let millis = match self {
+            Self::Rfc3339 => DateTime::parse_from_rfc3339(text)?.timestamp_millis(),
+            Self::Rfc2822 => DateTime::parse_from_rfc2822(text)?.timestamp_millis(),
+            Self::UnixSeconds => {
+                let secs = Duration::try_from_secs_f64(text.parse()?)?;
+                return Ok(u64::try_from(secs.as_millis())?);
+            }
+            Self::UnixMillis => return Ok(text.parse()?),
+            Self::Pattern(pattern) => parse_pattern(text, pattern)?,
+        };
+        u64::try_from(millis).map_err(|_| anyhow!("{text} is before the Unix epoch"))
+    }
+}
+
+impl Display for TimestampFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rfc3339 => f.write_str("RFC 3339"),
+            Self::Rfc2822 => f.write_str("RFC 2822"),
+            Self::UnixSeconds => f.write_str("Unix seconds"),
+            Self::UnixMillis => f.write_str("Unix milliseconds"),
+            Self::Pattern(pattern) => write!(f, "{pattern:?}"),
+        }
+    }
+}
+
+/// Parse `text` with a `strftime` pattern: offset-aware if the pattern has an offset,
+/// otherwise as a UTC date-time, or as a UTC date at midnight.
+fn parse_pattern(text: &str, pattern: &str) -> Result<i64> {
+    if let Ok(dt) = DateTime::parse_from_str(text, pattern) {
+        return Ok(dt.timestamp_millis());
+    }
+    match NaiveDateTime::parse_from_str(text, pattern) {
+        Ok(dt) => Ok(dt.and_utc().timestamp_millis()),
+        Err(e) => NaiveDate::parse_from_str(text, pattern)
+            .map(|d| {
+                let midnight = d.and_hms_opt(0, 0, 0).unwrap_or_default();
+                midnight.and_utc().timestamp_millis()
+            })
+            .map_err(|_| e.into()),
+    }
+}
+
+/// Parse `text` written in `format` into milliseconds since the epoch.
+///
+/// # Errors
+/// Returns an error if `text` does not match `format` or lies before the epoch.
+pub fn parse_timestamp(text: &str, format: &TimestampFormat) -> Result<TimestampMs> {
+    format
+        .parse(text)
+        .with_context(|| format!("parse {text:?} as {format}"))
+}
+
+/// A calendar unit to truncate or bucket timestamps by, in UTC.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TimeUnit {
+    /// Whole seconds.
+    Second,
+    /// Whole minutes.
+    Minute,
+    /// Whole hours.
+    Hour,
+    /// Calendar days, from midnight.
+    Day,
+    /// ISO weeks, from Monday midnight.
+    Week,
+    /// Calendar months, from the first day.
+    Month,
+    /// Calendar years, from January 1st.
+    Year,
+}
+
+/// Convert to a UTC date-time; timestamps past chrono's range clamp to its maximum.
+fn to_datetime(ts: TimestampMs) -> DateTime<Utc> {
+    i64::try_from(ts)
+        .ok()
+        .and_then(DateTime::from_timestamp_millis)
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
+/// Whole days between the Monday starting the ISO week of `ts` and `ts`'s day.
+fn days_since_monday(ts: TimestampMs) -> u64 {
+    u64::from(to_datetime(ts).weekday().num_days_from_monday())
+}
+
+/// Milliseconds since the epoch of midnight UTC on `date`, clamped at the epoch.
+fn midnight_ms(date: NaiveDate) -> TimestampMs {
+    let millis = date
+        .and_hms_opt(0, 0, 0)
+        .unwrap_or_default()
+        .and_utc()
+        .timestamp_millis();
+    u64::try_from(millis).unwrap_or(0)
+}
+
+impl TimeUnit {
+    /// The start of the unit containing `ts`. Truncating to a week in the first days
+    /// of 1970 clamps to the epoch rather than reaching back to Monday 1969-12-29.
+    #[must_use]
+    pub fn truncate(self, ts: TimestampMs) -> TimestampMs {
+        match self {
+            Self::Second => ts - ts % SECOND_MS,
+            Self::Minute => ts - ts % MINUTE_MS,
+            Self::Hour => ts - ts % HOUR_MS,
+            Self::Day => ts - ts % DAY_MS,
+            Self::Week => (ts - ts % DAY_MS).saturating_sub(days_since_monday(ts) * DAY_MS),
+            Self::Month => {
+                let dt = to_datetime(ts);
+                NaiveDate::from_ymd_opt(dt.year(), dt.month(), 1).map_or(ts, midnight_ms)
+            }
+            Self::Year => {
+                let dt = to_datetime(ts);
+                NaiveDate::from_ymd_opt(dt.year(), 1, 1).map_or(ts, midnight_ms)
+            }
+        }
+    }
+
+    /// The window `[start, end)` of the unit containing `ts`.
+    #[must_use]
+    pub fn window(self, ts: TimestampMs) -> Window {
+        let start = self.truncate(ts);
+        let end = match self {
+            Self::Second => start + SECOND_MS,
+            Self::Minute => start + MINUTE_MS,
+            Self::Hour => start + HOUR_MS,
+            Self::Day => start + DAY_MS,
+            Self::Week => (ts - ts % DAY_MS) + (7 - days_since_monday(ts)) * DAY_MS,
+            Self::Month => {
+                let dt = to_datetime(ts);
+                let (year, month) = if dt.month() == 12 {
+                    (dt.year() + 1, 1)
+                } else {
+                    (dt.year(), dt.month() + 1)
+                };
+                NaiveDate::from_ymd_opt(year, month, 1).map_or(TimestampMs::MAX, midnight_ms)
+            }
+            Self::Year => NaiveDate::from_ymd_opt(to_datetime(ts).year() + 1, 1, 1)
+                .map_or(TimestampMs::MAX, midnight_ms),
+        };
+        Window::new(start, end)
+    }
+}
+
+/// The start of the `unit` containing `ts` (see [`TimeUnit::truncate`]).
+#[must_use]
+pub fn truncate_timestamp(ts: TimestampMs, unit: TimeUnit) -> TimestampMs {
+    unit.truncate(ts)
+}
+
+/// The buckets of [`key_by_time_bucket`](PCollection::key_by_time_bucket): fixed
+/// durations aligned to the epoch, or calendar units.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeBucket {
+    /// Buckets of a fixed length (minimum 1 ms), as in
+    /// [`key_by_window`](PCollection::key_by_window).
+    Fixed(Duration),
+    /// Calendar buckets, which may vary in length.
+    Calendar(TimeUnit),
+}
+
+impl TimeBucket {
+    /// The bucket containing `ts`.
+    #[must_use]
+    pub fn window(self, ts: TimestampMs) -> Window {
+        match self {
+            Self::Fixed(size) => {
+                let size_ms = u64::try_from(size.as_millis()).unwrap_or(u64::MAX).max(1);
+                Window::tumble(ts, size_ms, 0)
+            }
+            Self::Calendar(unit) => unit.window(ts),
+        }
+    }
+}
+
+impl From<Duration> for TimeBucket {
+    fn from(size: Duration) -> Self {
+        Self::Fixed(size)
+    }
+}
+
+impl From<TimeUnit> for TimeBucket {
+    fn from(unit: TimeUnit) -> Self {
+        Self::Calendar(unit)
+    }
+}
+
+impl<T: Element> PCollection<T> {
+    /// Stamp each element with the timestamp parsed from the text `field` returns.
+    ///
+    /// Returns `(stamped, unparsable)`: elements whose text does not parse go to the
+    /// second collection as [`DeadLetter`]s naming the text and format, as with
+    /// [`map_catching`](PCollection::map_catching).
+    #[must_use]
+    pub fn parse_timestamp<F>(
+        self,
+        format: TimestampFormat,
+        field: F,
+    ) -> (PCollection<Timestamped<T>>, PCollection<DeadLetter<T>>)
+    where
+        F: 'static + Send + Sync + Fn(&T) -> &str,
+    {
+        self.map_catching(move |t: &T| {
+            parse_timestamp(field(t), &format)
+                .map(|ts| Timestamped::new(ts, t.clone()))
+                .map_err(|e| format!("{e:#}"))
+        })
+    }
+}
+
+impl<T: Element> PCollection<Timestamped<T>> {
+    /// Round each timestamp down to the start of its `unit`.
+    #[must_use]
+    pub fn truncate_to(self, unit: TimeUnit) -> Self {
+        self.map(move |ev: &Timestamped<T>| {
+            Timestamped::new(unit.truncate(ev.ts), ev.value.clone())
+        })
+    }
+
+    /// Key each element by the [`Window`] of the bucket its timestamp falls in, for
+    /// per-bucket aggregation. Accepts a [`Duration`] or a [`TimeUnit`].
+    #[must_use]
+    pub fn key_by_time_bucket(self, bucket: impl Into<TimeBucket>) -> PCollection<(Window, T)> {
+        let bucket = bucket.into();
+        self.map(move |ev: &Timestamped<T>| (bucket.window(ev.ts), ev.value.clone()))
+    }
+}
@@ -0,0 +1,236 @@
+//! Per-key time-series helpers: lag, differences, rates, and running totals.
+//!
+//! These operate on keyed event streams `PCollection<(K, Timestamped<V>)>`. Each key's
+//! events are gathered (a `group_by_key` barrier) and visited in timestamp order, so
+//! the usual group + sort + scan code reduces to a single call.
+//!
+//! ## Available operations
+//! - [`PCollection::map_values_with_previous`] - Map each event together with the
+//!   previous event of the same key
+//! - [`PCollection::deltas`] - Difference from the previous value, as `f64`
+//! - [`PCollection::rates_per_second`] - Change per second since the previous value
+//! - [`PCollection::cumulative_sum`] - Running total per key
+//! - [`PCollection::rolling`] - Any [`RollingFn`] per key, such as an [`Ewma`] or a
+//!   [`RollingMean`](crate::combiners::RollingMean)
+//!
+//! Outputs keep the key and the timestamp of the event they were computed for. Events
+//! of one key with equal timestamps are visited in an arbitrary relative order.
+//!
+//! ## Example
+//! ```
+//! use ironbeam::*;
+//! use anyhow::Result;
+//! # fn main() -> Result<()> {
+//! let p = Pipeline::default();
+//! // (meter, cumulative kWh reading)
+//! let readings = from_vec(&p, vec![
+//!     ("m1".to_string(), Timestamped::new(0, 100u32)),
+//!     ("m1".to_string(), Timestamped::new(2_000, 106)),
+//!     ("m1".to_string(), Timestamped::new(1_000, 101)),
+//! ]);
+//!
+//! let mut usage: Vec<(u64, f64)> = readings
+//!     .deltas()
+//!     .map(|(_, d)| (d.ts, d.value))
+//!     .collect_seq()?;
+//! usage.sort_by_key(|&(ts, _)| ts);
+//! assert_eq!(usage, vec![(1_000, 1.0), (2_000, 5.0)]);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::combiners::{Ewma, RollingFn};
+use crate::{Element, PCollection, Timestamped};
+use std::hash::Hash;
+
+/// Sort one key's events by timestamp.
+fn in_time_order<V: Clone>(events: &[Timestamped<V>]) -> Vec<Timestamped<V>> {
+    let mut events = events.to_vec();
+    events.sort_by_key(|e| e.ts);
+    events
+}
+
+impl<K: Element + Eq + Hash, V: Element> PCollection<(K, Timestamped<V>)> {
+    /// Map each event together with the previous event of the same key.
+    ///
+    /// `f(previous, current)` is called once per event in timestamp order; `previous`
+    /// is `None` for the first event of each key. The result is emitted as
+    /// `(K, Timestamped<O>)` at the current event's timestamp.
+    ///
+    /// ### Example
+    /// ```
+    /// use ironbeam::*;
+    /// use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let states = from_vec(&p, vec![
+    ///     (1u32, Timestamped::new(10, "up".to_string())),
+    ///     (1, Timestamped::new(20, "up".to_string())),
+    ///     (1, Timestamped::new(30, "down".to_string())),
+    /// ]);
+    /// // Flag state changes.
+    /// let changes = states
+    ///     .map_values_with_previous(|prev, cur| prev.is_some_and(|p| p.value != cur.value))
+    ///     .filter(|(_, changed)| changed.value)
+    ///     .collect_seq()?;
+    /// assert_eq!(changes.len(), 1);
+    /// assert_eq!(changes[0].1.ts, 30);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn map_values_with_previous<O, F>(self, f: F) -> PCollection<(K, Timestamped<O>)>
+    where
+        O: Element,
+        F: 'static + Send + Sync + Fn(Option<&Timestamped<V>>, &Timestamped<V>) -> O,
+    {
+        self.group_by_key()
+            .flat_map(move |kv: &(K, Vec<Timestamped<V>>)| {
+                let (key, events) = kv;
+                let events = in_time_order(events);
+                let mut prev = None;
+                let mut out = Vec::with_capacity(events.len());
+                for cur in &events {
+                    out.push((key.clone(), Timestamped::new(cur.ts, f(prev, cur))));
+                    prev = Some(cur);
+                }
+                out
+            })
+    }
+
+    /// Apply a rolling statistic to each key's values, in timestamp order.
+    ///
+    /// Emits `(K, Timestamped<O>)` for every event, holding the statistic over that
+    /// key's values up to and including the event. See [`combiners`](crate::combiners)
+    /// for [`Ewma`], [`RollingMean`](crate::combiners::RollingMean), and
+    /// [`RollingMax`](crate::combiners::RollingMax).
+    ///
+    /// ### Example
+    /// ```
+    /// use ironbeam::*;
+    /// use ironbeam::combiners::RollingMax;
+    /// use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let latency = from_vec(&p, vec![
+    ///     (1u8, Timestamped::new(1, 120u32)),
+    ///     (1, Timestamped::new(2, 80)),
+    ///     (1, Timestamped::new(3, 95)),
+    ///     (1, Timestamped::new(4, 70)),
+    /// ]);
+    ///
+    /// let mut peaks = latency.rolling(RollingMax::new(2)).collect_seq()?;
+    /// peaks.sort_by_key(|(_, t)| t.ts);
+    /// let values: Vec<u32> = peaks.iter().map(|(_, t)| t.value).collect();
+    /// assert_eq!(values, vec![120, 120, 95, 95]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn rolling<R>(self, stat: R) -> PCollection<(K, Timestamped<R::Output>)>
+    where
+        R: RollingFn<V>,
+        R::Output: Element,
+    {
+        self.group_by_key()
+            .flat_map(move |kv: &(K, Vec<Timestamped<V>>)| {
+                let (key, events) = kv;
+                let mut state = stat.create();
+                in_time_order(events)
+                    .into_iter()
+                    .map(|e| {
+                        let out = stat.push(&mut state, e.value);
+                        (key.clone(), Timestamped::new(e.ts, out))
+                    })
+                    .collect::<Vec<_>>()
+            })
+    }
+}
+
+impl<K, V> PCollection<(K, Timestamped<V>)>
+where
+    K: Element + Eq + Hash,
+    V: Element + Into<f64>,
+{
+    /// Difference between each value and the previous value of the same key.
+    ///
+    /// The first event of each key has no predecessor and produces no output, so a key
+    /// with `n` events yields `n - 1` deltas. Values are converted to `f64`, so
+    /// decreasing unsigned values give negative deltas rather than overflowing.
+    #[must_use]
+    pub fn deltas(self) -> PCollection<(K, Timestamped<f64>)> {
+        self.map_values_with_previous(|prev, cur| {
+            prev.map(|p| cur.value.clone().into() - p.value.clone().into())
+        })
+        .filter_map(|(k, d): &(K, Timestamped<Option<f64>>)| {
+            d.value.map(|v| (k.clone(), Timestamped::new(d.ts, v)))
+        })
+    }
+
+    /// Rate of change per second since the previous value of the same key.
+    ///
+    /// Computed as `delta / elapsed_seconds`. Like [`deltas`](Self::deltas), the first
+    /// event of each key produces no output; events at the same timestamp as their
+    /// predecessor are skipped too, since no time has passed.
+    ///
+    /// ### Example
+    /// ```
+    /// use ironbeam::*;
+    /// use anyhow::Result;
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// // Bytes sent, sampled every 500ms.
+    /// let counters = from_vec(&p, vec![
+    ///     ("eth0".to_string(), Timestamped::new(0, 0u32)),
+    ///     ("eth0".to_string(), Timestamped::new(500, 4_000)),
+    /// ]);
+    /// let rates = counters.rates_per_second().collect_seq()?;
+    /// assert_eq!(rates[0].1.value, 8_000.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn rates_per_second(self) -> PCollection<(K, Timestamped<f64>)> {
+        self.map_values_with_previous(|prev, cur| {
+            prev.filter(|p| p.ts < cur.ts).map(|p| {
+                #[allow(clippy::cast_precision_loss)]
+                let elapsed_secs = (cur.ts - p.ts) as f64 / 1_000.0;
+                (cur.value.clone().into() - p.value.clone().into()) / elapsed_secs
+            })
+        })
+        .filter_map(|(k, r): &(K, Timestamped<Option<f64>>)| {
+            r.value.map(|v| (k.clone(), Timestamped::new(r.ts, v)))
+        })
+    }
+
+    /// Exponentially weighted moving average of each key's values, in timestamp order.
+    ///
+    /// Shorthand for [`rolling(Ewma::new(alpha))`](PCollection::rolling).
+    ///
+    /// # Panics
+    /// Panics if `alpha` is not in `(0, 1]`.
+    #[must_use]
+    pub fn ewma(self, alpha: f64) -> PCollection<(K, Timestamped<f64>)> {
+        self.rolling(Ewma::new(alpha))
+    }
+
+    /// Running total of each key's values, in timestamp order.
+    ///
+    /// Emits one `(K, Timestamped<f64>)` per event, holding the sum of that key's values
+    /// up to and including the event.
+    #[must_use]
+    pub fn cumulative_sum(self) -> PCollection<(K, Timestamped<f64>)> {
+        self.group_by_key()
+            .flat_map(|kv: &(K, Vec<Timestamped<V>>)| {
+                let (key, events) = kv;
+                let mut total = 0.0;
+                in_time_order(events)
+                    .into_iter()
+                    .map(|e| {
+                        total += e.value.into();
+                        (key.clone(), Timestamped::new(e.ts, total))
+                    })
+                    .collect::<Vec<_>>()
+            })
+    }
+}
@@ -8,8 +8,13 @@
 //!
 //! ## Available operations
 //! - [`PCollection::attach_timestamps`](PCollection::attach_timestamps) - Attach event timestamps using a function
+//! - [`PCollection::attach_processing_time`](PCollection::attach_processing_time) - Attach processing time from the pipeline clock
 //! - [`PCollection::to_timestamped`](crate::PCollection::to_timestamped) - Normalize `(timestamp, value)` pairs into `Timestamped<T>`
 //! - [`PCollection::reify_timestamps`](crate::PCollection::reify_timestamps) - Make timestamps explicit as `(TimestampMs, T)` tuples
+//! - [`EventTime`] - How the `read_*_timestamped` sources stamp records as they read them
+//!   ([`read_jsonl_timestamped`](crate::read_jsonl_timestamped),
+//!   [`read_csv_timestamped`](crate::read_csv_timestamped),
+//!   [`read_parquet_timestamped`](crate::read_parquet_timestamped))
 //!
 //! ### What this is (and isn't)
 //! - ✅ Attaches/normalizes event timestamps, preserving data and order within a partition
@@ -47,6 +52,96 @@
 //! ```
 
 use crate::{Element, PCollection, TimestampMs, Timestamped};
+use anyhow::{Context, Result, anyhow};
+use serde::Serialize;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Where a `read_*_timestamped` source takes each record's event time from.
+///
+/// The sources stamp records while building the collection, producing
+/// `PCollection<Timestamped<T>>` without a separate
+/// [`attach_timestamps`](PCollection::attach_timestamps) step.
+///
+/// ### Example
+/// ```no_run
+/// use ironbeam::*;
+/// use serde::{Deserialize, Serialize};
+/// use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// #[derive(Clone, Serialize, Deserialize)]
+/// struct Click { ts_ms: u64, user: String }
+///
+/// let p = Pipeline::default();
+/// let clicks = read_jsonl_timestamped::<Click>(&p, "clicks.jsonl", &EventTime::field("ts_ms"))?;
+/// let same = read_jsonl_timestamped(&p, "clicks.jsonl", &EventTime::extract(|c: &Click| c.ts_ms))?;
+/// # Ok(()) }
+/// ```
+pub enum EventTime<T> {
+    /// A top-level field of the record (by its serialized name) holding
+    /// milliseconds since the epoch, as an unsigned integer or a string of digits.
+    Field(String),
+    /// A function computing the timestamp from the record.
+    Extract(Arc<dyn Fn(&T) -> TimestampMs + Send + Sync>),
+}
+
+impl<T> Clone for EventTime<T> {
+    fn clone(&self) -> Self {
+        match self {
+            Self::Field(name) => Self::Field(name.clone()),
+            Self::Extract(f) => Self::Extract(Arc::clone(f)),
+        }
+    }
+}
+
+impl<T: Serialize> EventTime<T> {
+    /// Read the event time from the field `name`.
+    pub fn field(name: impl Into<String>) -> Self {
+        Self::Field(name.into())
+    }
+
+    /// Compute the event time with `f`.
+    pub fn extract(f: impl Fn(&T) -> TimestampMs + Send + Sync + 'static) -> Self {
+        Self::Extract(Arc::new(f))
+    }
+
+    /// Stamp `rows` in order; errors name the 1-based record number.
+    pub(crate) fn stamp(&self, rows: Vec<T>) -> Result<Vec<Timestamped<T>>> {
+        match self {
+            Self::Extract(f) => Ok(rows
+                .into_iter()
+                .map(|t| Timestamped::new(f(&t), t))
+                .collect()),
+            Self::Field(name) => rows
+                .into_iter()
+                .enumerate()
+                .map(|(i, t)| {
+                    let ts = field_timestamp(&t, name)
+                        .with_context(|| format!("event time of record #{}", i + 1))?;
+                    Ok(Timestamped::new(ts, t))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The millisecond timestamp in field `name` of `t`'s serialized form.
+fn field_timestamp<T: Serialize>(t: &T, name: &str) -> Result<TimestampMs> {
+    let Value::Object(mut record) = serde_json::to_value(t)? else {
+        return Err(anyhow!(
+            "record is not a struct or map, so it has no field `{name}`"
+        ));
+    };
+    let value = record
+        .remove(name)
+        .ok_or_else(|| anyhow!("record has no field `{name}`"))?;
+    let ts = match &value {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    };
+    ts.ok_or_else(|| anyhow!("field `{name}` is {value}, not a millisecond timestamp"))
+}
 
 impl<T: Element> PCollection<T> {
     /// Attach event timestamps using a user-provided function.
@@ -87,6 +182,28 @@ impl<T: Element> PCollection<T> {
     {
         self.map(move |t| Timestamped::new(ts_fn(t), t.clone()))
     }
+
+    /// Stamp each element with the processing time at which it is seen.
+    ///
+    /// The time comes from the pipeline's [`Clock`](crate::Clock) (the system clock
+    /// unless replaced with [`Pipeline::set_clock`](crate::Pipeline::set_clock)), which
+    /// is captured here, so install a custom clock before calling this.
+    ///
+    /// ### Returns
+    /// A timestamped stream: `PCollection<Timestamped<T>>`.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// use ironbeam::*;
+    ///
+    /// let p = Pipeline::default();
+    /// let stamped = from_vec(&p, vec!["a".to_string(), "b".to_string()]).attach_processing_time();
+    /// // stamped: PCollection<Timestamped<String>>
+    /// ```
+    pub fn attach_processing_time(self) -> PCollection<Timestamped<T>> {
+        let clock = self.pipeline.clock();
+        self.map(move |t| Timestamped::new(clock.now_ms(), t.clone()))
+    }
 }
 
 impl<T: Element> PCollection<(TimestampMs, T)> {
@@ -6,6 +6,7 @@
 //! ## Available operations
 //! - [`PCollection::top_k_globally`] - Select the top-K largest elements (global)
 //! - [`PCollection::top_k_per_key`](crate::PCollection::top_k_per_key) - Select the top-K largest values per key
+//! - [`PCollection::top_k_per_key_by`](crate::PCollection::top_k_per_key_by) - Select the top-K values per key ranked by a derived key
 //! - [`PCollection::bottom_k_globally`] - Select the bottom-K smallest elements (global)
 //! - [`PCollection::bottom_k_per_key`](crate::PCollection::bottom_k_per_key) - Select the bottom-K smallest values per key
 //!
@@ -35,7 +36,7 @@
 //! # }
 //! ```
 
-use crate::combiners::{BottomK, TopK};
+use crate::combiners::{BottomK, TopK, TopKBy};
 use crate::{Element, PCollection};
 use std::cmp::Ord;
 use std::hash::Hash;
@@ -206,3 +207,46 @@ where
         self.combine_values(BottomK::new(k))
     }
 }
+
+impl<K, V> PCollection<(K, V)>
+where
+    K: Element + Eq + Hash,
+    V: Element,
+{
+    /// Select the top-K values per key, ranked by a key derived from each value.
+    ///
+    /// Unlike [`top_k_per_key`](PCollection::top_k_per_key), `V` need not be
+    /// `Ord`: `key_fn` extracts the ranking key and the whole value is kept.
+    /// Each vector holds at most `k` values sorted by derived key, descending.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ironbeam::*;
+    /// # use anyhow::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let events = from_vec(&p, vec![
+    ///     ("u1".to_string(), ("login".to_string(), 10u64)),
+    ///     ("u1".to_string(), ("click".to_string(), 30)),
+    ///     ("u1".to_string(), ("view".to_string(), 20)),
+    /// ]);
+    ///
+    /// // Latest two events per user, by timestamp.
+    /// let latest = events.top_k_per_key_by(2, |(_, ts)| *ts).collect_seq()?;
+    /// assert_eq!(latest[0].1[0].0, "click");
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # See Also
+    /// - [`TopKBy`] - The underlying combiner implementation
+    #[must_use]
+    pub fn top_k_per_key_by<O, F>(self, k: usize, key_fn: F) -> PCollection<(K, Vec<V>)>
+    where
+        O: Ord,
+        F: Fn(&V) -> O + Send + Sync + 'static,
+    {
+        self.combine_values(TopKBy::new(k, key_fn))
+    }
+}
@@ -8,6 +8,12 @@
 //! ## Available operations
 //! - [`PCollection::try_map`](PCollection::try_map) - Fallible 1->1 transform
 //! - [`PCollection::try_flat_map`](PCollection::try_flat_map) - Fallible 1->N transform
+//! - [`PCollection::try_map_with_errors`](PCollection::try_map_with_errors) - Fallible 1->1
+//!   transform that routes failures to a separate `(input, error)` collection
+//! - [`PCollection::try_flat_map_with_errors`](PCollection::try_flat_map_with_errors) - Same,
+//!   for 1->N transforms
+//! - [`PCollection::try_map_with_retry`](PCollection::try_map_with_retry) - Fallible 1->1
+//!   transform retried per element according to a [`RetryPolicy`]
 //! - [`PCollection::collect_fail_fast`](crate::PCollection::collect_fail_fast) - Fail-fast terminal
 //!
 //! ## When to use
@@ -15,10 +21,16 @@
 //!   want to keep going until the terminal sink is reached.
 //! - You want easy error surfacing during tests or interactive runs.
 //!
+//! - You want to keep the good records flowing and park the bad ones (with their typed
+//!   error) in a dead-letter collection: use the `*_with_errors` variants.
+//!
 //! ## Pattern
 //! 1) turn `PCollection<T>` into `PCollection<Result<O,E>>` (or `Result<Vec<O>,E>`)
 //! 2) at the end, call `collect_fail_fast()` to bail out on the first error.
 //!
+//! Or split up front with `let (ok, errors) = data.try_map_with_errors(f);` and write
+//! `errors` to its own sink.
+//!
 //! ```no_run
 //! use ironbeam::*;
 //! use anyhow::Result;
@@ -38,6 +50,53 @@
 use crate::{Element, PCollection};
 use anyhow::{Result, anyhow};
 use std::fmt::Display;
+use std::time::Duration;
+
+/// Per-element retry policy for [`PCollection::try_map_with_retry`].
+///
+/// The function is called up to `max_attempts` times. After the `n`-th failed attempt
+/// (1-based) the worker sleeps `backoff * 2^(n-1)` before trying again, capped at
+/// [`Self::MAX_BACKOFF`]. A zero `backoff` retries immediately.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first call. `0` is treated as `1`.
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled for each subsequent retry.
+    pub backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Upper bound on any single backoff sleep.
+    pub const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+    /// Policy with `max_attempts` attempts and exponential backoff starting at `backoff`.
+    #[must_use]
+    pub const fn new(max_attempts: u32, backoff: Duration) -> Self {
+        Self {
+            max_attempts,
+            backoff,
+        }
+    }
+
+    /// Delay to wait after the given failed attempt (1-based).
+    #[must_use]
+    pub fn delay_after(&self, attempt: u32) -> Duration {
+        let factor = 1u32
+            .checked_shl(attempt.saturating_sub(1))
+            .unwrap_or(u32::MAX);
+        self.backoff
+            .checked_mul(factor)
+            .unwrap_or(Self::MAX_BACKOFF)
+            .min(Self::MAX_BACKOFF)
+    }
+}
+
+impl Default for RetryPolicy {
+    /// Three attempts, starting at 100 ms of backoff.
+    fn default() -> Self {
+        Self::new(3, Duration::from_millis(100))
+    }
+}
 
 impl<T: Element> PCollection<T> {
     /// Fallible 1->1 transform: `T -> Result<O, E>`.
@@ -104,6 +163,130 @@ impl<T: Element> PCollection<T> {
     {
         self.map(move |t| f(t))
     }
+
+    /// Fallible 1->1 transform that routes failures to a dead-letter collection.
+    ///
+    /// Each `Ok(o)` flows into the first returned collection. Each `Err(e)` is paired
+    /// with the input element that produced it and flows into the second collection as
+    /// `(T, E)`, so the error keeps its type (unlike
+    /// [`map_catching`](PCollection::map_catching), which renders it to a string).
+    ///
+    /// ### Example
+    /// ```no_run
+    /// use ironbeam::*;
+    /// # use anyhow::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let raw = from_vec(&p, vec!["10".to_string(), "oops".to_string(), "42".to_string()]);
+    ///
+    /// let (ok, errors) = raw.try_map_with_errors::<u64, String, _>(|s| {
+    ///     s.parse::<u64>().map_err(|e| e.to_string())
+    /// });
+    /// assert_eq!(ok.collect_seq()?, vec![10, 42]);
+    /// assert_eq!(errors.collect_seq()?[0].0, "oops");
+    /// # Ok(()) }
+    /// ```
+    #[must_use]
+    pub fn try_map_with_errors<O, E, F>(self, f: F) -> (PCollection<O>, PCollection<(T, E)>)
+    where
+        O: Element,
+        E: Element,
+        F: 'static + Send + Sync + Fn(&T) -> Result<O, E>,
+    {
+        self.flat_map_multi(move |t, out| match f(t) {
+            Ok(o) => out.emit(o),
+            Err(e) => out.emit_side((t.clone(), e)),
+        })
+    }
+
+    /// Fallible 1->N transform that routes failures to a dead-letter collection.
+    ///
+    /// Like [`PCollection<T>::try_map_with_errors`], but an `Ok(vec)` expands into every
+    /// element of `vec`. A single `Err(e)` produces one `(T, E)` dead-letter row.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// use ironbeam::*;
+    /// # use anyhow::Result;
+    ///
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let raw = from_vec(&p, vec!["1,2".to_string(), "bad".to_string()]);
+    ///
+    /// let (nums, errors) = raw.try_flat_map_with_errors::<u32, String, _>(|s| {
+    ///     s.split(',')
+    ///         .map(|tok| tok.parse::<u32>().map_err(|e| e.to_string()))
+    ///         .collect()
+    /// });
+    /// assert_eq!(nums.collect_seq()?, vec![1, 2]);
+    /// assert_eq!(errors.collect_seq()?.len(), 1);
+    /// # Ok(()) }
+    /// ```
+    #[must_use]
+    pub fn try_flat_map_with_errors<O, E, F>(self, f: F) -> (PCollection<O>, PCollection<(T, E)>)
+    where
+        O: Element,
+        E: Element,
+        F: 'static + Send + Sync + Fn(&T) -> Result<Vec<O>, E>,
+    {
+        self.flat_map_multi(move |t, out| match f(t) {
+            Ok(outs) => outs.into_iter().for_each(|o| out.emit(o)),
+            Err(e) => out.emit_side((t.clone(), e)),
+        })
+    }
+}
+
+impl<T: Element> PCollection<T> {
+    /// Fallible 1->1 transform with per-element retries: `T -> Result<O, E>`.
+    ///
+    /// Calls `f` until it returns `Ok` or `policy.max_attempts` attempts have been
+    /// made, sleeping with exponential backoff between attempts (see [`RetryPolicy`]).
+    /// Only the final outcome is emitted: an element that eventually succeeds yields
+    /// `Ok`, one that exhausts its attempts yields the **last** `Err`.
+    ///
+    /// Retries run inside the worker processing the element's partition, so backoff
+    /// sleeps delay only that partition.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// use ironbeam::*;
+    /// use std::time::Duration;
+    ///
+    /// let p = Pipeline::default();
+    /// let ids = from_vec(&p, vec![1u32, 2, 3]);
+    ///
+    /// let fetched = ids.try_map_with_retry::<String, String, _>(
+    ///     |id| Ok(format!("record-{id}")), // e.g. a flaky HTTP call
+    ///     RetryPolicy { max_attempts: 5, backoff: Duration::from_millis(50) },
+    /// );
+    /// // fetched: PCollection<Result<String, String>>
+    /// ```
+    #[must_use]
+    pub fn try_map_with_retry<O, E, F>(self, f: F, policy: RetryPolicy) -> PCollection<Result<O, E>>
+    where
+        O: Element,
+        E: Element + Display,
+        F: 'static + Send + Sync + Fn(&T) -> Result<O, E>,
+    {
+        let max_attempts = policy.max_attempts.max(1);
+        self.map(move |t| {
+            let mut attempt = 1;
+            loop {
+                match f(t) {
+                    Ok(o) => return Ok(o),
+                    Err(e) if attempt >= max_attempts => return Err(e),
+                    Err(_) => {
+                        let delay = policy.delay_after(attempt);
+                        if !delay.is_zero() {
+                            std::thread::sleep(delay);
+                        }
+                        attempt += 1;
+                    }
+                }
+            }
+        })
+    }
 }
 
 // Fail-fast terminal (keeps errors ergonomic)
@@ -6,7 +6,10 @@
 use crate::collection::{Element, PCollection};
 use crate::node::DynOp;
 use crate::type_token::Partition;
-use crate::validation::{ErrorCollector, Validate, ValidationError, ValidationMode};
+use crate::validation::{
+    ErrorCollector, Schema, Validate, ValidationError, ValidationMode, Violation,
+};
+use serde::Serialize;
 use std::marker::PhantomData;
 use std::sync::{Arc, Mutex};
 
@@ -144,6 +147,76 @@ impl<T: Element + Validate> PCollection<T> {
     }
 }
 
+impl<T: Element + Serialize> PCollection<T> {
+    /// Check every element against `schema`, splitting the collection into the records
+    /// that pass and a side output of [`Violation`]s.
+    ///
+    /// Elements are checked by their JSON form (see [`Schema::check`]), so this works
+    /// for [`Row`](crate::Row) collections and for any `#[derive(Serialize)]` struct. A
+    /// record with several failed rules produces one violation per rule and is left out
+    /// of the first output.
+    ///
+    /// With the `metrics` feature, the run also records counters in the pipeline's
+    /// user metrics: `schema.records`, `schema.invalid_records`, `schema.violations`,
+    /// and `schema.violations.<field>` per field path. Collecting both outputs separately
+    /// checks the input twice; [`Pipeline::run_all`](crate::Pipeline::run_all) checks it once.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ironbeam::*;
+    /// use ironbeam::validation::{FieldRule, Schema};
+    /// use serde::{Deserialize, Serialize};
+    /// use anyhow::Result;
+    ///
+    /// #[derive(Clone, Serialize, Deserialize)]
+    /// struct User { id: u32, email: String, age: i32 }
+    ///
+    /// # fn main() -> Result<()> {
+    /// let p = Pipeline::default();
+    /// let users = from_vec(&p, vec![
+    ///     User { id: 1, email: "alice@example.com".into(), age: 30 },
+    ///     User { id: 2, email: "invalid".into(), age: -5 },
+    /// ]);
+    ///
+    /// let schema = Schema::new()
+    ///     .field(FieldRule::new("email").pattern("@"))
+    ///     .field(FieldRule::new("age").range(0.0, 150.0));
+    /// let (valid, violations) = users.validate_schema(&schema);
+    /// assert_eq!(valid.collect_seq()?.len(), 1);
+    /// assert_eq!(violations.collect_seq()?.len(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn validate_schema(self, schema: &Schema) -> (Self, PCollection<Violation>) {
+        let schema = Arc::new(schema.clone());
+
+        #[cfg(feature = "metrics")]
+        let checked = self.map_with_metrics(move |t: &T, m| {
+            let violations = schema.check(t);
+            m.counter("schema.records").inc();
+            if !violations.is_empty() {
+                m.counter("schema.invalid_records").inc();
+                m.counter("schema.violations")
+                    .inc_by(violations.len() as u64);
+            }
+            for v in &violations {
+                m.counter(&format!("schema.violations.{}", v.field)).inc();
+            }
+            (t.clone(), violations)
+        });
+        #[cfg(not(feature = "metrics"))]
+        let checked = self.map(move |t: &T| (t.clone(), schema.check(t)));
+
+        let valid = checked.filter_map(|(t, violations): &(T, Vec<Violation>)| {
+            violations.is_empty().then(|| t.clone())
+        });
+        let violations =
+            checked.flat_map(|(_, violations): &(T, Vec<Violation>)| violations.clone());
+        (valid, violations)
+    }
+}
+
 /// Internal operator for validation.
 struct ValidateOp<T: Element + Validate> {
     mode: ValidationMode,
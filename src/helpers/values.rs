@@ -8,6 +8,9 @@
 //! ## Provided methods
 //! - [`crate::PCollection::map_values`] -- apply a function `&V -> O`, producing `(K, O)`
 //! - [`crate::PCollection::filter_values`] -- retain only entries where `pred(&V)` is true
+//! - [`crate::PCollection::filter_keys`] -- retain only entries where `pred(&K)` is true;
+//!   the planner may run it ahead of earlier key-preserving transforms such as
+//!   `map_values`, so expensive value work is skipped for rows that would be dropped
 //!
 //! ## Example
 //! ```no_run
@@ -33,9 +36,10 @@
 //! # Ok::<()>(())
 //! ```
 
-use crate::collection::{FilterValuesOp, MapValuesOp};
-use crate::node::{DynOp, Node};
+use crate::collection::{FilterValuesOp, KeyFilterOp, MapValuesOp};
+use crate::node::{DynOp, KeyPredicate, Node};
 use crate::{Element, PCollection};
+use std::any::Any;
 use std::hash::Hash;
 use std::marker::PhantomData;
 use std::sync::Arc;
@@ -116,4 +120,56 @@ impl<K: Element + Eq + Hash, V: Element> PCollection<(K, V)> {
             _t: PhantomData,
         }
     }
+
+    /// Filter elements based on their *key* component.
+    ///
+    /// Keeps only key–value pairs `(K, V)` where `pred(&K)` returns true.
+    ///
+    /// Because the predicate never looks at the value, the planner is free to move the
+    /// filter ahead of preceding key-preserving transforms in the same stage (such as
+    /// [`map_values`](Self::map_values)) when they are costlier than the filter. The
+    /// move is reported as
+    /// [`OptimizationDecision::HoistedKeyFilters`](crate::planner::OptimizationDecision::HoistedKeyFilters).
+    ///
+    /// ### Arguments
+    /// - `pred`: A predicate function evaluated for each key.
+    ///
+    /// ### Returns
+    /// A filtered `PCollection<(K, V)>`.
+    ///
+    /// ### Example
+    /// ```no_run
+    /// use ironbeam::*;
+    ///
+    /// let p = Pipeline::default();
+    /// let kv = from_vec(&p, vec![("x".to_string(), 3u32), ("y".to_string(), 8u32)]);
+    ///
+    /// let out = kv
+    ///     .map_values(|v| v * 10)
+    ///     .filter_keys(|k: &String| k == "y")
+    ///     .collect_seq()?;
+    /// assert_eq!(out, vec![("y".to_string(), 80u32)]);
+    /// # use anyhow::Ok; Ok::<()>(())
+    /// ```
+    #[must_use]
+    pub fn filter_keys<F>(self, pred: F) -> Self
+    where
+        F: 'static + Send + Sync + Fn(&K) -> bool,
+    {
+        let pred: KeyPredicate = Arc::new(move |k: &dyn Any| {
+            pred(
+                k.downcast_ref::<K>()
+                    .expect("filter_keys: unexpected key type"),
+            )
+        });
+        let op: Arc<dyn DynOp> = Arc::new(KeyFilterOp::<K, V>::new(pred));
+        let id = self.pipeline.insert_node(Node::Stateless(vec![op]));
+        self.pipeline.connect(self.id, id);
+        self.pipeline.set_coder::<(K, V)>(id);
+        Self {
+            pipeline: self.pipeline,
+            id,
+            _t: PhantomData,
+        }
+    }
 }
@@ -0,0 +1,140 @@
+//! Transforms that update user-defined metrics.
+//!
+//! [`PCollection::map_with_metrics`] and [`PCollection::filter_with_metrics`] behave like
+//! [`map`](PCollection::map) and [`filter`](PCollection::filter), but also hand their
+//! closure a [`MetricsContext`] for updating counters, gauges, and distributions. The
+//! values are folded into the pipeline's [`MetricsCollector`](crate::metrics::MetricsCollector)
+//! when the run ends; see [`crate::metrics`] for how they are aggregated.
+//!
+//! ```no_run
+//! use ironbeam::*;
+//! use ironbeam::metrics::MetricsCollector;
+//! # use anyhow::Result;
+//! # fn main() -> Result<()> {
+//! let p = Pipeline::default();
+//! p.set_metrics(MetricsCollector::new());
+//!
+//! let lengths = from_vec(&p, vec!["a".to_string(), String::new(), "abc".to_string()])
+//!     .map_with_metrics(|s, m| {
+//!         if s.is_empty() {
+//!             m.counter("empty").inc();
+//!         }
+//!         s.len()
+//!     });
+//! lengths.collect_seq()?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::metrics::{MetricsContext, UserMetrics};
+use crate::node::{DynOp, Node};
+use crate::{Element, PCollection, Partition};
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+impl<T: Element> PCollection<T> {
+    /// Map each element with access to a [`MetricsContext`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the input partition is not a `Vec<T>`. This cannot occur in normal
+    /// usage because the op is constructed from a typed `PCollection<T>`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ironbeam::*;
+    /// let p = Pipeline::default();
+    /// let scores = from_vec(&p, vec![3u32, 12, 7]).map_with_metrics(|x, m| {
+    ///     m.distribution("score").record(f64::from(*x));
+    ///     x * 10
+    /// });
+    /// ```
+    #[must_use]
+    pub fn map_with_metrics<O, F>(self, f: F) -> PCollection<O>
+    where
+        O: Element,
+        F: 'static + Send + Sync + Fn(&T, &MetricsContext) -> O,
+    {
+        self.with_metrics_op(move |input: Vec<T>, ctx: &MetricsContext| {
+            input.iter().map(|x| f(x, ctx)).collect()
+        })
+    }
+
+    /// Keep the elements matching `pred`, with access to a [`MetricsContext`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the input partition is not a `Vec<T>`. This cannot occur in normal
+    /// usage because the op is constructed from a typed `PCollection<T>`.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use ironbeam::*;
+    /// let p = Pipeline::default();
+    /// let valid = from_vec(&p, vec![-1i32, 4, 9]).filter_with_metrics(|x, m| {
+    ///     let keep = *x >= 0;
+    ///     if !keep {
+    ///         m.counter("negative").inc();
+    ///     }
+    ///     keep
+    /// });
+    /// ```
+    #[must_use]
+    pub fn filter_with_metrics<F>(self, pred: F) -> Self
+    where
+        F: 'static + Send + Sync + Fn(&T, &MetricsContext) -> bool,
+    {
+        self.with_metrics_op(move |input: Vec<T>, ctx: &MetricsContext| {
+            input.into_iter().filter(|x| pred(x, ctx)).collect()
+        })
+    }
+
+    /// Append a stateless node running `f` over each partition with a fresh context.
+    fn with_metrics_op<O, F>(self, f: F) -> PCollection<O>
+    where
+        O: Element,
+        F: 'static + Send + Sync + Fn(Vec<T>, &MetricsContext) -> Vec<O>,
+    {
+        let op: Arc<dyn DynOp> = Arc::new(WithMetricsOp {
+            f,
+            sink: self.pipeline.user_metrics(),
+            _t: PhantomData::<fn(T) -> O>,
+        });
+        let id = self.pipeline.insert_node(Node::Stateless(vec![op]));
+        self.pipeline.connect(self.id, id);
+        self.pipeline.set_coder::<O>(id);
+        PCollection {
+            pipeline: self.pipeline,
+            id,
+            _t: PhantomData,
+        }
+    }
+}
+
+/// Runs `f` over one partition with its own [`MetricsContext`], then merges the
+/// context into the pipeline's shared accumulator.
+struct WithMetricsOp<T, O, F> {
+    f: F,
+    sink: Arc<Mutex<UserMetrics>>,
+    _t: PhantomData<fn(T) -> O>,
+}
+
+impl<T, O, F> DynOp for WithMetricsOp<T, O, F>
+where
+    T: Element,
+    O: Element,
+    F: 'static + Send + Sync + Fn(Vec<T>, &MetricsContext) -> Vec<O>,
+{
+    fn apply(&self, input: Partition) -> Partition {
+        let input = *input
+            .downcast::<Vec<T>>()
+            .expect("WithMetricsOp input type");
+        let ctx = MetricsContext::default();
+        let out = (self.f)(input, &ctx);
+        self.sink
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .merge(ctx.into_values());
+        Box::new(out) as Partition
+    }
+}
@@ -11,6 +11,10 @@
 //!   XML is not splittable) and parses it lazily in the runner:
 //!   - [`read_xml_streaming`] -> `PCollection<T>`
 //!
+//! - **Arbitrary documents** — extract one record per element selected by a tag
+//!   path such as `"feed/entry"`:
+//!   - [`read_xml_records`] / [`read_xml_records_streaming`] -> `PCollection<T>`
+//!
 //! All functions are serde-driven: your record type `T` should
 //! `#[derive(serde::Deserialize)]` for reads and additionally
 //! `#[derive(serde::Serialize)]` for writes.
@@ -64,7 +68,10 @@
 use std::sync::Arc;
 
 use crate::io::glob::expand_glob;
-use crate::io::xml::{XmlShards, XmlVecOps, build_xml_shards, read_xml_vec, write_xml_vec};
+use crate::io::xml::{
+    XmlShards, XmlVecOps, build_xml_record_shards, build_xml_shards, read_xml_records_vec,
+    read_xml_vec, write_xml_vec,
+};
 use crate::node::Node;
 use crate::type_token::TypeTag;
 use crate::{Element, PCollection, Pipeline, from_vec};
@@ -211,31 +218,78 @@ pub fn read_xml<T>(p: &Pipeline, path: impl AsRef<Path>) -> Result<PCollection<T
 where
     T: Element + DeserializeOwned,
 {
+    let data = read_xml_files(path.as_ref(), |file| read_xml_vec::<T>(file))?;
+    Ok(from_vec(p, data).record_input("xml", path.as_ref().display()))
+}
+
+/// Read the elements selected by `record_path` from XML file(s) into a typed
+/// `PCollection<T>` (vector mode).
+///
+/// Unlike [`read_xml`], the document can have any layout: every element reached by
+/// the path (for example `"catalog/book"`, or `"feed/*/entry"` with a wildcard)
+/// becomes one `T`. See [`io::xml`](crate::io::xml#record-paths) for the matching
+/// rules. Glob patterns are supported as in [`read_xml`].
+///
+/// # Errors
+/// Returns an error if no files match, any file cannot be read, or any record fails
+/// to deserialize.
+///
+/// # Example
+/// ```no_run
+/// use ironbeam::*;
+/// use serde::{Deserialize, Serialize};
+/// use anyhow::Result;
+///
+/// #[derive(Clone, Serialize, Deserialize)]
+/// struct Order {
+///     #[serde(rename = "@id")]
+///     id: u64,
+///     total: f64,
+/// }
+///
+/// # fn main() -> Result<()> {
+/// let p = Pipeline::default();
+/// let orders = read_xml_records::<Order>(&p, "exports/*.xml", "export/orders/order")?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn read_xml_records<T>(
+    p: &Pipeline,
+    path: impl AsRef<Path>,
+    record_path: &str,
+) -> Result<PCollection<T>>
+where
+    T: Element + DeserializeOwned,
+{
+    let data = read_xml_files(path.as_ref(), |file| {
+        read_xml_records_vec::<T>(file, record_path)
+    })?;
+    Ok(from_vec(p, data).record_input("xml", path.as_ref().display()))
+}
+
+/// Read a file or every file matching a glob pattern with `read`, concatenating the
+/// results in sorted file order.
+fn read_xml_files<T>(path: &Path, read: impl Fn(&Path) -> Result<Vec<T>>) -> Result<Vec<T>> {
     let path_str = path
-        .as_ref()
         .to_str()
         .ok_or_else(|| anyhow!("path contains invalid UTF-8"))?;
 
     let glob_regex = Regex::new(r"[*?\[]").expect("valid glob regex");
-    if glob_regex.is_match(path_str) {
-        let files =
-            expand_glob(path_str).with_context(|| format!("expanding glob pattern: {path_str}"))?;
-
-        if files.is_empty() {
-            bail!("no files found matching pattern: {path_str}");
-        }
+    if !glob_regex.is_match(path_str) {
+        return read(path);
+    }
+    let files =
+        expand_glob(path_str).with_context(|| format!("expanding glob pattern: {path_str}"))?;
+    if files.is_empty() {
+        bail!("no files found matching pattern: {path_str}");
+    }
 
-        let mut all_data = Vec::new();
-        for file in files {
-            let data: Vec<T> =
-                read_xml_vec(&file).with_context(|| format!("reading {}", file.display()))?;
-            all_data.extend(data);
-        }
-        Ok(from_vec(p, all_data))
-    } else {
-        let v = read_xml_vec::<T>(path)?;
-        Ok(from_vec(p, v))
+    let mut all_data = Vec::new();
+    for file in files {
+        let data = read(&file).with_context(|| format!("reading {}", file.display()))?;
+        all_data.extend(data);
     }
+    Ok(all_data)
 }
 
 /// Create a **streaming** XML source, sharded by record count.
@@ -278,16 +332,64 @@ pub fn read_xml_streaming<T>(
 where
     T: Element + DeserializeOwned,
 {
+    let location = path.as_ref().display().to_string();
     let shards: XmlShards = build_xml_shards(path, records_per_shard)?;
+    Ok(xml_source(p, shards).record_input("xml", location))
+}
+
+/// Create a **streaming** source over the elements selected by `record_path`.
+///
+/// Like [`read_xml_records`], but the records are counted up front and split into
+/// shards of `records_per_shard`, each parsed lazily by the runner. Every shard
+/// streams the file from the start and skips the records before its range.
+///
+/// # Errors
+/// Returns an error if `record_path` is empty or the file cannot be read as XML.
+///
+/// # Example
+/// ```no_run
+/// use ironbeam::*;
+/// use serde::{Deserialize, Serialize};
+/// use anyhow::Result;
+///
+/// #[derive(Clone, Serialize, Deserialize)]
+/// struct Entry { id: String, title: String }
+///
+/// # fn main() -> Result<()> {
+/// let p = Pipeline::default();
+/// let entries = read_xml_records_streaming::<Entry>(&p, "feed.xml", "feed/entry", 10_000)?;
+/// let out = entries.collect_par(None, None)?;
+/// # Ok(())
+/// # }
+/// ```
+pub fn read_xml_records_streaming<T>(
+    p: &Pipeline,
+    path: impl AsRef<Path>,
+    record_path: &str,
+    records_per_shard: usize,
+) -> Result<PCollection<T>>
+where
+    T: Element + DeserializeOwned,
+{
+    let location = path.as_ref().display().to_string();
+    let shards = build_xml_record_shards(path, record_path, records_per_shard)?;
+    Ok(xml_source(p, shards).record_input("xml", location))
+}
+
+/// Insert a source node reading `shards` as `T`.
+fn xml_source<T>(p: &Pipeline, shards: XmlShards) -> PCollection<T>
+where
+    T: Element + DeserializeOwned,
+{
     let id = p.insert_node(Node::Source {
         payload: Arc::new(shards),
         vec_ops: XmlVecOps::<T>::new(),
         elem_tag: TypeTag::of::<T>(),
     });
     p.set_coder::<T>(id);
-    Ok(PCollection {
+    PCollection {
         pipeline: p.clone(),
         id,
         _t: PhantomData,
-    })
+    }
 }
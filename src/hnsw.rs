@@ -0,0 +1,267 @@
+//! In-tree HNSW index for approximate nearest-neighbor search over embeddings.
+//!
+//! Internally it backs [`similarity_join`](crate::PCollection::similarity_join): the
+//! right side of the join is inserted into an [`HnswIndex`], and each element of the
+//! left side queries it for its nearest neighbors, so matching `n` vectors against
+//! `m` costs about `n log m` distance computations instead of the `n × m` of a cross
+//! join.
+//!
+//! The index is a Hierarchical Navigable Small World graph (Malkov & Yashunin): every
+//! vector is a node on layer 0, and a geometrically shrinking subset also lives on
+//! higher, sparser layers. A search descends greedily from the top layer, then runs
+//! a best-first search on layer 0.
+//!
+//! ## Parameters
+//!
+//! | Parameter | Value | Notes |
+//! |-----------|-------|-------|
+//! | Links per node (`M`) | 16, 32 on layer 0 | The closest neighbors are kept when a list overflows |
+//! | `ef_construction` | 100 | Candidate list size while inserting |
+//! | `ef_search` | `max(64, k)` | Candidate list size while querying |
+//! | Exact search | up to 256 vectors | Small indexes are scanned, so their results are exact |
+//!
+//! Node levels come from a fixed hash of the insertion index rather than a random
+//! number generator, so the same inserts always build the same graph and searches
+//! are reproducible.
+
+use ordered_float::OrderedFloat;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashSet};
+
+/// Maximum number of links per node above layer 0.
+const M: usize = 16;
+
+/// Maximum number of links per node on layer 0.
+const M0: usize = 2 * M;
+
+/// Candidate list size while inserting.
+const EF_CONSTRUCTION: usize = 100;
+
+/// Minimum candidate list size while querying.
+const EF_SEARCH: usize = 64;
+
+/// Indexes with at most this many vectors are searched exhaustively.
+const EXACT_SEARCH_LIMIT: usize = 256;
+
+/// Upper bound on node levels; with `M = 16` a level above 5 is already rare.
+const MAX_LEVEL: usize = 16;
+
+/// A distance paired with a node index; orders by distance, then by insertion order.
+type Candidate = (OrderedFloat<f32>, usize);
+
+/// How the distance between two vectors is measured. Smaller is always closer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum VectorMetric {
+    /// `1 - cosine similarity`, from 0 (same direction) to 2 (opposite). A zero vector
+    /// is at distance 1 from everything.
+    Cosine,
+    /// Straight-line (L2) distance.
+    Euclidean,
+    /// The negated dot product, for embeddings trained for inner-product search.
+    DotProduct,
+}
+
+impl VectorMetric {
+    /// The distance between `a` and `b`.
+    ///
+    /// # Panics
+    /// Panics if `a` and `b` have different lengths.
+    #[must_use]
+    pub fn distance(self, a: &[f32], b: &[f32]) -> f32 {
+        assert_eq!(
+            a.len(),
+            b.len(),
+            "vector dimensions differ ({} vs {})",
+            a.len(),
+            b.len()
+        );
+        let dot = || a.iter().zip(b).map(|(x, y)| x * y).sum::<f32>();
+        match self {
+            Self::Cosine => {
+                let norms = norm(a) * norm(b);
+                if norms == 0.0 {
+                    1.0
+                } else {
+                    1.0 - dot() / norms
+                }
+            }
+            Self::Euclidean => a
+                .iter()
+                .zip(b)
+                .map(|(x, y)| (x - y) * (x - y))
+                .sum::<f32>()
+                .sqrt(),
+            Self::DotProduct => -dot(),
+        }
+    }
+}
+
+fn norm(v: &[f32]) -> f32 {
+    v.iter().map(|x| x * x).sum::<f32>().sqrt()
+}
+
+/// An approximate nearest-neighbor index over vectors, each carrying a payload
+/// (typically an id).
+///
+/// Construct with [`HnswIndex::new`], populate with [`HnswIndex::insert`], then query
+/// with [`HnswIndex::search`]. All vectors must have the same length.
+#[derive(Clone, Debug)]
+pub struct HnswIndex<T> {
+    metric: VectorMetric,
+    vectors: Vec<Vec<f32>>,
+    payloads: Vec<T>,
+    /// `links[node][level]` are the neighbors of `node` on `level`.
+    links: Vec<Vec<Vec<usize>>>,
+    /// The node the searches start from: one on the top layer.
+    entry: Option<usize>,
+    top_level: usize,
+}
+
+impl<T> HnswIndex<T> {
+    /// Create an empty index measuring distances with `metric`.
+    #[must_use]
+    pub const fn new(metric: VectorMetric) -> Self {
+        Self {
+            metric,
+            vectors: Vec::new(),
+            payloads: Vec::new(),
+            links: Vec::new(),
+            entry: None,
+            top_level: 0,
+        }
+    }
+
+    /// Number of vectors in the index.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.vectors.len()
+    }
+
+    /// Whether the index is empty.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.vectors.is_empty()
+    }
+
+    /// Add `vector` with its `payload`.
+    ///
+    /// # Panics
+    /// Panics if `vector` has a different length than the vectors already inserted.
+    pub fn insert(&mut self, payload: T, vector: Vec<f32>) {
+        let id = self.vectors.len();
+        let level = level_of(id);
+        let mut neighbors = vec![Vec::new(); level + 1];
+        if let Some(mut entry) = self.entry {
+            for l in (level + 1..=self.top_level).rev() {
+                entry = self.search_layer(&vector, entry, 1, l)[0].1;
+            }
+            for l in (0..=level.min(self.top_level)).rev() {
+                let found = self.search_layer(&vector, entry, EF_CONSTRUCTION, l);
+                neighbors[l] = found.iter().take(M).map(|&(_, n)| n).collect();
+                entry = found[0].1;
+            }
+        }
+
+        self.vectors.push(vector);
+        self.payloads.push(payload);
+        self.links.push(neighbors.clone());
+        for (l, level_neighbors) in neighbors.into_iter().enumerate() {
+            let max_links = if l == 0 { M0 } else { M };
+            for n in level_neighbors {
+                self.links[n][l].push(id);
+                if self.links[n][l].len() > max_links {
+                    self.prune(n, l, max_links);
+                }
+            }
+        }
+        if self.entry.is_none() || level > self.top_level {
+            self.entry = Some(id);
+            self.top_level = level;
+        }
+    }
+
+    /// The (at most) `k` payloads whose vectors are closest to `query`, with their
+    /// distances, closest first.
+    ///
+    /// # Panics
+    /// Panics if `query` has a different length than the indexed vectors.
+    #[must_use]
+    pub fn search(&self, query: &[f32], k: usize) -> Vec<(&T, f32)> {
+        let Some(mut entry) = self.entry.filter(|_| k > 0) else {
+            return Vec::new();
+        };
+        let found = if self.len() <= EXACT_SEARCH_LIMIT {
+            let mut all: Vec<Candidate> = (0..self.len())
+                .map(|n| (self.distance(query, n), n))
+                .collect();
+            all.sort_unstable();
+            all
+        } else {
+            for l in (1..=self.top_level).rev() {
+                entry = self.search_layer(query, entry, 1, l)[0].1;
+            }
+            self.search_layer(query, entry, EF_SEARCH.max(k), 0)
+        };
+        found
+            .into_iter()
+            .take(k)
+            .map(|(d, n)| (&self.payloads[n], d.0))
+            .collect()
+    }
+
+    fn distance(&self, query: &[f32], node: usize) -> OrderedFloat<f32> {
+        OrderedFloat(self.metric.distance(query, &self.vectors[node]))
+    }
+
+    /// Best-first search of one layer from `entry`, returning up to `ef` of the nodes
+    /// closest to `query`, closest first.
+    fn search_layer(&self, query: &[f32], entry: usize, ef: usize, level: usize) -> Vec<Candidate> {
+        let start = (self.distance(query, entry), entry);
+        let mut visited = HashSet::from([entry]);
+        let mut candidates = BinaryHeap::from([Reverse(start)]);
+        let mut nearest = BinaryHeap::from([start]);
+        while let Some(Reverse((d, node))) = candidates.pop() {
+            if nearest.len() >= ef && nearest.peek().is_some_and(|&(worst, _)| d > worst) {
+                break;
+            }
+            for &next in &self.links[node][level] {
+                if !visited.insert(next) {
+                    continue;
+                }
+                let candidate = (self.distance(query, next), next);
+                if nearest.len() < ef || nearest.peek().is_some_and(|&worst| candidate < worst) {
+                    candidates.push(Reverse(candidate));
+                    nearest.push(candidate);
+                    if nearest.len() > ef {
+                        nearest.pop();
+                    }
+                }
+            }
+        }
+        nearest.into_sorted_vec()
+    }
+
+    /// Cut the links of `node` on `level` down to its `max_links` closest neighbors.
+    fn prune(&mut self, node: usize, level: usize, max_links: usize) {
+        let mut links = std::mem::take(&mut self.links[node][level]);
+        let base = &self.vectors[node];
+        links.sort_by_cached_key(|&n| (self.distance(base, n), n));
+        links.truncate(max_links);
+        self.links[node][level] = links;
+    }
+}
+
+/// The level of the `id`-th inserted node: `floor(-ln(u) / ln(M))` for a `u` in
+/// `(0, 1]` derived from `id` by a SplitMix64 hash.
+fn level_of(id: usize) -> usize {
+    let mut x = (id as u64).wrapping_add(0x9E37_79B9_7F4A_7C15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    x ^= x >> 31;
+    #[allow(clippy::cast_precision_loss)]
+    let u = ((x >> 11) + 1) as f64 / (1u64 << 53) as f64;
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let level = (-u.ln() / (M as f64).ln()).floor() as usize;
+    level.min(MAX_LEVEL)
+}
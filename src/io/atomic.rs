@@ -0,0 +1,97 @@
+//! Atomic file output.
+//!
+//! Every file writer in [`io`](crate::io) and the `write_*` methods built on them write
+//! to a temporary file beside the destination, named after it with `.tmp` appended
+//! (`out.jsonl` is written as `out.jsonl.tmp`). The temporary file is synced and renamed
+//! over the destination only once it is complete. If a write fails, panics, or its run
+//! is cancelled before that point, the temporary file is removed and any previous file
+//! at the destination is left untouched, so consumers never read a half-written
+//! output.
+//!
+//! Writers that take [`WriteOptions`](crate::io::compression::WriteOptions) can also
+//! create an empty `_SUCCESS` file in the destination's directory once the output is in
+//! place ([`WriteOptions::with_success_marker`](crate::io::compression::WriteOptions::with_success_marker)),
+//! following the Hadoop/Spark convention for marking a completed output directory.
+
+use anyhow::{Context, Result};
+use std::fs::{File, create_dir_all, remove_file, rename};
+use std::path::{Path, PathBuf};
+
+/// Name of the marker file written by
+/// [`WriteOptions::with_success_marker`](crate::io::compression::WriteOptions::with_success_marker).
+pub const SUCCESS_MARKER: &str = "_SUCCESS";
+
+/// Temporary path that a write to `path` goes to before it is renamed into place.
+#[must_use]
+pub fn tmp_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".tmp");
+    path.with_file_name(name)
+}
+
+/// Removes the temporary file on drop unless the write was committed, so a panicking
+/// writer does not leave it behind either.
+struct TmpGuard<'a> {
+    path: &'a Path,
+    committed: bool,
+}
+
+impl Drop for TmpGuard<'_> {
+    fn drop(&mut self) {
+        if !self.committed {
+            let _ = remove_file(self.path);
+        }
+    }
+}
+
+/// Create `path`'s parent directories if they are missing.
+///
+/// # Errors
+/// Returns an error if a directory cannot be created.
+pub(crate) fn create_parent_dir(path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        create_dir_all(parent).with_context(|| format!("mkdir -p {}", parent.display()))?;
+    }
+    Ok(())
+}
+
+/// Pass `write` a new file at [`tmp_path`], and rename that file over `path` once
+/// `write` returns successfully.
+///
+/// `write` owns the file, so every writer wrapping it (buffers, compression encoders)
+/// has been dropped, and has finished its output, by the time it returns.
+///
+/// # Errors
+/// Returns the error of `write`, or an error if the file cannot be created, synced, or
+/// renamed. The temporary file is removed in every failure case.
+pub(crate) fn write_atomically<R>(path: &Path, write: impl FnOnce(File) -> Result<R>) -> Result<R> {
+    let tmp = tmp_path(path);
+    let mut guard = TmpGuard {
+        path: &tmp,
+        committed: false,
+    };
+    let file = File::create(&tmp).with_context(|| format!("create {}", tmp.display()))?;
+    let handle = file
+        .try_clone()
+        .with_context(|| format!("open {}", tmp.display()))?;
+    let out = write(file)?;
+    handle
+        .sync_all()
+        .with_context(|| format!("sync {}", tmp.display()))?;
+    drop(handle);
+    rename(&tmp, path).with_context(|| format!("move {} into place", path.display()))?;
+    guard.committed = true;
+    Ok(out)
+}
+
+/// Create the empty [`SUCCESS_MARKER`] file in the directory holding `path`.
+///
+/// # Errors
+/// Returns an error if the marker cannot be created.
+pub(crate) fn write_success_marker(path: &Path) -> Result<()> {
+    let marker = path.with_file_name(SUCCESS_MARKER);
+    File::create(&marker).with_context(|| format!("create {}", marker.display()))?;
+    Ok(())
+}
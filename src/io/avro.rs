@@ -26,10 +26,13 @@ use crate::type_token::VecOps;
 use anyhow::Result;
 use serde::{Serialize, de::DeserializeOwned};
 use std::any::Any;
+use std::fs::metadata;
 use std::marker::PhantomData;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+#[cfg(feature = "io-avro")]
+use crate::io::atomic::{create_parent_dir, write_atomically};
 #[cfg(feature = "io-avro")]
 use crate::io::compression::{auto_detect_reader, auto_detect_writer};
 #[cfg(feature = "io-avro")]
@@ -37,7 +40,7 @@ use anyhow::Context;
 #[cfg(feature = "io-avro")]
 use apache_avro::{Schema, Writer, from_value, to_value, types::Value};
 #[cfg(feature = "io-avro")]
-use std::fs::{File, create_dir_all};
+use std::fs::File;
 #[cfg(feature = "io-avro")]
 use std::io::{BufReader, Read};
 #[cfg(feature = "io-avro")]
@@ -206,23 +209,23 @@ pub fn write_avro_vec_with_schema<T: Serialize>(
     schema: &Schema,
 ) -> Result<usize> {
     let path = path.as_ref();
-    if let Some(parent) = path.parent()
-        && !parent.as_os_str().is_empty()
-    {
-        create_dir_all(parent).with_context(|| format!("mkdir -p {}", parent.display()))?;
-    }
-    let f = File::create(path).with_context(|| format!("create {}", path.display()))?;
-    let mut w = auto_detect_writer(f, path)
-        .with_context(|| format!("setup compression for {}", path.display()))?;
-    let mut writer = Writer::new(schema, &mut w);
-    for (i, item) in data.iter().enumerate() {
-        let value: Value = to_value(item)
-            .with_context(|| format!("serialize item #{} to Avro in {}", i, path.display()))?;
-        writer
-            .append(value)
-            .with_context(|| format!("write record #{} to Avro in {}", i, path.display()))?;
-    }
-    writer.flush().context("flush Avro writer")?;
+    create_parent_dir(path)?;
+    write_atomically(path, |f| {
+        let mut w = auto_detect_writer(f, path)
+            .with_context(|| format!("setup compression for {}", path.display()))?;
+        let mut writer = Writer::new(schema, &mut w);
+        for (i, item) in data.iter().enumerate() {
+            let value: Value = to_value(item)
+                .with_context(|| format!("serialize item #{} to Avro in {}", i, path.display()))?;
+            writer
+                .append(value)
+                .with_context(|| format!("write record #{} to Avro in {}", i, path.display()))?;
+        }
+        writer.flush().context("flush Avro writer")?;
+        drop(writer);
+        w.flush()?;
+        Ok(())
+    })?;
     Ok(data.len())
 }
 
@@ -258,14 +261,10 @@ pub fn write_avro_par<T: Serialize + Sync>(
     schema: impl AsRef<str>,
 ) -> Result<usize> {
     use rayon::prelude::*;
-    use std::io::BufWriter;
+    use std::io::{BufWriter, Write};
 
     let path = path.as_ref();
-    if let Some(parent) = path.parent()
-        && !parent.as_os_str().is_empty()
-    {
-        create_dir_all(parent).with_context(|| format!("mkdir -p {}", parent.display()))?;
-    }
+    create_parent_dir(path)?;
     let n = data.len();
     let schema = Schema::parse_str(schema.as_ref()).context("parse Avro schema string")?;
 
@@ -279,15 +278,19 @@ pub fn write_avro_par<T: Serialize + Sync>(
         .collect::<Result<Vec<_>>>()
         .with_context(|| format!("serialize records to Avro for {}", path.display()))?;
 
-    let f = File::create(path).with_context(|| format!("create {}", path.display()))?;
-    let mut w = BufWriter::new(f);
-    let mut writer = Writer::new(&schema, &mut w);
-    for (i, value) in values.into_iter().enumerate() {
-        writer
-            .append(value)
-            .with_context(|| format!("write record #{} to {}", i, path.display()))?;
-    }
-    writer.flush().context("flush Avro writer")?;
+    write_atomically(path, |f| {
+        let mut w = BufWriter::new(f);
+        let mut writer = Writer::new(&schema, &mut w);
+        for (i, value) in values.into_iter().enumerate() {
+            writer
+                .append(value)
+                .with_context(|| format!("write record #{} to {}", i, path.display()))?;
+        }
+        writer.flush().context("flush Avro writer")?;
+        drop(writer);
+        w.flush()?;
+        Ok(())
+    })?;
     Ok(n)
 }
 
@@ -539,6 +542,12 @@ where
         usize::try_from(s.total_records).ok()
     }
 
+    fn byte_size_hint(&self, data: &dyn Any) -> Option<usize> {
+        let s = data.downcast_ref::<AvroShards>()?;
+        let meta = metadata(&s.path).ok()?;
+        usize::try_from(meta.len()).ok()
+    }
+
     fn split(&self, data: &dyn Any, _n: usize) -> Option<Vec<Partition>> {
         let s = data.downcast_ref::<AvroShards>()?;
         let mut parts = Vec::<Partition>::with_capacity(s.ranges.len());
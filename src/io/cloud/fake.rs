@@ -7,18 +7,23 @@ use crate::io::cloud::traits::{
     CacheIO, CloudConfig, CloudCredentials, CloudIOError, CloudResult, ComputeIO, ComputeResult,
     ConfigIO, ConfigValue, DatabaseIO, Document, EdgeDirection, ErrorKind, GraphEdge, GraphIO,
     GraphNode, InferenceInput, InferenceOutput, IntelligenceIO, InvocationStatus, KeyValueIO,
-    Message, MetricIO, MetricPoint, MetricQuery, Notification, NotificationIO, NotificationResult,
-    NotificationStatus, ObjectIO, ObjectMetadata, PubSubIO, QueryResult, QueueIO, QueueMessage,
-    Row, SearchHit, SearchIO, SearchQuery, Transaction, WarehouseIO,
+    Message, MetricIO, MetricPoint, MetricQuery, NewGraphEdge, NewGraphNode, Notification,
+    NotificationIO, NotificationResult, NotificationStatus, ObjectIO, ObjectMetadata, PubSubIO,
+    QueryResult, QueueIO, QueueMessage, Row, SearchHit, SearchIO, SearchQuery, Transaction,
+    WarehouseIO,
 };
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
 
 // Type aliases for complex nested types
 type BucketStorage = Arc<Mutex<HashMap<String, HashMap<String, Vec<u8>>>>>;
+type UploadStorage = Arc<Mutex<HashMap<String, PendingUpload>>>;
 type CollectionStorage = Arc<Mutex<HashMap<String, HashMap<String, Document>>>>;
 type IndexStorage = Arc<Mutex<HashMap<String, HashMap<String, HashMap<String, String>>>>>;
 type SchemaMap = Arc<Mutex<HashMap<String, Vec<(String, String)>>>>;
+type InFlightMap = Arc<Mutex<HashMap<String, (String, QueueMessage)>>>;
+type SetLog = Arc<Mutex<Vec<(String, Option<u64>)>>>;
 type FunctionMap = Arc<Mutex<HashMap<String, Box<dyn Fn(&[u8]) -> Vec<u8> + Send + Sync>>>>;
 
 // ============================================================================
@@ -216,9 +221,18 @@ impl WarehouseIO for FakeWarehouseIO {
 // FakeObjectIO
 // ============================================================================
 
+/// A multipart upload that has been started but not completed or aborted.
+struct PendingUpload {
+    bucket: String,
+    key: String,
+    parts: BTreeMap<u32, Vec<u8>>,
+}
+
 #[derive(Clone)]
 pub struct FakeObjectIO {
     storage: BucketStorage,
+    uploads: UploadStorage,
+    next_upload: Arc<AtomicU64>,
 }
 
 impl FakeObjectIO {
@@ -226,8 +240,23 @@ impl FakeObjectIO {
     pub fn new() -> Self {
         Self {
             storage: Arc::new(Mutex::new(HashMap::new())),
+            uploads: Arc::new(Mutex::new(HashMap::new())),
+            next_upload: Arc::new(AtomicU64::new(0)),
         }
     }
+
+    /// Number of multipart uploads started but not yet completed or aborted.
+    #[must_use]
+    pub fn pending_uploads(&self) -> usize {
+        self.uploads.lock().expect("uploads mutex poisoned").len()
+    }
+
+    fn pending_upload_not_found(bucket: &str, key: &str, upload_id: &str) -> CloudIOError {
+        CloudIOError::new(
+            ErrorKind::NotFound,
+            format!("Upload {upload_id} of {bucket}/{key} not found"),
+        )
+    }
 }
 
 impl Default for FakeObjectIO {
@@ -333,6 +362,85 @@ impl ObjectIO for FakeObjectIO {
         let data = self.get_object(src_bucket, src_key)?;
         self.put_object(dst_bucket, dst_key, &data)
     }
+
+    fn supports_multipart(&self) -> bool {
+        true
+    }
+
+    fn create_multipart_upload(&self, bucket: &str, key: &str) -> CloudResult<String> {
+        let upload_id = format!(
+            "upload-{}",
+            self.next_upload.fetch_add(1, Ordering::Relaxed)
+        );
+        self.uploads.lock().expect("uploads mutex poisoned").insert(
+            upload_id.clone(),
+            PendingUpload {
+                bucket: bucket.to_string(),
+                key: key.to_string(),
+                parts: BTreeMap::new(),
+            },
+        );
+        Ok(upload_id)
+    }
+
+    fn upload_part(
+        &self,
+        bucket: &str,
+        key: &str,
+        upload_id: &str,
+        part_number: u32,
+        data: &[u8],
+    ) -> CloudResult<String> {
+        let mut uploads = self.uploads.lock().expect("uploads mutex poisoned");
+        let upload = uploads
+            .get_mut(upload_id)
+            .filter(|u| u.bucket == bucket && u.key == key)
+            .ok_or_else(|| Self::pending_upload_not_found(bucket, key, upload_id))?;
+        upload.parts.insert(part_number, data.to_vec());
+        Ok(format!("etag-{upload_id}-{part_number}"))
+    }
+
+    fn complete_multipart_upload(
+        &self,
+        bucket: &str,
+        key: &str,
+        upload_id: &str,
+        etags: &[String],
+    ) -> CloudResult<()> {
+        let mut uploads = self.uploads.lock().expect("uploads mutex poisoned");
+        let upload = uploads
+            .get(upload_id)
+            .filter(|u| u.bucket == bucket && u.key == key)
+            .ok_or_else(|| Self::pending_upload_not_found(bucket, key, upload_id))?;
+        let expected: Vec<String> = upload
+            .parts
+            .keys()
+            .map(|n| format!("etag-{upload_id}-{n}"))
+            .collect();
+        if expected != etags {
+            return Err(CloudIOError::new(
+                ErrorKind::InvalidInput,
+                format!("ETags do not match the uploaded parts of {bucket}/{key}"),
+            ));
+        }
+        let upload = uploads.remove(upload_id).expect("upload was found above");
+        drop(uploads);
+        let data: Vec<u8> = upload.parts.into_values().flatten().collect();
+        self.put_object(bucket, key, &data)
+    }
+
+    fn abort_multipart_upload(&self, bucket: &str, key: &str, upload_id: &str) -> CloudResult<()> {
+        let mut uploads = self.uploads.lock().expect("uploads mutex poisoned");
+        if uploads
+            .get(upload_id)
+            .is_some_and(|u| u.bucket == bucket && u.key == key)
+        {
+            uploads.remove(upload_id);
+            Ok(())
+        } else {
+            Err(Self::pending_upload_not_found(bucket, key, upload_id))
+        }
+    }
 }
 
 // ============================================================================
@@ -569,6 +677,10 @@ impl Transaction for FakeTransaction {
 #[derive(Clone)]
 pub struct FakeKeyValueIO {
     collections: CollectionStorage,
+    /// Number of keys of every `batch_get` call, in call order.
+    batch_gets: Arc<Mutex<Vec<usize>>>,
+    /// Number of documents of every `batch_put` call, in call order.
+    batch_puts: Arc<Mutex<Vec<usize>>>,
 }
 
 impl FakeKeyValueIO {
@@ -576,8 +688,36 @@ impl FakeKeyValueIO {
     pub fn new() -> Self {
         Self {
             collections: Arc::new(Mutex::new(HashMap::new())),
+            batch_gets: Arc::new(Mutex::new(Vec::new())),
+            batch_puts: Arc::new(Mutex::new(Vec::new())),
         }
     }
+
+    /// Number of keys requested by each `batch_get` call so far, in call order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mutex protecting the call log is poisoned.
+    #[must_use]
+    pub fn batch_get_sizes(&self) -> Vec<usize> {
+        self.batch_gets
+            .lock()
+            .expect("batch_gets mutex poisoned")
+            .clone()
+    }
+
+    /// Number of documents written by each `batch_put` call so far, in call order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mutex protecting the call log is poisoned.
+    #[must_use]
+    pub fn batch_put_sizes(&self) -> Vec<usize> {
+        self.batch_puts
+            .lock()
+            .expect("batch_puts mutex poisoned")
+            .clone()
+    }
 }
 
 impl Default for FakeKeyValueIO {
@@ -646,6 +786,10 @@ impl KeyValueIO for FakeKeyValueIO {
     }
 
     fn batch_get(&self, collection: &str, keys: Vec<String>) -> CloudResult<Vec<Option<Document>>> {
+        self.batch_gets
+            .lock()
+            .expect("batch_gets mutex poisoned")
+            .push(keys.len());
         keys.into_iter().map(|k| self.get(collection, &k)).collect()
     }
 
@@ -654,6 +798,10 @@ impl KeyValueIO for FakeKeyValueIO {
         collection: &str,
         documents: Vec<(String, HashMap<String, String>)>,
     ) -> CloudResult<()> {
+        self.batch_puts
+            .lock()
+            .expect("batch_puts mutex poisoned")
+            .push(documents.len());
         for (key, data) in documents {
             self.put(collection, &key, data)?;
         }
@@ -675,6 +823,8 @@ impl KeyValueIO for FakeKeyValueIO {
 #[derive(Clone)]
 pub struct FakeSearchIO {
     indices: IndexStorage,
+    /// Number of documents of every `batch_index` call, in call order.
+    batch_indexes: Arc<Mutex<Vec<usize>>>,
 }
 
 impl FakeSearchIO {
@@ -682,8 +832,22 @@ impl FakeSearchIO {
     pub fn new() -> Self {
         Self {
             indices: Arc::new(Mutex::new(HashMap::new())),
+            batch_indexes: Arc::new(Mutex::new(Vec::new())),
         }
     }
+
+    /// Number of documents indexed by each `batch_index` call so far, in call order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mutex protecting the call log is poisoned.
+    #[must_use]
+    pub fn batch_index_sizes(&self) -> Vec<usize> {
+        self.batch_indexes
+            .lock()
+            .expect("batch_indexes mutex poisoned")
+            .clone()
+    }
 }
 
 impl Default for FakeSearchIO {
@@ -708,6 +872,10 @@ impl SearchIO for FakeSearchIO {
         index: &str,
         documents: Vec<(String, HashMap<String, String>)>,
     ) -> CloudResult<()> {
+        self.batch_indexes
+            .lock()
+            .expect("batch_indexes mutex poisoned")
+            .push(documents.len());
         for (id, doc) in documents {
             self.index(index, &id, doc)?;
         }
@@ -939,6 +1107,9 @@ impl ConfigIO for FakeConfigIO {
 #[derive(Clone)]
 pub struct FakeQueueIO {
     queues: Arc<Mutex<HashMap<String, Vec<QueueMessage>>>>,
+    /// Received but not yet deleted messages, by receipt handle. Visibility timeouts
+    /// never expire here; a message only comes back through `change_visibility`.
+    in_flight: InFlightMap,
     message_counter: Arc<Mutex<u64>>,
 }
 
@@ -947,10 +1118,26 @@ impl FakeQueueIO {
     pub fn new() -> Self {
         Self {
             queues: Arc::new(Mutex::new(HashMap::new())),
+            in_flight: Arc::new(Mutex::new(HashMap::new())),
             message_counter: Arc::new(Mutex::new(0)),
         }
     }
 
+    /// Number of messages received from `queue` that have not been deleted or released.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mutex protecting the in-flight messages is poisoned.
+    #[must_use]
+    pub fn in_flight(&self, queue: &str) -> usize {
+        self.in_flight
+            .lock()
+            .expect("in_flight mutex poisoned")
+            .values()
+            .filter(|(q, _)| q == queue)
+            .count()
+    }
+
     fn next_id(&self) -> String {
         let mut counter = self
             .message_counter
@@ -1016,16 +1203,35 @@ impl QueueIO for FakeQueueIO {
         let q = queues.entry(queue.to_string()).or_default();
 
         let count = std::cmp::min(max_messages as usize, q.len());
-        let messages = q.drain(0..count).collect();
+        let messages: Vec<QueueMessage> = q
+            .drain(0..count)
+            .map(|mut m| {
+                m.receive_count += 1;
+                m
+            })
+            .collect();
         drop(queues);
+
+        let mut in_flight = self.in_flight.lock().expect("in_flight mutex poisoned");
+        for m in &messages {
+            in_flight.insert(m.receipt_handle.clone(), (queue.to_string(), m.clone()));
+        }
+        drop(in_flight);
         Ok(messages)
     }
 
-    fn delete(&self, _queue: &str, _receipt_handle: &str) -> CloudResult<()> {
+    fn delete(&self, _queue: &str, receipt_handle: &str) -> CloudResult<()> {
+        self.in_flight
+            .lock()
+            .expect("in_flight mutex poisoned")
+            .remove(receipt_handle);
         Ok(())
     }
 
-    fn delete_batch(&self, _queue: &str, _receipt_handles: Vec<String>) -> CloudResult<()> {
+    fn delete_batch(&self, queue: &str, receipt_handles: Vec<String>) -> CloudResult<()> {
+        for receipt_handle in receipt_handles {
+            self.delete(queue, &receipt_handle)?;
+        }
         Ok(())
     }
 
@@ -1045,6 +1251,36 @@ impl QueueIO for FakeQueueIO {
         }
         Ok(())
     }
+
+    fn change_visibility(
+        &self,
+        queue: &str,
+        receipt_handle: &str,
+        visibility_timeout_secs: u32,
+    ) -> CloudResult<()> {
+        let mut in_flight = self.in_flight.lock().expect("in_flight mutex poisoned");
+        if !in_flight.contains_key(receipt_handle) {
+            return Err(CloudIOError::new(
+                ErrorKind::NotFound,
+                format!("Receipt {receipt_handle} is not in flight on {queue}"),
+            ));
+        }
+        if visibility_timeout_secs > 0 {
+            return Ok(());
+        }
+        let (q, message) = in_flight
+            .remove(receipt_handle)
+            .expect("receipt checked above");
+        drop(in_flight);
+
+        self.queues
+            .lock()
+            .expect("queues mutex poisoned")
+            .entry(q)
+            .or_default()
+            .push(message);
+        Ok(())
+    }
 }
 
 // ============================================================================
@@ -1054,6 +1290,10 @@ impl QueueIO for FakeQueueIO {
 #[derive(Clone)]
 pub struct FakeCacheIO {
     cache: Arc<Mutex<HashMap<String, Vec<u8>>>>,
+    /// The key of every `get` call, in call order.
+    gets: Arc<Mutex<Vec<String>>>,
+    /// The key and TTL of every `set` call, in call order.
+    sets: SetLog,
 }
 
 impl FakeCacheIO {
@@ -1061,8 +1301,31 @@ impl FakeCacheIO {
     pub fn new() -> Self {
         Self {
             cache: Arc::new(Mutex::new(HashMap::new())),
+            gets: Arc::new(Mutex::new(Vec::new())),
+            sets: Arc::new(Mutex::new(Vec::new())),
         }
     }
+
+    /// The key of each `get` call so far, in call order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mutex protecting the call log is poisoned.
+    #[must_use]
+    pub fn get_calls(&self) -> Vec<String> {
+        self.gets.lock().expect("gets mutex poisoned").clone()
+    }
+
+    /// The key and TTL of each `set` call so far, in call order. TTLs are recorded but
+    /// never expire entries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mutex protecting the call log is poisoned.
+    #[must_use]
+    pub fn set_calls(&self) -> Vec<(String, Option<u64>)> {
+        self.sets.lock().expect("sets mutex poisoned").clone()
+    }
 }
 
 impl Default for FakeCacheIO {
@@ -1073,6 +1336,10 @@ impl Default for FakeCacheIO {
 
 impl CacheIO for FakeCacheIO {
     fn get(&self, key: &str) -> CloudResult<Option<Vec<u8>>> {
+        self.gets
+            .lock()
+            .expect("gets mutex poisoned")
+            .push(key.to_string());
         Ok(self
             .cache
             .lock()
@@ -1081,7 +1348,11 @@ impl CacheIO for FakeCacheIO {
             .cloned())
     }
 
-    fn set(&self, key: &str, value: &[u8], _ttl_secs: Option<u64>) -> CloudResult<()> {
+    fn set(&self, key: &str, value: &[u8], ttl_secs: Option<u64>) -> CloudResult<()> {
+        self.sets
+            .lock()
+            .expect("sets mutex poisoned")
+            .push((key.to_string(), ttl_secs));
         self.cache
             .lock()
             .expect("cache mutex poisoned")
@@ -1145,6 +1416,12 @@ pub struct FakeGraphIO {
     edges: Arc<Mutex<HashMap<String, GraphEdge>>>,
     node_counter: Arc<Mutex<u64>>,
     edge_counter: Arc<Mutex<u64>>,
+    /// Number of nodes of every `batch_add_nodes` call, in call order.
+    node_batches: Arc<Mutex<Vec<usize>>>,
+    /// Number of edges of every `batch_add_edges` call, in call order.
+    edge_batches: Arc<Mutex<Vec<usize>>>,
+    /// The node of every `get_neighbors` call, in call order.
+    neighbor_lookups: Arc<Mutex<Vec<String>>>,
 }
 
 impl FakeGraphIO {
@@ -1155,9 +1432,51 @@ impl FakeGraphIO {
             edges: Arc::new(Mutex::new(HashMap::new())),
             node_counter: Arc::new(Mutex::new(0)),
             edge_counter: Arc::new(Mutex::new(0)),
+            node_batches: Arc::new(Mutex::new(Vec::new())),
+            edge_batches: Arc::new(Mutex::new(Vec::new())),
+            neighbor_lookups: Arc::new(Mutex::new(Vec::new())),
         }
     }
 
+    /// Number of nodes added by each `batch_add_nodes` call so far, in call order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mutex protecting the call log is poisoned.
+    #[must_use]
+    pub fn node_batch_sizes(&self) -> Vec<usize> {
+        self.node_batches
+            .lock()
+            .expect("node_batches mutex poisoned")
+            .clone()
+    }
+
+    /// Number of edges added by each `batch_add_edges` call so far, in call order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mutex protecting the call log is poisoned.
+    #[must_use]
+    pub fn edge_batch_sizes(&self) -> Vec<usize> {
+        self.edge_batches
+            .lock()
+            .expect("edge_batches mutex poisoned")
+            .clone()
+    }
+
+    /// The node of each `get_neighbors` call so far, in call order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the mutex protecting the call log is poisoned.
+    #[must_use]
+    pub fn neighbor_lookups(&self) -> Vec<String> {
+        self.neighbor_lookups
+            .lock()
+            .expect("neighbor_lookups mutex poisoned")
+            .clone()
+    }
+
     fn next_node_id(&self) -> String {
         let mut counter = self
             .node_counter
@@ -1240,6 +1559,14 @@ impl GraphIO for FakeGraphIO {
         label: &str,
         properties: HashMap<String, String>,
     ) -> CloudResult<String> {
+        let nodes = self.nodes.lock().expect("nodes mutex poisoned");
+        if let Some(missing) = [from, to].into_iter().find(|id| !nodes.contains_key(*id)) {
+            return Err(CloudIOError::new(
+                ErrorKind::NotFound,
+                format!("Node {missing} not found"),
+            ));
+        }
+        drop(nodes);
         let id = self.next_edge_id();
         let edge = GraphEdge {
             id: id.clone(),
@@ -1283,6 +1610,10 @@ impl GraphIO for FakeGraphIO {
         node_id: &str,
         direction: EdgeDirection,
     ) -> CloudResult<Vec<GraphNode>> {
+        self.neighbor_lookups
+            .lock()
+            .expect("neighbor_lookups mutex poisoned")
+            .push(node_id.to_string());
         let edges = self.edges.lock().expect("edges mutex poisoned");
 
         let neighbor_ids: Vec<String> = edges
@@ -1322,6 +1653,28 @@ impl GraphIO for FakeGraphIO {
             .filter_map(|id| nodes.get(id).cloned())
             .collect())
     }
+
+    fn batch_add_nodes(&self, nodes: Vec<NewGraphNode>) -> CloudResult<Vec<String>> {
+        self.node_batches
+            .lock()
+            .expect("node_batches mutex poisoned")
+            .push(nodes.len());
+        nodes
+            .into_iter()
+            .map(|node| self.add_node(node.labels, node.properties))
+            .collect()
+    }
+
+    fn batch_add_edges(&self, edges: Vec<NewGraphEdge>) -> CloudResult<Vec<String>> {
+        self.edge_batches
+            .lock()
+            .expect("edge_batches mutex poisoned")
+            .push(edges.len());
+        edges
+            .into_iter()
+            .map(|edge| self.add_edge(&edge.from, &edge.to, &edge.label, edge.properties))
+            .collect()
+    }
 }
 
 // ============================================================================
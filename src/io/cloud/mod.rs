@@ -151,6 +151,8 @@
 //!
 //! - [`traits`] - Core trait definitions and types
 //! - [`fake`] - In-memory fake implementations for testing
+//! - [`resolver`] - Resolving `config://` references through a [`ConfigIO`] service
+//! - [`upload`] - Chunked, retried uploads to [`ObjectIO`] stores
 //! - [`utils`] - Generic utilities for implementing cloud I/O
 //!
 //! ## Examples
@@ -160,8 +162,11 @@
 
 pub mod fake;
 pub mod readers;
+pub mod resolver;
 pub mod traits;
+pub mod upload;
 pub mod utils;
 
 pub use fake::*;
+pub use resolver::ConfigResolver;
 pub use traits::*;
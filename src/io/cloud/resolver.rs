@@ -0,0 +1,143 @@
+//! Resolving `config://` references through a [`ConfigIO`] service.
+//!
+//! A [`ConfigResolver`] turns references to configuration keys into their values, so
+//! connection strings, bucket names and paths can live in a parameter store or secret
+//! manager instead of pipeline code:
+//!
+//! - a value that is exactly `config://<key>` resolves to the value stored under `<key>`;
+//! - `${config://<key>}` placeholders inside a longer value are replaced in place, e.g.
+//!   `postgres://etl:${config://db/password}@db:5432/sales`;
+//! - anything else is returned unchanged.
+//!
+//! Each key is fetched once per resolver and cached. Registered on a pipeline with
+//! [`Pipeline::set_config_resolver`](crate::Pipeline::set_config_resolver), the
+//! resolver is applied when a source or sink is built to:
+//!
+//! - the paths of [`read_jsonl`](crate::read_jsonl), [`read_csv`](crate::read_csv) and
+//!   their variants, and of the JSONL and CSV file writers;
+//! - the bucket and key prefix of the object storage sinks, such as
+//!   [`write_jsonl_object`](crate::PCollection::write_jsonl_object);
+//! - the collection of [`write_key_value`](crate::PCollection::write_key_value) and the
+//!   index of [`write_search_index`](crate::PCollection::write_search_index).
+//!
+//! Other helpers can resolve their own arguments through
+//! [`Pipeline::resolve_config`](crate::Pipeline::resolve_config).
+//!
+//! ```
+//! use ironbeam::io::cloud::resolver::ConfigResolver;
+//! use ironbeam::io::cloud::{ConfigIO, FakeConfigIO};
+//! use std::sync::Arc;
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let config = FakeConfigIO::new();
+//! config.set("etl/bucket", "sales-prod", false)?;
+//! config.set("db/password", "hunter2", true)?;
+//!
+//! let resolver = ConfigResolver::new(Arc::new(config));
+//! assert_eq!(resolver.resolve("config://etl/bucket")?, "sales-prod");
+//! assert_eq!(
+//!     resolver.resolve("postgres://etl:${config://db/password}@db/sales")?,
+//!     "postgres://etl:hunter2@db/sales"
+//! );
+//! assert_eq!(resolver.resolve("data/input.jsonl")?, "data/input.jsonl");
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::io::cloud::traits::{CloudIOError, CloudResult, ConfigIO, ErrorKind};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// Prefix of a reference to a configuration key.
+pub const CONFIG_SCHEME: &str = "config://";
+
+/// Opening of a reference embedded in a longer value; closed by `}`.
+const PLACEHOLDER: &str = "${config://";
+
+/// Resolves `config://` references through a [`ConfigIO`] service.
+///
+/// Clones share the service and the cache of resolved values.
+#[derive(Clone)]
+pub struct ConfigResolver {
+    config: Arc<dyn ConfigIO>,
+    cache: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl fmt::Debug for ConfigResolver {
+    // Resolved values may be secrets, so only the number of cached keys is shown.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cached = self.cache.lock().map_or(0, |c| c.len());
+        f.debug_struct("ConfigResolver")
+            .field("cached_keys", &cached)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ConfigResolver {
+    /// Create a resolver that reads values from `config`.
+    #[must_use]
+    pub fn new(config: Arc<dyn ConfigIO>) -> Self {
+        Self {
+            config,
+            cache: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Whether `value` contains a `config://` reference this resolver would replace.
+    #[must_use]
+    pub fn is_reference(value: &str) -> bool {
+        value.starts_with(CONFIG_SCHEME) || value.contains(PLACEHOLDER)
+    }
+
+    /// Resolve the `config://` references in `value`; see the
+    /// [module documentation](self).
+    ///
+    /// # Errors
+    /// Returns an error if a referenced key is empty or cannot be read from the
+    /// service, or a `${config://` placeholder is not closed. Error messages name the
+    /// key, never a value.
+    ///
+    /// # Panics
+    /// Panics if the cache mutex is poisoned.
+    pub fn resolve(&self, value: &str) -> CloudResult<String> {
+        if let Some(key) = value.strip_prefix(CONFIG_SCHEME) {
+            return self.lookup(key);
+        }
+        let mut out = String::with_capacity(value.len());
+        let mut rest = value;
+        while let Some(start) = rest.find(PLACEHOLDER) {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + PLACEHOLDER.len()..];
+            let end = after.find('}').ok_or_else(|| {
+                CloudIOError::new(
+                    ErrorKind::InvalidInput,
+                    format!("Unclosed {PLACEHOLDER} placeholder in config reference"),
+                )
+            })?;
+            out.push_str(&self.lookup(&after[..end])?);
+            rest = &after[end + 1..];
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+
+    /// Fetch the value of `key`, from the cache if it was resolved before.
+    fn lookup(&self, key: &str) -> CloudResult<String> {
+        if key.is_empty() {
+            return Err(CloudIOError::new(
+                ErrorKind::InvalidInput,
+                "Empty key in config reference",
+            ));
+        }
+        if let Some(value) = self.cache.lock().expect("cache mutex poisoned").get(key) {
+            return Ok(value.clone());
+        }
+        let value = self.config.get(key)?.value;
+        self.cache
+            .lock()
+            .expect("cache mutex poisoned")
+            .insert(key.to_string(), value.clone());
+        Ok(value)
+    }
+}
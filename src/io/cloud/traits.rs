@@ -3,6 +3,7 @@
 //! These traits provide synchronous interfaces for various cloud services,
 //! with internal async handling where necessary.
 
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt;
@@ -299,6 +300,78 @@ pub trait ObjectIO: Send + Sync {
         dst_bucket: &str,
         dst_key: &str,
     ) -> CloudResult<()>;
+
+    /// Whether this store implements the multipart upload methods below.
+    ///
+    /// Defaults to `false`, in which case uploaders such as
+    /// [`upload_object`](crate::io::cloud::upload::upload_object) send every object with a
+    /// single [`put_object`](Self::put_object).
+    fn supports_multipart(&self) -> bool {
+        false
+    }
+
+    /// Start a multipart upload of an object and return its upload ID
+    ///
+    /// The object is not visible until
+    /// [`complete_multipart_upload`](Self::complete_multipart_upload) succeeds.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the bucket doesn't exist, permissions are not enough, or the store
+    /// does not support multipart uploads (the default)
+    fn create_multipart_upload(&self, bucket: &str, key: &str) -> CloudResult<String> {
+        Err(multipart_unsupported(bucket, key))
+    }
+
+    /// Upload one part of a multipart upload and return its ETag
+    ///
+    /// Parts are numbered from 1, in the order they make up the object.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the upload doesn't exist or the part upload fails
+    fn upload_part(
+        &self,
+        bucket: &str,
+        key: &str,
+        _upload_id: &str,
+        _part_number: u32,
+        _data: &[u8],
+    ) -> CloudResult<String> {
+        Err(multipart_unsupported(bucket, key))
+    }
+
+    /// Assemble the uploaded parts into the object, given their ETags in part order
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the upload doesn't exist, the ETags don't match the uploaded
+    /// parts, or the operation fails
+    fn complete_multipart_upload(
+        &self,
+        bucket: &str,
+        key: &str,
+        _upload_id: &str,
+        _etags: &[String],
+    ) -> CloudResult<()> {
+        Err(multipart_unsupported(bucket, key))
+    }
+
+    /// Discard a multipart upload and the parts uploaded so far
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the upload doesn't exist or the operation fails
+    fn abort_multipart_upload(&self, bucket: &str, key: &str, _upload_id: &str) -> CloudResult<()> {
+        Err(multipart_unsupported(bucket, key))
+    }
+}
+
+fn multipart_unsupported(bucket: &str, key: &str) -> CloudIOError {
+    CloudIOError::new(
+        ErrorKind::InvalidInput,
+        format!("Cannot upload {bucket}/{key} in parts: multipart uploads are not supported"),
+    )
 }
 
 // ============================================================================
@@ -769,6 +842,27 @@ pub trait QueueIO: Send + Sync {
     ///
     /// Returns an error if the queue doesn't exist, permissions are not enough, or purging fails
     fn purge(&self, queue: &str) -> CloudResult<()>;
+
+    /// Change how long a received message stays hidden from other consumers
+    ///
+    /// A timeout of `0` makes the message visible again at once, so it is redelivered
+    /// without waiting for the original visibility timeout to expire. The default
+    /// implementation reports that the queue does not support this.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the queue doesn't exist, the receipt handle is invalid, or the operation fails
+    fn change_visibility(
+        &self,
+        queue: &str,
+        receipt_handle: &str,
+        _visibility_timeout_secs: u32,
+    ) -> CloudResult<()> {
+        Err(CloudIOError::new(
+            ErrorKind::InvalidInput,
+            format!("Cannot change the visibility of {receipt_handle} on {queue}: not supported"),
+        ))
+    }
 }
 
 // ============================================================================
@@ -839,7 +933,7 @@ pub trait CacheIO: Send + Sync {
 // ============================================================================
 
 /// A node in a graph
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct GraphNode {
     pub id: String,
     pub labels: Vec<String>,
@@ -935,6 +1029,52 @@ pub trait GraphIO: Send + Sync {
     /// Returns an error if the node doesn't exist, permissions are not enough, or the operation fails
     fn get_neighbors(&self, node_id: &str, direction: EdgeDirection)
     -> CloudResult<Vec<GraphNode>>;
+
+    /// Add multiple nodes, returning their IDs in input order
+    ///
+    /// The default implementation calls [`add_node`](Self::add_node) once per node;
+    /// providers with a bulk-load API should override it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if permissions are not enough or the operation fails
+    fn batch_add_nodes(&self, nodes: Vec<NewGraphNode>) -> CloudResult<Vec<String>> {
+        nodes
+            .into_iter()
+            .map(|node| self.add_node(node.labels, node.properties))
+            .collect()
+    }
+
+    /// Add multiple edges, returning their IDs in input order
+    ///
+    /// The default implementation calls [`add_edge`](Self::add_edge) once per edge;
+    /// providers with a bulk-load API should override it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a node doesn't exist, permissions are not enough, or the operation fails
+    fn batch_add_edges(&self, edges: Vec<NewGraphEdge>) -> CloudResult<Vec<String>> {
+        edges
+            .into_iter()
+            .map(|edge| self.add_edge(&edge.from, &edge.to, &edge.label, edge.properties))
+            .collect()
+    }
+}
+
+/// A node to add with [`GraphIO::batch_add_nodes`]
+#[derive(Debug, Clone)]
+pub struct NewGraphNode {
+    pub labels: Vec<String>,
+    pub properties: HashMap<String, String>,
+}
+
+/// An edge to add with [`GraphIO::batch_add_edges`]
+#[derive(Debug, Clone)]
+pub struct NewGraphEdge {
+    pub from: String,
+    pub to: String,
+    pub label: String,
+    pub properties: HashMap<String, String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -0,0 +1,131 @@
+//! Chunked, retried uploads to object storage.
+//!
+//! [`upload_object`] uploads one object through any [`ObjectIO`], retrying transient
+//! failures with [`retry_with_backoff`]. An object larger than
+//! [`ObjectWriteOptions::part_size`] is sent as a multipart upload when the store
+//! supports it ([`ObjectIO::supports_multipart`]). Each part is retried on its own, so a
+//! failure late in a large object does not resend the parts before it. A multipart
+//! upload that fails is aborted, and no object is created.
+//!
+//! The pipeline sinks built on this are
+//! [`PCollection::write_jsonl_object`](crate::PCollection::write_jsonl_object),
+//! [`write_csv_object`](crate::PCollection::write_csv_object) and
+//! [`write_parquet_object`](crate::PCollection::write_parquet_object).
+//!
+//! ```
+//! use ironbeam::io::cloud::upload::{ObjectWriteOptions, upload_object};
+//! use ironbeam::io::cloud::{CloudResult, FakeObjectIO, ObjectIO};
+//!
+//! # fn main() -> CloudResult<()> {
+//! let storage = FakeObjectIO::new();
+//! let opts = ObjectWriteOptions::default().with_part_size(4);
+//! upload_object(&storage, "bucket", "greeting.txt", b"hello, world", &opts)?;
+//! assert_eq!(storage.get_object("bucket", "greeting.txt")?, b"hello, world");
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::io::cloud::traits::{CloudResult, ObjectIO};
+use crate::io::cloud::utils::{RetryConfig, retry_with_backoff};
+
+/// Default [`ObjectWriteOptions::part_size`]: 8 MiB.
+pub const DEFAULT_PART_SIZE: usize = 8 * 1024 * 1024;
+
+/// Options for uploads to object storage.
+#[derive(Debug, Clone, Copy)]
+pub struct ObjectWriteOptions {
+    /// Size in bytes of each part of a multipart upload (default:
+    /// [`DEFAULT_PART_SIZE`]). Objects no larger than this are uploaded with a single
+    /// [`put_object`](ObjectIO::put_object).
+    pub part_size: usize,
+    /// Retry policy applied to every request (default: [`RetryConfig::default`]).
+    pub retry: RetryConfig,
+    /// Number of output shards, and so of objects, a pipeline sink writes (default:
+    /// the runner's default partition count). Empty shards are not uploaded.
+    pub shards: Option<usize>,
+    /// Upload an empty `_SUCCESS` object under the key prefix once every shard has been
+    /// uploaded (default: `false`).
+    pub success_marker: bool,
+}
+
+impl Default for ObjectWriteOptions {
+    fn default() -> Self {
+        Self {
+            part_size: DEFAULT_PART_SIZE,
+            retry: RetryConfig::default(),
+            shards: None,
+            success_marker: false,
+        }
+    }
+}
+
+impl ObjectWriteOptions {
+    /// Set the multipart part size in bytes (at least 1).
+    #[must_use]
+    pub fn with_part_size(mut self, part_size: usize) -> Self {
+        self.part_size = part_size.max(1);
+        self
+    }
+
+    /// Set the retry policy.
+    #[must_use]
+    pub const fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Set the number of output shards.
+    #[must_use]
+    pub const fn with_shards(mut self, shards: usize) -> Self {
+        self.shards = Some(shards);
+        self
+    }
+
+    /// Set whether to upload a `_SUCCESS` marker once every shard is uploaded.
+    #[must_use]
+    pub const fn with_success_marker(mut self, success_marker: bool) -> Self {
+        self.success_marker = success_marker;
+        self
+    }
+}
+
+/// Upload `data` to `bucket`/`key`, in parts of `opts.part_size` bytes when the store
+/// supports multipart uploads, retrying every request per `opts.retry`.
+///
+/// # Errors
+///
+/// Returns the error of the first request that fails after its retries. A failed
+/// multipart upload is aborted before the error is returned.
+pub fn upload_object(
+    storage: &dyn ObjectIO,
+    bucket: &str,
+    key: &str,
+    data: &[u8],
+    opts: &ObjectWriteOptions,
+) -> CloudResult<()> {
+    let retry = &opts.retry;
+    let part_size = opts.part_size.max(1);
+    if data.len() <= part_size || !storage.supports_multipart() {
+        return retry_with_backoff(retry, || storage.put_object(bucket, key, data));
+    }
+
+    let upload_id = retry_with_backoff(retry, || storage.create_multipart_upload(bucket, key))?;
+    let uploaded = data
+        .chunks(part_size)
+        .zip(1u32..)
+        .map(|(chunk, number)| {
+            retry_with_backoff(retry, || {
+                storage.upload_part(bucket, key, &upload_id, number, chunk)
+            })
+        })
+        .collect::<CloudResult<Vec<String>>>()
+        .and_then(|etags| {
+            retry_with_backoff(retry, || {
+                storage.complete_multipart_upload(bucket, key, &upload_id, &etags)
+            })
+        });
+    if uploaded.is_err() {
+        let _ = storage.abort_multipart_upload(bucket, key, &upload_id);
+    }
+    uploaded
+}
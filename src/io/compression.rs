@@ -37,6 +37,20 @@
 //! # }
 //! ```
 //!
+//! ### Explicit Codec and Level
+//! ```no_run
+//! use ironbeam::io::compression::{Compression, compressed_writer};
+//! use std::fs::File;
+//! use anyhow::Result;
+//! # fn main() -> Result<()> {
+//!
+//! // Compress with zstd at level 19 regardless of the file name
+//! let file = File::create("output.bin")?;
+//! let writer = compressed_writer(file, "output.bin", Compression::Zstd { level: 19 })?;
+//! # Ok(())
+//! # }
+//! ```
+//!
 //! ### Custom Codec Implementation
 //! ```
 //! use ironbeam::io::compression::CompressionCodec;
@@ -65,9 +79,25 @@
 //!
 //! ## Design Decisions
 //!
-//! ### Extension-First Detection
-//! File extensions are checked first for performance, falling back to magic bytes
-//! only when necessary. This avoids reading file headers in the common case.
+//! ### Content-Aware Detection
+//! Reads peek at the first bytes of the stream and match them against each codec's
+//! magic bytes, so the content wins over a misleading extension: a `.dat` file that
+//! holds gzip is decompressed, and a `.gz` file that was already inflated (e.g., by
+//! an HTTP client) is read as-is. The extension is only trusted on its own for
+//! codecs that declare no magic bytes.
+//!
+//! ### Concatenated Streams and Archives
+//! Gzip input may consist of several concatenated members (as produced by
+//! `cat a.gz b.gz` or by compressing shards independently); all members are read.
+//! With the `archive-tar` feature, `.tar`, `.tgz` and `.tar.<codec>` files are
+//! unpacked on the fly and the contents of their regular-file members are
+//! concatenated in archive order, so a tarball of JSONL shards reads like a single
+//! file. Members are joined byte-for-byte, so line-oriented shards should end with a
+//! newline.
+//!
+//! ### Explicit Write Compression
+//! Writers infer the codec from the extension by default. [`WriteOptions`] carries a
+//! [`Compression`] choice that overrides this, including the compression level.
 //!
 //! ### Pluggable Architecture
 //! The [`CompressionCodec`] trait allows users to implement custom codecs without
@@ -82,7 +112,7 @@
 //! When no compression features are enabled, the auto-detection functions become
 //! simple pass-through operations with minimal overhead.
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use std::io::{BufRead, BufReader, BufWriter, Read, Result as IoResult, Write};
 use std::path::Path;
 use std::sync::{Arc, RwLock};
@@ -243,10 +273,15 @@ fn detect_from_magic<R: BufRead>(reader: &mut R) -> Option<Arc<dyn CompressionCo
 /// Automatically detect and wrap a reader with decompression if needed.
 ///
 /// Detection strategy:
-/// 1. Check file path extension (fast path)
-/// 2. Fall back to magic byte detection if the extension is not recognized
+/// 1. Peek at the start of the stream and match registered magic bytes
+/// 2. Otherwise, use the codec named by the file extension if it has no magic bytes
+///    (an extension whose codec *does* have magic bytes is treated as a mislabel)
 /// 3. Return an unwrapped reader if no compression is detected
 ///
+/// Gzip streams made of several concatenated members are read in full. With the
+/// `archive-tar` feature, `.tar`, `.tgz` and `.tar.<codec>` paths are additionally
+/// unpacked and their regular-file members concatenated in archive order.
+///
 /// # Examples
 /// ```no_run
 /// use ironbeam::io::compression::auto_detect_reader;
@@ -268,20 +303,24 @@ pub fn auto_detect_reader<R: Read + 'static>(
     reader: R,
     path_hint: impl AsRef<Path>,
 ) -> Result<Box<dyn Read>> {
-    if let Some(codec) = detect_from_extension(&path_hint) {
-        return codec
-            .wrap_reader_dyn(Box::new(reader))
-            .with_context(|| format!("wrap reader with {} codec", codec.name()));
-    }
-
     let mut buf_reader = BufReader::new(reader);
-    if let Some(codec) = detect_from_magic(&mut buf_reader) {
-        return codec
+    let codec = detect_from_magic(&mut buf_reader).or_else(|| {
+        detect_from_extension(&path_hint).filter(|codec| codec.magic_bytes().is_none())
+    });
+
+    let reader: Box<dyn Read> = match codec {
+        Some(codec) => codec
             .wrap_reader_dyn(Box::new(buf_reader))
-            .with_context(|| format!("wrap reader with {} codec", codec.name()));
+            .with_context(|| format!("wrap reader with {} codec", codec.name()))?,
+        None => Box::new(buf_reader),
+    };
+
+    #[cfg(feature = "archive-tar")]
+    if is_tar_path(&path_hint) {
+        return Ok(Box::new(TarConcatReader::new(reader)));
     }
 
-    Ok(Box::new(buf_reader))
+    Ok(reader)
 }
 
 /// Automatically detect and wrap a writer with compression if needed.
@@ -319,6 +358,328 @@ pub fn auto_detect_writer<W: Write + 'static>(
     Ok(Box::new(BufWriter::new(writer)))
 }
 
+/// Compression applied by writers that accept [`WriteOptions`].
+///
+/// [`Compression::Auto`] keeps the default behavior of inferring the codec from the
+/// file extension. The other variants force a codec (and level) regardless of the
+/// file name; selecting a codec whose feature flag is disabled is an error at write
+/// time.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Compression {
+    /// Infer the codec from the file extension, as [`auto_detect_writer`] does.
+    #[default]
+    Auto,
+    /// Write uncompressed output, even if the extension names a codec.
+    None,
+    /// Gzip at `level` `0..=9` (feature: `compression-gzip`).
+    Gzip {
+        /// Compression level; 6 is the `gzip` default.
+        level: u32,
+    },
+    /// Zstd at `level` within zstd's supported range, typically `1..=22`
+    /// (feature: `compression-zstd`).
+    Zstd {
+        /// Compression level; 3 is the `zstd` default.
+        level: i32,
+    },
+    /// Bzip2 at `level` `1..=9` (feature: `compression-bzip2`).
+    Bzip2 {
+        /// Compression level (block size in 100k units); 9 is the `bzip2` default.
+        level: u32,
+    },
+    /// Xz at `level` `0..=9` (feature: `compression-xz`).
+    Xz {
+        /// Compression preset; 6 is the `xz` default.
+        level: u32,
+    },
+}
+
+/// Options shared by writers that take an explicit configuration, such as
+/// [`write_jsonl_vec_with`](crate::io::jsonl::write_jsonl_vec_with) and
+/// [`write_csv_vec_with`](crate::io::csv::write_csv_vec_with).
+///
+/// # Example
+/// ```
+/// use ironbeam::io::compression::{Compression, WriteOptions};
+///
+/// let opts = WriteOptions::default().with_compression(Compression::Gzip { level: 9 });
+/// assert_eq!(opts.compression, Compression::Gzip { level: 9 });
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct WriteOptions {
+    /// Output compression (default: [`Compression::Auto`]).
+    pub compression: Compression,
+    /// Create an empty `_SUCCESS` file next to the output once it is in place
+    /// (default: `false`). See [`crate::io::atomic`].
+    pub success_marker: bool,
+}
+
+impl WriteOptions {
+    /// Set the output compression.
+    #[must_use]
+    pub const fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Set whether to create a `_SUCCESS` marker next to the output once it is in place.
+    #[must_use]
+    pub const fn with_success_marker(mut self, success_marker: bool) -> Self {
+        self.success_marker = success_marker;
+        self
+    }
+}
+
+/// Wrap a writer with an explicitly chosen [`Compression`].
+///
+/// [`Compression::Auto`] defers to [`auto_detect_writer`] using `path_hint`; every
+/// other variant ignores the hint.
+///
+/// # Errors
+///
+/// Returns an error if the level is out of range for the codec, the codec's feature
+/// flag is disabled, or the encoder cannot be created.
+pub fn compressed_writer<W: Write + 'static>(
+    writer: W,
+    path_hint: impl AsRef<Path>,
+    compression: Compression,
+) -> Result<Box<dyn Write>> {
+    match compression {
+        Compression::Auto => auto_detect_writer(writer, path_hint),
+        Compression::None => Ok(Box::new(BufWriter::new(writer))),
+        Compression::Gzip { level } => gzip_writer(writer, level),
+        Compression::Zstd { level } => zstd_writer(writer, level),
+        Compression::Bzip2 { level } => bzip2_writer(writer, level),
+        Compression::Xz { level } => xz_writer(writer, level),
+    }
+}
+
+#[cfg(feature = "compression-gzip")]
+fn gzip_writer<W: Write + 'static>(writer: W, level: u32) -> Result<Box<dyn Write>> {
+    use flate2::write::GzEncoder;
+    if level > 9 {
+        bail!("gzip compression level must be in 0..=9, got {level}");
+    }
+    let encoder = GzEncoder::new(BufWriter::new(writer), flate2::Compression::new(level));
+    Ok(Box::new(encoder))
+}
+
+#[cfg(not(feature = "compression-gzip"))]
+fn gzip_writer<W: Write + 'static>(_writer: W, _level: u32) -> Result<Box<dyn Write>> {
+    bail!("the `compression-gzip` feature is not enabled")
+}
+
+#[cfg(feature = "compression-zstd")]
+fn zstd_writer<W: Write + 'static>(writer: W, level: i32) -> Result<Box<dyn Write>> {
+    let range = zstd::compression_level_range();
+    if !range.contains(&level) {
+        bail!(
+            "zstd compression level must be in {}..={}, got {level}",
+            range.start(),
+            range.end()
+        );
+    }
+    let encoder = Encoder::new(writer, level).context("create zstd encoder")?;
+    Ok(Box::new(encoder.auto_finish()))
+}
+
+#[cfg(not(feature = "compression-zstd"))]
+fn zstd_writer<W: Write + 'static>(_writer: W, _level: i32) -> Result<Box<dyn Write>> {
+    bail!("the `compression-zstd` feature is not enabled")
+}
+
+#[cfg(feature = "compression-bzip2")]
+fn bzip2_writer<W: Write + 'static>(writer: W, level: u32) -> Result<Box<dyn Write>> {
+    use bzip2::write::BzEncoder;
+    if !(1..=9).contains(&level) {
+        bail!("bzip2 compression level must be in 1..=9, got {level}");
+    }
+    let encoder = BzEncoder::new(BufWriter::new(writer), bzip2::Compression::new(level));
+    Ok(Box::new(encoder))
+}
+
+#[cfg(not(feature = "compression-bzip2"))]
+fn bzip2_writer<W: Write + 'static>(_writer: W, _level: u32) -> Result<Box<dyn Write>> {
+    bail!("the `compression-bzip2` feature is not enabled")
+}
+
+#[cfg(feature = "compression-xz")]
+fn xz_writer<W: Write + 'static>(writer: W, level: u32) -> Result<Box<dyn Write>> {
+    use xz2::write::XzEncoder;
+    if level > 9 {
+        bail!("xz compression level must be in 0..=9, got {level}");
+    }
+    Ok(Box::new(XzEncoder::new(BufWriter::new(writer), level)))
+}
+
+#[cfg(not(feature = "compression-xz"))]
+fn xz_writer<W: Write + 'static>(_writer: W, _level: u32) -> Result<Box<dyn Write>> {
+    bail!("the `compression-xz` feature is not enabled")
+}
+
+/// Compress `bytes` in memory with `compression`.
+///
+/// Used for on-disk engine state (checkpoints, spill files) that is encoded as one
+/// blob. [`Compression::Auto`] has no file name to go by and stores the bytes as-is.
+#[cfg(any(feature = "checkpointing", feature = "spilling"))]
+pub(crate) fn compress_to_vec(bytes: &[u8], compression: Compression) -> Result<Vec<u8>> {
+    let compression = match compression {
+        Compression::Auto | Compression::None => return Ok(bytes.to_vec()),
+        other => other,
+    };
+    let buf = SharedBuf::default();
+    let mut w = compressed_writer(buf.clone(), "", compression)?;
+    w.write_all(bytes).context("compress buffer")?;
+    w.flush().context("compress buffer")?;
+    // Encoders write their trailers when dropped.
+    drop(w);
+    Ok(buf.take())
+}
+
+/// Decompress an in-memory buffer written by [`compress_to_vec`], detecting the codec
+/// from its magic bytes. Uncompressed input is returned unchanged.
+#[cfg(any(feature = "checkpointing", feature = "spilling"))]
+pub(crate) fn decompress_to_vec(bytes: Vec<u8>) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    auto_detect_reader(std::io::Cursor::new(bytes), "")?
+        .read_to_end(&mut out)
+        .context("decompress buffer")?;
+    Ok(out)
+}
+
+/// A `'static` in-memory sink whose contents outlive the boxed writer wrapping it.
+#[cfg(any(feature = "checkpointing", feature = "spilling"))]
+#[derive(Clone, Default)]
+struct SharedBuf(Arc<std::sync::Mutex<Vec<u8>>>);
+
+#[cfg(any(feature = "checkpointing", feature = "spilling"))]
+impl SharedBuf {
+    fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut self.0.lock().unwrap())
+    }
+}
+
+#[cfg(any(feature = "checkpointing", feature = "spilling"))]
+impl Write for SharedBuf {
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Tar Archives
+// ============================================================================
+
+/// Whether `path` names a tar archive, optionally compressed (`.tar`, `.tgz`,
+/// `.tar.gz`, `.tar.zst`, ...).
+#[cfg(feature = "archive-tar")]
+fn is_tar_path(path: impl AsRef<Path>) -> bool {
+    let name = path.as_ref().to_string_lossy().to_lowercase();
+    name.ends_with(".tar")
+        || name.ends_with(".tgz")
+        || name
+            .rsplit_once('.')
+            .is_some_and(|(stem, _)| stem.ends_with(".tar"))
+}
+
+/// Streams the contents of every regular file in a tar archive, back to back.
+///
+/// Directories, links and metadata entries (PAX/GNU extension headers) are skipped,
+/// as are macOS `._*` resource-fork files.
+#[cfg(feature = "archive-tar")]
+struct TarConcatReader {
+    inner: Box<dyn Read>,
+    /// Bytes left in the current member's data.
+    remaining: u64,
+    /// Bytes to discard after the current entry (unread data plus block padding).
+    skip: u64,
+    done: bool,
+}
+
+#[cfg(feature = "archive-tar")]
+impl TarConcatReader {
+    const BLOCK: u64 = 512;
+
+    fn new(inner: Box<dyn Read>) -> Self {
+        Self {
+            inner,
+            remaining: 0,
+            skip: 0,
+            done: false,
+        }
+    }
+
+    /// Advance to the next regular-file member; returns `false` at the end of the archive.
+    fn next_member(&mut self) -> IoResult<bool> {
+        use std::io::{Error, ErrorKind, copy, sink};
+
+        loop {
+            let skip = std::mem::take(&mut self.skip);
+            if copy(&mut (&mut self.inner).take(skip), &mut sink())? < skip {
+                return Err(Error::new(ErrorKind::UnexpectedEof, "truncated tar entry"));
+            }
+
+            let mut block = [0u8; 512];
+            let mut filled = 0;
+            while filled < block.len() {
+                match self.inner.read(&mut block[filled..])? {
+                    0 => break,
+                    n => filled += n,
+                }
+            }
+            // A missing end-of-archive marker is tolerated, a torn header is not.
+            if filled == 0 || block.iter().all(|&b| b == 0) {
+                return Ok(false);
+            }
+            if filled < block.len() {
+                return Err(Error::new(ErrorKind::UnexpectedEof, "truncated tar header"));
+            }
+
+            let header = tar::Header::from_byte_slice(&block);
+            let size = header.entry_size()?;
+            let padding = size.next_multiple_of(Self::BLOCK) - size;
+            let path = header.path_bytes();
+            let name = path.rsplit(|&b| b == b'/').next().unwrap_or_default();
+            if header.entry_type().is_file() && !name.starts_with(b"._") {
+                self.remaining = size;
+                self.skip = padding;
+                return Ok(true);
+            }
+            self.skip = size + padding;
+        }
+    }
+}
+
+#[cfg(feature = "archive-tar")]
+impl Read for TarConcatReader {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        while self.remaining == 0 {
+            if self.done || !self.next_member()? {
+                self.done = true;
+                return Ok(0);
+            }
+        }
+        let cap = usize::try_from(self.remaining).map_or(buf.len(), |r| r.min(buf.len()));
+        let n = self.inner.read(&mut buf[..cap])?;
+        if n == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "truncated tar entry",
+            ));
+        }
+        self.remaining -= n as u64;
+        Ok(n)
+    }
+}
+
 // ============================================================================
 // Built-in Codec Implementations
 // ============================================================================
@@ -341,8 +702,9 @@ impl CompressionCodec for GzipCodec {
     }
 
     fn wrap_reader_dyn(&self, reader: Box<dyn Read>) -> IoResult<Box<dyn Read>> {
-        use flate2::read::GzDecoder;
-        Ok(Box::new(GzDecoder::new(reader)))
+        // Multi-member aware, so concatenated gzip files are read to the end.
+        use flate2::read::MultiGzDecoder;
+        Ok(Box::new(MultiGzDecoder::new(reader)))
     }
 
     fn wrap_writer_dyn(&self, writer: Box<dyn Write>) -> IoResult<Box<dyn Write>> {
@@ -392,7 +754,7 @@ impl CompressionCodec for Bzip2Codec {
     }
 
     fn magic_bytes(&self) -> Option<&[u8]> {
-        Some(&[0x42, 0x5a])
+        Some(b"BZh")
     }
 
     fn wrap_reader_dyn(&self, reader: Box<dyn Read>) -> IoResult<Box<dyn Read>> {
@@ -2,6 +2,11 @@
 //!
 //! This module provides:
 //! - **Typed vector I/O** with Serde: [`read_csv_vec`] and [`write_csv_vec`]
+//! - **Reader options**: [`CsvReadOptions`] (delimiter, quoting, comments, encoding,
+//!   malformed-row and schema drift policies) for [`read_csv_vec_with`] and
+//!   [`build_csv_shards_with`]
+//! - **Header alignment**: [`read_csv_header`] and [`read_csv_vec_aligned`], used by
+//!   glob reads to check and align headers across files
 //! - **Deterministic parallel writer**: [`write_csv_par`] (feature `parallel-io`)
 //! - **Streaming ingestion** by sharding rows: [`CsvShards`], [`build_csv_shards`], [`read_csv_range`]
 //! - **Execution runner integration**: [`CsvVecOps<T>`] implements [`VecOps`] over `CsvShards`
@@ -11,28 +16,308 @@
 //! - Sharding is **row-count-based** (header excluded), not byte-range-based.
 //! - The parallel writer preserves **deterministic final order** by writing shard
 //!   buffers in index order after parallel serialization.
+//! - Every raw record counts as a row for sharding, including malformed ones, so the
+//!   [`MalformedRowPolicy`] only affects what a range read returns.
 
 use crate::Partition;
+use crate::helpers::DeadLetter;
+use crate::io::compression::WriteOptions;
+use crate::io::drift::SchemaDriftPolicy;
 use crate::type_token::VecOps;
 use anyhow::Result;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 use std::any::Any;
+use std::fs::metadata;
 use std::marker::PhantomData;
 use std::path::PathBuf;
 use std::sync::Arc;
 
 #[cfg(feature = "io-csv")]
-use crate::io::compression::{auto_detect_reader, auto_detect_writer};
+use crate::io::atomic::{create_parent_dir, write_atomically, write_success_marker};
+#[cfg(feature = "io-csv")]
+use crate::io::compression::{auto_detect_reader, compressed_writer};
 #[cfg(feature = "io-csv")]
 use anyhow::Context;
 #[cfg(feature = "io-csv")]
-use csv::{ReaderBuilder, WriterBuilder};
+use csv::{ByteRecord, Reader, ReaderBuilder, StringRecord, Trim, WriterBuilder};
+#[cfg(feature = "io-csv")]
+use std::fs::File;
 #[cfg(feature = "io-csv")]
-use std::fs::{File, create_dir_all};
+use std::io::Read;
 #[cfg(feature = "io-csv")]
 use std::path::Path;
 
+/// Character encoding of a CSV file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CsvEncoding {
+    /// UTF-8. A field that is not valid UTF-8 makes its row malformed.
+    #[default]
+    Utf8,
+    /// ISO-8859-1 (Latin-1): every byte maps to the code point of the same value.
+    Latin1,
+}
+
+/// What a CSV reader does with a row it cannot parse or deserialize.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MalformedRowPolicy {
+    /// Fail the whole read with an error naming the row.
+    #[default]
+    Fail,
+    /// Drop the row silently.
+    Skip,
+    /// Drop the row from the data and return it as a [`DeadLetter`] holding the raw
+    /// row text and the error.
+    DeadLetter,
+}
+
+/// Options for reading CSV files.
+///
+/// The defaults match the plain readers: a header row, `,` delimiter, `"` quotes, no
+/// comments, a fixed column count, no trimming, UTF-8, failing on the first
+/// malformed row, and failing a glob read whose files' headers differ.
+///
+/// # Example
+/// ```
+/// use ironbeam::io::csv::{CsvEncoding, CsvReadOptions, MalformedRowPolicy};
+///
+/// let opts = CsvReadOptions::new()
+///     .with_delimiter(b';')
+///     .with_comment(Some(b'#'))
+///     .with_encoding(CsvEncoding::Latin1)
+///     .with_malformed_policy(MalformedRowPolicy::DeadLetter);
+/// assert!(opts.has_headers);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CsvReadOptions {
+    /// Whether the first row is a header.
+    pub has_headers: bool,
+    /// Field delimiter.
+    pub delimiter: u8,
+    /// Quote character.
+    pub quote: u8,
+    /// Lines starting with this byte are ignored.
+    pub comment: Option<u8>,
+    /// Allow rows with differing column counts.
+    pub flexible: bool,
+    /// Trim leading and trailing whitespace from headers and fields.
+    pub trim: bool,
+    /// Character encoding of the file.
+    pub encoding: CsvEncoding,
+    /// What to do with rows that fail to parse or deserialize.
+    pub on_malformed: MalformedRowPolicy,
+    /// What a glob read does when the files' headers differ.
+    pub on_drift: SchemaDriftPolicy,
+}
+
+impl Default for CsvReadOptions {
+    fn default() -> Self {
+        Self {
+            has_headers: true,
+            delimiter: b',',
+            quote: b'"',
+            comment: None,
+            flexible: false,
+            trim: false,
+            encoding: CsvEncoding::Utf8,
+            on_malformed: MalformedRowPolicy::Fail,
+            on_drift: SchemaDriftPolicy::Fail,
+        }
+    }
+}
+
+impl CsvReadOptions {
+    /// Create options with the default settings.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set whether the first row is a header.
+    #[must_use]
+    pub const fn with_headers(mut self, has_headers: bool) -> Self {
+        self.has_headers = has_headers;
+        self
+    }
+
+    /// Set the field delimiter, e.g. `b';'` or `b'\t'`.
+    #[must_use]
+    pub const fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Set the quote character.
+    #[must_use]
+    pub const fn with_quote(mut self, quote: u8) -> Self {
+        self.quote = quote;
+        self
+    }
+
+    /// Set the comment character; `None` disables comments.
+    #[must_use]
+    pub const fn with_comment(mut self, comment: Option<u8>) -> Self {
+        self.comment = comment;
+        self
+    }
+
+    /// Allow rows with differing column counts.
+    #[must_use]
+    pub const fn with_flexible(mut self, flexible: bool) -> Self {
+        self.flexible = flexible;
+        self
+    }
+
+    /// Trim whitespace around headers and fields.
+    #[must_use]
+    pub const fn with_trim(mut self, trim: bool) -> Self {
+        self.trim = trim;
+        self
+    }
+
+    /// Set the character encoding.
+    #[must_use]
+    pub const fn with_encoding(mut self, encoding: CsvEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Set the malformed-row policy.
+    #[must_use]
+    pub const fn with_malformed_policy(mut self, policy: MalformedRowPolicy) -> Self {
+        self.on_malformed = policy;
+        self
+    }
+
+    /// Set the schema drift policy for glob reads. Only files with a header row are
+    /// checked.
+    #[must_use]
+    pub const fn with_drift_policy(mut self, policy: SchemaDriftPolicy) -> Self {
+        self.on_drift = policy;
+        self
+    }
+
+    /// Open `path` (decompressing if needed) as a CSV reader configured by `self`.
+    #[cfg(feature = "io-csv")]
+    fn open(&self, path: &Path) -> Result<Reader<Box<dyn Read>>> {
+        let f = File::open(path).with_context(|| format!("open {}", path.display()))?;
+        let rdr = auto_detect_reader(f, path)
+            .with_context(|| format!("setup decompression for {}", path.display()))?;
+        Ok(ReaderBuilder::new()
+            .has_headers(self.has_headers)
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .comment(self.comment)
+            .flexible(self.flexible)
+            .trim(if self.trim { Trim::All } else { Trim::None })
+            .from_reader(rdr))
+    }
+
+    /// Decode a raw record into text.
+    #[cfg(feature = "io-csv")]
+    fn decode(&self, rec: &ByteRecord) -> Result<StringRecord> {
+        match self.encoding {
+            CsvEncoding::Utf8 => StringRecord::from_byte_record(rec.clone())
+                .map_err(|e| anyhow::anyhow!("invalid UTF-8: {}", e.utf8_error())),
+            CsvEncoding::Latin1 => Ok(rec
+                .iter()
+                .map(|field| field.iter().copied().map(char::from).collect::<String>())
+                .collect()),
+        }
+    }
+
+    /// Raw text of a record, for dead letters; undecodable bytes are replaced.
+    #[cfg(feature = "io-csv")]
+    fn raw_text(&self, rec: &ByteRecord) -> String {
+        let fields: Vec<String> = match self.decode(rec) {
+            Ok(decoded) => decoded.iter().map(str::to_string).collect(),
+            Err(_) => rec
+                .iter()
+                .map(|f| String::from_utf8_lossy(f).into_owned())
+                .collect(),
+        };
+        fields.join(&char::from(self.delimiter).to_string())
+    }
+}
+
+/// Read data rows `[start, end)` of the CSV at `path`, applying the malformed-row
+/// policy of `opts`. With `columns`, each row is rearranged by header name into that
+/// column order first, leaving columns the file lacks empty.
+#[cfg(feature = "io-csv")]
+fn read_rows<T: DeserializeOwned>(
+    path: &Path,
+    opts: &CsvReadOptions,
+    start: u64,
+    end: u64,
+    columns: Option<&[String]>,
+) -> Result<(Vec<T>, Vec<DeadLetter<String>>)> {
+    let mut rdr = opts.open(path)?;
+    let mut headers = if opts.has_headers {
+        let raw = rdr
+            .byte_headers()
+            .with_context(|| format!("read CSV header of {}", path.display()))?
+            .clone();
+        Some(opts.decode(&raw).context("decode CSV header")?)
+    } else {
+        None
+    };
+    // Target column -> index of that column in this file.
+    let layout: Option<Vec<Option<usize>>> = match (columns, &headers) {
+        (Some(columns), Some(file)) => Some(
+            columns
+                .iter()
+                .map(|c| file.iter().position(|h| h == c))
+                .collect(),
+        ),
+        (Some(_), None) => anyhow::bail!("aligning CSV columns by name requires a header row"),
+        (None, _) => None,
+    };
+    if let Some(columns) = columns {
+        headers = Some(StringRecord::from(columns.to_vec()));
+    }
+
+    let mut rows = Vec::new();
+    let mut malformed = Vec::new();
+    let mut rec = ByteRecord::new();
+    let mut i: u64 = 0;
+    while i < end {
+        let parsed = match rdr.read_byte_record(&mut rec) {
+            Ok(false) => break,
+            Ok(true) => opts.decode(&rec).and_then(|text| {
+                let text = match &layout {
+                    Some(layout) => layout
+                        .iter()
+                        .map(|i| i.and_then(|i| text.get(i)).unwrap_or(""))
+                        .collect(),
+                    None => text,
+                };
+                Ok(text.deserialize::<T>(headers.as_ref())?)
+            }),
+            Err(e) if e.is_io_error() => {
+                return Err(e).with_context(|| format!("read {}", path.display()));
+            }
+            Err(e) => Err(e.into()),
+        };
+        if i >= start {
+            match parsed {
+                Ok(v) => rows.push(v),
+                Err(e) => match opts.on_malformed {
+                    MalformedRowPolicy::Fail => {
+                        return Err(e).with_context(|| format!("parse CSV record #{}", i + 1));
+                    }
+                    MalformedRowPolicy::Skip => {}
+                    MalformedRowPolicy::DeadLetter => malformed.push(DeadLetter::new(
+                        opts.raw_text(&rec),
+                        format!("CSV record #{}: {e}", i + 1),
+                    )),
+                },
+            }
+        }
+        i += 1;
+    }
+    Ok((rows, malformed))
+}
+
 /// Read a CSV file into a typed `Vec<T>`.
 ///
 /// Rows are deserialized with Serde using `T: DeserializeOwned`.
@@ -41,6 +326,9 @@ use std::path::Path;
 ///   not deserialized into `T`.
 /// * Errors are annotated with row numbers for easier debugging.
 ///
+/// For other delimiters, encodings, or lenient handling of bad rows, see
+/// [`read_csv_vec_with`].
+///
 /// **Compression**: Automatically detects and decompresses gzip, zstd, bzip2, and xz
 /// formats based on file extension or magic bytes (when respective feature flags are enabled).
 ///
@@ -53,19 +341,67 @@ pub fn read_csv_vec<T: DeserializeOwned>(
     path: impl AsRef<Path>,
     has_headers: bool,
 ) -> Result<Vec<T>> {
+    let opts = CsvReadOptions::new().with_headers(has_headers);
+    read_csv_vec_with(path, &opts).map(|(rows, _)| rows)
+}
+
+/// Read a CSV file into a typed `Vec<T>` using [`CsvReadOptions`].
+///
+/// Returns the deserialized rows together with the rows diverted by
+/// [`MalformedRowPolicy::DeadLetter`]; the second vector is always empty under the
+/// other policies. Each [`DeadLetter`] holds the raw row text (fields re-joined with
+/// the delimiter) and an error naming the record number.
+///
+/// **Compression**: Same automatic detection as [`read_csv_vec`].
+///
+/// # Errors
+/// Returns an error if the file cannot be opened or read, if the header cannot be
+/// decoded, or, under [`MalformedRowPolicy::Fail`], if any row is malformed. When the
+/// `io-csv` feature is disabled, always returns an error.
+#[cfg(feature = "io-csv")]
+pub fn read_csv_vec_with<T: DeserializeOwned>(
+    path: impl AsRef<Path>,
+    opts: &CsvReadOptions,
+) -> Result<(Vec<T>, Vec<DeadLetter<String>>)> {
+    read_rows(path.as_ref(), opts, 0, u64::MAX, None)
+}
+
+/// Read a CSV file like [`read_csv_vec_with`], with each row's fields rearranged by
+/// header name into the order of `columns`. Columns the file does not have read as
+/// empty fields, which deserialize as `None` into `Option` fields; file columns not in
+/// `columns` are dropped. This is how glob reads apply
+/// [`SchemaDriftPolicy::AlignByName`].
+///
+/// # Errors
+/// Returns an error if `opts` has no header row, or for the reasons of
+/// [`read_csv_vec_with`]. When the `io-csv` feature is disabled, always returns an
+/// error.
+#[cfg(feature = "io-csv")]
+pub fn read_csv_vec_aligned<T: DeserializeOwned>(
+    path: impl AsRef<Path>,
+    opts: &CsvReadOptions,
+    columns: &[String],
+) -> Result<(Vec<T>, Vec<DeadLetter<String>>)> {
+    read_rows(path.as_ref(), opts, 0, u64::MAX, Some(columns))
+}
+
+/// Read the header row of the CSV at `path` as configured by `opts`.
+///
+/// # Errors
+/// Returns an error if the file cannot be opened or read or the header cannot be
+/// decoded. When the `io-csv` feature is disabled, always returns an error.
+#[cfg(feature = "io-csv")]
+pub fn read_csv_header(path: impl AsRef<Path>, opts: &CsvReadOptions) -> Result<Vec<String>> {
     let path = path.as_ref();
-    let f = File::open(path).with_context(|| format!("open {}", path.display()))?;
-    let rdr = auto_detect_reader(f, path)
-        .with_context(|| format!("setup decompression for {}", path.display()))?;
-    let mut rdr = ReaderBuilder::new()
-        .has_headers(has_headers)
-        .from_reader(rdr);
-    let mut out = Vec::<T>::new();
-    for (i, rec) in rdr.deserialize::<T>().enumerate() {
-        let v = rec.with_context(|| format!("parse CSV record #{}", i + 1))?;
-        out.push(v);
-    }
-    Ok(out)
+    let mut rdr = opts.open(path)?;
+    let raw = rdr
+        .byte_headers()
+        .with_context(|| format!("read CSV header of {}", path.display()))?
+        .clone();
+    let header = opts
+        .decode(&raw)
+        .with_context(|| format!("decode CSV header of {}", path.display()))?;
+    Ok(header.iter().map(str::to_string).collect())
 }
 
 /// Write a typed slice to a CSV file.
@@ -91,25 +427,84 @@ pub fn write_csv_vec<T: Serialize>(
     path: impl AsRef<Path>,
     has_headers: bool,
     data: &[T],
+) -> Result<usize> {
+    write_csv_vec_with(path, has_headers, data, &WriteOptions::default())
+}
+
+/// Write a typed slice to CSV using explicit [`WriteOptions`].
+///
+/// Like [`write_csv_vec`], but the output codec and level come from
+/// `opts.compression` instead of being inferred from the extension (unless it is
+/// [`Compression::Auto`](crate::io::compression::Compression::Auto)).
+///
+/// # Returns
+/// The number of rows written (i.e., `data.len()`).
+///
+/// # Errors
+/// Returns an error if the file/dirs cannot be created, the compression settings are
+/// invalid, or any row fails to serialize/flush. When the `io-csv` feature is
+/// disabled, always returns an error.
+#[cfg(feature = "io-csv")]
+pub fn write_csv_vec_with<T: Serialize>(
+    path: impl AsRef<Path>,
+    has_headers: bool,
+    data: &[T],
+    opts: &WriteOptions,
 ) -> Result<usize> {
     let path = path.as_ref();
-    if let Some(parent) = path.parent()
-        && !parent.as_os_str().is_empty()
-    {
-        create_dir_all(parent).with_context(|| format!("mkdir -p {}", parent.display()))?;
-    }
-    let f = File::create(path).with_context(|| format!("create {}", path.display()))?;
-    let w = auto_detect_writer(f, path)
-        .with_context(|| format!("setup compression for {}", path.display()))?;
-    let mut wtr = WriterBuilder::new().has_headers(has_headers).from_writer(w);
-    for (i, row) in data.iter().enumerate() {
-        wtr.serialize(row)
-            .with_context(|| format!("serialize CSV row #{}", i + 1))?;
-    }
-    wtr.flush()?;
+    create_parent_dir(path)?;
+    write_atomically(path, |f| {
+        let w = compressed_writer(f, path, opts.compression)
+            .with_context(|| format!("setup compression for {}", path.display()))?;
+        let mut wtr = WriterBuilder::new().has_headers(has_headers).from_writer(w);
+        for (i, row) in data.iter().enumerate() {
+            wtr.serialize(row)
+                .with_context(|| format!("serialize CSV row #{}", i + 1))?;
+        }
+        wtr.flush()?;
+        Ok(())
+    })?;
+    if opts.success_marker {
+        write_success_marker(path)?;
+    }
     Ok(data.len())
 }
 
+/// Serialize `data` as CSV into a buffer, as one part of a file written by
+/// [`PCollection::write_csv`](crate::PCollection::write_csv).
+///
+/// Returns the length in bytes of the buffer's leading header row (`0` without headers
+/// or rows) and the buffer.
+///
+/// # Errors
+/// Returns an error if any row fails to serialize.
+#[cfg(feature = "io-csv")]
+pub(crate) fn encode_csv_part<T: Serialize>(
+    data: &[T],
+    has_headers: bool,
+) -> Result<(usize, Vec<u8>)> {
+    fn encode<T: Serialize>(rows: &[T], has_headers: bool) -> Result<Vec<u8>> {
+        let mut wtr = WriterBuilder::new()
+            .has_headers(has_headers)
+            .from_writer(Vec::new());
+        for (i, row) in rows.iter().enumerate() {
+            wtr.serialize(row)
+                .with_context(|| format!("serialize CSV row #{}", i + 1))?;
+        }
+        wtr.into_inner()
+            .map_err(|e| anyhow::anyhow!("flush CSV buffer: {}", e.error()))
+    }
+
+    let header = match data.first() {
+        Some(first) if has_headers => {
+            let first = std::slice::from_ref(first);
+            encode(first, true)?.len() - encode(first, false)?.len()
+        }
+        _ => 0,
+    };
+    Ok((header, encode(data, has_headers)?))
+}
+
 /// Sharding metadata for streaming CSV ingestion.
 ///
 /// The CSV is split into contiguous row ranges (start-inclusive, end-exclusive),
@@ -124,8 +519,8 @@ pub struct CsvShards {
     pub ranges: Vec<(u64, u64)>,
     /// Total number of data rows (excluding header).
     pub total_rows: u64,
-    /// Whether the source has a header row.
-    pub has_headers: bool,
+    /// Options used to count and read rows.
+    pub options: CsvReadOptions,
 }
 
 /// Build [`CsvShards`] by scanning row count and slicing into `rows_per_shard`.
@@ -147,25 +542,48 @@ pub fn build_csv_shards(
     path: impl AsRef<Path>,
     has_headers: bool,
     rows_per_shard: usize,
+) -> Result<CsvShards> {
+    let opts = CsvReadOptions::new().with_headers(has_headers);
+    build_csv_shards_with(path, &opts, rows_per_shard)
+}
+
+/// Like [`build_csv_shards`], but counts and later reads rows with `opts`.
+///
+/// Malformed rows are counted like any other row; the options' policy applies when
+/// each range is read.
+///
+/// # Errors
+/// Returns an error if the file cannot be opened or read. When the `io-csv` feature
+/// is disabled, always returns an error.
+///
+/// # Panics
+/// If the shard calculation overflows.
+#[cfg(feature = "io-csv")]
+pub fn build_csv_shards_with(
+    path: impl AsRef<Path>,
+    opts: &CsvReadOptions,
+    rows_per_shard: usize,
 ) -> Result<CsvShards> {
     let path = path.as_ref().to_path_buf();
-    let f = File::open(&path).with_context(|| format!("open {}", path.display()))?;
-    let rdr = auto_detect_reader(f, &path)
-        .with_context(|| format!("setup decompression for {}", path.display()))?;
-    let mut rdr = ReaderBuilder::new()
-        .has_headers(has_headers)
-        .from_reader(rdr);
+    let mut rdr = opts.open(&path)?;
     let mut total: u64 = 0;
-    let it = rdr.records();
-    for _ in it {
-        total += 1;
+    let mut rec = ByteRecord::new();
+    loop {
+        match rdr.read_byte_record(&mut rec) {
+            Ok(false) => break,
+            Ok(true) => total += 1,
+            Err(e) if e.is_io_error() => {
+                return Err(e).with_context(|| format!("read {}", path.display()));
+            }
+            Err(_) => total += 1,
+        }
     }
     if total == 0 {
         return Ok(CsvShards {
             path,
             ranges: vec![],
             total_rows: 0,
-            has_headers,
+            options: opts.clone(),
         });
     }
     let rps = rows_per_shard.max(1) as u64;
@@ -180,13 +598,15 @@ pub fn build_csv_shards(
         path,
         ranges,
         total_rows: total,
-        has_headers,
+        options: opts.clone(),
     })
 }
 
 /// Read a single shard (row range) from a CSV described by [`CsvShards`].
 ///
 /// `start` and `end` are row indices into the data region (excluding header).
+/// Malformed rows are handled by the shards' [`MalformedRowPolicy`]; dead letters
+/// are dropped here, see [`read_csv_range_with`] to keep them.
 ///
 /// **Compression**: Automatically detects and decompresses compressed files. Note that
 /// compressed streams don't support seeking, so the entire file is decompressed from
@@ -202,24 +622,22 @@ pub fn read_csv_range<T: DeserializeOwned>(
     start: u64,
     end: u64,
 ) -> Result<Vec<T>> {
-    let f = File::open(&src.path).with_context(|| format!("open {}", src.path.display()))?;
-    let rdr = auto_detect_reader(f, &src.path)
-        .with_context(|| format!("setup decompression for {}", src.path.display()))?;
-    let mut rdr = ReaderBuilder::new()
-        .has_headers(src.has_headers)
-        .from_reader(rdr);
-    let mut out = Vec::<T>::new();
-    for (i, rec) in rdr.deserialize::<T>().enumerate() {
-        let i = i as u64;
-        if i < start {
-            continue;
-        }
-        if i >= end {
-            break;
-        }
-        out.push(rec?);
-    }
-    Ok(out)
+    read_csv_range_with(src, start, end).map(|(rows, _)| rows)
+}
+
+/// Read a single shard like [`read_csv_range`], also returning the rows diverted by
+/// [`MalformedRowPolicy::DeadLetter`].
+///
+/// # Errors
+/// See [`read_csv_range`]. When the `io-csv` feature is disabled, always returns an
+/// error.
+#[cfg(feature = "io-csv")]
+pub fn read_csv_range_with<T: DeserializeOwned>(
+    src: &CsvShards,
+    start: u64,
+    end: u64,
+) -> Result<(Vec<T>, Vec<DeadLetter<String>>)> {
+    read_rows(&src.path, &src.options, start, end, None)
 }
 
 /// `VecOps` adapter for streaming CSV via [`CsvShards`].
@@ -250,6 +668,12 @@ where
         usize::try_from(s.total_rows).ok()
     }
 
+    fn byte_size_hint(&self, data: &dyn Any) -> Option<usize> {
+        let s = data.downcast_ref::<CsvShards>()?;
+        let meta = metadata(&s.path).ok()?;
+        usize::try_from(meta.len()).ok()
+    }
+
     fn split(&self, data: &dyn Any, _n: usize) -> Option<Vec<Partition>> {
         let s = data.downcast_ref::<CsvShards>()?;
         let mut parts = Vec::<Partition>::with_capacity(s.ranges.len());
@@ -267,6 +691,46 @@ where
     }
 }
 
+/// `VecOps` adapter yielding the rows of [`CsvShards`] that failed to parse as `T`.
+///
+/// Pairs with [`CsvVecOps`] when the shards use [`MalformedRowPolicy::DeadLetter`]:
+/// each shard is read again and only its [`DeadLetter`] rows are kept. The count is
+/// unknown until the file is read, so `len` returns `None`.
+pub struct CsvDeadLetterVecOps<T>(PhantomData<T>);
+
+impl<T> CsvDeadLetterVecOps<T> {
+    /// Construct an `Arc` to the adapter.
+    #[must_use]
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self(PhantomData))
+    }
+}
+
+impl<T> VecOps for CsvDeadLetterVecOps<T>
+where
+    T: DeserializeOwned + Send + Sync + 'static,
+{
+    fn len(&self, _data: &dyn Any) -> Option<usize> {
+        None
+    }
+
+    fn split(&self, data: &dyn Any, _n: usize) -> Option<Vec<Partition>> {
+        let s = data.downcast_ref::<CsvShards>()?;
+        let mut parts = Vec::<Partition>::with_capacity(s.ranges.len());
+        for &(start, end) in &s.ranges {
+            let (_, bad) = read_csv_range_with::<T>(s, start, end).ok()?;
+            parts.push(Box::new(bad) as Partition);
+        }
+        Some(parts)
+    }
+
+    fn clone_any(&self, data: &dyn Any) -> Option<Partition> {
+        let s = data.downcast_ref::<CsvShards>()?;
+        let (_, bad) = read_csv_range_with::<T>(s, 0, s.total_rows).ok()?;
+        Some(Box::new(bad) as Partition)
+    }
+}
+
 /// Convenience wrapper that accepts `&Vec<T>` for writing.
 ///
 /// Equivalent to `write_csv_vec(path, has_headers, data.as_slice())`.
@@ -319,7 +783,7 @@ pub fn write_csv_par<T: Serialize + Sync>(
     let path = path.as_ref();
 
     if n == 0 {
-        let _ = File::create(path).with_context(|| format!("create {}", path.display()))?;
+        write_atomically(path, |_| Ok(()))?;
         return Ok(0);
     }
 
@@ -349,11 +813,13 @@ pub fn write_csv_par<T: Serialize + Sync>(
 
     buffers.sort_by_key(|(idx, _)| *idx);
 
-    let mut file = File::create(path).with_context(|| format!("create {}", path.display()))?;
-    for (_, buf) in buffers {
-        file.write_all(&buf)?;
-    }
-    file.flush()?;
+    write_atomically(path, |mut file| {
+        for (_, buf) in buffers {
+            file.write_all(&buf)?;
+        }
+        file.flush()?;
+        Ok(())
+    })?;
 
     Ok(n)
 }
@@ -388,6 +854,18 @@ fn split_ranges(len: usize, parts: usize) -> Vec<(usize, usize, usize)> {
 // When `io-csv` is off, the functions above are not compiled. These stubs keep
 // the public ABI identical and fail at runtime instead.
 
+/// Stub returned when the `io-csv` feature is disabled.
+///
+/// # Errors
+/// Always returns an error: the `io-csv` feature is not enabled.
+#[cfg(not(feature = "io-csv"))]
+pub(crate) fn encode_csv_part<T: Serialize>(
+    _data: &[T],
+    _has_headers: bool,
+) -> Result<(usize, Vec<u8>)> {
+    anyhow::bail!("the `io-csv` feature is not enabled")
+}
+
 /// Stub returned when the `io-csv` feature is disabled.
 ///
 /// # Errors
@@ -400,6 +878,43 @@ pub fn read_csv_vec<T: DeserializeOwned>(
     anyhow::bail!("the `io-csv` feature is not enabled")
 }
 
+/// Stub returned when the `io-csv` feature is disabled.
+///
+/// # Errors
+/// Always returns an error: the `io-csv` feature is not enabled.
+#[cfg(not(feature = "io-csv"))]
+pub fn read_csv_vec_with<T: DeserializeOwned>(
+    _path: impl AsRef<std::path::Path>,
+    _opts: &CsvReadOptions,
+) -> Result<(Vec<T>, Vec<DeadLetter<String>>)> {
+    anyhow::bail!("the `io-csv` feature is not enabled")
+}
+
+/// Stub returned when the `io-csv` feature is disabled.
+///
+/// # Errors
+/// Always returns an error: the `io-csv` feature is not enabled.
+#[cfg(not(feature = "io-csv"))]
+pub fn read_csv_vec_aligned<T: DeserializeOwned>(
+    _path: impl AsRef<std::path::Path>,
+    _opts: &CsvReadOptions,
+    _columns: &[String],
+) -> Result<(Vec<T>, Vec<DeadLetter<String>>)> {
+    anyhow::bail!("the `io-csv` feature is not enabled")
+}
+
+/// Stub returned when the `io-csv` feature is disabled.
+///
+/// # Errors
+/// Always returns an error: the `io-csv` feature is not enabled.
+#[cfg(not(feature = "io-csv"))]
+pub fn read_csv_header(
+    _path: impl AsRef<std::path::Path>,
+    _opts: &CsvReadOptions,
+) -> Result<Vec<String>> {
+    anyhow::bail!("the `io-csv` feature is not enabled")
+}
+
 /// Stub returned when the `io-csv` feature is disabled.
 ///
 /// # Errors
@@ -413,6 +928,20 @@ pub fn write_csv_vec<T: Serialize>(
     anyhow::bail!("the `io-csv` feature is not enabled")
 }
 
+/// Stub returned when the `io-csv` feature is disabled.
+///
+/// # Errors
+/// Always returns an error: the `io-csv` feature is not enabled.
+#[cfg(not(feature = "io-csv"))]
+pub fn write_csv_vec_with<T: Serialize>(
+    _path: impl AsRef<std::path::Path>,
+    _has_headers: bool,
+    _data: &[T],
+    _opts: &WriteOptions,
+) -> Result<usize> {
+    anyhow::bail!("the `io-csv` feature is not enabled")
+}
+
 /// Stub returned when the `io-csv` feature is disabled.
 ///
 /// # Errors
@@ -426,6 +955,19 @@ pub fn build_csv_shards(
     anyhow::bail!("the `io-csv` feature is not enabled")
 }
 
+/// Stub returned when the `io-csv` feature is disabled.
+///
+/// # Errors
+/// Always returns an error: the `io-csv` feature is not enabled.
+#[cfg(not(feature = "io-csv"))]
+pub fn build_csv_shards_with(
+    _path: impl AsRef<std::path::Path>,
+    _opts: &CsvReadOptions,
+    _rows_per_shard: usize,
+) -> Result<CsvShards> {
+    anyhow::bail!("the `io-csv` feature is not enabled")
+}
+
 /// Stub returned when the `io-csv` feature is disabled.
 ///
 /// # Errors
@@ -439,6 +981,19 @@ pub fn read_csv_range<T: DeserializeOwned>(
     anyhow::bail!("the `io-csv` feature is not enabled")
 }
 
+/// Stub returned when the `io-csv` feature is disabled.
+///
+/// # Errors
+/// Always returns an error: the `io-csv` feature is not enabled.
+#[cfg(not(feature = "io-csv"))]
+pub fn read_csv_range_with<T: DeserializeOwned>(
+    _src: &CsvShards,
+    _start: u64,
+    _end: u64,
+) -> Result<(Vec<T>, Vec<DeadLetter<String>>)> {
+    anyhow::bail!("the `io-csv` feature is not enabled")
+}
+
 /// Stub returned when the `io-csv` feature is disabled.
 ///
 /// # Errors
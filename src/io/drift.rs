@@ -0,0 +1,204 @@
+//! Schema drift detection across the files of a glob read.
+//!
+//! Files matched by one glob pattern are read as a single collection, so they are
+//! expected to share a schema: the same CSV header, or the same top-level JSON keys.
+//! When a producer adds a column, drops one, or reorders its header, reading the files
+//! together can put values under the wrong fields without any error. [`check_drift`]
+//! compares every file's columns against the first file's before any row is read and,
+//! according to a [`SchemaDriftPolicy`], either fails with a [`SchemaDriftError`]
+//! describing each drifted file or returns the column list to align all rows to.
+//!
+//! [`read_csv_with`](crate::read_csv_with) applies the policy set on
+//! [`CsvReadOptions`](crate::io::csv::CsvReadOptions) (failing by default), and
+//! [`read_jsonl_checked`](crate::read_jsonl_checked) applies one to JSONL files.
+//!
+//! # Example
+//! ```
+//! use ironbeam::io::drift::{check_drift, SchemaDriftError, SchemaDriftPolicy};
+//! use std::path::PathBuf;
+//!
+//! let cols = |c: &[&str]| c.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+//! let schemas = vec![
+//!     (PathBuf::from("a.csv"), cols(&["id", "name"])),
+//!     (PathBuf::from("b.csv"), cols(&["name", "id", "email"])),
+//! ];
+//!
+//! let err = check_drift(&schemas, SchemaDriftPolicy::Fail).unwrap_err();
+//! let drift = err.downcast_ref::<SchemaDriftError>().unwrap();
+//! assert_eq!(drift.drifts[0].added, vec!["email"]);
+//! assert!(drift.drifts[0].reordered);
+//!
+//! let aligned = check_drift(&schemas, SchemaDriftPolicy::AlignByName).unwrap();
+//! assert_eq!(aligned, Some(cols(&["id", "name", "email"])));
+//! ```
+
+use anyhow::{Result, bail};
+use std::collections::HashSet;
+use std::fmt;
+use std::path::PathBuf;
+
+/// What a glob read does when its files do not share a schema.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SchemaDriftPolicy {
+    /// Fail before reading any row with a [`SchemaDriftError`] if any file's columns
+    /// differ from the first file's: added, missing, or in a different order.
+    #[default]
+    Fail,
+    /// Match columns by name. Files may reorder, add or omit columns; every row is
+    /// read against the union of all files' columns (the first file's in order, then
+    /// new ones as they appear), with a column a file lacks read as empty.
+    AlignByName,
+    /// Skip the check and read each file on its own terms.
+    Ignore,
+}
+
+/// How one file's columns differ from the reference file's.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaDrift {
+    /// The drifted file.
+    pub path: PathBuf,
+    /// Columns of this file that the reference file does not have, in file order.
+    pub added: Vec<String>,
+    /// Columns of the reference file that this file does not have, in reference order.
+    pub missing: Vec<String>,
+    /// Whether the columns both files have appear in a different order.
+    pub reordered: bool,
+}
+
+impl fmt::Display for SchemaDrift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if !self.added.is_empty() {
+            parts.push(format!("added [{}]", self.added.join(", ")));
+        }
+        if !self.missing.is_empty() {
+            parts.push(format!("missing [{}]", self.missing.join(", ")));
+        }
+        if self.reordered {
+            parts.push("reordered".to_string());
+        }
+        write!(f, "{}: {}", self.path.display(), parts.join(", "))
+    }
+}
+
+/// Files of a glob read whose columns differ from the first file's.
+///
+/// Returned (inside an [`anyhow::Error`]) under [`SchemaDriftPolicy::Fail`]; recover
+/// it with `err.downcast_ref::<SchemaDriftError>()`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SchemaDriftError {
+    /// The first file, whose columns the others are compared against.
+    pub reference: PathBuf,
+    /// The reference file's columns.
+    pub columns: Vec<String>,
+    /// One entry per drifted file, in read order.
+    pub drifts: Vec<SchemaDrift>,
+}
+
+impl fmt::Display for SchemaDriftError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "schema drift in {} file(s) against {} [{}]",
+            self.drifts.len(),
+            self.reference.display(),
+            self.columns.join(", ")
+        )?;
+        for drift in &self.drifts {
+            write!(f, "; {drift}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for SchemaDriftError {}
+
+/// Compare `columns` of the file at `path` with the `reference` columns. Returns
+/// `None` if they are identical.
+#[must_use]
+pub fn compare_columns(
+    reference: &[String],
+    path: impl Into<PathBuf>,
+    columns: &[String],
+) -> Option<SchemaDrift> {
+    if reference == columns {
+        return None;
+    }
+    let in_reference: HashSet<&String> = reference.iter().collect();
+    let in_file: HashSet<&String> = columns.iter().collect();
+    let added = columns
+        .iter()
+        .filter(|c| !in_reference.contains(c))
+        .cloned()
+        .collect();
+    let missing = reference
+        .iter()
+        .filter(|c| !in_file.contains(c))
+        .cloned()
+        .collect();
+    let shared_in_file = columns.iter().filter(|c| in_reference.contains(c));
+    let shared_in_reference = reference.iter().filter(|c| in_file.contains(c));
+    Some(SchemaDrift {
+        path: path.into(),
+        added,
+        missing,
+        reordered: !shared_in_file.eq(shared_in_reference),
+    })
+}
+
+/// Check the columns of each `(path, columns)` file against the first under `policy`.
+///
+/// Returns `None` when rows can be read as they are: the policy is
+/// [`SchemaDriftPolicy::Ignore`], or every file has the reference columns in the
+/// reference order. Under [`SchemaDriftPolicy::AlignByName`] with drift, returns the
+/// union of all columns to align each file's rows to.
+///
+/// # Errors
+/// Returns a [`SchemaDriftError`] under [`SchemaDriftPolicy::Fail`] if any file
+/// drifts, or an error under [`SchemaDriftPolicy::AlignByName`] if a drifted set of
+/// files repeats a column name within one file, since such columns cannot be matched
+/// by name.
+pub fn check_drift(
+    schemas: &[(PathBuf, Vec<String>)],
+    policy: SchemaDriftPolicy,
+) -> Result<Option<Vec<String>>> {
+    let Some(((reference, columns), rest)) = schemas.split_first() else {
+        return Ok(None);
+    };
+    if policy == SchemaDriftPolicy::Ignore {
+        return Ok(None);
+    }
+    let drifts: Vec<SchemaDrift> = rest
+        .iter()
+        .filter_map(|(path, cols)| compare_columns(columns, path, cols))
+        .collect();
+    if drifts.is_empty() {
+        return Ok(None);
+    }
+    if policy == SchemaDriftPolicy::Fail {
+        return Err(SchemaDriftError {
+            reference: reference.clone(),
+            columns: columns.clone(),
+            drifts,
+        }
+        .into());
+    }
+
+    let mut union = Vec::new();
+    let mut seen = HashSet::new();
+    for (path, cols) in schemas {
+        let mut in_file = HashSet::new();
+        for column in cols {
+            if !in_file.insert(column) {
+                bail!(
+                    "{} has more than one column named {column:?}; columns cannot be aligned by name",
+                    path.display()
+                );
+            }
+            if seen.insert(column) {
+                union.push(column.clone());
+            }
+        }
+    }
+    Ok(Some(union))
+}
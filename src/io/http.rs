@@ -0,0 +1,447 @@
+//! JSON REST sources and sinks over HTTP.
+//!
+//! This module provides:
+//! - **Paginated reads**: [`read_http_json_vec`] follows a [`Pagination`] scheme through
+//!   an API and deserializes every record of every page
+//! - **Batched writes**: [`write_http_json_vec`] POSTs records as JSON arrays,
+//!   [`HttpSinkConfig::batch_size`] at a time
+//!
+//! [`read_http_json`](crate::read_http_json) and
+//! [`PCollection::write_http_json`](crate::PCollection::write_http_json) wrap these for
+//! pipelines.
+//!
+//! # Retries and rate limiting
+//! Every request is retried with [`retry_with_backoff`] under the config's
+//! [`RetryConfig`]. Responses are mapped to [`ErrorKind`]s so that only transient
+//! failures are retried: connection errors, `408`, `429`, and `5xx` statuses. An
+//! optional rate limit spaces requests, retries included.
+//!
+//! # Feature gating
+//! This module only exists when the `io-http` feature is enabled, since it pulls in an
+//! HTTP client with TLS support.
+//!
+//! ```no_run
+//! use ironbeam::io::http::{HttpSourceConfig, Pagination, read_http_json_vec};
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Issue {
+//!     number: u64,
+//!     title: String,
+//! }
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let config = HttpSourceConfig::new("https://api.github.com/repos/rust-lang/rust/issues")
+//!     .with_pagination(Pagination::LinkHeader)
+//!     .with_header("User-Agent", "ironbeam")
+//!     .with_rate_limit(5.0)
+//!     .with_max_pages(10);
+//! let issues: Vec<Issue> = read_http_json_vec(&config)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::helpers::inference::RateLimiter;
+use crate::io::cloud::traits::{CloudIOError, CloudResult, ErrorKind};
+use crate::io::cloud::utils::{RetryConfig, retry_with_backoff};
+use anyhow::{Context, Result, bail, ensure};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::time::Duration;
+
+/// Timeout of a single request, connection included.
+pub const HTTP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Default number of records per POST of [`write_http_json_vec`].
+pub const HTTP_BATCH_SIZE: usize = 100;
+
+/// How [`read_http_json_vec`] walks through the pages of an API.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Pagination {
+    /// A single request.
+    #[default]
+    None,
+    /// Send `param=start`, `start + 1`, … until a page has no records.
+    PageNumber { param: String, start: u64 },
+    /// Send `offset_param=0`, `limit`, `2 * limit`, … with `limit_param=limit`, until a
+    /// page has fewer than `limit` records.
+    Offset {
+        offset_param: String,
+        limit_param: String,
+        limit: u64,
+    },
+    /// Read the next page's cursor from each response at the JSON pointer `next` (e.g.
+    /// `/meta/next_cursor`) and send it as `param`, until the cursor is missing, `null`,
+    /// or empty.
+    Cursor { param: String, next: String },
+    /// Follow the `rel="next"` URL of each response's `Link` header, as GitHub's API
+    /// does, until there is none.
+    LinkHeader,
+}
+
+/// Where and how [`read_http_json_vec`] reads records.
+#[derive(Debug, Clone)]
+pub struct HttpSourceConfig {
+    /// URL of the first page. Pagination parameters are appended to its query.
+    pub url: String,
+    /// How to reach the following pages (default: [`Pagination::None`]).
+    pub pagination: Pagination,
+    /// Headers sent with every request, e.g. `Authorization`.
+    pub headers: Vec<(String, String)>,
+    /// Maximum number of requests started per second, retries included (default:
+    /// unlimited).
+    pub rate_limit: Option<f64>,
+    /// JSON pointer to the array of records in each page, e.g. `/data` (default: the
+    /// page itself is the array).
+    pub records: Option<String>,
+    /// Stop after this many pages (default: no limit).
+    pub max_pages: Option<usize>,
+    /// Retry policy for each request (default: [`RetryConfig::default`]).
+    pub retry: RetryConfig,
+}
+
+impl HttpSourceConfig {
+    /// Read a single page from `url`.
+    #[must_use]
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            pagination: Pagination::None,
+            headers: Vec::new(),
+            rate_limit: None,
+            records: None,
+            max_pages: None,
+            retry: RetryConfig::default(),
+        }
+    }
+
+    /// Set the pagination scheme.
+    #[must_use]
+    pub fn with_pagination(mut self, pagination: Pagination) -> Self {
+        self.pagination = pagination;
+        self
+    }
+
+    /// Add a header sent with every request.
+    #[must_use]
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Limit the number of requests started per second.
+    #[must_use]
+    pub const fn with_rate_limit(mut self, max_per_second: f64) -> Self {
+        self.rate_limit = Some(max_per_second);
+        self
+    }
+
+    /// Read the records of each page from the array at JSON pointer `pointer`.
+    #[must_use]
+    pub fn with_records(mut self, pointer: impl Into<String>) -> Self {
+        self.records = Some(pointer.into());
+        self
+    }
+
+    /// Stop after `max_pages` pages.
+    #[must_use]
+    pub const fn with_max_pages(mut self, max_pages: usize) -> Self {
+        self.max_pages = Some(max_pages);
+        self
+    }
+
+    /// Set the retry policy.
+    #[must_use]
+    pub const fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+}
+
+/// Where and how [`write_http_json_vec`] sends records.
+#[derive(Debug, Clone)]
+pub struct HttpSinkConfig {
+    /// URL every batch is POSTed to.
+    pub url: String,
+    /// Headers sent with every request, e.g. `Authorization`.
+    pub headers: Vec<(String, String)>,
+    /// Number of records per request (default: [`HTTP_BATCH_SIZE`]).
+    pub batch_size: usize,
+    /// Maximum number of requests started per second, retries included (default:
+    /// unlimited).
+    pub rate_limit: Option<f64>,
+    /// Retry policy for each request (default: [`RetryConfig::default`]).
+    pub retry: RetryConfig,
+}
+
+impl HttpSinkConfig {
+    /// POST records to `url` in batches of [`HTTP_BATCH_SIZE`].
+    #[must_use]
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            headers: Vec::new(),
+            batch_size: HTTP_BATCH_SIZE,
+            rate_limit: None,
+            retry: RetryConfig::default(),
+        }
+    }
+
+    /// Add a header sent with every request.
+    #[must_use]
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Set the number of records per request.
+    #[must_use]
+    pub const fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Limit the number of requests started per second.
+    #[must_use]
+    pub const fn with_rate_limit(mut self, max_per_second: f64) -> Self {
+        self.rate_limit = Some(max_per_second);
+        self
+    }
+
+    /// Set the retry policy.
+    #[must_use]
+    pub const fn with_retry(mut self, retry: RetryConfig) -> Self {
+        self.retry = retry;
+        self
+    }
+}
+
+/// Read every record of every page described by `config`.
+///
+/// # Errors
+/// Returns an error if a request still fails after its retries, a page is not JSON or
+/// has no array of records where expected, or a record fails to deserialize.
+pub fn read_http_json_vec<T: DeserializeOwned>(config: &HttpSourceConfig) -> Result<Vec<T>> {
+    let agent = agent();
+    let mut limiter = RateLimiter::new(config.rate_limit);
+    let mut records = Vec::new();
+    let mut url = config.url.clone();
+    let mut cursor: Option<String> = None;
+
+    for page in 0usize.. {
+        if config.max_pages.is_some_and(|max| page >= max) {
+            break;
+        }
+        let query = page_query(&config.pagination, page, cursor.as_deref());
+        let (mut body, next_link) = retry_with_backoff(&config.retry, || {
+            limiter.wait();
+            get(&agent, &url, &config.headers, &query)
+        })
+        .with_context(|| format!("read_http_json: GET {url} (page {page})"))?;
+
+        cursor = match &config.pagination {
+            Pagination::Cursor { next, .. } => next_cursor(&body, next),
+            _ => None,
+        };
+        let page_records = take_records(&mut body, config.records.as_deref())
+            .with_context(|| format!("read_http_json: page {page} of {url}"))?;
+        let count = page_records.len() as u64;
+        for (i, record) in page_records.into_iter().enumerate() {
+            records.push(
+                serde_json::from_value(record)
+                    .with_context(|| format!("read_http_json: page {page}, record #{i}"))?,
+            );
+        }
+
+        match &config.pagination {
+            Pagination::None => break,
+            Pagination::PageNumber { .. } if count == 0 => break,
+            Pagination::Offset { limit, .. } if count < *limit => break,
+            Pagination::Cursor { .. } if cursor.is_none() => break,
+            Pagination::LinkHeader => match next_link {
+                Some(next) => url = next,
+                None => break,
+            },
+            _ => {}
+        }
+    }
+    Ok(records)
+}
+
+/// POST `data` to `config.url` as JSON arrays of up to `config.batch_size` records.
+/// Returns the number of records sent.
+///
+/// # Errors
+/// Returns an error if `batch_size` is zero, a batch fails to serialize, or a request
+/// still fails after its retries. Batches before the failing one have been sent.
+pub fn write_http_json_vec<T: Serialize>(config: &HttpSinkConfig, data: &[T]) -> Result<usize> {
+    ensure!(
+        config.batch_size > 0,
+        "write_http_json: batch_size must be positive"
+    );
+    let agent = agent();
+    let mut limiter = RateLimiter::new(config.rate_limit);
+    for (i, batch) in data.chunks(config.batch_size).enumerate() {
+        let body = serde_json::to_vec(batch)
+            .with_context(|| format!("write_http_json: serialize batch #{i}"))?;
+        retry_with_backoff(&config.retry, || {
+            limiter.wait();
+            post(&agent, &config.url, &config.headers, &body)
+        })
+        .with_context(|| format!("write_http_json: POST batch #{i} to {}", config.url))?;
+    }
+    Ok(data.len())
+}
+
+fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new().timeout(HTTP_TIMEOUT).build()
+}
+
+/// Query parameters selecting page `page` (0-based).
+fn page_query(pagination: &Pagination, page: usize, cursor: Option<&str>) -> Vec<(String, String)> {
+    let page = page as u64;
+    match pagination {
+        Pagination::None | Pagination::LinkHeader => Vec::new(),
+        Pagination::PageNumber { param, start } => {
+            vec![(param.clone(), (start + page).to_string())]
+        }
+        Pagination::Offset {
+            offset_param,
+            limit_param,
+            limit,
+        } => vec![
+            (offset_param.clone(), (limit * page).to_string()),
+            (limit_param.clone(), limit.to_string()),
+        ],
+        Pagination::Cursor { param, .. } => cursor
+            .map(|c| vec![(param.clone(), c.to_string())])
+            .unwrap_or_default(),
+    }
+}
+
+/// The cursor at `pointer` in `body`, if there is a non-empty one.
+fn next_cursor(body: &Value, pointer: &str) -> Option<String> {
+    match body.pointer(pointer)? {
+        Value::String(s) if !s.is_empty() => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// Move the array of records at `pointer` (or the whole body) out of `body`.
+fn take_records(body: &mut Value, pointer: Option<&str>) -> Result<Vec<Value>> {
+    let target = match pointer {
+        Some(pointer) => body
+            .pointer_mut(pointer)
+            .with_context(|| format!("no records at {pointer}"))?,
+        None => body,
+    };
+    match target.take() {
+        Value::Array(records) => Ok(records),
+        other => bail!(
+            "expected an array of records at {}, found {}",
+            pointer.unwrap_or("the top level"),
+            json_type(&other)
+        ),
+    }
+}
+
+const fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+/// The URL marked `rel="next"` in a `Link` header (RFC 8288).
+fn next_link(header: &str) -> Option<String> {
+    header.split(',').find_map(|link| {
+        let mut parts = link.split(';');
+        let url = parts.next()?.trim().strip_prefix('<')?.strip_suffix('>')?;
+        parts
+            .any(|param| {
+                param
+                    .trim()
+                    .strip_prefix("rel=")
+                    .is_some_and(|rel| rel.trim_matches('"').split(' ').any(|r| r == "next"))
+            })
+            .then(|| url.to_string())
+    })
+}
+
+/// GET `url` and return its JSON body and next `Link`, if any.
+fn get(
+    agent: &ureq::Agent,
+    url: &str,
+    headers: &[(String, String)],
+    query: &[(String, String)],
+) -> CloudResult<(Value, Option<String>)> {
+    let mut request = agent.get(url);
+    for (name, value) in headers {
+        request = request.set(name, value);
+    }
+    for (name, value) in query {
+        request = request.query(name, value);
+    }
+    let response = request.call().map_err(|err| http_error(url, err))?;
+    let next = response.header("link").and_then(next_link);
+    let body = serde_json::from_reader(response.into_reader()).map_err(|err| {
+        let kind = if err.is_io() {
+            ErrorKind::Network
+        } else {
+            ErrorKind::InvalidInput
+        };
+        CloudIOError::new(kind, format!("Response from {url} is not JSON: {err}"))
+    })?;
+    Ok((body, next))
+}
+
+/// POST `body` to `url` as JSON.
+fn post(
+    agent: &ureq::Agent,
+    url: &str,
+    headers: &[(String, String)],
+    body: &[u8],
+) -> CloudResult<()> {
+    let mut request = agent.post(url).set("Content-Type", "application/json");
+    for (name, value) in headers {
+        request = request.set(name, value);
+    }
+    request
+        .send_bytes(body)
+        .map_err(|err| http_error(url, err))?;
+    Ok(())
+}
+
+/// Classify a failed request so that only transient failures are retried.
+fn http_error(url: &str, err: ureq::Error) -> CloudIOError {
+    match err {
+        ureq::Error::Status(status, response) => {
+            let kind = match status {
+                401 => ErrorKind::Authentication,
+                403 => ErrorKind::Authorization,
+                404 => ErrorKind::NotFound,
+                408 => ErrorKind::Timeout,
+                409 => ErrorKind::AlreadyExists,
+                429 => ErrorKind::RateLimited,
+                500..=599 => ErrorKind::ServiceUnavailable,
+                _ => ErrorKind::InvalidInput,
+            };
+            let detail: String = response
+                .into_string()
+                .unwrap_or_default()
+                .chars()
+                .take(200)
+                .collect();
+            CloudIOError::new(kind, format!("HTTP {status} from {url}: {detail}"))
+        }
+        ureq::Error::Transport(transport) => {
+            CloudIOError::new(ErrorKind::Network, format!("{url}: {transport}"))
+        }
+    }
+}
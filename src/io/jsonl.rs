@@ -2,6 +2,7 @@
 //!
 //! This module provides:
 //! - **Typed vector I/O** with Serde: [`read_jsonl_vec`] and [`write_jsonl_vec`]
+//! - **Tolerant reads**: [`read_jsonl_vec_lenient`] collects bad lines instead of failing
 //! - **Deterministic parallel writer**: [`write_jsonl_par`] (feature `parallel-io`)
 //! - **Streaming ingestion** by line ranges: [`JsonlShards`], [`build_jsonl_shards`], [`read_jsonl_range`]
 //! - **Execution runner integration**: [`JsonlVecOps<T>`] implements [`VecOps`] over `JsonlShards`
@@ -22,22 +23,26 @@
 //!   outputs in index order.
 
 use crate::Partition;
+use crate::io::compression::WriteOptions;
 use crate::type_token::VecOps;
 use anyhow::Result;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 use std::any::Any;
+use std::fs::metadata;
 use std::marker::PhantomData;
 use std::path::PathBuf;
 
 #[cfg(feature = "io-jsonl")]
-use crate::io::compression::{auto_detect_reader, auto_detect_writer};
+use crate::io::atomic::{create_parent_dir, write_atomically, write_success_marker};
+#[cfg(feature = "io-jsonl")]
+use crate::io::compression::{auto_detect_reader, compressed_writer};
 #[cfg(feature = "io-jsonl")]
 use anyhow::Context;
 #[cfg(feature = "io-jsonl")]
 use serde_json::{from_str, to_writer};
 #[cfg(feature = "io-jsonl")]
-use std::fs::{File, create_dir_all};
+use std::fs::File;
 #[cfg(feature = "io-jsonl")]
 use std::io::{BufRead, BufReader, Write};
 #[cfg(feature = "io-jsonl")]
@@ -57,12 +62,27 @@ use std::sync::Arc;
 /// the `io-jsonl` feature is disabled, always returns an error.
 #[cfg(feature = "io-jsonl")]
 pub fn read_jsonl_vec<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<Vec<T>> {
+    let rows = read_jsonl_vec_numbered(path)?;
+    Ok(rows.into_iter().map(|(_, v)| v).collect())
+}
+
+/// Read a JSONL file like [`read_jsonl_vec`], pairing each value with its 1-based
+/// line number. Blank lines are skipped but still counted, so the numbers match what
+/// an editor shows.
+///
+/// # Errors
+/// Same as [`read_jsonl_vec`]. When the `io-jsonl` feature is disabled, always
+/// returns an error.
+#[cfg(feature = "io-jsonl")]
+pub fn read_jsonl_vec_numbered<T: DeserializeOwned>(
+    path: impl AsRef<Path>,
+) -> Result<Vec<(usize, T)>> {
     let path = path.as_ref();
     let f = File::open(path).with_context(|| format!("open {}", path.display()))?;
     let rdr = auto_detect_reader(f, path)
         .with_context(|| format!("setup decompression for {}", path.display()))?;
     let rdr = BufReader::new(rdr);
-    let mut out = Vec::<T>::new();
+    let mut out = Vec::new();
     for (i, line) in rdr.lines().enumerate() {
         let line = line.with_context(|| format!("read line {} in {}", i + 1, path.display()))?;
         if line.trim().is_empty() {
@@ -71,11 +91,65 @@ pub fn read_jsonl_vec<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<Vec
         let v: T = from_str(&line).with_context(|| {
             format!("parse JSONL line {} in {}: {}", i + 1, path.display(), line)
         })?;
-        out.push(v);
+        out.push((i + 1, v));
     }
     Ok(out)
 }
 
+/// A JSONL line that could not be read as `T`: `(line_number, raw_line, error)`.
+///
+/// Line numbers are 1-based and count every line of the file, including blank ones.
+pub type JsonlLineError = (usize, String, String);
+
+/// Read a JSONL file into a typed `Vec<T>`, collecting bad lines instead of failing.
+///
+/// Like [`read_jsonl_vec`], but a line that is not valid UTF-8 or does not
+/// deserialize into `T` is returned as a [`JsonlLineError`] and reading continues.
+/// Invalid UTF-8 is replaced in the returned raw line.
+///
+/// # Errors
+/// Returns an error if the file cannot be opened or read. When the `io-jsonl`
+/// feature is disabled, always returns an error.
+#[cfg(feature = "io-jsonl")]
+pub fn read_jsonl_vec_lenient<T: DeserializeOwned>(
+    path: impl AsRef<Path>,
+) -> Result<(Vec<T>, Vec<JsonlLineError>)> {
+    let path = path.as_ref();
+    let f = File::open(path).with_context(|| format!("open {}", path.display()))?;
+    let rdr = auto_detect_reader(f, path)
+        .with_context(|| format!("setup decompression for {}", path.display()))?;
+    let mut rdr = BufReader::new(rdr);
+    let mut out = Vec::<T>::new();
+    let mut bad = Vec::new();
+    let mut buf = Vec::new();
+    for line_no in 1.. {
+        buf.clear();
+        let n = rdr
+            .read_until(b'\n', &mut buf)
+            .with_context(|| format!("read line {} in {}", line_no, path.display()))?;
+        if n == 0 {
+            break;
+        }
+        let line = match std::str::from_utf8(&buf) {
+            Ok(line) => line.trim_end_matches(['\n', '\r']),
+            Err(e) => {
+                let raw = String::from_utf8_lossy(&buf);
+                let raw = raw.trim_end_matches(['\n', '\r']).to_string();
+                bad.push((line_no, raw, format!("invalid UTF-8: {e}")));
+                continue;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        match from_str::<T>(line) {
+            Ok(v) => out.push(v),
+            Err(e) => bad.push((line_no, line.to_string(), e.to_string())),
+        }
+    }
+    Ok((out, bad))
+}
+
 /// Write a typed slice as a JSONL file (one JSON value per line).
 ///
 /// Each element is serialized with Serde to a single line, followed by `\n`.
@@ -93,29 +167,68 @@ pub fn read_jsonl_vec<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<Vec
 /// error.
 #[cfg(feature = "io-jsonl")]
 pub fn write_jsonl_vec<T: Serialize>(path: impl AsRef<Path>, data: &[T]) -> Result<usize> {
+    write_jsonl_vec_with(path, data, &WriteOptions::default())
+}
+
+/// Write a typed slice as a JSONL file using explicit [`WriteOptions`].
+///
+/// Like [`write_jsonl_vec`], but the output codec and level come from
+/// `opts.compression` instead of being inferred from the extension (unless it is
+/// [`Compression::Auto`](crate::io::compression::Compression::Auto)).
+///
+/// # Returns
+/// The number of items written (`data.len()`).
+///
+/// # Errors
+/// Returns an error if the file/dirs cannot be created, the compression settings are
+/// invalid, or any item fails to serialize/flush. When the `io-jsonl` feature is
+/// disabled, always returns an error.
+#[cfg(feature = "io-jsonl")]
+pub fn write_jsonl_vec_with<T: Serialize>(
+    path: impl AsRef<Path>,
+    data: &[T],
+    opts: &WriteOptions,
+) -> Result<usize> {
     let path = path.as_ref();
-    if let Some(parent) = path.parent()
-        && !parent.as_os_str().is_empty()
-    {
-        create_dir_all(parent).with_context(|| format!("mkdir -p {}", parent.display()))?;
+    create_parent_dir(path)?;
+    write_atomically(path, |f| {
+        let mut w = compressed_writer(f, path, opts.compression)
+            .with_context(|| format!("setup compression for {}", path.display()))?;
+        for (i, item) in data.iter().enumerate() {
+            to_writer(&mut w, item)
+                .with_context(|| format!("serialize item #{} to {}", i, path.display()))?;
+            w.write_all(b"\n")?;
+        }
+        w.flush()?;
+        Ok(())
+    })?;
+    if opts.success_marker {
+        write_success_marker(path)?;
     }
-    let f = File::create(path).with_context(|| format!("create {}", path.display()))?;
-    let mut w = auto_detect_writer(f, path)
-        .with_context(|| format!("setup compression for {}", path.display()))?;
+    Ok(data.len())
+}
+
+/// Serialize `data` as JSONL into a buffer, as one part of a file written by
+/// [`PCollection::write_jsonl`](crate::PCollection::write_jsonl).
+///
+/// # Errors
+/// Returns an error if any item fails to serialize.
+#[cfg(feature = "io-jsonl")]
+pub(crate) fn encode_jsonl_part<T: Serialize>(data: &[T]) -> Result<Vec<u8>> {
+    let mut buf = Vec::new();
     for (i, item) in data.iter().enumerate() {
-        to_writer(&mut w, item)
-            .with_context(|| format!("serialize item #{} to {}", i, path.display()))?;
-        w.write_all(b"\n")?;
+        to_writer(&mut buf, item).with_context(|| format!("serialize item #{i}"))?;
+        buf.push(b'\n');
     }
-    w.flush()?;
-    Ok(data.len())
+    Ok(buf)
 }
 
 /// Write JSONL in parallel while keeping **deterministic final order**.
 ///
 /// The input slice is split into contiguous shards; each shard is serialized to
-/// a temporary part file in parallel, then all parts are concatenated in shard
-/// index order into the final file. Temporary files are removed at the end.
+/// a temporary part file (`<name>.part<i>.tmp`) in parallel, then all parts are
+/// concatenated in shard index order into the final file, which is written atomically
+/// (see [`crate::io::atomic`]). Part files are removed at the end.
 ///
 /// * `shards`: if `None`, defaults to `num_cpus::get().max(2)`, clamped to `[1,n]`.
 ///
@@ -139,14 +252,10 @@ pub fn write_jsonl_par<T: Serialize + Send + Sync>(
     use std::io::{BufWriter, copy};
 
     let path = path.as_ref();
-    if let Some(parent) = path.parent()
-        && !parent.as_os_str().is_empty()
-    {
-        create_dir_all(parent).with_context(|| format!("mkdir -p {}", parent.display()))?;
-    }
+    create_parent_dir(path)?;
     let n = data.len();
     if n == 0 {
-        File::create(path)?; // touch
+        write_atomically(path, |_| Ok(()))?; // touch
         return Ok(0);
     }
     let requested_shards = shards.unwrap_or_else(|| num_cpus::get().max(2));
@@ -159,10 +268,10 @@ pub fn write_jsonl_par<T: Serialize + Send + Sync>(
     let non_empty_shards = n.div_ceil(chunk);
 
     let shard_paths: Vec<PathBuf> = (0..non_empty_shards)
-        .map(|i| path.with_extension(format!("jsonl.part{i}")))
+        .map(|i| path.with_extension(format!("jsonl.part{i}.tmp")))
         .collect();
 
-    shard_paths
+    let written = shard_paths
         .par_iter()
         .enumerate()
         .try_for_each(|(i, p)| -> Result<()> {
@@ -176,17 +285,22 @@ pub fn write_jsonl_par<T: Serialize + Send + Sync>(
             }
             w.flush()?;
             Ok(())
-        })?;
-
-    let mut out = BufWriter::new(File::create(path)?);
-    for p in &shard_paths {
-        let mut r = BufReader::new(File::open(p)?);
-        copy(&mut r, &mut out)?;
-    }
-    out.flush()?;
+        })
+        .and_then(|()| {
+            write_atomically(path, |f| {
+                let mut out = BufWriter::new(f);
+                for p in &shard_paths {
+                    let mut r = BufReader::new(File::open(p)?);
+                    copy(&mut r, &mut out)?;
+                }
+                out.flush()?;
+                Ok(())
+            })
+        });
     for p in shard_paths {
         let _ = remove_file(p);
     }
+    written?;
     Ok(n)
 }
 
@@ -309,6 +423,37 @@ pub fn read_jsonl_vec<T: DeserializeOwned>(_path: impl AsRef<std::path::Path>) -
     anyhow::bail!("the `io-jsonl` feature is not enabled")
 }
 
+/// Stub returned when the `io-jsonl` feature is disabled.
+///
+/// # Errors
+/// Always returns an error: the `io-jsonl` feature is not enabled.
+#[cfg(not(feature = "io-jsonl"))]
+pub fn read_jsonl_vec_numbered<T: DeserializeOwned>(
+    _path: impl AsRef<std::path::Path>,
+) -> Result<Vec<(usize, T)>> {
+    anyhow::bail!("the `io-jsonl` feature is not enabled")
+}
+
+/// Stub returned when the `io-jsonl` feature is disabled.
+///
+/// # Errors
+/// Always returns an error: the `io-jsonl` feature is not enabled.
+#[cfg(not(feature = "io-jsonl"))]
+pub fn read_jsonl_vec_lenient<T: DeserializeOwned>(
+    _path: impl AsRef<std::path::Path>,
+) -> Result<(Vec<T>, Vec<JsonlLineError>)> {
+    anyhow::bail!("the `io-jsonl` feature is not enabled")
+}
+
+/// Stub returned when the `io-jsonl` feature is disabled.
+///
+/// # Errors
+/// Always returns an error: the `io-jsonl` feature is not enabled.
+#[cfg(not(feature = "io-jsonl"))]
+pub(crate) fn encode_jsonl_part<T: Serialize>(_data: &[T]) -> Result<Vec<u8>> {
+    anyhow::bail!("the `io-jsonl` feature is not enabled")
+}
+
 /// Stub returned when the `io-jsonl` feature is disabled.
 ///
 /// # Errors
@@ -321,6 +466,19 @@ pub fn write_jsonl_vec<T: Serialize>(
     anyhow::bail!("the `io-jsonl` feature is not enabled")
 }
 
+/// Stub returned when the `io-jsonl` feature is disabled.
+///
+/// # Errors
+/// Always returns an error: the `io-jsonl` feature is not enabled.
+#[cfg(not(feature = "io-jsonl"))]
+pub fn write_jsonl_vec_with<T: Serialize>(
+    _path: impl AsRef<std::path::Path>,
+    _data: &[T],
+    _opts: &WriteOptions,
+) -> Result<usize> {
+    anyhow::bail!("the `io-jsonl` feature is not enabled")
+}
+
 /// Stub returned when the `io-jsonl` feature is disabled.
 ///
 /// # Errors
@@ -388,6 +546,12 @@ where
         usize::try_from(s.total_lines).ok()
     }
 
+    fn byte_size_hint(&self, data: &dyn Any) -> Option<usize> {
+        let s = data.downcast_ref::<JsonlShards>()?;
+        let meta = metadata(&s.path).ok()?;
+        usize::try_from(meta.len()).ok()
+    }
+
     fn split(&self, data: &dyn Any, _n: usize) -> Option<Vec<Partition>> {
         let s = data.downcast_ref::<JsonlShards>()?;
         let mut parts = Vec::<Partition>::with_capacity(s.ranges.len());
@@ -0,0 +1,596 @@
+//! Lakehouse table reads: Delta Lake snapshots over Parquet data files.
+//!
+//! This module provides:
+//! - **Snapshots**: [`DeltaSnapshot::load`] replays a table's transaction log (the
+//!   newest checkpoint plus the JSON commits after it) into the set of active data files
+//! - **Partition pruning**: [`PartitionFilter`] keeps only the files whose partition
+//!   values match, without opening them
+//! - **Vector I/O**: [`read_delta_vec`] reads a (filtered) snapshot into `Vec<T>`
+//! - **Execution runner integration**: [`DeltaVecOps<T>`] implements [`VecOps`] over a
+//!   [`DeltaSnapshot`], one partition per data file
+//!
+//! [`read_delta_table`](crate::read_delta_table) wraps these as a pipeline source.
+//!
+//! # Table layout
+//! A Delta table is a directory of Parquet files plus a `_delta_log` directory of
+//! numbered commits (`00000000000000000000.json`, ...), each a JSON line per action.
+//! Replaying `add` and `remove` actions up to a version yields the files of that
+//! version. Checkpoints (`<version>.checkpoint.parquet`, single or multi-part) hold the
+//! replayed state at their version, so reads start from the newest one at or below the
+//! target version and the commits before it may have been cleaned up.
+//!
+//! Partition columns are not stored in the data files; their values come from the log
+//! and are filled into each row, typed by the table schema.
+//!
+//! # Limitations
+//! Only local tables are read. Tables using column mapping, and files with deletion
+//! vectors, are rejected rather than read incorrectly. Apache Iceberg tables are not
+//! supported yet.
+//!
+//! # Feature gating
+//! This module only exists when the `io-parquet` feature is enabled.
+//!
+//! ```no_run
+//! use ironbeam::io::lakehouse::{DeltaReadOptions, PartitionFilter, read_delta_vec};
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Event {
+//!     date: String,
+//!     user: String,
+//! }
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let opts = DeltaReadOptions::default()
+//!     .with_filter(PartitionFilter::Eq("date".into(), "2024-06-01".into()));
+//! let events: Vec<Event> = read_delta_vec("lake/events", &opts)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::Partition;
+use crate::io::parquet::read_parquet_vec;
+use crate::type_token::VecOps;
+use anyhow::{Context, Result, bail};
+use arrow::record_batch::RecordBatch;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use serde::Deserialize;
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde_arrow::from_record_batch;
+use serde_json::{Map, Value};
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::fs::{File, read_dir, read_to_string};
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Name of the transaction log directory inside a Delta table.
+pub const DELTA_LOG_DIR: &str = "_delta_log";
+
+/// Table features a reader may be asked to support that this reader handles.
+/// `deletionVectors` is checked per file instead.
+const SUPPORTED_READER_FEATURES: &[&str] =
+    &["deletionVectors", "timestampNtz", "vacuumProtocolCheck"];
+
+/// A predicate on one partition column, matched against the raw partition values
+/// recorded in the log (dates as `2024-06-01`, numbers in decimal, and so on).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PartitionFilter {
+    /// The column equals the value.
+    Eq(String, String),
+    /// The column equals one of the values.
+    In(String, Vec<String>),
+    /// The column is null.
+    IsNull(String),
+}
+
+impl PartitionFilter {
+    /// Whether `file` satisfies the filter. A column the file has no value for counts
+    /// as null.
+    #[must_use]
+    pub fn matches(&self, file: &DeltaFile) -> bool {
+        let value = |column: &str| file.partition_values.get(column).and_then(Option::as_deref);
+        match self {
+            Self::Eq(column, expected) => value(column) == Some(expected.as_str()),
+            Self::In(column, expected) => {
+                value(column).is_some_and(|v| expected.iter().any(|e| e == v))
+            }
+            Self::IsNull(column) => value(column).is_none(),
+        }
+    }
+}
+
+/// Options for reading a Delta table.
+#[derive(Clone, Debug, Default)]
+pub struct DeltaReadOptions {
+    /// Table version to read (time travel). Default: the latest.
+    pub version: Option<u64>,
+    /// Partition filters; a file is read only if it matches all of them.
+    pub filters: Vec<PartitionFilter>,
+}
+
+impl DeltaReadOptions {
+    /// Read the table as of `version`.
+    #[must_use]
+    pub const fn with_version(mut self, version: u64) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Add a partition filter.
+    #[must_use]
+    pub fn with_filter(mut self, filter: PartitionFilter) -> Self {
+        self.filters.push(filter);
+        self
+    }
+}
+
+/// An active data file of a Delta snapshot.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeltaFile {
+    /// Local path of the Parquet file.
+    pub path: PathBuf,
+    /// Partition column values; `None` is null.
+    pub partition_values: BTreeMap<String, Option<String>>,
+    /// File size in bytes.
+    pub size: u64,
+    /// Row count, when the writer recorded statistics.
+    pub num_records: Option<u64>,
+}
+
+/// The active files of a Delta table at one version.
+///
+/// Also the payload of [`read_delta_table`](crate::read_delta_table) sources, read
+/// through [`DeltaVecOps`].
+#[derive(Clone, Debug)]
+pub struct DeltaSnapshot {
+    /// Table root directory.
+    pub table: PathBuf,
+    /// Version the snapshot reflects.
+    pub version: u64,
+    /// Partition columns, in table order.
+    pub partition_columns: Vec<String>,
+    /// Active data files, sorted by path.
+    pub files: Vec<DeltaFile>,
+    /// Schema type of each partition column (`"integer"`, `"date"`, ...).
+    partition_types: BTreeMap<String, String>,
+}
+
+// ── Log actions ──────────────────────────────────────────────────────────────
+//
+// One line of a JSON commit, or one row of a checkpoint, holds one of these.
+
+#[derive(Deserialize)]
+struct Action {
+    add: Option<AddAction>,
+    remove: Option<RemoveAction>,
+    #[serde(rename = "metaData")]
+    metadata: Option<MetadataAction>,
+    protocol: Option<ProtocolAction>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AddAction {
+    path: String,
+    #[serde(default)]
+    partition_values: BTreeMap<String, Option<String>>,
+    size: i64,
+    stats: Option<String>,
+    deletion_vector: Option<IgnoredAny>,
+}
+
+#[derive(Deserialize)]
+struct RemoveAction {
+    path: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MetadataAction {
+    #[serde(default)]
+    partition_columns: Vec<String>,
+    schema_string: String,
+    #[serde(default)]
+    configuration: BTreeMap<String, Option<String>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProtocolAction {
+    min_reader_version: i32,
+    reader_features: Option<Vec<String>>,
+}
+
+/// Replay state: files by their path in the log, plus the latest metadata.
+#[derive(Default)]
+struct Replay {
+    files: BTreeMap<String, AddAction>,
+    metadata: Option<MetadataAction>,
+}
+
+impl Replay {
+    fn apply(&mut self, action: Action) -> Result<()> {
+        if let Some(protocol) = action.protocol {
+            check_protocol(&protocol)?;
+        }
+        if let Some(metadata) = action.metadata {
+            self.metadata = Some(metadata);
+        }
+        if let Some(remove) = action.remove {
+            self.files.remove(&remove.path);
+        }
+        if let Some(add) = action.add {
+            self.files.insert(add.path.clone(), add);
+        }
+        Ok(())
+    }
+}
+
+fn check_protocol(protocol: &ProtocolAction) -> Result<()> {
+    if protocol.min_reader_version > 3 {
+        bail!(
+            "Delta reader version {} is not supported",
+            protocol.min_reader_version
+        );
+    }
+    for feature in protocol.reader_features.iter().flatten() {
+        if !SUPPORTED_READER_FEATURES.contains(&feature.as_str()) {
+            bail!("Delta reader feature `{feature}` is not supported");
+        }
+    }
+    Ok(())
+}
+
+/// The commits and checkpoints found in a `_delta_log` directory.
+#[derive(Default)]
+struct LogListing {
+    commits: BTreeMap<u64, PathBuf>,
+    /// Version -> (part count, parts found).
+    checkpoints: BTreeMap<u64, (u64, Vec<PathBuf>)>,
+}
+
+impl LogListing {
+    fn read(log: &Path) -> Result<Self> {
+        let mut listing = Self::default();
+        for entry in read_dir(log).with_context(|| format!("list {}", log.display()))? {
+            let path = entry?.path();
+            let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+                continue;
+            };
+            let Some((version, rest)) = name.split_once('.') else {
+                continue;
+            };
+            let Ok(version) = version.parse::<u64>() else {
+                continue;
+            };
+            if rest == "json" {
+                listing.commits.insert(version, path);
+            } else if rest == "checkpoint.parquet" {
+                listing.checkpoints.insert(version, (1, vec![path]));
+            } else if let Some(parts) = rest
+                .strip_prefix("checkpoint.")
+                .and_then(|r| r.strip_suffix(".parquet"))
+                .and_then(|r| r.split_once('.'))
+                .and_then(|(_, n)| n.parse::<u64>().ok())
+            {
+                listing
+                    .checkpoints
+                    .entry(version)
+                    .or_insert((parts, Vec::new()))
+                    .1
+                    .push(path);
+            }
+        }
+        Ok(listing)
+    }
+
+    /// The newest complete checkpoint at or below `version`.
+    fn checkpoint_for(&self, version: u64) -> Option<(u64, &[PathBuf])> {
+        self.checkpoints
+            .range(..=version)
+            .rev()
+            .find(|(_, (parts, found))| found.len() as u64 == *parts)
+            .map(|(v, (_, found))| (*v, found.as_slice()))
+    }
+}
+
+impl DeltaSnapshot {
+    /// Load the snapshot of the Delta table at `table` as of `version`, or the latest
+    /// version.
+    ///
+    /// # Errors
+    /// Returns an error if the log cannot be listed or read, the version does not
+    /// exist or a commit needed to reach it is missing, an action cannot be parsed, or
+    /// the table uses a feature this reader does not support.
+    pub fn load(table: impl AsRef<Path>, version: Option<u64>) -> Result<Self> {
+        let table = table.as_ref().to_path_buf();
+        let log = table.join(DELTA_LOG_DIR);
+        let listing = LogListing::read(&log)?;
+        let latest = listing
+            .commits
+            .keys()
+            .chain(listing.checkpoints.keys())
+            .max()
+            .copied()
+            .with_context(|| format!("{} holds no Delta commits", log.display()))?;
+        let version = version.unwrap_or(latest);
+        if version > latest {
+            bail!(
+                "{} has no version {version}; the latest is {latest}",
+                table.display()
+            );
+        }
+
+        let mut replay = Replay::default();
+        let first_commit = match listing.checkpoint_for(version) {
+            Some((at, parts)) => {
+                for part in parts {
+                    for action in read_checkpoint(part)? {
+                        replay.apply(action)?;
+                    }
+                }
+                at + 1
+            }
+            None => 0,
+        };
+        for v in first_commit..=version {
+            let path = listing.commits.get(&v).with_context(|| {
+                format!(
+                    "commit {v} of {} is missing and no checkpoint covers it",
+                    table.display()
+                )
+            })?;
+            let text = read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+            for (line, json) in text
+                .lines()
+                .enumerate()
+                .filter(|(_, l)| !l.trim().is_empty())
+            {
+                let action: Action = serde_json::from_str(json)
+                    .with_context(|| format!("{}:{}", path.display(), line + 1))?;
+                replay.apply(action)?;
+            }
+        }
+
+        let metadata = replay
+            .metadata
+            .with_context(|| format!("{} has no table metadata", table.display()))?;
+        if let Some(Some(mode)) = metadata.configuration.get("delta.columnMapping.mode")
+            && mode != "none"
+        {
+            bail!("Delta column mapping (mode `{mode}`) is not supported");
+        }
+        let partition_types = partition_types(&metadata)?;
+
+        let files = replay
+            .files
+            .into_values()
+            .map(|add| {
+                if add.deletion_vector.is_some() {
+                    bail!("{} has a deletion vector, which is not supported", add.path);
+                }
+                let num_records = add
+                    .stats
+                    .as_deref()
+                    .and_then(|s| serde_json::from_str::<Value>(s).ok())
+                    .and_then(|s| s.get("numRecords")?.as_u64());
+                Ok(DeltaFile {
+                    path: data_path(&table, &add.path)?,
+                    partition_values: add.partition_values,
+                    size: u64::try_from(add.size).unwrap_or(0),
+                    num_records,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            table,
+            version,
+            partition_columns: metadata.partition_columns,
+            files,
+            partition_types,
+        })
+    }
+
+    /// Keep only the files matching every filter.
+    pub fn retain(&mut self, filters: &[PartitionFilter]) {
+        self.files
+            .retain(|file| filters.iter().all(|f| f.matches(file)));
+    }
+
+    /// Total row count, if every file recorded one.
+    #[must_use]
+    pub fn num_records(&self) -> Option<u64> {
+        self.files.iter().map(|f| f.num_records).sum()
+    }
+
+    /// Read one data file of the snapshot into `Vec<T>`, filling in its partition
+    /// values.
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read, a partition value does not parse
+    /// as its column type, or a row fails to deserialize.
+    pub fn read_file<T: DeserializeOwned>(&self, file: &DeltaFile) -> Result<Vec<T>> {
+        let context = || format!("read {}", file.path.display());
+        if self.partition_columns.is_empty() {
+            return read_parquet_vec(&file.path).with_context(context);
+        }
+        let rows: Vec<Map<String, Value>> = read_parquet_vec(&file.path).with_context(context)?;
+        let mut partition = Map::new();
+        for column in &self.partition_columns {
+            let raw = file.partition_values.get(column).cloned().flatten();
+            let kind = self
+                .partition_types
+                .get(column)
+                .map_or("string", String::as_str);
+            partition.insert(column.clone(), partition_value(column, kind, raw)?);
+        }
+        rows.into_iter()
+            .enumerate()
+            .map(|(i, mut row)| {
+                for (column, value) in &partition {
+                    row.entry(column.clone()).or_insert_with(|| value.clone());
+                }
+                serde_json::from_value(Value::Object(row))
+                    .with_context(|| format!("deserialize row #{i} of {}", file.path.display()))
+            })
+            .collect()
+    }
+
+    /// Read every file of the snapshot, in order.
+    fn read_all<T: DeserializeOwned>(&self) -> Result<Vec<T>> {
+        let mut out = Vec::new();
+        for file in &self.files {
+            out.extend(self.read_file(file)?);
+        }
+        Ok(out)
+    }
+}
+
+/// Load the snapshot described by `opts` and read its matching files into `Vec<T>`.
+///
+/// # Errors
+/// Returns an error for the reasons of [`DeltaSnapshot::load`] and
+/// [`DeltaSnapshot::read_file`].
+pub fn read_delta_vec<T: DeserializeOwned>(
+    table: impl AsRef<Path>,
+    opts: &DeltaReadOptions,
+) -> Result<Vec<T>> {
+    let mut snapshot = DeltaSnapshot::load(table, opts.version)?;
+    snapshot.retain(&opts.filters);
+    snapshot.read_all()
+}
+
+fn read_checkpoint(path: &Path) -> Result<Vec<Action>> {
+    let context = || format!("read checkpoint {}", path.display());
+    let f = File::open(path).with_context(context)?;
+    let reader = ParquetRecordBatchReaderBuilder::try_new(f)
+        .and_then(ParquetRecordBatchReaderBuilder::build)
+        .with_context(context)?;
+    let mut out = Vec::new();
+    for batch in reader {
+        let batch: RecordBatch = batch.with_context(context)?;
+        let mut actions: Vec<Action> = from_record_batch(&batch).with_context(context)?;
+        out.append(&mut actions);
+    }
+    Ok(out)
+}
+
+/// Schema types of the partition columns, from the table's schema string.
+fn partition_types(metadata: &MetadataAction) -> Result<BTreeMap<String, String>> {
+    let schema: Value =
+        serde_json::from_str(&metadata.schema_string).context("parse the table schema")?;
+    let fields = schema.get("fields").and_then(Value::as_array);
+    Ok(fields
+        .into_iter()
+        .flatten()
+        .filter_map(|field| {
+            let name = field.get("name")?.as_str()?;
+            let kind = field.get("type")?.as_str()?;
+            metadata
+                .partition_columns
+                .iter()
+                .any(|c| c == name)
+                .then(|| (name.to_string(), kind.to_string()))
+        })
+        .collect())
+}
+
+/// Type a raw partition value as its schema type.
+fn partition_value(column: &str, kind: &str, raw: Option<String>) -> Result<Value> {
+    let Some(raw) = raw.filter(|r| !r.is_empty()) else {
+        return Ok(Value::Null);
+    };
+    let context = || format!("partition value {raw:?} of {column} is not a valid {kind}");
+    Ok(match kind {
+        "byte" | "short" | "integer" | "long" => {
+            Value::from(raw.parse::<i64>().with_context(context)?)
+        }
+        "float" | "double" => Value::from(raw.parse::<f64>().with_context(context)?),
+        "boolean" => Value::Bool(raw.parse::<bool>().with_context(context)?),
+        _ => Value::String(raw),
+    })
+}
+
+/// Local path of a data file recorded in the log as `path`: relative to the table and
+/// percent-encoded, or an absolute `file:` URI.
+fn data_path(table: &Path, path: &str) -> Result<PathBuf> {
+    if let Some(local) = path
+        .strip_prefix("file://")
+        .or_else(|| path.strip_prefix("file:"))
+    {
+        return Ok(PathBuf::from(percent_decode(local)?));
+    }
+    if path.contains("://") {
+        bail!("data file {path} is not local");
+    }
+    Ok(table.join(percent_decode(path)?))
+}
+
+fn percent_decode(s: &str) -> Result<String> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = s.get(i + 1..i + 3).context("truncated percent escape")?;
+            out.push(u8::from_str_radix(hex, 16).with_context(|| format!("bad escape %{hex}"))?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).with_context(|| format!("{s} does not decode to UTF-8"))
+}
+
+// ── VecOps adapter ───────────────────────────────────────────────────────────
+
+/// `VecOps` adapter for Delta tables via [`DeltaSnapshot`].
+///
+/// Enables the engine to:
+/// - Get total length (`len`), when every file recorded its row count
+/// - Split into partitions, one per data file (`split`)
+/// - Read the entire snapshot for sequential paths (`clone_any`)
+pub struct DeltaVecOps<T>(PhantomData<T>);
+
+impl<T> DeltaVecOps<T> {
+    /// Construct an `Arc` to the adapter.
+    #[must_use]
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self(PhantomData))
+    }
+}
+
+impl<T> VecOps for DeltaVecOps<T>
+where
+    T: DeserializeOwned + Send + Sync + Clone + 'static,
+{
+    fn len(&self, data: &dyn Any) -> Option<usize> {
+        let s = data.downcast_ref::<DeltaSnapshot>()?;
+        usize::try_from(s.num_records()?).ok()
+    }
+
+    fn byte_size_hint(&self, data: &dyn Any) -> Option<usize> {
+        let s = data.downcast_ref::<DeltaSnapshot>()?;
+        usize::try_from(s.files.iter().map(|f| f.size).sum::<u64>()).ok()
+    }
+
+    fn split(&self, data: &dyn Any, _n: usize) -> Option<Vec<Partition>> {
+        let s = data.downcast_ref::<DeltaSnapshot>()?;
+        let mut parts: Vec<Partition> = Vec::with_capacity(s.files.len());
+        for file in &s.files {
+            let v: Vec<T> = s.read_file(file).ok()?;
+            parts.push(Box::new(v) as Partition);
+        }
+        Some(parts)
+    }
+
+    fn clone_any(&self, data: &dyn Any) -> Option<Partition> {
+        let s = data.downcast_ref::<DeltaSnapshot>()?;
+        let v: Vec<T> = s.read_all().ok()?;
+        Some(Box::new(v) as Partition)
+    }
+}
@@ -21,6 +21,9 @@
 //! - **Module**: [`csv`]
 //! - **Format**: Comma-separated values with optional headers
 //! - **Vector I/O**: [`read_csv_vec`](csv::read_csv_vec), [`write_csv_vec`](csv::write_csv_vec)
+//! - **Options**: [`CsvReadOptions`](csv::CsvReadOptions) for
+//!   [`read_csv_vec_with`](csv::read_csv_vec_with) and
+//!   [`build_csv_shards_with`](csv::build_csv_shards_with)
 //! - **Streaming**: [`CsvShards`](csv::CsvShards), [`build_csv_shards`](csv::build_csv_shards)
 //! - **Parallel**: [`write_csv_par`](csv::write_csv_par) (requires `parallel-io`)
 //!
@@ -31,6 +34,12 @@
 //! - **Streaming**: [`ParquetShards`](parquet::ParquetShards), [`build_parquet_shards`](parquet::build_parquet_shards)
 //! - **Note**: Uses Arrow 56 and `serde_arrow` 0.13 for schema inference
 //!
+//! ### Delta Lake (feature: `io-parquet`)
+//! - **Module**: `lakehouse` (compiled only with the feature)
+//! - **Format**: Delta Lake tables: a transaction log over Parquet data files
+//! - **Vector I/O**: `read_delta_vec`
+//! - **Streaming**: `DeltaSnapshot`, one shard per active data file
+//!
 //! ### Avro (feature: `io-avro`)
 //! - **Module**: [`avro`]
 //! - **Format**: Apache Avro binary serialization
@@ -39,6 +48,29 @@
 //! - **Streaming**: [`AvroShards`](avro::AvroShards), [`build_avro_shards`](avro::build_avro_shards)
 //! - **Note**: Compression support for gzip, zstd, bzip2, xz
 //!
+//! ### XML (feature: `io-xml`)
+//! - **Module**: [`xml`]
+//! - **Vector I/O**: [`read_xml_vec`](xml::read_xml_vec), [`write_xml_vec`](xml::write_xml_vec)
+//! - **Record paths**: [`read_xml_records_vec`](xml::read_xml_records_vec) and
+//!   [`build_xml_record_shards`](xml::build_xml_record_shards) extract one record per
+//!   element selected by a tag path such as `"catalog/book"`
+//!
+//! ### Protobuf (feature: `io-proto`, opt-in)
+//! - **Module**: `proto` (compiled only with the feature)
+//! - **Format**: Varint-length-delimited protobuf messages decoded with `prost`
+//! - **Vector I/O**: `read_proto_vec`, `write_proto_vec`
+//! - **Streaming**: `ProtoShards`, `build_proto_shards`
+//!
+//! ### HTTP (feature: `io-http`, opt-in)
+//! - **Module**: `http` (compiled only with the feature)
+//! - **Format**: JSON records from paginated REST APIs, JSON arrays POSTed in batches
+//! - **Vector I/O**: `read_http_json_vec`, `write_http_json_vec`
+//!
+//! ### SQLite (feature: `io-sqlite`, opt-in)
+//! - **Module**: `sqlite` (compiled only with the feature)
+//! - **Format**: Rows of a local SQLite database, matched to struct fields by column name
+//! - **Vector I/O**: `read_sql_query_vec`, `write_table_vec`
+//!
 //! ## Architecture
 //!
 //! ### Vector I/O Pattern
@@ -105,6 +137,26 @@
 //! - Works well with compressed files (though streaming reads from compressed
 //!   files still need to decompress from the start)
 //!
+//! ### Compression
+//! Every reader goes through [`compression::auto_detect_reader`], which sniffs magic
+//! bytes (falling back to the extension), reads concatenated gzip members, and with
+//! `archive-tar` unpacks tarballs of shards. Writers infer the codec from the
+//! extension; [`WriteOptions`](compression::WriteOptions) with a
+//! [`Compression`](compression::Compression) forces a codec and level through
+//! [`write_jsonl_vec_with`](jsonl::write_jsonl_vec_with) and
+//! [`write_csv_vec_with`](csv::write_csv_vec_with).
+//!
+//! ### Atomic Output
+//! Writers never leave a partial file at the destination: output goes to a `.tmp`
+//! file beside it that is renamed into place once complete, and writers taking
+//! [`WriteOptions`](compression::WriteOptions) can add a `_SUCCESS` marker (see
+//! [`atomic`]).
+//!
+//! ### Schema Drift
+//! Glob reads check that every matched file shares the first file's columns before
+//! reading rows; [`drift::SchemaDriftPolicy`] chooses between failing with a
+//! structured [`drift::SchemaDriftError`] and aligning columns by name.
+//!
 //! ### Error Context
 //! All I/O operations use `anyhow::Context` to provide detailed error messages
 //! including file paths, line/row numbers, and operation context.
@@ -129,6 +181,8 @@
 //! - `io-csv` - Enable CSV I/O
 //! - `io-parquet` - Enable Parquet I/O (adds Arrow dependencies)
 //! - `parallel-io` - Enable parallel writers for all formats
+//! - `compression-{gzip,zstd,bzip2,xz}` - Enable the respective codecs
+//! - `archive-tar` - Read `.tar`, `.tgz` and `.tar.<codec>` archives of shards
 //!
 //! ## Examples
 //!
@@ -205,12 +259,26 @@ pub mod csv;
 
 pub mod parquet;
 
+#[cfg(feature = "io-parquet")]
+pub mod lakehouse;
+
 pub mod avro;
 
 pub mod xml;
 
 pub mod msgpack;
 
+#[cfg(feature = "io-proto")]
+pub mod proto;
+
+#[cfg(feature = "io-http")]
+pub mod http;
+
+#[cfg(feature = "io-sqlite")]
+pub mod sqlite;
+
+pub mod atomic;
 pub mod cloud;
 pub mod compression;
+pub mod drift;
 pub mod glob;
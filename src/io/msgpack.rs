@@ -30,9 +30,12 @@ use anyhow::Result;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 use std::any::Any;
+use std::fs::metadata;
 use std::marker::PhantomData;
 use std::path::PathBuf;
 
+#[cfg(feature = "io-msgpack")]
+use crate::io::atomic::{create_parent_dir, write_atomically};
 #[cfg(feature = "io-msgpack")]
 use crate::io::compression::{auto_detect_reader, auto_detect_writer};
 #[cfg(feature = "io-msgpack")]
@@ -44,7 +47,7 @@ use rmp_serde::decode::Error as DecodeError;
 #[cfg(feature = "io-msgpack")]
 use serde::de::{Deserialize, IgnoredAny};
 #[cfg(feature = "io-msgpack")]
-use std::fs::{File, create_dir_all};
+use std::fs::File;
 #[cfg(feature = "io-msgpack")]
 use std::io::{BufReader, ErrorKind, Read, Write};
 #[cfg(feature = "io-msgpack")]
@@ -194,28 +197,27 @@ pub fn write_msgpack_vec<T: Serialize>(
     data: &[T],
 ) -> Result<usize> {
     let path = path.as_ref();
-    if let Some(parent) = path.parent()
-        && !parent.as_os_str().is_empty()
-    {
-        create_dir_all(parent).with_context(|| format!("mkdir -p {}", parent.display()))?;
-    }
-    let f = File::create(path).with_context(|| format!("create {}", path.display()))?;
-    let mut w = auto_detect_writer(f, path)
-        .with_context(|| format!("setup compression for {}", path.display()))?;
-    for (i, item) in data.iter().enumerate() {
-        rmp_serde::encode::write(&mut w, item).with_context(|| {
-            format!("serialize item #{} to MessagePack in {}", i, path.display())
-        })?;
-    }
-    w.flush().context("flush MessagePack writer")?;
+    create_parent_dir(path)?;
+    write_atomically(path, |f| {
+        let mut w = auto_detect_writer(f, path)
+            .with_context(|| format!("setup compression for {}", path.display()))?;
+        for (i, item) in data.iter().enumerate() {
+            rmp_serde::encode::write(&mut w, item).with_context(|| {
+                format!("serialize item #{} to MessagePack in {}", i, path.display())
+            })?;
+        }
+        w.flush().context("flush MessagePack writer")?;
+        Ok(())
+    })?;
     Ok(data.len())
 }
 
 /// Write `MessagePack` in parallel while keeping **deterministic final order**.
 ///
 /// The input slice is split into contiguous shards; each shard is serialized to a
-/// temporary part file in parallel, then all parts are concatenated in shard index
-/// order into the final file. Temporary files are removed at the end.
+/// temporary part file (`<name>.part<i>.tmp`) in parallel, then all parts are
+/// concatenated in shard index order into the final file, which is written atomically
+/// (see [`crate::io::atomic`]). Part files are removed at the end.
 ///
 /// Because `MessagePack` records are self-delimiting and carry no per-file framing,
 /// concatenating shard byte streams yields a valid combined file.
@@ -242,14 +244,10 @@ pub fn write_msgpack_par<T: Serialize + Send + Sync>(
     use std::io::{BufWriter, copy};
 
     let path = path.as_ref();
-    if let Some(parent) = path.parent()
-        && !parent.as_os_str().is_empty()
-    {
-        create_dir_all(parent).with_context(|| format!("mkdir -p {}", parent.display()))?;
-    }
+    create_parent_dir(path)?;
     let n = data.len();
     if n == 0 {
-        File::create(path).with_context(|| format!("create {}", path.display()))?; // touch
+        write_atomically(path, |_| Ok(()))?; // touch
         return Ok(0);
     }
     let requested_shards = shards.unwrap_or_else(|| num_cpus::get().max(2));
@@ -260,10 +258,10 @@ pub fn write_msgpack_par<T: Serialize + Send + Sync>(
     let non_empty_shards = n.div_ceil(chunk);
 
     let shard_paths: Vec<PathBuf> = (0..non_empty_shards)
-        .map(|i| path.with_extension(format!("msgpack.part{i}")))
+        .map(|i| path.with_extension(format!("msgpack.part{i}.tmp")))
         .collect();
 
-    shard_paths
+    let written = shard_paths
         .par_iter()
         .enumerate()
         .try_for_each(|(i, p)| -> Result<()> {
@@ -277,18 +275,24 @@ pub fn write_msgpack_par<T: Serialize + Send + Sync>(
             }
             w.flush()?;
             Ok(())
-        })?;
-
-    let mut out =
-        BufWriter::new(File::create(path).with_context(|| format!("create {}", path.display()))?);
-    for p in &shard_paths {
-        let mut r = BufReader::new(File::open(p).with_context(|| format!("open {}", p.display()))?);
-        copy(&mut r, &mut out)?;
-    }
-    out.flush()?;
+        })
+        .and_then(|()| {
+            write_atomically(path, |f| {
+                let mut out = BufWriter::new(f);
+                for p in &shard_paths {
+                    let mut r = BufReader::new(
+                        File::open(p).with_context(|| format!("open {}", p.display()))?,
+                    );
+                    copy(&mut r, &mut out)?;
+                }
+                out.flush()?;
+                Ok(())
+            })
+        });
     for p in shard_paths {
         let _ = remove_file(p);
     }
+    written?;
     Ok(n)
 }
 
@@ -460,6 +464,12 @@ where
         usize::try_from(s.total_records).ok()
     }
 
+    fn byte_size_hint(&self, data: &dyn Any) -> Option<usize> {
+        let s = data.downcast_ref::<MsgpackShards>()?;
+        let meta = metadata(&s.path).ok()?;
+        usize::try_from(meta.len()).ok()
+    }
+
     fn split(&self, data: &dyn Any, _n: usize) -> Option<Vec<Partition>> {
         let s = data.downcast_ref::<MsgpackShards>()?;
         let mut parts = Vec::<Partition>::with_capacity(s.ranges.len());
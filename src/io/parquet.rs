@@ -2,8 +2,11 @@
 //!
 //! This module provides:
 //! - **Typed vector I/O** powered by Serde + Arrow + Parquet:
-//!   - [`write_parquet_vec`] to write `&Vec<T>`
-//!   - [`read_parquet_vec`] to read an entire file into `Vec<T>`
+//!   - [`write_parquet_vec`] to write `&Vec<T>`, or [`write_parquet_vec_with`] to tune
+//!     row groups, compression and encodings through [`ParquetWriteOptions`]
+//!   - [`read_parquet_vec`] to read an entire file into `Vec<T>`, or
+//!     [`read_parquet_vec_with`] to read files written with an older schema under a
+//!     [`SchemaCoercion`] policy
 //! - **Streaming ingestion** by row-group ranges:
 //!   - [`ParquetShards`] metadata (row-group slicing)
 //!   - [`build_parquet_shards`] to compute ranges
@@ -27,31 +30,488 @@ use crate::type_token::VecOps;
 use anyhow::Result;
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use std::any::Any;
+use std::fs::metadata;
 use std::marker::PhantomData;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
+#[cfg(feature = "io-parquet")]
+use crate::helpers::ArrowBatch;
+#[cfg(feature = "io-parquet")]
+use crate::io::atomic::write_atomically;
 #[cfg(feature = "io-parquet")]
 use anyhow::Context;
 #[cfg(feature = "io-parquet")]
+use arrow::array::{ArrayRef, BooleanArray, Int8Array, StringArray, new_null_array};
+#[cfg(feature = "io-parquet")]
+use arrow::compute::{CastOptions, cast_with_options};
+#[cfg(feature = "io-parquet")]
 use arrow::datatypes::FieldRef;
 #[cfg(feature = "io-parquet")]
+use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+#[cfg(feature = "io-parquet")]
 use arrow::record_batch::RecordBatch;
 #[cfg(feature = "io-parquet")]
 use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
 #[cfg(feature = "io-parquet")]
 use parquet::arrow::arrow_writer::ArrowWriter;
 #[cfg(feature = "io-parquet")]
+use parquet::basic::{Compression, Encoding, GzipLevel, ZstdLevel};
+#[cfg(feature = "io-parquet")]
+use parquet::file::properties::EnabledStatistics;
+#[cfg(feature = "io-parquet")]
 use parquet::file::properties::WriterProperties;
 #[cfg(feature = "io-parquet")]
 use parquet::file::reader::{FileReader, SerializedFileReader};
 #[cfg(feature = "io-parquet")]
+use parquet::schema::types::ColumnPath;
+#[cfg(feature = "io-parquet")]
 use serde_arrow::schema::{SchemaLike, TracingOptions};
 #[cfg(feature = "io-parquet")]
 use serde_arrow::{from_record_batch, to_record_batch};
+use std::collections::BTreeMap;
 #[cfg(feature = "io-parquet")]
 use std::fs::File;
 
+/// Default maximum number of rows per row group, matching the Parquet writer's own.
+pub const PARQUET_ROW_GROUP_SIZE: usize = 1024 * 1024;
+
+/// Compression codec for Parquet column chunks.
+///
+/// Unlike [`Compression`](crate::io::compression::Compression), this compresses pages
+/// inside the file, so the output stays a readable `.parquet` file.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ParquetCompression {
+    /// No compression.
+    #[default]
+    Uncompressed,
+    /// Snappy: fast, with a moderate ratio.
+    Snappy,
+    /// Gzip at `level` `0..=9`.
+    Gzip {
+        /// Compression level; 6 is the `gzip` default.
+        level: u32,
+    },
+    /// Zstd at `level` `1..=22`.
+    Zstd {
+        /// Compression level; 3 is the `zstd` default.
+        level: i32,
+    },
+}
+
+/// A non-dictionary column encoding.
+///
+/// With dictionary encoding enabled it is the fallback used once a column's dictionary
+/// grows too large; with it disabled it is the column's encoding. Not every encoding
+/// applies to every physical type (the delta encodings take integers or byte arrays,
+/// byte-stream split takes fixed-width values); a mismatch fails the write.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParquetEncoding {
+    /// Values stored as they are.
+    Plain,
+    /// Deltas between consecutive integers, bit-packed.
+    DeltaBinaryPacked,
+    /// Byte arrays with their lengths delta-encoded.
+    DeltaLengthByteArray,
+    /// Byte arrays with shared prefixes delta-encoded.
+    DeltaByteArray,
+    /// Bytes of fixed-width values split into streams, which compresses floats well.
+    ByteStreamSplit,
+}
+
+/// Options for writing Parquet files, such as
+/// [`write_parquet_vec_with`] and
+/// [`PCollection::write_parquet_with`](crate::PCollection::write_parquet_with).
+///
+/// The defaults match the plain writers: row groups of up to
+/// [`PARQUET_ROW_GROUP_SIZE`] rows, no compression, dictionary encoding and page
+/// statistics on.
+///
+/// # Example
+/// ```
+/// use ironbeam::io::parquet::{ParquetCompression, ParquetEncoding, ParquetWriteOptions};
+///
+/// let opts = ParquetWriteOptions::default()
+///     .with_row_group_size(100_000)
+///     .with_compression(ParquetCompression::Zstd { level: 3 })
+///     .with_column_encoding("ts", ParquetEncoding::DeltaBinaryPacked);
+/// assert_eq!(opts.row_group_size, 100_000);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParquetWriteOptions {
+    /// Maximum rows per row group (default: [`PARQUET_ROW_GROUP_SIZE`]). Smaller groups
+    /// let readers skip more and shard finer; larger ones compress better.
+    pub row_group_size: usize,
+    /// Page compression codec (default: [`ParquetCompression::Uncompressed`]).
+    pub compression: ParquetCompression,
+    /// Dictionary-encode columns (default: `true`).
+    pub dictionary: bool,
+    /// Write min/max statistics for column chunks and pages, which query engines use to
+    /// skip data (default: `true`).
+    pub statistics: bool,
+    /// Encodings for individual columns, by dotted path (`"a.b"` for field `b` of
+    /// struct `a`).
+    pub column_encodings: BTreeMap<String, ParquetEncoding>,
+}
+
+impl Default for ParquetWriteOptions {
+    fn default() -> Self {
+        Self {
+            row_group_size: PARQUET_ROW_GROUP_SIZE,
+            compression: ParquetCompression::default(),
+            dictionary: true,
+            statistics: true,
+            column_encodings: BTreeMap::new(),
+        }
+    }
+}
+
+impl ParquetWriteOptions {
+    /// Set the maximum rows per row group.
+    #[must_use]
+    pub const fn with_row_group_size(mut self, row_group_size: usize) -> Self {
+        self.row_group_size = row_group_size;
+        self
+    }
+
+    /// Set the page compression codec.
+    #[must_use]
+    pub const fn with_compression(mut self, compression: ParquetCompression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Set whether to dictionary-encode columns.
+    #[must_use]
+    pub const fn with_dictionary(mut self, dictionary: bool) -> Self {
+        self.dictionary = dictionary;
+        self
+    }
+
+    /// Set whether to write column statistics.
+    #[must_use]
+    pub const fn with_statistics(mut self, statistics: bool) -> Self {
+        self.statistics = statistics;
+        self
+    }
+
+    /// Set the encoding of the column at the dotted path `column`.
+    #[must_use]
+    pub fn with_column_encoding(
+        mut self,
+        column: impl Into<String>,
+        encoding: ParquetEncoding,
+    ) -> Self {
+        self.column_encodings.insert(column.into(), encoding);
+        self
+    }
+
+    /// Build the Parquet writer properties.
+    ///
+    /// # Errors
+    /// Returns an error if the row group size is zero or the compression level is out
+    /// of range for the codec.
+    #[cfg(feature = "io-parquet")]
+    pub(crate) fn writer_properties(&self) -> Result<WriterProperties> {
+        if self.row_group_size == 0 {
+            anyhow::bail!("Parquet row group size must be positive");
+        }
+        let compression = match self.compression {
+            ParquetCompression::Uncompressed => Compression::UNCOMPRESSED,
+            ParquetCompression::Snappy => Compression::SNAPPY,
+            ParquetCompression::Gzip { level } => {
+                Compression::GZIP(GzipLevel::try_new(level).context("Parquet gzip level")?)
+            }
+            ParquetCompression::Zstd { level } => {
+                Compression::ZSTD(ZstdLevel::try_new(level).context("Parquet zstd level")?)
+            }
+        };
+        let statistics = if self.statistics {
+            EnabledStatistics::Page
+        } else {
+            EnabledStatistics::None
+        };
+        let mut props = WriterProperties::builder()
+            .set_max_row_group_row_count(Some(self.row_group_size))
+            .set_compression(compression)
+            .set_dictionary_enabled(self.dictionary)
+            .set_statistics_enabled(statistics);
+        for (column, encoding) in &self.column_encodings {
+            let encoding = match encoding {
+                ParquetEncoding::Plain => Encoding::PLAIN,
+                ParquetEncoding::DeltaBinaryPacked => Encoding::DELTA_BINARY_PACKED,
+                ParquetEncoding::DeltaLengthByteArray => Encoding::DELTA_LENGTH_BYTE_ARRAY,
+                ParquetEncoding::DeltaByteArray => Encoding::DELTA_BYTE_ARRAY,
+                ParquetEncoding::ByteStreamSplit => Encoding::BYTE_STREAM_SPLIT,
+            };
+            let path = ColumnPath::new(column.split('.').map(str::to_string).collect());
+            props = props.set_column_encoding(path, encoding);
+        }
+        Ok(props.build())
+    }
+}
+
+/// How reads reconcile the columns of a Parquet file with the schema of `T`, so that
+/// files written before a field was added or widened stay readable.
+///
+/// Used by [`read_parquet_vec_with`] and
+/// [`read_parquet_streaming_with`](crate::read_parquet_streaming_with). The policy is
+/// checked against the file schema before any row is read, and applies to top-level
+/// columns; nested fields are left to `serde_arrow`. Columns the file has but `T`
+/// lacks are ignored.
+///
+/// The default fills missing columns, widens numerics, and rejects narrowing.
+///
+/// # Example
+/// ```
+/// use ironbeam::io::parquet::SchemaCoercion;
+///
+/// let lenient = SchemaCoercion::default().with_allow_narrowing(true);
+/// assert!(lenient.fill_missing && lenient.widen_numerics);
+/// assert!(!SchemaCoercion::strict().fill_missing);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SchemaCoercion {
+    /// Fill columns missing from the file: `None` for `Option` fields, the type's
+    /// default (zero, `false`, `""`) for numeric, boolean and string fields. When
+    /// `false`, a missing column is an error. Default: `true`.
+    pub fill_missing: bool,
+    /// Cast numeric columns to a wider type of `T` without loss (`i32` to `i64`,
+    /// `u16` to `i32`, `f32` to `f64`, integers of up to 32 bits to `f64`). When
+    /// `false`, a widened column is an error. Default: `true`.
+    pub widen_numerics: bool,
+    /// Cast numeric columns to a narrower or lossy type of `T` (`i64` to `i32`, `f64`
+    /// to `f32`, `i64` to `f64`), failing the read if a value is out of range. When
+    /// `false`, a narrowed column is an error. Default: `false`.
+    pub allow_narrowing: bool,
+}
+
+impl Default for SchemaCoercion {
+    fn default() -> Self {
+        Self {
+            fill_missing: true,
+            widen_numerics: true,
+            allow_narrowing: false,
+        }
+    }
+}
+
+impl SchemaCoercion {
+    /// A policy that coerces nothing: every column of `T` must be in the file with a
+    /// matching type.
+    #[must_use]
+    pub const fn strict() -> Self {
+        Self {
+            fill_missing: false,
+            widen_numerics: false,
+            allow_narrowing: false,
+        }
+    }
+
+    /// Set whether to fill columns missing from the file.
+    #[must_use]
+    pub const fn with_fill_missing(mut self, fill_missing: bool) -> Self {
+        self.fill_missing = fill_missing;
+        self
+    }
+
+    /// Set whether to widen numeric columns.
+    #[must_use]
+    pub const fn with_widen_numerics(mut self, widen_numerics: bool) -> Self {
+        self.widen_numerics = widen_numerics;
+        self
+    }
+
+    /// Set whether to narrow numeric columns, checking each value.
+    #[must_use]
+    pub const fn with_allow_narrowing(mut self, allow_narrowing: bool) -> Self {
+        self.allow_narrowing = allow_narrowing;
+        self
+    }
+}
+
+/// How one column of `T` is produced from a file batch.
+#[cfg(feature = "io-parquet")]
+enum ColumnPlan {
+    /// The file column at this index, as it is.
+    Keep(usize),
+    /// The file column at this index, cast to the type of `T`.
+    Cast(usize, DataType),
+    /// A column of nulls.
+    Nulls(DataType),
+    /// A column of the type's default value.
+    Defaults(DataType),
+}
+
+/// The columns of `T`, resolved against one file's schema under a [`SchemaCoercion`].
+#[cfg(feature = "io-parquet")]
+struct CoercionPlan {
+    schema: SchemaRef,
+    columns: Vec<ColumnPlan>,
+}
+
+#[cfg(feature = "io-parquet")]
+impl CoercionPlan {
+    fn new<T: DeserializeOwned>(file: &Schema, policy: &SchemaCoercion) -> Result<Self> {
+        let target = Vec::<FieldRef>::from_type::<T>(TracingOptions::default())
+            .context("infer Arrow schema from type T")?;
+        let mut fields = Vec::with_capacity(target.len());
+        let mut columns = Vec::with_capacity(target.len());
+        for want in &target {
+            let name = want.name();
+            let Ok(index) = file.index_of(name) else {
+                if !policy.fill_missing {
+                    anyhow::bail!("column `{name}` is missing from the file");
+                }
+                if want.is_nullable() {
+                    columns.push(ColumnPlan::Nulls(want.data_type().clone()));
+                    fields.push(Field::new(name, want.data_type().clone(), true));
+                } else if has_default(want.data_type()) {
+                    columns.push(ColumnPlan::Defaults(want.data_type().clone()));
+                    fields.push(Field::new(name, want.data_type().clone(), false));
+                } else {
+                    anyhow::bail!(
+                        "column `{name}` is missing from the file and {} has no default",
+                        want.data_type()
+                    );
+                }
+                continue;
+            };
+            let have = file.field(index);
+            let (from, to) = (have.data_type(), want.data_type());
+            if equivalent(from, to) || numeric(from).is_none() || numeric(to).is_none() {
+                // Identical or not numeric: leave it to `serde_arrow`.
+                columns.push(ColumnPlan::Keep(index));
+                fields.push(have.clone());
+            } else if widens(from, to) {
+                if !policy.widen_numerics {
+                    anyhow::bail!(
+                        "column `{name}` is {from} in the file but {to} in T; widening is disabled"
+                    );
+                }
+                columns.push(ColumnPlan::Cast(index, to.clone()));
+                fields.push(Field::new(name, to.clone(), have.is_nullable()));
+            } else {
+                if !policy.allow_narrowing {
+                    anyhow::bail!(
+                        "column `{name}` is {from} in the file but {to} in T; narrowing is disabled"
+                    );
+                }
+                columns.push(ColumnPlan::Cast(index, to.clone()));
+                fields.push(Field::new(name, to.clone(), have.is_nullable()));
+            }
+        }
+        Ok(Self {
+            schema: Arc::new(Schema::new(fields)),
+            columns,
+        })
+    }
+
+    fn apply(&self, batch: &RecordBatch) -> Result<RecordBatch> {
+        let rows = batch.num_rows();
+        // `safe: false` makes out-of-range values an error instead of null.
+        let checked = CastOptions {
+            safe: false,
+            ..CastOptions::default()
+        };
+        let columns = self
+            .columns
+            .iter()
+            .zip(self.schema.fields())
+            .map(|(plan, field)| -> Result<ArrayRef> {
+                Ok(match plan {
+                    ColumnPlan::Keep(index) => Arc::clone(batch.column(*index)),
+                    ColumnPlan::Cast(index, to) => {
+                        cast_with_options(batch.column(*index), to, &checked)
+                            .with_context(|| format!("cast column `{}` to {to}", field.name()))?
+                    }
+                    ColumnPlan::Nulls(to) => new_null_array(to, rows),
+                    ColumnPlan::Defaults(to) => default_array(to, rows)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        RecordBatch::try_new(Arc::clone(&self.schema), columns).context("build coerced RecordBatch")
+    }
+}
+
+/// Numeric families, for deciding whether a cast widens.
+#[cfg(feature = "io-parquet")]
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Numeric {
+    Signed(u8),
+    Unsigned(u8),
+    Float(u8),
+}
+
+#[cfg(feature = "io-parquet")]
+const fn numeric(data_type: &DataType) -> Option<Numeric> {
+    Some(match data_type {
+        DataType::Int8 => Numeric::Signed(8),
+        DataType::Int16 => Numeric::Signed(16),
+        DataType::Int32 => Numeric::Signed(32),
+        DataType::Int64 => Numeric::Signed(64),
+        DataType::UInt8 => Numeric::Unsigned(8),
+        DataType::UInt16 => Numeric::Unsigned(16),
+        DataType::UInt32 => Numeric::Unsigned(32),
+        DataType::UInt64 => Numeric::Unsigned(64),
+        DataType::Float16 => Numeric::Float(16),
+        DataType::Float32 => Numeric::Float(32),
+        DataType::Float64 => Numeric::Float(64),
+        _ => return None,
+    })
+}
+
+/// Whether every value of `from` is exactly representable in `to`.
+#[cfg(feature = "io-parquet")]
+fn widens(from: &DataType, to: &DataType) -> bool {
+    match (numeric(from), numeric(to)) {
+        (Some(Numeric::Signed(a)), Some(Numeric::Signed(b)))
+        | (Some(Numeric::Unsigned(a)), Some(Numeric::Unsigned(b) | Numeric::Signed(b)))
+        | (Some(Numeric::Float(a)), Some(Numeric::Float(b))) => a < b,
+        // Integers fit a float whose mantissa is wider: up to 16 bits in f32, 32 in f64.
+        (Some(Numeric::Signed(a) | Numeric::Unsigned(a)), Some(Numeric::Float(b))) => a <= b / 2,
+        _ => false,
+    }
+}
+
+/// Whether `from` and `to` hold the same values in different Arrow layouts.
+#[cfg(feature = "io-parquet")]
+fn equivalent(from: &DataType, to: &DataType) -> bool {
+    use DataType::{Binary, BinaryView, LargeBinary, LargeUtf8, Utf8, Utf8View};
+    from == to
+        || matches!(
+            (from, to),
+            (Utf8 | LargeUtf8 | Utf8View, Utf8 | LargeUtf8 | Utf8View)
+                | (
+                    Binary | LargeBinary | BinaryView,
+                    Binary | LargeBinary | BinaryView
+                )
+        )
+}
+
+#[cfg(feature = "io-parquet")]
+const fn has_default(data_type: &DataType) -> bool {
+    numeric(data_type).is_some()
+        || matches!(
+            data_type,
+            DataType::Boolean | DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View
+        )
+}
+
+/// A column of `rows` default values (zero, `false`, `""`) of `data_type`.
+#[cfg(feature = "io-parquet")]
+fn default_array(data_type: &DataType, rows: usize) -> Result<ArrayRef> {
+    let array: ArrayRef = if *data_type == DataType::Boolean {
+        Arc::new(BooleanArray::from(vec![false; rows]))
+    } else if numeric(data_type).is_some() {
+        Arc::new(Int8Array::from(vec![0; rows]))
+    } else {
+        Arc::new(StringArray::from(vec![""; rows]))
+    };
+    cast_with_options(&array, data_type, &CastOptions::default())
+        .with_context(|| format!("build default {data_type} column"))
+}
+
 /// Write a typed `Vec<T>` to a Parquet file.
 ///
 /// Internally:
@@ -74,8 +534,24 @@ use std::fs::File;
 pub fn write_parquet_vec<T: Serialize + Deserialize<'static>>(
     path: impl AsRef<Path>,
     data: &Vec<T>,
+) -> Result<usize> {
+    write_parquet_vec_with(path, data, &ParquetWriteOptions::default())
+}
+
+/// Write a typed `Vec<T>` to a Parquet file as [`write_parquet_vec`] does, with the
+/// row groups, compression and encodings set by `opts`.
+///
+/// # Errors
+/// An error is returned if `opts` is invalid, or for the reasons of
+/// [`write_parquet_vec`].
+#[cfg(feature = "io-parquet")]
+pub fn write_parquet_vec_with<T: Serialize + Deserialize<'static>>(
+    path: impl AsRef<Path>,
+    data: &Vec<T>,
+    opts: &ParquetWriteOptions,
 ) -> Result<usize> {
     let path = path.as_ref();
+    let props = opts.writer_properties()?;
 
     // 1) Infer fields from T (works even if data.is_empty()).
     let fields: Vec<FieldRef> = Vec::<FieldRef>::from_type::<T>(TracingOptions::default())
@@ -86,18 +562,77 @@ pub fn write_parquet_vec<T: Serialize + Deserialize<'static>>(
         to_record_batch(&fields, data).context("convert rows to RecordBatch")?;
 
     // 3) Open the writer with the batch schema and always close it.
-    let file = File::create(path).with_context(|| format!("create {}", path.display()))?;
-    let props = WriterProperties::builder().build();
-    let mut writer =
-        ArrowWriter::try_new(file, batch.schema(), Some(props)).context("create ArrowWriter")?;
+    write_atomically(path, |file| {
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(props))
+            .context("create ArrowWriter")?;
 
-    // Writing a zero-row batch is fine; alternatively, you could skip write() when empty.
-    writer.write(&batch).context("write batch to parquet")?;
-    writer.close().context("close ArrowWriter")?;
+        // Writing a zero-row batch is fine; alternatively, you could skip write() when empty.
+        writer.write(&batch).context("write batch to parquet")?;
+        writer.close().context("close ArrowWriter")?;
+        Ok(())
+    })?;
 
     Ok(data.len())
 }
 
+/// Write `batches`, each holding rows of `T`, to one Parquet file with the schema
+/// inferred from `T`, as [`write_parquet_vec`] would write their rows.
+///
+/// Used by [`PCollection::write_parquet`](crate::PCollection::write_parquet) once the
+/// run has converted every partition to a batch. Writes a zero-row batch when
+/// `batches` is empty.
+///
+/// # Errors
+/// An error is returned if the schema inference, file creation, or writing fails.
+#[cfg(feature = "io-parquet")]
+pub(crate) fn write_parquet_batches_with_schema<T: Serialize + Deserialize<'static>>(
+    path: impl AsRef<Path>,
+    batches: &[ArrowBatch],
+    opts: &ParquetWriteOptions,
+) -> Result<usize> {
+    let path = path.as_ref();
+    let props = opts.writer_properties()?;
+    let fields: Vec<FieldRef> = Vec::<FieldRef>::from_type::<T>(TracingOptions::default())
+        .context("infer Arrow schema from type T")?;
+    let empty: RecordBatch =
+        to_record_batch(&fields, &Vec::<T>::new()).context("build empty RecordBatch")?;
+
+    write_atomically(path, |file| {
+        let mut writer = ArrowWriter::try_new(file, empty.schema(), Some(props))
+            .context("create ArrowWriter")?;
+        if batches.is_empty() {
+            writer.write(&empty).context("write batch to parquet")?;
+        }
+        for batch in batches {
+            writer.write(batch).context("write batch to parquet")?;
+        }
+        writer.close().context("close ArrowWriter")?;
+        Ok(())
+    })?;
+    Ok(batches.iter().map(|b| b.num_rows()).sum())
+}
+
+/// Serialize `data` into an in-memory Parquet file with the schema inferred from `T`,
+/// as one object written by
+/// [`PCollection::write_parquet_object`](crate::PCollection::write_parquet_object).
+///
+/// # Errors
+/// An error is returned if the schema inference, conversion, or writing fails.
+#[cfg(feature = "io-parquet")]
+pub(crate) fn encode_parquet_part<T: Serialize + Deserialize<'static>>(
+    data: &[T],
+) -> Result<Vec<u8>> {
+    let fields: Vec<FieldRef> = Vec::<FieldRef>::from_type::<T>(TracingOptions::default())
+        .context("infer Arrow schema from type T")?;
+    let batch: RecordBatch =
+        to_record_batch(&fields, &data).context("convert rows to RecordBatch")?;
+    let props = WriterProperties::builder().build();
+    let mut writer = ArrowWriter::try_new(Vec::new(), batch.schema(), Some(props))
+        .context("create ArrowWriter")?;
+    writer.write(&batch).context("write batch to parquet")?;
+    writer.into_inner().context("close ArrowWriter")
+}
+
 /// Read a Parquet file into a typed `Vec<T>`.
 ///
 /// Uses `ParquetRecordBatchReaderBuilder` to iterate Arrow batches from the file
@@ -110,11 +645,55 @@ pub fn write_parquet_vec<T: Serialize + Deserialize<'static>>(
 /// feature is disabled, always returns an error.
 #[cfg(feature = "io-parquet")]
 pub fn read_parquet_vec<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<Vec<T>> {
-    let path = path.as_ref();
+    read_parquet_file(path.as_ref(), None)
+}
+
+/// Read a Parquet file into a typed `Vec<T>` as [`read_parquet_vec`] does, first
+/// reconciling the file's columns with the schema of `T` under `coercion`.
+///
+/// # Errors
+/// Returns an error if a column cannot be coerced under the policy (checked before any
+/// row is read), a narrowed value is out of range, or for the reasons of
+/// [`read_parquet_vec`].
+#[cfg(feature = "io-parquet")]
+pub fn read_parquet_vec_with<T: DeserializeOwned>(
+    path: impl AsRef<Path>,
+    coercion: &SchemaCoercion,
+) -> Result<Vec<T>> {
+    read_parquet_file(path.as_ref(), Some(coercion))
+}
+
+/// Check that the file at `path` can be read as `T` under `coercion`, without reading
+/// any row, so that sharded sources fail when built rather than when run.
+///
+/// # Errors
+/// Returns an error if the file cannot be opened or a column cannot be coerced.
+#[cfg(feature = "io-parquet")]
+pub(crate) fn check_parquet_coercion<T: DeserializeOwned>(
+    path: &Path,
+    coercion: &SchemaCoercion,
+) -> Result<()> {
+    let file = File::open(path).with_context(|| format!("open {}", path.display()))?;
+    let builder =
+        ParquetRecordBatchReaderBuilder::try_new(file).context("open ParquetRecordBatchReader")?;
+    CoercionPlan::new::<T>(builder.schema(), coercion)
+        .with_context(|| format!("coerce the schema of {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(feature = "io-parquet")]
+fn read_parquet_file<T: DeserializeOwned>(
+    path: &Path,
+    coercion: Option<&SchemaCoercion>,
+) -> Result<Vec<T>> {
     let file = File::open(path).with_context(|| format!("open {}", path.display()))?;
 
     let builder =
         ParquetRecordBatchReaderBuilder::try_new(file).context("open ParquetRecordBatchReader")?;
+    let plan = coercion
+        .map(|c| CoercionPlan::new::<T>(builder.schema(), c))
+        .transpose()
+        .with_context(|| format!("coerce the schema of {}", path.display()))?;
     let mut reader = builder
         .with_batch_size(64 * 1024)
         .build()
@@ -122,6 +701,10 @@ pub fn read_parquet_vec<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<V
 
     let mut out: Vec<T> = Vec::new();
     while let Some(batch) = reader.next().transpose().context("read next batch")? {
+        let batch = match &plan {
+            Some(plan) => plan.apply(&batch)?,
+            None => batch,
+        };
         let mut rows: Vec<T> =
             from_record_batch(&batch).context("deserialize RecordBatch rows to T")?;
         out.append(&mut rows);
@@ -205,9 +788,40 @@ pub fn read_parquet_row_group_range<T: DeserializeOwned>(
     src: &ParquetShards,
     start_group: usize,
     end_group: usize,
+) -> Result<Vec<T>> {
+    read_row_groups(src, start_group, end_group, None)
+}
+
+/// Read a row-group range `[start_group, end_group)` into `Vec<T>` as
+/// [`read_parquet_row_group_range`] does, reconciling the file's columns with the
+/// schema of `T` under `coercion`.
+///
+/// # Errors
+/// Returns an error if a column cannot be coerced under the policy, a narrowed value is
+/// out of range, or for the reasons of [`read_parquet_row_group_range`].
+#[cfg(feature = "io-parquet")]
+pub fn read_parquet_row_group_range_with<T: DeserializeOwned>(
+    src: &ParquetShards,
+    start_group: usize,
+    end_group: usize,
+    coercion: &SchemaCoercion,
+) -> Result<Vec<T>> {
+    read_row_groups(src, start_group, end_group, Some(coercion))
+}
+
+#[cfg(feature = "io-parquet")]
+fn read_row_groups<T: DeserializeOwned>(
+    src: &ParquetShards,
+    start_group: usize,
+    end_group: usize,
+    coercion: Option<&SchemaCoercion>,
 ) -> Result<Vec<T>> {
     let f = File::open(&src.path).with_context(|| format!("open {}", src.path.display()))?;
     let b = ParquetRecordBatchReaderBuilder::try_new(f).context("open ParquetRecordBatchReader")?;
+    let plan = coercion
+        .map(|c| CoercionPlan::new::<T>(b.schema(), c))
+        .transpose()
+        .with_context(|| format!("coerce the schema of {}", src.path.display()))?;
     // select only the row groups for this shard
     let groups: Vec<usize> = (start_group..end_group).collect();
     let mut reader = b
@@ -217,6 +831,10 @@ pub fn read_parquet_row_group_range<T: DeserializeOwned>(
 
     let mut out: Vec<T> = Vec::new();
     while let Some(batch) = reader.next().transpose().context("read batch")? {
+        let batch = match &plan {
+            Some(plan) => plan.apply(&batch)?,
+            None => batch,
+        };
         let mut rows: Vec<T> =
             from_record_batch(&batch).context("deserialize RecordBatch rows to T")?;
         out.append(&mut rows);
@@ -241,6 +859,30 @@ pub fn write_parquet_vec<T: Serialize + Deserialize<'static>>(
     anyhow::bail!("the `io-parquet` feature is not enabled")
 }
 
+/// Stub returned when the `io-parquet` feature is disabled.
+///
+/// # Errors
+/// Always returns an error: the `io-parquet` feature is not enabled.
+#[cfg(not(feature = "io-parquet"))]
+pub fn write_parquet_vec_with<T: Serialize + Deserialize<'static>>(
+    _path: impl AsRef<Path>,
+    _data: &Vec<T>,
+    _opts: &ParquetWriteOptions,
+) -> Result<usize> {
+    anyhow::bail!("the `io-parquet` feature is not enabled")
+}
+
+/// Stub returned when the `io-parquet` feature is disabled.
+///
+/// # Errors
+/// Always returns an error: the `io-parquet` feature is not enabled.
+#[cfg(not(feature = "io-parquet"))]
+pub(crate) fn encode_parquet_part<T: Serialize + Deserialize<'static>>(
+    _data: &[T],
+) -> Result<Vec<u8>> {
+    anyhow::bail!("the `io-parquet` feature is not enabled")
+}
+
 /// Stub returned when the `io-parquet` feature is disabled.
 ///
 /// # Errors
@@ -250,6 +892,31 @@ pub fn read_parquet_vec<T: DeserializeOwned>(_path: impl AsRef<Path>) -> Result<
     anyhow::bail!("the `io-parquet` feature is not enabled")
 }
 
+/// Stub returned when the `io-parquet` feature is disabled.
+///
+/// # Errors
+/// Always returns an error: the `io-parquet` feature is not enabled.
+#[cfg(not(feature = "io-parquet"))]
+pub fn read_parquet_vec_with<T: DeserializeOwned>(
+    _path: impl AsRef<Path>,
+    _coercion: &SchemaCoercion,
+) -> Result<Vec<T>> {
+    anyhow::bail!("the `io-parquet` feature is not enabled")
+}
+
+/// Stub returned when the `io-parquet` feature is disabled.
+///
+/// # Errors
+/// Always returns an error: the `io-parquet` feature is not enabled.
+#[cfg(not(feature = "io-parquet"))]
+#[allow(clippy::extra_unused_type_parameters)] // mirrors the enabled signature
+pub(crate) fn check_parquet_coercion<T: DeserializeOwned>(
+    _path: &Path,
+    _coercion: &SchemaCoercion,
+) -> Result<()> {
+    anyhow::bail!("the `io-parquet` feature is not enabled")
+}
+
 /// Stub returned when the `io-parquet` feature is disabled.
 ///
 /// # Errors
@@ -275,6 +942,20 @@ pub fn read_parquet_row_group_range<T: DeserializeOwned>(
     anyhow::bail!("the `io-parquet` feature is not enabled")
 }
 
+/// Stub returned when the `io-parquet` feature is disabled.
+///
+/// # Errors
+/// Always returns an error: the `io-parquet` feature is not enabled.
+#[cfg(not(feature = "io-parquet"))]
+pub fn read_parquet_row_group_range_with<T: DeserializeOwned>(
+    _src: &ParquetShards,
+    _start_group: usize,
+    _end_group: usize,
+    _coercion: &SchemaCoercion,
+) -> Result<Vec<T>> {
+    anyhow::bail!("the `io-parquet` feature is not enabled")
+}
+
 // ── VecOps adapter (always available) ────────────────────────────────────────
 
 /// `VecOps` adapter for streaming Parquet via [`ParquetShards`].
@@ -283,13 +964,37 @@ pub fn read_parquet_row_group_range<T: DeserializeOwned>(
 /// - Get total length (`len`)
 /// - Split into partitions by row-group ranges (`split`)
 /// - Read the entire dataset for sequential paths (`clone_any`)
-pub struct ParquetVecOps<T>(PhantomData<T>);
+pub struct ParquetVecOps<T> {
+    coercion: Option<SchemaCoercion>,
+    _t: PhantomData<T>,
+}
 
 impl<T> ParquetVecOps<T> {
     /// Construct an `Arc` to the adapter.
     #[must_use]
     pub fn new() -> Arc<Self> {
-        Arc::new(Self(PhantomData))
+        Arc::new(Self {
+            coercion: None,
+            _t: PhantomData,
+        })
+    }
+
+    /// Construct an `Arc` to an adapter that reads shards under `coercion`.
+    #[must_use]
+    pub fn with_coercion(coercion: SchemaCoercion) -> Arc<Self> {
+        Arc::new(Self {
+            coercion: Some(coercion),
+            _t: PhantomData,
+        })
+    }
+}
+
+impl<T: DeserializeOwned> ParquetVecOps<T> {
+    fn read_range(&self, src: &ParquetShards, start: usize, end: usize) -> Result<Vec<T>> {
+        match &self.coercion {
+            Some(coercion) => read_parquet_row_group_range_with(src, start, end, coercion),
+            None => read_parquet_row_group_range(src, start, end),
+        }
     }
 }
 
@@ -302,11 +1007,17 @@ where
         usize::try_from(s.total_rows).ok()
     }
 
+    fn byte_size_hint(&self, data: &dyn Any) -> Option<usize> {
+        let s = data.downcast_ref::<ParquetShards>()?;
+        let meta = metadata(&s.path).ok()?;
+        usize::try_from(meta.len()).ok()
+    }
+
     fn split(&self, data: &dyn Any, _n: usize) -> Option<Vec<Partition>> {
         let s = data.downcast_ref::<ParquetShards>()?;
         let mut parts: Vec<Partition> = Vec::with_capacity(s.group_ranges.len());
         for &(start, end) in &s.group_ranges {
-            let v: Vec<T> = read_parquet_row_group_range::<T>(s, start, end).ok()?;
+            let v: Vec<T> = self.read_range(s, start, end).ok()?;
             parts.push(Box::new(v) as Partition);
         }
         Some(parts)
@@ -314,9 +1025,9 @@ where
 
     fn clone_any(&self, data: &dyn Any) -> Option<Partition> {
         let s = data.downcast_ref::<ParquetShards>()?;
-        let v: Vec<T> =
-            read_parquet_row_group_range::<T>(s, 0, s.group_ranges.last().map_or(0, |&(_, e)| e))
-                .ok()?;
+        let v: Vec<T> = self
+            .read_range(s, 0, s.group_ranges.last().map_or(0, |&(_, e)| e))
+            .ok()?;
         Some(Box::new(v) as Partition)
     }
 }
@@ -0,0 +1,260 @@
+//! Local SQLite database I/O.
+//!
+//! This module provides:
+//! - **Queries**: [`read_sql_query_vec`] runs a query and deserializes each row into `T`
+//! - **Tables**: [`write_table_vec`] inserts records into a table, creating it if needed
+//!
+//! [`read_sql_query`](crate::read_sql_query) and
+//! [`PCollection::write_table`](crate::PCollection::write_table) wrap these for
+//! pipelines.
+//!
+//! # Mapping
+//! Rows and records meet through serde's data model, with columns matched to fields by
+//! name:
+//!
+//! | Rust (serde)              | SQLite             |
+//! |---------------------------|--------------------|
+//! | integers                  | `INTEGER`          |
+//! | floats                    | `REAL`             |
+//! | strings                   | `TEXT`             |
+//! | `bool`                    | `INTEGER` `0`/`1`  |
+//! | `None`                    | `NULL`             |
+//! | sequences, maps, structs  | `TEXT` holding JSON |
+//!
+//! JSON text decodes back into sequences, maps and structs when a row does not
+//! deserialize with it left as a string. `BLOB`s read as byte arrays. SQLite has no boolean type, so a `bool` written by
+//! [`write_table_vec`] reads back as an integer; select it as `col != 0` into a
+//! numeric field, or keep such columns as integers on the Rust side.
+//!
+//! # Feature gating
+//! This module only exists when the `io-sqlite` feature is enabled, since it compiles
+//! SQLite itself into the crate.
+//!
+//! ```no_run
+//! use ironbeam::io::sqlite::{read_sql_query_vec, write_table_vec};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Sale {
+//!     region: String,
+//!     cents: i64,
+//! }
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! write_table_vec("local.db", "sales", &[Sale { region: "eu".into(), cents: 1200 }])?;
+//! let eu: Vec<Sale> = read_sql_query_vec("local.db", "SELECT * FROM sales WHERE region = 'eu'")?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::io::atomic::create_parent_dir;
+use anyhow::{Context, Result, bail};
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use rusqlite::{Connection, OpenFlags, params_from_iter};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Number, Value};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// Run `query` against the SQLite database at `path` and deserialize each row into a
+/// `T`, with columns matched to fields by name.
+///
+/// The database is opened read-only.
+///
+/// # Errors
+/// Returns an error if the database cannot be opened, the query fails, a text column
+/// is not valid UTF-8, or a row fails to deserialize.
+pub fn read_sql_query_vec<T: DeserializeOwned>(
+    path: impl AsRef<Path>,
+    query: &str,
+) -> Result<Vec<T>> {
+    let path = path.as_ref();
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .with_context(|| format!("open {}", path.display()))?;
+    let mut stmt = conn
+        .prepare(query)
+        .with_context(|| format!("prepare query on {}", path.display()))?;
+    let columns: Vec<String> = stmt
+        .column_names()
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+
+    let mut rows = stmt.query([])?;
+    let mut out = Vec::new();
+    while let Some(row) = rows.next()? {
+        let mut record = Map::with_capacity(columns.len());
+        for (i, column) in columns.iter().enumerate() {
+            let value = to_json(row.get_ref(i)?)
+                .with_context(|| format!("row #{}, column {column}", out.len()))?;
+            record.insert(column.clone(), value);
+        }
+        out.push(from_row(record).with_context(|| format!("deserialize row #{}", out.len()))?);
+    }
+    Ok(out)
+}
+
+/// Insert `data` into `table` of the SQLite database at `path`, in one transaction.
+/// Returns the number of rows inserted.
+///
+/// The database file is created if it does not exist, and the table if it has no such
+/// table yet, with one column per field seen in `data` and column types inferred from
+/// the first non-null value of each. Rows are appended to an existing table. Writing
+/// no rows creates nothing.
+///
+/// # Errors
+/// Returns an error if a record does not serialize to a struct or map, the database
+/// cannot be opened, or creating the table or inserting a row fails; in that case no
+/// row is inserted.
+pub fn write_table_vec<T: Serialize>(
+    path: impl AsRef<Path>,
+    table: &str,
+    data: &[T],
+) -> Result<usize> {
+    let path = path.as_ref();
+    let records = data
+        .iter()
+        .enumerate()
+        .map(|(i, record)| match serde_json::to_value(record) {
+            Ok(Value::Object(fields)) => Ok(fields),
+            Ok(_) => bail!("record #{i} does not serialize to a struct or map"),
+            Err(err) => Err(err).with_context(|| format!("serialize record #{i}")),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    if records.is_empty() {
+        return Ok(0);
+    }
+
+    // Column name -> declared type, from the first non-null value of each column.
+    let mut columns: BTreeMap<&str, Option<&'static str>> = BTreeMap::new();
+    for record in &records {
+        for (name, value) in record {
+            let declared = columns.entry(name.as_str()).or_default();
+            if declared.is_none() {
+                *declared = column_type(value);
+            }
+        }
+    }
+
+    create_parent_dir(path)?;
+    let mut conn = Connection::open(path).with_context(|| format!("open {}", path.display()))?;
+    let tx = conn.transaction()?;
+    let definitions: Vec<String> = columns
+        .iter()
+        .map(|(name, declared)| match declared {
+            Some(declared) => format!("{} {declared}", quote(name)),
+            None => quote(name),
+        })
+        .collect();
+    tx.execute_batch(&format!(
+        "CREATE TABLE IF NOT EXISTS {} ({})",
+        quote(table),
+        definitions.join(", ")
+    ))
+    .with_context(|| format!("create table {table} in {}", path.display()))?;
+
+    let names: Vec<&str> = columns.keys().copied().collect();
+    let insert = format!(
+        "INSERT INTO {} ({}) VALUES ({})",
+        quote(table),
+        names
+            .iter()
+            .map(|n| quote(n))
+            .collect::<Vec<_>>()
+            .join(", "),
+        (1..=names.len())
+            .map(|i| format!("?{i}"))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    {
+        let mut stmt = tx
+            .prepare(&insert)
+            .with_context(|| format!("prepare insert into {table}"))?;
+        for (i, record) in records.iter().enumerate() {
+            let values = names
+                .iter()
+                .map(|name| record.get(*name).map_or(SqlValue::Null, to_sql));
+            stmt.execute(params_from_iter(values))
+                .with_context(|| format!("insert record #{i} into {table}"))?;
+        }
+    }
+    tx.commit()
+        .with_context(|| format!("commit to {}", path.display()))?;
+    Ok(records.len())
+}
+
+/// Deserialize a row, retrying with JSON text columns decoded if the plain mapping
+/// fails, so nested values written by [`write_table_vec`] read back.
+fn from_row<T: DeserializeOwned>(record: Map<String, Value>) -> serde_json::Result<T> {
+    match serde_json::from_value(Value::Object(record.clone())) {
+        Ok(row) => Ok(row),
+        Err(err) => {
+            let mut decoded = false;
+            let record = record
+                .into_iter()
+                .map(|(column, value)| match value {
+                    Value::String(text) if text.starts_with(['[', '{']) => {
+                        match serde_json::from_str::<Value>(&text) {
+                            Ok(nested @ (Value::Array(_) | Value::Object(_))) => {
+                                decoded = true;
+                                (column, nested)
+                            }
+                            _ => (column, Value::String(text)),
+                        }
+                    }
+                    value => (column, value),
+                })
+                .collect();
+            if decoded {
+                serde_json::from_value(Value::Object(record)).map_err(|_| err)
+            } else {
+                Err(err)
+            }
+        }
+    }
+}
+
+/// Quote an SQL identifier.
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+/// Declared type of a column whose first non-null value is `value`.
+fn column_type(value: &Value) -> Option<&'static str> {
+    match value {
+        Value::Null => None,
+        Value::Bool(_) => Some("INTEGER"),
+        Value::Number(n) if n.is_f64() => Some("REAL"),
+        Value::Number(_) => Some("INTEGER"),
+        Value::String(_) | Value::Array(_) | Value::Object(_) => Some("TEXT"),
+    }
+}
+
+fn to_sql(value: &Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(i64::from(*b)),
+        Value::Number(n) => n.as_i64().map_or_else(
+            || SqlValue::Real(n.as_f64().unwrap_or(f64::NAN)),
+            SqlValue::Integer,
+        ),
+        Value::String(s) => SqlValue::Text(s.clone()),
+        Value::Array(_) | Value::Object(_) => SqlValue::Text(value.to_string()),
+    }
+}
+
+fn to_json(value: ValueRef<'_>) -> Result<Value> {
+    Ok(match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => Value::from(i),
+        ValueRef::Real(f) => Number::from_f64(f).map_or(Value::Null, Value::Number),
+        ValueRef::Text(bytes) => Value::String(
+            std::str::from_utf8(bytes)
+                .context("text is not valid UTF-8")?
+                .to_string(),
+        ),
+        ValueRef::Blob(bytes) => Value::from(bytes.to_vec()),
+    })
+}
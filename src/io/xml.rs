@@ -4,6 +4,8 @@
 //! - **Typed vector I/O** with Serde: [`read_xml_vec`] and [`write_xml_vec`]
 //! - **Deterministic parallel writer**: [`write_xml_par`] (feature `parallel-io`)
 //! - **Streaming ingestion** (single-shard): [`XmlShards`], [`build_xml_shards`], [`read_xml_range`]
+//! - **Record extraction by tag path** from arbitrary documents:
+//!   [`read_xml_records_vec`] and [`build_xml_record_shards`]
 //! - **Execution runner integration**: [`XmlVecOps<T>`] implements [`VecOps`] over `XmlShards`
 //!
 //! # Wire Format
@@ -17,6 +19,15 @@
 //! The `<records>` root and `<item>` child elements are internal conventions;
 //! users only interact with `T` via Serde.
 //!
+//! # Record paths
+//!
+//! Third-party feeds rarely follow that layout. A **record path** such as
+//! `"catalog/books/book"` selects every element reached by that chain of element
+//! names from the document root, and each selected element is deserialized into one
+//! `T`. Names are matched without namespace prefixes, and a `*` segment matches any
+//! element name. Documents read this way are streamed element by element, so they
+//! can be split into several shards by record count.
+//!
 //! # Notes
 //! - XML is not byte-splittable. Sharding always produces exactly **one shard**
 //!   covering all records. The `records_per_shard` parameter of
@@ -40,20 +51,25 @@ use anyhow::Result;
 use serde::Serialize;
 use serde::de::DeserializeOwned;
 use std::any::Any;
+use std::fs::metadata;
 use std::marker::PhantomData;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+#[cfg(feature = "io-xml")]
+use crate::io::atomic::{create_parent_dir, write_atomically};
 #[cfg(feature = "io-xml")]
 use crate::io::compression::{auto_detect_reader, auto_detect_writer};
 #[cfg(feature = "io-xml")]
 use anyhow::Context;
 #[cfg(feature = "io-xml")]
+use quick_xml::Writer;
+#[cfg(feature = "io-xml")]
 use quick_xml::events::Event;
 #[cfg(feature = "io-xml")]
 use serde::Deserialize;
 #[cfg(feature = "io-xml")]
-use std::fs::{File, create_dir_all};
+use std::fs::File;
 #[cfg(feature = "io-xml")]
 use std::io::{BufReader, Read, Write};
 #[cfg(feature = "io-xml")]
@@ -134,6 +150,123 @@ fn xml_count_items(path: &Path) -> Result<u64> {
     Ok(count)
 }
 
+/// Split a record path like `"catalog/book"` into its element names.
+#[cfg(feature = "io-xml")]
+fn parse_record_path(record_path: &str) -> Result<Vec<String>> {
+    let segments: Vec<String> = record_path
+        .split('/')
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect();
+    if segments.is_empty() {
+        anyhow::bail!("XML record path {record_path:?} names no element");
+    }
+    Ok(segments)
+}
+
+/// Walk the elements of `path` selected by `record_path`, passing the XML text of
+/// records `[start, end)` to `on_record`.
+///
+/// Returns the number of records seen; reading stops early once `end` is reached,
+/// so pass `start == end` to count the whole file.
+#[cfg(feature = "io-xml")]
+fn xml_scan_records(
+    path: &Path,
+    record_path: &str,
+    start: u64,
+    end: u64,
+    mut on_record: impl FnMut(&str) -> Result<()>,
+) -> Result<u64> {
+    let segments = parse_record_path(record_path)?;
+    let selects = |stack: &[Vec<u8>]| {
+        stack.len() == segments.len()
+            && stack
+                .iter()
+                .zip(&segments)
+                .all(|(name, seg)| seg == "*" || name.as_slice() == seg.as_bytes())
+    };
+
+    let mut reader = quick_xml::Reader::from_reader(open_xml_reader(path)?);
+    let mut stack: Vec<Vec<u8>> = Vec::new();
+    // Depth of the record being captured, and the writer re-serializing it.
+    let mut capture: Option<(usize, Writer<Vec<u8>>)> = None;
+    let mut count = 0u64;
+    let mut buf = Vec::new();
+    loop {
+        let event = reader
+            .read_event_into(&mut buf)
+            .with_context(|| format!("read records from {}", path.display()))?;
+        let mut begins = false;
+        let mut ends = false;
+        match &event {
+            Event::Start(e) => {
+                stack.push(e.local_name().as_ref().to_vec());
+                begins = capture.is_none() && selects(&stack);
+            }
+            Event::Empty(e) => {
+                stack.push(e.local_name().as_ref().to_vec());
+                begins = capture.is_none() && selects(&stack);
+                ends = begins;
+                stack.pop();
+            }
+            Event::End(_) => {
+                ends = capture
+                    .as_ref()
+                    .is_some_and(|(depth, _)| *depth == stack.len());
+                stack.pop();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        if begins {
+            if (start..end).contains(&count) {
+                capture = Some((stack.len(), Writer::new(Vec::new())));
+            }
+            count += 1;
+        }
+        if let Some((_, writer)) = capture.as_mut() {
+            writer
+                .write_event(event)
+                .with_context(|| format!("buffer record from {}", path.display()))?;
+        }
+        if ends && let Some((_, writer)) = capture.take() {
+            let xml = String::from_utf8(writer.into_inner())
+                .with_context(|| format!("record in {} is not UTF-8", path.display()))?;
+            on_record(&xml)?;
+        }
+        if start < end && count >= end && capture.is_none() {
+            break;
+        }
+        buf.clear();
+    }
+    Ok(count)
+}
+
+/// Read records `[start, end)` selected by `record_path` into `Vec<T>`.
+#[cfg(feature = "io-xml")]
+fn xml_read_records<T: DeserializeOwned>(
+    path: &Path,
+    record_path: &str,
+    start: u64,
+    end: u64,
+) -> Result<Vec<T>> {
+    let mut out = Vec::new();
+    let mut index = start;
+    xml_scan_records(path, record_path, start, end, |xml| {
+        let v = quick_xml::de::from_str::<T>(xml).with_context(|| {
+            format!(
+                "deserialize XML record #{} ({record_path}) from {}",
+                index + 1,
+                path.display()
+            )
+        })?;
+        out.push(v);
+        index += 1;
+        Ok(())
+    })?;
+    Ok(out)
+}
+
 /// Build [`XmlShards`] from a counted total.
 ///
 /// XML is not splittable, so this always produces at most one shard.
@@ -144,12 +277,14 @@ fn make_xml_shards(path: PathBuf, total: u64) -> XmlShards {
             path,
             ranges: vec![],
             total_records: 0,
+            record_path: None,
         };
     }
     XmlShards {
         path,
         ranges: vec![(0, total)],
         total_records: total,
+        record_path: None,
     }
 }
 
@@ -173,6 +308,41 @@ pub fn read_xml_vec<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<Vec<T
     Ok(wrapper.item)
 }
 
+/// Read the elements of an XML file selected by `record_path` into a typed `Vec<T>`.
+///
+/// Each element reached by the path (see the [module docs](self#record-paths)) is
+/// deserialized into one `T`, in document order; everything else in the document is
+/// ignored. Compression is auto-detected.
+///
+/// # Example
+/// ```no_run
+/// use ironbeam::io::xml::read_xml_records_vec;
+/// use serde::Deserialize;
+///
+/// // <catalog><book id="1"><title>Dune</title></book>...</catalog>
+/// #[derive(Deserialize)]
+/// struct Book {
+///     #[serde(rename = "@id")]
+///     id: u32,
+///     title: String,
+/// }
+///
+/// let books: Vec<Book> = read_xml_records_vec("catalog.xml", "catalog/book")?;
+/// # Ok::<(), anyhow::Error>(())
+/// ```
+///
+/// # Errors
+/// Returns an error if `record_path` is empty, the file cannot be read as XML, or any
+/// record fails to deserialize into `T`. When the `io-xml` feature is disabled,
+/// always returns an error.
+#[cfg(feature = "io-xml")]
+pub fn read_xml_records_vec<T: DeserializeOwned>(
+    path: impl AsRef<Path>,
+    record_path: &str,
+) -> Result<Vec<T>> {
+    xml_read_records(path.as_ref(), record_path, 0, u64::MAX)
+}
+
 /// Write a typed slice to an XML file.
 ///
 /// Parent directories are created as needed. Compression is auto-detected from
@@ -187,22 +357,20 @@ pub fn read_xml_vec<T: DeserializeOwned>(path: impl AsRef<Path>) -> Result<Vec<T
 #[cfg(feature = "io-xml")]
 pub fn write_xml_vec<T: Serialize>(path: impl AsRef<Path>, data: &[T]) -> Result<usize> {
     let path = path.as_ref();
-    if let Some(parent) = path.parent()
-        && !parent.as_os_str().is_empty()
-    {
-        create_dir_all(parent).with_context(|| format!("mkdir -p {}", parent.display()))?;
-    }
-    let f = File::create(path).with_context(|| format!("create {}", path.display()))?;
-    let mut w = auto_detect_writer(f, path)
-        .with_context(|| format!("setup compression for {}", path.display()))?;
-    // quick-xml's Serializer uses fmt::Write; serialize to a String, then copy bytes.
-    let wrapper = XmlFileWrite { item: data };
-    let xml = quick_xml::se::to_string(&wrapper)
-        .with_context(|| format!("serialise XML to {}", path.display()))?;
-    w.write_all(xml.as_bytes())
-        .with_context(|| format!("write XML to {}", path.display()))?;
-    w.flush()
-        .with_context(|| format!("flush {}", path.display()))?;
+    create_parent_dir(path)?;
+    write_atomically(path, |f| {
+        let mut w = auto_detect_writer(f, path)
+            .with_context(|| format!("setup compression for {}", path.display()))?;
+        // quick-xml's Serializer uses fmt::Write; serialize to a String, then copy bytes.
+        let wrapper = XmlFileWrite { item: data };
+        let xml = quick_xml::se::to_string(&wrapper)
+            .with_context(|| format!("serialise XML to {}", path.display()))?;
+        w.write_all(xml.as_bytes())
+            .with_context(|| format!("write XML to {}", path.display()))?;
+        w.flush()
+            .with_context(|| format!("flush {}", path.display()))?;
+        Ok(())
+    })?;
     Ok(data.len())
 }
 
@@ -234,11 +402,7 @@ pub fn write_xml_par<T: Serialize + Sync>(
     use rayon::prelude::*;
 
     let path = path.as_ref();
-    if let Some(parent) = path.parent()
-        && !parent.as_os_str().is_empty()
-    {
-        create_dir_all(parent).with_context(|| format!("mkdir -p {}", parent.display()))?;
-    }
+    create_parent_dir(path)?;
 
     let _ = shards; // rayon auto-scales
 
@@ -256,19 +420,21 @@ pub fn write_xml_par<T: Serialize + Sync>(
         .with_context(|| format!("serialise records to XML for {}", path.display()))?;
 
     // Sequential write: root element wrapping all fragments.
-    let f = File::create(path).with_context(|| format!("create {}", path.display()))?;
-    let mut w = auto_detect_writer(f, path)
-        .with_context(|| format!("setup compression for {}", path.display()))?;
-    w.write_all(b"<records>")
-        .with_context(|| format!("write XML header to {}", path.display()))?;
-    for frag in &fragments {
-        w.write_all(frag.as_bytes())
-            .with_context(|| format!("write XML fragment to {}", path.display()))?;
-    }
-    w.write_all(b"</records>")
-        .with_context(|| format!("write XML footer to {}", path.display()))?;
-    w.flush()
-        .with_context(|| format!("flush {}", path.display()))?;
+    write_atomically(path, |f| {
+        let mut w = auto_detect_writer(f, path)
+            .with_context(|| format!("setup compression for {}", path.display()))?;
+        w.write_all(b"<records>")
+            .with_context(|| format!("write XML header to {}", path.display()))?;
+        for frag in &fragments {
+            w.write_all(frag.as_bytes())
+                .with_context(|| format!("write XML fragment to {}", path.display()))?;
+        }
+        w.write_all(b"</records>")
+            .with_context(|| format!("write XML footer to {}", path.display()))?;
+        w.flush()
+            .with_context(|| format!("flush {}", path.display()))?;
+        Ok(())
+    })?;
     Ok(data.len())
 }
 
@@ -279,16 +445,19 @@ pub fn write_xml_par<T: Serialize + Sync>(
 /// Produced by [`build_xml_shards`] and consumed by [`read_xml_range`] and the
 /// execution engine via [`XmlVecOps`].
 ///
-/// Because XML is not byte-splittable, `ranges` will always contain at most one
-/// entry covering the entire file.
+/// For the `<records><item>` layout, `ranges` contains at most one entry covering
+/// the entire file. Shards built by [`build_xml_record_shards`] stream the records
+/// selected by `record_path` and may have several ranges.
 #[derive(Clone)]
 pub struct XmlShards {
     /// Source file path.
     pub path: PathBuf,
-    /// Record ranges `(start, end)` (0-based, end-exclusive). At most one entry.
+    /// Record ranges `(start, end)` (0-based, end-exclusive).
     pub ranges: Vec<(u64, u64)>,
     /// Total number of records in the file.
     pub total_records: u64,
+    /// Record path selecting the records, or `None` for the `<records><item>` layout.
+    pub record_path: Option<String>,
 }
 
 /// Build [`XmlShards`] by counting items in `path`, producing at most one shard.
@@ -310,10 +479,44 @@ pub fn build_xml_shards(path: impl AsRef<Path>, records_per_shard: usize) -> Res
     Ok(make_xml_shards(path, total))
 }
 
+/// Build [`XmlShards`] over the records selected by `record_path`, with up to
+/// `records_per_shard` records per shard.
+///
+/// Counting streams through the whole file once; each shard later re-reads the file
+/// from the start and skips the records before its range.
+///
+/// # Errors
+/// Returns an error if `record_path` is empty or the file cannot be read as XML. When
+/// the `io-xml` feature is disabled, always returns an error.
+///
+/// # Panics
+/// If the shard calculation overflows.
+#[cfg(feature = "io-xml")]
+pub fn build_xml_record_shards(
+    path: impl AsRef<Path>,
+    record_path: &str,
+    records_per_shard: usize,
+) -> Result<XmlShards> {
+    let path = path.as_ref().to_path_buf();
+    let total = xml_scan_records(&path, record_path, 0, 0, |_| Ok(()))?;
+    let rps = records_per_shard.max(1) as u64;
+    let shards = usize::try_from(total.div_ceil(rps)).expect("overflow while calculating shards");
+    let ranges = (0..shards as u64)
+        .map(|i| (i * rps, ((i + 1) * rps).min(total)))
+        .collect();
+    Ok(XmlShards {
+        path,
+        ranges,
+        total_records: total,
+        record_path: Some(record_path.to_string()),
+    })
+}
+
 /// Read a `[start, end)` record range from an XML file into `Vec<T>`.
 ///
-/// Because XML must be loaded in full, this reads the entire file then slices
-/// `items[start..end]`.
+/// For the `<records><item>` layout, XML must be loaded in full, so this reads the
+/// entire file then slices `items[start..end]`. With a record path, only the records
+/// up to `end` are parsed.
 ///
 /// # Errors
 /// Returns an error if the file cannot be opened or items fail to deserialize.
@@ -324,6 +527,9 @@ pub fn read_xml_range<T: DeserializeOwned + Clone>(
     start: u64,
     end: u64,
 ) -> Result<Vec<T>> {
+    if let Some(record_path) = &src.record_path {
+        return xml_read_records(&src.path, record_path, start, end);
+    }
     let all = read_xml_vec::<T>(&src.path)?;
     let s = usize::try_from(start).unwrap_or(0).min(all.len());
     let e = usize::try_from(end).unwrap_or(all.len()).min(all.len());
@@ -344,6 +550,18 @@ pub fn read_xml_vec<T: DeserializeOwned>(_path: impl AsRef<std::path::Path>) ->
     anyhow::bail!("the `io-xml` feature is not enabled")
 }
 
+/// Stub returned when the `io-xml` feature is disabled.
+///
+/// # Errors
+/// Always returns an error: the `io-xml` feature is not enabled.
+#[cfg(not(feature = "io-xml"))]
+pub fn read_xml_records_vec<T: DeserializeOwned>(
+    _path: impl AsRef<std::path::Path>,
+    _record_path: &str,
+) -> Result<Vec<T>> {
+    anyhow::bail!("the `io-xml` feature is not enabled")
+}
+
 /// Stub returned when the `io-xml` feature is disabled.
 ///
 /// # Errors
@@ -384,6 +602,19 @@ pub fn build_xml_shards(
     anyhow::bail!("the `io-xml` feature is not enabled")
 }
 
+/// Stub returned when the `io-xml` feature is disabled.
+///
+/// # Errors
+/// Always returns an error: the `io-xml` feature is not enabled.
+#[cfg(not(feature = "io-xml"))]
+pub fn build_xml_record_shards(
+    _path: impl AsRef<std::path::Path>,
+    _record_path: &str,
+    _records_per_shard: usize,
+) -> Result<XmlShards> {
+    anyhow::bail!("the `io-xml` feature is not enabled")
+}
+
 /// Stub returned when the `io-xml` feature is disabled.
 ///
 /// # Errors
@@ -423,6 +654,12 @@ where
         usize::try_from(s.total_records).ok()
     }
 
+    fn byte_size_hint(&self, data: &dyn Any) -> Option<usize> {
+        let s = data.downcast_ref::<XmlShards>()?;
+        let meta = metadata(&s.path).ok()?;
+        usize::try_from(meta.len()).ok()
+    }
+
     fn split(&self, data: &dyn Any, _n: usize) -> Option<Vec<Partition>> {
         let s = data.downcast_ref::<XmlShards>()?;
         let mut parts = Vec::<Partition>::with_capacity(s.ranges.len());
@@ -190,6 +190,14 @@
 //! - `metrics` - Enable metrics collection and reporting (enabled by default)
 //! - `checkpointing` - Enable automatic checkpointing for fault tolerance (enabled by default)
 //! - `spilling` - Enable automatic memory spilling to disk (enabled by default)
+//! - `cluster` - Enable the experimental multi-process [`cluster::ClusterRunner`] (enabled by default)
+//! - `result-cache` - Enable persistent intermediate results with [`result_cache::ResultCache`] (enabled by default)
+//! - `sql` - Enable SQL queries over collections with [`Pipeline::sql`] (enabled by default)
+//! - `cli` - Enable the command-line pipeline runner [`cli::Cli`] (enabled by default)
+//! - `async` - Enable `map_async` and `collect_async` on a tokio runtime (opt-in)
+//! - `bench` - Enable pipeline micro-benchmarks with `bench::PipelineBench` (opt-in)
+//! - `time` - Enable calendar-aware timestamp parsing and bucketing in `helpers::time` (opt-in)
+//! - `geo` - Enable geohash keys, distance filters and spatial joins in `transforms::geo` (opt-in)
 //!
 //! ## Examples
 //!
@@ -335,9 +343,10 @@
 //!     policy: CheckpointPolicy::AfterEveryBarrier,
 //!     auto_recover: true,
 //!     max_checkpoints: Some(5),
+//!     ..Default::default()
 //! };
 //!
-//! let runner = Runner {
+//! let runner = DirectRunner {
 //!     mode: ExecMode::Sequential,
 //!     checkpoint_config: Some(checkpoint_config),
 //!     ..Default::default()
@@ -464,11 +473,15 @@
 //! - [`pipeline`] - Pipeline construction and management
 //! - [`io`] - I/O operations for JSON Lines, CSV, and Parquet
 //! - [`runner`] - Execution engine (sequential and parallel modes)
+//! - [`pipeline_result`] - Collected output with run metadata (timings, partitions, warnings)
 //! - [`planner`] - Query optimization and graph transformations
 //! - [`helpers`] - Convenience functions and side input builders
 //! - [`extensions`] - Extension points for custom transforms and I/O
+//! - [`transforms`] - Reusable composite transforms (text preprocessing, geospatial)
 //! - [`metrics`] - Metrics collection and reporting (feature: `metrics`)
 //! - [`checkpoint`] - Automatic checkpointing for fault tolerance (feature: `checkpointing`)
+//! - [`cluster`] - Experimental multi-process runner (feature: `cluster`)
+//! - [`result_cache`] - Persistent intermediate results for incremental runs (feature: `result-cache`)
 //!
 //! ## Extensibility
 //!
@@ -499,6 +512,10 @@
 //! Implement [`VecOps`] to integrate custom data sources.
 //! See [`from_custom_source`] for a complete example.
 //!
+//! ### Custom I/O Sinks
+//! Implement [`Sink`] and call [`PCollection::write_to`] to write to custom
+//! destinations (databases, HTTP endpoints, message queues).
+//!
 //! ### Composite Transforms
 //! Use [`CompositeTransform`] to package reusable pipelines:
 //! ```no_run
@@ -515,55 +532,91 @@
 //! ```
 
 pub mod bloom_filter;
+pub mod cancel;
 #[cfg(feature = "coders")]
 pub mod coders;
 pub mod collection;
 pub mod combiners;
+pub mod error;
 pub mod extensions;
 pub mod helpers;
+pub mod hnsw;
 pub mod io;
+pub mod manifest;
+pub mod memory;
 pub mod node;
 pub mod node_id;
 pub mod pipeline;
+pub mod pipeline_result;
 pub mod planner;
+pub mod row;
 pub mod runner;
+pub mod spec;
 pub mod testing;
+pub mod transforms;
 pub mod type_token;
 pub mod utils;
 pub mod validation;
+pub mod visualize;
 pub mod window;
 
 #[cfg(feature = "metrics")]
 pub mod metrics;
 
+#[cfg(feature = "bench")]
+pub mod bench;
+
 #[cfg(feature = "checkpointing")]
 pub mod checkpoint;
 
+#[cfg(feature = "cluster")]
+pub mod cluster;
+
+#[cfg(feature = "result-cache")]
+pub mod result_cache;
+
+#[cfg(feature = "sql")]
+pub mod sql;
+
+#[cfg(feature = "cli")]
+pub mod cli;
+
 #[cfg(feature = "spilling")]
 pub mod spill;
+#[cfg(feature = "spilling")]
+pub mod spill_group;
 
 pub mod spill_integration;
 
 // General re-exports
+pub use cancel::{CancelReason, CancellationToken};
 pub use collection::{
     CombineFn, Count, Element, PCollection, SideInput, SideMap, SideMultimap, SideSingleton,
 };
 pub use combiners::{AverageF64, BottomK, DistinctCount, Max, Min, Sum, TopK};
+pub use error::{IronbeamError, StepRef};
 pub use helpers::*;
+pub use hnsw::VectorMetric;
 pub use node_id::NodeId;
 pub use pipeline::Pipeline;
+pub use pipeline_result::{PipelineResult, StageSummary};
 pub use planner::{
     CostEstimate, ExecutionExplanation, ExplainStep, OptimizationDecision, Plan, build_plan,
 };
-pub use runner::{ExecMode, Runner, SharedCSECache};
+pub use row::Row;
+#[allow(deprecated)]
+pub use runner::{
+    DirectRunner, ExecMode, PipelineRunner, PrepareContext, Runner, RunnerConfig,
+    RunnerConfigBuilder, SharedCSECache,
+};
 pub use type_token::Partition;
 pub use utils::OrdF64;
-pub use window::{TimestampMs, Timestamped, Window};
+pub use window::{Clock, Session, SystemClock, TimestampMs, Timestamped, Window};
 
 // Extension point exports
-pub use extensions::CompositeTransform;
+pub use extensions::{CompositeTransform, Sink};
 pub use node::DynOp;
-pub use type_token::{TypeTag, VecOps};
+pub use type_token::{LazyPartition, TypeTag, VecOps};
 
 #[cfg(feature = "coders")]
 pub use coders::{ElementCoder, PostcardCoder, PostcardKvCoder};
@@ -571,24 +624,38 @@ pub use coders::{ElementCoder, PostcardCoder, PostcardKvCoder};
 // I/O re-exports. The API surface is always present (the modules compile
 // unconditionally and stub at runtime when their feature is disabled); only the
 // `*_par` writers stay behind `parallel-io`, which remains a compile gate.
-pub use io::jsonl::{read_jsonl_range, read_jsonl_vec};
+pub use io::jsonl::{
+    JsonlLineError, read_jsonl_range, read_jsonl_vec, read_jsonl_vec_lenient,
+    read_jsonl_vec_numbered, write_jsonl_vec_with,
+};
+
+pub use io::compression::{Compression, WriteOptions};
 
 pub use helpers::jsonl::read_jsonl_streaming;
 
 #[cfg(feature = "parallel-io")]
 pub use io::jsonl::write_jsonl_par;
 
-pub use io::csv::{read_csv_vec, write_csv, write_csv_vec};
+pub use io::csv::{read_csv_vec, read_csv_vec_with, write_csv, write_csv_vec, write_csv_vec_with};
 
 #[cfg(feature = "parallel-io")]
 pub use io::csv::write_csv_par;
 
-pub use io::parquet::{read_parquet_vec, write_parquet_vec};
+pub use io::parquet::{
+    read_parquet_vec, read_parquet_vec_with, write_parquet_vec, write_parquet_vec_with,
+};
 
 pub use helpers::csv::read_csv;
 pub use helpers::csv::read_csv_streaming;
+pub use helpers::csv::{read_csv_streaming_with, read_csv_with};
 pub use helpers::jsonl::read_jsonl;
-pub use helpers::parquet::read_parquet_streaming;
+pub use helpers::jsonl::read_jsonl_checked;
+pub use helpers::jsonl::read_jsonl_lenient;
+#[cfg(feature = "io-parquet")]
+pub use helpers::lakehouse::read_delta_table;
+pub use helpers::parquet::{read_parquet_streaming, read_parquet_streaming_with};
+#[cfg(feature = "io-parquet")]
+pub use io::lakehouse::read_delta_vec;
 
 pub use io::avro::{read_avro_vec, write_avro_vec};
 
@@ -597,9 +664,11 @@ pub use helpers::avro::{read_avro, read_avro_streaming};
 #[cfg(feature = "parallel-io")]
 pub use io::avro::write_avro_par;
 
-pub use io::xml::{read_xml_vec, write_xml_vec};
+pub use io::xml::{read_xml_records_vec, read_xml_vec, write_xml_vec};
 
-pub use helpers::xml::{read_xml, read_xml_streaming};
+pub use helpers::xml::{
+    read_xml, read_xml_records, read_xml_records_streaming, read_xml_streaming,
+};
 
 #[cfg(feature = "parallel-io")]
 pub use io::xml::write_xml_par;
@@ -610,3 +679,23 @@ pub use helpers::msgpack::{read_msgpack, read_msgpack_streaming};
 
 #[cfg(feature = "parallel-io")]
 pub use io::msgpack::write_msgpack_par;
+
+#[cfg(feature = "io-proto")]
+pub use io::proto::{read_proto_vec, write_proto_vec};
+
+#[cfg(feature = "io-proto")]
+pub use helpers::proto::{read_proto, read_proto_streaming};
+
+#[cfg(feature = "io-http")]
+pub use io::http::{read_http_json_vec, write_http_json_vec};
+
+#[cfg(feature = "io-http")]
+pub use helpers::http::read_http_json;
+
+#[cfg(feature = "io-sqlite")]
+pub use io::sqlite::{read_sql_query_vec, write_table_vec};
+
+#[cfg(feature = "io-sqlite")]
+pub use helpers::sqlite::read_sql_query;
+
+pub use helpers::queue::{QueueBatch, read_queue};
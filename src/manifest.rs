@@ -0,0 +1,406 @@
+//! Machine-readable job manifests for orchestration tools.
+//!
+//! [`Pipeline::describe`] summarizes a built pipeline as a [`PipelineManifest`]
+//! without running it: the files and services it reads, the outputs it produces, every
+//! transform and what it consumes, the crate features compiled in, and resource
+//! estimates from the planner (see [`DirectRunner::dry_run`](crate::DirectRunner::dry_run)).
+//! Serialized with [`PipelineManifest::to_json`], it gives scheduler wrappers (an
+//! Airflow operator, a Dagster asset) what they need for lineage and dependency wiring
+//! without having to understand the pipeline's code.
+//!
+//! # Inputs
+//! The file readers (`read_jsonl`, `read_csv`, `read_parquet_streaming`, ...) record
+//! their format and path or glob on the source node they create. Other sources, such
+//! as [`from_vec`](crate::from_vec) or a custom source, are listed without a location.
+//!
+//! # Outputs
+//! The `write_*` sinks run the pipeline as soon as they are called, so a pipeline that
+//! is described before it runs has no sink nodes yet. Its outputs are:
+//! - collections marked with [`PCollection::declare_output`], which records where the
+//!   binary will write them;
+//! - collections registered for [`Pipeline::run_all`] with
+//!   [`materialize`](PCollection::materialize) or
+//!   [`write_to_sink`](PCollection::write_to_sink);
+//! - every other collection nothing consumes.
+//!
+//! Each output lists the inputs it is computed from.
+//!
+//! ```no_run
+//! use ironbeam::*;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Clone, Serialize, Deserialize)]
+//! struct Order {
+//!     region: String,
+//!     cents: u64,
+//! }
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let p = Pipeline::default();
+//! let totals = read_jsonl::<Order>(&p, "orders/*.jsonl")?
+//!     .map(|o: &Order| (o.region.clone(), o.cents))
+//!     .sum_per_key()
+//!     .declare_output("jsonl", "out/totals.jsonl");
+//!
+//! let manifest = p.describe()?;
+//! assert_eq!(manifest.inputs[0].location.as_deref(), Some("orders/*.jsonl"));
+//! assert_eq!(manifest.outputs[0].node, totals.node_id().raw());
+//! std::fs::write("manifest.json", manifest.to_json()?)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::node::Node;
+use crate::planner::build_plan;
+use crate::{Element, NodeId, PCollection, Pipeline};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+
+/// Version of the [`PipelineManifest`] format written by this build.
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// Crate features, in `Cargo.toml` order, with whether this build enables them.
+const FEATURES: [(&str, bool); 28] = [
+    ("io-jsonl", cfg!(feature = "io-jsonl")),
+    ("io-csv", cfg!(feature = "io-csv")),
+    ("io-parquet", cfg!(feature = "io-parquet")),
+    ("io-avro", cfg!(feature = "io-avro")),
+    ("io-xml", cfg!(feature = "io-xml")),
+    ("io-msgpack", cfg!(feature = "io-msgpack")),
+    ("io-proto", cfg!(feature = "io-proto")),
+    ("io-http", cfg!(feature = "io-http")),
+    ("io-sqlite", cfg!(feature = "io-sqlite")),
+    ("compression-gzip", cfg!(feature = "compression-gzip")),
+    ("compression-zstd", cfg!(feature = "compression-zstd")),
+    ("compression-bzip2", cfg!(feature = "compression-bzip2")),
+    ("compression-xz", cfg!(feature = "compression-xz")),
+    ("archive-tar", cfg!(feature = "archive-tar")),
+    ("parallel-io", cfg!(feature = "parallel-io")),
+    ("metrics", cfg!(feature = "metrics")),
+    ("metrics-http", cfg!(feature = "metrics-http")),
+    ("otel", cfg!(feature = "otel")),
+    ("checkpointing", cfg!(feature = "checkpointing")),
+    ("spilling", cfg!(feature = "spilling")),
+    ("coders", cfg!(feature = "coders")),
+    ("cluster", cfg!(feature = "cluster")),
+    ("result-cache", cfg!(feature = "result-cache")),
+    ("sql", cfg!(feature = "sql")),
+    ("cli", cfg!(feature = "cli")),
+    ("async", cfg!(feature = "async")),
+    ("bench", cfg!(feature = "bench")),
+    ("proptest", cfg!(feature = "proptest")),
+];
+
+/// Format and location of data a node reads or an output is written to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct IoLocation {
+    pub format: String,
+    pub location: String,
+}
+
+impl IoLocation {
+    pub(crate) fn new(format: &str, location: impl Display) -> Self {
+        Self {
+            format: format.to_string(),
+            location: location.to_string(),
+        }
+    }
+}
+
+/// A serializable summary of a pipeline, built by [`Pipeline::describe`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PipelineManifest {
+    /// Format version, see [`MANIFEST_VERSION`].
+    pub version: u32,
+    /// Version of this crate.
+    pub crate_version: String,
+    /// Crate features enabled in this build.
+    pub features: Vec<String>,
+    /// Sources, in node order.
+    pub inputs: Vec<ManifestInput>,
+    /// Outputs, in node order.
+    pub outputs: Vec<ManifestOutput>,
+    /// Every node that contributes to an output, in node order.
+    pub transforms: Vec<ManifestTransform>,
+    /// Estimates across all outputs.
+    pub resources: ResourceEstimate,
+}
+
+/// A source of a [`PipelineManifest`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestInput {
+    /// Raw [`NodeId`] of the source.
+    pub node: u64,
+    /// Name given with [`PCollection::with_name`], if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Format read, such as `"jsonl"` or `"parquet"`; `None` for in-memory and custom
+    /// sources.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// Path, glob or URL read; `None` for in-memory and custom sources.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// Number of elements, when the source knows it without being read.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_elements: Option<usize>,
+}
+
+/// An output of a [`PipelineManifest`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestOutput {
+    /// Raw [`NodeId`] of the output collection.
+    pub node: u64,
+    /// Name given with [`PCollection::with_name`], if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Format given to [`PCollection::declare_output`], if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// Location given to [`PCollection::declare_output`], if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// Raw node ids of the inputs this output is computed from.
+    pub inputs: Vec<u64>,
+    /// Planner estimate of the number of elements produced.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_elements: Option<usize>,
+    /// Planner estimate of the memory high-water mark, in bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_peak_bytes: Option<usize>,
+    /// Partition count the planner suggests.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggested_partitions: Option<usize>,
+}
+
+/// One node of a [`PipelineManifest`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestTransform {
+    /// Raw [`NodeId`] of the node.
+    pub node: u64,
+    /// Node kind, such as `"Stateless"` or `"GroupByKey"` (see [`Node::kind`]).
+    pub kind: String,
+    /// Name given with [`PCollection::with_name`], if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Raw node ids of the nodes this one consumes.
+    pub upstream: Vec<u64>,
+    /// Whether the node is a barrier that needs all of its input before emitting.
+    pub barrier: bool,
+}
+
+/// Resource estimates of a [`PipelineManifest`].
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceEstimate {
+    /// Total elements across all inputs; `None` if any input cannot tell.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_elements: Option<usize>,
+    /// Largest memory high-water mark of any output, in bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub peak_bytes: Option<usize>,
+    /// Largest partition count suggested for any output.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub suggested_partitions: Option<usize>,
+    /// Number of barrier nodes among the transforms.
+    pub barriers: usize,
+}
+
+impl PipelineManifest {
+    /// Render the manifest as pretty-printed JSON.
+    ///
+    /// # Errors
+    /// Returns an error if the manifest cannot be serialized.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).context("serialize pipeline manifest")
+    }
+
+    /// Parse a manifest from JSON.
+    ///
+    /// # Errors
+    /// Returns an error if `json` is not a valid manifest.
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).context("parse pipeline manifest")
+    }
+}
+
+impl<T: Element> PCollection<T> {
+    /// Declare that this collection is an output written as `format` to `location`,
+    /// for [`Pipeline::describe`]. Nothing is written: this only labels the collection
+    /// in the manifest.
+    #[must_use]
+    pub fn declare_output(self, format: &str, location: impl Display) -> Self {
+        self.pipeline.record_output(self.id, format, location);
+        self
+    }
+
+    /// Record that this source reads `format` data from `location`.
+    pub(crate) fn record_input(self, format: &str, location: impl Display) -> Self {
+        self.pipeline.record_input(self.id, format, location);
+        self
+    }
+}
+
+impl Pipeline {
+    /// Describe this pipeline as a [`PipelineManifest`] without running it. See the
+    /// [module documentation](crate::manifest) for how inputs and outputs are found.
+    ///
+    /// # Errors
+    /// Returns an error if an output cannot be planned, e.g. when pre-flight
+    /// validation reports an error.
+    ///
+    /// # Panics
+    ///
+    /// If the pipeline mutex is poisoned by a concurrent panic.
+    pub fn describe(&self) -> Result<PipelineManifest> {
+        let g = self.inner.lock().unwrap();
+        let mut upstream: HashMap<NodeId, Vec<NodeId>> = HashMap::new();
+        let mut anchors: HashSet<NodeId> = HashSet::new();
+        for (from, to) in &g.edges {
+            if g.upstream.contains_key(to) {
+                // The placeholder source a join or flatten starts its plan with.
+                anchors.insert(*from);
+            } else {
+                upstream.entry(*to).or_default().push(*from);
+            }
+        }
+        for (id, inputs) in &g.upstream {
+            upstream.insert(*id, inputs.clone());
+        }
+        for (id, source) in &g.cached_from {
+            upstream.entry(*id).or_default().push(*source);
+        }
+        let consumed: HashSet<NodeId> = upstream.values().flatten().copied().collect();
+
+        // Node ids are ordered by their raw value, which is build order.
+        let mut outputs: BTreeSet<u64> = g
+            .nodes
+            .keys()
+            .filter(|id| !consumed.contains(id) && !anchors.contains(id))
+            .map(NodeId::raw)
+            .collect();
+        outputs.extend(g.io_outputs.keys().map(NodeId::raw));
+        outputs.extend(g.outputs.keys().map(NodeId::raw));
+
+        let ancestors = |id: u64| {
+            let mut seen = BTreeSet::from([id]);
+            let mut stack = vec![NodeId::new(id)];
+            while let Some(cur) = stack.pop() {
+                for prev in upstream.get(&cur).into_iter().flatten() {
+                    if seen.insert(prev.raw()) {
+                        stack.push(*prev);
+                    }
+                }
+            }
+            seen
+        };
+        let is_source =
+            |id: u64| matches!(g.nodes.get(&NodeId::new(id)), Some(Node::Source { .. }));
+        let name = |id: u64| g.node_names.get(&NodeId::new(id)).cloned();
+
+        let mut used = BTreeSet::new();
+        let mut manifest_outputs = Vec::new();
+        for &id in &outputs {
+            let lineage = ancestors(id);
+            let declared = g.io_outputs.get(&NodeId::new(id));
+            manifest_outputs.push(ManifestOutput {
+                node: id,
+                name: name(id),
+                format: declared.map(|d| d.format.clone()),
+                location: declared.map(|d| d.location.clone()),
+                inputs: lineage.iter().copied().filter(|&n| is_source(n)).collect(),
+                estimated_elements: None,
+                estimated_peak_bytes: None,
+                suggested_partitions: None,
+            });
+            used.extend(lineage);
+        }
+
+        let inputs: Vec<ManifestInput> = used
+            .iter()
+            .filter_map(|&id| match g.nodes.get(&NodeId::new(id)) {
+                Some(Node::Source {
+                    payload, vec_ops, ..
+                }) => {
+                    let io = g.io_inputs.get(&NodeId::new(id));
+                    Some(ManifestInput {
+                        node: id,
+                        name: name(id),
+                        format: io.map(|i| i.format.clone()),
+                        location: io.map(|i| i.location.clone()),
+                        estimated_elements: vec_ops.len(payload.as_ref()),
+                    })
+                }
+                _ => None,
+            })
+            .collect();
+        let transforms: Vec<ManifestTransform> = used
+            .iter()
+            .filter_map(|&id| {
+                let node = g.nodes.get(&NodeId::new(id))?;
+                Some(ManifestTransform {
+                    node: id,
+                    kind: node.kind().to_string(),
+                    name: name(id),
+                    upstream: upstream
+                        .get(&NodeId::new(id))
+                        .into_iter()
+                        .flatten()
+                        .map(|n| n.raw())
+                        .collect(),
+                    barrier: node.is_barrier(),
+                })
+            })
+            .collect();
+        drop(g);
+
+        for output in &mut manifest_outputs {
+            let explanation = build_plan(self, NodeId::new(output.node))
+                .with_context(|| format!("plan output #{}", output.node))?
+                .explain_estimated();
+            output.estimated_elements = explanation.cost_estimate.estimated_output_elements;
+            output.estimated_peak_bytes = explanation.cost_estimate.estimated_peak_bytes;
+            output.suggested_partitions = explanation.suggested_partitions;
+        }
+
+        let resources = ResourceEstimate {
+            input_elements: inputs.iter().map(|i| i.estimated_elements).sum(),
+            peak_bytes: manifest_outputs
+                .iter()
+                .filter_map(|o| o.estimated_peak_bytes)
+                .max(),
+            suggested_partitions: manifest_outputs
+                .iter()
+                .filter_map(|o| o.suggested_partitions)
+                .max(),
+            barriers: transforms.iter().filter(|t| t.barrier).count(),
+        };
+        Ok(PipelineManifest {
+            version: MANIFEST_VERSION,
+            crate_version: env!("CARGO_PKG_VERSION").to_string(),
+            features: FEATURES
+                .iter()
+                .filter(|(_, enabled)| *enabled)
+                .map(|(feature, _)| (*feature).to_string())
+                .collect(),
+            inputs,
+            outputs: manifest_outputs,
+            transforms,
+            resources,
+        })
+    }
+
+    /// Write this pipeline's [`describe`](Self::describe) manifest to `path` as
+    /// pretty-printed JSON.
+    ///
+    /// # Errors
+    /// Returns an error if the manifest cannot be built or the file cannot be written.
+    pub fn save_manifest(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        fs::write(path, self.describe()?.to_json()?)
+            .with_context(|| format!("write pipeline manifest {}", path.display()))
+    }
+}
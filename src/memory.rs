@@ -0,0 +1,84 @@
+//! Coarse memory accounting for [`DirectRunner::max_memory_bytes`](crate::DirectRunner::max_memory_bytes).
+//!
+//! With a budget set, the runner estimates the size of every partition it holds as
+//! `element count × sampled element size`, where the element size is `size_of::<T>()`
+//! plus (with the `coders` feature) the average postcard-encoded size of up to 64
+//! leading elements, a proxy for heap data such as string contents. The estimate is
+//! used in three ways:
+//!
+//! 1. **More partitions** — in parallel execution, the source is split into at least
+//!    `estimated source bytes / (budget / 2)` partitions, so each starts well within the
+//!    budget. A [`with_partitions`](crate::PCollection::with_partitions) hint on the
+//!    source still wins.
+//! 2. **Spilling** — with the `spilling` feature, group-by-key uses the budget as its
+//!    working-memory limit and spills to disk above it (see [`crate::spill_group`]),
+//!    unless [`DirectRunner::memory_budget`](crate::DirectRunner::memory_budget) sets one explicitly.
+//! 3. **A clear error** — after each plan step, a partition estimated above the budget
+//!    stops the run with [`IronbeamError::MemoryBudgetExceeded`](crate::IronbeamError::MemoryBudgetExceeded),
+//!    naming the step and partition, instead of letting later steps grow it until the
+//!    process is killed.
+//!
+//! The check runs between steps, so a single step can still allocate past the budget
+//! while it runs; the budget catches the stage whose output outgrew it. Estimates
+//! ignore allocator overhead and spare vector capacity.
+
+use crate::Element;
+use std::any::Any;
+
+/// Number of leading elements encoded to estimate the average element size.
+const SIZE_SAMPLE: usize = 64;
+
+/// Estimate the in-memory size of `elems` in bytes: the element count times the shallow
+/// element size plus, with the `coders` feature, the average encoded size of a sample.
+///
+/// This is the estimate [`DirectRunner::max_memory_bytes`](crate::DirectRunner::max_memory_bytes) is
+/// compared against, so it can be used to pick a budget.
+#[must_use]
+pub fn estimate_bytes<T: Element>(elems: &[T]) -> usize {
+    if elems.is_empty() {
+        return 0;
+    }
+    let sample = &elems[..elems.len().min(SIZE_SAMPLE)];
+    let encoded: usize = sample.iter().map(encoded_len).sum();
+    let per_elem = size_of::<T>() + encoded / sample.len();
+    elems.len().saturating_mul(per_elem)
+}
+
+#[cfg(feature = "coders")]
+fn encoded_len<T: Element>(elem: &T) -> usize {
+    postcard::to_allocvec(elem).map_or(0, |b| b.len())
+}
+
+#[cfg(not(feature = "coders"))]
+const fn encoded_len<T: Element>(_elem: &T) -> usize {
+    0
+}
+
+/// Size estimation and element counting for a pipeline node's output partitions,
+/// registered alongside its coder.
+#[derive(Clone, Copy)]
+pub(crate) struct PartitionSizer {
+    estimate: fn(&dyn Any) -> Option<usize>,
+    len: fn(&dyn Any) -> Option<usize>,
+}
+
+impl PartitionSizer {
+    /// Sizer for partitions of type `Vec<T>`.
+    pub(crate) fn of<T: Element>() -> Self {
+        Self {
+            estimate: |p| p.downcast_ref::<Vec<T>>().map(|v| estimate_bytes(v)),
+            len: |p| p.downcast_ref::<Vec<T>>().map(Vec::len),
+        }
+    }
+
+    /// Estimated size of `part`, or `None` if it is not a `Vec` of the registered type.
+    pub(crate) fn estimate(&self, part: &dyn Any) -> Option<usize> {
+        (self.estimate)(part)
+    }
+
+    /// Number of elements in `part`, or `None` if it is not a `Vec` of the registered
+    /// type.
+    pub(crate) fn len(&self, part: &dyn Any) -> Option<usize> {
+        (self.len)(part)
+    }
+}
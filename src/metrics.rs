@@ -10,6 +10,12 @@
 //! - [`MetricsCollector`] manages metric registration and collection
 //! - Built-in metrics track common execution statistics
 //! - Metrics can be printed to stdout or saved to a JSON file
+//! - The runner records per-transform element counts, sizes, and wall time
+//!   automatically (see [Per-transform metrics](#per-transform-metrics))
+//! - Transforms can update their own counters, gauges, and distributions through a
+//!   [`MetricsContext`] (see [User metrics in transforms](#user-metrics-in-transforms))
+//! - [`PrometheusExporter`] exposes a collector in Prometheus / `OpenMetrics` format, and
+//!   can serve it for scraping with the `metrics-http` feature
 //!
 //! # Example
 //!
@@ -59,12 +65,100 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! # Per-transform metrics
+//!
+//! While a collector is attached, every run records one [`TransformMetrics`] per step of
+//! the optimized plan: its input and output element counts, an estimate of the output
+//! size in bytes, and its wall time. Steps are identified by a [`StepRef`] (position,
+//! kind, and name from [`PCollection::with_name`](crate::PCollection::with_name)) and by
+//! the pipeline [`NodeId`]s fused into them. Read them back through
+//! [`MetricsCollector::report`]:
+//!
+//! ```no_run
+//! # use anyhow::Result;
+//! use ironbeam::*;
+//! use ironbeam::metrics::MetricsCollector;
+//!
+//! # fn main() -> Result<()> {
+//! let p = Pipeline::default();
+//! p.set_metrics(MetricsCollector::new());
+//!
+//! let evens = from_vec(&p, (0..100u32).collect::<Vec<_>>())
+//!     .filter(|x| x % 2 == 0)
+//!     .with_name("evens");
+//! evens.collect_seq()?;
+//!
+//! let report = p.get_metrics().unwrap().report();
+//! let step = report.transform("evens").unwrap();
+//! assert_eq!(step.elements_in, Some(100));
+//! assert_eq!(step.elements_out, Some(50));
+//! for t in report.per_transform() {
+//!     println!("{}: {:?} -> {:?} in {:?}", t.node, t.elements_in, t.elements_out, t.wall_time);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Counts are taken from the materialized partitions between steps, so steps that run
+//! inside a fused parallel block (or inside the input subplans of a join) are folded into
+//! the step that ends the block. Element counts are `None` when a step's output type is
+//! not known to the pipeline, e.g. for custom [`DynOp`](crate::DynOp)s inserted by hand.
+//!
+//! # User metrics in transforms
+//!
+//! [`PCollection::map_with_metrics`](crate::PCollection::map_with_metrics) and
+//! [`PCollection::filter_with_metrics`](crate::PCollection::filter_with_metrics) pass a
+//! [`MetricsContext`] to their closure alongside each element:
+//!
+//! ```no_run
+//! # use anyhow::Result;
+//! use ironbeam::*;
+//! use ironbeam::metrics::MetricsCollector;
+//!
+//! # fn main() -> Result<()> {
+//! let p = Pipeline::default();
+//! p.set_metrics(MetricsCollector::new());
+//!
+//! let rows = from_vec(&p, vec!["1".to_string(), "x".to_string(), "3".to_string()]);
+//! let parsed = rows.filter_with_metrics(|row, m| {
+//!     let ok = row.parse::<u32>().is_ok();
+//!     if !ok {
+//!         m.counter("bad_rows").inc();
+//!     }
+//!     m.distribution("row_len").record(row.len() as f64);
+//!     ok
+//! });
+//! parsed.collect_par(None, Some(2))?;
+//!
+//! let report = p.get_metrics().unwrap().report();
+//! assert_eq!(report.values()["bad_rows"], serde_json::json!(1));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! Each partition updates a private context, so transforms never contend on a lock per
+//! element. Contexts are merged as partitions finish and folded into the pipeline's
+//! [`MetricsCollector`] when the run ends: counters are summed, distributions become
+//! [`HistogramMetric`]s over every recorded value, and gauges keep the last value set
+//! (by merge order, which is unspecified across partitions). Without a collector the
+//! values are discarded.
+
+mod prometheus;
+
+#[cfg(feature = "metrics-http")]
+pub use prometheus::MetricsServer;
+pub use prometheus::{OPENMETRICS_CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE, PrometheusExporter};
 
+use crate::NodeId;
+use crate::error::StepRef;
 use anyhow::Result;
 use serde_json::{Map, Value, json, to_string_pretty};
 use std::any::Any;
+use std::cell::RefCell;
 use std::cmp::Ordering;
 use std::collections::HashMap;
+use std::fmt::{Display, Formatter, Result as FormatResult};
 use std::fs::File;
 use std::io::Write;
 use std::sync::{Arc, Mutex};
@@ -103,6 +197,7 @@ struct MetricsCollectorInner {
     metrics: HashMap<String, Box<dyn Metric>>,
     start_time: Option<Instant>,
     end_time: Option<Instant>,
+    transforms: Vec<TransformMetrics>,
 }
 
 impl MetricsCollector {
@@ -114,6 +209,7 @@ impl MetricsCollector {
                 metrics: HashMap::new(),
                 start_time: None,
                 end_time: None,
+                transforms: Vec::new(),
             })),
         }
     }
@@ -145,12 +241,50 @@ impl MetricsCollector {
 
     /// Record the start time of pipeline execution.
     ///
+    /// Per-transform metrics of a previous run are cleared, so
+    /// [`report`](Self::report) always describes the most recent run.
+    ///
     /// # Panics
     ///
     /// Panics if the internal metrics mutex is poisoned.
     pub fn record_start(&self) {
         let mut inner = self.inner.lock().unwrap();
         inner.start_time = Some(Instant::now());
+        inner.transforms.clear();
+    }
+
+    /// Fold metrics recorded through [`MetricsContext`]s into this collector.
+    ///
+    /// Counters are added to existing [`CounterMetric`]s, distributions are appended to
+    /// existing [`HistogramMetric`]s, and gauges replace any metric of the same name.
+    pub(crate) fn merge_user_metrics(&self, user: UserMetrics) {
+        for (name, value) in user.counters {
+            self.increment_counter(&name, value);
+        }
+        let mut inner = self.inner.lock().unwrap();
+        for (name, value) in user.gauges {
+            inner
+                .metrics
+                .insert(name.clone(), Box::new(GaugeMetric::new(name, value)));
+        }
+        for (name, mut values) in user.distributions {
+            if let Some(existing) = inner
+                .metrics
+                .get(&name)
+                .and_then(|m| m.as_any().downcast_ref::<HistogramMetric>())
+            {
+                values.splice(0..0, existing.values.iter().copied());
+            }
+            inner.metrics.insert(
+                name.clone(),
+                Box::new(HistogramMetric::with_values(name, values)),
+            );
+        }
+    }
+
+    /// Record the metrics of one executed plan step. Called by the runner.
+    pub(crate) fn record_transform(&self, transform: TransformMetrics) {
+        self.inner.lock().unwrap().transforms.push(transform);
     }
 
     /// Record the end time of pipeline execution.
@@ -249,6 +383,23 @@ impl MetricsCollector {
             );
             metrics_json.insert("execution_time_ms".to_string(), Value::Object(time_obj));
         }
+
+        if !inner.transforms.is_empty() {
+            let mut transforms_obj = Map::new();
+            transforms_obj.insert(
+                "value".to_string(),
+                inner
+                    .transforms
+                    .iter()
+                    .map(TransformMetrics::to_json)
+                    .collect(),
+            );
+            transforms_obj.insert(
+                "description".to_string(),
+                json!("Per-transform element counts, output bytes, and wall time"),
+            );
+            metrics_json.insert("transforms".to_string(), Value::Object(transforms_obj));
+        }
         drop(inner);
         json!(metrics_json)
     }
@@ -282,6 +433,13 @@ impl MetricsCollector {
                 println!("{}: {}", name, metric.value());
             }
         }
+
+        if !inner.transforms.is_empty() {
+            println!("--------------------------------------");
+            for t in &inner.transforms {
+                println!("{t}");
+            }
+        }
         drop(inner);
         println!("======================================\n");
     }
@@ -313,6 +471,24 @@ impl MetricsCollector {
             .map(|(name, metric)| (name.clone(), metric.value()))
             .collect()
     }
+
+    /// Take a structured snapshot of the collected metrics, including the
+    /// per-transform metrics of the most recent run.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the internal metrics mutex is poisoned.
+    #[must_use]
+    pub fn report(&self) -> MetricsReport {
+        let values = self.snapshot();
+        let elapsed = self.elapsed();
+        let transforms = self.inner.lock().unwrap().transforms.clone();
+        MetricsReport {
+            elapsed,
+            values,
+            transforms,
+        }
+    }
 }
 
 impl Default for MetricsCollector {
@@ -321,6 +497,262 @@ impl Default for MetricsCollector {
     }
 }
 
+// ========== Per-transform Metrics ==========
+
+/// Execution statistics of one step of the optimized plan.
+///
+/// Recorded automatically by the runner while a [`MetricsCollector`] is attached to the
+/// pipeline; see the [module docs](self#per-transform-metrics).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransformMetrics {
+    /// The plan step (position, kind, and name).
+    pub node: StepRef,
+    /// Pipeline nodes fused into this step, in chain order.
+    pub node_ids: Vec<NodeId>,
+    /// Elements consumed from the previous step; `None` for sources and for joins and
+    /// flattens, whose inputs come from their own subplans.
+    pub elements_in: Option<u64>,
+    /// Elements produced by the step, if its output type is known.
+    pub elements_out: Option<u64>,
+    /// Estimated output size: `elements_out × size_of::<T>()`. Heap data owned by the
+    /// elements (string contents, vector buffers, …) is not included.
+    pub bytes_out: Option<u64>,
+    /// Wall-clock time spent executing the step.
+    pub wall_time: Duration,
+}
+
+impl TransformMetrics {
+    fn to_json(&self) -> Value {
+        json!({
+            "step": self.node.step,
+            "kind": self.node.kind,
+            "name": self.node.name,
+            "node_ids": self.node_ids.iter().map(|id| id.raw()).collect::<Vec<_>>(),
+            "elements_in": self.elements_in,
+            "elements_out": self.elements_out,
+            "bytes_out": self.bytes_out,
+            "wall_time_us": u64::try_from(self.wall_time.as_micros()).unwrap_or(u64::MAX),
+        })
+    }
+}
+
+impl Display for TransformMetrics {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        let count = |n: Option<u64>| n.map_or_else(|| "?".to_string(), |n| n.to_string());
+        write!(
+            f,
+            "{}: {} -> {} elements",
+            self.node,
+            count(self.elements_in),
+            count(self.elements_out)
+        )?;
+        if let Some(bytes) = self.bytes_out {
+            write!(f, " (~{bytes} bytes)")?;
+        }
+        write!(f, " in {:.3} ms", self.wall_time.as_secs_f64() * 1_000.0)
+    }
+}
+
+/// Point-in-time snapshot of a [`MetricsCollector`], returned by
+/// [`MetricsCollector::report`].
+#[derive(Debug, Clone)]
+pub struct MetricsReport {
+    elapsed: Option<Duration>,
+    values: HashMap<String, Value>,
+    transforms: Vec<TransformMetrics>,
+}
+
+impl MetricsReport {
+    /// Total execution time of the most recent run, if it has finished.
+    #[must_use]
+    pub const fn elapsed(&self) -> Option<Duration> {
+        self.elapsed
+    }
+
+    /// Values of all registered metrics, keyed by name.
+    #[must_use]
+    pub const fn values(&self) -> &HashMap<String, Value> {
+        &self.values
+    }
+
+    /// Per-step metrics of the most recent run, in execution order.
+    #[must_use]
+    pub fn per_transform(&self) -> &[TransformMetrics] {
+        &self.transforms
+    }
+
+    /// Metrics of the step with the given name (as rendered by
+    /// [`Plan::explain`](crate::planner::Plan::explain), e.g. `"parse + clean"` for
+    /// fused named nodes).
+    #[must_use]
+    pub fn transform(&self, name: &str) -> Option<&TransformMetrics> {
+        self.transforms
+            .iter()
+            .find(|t| t.node.name.as_deref() == Some(name))
+    }
+
+    /// Metrics of the step that pipeline node `id` was fused into.
+    #[must_use]
+    pub fn transform_for_node(&self, id: NodeId) -> Option<&TransformMetrics> {
+        self.transforms.iter().find(|t| t.node_ids.contains(&id))
+    }
+}
+
+// ========== User Metrics ==========
+
+/// Handle for updating user-defined metrics from inside a transform.
+///
+/// Passed to the closures of
+/// [`PCollection::map_with_metrics`](crate::PCollection::map_with_metrics) and
+/// [`PCollection::filter_with_metrics`](crate::PCollection::filter_with_metrics). Each
+/// partition gets its own context; see the
+/// [module docs](self#user-metrics-in-transforms) for how they are aggregated.
+#[derive(Default)]
+pub struct MetricsContext {
+    values: RefCell<UserMetrics>,
+}
+
+impl MetricsContext {
+    /// The counter named `name`, created at zero on first use.
+    #[must_use]
+    pub const fn counter<'a>(&'a self, name: &'a str) -> Counter<'a> {
+        Counter { ctx: self, name }
+    }
+
+    /// The gauge named `name`.
+    #[must_use]
+    pub const fn gauge<'a>(&'a self, name: &'a str) -> Gauge<'a> {
+        Gauge { ctx: self, name }
+    }
+
+    /// The distribution named `name`.
+    #[must_use]
+    pub const fn distribution<'a>(&'a self, name: &'a str) -> Distribution<'a> {
+        Distribution { ctx: self, name }
+    }
+
+    /// Consume the context, returning everything recorded through it.
+    pub(crate) fn into_values(self) -> UserMetrics {
+        self.values.into_inner()
+    }
+}
+
+/// A monotonically increasing count; see [`MetricsContext::counter`].
+pub struct Counter<'a> {
+    ctx: &'a MetricsContext,
+    name: &'a str,
+}
+
+impl Counter<'_> {
+    /// Add one.
+    pub fn inc(&self) {
+        self.inc_by(1);
+    }
+
+    /// Add `n`.
+    pub fn inc_by(&self, n: u64) {
+        let mut values = self.ctx.values.borrow_mut();
+        match values.counters.get_mut(self.name) {
+            Some(count) => *count += n,
+            None => {
+                values.counters.insert(self.name.to_string(), n);
+            }
+        }
+    }
+}
+
+/// A point-in-time value; see [`MetricsContext::gauge`].
+pub struct Gauge<'a> {
+    ctx: &'a MetricsContext,
+    name: &'a str,
+}
+
+impl Gauge<'_> {
+    /// Set the gauge to `value`.
+    pub fn set(&self, value: f64) {
+        self.ctx
+            .values
+            .borrow_mut()
+            .gauges
+            .insert(self.name.to_string(), value);
+    }
+}
+
+/// A distribution of observed values, reported as a [`HistogramMetric`]; see
+/// [`MetricsContext::distribution`].
+pub struct Distribution<'a> {
+    ctx: &'a MetricsContext,
+    name: &'a str,
+}
+
+impl Distribution<'_> {
+    /// Record one observation.
+    pub fn record(&self, value: f64) {
+        let mut values = self.ctx.values.borrow_mut();
+        match values.distributions.get_mut(self.name) {
+            Some(observed) => observed.push(value),
+            None => {
+                values
+                    .distributions
+                    .insert(self.name.to_string(), vec![value]);
+            }
+        }
+    }
+}
+
+/// Values recorded through one or more [`MetricsContext`]s.
+#[derive(Default)]
+pub(crate) struct UserMetrics {
+    counters: HashMap<String, u64>,
+    gauges: HashMap<String, f64>,
+    distributions: HashMap<String, Vec<f64>>,
+}
+
+impl UserMetrics {
+    /// Merge `other` into `self`.
+    pub(crate) fn merge(&mut self, other: Self) {
+        for (name, n) in other.counters {
+            *self.counters.entry(name).or_default() += n;
+        }
+        self.gauges.extend(other.gauges);
+        for (name, values) in other.distributions {
+            self.distributions.entry(name).or_default().extend(values);
+        }
+    }
+}
+
+/// Element counting for a pipeline node's output type, registered alongside its coder
+/// and used by the runner to fill in [`TransformMetrics`].
+#[derive(Clone, Copy)]
+pub(crate) struct ElemStats {
+    count: fn(&dyn Any) -> Option<usize>,
+    elem_size: usize,
+}
+
+impl ElemStats {
+    /// Stats for partitions of type `Vec<T>`.
+    pub(crate) fn of<T: 'static>() -> Self {
+        Self {
+            count: |p| p.downcast_ref::<Vec<T>>().map(Vec::len),
+            elem_size: size_of::<T>(),
+        }
+    }
+
+    /// Total element count and estimated byte size of `parts`, or `None` if any
+    /// partition is not a `Vec` of the registered type.
+    pub(crate) fn measure<'a>(
+        &self,
+        parts: impl IntoIterator<Item = &'a dyn Any>,
+    ) -> Option<(u64, u64)> {
+        let mut elements = 0usize;
+        for p in parts {
+            elements += (self.count)(p)?;
+        }
+        let elements = elements as u64;
+        Some((elements, elements.saturating_mul(self.elem_size as u64)))
+    }
+}
+
 // ========== Built-in Metrics ==========
 
 /// A simple counter metric.
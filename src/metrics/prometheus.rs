@@ -0,0 +1,400 @@
+//! Prometheus / `OpenMetrics` text exposition for a [`MetricsCollector`].
+//!
+//! [`PrometheusExporter`] renders everything a collector holds:
+//!
+//! | Source                                   | Exposed as                                      |
+//! |------------------------------------------|-------------------------------------------------|
+//! | [`CounterMetric`]                        | `counter` (`<name>_total`)                      |
+//! | [`GaugeMetric`] and numeric custom metrics | `gauge`                                       |
+//! | [`HistogramMetric`]                      | `summary` (p50/p95/p99 quantiles, sum, count)   |
+//! | Total execution time                     | `pipeline_execution_seconds` gauge              |
+//! | [Per-transform metrics](super#per-transform-metrics) | `transform_*` gauges labeled by `step`, `kind`, and `name` |
+//!
+//! All names are prefixed with the exporter's namespace (`ironbeam_` by default) and
+//! characters Prometheus does not allow in names are replaced with `_`, so a counter
+//! registered as `stage.parse.micros` is exposed as `ironbeam_stage_parse_micros_total`.
+//! Custom metrics whose value is not a JSON number are skipped.
+//!
+//! The collector is shared, so an exporter created before a run reflects updates made
+//! while it executes. Write a snapshot with [`PrometheusExporter::write_to_file`] (e.g.
+//! for the node exporter's textfile collector), or, with the `metrics-http` feature,
+//! serve it for scraping with [`PrometheusExporter::serve`].
+
+use super::{CounterMetric, GaugeMetric, HistogramMetric, MetricsCollector, TransformMetrics};
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::fmt::Write as _;
+use std::path::Path;
+
+#[cfg(feature = "metrics-http")]
+use std::io::{Read, Write};
+#[cfg(feature = "metrics-http")]
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+#[cfg(feature = "metrics-http")]
+use std::sync::Arc;
+#[cfg(feature = "metrics-http")]
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(feature = "metrics-http")]
+use std::thread::JoinHandle;
+
+/// Content type of [`PrometheusExporter::render`] output.
+pub const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+/// Content type of [`PrometheusExporter::render_openmetrics`] output.
+pub const OPENMETRICS_CONTENT_TYPE: &str =
+    "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// Renders a [`MetricsCollector`] in Prometheus or `OpenMetrics` text format.
+///
+/// # Example
+/// ```no_run
+/// use ironbeam::*;
+/// use ironbeam::metrics::{MetricsCollector, PrometheusExporter};
+/// # use anyhow::Result;
+/// # fn main() -> Result<()> {
+/// let p = Pipeline::default();
+/// let metrics = MetricsCollector::new();
+/// p.set_metrics(metrics.clone());
+///
+/// from_vec(&p, (0..1_000u32).collect::<Vec<_>>())
+///     .map(|x| x * 2)
+///     .collect_par(None, None)?;
+///
+/// let exporter = PrometheusExporter::new(metrics).with_namespace("etl");
+/// print!("{}", exporter.render());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct PrometheusExporter {
+    collector: MetricsCollector,
+    namespace: String,
+}
+
+impl PrometheusExporter {
+    /// Exporter for `collector` using the default `ironbeam` namespace.
+    #[must_use]
+    pub fn new(collector: MetricsCollector) -> Self {
+        Self {
+            collector,
+            namespace: "ironbeam".to_string(),
+        }
+    }
+
+    /// Prefix every exposed name with `namespace` (joined by `_`). An empty namespace
+    /// exposes the bare metric names.
+    #[must_use]
+    pub fn with_namespace(mut self, namespace: impl Into<String>) -> Self {
+        self.namespace = namespace.into();
+        self
+    }
+
+    /// Render the current metrics in the Prometheus text exposition format (0.0.4).
+    #[must_use]
+    pub fn render(&self) -> String {
+        self.render_as(false)
+    }
+
+    /// Render the current metrics in the `OpenMetrics` 1.0 text format.
+    #[must_use]
+    pub fn render_openmetrics(&self) -> String {
+        self.render_as(true)
+    }
+
+    /// Write [`render`](Self::render) output to `path`, replacing it atomically so a
+    /// concurrent reader never sees a partial file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the temporary file cannot be written or renamed.
+    pub fn write_to_file(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let mut tmp = path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        std::fs::write(&tmp, self.render()).context("Failed to write metrics file")?;
+        std::fs::rename(&tmp, path).context("Failed to replace metrics file")?;
+        Ok(())
+    }
+
+    fn render_as(&self, openmetrics: bool) -> String {
+        let mut out = String::new();
+        let inner = self.collector.inner.lock().unwrap();
+
+        let mut metrics: Vec<_> = inner.metrics.iter().collect();
+        metrics.sort_by_key(|(name, _)| *name);
+        for (name, metric) in metrics {
+            let help = metric.description();
+            let any = metric.as_any();
+            if let Some(counter) = any.downcast_ref::<CounterMetric>() {
+                let base = self.metric_name(name.strip_suffix("_total").unwrap_or(name));
+                let family = if openmetrics {
+                    base.clone()
+                } else {
+                    format!("{base}_total")
+                };
+                header(&mut out, &family, "counter", help);
+                let _ = writeln!(out, "{base}_total {}", counter.count);
+            } else if let Some(gauge) = any.downcast_ref::<GaugeMetric>() {
+                let name = self.metric_name(name);
+                header(&mut out, &name, "gauge", help);
+                let _ = writeln!(out, "{name} {}", number(gauge.value));
+            } else if let Some(histogram) = any.downcast_ref::<HistogramMetric>() {
+                let name = self.metric_name(name);
+                let stats = histogram.stats();
+                header(&mut out, &name, "summary", help);
+                for (q, v) in [("0.5", stats.p50), ("0.95", stats.p95), ("0.99", stats.p99)] {
+                    if stats.count > 0 {
+                        let _ = writeln!(out, "{name}{{quantile=\"{q}\"}} {}", number(v));
+                    }
+                }
+                let _ = writeln!(out, "{name}_sum {}", number(stats.sum));
+                let _ = writeln!(out, "{name}_count {}", stats.count);
+            } else if let Value::Number(n) = metric.value() {
+                let name = self.metric_name(name);
+                header(&mut out, &name, "gauge", help);
+                let _ = writeln!(out, "{name} {n}");
+            }
+        }
+
+        if let (Some(start), Some(end)) = (inner.start_time, inner.end_time) {
+            let name = self.metric_name("pipeline_execution_seconds");
+            header(
+                &mut out,
+                &name,
+                "gauge",
+                Some("Wall time of the most recent pipeline run"),
+            );
+            let secs = end.saturating_duration_since(start).as_secs_f64();
+            let _ = writeln!(out, "{name} {}", number(secs));
+        }
+
+        if !inner.transforms.is_empty() {
+            self.render_transforms(&mut out, &inner.transforms);
+        }
+        drop(inner);
+
+        if openmetrics {
+            out.push_str("# EOF\n");
+        }
+        out
+    }
+
+    fn render_transforms(&self, out: &mut String, transforms: &[TransformMetrics]) {
+        type Field = fn(&TransformMetrics) -> Option<f64>;
+        #[allow(clippy::cast_precision_loss)]
+        let families: [(&str, &str, Field); 4] = [
+            (
+                "transform_elements_in",
+                "Elements consumed by a plan step in the most recent run",
+                |t| t.elements_in.map(|n| n as f64),
+            ),
+            (
+                "transform_elements_out",
+                "Elements produced by a plan step in the most recent run",
+                |t| t.elements_out.map(|n| n as f64),
+            ),
+            (
+                "transform_bytes_out",
+                "Estimated in-memory size of a plan step's output in the most recent run",
+                |t| t.bytes_out.map(|n| n as f64),
+            ),
+            (
+                "transform_wall_seconds",
+                "Wall time of a plan step in the most recent run",
+                |t| Some(t.wall_time.as_secs_f64()),
+            ),
+        ];
+        for (family, help, field) in families {
+            let name = self.metric_name(family);
+            header(out, &name, "gauge", Some(help));
+            for t in transforms {
+                if let Some(v) = field(t) {
+                    let _ = writeln!(out, "{name}{{{}}} {}", transform_labels(t), number(v));
+                }
+            }
+        }
+    }
+
+    /// Sanitized, namespaced metric name.
+    fn metric_name(&self, name: &str) -> String {
+        let full = if self.namespace.is_empty() {
+            name.to_string()
+        } else {
+            format!("{}_{name}", self.namespace)
+        };
+        let mut out: String = full
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' || c == ':' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        if out.is_empty() || out.starts_with(|c: char| c.is_ascii_digit()) {
+            out.insert(0, '_');
+        }
+        out
+    }
+}
+
+#[cfg(feature = "metrics-http")]
+impl PrometheusExporter {
+    /// Serve the metrics over HTTP at `addr` from a background thread.
+    ///
+    /// Every `GET` request to `/metrics` (or `/`) returns a fresh rendering: `OpenMetrics`
+    /// when the request's `Accept` header asks for `application/openmetrics-text`, the
+    /// Prometheus text format otherwise. Other paths return `404`. The server stops when
+    /// the returned [`MetricsServer`] is dropped or [`shut down`](MetricsServer::shutdown).
+    ///
+    /// Bind to port `0` to let the OS pick a free port; see
+    /// [`MetricsServer::local_addr`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `addr` cannot be bound.
+    pub fn serve(&self, addr: impl ToSocketAddrs) -> Result<MetricsServer> {
+        let listener = TcpListener::bind(addr).context("Failed to bind metrics endpoint")?;
+        let local_addr = listener.local_addr()?;
+        let stop = Arc::new(AtomicBool::new(false));
+        let exporter = self.clone();
+        let stop_flag = Arc::clone(&stop);
+        let handle = std::thread::Builder::new()
+            .name("ironbeam-metrics-http".to_string())
+            .spawn(move || {
+                for stream in listener.incoming() {
+                    if stop_flag.load(Ordering::Acquire) {
+                        break;
+                    }
+                    if let Ok(stream) = stream {
+                        // A broken scrape connection must not take the endpoint down.
+                        let _ = exporter.respond(stream);
+                    }
+                }
+            })
+            .context("Failed to spawn metrics endpoint thread")?;
+        Ok(MetricsServer {
+            local_addr,
+            stop,
+            handle: Some(handle),
+        })
+    }
+
+    /// Answer one HTTP request on `stream`.
+    fn respond(&self, mut stream: TcpStream) -> std::io::Result<()> {
+        const MAX_REQUEST: usize = 8 * 1024;
+        stream.set_read_timeout(Some(std::time::Duration::from_secs(5)))?;
+        let mut request = Vec::new();
+        let mut buf = [0u8; 1024];
+        while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < MAX_REQUEST {
+            let n = stream.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            request.extend_from_slice(&buf[..n]);
+        }
+        let request = String::from_utf8_lossy(&request);
+        let mut request_line = request.lines().next().unwrap_or_default().split(' ');
+        let method = request_line.next().unwrap_or_default();
+        let path = request_line.next().unwrap_or_default();
+        let path = path.split('?').next().unwrap_or_default();
+        let openmetrics = request.lines().any(|line| {
+            line.to_ascii_lowercase().starts_with("accept:")
+                && line.contains("application/openmetrics-text")
+        });
+
+        let (status, content_type, body) = match (method, path) {
+            ("GET", "/metrics" | "/") if openmetrics => (
+                "200 OK",
+                OPENMETRICS_CONTENT_TYPE,
+                self.render_openmetrics(),
+            ),
+            ("GET", "/metrics" | "/") => ("200 OK", PROMETHEUS_CONTENT_TYPE, self.render()),
+            ("GET", _) => (
+                "404 Not Found",
+                "text/plain; charset=utf-8",
+                "not found\n".to_string(),
+            ),
+            _ => (
+                "405 Method Not Allowed",
+                "text/plain; charset=utf-8",
+                "method not allowed\n".to_string(),
+            ),
+        };
+        write!(
+            stream,
+            "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )?;
+        stream.flush()
+    }
+}
+
+/// Handle to a running metrics endpoint started by [`PrometheusExporter::serve`].
+///
+/// Dropping the handle stops the server.
+#[cfg(feature = "metrics-http")]
+pub struct MetricsServer {
+    local_addr: SocketAddr,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+#[cfg(feature = "metrics-http")]
+impl MetricsServer {
+    /// Address the endpoint is listening on.
+    #[must_use]
+    pub const fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// Stop the server and wait for its thread to exit.
+    pub fn shutdown(self) {
+        drop(self);
+    }
+}
+
+#[cfg(feature = "metrics-http")]
+impl Drop for MetricsServer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+        // Wake the accept loop so it observes the stop flag.
+        let _ = TcpStream::connect(self.local_addr);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn header(out: &mut String, name: &str, kind: &str, help: Option<&str>) {
+    if let Some(help) = help {
+        let help = help.replace('\\', "\\\\").replace('\n', "\\n");
+        let _ = writeln!(out, "# HELP {name} {help}");
+    }
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+}
+
+fn transform_labels(t: &TransformMetrics) -> String {
+    let mut labels = format!("step=\"{}\",kind=\"{}\"", t.node.step, t.node.kind);
+    if let Some(name) = &t.node.name {
+        let _ = write!(labels, ",name=\"{}\"", label_escape(name));
+    }
+    labels
+}
+
+fn label_escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Sample value text; non-finite values use the exposition spellings.
+fn number(v: f64) -> String {
+    if v.is_nan() {
+        "NaN".to_string()
+    } else if v.is_infinite() {
+        if v > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        v.to_string()
+    }
+}
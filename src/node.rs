@@ -17,20 +17,47 @@
 //!   other `value_only` ops without changing semantics.
 //! - [`DynOp::cost_hint`] -- tiny integer used to bias local ordering (smaller
 //!   tends to run earlier).
+//! - [`DynOp::expanding`] -- the op may emit several outputs per input (`flat_map`);
+//!   used by pipeline validation to flag re-expanded groups.
+//! - [`DynOp::key_predicate`] / [`DynOp::key_filter_before`] -- a filter that reads only
+//!   keys, and an op that can take such a filter ahead of itself; used to run
+//!   `filter_keys` before costlier key-preserving transforms.
+//! - [`DynOp::is_noop`] -- the op passes its input through unchanged and can be dropped.
 //!
 //! # Notes
 //! * Nodes are **type-erased** at runtime via `Partition` (a boxed `Any`), but
 //!   every node closure we build is typed, so downcasts are safe where used.
-//! * Barriers like [`Node::GroupByKey`], [`Node::CombineValues`], and [`Node::Reshuffle`]
-//!   intentionally break partition parallelism to enforce global grouping/merge/redistribution semantics.
+//! * Barriers like [`Node::GroupByKey`], [`Node::CombineValues`], [`Node::Reshuffle`], and
+//!   [`Node::Sort`] intentionally break partition parallelism to enforce global
+//!   grouping/merge/redistribution/ordering semantics.
 //! * [`Node::CoGroup`] executes two **subplans** (left/right) and then invokes a
 //!   typed closure to produce joined results; it is the building block for
-//!   `join_inner`, `join_left`, `join_right`, and `join_full`.
+//!   `join_inner`, `join_left`, `join_right`, and `join_full`. [`Node::CoGroupN`] is its
+//!   n-ary counterpart used by `join_many` and by collapsed join chains.
 
-use crate::type_token::{Partition, TypeTag, VecOps};
+use crate::type_token::{LazyPartition, Partition, TypeTag, VecOps};
 use std::any::Any;
+use std::path::Path;
 use std::sync::Arc;
 
+/// A key predicate with the key type erased, as handed between ops by
+/// [`DynOp::key_predicate`] and [`DynOp::key_filter_before`].
+///
+/// The argument is a `&K` for the `K` of the `(K, V)` rows being filtered.
+pub type KeyPredicate = Arc<dyn Fn(&dyn Any) -> bool + Send + Sync>;
+
+/// The `local` and `merge` closures of one external group-by-key run.
+pub type SpillStages = (
+    Arc<dyn Fn(Partition) -> Partition + Send + Sync>,
+    Arc<dyn Fn(Vec<Partition>) -> Partition + Send + Sync>,
+);
+
+/// External group-by-key factory: `(memory_budget_bytes, spill_dir) -> (local, merge)`,
+/// called once per run.
+///
+/// See [`Node::GroupByKey`].
+pub type SpillGroupFn = Arc<dyn Fn(usize, &Path) -> SpillStages + Send + Sync>;
+
 /// Trait for **stateless, per-partition** operators.
 ///
 /// Implementors receive a single `Partition` (typically a `Vec<T>` or `Vec<(K,V)>`)
@@ -50,6 +77,16 @@ pub trait DynOp: Send + Sync {
     /// Apply the operator to a single partition.
     fn apply(&self, input: Partition) -> Partition;
 
+    /// Streaming form of [`Self::apply`], used by
+    /// [`ExecMode::Streaming`](crate::ExecMode::Streaming).
+    ///
+    /// Ops that work element by element override this to wrap the input stream lazily
+    /// (see [`LazyPartition::into_elems`] and [`LazyPartition::stream`]). The default
+    /// materializes the input and calls [`Self::apply`], which is always correct.
+    fn apply_lazy(self: Arc<Self>, input: LazyPartition) -> LazyPartition {
+        LazyPartition::from(self.apply(input.materialize()))
+    }
+
     /// True if the op preserves the key in `(K, V)` rows.
     fn key_preserving(&self) -> bool {
         false
@@ -79,6 +116,14 @@ pub trait DynOp: Send + Sync {
         false
     }
 
+    /// True if the op may emit more than one element per input (`flat_map`-style).
+    ///
+    /// Pre-flight validation uses this to warn when the groups produced by a
+    /// `GroupByKey` are immediately flattened back into elements.
+    fn expanding(&self) -> bool {
+        false
+    }
+
     /// If this op is a hard upper-bound limit (`take(N)`), returns `Some(N)`.
     ///
     /// The planner uses this to set [`crate::planner::Plan::limit`], which the
@@ -103,6 +148,47 @@ pub trait DynOp: Send + Sync {
     fn cardinality_multiplier_hint(&self) -> f64 {
         1.0
     }
+
+    /// If the op is a filter over `(K, V)` rows whose predicate reads only the key
+    /// ([`PCollection::filter_keys`](crate::PCollection::filter_keys)), returns that
+    /// predicate.
+    ///
+    /// The planner offers it to the preceding op through [`Self::key_filter_before`].
+    fn key_predicate(&self) -> Option<KeyPredicate> {
+        None
+    }
+
+    /// Build a filter over this op's *input* rows that keeps those whose key satisfies
+    /// `pred`, so that a key-only filter placed after this op can run before it instead.
+    ///
+    /// Only key-preserving ops over `(K, V)` rows can do this, since the filter sees the
+    /// same keys on either side of them. The default returns `None`, which leaves the
+    /// filter where it is.
+    fn key_filter_before(&self, _pred: &KeyPredicate) -> Option<Arc<dyn DynOp>> {
+        None
+    }
+
+    /// True if the op returns its input unchanged (for example `take(usize::MAX)`).
+    ///
+    /// The planner drops such ops, and any stateless stage left empty, before execution.
+    fn is_noop(&self) -> bool {
+        false
+    }
+}
+
+/// How a [`Node::CoGroup`] pairs the elements of its two inputs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CoGroupKind {
+    /// Rows with equal keys are joined (the keyed `join_*` helpers).
+    Keyed,
+    /// Every left element is paired with every right element
+    /// ([`PCollection::cross_join`](crate::PCollection::cross_join)). The planner rejects
+    /// the plan when the estimated number of pairs exceeds `max_pairs`; `None` (set by
+    /// [`PCollection::allow_large`](crate::PCollection::allow_large)) skips the check.
+    Cross {
+        /// The largest estimated output the planner accepts.
+        max_pairs: Option<usize>,
+    },
 }
 
 /// A node in the compiled execution plan.
@@ -112,6 +198,7 @@ pub trait DynOp: Send + Sync {
 /// - The planner may fuse zero or more [`Node::Stateless`] segments.
 /// - Barriers like [`Node::GroupByKey`] and [`Node::CombineValues`] materialize/merge partitions.
 /// - [`Node::CoGroup`] executes two subplans (for joins) and then a typed exec closure.
+/// - [`Node::CoGroupN`] does the same for any number of subplans (multi-way joins).
 /// - [`Node::Materialized`] anchors a pre-existing typed payload for terminal reads.
 #[derive(Clone)]
 pub enum Node {
@@ -148,9 +235,17 @@ pub enum Node {
     ///
     /// - `local`: partitions of `Vec<(K, V)>` -> `HashMap<K, Vec<V>>`
     /// - `merge`: merges `Vec<HashMap<K, Vec<V>>>` -> `Vec<(K, Vec<V>)>`
+    /// - `spill_group`: optional external implementation. Given a memory budget in bytes
+    ///   and a spill directory, it returns replacement `local`/`merge` closures that spill
+    ///   each partition's pairs as needed and merge them into `Vec<(K, Vec<V>)>` (see
+    ///   [`ExternalGroup`](crate::spill_group::ExternalGroup)). The runner swaps them in
+    ///   when [`DirectRunner::memory_budget`](crate::DirectRunner::memory_budget) is set.
+    ///   Only available when keys and values are serializable (`spilling` + `coders`
+    ///   features).
     GroupByKey {
         local: Arc<dyn Fn(Partition) -> Partition + Send + Sync>,
         merge: Arc<dyn Fn(Vec<Partition>) -> Partition + Send + Sync>,
+        spill_group: Option<SpillGroupFn>,
     },
 
     /// Binary co-group (building block for joins).
@@ -168,10 +263,12 @@ pub enum Node {
     ///   whose key is definitively absent from the other side before the hash-join step.
     ///   Set by the join builders in [`crate::helpers::joins`]; the planner reads this
     ///   flag to emit an [`crate::planner::OptimizationDecision::BloomSemiJoin`] record.
+    /// - `kind`: keyed join or cross join; the planner checks the size guard of cross joins.
     ///
     /// **Typical `exec` outputs**
     /// - Inner join: `Vec<(K, (V, W))>`
     /// - Left/Right/Full outer: `Vec<(K, (Option<V>, Option<W>))>` with appropriate `None`s.
+    /// - Cross join: `Vec<(A, B)>`, from unkeyed `Vec<A>` and `Vec<B>` inputs.
     CoGroup {
         left_chain: Arc<Vec<Self>>,
         right_chain: Arc<Vec<Self>>,
@@ -180,6 +277,27 @@ pub enum Node {
         exec: Arc<dyn Fn(Partition, Partition) -> Partition + Send + Sync>,
         /// `true` when the `exec` closure applies a Bloom semi-join pre-filter.
         uses_bloom_semi_join: bool,
+        /// Keyed or cross join.
+        kind: CoGroupKind,
+    },
+
+    /// N-ary co-group (multi-way join).
+    ///
+    /// Generalizes [`Node::CoGroup`] to any number of inputs that are shuffled once and
+    /// joined in a single step. Built directly by
+    /// [`PCollection::join_many`](crate::PCollection::join_many), and by the planner when it
+    /// collapses a chain of binary joins whose subplans themselves end in a join.
+    ///
+    /// **Fields**
+    /// - `chains`: subplans to execute to materialization (one per input).
+    /// - `coalesce`: per-input merge of that subplan's partition outputs into a single
+    ///   partition; `coalesce[i]` belongs to `chains[i]`. Inputs may have distinct types.
+    /// - `exec`: typed closure that receives the coalesced partitions (in `chains` order)
+    ///   and returns the joined partition.
+    CoGroupN {
+        chains: Arc<Vec<Vec<Self>>>,
+        coalesce: Vec<Arc<dyn Fn(Vec<Partition>) -> Partition + Send + Sync>>,
+        exec: Arc<dyn Fn(Vec<Partition>) -> Partition + Send + Sync>,
     },
 
     /// Global (non-keyed) combine:
@@ -198,19 +316,40 @@ pub enum Node {
         tree_reduce: bool,
     },
 
-    /// Shuffle barrier: collect all input partitions and re-distribute elements evenly.
+    /// Shuffle barrier: collect all input partitions and re-distribute elements.
     ///
     /// The `reshuffle` closure receives all current partitions and the desired output
-    /// partition count `n`, then returns exactly `n` evenly sized partitions.
+    /// partition count `n`, then returns (at most) `n` partitions.
     ///
-    /// - In parallel execution `n` equals the runner's configured partition count,
-    ///   restoring full parallelism for downstream [`Node::Stateless`] stages.
+    /// - In parallel execution `n` is `target_partitions` when set, otherwise the runner's
+    ///   adaptive partition count, restoring full parallelism for downstream
+    ///   [`Node::Stateless`] stages.
     /// - In sequential execution `n = 1`; elements are collected into a single partition.
     ///
-    /// The closure is produced by [`PCollection::reshuffle`](crate::helpers::reshuffle) and
-    /// captures the element type `T` via downcast.
+    /// The closure is produced by [`PCollection::reshuffle`](crate::helpers::reshuffle) or
+    /// one of the [`repartition`](crate::helpers::repartition) transforms and captures the
+    /// element type `T` via downcast.
     Reshuffle {
         reshuffle: Arc<dyn Fn(Vec<Partition>, usize) -> Vec<Partition> + Send + Sync>,
+        /// Caller-fixed output partition count (`repartition(n)` and friends).
+        target_partitions: Option<usize>,
+    },
+
+    /// Global sort barrier: produce a totally ordered collection.
+    ///
+    /// - `range_partition`: receives all current partitions and the desired output count
+    ///   `n`, and routes every element into one of at most `n` contiguous key ranges
+    ///   (split points are sampled from the data). Range `i` holds only elements that sort
+    ///   before those of range `i + 1`. With `n = 1` it simply concatenates the input.
+    /// - `sort_run`: stably sorts a single `Vec<T>` range. The parallel runner sorts all
+    ///   ranges concurrently, so concatenating the output partitions yields the sorted
+    ///   sequence.
+    ///
+    /// Built by [`PCollection::sort_by`](crate::PCollection::sort_by) and
+    /// [`PCollection::sort_by_key`](crate::PCollection::sort_by_key).
+    Sort {
+        range_partition: Arc<dyn Fn(Vec<Partition>, usize) -> Vec<Partition> + Send + Sync>,
+        sort_run: Arc<dyn Fn(Partition) -> Partition + Send + Sync>,
     },
 
     /// Pre-materialized payload (type-erased).
@@ -234,3 +373,53 @@ pub enum Node {
         merge: Arc<dyn Fn(Vec<Partition>) -> Partition + Send + Sync>,
     },
 }
+
+impl Node {
+    /// Short, stable name of the node variant (e.g. `"GroupByKey"`), as used in
+    /// explain output and graph exports.
+    #[must_use]
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            Self::Source { .. } => "Source",
+            Self::Stateless(_) => "Stateless",
+            Self::CombineValues { .. } => "CombineValues",
+            Self::GroupByKey { .. } => "GroupByKey",
+            Self::CoGroup { .. } => "CoGroup",
+            Self::CoGroupN { .. } => "CoGroupN",
+            Self::CombineGlobal { .. } => "CombineGlobal",
+            Self::Reshuffle { .. } => "Reshuffle",
+            Self::Sort { .. } => "Sort",
+            Self::Materialized(_) => "Materialized",
+            Self::Flatten { .. } => "Flatten",
+        }
+    }
+
+    /// True for nodes that coalesce or redistribute partitions (everything except
+    /// sources, stateless runs, and materialized payloads).
+    #[must_use]
+    pub const fn is_barrier(&self) -> bool {
+        !matches!(
+            self,
+            Self::Source { .. } | Self::Stateless(_) | Self::Materialized(_)
+        )
+    }
+
+    /// Input subplans executed by this node, in input order.
+    ///
+    /// Empty for every node except [`Node::CoGroup`] (left, right), [`Node::CoGroupN`],
+    /// and [`Node::Flatten`].
+    #[must_use]
+    pub fn subplans(&self) -> Vec<&[Self]> {
+        match self {
+            Self::CoGroup {
+                left_chain,
+                right_chain,
+                ..
+            } => vec![left_chain.as_slice(), right_chain.as_slice()],
+            Self::CoGroupN { chains, .. } | Self::Flatten { chains, .. } => {
+                chains.iter().map(Vec::as_slice).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+}
@@ -14,17 +14,29 @@
 //! execution occurs in topologically sorted linear chains rather than arbitrary DAGs.
 
 use crate::NodeId;
+use crate::RunnerConfig;
+use crate::helpers::cache::{CacheSourceFn, cached_source};
+use crate::helpers::combine::GroupLift;
+use crate::helpers::run_all::OutputAction;
+use crate::helpers::sampling::DEFAULT_SAMPLE_SEED;
+use crate::io::cloud::resolver::ConfigResolver;
+use crate::manifest::IoLocation;
+use crate::memory::PartitionSizer;
 use crate::node::Node;
+use crate::spec::SpecRecord;
+use crate::window::{Clock, SystemClock};
+use anyhow::{Result, anyhow};
 use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex, PoisonError};
 
 #[cfg(feature = "coders")]
 use crate::coders::{ElementCoder, PostcardCoder, PostcardKvCoder};
-#[cfg(feature = "coders")]
 use crate::collection::Element;
 
 #[cfg(feature = "metrics")]
-use crate::metrics::MetricsCollector;
+use crate::metrics::{ElemStats, MetricsCollector, UserMetrics};
 
 /// Thread-safe pipeline graph structure holding all nodes and edges.
 ///
@@ -49,13 +61,42 @@ pub struct Pipeline {
 /// - `node_names`: optional human-readable labels for individual nodes, populated by
 ///   [`PCollection::with_name`](crate::PCollection::with_name); see
 ///   [`Pipeline::set_node_name`] and [`Pipeline::node_name`] for the public accessors.
+/// - `partition_hints`: per-node partition counts requested through
+///   [`PCollection::with_partitions`](crate::PCollection::with_partitions).
+/// - `group_lifts`: nodes after a `group_by_key` that the planner may lift into
+///   per-partition partial aggregation, with the merge to use.
+/// - `clock`: processing-time source read by transforms like
+///   [`attach_processing_time`](crate::PCollection::attach_processing_time).
+/// - `sample_seed`: seed of the default-seeded sampling transforms.
+/// - `config_resolver`: resolver of `config://` references in I/O paths and names, set
+///   through [`Pipeline::set_config_resolver`].
+/// - `runner_config`: configuration of the runs the pipeline starts itself, set through
+///   [`Pipeline::set_runner_config`].
 /// - `scope_stack`: stack of active [`ScopeFrame`]s for [`Pipeline::named_scope`].
 ///   The active scope path is `scope_stack.iter().map(|f| &f.name).join("/")`;
 ///   newly inserted nodes inside a scope get an auto-generated name of
 ///   `"<path>/<counter>"` (per-frame counter), and
 ///   [`PCollection::with_name`](crate::PCollection::with_name) prepends the
 ///   active path to user-supplied labels.
+/// - `specs`: how each node built through a [`DoFnRegistry`](crate::spec::DoFnRegistry)
+///   was made, used by [`Pipeline::spec`].
+/// - `cachers`: per-node builders of cached sources, used by [`Pipeline::run_all`].
+/// - `cached_from`: the node each [`PCollection::cache`](crate::PCollection::cache)d
+///   source reads, which is not connected to it by an edge.
+/// - `upstream`: the nodes each join or flatten consumes, which are not connected to it
+///   by an edge.
+/// - `outputs`: outputs registered for [`Pipeline::run_all`] and not yet run.
+/// - `io_inputs` / `io_outputs`: the format and location of nodes read from files or
+///   services, and of outputs declared with
+///   [`PCollection::declare_output`](crate::PCollection::declare_output), used by
+///   [`Pipeline::describe`].
+/// - `sizers`: per-node partition size estimation for the node's output type, used by
+///   the runner's memory budget.
 /// - `metrics`: optional metrics collector for tracking execution statistics.
+/// - `elem_stats`: per-node element counting for the node's output type, used by the
+///   runner's per-transform metrics.
+/// - `user_metrics`: values recorded through [`MetricsContext`](crate::metrics::MetricsContext)s
+///   during the current run, folded into `metrics` when the run ends.
 ///
 /// The parent synchronizes access to the data in the [`Pipeline`].
 pub(crate) struct PipelineInner {
@@ -63,7 +104,26 @@ pub(crate) struct PipelineInner {
     pub nodes: HashMap<NodeId, Node>,
     pub edges: Vec<(NodeId, NodeId)>,
     pub node_names: HashMap<NodeId, String>,
+    pub partition_hints: HashMap<NodeId, usize>,
+    pub group_lifts: HashMap<NodeId, GroupLift>,
+    pub clock: Arc<dyn Clock>,
+    pub sample_seed: u64,
+    pub config_resolver: Option<ConfigResolver>,
+    pub runner_config: RunnerConfig,
     pub scope_stack: Vec<ScopeFrame>,
+    pub specs: HashMap<NodeId, SpecRecord>,
+    /// Per-node builder of a cached source for the node's output type, used by
+    /// [`Pipeline::run_all`] to share work between outputs.
+    pub cachers: HashMap<NodeId, CacheSourceFn>,
+    /// For each cached source node, the node whose output it caches.
+    pub cached_from: HashMap<NodeId, NodeId>,
+    /// For each join or flatten node, the nodes whose output it consumes.
+    pub upstream: HashMap<NodeId, Vec<NodeId>>,
+    /// Work registered for each pending [`Pipeline::run_all`] output node.
+    pub outputs: HashMap<NodeId, OutputAction>,
+    pub io_inputs: HashMap<NodeId, IoLocation>,
+    pub io_outputs: HashMap<NodeId, IoLocation>,
+    pub sizers: HashMap<NodeId, PartitionSizer>,
     /// Per-node element coder, keyed by output [`NodeId`]. Populated by the
     /// combinators when `coders` is on; consumed by wire backends via
     /// [`Pipeline::snapshot_coders`].
@@ -71,6 +131,10 @@ pub(crate) struct PipelineInner {
     pub coders: HashMap<NodeId, Arc<dyn ElementCoder>>,
     #[cfg(feature = "metrics")]
     pub metrics: Option<MetricsCollector>,
+    #[cfg(feature = "metrics")]
+    pub elem_stats: HashMap<NodeId, ElemStats>,
+    #[cfg(feature = "metrics")]
+    pub user_metrics: Arc<Mutex<UserMetrics>>,
 }
 
 /// One frame of the active scope stack used by [`Pipeline::named_scope`].
@@ -91,11 +155,29 @@ impl Default for Pipeline {
                 nodes: HashMap::new(),
                 edges: vec![],
                 node_names: HashMap::new(),
+                partition_hints: HashMap::new(),
+                group_lifts: HashMap::new(),
+                clock: Arc::new(SystemClock),
+                sample_seed: DEFAULT_SAMPLE_SEED,
+                config_resolver: None,
+                runner_config: RunnerConfig::default(),
                 scope_stack: Vec::new(),
+                specs: HashMap::new(),
+                cachers: HashMap::new(),
+                cached_from: HashMap::new(),
+                upstream: HashMap::new(),
+                outputs: HashMap::new(),
+                io_inputs: HashMap::new(),
+                io_outputs: HashMap::new(),
+                sizers: HashMap::new(),
                 #[cfg(feature = "coders")]
                 coders: HashMap::new(),
                 #[cfg(feature = "metrics")]
                 metrics: None,
+                #[cfg(feature = "metrics")]
+                elem_stats: HashMap::new(),
+                #[cfg(feature = "metrics")]
+                user_metrics: Arc::default(),
             })),
         }
     }
@@ -144,6 +226,15 @@ impl Pipeline {
         id
     }
 
+    /// Apply `f` to the node `id` in place, returning its result, or `None` if there
+    /// is no such node.
+    ///
+    /// Used by fluent modifiers such as [`PCollection::allow_large`](crate::PCollection::allow_large)
+    /// that adjust the node a collection was just built from.
+    pub(crate) fn update_node<R>(&self, id: NodeId, f: impl FnOnce(&mut Node) -> R) -> Option<R> {
+        self.inner.lock().unwrap().nodes.get_mut(&id).map(f)
+    }
+
     /// Connect two nodes by their IDs, forming a directed edge `(from -> to)`.
     ///
     /// Used to chain together consecutive transforms within the same pipeline.
@@ -151,23 +242,23 @@ impl Pipeline {
         self.inner.lock().unwrap().edges.push((from, to));
     }
 
-    /// Attach the default postcard coder for output type `T` to `id`.
+    /// Record `T` as the output type of `id`: its default postcard coder (with the
+    /// `coders` feature), how to cache it for [`run_all`](Self::run_all), its size
+    /// estimation for the runner's memory budget, and its element counting for
+    /// per-transform metrics.
     ///
-    /// Combinators call this unconditionally right after `insert_node`; without
-    /// the `coders` feature it compiles to a no-op so the call sites stay
-    /// feature-agnostic.
-    #[cfg(feature = "coders")]
+    /// Combinators call this unconditionally right after `insert_node`, so the call
+    /// sites stay feature-agnostic.
     pub(crate) fn set_coder<T: Element>(&self, id: NodeId) {
-        self.inner
-            .lock()
-            .unwrap()
-            .coders
-            .insert(id, Arc::new(PostcardCoder::<T>::new()));
+        let mut g = self.inner.lock().unwrap();
+        g.cachers.insert(id, cached_source::<T>);
+        g.sizers.insert(id, PartitionSizer::of::<T>());
+        #[cfg(feature = "coders")]
+        g.coders.insert(id, Arc::new(PostcardCoder::<T>::new()));
+        #[cfg(feature = "metrics")]
+        g.elem_stats.insert(id, ElemStats::of::<T>());
     }
 
-    #[cfg(not(feature = "coders"))]
-    pub(crate) fn set_coder<T>(&self, _id: NodeId) {}
-
     /// Upgrade `id` to a KV-aware coder. Called by `group_by_key` on its
     /// predecessor so the pre-GBK edge can emit each `(K, V)` as two
     /// independently length-prefixed postcard halves (mirroring Beam's
@@ -220,6 +311,43 @@ impl Pipeline {
         (g.nodes.clone(), g.edges.clone())
     }
 
+    /// A separate pipeline with a copy of this graph and its metadata, sharing the
+    /// metrics collector. Registered [`run_all`](Self::run_all) outputs are not copied.
+    pub(crate) fn execution_copy(&self) -> Self {
+        let g = self.inner.lock().unwrap();
+        Self {
+            inner: Arc::new(Mutex::new(PipelineInner {
+                next_id: g.next_id,
+                nodes: g.nodes.clone(),
+                edges: g.edges.clone(),
+                node_names: g.node_names.clone(),
+                partition_hints: g.partition_hints.clone(),
+                group_lifts: g.group_lifts.clone(),
+                clock: Arc::clone(&g.clock),
+                sample_seed: g.sample_seed,
+                config_resolver: g.config_resolver.clone(),
+                runner_config: g.runner_config.clone(),
+                scope_stack: Vec::new(),
+                specs: g.specs.clone(),
+                cachers: g.cachers.clone(),
+                cached_from: g.cached_from.clone(),
+                upstream: g.upstream.clone(),
+                outputs: HashMap::new(),
+                io_inputs: g.io_inputs.clone(),
+                io_outputs: g.io_outputs.clone(),
+                sizers: g.sizers.clone(),
+                #[cfg(feature = "coders")]
+                coders: g.coders.clone(),
+                #[cfg(feature = "metrics")]
+                metrics: g.metrics.clone(),
+                #[cfg(feature = "metrics")]
+                elem_stats: g.elem_stats.clone(),
+                #[cfg(feature = "metrics")]
+                user_metrics: Arc::clone(&g.user_metrics),
+            })),
+        }
+    }
+
     /// Attach a human-readable name to the node identified by `id`.
     ///
     /// Names are pure metadata — they do not influence planning or execution.
@@ -278,6 +406,180 @@ impl Pipeline {
         g.node_names.clone()
     }
 
+    /// Ask the parallel runner to use `partitions` partitions for the stage that
+    /// produces node `id`, overriding the planner's suggestion and the runner's
+    /// [`ExecMode`](crate::ExecMode) partition count for that stage.
+    ///
+    /// A later call for the same node replaces the earlier hint. Most user code goes
+    /// through [`PCollection::with_partitions`](crate::PCollection::with_partitions),
+    /// whose docs describe which stages can honor a hint. `0` is treated as `1`.
+    ///
+    /// # Panics
+    ///
+    /// If the pipeline mutex is poisoned by a concurrent panic.
+    pub fn set_partition_hint(&self, id: NodeId, partitions: usize) {
+        let mut g = self.inner.lock().unwrap();
+        g.partition_hints.insert(id, partitions.max(1));
+    }
+
+    /// Return a clone of the `NodeId -> partition count` hints set through
+    /// [`set_partition_hint`](Self::set_partition_hint).
+    ///
+    /// # Panics
+    ///
+    /// If the pipeline mutex is poisoned by a concurrent panic.
+    #[must_use]
+    pub fn partition_hints_snapshot(&self) -> HashMap<NodeId, usize> {
+        let g = self.inner.lock().unwrap();
+        g.partition_hints.clone()
+    }
+
+    /// Record that node `id` may be lifted into partial aggregation when it directly
+    /// follows a `group_by_key`.
+    pub(crate) fn set_group_lift(&self, id: NodeId, lift: GroupLift) {
+        let mut g = self.inner.lock().unwrap();
+        g.group_lifts.insert(id, lift);
+    }
+
+    /// Return a clone of the lift hints set through
+    /// [`set_group_lift`](Self::set_group_lift).
+    pub(crate) fn group_lifts_snapshot(&self) -> HashMap<NodeId, GroupLift> {
+        let g = self.inner.lock().unwrap();
+        g.group_lifts.clone()
+    }
+
+    /// Replace the clock that supplies processing time (the [`SystemClock`] by default).
+    ///
+    /// Transforms read the clock when they run, but capture it when they are built, so
+    /// set it before building the transforms that should use it.
+    ///
+    /// # Panics
+    ///
+    /// If the pipeline mutex is poisoned by a concurrent panic.
+    pub fn set_clock(&self, clock: Arc<dyn Clock>) {
+        let mut g = self.inner.lock().unwrap();
+        g.clock = clock;
+    }
+
+    /// Return the pipeline's processing-time clock.
+    ///
+    /// # Panics
+    ///
+    /// If the pipeline mutex is poisoned by a concurrent panic.
+    #[must_use]
+    pub fn clock(&self) -> Arc<dyn Clock> {
+        let g = self.inner.lock().unwrap();
+        Arc::clone(&g.clock)
+    }
+
+    /// Set the seed used by sampling transforms that take no explicit seed, such as
+    /// [`sample_globally`](crate::PCollection::sample_globally) and
+    /// [`sample_per_key`](crate::PCollection::sample_per_key).
+    ///
+    /// The seed is read when a transform is built, so set it first.
+    ///
+    /// # Panics
+    ///
+    /// If the pipeline mutex is poisoned by a concurrent panic.
+    pub fn set_sample_seed(&self, seed: u64) {
+        let mut g = self.inner.lock().unwrap();
+        g.sample_seed = seed;
+    }
+
+    /// Return the seed set through [`set_sample_seed`](Self::set_sample_seed).
+    ///
+    /// # Panics
+    ///
+    /// If the pipeline mutex is poisoned by a concurrent panic.
+    #[must_use]
+    pub fn sample_seed(&self) -> u64 {
+        let g = self.inner.lock().unwrap();
+        g.sample_seed
+    }
+
+    /// Resolve `config://` references in the paths and names given to I/O helpers
+    /// through `resolver`.
+    ///
+    /// References are resolved when a source or sink is built, so set the resolver
+    /// first. See [`ConfigResolver`] for the reference syntax.
+    ///
+    /// # Panics
+    ///
+    /// If the pipeline mutex is poisoned by a concurrent panic.
+    pub fn set_config_resolver(&self, resolver: ConfigResolver) {
+        let mut g = self.inner.lock().unwrap();
+        g.config_resolver = Some(resolver);
+    }
+
+    /// Return the resolver set through [`set_config_resolver`](Self::set_config_resolver).
+    ///
+    /// # Panics
+    ///
+    /// If the pipeline mutex is poisoned by a concurrent panic.
+    #[must_use]
+    pub fn config_resolver(&self) -> Option<ConfigResolver> {
+        let g = self.inner.lock().unwrap();
+        g.config_resolver.clone()
+    }
+
+    /// Configure the runs the pipeline starts on the caller's behalf: the file, key and
+    /// object writers (such as [`write_jsonl`](crate::PCollection::write_jsonl)) and
+    /// [`run_all`](Self::run_all).
+    ///
+    /// Their spill directory, memory budget, timeout, metrics and cancellation token come
+    /// from `config`. The writers still choose their own execution mode (see
+    /// [`crate::helpers::file_sink`]). The default is [`RunnerConfig::default`].
+    ///
+    /// # Panics
+    ///
+    /// If the pipeline mutex is poisoned by a concurrent panic.
+    pub fn set_runner_config(&self, config: RunnerConfig) {
+        let mut g = self.inner.lock().unwrap();
+        g.runner_config = config;
+    }
+
+    /// Return the configuration set through
+    /// [`set_runner_config`](Self::set_runner_config).
+    ///
+    /// # Panics
+    ///
+    /// If the pipeline mutex is poisoned by a concurrent panic.
+    #[must_use]
+    pub fn runner_config(&self) -> RunnerConfig {
+        let g = self.inner.lock().unwrap();
+        g.runner_config.clone()
+    }
+
+    /// Resolve the `config://` references in `value` through the pipeline's
+    /// [`ConfigResolver`]. Values without references are returned unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `value` holds a reference but no resolver is set, or the
+    /// resolver fails.
+    ///
+    /// # Panics
+    ///
+    /// If the pipeline mutex is poisoned by a concurrent panic.
+    pub fn resolve_config(&self, value: &str) -> Result<String> {
+        if !ConfigResolver::is_reference(value) {
+            return Ok(value.to_string());
+        }
+        let resolver = self.config_resolver().ok_or_else(|| {
+            anyhow!("{value:?} is a config reference, but no config resolver is set")
+        })?;
+        Ok(resolver.resolve(value)?)
+    }
+
+    /// [`resolve_config`](Self::resolve_config) for a filesystem path. Paths that are
+    /// not valid UTF-8 cannot hold a reference and are returned unchanged.
+    pub(crate) fn resolve_path(&self, path: &Path) -> Result<PathBuf> {
+        match path.to_str() {
+            Some(s) => Ok(PathBuf::from(self.resolve_config(s)?)),
+            None => Ok(path.to_path_buf()),
+        }
+    }
+
     /// Run `f` inside a named scope, returning whatever the closure returns.
     ///
     /// While the closure is executing, the supplied `name` is pushed onto an
@@ -414,6 +716,45 @@ impl Pipeline {
         g.metrics.clone()
     }
 
+    /// Record that `id` was built through a [`DoFnRegistry`](crate::spec::DoFnRegistry).
+    pub(crate) fn record_spec(&self, id: NodeId, record: SpecRecord) {
+        self.inner.lock().unwrap().specs.insert(id, record);
+    }
+
+    /// Record that the join or flatten `id` consumes the output of `inputs`.
+    pub(crate) fn record_upstream(&self, id: NodeId, inputs: Vec<NodeId>) {
+        self.inner.lock().unwrap().upstream.insert(id, inputs);
+    }
+
+    /// Record that the source `id` reads `format` data from `location`.
+    pub(crate) fn record_input(&self, id: NodeId, format: &str, location: impl Display) {
+        self.inner
+            .lock()
+            .unwrap()
+            .io_inputs
+            .insert(id, IoLocation::new(format, location));
+    }
+
+    /// Record that the output `id` is written as `format` to `location`.
+    pub(crate) fn record_output(&self, id: NodeId, format: &str, location: impl Display) {
+        self.inner
+            .lock()
+            .unwrap()
+            .io_outputs
+            .insert(id, IoLocation::new(format, location));
+    }
+
+    /// Size estimation registered for the output type of `id`, if known.
+    pub(crate) fn sizer(&self, id: NodeId) -> Option<PartitionSizer> {
+        self.inner.lock().unwrap().sizers.get(&id).copied()
+    }
+
+    /// Element counting registered for the output type of `id`, if known.
+    #[cfg(feature = "metrics")]
+    pub(crate) fn elem_stats(&self, id: NodeId) -> Option<ElemStats> {
+        self.inner.lock().unwrap().elem_stats.get(&id).copied()
+    }
+
     /// Record the start of pipeline execution in metrics.
     ///
     /// # Panics
@@ -422,6 +763,7 @@ impl Pipeline {
     #[cfg(feature = "metrics")]
     pub fn record_metrics_start(&self) {
         let g = self.inner.lock().unwrap();
+        *g.user_metrics.lock().unwrap() = UserMetrics::default();
         if let Some(ref metrics) = g.metrics {
             metrics.record_start();
         }
@@ -429,14 +771,28 @@ impl Pipeline {
 
     /// Record the end of pipeline execution in metrics.
     ///
+    /// Also folds the values recorded through
+    /// [`MetricsContext`](crate::metrics::MetricsContext)s during the run into the
+    /// collector.
+    ///
     /// # Panics
     ///
     /// If the pipeline is in an inconsistent state, such as during concurrent modifications.
     #[cfg(feature = "metrics")]
     pub fn record_metrics_end(&self) {
         let g = self.inner.lock().unwrap();
+        let user = std::mem::take(&mut *g.user_metrics.lock().unwrap());
         if let Some(ref metrics) = g.metrics {
+            metrics.merge_user_metrics(user);
             metrics.record_end();
         }
     }
+
+    /// Shared accumulator that transforms merge their [`MetricsContext`]s into.
+    ///
+    /// [`MetricsContext`]: crate::metrics::MetricsContext
+    #[cfg(feature = "metrics")]
+    pub(crate) fn user_metrics(&self) -> Arc<Mutex<UserMetrics>> {
+        Arc::clone(&self.inner.lock().unwrap().user_metrics)
+    }
 }
@@ -0,0 +1,97 @@
+//! [`PipelineResult`]: collected output together with metadata about the run that
+//! produced it.
+//!
+//! [`PCollection::collect_seq`](crate::PCollection::collect_seq) and friends return only
+//! the data. Their `_result` counterparts
+//! ([`collect_seq_result`](crate::PCollection::collect_seq_result),
+//! [`collect_par_result`](crate::PCollection::collect_par_result)) and
+//! [`DirectRunner::run_with_result`](crate::DirectRunner::run_with_result) return a
+//! [`PipelineResult`] instead, which also records how the run went: total wall time,
+//! the execution mode and partition count actually used, one [`StageSummary`] per plan
+//! step, the planner's warnings, and (with the `checkpointing` feature) what the run
+//! saved to or resumed from checkpoints.
+//!
+//! ```no_run
+//! use ironbeam::*;
+//! # fn main() -> anyhow::Result<()> {
+//! let p = Pipeline::default();
+//! let result = from_vec(&p, (0u32..1_000).collect::<Vec<_>>())
+//!     .filter(|x: &u32| x.is_multiple_of(3))
+//!     .with_name("multiples of three")
+//!     .collect_par_result(None, Some(4))?;
+//!
+//! println!("{} rows in {:?} on {} partitions", result.data.len(), result.wall_time, result.partitions);
+//! for stage in &result.stages {
+//!     println!("{}: {:?} elements", stage.step, stage.elements);
+//! }
+//! for warning in &result.warnings {
+//!     println!("warning: {warning}");
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+#[cfg(feature = "checkpointing")]
+use crate::checkpoint::CheckpointSummary;
+use crate::error::StepRef;
+use crate::runner::ExecMode;
+use crate::validation::Diagnostic;
+use std::time::Duration;
+
+/// The output of a pipeline run plus metadata about the run.
+///
+/// See the [module documentation](self) for how to obtain one.
+#[derive(Debug, Clone)]
+pub struct PipelineResult<T> {
+    /// The collected elements, as `collect_*` would have returned them.
+    pub data: Vec<T>,
+    /// Wall time of the whole run, from planning to the collected output.
+    pub wall_time: Duration,
+    /// Execution mode the runner was configured with.
+    pub mode: ExecMode,
+    /// Number of source partitions the run used (`1` for sequential and streaming runs,
+    /// and for single-element sources; `0` if the source was empty and nothing ran).
+    pub partitions: usize,
+    /// One summary per executed plan step, in execution order. Steps skipped because
+    /// the run resumed from a checkpoint are not listed.
+    pub stages: Vec<StageSummary>,
+    /// Warnings the planner raised for this pipeline (see
+    /// [`Plan::diagnostics`](crate::Plan::diagnostics)).
+    pub warnings: Vec<Diagnostic>,
+    /// What the run saved to and resumed from checkpoints, when checkpointing was
+    /// enabled.
+    #[cfg(feature = "checkpointing")]
+    pub checkpoints: Option<CheckpointSummary>,
+}
+
+impl<T> PipelineResult<T> {
+    /// The collected elements, dropping the metadata.
+    #[must_use]
+    pub fn into_data(self) -> Vec<T> {
+        self.data
+    }
+
+    /// The summary of the first stage named `name` (see
+    /// [`PCollection::with_name`](crate::PCollection::with_name)).
+    #[must_use]
+    pub fn stage(&self, name: &str) -> Option<&StageSummary> {
+        self.stages
+            .iter()
+            .find(|s| s.step.name.as_deref() == Some(name))
+    }
+}
+
+/// Metadata about one executed plan step.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StageSummary {
+    /// The plan step.
+    pub step: StepRef,
+    /// Number of partitions the step produced.
+    pub partitions: usize,
+    /// Number of elements the step produced, when they were counted. Streamed steps and
+    /// steps whose output type is not known are not counted.
+    pub elements: Option<u64>,
+    /// Wall time of the step. Steps executed together as one block report the block's
+    /// time on its last step.
+    pub wall_time: Duration,
+}
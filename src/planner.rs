@@ -11,42 +11,56 @@
 //!    predecessor selection when dead branches introduce extra incoming edges at a shared
 //!    node.
 //! 1. **Fuse stateless ops** -- adjacent `Node::Stateless` blocks are concatenated.
-//! 2. **`CoGroup` input reordering** -- input subchains of every `Flatten` node are
+//! 2. **Collapse join chains** -- a join whose input subplan is itself a join followed
+//!    only by stateless ops (e.g. `a.join_inner(&b).map_values(..).join_inner(&c)`) is
+//!    rewritten into a single n-ary `CoGroupN`, so every input is shuffled exactly once.
+//! 3. **`CoGroup` input reordering** -- input subchains of every `Flatten` node are
 //!    sorted by estimated cardinality (ascending). `cogroup_by_key!` implements N-way
 //!    grouping as a `Flatten` (one subplan per input collection) followed by a
 //!    `GroupByKey`; placing smaller subchains first reduces peak intermediate memory in
 //!    sequential execution. Subchains with unknown cardinality are moved to the end.
-//! 3. **Predicate pushdown before barriers** -- within a fused `Stateless` block immediately
+//! 4. **Predicate pushdown before barriers** -- within a fused `Stateless` block immediately
 //!    before a `GroupByKey` *or* `Reshuffle`, ops that are `key_preserving + value_only +
 //!    cardinality_reducing` (e.g. `filter_values`) are split into their own earlier block when
 //!    doing so is type-safe and cost-beneficial (cost-hint gate). Because `Reshuffle` never
 //!    alters element content or count, the same pushdown rationale that applies to `GroupByKey`
 //!    applies equally, reducing the volume of elements that flow into the redistribution step.
-//! 4. **Predicate pushdown into Flatten subplans** -- `value_only + cardinality_reducing` ops
+//! 5. **Predicate pushdown into Flatten subplans** -- `value_only + cardinality_reducing` ops
 //!    that immediately follow a `Flatten` are cloned into the tail of every Flatten input
 //!    subplan and removed from the post-Flatten block. Because each subplan produces the same
 //!    element type that the merge function expects, pushing a filter *before* the fan-in reduces
 //!    the volume of elements that flow into the merge step.
-//! 5. **Reorder value-only runs** -- within a stateless block where *all* ops are
+//! 6. **Reorder value-only runs** -- within a stateless block where *all* ops are
 //!    key-preserving and value-only, put cheaper/filters first using `cost_hint`.
-//! 6. **Lift GBK->Combine** -- if a `GroupByKey` is immediately followed by a
+//! 7. **Lift GBK->Combine** -- if a `GroupByKey` is immediately followed by a
 //!    `CombineValues` that also has a lifted local (`local_groups.is_some()`),
 //!    drop the `GroupByKey` and keep the combine, switching it to consume
 //!    `(K, V)` pairs via `local_pairs`.
-//! 7. **Eliminate redundant Reshuffle** -- a `Reshuffle` immediately before a shuffle
+//! 8. **Eliminate redundant Reshuffle** -- a `Reshuffle` immediately before a shuffle
 //!    barrier (`GroupByKey`, `CombineValues`, `CoGroup`, `Flatten`) is a no-op because
 //!    the barrier already redistributes all elements. Two consecutive `Reshuffle` nodes
-//!    reduce to one for the same reason. Runs after pass 6 so that lifted combiners
+//!    reduce to one for the same reason. Runs after pass 7 so that lifted combiners
 //!    (which remove the `GroupByKey`) are visible as `CombineValues` targets.
-//! 8. **Drop mid-materialized** -- only keep a `Materialized` node if it is the final
+//! 9. **Drop mid-materialized** -- only keep a `Materialized` node if it is the final
 //!    terminal in the chain.
 //!
+//! Before any pass runs, the graph feeding the terminal goes through the pre-flight
+//! checks of [`crate::validation::preflight`]: errors abort planning, warnings are
+//! attached to the plan.
+//!
 //! The planner also provides a heuristic **partition suggestion** that the runner
 //! may use to size parallel execution.
 
-use crate::node::{DynOp, Node};
-use crate::{NodeId, Pipeline};
-use anyhow::{Result, anyhow};
+#[cfg(feature = "coders")]
+use crate::coders::ElementCoder;
+use crate::helpers::combine::GroupLift;
+use crate::node::{CoGroupKind, DynOp, Node};
+use crate::type_token::VecOps;
+use crate::validation::preflight::preflight;
+use crate::validation::{Diagnostic, DiagnosticKind, Severity};
+use crate::{NodeId, Partition, Pipeline};
+use anyhow::{Result, anyhow, bail};
+use std::any::Any;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::fmt::{Display, Formatter, Result as FormatResult};
 use std::sync::Arc;
@@ -118,6 +132,23 @@ pub struct Plan {
     /// Used by [`Plan::explain`] to attach a per-step `name` derived from
     /// [`Plan::node_names`].
     pub chain_origin_ids: Vec<Vec<NodeId>>,
+    /// Warnings from the pre-flight validation run at the start of [`build_plan`]
+    /// (see [`crate::validation::preflight`]), followed by any
+    /// [`PCollection::with_partitions`](crate::PCollection::with_partitions) hints the
+    /// plan cannot honor. Errors never reach a plan: they make [`build_plan`] fail
+    /// instead.
+    pub diagnostics: Vec<Diagnostic>,
+    /// Per-chain-entry partition count requested through
+    /// [`PCollection::with_partitions`](crate::PCollection::with_partitions), parallel
+    /// to [`Plan::chain`].
+    ///
+    /// Only entries where the parallel runner forms partitions can be `Some`: the
+    /// source (how many partitions it is split into), and `Reshuffle` and `Sort`
+    /// barriers (how many partitions they produce). A hint on a stateless entry is
+    /// moved to the split that feeds it. These counts take precedence over
+    /// [`Plan::suggested_partitions`], the runner's configured partition count, and
+    /// its adaptive rescaling between barriers.
+    pub stage_partitions: Vec<Option<usize>>,
 }
 
 /// Represents an optimization decision made by the planner.
@@ -144,6 +175,20 @@ pub enum OptimizationDecision {
         /// The optimization removes the `GroupByKey` barrier.
         removed_barrier: bool,
     },
+    /// Aggregations reading `group_by_key` output were rewritten into per-partition
+    /// partial aggregation, removing the `GroupByKey` barrier.
+    ///
+    /// Covers `combine_values` with a combiner that
+    /// [accepts partial groups](crate::collection::CombineFn::accepts_partial_groups) and
+    /// `map_values` marked with
+    /// [`with_partial_merge`](crate::PCollection::with_partial_merge). Each partition's
+    /// values are grouped and aggregated locally; only the partial results are merged.
+    LiftedGroupAggregation {
+        /// Number of `combine_values` lifted.
+        combines: usize,
+        /// Number of `map_values` aggregations lifted.
+        aggregations: usize,
+    },
     /// Mid-pipeline materialized nodes were dropped.
     DroppedMidMaterialized {
         /// Number of materialized nodes removed.
@@ -299,6 +344,39 @@ pub enum OptimizationDecision {
         /// Estimated upper-bound percentage of elements filtered from the probe side.
         estimated_reduction_pct: u8,
     },
+
+    /// A chain of joins was collapsed into a single [`Node::CoGroupN`].
+    ///
+    /// Chaining `a.join_inner(&b).join_inner(&c)` nests the first join inside the
+    /// second join's left subplan. The planner inlines every nested join whose result
+    /// feeds the outer join only through stateless ops: the nested join's inputs become
+    /// direct inputs of one n-ary co-group, so all inputs are materialized and shuffled
+    /// once, and the original join closures (plus the stateless ops between them) run
+    /// back-to-back inside the fused `exec`.
+    CollapsedJoinChain {
+        /// Number of nested joins inlined into the outer join.
+        joins_collapsed: usize,
+        /// Number of input subplans of the resulting `CoGroupN`.
+        inputs: usize,
+    },
+
+    /// Key-only filters ([`PCollection::filter_keys`](crate::PCollection::filter_keys))
+    /// were moved ahead of costlier key-preserving ops in the same stateless stage.
+    ///
+    /// A filter that reads only the key sees the same keys before and after an op such
+    /// as `map_values`, so running it first skips the op's work on rows that would be
+    /// dropped anyway.
+    HoistedKeyFilters {
+        /// Number of filters moved.
+        filters: usize,
+    },
+
+    /// Ops that pass their input through unchanged (see [`DynOp::is_noop`]) were
+    /// removed, along with any stateless stage they left empty.
+    DroppedNoOps {
+        /// Number of ops removed.
+        count: usize,
+    },
 }
 
 /// Detailed explanation of an execution plan including cost estimates and optimizations.
@@ -369,6 +447,12 @@ impl Display for ExecutionExplanation {
         if let Some(parts) = self.suggested_partitions {
             writeln!(f, "│ Suggested Parts:   {parts:>10}")?;
         }
+        if let Some(n) = self.cost_estimate.estimated_output_elements {
+            writeln!(f, "│ Est. Output:       {n:>10}")?;
+        }
+        if let Some(bytes) = self.cost_estimate.estimated_peak_bytes {
+            writeln!(f, "│ Est. Peak Memory:  {:>10}", format_bytes(bytes))?;
+        }
         writeln!(
             f,
             "└──────────────────────────────────────────────────────────────┘"
@@ -393,6 +477,12 @@ impl Display for ExecutionExplanation {
             )?;
             writeln!(f, "│   {}", step.description)?;
             writeln!(f, "│   Cost: {}", step.cost_hint)?;
+            if let Some(n) = step.estimated_elements {
+                let bytes = step
+                    .estimated_bytes
+                    .map_or_else(String::new, |b| format!(", ~{}", format_bytes(b)));
+                writeln!(f, "│   Est: {n} elements{bytes}")?;
+            }
         }
         writeln!(f, "│")?;
         writeln!(
@@ -438,6 +528,16 @@ impl Display for ExecutionExplanation {
                             writeln!(f, "│   Removed GroupByKey barrier for efficiency")?;
                         }
                     }
+                    OptimizationDecision::LiftedGroupAggregation {
+                        combines,
+                        aggregations,
+                    } => {
+                        writeln!(f, "│ • Lifted Grouped Aggregation")?;
+                        writeln!(
+                            f,
+                            "│   {combines} combine(s) and {aggregations} map_values aggregation(s) run as partial aggregation"
+                        )?;
+                    }
                     OptimizationDecision::DroppedMidMaterialized { count } => {
                         writeln!(f, "│ • Dropped Mid-Pipeline Materialization")?;
                         writeln!(f, "│   Removed {count} unnecessary materialized node(s)")?;
@@ -524,6 +624,16 @@ impl Display for ExecutionExplanation {
                             "│   Build side: {smaller_side}; estimated probe-side reduction: {estimated_reduction_pct}%"
                         )?;
                     }
+                    OptimizationDecision::CollapsedJoinChain {
+                        joins_collapsed,
+                        inputs,
+                    } => {
+                        writeln!(f, "│ • Multi-Way Join Collapsing")?;
+                        writeln!(
+                            f,
+                            "│   Inlined {joins_collapsed} nested join(s) into one {inputs}-way co-group"
+                        )?;
+                    }
                     OptimizationDecision::AdaptivePartitionCount { barrier_count } => {
                         writeln!(f, "│ • Adaptive Inter-Stage Partition Count")?;
                         writeln!(
@@ -531,6 +641,17 @@ impl Display for ExecutionExplanation {
                             "│   {barrier_count} barrier stage(s) will rescale partition count by cardinality ratio"
                         )?;
                     }
+                    OptimizationDecision::HoistedKeyFilters { filters } => {
+                        writeln!(f, "│ • Key Filter Hoisting")?;
+                        writeln!(
+                            f,
+                            "│   {filters} key-only filter(s) moved ahead of costlier key-preserving ops"
+                        )?;
+                    }
+                    OptimizationDecision::DroppedNoOps { count } => {
+                        writeln!(f, "│ • No-Op Elimination")?;
+                        writeln!(f, "│   Removed {count} pass-through op(s)")?;
+                    }
                     OptimizationDecision::EmptySourceShortCircuit => {
                         writeln!(f, "│ • Empty Source Short-Circuit")?;
                         writeln!(
@@ -582,6 +703,10 @@ pub struct ExplainStep {
     /// `" + "` in chain order.  Anonymous fused nodes do not appear, so
     /// `"A + C"` is a valid label for a fused chain `[A, anon, C]`.
     pub name: Option<String>,
+    /// Estimated number of elements this step produces (see [`Plan::explain_estimated`]).
+    pub estimated_elements: Option<usize>,
+    /// Estimated bytes this step holds in memory (see [`Plan::explain_estimated`]).
+    pub estimated_bytes: Option<usize>,
 }
 
 /// Cost estimates for the execution plan.
@@ -595,9 +720,58 @@ pub struct CostEstimate {
     pub stateless_ops: usize,
     /// Estimated source size hint.
     pub source_size: Option<usize>,
+    /// Estimated number of elements produced by the last step.
+    ///
+    /// Only filled in by [`DirectRunner::dry_run`](crate::DirectRunner::dry_run); `None` from
+    /// [`Plan::explain`] or when a source cannot report its length.
+    pub estimated_output_elements: Option<usize>,
+    /// Estimated memory high-water mark across all steps, in bytes.
+    ///
+    /// Only filled in by [`DirectRunner::dry_run`](crate::DirectRunner::dry_run); `None` from
+    /// [`Plan::explain`] or when a source cannot report its size.
+    pub estimated_peak_bytes: Option<usize>,
 }
 
 impl Plan {
+    /// User-supplied label for chain entry `idx`.
+    ///
+    /// Joins the contributing origin nodes' names (in chain order) with `" + "`.
+    /// Returns `None` when the entry's origin slot lies outside
+    /// [`Plan::chain_origin_ids`] (defensive guard for hand-constructed plans) or when
+    /// no contributing node has been named.
+    pub(crate) fn step_name(&self, idx: usize) -> Option<String> {
+        self.chain_origin_ids.get(idx).and_then(|ids| {
+            let parts: Vec<&str> = ids
+                .iter()
+                .filter_map(|id| self.node_names.get(id).map(String::as_str))
+                .collect();
+            if parts.is_empty() {
+                None
+            } else {
+                Some(parts.join(" + "))
+            }
+        })
+    }
+
+    /// The coder attached to the output of each chain entry: the coder of the entry's
+    /// last origin node, if it has one.
+    #[cfg(feature = "coders")]
+    #[cfg_attr(
+        not(any(feature = "checkpointing", feature = "cluster")),
+        allow(dead_code)
+    )]
+    pub(crate) fn step_coders(&self, p: &Pipeline) -> Vec<Option<Arc<dyn ElementCoder>>> {
+        let all = p.snapshot_coders();
+        (0..self.chain.len())
+            .map(|idx| {
+                self.chain_origin_ids
+                    .get(idx)
+                    .and_then(|ids| ids.last())
+                    .and_then(|id| all.get(id).cloned())
+            })
+            .collect()
+    }
+
     /// Generate a detailed explanation of the execution plan.
     ///
     /// Returns an [`ExecutionExplanation`] containing:
@@ -675,12 +849,21 @@ impl Plan {
                         120,
                     )
                 }
-                Node::CoGroup { .. } => {
+                Node::CoGroup { kind, .. } => {
+                    barriers += 1;
+                    total_ops += 1;
+                    let description = match kind {
+                        CoGroupKind::Keyed => "Co-group two collections (BARRIER)",
+                        CoGroupKind::Cross { .. } => "Cross join two collections (BARRIER)",
+                    };
+                    ("CoGroup", description.to_string(), true, 150)
+                }
+                Node::CoGroupN { chains, .. } => {
                     barriers += 1;
                     total_ops += 1;
                     (
-                        "CoGroup",
-                        "Co-group two collections (BARRIER)".to_string(),
+                        "CoGroupN",
+                        format!("Co-group {} collections (BARRIER)", chains.len()),
                         true,
                         150,
                     )
@@ -710,35 +893,38 @@ impl Plan {
                     total_ops += 1;
                     ("Materialized", "Materialize results".to_string(), false, 1)
                 }
-                Node::Reshuffle { .. } => {
+                Node::Reshuffle {
+                    target_partitions, ..
+                } => {
+                    barriers += 1;
+                    total_ops += 1;
+                    let description = target_partitions.map_or_else(
+                        || {
+                            "Collect all partitions and redistribute elements evenly (BARRIER)"
+                                .to_string()
+                        },
+                        |n| format!("Collect all partitions and repartition into {n} (BARRIER)"),
+                    );
+                    ("Reshuffle", description, true, 100)
+                }
+                Node::Sort { .. } => {
                     barriers += 1;
                     total_ops += 1;
                     (
-                        "Reshuffle",
-                        "Collect all partitions and redistribute elements evenly (BARRIER)"
+                        "Sort",
+                        "Range-partition by sampled split points and sort each range (BARRIER)"
                             .to_string(),
                         true,
-                        100,
+                        110,
                     )
                 }
             };
 
-            // Build a per-step name by joining the contributing origin nodes'
-            // user-supplied labels (in chain order) with " + ". When the
-            // chain entry's origin slot lies outside `chain_origin_ids`
-            // (defensive guard for hand-constructed plans), or when no
-            // contributing node has been named, leave `name` as `None`.
-            let name = self.chain_origin_ids.get(idx).and_then(|ids| {
-                let parts: Vec<&str> = ids
-                    .iter()
-                    .filter_map(|id| self.node_names.get(id).map(String::as_str))
-                    .collect();
-                if parts.is_empty() {
-                    None
-                } else {
-                    Some(parts.join(" + "))
-                }
-            });
+            let name = self.step_name(idx);
+            let description = match self.stage_partitions.get(idx).copied().flatten() {
+                Some(parts) => format!("{description} [partitions={parts}]"),
+                None => description,
+            };
 
             steps.push(ExplainStep {
                 step: idx + 1,
@@ -747,6 +933,8 @@ impl Plan {
                 is_barrier,
                 cost_hint: cost,
                 name,
+                estimated_elements: None,
+                estimated_bytes: None,
             });
         }
 
@@ -757,37 +945,239 @@ impl Plan {
                 total_ops,
                 stateless_ops,
                 source_size,
+                estimated_output_elements: None,
+                estimated_peak_bytes: None,
             },
             optimizations: self.optimizations.clone(),
             suggested_partitions: self.suggested_partitions,
             node_names: self.node_names.clone(),
         }
     }
+
+    /// Like [`Plan::explain`], but also estimates how many elements each step produces
+    /// and how much memory it holds, without running any user code.
+    ///
+    /// Estimates start from each source's [`VecOps::len`]
+    /// and [`VecOps::byte_size_hint`] (shard
+    /// metadata for file-backed sources) and are carried down the chain:
+    /// - stateless runs scale by each op's `cardinality_multiplier_hint` and are capped
+    ///   by `take(n)`;
+    /// - `GroupByKey`, `CombineValues`, `Reshuffle`, and `Sort` keep their input size
+    ///   (an upper bound for the keyed barriers);
+    /// - `CombineGlobal` yields one element;
+    /// - `Flatten` and the co-groups add up the estimates of their input subplans.
+    ///
+    /// Bytes per element stay those of the source, so the memory figures are a rough
+    /// guide rather than a measurement. A step whose inputs cannot be sized (for
+    /// example, a cached collection that has not been computed yet) reports `None`.
+    #[must_use]
+    pub fn explain_estimated(&self) -> ExecutionExplanation {
+        let mut explanation = self.explain();
+        let sizes = estimate_chain_sizes(&self.chain);
+        for (step, size) in explanation.steps.iter_mut().zip(&sizes) {
+            step.estimated_elements = size.elements_usize();
+            step.estimated_bytes = size.bytes_usize();
+        }
+        explanation.cost_estimate.estimated_output_elements =
+            sizes.last().and_then(SizeEstimate::elements_usize);
+        explanation.cost_estimate.estimated_peak_bytes = sizes
+            .iter()
+            .map(SizeEstimate::bytes_usize)
+            .collect::<Option<Vec<_>>>()
+            .and_then(|bytes| bytes.into_iter().max());
+        explanation
+    }
+}
+
+/// Estimated element count and in-memory size of one step's output.
+#[derive(Clone, Copy, Default)]
+struct SizeEstimate {
+    elements: Option<f64>,
+    bytes: Option<f64>,
+}
+
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss,
+    clippy::cast_possible_truncation
+)]
+impl SizeEstimate {
+    fn of_source(payload: &(dyn Any + Send + Sync), vec_ops: &dyn VecOps) -> Self {
+        Self {
+            elements: vec_ops.len(payload).map(|n| n as f64),
+            bytes: vec_ops.byte_size_hint(payload).map(|b| b as f64),
+        }
+    }
+
+    fn scaled(self, factor: f64) -> Self {
+        Self {
+            elements: self.elements.map(|n| n * factor),
+            bytes: self.bytes.map(|b| b * factor),
+        }
+    }
+
+    fn capped(self, limit: usize) -> Self {
+        let limit = limit as f64;
+        match self.elements {
+            Some(n) if n > limit => self.scaled(limit / n),
+            Some(_) => self,
+            None => Self {
+                elements: Some(limit),
+                bytes: None,
+            },
+        }
+    }
+
+    fn sum(self, other: Self) -> Self {
+        Self {
+            elements: self.elements.zip(other.elements).map(|(a, b)| a + b),
+            bytes: self.bytes.zip(other.bytes).map(|(a, b)| a + b),
+        }
+    }
+
+    /// Every pairing of `self` with `other`: each output element holds one of each.
+    fn product(self, other: Self) -> Self {
+        let per_element = |size: Self| {
+            size.elements
+                .zip(size.bytes)
+                .map(|(n, b)| if n > 0.0 { b / n } else { 0.0 })
+        };
+        let elements = self.elements.zip(other.elements).map(|(a, b)| a * b);
+        Self {
+            elements,
+            bytes: elements
+                .zip(per_element(self))
+                .zip(per_element(other))
+                .map(|((n, a), b)| n * (a + b)),
+        }
+    }
+
+    fn elements_usize(&self) -> Option<usize> {
+        self.elements.map(|n| n.round() as usize)
+    }
+
+    fn bytes_usize(&self) -> Option<usize> {
+        self.bytes.map(|b| b.round() as usize)
+    }
+}
+
+/// Estimate the output size of every step of `chain` (see [`Plan::explain_estimated`]).
+fn estimate_chain_sizes(chain: &[Node]) -> Vec<SizeEstimate> {
+    let output_of = |sub: &[Node]| {
+        estimate_chain_sizes(sub)
+            .last()
+            .copied()
+            .unwrap_or_default()
+    };
+    let total_of = |subs: &[Vec<Node>]| {
+        subs.iter()
+            .map(|sub| output_of(sub))
+            .reduce(SizeEstimate::sum)
+            .unwrap_or_default()
+    };
+
+    let mut out = Vec::with_capacity(chain.len());
+    let mut current = SizeEstimate::default();
+    for node in chain {
+        current = match node {
+            Node::Source {
+                payload, vec_ops, ..
+            } => SizeEstimate::of_source(payload.as_ref(), vec_ops.as_ref()),
+            Node::Stateless(ops) => ops.iter().fold(current, |size, op| {
+                let size = size.scaled(op.cardinality_multiplier_hint());
+                op.limit_n().map_or(size, |n| size.capped(n))
+            }),
+            Node::CombineGlobal { .. } => SizeEstimate {
+                elements: Some(1.0),
+                bytes: current
+                    .elements
+                    .zip(current.bytes)
+                    .map(|(n, b)| if n > 0.0 { b / n } else { 0.0 }),
+            },
+            Node::CoGroup {
+                left_chain,
+                right_chain,
+                kind: CoGroupKind::Keyed,
+                ..
+            } => output_of(left_chain).sum(output_of(right_chain)),
+            Node::CoGroup {
+                left_chain,
+                right_chain,
+                kind: CoGroupKind::Cross { .. },
+                ..
+            } => output_of(left_chain).product(output_of(right_chain)),
+            Node::CoGroupN { chains, .. } | Node::Flatten { chains, .. } => total_of(chains),
+            Node::Materialized(_) if out.is_empty() => SizeEstimate::default(),
+            Node::GroupByKey { .. }
+            | Node::CombineValues { .. }
+            | Node::Reshuffle { .. }
+            | Node::Sort { .. }
+            | Node::Materialized(_) => current,
+        };
+        out.push(current);
+    }
+    out
+}
+
+/// Render a byte count with a binary unit suffix, e.g. `"1.5 MiB"`.
+#[allow(clippy::cast_precision_loss)]
+fn format_bytes(bytes: usize) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
 }
 
 /// Build a linear plan from `terminal`, apply optimizer passes, and produce
 /// a partitioning hint.
 ///
+/// Planning starts with the pre-flight checks of [`crate::validation::preflight`] over
+/// the nodes feeding `terminal`; their warnings are kept on [`Plan::diagnostics`].
+///
 /// The pass order is intentional:
 /// 0) dead subtree elimination (pre-pass before chain extraction — operates on the raw graph)
 /// 1) backwalk graph -> chain
 /// 2) fuse stateless
-/// 3) `CoGroup` input reordering — sort Flatten subchains by estimated cardinality ascending
-/// 4) predicate pushdown before shuffle barriers — `GroupByKey` and `Reshuffle` — (requires fused
+/// 3) lift hinted grouped aggregations (`combine_values` / `map_values` right after a GBK;
+///    runs before any pass can reorder the ops that follow the GBK)
+/// 4) collapse join chains — inline nested joins into a single `CoGroupN`
+/// 5) `CoGroup` input reordering — sort Flatten subchains by estimated cardinality ascending
+/// 6) drop no-op ops and the stateless stages they leave empty
+/// 7) hoist key-only filters ahead of costlier key-preserving ops (requires fused blocks)
+/// 8) predicate pushdown before shuffle barriers — `GroupByKey` and `Reshuffle` — (requires fused
 ///    blocks; may split one Stateless into two)
-/// 5) predicate pushdown into Flatten subplans (clones qualifying ops into each subplan tail)
-/// 6) reorder value-only ops (works on the blocks produced by steps 4–5)
-/// 7) lift GBK->Combine (structure-changing; GBK must still be present)
-/// 8) eliminate redundant Reshuffle (runs after lift so lifted `CombineValues` is visible as a target)
-/// 9) drop mid-materialized (cleanup)
+/// 9) predicate pushdown into Flatten subplans (clones qualifying ops into each subplan tail)
+/// 10) reorder value-only ops (works on the blocks produced by steps 8–9)
+/// 11) lift GBK->Combine (structure-changing; GBK must still be present)
+/// 12) eliminate redundant Reshuffle (runs after lift so lifted `CombineValues` is visible as a target)
+/// 13) drop mid-materialized (cleanup)
 ///
 /// # Errors
 ///
-/// If any of the optimizer passes fail, or the pipeline is in an inconsistent state.
+/// If pre-flight validation reports an error (e.g., a transform with no source), any
+/// of the optimizer passes fail, or the pipeline is in an inconsistent state.
 #[allow(clippy::too_many_lines)]
 pub fn build_plan(p: &Pipeline, terminal: NodeId) -> Result<Plan> {
     let (nodes, edges) = p.snapshot();
 
+    // Pre-flight validation of the subgraph feeding `terminal`: errors abort
+    // planning, warnings travel with the plan.
+    let (errors, diagnostics): (Vec<_>, Vec<_>) = preflight(p, &nodes, &edges, Some(terminal))
+        .into_iter()
+        .partition(Diagnostic::is_error);
+    if !errors.is_empty() {
+        let report: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        bail!("pipeline validation failed:\n  {}", report.join("\n  "));
+    }
+
     let mut optimizations = Vec::new();
 
     // Pre-pass 0: dead subtree elimination — remove nodes with no forward path to terminal.
@@ -797,7 +1187,12 @@ pub fn build_plan(p: &Pipeline, terminal: NodeId) -> Result<Plan> {
     }
 
     let (mut chain, mut chain_origin_ids) = backwalk_linear(nodes, &edges, terminal)?;
+    // Before any pass rewrites the co-groups (join collapsing turns them into `CoGroupN`).
+    check_cross_join_guards(&chain)?;
     let len_hint = estimate_source_len(&chain);
+    let bytes_hint = estimate_source_bytes(&chain);
+
+    let group_lifts = liftable_group_hints(&chain, &chain_origin_ids, p.group_lifts_snapshot());
 
     let (new_chain, new_ids, fusion_opt) = fuse_stateless_tracked(chain, chain_origin_ids);
     chain = new_chain;
@@ -806,6 +1201,20 @@ pub fn build_plan(p: &Pipeline, terminal: NodeId) -> Result<Plan> {
         optimizations.push(opt);
     }
 
+    let (new_chain, new_ids, group_lift_opt) =
+        lift_grouped_aggregations_tracked(chain, chain_origin_ids, &group_lifts);
+    chain = new_chain;
+    chain_origin_ids = new_ids;
+    if let Some(opt) = group_lift_opt {
+        optimizations.push(opt);
+    }
+
+    // `collapse_join_chains_pass` rewrites join nodes in place (one chain entry
+    // in, one out), so `chain_origin_ids` is unaffected.
+    let (new_chain, collapse_opts) = collapse_join_chains_pass(chain);
+    chain = new_chain;
+    optimizations.extend(collapse_opts);
+
     // `reorder_cogroup_inputs_pass` only reorders subplans inside Flatten
     // nodes; the top-level chain length and positions are unchanged, so
     // `chain_origin_ids` flows through untouched.
@@ -813,6 +1222,21 @@ pub fn build_plan(p: &Pipeline, terminal: NodeId) -> Result<Plan> {
     chain = new_chain;
     optimizations.extend(cogroup_order_opts);
 
+    let (new_chain, new_ids, noop_opt) = drop_noop_ops_tracked(chain, chain_origin_ids);
+    chain = new_chain;
+    chain_origin_ids = new_ids;
+    if let Some(opt) = noop_opt {
+        optimizations.push(opt);
+    }
+
+    // Key filters only swap places with ops inside a Stateless block, so
+    // `chain_origin_ids` is unaffected.
+    let (new_chain, hoist_opt) = hoist_key_filters_pass(chain);
+    chain = new_chain;
+    if let Some(opt) = hoist_opt {
+        optimizations.push(opt);
+    }
+
     let (new_chain, new_ids, pushdown_opt) = push_down_before_barrier_pass(chain, chain_origin_ids);
     chain = new_chain;
     chain_origin_ids = new_ids;
@@ -910,9 +1334,12 @@ pub fn build_plan(p: &Pipeline, terminal: NodeId) -> Result<Plan> {
     let has_combine_global = chain
         .iter()
         .any(|n| matches!(n, Node::CombineGlobal { .. }));
-    let has_flatten_or_cogroup = chain
-        .iter()
-        .any(|n| matches!(n, Node::Flatten { .. } | Node::CoGroup { .. }));
+    let has_flatten_or_cogroup = chain.iter().any(|n| {
+        matches!(
+            n,
+            Node::Flatten { .. } | Node::CoGroup { .. } | Node::CoGroupN { .. }
+        )
+    });
     let is_empty = !has_combine_global && len_hint == Some(0);
     let is_singleton = !has_flatten_or_cogroup && len_hint == Some(1);
     if is_empty {
@@ -925,7 +1352,7 @@ pub fn build_plan(p: &Pipeline, terminal: NodeId) -> Result<Plan> {
     let suggested = if is_singleton {
         Some(1)
     } else {
-        suggest_partitions(len_hint)
+        suggest_partitions(len_hint, bytes_hint, &chain)
     };
     if let Some(parts) = suggested {
         optimizations.push(OptimizationDecision::PartitionSuggestion {
@@ -934,6 +1361,16 @@ pub fn build_plan(p: &Pipeline, terminal: NodeId) -> Result<Plan> {
         });
     }
 
+    let node_names = p.node_names_snapshot();
+    let (stage_partitions, ignored_hints) = resolve_partition_hints(
+        &chain,
+        &chain_origin_ids,
+        &p.partition_hints_snapshot(),
+        &node_names,
+    );
+    let mut diagnostics = diagnostics;
+    diagnostics.extend(ignored_hints);
+
     Ok(Plan {
         chain,
         suggested_partitions: suggested,
@@ -941,11 +1378,242 @@ pub fn build_plan(p: &Pipeline, terminal: NodeId) -> Result<Plan> {
         limit,
         is_empty,
         is_singleton,
-        node_names: p.node_names_snapshot(),
+        node_names,
         chain_origin_ids,
+        diagnostics,
+        stage_partitions,
     })
 }
 
+/* ---------- Cross join size guard ---------- */
+
+/// Fail when a guarded cross join in `chain`, or in any of its subplans, is estimated to
+/// produce more pairs than its `max_pairs` (see [`Plan::explain_estimated`] for how sizes
+/// are estimated). Sides whose size cannot be estimated pass.
+#[allow(clippy::cast_precision_loss)]
+fn check_cross_join_guards(chain: &[Node]) -> Result<()> {
+    for node in chain {
+        match node {
+            Node::CoGroup {
+                left_chain,
+                right_chain,
+                kind,
+                ..
+            } => {
+                check_cross_join_guards(left_chain)?;
+                check_cross_join_guards(right_chain)?;
+                let CoGroupKind::Cross {
+                    max_pairs: Some(max_pairs),
+                } = kind
+                else {
+                    continue;
+                };
+                let output_of = |sub: &[Node]| {
+                    estimate_chain_sizes(sub)
+                        .last()
+                        .and_then(SizeEstimate::elements_usize)
+                };
+                if let (Some(left), Some(right)) = (output_of(left_chain), output_of(right_chain))
+                    && left as f64 * right as f64 > *max_pairs as f64
+                {
+                    bail!(
+                        "cross join of ~{left} x ~{right} elements would produce more than \
+                         {max_pairs} pairs; call `allow_large()` on the cross join to run it anyway"
+                    );
+                }
+            }
+            Node::CoGroupN { chains, .. } | Node::Flatten { chains, .. } => {
+                for sub in chains.iter() {
+                    check_cross_join_guards(sub)?;
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/* ---------- Join chain collapsing ---------- */
+
+type CoalesceFn = Arc<dyn Fn(Vec<Partition>) -> Partition + Send + Sync>;
+type JoinExecFn = Arc<dyn Fn(Vec<Partition>) -> Partition + Send + Sync>;
+
+/// N-ary view of a join node: its input subplans, per-input coalesce closures, and
+/// an `exec` taking the coalesced inputs in subplan order.
+struct JoinParts {
+    chains: Vec<Vec<Node>>,
+    coalesce: Vec<CoalesceFn>,
+    exec: JoinExecFn,
+}
+
+/// View a [`Node::CoGroup`] or [`Node::CoGroupN`] as [`JoinParts`]. Binary co-groups
+/// are adapted by wrapping their two-argument `exec`.
+fn as_join_parts(node: &Node) -> Option<JoinParts> {
+    match node {
+        Node::CoGroup {
+            left_chain,
+            right_chain,
+            coalesce_left,
+            coalesce_right,
+            exec,
+            ..
+        } => {
+            let exec = exec.clone();
+            Some(JoinParts {
+                chains: vec![(**left_chain).clone(), (**right_chain).clone()],
+                coalesce: vec![coalesce_left.clone(), coalesce_right.clone()],
+                exec: Arc::new(move |mut inputs: Vec<Partition>| {
+                    let right = inputs.pop().expect("cogroup exec: right input");
+                    let left = inputs.pop().expect("cogroup exec: left input");
+                    exec(left, right)
+                }),
+            })
+        }
+        Node::CoGroupN {
+            chains,
+            coalesce,
+            exec,
+        } => Some(JoinParts {
+            chains: (**chains).clone(),
+            coalesce: coalesce.clone(),
+            exec: exec.clone(),
+        }),
+        _ => None,
+    }
+}
+
+/// Match a subplan of the shape `[Source, <join>, Stateless*]` — i.e. the output of a
+/// join helper, optionally followed by stateless ops — and return the join node plus
+/// the trailing ops in application order.
+fn split_nested_join(chain: &[Node]) -> Option<(&Node, Vec<Arc<dyn DynOp>>)> {
+    let [
+        Node::Source { .. },
+        join @ (Node::CoGroup { .. } | Node::CoGroupN { .. }),
+        rest @ ..,
+    ] = chain
+    else {
+        return None;
+    };
+    let mut ops = Vec::new();
+    for node in rest {
+        let Node::Stateless(block) = node else {
+            return None;
+        };
+        ops.extend(block.iter().cloned());
+    }
+    Some((join, ops))
+}
+
+/// Recursively inline nested joins found in the input subplans of `node`.
+///
+/// Returns the collapsed [`Node::CoGroupN`] together with the number of nested joins
+/// that were inlined, or `None` when `node` is not a join or has no nested join inputs.
+fn collapse_join(node: &Node) -> Option<(Node, usize)> {
+    /// How one input of the outer join is produced from the flattened input list.
+    enum Segment {
+        /// Taken verbatim from the next input.
+        Direct,
+        /// Produced by running an inlined join over the next `arity` inputs and then
+        /// replaying the stateless ops that sat between the two joins.
+        Nested {
+            arity: usize,
+            exec: JoinExecFn,
+            ops: Vec<Arc<dyn DynOp>>,
+        },
+    }
+
+    let outer = as_join_parts(node)?;
+    let mut chains = Vec::with_capacity(outer.chains.len());
+    let mut coalesce = Vec::with_capacity(outer.coalesce.len());
+    let mut segments = Vec::with_capacity(outer.chains.len());
+    let mut collapsed = 0usize;
+
+    for (chain, co) in outer.chains.into_iter().zip(outer.coalesce) {
+        let Some((inner, ops)) = split_nested_join(&chain) else {
+            chains.push(chain);
+            coalesce.push(co);
+            segments.push(Segment::Direct);
+            continue;
+        };
+        let (inner, inner_collapsed) = collapse_join(inner).unwrap_or_else(|| (inner.clone(), 0));
+        let inner = as_join_parts(&inner)?;
+        collapsed += 1 + inner_collapsed;
+        segments.push(Segment::Nested {
+            arity: inner.chains.len(),
+            exec: inner.exec,
+            ops,
+        });
+        chains.extend(inner.chains);
+        coalesce.extend(inner.coalesce);
+    }
+
+    if collapsed == 0 {
+        return None;
+    }
+
+    let outer_exec = outer.exec;
+    let exec: JoinExecFn = Arc::new(move |inputs: Vec<Partition>| {
+        let mut inputs = inputs.into_iter();
+        let mut outer_inputs = Vec::with_capacity(segments.len());
+        for segment in &segments {
+            match segment {
+                Segment::Direct => {
+                    outer_inputs.push(inputs.next().expect("collapsed join: missing input"));
+                }
+                Segment::Nested { arity, exec, ops } => {
+                    let joined = exec(inputs.by_ref().take(*arity).collect());
+                    outer_inputs.push(ops.iter().fold(joined, |acc, op| op.apply(acc)));
+                }
+            }
+        }
+        outer_exec(outer_inputs)
+    });
+
+    Some((
+        Node::CoGroupN {
+            chains: Arc::new(chains),
+            coalesce,
+            exec,
+        },
+        collapsed,
+    ))
+}
+
+/// Collapse chains of joins into single [`Node::CoGroupN`] nodes.
+///
+/// The join helpers snapshot each input as a self-contained subplan, so joining the
+/// output of another join (`a.join_inner(&b).join_inner(&c)`) nests the inner
+/// `CoGroup` inside the outer one's subplan — which the runner cannot execute, and which
+/// would otherwise materialize and re-shuffle the intermediate join result.
+///
+/// For every join node in the chain, each input subplan of the shape
+/// `[Source, <join>, Stateless*]` is inlined: the inner join's inputs are spliced into
+/// the outer node's input list, and the fused `exec` runs the inner join, replays the
+/// trailing stateless ops, and feeds the result to the outer join. Nesting is resolved
+/// recursively, so `k` chained joins become one `(k + 1)`-way co-group.
+///
+/// Subplans that put a barrier between the two joins (e.g. a `GroupByKey`) are left
+/// untouched. Emits one [`OptimizationDecision::CollapsedJoinChain`] per rewritten node.
+fn collapse_join_chains_pass(chain: Vec<Node>) -> (Vec<Node>, Vec<OptimizationDecision>) {
+    let mut decisions = Vec::new();
+    let out = chain
+        .into_iter()
+        .map(|node| match collapse_join(&node) {
+            Some((collapsed, joins_collapsed)) => {
+                if let Node::CoGroupN { chains, .. } = &collapsed {
+                    decisions.push(OptimizationDecision::CollapsedJoinChain {
+                        joins_collapsed,
+                        inputs: chains.len(),
+                    });
+                }
+                collapsed
+            }
+            None => node,
+        })
+        .collect();
+    (out, decisions)
+}
+
 /* ---------- CoGroup input reordering ---------- */
 
 /// Estimate the cardinality of a subchain by inspecting its first node.
@@ -1147,7 +1815,8 @@ fn bloom_semi_join_pass(chain: &[Node]) -> Vec<OptimizationDecision> {
 /// adaptive partition rescaling at runtime.
 ///
 /// A barrier stage is any node that collapses or reshapes partitions:
-/// `GroupByKey`, `CombineValues`, `CombineGlobal`, `Flatten`, `CoGroup`, `Reshuffle`.
+/// `GroupByKey`, `CombineValues`, `CombineGlobal`, `Flatten`, `CoGroup`, `CoGroupN`,
+/// `Reshuffle`, `Sort`.
 ///
 /// The runner uses per-barrier cardinality ratios to adaptively scale the current
 /// partition count after each such stage, keeping downstream Reshuffle splits in proportion
@@ -1163,7 +1832,9 @@ fn count_adaptive_barriers(chain: &[Node]) -> usize {
                     | Node::CombineGlobal { .. }
                     | Node::Flatten { .. }
                     | Node::CoGroup { .. }
+                    | Node::CoGroupN { .. }
                     | Node::Reshuffle { .. }
+                    | Node::Sort { .. }
             )
         })
         .count()
@@ -1309,6 +1980,87 @@ fn fuse_stateless_tracked(
     (out, out_ids, optimization)
 }
 
+/* ---------- No-op elimination ---------- */
+
+/// Remove ops whose [`DynOp::is_noop`] is true and drop `Stateless` entries left empty.
+///
+/// A dropped entry's origin ids are folded into the entry before it, so partition hints
+/// and labels attached to it are still found. The first chain entry is always a source,
+/// so there is always an entry to fold into.
+fn drop_noop_ops_tracked(
+    chain: Vec<Node>,
+    origin_ids: Vec<Vec<NodeId>>,
+) -> (Vec<Node>, Vec<Vec<NodeId>>, Option<OptimizationDecision>) {
+    let mut out = Vec::<Node>::with_capacity(chain.len());
+    let mut out_ids = Vec::<Vec<NodeId>>::with_capacity(chain.len());
+    let mut dropped = 0usize;
+
+    for (n, ids) in chain.into_iter().zip(origin_ids) {
+        let Node::Stateless(ops) = n else {
+            out.push(n);
+            out_ids.push(ids);
+            continue;
+        };
+        let before = ops.len();
+        let kept: Vec<Arc<dyn DynOp>> = ops.into_iter().filter(|op| !op.is_noop()).collect();
+        dropped += before - kept.len();
+        if kept.is_empty()
+            && before > 0
+            && let Some(prev_ids) = out_ids.last_mut()
+        {
+            prev_ids.extend(ids);
+        } else {
+            out.push(Node::Stateless(kept));
+            out_ids.push(ids);
+        }
+    }
+
+    let opt = (dropped > 0).then_some(OptimizationDecision::DroppedNoOps { count: dropped });
+    (out, out_ids, opt)
+}
+
+/* ---------- Key filter hoisting ---------- */
+
+/// Move key-only filters ahead of costlier key-preserving ops within each `Stateless`
+/// block.
+///
+/// A filter exposing a [`DynOp::key_predicate`] moves one position left whenever the op
+/// before it has a higher `cost_hint` and can rebuild the filter for its own input rows
+/// via [`DynOp::key_filter_before`]; the rebuilt filter takes the earlier position. Ops
+/// only swap places inside a block, so the chain length and `origin_ids` are unchanged.
+fn hoist_key_filters_pass(chain: Vec<Node>) -> (Vec<Node>, Option<OptimizationDecision>) {
+    let mut hoisted = 0usize;
+    let out = chain
+        .into_iter()
+        .map(|n| {
+            let Node::Stateless(mut ops) = n else {
+                return n;
+            };
+            for i in 1..ops.len() {
+                let Some(pred) = ops[i].key_predicate() else {
+                    continue;
+                };
+                let mut pos = i;
+                let mut moved = false;
+                while pos > 0 && ops[pos - 1].cost_hint() > ops[pos].cost_hint() {
+                    let Some(filter) = ops[pos - 1].key_filter_before(&pred) else {
+                        break;
+                    };
+                    ops[pos] = Arc::clone(&ops[pos - 1]);
+                    ops[pos - 1] = filter;
+                    pos -= 1;
+                    moved = true;
+                }
+                hoisted += usize::from(moved);
+            }
+            Node::Stateless(ops)
+        })
+        .collect();
+
+    let opt = (hoisted > 0).then_some(OptimizationDecision::HoistedKeyFilters { filters: hoisted });
+    (out, opt)
+}
+
 /* ---------- Predicate pushdown into Flatten subplans ---------- */
 
 /// Push `value_only + cardinality_reducing` ops from the post-`Flatten` `Stateless` block
@@ -1656,6 +2408,127 @@ fn lift_gbk_then_combine_tracked(
     (out, out_ids, optimization)
 }
 
+/* ---------- Lift hinted grouped aggregations ---------- */
+
+/// Keep the lift hints the planner can act on: `Combine` hints on `CombineValues` nodes,
+/// and `MergeOutputs` hints on single-op `Stateless` nodes, so that after fusion the
+/// hinted op is known to be the first op of its block. Runs on the unfused chain.
+fn liftable_group_hints(
+    chain: &[Node],
+    origin_ids: &[Vec<NodeId>],
+    mut hints: HashMap<NodeId, GroupLift>,
+) -> HashMap<NodeId, GroupLift> {
+    if hints.is_empty() {
+        return hints;
+    }
+    let shape: HashMap<NodeId, &Node> = origin_ids
+        .iter()
+        .zip(chain)
+        .filter_map(|(ids, n)| ids.first().map(|&id| (id, n)))
+        .collect();
+    hints.retain(|id, lift| match (lift, shape.get(id)) {
+        (GroupLift::Combine, Some(Node::CombineValues { local_groups, .. })) => {
+            local_groups.is_none()
+        }
+        (GroupLift::MergeOutputs(_), Some(Node::Stateless(ops))) => ops.len() == 1,
+        _ => false,
+    });
+    hints
+}
+
+/// Rewrite `GroupByKey` followed by a hinted aggregation into a single `CombineValues`
+/// that groups and aggregates each partition locally.
+///
+/// - `[GroupByKey, CombineValues]` with a [`GroupLift::Combine`] hint: the combine's
+///   `local_pairs` runs on the partition's own groups (the GBK's `local` then `merge`
+///   applied to that one partition); the combine's `merge` is kept.
+/// - `[GroupByKey, Stateless(op, rest…)]` whose first op carries a
+///   [`GroupLift::MergeOutputs`] hint: the op runs on the partition's own groups, and the
+///   hint's closure merges the partial `(K, O)` results. Any `rest` stays in a following
+///   `Stateless` block.
+///
+/// The surviving `CombineValues` slot takes the origin ids of the `GroupByKey` and of
+/// the lifted node, like [`lift_gbk_then_combine_tracked`].
+fn lift_grouped_aggregations_tracked(
+    chain: Vec<Node>,
+    origin_ids: Vec<Vec<NodeId>>,
+    lifts: &HashMap<NodeId, GroupLift>,
+) -> (Vec<Node>, Vec<Vec<NodeId>>, Option<OptimizationDecision>) {
+    if lifts.is_empty() || chain.len() < 2 {
+        return (chain, origin_ids, None);
+    }
+    let mut out = Vec::with_capacity(chain.len());
+    let mut out_ids = Vec::with_capacity(chain.len());
+    let mut combines = 0usize;
+    let mut aggregations = 0usize;
+    let mut i = 0usize;
+
+    while i < chain.len() {
+        let next_hint = origin_ids
+            .get(i + 1)
+            .and_then(|ids| ids.first())
+            .and_then(|id| lifts.get(id));
+        if let (Node::GroupByKey { local, merge, .. }, Some(hint)) = (&chain[i], next_hint) {
+            let group_local = Arc::clone(local);
+            let group_merge = Arc::clone(merge);
+            let groups_of = move |p: Partition| group_merge(vec![group_local(p)]);
+            let mut merged_ids = origin_ids[i].clone();
+
+            match (hint, &chain[i + 1]) {
+                (
+                    GroupLift::Combine,
+                    Node::CombineValues {
+                        local_pairs, merge, ..
+                    },
+                ) => {
+                    let combine_local = Arc::clone(local_pairs);
+                    out.push(Node::CombineValues {
+                        local_pairs: Arc::new(move |p| combine_local(groups_of(p))),
+                        local_groups: None,
+                        merge: Arc::clone(merge),
+                    });
+                    merged_ids.extend(origin_ids[i + 1].iter().copied());
+                    out_ids.push(merged_ids);
+                    combines += 1;
+                    i += 2;
+                    continue;
+                }
+                (GroupLift::MergeOutputs(merge_outputs), Node::Stateless(ops))
+                    if !ops.is_empty() =>
+                {
+                    let op = Arc::clone(&ops[0]);
+                    out.push(Node::CombineValues {
+                        local_pairs: Arc::new(move |p| op.apply(groups_of(p))),
+                        local_groups: None,
+                        merge: Arc::clone(merge_outputs),
+                    });
+                    let block_ids = &origin_ids[i + 1];
+                    merged_ids.push(block_ids[0]);
+                    out_ids.push(merged_ids);
+                    if ops.len() > 1 {
+                        out.push(Node::Stateless(ops[1..].to_vec()));
+                        out_ids.push(block_ids[1..].to_vec());
+                    }
+                    aggregations += 1;
+                    i += 2;
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        out.push(chain[i].clone());
+        out_ids.push(origin_ids[i].clone());
+        i += 1;
+    }
+
+    let opt =
+        (combines + aggregations > 0).then_some(OptimizationDecision::LiftedGroupAggregation {
+            combines,
+            aggregations,
+        });
+    (out, out_ids, opt)
+}
+
 /* ---------- Reshuffle elimination ---------- */
 
 /// Remove redundant [`Node::Reshuffle`] nodes and track the decision.
@@ -1663,7 +2536,8 @@ fn lift_gbk_then_combine_tracked(
 /// A `Reshuffle` is redundant in two cases, both of which are pure no-ops:
 ///
 /// 1. It immediately precedes a shuffle barrier — [`Node::GroupByKey`],
-///    [`Node::CombineValues`], [`Node::CoGroup`], or [`Node::Flatten`] — each of
+///    [`Node::CombineValues`], [`Node::CoGroup`], [`Node::CoGroupN`], [`Node::Flatten`], or
+///    [`Node::Sort`] — each of
 ///    which already materializes and redistributes all elements across partitions.
 ///    Reshuffling immediately before such a barrier is therefore a wasted O(N) pass.
 /// 2. It immediately precedes another [`Node::Reshuffle`]. After the first pass
@@ -1694,7 +2568,9 @@ fn eliminate_reshuffle_pass(
                 Node::GroupByKey { .. }
                     | Node::CombineValues { .. }
                     | Node::CoGroup { .. }
+                    | Node::CoGroupN { .. }
                     | Node::Flatten { .. }
+                    | Node::Sort { .. }
                     | Node::Reshuffle { .. }
             );
             if successor_absorbs {
@@ -1932,16 +2808,137 @@ fn estimate_source_len(chain: &[Node]) -> Option<usize> {
     }
 }
 
-/// Suggest a parallelism level from an optional input length hint.
-/// Heuristic target ≈ 64k rows/partition, then clamped between
-/// `[num_cpus, 8*num_cpus]`.
-fn suggest_partitions(len_hint: Option<usize>) -> Option<usize> {
-    let n = len_hint?;
-    let target_rows_per_part = 64_000usize;
-    let mut parts = n.div_ceil(target_rows_per_part);
+/// If the first node is a `Source`, ask its `VecOps` for a byte-size hint (the file
+/// size for file-backed shards). Returns `None` when not available.
+fn estimate_source_bytes(chain: &[Node]) -> Option<usize> {
+    if let Some(Node::Source {
+        payload, vec_ops, ..
+    }) = chain.first()
+    {
+        vec_ops.byte_size_hint(payload.as_ref())
+    } else {
+        None
+    }
+}
+
+/// Rows per partition aimed for by [`suggest_partitions`].
+const TARGET_ROWS_PER_PARTITION: usize = 64_000;
+
+/// Input bytes per partition aimed for by [`suggest_partitions`].
+const TARGET_BYTES_PER_PARTITION: usize = 32 * 1024 * 1024;
+
+/// Suggest a parallelism level from the source's length and byte-size hints and the
+/// barriers downstream of it.
+///
+/// Each hint gives a partition count on its own (≈ 64k rows or ≈ 32 MiB per
+/// partition) and the larger one wins, so sources of few but large elements (wide rows,
+/// big documents) still spread out. The result is clamped to `[num_cpus, ceiling]`,
+/// where the ceiling is `8*num_cpus`, or `4*num_cpus` when the chain contains a keyed
+/// barrier (`GroupByKey`, `CombineValues`, or a co-group): every partition feeding one
+/// builds its own per-key map, and those maps all have to be merged.
+fn suggest_partitions(
+    len_hint: Option<usize>,
+    bytes_hint: Option<usize>,
+    chain: &[Node],
+) -> Option<usize> {
+    if len_hint.is_none() && bytes_hint.is_none() {
+        return None;
+    }
+    let by_rows = len_hint.map_or(0, |n| n.div_ceil(TARGET_ROWS_PER_PARTITION));
+    let by_bytes = bytes_hint.map_or(0, |b| b.div_ceil(TARGET_BYTES_PER_PARTITION));
+    let keyed = chain.iter().any(|n| {
+        matches!(
+            n,
+            Node::GroupByKey { .. }
+                | Node::CombineValues { .. }
+                | Node::CoGroup { .. }
+                | Node::CoGroupN { .. }
+        )
+    });
     let hw = num_cpus::get().max(2);
-    parts = parts.clamp(hw, hw * 8);
-    Some(parts)
+    let ceiling = if keyed { hw * 4 } else { hw * 8 };
+    Some(by_rows.max(by_bytes).clamp(hw, ceiling))
+}
+
+/* ---------- Partition hints ---------- */
+
+/// Map [`PCollection::with_partitions`](crate::PCollection::with_partitions) hints onto
+/// the chain, producing [`Plan::stage_partitions`].
+///
+/// Splits (the source, `Reshuffle`, `Sort`) take their own hint, or else the hint of the
+/// first stateless entry after them that carries one. Any other barrier merges into a
+/// single partition, so a hint on it, or on a stateless entry after it, is dropped with
+/// a [`DiagnosticKind::IgnoredPartitionHint`] warning.
+fn resolve_partition_hints(
+    chain: &[Node],
+    chain_origin_ids: &[Vec<NodeId>],
+    hints: &HashMap<NodeId, usize>,
+    names: &HashMap<NodeId, String>,
+) -> (Vec<Option<usize>>, Vec<Diagnostic>) {
+    let mut stages = vec![None; chain.len()];
+    let mut warnings = Vec::new();
+    if hints.is_empty() {
+        return (stages, warnings);
+    }
+
+    let mut applied = HashSet::new();
+    let mut ignored = Vec::new();
+    // Index of the split that currently feeds the chain, or the kind of the barrier that
+    // merged it into a single partition.
+    let mut split: Result<usize, &'static str> = Err("the start of the plan");
+    for (idx, node) in chain.iter().enumerate() {
+        let own = chain_origin_ids
+            .get(idx)
+            .into_iter()
+            .flatten()
+            .find_map(|id| hints.get(id).map(|&n| (*id, n)));
+        match node {
+            Node::Source { .. } | Node::Reshuffle { .. } | Node::Sort { .. } => {
+                split = Ok(idx);
+                if let Some((id, n)) = own {
+                    stages[idx] = Some(n);
+                    applied.insert(id);
+                }
+            }
+            Node::Stateless(_) => {
+                if let Some((id, n)) = own {
+                    match split {
+                        Ok(at) => {
+                            stages[at].get_or_insert(n);
+                            applied.insert(id);
+                        }
+                        Err(merged_by) => ignored.push((id, n, merged_by)),
+                    }
+                }
+            }
+            other => {
+                split = Err(other.kind());
+                if let Some((id, n)) = own {
+                    ignored.push((id, n, other.kind()));
+                }
+            }
+        }
+    }
+
+    for (id, n, merged_by) in ignored {
+        if !applied.insert(id) {
+            continue;
+        }
+        let label = names.get(&id).map_or_else(
+            || format!("node {}", id.raw()),
+            |name| format!("`{name}` (node {})", id.raw()),
+        );
+        warnings.push(Diagnostic {
+            severity: Severity::Warning,
+            kind: DiagnosticKind::IgnoredPartitionHint,
+            node: Some(id),
+            message: format!(
+                "with_partitions({n}) on {label} is ignored: its stage runs on the single \
+                 partition produced by {merged_by}; insert repartition({n}) instead"
+            ),
+        });
+    }
+    (stages, warnings)
 }
 
 #[cfg(test)]
@@ -0,0 +1,362 @@
+//! Persistent intermediate results for incremental re-runs (feature: `result-cache`).
+//!
+//! While iterating on a pipeline over large inputs, the early stages (reading, parsing,
+//! cleaning) usually stay the same between runs while the later ones change.
+//! [`PCollection::cache_persistent`] stores a collection's elements under a
+//! [`ResultCache`] directory; a later run whose upstream has not changed loads them
+//! from disk and runs only the stages after it.
+//!
+//! # Invalidation
+//!
+//! Each stored result is keyed by a SHA-256 hash (see [`ResultCache::key`]) of:
+//!
+//! - the element type and the cache's [`salt`](ResultCache::with_salt);
+//! - the shape of the collection's upstream subgraph: node kinds, how they are
+//!   connected, and, for nodes built through a [`DoFnRegistry`](crate::spec::DoFnRegistry),
+//!   the transform name and config;
+//! - the contents of in-memory sources (such as [`from_vec`](crate::from_vec) or the
+//!   eager file readers), encoded with their coders;
+//! - the size and modification time of every declared
+//!   [input file](ResultCache::with_input) and of every existing file named by a
+//!   registered transform's config.
+//!
+//! Closures cannot be hashed, so editing the body of a `map` or `filter` does not
+//! change the key. Bump the salt (or clear the cache) after changing upstream code.
+//!
+//! # File format
+//!
+//! Results are stored as `<key>.bin`: the magic bytes `IBRC`, the format version (see
+//! [`RESULT_CACHE_FORMAT_VERSION`]) as a little-endian `u32`, a SHA-256 checksum of the
+//! body, and the postcard-encoded elements. Files are written under a temporary name
+//! and renamed into place. A file that fails validation is recomputed and overwritten.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use ironbeam::*;
+//! use ironbeam::result_cache::ResultCache;
+//! use anyhow::Result;
+//!
+//! # fn main() -> Result<()> {
+//! let cache = ResultCache::new("./.ironbeam-cache").with_input("events.jsonl");
+//!
+//! let p = Pipeline::default();
+//! let events = read_jsonl::<(String, u64)>(&p, "events.jsonl")?
+//!     .filter(|(_, n): &(String, u64)| *n > 0)
+//!     .cache_persistent(&cache); // loaded from disk on the next run
+//!
+//! let totals = events
+//!     .combine_values(Sum::<u64>::default())
+//!     .collect_par_sorted_by_key(None, None)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::helpers::cache::persisted_source;
+use crate::node::Node;
+use crate::runner::PrepareContext;
+use crate::{DirectRunner, Element, NodeId, PCollection, Pipeline};
+use anyhow::{Context, Result, anyhow, bail};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::any::type_name;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File, create_dir_all, remove_file, rename};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// Version of the result file format written by [`ResultCache`].
+///
+/// Files start with the magic bytes `IBRC` followed by this version as a little-endian
+/// `u32`. Files with any other version are recomputed.
+pub const RESULT_CACHE_FORMAT_VERSION: u32 = 1;
+
+const RESULT_MAGIC: &[u8; 4] = b"IBRC";
+
+/// A directory of persisted collection results, and what invalidates them.
+///
+/// See the [module docs](self) for how results are keyed.
+#[derive(Clone, Debug)]
+pub struct ResultCache {
+    directory: PathBuf,
+    inputs: Vec<PathBuf>,
+    salt: String,
+}
+
+impl ResultCache {
+    /// A cache storing its results in `directory` (created on first write).
+    #[must_use]
+    pub fn new(directory: impl Into<PathBuf>) -> Self {
+        Self {
+            directory: directory.into(),
+            inputs: Vec::new(),
+            salt: String::new(),
+        }
+    }
+
+    /// Also invalidate results when the size or modification time of `path` changes.
+    ///
+    /// Declare files whose contents are not otherwise part of the key: files read
+    /// inside a transform, or through a streaming source such as
+    /// [`read_jsonl_streaming`](crate::read_jsonl_streaming).
+    #[must_use]
+    pub fn with_input(mut self, path: impl Into<PathBuf>) -> Self {
+        self.inputs.push(path.into());
+        self
+    }
+
+    /// Mix `salt` into every key, e.g. a version to bump after editing upstream
+    /// closures.
+    #[must_use]
+    pub fn with_salt(mut self, salt: impl Into<String>) -> Self {
+        self.salt = salt.into();
+        self
+    }
+
+    /// The directory results are stored in.
+    #[must_use]
+    pub fn directory(&self) -> &Path {
+        &self.directory
+    }
+
+    /// The key `collection` would be stored under.
+    ///
+    /// # Errors
+    /// Returns an error if a declared input file's metadata cannot be read for a reason
+    /// other than it not existing.
+    pub fn key<T: Element>(&self, collection: &PCollection<T>) -> Result<String> {
+        self.key_for(&collection.pipeline, collection.id, type_name::<T>())
+    }
+
+    /// Delete every stored result, returning how many were removed.
+    ///
+    /// # Errors
+    /// Returns an error if the directory exists but cannot be read, or a result file
+    /// cannot be removed.
+    pub fn clear(&self) -> Result<usize> {
+        if !self.directory.exists() {
+            return Ok(0);
+        }
+        let mut removed = 0;
+        let entries = fs::read_dir(&self.directory)
+            .with_context(|| format!("read result cache {}", self.directory.display()))?;
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "bin") {
+                remove_file(&path).with_context(|| format!("remove {}", path.display()))?;
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Load the stored result for `upstream` of `p`, or run it as part of the run in
+    /// `ctx` and store the result.
+    pub(crate) fn load_or_compute<T: Element>(
+        &self,
+        p: &Pipeline,
+        upstream: NodeId,
+        ctx: &PrepareContext<'_>,
+    ) -> Result<Vec<T>> {
+        let key = self.key_for(p, upstream, type_name::<T>())?;
+        let path = self.directory.join(format!("{key}.bin"));
+        if let Ok(out) = read_result::<T>(&path) {
+            return Ok(out);
+        }
+        let out = ctx.run_collect::<T>(p, upstream)?;
+        self.write_result(&path, &out)?;
+        Ok(out)
+    }
+
+    fn key_for(&self, p: &Pipeline, upstream: NodeId, elem_type: &str) -> Result<String> {
+        let (nodes, edges) = p.snapshot();
+        let coders = p.snapshot_coders();
+        let (specs, cached_from) = {
+            let g = p.inner.lock().unwrap();
+            (g.specs.clone(), g.cached_from.clone())
+        };
+
+        // Every node `upstream` depends on, numbered by build order so unrelated
+        // nodes added elsewhere in the pipeline do not change the key.
+        let mut ancestors = HashSet::from([upstream]);
+        let mut stack = vec![upstream];
+        while let Some(cur) = stack.pop() {
+            let parents = edges
+                .iter()
+                .filter(|(_, to)| *to == cur)
+                .map(|(from, _)| *from)
+                .chain(cached_from.get(&cur).copied());
+            for parent in parents {
+                if ancestors.insert(parent) {
+                    stack.push(parent);
+                }
+            }
+        }
+        let mut ordered: Vec<NodeId> = ancestors.iter().copied().collect();
+        ordered.sort_by_key(|id| id.raw());
+        let index: HashMap<NodeId, usize> = ordered
+            .iter()
+            .enumerate()
+            .map(|(idx, id)| (*id, idx))
+            .collect();
+
+        let mut hasher = Sha256::new();
+        let mut field = |bytes: &[u8]| {
+            hasher.update((bytes.len() as u64).to_le_bytes());
+            hasher.update(bytes);
+        };
+        field(&RESULT_CACHE_FORMAT_VERSION.to_le_bytes());
+        field(elem_type.as_bytes());
+        field(self.salt.as_bytes());
+
+        let mut files = self.inputs.clone();
+        for id in &ordered {
+            let node = nodes
+                .get(id)
+                .ok_or_else(|| anyhow!("result cache: missing node {id:?}"))?;
+            field(describe(node).as_bytes());
+            if let Some(source) = cached_from.get(id) {
+                field(format!("cache of {}", index[source]).as_bytes());
+            }
+            if let Some(spec) = specs.get(id) {
+                field(spec.transform.as_bytes());
+                field(spec.config.to_string().as_bytes());
+                collect_paths(&spec.config, &mut files);
+            }
+            if let Node::Source {
+                payload, vec_ops, ..
+            } = node
+            {
+                match coders.get(id).map(|c| c.encode_partition(payload.as_ref())) {
+                    Some(Ok(bytes)) => field(&bytes),
+                    _ => field(format!("{:?}", vec_ops.len(payload.as_ref())).as_bytes()),
+                }
+            }
+        }
+        let mut links: Vec<(usize, usize)> = edges
+            .iter()
+            .filter(|(from, to)| ancestors.contains(from) && ancestors.contains(to))
+            .map(|(from, to)| (index[from], index[to]))
+            .collect();
+        links.sort_unstable();
+        field(format!("{links:?}").as_bytes());
+
+        files.sort();
+        files.dedup();
+        for file in &files {
+            field(file.to_string_lossy().as_bytes());
+            field(file_fingerprint(file)?.as_bytes());
+        }
+
+        Ok(hasher.finalize().iter().fold(String::new(), |mut s, b| {
+            use std::fmt::Write;
+            let _ = write!(s, "{b:02x}");
+            s
+        }))
+    }
+
+    fn write_result<T: Element>(&self, path: &Path, data: &[T]) -> Result<()> {
+        create_dir_all(&self.directory)
+            .with_context(|| format!("create result cache {}", self.directory.display()))?;
+        let body = postcard::to_allocvec(data).context("encode cached result")?;
+        let mut encoded = Vec::from(*RESULT_MAGIC);
+        encoded.extend_from_slice(&RESULT_CACHE_FORMAT_VERSION.to_le_bytes());
+        encoded.extend_from_slice(&Sha256::digest(&body));
+        encoded.extend_from_slice(&body);
+
+        let tmp_path = path.with_extension("tmp");
+        let mut file =
+            File::create(&tmp_path).with_context(|| format!("create {}", tmp_path.display()))?;
+        file.write_all(&encoded)
+            .with_context(|| format!("write {}", tmp_path.display()))?;
+        file.sync_all()
+            .with_context(|| format!("sync {}", tmp_path.display()))?;
+        rename(&tmp_path, path).with_context(|| format!("move {} into place", path.display()))
+    }
+}
+
+impl<T: Element> PCollection<T> {
+    /// Like [`cache`](Self::cache), but also store the result in `cache` so later runs
+    /// with an unchanged upstream load it from disk instead of recomputing it.
+    ///
+    /// See the [module docs](crate::result_cache) for what invalidates a stored result.
+    #[must_use]
+    pub fn cache_persistent(self, cache: &ResultCache) -> Self {
+        let node = persisted_source::<T>(&self.pipeline, self.id, cache.clone());
+        self.insert_cached(node)
+    }
+}
+
+/// Read and verify a result file written by [`ResultCache::write_result`].
+fn read_result<T: Element>(path: &Path) -> Result<Vec<T>> {
+    let encoded = fs::read(path)?;
+    let rest = encoded
+        .strip_prefix(RESULT_MAGIC)
+        .ok_or_else(|| anyhow!("not a result cache file"))?;
+    let (version, rest) = rest
+        .split_first_chunk::<4>()
+        .ok_or_else(|| anyhow!("result header is truncated"))?;
+    if u32::from_le_bytes(*version) != RESULT_CACHE_FORMAT_VERSION {
+        bail!("unsupported result cache format version");
+    }
+    let (checksum, body) = rest
+        .split_first_chunk::<32>()
+        .ok_or_else(|| anyhow!("result header is truncated"))?;
+    if Sha256::digest(body).as_slice() != checksum {
+        bail!("result checksum mismatch");
+    }
+    Ok(postcard::from_bytes(body)?)
+}
+
+/// The node's kind plus enough shape to tell apart nodes of the same kind.
+fn describe(node: &Node) -> String {
+    let mut out = node.kind().to_string();
+    if let Node::Stateless(ops) = node {
+        out.push_str(&format!(":{}", ops.len()));
+    }
+    for chain in node.subplans() {
+        out.push('[');
+        for step in chain {
+            out.push_str(&describe(step));
+            if let Node::Source {
+                payload, vec_ops, ..
+            } = step
+            {
+                out.push_str(&format!("={:?}", vec_ops.len(payload.as_ref())));
+            }
+            out.push(',');
+        }
+        out.push(']');
+    }
+    out
+}
+
+/// Add every string in `config` that names an existing file to `files`.
+fn collect_paths(config: &Value, files: &mut Vec<PathBuf>) {
+    match config {
+        Value::String(s) if Path::new(s).is_file() => files.push(PathBuf::from(s)),
+        Value::Array(items) => items.iter().for_each(|v| collect_paths(v, files)),
+        Value::Object(map) => map.values().for_each(|v| collect_paths(v, files)),
+        _ => {}
+    }
+}
+
+/// Size and modification time of `path`, or `"missing"`.
+fn file_fingerprint(path: &Path) -> Result<String> {
+    let meta = match fs::metadata(path) {
+        Ok(meta) => meta,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            return Ok("missing".to_string());
+        }
+        Err(err) => {
+            return Err(err).with_context(|| format!("read metadata of {}", path.display()));
+        }
+    };
+    let modified = meta
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |d| d.as_nanos());
+    Ok(format!("{}:{modified}", meta.len()))
+}
@@ -0,0 +1,210 @@
+//! A dynamically typed record.
+//!
+//! [`Row`] is an ordered list of named [`serde_json::Value`] columns. It is the element
+//! type for transforms that work on fields by name rather than through a Rust struct,
+//! such as the SQL frontend ([`Pipeline::sql`](crate::Pipeline::sql), feature `sql`).
+//!
+//! Any `Serialize` value converts to a row with [`Row::from_serialize`], and a row
+//! converts back to a typed value with [`Row::deserialize`]:
+//!
+//! ```
+//! use ironbeam::Row;
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize, Debug, PartialEq)]
+//! struct Sale { region: String, amount: u64 }
+//!
+//! let row = Row::from_serialize(&Sale { region: "eu".into(), amount: 3 }).unwrap();
+//! assert_eq!(row.get("amount"), Some(&serde_json::json!(3)));
+//! let back: Sale = row.deserialize().unwrap();
+//! assert_eq!(back, Sale { region: "eu".into(), amount: 3 });
+//! ```
+
+use std::fmt;
+
+use anyhow::{Context, Result};
+use serde::de::{DeserializeOwned, MapAccess, Visitor};
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::{Map, Value};
+
+/// An ordered list of named, dynamically typed columns.
+///
+/// Column names are unique; [`insert`](Row::insert) replaces an existing column in place.
+///
+/// A row serializes as a map of column name to value in self-describing formats such
+/// as JSON, and as that map's JSON text in binary formats such as the postcard
+/// element coders, which cannot decode a [`Value`] directly.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Row {
+    columns: Vec<(String, Value)>,
+}
+
+impl Row {
+    /// An empty row.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Convert a serializable value to a row.
+    ///
+    /// - A struct or map becomes one column per field, in field-name order.
+    /// - A 2-tuple `(K, V)` becomes a `key` column plus the columns of `V` when `V` is
+    ///   a struct or map, or a single `value` column otherwise.
+    /// - Anything else becomes a single `value` column.
+    ///
+    /// # Errors
+    /// Returns an error if `value` cannot be serialized to JSON.
+    pub fn from_serialize<T: Serialize>(value: &T) -> Result<Self> {
+        let json = serde_json::to_value(value).context("convert value to a row")?;
+        Ok(match json {
+            Value::Object(fields) => fields.into_iter().collect(),
+            Value::Array(mut pair) if pair.len() == 2 => {
+                let v = pair.pop().unwrap_or(Value::Null);
+                let k = pair.pop().unwrap_or(Value::Null);
+                let mut row = Self::new();
+                row.insert("key", k);
+                match v {
+                    Value::Object(fields) => row.extend(fields),
+                    v => row.insert("value", v),
+                }
+                row
+            }
+            other => std::iter::once(("value".to_string(), other)).collect(),
+        })
+    }
+
+    /// Convert the row to a typed value, reading it as a JSON object.
+    ///
+    /// # Errors
+    /// Returns an error if the columns do not match `T`.
+    pub fn deserialize<T: DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_value(self.clone().into_value()).context("convert row to a value")
+    }
+
+    /// The value of column `name`, if present.
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.columns.iter().find(|(n, _)| n == name).map(|(_, v)| v)
+    }
+
+    /// Set column `name`, replacing its value if it already exists.
+    pub fn insert(&mut self, name: impl Into<String>, value: Value) {
+        let name = name.into();
+        match self.columns.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, v)) => *v = value,
+            None => self.columns.push((name, value)),
+        }
+    }
+
+    /// The columns, in order.
+    pub fn columns(&self) -> impl Iterator<Item = (&str, &Value)> {
+        self.columns.iter().map(|(n, v)| (n.as_str(), v))
+    }
+
+    /// Number of columns.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// True if the row has no columns.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty()
+    }
+
+    /// The row as a JSON object.
+    #[must_use]
+    pub fn into_value(self) -> Value {
+        Value::Object(self.columns.into_iter().collect::<Map<_, _>>())
+    }
+}
+
+impl<S: Into<String>> FromIterator<(S, Value)> for Row {
+    fn from_iter<I: IntoIterator<Item = (S, Value)>>(iter: I) -> Self {
+        let mut row = Self::new();
+        row.extend(iter);
+        row
+    }
+}
+
+impl<S: Into<String>> Extend<(S, Value)> for Row {
+    fn extend<I: IntoIterator<Item = (S, Value)>>(&mut self, iter: I) {
+        for (name, value) in iter {
+            self.insert(name, value);
+        }
+    }
+}
+
+impl Serialize for Row {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if !serializer.is_human_readable() {
+            let text = serde_json::to_string(self).map_err(serde::ser::Error::custom)?;
+            return serializer.serialize_str(&text);
+        }
+        let mut map = serializer.serialize_map(Some(self.columns.len()))?;
+        for (name, value) in &self.columns {
+            map.serialize_entry(name, value)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Row {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RowVisitor;
+
+        impl<'de> Visitor<'de> for RowVisitor {
+            type Value = Row;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a map of column names to values")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut access: A) -> Result<Row, A::Error> {
+                let mut row = Row::new();
+                while let Some((name, value)) = access.next_entry::<String, Value>()? {
+                    row.insert(name, value);
+                }
+                Ok(row)
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_map(RowVisitor)
+        } else {
+            let text = String::deserialize(deserializer)?;
+            serde_json::from_str(&text).map_err(serde::de::Error::custom)
+        }
+    }
+}
+
+/// Serde adapter (`#[serde(with = "...")]`) for fields holding [`Value`]s: the value
+/// itself in self-describing formats, its JSON text in binary ones.
+pub(crate) mod json_text {
+    use serde::de::DeserializeOwned;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<T: Serialize, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            return value.serialize(serializer);
+        }
+        let text = serde_json::to_string(value).map_err(serde::ser::Error::custom)?;
+        serializer.serialize_str(&text)
+    }
+
+    pub fn deserialize<'de, T: DeserializeOwned, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        if deserializer.is_human_readable() {
+            return T::deserialize(deserializer);
+        }
+        let text = String::deserialize(deserializer)?;
+        serde_json::from_str(&text).map_err(serde::de::Error::custom)
+    }
+}
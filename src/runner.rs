@@ -1,6 +1,6 @@
 //! Execution engine.
 //!
-//! The `Runner` executes an optimized, linearized plan produced by the planner.
+//! The [`DirectRunner`] executes an optimized, linearized plan produced by the planner.
 //! It supports both **sequential** and **parallel** execution modes:
 //!
 //! - **Sequential** walks the node chain in a single thread, materializing one
@@ -12,27 +12,55 @@
 //! order. Parallel execution may interleave partitions; callers that require a
 //! stable final order can use the `collect_*_sorted` helpers after the collection
 //! is complete.
+//!
+//! # Tracing
+//!
+//! With the `otel` feature, every [`DirectRunner::run_collect`] runs inside an `ironbeam.run`
+//! [`tracing`](https://docs.rs/tracing) span (fields `terminal`, `mode`, `steps`), and
+//! every step of the optimized plan inside an `ironbeam.stage` child span with fields
+//! `step`, `kind`, `name`, `partitions_in`, `partitions_out`, and `duration_us`. Install
+//! a subscriber such as `tracing-opentelemetry` to ship them to Jaeger, Tempo, or any
+//! other OpenTelemetry backend. Like per-transform metrics, spans cover the main chain:
+//! the input subplans of a join run inside the join's span, and a fused parallel block
+//! of stateless steps gets a single span.
 
 use crate::NodeId;
+use crate::cancel::{CancelCheck, CancellationToken};
+use crate::collection::Element;
+use crate::error::{IronbeamError, StepFailure, StepRef};
+use crate::extensions::{Sink, SinkDriver};
+use crate::memory::PartitionSizer;
+use crate::node::DynOp;
 use crate::node::Node;
 use crate::pipeline::Pipeline;
-use crate::planner::{build_plan, find_cache_node_via_dominators};
-use crate::type_token::{Partition, TypeTag, vec_ops_for};
+use crate::pipeline_result::{PipelineResult, StageSummary};
+use crate::planner::{ExecutionExplanation, Plan, build_plan, find_cache_node_via_dominators};
+use crate::type_token::{LazyPartition, Partition, TypeTag, vec_ops_for};
 use anyhow::{Result, anyhow, bail};
 use ordered_float::NotNan;
 use rayon::ThreadPoolBuilder;
 use rayon::prelude::*;
 use std::any::Any;
 use std::collections::{BinaryHeap, HashMap};
+use std::panic::{AssertUnwindSafe, catch_unwind, resume_unwind};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "spilling")]
+use std::path::{Path, PathBuf};
 
 #[cfg(feature = "checkpointing")]
-use crate::checkpoint::CheckpointConfig;
+use crate::checkpoint::{CheckpointConfig, CheckpointRun};
+
+#[cfg(feature = "metrics")]
+use crate::metrics::{ElemStats, MetricsCollector, TransformMetrics};
+#[cfg(any(feature = "metrics", feature = "otel"))]
+use std::cell::Cell;
 
 /// A shared cache for Common Subexpression Elimination (CSE).
 ///
 /// Maps a [`NodeId`] to the type-erased `Vec<T>` result materialized at that node.
-/// Pass the **same** `SharedCSECache` to multiple [`Runner::run_collect_cached`] calls
+/// Pass the **same** `SharedCSECache` to multiple [`DirectRunner::run_collect_cached`] calls
 /// that share a common pipeline prefix, so the shared work executes only once.
 ///
 /// The cache is correct for the lifetime of the owning [`Pipeline`]: pipelines are
@@ -41,21 +69,29 @@ use crate::checkpoint::CheckpointConfig;
 /// # Type invariant
 ///
 /// Each entry stores `Arc<Vec<T>>` for the *exact* `T` produced by that node.
-/// [`Runner::run_collect_cached`] downcasts on retrieval; a mismatch returns an error.
+/// [`DirectRunner::run_collect_cached`] downcasts on retrieval; a mismatch returns an error.
 pub type SharedCSECache = Arc<Mutex<HashMap<NodeId, Arc<dyn Any + Send + Sync>>>>;
 
 /// Execution mode for a plan.
 ///
 /// - `Sequential` runs in a single thread.
+/// - `Streaming` runs in a single thread like `Sequential`, but streams elements
+///   through each fused run of stateless ops instead of materializing a full
+///   partition after every op (see [`LazyPartition`]). Partitions are still
+///   materialized at barriers and at the terminal.
 /// - `Parallel` runs with optional thread count and partition count hints.
-///   If `threads` is `Some(n)`, a global rayon thread pool with `n` threads
-///   is installed for this process (first one wins; later calls are no-ops).
+///   If `threads` is `Some(n)`, the run executes on its own rayon thread pool
+///   with `n` threads, built for the run and dropped when it ends; otherwise it
+///   uses the current pool (rayon's global pool unless the caller is already
+///   inside another pool).
 ///   If `partitions` is `None`, the planner's suggestion (if any) is used,
-///   otherwise `Runner::default_partitions`.
+///   otherwise `DirectRunner::default_partitions`.
 #[derive(Clone, Copy, Debug)]
 pub enum ExecMode {
     /// Single-threaded execution.
     Sequential,
+    /// Single-threaded execution that streams elements through stateless runs.
+    Streaming,
     /// Parallel execution using rayon.
     Parallel {
         /// Optional rayon worker thread count.
@@ -65,12 +101,58 @@ pub enum ExecMode {
     },
 }
 
-/// Executes a pipeline produced by the builder API.
+/// An execution backend: turns a pipeline and a terminal node into results.
 ///
-/// Construct a `Runner` and call [`Runner::run_collect`] with a pipeline and
+/// [`DirectRunner`] is the in-process sequential/parallel engine. Other crates can
+/// implement this trait to run pipelines elsewhere (a worker pool, a cluster, a
+/// managed service) without forking ironbeam; they typically plan the pipeline with
+/// [`build_plan`] and ship elements using the per-node coders from
+/// [`Pipeline::snapshot_coders`](crate::Pipeline::snapshot_coders) (with the `coders`
+/// feature). [`PCollection::collect_with`](crate::PCollection::collect_with) and
+/// [`PCollection::write_to_with`](crate::PCollection::write_to_with) run a collection on
+/// any backend.
+pub trait PipelineRunner {
+    /// Execute the pipeline ending at `terminal` and collect its output.
+    ///
+    /// # Errors
+    /// Returns an error if planning or execution fails, or if the terminal does not
+    /// produce `T`.
+    fn run_collect<T: Element>(&self, p: &Pipeline, terminal: NodeId) -> Result<Vec<T>>;
+
+    /// Execute the pipeline ending at `terminal` and drive `sink` through its lifecycle
+    /// (see [`Sink`]), returning the number of elements written.
+    ///
+    /// The default collects with [`run_collect`](Self::run_collect) and writes the output
+    /// from the calling thread as a single partition; backends that can write each
+    /// partition from the worker producing it should override it, as [`DirectRunner`]
+    /// does.
+    ///
+    /// # Errors
+    /// Returns an error if execution fails or the sink reports one. The sink is closed
+    /// in either case.
+    fn run_to_sink<T: Element, S: Sink<T>>(
+        &self,
+        p: &Pipeline,
+        terminal: NodeId,
+        sink: S,
+    ) -> Result<usize> {
+        let driver = SinkDriver::new(sink);
+        let run = self
+            .run_collect::<T>(p, terminal)
+            .map(|data| driver.write(0, 1, &data));
+        driver.finish(run)
+    }
+}
+
+/// The in-process execution engine, running a pipeline sequentially or in parallel on
+/// the current machine.
+///
+/// Construct a `DirectRunner` and call [`DirectRunner::run_collect`] with a pipeline and
 /// terminal node id. See `helpers` for higher-level `collect_*` convenience
-/// methods that build a `Runner` for you.
-pub struct Runner {
+/// methods that build one for you, and [`RunnerConfig::builder`] for configuring one
+/// without naming feature-gated fields.
+#[derive(Clone)]
+pub struct DirectRunner {
     /// Selected execution mode.
     pub mode: ExecMode,
     /// Default partition count when neither the caller nor the planner suggests one.
@@ -78,9 +160,60 @@ pub struct Runner {
     /// Optional checkpoint configuration for fault tolerance.
     #[cfg(feature = "checkpointing")]
     pub checkpoint_config: Option<CheckpointConfig>,
+    /// Optional working-memory budget (in bytes) for group-by-key barriers.
+    ///
+    /// When set, a `GroupByKey` orders each input partition by hash bucket in its local
+    /// stage, spilling the partition to disk once the partitions kept in memory would
+    /// exceed the budget, and groups a run of buckets at a time instead of building one
+    /// in-memory map (see [`crate::spill_group`]).
+    #[cfg(feature = "spilling")]
+    pub memory_budget: Option<usize>,
+    /// Directory for group-by-key spill files; defaults to the system temp directory.
+    #[cfg(feature = "spilling")]
+    pub spill_dir: Option<PathBuf>,
+    /// Optional limit (in bytes) on the estimated size of any one partition.
+    ///
+    /// When set, parallel runs split the source into enough partitions to fit the
+    /// budget, group-by-key spills to disk above it (with the `spilling` feature), and a
+    /// step whose output partition is estimated above it fails the run with
+    /// [`IronbeamError::MemoryBudgetExceeded`]. See [`crate::memory`] for how sizes are
+    /// estimated.
+    pub max_memory_bytes: Option<usize>,
+    /// Optional limit on the wall-clock time of one run, counted from the start of
+    /// [`run_collect`](Self::run_collect). A run that exceeds it stops at the next
+    /// cancellation check and fails with [`IronbeamError::Cancelled`]; see
+    /// [`crate::cancel`].
+    pub timeout: Option<Duration>,
+}
+
+/// The name [`DirectRunner`] had before execution backends became pluggable.
+#[deprecated(
+    since = "4.1.0",
+    note = "use `DirectRunner`, or build a configuration with `RunnerConfig::builder()`"
+)]
+pub type Runner = DirectRunner;
+
+impl PipelineRunner for DirectRunner {
+    fn run_collect<T: Element>(&self, p: &Pipeline, terminal: NodeId) -> Result<Vec<T>> {
+        Self::run_collect(self, p, terminal)
+    }
+
+    /// Drive `sink` from the run's own output partitions: each is written, whole, by the
+    /// worker that produced it, while other partitions are still being computed.
+    /// Partitions therefore reach the sink in the order they finish, not by index. A
+    /// collection ending in [`take`](crate::PCollection::take) runs sequentially, so the
+    /// limit applies to the whole output.
+    fn run_to_sink<T: Element, S: Sink<T>>(
+        &self,
+        p: &Pipeline,
+        terminal: NodeId,
+        sink: S,
+    ) -> Result<usize> {
+        self.run_to_sink_until(p, terminal, sink, None)
+    }
 }
 
-impl Default for Runner {
+impl Default for DirectRunner {
     fn default() -> Self {
         Self {
             mode: ExecMode::Parallel {
@@ -91,11 +224,247 @@ impl Default for Runner {
             default_partitions: 2 * num_cpus::get().max(2),
             #[cfg(feature = "checkpointing")]
             checkpoint_config: None,
+            #[cfg(feature = "spilling")]
+            memory_budget: None,
+            #[cfg(feature = "spilling")]
+            spill_dir: None,
+            max_memory_bytes: None,
+            timeout: None,
+        }
+    }
+}
+
+/// A [`DirectRunner`] configuration assembled with [`RunnerConfig::builder`].
+///
+/// Unlike a `DirectRunner` struct literal, the builder keeps working as options are
+/// added, and its feature-gated options are plain methods. A `RunnerConfig` is itself a
+/// [`PipelineRunner`], so it can be passed straight to
+/// [`PCollection::collect_with`](crate::PCollection::collect_with).
+///
+/// # Example
+/// ```no_run
+/// use ironbeam::*;
+/// use std::time::Duration;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let config = RunnerConfig::builder()
+///     .threads(4)
+///     .partitions(16)
+///     .memory_budget(512 << 20)
+///     .timeout(Duration::from_secs(600))
+///     .build();
+///
+/// let p = Pipeline::default();
+/// let out = from_vec(&p, vec![1u32, 2, 3]).map(|x| x * 2).collect_with(&config)?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct RunnerConfig {
+    runner: DirectRunner,
+    #[cfg(feature = "metrics")]
+    metrics: Option<MetricsCollector>,
+    cancellation: Option<CancellationToken>,
+}
+
+impl RunnerConfig {
+    /// A builder starting from [`DirectRunner::default`].
+    pub fn builder() -> RunnerConfigBuilder {
+        RunnerConfigBuilder::default()
+    }
+
+    /// The configured runner, e.g. for [`DirectRunner::dry_run`] or
+    /// [`DirectRunner::run_with_cancellation`].
+    #[must_use]
+    pub const fn runner(&self) -> &DirectRunner {
+        &self.runner
+    }
+
+    /// Execute the pipeline ending at `terminal` with this configuration (see
+    /// [`DirectRunner::run_collect`]).
+    ///
+    /// With the `metrics` feature, a collector set with
+    /// [`RunnerConfigBuilder::metrics`] is installed on `p` first. A token set with
+    /// [`RunnerConfigBuilder::cancellation`] is checked as in
+    /// [`DirectRunner::run_with_cancellation`].
+    ///
+    /// # Errors
+    /// Returns the errors of [`DirectRunner::run_collect`].
+    pub fn run_collect<T: Element>(&self, p: &Pipeline, terminal: NodeId) -> Result<Vec<T>> {
+        self.install_metrics(p);
+        self.runner
+            .run_collect_until::<T>(p, terminal, self.cancellation.as_ref(), None)
+            .map(PipelineResult::into_data)
+    }
+
+    /// This configuration with its execution mode replaced by `mode`.
+    pub(crate) fn with_mode(mut self, mode: ExecMode) -> Self {
+        self.runner.mode = mode;
+        self
+    }
+
+    /// Install the configured metrics collector, if any, on `p`.
+    #[cfg_attr(not(feature = "metrics"), allow(unused_variables, clippy::unused_self))]
+    fn install_metrics(&self, p: &Pipeline) {
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = &self.metrics {
+            p.set_metrics(metrics.clone());
         }
     }
 }
 
-impl Runner {
+impl From<RunnerConfig> for DirectRunner {
+    fn from(config: RunnerConfig) -> Self {
+        config.runner
+    }
+}
+
+impl PipelineRunner for RunnerConfig {
+    fn run_collect<T: Element>(&self, p: &Pipeline, terminal: NodeId) -> Result<Vec<T>> {
+        Self::run_collect(self, p, terminal)
+    }
+
+    /// Drive `sink` as [`DirectRunner`] does, with this configuration's metrics and
+    /// cancellation token.
+    fn run_to_sink<T: Element, S: Sink<T>>(
+        &self,
+        p: &Pipeline,
+        terminal: NodeId,
+        sink: S,
+    ) -> Result<usize> {
+        self.install_metrics(p);
+        self.runner
+            .run_to_sink_until(p, terminal, sink, self.cancellation.as_ref())
+    }
+}
+
+/// Builder for [`RunnerConfig`]; see [`RunnerConfig::builder`].
+#[derive(Default)]
+#[must_use]
+pub struct RunnerConfigBuilder {
+    config: RunnerConfig,
+}
+
+impl RunnerConfigBuilder {
+    /// Set the execution mode (parallel by default).
+    pub const fn mode(mut self, mode: ExecMode) -> Self {
+        self.config.runner.mode = mode;
+        self
+    }
+
+    /// Use [`ExecMode::Parallel`] with `threads` rayon worker threads, switching from a
+    /// sequential or streaming mode if one was set.
+    pub const fn threads(mut self, threads: usize) -> Self {
+        self.config.runner.mode = match self.config.runner.mode {
+            ExecMode::Parallel { partitions, .. } => ExecMode::Parallel {
+                threads: Some(threads),
+                partitions,
+            },
+            ExecMode::Sequential | ExecMode::Streaming => ExecMode::Parallel {
+                threads: Some(threads),
+                partitions: None,
+            },
+        };
+        self
+    }
+
+    /// Use [`ExecMode::Parallel`] with `partitions` source partitions, switching from a
+    /// sequential or streaming mode if one was set.
+    pub const fn partitions(mut self, partitions: usize) -> Self {
+        self.config.runner.mode = match self.config.runner.mode {
+            ExecMode::Parallel { threads, .. } => ExecMode::Parallel {
+                threads,
+                partitions: Some(partitions),
+            },
+            ExecMode::Sequential | ExecMode::Streaming => ExecMode::Parallel {
+                threads: None,
+                partitions: Some(partitions),
+            },
+        };
+        self
+    }
+
+    /// Partition count used when neither the mode nor the planner suggests one (see
+    /// [`DirectRunner::default_partitions`]).
+    pub const fn default_partitions(mut self, partitions: usize) -> Self {
+        self.config.runner.default_partitions = partitions;
+        self
+    }
+
+    /// Checkpoint the run with `config` (see [`DirectRunner::checkpoint_config`]).
+    #[cfg(feature = "checkpointing")]
+    pub fn checkpointing(mut self, config: CheckpointConfig) -> Self {
+        self.config.runner.checkpoint_config = Some(config);
+        self
+    }
+
+    /// Install `metrics` on the pipeline before each run, so it collects the run's
+    /// built-in and user metrics.
+    #[cfg(feature = "metrics")]
+    pub fn metrics(mut self, metrics: MetricsCollector) -> Self {
+        self.config.metrics = Some(metrics);
+        self
+    }
+
+    /// Spill group-by-key input to disk beyond `bytes` of working memory (see
+    /// [`DirectRunner::memory_budget`]). Runs never fail for exceeding it.
+    #[cfg(feature = "spilling")]
+    pub const fn memory_budget(mut self, bytes: usize) -> Self {
+        self.config.runner.memory_budget = Some(bytes);
+        self
+    }
+
+    /// Limit the estimated size of any one partition to `bytes`, failing the run with
+    /// [`IronbeamError::MemoryBudgetExceeded`] above it (see
+    /// [`DirectRunner::max_memory_bytes`]).
+    pub const fn max_memory_bytes(mut self, bytes: usize) -> Self {
+        self.config.runner.max_memory_bytes = Some(bytes);
+        self
+    }
+
+    /// Write group-by-key spill files under `dir` instead of the system temp directory
+    /// (see [`DirectRunner::spill_dir`]).
+    #[cfg(feature = "spilling")]
+    pub fn temp_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.config.runner.spill_dir = Some(dir.into());
+        self
+    }
+
+    /// Stop runs that take longer than `timeout` (see [`DirectRunner::timeout`]).
+    pub const fn timeout(mut self, timeout: Duration) -> Self {
+        self.config.runner.timeout = Some(timeout);
+        self
+    }
+
+    /// Stop runs once `token` is cancelled (see [`DirectRunner::run_with_cancellation`]).
+    pub fn cancellation(mut self, token: CancellationToken) -> Self {
+        self.config.cancellation = Some(token);
+        self
+    }
+
+    /// Finish the configuration.
+    #[must_use]
+    pub fn build(self) -> RunnerConfig {
+        self.config
+    }
+}
+
+impl DirectRunner {
+    /// Plan the pipeline ending at `terminal` and estimate its cost without executing it.
+    ///
+    /// Runs the planner (including its pre-flight checks) but no user code: sources
+    /// are sized from their length and shard metadata, never read or prepared. The
+    /// returned [`ExecutionExplanation`] is [`Plan::explain`] with per-step element and
+    /// memory estimates filled in (see [`Plan::explain_estimated`]), which makes it a
+    /// cheap sanity check before starting a long batch job.
+    ///
+    /// # Errors
+    /// Returns an error if planning fails, e.g. when pre-flight validation reports an
+    /// error.
+    pub fn dry_run(&self, p: &Pipeline, terminal: NodeId) -> Result<ExecutionExplanation> {
+        Ok(build_plan(p, terminal)?.explain_estimated())
+    }
+
     /// Execute the pipeline ending at `terminal`, collecting the terminal
     /// vector as `Vec<T>`.
     ///
@@ -117,97 +486,275 @@ impl Runner {
         p: &Pipeline,
         terminal: NodeId,
     ) -> Result<Vec<T>> {
+        self.run_collect_until::<T>(p, terminal, None, None)
+            .map(PipelineResult::into_data)
+    }
+
+    /// Like [`run_collect`](Self::run_collect), but returns a [`PipelineResult`] holding
+    /// the collected elements together with metadata about the run: wall time,
+    /// partitions used, per-stage element counts and timings, planner warnings, and
+    /// checkpoint activity.
+    ///
+    /// # Errors
+    /// The same as [`run_collect`](Self::run_collect).
+    pub fn run_with_result<T: 'static + Send + Sync + Clone>(
+        &self,
+        p: &Pipeline,
+        terminal: NodeId,
+    ) -> Result<PipelineResult<T>> {
+        self.run_collect_until::<T>(p, terminal, None, None)
+    }
+
+    /// Like [`run_collect`](Self::run_collect), but stops early once `token` is
+    /// cancelled.
+    ///
+    /// Cancellation is cooperative: the run checks the token between plan steps and
+    /// between the stateless transforms applied to each partition (see
+    /// [`crate::cancel`]).
+    ///
+    /// # Errors
+    /// Returns [`IronbeamError::Cancelled`] if the run stopped because `token` was
+    /// cancelled or [`timeout`](Self::timeout) elapsed, and otherwise the errors of
+    /// [`run_collect`](Self::run_collect).
+    pub fn run_with_cancellation<T: 'static + Send + Sync + Clone>(
+        &self,
+        p: &Pipeline,
+        terminal: NodeId,
+        token: &CancellationToken,
+    ) -> Result<Vec<T>> {
+        self.run_collect_until::<T>(p, terminal, Some(token), None)
+            .map(PipelineResult::into_data)
+    }
+
+    /// [`PipelineRunner::run_to_sink`], checking `token` (if any) and the timeout.
+    fn run_to_sink_until<T: Element, S: Sink<T>>(
+        &self,
+        p: &Pipeline,
+        terminal: NodeId,
+        sink: S,
+        token: Option<&CancellationToken>,
+    ) -> Result<usize> {
+        let driver = SinkDriver::new(sink);
+        let tail = |partition: usize, num_partitions: usize, part: &Partition| match part
+            .downcast_ref::<Vec<T>>()
+        {
+            Some(data) => driver.write(partition, num_partitions, data),
+            None => driver.fail(terminal_mismatch::<T>().into()),
+        };
+        let run = self
+            .run_collect_until::<T>(p, terminal, token, Some(&tail))
+            .map(drop);
+        driver.finish(run)
+    }
+
+    /// [`run_with_result`](Self::run_with_result), checking `token` (if any) and the
+    /// timeout.
+    ///
+    /// With a `tail`, the terminal partitions are handed to it instead of being collected,
+    /// and the result holds no data.
+    fn run_collect_until<T: 'static + Send + Sync + Clone>(
+        &self,
+        p: &Pipeline,
+        terminal: NodeId,
+        token: Option<&CancellationToken>,
+        tail: Option<PartitionTail<'_>>,
+    ) -> Result<PipelineResult<T>> {
+        let started = Instant::now();
+        let cancel = CancelCheck::new(token, self.timeout).map(Arc::new);
+
+        #[cfg(feature = "otel")]
+        let run_span = tracing::info_span!(
+            "ironbeam.run",
+            terminal = terminal.raw(),
+            mode = ?self.mode,
+            steps = tracing::field::Empty,
+        )
+        .entered();
+
         #[cfg(feature = "metrics")]
         p.record_metrics_start();
 
-        let plan = build_plan(p, terminal)?;
+        let mut plan = build_plan(p, terminal)?;
+        let ctx = PrepareContext {
+            runner: self,
+            token,
+        };
+        if prepare_sources(&plan.chain, &ctx)? {
+            // Lazily computed sources now know their length; replan so size-based
+            // decisions (empty/singleton fast paths, partition count) can use it.
+            plan = build_plan(p, terminal)?;
+        }
+        #[cfg(feature = "otel")]
+        run_span.record("steps", plan.chain.len());
 
         // Fast-path: empty source — skip the executor entirely.
         if plan.is_empty {
             #[cfg(feature = "metrics")]
             p.record_metrics_end();
-            return Ok(Vec::new());
+            return Ok(PipelineResult {
+                data: Vec::new(),
+                wall_time: started.elapsed(),
+                mode: self.mode,
+                partitions: 0,
+                stages: Vec::new(),
+                warnings: plan.diagnostics,
+                #[cfg(feature = "checkpointing")]
+                checkpoints: None,
+            });
         }
 
         let is_singleton = plan.is_singleton;
-        let chain = plan.chain;
-        let suggested_parts = plan.suggested_partitions;
+        // A tail sees partitions as they are produced, so a limit on the whole output
+        // needs the sequential engine.
+        let mode = if tail.is_some() && plan.limit.is_some() {
+            ExecMode::Sequential
+        } else {
+            self.mode
+        };
+        #[cfg(feature = "checkpointing")]
+        let checkpoints = match &self.checkpoint_config {
+            Some(config) if config.enabled => {
+                let exec_mode = match mode {
+                    ExecMode::Sequential | ExecMode::Streaming => "sequential",
+                    ExecMode::Parallel { .. } => "parallel",
+                };
+                Some(CheckpointRun::new::<T>(
+                    config.clone(),
+                    p,
+                    &plan,
+                    exec_mode,
+                )?)
+            }
+            _ => None,
+        };
+        #[cfg(feature = "checkpointing")]
+        let checkpoint_summary = checkpoints.as_ref().map(CheckpointRun::summary);
+        let step_names: Vec<Option<String>> =
+            (0..plan.chain.len()).map(|i| plan.step_name(i)).collect();
+        let recorder =
+            StepRecorder::new(p, &plan, &step_names, self.max_memory_bytes, cancel.clone());
+        let stages = recorder.stages();
+        // Enough source partitions that each starts at about half the memory budget,
+        // leaving room for the per-partition size estimates to differ from the total's.
+        let budget_parts = self
+            .max_memory_bytes
+            .and_then(|budget| Some(source_bytes(p, &plan)?.div_ceil((budget / 2).max(1))))
+            .unwrap_or(1);
+        let chain = attribute_stages(
+            plan.chain,
+            &step_names,
+            cancel.as_ref(),
+            #[cfg(feature = "metrics")]
+            p.get_metrics(),
+        );
+        #[cfg(feature = "spilling")]
+        let chain = match self.memory_budget.or(self.max_memory_bytes) {
+            Some(budget) => {
+                let dir = self.spill_dir.clone().unwrap_or_else(std::env::temp_dir);
+                apply_memory_budget(chain, &step_names, budget, &dir)
+            }
+            None => chain,
+        };
+        let warnings = std::mem::take(&mut plan.diagnostics);
+        let stage_parts = std::mem::take(&mut plan.stage_partitions);
         let limit = plan.limit;
+        let streaming = matches!(mode, ExecMode::Streaming);
+
+        let (threads, parts) = match mode {
+            ExecMode::Parallel {
+                threads,
+                partitions,
+            } => (
+                threads,
+                partitions
+                    .or(plan.suggested_partitions)
+                    .unwrap_or(self.default_partitions)
+                    .max(budget_parts),
+            ),
+            ExecMode::Sequential | ExecMode::Streaming => (None, 1),
+        };
 
+        // Singleton sources run sequentially, unless checkpointing keeps the configured mode.
+        #[allow(unused_mut)]
+        let mut used_parts = if is_singleton { 1 } else { parts };
         #[cfg(feature = "checkpointing")]
-        let checkpoint_enabled = self.checkpoint_config.as_ref().is_some_and(|c| c.enabled);
+        if checkpoint_summary.is_some() {
+            used_parts = parts;
+        }
 
         #[cfg(feature = "checkpointing")]
-        let result = if checkpoint_enabled {
-            let config = self.checkpoint_config.as_ref().unwrap().clone();
-            match self.mode {
-                ExecMode::Sequential => exec_seq_with_checkpointing::<T>(chain, config),
-                ExecMode::Parallel {
-                    threads,
-                    partitions,
-                } => {
-                    if let Some(t) = threads {
-                        ThreadPoolBuilder::new().num_threads(t).build_global().ok();
+        let run = move || {
+            catch_stage_panics(|| {
+                if let Some(checkpoints) = checkpoints {
+                    match mode {
+                        ExecMode::Sequential | ExecMode::Streaming => {
+                            exec_seq_with_checkpointing::<T>(chain, checkpoints, &recorder, tail)
+                        }
+                        ExecMode::Parallel { .. } => exec_par_with_checkpointing::<T>(
+                            &chain,
+                            parts,
+                            &stage_parts,
+                            checkpoints,
+                            &recorder,
+                            tail,
+                        ),
                     }
-                    let parts = partitions
-                        .or(suggested_parts)
-                        .unwrap_or(self.default_partitions);
-                    exec_par_with_checkpointing::<T>(&chain, parts, config)
-                }
-            }
-        } else if is_singleton {
-            // Singleton source: force sequential to avoid partition overhead.
-            exec_seq::<T>(chain)
-        } else {
-            match self.mode {
-                ExecMode::Sequential => exec_seq::<T>(chain),
-                ExecMode::Parallel {
-                    threads,
-                    partitions,
-                } => {
-                    if let Some(t) = threads {
-                        ThreadPoolBuilder::new().num_threads(t).build_global().ok();
+                } else if is_singleton {
+                    // Singleton source: force sequential to avoid partition overhead.
+                    exec_seq::<T>(chain, streaming, &recorder, tail)
+                } else {
+                    match mode {
+                        ExecMode::Sequential | ExecMode::Streaming => {
+                            exec_seq::<T>(chain, streaming, &recorder, tail)
+                        }
+                        ExecMode::Parallel { .. } => {
+                            exec_par::<T>(&chain, parts, &stage_parts, limit, &recorder, tail)
+                        }
                     }
-                    let parts = partitions
-                        .or(suggested_parts)
-                        .unwrap_or(self.default_partitions);
-                    exec_par::<T>(&chain, parts, limit)
                 }
-            }
+            })
         };
 
         #[cfg(not(feature = "checkpointing"))]
-        let result = if is_singleton {
-            // Singleton source: force sequential to avoid partition overhead.
-            exec_seq::<T>(chain)
-        } else {
-            match self.mode {
-                ExecMode::Sequential => exec_seq::<T>(chain),
-                ExecMode::Parallel {
-                    threads,
-                    partitions,
-                } => {
-                    if let Some(t) = threads {
-                        // Best-effort: first builder to install wins globally.
-                        ThreadPoolBuilder::new().num_threads(t).build_global().ok();
+        let run = move || {
+            catch_stage_panics(|| {
+                if is_singleton {
+                    // Singleton source: force sequential to avoid partition overhead.
+                    exec_seq::<T>(chain, streaming, &recorder, tail)
+                } else {
+                    match mode {
+                        ExecMode::Sequential | ExecMode::Streaming => {
+                            exec_seq::<T>(chain, streaming, &recorder, tail)
+                        }
+                        ExecMode::Parallel { .. } => {
+                            exec_par::<T>(&chain, parts, &stage_parts, limit, &recorder, tail)
+                        }
                     }
-                    let parts = partitions
-                        .or(suggested_parts)
-                        .unwrap_or(self.default_partitions);
-                    exec_par::<T>(&chain, parts, limit)
                 }
-            }
+            })
         };
+        let data = in_thread_pool(threads, run);
 
         #[cfg(feature = "metrics")]
         p.record_metrics_end();
 
-        result
+        Ok(PipelineResult {
+            data: data?,
+            wall_time: started.elapsed(),
+            mode,
+            partitions: used_parts,
+            stages: stages
+                .map(|log| std::mem::take(&mut *log.lock().unwrap()))
+                .unwrap_or_default(),
+            warnings,
+            #[cfg(feature = "checkpointing")]
+            checkpoints: checkpoint_summary.map(|summary| summary.lock().unwrap().clone()),
+        })
     }
 
     /// Execute the pipeline ending at `terminal` with Common Subexpression Elimination.
     ///
-    /// Identical to [`Runner::run_collect`] for pipelines with no shared prefix. When
+    /// Identical to [`DirectRunner::run_collect`] for pipelines with no shared prefix. When
     /// the pipeline graph contains shared computation, this method determines the
     /// **immediate dominator** of `terminal` in the pipeline DAG — the deepest node
     /// that every source-to-terminal path passes through. It materializes the result
@@ -221,7 +768,7 @@ impl Runner {
     /// Pass the **same** `cache` instance across all calls that should share work:
     ///
     /// ```no_run
-    /// use ironbeam::{Pipeline, Runner, SharedCSECache, from_vec};
+    /// use ironbeam::{Pipeline, DirectRunner, SharedCSECache, from_vec};
     /// use anyhow::Result;
     ///
     /// # fn main() -> Result<()> {
@@ -231,7 +778,7 @@ impl Runner {
     /// let b = shared.map(|x: &u32| x + 1);
     ///
     /// let cache = SharedCSECache::default();
-    /// let runner = Runner::default();
+    /// let runner = DirectRunner::default();
     /// let out_a = runner.run_collect_cached::<u32>(&p, a.node_id(), &cache)?;
     /// let out_b = runner.run_collect_cached::<u32>(&p, b.node_id(), &cache)?;
     /// // The `+10` map ran only 3 times total, not 6.
@@ -244,11 +791,11 @@ impl Runner {
     /// The cache node (immediate dominator of `terminal`) must produce `Vec<T>`.
     /// If the shared prefix ends with a different intermediate type, the cache
     /// insertion fails and an error is returned. In that case use
-    /// [`Runner::run_collect`] directly.
+    /// [`DirectRunner::run_collect`] directly.
     ///
     /// # Errors
     ///
-    /// Same as [`Runner::run_collect`], plus a type-mismatch error if the cache
+    /// Same as [`DirectRunner::run_collect`], plus a type-mismatch error if the cache
     /// node does not produce `Vec<T>`.
     ///
     /// # Panics
@@ -293,7 +840,7 @@ impl Runner {
 /// predicate pushdown, etc.) are applied to the suffix just as they would be to
 /// a normal top-level plan.
 fn run_collect_suffix<T: 'static + Send + Sync + Clone>(
-    runner: &Runner,
+    runner: &DirectRunner,
     terminal: NodeId,
     fanout_id: NodeId,
     cached: Vec<T>,
@@ -336,12 +883,661 @@ fn run_collect_suffix<T: 'static + Send + Sync + Clone>(
     runner.run_collect::<T>(&new_p, prev_id)
 }
 
+/// Wrap the ops of every `Stateless` step in a [`StageOp`] attributed to that step.
+///
+/// `names[i]` is the user-supplied label of `chain[i]`, as rendered by
+/// [`Plan::explain`](crate::planner::Plan::explain). With `cancel`, every op checks it
+/// before running. Stateless ops inside the subplans of a join or flatten are attributed
+/// to the step they feed (without `metrics` timing). Other barriers are returned
+/// unchanged.
+fn attribute_stages(
+    chain: Vec<Node>,
+    names: &[Option<String>],
+    cancel: Option<&Arc<CancelCheck>>,
+    #[cfg(feature = "metrics")] metrics: Option<MetricsCollector>,
+) -> Vec<Node> {
+    let wrap = |step: &Arc<StepRef>, ops: Vec<Arc<dyn DynOp>>, timed: bool| -> Vec<_> {
+        #[cfg(not(feature = "metrics"))]
+        let _ = timed;
+        ops.into_iter()
+            .map(|inner| {
+                Arc::new(StageOp {
+                    step: Arc::clone(step),
+                    inner,
+                    cancel: cancel.cloned(),
+                    #[cfg(feature = "metrics")]
+                    metrics: metrics.clone().filter(|_| timed),
+                }) as Arc<dyn DynOp>
+            })
+            .collect()
+    };
+    chain
+        .into_iter()
+        .zip(names)
+        .enumerate()
+        .map(|(idx, (node, name))| {
+            let step = Arc::new(StepRef {
+                step: idx + 1,
+                kind: node.kind(),
+                name: name.clone(),
+            });
+            match node {
+                Node::Stateless(ops) => Node::Stateless(wrap(&step, ops, true)),
+                node => attribute_subplans(node, &|ops| wrap(&step, ops, false)),
+            }
+        })
+        .collect()
+}
+
+/// Rewrites the ops of one `Stateless` step, as used by [`attribute_subplans`].
+type OpWrapper<'a> = &'a dyn Fn(Vec<Arc<dyn DynOp>>) -> Vec<Arc<dyn DynOp>>;
+
+/// Rebuild a join or flatten `node` with `wrap` applied to the ops of every `Stateless`
+/// step in its subplans, recursively. Other nodes are returned unchanged.
+fn attribute_subplans(node: Node, wrap: OpWrapper<'_>) -> Node {
+    let chain = |chain: &[Node]| -> Vec<Node> {
+        chain
+            .iter()
+            .cloned()
+            .map(|node| match node {
+                Node::Stateless(ops) => Node::Stateless(wrap(ops)),
+                node => attribute_subplans(node, wrap),
+            })
+            .collect()
+    };
+    match node {
+        Node::CoGroup {
+            left_chain,
+            right_chain,
+            coalesce_left,
+            coalesce_right,
+            exec,
+            uses_bloom_semi_join,
+            kind,
+        } => Node::CoGroup {
+            left_chain: Arc::new(chain(&left_chain)),
+            right_chain: Arc::new(chain(&right_chain)),
+            coalesce_left,
+            coalesce_right,
+            exec,
+            uses_bloom_semi_join,
+            kind,
+        },
+        Node::CoGroupN {
+            chains,
+            coalesce,
+            exec,
+        } => Node::CoGroupN {
+            chains: Arc::new(chains.iter().map(|c| chain(c)).collect()),
+            coalesce,
+            exec,
+        },
+        Node::Flatten {
+            chains,
+            coalesce,
+            merge,
+        } => Node::Flatten {
+            chains: Arc::new(chains.iter().map(|c| chain(c)).collect()),
+            coalesce,
+            merge,
+        },
+        node => node,
+    }
+}
+
+/// A stateless op running as part of a specific plan step.
+///
+/// A panic inside the op is re-raised with an [`IronbeamError::PanicInTransform`]
+/// payload naming the step, which [`catch_stage_panics`] turns into an error. A
+/// [`fail_step`](crate::error::fail_step) is re-raised as
+/// [`IronbeamError::TransformFailed`] the same way. With the
+/// `metrics` feature, time spent in an op of a named step is added to the
+/// `stage.<name>.micros` counter of the pipeline's collector, if one is set. With a
+/// cancellation check, a cancelled run raises [`IronbeamError::Cancelled`] the same way
+/// instead of applying the op.
+///
+/// In [`ExecMode::Streaming`], element work happens when the stage's stream is drained,
+/// so only the eager [`DynOp::apply`] path is attributed, along with
+/// [`fail_step`](crate::error::fail_step) failures from ops that run eagerly inside
+/// [`DynOp::apply_lazy`].
+struct StageOp {
+    step: Arc<StepRef>,
+    inner: Arc<dyn DynOp>,
+    cancel: Option<Arc<CancelCheck>>,
+    #[cfg(feature = "metrics")]
+    metrics: Option<MetricsCollector>,
+}
+
+impl DynOp for StageOp {
+    fn apply(&self, input: Partition) -> Partition {
+        if let Some(reason) = self.cancel.as_ref().and_then(|c| c.reason()) {
+            resume_unwind(Box::new(IronbeamError::Cancelled {
+                node: (*self.step).clone(),
+                reason,
+            }));
+        }
+        #[cfg(feature = "metrics")]
+        let started = std::time::Instant::now();
+        let out = catch_unwind(AssertUnwindSafe(|| self.inner.apply(input)))
+            .unwrap_or_else(|payload| raise_in_step(&self.step, payload));
+        #[cfg(feature = "metrics")]
+        if let (Some(metrics), Some(name)) = (&self.metrics, &self.step.name) {
+            let micros = u64::try_from(started.elapsed().as_micros()).unwrap_or(u64::MAX);
+            metrics.increment_counter(&format!("stage.{name}.micros"), micros);
+        }
+        out
+    }
+
+    /// Failures raised while building the lazy stream (per-partition ops materialize
+    /// their input and run eagerly here) are re-raised like in [`apply`](Self::apply);
+    /// panics are left as they are, since they may come from draining upstream ops.
+    fn apply_lazy(self: Arc<Self>, input: LazyPartition) -> LazyPartition {
+        let inner = Arc::clone(&self.inner);
+        catch_unwind(AssertUnwindSafe(|| inner.apply_lazy(input))).unwrap_or_else(|payload| {
+            if payload.is::<StepFailure>() {
+                raise_in_step(&self.step, payload)
+            }
+            resume_unwind(payload)
+        })
+    }
+}
+
+/// Re-raise a panic caught while running `step` with an [`IronbeamError`] payload naming
+/// it: [`IronbeamError::TransformFailed`] for a [`fail_step`](crate::error::fail_step),
+/// [`IronbeamError::PanicInTransform`] for anything else. Payloads that already are an
+/// [`IronbeamError`] pass through unchanged.
+fn raise_in_step(step: &StepRef, payload: Box<dyn Any + Send>) -> ! {
+    if payload.is::<IronbeamError>() {
+        resume_unwind(payload)
+    }
+    let node = step.clone();
+    let err = match payload.downcast::<StepFailure>() {
+        Ok(failure) => IronbeamError::TransformFailed {
+            node,
+            partition: None,
+            message: failure.0,
+        },
+        Err(payload) => IronbeamError::PanicInTransform {
+            node,
+            partition: None,
+            payload: panic_message(payload.as_ref()),
+        },
+    };
+    resume_unwind(Box::new(err))
+}
+
+/// Per-step instrumentation of an executing chain.
+///
+/// Executors call [`start`](Self::start) before and [`finish`](Self::finish) after each
+/// step. With the `metrics` feature and a collector on the pipeline, every step is
+/// recorded as a [`TransformMetrics`]; with the `otel` feature, every step runs inside an
+/// `ironbeam.stage` tracing span. Otherwise both calls are no-ops. With a
+/// [`max_memory_bytes`](DirectRunner::max_memory_bytes) budget, executors also call
+/// [`check_memory`](Self::check_memory) on every step's output; with a cancellation
+/// token or timeout, they call [`check_cancelled`](Self::check_cancelled) before every
+/// step. Recorders built with [`new`](Self::new) also keep a [`StageSummary`] of every
+/// finished step for [`PipelineResult::stages`].
+#[derive(Default)]
+struct StepRecorder {
+    #[cfg(feature = "metrics")]
+    state: Option<RecorderState>,
+    /// Every chain step, for span attributes and error reports.
+    steps: Vec<StepRef>,
+    /// Partition count of the last finished step.
+    #[cfg(feature = "otel")]
+    last_parts: Cell<Option<usize>>,
+    /// Per-partition memory budget checked by [`check_memory`](Self::check_memory).
+    memory_budget: Option<usize>,
+    /// Size estimation and element counting for every chain step's output.
+    sizers: Vec<Option<PartitionSizer>>,
+    /// Summaries of the finished steps.
+    stages: Option<StageLog>,
+    /// Token and deadline checked by [`check_cancelled`](Self::check_cancelled).
+    cancel: Option<Arc<CancelCheck>>,
+}
+
+/// The run a plan's sources are prepared for, handed to
+/// [`VecOps::prepare`](crate::type_token::VecOps::prepare).
+///
+/// Lazily computed sources, such as the output of
+/// [`PCollection::cache`](crate::PCollection::cache), compute their upstream through
+/// [`run_collect`](Self::run_collect), so it runs on the same runner, with the same mode,
+/// limits and spill settings, and under the same cancellation token as the run reading
+/// them.
+pub struct PrepareContext<'a> {
+    runner: &'a DirectRunner,
+    token: Option<&'a CancellationToken>,
+}
+
+impl PrepareContext<'_> {
+    /// Execute the pipeline ending at `terminal` as part of the run being prepared.
+    ///
+    /// # Errors
+    /// Returns the errors of [`DirectRunner::run_with_cancellation`].
+    pub fn run_collect<T: 'static + Send + Sync + Clone>(
+        &self,
+        p: &Pipeline,
+        terminal: NodeId,
+    ) -> Result<Vec<T>> {
+        self.runner
+            .run_collect_until::<T>(p, terminal, self.token, None)
+            .map(PipelineResult::into_data)
+    }
+}
+
+/// Summaries of finished steps, shared between a run's recorder and its caller.
+type StageLog = Arc<Mutex<Vec<StageSummary>>>;
+
+/// Consumer of a run's terminal partitions, called with each partition's index, the
+/// number of partitions, and the partition. Parallel runs call it on the worker that
+/// produced the partition.
+type PartitionTail<'a> = &'a (dyn Fn(usize, usize, &Partition) + Sync);
+
+#[cfg(feature = "metrics")]
+struct RecorderState {
+    metrics: MetricsCollector,
+    steps: Vec<RecordedStep>,
+    /// Output element count of the last recorded step.
+    last_out: Cell<Option<u64>>,
+}
+
+#[cfg(feature = "metrics")]
+struct RecordedStep {
+    node: StepRef,
+    node_ids: Vec<NodeId>,
+    /// Counting for the step's output type, taken from its last origin node.
+    stats: Option<ElemStats>,
+    /// Whether the step consumes the previous step's output (rather than a
+    /// payload or subplans of its own).
+    chained: bool,
+}
+
+/// A step started by [`StepRecorder::start`]; its span closes when it is dropped.
+struct StepTimer {
+    started: Option<Instant>,
+    #[cfg(feature = "otel")]
+    span: tracing::span::EnteredSpan,
+}
+
+impl StepRecorder {
+    /// Recorder for `plan`, with `names[i]` the label of `plan.chain[i]`, checking
+    /// step outputs against `max_memory_bytes` if set and steps against `cancel`.
+    fn new(
+        p: &Pipeline,
+        plan: &Plan,
+        names: &[Option<String>],
+        max_memory_bytes: Option<usize>,
+        cancel: Option<Arc<CancelCheck>>,
+    ) -> Self {
+        let step_refs: Vec<StepRef> = plan
+            .chain
+            .iter()
+            .enumerate()
+            .map(|(idx, node)| StepRef {
+                step: idx + 1,
+                kind: node.kind(),
+                name: names.get(idx).cloned().flatten(),
+            })
+            .collect();
+        Self {
+            #[cfg(feature = "metrics")]
+            state: p.get_metrics().map(|metrics| RecorderState {
+                metrics,
+                steps: plan
+                    .chain
+                    .iter()
+                    .zip(&step_refs)
+                    .enumerate()
+                    .map(|(idx, (node, step))| {
+                        let node_ids = plan.chain_origin_ids.get(idx).cloned().unwrap_or_default();
+                        RecordedStep {
+                            node: step.clone(),
+                            stats: node_ids.last().and_then(|id| p.elem_stats(*id)),
+                            chained: node.subplans().is_empty()
+                                && !matches!(node, Node::Source { .. } | Node::Materialized(_)),
+                            node_ids,
+                        }
+                    })
+                    .collect(),
+                last_out: Cell::new(None),
+            }),
+            steps: step_refs,
+            #[cfg(feature = "otel")]
+            last_parts: Cell::new(None),
+            memory_budget: max_memory_bytes,
+            sizers: (0..plan.chain.len())
+                .map(|idx| {
+                    plan.chain_origin_ids
+                        .get(idx)
+                        .and_then(|ids| ids.last())
+                        .and_then(|id| p.sizer(*id))
+                })
+                .collect(),
+            stages: Some(StageLog::default()),
+            cancel,
+        }
+    }
+
+    /// Handle on the summaries of the steps finished so far, if this recorder keeps them.
+    fn stages(&self) -> Option<StageLog> {
+        self.stages.clone()
+    }
+
+    /// Start chain step `idx`.
+    #[cfg_attr(not(feature = "otel"), allow(unused_variables))]
+    fn start(&self, idx: usize) -> StepTimer {
+        #[cfg(feature = "otel")]
+        let span = self
+            .steps
+            .get(idx)
+            .map_or_else(tracing::Span::none, |step| {
+                tracing::info_span!(
+                    "ironbeam.stage",
+                    step = step.step,
+                    kind = step.kind,
+                    name = step.name.as_deref(),
+                    partitions_in = self.last_parts.get(),
+                    partitions_out = tracing::field::Empty,
+                    duration_us = tracing::field::Empty,
+                )
+            });
+        #[allow(unused_mut)]
+        let mut timed = cfg!(feature = "otel") || self.stages.is_some();
+        #[cfg(feature = "metrics")]
+        {
+            timed |= self.state.is_some();
+        }
+        StepTimer {
+            started: timed.then(Instant::now),
+            #[cfg(feature = "otel")]
+            span: span.entered(),
+        }
+    }
+
+    /// Finish chain step `idx`, started as `timer`, whose output is `parts`.
+    ///
+    /// When several steps run as one block, the block is recorded under its last step.
+    fn finish(&self, idx: usize, timer: StepTimer, parts: &[Partition]) {
+        let elapsed = timer.started.map(|started| started.elapsed());
+
+        if let (Some(stages), Some(wall_time)) = (&self.stages, elapsed)
+            && let Some(step) = self.steps.get(idx)
+        {
+            let elements = self.sizers.get(idx).copied().flatten().and_then(|sizer| {
+                parts
+                    .iter()
+                    .map(|part| sizer.len(&**part).map(|n| n as u64))
+                    .sum::<Option<u64>>()
+            });
+            stages.lock().unwrap().push(StageSummary {
+                step: step.clone(),
+                partitions: parts.len(),
+                elements,
+                wall_time,
+            });
+        }
+
+        #[cfg(feature = "otel")]
+        {
+            timer.span.record("partitions_out", parts.len());
+            if let Some(elapsed) = elapsed {
+                let micros = u64::try_from(elapsed.as_micros()).unwrap_or(u64::MAX);
+                timer.span.record("duration_us", micros);
+            }
+            self.last_parts.set(Some(parts.len()));
+        }
+
+        #[cfg(feature = "metrics")]
+        if let (Some(state), Some(wall_time)) = (&self.state, elapsed)
+            && let Some(step) = state.steps.get(idx)
+        {
+            let measured = step
+                .stats
+                .and_then(|s| s.measure(parts.iter().map(|p| &**p as &dyn Any)));
+            let elements_in = if step.chained {
+                state.last_out.get()
+            } else {
+                None
+            };
+            state.last_out.set(measured.map(|(n, _)| n));
+            state.metrics.record_transform(TransformMetrics {
+                node: step.node.clone(),
+                node_ids: step.node_ids.clone(),
+                elements_in,
+                elements_out: measured.map(|(n, _)| n),
+                bytes_out: measured.map(|(_, bytes)| bytes),
+                wall_time,
+            });
+        }
+    }
+
+    /// Check the output `parts` of chain step `idx` against the memory budget.
+    ///
+    /// # Errors
+    /// Returns [`IronbeamError::MemoryBudgetExceeded`] for the first partition estimated
+    /// above the budget.
+    fn check_memory(&self, idx: usize, parts: &[Partition]) -> Result<(), IronbeamError> {
+        let Some(budget) = self.memory_budget else {
+            return Ok(());
+        };
+        let (Some(step), Some(Some(sizer))) = (self.steps.get(idx), self.sizers.get(idx)) else {
+            return Ok(());
+        };
+        for (i, part) in parts.iter().enumerate() {
+            if let Some(bytes) = sizer.estimate(&**part)
+                && bytes > budget
+            {
+                return Err(IronbeamError::MemoryBudgetExceeded {
+                    node: step.clone(),
+                    partition: (parts.len() > 1).then_some(i),
+                    estimated_bytes: bytes,
+                    budget,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Check, before chain step `idx` starts, whether the run has been cancelled.
+    ///
+    /// # Errors
+    /// Returns [`IronbeamError::Cancelled`] naming step `idx` if it has.
+    fn check_cancelled(&self, idx: usize) -> Result<(), IronbeamError> {
+        let Some(reason) = self.cancel.as_ref().and_then(|c| c.reason()) else {
+            return Ok(());
+        };
+        Err(IronbeamError::Cancelled {
+            node: self.steps.get(idx).cloned().unwrap_or(StepRef {
+                step: idx + 1,
+                kind: "Unknown",
+                name: None,
+            }),
+            reason,
+        })
+    }
+}
+
+/// Estimated size of the plan's source payload, if its type is known.
+fn source_bytes(p: &Pipeline, plan: &Plan) -> Option<usize> {
+    let Some(Node::Source { payload, .. }) = plan.chain.first() else {
+        return None;
+    };
+    let id = plan.chain_origin_ids.first()?.last()?;
+    p.sizer(*id)?.estimate(payload.as_ref())
+}
+
+/// Run `f` over partition `idx`, tagging a stage panic raised inside it with the index.
+fn in_partition<R>(idx: usize, f: impl FnOnce() -> R) -> R {
+    catch_unwind(AssertUnwindSafe(f)).unwrap_or_else(|payload| {
+        match payload.downcast::<IronbeamError>() {
+            Ok(err) => match *err {
+                IronbeamError::PanicInTransform {
+                    node,
+                    partition: None,
+                    payload,
+                } => resume_unwind(Box::new(IronbeamError::PanicInTransform {
+                    node,
+                    partition: Some(idx),
+                    payload,
+                })),
+                IronbeamError::TransformFailed {
+                    node,
+                    partition: None,
+                    message,
+                } => resume_unwind(Box::new(IronbeamError::TransformFailed {
+                    node,
+                    partition: Some(idx),
+                    message,
+                })),
+                other => resume_unwind(Box::new(other)),
+            },
+            Err(payload) => resume_unwind(payload),
+        }
+    })
+}
+
+/// Run `f` on a rayon pool of `threads` workers built for this call, or on the
+/// current pool when `threads` is `None`.
+///
+/// A scoped pool (rather than configuring rayon's global pool, which can only be done
+/// once per process) lets runs in the same process use different thread counts.
+fn in_thread_pool<R: Send>(
+    threads: Option<usize>,
+    f: impl FnOnce() -> Result<R> + Send,
+) -> Result<R> {
+    match threads {
+        Some(n) => ThreadPoolBuilder::new()
+            .num_threads(n)
+            .thread_name(|i| format!("ironbeam-worker-{i}"))
+            .build()?
+            .install(f),
+        None => f(),
+    }
+}
+
+/// Run an executor, converting stage panics raised by [`StageOp`] into errors.
+///
+/// Any other panic is propagated unchanged.
+fn catch_stage_panics<T>(run: impl FnOnce() -> Result<T>) -> Result<T> {
+    catch_unwind(AssertUnwindSafe(run)).unwrap_or_else(|payload| {
+        match payload.downcast::<IronbeamError>() {
+            Ok(err) => Err((*err).into()),
+            Err(payload) => resume_unwind(payload),
+        }
+    })
+}
+
+/// Error for a collected terminal output that is not `Vec<T>`.
+fn terminal_mismatch<T>() -> IronbeamError {
+    IronbeamError::TypeMismatch {
+        expected: std::any::type_name::<Vec<T>>(),
+        node: None,
+    }
+}
+
+/// Best-effort text of a panic payload (`&str` or `String`).
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| (*s).to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .or_else(|| payload.downcast_ref::<StepFailure>().map(|f| f.0.clone()))
+        .unwrap_or_else(|| "non-string panic payload".to_string())
+}
+
+/// Swap every spill-capable `GroupByKey` in `chain` for its external implementation.
+///
+/// The replacement `local` stage scatters each partition (spilling it when needed) and
+/// the `merge` gathers the groups, both bound to `budget` and `dir` (see
+/// [`ExternalGroup`](crate::spill_group::ExternalGroup)). A failure in either is raised
+/// as a stage error for the step, labelled from `names` as in [`attribute_stages`].
+/// Nodes without an external implementation are left untouched.
+#[cfg(feature = "spilling")]
+fn apply_memory_budget(
+    chain: Vec<Node>,
+    names: &[Option<String>],
+    budget: usize,
+    dir: &Path,
+) -> Vec<Node> {
+    chain
+        .into_iter()
+        .zip(names)
+        .enumerate()
+        .map(|(idx, (node, name))| match node {
+            Node::GroupByKey {
+                spill_group: Some(spill_group),
+                ..
+            } => {
+                let step = Arc::new(StepRef {
+                    step: idx + 1,
+                    kind: "GroupByKey",
+                    name: name.clone(),
+                });
+                let (local, merge) = spill_group(budget, dir);
+                let local_step = Arc::clone(&step);
+                Node::GroupByKey {
+                    local: Arc::new(move |p: Partition| {
+                        catch_unwind(AssertUnwindSafe(|| local(p)))
+                            .unwrap_or_else(|payload| raise_in_step(&local_step, payload))
+                    }),
+                    merge: Arc::new(move |parts: Vec<Partition>| {
+                        catch_unwind(AssertUnwindSafe(|| merge(parts)))
+                            .unwrap_or_else(|payload| raise_in_step(&step, payload))
+                    }),
+                    spill_group: None,
+                }
+            }
+            other => other,
+        })
+        .collect()
+}
+
+/// Apply a fused run of stateless ops to one partition.
+///
+/// With `streaming`, elements are pulled through the whole run lazily via
+/// [`DynOp::apply_lazy`] and materialized once at the end; otherwise each op
+/// materializes its full output via [`DynOp::apply`].
+fn run_stateless(ops: Vec<Arc<dyn DynOp>>, input: Partition, streaming: bool) -> Partition {
+    if streaming {
+        ops.into_iter()
+            .fold(LazyPartition::from(input), |acc, op| op.apply_lazy(acc))
+            .materialize()
+    } else {
+        ops.into_iter().fold(input, |acc, op| op.apply(acc))
+    }
+}
+
+/// Prepare every `Source` in `chain` and its subplans for the run in `ctx` (see
+/// [`VecOps::prepare`](crate::type_token::VecOps::prepare)),
+/// returning `true` if any of them did work.
+fn prepare_sources(chain: &[Node], ctx: &PrepareContext<'_>) -> Result<bool> {
+    let mut prepared = false;
+    for node in chain {
+        if let Node::Source {
+            payload, vec_ops, ..
+        } = node
+        {
+            prepared |= vec_ops.prepare(payload.as_ref(), ctx)?;
+        }
+        for sub in node.subplans() {
+            prepared |= prepare_sources(sub, ctx)?;
+        }
+    }
+    Ok(prepared)
+}
+
 /// Execute a fully linearized chain **sequentially**, collecting `Vec<T>`.
 ///
-/// Internal helper used by [`Runner::run_collect`]. Walks the chain left->right,
-/// maintaining a single opaque `Partition` buffer.
+/// Internal helper used by [`DirectRunner::run_collect`]. Walks the chain left->right,
+/// maintaining a single opaque `Partition` buffer. With `streaming`, fused stateless
+/// runs are executed lazily (see [`run_stateless`]).
 #[allow(clippy::too_many_lines)]
-fn exec_seq<T: 'static + Send + Sync + Clone>(chain: Vec<Node>) -> Result<Vec<T>> {
+fn exec_seq<T: 'static + Send + Sync + Clone>(
+    chain: Vec<Node>,
+    streaming: bool,
+    recorder: &StepRecorder,
+    tail: Option<PartitionTail<'_>>,
+) -> Result<Vec<T>> {
     let mut buf: Option<Partition> = None;
 
     let run_subplan_seq = |chain: Vec<Node>| -> Result<Vec<Partition>> {
@@ -352,11 +1548,9 @@ fn exec_seq<T: 'static + Send + Sync + Clone>(chain: Vec<Node>) -> Result<Vec<T>
                     payload, vec_ops, ..
                 } => vec_ops
                     .clone_any(payload.as_ref())
-                    .ok_or_else(|| anyhow!("unsupported source vec type"))?,
-                Node::Stateless(ops) => ops
-                    .into_iter()
-                    .fold(curr.take().unwrap(), |acc, op| op.apply(acc)),
-                Node::GroupByKey { local, merge } => {
+                    .ok_or(IronbeamError::UnsupportedSource)?,
+                Node::Stateless(ops) => run_stateless(ops, curr.take().unwrap(), streaming),
+                Node::GroupByKey { local, merge, .. } => {
                     let mid = local(curr.take().unwrap());
                     merge(vec![mid])
                 }
@@ -371,11 +1565,14 @@ fn exec_seq<T: 'static + Send + Sync + Clone>(chain: Vec<Node>) -> Result<Vec<T>
                     merge(vec![mid])
                 }
                 Node::Materialized(p) => Box::new(p) as Partition,
-                Node::Flatten { .. } => bail!("nested Flatten not supported in subplan"),
-                Node::CoGroup { .. } => bail!("nested CoGroup not supported in subplan"),
-                Node::Reshuffle { .. } => {
-                    bail!("Reshuffle not supported in CoGroup/Flatten subplans")
-                }
+                node @ (Node::Flatten { .. }
+                | Node::CoGroup { .. }
+                | Node::CoGroupN { .. }
+                | Node::Reshuffle { .. }) => bail!(IronbeamError::UnsupportedNode {
+                    kind: node.kind(),
+                    context: "inside a join or flatten subplan",
+                }),
+                Node::Sort { sort_run, .. } => sort_run(curr.take().unwrap()),
                 Node::CombineGlobal {
                     local,
                     merge,
@@ -394,7 +1591,9 @@ fn exec_seq<T: 'static + Send + Sync + Clone>(chain: Vec<Node>) -> Result<Vec<T>
         Ok(vec![curr.unwrap()])
     };
 
-    for node in chain {
+    for (idx, node) in chain.into_iter().enumerate() {
+        recorder.check_cancelled(idx)?;
+        let timer = recorder.start(idx);
         buf = Some(match node {
             Node::Flatten {
                 chains,
@@ -437,15 +1636,30 @@ fn exec_seq<T: 'static + Send + Sync + Clone>(chain: Vec<Node>) -> Result<Vec<T>
 
                 exec(left_single, right_single)
             }
+            Node::CoGroupN {
+                chains,
+                coalesce,
+                exec,
+            } => {
+                let mut coalesced_inputs: Vec<Partition> = Vec::with_capacity(chains.len());
+                for (chain, coalesce) in chains.iter().zip(coalesce.iter()) {
+                    let mut parts = run_subplan_seq(chain.clone())?;
+                    let single: Partition = if parts.len() == 1 {
+                        parts.pop().unwrap()
+                    } else {
+                        coalesce(parts)
+                    };
+                    coalesced_inputs.push(single);
+                }
+                exec(coalesced_inputs)
+            }
             Node::Source {
                 payload, vec_ops, ..
             } => vec_ops
                 .clone_any(payload.as_ref())
-                .ok_or_else(|| anyhow!("unsupported source vec type"))?,
-            Node::Stateless(ops) => ops
-                .into_iter()
-                .fold(buf.take().unwrap(), |acc, op| op.apply(acc)),
-            Node::GroupByKey { local, merge } => {
+                .ok_or(IronbeamError::UnsupportedSource)?,
+            Node::Stateless(ops) => run_stateless(ops, buf.take().unwrap(), streaming),
+            Node::GroupByKey { local, merge, .. } => {
                 let mid = local(buf.take().unwrap());
                 merge(vec![mid])
             }
@@ -461,7 +1675,7 @@ fn exec_seq<T: 'static + Send + Sync + Clone>(chain: Vec<Node>) -> Result<Vec<T>
             Node::Materialized(p) => Box::new(
                 p.downcast_ref::<Vec<T>>()
                     .cloned()
-                    .ok_or_else(|| anyhow!("terminal type mismatch"))?,
+                    .ok_or_else(terminal_mismatch::<T>)?,
             ) as Partition,
             Node::CombineGlobal {
                 local,
@@ -473,35 +1687,107 @@ fn exec_seq<T: 'static + Send + Sync + Clone>(chain: Vec<Node>) -> Result<Vec<T>
                 let acc = merge(vec![mid_acc]);
                 finish(acc)
             }
-            Node::Reshuffle { reshuffle } => reshuffle(vec![buf.take().unwrap()], 1)
+            Node::Reshuffle { reshuffle, .. } => reshuffle(vec![buf.take().unwrap()], 1)
                 .into_iter()
                 .next()
                 .expect("Reshuffle returned empty vec in sequential mode"),
+            Node::Sort { sort_run, .. } => sort_run(buf.take().unwrap()),
         });
+        recorder.finish(idx, timer, buf.as_slice());
+        recorder.check_memory(idx, buf.as_slice())?;
     }
 
     let out = buf.unwrap();
+    if let Some(tail) = tail {
+        tail(0, 1, &out);
+        return Ok(Vec::new());
+    }
     let v = *out
         .downcast::<Vec<T>>()
-        .map_err(|_| anyhow!("terminal type mismatch"))?;
+        .map_err(|_| terminal_mismatch::<T>())?;
     Ok(v)
 }
 
 /// Execute a fully linearized chain **in parallel**, collecting `Vec<T>`.
 ///
-/// Internal helper used by [`Runner::run_collect`]. Partitions the head source
+/// Internal helper used by [`DirectRunner::run_collect`]. Partitions the head source
 /// and applies stateless runs with rayon. Barriers (`GroupByKey`, `CombineValues`,
 /// `CoGroup`) perform a parallel local phase followed by a global merge.
 ///
+/// `stage_parts` ([`Plan::stage_partitions`]) overrides the partition count at the
+/// source split and at `Reshuffle`/`Sort` barriers.
+///
 /// When `limit` is `Some(n)`, the final merge step stops accumulating elements as
 /// soon as `n` total have been collected — providing early termination for
 /// pipelines that end with `take(n)` / `first()`.
-#[allow(clippy::too_many_lines)]
 fn exec_par<T: 'static + Send + Sync + Clone>(
     chain: &[Node],
     partitions: usize,
+    stage_parts: &[Option<usize>],
+    limit: Option<usize>,
+    recorder: &StepRecorder,
+    tail: Option<PartitionTail<'_>>,
+) -> Result<Vec<T>> {
+    exec_par_from::<T>(
+        chain,
+        partitions,
+        stage_parts,
+        limit,
+        recorder,
+        None,
+        &mut |_, _| {},
+        tail,
+    )
+}
+
+/// [`exec_par`] with checkpointing hooks.
+///
+/// With `resume = Some((idx, parts))`, steps `0..=idx` are skipped and execution
+/// continues from `parts` as the output of step `idx`. `after_step(idx, parts)` is
+/// called with the output of every executed step (for a fused stateless block, only
+/// its last step). With a `tail`, the terminal partitions go to it and nothing is
+/// collected.
+#[allow(clippy::too_many_arguments)]
+fn exec_par_from<T: 'static + Send + Sync + Clone>(
+    chain: &[Node],
+    partitions: usize,
+    stage_parts: &[Option<usize>],
     limit: Option<usize>,
+    recorder: &StepRecorder,
+    resume: Option<(usize, Vec<Partition>)>,
+    after_step: &mut dyn FnMut(usize, &[Partition]),
+    tail: Option<PartitionTail<'_>>,
 ) -> Result<Vec<T>> {
+    let parts = exec_par_parts(
+        chain,
+        partitions,
+        stage_parts,
+        recorder,
+        resume,
+        after_step,
+        tail,
+    )?;
+    if tail.is_some() {
+        return Ok(Vec::new());
+    }
+    collect_parts::<T>(parts, limit)
+}
+
+/// The parallel engine behind [`exec_par_from`], returning the partitions produced by
+/// the last step of `chain` instead of collecting them.
+///
+/// A `tail` is handed every terminal partition. When the chain ends in stateless steps
+/// it runs inside their parallel map, on the worker that produced the partition.
+#[allow(clippy::too_many_lines)]
+fn exec_par_parts(
+    chain: &[Node],
+    partitions: usize,
+    stage_parts: &[Option<usize>],
+    recorder: &StepRecorder,
+    resume: Option<(usize, Vec<Partition>)>,
+    after_step: &mut dyn FnMut(usize, &[Partition]),
+    tail: Option<PartitionTail<'_>>,
+) -> Result<Vec<Partition>> {
     /// Run a nested subplan (used by `CoGroup`) in parallel, returning a vector
     /// of partitions. The subplan must start with a `Source`. Nested `CoGroup`
     /// inside a subplan is not supported.
@@ -510,7 +1796,7 @@ fn exec_par<T: 'static + Send + Sync + Clone>(
             Node::Source {
                 payload, vec_ops, ..
             } => (payload.clone(), vec_ops.clone(), &chain[1..]),
-            _ => bail!("subplan must start with a Source"),
+            _ => bail!(IronbeamError::MissingSource),
         };
         let total_len = vec_ops.len(payload.as_ref()).unwrap_or(0);
         let parts = partitions.max(1).min(total_len.max(1));
@@ -537,10 +1823,13 @@ fn exec_par<T: 'static + Send + Sync + Clone>(
                     }
                     curr = curr
                         .into_par_iter()
-                        .map(|p| ops.iter().fold(p, |acc, op| op.apply(acc)))
+                        .enumerate()
+                        .map(|(idx, p)| {
+                            in_partition(idx, || ops.iter().fold(p, |acc, op| op.apply(acc)))
+                        })
                         .collect();
                 }
-                Node::GroupByKey { local, merge } => {
+                Node::GroupByKey { local, merge, .. } => {
                     let mids: Vec<Partition> = curr.into_par_iter().map(|p| local(p)).collect();
                     curr = vec![merge(mids)];
                     i += 1;
@@ -557,13 +1846,28 @@ fn exec_par<T: 'static + Send + Sync + Clone>(
                     curr = vec![merge(mids)];
                     i += 1;
                 }
-                Node::Source { .. } | Node::Materialized(_) => {
-                    bail!("unexpected source/materialized in subplan")
+                node @ (Node::Source { .. } | Node::Materialized(_)) => {
+                    bail!(IronbeamError::UnsupportedNode {
+                        kind: node.kind(),
+                        context: "after the start of a subplan",
+                    })
                 }
-                Node::Flatten { .. } => bail!("nested Flatten not supported in subplan"),
-                Node::CoGroup { .. } => bail!("nested CoGroup not supported in subplan"),
-                Node::Reshuffle { .. } => {
-                    bail!("Reshuffle not supported in CoGroup/Flatten subplans")
+                node @ (Node::Flatten { .. }
+                | Node::CoGroup { .. }
+                | Node::CoGroupN { .. }
+                | Node::Reshuffle { .. }) => bail!(IronbeamError::UnsupportedNode {
+                    kind: node.kind(),
+                    context: "inside a join or flatten subplan",
+                }),
+                Node::Sort {
+                    range_partition,
+                    sort_run,
+                } => {
+                    curr = range_partition(curr, partitions)
+                        .into_par_iter()
+                        .map(|p| sort_run(p))
+                        .collect();
+                    i += 1;
                 }
                 Node::CombineGlobal {
                     local,
@@ -624,27 +1928,46 @@ fn exec_par<T: 'static + Send + Sync + Clone>(
         Node::Source {
             payload, vec_ops, ..
         } => (Arc::clone(payload), Arc::clone(vec_ops), &chain[1..]),
-        _ => bail!("execution plan must start with a Source node"),
+        _ => bail!(IronbeamError::MissingSource),
     };
+    // A `with_partitions` hint on the source replaces the overall count, including as
+    // the ceiling of the adaptive rescaling below.
+    let stage_hint = |step: usize| stage_parts.get(step).copied().flatten();
+    let partitions = stage_hint(0).unwrap_or(partitions);
 
-    let total_len = vec_ops.len(payload.as_ref()).unwrap_or(0);
-    let parts = partitions.max(1).min(total_len.max(1));
-    let mut curr = vec_ops.split(payload.as_ref(), parts).unwrap_or_else(|| {
-        vec![
-            vec_ops
-                .clone_any(payload.as_ref())
-                .expect("cloneable source"),
-        ]
-    });
+    // `rest[i]` is chain step `i + 1`, so resuming after step `idx` starts at `rest[idx]`.
+    let (mut curr, mut i) = if let Some((idx, restored)) = resume {
+        (restored, idx)
+    } else {
+        recorder.check_cancelled(0)?;
+        let timer = recorder.start(0);
+        let total_len = vec_ops.len(payload.as_ref()).unwrap_or(0);
+        let parts = partitions.max(1).min(total_len.max(1));
+        let curr = vec_ops.split(payload.as_ref(), parts).unwrap_or_else(|| {
+            vec![
+                vec_ops
+                    .clone_any(payload.as_ref())
+                    .expect("cloneable source"),
+            ]
+        });
+        recorder.finish(0, timer, &curr);
+        recorder.check_memory(0, &curr)?;
+        after_step(0, &curr);
+        (curr, 0)
+    };
 
     // Tracks the adaptive partition count updated after each barrier stage.
     // Starts at the source-based split count; updated by barrier_cardinality_hint after
     // each barrier so that downstream Reshuffle calls use a proportional split count
     // rather than always re-expanding to the original `partitions` suggestion.
-    let mut current_parts = parts;
+    let mut current_parts = curr.len().max(1);
+    // Whether `tail` has already seen the terminal partitions.
+    let mut tailed = false;
 
-    let mut i = 0usize;
     while i < rest.len() {
+        // `rest` starts at chain index 1.
+        recorder.check_cancelled(i + 1)?;
+        let timer = recorder.start(i + 1);
         match &rest[i] {
             Node::Stateless(_) => {
                 let mut ops = Vec::new();
@@ -671,12 +1994,24 @@ fn exec_par<T: 'static + Send + Sync + Clone>(
                     let scaled = (current_parts as f64 * multiplier).round() as usize;
                     current_parts = scaled.max(1).min(partitions);
                 }
+                let tail = tail.filter(|_| i == rest.len());
+                tailed |= tail.is_some();
+                let n = curr.len();
                 curr = curr
                     .into_par_iter()
-                    .map(|p| ops.iter().fold(p, |acc, op| op.apply(acc)))
+                    .enumerate()
+                    .map(|(idx, p)| {
+                        in_partition(idx, || {
+                            let out = ops.iter().fold(p, |acc, op| op.apply(acc));
+                            if let Some(tail) = tail {
+                                tail(idx, n, &out);
+                            }
+                            out
+                        })
+                    })
                     .collect();
             }
-            Node::GroupByKey { local, merge } => {
+            Node::GroupByKey { local, merge, .. } => {
                 let mids: Vec<Partition> = curr.into_par_iter().map(|p| local(p)).collect();
                 curr = vec![merge(mids)];
                 // GBK collapses to a single partition, then expands downstream; ratio ~0.1.
@@ -787,8 +2122,42 @@ fn exec_par<T: 'static + Send + Sync + Clone>(
                 }
                 i += 1;
             }
-            Node::Source { .. } | Node::Materialized(_) => {
-                bail!("unexpected additional source/materialized")
+            Node::CoGroupN {
+                chains,
+                coalesce,
+                exec,
+            } => {
+                // Run every input subplan concurrently via Rayon, then join once.
+                let coalesced_inputs: Vec<Partition> = chains
+                    .par_iter()
+                    .zip(coalesce.par_iter())
+                    .map(|(chain, coalesce)| {
+                        let parts = run_subplan_par(chain, partitions)?;
+                        Ok(if parts.len() == 1 {
+                            parts.into_iter().next().unwrap()
+                        } else {
+                            coalesce(parts)
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                curr = vec![exec(coalesced_inputs)];
+                // Same ratio as the binary CoGroup: the join drops unmatched keys.
+                #[allow(
+                    clippy::cast_precision_loss,
+                    clippy::cast_sign_loss,
+                    clippy::cast_possible_truncation
+                )]
+                {
+                    let scaled = (current_parts as f64 * 0.5_f64).round() as usize;
+                    current_parts = scaled.max(1);
+                }
+                i += 1;
+            }
+            node @ (Node::Source { .. } | Node::Materialized(_)) => {
+                bail!(IronbeamError::UnsupportedNode {
+                    kind: node.kind(),
+                    context: "after the start of the plan",
+                })
             }
             Node::CombineGlobal {
                 local,
@@ -845,21 +2214,63 @@ fn exec_par<T: 'static + Send + Sync + Clone>(
                 current_parts = 1;
                 i += 1;
             }
-            Node::Reshuffle { reshuffle } => {
+            Node::Reshuffle {
+                reshuffle,
+                target_partitions,
+            } => {
                 // Use the adaptively updated current_parts instead of the original
                 // `partitions` suggestion, keeping the split count proportional to the
-                // post-barrier cardinality estimate rather than the source size.
-                curr = reshuffle(curr, current_parts);
+                // post-barrier cardinality estimate rather than the source size. An explicit
+                // repartition target overrides both and becomes the new adaptive baseline,
+                // and a `with_partitions` hint overrides that.
+                let n = stage_hint(i + 1)
+                    .or(*target_partitions)
+                    .unwrap_or(current_parts);
+                curr = reshuffle(curr, n);
+                current_parts = n;
+                i += 1;
+            }
+            Node::Sort {
+                range_partition,
+                sort_run,
+            } => {
+                // Route elements into sampled key ranges, then sort every range concurrently;
+                // range order is element order, and cardinality is unchanged.
+                if let Some(n) = stage_hint(i + 1) {
+                    current_parts = n;
+                }
+                curr = range_partition(curr, current_parts)
+                    .into_par_iter()
+                    .map(|p| sort_run(p))
+                    .collect();
                 i += 1;
             }
         }
+        // The last step consumed is `rest[i - 1]`, i.e. chain index `i`.
+        recorder.finish(i, timer, &curr);
+        recorder.check_memory(i, &curr)?;
+        after_step(i, &curr);
     }
 
+    if let Some(tail) = tail
+        && !tailed
+    {
+        let n = curr.len();
+        curr.par_iter()
+            .enumerate()
+            .for_each(|(idx, p)| tail(idx, n, p));
+    }
+    Ok(curr)
+}
+
+/// Concatenate the terminal partitions `curr` into a `Vec<T>`, stopping after `limit`
+/// elements.
+fn collect_parts<T: 'static>(curr: Vec<Partition>, limit: Option<usize>) -> Result<Vec<T>> {
     if curr.len() == 1 {
         let one = curr.into_iter().next().unwrap();
         let mut v = *one
             .downcast::<Vec<T>>()
-            .map_err(|_| anyhow!("terminal type mismatch"))?;
+            .map_err(|_| terminal_mismatch::<T>())?;
         if let Some(n) = limit {
             v.truncate(n);
         }
@@ -869,7 +2280,7 @@ fn exec_par<T: 'static + Send + Sync + Clone>(
         for part in curr {
             let v = *part
                 .downcast::<Vec<T>>()
-                .map_err(|_| anyhow!("terminal type mismatch"))?;
+                .map_err(|_| terminal_mismatch::<T>())?;
             if let Some(n) = limit {
                 let remaining = n.saturating_sub(out.len());
                 if remaining == 0 {
@@ -887,81 +2298,83 @@ fn exec_par<T: 'static + Send + Sync + Clone>(
     }
 }
 
+/// Run chain steps `from + 1..to` in parallel over `parts`, the output of step `from`,
+/// returning the output of step `to - 1`. Used by the cluster workers.
+#[cfg(feature = "cluster")]
+pub(crate) fn run_steps(
+    chain: &[Node],
+    from: usize,
+    parts: Vec<Partition>,
+    to: usize,
+    partitions: usize,
+) -> Result<Vec<Partition>> {
+    catch_stage_panics(|| {
+        exec_par_parts(
+            &chain[..to],
+            partitions,
+            &[],
+            &StepRecorder::default(),
+            Some((from, parts)),
+            &mut |_, _| {},
+            None,
+        )
+    })
+}
+
+/// Run the rest of `chain` in parallel from `parts`, the output of step `from`, and
+/// collect the terminal output. Used by the cluster coordinator.
+#[cfg(feature = "cluster")]
+pub(crate) fn finish_steps<T: 'static + Send + Sync + Clone>(
+    chain: &[Node],
+    from: usize,
+    parts: Vec<Partition>,
+    partitions: usize,
+    limit: Option<usize>,
+) -> Result<Vec<T>> {
+    catch_stage_panics(|| {
+        exec_par_from::<T>(
+            chain,
+            partitions,
+            &[],
+            limit,
+            &StepRecorder::default(),
+            Some((from, parts)),
+            &mut |_, _| {},
+            None,
+        )
+    })
+}
+
 /// Execute a fully linearized chain **sequentially** with checkpointing support.
 ///
-/// This version saves checkpoint state at configurable intervals and can resume
-/// from the last checkpoint on failure. Note: Due to type-erasure, we cannot
-/// serialize intermediate partition state, so checkpoints track progress only.
-/// On recovery, the pipeline re-executes from the start but logs progress.
+/// Checkpoints are saved as dictated by the configured policy. With `auto_recover`,
+/// execution resumes after the last checkpointed barrier whose output was stored (see
+/// [`crate::checkpoint`]); otherwise the chain runs from the start.
 #[cfg(feature = "checkpointing")]
-#[allow(clippy::too_many_lines)]
 fn exec_seq_with_checkpointing<T: 'static + Send + Sync + Clone>(
     chain: Vec<Node>,
-    config: CheckpointConfig,
+    mut checkpoints: CheckpointRun,
+    recorder: &StepRecorder,
+    tail: Option<PartitionTail<'_>>,
 ) -> Result<Vec<T>> {
-    use crate::checkpoint::{
-        CheckpointManager, CheckpointMetadata, CheckpointState, compute_checksum,
-        current_timestamp_ms, generate_pipeline_id,
+    let (skip, mut buf) = match checkpoints.recover() {
+        Some((idx, parts)) => (idx + 1, parts.into_iter().next()),
+        None => (0, None),
     };
 
-    let total_nodes = chain.len();
-    let mut manager = CheckpointManager::new(config)?;
-
-    let pipeline_id = generate_pipeline_id(&format!("{:?}", chain.len()));
-
-    if manager.config.auto_recover
-        && let Some(checkpoint_path) = manager.find_latest_checkpoint(&pipeline_id)?
-    {
-        eprintln!("[Checkpoint] Found existing checkpoint, attempting recovery...");
-        match manager.load_checkpoint(&checkpoint_path) {
-            Ok(state) => {
-                eprintln!(
-                    "[Checkpoint] Recovered from node {} ({:.0}% complete)",
-                    state.completed_node_index, state.metadata.progress_percent
-                );
-                // Type-erasure prevents restoring partition state; we re-execute from the start.
-            }
-            Err(e) => {
-                eprintln!("[Checkpoint] Failed to load checkpoint: {e}");
-            }
-        }
-    }
-
-    let mut buf: Option<Partition> = None;
-
-    for (idx, node) in chain.into_iter().enumerate() {
-        let is_barrier = matches!(
-            node,
-            Node::GroupByKey { .. }
-                | Node::CombineValues { .. }
-                | Node::Flatten { .. }
-                | Node::CoGroup { .. }
-                | Node::CombineGlobal { .. }
-                | Node::Reshuffle { .. }
-        );
-
-        let node_type = match &node {
-            Node::Source { .. } => "Source",
-            Node::Stateless(_) => "Stateless",
-            Node::GroupByKey { .. } => "GroupByKey",
-            Node::CombineValues { .. } => "CombineValues",
-            Node::Flatten { .. } => "Flatten",
-            Node::CoGroup { .. } => "CoGroup",
-            Node::Materialized(_) => "Materialized",
-            Node::CombineGlobal { .. } => "CombineGlobal",
-            Node::Reshuffle { .. } => "Reshuffle",
-        };
-
+    for (idx, node) in chain.into_iter().enumerate().skip(skip) {
+        recorder.check_cancelled(idx)?;
+        let timer = recorder.start(idx);
         buf = Some(match node {
             Node::Source {
                 payload, vec_ops, ..
             } => vec_ops
                 .clone_any(payload.as_ref())
-                .ok_or_else(|| anyhow!("unsupported source vec type"))?,
+                .ok_or(IronbeamError::UnsupportedSource)?,
             Node::Stateless(ops) => ops
                 .into_iter()
                 .fold(buf.take().unwrap(), |acc, op| op.apply(acc)),
-            Node::GroupByKey { local, merge } => {
+            Node::GroupByKey { local, merge, .. } => {
                 let mid = local(buf.take().unwrap());
                 merge(vec![mid])
             }
@@ -977,10 +2390,14 @@ fn exec_seq_with_checkpointing<T: 'static + Send + Sync + Clone>(
             Node::Materialized(p) => Box::new(
                 p.downcast_ref::<Vec<T>>()
                     .cloned()
-                    .ok_or_else(|| anyhow!("terminal type mismatch"))?,
+                    .ok_or_else(terminal_mismatch::<T>)?,
             ) as Partition,
-            Node::Flatten { .. } => bail!("Flatten requires subplan execution"),
-            Node::CoGroup { .. } => bail!("CoGroup requires subplan execution"),
+            node @ (Node::Flatten { .. } | Node::CoGroup { .. } | Node::CoGroupN { .. }) => {
+                bail!(IronbeamError::UnsupportedNode {
+                    kind: node.kind(),
+                    context: "with sequential checkpointing",
+                })
+            }
             Node::CombineGlobal {
                 local,
                 merge,
@@ -991,125 +2408,59 @@ fn exec_seq_with_checkpointing<T: 'static + Send + Sync + Clone>(
                 let acc = merge(vec![mid_acc]);
                 finish(acc)
             }
-            Node::Reshuffle { reshuffle } => reshuffle(vec![buf.take().unwrap()], 1)
+            Node::Reshuffle { reshuffle, .. } => reshuffle(vec![buf.take().unwrap()], 1)
                 .into_iter()
                 .next()
                 .expect("Reshuffle returned empty vec in sequential mode"),
+            Node::Sort { sort_run, .. } => sort_run(buf.take().unwrap()),
         });
-
-        if manager.should_checkpoint(idx, is_barrier, total_nodes) {
-            let timestamp = current_timestamp_ms();
-            #[allow(
-                clippy::cast_possible_truncation,
-                clippy::cast_sign_loss,
-                clippy::cast_precision_loss
-            )]
-            let progress_percent = ((idx as f64 / total_nodes as f64) * 100.0) as u8;
-            let metadata_str = format!("{pipeline_id}:{idx}:{timestamp}:1");
-            let checksum = compute_checksum(metadata_str.as_bytes());
-
-            let state = CheckpointState {
-                pipeline_id: pipeline_id.clone(),
-                completed_node_index: idx,
-                timestamp,
-                partition_count: 1,
-                checksum,
-                exec_mode: "sequential".to_string(),
-                metadata: CheckpointMetadata {
-                    total_nodes,
-                    last_node_type: node_type.to_string(),
-                    progress_percent,
-                },
-            };
-
-            match manager.save_checkpoint(&state) {
-                Ok(path) => {
-                    eprintln!(
-                        "[Checkpoint] Saved checkpoint at node {idx} ({:.0}% complete) to {:?}",
-                        progress_percent,
-                        path.display()
-                    );
-                }
-                Err(e) => {
-                    eprintln!("[Checkpoint] Warning: Failed to save checkpoint: {e}");
-                }
-            }
-        }
+        recorder.finish(idx, timer, buf.as_slice());
+        recorder.check_memory(idx, buf.as_slice())?;
+        checkpoints.after_step(idx, buf.as_slice());
     }
 
     let out = buf.unwrap();
+    if let Some(tail) = tail {
+        tail(0, 1, &out);
+        checkpoints.finish();
+        return Ok(Vec::new());
+    }
     let v = *out
         .downcast::<Vec<T>>()
-        .map_err(|_| anyhow!("terminal type mismatch"))?;
-
-    manager.clear_checkpoints(&pipeline_id).ok();
-    eprintln!("[Checkpoint] Pipeline completed successfully, checkpoints cleared");
+        .map_err(|_| terminal_mismatch::<T>())?;
 
+    checkpoints.finish();
     Ok(v)
 }
 
 /// Execute a fully linearized chain **in parallel** with checkpointing support.
+///
+/// Like [`exec_seq_with_checkpointing`], but a fused block of stateless steps is one
+/// checkpoint opportunity, at its last step.
 #[cfg(feature = "checkpointing")]
 fn exec_par_with_checkpointing<T: 'static + Send + Sync + Clone>(
     chain: &[Node],
     partitions: usize,
-    config: CheckpointConfig,
+    stage_parts: &[Option<usize>],
+    mut checkpoints: CheckpointRun,
+    recorder: &StepRecorder,
+    tail: Option<PartitionTail<'_>>,
 ) -> Result<Vec<T>> {
-    use crate::checkpoint::{
-        CheckpointManager, CheckpointMetadata, CheckpointState, compute_checksum,
-        current_timestamp_ms, generate_pipeline_id,
-    };
-
-    let total_nodes = chain.len();
-    let mut manager = CheckpointManager::new(config)?;
-
-    let pipeline_id = generate_pipeline_id(&format!("{:?}:{}", chain.len(), partitions));
-
-    if manager.config.auto_recover
-        && let Some(checkpoint_path) = manager.find_latest_checkpoint(&pipeline_id)?
-    {
-        eprintln!("[Checkpoint] Found existing checkpoint, attempting recovery...");
-        match manager.load_checkpoint(&checkpoint_path) {
-            Ok(state) => {
-                eprintln!(
-                    "[Checkpoint] Recovered from node {} ({:.0}% complete)",
-                    state.completed_node_index, state.metadata.progress_percent
-                );
-            }
-            Err(e) => {
-                eprintln!("[Checkpoint] Failed to load checkpoint: {e}");
-            }
-        }
-    }
-
-    // Execute with checkpointing (simplified: checkpoint after major barriers only)
-    // Due to parallel execution complexity, we use the standard exec_par and checkpoint
-    // at coarser granularity. No limit is passed here because checkpointing pipelines
-    // do not currently support early termination.
-    let result = exec_par::<T>(chain, partitions, None);
-
+    let resume = checkpoints.recover();
+    // No limit is passed here because checkpointing pipelines do not currently
+    // support early termination.
+    let result = exec_par_from::<T>(
+        chain,
+        partitions,
+        stage_parts,
+        None,
+        recorder,
+        resume,
+        &mut |idx, parts| checkpoints.after_step(idx, parts),
+        tail,
+    );
     if result.is_ok() {
-        manager.clear_checkpoints(&pipeline_id).ok();
-        eprintln!("[Checkpoint] Pipeline completed successfully, checkpoints cleared");
-    } else {
-        let timestamp = current_timestamp_ms();
-        let metadata_str = format!("{pipeline_id}:0:{timestamp}:{partitions}");
-        let checksum = compute_checksum(metadata_str.as_bytes());
-        let state = CheckpointState {
-            pipeline_id,
-            completed_node_index: 0,
-            timestamp,
-            partition_count: partitions,
-            checksum,
-            exec_mode: format!("parallel:{partitions}"),
-            metadata: CheckpointMetadata {
-                total_nodes,
-                last_node_type: "Failed".to_string(),
-                progress_percent: 0,
-            },
-        };
-        manager.save_checkpoint(&state).ok();
+        checkpoints.finish();
     }
-
     result
 }
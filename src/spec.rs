@@ -0,0 +1,524 @@
+//! Serializable pipeline specs built from a registry of named transforms.
+//!
+//! Closures cannot be serialized, so a [`PipelineSpec`] describes a pipeline in terms of
+//! **registered** transforms instead: every step names an entry of a [`DoFnRegistry`],
+//! the JSON config it was built with, and the steps feeding it. The registry maps each
+//! name back to code, so the same spec can be rebuilt in another process (this is how
+//! the experimental `cluster` runner ships pipelines to its workers), checked into
+//! version control, and diffed.
+//!
+//! Steps enter a spec by being built through the registry —
+//! [`DoFnRegistry::source`], [`DoFnRegistry::apply`], and [`DoFnRegistry::apply_binary`]
+//! — rather than by calling `map`, `filter`, etc. directly. Names given with
+//! [`PCollection::with_name`](crate::PCollection::with_name) are kept.
+//!
+//! ```no_run
+//! use ironbeam::*;
+//! use ironbeam::spec::DoFnRegistry;
+//! use anyhow::Result;
+//! use serde_json::json;
+//!
+//! # fn main() -> Result<()> {
+//! let mut registry = DoFnRegistry::new();
+//! registry
+//!     .register_source("numbers", |p, cfg| {
+//!         let n = cfg["n"].as_u64().unwrap_or(0);
+//!         Ok(from_vec(p, (0..n).collect::<Vec<u64>>()))
+//!     })
+//!     .register_map("scale", |cfg| {
+//!         let factor = cfg["factor"].as_u64().unwrap_or(1);
+//!         Ok(move |x: &u64| x * factor)
+//!     });
+//!
+//! let p = Pipeline::default();
+//! let nums = registry.source::<u64>(&p, "numbers", json!({ "n": 10 }))?;
+//! let scaled = registry.apply::<u64, u64>(nums, "scale", json!({ "factor": 3 }))?;
+//! p.save_spec("pipeline.json")?;
+//!
+//! // Elsewhere, with the same registry:
+//! let spec = ironbeam::spec::PipelineSpec::load("pipeline.json")?;
+//! let (p2, nodes) = Pipeline::from_spec(&spec, &registry)?;
+//! let out = DirectRunner::default().run_collect::<u64>(&p2, nodes[1])?;
+//! # let _ = scaled;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::{Element, NodeId, PCollection, Pipeline};
+use anyhow::{Context, Result, anyhow, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::any::{TypeId, type_name};
+use std::collections::HashMap;
+use std::fs;
+use std::marker::PhantomData;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Version of the [`PipelineSpec`] format written by this build.
+pub const SPEC_VERSION: u32 = 1;
+
+/// A serializable description of a pipeline built from registered transforms.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PipelineSpec {
+    /// Format version, see [`SPEC_VERSION`].
+    pub version: u32,
+    /// Steps in build order; a step only consumes earlier steps.
+    pub steps: Vec<StepSpec>,
+}
+
+/// One registered transform application in a [`PipelineSpec`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StepSpec {
+    /// Name of the transform in the [`DoFnRegistry`].
+    pub transform: String,
+    /// Kind of the node the transform produced (e.g. `"GroupByKey"`, see
+    /// [`Node::kind`](crate::node::Node::kind)), checked again on rebuild.
+    pub kind: String,
+    /// Indices of the steps this step consumes, in argument order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub inputs: Vec<usize>,
+    /// Config passed to the transform's factory.
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub config: Value,
+    /// Name of the step's output node, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl PipelineSpec {
+    /// Render the spec as pretty-printed JSON.
+    ///
+    /// # Errors
+    /// Returns an error if a config value cannot be serialized.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).context("serialize pipeline spec")
+    }
+
+    /// Parse a spec from JSON.
+    ///
+    /// # Errors
+    /// Returns an error if `json` is not a valid spec.
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json).context("parse pipeline spec")
+    }
+
+    /// Read a spec written by [`Pipeline::save_spec`].
+    ///
+    /// # Errors
+    /// Returns an error if the file cannot be read or is not a valid spec.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let json = fs::read_to_string(path)
+            .with_context(|| format!("read pipeline spec {}", path.display()))?;
+        Self::from_json(&json)
+    }
+}
+
+/// How a node was built through a [`DoFnRegistry`], recorded on the pipeline.
+#[derive(Clone)]
+pub(crate) struct SpecRecord {
+    pub transform: String,
+    pub config: Value,
+    pub inputs: Vec<NodeId>,
+}
+
+type BuildFn = dyn Fn(&Pipeline, &[NodeId], &Value) -> Result<NodeId> + Send + Sync;
+
+#[derive(Clone)]
+struct Entry {
+    inputs: Vec<(TypeId, &'static str)>,
+    output: (TypeId, &'static str),
+    build: Arc<BuildFn>,
+}
+
+/// Named transforms that can be recorded in, and rebuilt from, a [`PipelineSpec`].
+///
+/// Each entry is a factory: given its JSON config (and its input collections), it
+/// builds the transform with ordinary combinators. Entries are typed; applying one to
+/// collections of the wrong element type is an error.
+#[derive(Clone, Default)]
+pub struct DoFnRegistry {
+    entries: HashMap<String, Entry>,
+}
+
+fn collection<T>(p: &Pipeline, id: NodeId) -> PCollection<T> {
+    PCollection {
+        pipeline: p.clone(),
+        id,
+        _t: PhantomData,
+    }
+}
+
+fn type_of<T: 'static>() -> (TypeId, &'static str) {
+    (TypeId::of::<T>(), type_name::<T>())
+}
+
+impl DoFnRegistry {
+    /// Create an empty registry.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether a transform named `name` is registered.
+    #[must_use]
+    pub fn contains(&self, name: &str) -> bool {
+        self.entries.contains_key(name)
+    }
+
+    /// Register a source: `f` builds a `PCollection<T>` on the given pipeline from its
+    /// config. Replaces any entry with the same name.
+    pub fn register_source<T, F>(&mut self, name: impl Into<String>, f: F) -> &mut Self
+    where
+        T: Element,
+        F: Fn(&Pipeline, &Value) -> Result<PCollection<T>> + Send + Sync + 'static,
+    {
+        self.insert(name.into(), Vec::new(), type_of::<T>(), move |p, _, cfg| {
+            Ok(f(p, cfg)?.id)
+        })
+    }
+
+    /// Register a single-input transform: `f` extends a `PCollection<I>` into a
+    /// `PCollection<O>` according to its config. Replaces any entry with the same name.
+    pub fn register_transform<I, O, F>(&mut self, name: impl Into<String>, f: F) -> &mut Self
+    where
+        I: Element,
+        O: Element,
+        F: Fn(PCollection<I>, &Value) -> Result<PCollection<O>> + Send + Sync + 'static,
+    {
+        self.insert(
+            name.into(),
+            vec![type_of::<I>()],
+            type_of::<O>(),
+            move |p, inputs, cfg| Ok(f(collection(p, inputs[0]), cfg)?.id),
+        )
+    }
+
+    /// Register a two-input transform, such as a join or a flatten of two collections.
+    /// Replaces any entry with the same name.
+    pub fn register_binary<A, B, O, F>(&mut self, name: impl Into<String>, f: F) -> &mut Self
+    where
+        A: Element,
+        B: Element,
+        O: Element,
+        F: Fn(PCollection<A>, PCollection<B>, &Value) -> Result<PCollection<O>>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.insert(
+            name.into(),
+            vec![type_of::<A>(), type_of::<B>()],
+            type_of::<O>(),
+            move |p, inputs, cfg| {
+                Ok(f(collection(p, inputs[0]), collection(p, inputs[1]), cfg)?.id)
+            },
+        )
+    }
+
+    /// Register a [`map`](PCollection::map) whose function `f` builds from the config.
+    pub fn register_map<I, O, F, G>(&mut self, name: impl Into<String>, f: F) -> &mut Self
+    where
+        I: Element,
+        O: Element,
+        F: Fn(&Value) -> Result<G> + Send + Sync + 'static,
+        G: Fn(&I) -> O + Send + Sync + 'static,
+    {
+        self.register_transform(name, move |input: PCollection<I>, cfg| {
+            Ok(input.map(f(cfg)?))
+        })
+    }
+
+    /// Register a [`filter`](PCollection::filter) whose predicate `f` builds from the
+    /// config.
+    pub fn register_filter<T, F, G>(&mut self, name: impl Into<String>, f: F) -> &mut Self
+    where
+        T: Element,
+        F: Fn(&Value) -> Result<G> + Send + Sync + 'static,
+        G: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        self.register_transform(name, move |input: PCollection<T>, cfg| {
+            Ok(input.filter(f(cfg)?))
+        })
+    }
+
+    /// Register a [`flat_map`](PCollection::flat_map) whose function `f` builds from the
+    /// config.
+    pub fn register_flat_map<I, O, F, G>(&mut self, name: impl Into<String>, f: F) -> &mut Self
+    where
+        I: Element,
+        O: Element,
+        F: Fn(&Value) -> Result<G> + Send + Sync + 'static,
+        G: Fn(&I) -> Vec<O> + Send + Sync + 'static,
+    {
+        self.register_transform(name, move |input: PCollection<I>, cfg| {
+            Ok(input.flat_map(f(cfg)?))
+        })
+    }
+
+    fn insert(
+        &mut self,
+        name: String,
+        inputs: Vec<(TypeId, &'static str)>,
+        output: (TypeId, &'static str),
+        build: impl Fn(&Pipeline, &[NodeId], &Value) -> Result<NodeId> + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.entries.insert(
+            name,
+            Entry {
+                inputs,
+                output,
+                build: Arc::new(build),
+            },
+        );
+        self
+    }
+
+    /// Build the registered source `name` on `p` and record it in the pipeline's spec.
+    ///
+    /// # Errors
+    /// Returns an error if `name` is not a source producing `T`, if `config` cannot be
+    /// converted to JSON, or if the factory fails.
+    pub fn source<T: Element>(
+        &self,
+        p: &Pipeline,
+        name: &str,
+        config: impl Serialize,
+    ) -> Result<PCollection<T>> {
+        let id = self.build(p, name, &[], TypeId::of::<T>(), to_config(config)?)?;
+        Ok(collection(p, id))
+    }
+
+    /// Apply the registered single-input transform `name` to `input` and record it in
+    /// the pipeline's spec.
+    ///
+    /// # Errors
+    /// Returns an error if `name` is not a transform from `I` to `O`, if `config` cannot
+    /// be converted to JSON, or if the factory fails.
+    pub fn apply<I: Element, O: Element>(
+        &self,
+        input: PCollection<I>,
+        name: &str,
+        config: impl Serialize,
+    ) -> Result<PCollection<O>> {
+        let id = self.build(
+            &input.pipeline,
+            name,
+            &[(input.id, TypeId::of::<I>())],
+            TypeId::of::<O>(),
+            to_config(config)?,
+        )?;
+        Ok(collection(&input.pipeline, id))
+    }
+
+    /// Apply the registered two-input transform `name` to `a` and `b` and record it in
+    /// the pipeline's spec.
+    ///
+    /// # Errors
+    /// Returns an error if `name` is not a transform from `(A, B)` to `O`, if `config`
+    /// cannot be converted to JSON, or if the factory fails.
+    pub fn apply_binary<A: Element, B: Element, O: Element>(
+        &self,
+        a: PCollection<A>,
+        b: PCollection<B>,
+        name: &str,
+        config: impl Serialize,
+    ) -> Result<PCollection<O>> {
+        let id = self.build(
+            &a.pipeline,
+            name,
+            &[(a.id, TypeId::of::<A>()), (b.id, TypeId::of::<B>())],
+            TypeId::of::<O>(),
+            to_config(config)?,
+        )?;
+        Ok(collection(&a.pipeline, id))
+    }
+
+    /// Type-check and run the factory for `name`, then record the spec of its output.
+    fn build(
+        &self,
+        p: &Pipeline,
+        name: &str,
+        inputs: &[(NodeId, TypeId)],
+        output: TypeId,
+        config: Value,
+    ) -> Result<NodeId> {
+        let entry = self
+            .entries
+            .get(name)
+            .ok_or_else(|| anyhow!("transform '{name}' is not registered"))?;
+        if entry.inputs.len() != inputs.len() {
+            bail!(
+                "transform '{name}' takes {} inputs, got {}",
+                entry.inputs.len(),
+                inputs.len()
+            );
+        }
+        for (i, ((_, got), (want, want_name))) in inputs.iter().zip(&entry.inputs).enumerate() {
+            if got != want {
+                bail!("input {i} of transform '{name}' must be a collection of {want_name}");
+            }
+        }
+        if entry.output.0 != output {
+            bail!(
+                "transform '{name}' produces a collection of {}",
+                entry.output.1
+            );
+        }
+
+        let input_ids: Vec<NodeId> = inputs.iter().map(|(id, _)| *id).collect();
+        let id = (entry.build)(p, &input_ids, &config)
+            .with_context(|| format!("build transform '{name}'"))?;
+        if input_ids.contains(&id) {
+            bail!("transform '{name}' must add a node to the pipeline");
+        }
+        p.record_spec(
+            id,
+            SpecRecord {
+                transform: name.to_string(),
+                config,
+                inputs: input_ids,
+            },
+        );
+        Ok(id)
+    }
+}
+
+fn to_config(config: impl Serialize) -> Result<Value> {
+    serde_json::to_value(config).context("convert transform config to JSON")
+}
+
+impl Pipeline {
+    /// Describe this pipeline as a [`PipelineSpec`].
+    ///
+    /// The spec lists every step built through a [`DoFnRegistry`], in build order.
+    ///
+    /// # Errors
+    /// Returns an error if a registered step consumes a collection that was not itself
+    /// built through the registry (e.g. the output of a plain `map`), since such a
+    /// step could not be rebuilt.
+    ///
+    /// # Panics
+    ///
+    /// If the pipeline mutex is poisoned by a concurrent panic.
+    pub fn spec(&self) -> Result<PipelineSpec> {
+        let g = self.inner.lock().unwrap();
+        let mut ids: Vec<NodeId> = g.specs.keys().copied().collect();
+        ids.sort_by_key(NodeId::raw);
+        let index: HashMap<NodeId, usize> =
+            ids.iter().enumerate().map(|(i, id)| (*id, i)).collect();
+
+        let steps = ids
+            .iter()
+            .map(|id| {
+                let record = &g.specs[id];
+                let inputs = record
+                    .inputs
+                    .iter()
+                    .map(|input| {
+                        index.get(input).copied().ok_or_else(|| {
+                            anyhow!(
+                                "transform '{}' consumes node #{}, which was not built through a DoFnRegistry",
+                                record.transform,
+                                input.raw()
+                            )
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                Ok(StepSpec {
+                    transform: record.transform.clone(),
+                    kind: g.nodes.get(id).map_or("Unknown", |n| n.kind()).to_string(),
+                    inputs,
+                    config: record.config.clone(),
+                    name: g.node_names.get(id).cloned(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(PipelineSpec {
+            version: SPEC_VERSION,
+            steps,
+        })
+    }
+
+    /// Index of `id` in [`spec`](Self::spec)'s steps, or `None` if `id` was not built
+    /// through a [`DoFnRegistry`].
+    #[cfg(feature = "cluster")]
+    pub(crate) fn spec_step(&self, id: NodeId) -> Option<usize> {
+        let g = self.inner.lock().unwrap();
+        g.specs
+            .contains_key(&id)
+            .then(|| g.specs.keys().filter(|k| k.raw() < id.raw()).count())
+    }
+
+    /// Write this pipeline's [`spec`](Self::spec) to `path` as pretty-printed JSON.
+    ///
+    /// # Errors
+    /// Returns an error if the spec cannot be built or the file cannot be written.
+    pub fn save_spec(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        fs::write(path, self.spec()?.to_json()?)
+            .with_context(|| format!("write pipeline spec {}", path.display()))
+    }
+
+    /// Rebuild a pipeline from `spec`, resolving transforms through `registry`.
+    ///
+    /// Returns the pipeline and the output [`NodeId`] of every step, indexed like
+    /// [`PipelineSpec::steps`]. The rebuilt pipeline records the same spec, so it can be
+    /// saved again.
+    ///
+    /// # Errors
+    /// Returns an error if the spec version is unsupported, a transform is not
+    /// registered, input types do not line up, a step references a later step, a
+    /// factory fails, or a transform now produces a different node kind than the spec
+    /// recorded.
+    ///
+    /// # Panics
+    ///
+    /// If the pipeline mutex is poisoned by a concurrent panic.
+    pub fn from_spec(spec: &PipelineSpec, registry: &DoFnRegistry) -> Result<(Self, Vec<NodeId>)> {
+        if spec.version != SPEC_VERSION {
+            bail!(
+                "unsupported pipeline spec version {} (expected {SPEC_VERSION})",
+                spec.version
+            );
+        }
+        let p = Self::default();
+        let mut built: Vec<(NodeId, TypeId)> = Vec::with_capacity(spec.steps.len());
+        for (idx, step) in spec.steps.iter().enumerate() {
+            let inputs = step
+                .inputs
+                .iter()
+                .map(|&i| {
+                    built.get(i).copied().ok_or_else(|| {
+                        anyhow!("step {idx} consumes step {i}, which does not precede it")
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            let output = registry
+                .entries
+                .get(&step.transform)
+                .ok_or_else(|| anyhow!("transform '{}' is not registered", step.transform))?
+                .output
+                .0;
+            let id = registry
+                .build(&p, &step.transform, &inputs, output, step.config.clone())
+                .with_context(|| format!("rebuild step {idx}"))?;
+            let kind = p.inner.lock().unwrap().nodes.get(&id).map(|n| n.kind());
+            if kind != Some(step.kind.as_str()) {
+                bail!(
+                    "step {idx} ('{}') built a {} node, but the spec recorded {}",
+                    step.transform,
+                    kind.unwrap_or("missing"),
+                    step.kind
+                );
+            }
+            if let Some(name) = &step.name {
+                p.set_node_name(id, name.clone());
+            }
+            built.push((id, output));
+        }
+        let nodes = built.into_iter().map(|(id, _)| id).collect();
+        Ok((p, nodes))
+    }
+}
@@ -34,6 +34,7 @@
 //! // Memory tracking and spilling will happen automatically during pipeline execution
 //! ```
 
+use crate::io::compression::{Compression, compress_to_vec, decompress_to_vec};
 use anyhow::{Context, Result, anyhow};
 use serde::{Deserialize, Serialize};
 use std::fs::{File, create_dir_all, remove_file};
@@ -42,6 +43,14 @@ use std::path::PathBuf;
 use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
 
+/// Codec for spill files when [`SpillConfig::compress`] is set: zstd at its default
+/// level, or none without the `compression-zstd` feature.
+const SPILL_COMPRESSION: Compression = if cfg!(feature = "compression-zstd") {
+    Compression::Zstd { level: 3 }
+} else {
+    Compression::None
+};
+
 /// Configuration for automatic memory spilling.
 ///
 /// This configuration determines when and where data should be spilled to disk
@@ -66,7 +75,9 @@ pub struct SpillConfig {
     /// Directory where spilled data is stored.
     pub spill_directory: PathBuf,
 
-    /// Whether to compress spilled data (reduces disk usage, increases CPU).
+    /// Whether to compress spilled data (reduces disk usage, increases CPU). Spill
+    /// files use zstd through [`crate::io::compression`] when `compression-zstd` is
+    /// enabled and are stored uncompressed otherwise.
     pub compress: bool,
 
     /// Minimum size (in bytes) for a partition to be considered for spilling.
@@ -295,19 +306,11 @@ impl SpillManager {
 
         let mut writer = BufWriter::new(file);
 
-        let serialized = if self.config.compress {
-            #[cfg(feature = "compression-zstd")]
-            {
-                let bytes = postcard::to_allocvec(data)?;
-                zstd::encode_all(&bytes[..], 3)?
-            }
-            #[cfg(not(feature = "compression-zstd"))]
-            {
-                postcard::to_allocvec(data)?
-            }
-        } else {
-            postcard::to_allocvec(data)?
-        };
+        let mut serialized = postcard::to_allocvec(data)?;
+        if self.config.compress {
+            serialized = compress_to_vec(&serialized, SPILL_COMPRESSION)
+                .context("Failed to compress spill data")?;
+        }
 
         writer
             .write_all(&serialized)
@@ -335,19 +338,10 @@ impl SpillManager {
             .read_to_end(&mut buffer)
             .context("Failed to read spill file")?;
 
-        let data: Vec<T> = if self.config.compress {
-            #[cfg(feature = "compression-zstd")]
-            {
-                let decompressed = zstd::decode_all(&buffer[..])?;
-                postcard::from_bytes(&decompressed)?
-            }
-            #[cfg(not(feature = "compression-zstd"))]
-            {
-                postcard::from_bytes(&buffer)?
-            }
-        } else {
-            postcard::from_bytes(&buffer)?
-        };
+        if self.config.compress {
+            buffer = decompress_to_vec(buffer).context("Failed to decompress spill data")?;
+        }
+        let data: Vec<T> = postcard::from_bytes(&buffer)?;
 
         Ok(data)
     }
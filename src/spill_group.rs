@@ -0,0 +1,359 @@
+//! External (spill-to-disk) grouping for `GroupByKey`.
+//!
+//! The in-memory `GroupByKey` builds a `HashMap<K, Vec<V>>` per partition and then a
+//! merged map, so at its peak the input pairs, the per-partition maps, and the merged
+//! groups are all resident at once. When the runner is given a memory budget
+//! ([`DirectRunner::memory_budget`](crate::DirectRunner::memory_budget)), group-by-key
+//! runs as an [`ExternalGroup`] instead:
+//!
+//! 1. **Scatter** (the local stage, per partition) — the partition's pairs are ordered by
+//!    `hash(K)` bucket. While the partitions kept so far fit in the budget the partition
+//!    stays in memory; otherwise its pairs are postcard-encoded to a spill file and the
+//!    partition is dropped, so the input never has to be resident as a whole.
+//! 2. **Gather** (the merge) — runs of adjacent buckets are read back from every
+//!    partition and grouped in memory, each run sized to fit comfortably within the
+//!    budget.
+//!
+//! All of a key's values land in the same bucket, so each run produces complete groups.
+//! Values keep their input order within a group. The grouped output itself is still a
+//! materialized `Vec<(K, Vec<V>)>`; the budget bounds the *working* memory of the
+//! grouping, not the size of its result, and a single key's group is never split.
+//!
+//! [`group_pairs_external`] does the same for partitions that are already in memory,
+//! scattering them to bucket files when their estimated size exceeds the budget.
+//!
+//! Spill files live in the configured spill directory and are removed when grouping
+//! finishes (or fails).
+
+use anyhow::{Context, Result, anyhow};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Upper bound on the number of bucket files opened by a single external grouping.
+pub const MAX_SPILL_BUCKETS: usize = 256;
+
+/// Number of leading pairs encoded to estimate the average serialized pair size.
+const SIZE_SAMPLE: usize = 64;
+
+/// Group `(K, V)` partitions by key, spilling to disk when they exceed `memory_budget`.
+///
+/// `parts` are the raw (ungrouped) `Vec<(K, V)>` partitions. If their estimated size fits
+/// in `memory_budget` bytes they are grouped in memory; otherwise pairs are scattered to
+/// hash buckets under `spill_dir` and grouped one bucket at a time. Values keep their
+/// input order (partition by partition) within each group. A bucket holding a key whose
+/// values alone exceed the budget is still grouped whole.
+///
+/// # Errors
+///
+/// Returns an error if a bucket file cannot be created, written, or read back, or if
+/// a pair fails to serialize or deserialize.
+pub fn group_pairs_external<K, V>(
+    parts: Vec<Vec<(K, V)>>,
+    memory_budget: usize,
+    spill_dir: &Path,
+) -> Result<Vec<(K, Vec<V>)>>
+where
+    K: Eq + Hash + Serialize + DeserializeOwned,
+    V: Serialize + DeserializeOwned,
+{
+    let estimated = estimate_bytes(&parts);
+    if estimated <= memory_budget {
+        let mut groups: HashMap<K, Vec<V>> = HashMap::new();
+        for (k, v) in parts.into_iter().flatten() {
+            groups.entry(k).or_default().push(v);
+        }
+        return Ok(groups.into_iter().collect());
+    }
+
+    // Aim for buckets of about half the budget so decoding plus grouping stays within it.
+    let buckets = estimated
+        .div_ceil(memory_budget.max(1) / 2 + 1)
+        .clamp(2, MAX_SPILL_BUCKETS);
+
+    std::fs::create_dir_all(spill_dir).context("Failed to create spill directory")?;
+    let dir = tempfile::Builder::new()
+        .prefix("ironbeam-gbk-")
+        .tempdir_in(spill_dir)
+        .context("Failed to create grouping spill directory")?;
+
+    let mut writers = (0..buckets)
+        .map(|b| {
+            File::create(dir.path().join(format!("bucket-{b}.bin")))
+                .map(BufWriter::new)
+                .context("Failed to create bucket file")
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    for part in parts {
+        for (k, v) in part {
+            let bucket = bucket_of(&k, buckets);
+            let bytes = postcard::to_allocvec(&(k, v)).context("Failed to encode pair")?;
+            let len = u32::try_from(bytes.len()).map_err(|_| anyhow!("pair too large to spill"))?;
+            let w = &mut writers[bucket];
+            w.write_all(&len.to_le_bytes())
+                .and_then(|()| w.write_all(&bytes))
+                .context("Failed to write bucket file")?;
+        }
+    }
+    for mut w in writers {
+        w.flush().context("Failed to flush bucket file")?;
+    }
+
+    let mut out: Vec<(K, Vec<V>)> = Vec::new();
+    for b in 0..buckets {
+        let path = dir.path().join(format!("bucket-{b}.bin"));
+        let mut reader = BufReader::new(File::open(&path).context("Failed to open bucket file")?);
+        let mut groups: HashMap<K, Vec<V>> = HashMap::new();
+        let mut len_buf = [0u8; 4];
+        let mut buf = Vec::new();
+        loop {
+            match reader.read_exact(&mut len_buf) {
+                Ok(()) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+                Err(e) => return Err(e).context("Failed to read bucket file"),
+            }
+            buf.resize(u32::from_le_bytes(len_buf) as usize, 0);
+            reader
+                .read_exact(&mut buf)
+                .context("Truncated bucket file")?;
+            let (k, v): (K, V) = postcard::from_bytes(&buf).context("Failed to decode pair")?;
+            groups.entry(k).or_default().push(v);
+        }
+        std::fs::remove_file(&path).ok();
+        out.extend(groups);
+    }
+    Ok(out)
+}
+
+/// Estimate the in-memory footprint of `parts` from the shallow pair size plus the
+/// average encoded size of a small sample (a proxy for heap-owned data).
+fn estimate_bytes<K: Serialize, V: Serialize>(parts: &[Vec<(K, V)>]) -> usize {
+    let total: usize = parts.iter().map(Vec::len).sum();
+    if total == 0 {
+        return 0;
+    }
+    let (sampled, encoded) =
+        parts
+            .iter()
+            .flatten()
+            .take(SIZE_SAMPLE)
+            .fold((0usize, 0usize), |(n, bytes), kv| {
+                let len = postcard::to_allocvec(kv).map_or(0, |b| b.len());
+                (n + 1, bytes + len)
+            });
+    let per_pair = size_of::<(K, V)>() + encoded / sampled.max(1);
+    total.saturating_mul(per_pair)
+}
+
+/// Deterministic bucket index for `key`.
+fn bucket_of<K: Hash>(key: &K, buckets: usize) -> usize {
+    let mut h = DefaultHasher::new();
+    key.hash(&mut h);
+    #[allow(clippy::cast_possible_truncation)]
+    let idx = (h.finish() % buckets as u64) as usize;
+    idx
+}
+
+/// Hash buckets each partition is ordered into by [`ExternalGroup::scatter`]. The gather
+/// groups runs of adjacent buckets at a time, so this bounds how finely the work can be
+/// split.
+const SCATTER_BUCKETS: usize = 256;
+
+/// One external group-by-key run, split into a per-partition scatter (the local stage)
+/// and a single gather (the merge).
+///
+/// [`scatter`](Self::scatter) orders a partition's pairs by hash bucket. The partition
+/// stays in memory while the raw pairs kept by all partitions fit in the budget;
+/// otherwise it is written to an anonymous file in the spill directory and dropped, so
+/// the whole input is never resident at once. [`gather`](Self::gather) then reads runs of
+/// adjacent buckets from every partition, in partition order, and groups each run in
+/// memory, sizing the runs to about half the budget.
+///
+/// All of a key's values share a bucket, so one run always holds whole groups. A single
+/// key whose values alone exceed the budget is therefore still grouped in one piece, and
+/// the grouping overshoots the budget by about that group's size.
+pub struct ExternalGroup {
+    budget: usize,
+    spill_dir: PathBuf,
+    /// Estimated bytes of the partitions kept in memory so far.
+    resident: AtomicUsize,
+}
+
+/// One partition scattered by [`ExternalGroup::scatter`], with its pairs ordered by
+/// bucket.
+pub struct ScatteredPart<K, V> {
+    /// Number of pairs in each bucket.
+    counts: Vec<usize>,
+    /// Estimated in-memory bytes of each bucket's pairs.
+    bytes: Vec<usize>,
+    pairs: Scattered<K, V>,
+}
+
+enum Scattered<K, V> {
+    Resident(Vec<(K, V)>),
+    Spilled(File),
+}
+
+/// Sequential reader over a [`ScatteredPart`]'s pairs.
+enum PartReader<K, V> {
+    Resident(std::vec::IntoIter<(K, V)>),
+    Spilled(BufReader<File>, Vec<u8>),
+}
+
+impl<K, V> PartReader<K, V>
+where
+    K: DeserializeOwned,
+    V: DeserializeOwned,
+{
+    fn next_pair(&mut self) -> Result<(K, V)> {
+        match self {
+            Self::Resident(pairs) => pairs.next().context("Scattered partition ended early"),
+            Self::Spilled(reader, buf) => {
+                let mut len_buf = [0u8; 4];
+                reader
+                    .read_exact(&mut len_buf)
+                    .context("Failed to read spilled partition")?;
+                buf.resize(u32::from_le_bytes(len_buf) as usize, 0);
+                reader
+                    .read_exact(buf)
+                    .context("Truncated spilled partition")?;
+                postcard::from_bytes(buf).context("Failed to decode pair")
+            }
+        }
+    }
+}
+
+impl ExternalGroup {
+    /// Start a grouping that keeps at most `memory_budget` bytes of raw pairs in memory
+    /// and spills the rest under `spill_dir`.
+    #[must_use]
+    pub fn new(memory_budget: usize, spill_dir: &Path) -> Self {
+        Self {
+            budget: memory_budget,
+            spill_dir: spill_dir.to_path_buf(),
+            resident: AtomicUsize::new(0),
+        }
+    }
+
+    /// Order one partition's pairs by bucket, spilling them if keeping them would exceed
+    /// the budget.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the spill file cannot be created or written, or if a pair
+    /// fails to serialize.
+    pub fn scatter<K, V>(&self, mut pairs: Vec<(K, V)>) -> Result<ScatteredPart<K, V>>
+    where
+        K: Hash + Serialize,
+        V: Serialize,
+    {
+        // A stable sort keeps each key's values in input order.
+        pairs.sort_by_cached_key(|(k, _)| bucket_of(k, SCATTER_BUCKETS));
+        let mut counts = vec![0usize; SCATTER_BUCKETS];
+        for (k, _) in &pairs {
+            counts[bucket_of(k, SCATTER_BUCKETS)] += 1;
+        }
+
+        let estimated = estimate_bytes(std::slice::from_ref(&pairs));
+        let keep = self
+            .resident
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |held| {
+                held.checked_add(estimated).filter(|&n| n <= self.budget)
+            })
+            .is_ok();
+        if keep {
+            let per_pair = estimated / pairs.len().max(1);
+            let bytes = counts.iter().map(|n| n * per_pair).collect();
+            return Ok(ScatteredPart {
+                counts,
+                bytes,
+                pairs: Scattered::Resident(pairs),
+            });
+        }
+
+        std::fs::create_dir_all(&self.spill_dir).context("Failed to create spill directory")?;
+        let mut file = BufWriter::new(
+            tempfile::tempfile_in(&self.spill_dir).context("Failed to create spill file")?,
+        );
+        let mut bytes = vec![0usize; SCATTER_BUCKETS];
+        for (k, v) in pairs {
+            let bucket = bucket_of(&k, SCATTER_BUCKETS);
+            let encoded = postcard::to_allocvec(&(k, v)).context("Failed to encode pair")?;
+            let len =
+                u32::try_from(encoded.len()).map_err(|_| anyhow!("pair too large to spill"))?;
+            file.write_all(&len.to_le_bytes())
+                .and_then(|()| file.write_all(&encoded))
+                .context("Failed to write spill file")?;
+            bytes[bucket] += size_of::<(K, V)>() + encoded.len();
+        }
+        let mut file = file.into_inner().context("Failed to flush spill file")?;
+        file.seek(SeekFrom::Start(0))
+            .context("Failed to rewind spill file")?;
+        Ok(ScatteredPart {
+            counts,
+            bytes,
+            pairs: Scattered::Spilled(file),
+        })
+    }
+
+    /// Group every scattered partition's pairs by key, a run of buckets at a time.
+    /// Values keep their input order (partition by partition) within each group.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a spill file cannot be read back or a pair fails to
+    /// deserialize.
+    pub fn gather<K, V>(&self, parts: Vec<ScatteredPart<K, V>>) -> Result<Vec<(K, Vec<V>)>>
+    where
+        K: Eq + Hash + DeserializeOwned,
+        V: DeserializeOwned,
+    {
+        let run_budget = (self.budget / 2).max(1);
+        let mut bucket_bytes = vec![0usize; SCATTER_BUCKETS];
+        for part in &parts {
+            for (total, b) in bucket_bytes.iter_mut().zip(&part.bytes) {
+                *total += b;
+            }
+        }
+        let (counts, mut readers): (Vec<_>, Vec<_>) = parts
+            .into_iter()
+            .map(|part| {
+                let reader = match part.pairs {
+                    Scattered::Resident(pairs) => PartReader::Resident(pairs.into_iter()),
+                    Scattered::Spilled(file) => {
+                        PartReader::Spilled(BufReader::new(file), Vec::new())
+                    }
+                };
+                (part.counts, reader)
+            })
+            .unzip();
+
+        let mut out: Vec<(K, Vec<V>)> = Vec::new();
+        let mut start = 0;
+        while start < SCATTER_BUCKETS {
+            let mut end = start + 1;
+            let mut run_bytes = bucket_bytes[start];
+            while end < SCATTER_BUCKETS && run_bytes + bucket_bytes[end] <= run_budget {
+                run_bytes += bucket_bytes[end];
+                end += 1;
+            }
+            let mut groups: HashMap<K, Vec<V>> = HashMap::new();
+            for (counts, reader) in counts.iter().zip(&mut readers) {
+                for _ in 0..counts[start..end].iter().sum::<usize>() {
+                    let (k, v) = reader.next_pair()?;
+                    groups.entry(k).or_default().push(v);
+                }
+            }
+            out.extend(groups);
+            start = end;
+        }
+        Ok(out)
+    }
+}
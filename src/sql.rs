@@ -0,0 +1,779 @@
+//! SQL frontend over `PCollection`s (feature `sql`).
+//!
+//! [`Pipeline::sql`] parses a small SQL subset and lowers it onto the ordinary
+//! transforms, so a query builds the same graph a hand-written pipeline would:
+//!
+//! | SQL                          | Lowered onto                                          |
+//! |------------------------------|-------------------------------------------------------|
+//! | `FROM t`                     | a `map` converting each element of `t` to a [`Row`]   |
+//! | `[INNER \| LEFT] JOIN ... ON` | `key_by` on both sides + `join_inner` / `join_left`   |
+//! | `WHERE`                      | `filter`                                              |
+//! | `GROUP BY` + aggregates      | `key_by` + `combine_values` (`combine_globally` when there is no `GROUP BY`) |
+//! | `SELECT` list                | `map`                                                 |
+//!
+//! Input tables are any `PCollection<T>` with `T: Serialize`, converted to rows with
+//! [`Row::from_serialize`]: struct fields become columns, and a keyed collection
+//! `(K, V)` has a `key` column plus the fields of `V` (or a `value` column). The result
+//! is a `PCollection<Row>`; use [`Row::deserialize`] to get typed values back.
+//!
+//! ### Supported SQL
+//! - `SELECT *` or expressions with optional `AS` aliases
+//! - `FROM` one table, followed by any number of `[INNER] JOIN` / `LEFT [OUTER] JOIN`
+//!   clauses; `ON` must be an `AND` of equalities between the joined table and earlier
+//!   ones, with table-qualified columns (`a.id = b.id`)
+//! - `WHERE` with comparisons (`=`, `<>`, `!=`, `<`, `<=`, `>`, `>=`), `AND`/`OR`/`NOT`,
+//!   `IS [NOT] NULL`, arithmetic (`+ - * / %`), and string/number/boolean/`NULL` literals
+//! - `GROUP BY` expressions with `COUNT(*)`, `COUNT`, `SUM`, `MIN`, `MAX`, `AVG`
+//!
+//! `NULL` follows SQL rules: comparisons with `NULL` are `NULL`, `WHERE` keeps only
+//! rows where the condition is `TRUE`, `NULL` join keys never match, and aggregates skip
+//! `NULL` inputs. A column missing from a row reads as `NULL`.
+//!
+//! Parse and planning errors are returned by [`Pipeline::sql`]. Errors while evaluating
+//! rows (for example comparing a string with a number) fail the run when it executes.
+//!
+//! ### Example
+//! ```no_run
+//! use ironbeam::*;
+//! # use anyhow::Result;
+//!
+//! # fn main() -> Result<()> {
+//! let p = Pipeline::default();
+//! let sales = from_vec(&p, vec![
+//!     ("eu".to_string(), 10u64),
+//!     ("us".to_string(), 5),
+//!     ("eu".to_string(), 7),
+//! ]);
+//!
+//! let totals = p.sql("SELECT key, SUM(value) AS total FROM t GROUP BY key", [("t", sales)])?;
+//! for row in totals.collect_seq()? {
+//!     println!("{} {}", row.get("key").unwrap(), row.get("total").unwrap());
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+mod parser;
+
+use crate::{CombineFn, Element, PCollection, Pipeline, Row};
+use anyhow::{Result, bail};
+use parser::{AggFunc, BinOp, Expr, SelectItem, TableRef};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// An input table for [`Pipeline::sql`]: a collection converted to [`Row`]s.
+///
+/// Created from any `PCollection<T>` with `T: Serialize` via `From`/`Into`. Passing
+/// collections directly works when all tables share one element type; otherwise convert
+/// each one with `.into()`:
+///
+/// ```no_run
+/// # use ironbeam::*;
+/// # use ironbeam::sql::SqlTable;
+/// # fn main() -> anyhow::Result<()> {
+/// # let p = Pipeline::default();
+/// let users = from_vec(&p, vec![(1u32, "ada".to_string())]);
+/// let orders = from_vec(&p, vec![(1u32, 9.5f64)]);
+/// let joined = p.sql(
+///     "SELECT u.value AS name, o.value AS amount FROM users u JOIN orders o ON u.key = o.key",
+///     [("users", SqlTable::from(users)), ("orders", orders.into())],
+/// )?;
+/// # Ok(()) }
+/// ```
+pub struct SqlTable {
+    rows: PCollection<Row>,
+}
+
+impl<T: Element + Serialize> From<PCollection<T>> for SqlTable {
+    fn from(collection: PCollection<T>) -> Self {
+        let rows =
+            collection.map(|x: &T| Row::from_serialize(x).unwrap_or_else(|e| panic!("sql: {e:#}")));
+        Self { rows }
+    }
+}
+
+impl Pipeline {
+    /// Run a SQL query over named collections, returning the result rows.
+    ///
+    /// `tables` maps the table names used in the query to collections of this pipeline.
+    /// The query is planned immediately and added to the graph as ordinary transforms;
+    /// nothing runs until the result is collected. See the [module docs](crate::sql) for
+    /// the supported subset.
+    ///
+    /// # Errors
+    /// Returns an error if the query does not parse, uses unsupported SQL, names a table
+    /// that is not in `tables`, or a table belongs to another pipeline.
+    pub fn sql<'a, I, S>(&self, query: &str, tables: I) -> Result<PCollection<Row>>
+    where
+        I: IntoIterator<Item = (&'a str, S)>,
+        S: Into<SqlTable>,
+    {
+        let mut inputs = HashMap::new();
+        for (name, table) in tables {
+            let table: SqlTable = table.into();
+            if !Arc::ptr_eq(&table.rows.pipeline.inner, &self.inner) {
+                bail!("sql: table {name} belongs to a different pipeline");
+            }
+            inputs.insert(name.to_string(), table.rows);
+        }
+        lower(&parser::parse(query)?, &inputs)
+    }
+}
+
+/// Build the transforms for `query`.
+fn lower(
+    query: &parser::Query,
+    inputs: &HashMap<String, PCollection<Row>>,
+) -> Result<PCollection<Row>> {
+    let scan = |table: &TableRef| -> Result<PCollection<Row>> {
+        let Some(rows) = inputs.get(&table.name) else {
+            bail!("sql: unknown table {}", table.name);
+        };
+        let alias = table.alias.clone();
+        Ok(rows.clone().map(move |r: &Row| {
+            r.columns()
+                .map(|(name, v)| (format!("{alias}.{name}"), v.clone()))
+                .collect::<Row>()
+        }))
+    };
+
+    let mut aliases = vec![query.from.alias.clone()];
+    let mut rows = scan(&query.from)?;
+
+    for join in &query.joins {
+        let alias = &join.table.alias;
+        if aliases.contains(alias) {
+            bail!("sql: table name {alias} is used twice; give one of them an alias");
+        }
+        let (left_keys, right_keys) = equi_keys(&join.on, &aliases, alias)?;
+        let right = scan(&join.table)?;
+        let right = right.flat_map(move |r: &Row| {
+            join_key(&right_keys, r)
+                .map(|k| (k, r.clone()))
+                .into_iter()
+                .collect()
+        });
+        aliases.push(alias.clone());
+        rows = if join.left_outer {
+            // Rows with a NULL key get "", which no right-hand key encodes to, so they
+            // stay unmatched.
+            rows.key_by(move |r: &Row| join_key(&left_keys, r).unwrap_or_default())
+                .join_left(&right)
+                .map(|(_, (l, r)): &(String, (Row, Option<Row>))| {
+                    let mut out = l.clone();
+                    if let Some(r) = r {
+                        out.extend(r.columns().map(|(n, v)| (n, v.clone())));
+                    }
+                    out
+                })
+        } else {
+            rows.flat_map(move |r: &Row| {
+                join_key(&left_keys, r)
+                    .map(|k| (k, r.clone()))
+                    .into_iter()
+                    .collect()
+            })
+            .join_inner(&right)
+            .map(|(_, (l, r)): &(String, (Row, Row))| {
+                let mut out = l.clone();
+                out.extend(r.columns().map(|(n, v)| (n, v.clone())));
+                out
+            })
+        };
+    }
+
+    if let Some(filter) = &query.filter {
+        check_scalar(filter, &aliases, "WHERE")?;
+        let filter = filter.clone();
+        rows = rows.filter(move |r: &Row| {
+            matches!(
+                eval(&filter, r).unwrap_or_else(|e| panic!("{e:#}")),
+                Value::Bool(true)
+            )
+        });
+    }
+
+    // Strip the table qualifier from `*` columns unless several tables were joined.
+    let qualified = aliases.len() > 1;
+    let aggregate = !query.group_by.is_empty()
+        || query
+            .select
+            .iter()
+            .any(|item| matches!(item, SelectItem::Expr { expr, .. } if expr.has_aggregate()));
+
+    let mut outputs: Vec<(String, Expr)> = Vec::new();
+    let mut wildcard = false;
+    let mut aggs: Vec<(AggFunc, Option<Expr>)> = Vec::new();
+    for item in &query.select {
+        match item {
+            SelectItem::Wildcard if aggregate => {
+                bail!("sql: SELECT * cannot be used with aggregates")
+            }
+            SelectItem::Wildcard => wildcard = true,
+            SelectItem::Expr { expr, alias } => {
+                check_columns(expr, &aliases)?;
+                let name = alias.clone().unwrap_or_else(|| match expr {
+                    Expr::Column { name, .. } => name.clone(),
+                    other => other.to_string(),
+                });
+                if outputs.iter().any(|(n, _)| *n == name) {
+                    bail!("sql: duplicate output column {name}; rename one with AS");
+                }
+                let expr = if aggregate {
+                    rewrite(expr, &query.group_by, &mut aggs)?
+                } else {
+                    expr.clone()
+                };
+                outputs.push((name, expr));
+            }
+        }
+    }
+
+    if aggregate {
+        for g in &query.group_by {
+            check_scalar(g, &aliases, "GROUP BY")?;
+        }
+        rows = aggregated(rows, query.group_by.clone(), aggs);
+    }
+
+    Ok(rows.map(move |r: &Row| {
+        let mut out = Row::new();
+        if wildcard {
+            for (name, v) in r.columns() {
+                let name = if qualified {
+                    name
+                } else {
+                    name.split_once('.').map_or(name, |(_, n)| n)
+                };
+                out.insert(name, v.clone());
+            }
+        }
+        for (name, expr) in &outputs {
+            out.insert(
+                name.clone(),
+                eval(expr, r).unwrap_or_else(|e| panic!("{e:#}")),
+            );
+        }
+        out
+    }))
+}
+
+/// Split a join condition into the key expressions of the earlier tables and of the
+/// joined table `right`.
+fn equi_keys(on: &Expr, left: &[String], right: &str) -> Result<(Vec<Expr>, Vec<Expr>)> {
+    let mut conjuncts = vec![on];
+    let mut keys = (Vec::new(), Vec::new());
+    while let Some(e) = conjuncts.pop() {
+        match e {
+            Expr::Binary {
+                op: BinOp::And,
+                left: a,
+                right: b,
+            } => {
+                conjuncts.push(b);
+                conjuncts.push(a);
+            }
+            Expr::Binary {
+                op: BinOp::Eq,
+                left: a,
+                right: b,
+            } if !e.has_aggregate() => {
+                let (ta, tb) = (tables_of(a)?, tables_of(b)?);
+                let is_left = |t: &[String]| !t.is_empty() && t.iter().all(|t| left.contains(t));
+                let is_right = |t: &[String]| !t.is_empty() && t.iter().all(|t| t == right);
+                if is_left(&ta) && is_right(&tb) {
+                    keys.0.push((**a).clone());
+                    keys.1.push((**b).clone());
+                } else if is_right(&ta) && is_left(&tb) {
+                    keys.0.push((**b).clone());
+                    keys.1.push((**a).clone());
+                } else {
+                    bail!(
+                        "sql: JOIN {right} ON: {e} does not compare {right} with an earlier table"
+                    );
+                }
+            }
+            _ => bail!("sql: JOIN {right} ON supports only equalities joined by AND, found {e}"),
+        }
+    }
+    Ok(keys)
+}
+
+/// The table qualifiers of the columns in `e`, requiring every column to have one.
+fn tables_of(e: &Expr) -> Result<Vec<String>> {
+    let mut out = Vec::new();
+    let mut stack = vec![e];
+    while let Some(e) = stack.pop() {
+        match e {
+            Expr::Column { table: Some(t), .. } => out.push(t.clone()),
+            Expr::Column { table: None, name } => {
+                bail!("sql: qualify column {name} in JOIN ... ON with its table name")
+            }
+            Expr::Literal(_) => {}
+            Expr::Neg(e) | Expr::Not(e) | Expr::IsNull { expr: e, .. } => stack.push(e),
+            Expr::Binary { left, right, .. } => {
+                stack.push(left);
+                stack.push(right);
+            }
+            Expr::Aggregate { arg, .. } => stack.extend(arg.as_deref()),
+        }
+    }
+    Ok(out)
+}
+
+/// Reject aggregates in `clause` and column qualifiers that name no table.
+fn check_scalar(e: &Expr, aliases: &[String], clause: &str) -> Result<()> {
+    if e.has_aggregate() {
+        bail!("sql: aggregates are not allowed in {clause}");
+    }
+    check_columns(e, aliases)
+}
+
+fn check_columns(e: &Expr, aliases: &[String]) -> Result<()> {
+    match e {
+        Expr::Column {
+            table: Some(t),
+            name,
+        } if !aliases.contains(t) => {
+            bail!("sql: unknown table {t} in column {t}.{name}")
+        }
+        Expr::Column { .. } | Expr::Literal(_) => Ok(()),
+        Expr::Neg(e) | Expr::Not(e) | Expr::IsNull { expr: e, .. } => check_columns(e, aliases),
+        Expr::Binary { left, right, .. } => {
+            check_columns(left, aliases)?;
+            check_columns(right, aliases)
+        }
+        Expr::Aggregate { arg, .. } => arg.as_deref().map_or(Ok(()), |a| check_columns(a, aliases)),
+    }
+}
+
+/// Rewrite a `SELECT` expression of an aggregate query to read the aggregation output:
+/// `GROUP BY` expressions become `#g<i>` columns and aggregate calls `#a<i>` columns,
+/// registering each distinct call in `aggs`.
+fn rewrite(e: &Expr, groups: &[Expr], aggs: &mut Vec<(AggFunc, Option<Expr>)>) -> Result<Expr> {
+    let column = |name: String| Expr::Column { table: None, name };
+    if let Some(i) = groups.iter().position(|g| g == e) {
+        return Ok(column(format!("#g{i}")));
+    }
+    Ok(match e {
+        Expr::Aggregate { func, arg } => {
+            let call = (*func, arg.as_deref().cloned());
+            let i = aggs.iter().position(|a| *a == call).unwrap_or_else(|| {
+                aggs.push(call);
+                aggs.len() - 1
+            });
+            column(format!("#a{i}"))
+        }
+        Expr::Column { .. } => {
+            bail!("sql: column {e} must appear in GROUP BY or be used in an aggregate")
+        }
+        Expr::Literal(_) => e.clone(),
+        Expr::Neg(a) => Expr::Neg(Box::new(rewrite(a, groups, aggs)?)),
+        Expr::Not(a) => Expr::Not(Box::new(rewrite(a, groups, aggs)?)),
+        Expr::IsNull { expr, negated } => Expr::IsNull {
+            expr: Box::new(rewrite(expr, groups, aggs)?),
+            negated: *negated,
+        },
+        Expr::Binary { op, left, right } => Expr::Binary {
+            op: *op,
+            left: Box::new(rewrite(left, groups, aggs)?),
+            right: Box::new(rewrite(right, groups, aggs)?),
+        },
+    })
+}
+
+/// Group `rows` by `groups` and compute `aggs`, producing one row per group with
+/// `#g<i>` and `#a<i>` columns.
+fn aggregated(
+    rows: PCollection<Row>,
+    groups: Vec<Expr>,
+    aggs: Vec<(AggFunc, Option<Expr>)>,
+) -> PCollection<Row> {
+    let global = groups.is_empty();
+    let combiner = Aggregate {
+        funcs: aggs.iter().map(|(f, _)| *f).collect(),
+    };
+    let inputs = rows.map(move |r: &Row| {
+        let value = |e: &Expr| eval(e, r).unwrap_or_else(|e| panic!("{e:#}"));
+        AggInput {
+            group: groups.iter().map(value).collect(),
+            // `COUNT(*)` counts every row: give it a non-NULL input.
+            args: aggs
+                .iter()
+                .map(|(_, arg)| arg.as_ref().map_or(Value::Bool(true), value))
+                .collect(),
+        }
+    });
+    if global {
+        // Without GROUP BY there is exactly one output row, even for empty input.
+        inputs.combine_globally(combiner, None)
+    } else {
+        inputs
+            .key_by(|i: &AggInput| key_string(&i.group))
+            .combine_values(combiner)
+            .values()
+    }
+}
+
+/// The join key of `row`, or `None` if any key value is `NULL` (it matches nothing).
+fn join_key(keys: &[Expr], row: &Row) -> Option<String> {
+    let values: Vec<Value> = keys
+        .iter()
+        .map(|k| eval(k, row).unwrap_or_else(|e| panic!("{e:#}")))
+        .collect();
+    if values.iter().any(Value::is_null) {
+        return None;
+    }
+    Some(key_string(&values))
+}
+
+/// A hashable encoding of `values`, with integral floats written as integers so `1`
+/// and `1.0` group and join together.
+fn key_string(values: &[Value]) -> String {
+    let normalized: Vec<Value> = values
+        .iter()
+        .map(|v| match v.as_f64() {
+            #[allow(clippy::cast_possible_truncation)]
+            Some(f) if v.is_f64() && f.fract() == 0.0 && f.abs() < 9.0e15 => Value::from(f as i64),
+            _ => v.clone(),
+        })
+        .collect();
+    Value::Array(normalized).to_string()
+}
+
+// ── Evaluation ───────────────────────────────────────────────────────────────
+
+/// Evaluate `e` against `row`, whose columns are qualified as `table.column`.
+fn eval(e: &Expr, row: &Row) -> Result<Value> {
+    Ok(match e {
+        Expr::Column { table, name } => lookup(row, table.as_deref(), name)?,
+        Expr::Literal(v) => v.clone(),
+        Expr::Neg(a) => match num(&eval(a, row)?)? {
+            None => Value::Null,
+            Some(Num::Int(i)) => i
+                .checked_neg()
+                .map_or_else(|| float(-Num::Int(i).as_f64()), Value::from),
+            Some(Num::Float(f)) => float(-f),
+        },
+        Expr::Not(a) => match boolean(&eval(a, row)?)? {
+            None => Value::Null,
+            Some(b) => Value::Bool(!b),
+        },
+        Expr::IsNull { expr, negated } => Value::Bool(eval(expr, row)?.is_null() != *negated),
+        Expr::Binary { op, left, right } => {
+            let (l, r) = (eval(left, row)?, eval(right, row)?);
+            match op {
+                BinOp::And => match (boolean(&l)?, boolean(&r)?) {
+                    (Some(false), _) | (_, Some(false)) => Value::Bool(false),
+                    (Some(true), Some(true)) => Value::Bool(true),
+                    _ => Value::Null,
+                },
+                BinOp::Or => match (boolean(&l)?, boolean(&r)?) {
+                    (Some(true), _) | (_, Some(true)) => Value::Bool(true),
+                    (Some(false), Some(false)) => Value::Bool(false),
+                    _ => Value::Null,
+                },
+                BinOp::Eq | BinOp::NotEq | BinOp::Lt | BinOp::LtEq | BinOp::Gt | BinOp::GtEq => {
+                    match compare(&l, &r)? {
+                        None => Value::Null,
+                        Some(ord) => Value::Bool(match op {
+                            BinOp::Eq => ord == Ordering::Equal,
+                            BinOp::NotEq => ord != Ordering::Equal,
+                            BinOp::Lt => ord == Ordering::Less,
+                            BinOp::LtEq => ord != Ordering::Greater,
+                            BinOp::Gt => ord == Ordering::Greater,
+                            _ => ord != Ordering::Less,
+                        }),
+                    }
+                }
+                BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div | BinOp::Mod => {
+                    arithmetic(*op, &l, &r)?
+                }
+            }
+        }
+        Expr::Aggregate { .. } => bail!("sql: aggregate {e} used outside an aggregate query"),
+    })
+}
+
+/// Resolve a column. Unqualified names match the one table that has the column.
+fn lookup(row: &Row, table: Option<&str>, name: &str) -> Result<Value> {
+    if let Some(t) = table {
+        return Ok(row
+            .get(&format!("{t}.{name}"))
+            .cloned()
+            .unwrap_or(Value::Null));
+    }
+    if let Some(v) = row.get(name) {
+        return Ok(v.clone());
+    }
+    let mut found = None;
+    for (column, v) in row.columns() {
+        if column.split_once('.').is_some_and(|(_, c)| c == name) {
+            if found.is_some() {
+                bail!("sql: column {name} is ambiguous; qualify it with its table name");
+            }
+            found = Some(v);
+        }
+    }
+    Ok(found.cloned().unwrap_or(Value::Null))
+}
+
+/// A number operand: integers stay exact until they overflow.
+#[derive(Clone, Copy, Debug)]
+enum Num {
+    Int(i64),
+    Float(f64),
+}
+
+impl Num {
+    fn as_f64(self) -> f64 {
+        match self {
+            #[allow(clippy::cast_precision_loss)]
+            Self::Int(i) => i as f64,
+            Self::Float(f) => f,
+        }
+    }
+
+    fn into_value(self) -> Value {
+        match self {
+            Self::Int(i) => Value::from(i),
+            Self::Float(f) => float(f),
+        }
+    }
+}
+
+/// A float value; non-finite results (which JSON cannot hold) become `NULL`.
+fn float(f: f64) -> Value {
+    Value::from(f)
+}
+
+fn num(v: &Value) -> Result<Option<Num>> {
+    match v {
+        Value::Null => Ok(None),
+        Value::Number(n) => Ok(Some(
+            n.as_i64()
+                .map_or_else(|| Num::Float(n.as_f64().unwrap_or(f64::NAN)), Num::Int),
+        )),
+        other => bail!("sql: expected a number, found {other}"),
+    }
+}
+
+fn boolean(v: &Value) -> Result<Option<bool>> {
+    match v {
+        Value::Null => Ok(None),
+        Value::Bool(b) => Ok(Some(*b)),
+        other => bail!("sql: expected a boolean, found {other}"),
+    }
+}
+
+/// Order two values of the same type; `None` if either is `NULL`.
+fn compare(a: &Value, b: &Value) -> Result<Option<Ordering>> {
+    Ok(match (a, b) {
+        (Value::Null, _) | (_, Value::Null) => None,
+        (Value::Number(_), Value::Number(_)) => match (num(a)?, num(b)?) {
+            (Some(Num::Int(x)), Some(Num::Int(y))) => Some(x.cmp(&y)),
+            (Some(x), Some(y)) => x.as_f64().partial_cmp(&y.as_f64()),
+            _ => None,
+        },
+        (Value::String(x), Value::String(y)) => Some(x.cmp(y)),
+        (Value::Bool(x), Value::Bool(y)) => Some(x.cmp(y)),
+        _ => bail!("sql: cannot compare {a} with {b}"),
+    })
+}
+
+fn arithmetic(op: BinOp, a: &Value, b: &Value) -> Result<Value> {
+    let (Some(x), Some(y)) = (num(a)?, num(b)?) else {
+        return Ok(Value::Null);
+    };
+    if matches!(op, BinOp::Div | BinOp::Mod) && y.as_f64() == 0.0 {
+        bail!("sql: division by zero");
+    }
+    if let (Num::Int(x), Num::Int(y)) = (x, y) {
+        let exact = match op {
+            BinOp::Add => x.checked_add(y),
+            BinOp::Sub => x.checked_sub(y),
+            BinOp::Mul => x.checked_mul(y),
+            BinOp::Div => x.checked_div(y),
+            _ => x.checked_rem(y),
+        };
+        if let Some(v) = exact {
+            return Ok(Value::from(v));
+        }
+    }
+    let (x, y) = (x.as_f64(), y.as_f64());
+    Ok(float(match op {
+        BinOp::Add => x + y,
+        BinOp::Sub => x - y,
+        BinOp::Mul => x * y,
+        BinOp::Div => x / y,
+        _ => x % y,
+    }))
+}
+
+// ── Aggregation ──────────────────────────────────────────────────────────────
+
+/// One row's contribution to an aggregate query: its `GROUP BY` values and the
+/// argument of each aggregate call.
+#[derive(Clone, Serialize, Deserialize)]
+struct AggInput {
+    #[serde(with = "crate::row::json_text")]
+    group: Vec<Value>,
+    #[serde(with = "crate::row::json_text")]
+    args: Vec<Value>,
+}
+
+/// Running state of one aggregate call.
+#[derive(Clone)]
+enum AggState {
+    Count(u64),
+    Sum(Option<Num>),
+    Min(Option<Value>),
+    Max(Option<Value>),
+    Avg { sum: f64, n: u64 },
+}
+
+/// Accumulator for one group.
+struct AggAcc {
+    group: Option<Vec<Value>>,
+    states: Vec<AggState>,
+}
+
+/// Computes every aggregate call of a query in one combiner.
+struct Aggregate {
+    funcs: Vec<AggFunc>,
+}
+
+fn add_nums(a: Num, b: Num) -> Num {
+    match (a, b) {
+        (Num::Int(x), Num::Int(y)) => x
+            .checked_add(y)
+            .map_or_else(|| Num::Float(a.as_f64() + b.as_f64()), Num::Int),
+        _ => Num::Float(a.as_f64() + b.as_f64()),
+    }
+}
+
+/// Keep the smaller (`keep == Less`) or larger of `current` and `v`.
+fn extreme(current: &mut Option<Value>, v: Value, keep: Ordering) {
+    let replace = match current {
+        None => true,
+        Some(c) => compare(&v, c).unwrap_or_else(|e| panic!("{e:#}")) == Some(keep),
+    };
+    if replace {
+        *current = Some(v);
+    }
+}
+
+impl AggState {
+    fn new(func: AggFunc) -> Self {
+        match func {
+            AggFunc::Count => Self::Count(0),
+            AggFunc::Sum => Self::Sum(None),
+            AggFunc::Min => Self::Min(None),
+            AggFunc::Max => Self::Max(None),
+            AggFunc::Avg => Self::Avg { sum: 0.0, n: 0 },
+        }
+    }
+
+    fn add(&mut self, v: Value) {
+        if v.is_null() {
+            return;
+        }
+        let number = || {
+            num(&v)
+                .unwrap_or_else(|e| panic!("{e:#}"))
+                .map_or(0.0, Num::as_f64)
+        };
+        match self {
+            Self::Count(n) => *n += 1,
+            Self::Sum(sum) => {
+                let x = num(&v).unwrap_or_else(|e| panic!("{e:#}"));
+                *sum = match (*sum, x) {
+                    (Some(s), Some(x)) => Some(add_nums(s, x)),
+                    (s, x) => s.or(x),
+                };
+            }
+            Self::Avg { sum, n } => {
+                *sum += number();
+                *n += 1;
+            }
+            Self::Min(m) => extreme(m, v, Ordering::Less),
+            Self::Max(m) => extreme(m, v, Ordering::Greater),
+        }
+    }
+
+    fn merge(&mut self, other: Self) {
+        match (self, other) {
+            (Self::Count(a), Self::Count(b)) => *a += b,
+            (Self::Sum(a), Self::Sum(b)) => {
+                *a = match (*a, b) {
+                    (Some(x), Some(y)) => Some(add_nums(x, y)),
+                    (x, y) => x.or(y),
+                };
+            }
+            (Self::Avg { sum, n }, Self::Avg { sum: s, n: m }) => {
+                *sum += s;
+                *n += m;
+            }
+            (Self::Min(a), Self::Min(Some(b))) => extreme(a, b, Ordering::Less),
+            (Self::Max(a), Self::Max(Some(b))) => extreme(a, b, Ordering::Greater),
+            _ => {}
+        }
+    }
+
+    fn finish(self) -> Value {
+        match self {
+            Self::Count(n) => Value::from(n),
+            Self::Sum(sum) => sum.map_or(Value::Null, Num::into_value),
+            Self::Min(v) | Self::Max(v) => v.unwrap_or(Value::Null),
+            #[allow(clippy::cast_precision_loss)]
+            Self::Avg { sum, n } => {
+                if n == 0 {
+                    Value::Null
+                } else {
+                    float(sum / n as f64)
+                }
+            }
+        }
+    }
+}
+
+impl CombineFn<AggInput, AggAcc, Row> for Aggregate {
+    fn create(&self) -> AggAcc {
+        AggAcc {
+            group: None,
+            states: self.funcs.iter().map(|f| AggState::new(*f)).collect(),
+        }
+    }
+
+    fn add_input(&self, acc: &mut AggAcc, v: AggInput) {
+        acc.group.get_or_insert(v.group);
+        for (state, arg) in acc.states.iter_mut().zip(v.args) {
+            state.add(arg);
+        }
+    }
+
+    fn merge(&self, acc: &mut AggAcc, other: AggAcc) {
+        if acc.group.is_none() {
+            acc.group = other.group;
+        }
+        for (state, o) in acc.states.iter_mut().zip(other.states) {
+            state.merge(o);
+        }
+    }
+
+    fn finish(&self, acc: AggAcc) -> Row {
+        let groups = acc.group.unwrap_or_default().into_iter().enumerate();
+        let aggs = acc.states.into_iter().enumerate();
+        groups
+            .map(|(i, v)| (format!("#g{i}"), v))
+            .chain(aggs.map(|(i, s)| (format!("#a{i}"), s.finish())))
+            .collect()
+    }
+
+    fn is_associative_commutative(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,611 @@
+//! Tokenizer and recursive-descent parser for the SQL subset.
+//!
+//! Grammar (keywords are case-insensitive):
+//!
+//! ```text
+//! query   := SELECT item (',' item)* FROM table join* [WHERE expr]
+//!            [GROUP BY expr (',' expr)*] [';']
+//! item    := '*' | expr [[AS] ident]
+//! table   := ident [[AS] ident]
+//! join    := [INNER | LEFT [OUTER]] JOIN table ON expr
+//! expr    := or
+//! or      := and (OR and)*
+//! and     := not (AND not)*
+//! not     := NOT not | cmp
+//! cmp     := sum [('=' | '<>' | '!=' | '<' | '<=' | '>' | '>=') sum | IS [NOT] NULL]
+//! sum     := product (('+' | '-') product)*
+//! product := unary (('*' | '/' | '%') unary)*
+//! unary   := '-' unary | primary
+//! primary := literal | ident ['.' ident] | func '(' ('*' | expr) ')' | '(' expr ')'
+//! ```
+
+use anyhow::{Result, anyhow, bail};
+use serde_json::Value;
+use std::fmt;
+
+/// A parsed `SELECT` statement.
+#[derive(Debug)]
+pub(crate) struct Query {
+    pub select: Vec<SelectItem>,
+    pub from: TableRef,
+    pub joins: Vec<Join>,
+    pub filter: Option<Expr>,
+    pub group_by: Vec<Expr>,
+}
+
+/// One entry of the `SELECT` list.
+#[derive(Debug)]
+pub(crate) enum SelectItem {
+    /// `*`: every column of the input.
+    Wildcard,
+    /// An expression with an optional `AS` name.
+    Expr { expr: Expr, alias: Option<String> },
+}
+
+/// A table in `FROM` or `JOIN`, with the name it is referred to by.
+#[derive(Debug)]
+pub(crate) struct TableRef {
+    pub name: String,
+    pub alias: String,
+}
+
+/// A `JOIN` clause.
+#[derive(Debug)]
+pub(crate) struct Join {
+    pub left_outer: bool,
+    pub table: TableRef,
+    pub on: Expr,
+}
+
+/// A scalar or aggregate expression.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Expr {
+    Column {
+        table: Option<String>,
+        name: String,
+    },
+    Literal(Value),
+    Neg(Box<Expr>),
+    Not(Box<Expr>),
+    Binary {
+        op: BinOp,
+        left: Box<Expr>,
+        right: Box<Expr>,
+    },
+    IsNull {
+        expr: Box<Expr>,
+        negated: bool,
+    },
+    /// An aggregate call; `arg` is `None` for `COUNT(*)`.
+    Aggregate {
+        func: AggFunc,
+        arg: Option<Box<Expr>>,
+    },
+}
+
+/// Binary operators.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum BinOp {
+    And,
+    Or,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+}
+
+/// Aggregate functions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum AggFunc {
+    Count,
+    Sum,
+    Min,
+    Max,
+    Avg,
+}
+
+impl Expr {
+    /// True if the expression contains an aggregate call.
+    pub fn has_aggregate(&self) -> bool {
+        match self {
+            Self::Aggregate { .. } => true,
+            Self::Column { .. } | Self::Literal(_) => false,
+            Self::Neg(e) | Self::Not(e) | Self::IsNull { expr: e, .. } => e.has_aggregate(),
+            Self::Binary { left, right, .. } => left.has_aggregate() || right.has_aggregate(),
+        }
+    }
+}
+
+impl fmt::Display for BinOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::And => "AND",
+            Self::Or => "OR",
+            Self::Eq => "=",
+            Self::NotEq => "<>",
+            Self::Lt => "<",
+            Self::LtEq => "<=",
+            Self::Gt => ">",
+            Self::GtEq => ">=",
+            Self::Add => "+",
+            Self::Sub => "-",
+            Self::Mul => "*",
+            Self::Div => "/",
+            Self::Mod => "%",
+        })
+    }
+}
+
+impl fmt::Display for AggFunc {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Count => "COUNT",
+            Self::Sum => "SUM",
+            Self::Min => "MIN",
+            Self::Max => "MAX",
+            Self::Avg => "AVG",
+        })
+    }
+}
+
+/// Renders the expression as SQL; used to name unaliased `SELECT` columns.
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Column {
+                table: Some(t),
+                name,
+            } => write!(f, "{t}.{name}"),
+            Self::Column { table: None, name } => f.write_str(name),
+            Self::Literal(Value::String(s)) => write!(f, "'{}'", s.replace('\'', "''")),
+            Self::Literal(Value::Null) => f.write_str("NULL"),
+            Self::Literal(v) => write!(f, "{v}"),
+            Self::Neg(e) => write!(f, "-{e}"),
+            Self::Not(e) => write!(f, "NOT {e}"),
+            Self::Binary { op, left, right } => write!(f, "({left} {op} {right})"),
+            Self::IsNull {
+                expr,
+                negated: false,
+            } => write!(f, "{expr} IS NULL"),
+            Self::IsNull {
+                expr,
+                negated: true,
+            } => write!(f, "{expr} IS NOT NULL"),
+            Self::Aggregate { func, arg: None } => write!(f, "{func}(*)"),
+            Self::Aggregate { func, arg: Some(a) } => write!(f, "{func}({a})"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    /// A bare or quoted identifier; `quoted` identifiers are never keywords.
+    Ident {
+        text: String,
+        quoted: bool,
+    },
+    Number(String),
+    Str(String),
+    Symbol(&'static str),
+}
+
+const SYMBOLS: [&str; 16] = [
+    "<>", "!=", "<=", ">=", "=", "<", ">", "+", "-", "*", "/", "%", "(", ")", ",", ".",
+];
+
+/// Keywords that cannot be used as bare aliases.
+const RESERVED: [&str; 19] = [
+    "SELECT", "FROM", "WHERE", "GROUP", "BY", "AS", "JOIN", "INNER", "LEFT", "OUTER", "ON", "AND",
+    "OR", "NOT", "IS", "NULL", "ORDER", "HAVING", "LIMIT",
+];
+
+/// Split `sql` into tokens, each with its byte offset for error messages.
+fn tokenize(sql: &str) -> Result<Vec<(usize, Token)>> {
+    let bytes = sql.as_bytes();
+    let mut out = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i];
+        let start = i;
+        if c.is_ascii_whitespace() || c == b';' {
+            i += 1;
+        } else if sql[i..].starts_with("--") {
+            i = sql[i..].find('\n').map_or(bytes.len(), |n| i + n);
+        } else if c == b'\'' {
+            let mut s = String::new();
+            i += 1;
+            loop {
+                let Some(n) = sql[i..].find('\'') else {
+                    bail!("sql: unterminated string starting at offset {start}");
+                };
+                s.push_str(&sql[i..i + n]);
+                i += n + 1;
+                if bytes.get(i) == Some(&b'\'') {
+                    s.push('\'');
+                    i += 1;
+                } else {
+                    break;
+                }
+            }
+            out.push((start, Token::Str(s)));
+        } else if c == b'"' || c == b'`' {
+            let close = c as char;
+            let Some(n) = sql[i + 1..].find(close) else {
+                bail!("sql: unterminated identifier starting at offset {start}");
+            };
+            out.push((
+                start,
+                Token::Ident {
+                    text: sql[i + 1..i + 1 + n].to_string(),
+                    quoted: true,
+                },
+            ));
+            i += n + 2;
+        } else if c.is_ascii_digit() {
+            while i < bytes.len() && (bytes[i].is_ascii_digit() || bytes[i] == b'.') {
+                i += 1;
+            }
+            out.push((start, Token::Number(sql[start..i].to_string())));
+        } else if c.is_ascii_alphabetic() || c == b'_' {
+            while i < bytes.len() && (bytes[i].is_ascii_alphanumeric() || bytes[i] == b'_') {
+                i += 1;
+            }
+            out.push((
+                start,
+                Token::Ident {
+                    text: sql[start..i].to_string(),
+                    quoted: false,
+                },
+            ));
+        } else if let Some(sym) = SYMBOLS.iter().find(|s| sql[i..].starts_with(**s)) {
+            out.push((start, Token::Symbol(sym)));
+            i += sym.len();
+        } else {
+            let ch = sql[i..].chars().next().unwrap_or_default();
+            bail!("sql: unexpected character {ch:?} at offset {start}");
+        }
+    }
+    Ok(out)
+}
+
+/// Parse a `SELECT` statement.
+pub(crate) fn parse(sql: &str) -> Result<Query> {
+    let mut parser = Parser {
+        tokens: tokenize(sql)?,
+        pos: 0,
+        len: sql.len(),
+    };
+    let query = parser.query()?;
+    if parser.pos < parser.tokens.len() {
+        return Err(parser.error("end of query"));
+    }
+    Ok(query)
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    /// Length of the source, reported as the offset of end-of-input errors.
+    len: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, t)| t)
+    }
+
+    fn error(&self, expected: &str) -> anyhow::Error {
+        match self.tokens.get(self.pos) {
+            Some((at, tok)) => anyhow!("sql: expected {expected} at offset {at}, found {tok:?}"),
+            None => anyhow!(
+                "sql: expected {expected} at offset {}, found end of query",
+                self.len
+            ),
+        }
+    }
+
+    /// True if the next token is the (unquoted) keyword `kw`.
+    fn at_keyword(&self, kw: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident { text, quoted: false }) if text.eq_ignore_ascii_case(kw))
+    }
+
+    fn eat_keyword(&mut self, kw: &str) -> bool {
+        let hit = self.at_keyword(kw);
+        if hit {
+            self.pos += 1;
+        }
+        hit
+    }
+
+    fn expect_keyword(&mut self, kw: &str) -> Result<()> {
+        if self.eat_keyword(kw) {
+            Ok(())
+        } else {
+            Err(self.error(kw))
+        }
+    }
+
+    fn eat_symbol(&mut self, sym: &str) -> bool {
+        let hit = matches!(self.peek(), Some(Token::Symbol(s)) if *s == sym);
+        if hit {
+            self.pos += 1;
+        }
+        hit
+    }
+
+    fn expect_symbol(&mut self, sym: &str) -> Result<()> {
+        if self.eat_symbol(sym) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("'{sym}'")))
+        }
+    }
+
+    /// An identifier that is not a reserved keyword.
+    fn ident(&mut self) -> Result<String> {
+        match self.peek() {
+            Some(Token::Ident { text, quoted })
+                if *quoted || !RESERVED.iter().any(|k| text.eq_ignore_ascii_case(k)) =>
+            {
+                let text = text.clone();
+                self.pos += 1;
+                Ok(text)
+            }
+            _ => Err(self.error("identifier")),
+        }
+    }
+
+    /// An optional alias: `AS name`, or a bare non-keyword identifier.
+    fn alias(&mut self) -> Result<Option<String>> {
+        if self.eat_keyword("AS") {
+            return self.ident().map(Some);
+        }
+        Ok(self.ident().ok())
+    }
+
+    fn query(&mut self) -> Result<Query> {
+        self.expect_keyword("SELECT")?;
+        let mut select = vec![self.select_item()?];
+        while self.eat_symbol(",") {
+            select.push(self.select_item()?);
+        }
+        self.expect_keyword("FROM")?;
+        let from = self.table()?;
+
+        let mut joins = Vec::new();
+        loop {
+            let left_outer = if self.eat_keyword("LEFT") {
+                self.eat_keyword("OUTER");
+                self.expect_keyword("JOIN")?;
+                true
+            } else if self.eat_keyword("INNER") {
+                self.expect_keyword("JOIN")?;
+                false
+            } else if self.eat_keyword("JOIN") {
+                false
+            } else {
+                break;
+            };
+            let table = self.table()?;
+            self.expect_keyword("ON")?;
+            let on = self.expr()?;
+            joins.push(Join {
+                left_outer,
+                table,
+                on,
+            });
+        }
+
+        let filter = if self.eat_keyword("WHERE") {
+            Some(self.expr()?)
+        } else {
+            None
+        };
+
+        let mut group_by = Vec::new();
+        if self.eat_keyword("GROUP") {
+            self.expect_keyword("BY")?;
+            group_by.push(self.expr()?);
+            while self.eat_symbol(",") {
+                group_by.push(self.expr()?);
+            }
+        }
+
+        Ok(Query {
+            select,
+            from,
+            joins,
+            filter,
+            group_by,
+        })
+    }
+
+    fn select_item(&mut self) -> Result<SelectItem> {
+        if self.eat_symbol("*") {
+            return Ok(SelectItem::Wildcard);
+        }
+        let expr = self.expr()?;
+        let alias = self.alias()?;
+        Ok(SelectItem::Expr { expr, alias })
+    }
+
+    fn table(&mut self) -> Result<TableRef> {
+        let name = self.ident()?;
+        let alias = self.alias()?.unwrap_or_else(|| name.clone());
+        Ok(TableRef { name, alias })
+    }
+
+    fn expr(&mut self) -> Result<Expr> {
+        let mut left = self.and()?;
+        while self.eat_keyword("OR") {
+            left = binary(BinOp::Or, left, self.and()?);
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Expr> {
+        let mut left = self.not()?;
+        while self.eat_keyword("AND") {
+            left = binary(BinOp::And, left, self.not()?);
+        }
+        Ok(left)
+    }
+
+    fn not(&mut self) -> Result<Expr> {
+        if self.eat_keyword("NOT") {
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr> {
+        let left = self.sum()?;
+        if self.eat_keyword("IS") {
+            let negated = self.eat_keyword("NOT");
+            self.expect_keyword("NULL")?;
+            return Ok(Expr::IsNull {
+                expr: Box::new(left),
+                negated,
+            });
+        }
+        let op = match self.peek() {
+            Some(Token::Symbol("=")) => BinOp::Eq,
+            Some(Token::Symbol("<>" | "!=")) => BinOp::NotEq,
+            Some(Token::Symbol("<")) => BinOp::Lt,
+            Some(Token::Symbol("<=")) => BinOp::LtEq,
+            Some(Token::Symbol(">")) => BinOp::Gt,
+            Some(Token::Symbol(">=")) => BinOp::GtEq,
+            _ => return Ok(left),
+        };
+        self.pos += 1;
+        Ok(binary(op, left, self.sum()?))
+    }
+
+    fn sum(&mut self) -> Result<Expr> {
+        let mut left = self.product()?;
+        loop {
+            let op = if self.eat_symbol("+") {
+                BinOp::Add
+            } else if self.eat_symbol("-") {
+                BinOp::Sub
+            } else {
+                return Ok(left);
+            };
+            left = binary(op, left, self.product()?);
+        }
+    }
+
+    fn product(&mut self) -> Result<Expr> {
+        let mut left = self.unary()?;
+        loop {
+            let op = if self.eat_symbol("*") {
+                BinOp::Mul
+            } else if self.eat_symbol("/") {
+                BinOp::Div
+            } else if self.eat_symbol("%") {
+                BinOp::Mod
+            } else {
+                return Ok(left);
+            };
+            left = binary(op, left, self.unary()?);
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr> {
+        if self.eat_symbol("-") {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr> {
+        if self.eat_symbol("(") {
+            let e = self.expr()?;
+            self.expect_symbol(")")?;
+            return Ok(e);
+        }
+        match self.peek().cloned() {
+            Some(Token::Number(n)) => {
+                self.pos += 1;
+                let v = if let Ok(i) = n.parse::<i64>() {
+                    Value::from(i)
+                } else {
+                    let f: f64 = n.parse().map_err(|_| anyhow!("sql: invalid number {n}"))?;
+                    Value::from(f)
+                };
+                Ok(Expr::Literal(v))
+            }
+            Some(Token::Str(s)) => {
+                self.pos += 1;
+                Ok(Expr::Literal(Value::String(s)))
+            }
+            Some(Token::Ident {
+                text,
+                quoted: false,
+            }) if ["NULL", "TRUE", "FALSE"]
+                .iter()
+                .any(|k| text.eq_ignore_ascii_case(k)) =>
+            {
+                self.pos += 1;
+                Ok(Expr::Literal(match text.to_ascii_uppercase().as_str() {
+                    "TRUE" => Value::Bool(true),
+                    "FALSE" => Value::Bool(false),
+                    _ => Value::Null,
+                }))
+            }
+            Some(Token::Ident { .. }) => {
+                let name = self.ident().map_err(|_| self.error("expression"))?;
+                if self.eat_symbol("(") {
+                    return self.call(&name);
+                }
+                if self.eat_symbol(".") {
+                    let column = self.ident()?;
+                    return Ok(Expr::Column {
+                        table: Some(name),
+                        name: column,
+                    });
+                }
+                Ok(Expr::Column { table: None, name })
+            }
+            _ => Err(self.error("expression")),
+        }
+    }
+
+    /// The rest of a function call, after `name(`.
+    fn call(&mut self, name: &str) -> Result<Expr> {
+        let func = match name.to_ascii_uppercase().as_str() {
+            "COUNT" => AggFunc::Count,
+            "SUM" => AggFunc::Sum,
+            "MIN" => AggFunc::Min,
+            "MAX" => AggFunc::Max,
+            "AVG" => AggFunc::Avg,
+            _ => bail!("sql: unknown function {name}"),
+        };
+        let arg = if func == AggFunc::Count && self.eat_symbol("*") {
+            None
+        } else {
+            let arg = self.expr()?;
+            if arg.has_aggregate() {
+                bail!("sql: aggregate calls cannot be nested");
+            }
+            Some(Box::new(arg))
+        };
+        self.expect_symbol(")")?;
+        Ok(Expr::Aggregate { func, arg })
+    }
+}
+
+fn binary(op: BinOp, left: Expr, right: Expr) -> Expr {
+    Expr::Binary {
+        op,
+        left: Box::new(left),
+        right: Box::new(right),
+    }
+}
@@ -8,6 +8,12 @@
 //! - **Debug utilities**: Inspect pipelines during execution
 //! - **Fixtures**: Pre-built test datasets for common scenarios
 //! - **Mock I/O**: Test I/O operations without actual files
+//! - **Transform harness**: Test one transform in sequential and parallel modes, with
+//!   golden-file snapshots ([`TransformTester`])
+//! - **Combiner laws**: Verify that a custom `CombineFn` is safe to merge in parallel
+//!   ([`combiner_laws`])
+//! - **Deterministic context**: A fake clock and fixed sampling seed for pipelines that
+//!   use processing time or sampling ([`TestContext`])
 //!
 //! # Quick Start
 //!
@@ -53,6 +59,27 @@
 //!     .build();
 //! ```
 //!
+//! # Transform Harness
+//!
+//! [`TransformTester`] runs a single closure or
+//! [`CompositeTransform`](crate::extensions::CompositeTransform) against supplied
+//! inputs, checks that sequential and parallel runs agree, and optionally compares the
+//! output with a JSONL golden file:
+//!
+//! ```no_run
+//! use ironbeam::*;
+//! use ironbeam::testing::*;
+//! # use anyhow::Result;
+//!
+//! # fn main() -> Result<()> {
+//! TransformTester::new(|xs: PCollection<i32>| xs.map(|x: &i32| x * 2))
+//!     .input(vec![1, 2, 3])
+//!     .golden("tests/golden/doubled.jsonl")
+//!     .run()?;
+//! # Ok(())
+//! # }
+//! ```
+//!
 //! # Debug Utilities
 //!
 //! Debug your pipelines during test execution:
@@ -75,8 +102,11 @@
 
 pub mod assertions;
 pub mod builders;
+pub mod combiner_laws;
+pub mod context;
 pub mod debug;
 pub mod fixtures;
+pub mod harness;
 
 #[cfg(any(feature = "io-csv", feature = "io-jsonl", feature = "io-parquet"))]
 pub mod mock_io;
@@ -85,8 +115,10 @@ use std::ops::Deref;
 // Re-export commonly used items
 pub use assertions::*;
 pub use builders::*;
+pub use context::*;
 pub use debug::*;
 pub use fixtures::*;
+pub use harness::*;
 
 #[cfg(any(feature = "io-csv", feature = "io-jsonl", feature = "io-parquet"))]
 pub use mock_io::*;
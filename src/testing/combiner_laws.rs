@@ -0,0 +1,290 @@
+//! Law checks for custom [`CombineFn`] implementations.
+//!
+//! The runners rely on a few algebraic properties of a combiner when they split work
+//! across partitions and merge the partial accumulators. The checks here verify those
+//! properties for concrete inputs, comparing the **finished** outputs (so accumulator
+//! types need not implement `PartialEq`):
+//!
+//! | Check | Property |
+//! |-------|----------|
+//! | [`check_merge_associative`] | `merge` is associative |
+//! | [`check_merge_commutative`] | `merge` is commutative (needed when [`CombineFn::is_associative_commutative`] is `true`) |
+//! | [`check_empty_identity`] | `create()` is an identity for `merge` |
+//! | [`check_lifted_equivalence`] | one accumulator over all values equals per-partition accumulators merged |
+//! | [`check_partial_groups`] | adding a group in pieces equals adding it whole (needed when [`CombineFn::accepts_partial_groups`] is `true`) |
+//!
+//! [`check_combiner_laws`] runs every check that applies to a combiner over one set of
+//! partitioned inputs.
+//!
+//! Each check returns an error describing the violated law rather than panicking, so it
+//! composes with `?` in ordinary tests and with `prop_assert!` in property tests. With
+//! the `proptest` feature, `partitioned` and `three_parts` generate inputs.
+//!
+//! Outputs are compared with `==`; floating-point combiners whose results differ in the
+//! last bits between merge orders need integer-valued test data. A runner never finishes
+//! an accumulator that saw no values, so checks whose inputs are all empty pass without
+//! calling `finish` (combiners like [`Min`](crate::Min) panic on an empty finish).
+//!
+//! # Example
+//! ```
+//! use ironbeam::*;
+//! use ironbeam::testing::combiner_laws::check_combiner_laws;
+//! # use anyhow::Result;
+//!
+//! # fn main() -> Result<()> {
+//! let partitions = vec![vec![1u64, 2, 3], vec![], vec![10, 20]];
+//! check_combiner_laws(&Sum::<u64>::new(), &partitions)?;
+//! check_combiner_laws(&Max::<u64>::new(), &partitions)?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Property tests
+//! ```ignore
+//! use ironbeam::*;
+//! use ironbeam::testing::combiner_laws::{check_combiner_laws, partitioned};
+//! use proptest::prelude::*;
+//!
+//! proptest! {
+//!     #[test]
+//!     fn sum_obeys_laws(parts in partitioned(-1_000i64..1_000, 20, 6)) {
+//!         prop_assert!(check_combiner_laws(&Sum::<i64>::new(), &parts).is_ok());
+//!     }
+//! }
+//! ```
+
+use crate::collection::CombineFn;
+use anyhow::{Result, bail};
+use std::fmt::Debug;
+
+/// Build an accumulator from `values` with `create` and `add_input`.
+fn accumulate<V, A, O, C>(comb: &C, values: &[V]) -> A
+where
+    V: Clone,
+    C: CombineFn<V, A, O>,
+{
+    let mut acc = comb.create();
+    for v in values {
+        comb.add_input(&mut acc, v.clone());
+    }
+    acc
+}
+
+/// Merge `other` into `acc` and return the result.
+fn merged<V, A, O, C>(comb: &C, mut acc: A, other: A) -> A
+where
+    C: CombineFn<V, A, O>,
+{
+    comb.merge(&mut acc, other);
+    acc
+}
+
+/// Check that `merge` is associative for accumulators built from `a`, `b` and `c`:
+/// `(a · b) · c` and `a · (b · c)` must finish to the same output.
+///
+/// # Errors
+/// Returns an error naming both outputs if they differ.
+pub fn check_merge_associative<V, A, O, C>(comb: &C, a: &[V], b: &[V], c: &[V]) -> Result<()>
+where
+    V: Clone,
+    O: PartialEq + Debug,
+    C: CombineFn<V, A, O>,
+{
+    if a.is_empty() && b.is_empty() && c.is_empty() {
+        return Ok(());
+    }
+    let left = merged(
+        comb,
+        merged(comb, accumulate(comb, a), accumulate(comb, b)),
+        accumulate(comb, c),
+    );
+    let right = merged(
+        comb,
+        accumulate(comb, a),
+        merged(comb, accumulate(comb, b), accumulate(comb, c)),
+    );
+    let (left, right) = (comb.finish(left), comb.finish(right));
+    if left != right {
+        bail!(
+            "merge is not associative: (a · b) · c gives {left:?} but a · (b · c) gives {right:?}"
+        );
+    }
+    Ok(())
+}
+
+/// Check that `merge` is commutative for accumulators built from `a` and `b`:
+/// `a · b` and `b · a` must finish to the same output.
+///
+/// # Errors
+/// Returns an error naming both outputs if they differ.
+pub fn check_merge_commutative<V, A, O, C>(comb: &C, a: &[V], b: &[V]) -> Result<()>
+where
+    V: Clone,
+    O: PartialEq + Debug,
+    C: CombineFn<V, A, O>,
+{
+    if a.is_empty() && b.is_empty() {
+        return Ok(());
+    }
+    let ab = comb.finish(merged(comb, accumulate(comb, a), accumulate(comb, b)));
+    let ba = comb.finish(merged(comb, accumulate(comb, b), accumulate(comb, a)));
+    if ab != ba {
+        bail!("merge is not commutative: a · b gives {ab:?} but b · a gives {ba:?}");
+    }
+    Ok(())
+}
+
+/// Check that a fresh accumulator from `create` is an identity for `merge` on both
+/// sides, for the accumulator built from `values`.
+///
+/// Partitions that receive no values for a key contribute such empty accumulators.
+///
+/// # Errors
+/// Returns an error if merging with an empty accumulator changes the output.
+pub fn check_empty_identity<V, A, O, C>(comb: &C, values: &[V]) -> Result<()>
+where
+    V: Clone,
+    O: PartialEq + Debug,
+    C: CombineFn<V, A, O>,
+{
+    if values.is_empty() {
+        return Ok(());
+    }
+    let plain = comb.finish(accumulate(comb, values));
+    let left = comb.finish(merged(comb, comb.create(), accumulate(comb, values)));
+    if left != plain {
+        bail!("create() is not a left identity of merge: expected {plain:?}, got {left:?}");
+    }
+    let right = comb.finish(merged(comb, accumulate(comb, values), comb.create()));
+    if right != plain {
+        bail!("create() is not a right identity of merge: expected {plain:?}, got {right:?}");
+    }
+    Ok(())
+}
+
+/// Check that one accumulator fed every value (the lifted path, as after
+/// `group_by_key`) finishes to the same output as one accumulator per partition merged
+/// in partition order (the partial-aggregation path of `combine_values`).
+///
+/// # Errors
+/// Returns an error naming both outputs if they differ.
+pub fn check_lifted_equivalence<V, A, O, C>(comb: &C, partitions: &[Vec<V>]) -> Result<()>
+where
+    V: Clone,
+    O: PartialEq + Debug,
+    C: CombineFn<V, A, O>,
+{
+    let all: Vec<V> = partitions.iter().flatten().cloned().collect();
+    if all.is_empty() {
+        return Ok(());
+    }
+    let lifted = comb.finish(accumulate(comb, &all));
+    let partial = partitions
+        .iter()
+        .map(|part| accumulate(comb, part))
+        .fold(comb.create(), |acc, part| merged(comb, acc, part));
+    let partial = comb.finish(partial);
+    if lifted != partial {
+        bail!(
+            "partitioned combine disagrees with a single pass over {} partition(s): \
+             single pass gives {lifted:?}, merged partials give {partial:?}",
+            partitions.len()
+        );
+    }
+    Ok(())
+}
+
+/// Check, for a combiner whose input is a whole group, that adding the group `a ++ b`
+/// at once finishes to the same output as adding `a` and then `b`.
+///
+/// This is the law [`CombineFn::accepts_partial_groups`] declares.
+///
+/// # Errors
+/// Returns an error naming both outputs if they differ.
+pub fn check_partial_groups<T, A, O, C>(comb: &C, a: &[T], b: &[T]) -> Result<()>
+where
+    T: Clone,
+    O: PartialEq + Debug,
+    C: CombineFn<Vec<T>, A, O>,
+{
+    let whole: Vec<T> = a.iter().chain(b).cloned().collect();
+    let at_once = comb.finish(accumulate(comb, &[whole]));
+    let in_pieces = comb.finish(accumulate(comb, &[a.to_vec(), b.to_vec()]));
+    if at_once != in_pieces {
+        bail!(
+            "adding a group in pieces changes the result: whole group gives {at_once:?}, \
+             pieces give {in_pieces:?}"
+        );
+    }
+    Ok(())
+}
+
+/// Run every law that applies to `comb` over `partitions`:
+///
+/// - [`check_empty_identity`] on all values,
+/// - [`check_lifted_equivalence`] on the partitions,
+/// - [`check_merge_associative`] on the first three partitions (missing ones are empty),
+/// - [`check_merge_commutative`] on the first two partitions, when the combiner reports
+///   [`CombineFn::is_associative_commutative`].
+///
+/// # Errors
+/// Returns the first violated law.
+pub fn check_combiner_laws<V, A, O, C>(comb: &C, partitions: &[Vec<V>]) -> Result<()>
+where
+    V: Clone,
+    O: PartialEq + Debug,
+    C: CombineFn<V, A, O>,
+{
+    let part = |i: usize| partitions.get(i).map_or(&[][..], Vec::as_slice);
+    let all: Vec<V> = partitions.iter().flatten().cloned().collect();
+
+    check_empty_identity(comb, &all)?;
+    check_lifted_equivalence(comb, partitions)?;
+    check_merge_associative(comb, part(0), part(1), part(2))?;
+    if comb.is_associative_commutative() {
+        check_merge_commutative(comb, part(0), part(1))?;
+    }
+    Ok(())
+}
+
+/// Generate between 1 and `max_parts` partitions of up to `max_len` elements each.
+///
+/// *Enabled when the `proptest` feature is on.*
+#[cfg(feature = "proptest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "proptest")))]
+pub fn partitioned<S>(
+    element: S,
+    max_len: usize,
+    max_parts: usize,
+) -> impl proptest::strategy::Strategy<Value = Vec<Vec<S::Value>>>
+where
+    S: proptest::strategy::Strategy,
+{
+    use proptest::collection::vec;
+    vec(vec(element, 0..=max_len), 1..=max_parts.max(1))
+}
+
+/// Three inputs generated by [`three_parts`].
+#[cfg(feature = "proptest")]
+type ThreeParts<T> = (Vec<T>, Vec<T>, Vec<T>);
+
+/// Generate three inputs of up to `max_len` elements each, for
+/// [`check_merge_associative`].
+///
+/// *Enabled when the `proptest` feature is on.*
+#[cfg(feature = "proptest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "proptest")))]
+pub fn three_parts<S>(
+    element: S,
+    max_len: usize,
+) -> impl proptest::strategy::Strategy<Value = ThreeParts<S::Value>>
+where
+    S: proptest::strategy::Strategy + Clone,
+{
+    use proptest::collection::vec;
+    (
+        vec(element.clone(), 0..=max_len),
+        vec(element.clone(), 0..=max_len),
+        vec(element, 0..=max_len),
+    )
+}
@@ -0,0 +1,164 @@
+//! Deterministic clock and randomness for pipeline tests.
+//!
+//! A [`TestContext`] holds a [`FakeClock`] and a sampling seed, and installs both on the
+//! pipelines it creates. Transforms that read processing time
+//! ([`attach_processing_time`](crate::PCollection::attach_processing_time)) then see the
+//! fake clock, and default-seeded sampling
+//! ([`sample_globally`](crate::PCollection::sample_globally),
+//! [`sample_per_key`](crate::PCollection::sample_per_key)) uses the context's seed, so
+//! their output is the same on every run.
+//!
+//! The clock is shared: advancing it through [`TestContext::clock`] between runs changes
+//! the times later runs observe.
+//!
+//! # Example
+//! ```
+//! use ironbeam::*;
+//! use ironbeam::testing::TestContext;
+//! # use anyhow::Result;
+//!
+//! # fn main() -> Result<()> {
+//! let ctx = TestContext::new().with_start_time(1_000);
+//! let p = ctx.pipeline();
+//! let stamped = from_vec(&p, vec!["a".to_string()]).attach_processing_time();
+//!
+//! assert_eq!(stamped.clone().collect_seq()?[0].ts, 1_000);
+//! ctx.clock().advance(500);
+//! assert_eq!(stamped.collect_seq()?[0].ts, 1_500);
+//! # Ok(())
+//! # }
+//! ```
+
+use super::TestPipeline;
+use crate::Pipeline;
+use crate::window::{Clock, TimestampMs};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// A [`Clock`] that only moves when told to.
+///
+/// Reads return the current time and then advance it by the tick (`0` by default), so a
+/// non-zero tick gives each read a distinct, predictable time. In a parallel run the
+/// order of reads across partitions is not fixed, so use a zero tick when outputs must
+/// match between modes.
+#[derive(Debug, Default)]
+pub struct FakeClock {
+    now: AtomicU64,
+    tick: u64,
+}
+
+impl FakeClock {
+    /// A clock stopped at `start`.
+    #[must_use]
+    pub const fn new(start: TimestampMs) -> Self {
+        Self {
+            now: AtomicU64::new(start),
+            tick: 0,
+        }
+    }
+
+    /// A clock at `start` that moves forward by `tick` after every read.
+    #[must_use]
+    pub const fn with_tick(start: TimestampMs, tick: u64) -> Self {
+        Self {
+            now: AtomicU64::new(start),
+            tick,
+        }
+    }
+
+    /// The time the next read will return, without advancing the clock.
+    #[must_use]
+    pub fn peek(&self) -> TimestampMs {
+        self.now.load(Ordering::SeqCst)
+    }
+
+    /// Move the clock to `now`.
+    pub fn set(&self, now: TimestampMs) {
+        self.now.store(now, Ordering::SeqCst);
+    }
+
+    /// Move the clock forward by `ms`.
+    pub fn advance(&self, ms: u64) {
+        self.now.fetch_add(ms, Ordering::SeqCst);
+    }
+}
+
+impl Clock for FakeClock {
+    fn now_ms(&self) -> TimestampMs {
+        self.now.fetch_add(self.tick, Ordering::SeqCst)
+    }
+}
+
+/// A fake clock and sampling seed for building deterministic test pipelines.
+///
+/// See the [module docs](self) for what each one controls.
+#[derive(Clone, Debug)]
+pub struct TestContext {
+    clock: Arc<FakeClock>,
+    seed: u64,
+}
+
+impl TestContext {
+    /// A context whose clock is stopped at `0` and whose sampling seed is `0`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            clock: Arc::new(FakeClock::new(0)),
+            seed: 0,
+        }
+    }
+
+    /// Use `seed` for default-seeded sampling.
+    #[must_use]
+    pub const fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Start the clock at `start`.
+    #[must_use]
+    pub fn with_start_time(self, start: TimestampMs) -> Self {
+        self.clock.set(start);
+        self
+    }
+
+    /// Use `clock`, for example one built with [`FakeClock::with_tick`].
+    #[must_use]
+    pub fn with_clock(mut self, clock: FakeClock) -> Self {
+        self.clock = Arc::new(clock);
+        self
+    }
+
+    /// The context's clock, for advancing time between runs.
+    #[must_use]
+    pub fn clock(&self) -> &FakeClock {
+        &self.clock
+    }
+
+    /// The sampling seed.
+    #[must_use]
+    pub const fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// A new [`TestPipeline`] using this context's clock and seed.
+    #[must_use]
+    pub fn pipeline(&self) -> TestPipeline {
+        let p = TestPipeline::new();
+        self.install(&p);
+        p
+    }
+
+    /// Install this context's clock and seed on an existing pipeline. Transforms built
+    /// before the call keep the clock and seed they were built with.
+    pub fn install(&self, p: &Pipeline) {
+        p.set_clock(Arc::clone(&self.clock) as Arc<dyn Clock>);
+        p.set_sample_seed(self.seed);
+    }
+}
+
+impl Default for TestContext {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,266 @@
+//! Transform-level test harness with golden-file snapshots.
+//!
+//! [`TransformTester`] runs one transform -- a closure over a [`PCollection`] or a
+//! [`CompositeTransform`] -- against supplied inputs, once sequentially and once in
+//! parallel, and fails if the two runs disagree. Its output can also be checked against a
+//! golden file: a JSONL snapshot with one element per line.
+//!
+//! # Golden files
+//! - A missing golden file is written from the current output and the check passes, so
+//!   the first run of a new test records its snapshot.
+//! - Setting the `IRONBEAM_UPDATE_GOLDEN` environment variable (to any value) rewrites
+//!   every golden file instead of comparing, after an intended behavior change.
+//! - On a mismatch the error lists a line diff of the snapshot against the output.
+//!
+//! Unless [`TransformTester::ordered`] is set, outputs are compared as multisets and the
+//! snapshot is written in sorted order, so transforms whose output order depends on
+//! partitioning (grouping, shuffles) still produce stable snapshots.
+//!
+//! # Example
+//! ```no_run
+//! use ironbeam::*;
+//! use ironbeam::testing::TransformTester;
+//! # use anyhow::Result;
+//!
+//! # fn main() -> Result<()> {
+//! let counts = TransformTester::new(|words: PCollection<String>| {
+//!     words.key_by(|w: &String| w.clone()).count_per_key()
+//! })
+//! .input(vec!["a".to_string(), "b".to_string(), "a".to_string()])
+//! .golden("tests/golden/word_counts.jsonl")
+//! .run()?;
+//! assert_eq!(counts.len(), 2);
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::extensions::CompositeTransform;
+use crate::{Element, PCollection, Pipeline, from_vec};
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+use std::env::var_os;
+use std::fmt::Write as _;
+use std::fs::{create_dir_all, read_to_string, write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Environment variable that makes [`TransformTester::run`] rewrite golden files.
+pub const UPDATE_GOLDEN_ENV: &str = "IRONBEAM_UPDATE_GOLDEN";
+
+/// Largest `expected × actual` line product diffed line by line; bigger mismatches are
+/// summarized by their first differing line instead.
+const MAX_DIFF_CELLS: usize = 4_000_000;
+
+type TransformFn<I, O> = Arc<dyn Fn(PCollection<I>) -> PCollection<O> + Send + Sync>;
+
+/// Runs a single transform against fixed inputs in sequential and parallel modes.
+///
+/// Built with [`new`](Self::new) or [`composite`](Self::composite), configured with the
+/// builder methods, and executed by [`run`](Self::run). Each run builds a fresh
+/// [`Pipeline`].
+///
+/// # Example
+/// ```
+/// use ironbeam::*;
+/// use ironbeam::testing::TransformTester;
+/// # use anyhow::Result;
+///
+/// # fn main() -> Result<()> {
+/// let out = TransformTester::new(|xs: PCollection<u32>| xs.filter(|x: &u32| x.is_multiple_of(2)))
+///     .input((1..=10).collect())
+///     .partitions(3)
+///     .run()?;
+/// assert_eq!(out, vec![2, 4, 6, 8, 10]);
+/// # Ok(())
+/// # }
+/// ```
+pub struct TransformTester<I, O> {
+    transform: TransformFn<I, O>,
+    input: Vec<I>,
+    partitions: usize,
+    ordered: bool,
+    golden: Option<PathBuf>,
+}
+
+impl<I: Element, O: Element + Serialize> TransformTester<I, O> {
+    /// Test the transform `f`, which receives the input collection and returns the
+    /// output collection.
+    #[must_use]
+    pub fn new<F>(f: F) -> Self
+    where
+        F: Fn(PCollection<I>) -> PCollection<O> + Send + Sync + 'static,
+    {
+        Self {
+            transform: Arc::new(f),
+            input: Vec::new(),
+            partitions: 4,
+            ordered: false,
+            golden: None,
+        }
+    }
+
+    /// Test a [`CompositeTransform`].
+    #[must_use]
+    pub fn composite<CT>(transform: CT) -> Self
+    where
+        CT: CompositeTransform<I, O> + 'static,
+    {
+        Self::new(move |input: PCollection<I>| transform.expand(input))
+    }
+
+    /// Set the input elements (empty by default).
+    #[must_use]
+    pub fn input(mut self, input: Vec<I>) -> Self {
+        self.input = input;
+        self
+    }
+
+    /// Set the partition count of the parallel run (default `4`, minimum `1`).
+    #[must_use]
+    pub fn partitions(mut self, partitions: usize) -> Self {
+        self.partitions = partitions.max(1);
+        self
+    }
+
+    /// Require the sequential and parallel runs, and the golden file, to match in order
+    /// rather than as multisets. Use for transforms with a defined output order, such as
+    /// sorts.
+    #[must_use]
+    pub const fn ordered(mut self) -> Self {
+        self.ordered = true;
+        self
+    }
+
+    /// Compare the output against the JSONL golden file at `path`.
+    ///
+    /// See the [module docs](self) for how the file is created and updated.
+    #[must_use]
+    pub fn golden(mut self, path: impl AsRef<Path>) -> Self {
+        self.golden = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Run the transform sequentially and in parallel, check that the outputs agree and
+    /// match the golden file if one is set, and return the sequential output.
+    ///
+    /// # Errors
+    /// Returns an error if either run fails, the runs disagree, an element cannot be
+    /// serialized, the golden file cannot be read or written, or the output differs from
+    /// the golden file. Mismatch errors include a line diff.
+    pub fn run(&self) -> Result<Vec<O>> {
+        let p = Pipeline::default();
+        let out = (self.transform)(from_vec(&p, self.input.clone()));
+        let sequential = out.clone().collect_seq().context("sequential run failed")?;
+        let parallel = out
+            .collect_par(None, Some(self.partitions))
+            .context("parallel run failed")?;
+
+        let seq_lines = self.render(&sequential)?;
+        let par_lines = self.render(&parallel)?;
+        if seq_lines != par_lines {
+            bail!(
+                "sequential and parallel ({} partitions) outputs differ:\n{}",
+                self.partitions,
+                line_diff(&seq_lines, &par_lines, "sequential", "parallel")
+            );
+        }
+
+        if let Some(path) = &self.golden {
+            check_golden(path, &seq_lines)?;
+        }
+        Ok(sequential)
+    }
+
+    /// Render elements as JSON lines, sorted unless the tester is ordered.
+    fn render(&self, elems: &[O]) -> Result<Vec<String>> {
+        let mut lines = elems
+            .iter()
+            .map(|e| serde_json::to_string(e).context("serialize output element"))
+            .collect::<Result<Vec<_>>>()?;
+        if !self.ordered {
+            lines.sort_unstable();
+        }
+        Ok(lines)
+    }
+}
+
+/// Compare `lines` with the golden file at `path`, writing it when missing or when
+/// [`UPDATE_GOLDEN_ENV`] is set.
+fn check_golden(path: &Path, lines: &[String]) -> Result<()> {
+    if var_os(UPDATE_GOLDEN_ENV).is_some() || !path.exists() {
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            create_dir_all(parent).with_context(|| format!("mkdir -p {}", parent.display()))?;
+        }
+        let mut body = lines.join("\n");
+        if !body.is_empty() {
+            body.push('\n');
+        }
+        write(path, body).with_context(|| format!("write golden file {}", path.display()))?;
+        return Ok(());
+    }
+
+    let text =
+        read_to_string(path).with_context(|| format!("read golden file {}", path.display()))?;
+    let expected: Vec<String> = text.lines().map(str::to_string).collect();
+    if expected != lines {
+        bail!(
+            "output differs from golden file {} (set {UPDATE_GOLDEN_ENV}=1 to update it):\n{}",
+            path.display(),
+            line_diff(&expected, lines, "golden", "actual")
+        );
+    }
+    Ok(())
+}
+
+/// Render a unified-style diff of `old` against `new`: unchanged lines are prefixed
+/// with two spaces, removed ones with `- ` and added ones with `+ `.
+fn line_diff(old: &[String], new: &[String], old_name: &str, new_name: &str) -> String {
+    let mut out = format!(
+        "--- {old_name} ({} lines)\n+++ {new_name} ({} lines)\n",
+        old.len(),
+        new.len()
+    );
+    if old.len().saturating_mul(new.len()) > MAX_DIFF_CELLS {
+        let first = old.iter().zip(new).position(|(a, b)| a != b);
+        let at = first.unwrap_or_else(|| old.len().min(new.len()));
+        let _ = writeln!(out, "first difference at line {}", at + 1);
+        if let Some(line) = old.get(at) {
+            let _ = writeln!(out, "- {line}");
+        }
+        if let Some(line) = new.get(at) {
+            let _ = writeln!(out, "+ {line}");
+        }
+        return out;
+    }
+
+    // Longest common subsequence table over suffixes.
+    let (n, m) = (old.len(), new.len());
+    let mut lcs = vec![vec![0usize; m + 1]; n + 1];
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    while i < n || j < m {
+        if i < n && j < m && old[i] == new[j] {
+            let _ = writeln!(out, "  {}", old[i]);
+            i += 1;
+            j += 1;
+        } else if i < n && (j == m || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            let _ = writeln!(out, "- {}", old[i]);
+            i += 1;
+        } else {
+            let _ = writeln!(out, "+ {}", new[j]);
+            j += 1;
+        }
+    }
+    out
+}
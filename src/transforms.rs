@@ -0,0 +1,15 @@
+//! Reusable [`CompositeTransform`](crate::extensions::CompositeTransform)s for common
+//! processing domains.
+//!
+//! Each submodule packages a family of transforms that projects otherwise rebuild
+//! from `map`/`filter`/`flat_map` by hand. Apply them with
+//! [`PCollection::apply_composite`](crate::PCollection::apply_composite), or, for
+//! transforms generic over the element type, as methods on the collection.
+//!
+//! - [`text`] - Tokenization, case normalization, stopword removal and n-grams
+//! - `geo` - Geohash keys, haversine distance filters and point-in-polygon joins
+//!   (feature `geo`)
+
+#[cfg(feature = "geo")]
+pub mod geo;
+pub mod text;
@@ -0,0 +1,303 @@
+//! Geospatial transforms over latitude/longitude points (feature `geo`).
+//!
+//! Location-analytics pipelines keep rebuilding the same three steps: bucketing
+//! events into grid cells, keeping events near a point of interest, and assigning
+//! each event to the region that contains it. These transforms work on any element
+//! type that implements [`Located`]:
+//!
+//! | Transform                               | Output                                            |
+//! |-----------------------------------------|---------------------------------------------------|
+//! | [`PCollection::key_by_geohash`]         | `(geohash, element)` pairs                        |
+//! | [`PCollection::filter_within_distance`] | elements at most `meters` from a center           |
+//! | [`PCollection::filter_beyond_distance`] | elements more than `meters` from a center         |
+//! | [`PCollection::spatial_join`]           | `(polygon name, element)` per containing polygon  |
+//!
+//! Coordinates are WGS 84 degrees. Distances are great-circle (haversine) distances
+//! on a spherical Earth of radius [`EARTH_RADIUS_M`], accurate to about 0.5%.
+//! Polygons are tested in plain longitude/latitude space, so a polygon must not
+//! cross the antimeridian; split it in two instead.
+//!
+//! ### Example
+//! ```no_run
+//! use ironbeam::*;
+//! use ironbeam::transforms::geo::{Located, Point, Polygon};
+//! use serde::{Deserialize, Serialize};
+//! # use anyhow::Result;
+//! # fn main() -> Result<()> {
+//! #[derive(Clone, Serialize, Deserialize)]
+//! struct Ride { lat: f64, lon: f64, fare_cents: u64 }
+//!
+//! impl Located for Ride {
+//!     fn location(&self) -> Point {
+//!         Point::new(self.lat, self.lon)
+//!     }
+//! }
+//!
+//! let p = Pipeline::default();
+//! let rides = read_jsonl::<Ride>(&p, "rides.jsonl")?;
+//! let zones = side_vec(vec![Polygon::new(
+//!     "downtown",
+//!     [(40.70, -74.02), (40.70, -73.97), (40.76, -73.97), (40.76, -74.02)],
+//! )]);
+//!
+//! let rides_per_cell = rides.clone().key_by_geohash(6).count_per_key();
+//! let fares_per_zone = rides
+//!     .filter_within_distance(Point::new(40.73, -73.99), 5_000.0)
+//!     .spatial_join(&zones)
+//!     .map_values(|r: &Ride| r.fare_cents)
+//!     .sum_per_key();
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::collection::SideInput;
+use crate::{Element, PCollection, Timestamped};
+use rstar::RTree;
+use rstar::primitives::{GeomWithData, Rectangle};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Mean Earth radius in meters, as used by [`Point::haversine_distance`].
+pub const EARTH_RADIUS_M: f64 = 6_371_008.8;
+
+/// The longest geohash [`geohash`] produces; 12 characters locate a point to a few
+/// centimeters.
+pub const MAX_GEOHASH_PRECISION: usize = 12;
+
+const GEOHASH_ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// A position in WGS 84 degrees.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Point {
+    /// Latitude, from -90 (south) to 90 (north).
+    pub lat: f64,
+    /// Longitude, from -180 (west) to 180 (east).
+    pub lon: f64,
+}
+
+impl Point {
+    /// Construct a new [`Point`].
+    #[must_use]
+    pub const fn new(lat: f64, lon: f64) -> Self {
+        Self { lat, lon }
+    }
+
+    /// Great-circle distance to `other`, in meters.
+    #[must_use]
+    pub fn haversine_distance(&self, other: &Self) -> f64 {
+        let (lat1, lat2) = (self.lat.to_radians(), other.lat.to_radians());
+        let dlat = lat2 - lat1;
+        let dlon = (other.lon - self.lon).to_radians();
+        let h = (dlat / 2.0).sin().powi(2) + lat1.cos() * lat2.cos() * (dlon / 2.0).sin().powi(2);
+        2.0 * EARTH_RADIUS_M * h.sqrt().min(1.0).asin()
+    }
+}
+
+impl From<(f64, f64)> for Point {
+    /// Converts a `(lat, lon)` pair.
+    fn from((lat, lon): (f64, f64)) -> Self {
+        Self::new(lat, lon)
+    }
+}
+
+/// Elements that have a position.
+pub trait Located {
+    /// The element's position.
+    fn location(&self) -> Point;
+}
+
+impl Located for Point {
+    fn location(&self) -> Point {
+        *self
+    }
+}
+
+impl Located for (f64, f64) {
+    /// Reads the pair as `(lat, lon)`.
+    fn location(&self) -> Point {
+        Point::from(*self)
+    }
+}
+
+impl<T: Located> Located for Timestamped<T> {
+    fn location(&self) -> Point {
+        self.value.location()
+    }
+}
+
+/// Encode `point` as a base-32 geohash of `precision` characters, clamped to
+/// `1..=`[`MAX_GEOHASH_PRECISION`].
+///
+/// Points sharing a geohash prefix lie in the same grid cell: 5 characters is a
+/// cell of about 5 km, 6 about 1.2 km, 7 about 150 m.
+#[must_use]
+pub fn geohash(point: Point, precision: usize) -> String {
+    let precision = precision.clamp(1, MAX_GEOHASH_PRECISION);
+    let (mut lat, mut lon) = ((-90.0, 90.0), (-180.0, 180.0));
+    let mut hash = String::with_capacity(precision);
+    let mut even = true;
+    let (mut bits, mut index) = (0, 0);
+    while hash.len() < precision {
+        let (range, value): (&mut (f64, f64), f64) = if even {
+            (&mut lon, point.lon)
+        } else {
+            (&mut lat, point.lat)
+        };
+        let mid = f64::midpoint(range.0, range.1);
+        index <<= 1;
+        if value >= mid {
+            index |= 1;
+            range.0 = mid;
+        } else {
+            range.1 = mid;
+        }
+        even = !even;
+        bits += 1;
+        if bits == 5 {
+            hash.push(char::from(GEOHASH_ALPHABET[index]));
+            (bits, index) = (0, 0);
+        }
+    }
+    hash
+}
+
+/// A named polygon with optional holes, the right-hand side of
+/// [`spatial_join`](PCollection::spatial_join).
+///
+/// Rings are lists of vertices and close implicitly; repeating the first vertex at
+/// the end is allowed. A point is inside when it lies inside the exterior ring and
+/// outside every hole; points exactly on an edge may fall on either side.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Polygon {
+    /// The key [`spatial_join`](PCollection::spatial_join) emits for points inside.
+    pub name: String,
+    /// The outer boundary.
+    pub exterior: Vec<Point>,
+    /// Areas cut out of the polygon.
+    pub holes: Vec<Vec<Point>>,
+}
+
+impl Polygon {
+    /// A polygon without holes; vertices are anything convertible to a [`Point`],
+    /// such as `(lat, lon)` pairs.
+    pub fn new(
+        name: impl Into<String>,
+        exterior: impl IntoIterator<Item = impl Into<Point>>,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            exterior: exterior.into_iter().map(Into::into).collect(),
+            holes: Vec::new(),
+        }
+    }
+
+    /// Cut a hole out of the polygon.
+    #[must_use]
+    pub fn with_hole(mut self, ring: impl IntoIterator<Item = impl Into<Point>>) -> Self {
+        self.holes.push(ring.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Whether `point` lies inside the polygon.
+    #[must_use]
+    pub fn contains(&self, point: Point) -> bool {
+        ring_contains(&self.exterior, point) && !self.holes.iter().any(|h| ring_contains(h, point))
+    }
+
+    /// The `[lon, lat]` bounding box of the exterior ring, or `None` if it is empty.
+    fn bounding_box(&self) -> Option<Rectangle<[f64; 2]>> {
+        let first = self.exterior.first()?;
+        let (mut lo, mut hi) = ([first.lon, first.lat], [first.lon, first.lat]);
+        for p in &self.exterior {
+            lo = [lo[0].min(p.lon), lo[1].min(p.lat)];
+            hi = [hi[0].max(p.lon), hi[1].max(p.lat)];
+        }
+        Some(Rectangle::from_corners(lo, hi))
+    }
+}
+
+/// Even-odd ray casting: count the edges a ray east of `point` crosses.
+fn ring_contains(ring: &[Point], point: Point) -> bool {
+    let mut inside = false;
+    let mut prev = match ring.last() {
+        Some(p) if ring.len() >= 3 => *p,
+        _ => return false,
+    };
+    for &cur in ring {
+        if (cur.lat > point.lat) != (prev.lat > point.lat) {
+            let crossing =
+                (prev.lon - cur.lon) * (point.lat - cur.lat) / (prev.lat - cur.lat) + cur.lon;
+            if point.lon < crossing {
+                inside = !inside;
+            }
+        }
+        prev = cur;
+    }
+    inside
+}
+
+/// Polygons indexed by bounding box, so each lookup only runs the exact test on the
+/// few polygons whose box contains the point.
+struct PolygonIndex {
+    polygons: Vec<Polygon>,
+    tree: RTree<GeomWithData<Rectangle<[f64; 2]>, usize>>,
+}
+
+impl PolygonIndex {
+    fn new(polygons: &[Polygon]) -> Self {
+        let boxes = polygons
+            .iter()
+            .enumerate()
+            .filter_map(|(i, poly)| Some(GeomWithData::new(poly.bounding_box()?, i)))
+            .collect();
+        Self {
+            polygons: polygons.to_vec(),
+            tree: RTree::bulk_load(boxes),
+        }
+    }
+
+    fn containing(&self, point: Point) -> impl Iterator<Item = &Polygon> {
+        self.tree
+            .locate_all_at_point(&[point.lon, point.lat])
+            .map(|entry| &self.polygons[entry.data])
+            .filter(move |poly| poly.contains(point))
+    }
+}
+
+impl<T: Element + Located> PCollection<T> {
+    /// Key each element by the geohash of its location, with `precision` characters
+    /// (see [`geohash`]).
+    #[must_use]
+    pub fn key_by_geohash(self, precision: usize) -> PCollection<(String, T)> {
+        self.key_by(move |t: &T| geohash(t.location(), precision))
+    }
+
+    /// Keep elements whose great-circle distance from `center` is at most `meters`.
+    #[must_use]
+    pub fn filter_within_distance(self, center: Point, meters: f64) -> Self {
+        self.filter(move |t: &T| t.location().haversine_distance(&center) <= meters)
+    }
+
+    /// Keep elements whose great-circle distance from `center` is more than `meters`.
+    #[must_use]
+    pub fn filter_beyond_distance(self, center: Point, meters: f64) -> Self {
+        self.filter(move |t: &T| t.location().haversine_distance(&center) > meters)
+    }
+
+    /// Join each element with the polygons that contain it, emitting one
+    /// `(polygon name, element)` pair per containing polygon; elements outside every
+    /// polygon are dropped.
+    ///
+    /// The polygons are indexed in an R-tree once, when the transform is built, and
+    /// the index is shared by every partition.
+    #[must_use]
+    pub fn spatial_join(self, polygons: &SideInput<Polygon>) -> PCollection<(String, T)> {
+        let index = Arc::new(PolygonIndex::new(&polygons.0));
+        self.flat_map(move |t: &T| {
+            index
+                .containing(t.location())
+                .map(|poly| (poly.name.clone(), t.clone()))
+                .collect::<Vec<_>>()
+        })
+    }
+}
@@ -0,0 +1,374 @@
+//! Text preprocessing transforms.
+//!
+//! The building blocks of word counts and NLP feature pipelines, as composites over
+//! `PCollection<String>` (one document or token per element) and
+//! `PCollection<Vec<String>>` (one tokenized document per element):
+//!
+//! | Transform           | Input → output                                         |
+//! |---------------------|--------------------------------------------------------|
+//! | [`Tokenize`]        | `String` → `Vec<String>`, split on whitespace or by a regex |
+//! | [`NormalizeCase`]   | `String` → `String`, or `Vec<String>` → `Vec<String>`  |
+//! | [`RemoveStopwords`] | drops stopword elements of a `String` collection, or stopword tokens of each `Vec<String>` |
+//! | [`NGrams`]          | `Vec<String>` → `Vec<String>` of space-joined n-grams  |
+//!
+//! Tokenized documents stay grouped until you flatten them, so n-grams never span two
+//! documents.
+//!
+//! ### Example
+//! ```no_run
+//! use ironbeam::*;
+//! use ironbeam::transforms::text::{NGrams, NormalizeCase, RemoveStopwords, Tokenize};
+//! # use anyhow::Result;
+//!
+//! # fn main() -> Result<()> {
+//! let p = Pipeline::default();
+//! let lines = from_vec(&p, vec!["The quick fox".to_string(), "the lazy dog".into()]);
+//!
+//! let tokens = lines
+//!     .apply_composite(&Tokenize::regex(r"[\p{L}\p{N}']+")?)
+//!     .apply_composite(&NormalizeCase::Lower)
+//!     .apply_composite(&RemoveStopwords::english());
+//! let word_counts = tokens
+//!     .clone()
+//!     .flat_map(|doc: &Vec<String>| doc.clone())
+//!     .count_per_element();
+//! let bigrams = tokens.apply_composite(&NGrams::new(2));
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::collection::{SideInput, SideSingleton};
+use crate::extensions::CompositeTransform;
+use crate::{PCollection, side_singleton};
+use anyhow::{Context, Result};
+use regex::Regex;
+use std::collections::HashSet;
+
+/// Split each document into tokens.
+///
+/// [`Tokenize::whitespace`] splits on Unicode whitespace; [`Tokenize::regex`] emits
+/// every match of a pattern, which also drops punctuation (e.g. `\w+`). Tokens
+/// shorter than [`with_min_len`](Self::with_min_len) characters are dropped.
+#[derive(Clone, Debug)]
+pub struct Tokenize {
+    pattern: Option<Regex>,
+    min_len: usize,
+}
+
+impl Tokenize {
+    /// Split on runs of whitespace.
+    #[must_use]
+    pub const fn whitespace() -> Self {
+        Self {
+            pattern: None,
+            min_len: 1,
+        }
+    }
+
+    /// Emit each non-overlapping match of `pattern` as a token.
+    ///
+    /// # Errors
+    /// Returns an error if `pattern` is not a valid regular expression.
+    pub fn regex(pattern: &str) -> Result<Self> {
+        let pattern =
+            Regex::new(pattern).with_context(|| format!("invalid token pattern {pattern:?}"))?;
+        Ok(Self {
+            pattern: Some(pattern),
+            min_len: 1,
+        })
+    }
+
+    /// Drop tokens with fewer than `min_len` characters (default 1).
+    #[must_use]
+    pub fn with_min_len(mut self, min_len: usize) -> Self {
+        self.min_len = min_len;
+        self
+    }
+
+    /// Tokenize one document.
+    #[must_use]
+    pub fn tokens(&self, text: &str) -> Vec<String> {
+        let keep = |t: &&str| t.chars().count() >= self.min_len;
+        match &self.pattern {
+            Some(pattern) => pattern
+                .find_iter(text)
+                .map(|m| m.as_str())
+                .filter(keep)
+                .map(str::to_string)
+                .collect(),
+            None => text
+                .split_whitespace()
+                .filter(keep)
+                .map(str::to_string)
+                .collect(),
+        }
+    }
+}
+
+impl CompositeTransform<String, Vec<String>> for Tokenize {
+    fn expand(&self, input: PCollection<String>) -> PCollection<Vec<String>> {
+        let tokenize = self.clone();
+        input.map(move |text: &String| tokenize.tokens(text))
+    }
+}
+
+/// Convert text to a single case, using Unicode case mapping.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NormalizeCase {
+    /// Lowercase everything.
+    Lower,
+    /// Uppercase everything.
+    Upper,
+}
+
+impl NormalizeCase {
+    /// Normalize one string.
+    #[must_use]
+    pub fn apply(self, text: &str) -> String {
+        match self {
+            Self::Lower => text.to_lowercase(),
+            Self::Upper => text.to_uppercase(),
+        }
+    }
+}
+
+impl CompositeTransform<String, String> for NormalizeCase {
+    fn expand(&self, input: PCollection<String>) -> PCollection<String> {
+        let case = *self;
+        input.map(move |text: &String| case.apply(text))
+    }
+}
+
+impl CompositeTransform<Vec<String>, Vec<String>> for NormalizeCase {
+    fn expand(&self, input: PCollection<Vec<String>>) -> PCollection<Vec<String>> {
+        let case = *self;
+        input.map(move |doc: &Vec<String>| doc.iter().map(|t| case.apply(t)).collect())
+    }
+}
+
+/// Common English function words, lowercase.
+pub const ENGLISH_STOPWORDS: &[&str] = &[
+    "a",
+    "about",
+    "above",
+    "after",
+    "again",
+    "against",
+    "all",
+    "am",
+    "an",
+    "and",
+    "any",
+    "are",
+    "as",
+    "at",
+    "be",
+    "because",
+    "been",
+    "before",
+    "being",
+    "below",
+    "between",
+    "both",
+    "but",
+    "by",
+    "can",
+    "could",
+    "did",
+    "do",
+    "does",
+    "doing",
+    "down",
+    "during",
+    "each",
+    "few",
+    "for",
+    "from",
+    "further",
+    "had",
+    "has",
+    "have",
+    "having",
+    "he",
+    "her",
+    "here",
+    "hers",
+    "herself",
+    "him",
+    "himself",
+    "his",
+    "how",
+    "i",
+    "if",
+    "in",
+    "into",
+    "is",
+    "it",
+    "its",
+    "itself",
+    "just",
+    "me",
+    "more",
+    "most",
+    "my",
+    "myself",
+    "no",
+    "nor",
+    "not",
+    "now",
+    "of",
+    "off",
+    "on",
+    "once",
+    "only",
+    "or",
+    "other",
+    "our",
+    "ours",
+    "ourselves",
+    "out",
+    "over",
+    "own",
+    "same",
+    "she",
+    "should",
+    "so",
+    "some",
+    "such",
+    "than",
+    "that",
+    "the",
+    "their",
+    "theirs",
+    "them",
+    "themselves",
+    "then",
+    "there",
+    "these",
+    "they",
+    "this",
+    "those",
+    "through",
+    "to",
+    "too",
+    "under",
+    "until",
+    "up",
+    "very",
+    "was",
+    "we",
+    "were",
+    "what",
+    "when",
+    "where",
+    "which",
+    "while",
+    "who",
+    "whom",
+    "why",
+    "will",
+    "with",
+    "would",
+    "you",
+    "your",
+    "yours",
+    "yourself",
+    "yourselves",
+];
+
+/// Drop stopwords, held as a side input shared by every partition.
+///
+/// Matching is exact, so apply [`NormalizeCase`] first when the word list is in one
+/// case (as [`ENGLISH_STOPWORDS`] is).
+#[derive(Clone)]
+pub struct RemoveStopwords {
+    words: SideSingleton<HashSet<String>>,
+}
+
+impl RemoveStopwords {
+    /// Remove the given words.
+    pub fn new(words: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            words: side_singleton(words.into_iter().map(Into::into).collect()),
+        }
+    }
+
+    /// Remove [`ENGLISH_STOPWORDS`].
+    #[must_use]
+    pub fn english() -> Self {
+        Self::new(ENGLISH_STOPWORDS.iter().copied())
+    }
+
+    /// Remove the words of a vector side input, e.g. a list loaded with
+    /// [`side_vec`](crate::side_vec).
+    #[must_use]
+    pub fn from_side(words: &SideInput<String>) -> Self {
+        Self::new(words.0.iter().cloned())
+    }
+
+    /// Whether `word` is a stopword.
+    #[must_use]
+    pub fn contains(&self, word: &str) -> bool {
+        self.words.0.contains(word)
+    }
+}
+
+impl CompositeTransform<String, String> for RemoveStopwords {
+    fn expand(&self, input: PCollection<String>) -> PCollection<String> {
+        input.filter_with_singleton(&self.words, |word, words| !words.contains(word))
+    }
+}
+
+impl CompositeTransform<Vec<String>, Vec<String>> for RemoveStopwords {
+    fn expand(&self, input: PCollection<Vec<String>>) -> PCollection<Vec<String>> {
+        input.map_with_singleton(&self.words, |doc, words| {
+            doc.iter()
+                .filter(|t| !words.contains(t.as_str()))
+                .cloned()
+                .collect()
+        })
+    }
+}
+
+/// Turn each tokenized document into its n-grams: every run of `n` consecutive
+/// tokens, joined with a separator (a space by default). A document with fewer than
+/// `n` tokens has no n-grams.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NGrams {
+    n: usize,
+    separator: String,
+}
+
+impl NGrams {
+    /// N-grams of `n` tokens (minimum 1).
+    #[must_use]
+    pub fn new(n: usize) -> Self {
+        Self {
+            n: n.max(1),
+            separator: " ".to_string(),
+        }
+    }
+
+    /// Join the tokens of each n-gram with `separator`.
+    #[must_use]
+    pub fn with_separator(mut self, separator: impl Into<String>) -> Self {
+        self.separator = separator.into();
+        self
+    }
+
+    /// The n-grams of one document.
+    #[must_use]
+    pub fn ngrams(&self, tokens: &[String]) -> Vec<String> {
+        tokens
+            .windows(self.n)
+            .map(|gram| gram.join(&self.separator))
+            .collect()
+    }
+}
+
+impl CompositeTransform<Vec<String>, Vec<String>> for NGrams {
+    fn expand(&self, input: PCollection<Vec<String>>) -> PCollection<Vec<String>> {
+        let ngrams = self.clone();
+        input.map(move |doc: &Vec<String>| ngrams.ngrams(doc))
+    }
+}
@@ -7,11 +7,15 @@
 //!   the runner (length, splitting, cloning). Concrete implementations are produced
 //!   via [`vec_ops_for`].
 //!
+//! - [`LazyPartition`]: a partition that may still be a lazy element stream, used by
+//!   streaming execution to avoid materializing between fused stateless ops.
+//!
 //! The runner relies on `VecOps` to handle `Source` payloads without knowing `T` at
 //! compile time. Splitting is used to create per-partition chunks for parallel
 //! execution. All operations are safe and return `None` if the dynamic type does not
 //! match the expected `Vec<T>`.
 
+use crate::runner::PrepareContext;
 use std::any::{Any, TypeId, type_name};
 use std::marker::PhantomData;
 use std::sync::Arc;
@@ -23,6 +27,82 @@ use std::sync::Arc;
 /// applying their work.
 pub type Partition = Box<dyn Any + Send + Sync>;
 
+/// A boxed, sendable stream of elements carried by a [`LazyPartition`].
+pub type ElemIter<T> = Box<dyn Iterator<Item = T> + Send>;
+
+/// A partition that may still be a lazy element stream.
+///
+/// In [`ExecMode::Streaming`](crate::ExecMode::Streaming) the runner threads a
+/// `LazyPartition` through each fused run of stateless ops (see [`DynOp::apply_lazy`](crate::node::DynOp::apply_lazy)
+/// (crate::DynOp::apply_lazy)), so elements flow through `map`, `filter`, `flat_map`, and
+/// friends one at a time instead of being collected into a full `Vec` between ops. The
+/// stream is materialized back into a [`Partition`] at the end of the run, before the
+/// next barrier or the terminal.
+pub enum LazyPartition {
+    /// An already materialized `Vec<T>` partition.
+    Materialized(Partition),
+    /// A lazy element stream (a boxed [`ElemIter<T>`]) and the function that collects it
+    /// into a `Vec<T>` partition.
+    Stream {
+        /// The type-erased `ElemIter<T>`.
+        iter: Box<dyn Any + Send>,
+        /// Collects `iter` into a `Vec<T>` partition.
+        collect: fn(Box<dyn Any + Send>) -> Partition,
+    },
+}
+
+impl LazyPartition {
+    /// Wrap a lazy stream of `T` elements.
+    pub fn stream<T, I>(iter: I) -> Self
+    where
+        T: Send + Sync + 'static,
+        I: Iterator<Item = T> + Send + 'static,
+    {
+        Self::Stream {
+            iter: Box::new(Box::new(iter) as ElemIter<T>),
+            collect: collect_stream::<T>,
+        }
+    }
+
+    /// Take the elements as a stream of `T`, whether materialized or lazy.
+    ///
+    /// Returns `None` if the partition does not hold `T` elements.
+    #[must_use]
+    pub fn into_elems<T: Send + Sync + 'static>(self) -> Option<ElemIter<T>> {
+        match self {
+            Self::Materialized(p) => p
+                .downcast::<Vec<T>>()
+                .ok()
+                .map(|v| Box::new(v.into_iter()) as ElemIter<T>),
+            Self::Stream { iter, .. } => iter.downcast::<ElemIter<T>>().ok().map(|it| *it),
+        }
+    }
+
+    /// Materialize into a regular `Vec<T>` [`Partition`], draining any pending stream.
+    #[must_use]
+    pub fn materialize(self) -> Partition {
+        match self {
+            Self::Materialized(p) => p,
+            Self::Stream { iter, collect } => collect(iter),
+        }
+    }
+}
+
+impl From<Partition> for LazyPartition {
+    fn from(p: Partition) -> Self {
+        Self::Materialized(p)
+    }
+}
+
+/// Monomorphized collector stored in [`LazyPartition::Stream`].
+fn collect_stream<T: Send + Sync + 'static>(iter: Box<dyn Any + Send>) -> Partition {
+    #[allow(clippy::expect_used)]
+    let it = *iter
+        .downcast::<ElemIter<T>>()
+        .expect("LazyPartition: stream held unexpected element type");
+    Box::new(it.collect::<Vec<T>>())
+}
+
 /// A lightweight runtime type tag for debugging and assertions.
 ///
 /// `TypeTag` carries the `TypeId` and a readable type name. It is attached to
@@ -75,6 +155,29 @@ pub trait VecOps: Send + Sync {
 
     /// Clone the entire `Vec<T>` behind `data` and return it boxed as a [`Partition`].
     fn clone_any(&self, data: &dyn Any) -> Option<Partition>;
+
+    /// Approximate size of `data` in bytes, if known without reading it.
+    ///
+    /// Used by [`DirectRunner::dry_run`](crate::DirectRunner::dry_run) to estimate memory use.
+    /// In-memory vectors report their shallow size (`len * size_of::<T>()`); file-backed
+    /// sources report the size of the file on disk. The default is `None`.
+    fn byte_size_hint(&self, _data: &dyn Any) -> Option<usize> {
+        None
+    }
+
+    /// Make `data` ready to be read, returning `true` if that did any work.
+    ///
+    /// The runner calls this on every `Source` of a plan before executing it, with the
+    /// run being prepared in `ctx`. Plain vectors are always ready; lazily computed
+    /// payloads (such as the output of [`PCollection::cache`](crate::PCollection::cache))
+    /// compute themselves here through `ctx` and report `None` from [`len`](Self::len)
+    /// until they have.
+    ///
+    /// # Errors
+    /// Returns an error if computing the payload fails.
+    fn prepare(&self, _data: &dyn Any, _ctx: &PrepareContext<'_>) -> anyhow::Result<bool> {
+        Ok(false)
+    }
 }
 
 /// Concrete `VecOps` for a specific `T`.
@@ -110,6 +213,11 @@ impl<T: Clone + Send + Sync + 'static> VecOps for VecOpsImpl<T> {
         data.downcast_ref::<Vec<T>>()
             .map(|v| Box::new(v.clone()) as Partition)
     }
+
+    fn byte_size_hint(&self, data: &dyn Any) -> Option<usize> {
+        data.downcast_ref::<Vec<T>>()
+            .map(|v| v.len() * size_of::<T>())
+    }
 }
 
 /// Create a type-erased `VecOps` for `Vec<T>`.
@@ -12,6 +12,11 @@
 //! - **Error handling modes** - Skip bad records, log and continue, or fail fast
 //! - **Error collectors** - Accumulate validation errors for batch reporting
 //! - **Built-in validators** - Common validation patterns
+//! - **Schemas** - Declarative per-field rules checked by
+//!   [`PCollection::validate_schema`](crate::PCollection::validate_schema)
+//! - **Pre-flight checks** - Structural diagnostics for the pipeline graph itself,
+//!   returned by [`Pipeline::validate`](crate::Pipeline::validate) and run
+//!   automatically when a plan is built (see [`preflight`])
 //!
 //! # Example
 //!
@@ -71,6 +76,12 @@
 //! # }
 //! ```
 
+pub mod preflight;
+pub mod schema;
+
+pub use preflight::{Diagnostic, DiagnosticKind, Severity};
+pub use schema::{FieldRule, FieldType, Schema, Violation, ViolationKind};
+
 use serde::{Deserialize, Serialize};
 use serde_json::{Error as JsonError, to_string_pretty};
 use std::error::Error as StdError;
@@ -0,0 +1,270 @@
+//! Pre-flight checks of a pipeline graph, run before anything executes.
+//!
+//! [`Pipeline::validate`] inspects the whole graph and returns one [`Diagnostic`] per
+//! finding. The planner runs the same checks over the part of the graph feeding the
+//! collection being executed: [`Severity::Error`] findings abort
+//! [`build_plan`](crate::planner::build_plan) with a message listing them, and
+//! [`Severity::Warning`] findings are kept on
+//! [`Plan::diagnostics`](crate::planner::Plan::diagnostics).
+//!
+//! | Kind | Severity | Finding |
+//! |------|----------|---------|
+//! | [`DiagnosticKind::EmptyPipeline`] | error | The pipeline has no nodes at all. |
+//! | [`DiagnosticKind::MissingSource`] | error | A transform has no upstream read. |
+//! | [`DiagnosticKind::TypeMismatch`] | error | Declared element types of connected nodes disagree (feature: `coders`). |
+//! | [`DiagnosticKind::RegroupedExpansion`] | warning | `group_by_key` output is immediately re-expanded by `flat_map`. |
+//! | [`DiagnosticKind::IgnoredPartitionHint`] | warning | A `with_partitions` hint cannot be honored (planner only). |
+//!
+//! ```
+//! use ironbeam::*;
+//! use ironbeam::validation::DiagnosticKind;
+//!
+//! let p = Pipeline::default();
+//! let words = from_vec(&p, vec![("a".to_string(), 1u32), ("a".to_string(), 2)]);
+//! let _flat = words
+//!     .group_by_key()
+//!     .flat_map(|(k, vs): &(String, Vec<u32>)| vs.iter().map(|v| (k.clone(), *v)).collect());
+//!
+//! let diagnostics = p.validate();
+//! assert_eq!(diagnostics.len(), 1);
+//! assert_eq!(diagnostics[0].kind, DiagnosticKind::RegroupedExpansion);
+//! assert!(!diagnostics[0].is_error());
+//! ```
+
+#[cfg(feature = "coders")]
+use crate::coders::ElementCoder;
+use crate::node::Node;
+use crate::{NodeId, Pipeline};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter, Result as FormatResult};
+#[cfg(feature = "coders")]
+use std::sync::Arc;
+
+/// How serious a [`Diagnostic`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// Legal but likely unintended; execution proceeds.
+    Warning,
+    /// The pipeline cannot run correctly; planning fails.
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        f.write_str(match self {
+            Self::Warning => "warning",
+            Self::Error => "error",
+        })
+    }
+}
+
+/// What a [`Diagnostic`] is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DiagnosticKind {
+    /// The pipeline contains no nodes.
+    EmptyPipeline,
+    /// A transform is not connected to any source.
+    MissingSource,
+    /// A node's declared element type disagrees with its input or its coder.
+    TypeMismatch,
+    /// A `group_by_key` result is flattened back out by `flat_map`.
+    RegroupedExpansion,
+    /// A [`PCollection::with_partitions`](crate::PCollection::with_partitions) hint sits
+    /// on a stage whose partitioning the runner cannot change. Reported by the planner
+    /// rather than [`Pipeline::validate`].
+    IgnoredPartitionHint,
+}
+
+/// One finding of the pre-flight checks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Whether the finding blocks execution.
+    pub severity: Severity,
+    /// Category of the finding.
+    pub kind: DiagnosticKind,
+    /// The node the finding is attached to, if any.
+    pub node: Option<NodeId>,
+    /// Human-readable explanation, naming the node by its label when it has one.
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// True for [`Severity::Error`] findings.
+    #[must_use]
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        write!(f, "{}: {}", self.severity, self.message)
+    }
+}
+
+impl Pipeline {
+    /// Run the pre-flight checks over the whole graph without executing anything.
+    ///
+    /// Returns every finding, errors and warnings alike, ordered by node. An empty
+    /// result means the checks found nothing to report. See the
+    /// [`preflight`](crate::validation::preflight) module for the list of checks.
+    #[must_use]
+    pub fn validate(&self) -> Vec<Diagnostic> {
+        let (nodes, edges) = self.snapshot();
+        preflight(self, &nodes, &edges, None)
+    }
+}
+
+/// Run the checks over the graph `nodes`/`edges` of `p`.
+///
+/// With a `terminal`, only that node and its ancestors are checked, so a broken
+/// branch elsewhere in the graph does not block running an unrelated output.
+pub(crate) fn preflight(
+    p: &Pipeline,
+    nodes: &HashMap<NodeId, Node>,
+    edges: &[(NodeId, NodeId)],
+    terminal: Option<NodeId>,
+) -> Vec<Diagnostic> {
+    if nodes.is_empty() {
+        return vec![Diagnostic {
+            severity: Severity::Error,
+            kind: DiagnosticKind::EmptyPipeline,
+            node: None,
+            message: "the pipeline has no transforms; create a source first".to_string(),
+        }];
+    }
+
+    let mut preds: HashMap<NodeId, Vec<NodeId>> = HashMap::new();
+    for &(from, to) in edges {
+        preds.entry(to).or_default().push(from);
+    }
+
+    let mut scope: Vec<NodeId> = match terminal {
+        Some(terminal) => {
+            let mut seen = HashSet::new();
+            let mut stack = vec![terminal];
+            while let Some(id) = stack.pop() {
+                if nodes.contains_key(&id) && seen.insert(id) {
+                    stack.extend(preds.get(&id).into_iter().flatten().copied());
+                }
+            }
+            seen.into_iter().collect()
+        }
+        None => nodes.keys().copied().collect(),
+    };
+    scope.sort_by_key(NodeId::raw);
+
+    let names = p.node_names_snapshot();
+    let label = |id: NodeId| {
+        let kind = nodes.get(&id).map_or("node", Node::kind);
+        match names.get(&id) {
+            Some(name) => format!("{kind} `{name}` (node {})", id.raw()),
+            None => format!("{kind} (node {})", id.raw()),
+        }
+    };
+
+    let mut out = Vec::new();
+    for &id in &scope {
+        let node = &nodes[&id];
+        let inputs = preds.get(&id).map_or(&[][..], Vec::as_slice);
+
+        if inputs.is_empty() && !matches!(node, Node::Source { .. } | Node::Materialized(_)) {
+            out.push(Diagnostic {
+                severity: Severity::Error,
+                kind: DiagnosticKind::MissingSource,
+                node: Some(id),
+                message: format!("{} has no upstream source", label(id)),
+            });
+        }
+
+        if let Node::Stateless(ops) = node
+            && ops.first().is_some_and(|op| op.expanding())
+            && let Some(&gbk) = inputs
+                .iter()
+                .find(|from| matches!(nodes.get(from), Some(Node::GroupByKey { .. })))
+        {
+            out.push(Diagnostic {
+                severity: Severity::Warning,
+                kind: DiagnosticKind::RegroupedExpansion,
+                node: Some(id),
+                message: format!(
+                    "{} re-expands the groups of {} with flat_map; if each group is only \
+                     flattened back out, the shuffle is wasted (consider combine_values, or \
+                     skipping the grouping)",
+                    label(id),
+                    label(gbk)
+                ),
+            });
+        }
+    }
+
+    #[cfg(feature = "coders")]
+    out.extend(type_mismatches(
+        nodes,
+        &preds,
+        &scope,
+        &p.snapshot_coders(),
+        &label,
+    ));
+
+    out
+}
+
+/// Compare declared element types where they must agree: a source's tag against its
+/// coder, and the input and output of type-preserving barriers (`Reshuffle`, `Sort`).
+#[cfg(feature = "coders")]
+fn type_mismatches(
+    nodes: &HashMap<NodeId, Node>,
+    preds: &HashMap<NodeId, Vec<NodeId>>,
+    scope: &[NodeId],
+    coders: &HashMap<NodeId, Arc<dyn ElementCoder>>,
+    label: &impl Fn(NodeId) -> String,
+) -> Vec<Diagnostic> {
+    let mismatch = |id: NodeId, message: String| Diagnostic {
+        severity: Severity::Error,
+        kind: DiagnosticKind::TypeMismatch,
+        node: Some(id),
+        message,
+    };
+
+    let mut out = Vec::new();
+    for &id in scope {
+        let Some(coder) = coders.get(&id) else {
+            continue;
+        };
+        match &nodes[&id] {
+            Node::Source { elem_tag, .. } if elem_tag.name != coder.type_name() => {
+                out.push(mismatch(
+                    id,
+                    format!(
+                        "{} yields `{}` but its coder encodes `{}`",
+                        label(id),
+                        elem_tag.name,
+                        coder.type_name()
+                    ),
+                ));
+            }
+            Node::Reshuffle { .. } | Node::Sort { .. } => {
+                for &from in preds.get(&id).into_iter().flatten() {
+                    if let Some(input) = coders.get(&from)
+                        && input.type_name() != coder.type_name()
+                    {
+                        out.push(mismatch(
+                            id,
+                            format!(
+                                "{} passes elements through unchanged but declares `{}`, \
+                                 while its input {} yields `{}`",
+                                label(id),
+                                coder.type_name(),
+                                label(from),
+                                input.type_name()
+                            ),
+                        ));
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    out
+}
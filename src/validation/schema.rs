@@ -0,0 +1,313 @@
+//! Declarative, per-field schemas checked inside a pipeline.
+//!
+//! A [`Schema`] lists [`FieldRule`]s: nullability, JSON type, numeric range, regex
+//! pattern, and allowed values. It checks any `Serialize` value by its JSON form, so
+//! it works the same for the dynamic [`Row`](crate::Row) type and for structs with
+//! `#[derive(Serialize)]`. Every failed rule becomes one [`Violation`].
+//!
+//! [`PCollection::validate_schema`](crate::PCollection::validate_schema) applies a
+//! schema as a data-quality gate: valid records continue, violations flow into a side
+//! output.
+//!
+//! ```
+//! use ironbeam::validation::{FieldRule, FieldType, Schema, ViolationKind};
+//! use serde_json::json;
+//!
+//! let schema = Schema::new()
+//!     .field(FieldRule::new("id").required().of_type(FieldType::Integer))
+//!     .field(FieldRule::new("age").range(0.0, 150.0))
+//!     .field(FieldRule::new("email").pattern(r"^[^@\s]+@[^@\s]+$"))
+//!     .field(FieldRule::new("plan").one_of(["free", "pro"]));
+//!
+//! assert!(schema.check(&json!({"id": 1, "age": 30, "plan": "pro"})).is_empty());
+//!
+//! let violations = schema.check(&json!({"age": 200, "email": "nope"}));
+//! let kinds: Vec<_> = violations.iter().map(|v| v.kind).collect();
+//! assert_eq!(
+//!     kinds,
+//!     [ViolationKind::Missing, ViolationKind::OutOfRange, ViolationKind::Pattern]
+//! );
+//! ```
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt::{Display, Formatter, Result as FormatResult};
+
+/// The JSON type a field must have; see [`FieldRule::of_type`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    /// `true` or `false`.
+    Boolean,
+    /// A number without a fractional part.
+    Integer,
+    /// Any number.
+    Number,
+    /// A string.
+    String,
+    /// An array.
+    Array,
+    /// An object (a nested struct or map).
+    Object,
+}
+
+impl FieldType {
+    fn matches(self, value: &Value) -> bool {
+        match self {
+            Self::Boolean => value.is_boolean(),
+            Self::Integer => value.is_i64() || value.is_u64(),
+            Self::Number => value.is_number(),
+            Self::String => value.is_string(),
+            Self::Array => value.is_array(),
+            Self::Object => value.is_object(),
+        }
+    }
+}
+
+/// Rules for one field of a [`Schema`].
+///
+/// Fields are optional by default: a missing or `null` field passes every other rule.
+/// Call [`required`](FieldRule::required) to reject those. Nested fields are addressed
+/// with dotted paths such as `"address.city"`.
+#[derive(Debug, Clone)]
+pub struct FieldRule {
+    path: String,
+    required: bool,
+    kind: Option<FieldType>,
+    range: Option<(f64, f64)>,
+    pattern: Option<Regex>,
+    allowed: Option<Vec<Value>>,
+}
+
+impl FieldRule {
+    /// Rules for the field at `path`, with no checks yet.
+    pub fn new(path: impl Into<String>) -> Self {
+        Self {
+            path: path.into(),
+            required: false,
+            kind: None,
+            range: None,
+            pattern: None,
+            allowed: None,
+        }
+    }
+
+    /// Reject records where the field is missing or `null`.
+    #[must_use]
+    pub const fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Require the field to have JSON type `kind`.
+    #[must_use]
+    pub const fn of_type(mut self, kind: FieldType) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// Require a number in `min..=max`. Non-numeric values are type violations.
+    #[must_use]
+    pub const fn range(mut self, min: f64, max: f64) -> Self {
+        self.range = Some((min, max));
+        self
+    }
+
+    /// Require a string matching `pattern`. The match is unanchored; use `^...$` to
+    /// match the whole string. Non-string values are type violations.
+    ///
+    /// # Panics
+    /// Panics if `pattern` is not a valid regular expression.
+    #[must_use]
+    pub fn pattern(mut self, pattern: &str) -> Self {
+        self.pattern = Some(Regex::new(pattern).expect("FieldRule::pattern: invalid pattern"));
+        self
+    }
+
+    /// Require the value to equal one of `values`.
+    #[must_use]
+    pub fn one_of<I, V>(mut self, values: I) -> Self
+    where
+        I: IntoIterator<Item = V>,
+        V: Into<Value>,
+    {
+        self.allowed = Some(values.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// The field's path.
+    #[must_use]
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    fn check(&self, record: &Value, out: &mut Vec<Violation>) {
+        let value = self.path.split('.').try_fold(record, |v, name| v.get(name));
+        let violation = |kind, value: &Value, message: String| Violation {
+            field: self.path.clone(),
+            kind,
+            value: value.clone(),
+            message,
+            record: record.clone(),
+        };
+
+        let value = match value {
+            None | Some(Value::Null) if self.required => {
+                let kind = if value.is_none() {
+                    ViolationKind::Missing
+                } else {
+                    ViolationKind::Null
+                };
+                out.push(violation(kind, &Value::Null, "required field".into()));
+                return;
+            }
+            None | Some(Value::Null) => return,
+            Some(value) => value,
+        };
+
+        if let Some(kind) = self.kind
+            && !kind.matches(value)
+        {
+            out.push(violation(
+                ViolationKind::Type,
+                value,
+                format!("expected {kind:?}"),
+            ));
+            return;
+        }
+        if let Some((min, max)) = self.range {
+            match value.as_f64() {
+                Some(x) if (min..=max).contains(&x) => {}
+                Some(_) => out.push(violation(
+                    ViolationKind::OutOfRange,
+                    value,
+                    format!("not in {min}..={max}"),
+                )),
+                None => out.push(violation(
+                    ViolationKind::Type,
+                    value,
+                    "expected Number".into(),
+                )),
+            }
+        }
+        if let Some(re) = &self.pattern {
+            match value.as_str() {
+                Some(s) if re.is_match(s) => {}
+                Some(_) => out.push(violation(
+                    ViolationKind::Pattern,
+                    value,
+                    format!("does not match {re}"),
+                )),
+                None => out.push(violation(
+                    ViolationKind::Type,
+                    value,
+                    "expected String".into(),
+                )),
+            }
+        }
+        if let Some(allowed) = &self.allowed
+            && !allowed.contains(value)
+        {
+            out.push(violation(
+                ViolationKind::NotAllowed,
+                value,
+                "not one of the allowed values".into(),
+            ));
+        }
+    }
+}
+
+/// A set of [`FieldRule`]s checked against each record.
+#[derive(Debug, Clone, Default)]
+pub struct Schema {
+    fields: Vec<FieldRule>,
+}
+
+impl Schema {
+    /// An empty schema, which accepts every record.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the rules for one field.
+    #[must_use]
+    pub fn field(mut self, rule: FieldRule) -> Self {
+        self.fields.push(rule);
+        self
+    }
+
+    /// The field rules, in the order they are checked.
+    #[must_use]
+    pub fn fields(&self) -> &[FieldRule] {
+        &self.fields
+    }
+
+    /// Check `record` and return its violations, in field order.
+    ///
+    /// A record whose JSON form is not an object has no fields, so only the
+    /// [`required`](FieldRule::required) rules can fail.
+    pub fn check<T: Serialize + ?Sized>(&self, record: &T) -> Vec<Violation> {
+        let record = match serde_json::to_value(record) {
+            Ok(record) => record,
+            Err(e) => {
+                return vec![Violation {
+                    field: String::new(),
+                    kind: ViolationKind::Type,
+                    value: Value::Null,
+                    message: format!("record is not serializable: {e}"),
+                    record: Value::Null,
+                }];
+            }
+        };
+        let mut out = Vec::new();
+        for rule in &self.fields {
+            rule.check(&record, &mut out);
+        }
+        out
+    }
+}
+
+/// Which rule a [`Violation`] broke.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ViolationKind {
+    /// A required field is absent.
+    Missing,
+    /// A required field is `null`.
+    Null,
+    /// The value has the wrong JSON type.
+    Type,
+    /// A number is outside the allowed range.
+    OutOfRange,
+    /// A string does not match the pattern.
+    Pattern,
+    /// The value is not one of the allowed values.
+    NotAllowed,
+}
+
+/// One failed rule for one record.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Violation {
+    /// Path of the field, as given to [`FieldRule::new`].
+    pub field: String,
+    /// The rule that failed.
+    pub kind: ViolationKind,
+    /// The offending value (`null` when the field is missing).
+    #[serde(with = "crate::row::json_text")]
+    pub value: Value,
+    /// Human-readable description of the failure.
+    pub message: String,
+    /// The whole record, as JSON.
+    #[serde(with = "crate::row::json_text")]
+    pub record: Value,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
+        write!(
+            f,
+            "[{}] {:?}: {} (value: {})",
+            self.field, self.kind, self.message, self.value
+        )
+    }
+}
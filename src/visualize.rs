@@ -0,0 +1,303 @@
+//! Graph export: render pipelines and plans as DOT (Graphviz) or Mermaid.
+//!
+//! Two views are available, each in both formats:
+//!
+//! - [`Pipeline::to_dot`] / [`Pipeline::to_mermaid`] render the **logical DAG** exactly as
+//!   the builder API recorded it: one vertex per inserted node, labeled with its
+//!   [`NodeId`], kind, and (if set) the name given via
+//!   [`PCollection::with_name`](crate::PCollection::with_name).
+//! - [`Plan::to_dot`] / [`Plan::to_mermaid`] render the **optimized chain** the runner
+//!   will execute, after fusion, lifting, and the other planner passes. Fused stateless
+//!   stages show their op count and the names of every node folded into them, and the
+//!   input subplans of joins and flattens are drawn as nested clusters.
+//!
+//! In both views barriers (`GroupByKey`, `CombineValues`, joins, `Reshuffle`, …) are
+//! highlighted, so comparing the two shows at a glance what the planner fused, lifted,
+//! or removed.
+//!
+//! ```no_run
+//! use ironbeam::*;
+//! # use anyhow::Result;
+//! # fn main() -> Result<()> {
+//! let p = Pipeline::default();
+//! let counts = from_vec(&p, vec!["a b".to_string(), "b c".to_string()])
+//!     .flat_map(|l: &String| l.split_whitespace().map(String::from).collect())
+//!     .with_name("Split")
+//!     .key_by(|w: &String| w.clone())
+//!     .map_values(|_| 1u64)
+//!     .combine_values(Sum::<u64>::default());
+//!
+//! println!("{}", p.to_dot());
+//! println!("{}", build_plan(&p, counts.node_id())?.to_mermaid());
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::node::Node;
+use crate::planner::Plan;
+use crate::{NodeId, Pipeline};
+use std::fmt::Write;
+
+/// Fill color for barrier vertices in both output formats.
+const BARRIER_FILL: &str = "#f8d7da";
+/// Border color for barrier vertices in both output formats.
+const BARRIER_STROKE: &str = "#b02a37";
+
+impl Pipeline {
+    /// Render the logical pipeline DAG in Graphviz DOT format.
+    ///
+    /// Vertices are labeled `#id Kind` plus the node's name, if any; barriers are filled
+    /// and outlined. Nodes are emitted in id order so the output is deterministic.
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        render_dot("pipeline", &pipeline_graph(self))
+    }
+
+    /// Render the logical pipeline DAG as a Mermaid `flowchart`.
+    ///
+    /// See [`Pipeline::to_dot`] for the labeling scheme.
+    #[must_use]
+    pub fn to_mermaid(&self) -> String {
+        render_mermaid(&pipeline_graph(self))
+    }
+}
+
+impl Plan {
+    /// Render the optimized execution chain in Graphviz DOT format.
+    ///
+    /// Each vertex is one chain step (`step N: Kind`), fused stateless stages list their
+    /// op count and contributing node names, and join/flatten inputs are drawn as nested
+    /// clusters feeding the barrier that consumes them.
+    #[must_use]
+    pub fn to_dot(&self) -> String {
+        render_dot("plan", &plan_graph(self))
+    }
+
+    /// Render the optimized execution chain as a Mermaid `flowchart`.
+    ///
+    /// See [`Plan::to_dot`] for the labeling scheme.
+    #[must_use]
+    pub fn to_mermaid(&self) -> String {
+        render_mermaid(&plan_graph(self))
+    }
+}
+
+/// Format-independent graph built from a pipeline or plan.
+#[derive(Default)]
+struct Graph {
+    items: Vec<Item>,
+    edges: Vec<(String, String)>,
+}
+
+enum Item {
+    Vertex {
+        id: String,
+        label: String,
+        barrier: bool,
+    },
+    Cluster {
+        id: String,
+        label: String,
+        items: Vec<Self>,
+    },
+}
+
+fn pipeline_graph(p: &Pipeline) -> Graph {
+    let (nodes, edges) = p.snapshot();
+    let names = p.node_names_snapshot();
+    let vid = |id: NodeId| format!("n{}", id.raw());
+
+    let mut ids: Vec<NodeId> = nodes.keys().copied().collect();
+    ids.sort_by_key(NodeId::raw);
+    let items = ids
+        .into_iter()
+        .map(|id| {
+            let node = &nodes[&id];
+            Item::Vertex {
+                id: vid(id),
+                label: label(
+                    &format!("#{}", id.raw()),
+                    node,
+                    names.get(&id).map(String::as_str),
+                ),
+                barrier: node.is_barrier(),
+            }
+        })
+        .collect();
+
+    let mut edges: Vec<(String, String)> =
+        edges.into_iter().map(|(a, b)| (vid(a), vid(b))).collect();
+    edges.sort();
+    Graph { items, edges }
+}
+
+fn plan_graph(plan: &Plan) -> Graph {
+    let mut g = Graph::default();
+    let mut items = Vec::new();
+    chain_graph(
+        &plan.chain,
+        "s",
+        &|idx| plan.step_name(idx),
+        &mut items,
+        &mut g.edges,
+    );
+    g.items = items;
+    g
+}
+
+/// Append one vertex per step of `chain` (ids prefixed with `prefix`) plus clusters for
+/// subplans, returning the id of the last step.
+fn chain_graph(
+    chain: &[Node],
+    prefix: &str,
+    name_of: &dyn Fn(usize) -> Option<String>,
+    items: &mut Vec<Item>,
+    edges: &mut Vec<(String, String)>,
+) -> Option<String> {
+    let mut prev: Option<String> = None;
+    for (idx, node) in chain.iter().enumerate() {
+        let id = format!("{prefix}{idx}");
+        for (i, sub) in node.subplans().into_iter().enumerate() {
+            let cluster_id = format!("{id}_in{i}");
+            let mut sub_items = Vec::new();
+            if let Some(last) = chain_graph(
+                sub,
+                &format!("{cluster_id}_"),
+                &|_| None,
+                &mut sub_items,
+                edges,
+            ) {
+                edges.push((last, id.clone()));
+            }
+            items.push(Item::Cluster {
+                id: cluster_id,
+                label: format!("input {i}"),
+                items: sub_items,
+            });
+        }
+        items.push(Item::Vertex {
+            id: id.clone(),
+            label: label(&format!("step {}:", idx + 1), node, name_of(idx).as_deref()),
+            barrier: node.is_barrier(),
+        });
+        if let Some(p) = prev.replace(id.clone()) {
+            edges.push((p, id));
+        }
+    }
+    prev
+}
+
+/// Vertex label: `"{prefix} Kind (detail)"`, plus the name on a second line.
+fn label(prefix: &str, node: &Node, name: Option<&str>) -> String {
+    let detail = match node {
+        Node::Source {
+            payload, vec_ops, ..
+        } => vec_ops
+            .len(payload.as_ref())
+            .map(|n| format!(" ({n} elements)")),
+        Node::Stateless(ops) => Some(match ops.len() {
+            1 => " (1 op)".to_string(),
+            n => format!(" ({n} ops fused)"),
+        }),
+        Node::CoGroupN { chains, .. } | Node::Flatten { chains, .. } => {
+            Some(format!(" ({} inputs)", chains.len()))
+        }
+        Node::Reshuffle {
+            target_partitions: Some(n),
+            ..
+        } => Some(format!(" (into {n})")),
+        _ => None,
+    };
+    let mut out = format!("{prefix} {}{}", node.kind(), detail.unwrap_or_default());
+    if let Some(name) = name {
+        out.push('\n');
+        out.push_str(name);
+    }
+    out
+}
+
+fn render_dot(graph_name: &str, g: &Graph) -> String {
+    fn write_items(out: &mut String, items: &[Item], indent: usize) {
+        let pad = "  ".repeat(indent);
+        for item in items {
+            match item {
+                Item::Vertex { id, label, barrier } => {
+                    let style = if *barrier {
+                        format!(
+                            ", style=\"filled,bold\", fillcolor=\"{BARRIER_FILL}\", color=\"{BARRIER_STROKE}\""
+                        )
+                    } else {
+                        String::new()
+                    };
+                    let _ = writeln!(out, "{pad}{id} [label=\"{}\"{style}];", dot_escape(label));
+                }
+                Item::Cluster {
+                    id,
+                    label,
+                    items: inner,
+                } => {
+                    let _ = writeln!(out, "{pad}subgraph cluster_{id} {{");
+                    let _ = writeln!(out, "{pad}  label=\"{}\";", dot_escape(label));
+                    let _ = writeln!(out, "{pad}  style=dashed;");
+                    write_items(out, inner, indent + 1);
+                    let _ = writeln!(out, "{pad}}}");
+                }
+            }
+        }
+    }
+
+    let mut out = String::new();
+    let _ = writeln!(out, "digraph {graph_name} {{");
+    let _ = writeln!(out, "  rankdir=TB;");
+    let _ = writeln!(out, "  node [shape=box, fontname=\"Helvetica\"];");
+    write_items(&mut out, &g.items, 1);
+    for (a, b) in &g.edges {
+        let _ = writeln!(out, "  {a} -> {b};");
+    }
+    out.push_str("}\n");
+    out
+}
+
+fn render_mermaid(g: &Graph) -> String {
+    fn write_items(out: &mut String, items: &[Item], indent: usize) {
+        let pad = "  ".repeat(indent);
+        for item in items {
+            match item {
+                Item::Vertex { id, label, barrier } => {
+                    let class = if *barrier { ":::barrier" } else { "" };
+                    let _ = writeln!(out, "{pad}{id}[\"{}\"]{class}", mermaid_escape(label));
+                }
+                Item::Cluster {
+                    id,
+                    label,
+                    items: inner,
+                } => {
+                    let _ = writeln!(out, "{pad}subgraph {id}[\"{}\"]", mermaid_escape(label));
+                    write_items(out, inner, indent + 1);
+                    let _ = writeln!(out, "{pad}end");
+                }
+            }
+        }
+    }
+
+    let mut out = String::from("flowchart TD\n");
+    write_items(&mut out, &g.items, 1);
+    for (a, b) in &g.edges {
+        let _ = writeln!(out, "  {a} --> {b}");
+    }
+    let _ = writeln!(
+        out,
+        "  classDef barrier fill:{BARRIER_FILL},stroke:{BARRIER_STROKE},stroke-width:2px"
+    );
+    out
+}
+
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn mermaid_escape(s: &str) -> String {
+    s.replace('"', "#quot;").replace('\n', "<br/>")
+}
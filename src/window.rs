@@ -24,10 +24,20 @@
 //!
 //! See also the higher-level helpers in `helpers/tumbling.rs` that derive window keys
 //! from `Timestamped<T>` streams.
+//!
+//! ## Sessions
+//! A [`Session<T>`] groups one key's events separated by less than an inactivity gap;
+//! `helpers/sessions.rs` builds them from keyed `(K, Timestamped<V>)` streams.
+//!
+//! ## Clocks
+//! A [`Clock`] supplies processing time. Each pipeline holds one (the [`SystemClock`]
+//! by default); `attach_processing_time` stamps elements with it, and tests swap in a
+//! controllable clock through `testing::TestContext`.
 
 use serde::{Deserialize, Serialize};
 use std::cmp::Ordering;
 use std::hash::{Hash, Hasher};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 /// Milliseconds since the Unix epoch (UTC).
 ///
@@ -35,6 +45,28 @@ use std::hash::{Hash, Hasher};
 /// event-time timestamps.
 pub type TimestampMs = u64;
 
+/// A source of processing time, in milliseconds since the Unix epoch.
+///
+/// Installed on a pipeline with [`Pipeline::set_clock`](crate::Pipeline::set_clock).
+pub trait Clock: Send + Sync {
+    /// The current time.
+    fn now_ms(&self) -> TimestampMs;
+}
+
+/// The wall clock: [`SystemTime::now`] in milliseconds since the Unix epoch.
+///
+/// Times before the epoch read as `0`.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_ms(&self) -> TimestampMs {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
+    }
+}
+
 /// A closed–open time interval: `[start, end)`.
 ///
 /// Windows are comparable and hashable, which makes them usable as map keys and
@@ -164,3 +196,42 @@ impl<T> Timestamped<T> {
         Self { ts, value }
     }
 }
+
+/// A burst of activity for one key, as produced by
+/// [`PCollection::sessionize`](crate::PCollection::sessionize).
+///
+/// A session holds consecutive events that are each less than the inactivity gap
+/// apart. `start` and `end` are the timestamps of its first and last event.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Session<T> {
+    /// Timestamp of the first event (inclusive).
+    pub start: TimestampMs,
+    /// Timestamp of the last event (inclusive).
+    pub end: TimestampMs,
+    /// The session's events, in timestamp order.
+    pub events: Vec<Timestamped<T>>,
+}
+
+impl<T> Session<T> {
+    /// Time between the first and last event, in milliseconds.
+    #[inline]
+    #[must_use]
+    pub const fn duration_ms(&self) -> u64 {
+        self.end - self.start
+    }
+
+    /// Number of events in the session.
+    #[inline]
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Whether the session has no events (never the case for sessions built by
+    /// `sessionize`).
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+}
@@ -0,0 +1,81 @@
+//! Tests for `map_async` and `collect_async`.
+#![cfg(feature = "async")]
+
+use anyhow::Result;
+use ironbeam::*;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+fn block_on<F: Future>(f: F) -> F::Output {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(f)
+}
+
+#[test]
+fn map_async_preserves_order() -> Result<()> {
+    let p = Pipeline::default();
+    let out = from_vec(&p, (0..200u64).collect::<Vec<_>>())
+        .map_async(16, |x| async move {
+            // Later elements finish first.
+            tokio::time::sleep(Duration::from_micros(200 - x)).await;
+            x * 2
+        })
+        .collect_seq()?;
+    assert_eq!(out, (0..200u64).map(|x| x * 2).collect::<Vec<_>>());
+    Ok(())
+}
+
+#[test]
+fn map_async_bounds_concurrency_across_partitions() -> Result<()> {
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let p = Pipeline::default();
+    let (now, max) = (Arc::clone(&in_flight), Arc::clone(&peak));
+    let out = from_vec(&p, (0..64u32).collect::<Vec<_>>())
+        .map_async(3, move |x| {
+            let (now, max) = (Arc::clone(&now), Arc::clone(&max));
+            async move {
+                let current = now.fetch_add(1, Ordering::SeqCst) + 1;
+                max.fetch_max(current, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(2)).await;
+                now.fetch_sub(1, Ordering::SeqCst);
+                x + 1
+            }
+        })
+        .collect_par(Some(4), Some(8))?;
+    assert_eq!(out.len(), 64);
+    assert!(peak.load(Ordering::SeqCst) <= 3);
+    assert!(peak.load(Ordering::SeqCst) >= 2);
+    Ok(())
+}
+
+#[test]
+fn collect_async_runs_from_async_code() -> Result<()> {
+    let out = block_on(async {
+        let p = Pipeline::default();
+        from_vec(&p, vec!["a".to_string(), "bb".to_string()])
+            .map_async(2, |s| async move { s.len() as u64 })
+            .map(|n: &u64| n * 10)
+            .collect_async()
+            .await
+    })?;
+    assert_eq!(out, vec![10, 20]);
+    Ok(())
+}
+
+#[test]
+fn map_async_panics_surface_as_errors() {
+    let p = Pipeline::default();
+    let result = from_vec(&p, vec![1u32, 2, 3])
+        .map_async(2, |x| async move {
+            assert!(x != 2, "two failed");
+            x
+        })
+        .collect_par(None, Some(1));
+    let err = result.unwrap_err();
+    assert!(format!("{err:#}").contains("two failed"));
+}
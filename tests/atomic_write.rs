@@ -0,0 +1,105 @@
+//! Tests for atomic file output and the `_SUCCESS` marker.
+#![cfg(all(feature = "io-jsonl", feature = "io-csv"))]
+
+use anyhow::Result;
+use ironbeam::io::atomic::{SUCCESS_MARKER, tmp_path};
+use ironbeam::io::compression::WriteOptions;
+use ironbeam::*;
+use serde::{Deserialize, Serialize, Serializer};
+use std::path::Path;
+
+/// Fails to serialize when its value is odd.
+#[derive(Clone, Deserialize, Debug)]
+struct Picky(u32);
+
+impl Serialize for Picky {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if !self.0.is_multiple_of(2) {
+            return Err(serde::ser::Error::custom(format!("odd value {}", self.0)));
+        }
+        serializer.serialize_u32(self.0)
+    }
+}
+
+fn entries(dir: &Path) -> Result<Vec<String>> {
+    let mut names = std::fs::read_dir(dir)?
+        .map(|e| Ok(e?.file_name().to_string_lossy().into_owned()))
+        .collect::<Result<Vec<_>>>()?;
+    names.sort();
+    Ok(names)
+}
+
+#[test]
+fn tmp_path_appends_suffix() {
+    assert_eq!(
+        tmp_path(Path::new("out/data.jsonl")),
+        Path::new("out/data.jsonl.tmp")
+    );
+}
+
+#[test]
+fn failed_pipeline_write_keeps_previous_output() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let file = tmp.path().join("out.jsonl");
+    let p = Pipeline::default();
+    from_vec(&p, vec![Picky(0), Picky(2)]).write_jsonl(&file)?;
+    let before = std::fs::read(&file)?;
+
+    let err = from_vec(&p, vec![Picky(4), Picky(5)])
+        .write_jsonl(&file)
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("odd value 5"), "{err:#}");
+    assert_eq!(std::fs::read(&file)?, before);
+    assert_eq!(entries(tmp.path())?, vec!["out.jsonl"]);
+    Ok(())
+}
+
+#[test]
+fn failed_vec_write_leaves_nothing_behind() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let file = tmp.path().join("out.csv");
+    let err = write_csv_vec(&file, false, &[Picky(1)]).unwrap_err();
+    assert!(format!("{err:#}").contains("odd value 1"), "{err:#}");
+    assert!(entries(tmp.path())?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn success_marker_is_opt_in() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let plain = tmp.path().join("plain");
+    let marked = tmp.path().join("marked");
+    let p = Pipeline::default();
+    let rows = || from_vec(&p, vec![1u32, 2, 3]);
+
+    rows().write_jsonl(plain.join("out.jsonl"))?;
+    assert_eq!(entries(&plain)?, vec!["out.jsonl"]);
+
+    let opts = WriteOptions::default().with_success_marker(true);
+    rows().write_csv_with(marked.join("out.csv"), false, &opts)?;
+    assert_eq!(entries(&marked)?, vec![SUCCESS_MARKER, "out.csv"]);
+    Ok(())
+}
+
+#[test]
+fn failed_write_does_not_create_marker() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let opts = WriteOptions::default().with_success_marker(true);
+    assert!(write_jsonl_vec_with(tmp.path().join("out.jsonl"), &[Picky(3)], &opts).is_err());
+    assert!(entries(tmp.path())?.is_empty());
+    Ok(())
+}
+
+#[cfg(feature = "parallel-io")]
+#[test]
+fn parallel_writers_clean_up_part_files() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let data: Vec<u32> = (0..100).collect();
+    write_jsonl_par(tmp.path().join("out.jsonl"), &data, Some(4))?;
+    assert_eq!(entries(tmp.path())?, vec!["out.jsonl"]);
+
+    let bad: Vec<Picky> = (0..10).map(Picky).collect();
+    assert!(write_jsonl_par(tmp.path().join("bad.jsonl"), &bad, Some(4)).is_err());
+    assert_eq!(entries(tmp.path())?, vec!["out.jsonl"]);
+    Ok(())
+}
@@ -98,7 +98,7 @@ fn test_map_values_batches_parallel_matches_sequential() {
         .unwrap();
     seq.sort_unstable();
 
-    let runner = Runner {
+    let runner = DirectRunner {
         mode: ExecMode::Parallel {
             threads: Some(4),
             partitions: Some(4),
@@ -1,7 +1,12 @@
 //! Tests for [`BloomFilter`]: the no-false-negatives guarantee, a bounded
-//! false-positive rate, the empty filter, and hashable non-integer keys.
+//! false-positive rate, the empty filter, hashable non-integer keys, merging
+//! and serialization, and the `BloomFilterBuilder` / `filter_with_bloom`
+//! pipeline integration.
 
+use anyhow::Result;
 use ironbeam::bloom_filter::BloomFilter;
+use ironbeam::combiners::BloomFilterBuilder;
+use ironbeam::*;
 
 #[test]
 fn inserted_keys_are_always_found() {
@@ -48,3 +53,60 @@ fn string_keys_work() {
     assert!(f.might_contain(&"banana"));
     assert!(!f.might_contain(&"cherry"));
 }
+
+#[test]
+fn merge_is_union_and_checks_size() {
+    let mut a = BloomFilter::new(100);
+    let mut b = BloomFilter::new(100);
+    a.insert(&1u32);
+    b.insert(&2u32);
+    a.merge(&b).unwrap();
+    assert!(a.might_contain(&1u32) && a.might_contain(&2u32));
+    assert!(a.merge(&BloomFilter::new(10_000)).is_err());
+}
+
+#[test]
+fn serde_round_trip_preserves_membership() -> Result<()> {
+    let mut f = BloomFilter::new(50);
+    for i in 0u32..50 {
+        f.insert(&i);
+    }
+    let back: BloomFilter = serde_json::from_str(&serde_json::to_string(&f)?)?;
+    assert_eq!(back, f);
+    assert!((0u32..50).all(|i| back.might_contain(&i)));
+    Ok(())
+}
+
+#[test]
+fn builder_matches_direct_construction_seq_and_par() -> Result<()> {
+    let p = Pipeline::default();
+    let keys: Vec<u64> = (0..2_000).collect();
+    let mut direct = BloomFilter::new(keys.len());
+    keys.iter().for_each(|k| direct.insert(k));
+
+    let built = from_vec(&p, keys).combine_globally(BloomFilterBuilder::new(2_000), None);
+    assert_eq!(built.clone().collect_seq()?, vec![direct.clone()]);
+    assert_eq!(built.collect_par(Some(8), None)?, vec![direct]);
+    Ok(())
+}
+
+#[test]
+fn filter_with_bloom_keeps_every_present_key() -> Result<()> {
+    let p = Pipeline::default();
+    let wanted = from_vec(&p, (0u32..100).map(|i| i * 10).collect::<Vec<_>>())
+        .combine_globally(BloomFilterBuilder::new(100), None)
+        .collect_seq()?
+        .remove(0);
+    let events = from_vec(&p, (0u32..5_000).map(|k| (k, k)).collect::<Vec<_>>());
+    let kept = events
+        .filter_with_bloom(&side_singleton(wanted))
+        .collect_seq()?;
+    let present = kept
+        .iter()
+        .filter(|(k, _)| k % 10 == 0 && *k < 1000)
+        .count();
+    assert_eq!(present, 100);
+    // ~1 % FPR over 4 900 absent keys; allow generous slack.
+    assert!(kept.len() < 100 + 250, "kept {}", kept.len());
+    Ok(())
+}
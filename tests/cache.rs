@@ -0,0 +1,112 @@
+//! Tests for `PCollection::cache`.
+
+use anyhow::Result;
+use ironbeam::*;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// A keyed source whose map counts how many elements it has processed.
+fn counted(p: &Pipeline, calls: &Arc<AtomicUsize>) -> PCollection<(String, u32)> {
+    let calls = Arc::clone(calls);
+    from_vec(p, vec![1u32, 2, 3, 4, 5, 6]).map(move |x: &u32| {
+        calls.fetch_add(1, Ordering::SeqCst);
+        (
+            if x.is_multiple_of(2) { "even" } else { "odd" }.to_string(),
+            *x,
+        )
+    })
+}
+
+#[test]
+fn cache_reuses_output_across_collects() -> Result<()> {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let p = Pipeline::default();
+    let shared = counted(&p, &calls).cache();
+
+    let values = shared.clone().values().collect_seq_sorted()?;
+    let sums = shared
+        .combine_values(Sum::<u32>::default())
+        .collect_par_sorted_by_key(None, None)?;
+
+    assert_eq!(values, vec![1, 2, 3, 4, 5, 6]);
+    assert_eq!(sums, vec![("even".to_string(), 12), ("odd".to_string(), 9)]);
+    assert_eq!(calls.load(Ordering::SeqCst), 6);
+    Ok(())
+}
+
+#[test]
+fn cache_is_shared_by_both_sides_of_a_join() -> Result<()> {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let p = Pipeline::default();
+    let shared = counted(&p, &calls).cache();
+
+    let totals = shared.clone().combine_values(Sum::<u32>::default());
+    let joined = shared.join_inner(&totals).collect_seq_sorted()?;
+
+    assert_eq!(joined.len(), 6);
+    assert_eq!(joined[0], ("even".to_string(), (2, 12)));
+    assert_eq!(calls.load(Ordering::SeqCst), 6);
+    Ok(())
+}
+
+#[test]
+fn uncached_diamond_recomputes_the_prefix() -> Result<()> {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let p = Pipeline::default();
+    let shared = counted(&p, &calls);
+
+    let totals = shared.clone().combine_values(Sum::<u32>::default());
+    shared.join_inner(&totals).collect_seq()?;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 12);
+    Ok(())
+}
+
+#[test]
+fn cache_feeds_flatten_inputs() -> Result<()> {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let p = Pipeline::default();
+    let shared = counted(&p, &calls).values().cache();
+
+    let doubled = shared.clone().map(|x: &u32| x * 2);
+    let mut out = flatten(&[&shared, &doubled]).collect_seq()?;
+    out.sort_unstable();
+
+    assert_eq!(out, vec![1, 2, 2, 3, 4, 4, 5, 6, 6, 8, 10, 12]);
+    assert_eq!(calls.load(Ordering::SeqCst), 6);
+    Ok(())
+}
+
+#[test]
+fn nested_caches_each_run_once() -> Result<()> {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let p = Pipeline::default();
+    let first = counted(&p, &calls).values().cache();
+    let second = first.clone().filter(|x: &u32| *x > 2).cache();
+
+    assert_eq!(second.clone().collect_seq()?, vec![3, 4, 5, 6]);
+    assert_eq!(first.collect_par(None, Some(3))?.len(), 6);
+    assert_eq!(second.count_globally().collect_seq()?, vec![4]);
+    assert_eq!(calls.load(Ordering::SeqCst), 6);
+    Ok(())
+}
+
+#[test]
+fn cache_surfaces_upstream_errors_and_retries() {
+    let fail = Arc::new(AtomicBool::new(true));
+    let f = Arc::clone(&fail);
+    let p = Pipeline::default();
+    let shared = from_vec(&p, vec![1u32, 2, 3])
+        .map(move |x: &u32| {
+            assert!(!f.load(Ordering::SeqCst), "upstream failed");
+            *x
+        })
+        .cache();
+
+    let err = shared.clone().collect_seq().unwrap_err();
+    assert!(format!("{err:#}").contains("upstream failed"));
+
+    // A failed computation is not stored, so the next read tries again.
+    fail.store(false, Ordering::SeqCst);
+    assert_eq!(shared.collect_seq().unwrap(), vec![1, 2, 3]);
+}
@@ -0,0 +1,153 @@
+//! Tests for enrichment lookups through a `CacheIO` cache.
+
+use anyhow::{Result, bail};
+use ironbeam::helpers::cached_lookup::CachedLookupOptions;
+use ironbeam::io::cloud::{CacheIO, FakeCacheIO};
+use ironbeam::*;
+use std::cell::RefCell;
+use std::sync::Arc;
+
+#[test]
+fn misses_load_and_write_back() -> Result<()> {
+    let cache = FakeCacheIO::new();
+    let loads = RefCell::new(Vec::new());
+    let p = Pipeline::default();
+
+    let out = from_vec(&p, Vec::from([1, 2, 1, 1, 2]))
+        .map_with_cached_lookup(
+            &cache,
+            |n: &u32| format!("k{n}"),
+            |key| {
+                loads.borrow_mut().push(key.to_string());
+                Ok(key.len() * 10)
+            },
+            Some(60),
+        )?
+        .collect_seq()?;
+
+    assert_eq!(out, vec![(1, 20), (2, 20), (1, 20), (1, 20), (2, 20)]);
+    assert_eq!(*loads.borrow(), vec!["k1", "k2"]);
+    assert_eq!(cache.get_calls(), vec!["k1", "k2"]);
+    assert_eq!(
+        cache.set_calls(),
+        vec![("k1".to_string(), Some(60)), ("k2".to_string(), Some(60))]
+    );
+    Ok(())
+}
+
+#[test]
+fn remote_hits_skip_the_loader() -> Result<()> {
+    let cache = FakeCacheIO::new();
+    cache.set("k1", b"\"cached\"", None)?;
+    let p = Pipeline::default();
+
+    let out = from_vec(&p, Vec::from([1, 2]))
+        .map_with_cached_lookup(
+            &cache,
+            |n: &u32| format!("k{n}"),
+            |_| Ok("loaded".to_string()),
+            None,
+        )?
+        .collect_seq()?;
+    assert_eq!(
+        out,
+        vec![(1, "cached".to_string()), (2, "loaded".to_string())]
+    );
+    Ok(())
+}
+
+#[test]
+fn undecodable_cached_values_are_reloaded() -> Result<()> {
+    let cache = FakeCacheIO::new();
+    cache.set("k1", b"not json", None)?;
+    let p = Pipeline::default();
+
+    let out = from_vec(&p, Vec::from([1]))
+        .map_with_cached_lookup(&cache, |n: &u32| format!("k{n}"), |_| Ok(7u32), None)?
+        .collect_seq()?;
+    assert_eq!(out, vec![(1, 7)]);
+    assert_eq!(cache.get("k1")?, Some(b"7".to_vec()));
+    Ok(())
+}
+
+#[test]
+fn lru_capacity_bounds_the_local_layer() -> Result<()> {
+    let cache = FakeCacheIO::new();
+    let p = Pipeline::default();
+    let opts = CachedLookupOptions::default().with_lru_capacity(1);
+
+    from_vec(&p, Vec::from([1, 2, 1, 1]))
+        .map_with_cached_lookup_options(&cache, |n: &u32| format!("k{n}"), |_| Ok(0u8), &opts)?
+        .collect_seq()?;
+    // k1 is evicted by k2, fetched again from the remote cache, then kept.
+    assert_eq!(cache.get_calls(), vec!["k1", "k2", "k1"]);
+    assert_eq!(cache.set_calls().len(), 2);
+
+    let cache = FakeCacheIO::new();
+    let opts = opts.with_lru_capacity(0);
+    from_vec(&p, Vec::from([1, 1]))
+        .map_with_cached_lookup_options(&cache, |n: &u32| format!("k{n}"), |_| Ok(0u8), &opts)?
+        .collect_seq()?;
+    assert_eq!(cache.get_calls().len(), 2);
+    Ok(())
+}
+
+#[test]
+fn loader_errors_name_the_key() {
+    let cache = FakeCacheIO::new();
+    let p = Pipeline::default();
+    let Err(err) = from_vec(&p, Vec::from([3])).map_with_cached_lookup(
+        &cache,
+        |n: &u32| format!("k{n}"),
+        |_| -> Result<u32> { bail!("database down") },
+        None,
+    ) else {
+        panic!("the loader should fail");
+    };
+    let msg = format!("{err:#}");
+    assert!(
+        msg.contains("load k3") && msg.contains("database down"),
+        "{msg}"
+    );
+}
+
+#[test]
+fn shared_lookup_is_lazy_and_shares_the_lru_across_partitions() -> Result<()> {
+    let cache = FakeCacheIO::new();
+    let p = Pipeline::default();
+
+    let looked_up = from_vec(&p, Vec::from([1, 2, 1, 2, 1, 2, 1, 2]))
+        .map_with_cached_lookup_shared(
+            Arc::new(cache.clone()),
+            |n: &u32| format!("k{n}"),
+            |key| Ok(key.len()),
+            None,
+        );
+    assert!(cache.get_calls().is_empty());
+
+    // One thread runs the four partitions in turn, so later ones hit the shared LRU.
+    let out = looked_up.collect_par(Some(1), Some(4))?;
+    assert_eq!(out.len(), 8);
+    assert!(out.iter().all(|(_, v)| *v == 2));
+    assert_eq!(cache.get_calls(), vec!["k1", "k2"]);
+    Ok(())
+}
+
+#[test]
+fn shared_lookup_loader_errors_fail_the_step() {
+    let p = Pipeline::default();
+    let err = from_vec(&p, Vec::from([3]))
+        .map_with_cached_lookup_shared(
+            Arc::new(FakeCacheIO::new()),
+            |n: &u32| format!("k{n}"),
+            |_| -> Result<u32> { bail!("database down") },
+            None,
+        )
+        .collect_seq()
+        .unwrap_err();
+    let msg = format!("{err:#}");
+    assert!(
+        msg.contains("load k3") && msg.contains("database down"),
+        "{msg}"
+    );
+}
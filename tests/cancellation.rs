@@ -0,0 +1,121 @@
+//! Tests for cooperative cancellation and `DirectRunner::timeout`.
+
+use anyhow::Result;
+use ironbeam::*;
+use std::thread::sleep;
+use std::time::Duration;
+
+fn parallel(partitions: usize) -> ExecMode {
+    ExecMode::Parallel {
+        threads: None,
+        partitions: Some(partitions),
+    }
+}
+
+fn cancelled_at(err: &anyhow::Error) -> (StepRef, CancelReason) {
+    match err.downcast_ref() {
+        Some(IronbeamError::Cancelled { node, reason }) => (node.clone(), *reason),
+        _ => panic!("expected a cancellation, got: {err}"),
+    }
+}
+
+#[test]
+fn uncancelled_token_runs_to_completion() -> Result<()> {
+    let p = Pipeline::default();
+    let out = from_vec(&p, (0u32..100).collect::<Vec<_>>()).map(|x: &u32| x + 1);
+    let token = CancellationToken::new();
+    let values: Vec<u32> =
+        DirectRunner::default().run_with_cancellation(&p, out.node_id(), &token)?;
+    assert_eq!(values, (1..=100).collect::<Vec<_>>());
+    assert!(!token.is_cancelled());
+    Ok(())
+}
+
+#[test]
+fn cancelled_token_stops_before_first_step() {
+    let p = Pipeline::default();
+    let out = from_vec(&p, vec![1u32, 2, 3]).map(|x: &u32| x * 2);
+    let token = CancellationToken::new();
+    token.cancel();
+
+    let err = DirectRunner::default()
+        .run_with_cancellation::<u32>(&p, out.node_id(), &token)
+        .unwrap_err();
+    let (node, reason) = cancelled_at(&err);
+    assert_eq!(node.step, 1);
+    assert_eq!(node.kind, "Source");
+    assert_eq!(reason, CancelReason::Requested);
+    assert_eq!(err.to_string(), "run cancelled at step 1 (Source)");
+}
+
+#[test]
+fn cancelling_mid_run_stops_at_next_transform() {
+    for mode in [ExecMode::Sequential, parallel(4)] {
+        let p = Pipeline::default();
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        let out = from_vec(&p, (0u32..1_000).collect::<Vec<_>>())
+            .map(move |x: &u32| {
+                canceller.cancel();
+                *x
+            })
+            .with_name("trigger")
+            .key_by(|x: &u32| x % 10)
+            .group_by_key()
+            .with_name("group");
+
+        let runner = DirectRunner {
+            mode,
+            ..DirectRunner::default()
+        };
+        let err = runner
+            .run_with_cancellation::<(u32, Vec<u32>)>(&p, out.node_id(), &token)
+            .unwrap_err();
+        let (node, reason) = cancelled_at(&err);
+        assert_eq!(reason, CancelReason::Requested);
+        assert_ne!(node.name.as_deref(), Some("group"), "{err}");
+    }
+}
+
+#[test]
+fn timeout_stops_slow_run() {
+    let p = Pipeline::default();
+    let out = from_vec(&p, (0u32..8).collect::<Vec<_>>())
+        .map(|x: &u32| {
+            sleep(Duration::from_millis(20));
+            *x
+        })
+        .with_name("slow")
+        .map(|x: &u32| x + 1)
+        .with_name("after");
+
+    let runner = DirectRunner {
+        mode: ExecMode::Sequential,
+        timeout: Some(Duration::from_millis(50)),
+        ..DirectRunner::default()
+    };
+    let err = runner.run_collect::<u32>(&p, out.node_id()).unwrap_err();
+    let (node, reason) = cancelled_at(&err);
+    assert_eq!(reason, CancelReason::TimedOut(Duration::from_millis(50)));
+    assert_eq!(node.name.as_deref(), Some("slow + after"));
+    assert!(
+        err.to_string().starts_with("run timed out after 50ms"),
+        "{err}"
+    );
+}
+
+#[test]
+fn generous_timeout_changes_nothing() -> Result<()> {
+    let p = Pipeline::default();
+    let out = from_vec(&p, (0u32..100).collect::<Vec<_>>())
+        .key_by(|x: &u32| x % 3)
+        .group_by_key();
+    let runner = DirectRunner {
+        mode: parallel(3),
+        timeout: Some(Duration::from_secs(60)),
+        ..DirectRunner::default()
+    };
+    let groups: Vec<(u32, Vec<u32>)> = runner.run_collect(&p, out.node_id())?;
+    assert_eq!(groups.len(), 3);
+    Ok(())
+}
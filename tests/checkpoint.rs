@@ -3,9 +3,11 @@
 #[cfg(feature = "checkpointing")]
 mod checkpoint_tests {
     use ironbeam::checkpoint::{
-        CheckpointConfig, CheckpointManager, CheckpointMetadata, CheckpointPolicy, CheckpointState,
-        compute_checksum, current_timestamp_ms,
+        CHECKPOINT_FORMAT_VERSION, CheckpointConfig, CheckpointData, CheckpointManager,
+        CheckpointMetadata, CheckpointPolicy, CheckpointState, compute_checksum,
+        current_timestamp_ms,
     };
+    use ironbeam::io::compression::Compression;
     use std::fs::{self, File};
     use std::io::{Read, Write};
     use tempfile::TempDir;
@@ -342,6 +344,7 @@ mod checkpoint_tests {
         let config = CheckpointConfig {
             enabled: true,
             directory: tmp.path().to_path_buf(),
+            compression: Compression::None,
             ..Default::default()
         };
         let mut manager = CheckpointManager::new(config).unwrap();
@@ -371,9 +374,15 @@ mod checkpoint_tests {
         file.read_to_end(&mut data).unwrap();
         drop(file);
 
-        let mut corrupted_state: CheckpointState = postcard::from_bytes(&data).unwrap();
+        // Skip the 9-byte format header (magic + version + compression flag) and keep
+        // whatever follows the state.
+        let (header, body) = data.split_at(9);
+        let (mut corrupted_state, rest): (CheckpointState, &[u8]) =
+            postcard::take_from_bytes(body).unwrap();
         corrupted_state.completed_node_index = 999; // Corrupt data
-        let corrupted_data = postcard::to_allocvec(&corrupted_state).unwrap();
+        let mut corrupted_data = header.to_vec();
+        corrupted_data.extend(postcard::to_allocvec(&corrupted_state).unwrap());
+        corrupted_data.extend_from_slice(rest);
 
         let mut file = File::create(&path).unwrap();
         file.write_all(&corrupted_data).unwrap();
@@ -382,6 +391,177 @@ mod checkpoint_tests {
         // Loading should fail due to checksum mismatch
         assert!(manager.load_checkpoint(&path).is_err());
     }
+
+    fn state_with_partitions(pipeline_id: &str, partition_count: usize) -> CheckpointState {
+        let timestamp = current_timestamp_ms();
+        let metadata_str = format!("{pipeline_id}:3:{timestamp}:{partition_count}");
+        CheckpointState {
+            pipeline_id: pipeline_id.to_string(),
+            completed_node_index: 3,
+            timestamp,
+            partition_count,
+            checksum: compute_checksum(metadata_str.as_bytes()),
+            exec_mode: "parallel".to_string(),
+            metadata: CheckpointMetadata {
+                total_nodes: 6,
+                last_node_type: "GroupByKey".to_string(),
+                progress_percent: 50,
+            },
+        }
+    }
+
+    #[test]
+    fn test_save_and_load_checkpoint_data() {
+        let tmp = TempDir::new().unwrap();
+        let config = CheckpointConfig {
+            enabled: true,
+            directory: tmp.path().to_path_buf(),
+            ..Default::default()
+        };
+        let mut manager = CheckpointManager::new(config).unwrap();
+
+        let data = CheckpointData::new(vec![vec![1, 2, 3], vec![], vec![4]]);
+        assert!(data.verify());
+        let path = manager
+            .save_checkpoint_with_data(&state_with_partitions("data", 3), Some(&data))
+            .unwrap();
+
+        let mut header = [0u8; 8];
+        File::open(&path).unwrap().read_exact(&mut header).unwrap();
+        assert_eq!(&header[..4], b"IBCK");
+        assert_eq!(
+            u32::from_le_bytes(header[4..].try_into().unwrap()),
+            CHECKPOINT_FORMAT_VERSION
+        );
+
+        let (state, loaded) = manager.load_checkpoint_with_data(&path).unwrap();
+        assert_eq!(state.completed_node_index, 3);
+        assert_eq!(loaded, Some(data));
+        // Plain loading ignores the data but still validates it.
+        assert_eq!(manager.load_checkpoint(&path).unwrap().partition_count, 3);
+    }
+
+    #[test]
+    fn test_data_checksum_verification() {
+        let tmp = TempDir::new().unwrap();
+        let config = CheckpointConfig {
+            enabled: true,
+            directory: tmp.path().to_path_buf(),
+            ..Default::default()
+        };
+        let mut manager = CheckpointManager::new(config).unwrap();
+
+        let mut data = CheckpointData::new(vec![vec![1, 2, 3]]);
+        data.partitions[0][1] = 9;
+        assert!(!data.verify());
+        let path = manager
+            .save_checkpoint_with_data(&state_with_partitions("tampered", 1), Some(&data))
+            .unwrap();
+        assert!(manager.load_checkpoint_with_data(&path).is_err());
+
+        // The partition count in the metadata must match the stored partitions.
+        let data = CheckpointData::new(vec![vec![1], vec![2]]);
+        let path = manager
+            .save_checkpoint_with_data(&state_with_partitions("miscounted", 1), Some(&data))
+            .unwrap();
+        assert!(manager.load_checkpoint_with_data(&path).is_err());
+    }
+
+    #[test]
+    fn test_checkpoint_format_versions() {
+        let tmp = TempDir::new().unwrap();
+        let config = CheckpointConfig {
+            enabled: true,
+            directory: tmp.path().to_path_buf(),
+            ..Default::default()
+        };
+        let manager = CheckpointManager::new(config).unwrap();
+        let state = state_with_partitions("versions", 1);
+
+        // Version 1 files are a bare postcard-encoded state without a header.
+        let legacy = tmp.path().join("legacy.bin");
+        fs::write(&legacy, postcard::to_allocvec(&state).unwrap()).unwrap();
+        let (loaded, data) = manager.load_checkpoint_with_data(&legacy).unwrap();
+        assert_eq!(loaded.pipeline_id, "versions");
+        assert!(data.is_none());
+
+        // Files from a newer format version are rejected.
+        let future = tmp.path().join("future.bin");
+        let mut bytes = b"IBCK".to_vec();
+        bytes.extend_from_slice(&(CHECKPOINT_FORMAT_VERSION + 1).to_le_bytes());
+        bytes.extend(postcard::to_allocvec(&state).unwrap());
+        fs::write(&future, bytes).unwrap();
+        let err = manager.load_checkpoint(&future).err().unwrap();
+        assert!(err.to_string().contains("format version"));
+    }
+
+    #[test]
+    fn test_version_2_checkpoint_still_loads() {
+        let tmp = TempDir::new().unwrap();
+        let config = CheckpointConfig {
+            enabled: true,
+            directory: tmp.path().to_path_buf(),
+            ..Default::default()
+        };
+        let manager = CheckpointManager::new(config).unwrap();
+        let state = state_with_partitions("v2", 1);
+        let data = CheckpointData::new(vec![vec![7, 8]]);
+
+        // Version 2: header without the compression flag, uncompressed postcard body.
+        #[derive(serde::Serialize)]
+        struct V2Body<'a> {
+            state: &'a CheckpointState,
+            data: Option<&'a CheckpointData>,
+        }
+        let mut bytes = b"IBCK".to_vec();
+        bytes.extend_from_slice(&2u32.to_le_bytes());
+        bytes.extend(
+            postcard::to_allocvec(&V2Body {
+                state: &state,
+                data: Some(&data),
+            })
+            .unwrap(),
+        );
+        let path = tmp.path().join("v2.bin");
+        fs::write(&path, bytes).unwrap();
+
+        let (loaded, stored) = manager.load_checkpoint_with_data(&path).unwrap();
+        assert_eq!(loaded.pipeline_id, "v2");
+        assert_eq!(stored, Some(data));
+    }
+
+    #[test]
+    #[cfg(feature = "compression-zstd")]
+    fn test_compressed_checkpoint_roundtrip() {
+        let tmp = TempDir::new().unwrap();
+        let save = |compression: Compression| {
+            let config = CheckpointConfig {
+                enabled: true,
+                directory: tmp.path().join(format!("{compression:?}")),
+                compression,
+                ..Default::default()
+            };
+            let mut manager = CheckpointManager::new(config).unwrap();
+            let data = CheckpointData::new(vec![vec![42u8; 64 * 1024]; 4]);
+            let path = manager
+                .save_checkpoint_with_data(&state_with_partitions("zstd", 4), Some(&data))
+                .unwrap();
+            let (_, loaded) = manager.load_checkpoint_with_data(&path).unwrap();
+            assert_eq!(loaded, Some(data));
+            fs::read(path).unwrap()
+        };
+
+        let plain = save(Compression::None);
+        let compressed = save(Compression::Zstd { level: 3 });
+        assert_eq!(plain[8], 0);
+        assert_eq!(compressed[8], 1);
+        assert!(compressed[9..].starts_with(&[0x28, 0xb5, 0x2f, 0xfd]));
+        assert!(compressed.len() * 10 < plain.len());
+        assert_eq!(
+            CheckpointConfig::default().compression,
+            Compression::Zstd { level: 3 }
+        );
+    }
 }
 
 #[cfg(not(feature = "checkpointing"))]
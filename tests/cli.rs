@@ -0,0 +1,185 @@
+//! Tests for the command-line pipeline runner.
+#![cfg(all(feature = "cli", feature = "io-jsonl"))]
+
+use anyhow::Result;
+use ironbeam::cli::{Cli, CliArgs, CliCommand, CliMode, PipelineOutput};
+use ironbeam::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+struct Order {
+    region: String,
+    cents: u64,
+}
+
+fn cli() -> Cli {
+    Cli::new("orders")
+        .register("totals", "Sum order value per region", |p, args| {
+            let min: u64 = args.param("min").map_or(Ok(0), str::parse)?;
+            let totals = read_jsonl::<Order>(p, args.input()?)?
+                .filter(move |o: &Order| o.cents >= min)
+                .map(|o: &Order| (o.region.clone(), o.cents))
+                .sum_per_key();
+            Ok(PipelineOutput::jsonl(totals))
+        })
+        .register("count", "Count the numbers 1..=n", |p, args| {
+            let n: u64 = args.require_param("n")?;
+            Ok(PipelineOutput::jsonl(from_vec(
+                p,
+                (1..=n).collect::<Vec<_>>(),
+            )))
+        })
+}
+
+#[test]
+fn parses_flags_in_both_forms() -> Result<()> {
+    let CliCommand::Run(args) = CliArgs::parse([
+        "totals",
+        "--input",
+        "a.jsonl",
+        "--input=b.jsonl",
+        "--output=out.jsonl",
+        "--partitions",
+        "8",
+        "--param",
+        "min=5",
+        "--dry-run",
+    ])?
+    else {
+        panic!("expected a run command");
+    };
+    assert_eq!(args.pipeline, "totals");
+    assert_eq!(args.inputs.len(), 2);
+    assert_eq!(args.output.as_deref(), Some("out.jsonl".as_ref()));
+    assert_eq!(args.mode, CliMode::Parallel);
+    assert_eq!(args.partitions, Some(8));
+    assert_eq!(args.param("min"), Some("5"));
+    assert!(args.dry_run && !args.quiet);
+    assert!(args.input().is_err());
+
+    assert_eq!(CliArgs::parse(["--list"])?, CliCommand::List);
+    assert_eq!(CliArgs::parse(["x", "--help"])?, CliCommand::Help);
+
+    for (bad, message) in [
+        (vec!["--partitions", "0"], "positive integer"),
+        (vec!["--mode", "fast"], "sequential or parallel"),
+        (
+            vec!["--mode=sequential", "--threads=2"],
+            "only apply to --mode parallel",
+        ),
+        (vec!["--param", "min"], "KEY=VALUE"),
+        (vec!["--output"], "needs a value"),
+        (vec!["--verbose"], "unknown option --verbose"),
+        (vec!["a", "b"], "already given"),
+    ] {
+        let err = CliArgs::parse(bad).unwrap_err();
+        assert!(format!("{err:#}").contains(message), "{err:#}");
+    }
+    Ok(())
+}
+
+#[test]
+fn runs_a_registered_pipeline_to_a_file() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let input = tmp.path().join("orders.jsonl");
+    let output = tmp.path().join("totals.jsonl");
+    let order = |region: &str, cents| Order {
+        region: region.into(),
+        cents,
+    };
+    write_jsonl_vec(
+        &input,
+        &[
+            order("eu", 10),
+            order("us", 3),
+            order("eu", 7),
+            order("us", 1),
+        ],
+    )?;
+
+    let mut log = Vec::new();
+    let report = cli()
+        .run(
+            [
+                "totals",
+                "--input",
+                input.to_str().unwrap(),
+                "--output",
+                output.to_str().unwrap(),
+                "--mode",
+                "sequential",
+                "--param",
+                "min=2",
+            ],
+            &mut log,
+        )?
+        .expect("a run report");
+    assert_eq!(report.pipeline, "totals");
+    assert_eq!(report.rows, 2);
+
+    let mut totals: Vec<(String, u64)> = read_jsonl_vec(&output)?;
+    totals.sort();
+    assert_eq!(totals, vec![("eu".to_string(), 17), ("us".to_string(), 3)]);
+
+    let log = String::from_utf8(log)?;
+    assert!(log.contains(&report.explanation.to_string()), "{log}");
+    assert!(log.contains("totals: wrote 2 records to"), "{log}");
+    Ok(())
+}
+
+#[test]
+fn dry_run_lists_and_errors() -> Result<()> {
+    let cli = cli();
+    let mut log = Vec::new();
+    let report = cli
+        .run(["count", "--param", "n=5", "--dry-run"], &mut log)?
+        .expect("a run report");
+    assert_eq!(report.rows, 0);
+    assert!(!String::from_utf8(log)?.contains("running"));
+
+    let mut log = Vec::new();
+    assert!(cli.run(["--list"], &mut log)?.is_none());
+    let listing = String::from_utf8(log)?;
+    assert!(listing.starts_with("count") && listing.contains("Sum order value per region"));
+
+    for (args, message) in [
+        (vec!["--quiet"], "name a pipeline to run: count, totals"),
+        (vec!["nope"], "unknown pipeline \"nope\""),
+        (vec!["count"], "--param n=... is required"),
+        (vec!["count", "--param", "n=x"], "--param n=x is invalid"),
+        (vec!["totals"], "--input is required"),
+    ] {
+        let err = cli.run(args, &mut Vec::new()).unwrap_err();
+        assert!(format!("{err:#}").contains(message), "{err:#}");
+    }
+
+    let mut log = Vec::new();
+    let single = Cli::new("one").register("count", "", |p, _| {
+        Ok(PipelineOutput::with_writer(
+            from_vec(p, vec![1u32, 2, 3]),
+            |rows, output| {
+                assert!(output.is_none());
+                Ok(rows.len())
+            },
+        ))
+    });
+    let report = single.run(["--quiet"], &mut log)?.expect("a run report");
+    assert_eq!(report.rows, 3);
+    assert!(log.is_empty());
+    Ok(())
+}
+
+#[test]
+fn describe_reports_the_manifest_without_running() -> Result<()> {
+    let mut log = Vec::new();
+    let report = cli()
+        .run(["count", "--param", "n=4", "--describe"], &mut log)?
+        .expect("a run report");
+    let manifest = report.manifest.expect("a manifest");
+    assert_eq!(report.rows, 0);
+    assert_eq!(manifest.inputs[0].estimated_elements, Some(4));
+    assert_eq!(manifest.outputs[0].format.as_deref(), Some("jsonl"));
+    assert_eq!(manifest.outputs[0].location.as_deref(), Some("stdout"));
+    assert!(log.is_empty());
+    Ok(())
+}
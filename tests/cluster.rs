@@ -0,0 +1,144 @@
+//! Tests for the experimental `ClusterRunner`, with workers served from threads.
+#![cfg(feature = "cluster")]
+
+use anyhow::Result;
+use ironbeam::cluster::{ClusterRunner, serve};
+use ironbeam::spec::DoFnRegistry;
+use ironbeam::*;
+use serde_json::json;
+use std::net::{SocketAddr, TcpListener};
+
+fn registry() -> DoFnRegistry {
+    let mut registry = DoFnRegistry::new();
+    registry
+        .register_source("range", |p, cfg| {
+            let n = cfg["n"].as_u64().unwrap_or(0);
+            Ok(from_vec(p, (0..n).collect::<Vec<u64>>()))
+        })
+        .register_filter("odd", |_| Ok(|x: &u64| x % 2 == 1))
+        .register_transform("sum_by_mod", |input: PCollection<u64>, cfg| {
+            let m = cfg.as_u64().unwrap_or(1);
+            Ok(input
+                .key_by(move |x: &u64| x % m)
+                .combine_values(Sum::<u64>::default()))
+        })
+        .register_transform("group_by_mod", |input: PCollection<u64>, cfg| {
+            let m = cfg.as_u64().unwrap_or(1);
+            Ok(input
+                .key_by(move |x: &u64| x % m)
+                .group_by_key()
+                .map(|(k, vs): &(u64, Vec<u64>)| (*k, vs.len() as u64)))
+        })
+        .register_transform("sorted", |input: PCollection<(u64, u64)>, _| {
+            Ok(input.sort_by_key(|kv: &(u64, u64)| kv.0))
+        })
+        .register_transform("total", |input: PCollection<(u64, u64)>, _| {
+            Ok(input
+                .map(|kv: &(u64, u64)| kv.1)
+                .combine_globally(Sum::<u64>::default(), None))
+        })
+        .register_map("panic", |_| {
+            Ok(|x: &u64| {
+                assert!(*x != 7, "seven is not allowed");
+                *x
+            })
+        });
+    registry
+}
+
+/// Start `n` worker threads and a runner connected to them.
+fn cluster(n: usize) -> Result<ClusterRunner> {
+    let mut addrs: Vec<SocketAddr> = Vec::new();
+    for _ in 0..n {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        addrs.push(listener.local_addr()?);
+        std::thread::spawn(move || serve(&listener, &registry()));
+    }
+    let mut runner = ClusterRunner::connect(registry(), addrs)?;
+    runner.partitions_per_worker = 2;
+    Ok(runner)
+}
+
+fn sorted<T: Ord>(mut v: Vec<T>) -> Vec<T> {
+    v.sort_unstable();
+    v
+}
+
+#[test]
+fn keyed_stages_shuffle_across_workers() -> Result<()> {
+    let registry = registry();
+    let p = Pipeline::default();
+    let nums = registry.source::<u64>(&p, "range", json!({ "n": 1000 }))?;
+    let odd = registry.apply::<u64, u64>(nums, "odd", ())?;
+    let sums = registry.apply::<u64, (u64, u64)>(odd.clone(), "sum_by_mod", 7)?;
+    let counts = registry.apply::<u64, (u64, u64)>(odd, "group_by_mod", 5)?;
+
+    let runner = cluster(3)?;
+    assert_eq!(runner.workers(), 3);
+    assert_eq!(
+        sorted(sums.clone().collect_with(&runner)?),
+        sums.collect_seq_sorted()?
+    );
+    // The same connections serve another run.
+    assert_eq!(
+        sorted(counts.clone().collect_with(&runner)?),
+        counts.collect_seq_sorted()?
+    );
+    Ok(())
+}
+
+#[test]
+fn unkeyed_barriers_finish_on_the_coordinator() -> Result<()> {
+    let registry = registry();
+    let p = Pipeline::default();
+    let nums = registry.source::<u64>(&p, "range", json!({ "n": 500 }))?;
+    let sums = registry.apply::<u64, (u64, u64)>(nums, "sum_by_mod", 10)?;
+    let ordered = registry.apply::<(u64, u64), (u64, u64)>(sums.clone(), "sorted", ())?;
+    let total = registry.apply::<(u64, u64), u64>(sums, "total", ())?;
+
+    let runner = cluster(2)?;
+    assert_eq!(
+        ordered.clone().collect_with(&runner)?,
+        ordered.collect_seq()?
+    );
+    assert_eq!(total.collect_with(&runner)?, vec![(0..500).sum::<u64>()]);
+    Ok(())
+}
+
+#[test]
+fn stateless_pipelines_collect_every_shard() -> Result<()> {
+    let registry = registry();
+    let p = Pipeline::default();
+    let nums = registry.source::<u64>(&p, "range", json!({ "n": 101 }))?;
+    let odd = registry.apply::<u64, u64>(nums, "odd", ())?;
+
+    let runner = cluster(4)?;
+    let out = sorted(odd.collect_with(&runner)?);
+    assert_eq!(out, (0..101).filter(|x| x % 2 == 1).collect::<Vec<u64>>());
+
+    let empty = registry.source::<u64>(&p, "range", json!({ "n": 0 }))?;
+    assert!(empty.collect_with(&runner)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn worker_failures_and_unregistered_steps_are_errors() -> Result<()> {
+    let registry = registry();
+    let p = Pipeline::default();
+    let nums = registry.source::<u64>(&p, "range", json!({ "n": 20 }))?;
+    let checked = registry.apply::<u64, u64>(nums.clone(), "panic", ())?;
+
+    let runner = cluster(2)?;
+    let err = checked.collect_with(&runner).unwrap_err();
+    assert!(format!("{err:#}").contains("seven is not allowed"));
+
+    // A failed run leaves the workers ready for the next one.
+    assert_eq!(
+        sorted(nums.clone().collect_with(&runner)?),
+        (0..20).collect::<Vec<u64>>()
+    );
+
+    let ad_hoc = nums.map(|x: &u64| x + 1);
+    assert!(ad_hoc.collect_with(&runner).is_err());
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use anyhow::Result;
+use ironbeam::combiners::{MaxBy, MinBy, TopKBy};
 use ironbeam::testing::*;
 use ironbeam::{AverageF64, BottomK, DistinctCount, Max, Min, Sum, TopK, from_vec};
 use std::collections::HashMap;
@@ -531,3 +532,86 @@ fn bottom_k_inverse_of_topk() -> Result<()> {
 
     Ok(())
 }
+
+/// A payload type with no `Ord` impl, to prove `MinBy`/`MaxBy`/`TopKBy` don't need one.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Reading {
+    sensor: String,
+    temp: f64,
+    ts: u64,
+}
+
+fn readings() -> Vec<(String, Reading)> {
+    [
+        ("x", 20.5, 1),
+        ("x", 25.0, 2),
+        ("x", 19.0, 3),
+        ("y", 30.0, 1),
+    ]
+    .into_iter()
+    .map(|(s, temp, ts)| {
+        (
+            s.to_string(),
+            Reading {
+                sensor: s.to_string(),
+                temp,
+                ts,
+            },
+        )
+    })
+    .collect()
+}
+
+#[test]
+fn min_by_max_by_keep_whole_value() -> Result<()> {
+    let p = TestPipeline::new();
+    let hottest = from_vec(&p, readings())
+        .combine_values(MaxBy::new(|r: &Reading| r.ts))
+        .collect_par_sorted_by_key(Some(3), None)?;
+    assert_eq!(hottest[0].1.temp, 19.0);
+    assert_eq!(hottest[1].1.sensor, "y");
+
+    let earliest = from_vec(&p, readings())
+        .group_by_key()
+        .combine_values_lifted(MinBy::new(|r: &Reading| r.ts))
+        .collect_par_sorted_by_key(None, None)?;
+    assert_eq!(earliest[0].1.ts, 1);
+    assert_eq!(earliest[0].1.temp, 20.5);
+
+    let global = from_vec(&p, vec![(1u32, "b".to_string()), (3, "a".to_string())])
+        .combine_globally(MaxBy::new(|(n, _): &(u32, String)| *n), None)
+        .collect_seq()?;
+    assert_eq!(global, vec![(3, "a".to_string())]);
+    Ok(())
+}
+
+#[test]
+fn top_k_per_key_by_ranks_on_derived_key() -> Result<()> {
+    let p = TestPipeline::new();
+    let latest = from_vec(&p, readings())
+        .top_k_per_key_by(2, |r| r.ts)
+        .collect_par_sorted_by_key(Some(4), None)?;
+    let ts: Vec<u64> = latest[0].1.iter().map(|r| r.ts).collect();
+    assert_eq!(ts, vec![3, 2]);
+    assert_eq!(latest[1].1.len(), 1);
+
+    let none = from_vec(&p, readings())
+        .top_k_per_key_by(0, |r| r.ts)
+        .collect_seq()?;
+    assert!(none.iter().all(|(_, v)| v.is_empty()));
+    Ok(())
+}
+
+#[test]
+fn top_k_by_large_input_matches_sort() -> Result<()> {
+    let p = TestPipeline::new();
+    let data: Vec<(u8, (u32, String))> = (0..5_000u32)
+        .map(|i| (0u8, ((i * 7919) % 5_000, i.to_string())))
+        .collect();
+    let top = from_vec(&p, data)
+        .combine_values(TopKBy::new(5, |(n, _): &(u32, String)| *n))
+        .collect_par(Some(8), None)?;
+    let ns: Vec<u32> = top[0].1.iter().map(|(n, _)| *n).collect();
+    assert_eq!(ns, vec![4_999, 4_998, 4_997, 4_996, 4_995]);
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use anyhow::Result;
+use ironbeam::combiners::{HllSketch, HyperLogLog};
 use ironbeam::testing::*;
 use ironbeam::*;
 
@@ -68,3 +69,113 @@ fn approx_distinct_per_key_kmv() -> Result<()> {
     assert!(eb > 5.0 && eb < 9.5);
     Ok(())
 }
+
+#[test]
+fn hyperloglog_global_estimate_within_bound() -> Result<()> {
+    let p = TestPipeline::new();
+    let out = from_vec(&p, (0u64..20_000).collect::<Vec<_>>())
+        .combine_globally(HyperLogLog::<u64>::with_precision(14), None)
+        .collect_seq()?;
+    let est = out[0].estimate();
+    assert!((19_000..=21_000).contains(&est), "est={est}");
+    assert!(out[0].sketch().is_none());
+    Ok(())
+}
+
+#[test]
+fn hyperloglog_small_cardinality_is_near_exact() -> Result<()> {
+    let p = TestPipeline::new();
+    let out = from_vec(&p, vec![1u32, 1, 2, 3, 3, 3])
+        .combine_globally(HyperLogLog::<u32>::new(), None)
+        .collect_seq()?;
+    assert_eq!(out[0].estimate(), 3);
+    Ok(())
+}
+
+#[test]
+fn hyperloglog_lifted_per_key_matches_unlifted() -> Result<()> {
+    let p = TestPipeline::new();
+    let mut data = Vec::new();
+    for i in 0..2_000u32 {
+        data.push(("a".to_string(), i % 500));
+        data.push(("b".to_string(), i % 20));
+    }
+    let mut plain = from_vec(&p, data.clone())
+        .combine_values(HyperLogLog::<u32>::new())
+        .collect_par_sorted_by_key(None, None)?;
+    let mut lifted = from_vec(&p, data)
+        .group_by_key()
+        .combine_values_lifted(HyperLogLog::<u32>::new())
+        .collect_par_sorted_by_key(None, None)?;
+    plain.sort_by(|a, b| a.0.cmp(&b.0));
+    lifted.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(plain, lifted);
+    assert_eq!(plain[1].1.estimate(), 20);
+    assert!((480..=520).contains(&plain[0].1.estimate()));
+    Ok(())
+}
+
+#[test]
+fn hyperloglog_sketch_merges_across_runs() -> Result<()> {
+    let comb = HyperLogLog::<u32>::with_precision(12).with_sketch();
+    let p = TestPipeline::new();
+    let day1 = from_vec(&p, (0u32..3_000).collect::<Vec<_>>())
+        .combine_globally(comb.clone(), None)
+        .collect_seq()?;
+    let day2 = from_vec(&p, (2_000u32..5_000).collect::<Vec<_>>())
+        .combine_globally(comb, None)
+        .collect_seq()?;
+
+    let stored = day1[0].sketch().unwrap().to_bytes();
+    let mut merged = HllSketch::from_bytes(&stored)?;
+    merged.merge(day2[0].sketch().unwrap())?;
+
+    let single = from_vec(&p, (0u32..5_000).collect::<Vec<_>>())
+        .combine_globally(HyperLogLog::<u32>::with_precision(12).with_sketch(), None)
+        .collect_seq()?;
+    assert_eq!(Some(&merged), single[0].sketch());
+    assert_eq!(merged.estimate(), single[0].estimate());
+    Ok(())
+}
+
+#[test]
+fn hll_sketch_rejects_bad_input() {
+    let mut a = HllSketch::new(10);
+    assert!(a.merge(&HllSketch::new(11)).is_err());
+    assert!(HllSketch::from_bytes(&[]).is_err());
+    assert!(HllSketch::from_bytes(&[3, 0, 0]).is_err());
+    assert!(HllSketch::from_bytes(&[4, 0, 0]).is_err());
+    assert!(HllSketch::from_bytes(&HllSketch::new(4).to_bytes()).is_ok());
+}
+
+#[test]
+fn hll_sketch_deserialization_checks_the_registers() -> Result<()> {
+    let mut sketch = HllSketch::new(4);
+    sketch.insert("a");
+    let json = serde_json::to_string(&sketch)?;
+    assert_eq!(serde_json::from_str::<HllSketch>(&json)?, sketch);
+
+    for bad in [
+        r#"{"precision":4,"registers":[0,0,0]}"#,
+        r#"{"precision":3,"registers":[0,0,0,0,0,0,0,0]}"#,
+        r#"{"precision":19,"registers":[]}"#,
+    ] {
+        let err = serde_json::from_str::<HllSketch>(bad).unwrap_err();
+        assert!(err.to_string().contains("HLL sketch"), "{err}");
+    }
+    Ok(())
+}
+
+#[test]
+fn hll_sketch_hash_is_fixed() {
+    // Pins the hash: a change here makes stored sketches unmergeable with new ones.
+    let mut sketch = HllSketch::new(4);
+    for v in 0u64..8 {
+        sketch.insert(&v);
+    }
+    sketch.insert("ironbeam");
+    assert_eq!(
+        sketch.to_bytes(),
+        [4, 0, 3, 0, 1, 2, 0, 2, 1, 0, 2, 0, 0, 3, 0, 0, 0]
+    );
+}
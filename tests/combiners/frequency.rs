@@ -0,0 +1,101 @@
+use anyhow::Result;
+use ironbeam::combiners::{CountMinSketch, FrequencySketch, TopKHeavyHitters};
+use ironbeam::testing::*;
+use ironbeam::*;
+
+/// Zipf-ish stream: value `i` appears `1000 / (i + 1)` times.
+fn skewed() -> Vec<u32> {
+    (0u32..200)
+        .flat_map(|i| std::iter::repeat_n(i, (1000 / (i + 1)) as usize))
+        .collect()
+}
+
+#[test]
+fn count_min_never_undercounts() -> Result<()> {
+    let p = TestPipeline::new();
+    let data = skewed();
+    let total = data.len() as u64;
+    let sketch = from_vec(&p, data)
+        .combine_globally(CountMinSketch::with_error(0.01, 0.01), None)
+        .collect_par(Some(4), None)?
+        .remove(0);
+    assert_eq!(sketch.total(), total);
+    for i in 0u32..200 {
+        let truth = u64::from(1000 / (i + 1));
+        let est = sketch.estimate(&i);
+        assert!(est >= truth, "value {i}: est {est} < truth {truth}");
+        assert!(est <= truth + total / 50, "value {i}: est {est} too high");
+    }
+    assert!(sketch.estimate(&10_000) <= total / 50);
+    Ok(())
+}
+
+#[test]
+fn count_min_per_key_and_merge() -> Result<()> {
+    let p = TestPipeline::new();
+    let data = vec![
+        ("x".to_string(), 1u8),
+        ("x".to_string(), 1u8),
+        ("x".to_string(), 2u8),
+        ("y".to_string(), 1u8),
+    ];
+    let mut out = from_vec(&p, data)
+        .combine_values(CountMinSketch::new(64, 4))
+        .collect_seq()?;
+    out.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(out[0].1.estimate(&1), 2);
+    assert_eq!(out[1].1.estimate(&1), 1);
+
+    let mut merged = out[0].1.clone();
+    merged.merge(&out[1].1)?;
+    assert_eq!(merged.estimate(&1), 3);
+    assert_eq!(merged.total(), 4);
+    assert!(merged.merge(&FrequencySketch::new(32, 4)).is_err());
+    Ok(())
+}
+
+#[test]
+fn heavy_hitters_globally_seq_and_par() -> Result<()> {
+    let p = TestPipeline::new();
+    let hh = from_vec(&p, skewed()).combine_globally(TopKHeavyHitters::new(3), None);
+    for top in [hh.clone().collect_seq()?, hh.collect_par(Some(8), None)?] {
+        let values: Vec<u32> = top[0].iter().map(|(v, _)| *v).collect();
+        assert_eq!(values, vec![0, 1, 2]);
+        assert!(top[0][0].1 >= 1000);
+    }
+    Ok(())
+}
+
+#[test]
+fn heavy_hitters_per_key_lifted() -> Result<()> {
+    let p = TestPipeline::new();
+    let mut data = Vec::new();
+    for i in 0..300u32 {
+        data.push(("a".to_string(), i % 3));
+        data.push(("b".to_string(), if i % 10 == 0 { 7 } else { i + 100 }));
+    }
+    let mut out = from_vec(&p, data)
+        .group_by_key()
+        .combine_values_lifted(TopKHeavyHitters::with_error(1, 0.001, 0.01))
+        .collect_seq()?;
+    out.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(out[0].1.len(), 1);
+    assert_eq!(out[1].1, vec![(7, 30)]);
+    Ok(())
+}
+
+#[test]
+fn heavy_hitters_zero_k_is_empty() -> Result<()> {
+    let p = TestPipeline::new();
+    let top = from_vec(&p, vec![1u8, 1, 2])
+        .combine_globally(TopKHeavyHitters::new(0), None)
+        .collect_seq()?;
+    assert!(top[0].is_empty());
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "epsilon")]
+fn count_min_rejects_bad_epsilon() {
+    let _ = CountMinSketch::<u32>::with_error(0.0, 0.1);
+}
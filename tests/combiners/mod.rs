@@ -3,10 +3,14 @@ mod basic;
 mod combine_global;
 mod count;
 mod distinct;
+mod frequency;
 mod integration;
 mod latest;
 mod lifting;
+mod multi;
+mod profile;
 mod quantiles;
 mod sampling;
+mod statistical;
 mod to_list;
 mod to_set;
@@ -0,0 +1,75 @@
+use anyhow::Result;
+use ironbeam::combiners::{AverageF64, Count, DistinctCount, Max, Min, MultiCombine, Sum, ToList};
+use ironbeam::testing::*;
+use ironbeam::*;
+
+fn kvs() -> Vec<(String, u64)> {
+    vec![
+        ("a".to_string(), 3),
+        ("a".to_string(), 5),
+        ("a".to_string(), 3),
+        ("b".to_string(), 10),
+    ]
+}
+
+#[test]
+fn multi_combine_per_key_matches_individual_passes() -> Result<()> {
+    let p = TestPipeline::new();
+    let fused = from_vec(&p, kvs())
+        .combine_values(
+            MultiCombine::new()
+                .add("sum", Sum::default())
+                .add("min", Min::default())
+                .add("max", Max::default())
+                .add("count", Count::new()),
+        )
+        .collect_par_sorted_by_key(Some(4), None)?;
+    assert_eq!(
+        fused,
+        vec![
+            ("a".to_string(), (11, 3, 5, 3)),
+            ("b".to_string(), (10, 10, 10, 1)),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn multi_combine_lifted_and_global() -> Result<()> {
+    let p = TestPipeline::new();
+    let comb = MultiCombine::new()
+        .add("distinct", DistinctCount::<u32>::new())
+        .add("avg", AverageF64);
+    let data: Vec<(String, u32)> = kvs()
+        .into_iter()
+        .map(|(k, v)| (k, u32::try_from(v).unwrap()))
+        .collect();
+    let lifted = from_vec(&p, data)
+        .group_by_key()
+        .combine_values_lifted(comb.clone())
+        .collect_par_sorted_by_key(None, None)?;
+    assert_eq!(lifted[0].1.0, 2);
+    assert!((lifted[0].1.1 - 11.0 / 3.0).abs() < 1e-12);
+
+    let global = from_vec(&p, vec![4u32, 2, 4])
+        .combine_globally(comb, None)
+        .collect_seq()?;
+    assert_eq!(global[0].0, 2);
+    assert!((global[0].1 - 10.0 / 3.0).abs() < 1e-12);
+    Ok(())
+}
+
+#[test]
+fn multi_combine_names_and_single_member() -> Result<()> {
+    let comb = MultiCombine::<u64, _>::new()
+        .add("all", ToList::<u64>::new())
+        .add("total", Sum::<u64>::default());
+    assert_eq!(comb.names(), ["all", "total"]);
+
+    let p = TestPipeline::new();
+    let out = from_vec(&p, vec![1u64, 2, 3])
+        .combine_globally(MultiCombine::new().add("sum", Sum::<u64>::default()), None)
+        .collect_seq()?;
+    assert_eq!(out, vec![(6,)]);
+    Ok(())
+}
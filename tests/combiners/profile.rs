@@ -0,0 +1,123 @@
+use anyhow::Result;
+use ironbeam::combiners::{DataProfile, DataProfiler};
+use ironbeam::testing::*;
+use ironbeam::*;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Order {
+    id: u32,
+    city: Option<String>,
+    total: f64,
+    express: bool,
+}
+
+fn orders() -> Vec<Order> {
+    let order = |id, city: Option<&str>, total, express| Order {
+        id,
+        city: city.map(Into::into),
+        total,
+        express,
+    };
+    vec![
+        order(1, Some("Oslo"), 10.0, false),
+        order(2, Some("Lima"), 30.0, true),
+        order(3, None, 20.0, false),
+        order(4, Some("Oslo"), 40.0, false),
+        order(5, Some("Oslo"), 50.0, true),
+    ]
+}
+
+#[test]
+fn profile_reports_per_column_statistics() -> Result<()> {
+    let p = TestPipeline::new();
+    let profile = from_vec(&p, orders()).profile().collect_seq()?.remove(0);
+
+    assert_eq!(profile.rows, 5);
+    let names: Vec<&str> = profile.columns.iter().map(|c| c.name.as_str()).collect();
+    assert_eq!(names, ["city", "express", "id", "total"]);
+
+    let city = profile.column("city").unwrap();
+    assert_eq!(city.null_count, 1);
+    assert_eq!(city.distinct_estimate, 2);
+    assert_eq!(city.min, Some(json!("Lima")));
+    assert_eq!(city.max, Some(json!("Oslo")));
+    assert_eq!(city.mean, None);
+    assert_eq!(city.top_values[0], (json!("Oslo"), 3));
+
+    let total = profile.column("total").unwrap();
+    assert_eq!(total.null_count, 0);
+    assert_eq!(total.min, Some(json!(10.0)));
+    assert_eq!(total.max, Some(json!(50.0)));
+    assert_eq!(total.mean, Some(30.0));
+    assert!((total.stddev.unwrap() - 250f64.sqrt()).abs() < 1e-9);
+
+    let express = profile.column("express").unwrap();
+    assert_eq!(express.min, Some(json!(false)));
+    assert_eq!(express.top_values[0], (json!(false), 3));
+    Ok(())
+}
+
+#[test]
+fn profile_is_partition_independent() -> Result<()> {
+    let data: Vec<(u32, u32)> = (0..2_000).map(|i| (i % 7, i)).collect();
+    let p = TestPipeline::new();
+    let seq = from_vec(&p, data.clone())
+        .profile()
+        .collect_seq()?
+        .remove(0);
+    let par = from_vec(&p, data)
+        .profile()
+        .collect_par(Some(4), Some(8))?
+        .remove(0);
+
+    assert_eq!(seq.rows, 2_000);
+    assert_eq!(seq.column("key").unwrap().distinct_estimate, 7);
+    assert_eq!(par.column("key").unwrap().distinct_estimate, 7);
+    assert_eq!(par.column("value").unwrap().max, Some(json!(1999)));
+    for profile in [&seq, &par] {
+        let mean = profile.column("value").unwrap().mean.unwrap();
+        assert!((mean - 999.5).abs() < 1e-9);
+    }
+    Ok(())
+}
+
+#[test]
+fn profile_handles_sparse_rows_and_empty_input() -> Result<()> {
+    let p = TestPipeline::new();
+    let rows = from_vec(
+        &p,
+        vec![json!({"a": 1}), json!({"b": "x"}), json!({"a": null})],
+    );
+    let profile = rows
+        .combine_globally(DataProfiler::new().with_top_k(1), None)
+        .collect_seq()?
+        .remove(0);
+    assert_eq!(profile.column("a").unwrap().null_count, 2);
+    assert_eq!(profile.column("b").unwrap().null_count, 2);
+    assert_eq!(profile.column("a").unwrap().top_values, vec![(json!(1), 1)]);
+    assert_eq!(profile.column("a").unwrap().stddev, None);
+
+    let empty = from_vec(&p, Vec::<Order>::new()).profile().collect_seq()?;
+    assert_eq!(empty, vec![DataProfile::default()]);
+    Ok(())
+}
+
+#[test]
+fn profile_prints_and_round_trips_as_json() -> Result<()> {
+    let p = TestPipeline::new();
+    let profile = from_vec(&p, orders()).profile().collect_seq()?.remove(0);
+
+    let text = profile.to_string();
+    assert!(text.starts_with("5 rows, 4 columns\n"));
+    assert!(text.contains("city: 1 null, ~2 distinct, min \"Lima\", max \"Oslo\""));
+    assert!(text.contains("total: 0 null, ~5 distinct, min 10.0, max 50.0, mean 30.0000"));
+
+    let dir = tempfile::tempdir()?;
+    let path = dir.path().join("profile.json");
+    profile.write_to_file(&path)?;
+    let back: DataProfile = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+    assert_eq!(back, profile);
+    Ok(())
+}
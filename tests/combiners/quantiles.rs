@@ -1,5 +1,7 @@
 use anyhow::Result;
-use ironbeam::combiners::{ApproxMedian, ApproxQuantiles, TDigest};
+use ironbeam::combiners::{
+    ApproxMedian, ApproxQuantiles, Percentiles, QuantileSketch, TDigest, TDigestSketch,
+};
 use ironbeam::from_vec;
 use ironbeam::testing::*;
 
@@ -551,3 +553,117 @@ fn approx_quantiles_empty_key() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn quantile_sketch_round_trips_through_serde() -> Result<()> {
+    let p = TestPipeline::new();
+    let sketch = from_vec(&p, (1..=1_000).map(f64::from).collect::<Vec<_>>())
+        .combine_globally(QuantileSketch::new(100.0), None)
+        .collect_seq()?
+        .remove(0);
+    let json = serde_json::to_string(&sketch)?;
+    let back: TDigestSketch = serde_json::from_str(&json)?;
+    assert_eq!(back, sketch);
+    assert_approx_eq!(back.compression(), 100.0);
+    assert_approx_eq!(back.count(), 1000.0);
+    Ok(())
+}
+
+#[test]
+fn quantile_sketches_merge_across_runs() -> Result<()> {
+    let p = TestPipeline::new();
+    let mut merged: Option<TDigestSketch> = None;
+    for day in 0..4 {
+        let data: Vec<(String, f64)> = (0..250)
+            .map(|i| ("k".to_string(), f64::from(day * 250 + i)))
+            .collect();
+        let (_, sketch) = from_vec(&p, data)
+            .quantile_sketch_per_key(100.0)
+            .collect_seq()?
+            .remove(0);
+        // Persist and reload, as a daily job would.
+        let stored = serde_json::to_vec(&sketch)?;
+        let sketch: TDigestSketch = serde_json::from_slice(&stored)?;
+        match merged.as_mut() {
+            Some(m) => m.merge(&sketch),
+            None => merged = Some(sketch),
+        }
+    }
+    let merged = merged.unwrap();
+    assert_approx_eq!(merged.count(), 1000.0);
+    assert!((merged.quantile(0.5) - 500.0).abs() < 25.0);
+    assert!((merged.quantile(0.9) - 900.0).abs() < 25.0);
+    Ok(())
+}
+
+#[test]
+fn quantile_sketch_lifted_matches_quantiles() -> Result<()> {
+    let p = TestPipeline::new();
+    let data: Vec<(String, f64)> = (1..=200).map(|i| ("a".to_string(), f64::from(i))).collect();
+    let sketches = from_vec(&p, data.clone())
+        .group_by_key()
+        .combine_values_lifted(QuantileSketch::<f64>::default())
+        .collect_par(None, None)?;
+    let qs = from_vec(&p, data)
+        .approx_quantiles_per_key(vec![0.5, 0.9], 100.0)
+        .collect_seq()?;
+    let from_sketch = sketches[0].1.quantiles(&[0.5, 0.9]);
+    assert!((from_sketch[0] - qs[0].1[0]).abs() < 5.0);
+    assert!((from_sketch[1] - qs[0].1[1]).abs() < 5.0);
+    Ok(())
+}
+
+#[test]
+fn quantile_sketch_globally_helper() -> Result<()> {
+    let p = TestPipeline::new();
+    let sketch = from_vec(&p, (1u32..=100).collect::<Vec<_>>())
+        .quantile_sketch_globally(100.0)
+        .collect_seq()?;
+    assert_eq!(sketch.len(), 1);
+    assert!((sketch[0].quantile(0.5) - 50.0).abs() < 5.0);
+    Ok(())
+}
+
+#[test]
+fn test_percentiles_exact_below_cap() -> Result<()> {
+    let p = TestPipeline::new();
+    let data: Vec<f64> = (0..1_001).rev().map(f64::from).collect();
+
+    let seq = from_vec(&p, data.clone())
+        .combine_globally(Percentiles::new(vec![0.0, 0.25, 0.5, 0.999, 1.0]), None)
+        .collect_seq()?;
+    assert_eq!(seq[0], vec![0.0, 250.0, 500.0, 999.0, 1_000.0]);
+
+    let par = from_vec(&p, data)
+        .combine_globally(Percentiles::median(), None)
+        .collect_par(Some(4), Some(64))?;
+    assert_eq!(par[0], vec![500.0]);
+
+    // Non-finite values are skipped; empty groups give NaN.
+    let skipped = from_vec(&p, vec![f64::NAN, 1.0, 2.0, f64::INFINITY])
+        .exact_quantiles_globally(vec![0.5])
+        .collect_seq()?;
+    assert_eq!(skipped[0], vec![1.5]);
+    let empty = from_vec(&p, Vec::<f64>::new())
+        .exact_quantiles_globally(vec![0.5])
+        .collect_seq()?;
+    assert!(empty[0][0].is_nan());
+    Ok(())
+}
+
+#[test]
+fn test_percentiles_per_key_and_fallback_above_cap() -> Result<()> {
+    let p = TestPipeline::new();
+    let mut data: Vec<(String, f64)> = (1..=4).map(|i| ("small".into(), f64::from(i))).collect();
+    data.extend((0..10_000).map(|i| ("big".into(), f64::from(i))));
+
+    let mut out = from_vec(&p, data)
+        .combine_values(Percentiles::median().with_cap(1_000))
+        .collect_par(Some(3), Some(500))?;
+    out.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(out[1], ("small".to_string(), vec![2.5]));
+    // Above the cap the median is a t-digest estimate.
+    assert_eq!(out[0].0, "big");
+    assert!((out[0].1[0] - 4_999.5).abs() < 100.0);
+    Ok(())
+}
@@ -64,3 +64,68 @@ fn reservoir_per_key_vec_and_flatten() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn stratified_sample_takes_a_fraction_of_each_stratum() -> Result<()> {
+    let p = TestPipeline::new();
+    let data: Vec<(u32, char)> = (0..1_000)
+        .map(|i| (i, if i % 10 == 0 { 'y' } else { 'n' }))
+        .collect();
+    let fraction = |label: &char| if *label == 'y' { 0.5 } else { 0.2 };
+
+    let sample = from_vec(&p, data.clone())
+        .sample_stratified(|&(_, label)| label, fraction, 3)
+        .collect_seq_sorted()?;
+    assert_eq!(sample.iter().filter(|(_, l)| *l == 'y').count(), 50);
+    assert_eq!(sample.iter().filter(|(_, l)| *l == 'n').count(), 180);
+    assert!(sample.iter().all(|x| data.contains(x)));
+    let mut ids: Vec<u32> = sample.iter().map(|&(i, _)| i).collect();
+    ids.dedup();
+    assert_eq!(ids.len(), 230);
+
+    // Same seed, same sample; fractions outside [0, 1] are clamped.
+    let again = from_vec(&p, data.clone())
+        .sample_stratified(|&(_, label)| label, fraction, 3)
+        .collect_seq_sorted()?;
+    assert_eq!(again, sample);
+    let clamped = from_vec(&p, data)
+        .sample_stratified(
+            |&(_, label)| label,
+            |&l| if l == 'y' { 2.0 } else { -1.0 },
+            3,
+        )
+        .collect_seq()?;
+    assert_eq!(clamped.len(), 100);
+    Ok(())
+}
+
+#[test]
+fn weighted_sample_favors_heavy_elements() -> Result<()> {
+    let p = TestPipeline::new();
+    // Ids 0..10 weigh 1000, ids 10..1000 weigh 1; id 1000 weighs nothing.
+    let data: Vec<u32> = (0..=1_000).collect();
+    let weight = |&i: &u32| match i {
+        0..10 => 1_000.0,
+        1_000 => 0.0,
+        _ => 1.0,
+    };
+
+    let seq = from_vec(&p, data.clone())
+        .sample_weighted(weight, 20, 11)
+        .collect_seq_sorted()?;
+    assert_eq!(seq.len(), 20);
+    assert!(seq.iter().filter(|&&i| i < 10).count() >= 8);
+    assert!(!seq.contains(&1_000));
+
+    let par = from_vec(&p, data.clone())
+        .sample_weighted(weight, 20, 11)
+        .collect_par(Some(4), Some(8))?;
+    assert_eq!(par.len(), 20);
+
+    // Only positive weights are eligible.
+    let few = from_vec(&p, data)
+        .sample_weighted(|&i| if i < 3 { 1.0 } else { f64::NAN }, 10, 1)
+        .collect_seq_sorted()?;
+    assert_eq!(few, vec![0, 1, 2]);
+    Ok(())
+}
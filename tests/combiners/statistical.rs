@@ -0,0 +1,119 @@
+use anyhow::Result;
+use ironbeam::combiners::{Moments, MomentsF64, StdDevF64, VarianceF64};
+use ironbeam::testing::*;
+use ironbeam::*;
+
+const EPS: f64 = 1e-9;
+
+/// Reference two-pass moments for comparison.
+fn reference(xs: &[f64]) -> (f64, f64, f64, f64) {
+    let n = xs.len() as f64;
+    let mean = xs.iter().sum::<f64>() / n;
+    let m = |k: i32| xs.iter().map(|x| (x - mean).powi(k)).sum::<f64>();
+    (mean, m(2), m(3), m(4))
+}
+
+#[test]
+fn variance_and_stddev_per_key() -> Result<()> {
+    let p = TestPipeline::new();
+    let data = vec![
+        ("a".to_string(), 2.0),
+        ("a".to_string(), 4.0),
+        ("a".to_string(), 4.0),
+        ("a".to_string(), 4.0),
+        ("a".to_string(), 5.0),
+        ("a".to_string(), 5.0),
+        ("a".to_string(), 7.0),
+        ("a".to_string(), 9.0),
+        ("b".to_string(), 1.0),
+    ];
+    let pop = from_vec(&p, data.clone())
+        .combine_values(VarianceF64::population())
+        .collect_par_sorted_by_key(None, None)?;
+    assert!((pop[0].1 - 4.0).abs() < EPS);
+    assert!(pop[1].1.abs() < EPS);
+
+    let sample = from_vec(&p, data.clone())
+        .combine_values(VarianceF64::default())
+        .collect_par_sorted_by_key(None, None)?;
+    assert!((sample[0].1 - 32.0 / 7.0).abs() < EPS);
+    assert!(sample[1].1.is_nan());
+
+    let sd = from_vec(&p, data)
+        .combine_values(StdDevF64::population())
+        .collect_par_sorted_by_key(None, None)?;
+    assert!((sd[0].1 - 2.0).abs() < EPS);
+    Ok(())
+}
+
+#[test]
+fn moments_parallel_lifted_match_reference() -> Result<()> {
+    let p = TestPipeline::new();
+    let xs: Vec<f64> = (0..5_000)
+        .map(|i| f64::from((i * 37) % 101).powf(1.3))
+        .collect();
+    let (mean, m2, m3, m4) = reference(&xs);
+
+    let data: Vec<(u8, f64)> = xs.iter().map(|&x| (0u8, x)).collect();
+    let plain = from_vec(&p, data.clone())
+        .combine_values(MomentsF64)
+        .collect_par(Some(8), None)?;
+    let lifted = from_vec(&p, data)
+        .group_by_key()
+        .combine_values_lifted(MomentsF64)
+        .collect_par(Some(8), None)?;
+
+    for (_, m) in [&plain[0], &lifted[0]] {
+        assert_eq!(m.count, 5_000);
+        assert!((m.mean - mean).abs() < 1e-9 * mean.abs());
+        assert!((m.m2 - m2).abs() < 1e-8 * m2.abs());
+        assert!((m.m3 - m3).abs() < 1e-6 * m3.abs().max(1.0));
+        assert!((m.m4 - m4).abs() < 1e-8 * m4.abs());
+    }
+    Ok(())
+}
+
+#[test]
+fn moments_globally_skewness_and_kurtosis() -> Result<()> {
+    let p = TestPipeline::new();
+    let m = from_vec(&p, vec![1u32, 2, 3, 4, 100])
+        .combine_globally(MomentsF64, None)
+        .collect_seq()?[0];
+    assert!(m.skewness() > 1.0, "right-skewed data");
+    assert!(m.excess_kurtosis() > 0.0);
+
+    let flat = from_vec(&p, vec![3.0f64; 4])
+        .combine_globally(MomentsF64, None)
+        .collect_seq()?[0];
+    assert!(flat.skewness().is_nan());
+    assert!(flat.sample_variance().abs() < EPS);
+    Ok(())
+}
+
+#[test]
+fn moments_merge_is_order_independent() {
+    let xs: Vec<f64> = (1..=40).map(f64::from).collect();
+    let mut whole = Moments::default();
+    xs.iter().for_each(|&x| whole.push(x));
+
+    let mut left = Moments::default();
+    let mut right = Moments::default();
+    xs[..13].iter().for_each(|&x| left.push(x));
+    xs[13..].iter().for_each(|&x| right.push(x));
+    let mut lr = left;
+    lr.merge(&right);
+    let mut rl = right;
+    rl.merge(&left);
+
+    for m in [lr, rl] {
+        assert_eq!(m.count, whole.count);
+        assert!((m.mean - whole.mean).abs() < EPS);
+        assert!((m.m2 - whole.m2).abs() < 1e-6);
+        assert!((m.m3 - whole.m3).abs() < 1e-6);
+        assert!((m.m4 - whole.m4).abs() < 1e-3);
+    }
+
+    let mut empty = Moments::default();
+    empty.merge(&Moments::default());
+    assert!(empty.population_variance().is_nan());
+}
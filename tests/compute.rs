@@ -0,0 +1,226 @@
+//! Tests for mapping a collection through a `ComputeIO` function.
+
+use anyhow::Result;
+use ironbeam::helpers::compute::ComputeError;
+use ironbeam::io::cloud::{CloudResult, ComputeIO, ComputeResult, FakeComputeIO, InvocationStatus};
+use ironbeam::*;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread::sleep;
+use std::time::Duration;
+
+fn serialize(x: &u32) -> Result<Vec<u8>> {
+    Ok(x.to_string().into_bytes())
+}
+
+fn deserialize(out: &[u8]) -> Result<u32> {
+    Ok(std::str::from_utf8(out)?.parse()?)
+}
+
+/// Doubles its input, answers 500 for multiples of five, and tracks how many
+/// invocations run at once and in total.
+#[derive(Default)]
+struct Doubler {
+    active: AtomicUsize,
+    peak: AtomicUsize,
+    calls: AtomicUsize,
+}
+
+impl ComputeIO for Doubler {
+    fn invoke(&self, _function_name: &str, payload: &[u8]) -> CloudResult<ComputeResult> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        let now = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+        self.peak.fetch_max(now, Ordering::SeqCst);
+        sleep(Duration::from_millis(5));
+        self.active.fetch_sub(1, Ordering::SeqCst);
+
+        let x: u32 = std::str::from_utf8(payload).unwrap().parse().unwrap();
+        let (status_code, output) = if x.is_multiple_of(5) {
+            (500, format!("cannot double {x}").into_bytes())
+        } else {
+            (200, (x * 2).to_string().into_bytes())
+        };
+        Ok(ComputeResult {
+            status_code,
+            output,
+            logs: Some(format!("handled {x}")),
+            execution_time_ms: 5,
+        })
+    }
+    fn invoke_async(&self, _function_name: &str, _payload: &[u8]) -> CloudResult<String> {
+        Ok("inv-1".to_string())
+    }
+    fn get_invocation_status(&self, _invocation_id: &str) -> CloudResult<InvocationStatus> {
+        Ok(InvocationStatus::Succeeded)
+    }
+    fn list_functions(&self) -> CloudResult<Vec<String>> {
+        Ok(vec!["double".to_string()])
+    }
+}
+
+#[test]
+fn outputs_keep_input_order() -> Result<()> {
+    let compute = FakeComputeIO::new();
+    compute.register_function("double", |input| {
+        let x: u32 = std::str::from_utf8(input).unwrap().parse().unwrap();
+        (x * 2).to_string().into_bytes()
+    });
+    let p = Pipeline::default();
+    let out = from_vec(&p, (0u32..50).collect::<Vec<_>>())
+        .map_via_compute(&compute, "double", serialize, deserialize, 8)?
+        .collect_fail_fast()?;
+    assert_eq!(out, (0u32..50).map(|x| x * 2).collect::<Vec<_>>());
+    Ok(())
+}
+
+#[test]
+fn concurrency_bounds_invocations_in_flight() -> Result<()> {
+    let compute = Doubler::default();
+    let p = Pipeline::default();
+    let out = from_vec(&p, (1u32..=40).collect::<Vec<_>>())
+        .map_via_compute(&compute, "double", serialize, deserialize, 3)?
+        .collect_seq()?;
+    assert_eq!(out.len(), 40);
+    let peak = compute.peak.load(Ordering::SeqCst);
+    assert!((1..=3).contains(&peak), "peak concurrency {peak}");
+    Ok(())
+}
+
+#[test]
+fn error_status_codes_become_compute_errors() -> Result<()> {
+    let compute = Doubler::default();
+    let p = Pipeline::default();
+    let out = from_vec(&p, vec![4u32, 5])
+        .map_via_compute(&compute, "double", serialize, deserialize, 2)?
+        .collect_seq()?;
+    assert_eq!(out[0], Ok(8));
+    let Err(err) = &out[1] else {
+        panic!("5 should fail");
+    };
+    assert_eq!(
+        err,
+        &ComputeError {
+            status_code: Some(500),
+            message: "cannot double 5".to_string(),
+            logs: Some("handled 5".to_string()),
+        }
+    );
+    assert_eq!(err.to_string(), "status 500: cannot double 5");
+    Ok(())
+}
+
+#[test]
+fn collect_fail_fast_surfaces_the_first_error() -> Result<()> {
+    let compute = Doubler::default();
+    let p = Pipeline::default();
+    let Err(err) = from_vec(&p, vec![1u32, 10, 2])
+        .map_via_compute(&compute, "double", serialize, deserialize, 2)?
+        .collect_fail_fast()
+    else {
+        panic!("10 should fail");
+    };
+    assert!(err.to_string().contains("cannot double 10"), "{err}");
+    Ok(())
+}
+
+#[test]
+fn failures_are_routed_to_dead_letters() -> Result<()> {
+    let compute = FakeComputeIO::new();
+    compute.register_function("echo", <[u8]>::to_vec);
+    let p = Pipeline::default();
+    let (good, dead) = from_vec(&p, vec![1u32, 2, 3]).map_via_compute_catching(
+        &compute,
+        "echo",
+        |x: &u32| {
+            anyhow::ensure!(*x != 2, "refusing {x}");
+            serialize(x)
+        },
+        deserialize,
+        2,
+    )?;
+    assert_eq!(good.collect_seq()?, vec![1, 3]);
+    let dead = dead.collect_seq()?;
+    assert_eq!(dead.len(), 1);
+    assert_eq!(dead[0].element, 2);
+    assert_eq!(dead[0].error, "serialize: refusing 2");
+    Ok(())
+}
+
+#[test]
+fn invocation_and_decode_failures_are_per_element() -> Result<()> {
+    let compute = FakeComputeIO::new();
+    compute.register_function("garble", |_| b"not a number".to_vec());
+    let p = Pipeline::default();
+
+    let out = from_vec(&p, vec![1u32])
+        .map_via_compute(&compute, "missing", serialize, deserialize, 1)?
+        .collect_seq()?;
+    let Err(err) = &out[0] else {
+        panic!("an unknown function should fail");
+    };
+    assert_eq!(err.status_code, None);
+    assert!(err.message.contains("missing"), "{err}");
+
+    let out = from_vec(&p, vec![1u32])
+        .map_via_compute(&compute, "garble", serialize, deserialize, 1)?
+        .collect_seq()?;
+    let Err(err) = &out[0] else {
+        panic!("the output should not deserialize");
+    };
+    assert_eq!(err.status_code, Some(200));
+    assert!(err.message.starts_with("deserialize:"), "{err}");
+    Ok(())
+}
+
+#[test]
+fn zero_concurrency_is_rejected() {
+    let p = Pipeline::default();
+    let Err(err) = from_vec(&p, vec![1u32]).map_via_compute(
+        &FakeComputeIO::new(),
+        "double",
+        serialize,
+        deserialize,
+        0,
+    ) else {
+        panic!("zero concurrency should be rejected");
+    };
+    assert!(err.to_string().contains("concurrency"), "{err}");
+}
+
+#[test]
+fn shared_map_is_lazy_and_shares_the_pool_across_partitions() -> Result<()> {
+    let compute = Arc::new(Doubler::default());
+    let p = Pipeline::default();
+    let doubled = from_vec(&p, (1u32..=12).collect::<Vec<_>>()).map_via_compute_shared(
+        compute.clone(),
+        "double",
+        serialize,
+        deserialize,
+        2,
+    )?;
+    assert_eq!(compute.calls.load(Ordering::SeqCst), 0);
+
+    let out = doubled.collect_par(Some(4), Some(4))?;
+    assert_eq!(out.len(), 12);
+    assert_eq!(out.iter().filter(|r| r.is_err()).count(), 2);
+    // Four partitions run at once, but through one pool of two threads.
+    assert!(compute.peak.load(Ordering::SeqCst) <= 2);
+    Ok(())
+}
+
+#[test]
+fn shared_catching_invokes_each_element_once() -> Result<()> {
+    let compute = Arc::new(Doubler::default());
+    let p = Pipeline::default();
+    let (good, dead) = from_vec(&p, (1u32..=10).collect::<Vec<_>>())
+        .map_via_compute_catching_shared(compute.clone(), "double", serialize, deserialize, 4)?;
+
+    let mut good = good.collect_par(None, Some(3))?;
+    good.sort_unstable();
+    assert_eq!(good, vec![2, 4, 6, 8, 12, 14, 16, 18]);
+    let mut dead: Vec<u32> = dead.collect_seq()?.into_iter().map(|d| d.element).collect();
+    dead.sort_unstable();
+    assert_eq!(dead, vec![5, 10]);
+    assert_eq!(compute.calls.load(Ordering::SeqCst), 10);
+    Ok(())
+}
@@ -0,0 +1,159 @@
+//! Tests for resolving `config://` references through a `ConfigIO` service.
+#![cfg(all(feature = "io-jsonl", feature = "io-csv"))]
+
+use anyhow::Result;
+use ironbeam::io::cloud::upload::ObjectWriteOptions;
+use ironbeam::io::cloud::{
+    CloudResult, ConfigIO, ConfigResolver, ConfigValue, ErrorKind, FakeConfigIO, FakeObjectIO,
+    ObjectIO,
+};
+use ironbeam::*;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+struct Row {
+    id: u32,
+}
+
+/// Counts the `get` calls that reach the underlying service.
+struct CountingConfig {
+    inner: FakeConfigIO,
+    gets: AtomicU32,
+}
+
+impl ConfigIO for CountingConfig {
+    fn get(&self, key: &str) -> CloudResult<ConfigValue> {
+        self.gets.fetch_add(1, Ordering::SeqCst);
+        self.inner.get(key)
+    }
+
+    fn set(&self, key: &str, value: &str, is_secret: bool) -> CloudResult<()> {
+        self.inner.set(key, value, is_secret)
+    }
+
+    fn delete(&self, key: &str) -> CloudResult<()> {
+        self.inner.delete(key)
+    }
+
+    fn list(&self, prefix: Option<&str>) -> CloudResult<Vec<String>> {
+        self.inner.list(prefix)
+    }
+
+    fn batch_get(&self, keys: Vec<String>) -> CloudResult<Vec<Option<ConfigValue>>> {
+        self.inner.batch_get(keys)
+    }
+}
+
+fn config(entries: &[(&str, &str)]) -> CloudResult<FakeConfigIO> {
+    let config = FakeConfigIO::new();
+    for (key, value) in entries {
+        config.set(key, value, false)?;
+    }
+    Ok(config)
+}
+
+#[test]
+fn resolves_whole_values_and_placeholders() -> Result<()> {
+    let resolver = ConfigResolver::new(Arc::new(config(&[
+        ("db/user", "etl"),
+        ("db/password", "hunter2"),
+    ])?));
+    assert_eq!(resolver.resolve("config://db/user")?, "etl");
+    assert_eq!(
+        resolver.resolve("postgres://${config://db/user}:${config://db/password}@db/sales")?,
+        "postgres://etl:hunter2@db/sales"
+    );
+    assert_eq!(resolver.resolve("plain/path.jsonl")?, "plain/path.jsonl");
+    Ok(())
+}
+
+#[test]
+fn each_key_is_fetched_once() -> Result<()> {
+    let counting = Arc::new(CountingConfig {
+        inner: config(&[("bucket", "sales")])?,
+        gets: AtomicU32::new(0),
+    });
+    let resolver = ConfigResolver::new(counting.clone());
+    let shared = resolver.clone();
+    assert_eq!(resolver.resolve("config://bucket")?, "sales");
+    assert_eq!(
+        shared.resolve("s3://${config://bucket}/in")?,
+        "s3://sales/in"
+    );
+    assert_eq!(counting.gets.load(Ordering::SeqCst), 1);
+    Ok(())
+}
+
+#[test]
+fn bad_references_are_errors() -> Result<()> {
+    let resolver = ConfigResolver::new(Arc::new(config(&[("db/password", "hunter2")])?));
+    let missing = resolver.resolve("config://db/host").unwrap_err();
+    assert_eq!(missing.kind, ErrorKind::NotFound);
+    assert_eq!(
+        resolver.resolve("config://").unwrap_err().kind,
+        ErrorKind::InvalidInput
+    );
+    let unclosed = resolver.resolve("x-${config://db/password").unwrap_err();
+    assert_eq!(unclosed.kind, ErrorKind::InvalidInput);
+    assert!(!unclosed.to_string().contains("hunter2"));
+    assert!(!format!("{resolver:?}").contains("hunter2"));
+    Ok(())
+}
+
+#[test]
+fn file_paths_resolve_through_the_pipeline() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let base = dir.path().to_str().unwrap();
+    let p = Pipeline::default();
+    p.set_config_resolver(ConfigResolver::new(Arc::new(config(&[("etl/dir", base)])?)));
+
+    let written = from_vec(&p, vec![Row { id: 1 }, Row { id: 2 }])
+        .write_jsonl("${config://etl/dir}/rows.jsonl")?;
+    assert_eq!(written, 2);
+    assert!(dir.path().join("rows.jsonl").exists());
+
+    let rows: Vec<Row> = read_jsonl(&p, "${config://etl/dir}/rows.jsonl")?.collect_seq()?;
+    assert_eq!(rows, vec![Row { id: 1 }, Row { id: 2 }]);
+
+    from_vec(&p, rows).write_csv("${config://etl/dir}/rows.csv", true)?;
+    let rows: Vec<Row> = read_csv(&p, "${config://etl/dir}/rows.csv", true)?.collect_seq()?;
+    assert_eq!(rows.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn object_sinks_resolve_bucket_and_prefix() -> Result<()> {
+    let storage = FakeObjectIO::new();
+    let p = Pipeline::default();
+    p.set_config_resolver(ConfigResolver::new(Arc::new(config(&[
+        ("etl/bucket", "sales-prod"),
+        ("etl/prefix", "daily/rows"),
+    ])?)));
+
+    from_vec(&p, vec![Row { id: 1 }]).write_jsonl_object(
+        &storage,
+        "config://etl/bucket",
+        "config://etl/prefix",
+        &ObjectWriteOptions::default(),
+    )?;
+    let keys: Vec<String> = storage
+        .list_objects("sales-prod", None)?
+        .into_iter()
+        .map(|o| o.key)
+        .collect();
+    assert_eq!(keys.len(), 1);
+    assert!(keys[0].starts_with("daily/rows"), "{keys:?}");
+    Ok(())
+}
+
+#[test]
+fn references_without_a_resolver_are_errors() {
+    let p = Pipeline::default();
+    assert_eq!(p.resolve_config("data/in.jsonl").unwrap(), "data/in.jsonl");
+    let Err(err) = read_jsonl::<Row>(&p, "config://etl/input") else {
+        panic!("the reference should not resolve");
+    };
+    assert!(err.to_string().contains("no config resolver"), "{err:#}");
+}
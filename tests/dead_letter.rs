@@ -10,6 +10,8 @@
 //! - Output collections are independent (transforms compose downstream).
 //! - `DeadLetter` survives further pipeline transforms (`map`, `key_by`, …).
 //! - Custom error types via `Display`.
+//! - `try_map_with_errors` / `try_flat_map_with_errors` keep the typed error
+//!   paired with its input element.
 
 use ironbeam::*;
 use std::fmt;
@@ -299,3 +301,60 @@ fn test_flat_map_catching_parallel() {
     assert_eq!(numbers.collect_par(Some(4), Some(8)).unwrap().len(), 2_000);
     assert_eq!(errors.collect_par(Some(4), Some(8)).unwrap().len(), 1_000);
 }
+
+// ── try_map_with_errors / try_flat_map_with_errors ──────────────────────────
+
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+enum ParseError {
+    Empty,
+    NotANumber(String),
+}
+
+/// Failed elements keep their typed error alongside the original input.
+#[test]
+fn test_try_map_with_errors_keeps_typed_error() {
+    let p = Pipeline::default();
+    let raw = from_vec(
+        &p,
+        vec!["7".to_string(), String::new(), "x".into(), "9".into()],
+    );
+    let (ok, errors) = raw.try_map_with_errors(|s: &String| {
+        if s.is_empty() {
+            Err(ParseError::Empty)
+        } else {
+            s.parse::<i32>()
+                .map_err(|_| ParseError::NotANumber(s.clone()))
+        }
+    });
+
+    assert_eq!(ok.collect_seq_sorted().unwrap(), vec![7, 9]);
+    assert_eq!(
+        errors.collect_seq().unwrap(),
+        vec![
+            (String::new(), ParseError::Empty),
+            ("x".to_string(), ParseError::NotANumber("x".into())),
+        ]
+    );
+}
+
+/// A failing element produces exactly one dead-letter row; successes expand.
+#[test]
+fn test_try_flat_map_with_errors_parallel() {
+    let p = Pipeline::default();
+    let mut raw: Vec<String> = Vec::with_capacity(200);
+    for i in 0..100 {
+        raw.push(format!("{i},{i}"));
+        raw.push(format!("{i},bad"));
+    }
+    let (numbers, errors) =
+        from_vec(&p, raw).try_flat_map_with_errors::<u32, String, _>(|s: &String| {
+            s.split(',')
+                .map(|t| t.parse::<u32>().map_err(|e| e.to_string()))
+                .collect()
+        });
+
+    assert_eq!(numbers.collect_par(Some(4), Some(8)).unwrap().len(), 200);
+    let errors = errors.collect_par(Some(4), Some(8)).unwrap();
+    assert_eq!(errors.len(), 100);
+    assert!(errors.iter().all(|(input, _)| input.ends_with(",bad")));
+}
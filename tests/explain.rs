@@ -132,3 +132,95 @@ fn test_explain_display_format() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_dry_run_estimates_without_executing() -> Result<()> {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    let p = TestPipeline::new();
+    let called = Arc::new(AtomicBool::new(false));
+    let seen = Arc::clone(&called);
+    let data = from_vec(&p, (0..1000u64).collect::<Vec<_>>());
+    let mapped = data.map(move |x| {
+        seen.store(true, Ordering::SeqCst);
+        x + 1
+    });
+    let limited = mapped.take(10);
+
+    let explanation = DirectRunner::default().dry_run(&p, limited.node_id())?;
+
+    assert!(
+        !called.load(Ordering::SeqCst),
+        "dry run executed a user closure"
+    );
+    let source = &explanation.steps[0];
+    assert_eq!(source.estimated_elements, Some(1000));
+    assert_eq!(source.estimated_bytes, Some(1000 * size_of::<u64>()));
+    assert_eq!(
+        explanation.cost_estimate.estimated_output_elements,
+        Some(10)
+    );
+    assert_eq!(
+        explanation.cost_estimate.estimated_peak_bytes,
+        Some(1000 * size_of::<u64>())
+    );
+
+    // Plain explain leaves the estimates unset.
+    let plain = build_plan(&p, limited.node_id())?.explain();
+    assert!(plain.steps.iter().all(|s| s.estimated_elements.is_none()));
+    assert_eq!(plain.cost_estimate.estimated_peak_bytes, None);
+    Ok(())
+}
+
+#[test]
+fn test_dry_run_global_combine_and_flatten() -> Result<()> {
+    let p = TestPipeline::new();
+    let a = from_vec(&p, vec![1u32; 30]);
+    let b = from_vec(&p, vec![2u32; 12]);
+    let merged = flatten(&[&a, &b]);
+
+    let explanation = DirectRunner::default().dry_run(&p, merged.node_id())?;
+    assert_eq!(
+        explanation.cost_estimate.estimated_output_elements,
+        Some(42)
+    );
+
+    let counted = merged.count_globally();
+    let explanation = DirectRunner::default().dry_run(&p, counted.node_id())?;
+    assert_eq!(explanation.cost_estimate.estimated_output_elements, Some(1));
+
+    let output = format!("{explanation}");
+    assert!(output.contains("Est. Output"), "{output}");
+    assert!(output.contains("Est. Peak Memory"), "{output}");
+    Ok(())
+}
+
+#[cfg(feature = "io-jsonl")]
+#[test]
+fn test_dry_run_sizes_file_source_from_shard_metadata() -> Result<()> {
+    use serde::Deserialize;
+
+    #[derive(Clone, Deserialize, serde::Serialize)]
+    struct Row {
+        n: u32,
+    }
+
+    let tmp = tempfile::tempdir()?;
+    let path = tmp.path().join("rows.jsonl");
+    let body: String = (0..50).map(|n| format!("{{\"n\":{n}}}\n")).collect();
+    std::fs::write(&path, &body)?;
+
+    let p = TestPipeline::new();
+    let rows = read_jsonl_streaming::<Row>(&p, &path, 10)?;
+    let ns = rows.map(|r: &Row| r.n);
+
+    let explanation = DirectRunner::default().dry_run(&p, ns.node_id())?;
+    assert_eq!(explanation.cost_estimate.source_size, Some(50));
+    assert_eq!(explanation.steps[0].estimated_bytes, Some(body.len()));
+    assert_eq!(
+        explanation.cost_estimate.estimated_output_elements,
+        Some(50)
+    );
+    Ok(())
+}
@@ -282,3 +282,196 @@ fn composite_transform_with_aggregation() -> Result<()> {
     assert_eq!(counts_map.get("lazy"), Some(&1));
     Ok(())
 }
+
+// Test custom Sink: records every lifecycle call.
+#[derive(Default)]
+struct RecordingSink {
+    partitions: usize,
+    batch_size: usize,
+    fail_on_partition: Option<usize>,
+    events: Vec<String>,
+    rows: Vec<u32>,
+}
+
+impl Sink<u32> for RecordingSink {
+    fn open(&mut self, partition: usize, num_partitions: usize) -> Result<()> {
+        self.events
+            .push(format!("open {partition}/{num_partitions}"));
+        if self.fail_on_partition == Some(partition) {
+            anyhow::bail!("cannot open partition {partition}");
+        }
+        Ok(())
+    }
+
+    fn write_batch(&mut self, batch: &[u32]) -> Result<()> {
+        self.events.push(format!("write {}", batch.len()));
+        self.rows.extend_from_slice(batch);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.events.push("flush".into());
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<()> {
+        self.events.push("close".into());
+        Ok(())
+    }
+
+    fn partitions(&self) -> usize {
+        self.partitions
+    }
+
+    fn batch_size(&self) -> usize {
+        self.batch_size
+    }
+}
+
+/// Split recorded events into one `(partition, events)` block per `open`, checking that
+/// no two partitions interleave and that `close` comes last.
+fn partition_blocks(events: &[String]) -> Vec<(usize, Vec<String>)> {
+    let (last, events) = events.split_last().expect("no events");
+    assert_eq!(last, "close");
+    let mut blocks: Vec<(usize, Vec<String>)> = Vec::new();
+    for event in events {
+        if let Some(open) = event.strip_prefix("open ") {
+            let partition = open.split('/').next().unwrap().parse().unwrap();
+            blocks.push((partition, Vec::new()));
+        } else {
+            let (_, block) = blocks.last_mut().expect("write before open");
+            assert_ne!(block.last().map(String::as_str), Some("flush"));
+            block.push(event.clone());
+        }
+    }
+    blocks.sort_by_key(|(partition, _)| *partition);
+    blocks
+}
+
+#[test]
+fn sink_is_driven_per_partition_in_order() -> Result<()> {
+    let p = TestPipeline::new();
+    let mut sink = RecordingSink {
+        batch_size: 2,
+        ..Default::default()
+    };
+
+    let written = from_vec(&p, (1u32..=5).collect::<Vec<_>>())
+        .map(|x: &u32| x * 10)
+        .write_to(&mut sink)?;
+
+    assert_eq!(written, 5);
+    assert_eq!(sink.rows, vec![10, 20, 30, 40, 50]);
+    assert_eq!(
+        sink.events,
+        vec![
+            "open 0/1", "write 2", "write 2", "write 1", "flush", "close"
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn sink_is_driven_from_the_runs_partitions() -> Result<()> {
+    let p = TestPipeline::new();
+    let mut sink = RecordingSink {
+        partitions: 3,
+        batch_size: 2,
+        ..Default::default()
+    };
+
+    let written = from_vec(&p, (1u32..=12).collect::<Vec<_>>())
+        .map(|x: &u32| x * 10)
+        .write_to(&mut sink)?;
+
+    assert_eq!(written, 12);
+    let mut rows = sink.rows.clone();
+    rows.sort_unstable();
+    assert_eq!(rows, (1u32..=12).map(|x| x * 10).collect::<Vec<_>>());
+    let blocks = partition_blocks(&sink.events);
+    assert_eq!(
+        blocks.iter().map(|(i, _)| *i).collect::<Vec<_>>(),
+        vec![0, 1, 2]
+    );
+    assert!(
+        sink.events
+            .iter()
+            .all(|e| !e.starts_with("open") || e.ends_with("/3"))
+    );
+    for (_, block) in &blocks {
+        assert_eq!(block, &["write 2", "write 2", "flush"]);
+    }
+    Ok(())
+}
+
+#[test]
+fn sink_after_stateful_step_gets_its_output_partitions() -> Result<()> {
+    let p = TestPipeline::new();
+    let mut sink = RecordingSink {
+        partitions: 2,
+        batch_size: 100,
+        ..Default::default()
+    };
+    let written = from_vec(&p, (0u32..20).collect::<Vec<_>>())
+        .key_by(|x: &u32| x % 4)
+        .group_by_key()
+        .map(|(k, vs): &(u32, Vec<u32>)| k * 100 + u32::try_from(vs.len()).unwrap())
+        .write_to(&mut sink)?;
+
+    assert_eq!(written, 4);
+    let mut rows = sink.rows.clone();
+    rows.sort_unstable();
+    assert_eq!(rows, vec![5, 105, 205, 305]);
+    let blocks = partition_blocks(&sink.events);
+    let n = blocks.len();
+    assert_eq!(
+        blocks.iter().map(|(i, _)| *i).collect::<Vec<_>>(),
+        (0..n).collect::<Vec<_>>()
+    );
+    Ok(())
+}
+
+#[test]
+fn sink_after_take_is_driven_sequentially() -> Result<()> {
+    let p = TestPipeline::new();
+    let mut sink = RecordingSink {
+        partitions: 4,
+        batch_size: 10,
+        ..Default::default()
+    };
+    let written = from_vec(&p, (1u32..=20).collect::<Vec<_>>())
+        .take(3)
+        .write_to(&mut sink)?;
+
+    assert_eq!(written, 3);
+    assert_eq!(sink.rows, vec![1, 2, 3]);
+    assert_eq!(sink.events, vec!["open 0/1", "write 3", "flush", "close"]);
+    Ok(())
+}
+
+#[test]
+fn sink_empty_collection_still_opens_and_closes() -> Result<()> {
+    let p = TestPipeline::new();
+    let mut sink = RecordingSink::default();
+    let written = from_vec(&p, Vec::<u32>::new()).write_to(&mut sink)?;
+    assert_eq!(written, 0);
+    assert_eq!(sink.events, vec!["open 0/1", "flush", "close"]);
+    Ok(())
+}
+
+#[test]
+fn sink_failure_stops_writing_and_still_closes() {
+    let p = TestPipeline::new();
+    let mut sink = RecordingSink {
+        partitions: 3,
+        batch_size: 10,
+        fail_on_partition: Some(1),
+        ..Default::default()
+    };
+    let err = from_vec(&p, (1u32..=9).collect::<Vec<_>>())
+        .write_to(&mut sink)
+        .unwrap_err();
+    assert!(err.to_string().contains("cannot open partition 1"));
+    let failed = sink.events.iter().position(|e| e == "open 1/3").unwrap();
+    assert_eq!(sink.events[failed + 1..], ["close"]);
+}
@@ -0,0 +1,210 @@
+//! Tests for file writes executed as pipeline steps.
+#![cfg(all(feature = "io-jsonl", feature = "io-csv", feature = "parallel-io"))]
+
+use anyhow::Result;
+use ironbeam::*;
+use serde::{Deserialize, Serialize, Serializer};
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+struct Row {
+    id: u32,
+    name: String,
+}
+
+fn rows(p: &Pipeline, n: u32) -> PCollection<Row> {
+    from_vec(p, (0..n).collect::<Vec<_>>()).map(|id: &u32| Row {
+        id: *id,
+        name: format!("row-{id}"),
+    })
+}
+
+#[test]
+fn csv_parts_share_one_header() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let file = tmp.path().join("nested/out.csv");
+    let p = Pipeline::default();
+    let expected = rows(&p, 100).collect_seq()?;
+
+    assert_eq!(rows(&p, 100).write_csv_par(&file, Some(7), true)?, 100);
+    let text = std::fs::read_to_string(&file)?;
+    assert_eq!(text.matches("id,name").count(), 1);
+    assert!(text.starts_with("id,name\n"));
+    assert_eq!(read_csv_vec::<Row>(&file, true)?, expected);
+    // The part files the workers wrote are gone.
+    let left: Vec<_> = std::fs::read_dir(tmp.path().join("nested"))?
+        .map(|e| e.map(|e| e.file_name()))
+        .collect::<Result<_, _>>()?;
+    assert_eq!(left, vec!["out.csv"]);
+    Ok(())
+}
+
+#[test]
+fn jsonl_output_is_the_same_for_any_partitioning() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let p = Pipeline::default();
+    let seq = tmp.path().join("seq.jsonl");
+    let par = tmp.path().join("par.jsonl");
+
+    assert_eq!(rows(&p, 500).write_jsonl(&seq)?, 500);
+    assert_eq!(rows(&p, 500).write_jsonl_par(&par, Some(9))?, 500);
+    assert_eq!(std::fs::read(&seq)?, std::fs::read(&par)?);
+    assert_eq!(read_jsonl_vec::<Row>(&par)?, rows(&p, 500).collect_seq()?);
+    Ok(())
+}
+
+#[test]
+fn write_after_take_keeps_the_limit() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let file = tmp.path().join("head.jsonl");
+    let p = Pipeline::default();
+
+    assert_eq!(rows(&p, 1_000).take(3).write_jsonl(&file)?, 3);
+    let back: Vec<Row> = read_jsonl_vec(&file)?;
+    assert_eq!(back.iter().map(|r| r.id).collect::<Vec<_>>(), vec![0, 1, 2]);
+    Ok(())
+}
+
+#[test]
+fn empty_collection_writes_empty_file() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let file = tmp.path().join("empty.csv");
+    let p = Pipeline::default();
+
+    assert_eq!(from_vec(&p, Vec::<Row>::new()).write_csv(&file, true)?, 0);
+    assert!(std::fs::read(&file)?.is_empty());
+    Ok(())
+}
+
+/// Fails to serialize when its value is odd.
+#[derive(Clone, Deserialize, Debug)]
+struct Picky(u32);
+
+impl Serialize for Picky {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if !self.0.is_multiple_of(2) {
+            return Err(serde::ser::Error::custom(format!("odd value {}", self.0)));
+        }
+        serializer.serialize_u32(self.0)
+    }
+}
+
+#[test]
+fn serialization_errors_fail_the_write() {
+    let tmp = tempfile::tempdir().unwrap();
+    let file = tmp.path().join("bad.jsonl");
+    let p = Pipeline::default();
+    let err = from_vec(&p, vec![Picky(0), Picky(2), Picky(3)])
+        .write_jsonl(&file)
+        .unwrap_err();
+    match err.downcast_ref::<IronbeamError>() {
+        Some(IronbeamError::TransformFailed { node, message, .. }) => {
+            assert_eq!(node.name.as_deref(), Some("write_jsonl"));
+            assert!(message.contains("odd value 3"), "{message}");
+        }
+        _ => panic!("expected TransformFailed, got {err:#}"),
+    }
+}
+
+#[test]
+fn writes_run_with_the_pipelines_runner_config() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let file = tmp.path().join("out.jsonl");
+    let p = Pipeline::default();
+    let token = CancellationToken::new();
+    p.set_runner_config(RunnerConfig::builder().cancellation(token.clone()).build());
+
+    assert_eq!(rows(&p, 50).write_jsonl_par(&file, Some(4))?, 50);
+
+    token.cancel();
+    for err in [
+        rows(&p, 50).write_jsonl(&file).unwrap_err(),
+        rows(&p, 50)
+            .write_csv_par(&file, Some(4), true)
+            .unwrap_err(),
+    ] {
+        assert!(
+            matches!(
+                err.downcast_ref::<IronbeamError>(),
+                Some(IronbeamError::Cancelled { .. })
+            ),
+            "expected Cancelled, got {err:#}"
+        );
+    }
+    // The cancelled writes left the earlier output in place.
+    assert_eq!(read_jsonl_vec::<Row>(&file)?.len(), 50);
+    Ok(())
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn encode_step_is_recorded_in_metrics() -> Result<()> {
+    use ironbeam::metrics::MetricsCollector;
+
+    let tmp = tempfile::tempdir()?;
+    let p = Pipeline::default();
+    p.set_metrics(MetricsCollector::new());
+    rows(&p, 200).write_jsonl_par(tmp.path().join("out.jsonl"), Some(4))?;
+
+    let report = p.take_metrics().expect("metrics were set").report();
+    let step = report
+        .per_transform()
+        .iter()
+        .find(|t| {
+            t.node
+                .name
+                .as_deref()
+                .is_some_and(|n| n.contains("write_jsonl"))
+        })
+        .expect("write step recorded");
+    assert_eq!(step.elements_out, Some(4));
+    Ok(())
+}
+
+#[cfg(feature = "io-parquet")]
+#[test]
+fn parquet_write_runs_per_partition() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let p = Pipeline::default();
+    let full = tmp.path().join("rows.parquet");
+    let empty = tmp.path().join("empty.parquet");
+
+    assert_eq!(rows(&p, 300).write_parquet(&full)?, 300);
+    assert_eq!(
+        read_parquet_vec::<Row>(&full)?,
+        rows(&p, 300).collect_seq()?
+    );
+
+    assert_eq!(from_vec(&p, Vec::<Row>::new()).write_parquet(&empty)?, 0);
+    assert!(read_parquet_vec::<Row>(&empty)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn file_sinks_match_the_write_methods() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let p = Pipeline::default();
+    let (jsonl, csv) = (tmp.path().join("a.jsonl"), tmp.path().join("a.csv"));
+    let (sink_jsonl, sink_csv) = (tmp.path().join("b.jsonl"), tmp.path().join("b.csv"));
+
+    rows(&p, 300).write_jsonl(&jsonl)?;
+    rows(&p, 300).write_csv(&csv, true)?;
+    assert_eq!(
+        rows(&p, 300).write_to(JsonlSink::new(&sink_jsonl).with_partitions(5))?,
+        300
+    );
+    assert_eq!(
+        rows(&p, 300).write_to(CsvSink::new(&sink_csv, true).with_partitions(5))?,
+        300
+    );
+    assert_eq!(std::fs::read(&jsonl)?, std::fs::read(&sink_jsonl)?);
+    assert_eq!(std::fs::read(&csv)?, std::fs::read(&sink_csv)?);
+
+    // A failed run writes nothing.
+    let failed = tmp.path().join("failed.jsonl");
+    let err = from_vec(&p, vec![Picky(0), Picky(3)])
+        .write_to(JsonlSink::new(&failed))
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("odd value 3"), "{err:#}");
+    assert!(!failed.exists());
+    Ok(())
+}
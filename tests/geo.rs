@@ -0,0 +1,135 @@
+//! Tests for the geospatial transforms.
+#![cfg(feature = "geo")]
+
+use anyhow::Result;
+use ironbeam::transforms::geo::{Located, Point, Polygon, geohash};
+use ironbeam::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Ping {
+    id: u32,
+    at: Point,
+}
+
+impl Located for Ping {
+    fn location(&self) -> Point {
+        self.at
+    }
+}
+
+fn ping(id: u32, lat: f64, lon: f64) -> Ping {
+    Ping {
+        id,
+        at: Point::new(lat, lon),
+    }
+}
+
+#[test]
+fn geohash_and_haversine_match_reference_values() {
+    let jutland = Point::new(57.649_11, 10.407_44);
+    assert_eq!(geohash(jutland, 11), "u4pruydqqvj");
+    assert_eq!(geohash(jutland, 0), "u");
+    assert_eq!(geohash(jutland, 40).len(), 12);
+    assert_eq!(geohash(Point::new(-33.868_8, 151.209_3), 5), "r3gx2");
+
+    let paris = Point::new(48.856_6, 2.352_2);
+    let london = (51.507_4, -0.127_8).location();
+    let d = paris.haversine_distance(&london);
+    assert!((343_000.0..344_500.0).contains(&d), "{d}");
+    assert!(paris.haversine_distance(&paris).abs() < 1e-9);
+}
+
+#[test]
+fn keys_and_filters_by_location() -> Result<()> {
+    let p = Pipeline::default();
+    let pings = from_vec(
+        &p,
+        vec![
+            ping(1, 48.856_6, 2.352_2),  // Paris
+            ping(2, 48.858_4, 2.294_5),  // Eiffel Tower, ~4.2 km away
+            ping(3, 51.507_4, -0.127_8), // London
+        ],
+    );
+
+    let cells = pings
+        .clone()
+        .key_by_geohash(3)
+        .map_values(|p: &Ping| p.id)
+        .collect_seq_sorted()?;
+    assert_eq!(
+        cells,
+        vec![
+            ("gcp".to_string(), 3),
+            ("u09".to_string(), 1),
+            ("u09".to_string(), 2)
+        ]
+    );
+
+    let paris = Point::new(48.856_6, 2.352_2);
+    let near: Vec<u32> = pings
+        .clone()
+        .filter_within_distance(paris, 5_000.0)
+        .map(|p: &Ping| p.id)
+        .collect_seq_sorted()?;
+    assert_eq!(near, vec![1, 2]);
+    let far: Vec<u32> = pings
+        .filter_beyond_distance(paris, 1_000.0)
+        .map(|p: &Ping| p.id)
+        .collect_seq_sorted()?;
+    assert_eq!(far, vec![2, 3]);
+    Ok(())
+}
+
+#[test]
+fn spatial_join_assigns_points_to_containing_polygons() -> Result<()> {
+    let square = [
+        (0.0, 0.0),
+        (0.0, 10.0),
+        (10.0, 10.0),
+        (10.0, 0.0),
+        (0.0, 0.0),
+    ];
+    let zones = side_vec(vec![
+        Polygon::new("square", square).with_hole([(4.0, 4.0), (4.0, 6.0), (6.0, 6.0), (6.0, 4.0)]),
+        // A concave "L" overlapping the square's east half.
+        Polygon::new(
+            "ell",
+            [
+                (0.0, 5.0),
+                (0.0, 20.0),
+                (3.0, 20.0),
+                (3.0, 8.0),
+                (10.0, 8.0),
+                (10.0, 5.0),
+            ],
+        ),
+        Polygon::new("empty", Vec::<Point>::new()),
+    ]);
+
+    let p = Pipeline::default();
+    let joined = from_vec(
+        &p,
+        vec![
+            ping(1, 1.0, 1.0),   // square only
+            ping(2, 5.0, 4.5),   // in the square's hole
+            ping(3, 2.0, 9.0),   // square and ell
+            ping(4, 2.0, 15.0),  // ell only
+            ping(5, 6.0, 15.0),  // in the ell's bounding box, outside the ell
+            ping(6, -1.0, -1.0), // nowhere
+        ],
+    )
+    .spatial_join(&zones)
+    .map_values(|p: &Ping| p.id)
+    .collect_seq_sorted()?;
+    assert_eq!(
+        joined,
+        vec![
+            ("ell".to_string(), 3),
+            ("ell".to_string(), 4),
+            ("square".to_string(), 1),
+            ("square".to_string(), 3),
+        ]
+    );
+    Ok(())
+}
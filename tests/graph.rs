@@ -0,0 +1,147 @@
+//! Tests for the `GraphIO` bulk-load sinks and neighbor expansion.
+
+use anyhow::Result;
+use ironbeam::helpers::graph::{GRAPH_BATCH_SIZE, GraphProperties};
+use ironbeam::io::cloud::{EdgeDirection, FakeGraphIO, GraphIO, GraphNode};
+use ironbeam::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+fn props(name: &str) -> GraphProperties {
+    HashMap::from([("name".to_string(), name.to_string())])
+}
+
+#[test]
+fn write_graph_nodes_pairs_elements_with_ids() -> Result<()> {
+    let graph = FakeGraphIO::new();
+    let p = Pipeline::default();
+    let n = GRAPH_BATCH_SIZE + 5;
+    let names: Vec<String> = (0..n).map(|i| format!("n{i}")).collect();
+
+    let nodes = from_vec(&p, names.clone())
+        .write_graph_nodes(
+            &graph,
+            |_: &String| vec!["Person".to_string()],
+            |name: &String| props(name),
+        )?
+        .collect_seq()?;
+    assert_eq!(graph.node_batch_sizes(), vec![GRAPH_BATCH_SIZE, 5]);
+    assert_eq!(nodes.len(), n);
+    for (name, id) in &nodes {
+        let node = graph.get_node(id)?.unwrap();
+        assert_eq!(node.labels, vec!["Person".to_string()]);
+        assert_eq!(node.properties, props(name));
+    }
+    assert_eq!(
+        nodes.into_iter().map(|(name, _)| name).collect::<Vec<_>>(),
+        names
+    );
+    Ok(())
+}
+
+#[test]
+fn write_graph_edges_links_loaded_nodes() -> Result<()> {
+    let graph = FakeGraphIO::new();
+    let p = Pipeline::default();
+    let ids: Vec<String> = from_vec(&p, vec!["a".to_string(), "b".to_string(), "c".to_string()])
+        .write_graph_nodes(&graph, |_: &String| vec![], |name: &String| props(name))?
+        .collect_seq()?
+        .into_iter()
+        .map(|(_, id)| id)
+        .collect();
+
+    let pairs = vec![
+        (ids[0].clone(), ids[1].clone()),
+        (ids[0].clone(), ids[2].clone()),
+    ];
+    let added = from_vec(&p, pairs).write_graph_edges(
+        &graph,
+        |pair: &(String, String)| pair.clone(),
+        |_: &(String, String)| "KNOWS".to_string(),
+        |_: &(String, String)| HashMap::from([("since".to_string(), "2020".to_string())]),
+    )?;
+    assert_eq!(added, 2);
+
+    let mut names: Vec<String> = graph
+        .get_neighbors(&ids[0], EdgeDirection::Outgoing)?
+        .into_iter()
+        .map(|node| node.properties["name"].clone())
+        .collect();
+    names.sort();
+    assert_eq!(names, vec!["b", "c"]);
+    Ok(())
+}
+
+#[test]
+fn failed_edge_batches_name_the_batch() {
+    let graph = FakeGraphIO::new();
+    let p = Pipeline::default();
+    let pairs = vec![("a".to_string(), "missing".to_string())];
+    let err = from_vec(&p, pairs)
+        .write_graph_edges(
+            &graph,
+            |pair: &(String, String)| pair.clone(),
+            |_: &(String, String)| "KNOWS".to_string(),
+            |_: &(String, String)| HashMap::new(),
+        )
+        .unwrap_err();
+    assert!(
+        format!("{err:#}").contains("write_graph_edges: batch #0"),
+        "{err:#}"
+    );
+}
+
+#[test]
+fn expand_neighbors_looks_up_each_node_once() -> Result<()> {
+    let graph = FakeGraphIO::new();
+    let a = graph.add_node(vec![], props("a"))?;
+    let b = graph.add_node(vec![], props("b"))?;
+    let c = graph.add_node(vec![], props("c"))?;
+    graph.add_edge(&a, &b, "KNOWS", HashMap::new())?;
+    graph.add_edge(&c, &a, "KNOWS", HashMap::new())?;
+
+    let p = Pipeline::default();
+    let lookups = vec![(a.clone(), 1u32), (b.clone(), 2), (a.clone(), 3)];
+    let expanded = from_vec(&p, lookups)
+        .expand_neighbors(
+            &graph,
+            |(id, _): &(String, u32)| id.clone(),
+            EdgeDirection::Both,
+        )?
+        .collect_seq()?;
+
+    assert_eq!(graph.neighbor_lookups(), vec![a.clone(), b.clone()]);
+    let names = |nodes: &[GraphNode]| {
+        let mut names: Vec<String> = nodes.iter().map(|n| n.properties["name"].clone()).collect();
+        names.sort();
+        names
+    };
+    assert_eq!(expanded[0].0.1, 1);
+    assert_eq!(names(&expanded[0].1), vec!["b", "c"]);
+    assert_eq!(names(&expanded[1].1), vec!["a"]);
+    assert_eq!(expanded[2].1, expanded[0].1);
+    Ok(())
+}
+
+#[test]
+fn shared_expand_neighbors_is_lazy_and_looks_up_per_partition() -> Result<()> {
+    let graph = FakeGraphIO::new();
+    let a = graph.add_node(vec![], props("a"))?;
+    let b = graph.add_node(vec![], props("b"))?;
+    graph.add_edge(&a, &b, "KNOWS", HashMap::new())?;
+
+    let p = Pipeline::default();
+    let expanded = from_vec(&p, vec![a.clone(); 8]).expand_neighbors_shared(
+        Arc::new(graph.clone()),
+        String::clone,
+        EdgeDirection::Outgoing,
+    );
+    assert!(graph.neighbor_lookups().is_empty());
+
+    let out = expanded.collect_par(None, Some(2))?;
+    assert_eq!(out.len(), 8);
+    assert!(out.iter().all(|(_, n)| n[0].properties["name"] == "b"));
+    // Each of the two partitions looks its node up once.
+    assert_eq!(graph.neighbor_lookups(), vec![a.clone(), a]);
+    Ok(())
+}
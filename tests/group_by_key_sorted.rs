@@ -0,0 +1,75 @@
+//! Tests for `group_by_key_sorted` / `group_by_key_sorted_by_key`: groups are
+//! sorted under both runners, ties keep arrival order, and downstream
+//! value-level transforms observe the ordering.
+
+use anyhow::Result;
+use ironbeam::testing::*;
+use ironbeam::*;
+
+fn events() -> Vec<(String, (u64, String))> {
+    let mut out = Vec::new();
+    for i in 0..500u64 {
+        let user = format!("u{}", i % 7);
+        out.push((user, ((i * 7919) % 1_000, format!("e{i}"))));
+    }
+    out
+}
+
+/// Both runners emit every group sorted by the comparator.
+#[test]
+fn sorted_groups_seq_and_par() -> Result<()> {
+    let p = TestPipeline::new();
+    let grouped = from_vec(&p, events()).group_by_key_sorted(|a, b| b.0.cmp(&a.0));
+    for out in [
+        grouped.clone().collect_seq()?,
+        grouped.collect_par(Some(8), None)?,
+    ] {
+        assert_eq!(out.len(), 7);
+        for (_, vs) in out {
+            assert!(vs.windows(2).all(|w| w[0].0 >= w[1].0));
+        }
+    }
+    Ok(())
+}
+
+/// `sorted_by_key` sorts ascending; equal keys keep input order (stable sort).
+#[test]
+fn sorted_by_key_is_stable() -> Result<()> {
+    let p = TestPipeline::new();
+    let data = vec![
+        ("k".to_string(), (2u8, "first two")),
+        ("k".to_string(), (1, "one")),
+        ("k".to_string(), (2, "second two")),
+    ];
+    let data: Vec<(String, (u8, String))> = data
+        .into_iter()
+        .map(|(k, (n, s))| (k, (n, s.to_string())))
+        .collect();
+    let out = from_vec(&p, data)
+        .group_by_key_sorted_by_key(|(n, _)| *n)
+        .collect_seq()?;
+    let labels: Vec<&str> = out[0].1.iter().map(|(_, s)| s.as_str()).collect();
+    assert_eq!(labels, vec!["one", "first two", "second two"]);
+    Ok(())
+}
+
+/// "Latest N events per user" without an explicit sort in `map_values`.
+#[test]
+fn latest_n_per_user_via_map_values() -> Result<()> {
+    let p = TestPipeline::new();
+    let latest = from_vec(&p, events())
+        .group_by_key_sorted_by_key(|(ts, _)| std::cmp::Reverse(*ts))
+        .map_values(|vs| vs.iter().take(3).map(|(ts, _)| *ts).collect::<Vec<_>>())
+        .collect_par_sorted_by_key(None, None)?;
+
+    let mut expected: std::collections::HashMap<String, Vec<u64>> = Default::default();
+    for (u, (ts, _)) in events() {
+        expected.entry(u).or_default().push(ts);
+    }
+    for (u, top) in latest {
+        let mut all = expected.remove(&u).unwrap();
+        all.sort_unstable_by(|a, b| b.cmp(a));
+        assert_eq!(top, all[..3].to_vec());
+    }
+    Ok(())
+}
@@ -0,0 +1,112 @@
+//! Tests for the Arrow record-batch helpers.
+
+#![cfg(feature = "io-parquet")]
+
+use anyhow::{Result, anyhow};
+use arrow::array::{Array, UInt64Array};
+use arrow::compute::filter_record_batch;
+use arrow::compute::kernels::cmp::gt;
+use ironbeam::testing::*;
+use ironbeam::{ArrowBatch, from_vec, read_parquet_batches, read_parquet_streaming};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Debug)]
+struct Rec {
+    id: u64,
+    word: String,
+}
+
+fn rows(n: u64) -> Vec<Rec> {
+    (0..n)
+        .map(|id| Rec {
+            id,
+            word: format!("w{}", id % 3),
+        })
+        .collect()
+}
+
+fn seed(path: &Path, n: u64) -> Result<()> {
+    let p = TestPipeline::new();
+    from_vec(&p, rows(n)).write_parquet(path)?;
+    Ok(())
+}
+
+#[test]
+fn map_batches_arrow_filters_without_row_conversion() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let path = tmp.path().join("in.parquet");
+    seed(&path, 100)?;
+
+    let p = TestPipeline::new();
+    let batches = read_parquet_batches(&p, &path, 1)?.map_batches_arrow(|batch| {
+        let ids = batch
+            .column_by_name("id")
+            .ok_or_else(|| anyhow!("no id column"))?;
+        let mask = gt(ids, &UInt64Array::new_scalar(89))?;
+        Ok(filter_record_batch(batch, &mask)?)
+    });
+
+    assert_eq!(batches.clone().count_rows()?, 10);
+    let mut out = batches.into_rows::<Rec>().collect_seq()?;
+    out.sort();
+    assert_eq!(out, rows(100)[90..]);
+    Ok(())
+}
+
+#[test]
+fn rows_to_batches_to_parquet_round_trip() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let path = tmp.path().join("out.parquet");
+
+    let p = TestPipeline::new();
+    let written = from_vec(&p, rows(50))
+        .into_arrow_batches()?
+        .write_parquet_batches(&path)?;
+    assert_eq!(written, 50);
+
+    let q = TestPipeline::new();
+    let back = read_parquet_streaming::<Rec>(&q, &path, 1)?.collect_seq_sorted()?;
+    assert_eq!(back, rows(50));
+    Ok(())
+}
+
+#[test]
+fn arrow_batch_serde_round_trip() -> Result<()> {
+    let p = TestPipeline::new();
+    let batch = from_vec(&p, rows(5))
+        .into_arrow_batches()?
+        .collect_seq()?
+        .remove(0);
+
+    let json = serde_json::to_string(&batch)?;
+    let decoded: ArrowBatch = serde_json::from_str(&json)?;
+    assert_eq!(decoded, batch);
+    assert_eq!(decoded.num_rows(), 5);
+    assert_eq!(decoded.column(0).len(), 5);
+    Ok(())
+}
+
+#[test]
+fn map_batches_arrow_errors_fail_the_run() -> Result<()> {
+    let p = TestPipeline::new();
+    let result = from_vec(&p, rows(3))
+        .into_arrow_batches()?
+        .map_batches_arrow(|batch| Ok(batch.project(&[7])?))
+        .collect_seq();
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn write_parquet_batches_rejects_empty_collections() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let p = TestPipeline::new();
+    let empty = from_vec(&p, Vec::<Rec>::new()).into_arrow_batches()?;
+    assert!(
+        empty
+            .write_parquet_batches(tmp.path().join("empty.parquet"))
+            .is_err()
+    );
+    Ok(())
+}
@@ -1,4 +1,4 @@
-//! Tests for `distinct_by`: deduplication by a computed key projection.
+//! Tests for `distinct_by` and the event-time `distinct_*_within_window` variants.
 
 use anyhow::Result;
 use ironbeam::*;
@@ -191,3 +191,72 @@ fn distinct_by_retains_full_element_not_just_key() -> Result<()> {
     assert!(!result[0].payload.is_empty());
     Ok(())
 }
+
+// ───────────────────────── distinct_*_within_window ──────────────────────────
+
+#[test]
+fn distinct_within_window_suppresses_only_nearby_repeats() -> Result<()> {
+    let p = Pipeline::default();
+    let out = from_vec(
+        &p,
+        vec![
+            (1_000u64, 7u32),
+            (1_999, 7),
+            (2_000, 7),
+            (2_500, 7),
+            (1_200, 8),
+        ],
+    )
+    .to_timestamped()
+    .distinct_within_window(1_000)
+    .reify_timestamps()
+    .collect_seq_sorted()?;
+    // 1_999 is within 1s of the kept 1_000; 2_000 starts a new window; 2_500 falls in it.
+    assert_eq!(out, vec![(1_000, 7), (1_200, 8), (2_000, 7)]);
+    Ok(())
+}
+
+#[test]
+fn distinct_by_within_window_uses_the_derived_key() -> Result<()> {
+    let p = Pipeline::default();
+    let event = |user_id, payload: &str| Event {
+        user_id,
+        payload: payload.into(),
+    };
+    // Arrival order differs from event time; the earliest event per key wins.
+    let out = from_vec(
+        &p,
+        vec![
+            (60_000u64, event(1, "retry")),
+            (10_000, event(1, "click")),
+            (10_500, event(2, "click")),
+            (200_000, event(1, "later")),
+        ],
+    )
+    .to_timestamped()
+    .distinct_by_within_window(60_000, |e| e.user_id)
+    .reify_timestamps()
+    .collect_par_sorted(Some(4), Some(3))?;
+    assert_eq!(
+        out,
+        vec![
+            (10_000, event(1, "click")),
+            (10_500, event(2, "click")),
+            (200_000, event(1, "later")),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn distinct_within_zero_window_keeps_everything() -> Result<()> {
+    let p = Pipeline::default();
+    let data: Vec<(u64, u32)> = (0..50u32).map(|i| (u64::from(i), i % 3)).collect();
+    let out = from_vec(&p, data.clone())
+        .to_timestamped()
+        .distinct_within_window(0)
+        .reify_timestamps()
+        .collect_seq_sorted()?;
+    assert_eq!(out, data);
+    Ok(())
+}
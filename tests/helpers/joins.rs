@@ -250,3 +250,179 @@ fn inner_join_bloom_correct_parallel() -> Result<()> {
     assert_eq!(out, vec![(3u32, (30u32, 300u32)), (4, (40, 400))]);
     Ok(())
 }
+
+// ── Multi-way joins ───────────────────────────────────────────────────────────
+
+/// `join_many` emits every cross-input combination for keys present in all inputs.
+#[test]
+fn join_many_inner_semantics_seq_par() -> Result<()> {
+    let p = TestPipeline::new();
+    let a = from_vec(
+        &p,
+        vec![
+            ("a".to_string(), 1u32),
+            ("a".to_string(), 2),
+            ("b".to_string(), 3),
+        ],
+    );
+    let b = from_vec(&p, vec![("a".to_string(), 10u32), ("b".to_string(), 30)]);
+    let c = from_vec(&p, vec![("a".to_string(), 100u32), ("z".to_string(), 0)]);
+
+    let joined = a.join_many(vec![&b, &c]);
+    let seq = sorted(joined.clone().collect_seq()?);
+    let par = sorted(joined.collect_par(None, Some(4))?);
+
+    let expected = vec![
+        ("a".to_string(), vec![1u32, 10, 100]),
+        ("a".to_string(), vec![2u32, 10, 100]),
+    ];
+    assert_eq!(seq, expected);
+    assert_eq!(par, expected);
+    Ok(())
+}
+
+/// With no other inputs, `join_many` simply wraps each value in a singleton `Vec`.
+#[test]
+fn join_many_no_others() -> Result<()> {
+    let p = TestPipeline::new();
+    let a = from_vec(&p, vec![(1u8, 'x'), (2u8, 'y')]);
+    let out = sorted(a.join_many(vec![]).collect_seq()?);
+    assert_eq!(out, vec![(1u8, vec!['x']), (2u8, vec!['y'])]);
+    Ok(())
+}
+
+/// Chained binary joins (with a stateless op in between) execute as one collapsed
+/// multi-way join and produce the same rows as the equivalent nested computation.
+#[test]
+fn chained_joins_are_collapsed_and_correct() -> Result<()> {
+    let p = TestPipeline::new();
+    let a = from_vec(&p, vec![(1u32, "a1".to_string()), (2, "a2".to_string())]);
+    let b = from_vec(&p, vec![(1u32, 10i64), (2, 20), (3, 30)]);
+    let c = from_vec(&p, vec![(1u32, 'x'), (1, 'y'), (2, 'z')]);
+    let d = from_vec(&p, vec![(1u32, true), (2, false)]);
+
+    let joined = a
+        .join_inner(&b)
+        .map_values(|(s, n): &(String, i64)| format!("{s}:{n}"))
+        .join_inner(&c)
+        .join_left(&d);
+
+    let plan = build_plan(&p, joined.node_id())?;
+    assert!(
+        plan.chain.iter().any(
+            |n| matches!(n, ironbeam::node::Node::CoGroupN { chains, .. } if chains.len() == 4)
+        ),
+        "chained joins should collapse into one 4-way CoGroupN"
+    );
+    assert!(plan.optimizations.iter().any(|o| matches!(
+        o,
+        OptimizationDecision::CollapsedJoinChain {
+            joins_collapsed: 2,
+            inputs: 4
+        }
+    )));
+
+    let expected = vec![
+        (1u32, (("a1:10".to_string(), 'x'), Some(true))),
+        (1u32, (("a1:10".to_string(), 'y'), Some(true))),
+        (2u32, (("a2:20".to_string(), 'z'), Some(false))),
+    ];
+    assert_eq!(sorted(joined.clone().collect_seq()?), expected);
+    assert_eq!(sorted(joined.collect_par(None, Some(3))?), expected);
+    Ok(())
+}
+
+/// A join whose input is another join on the right-hand side is collapsed too.
+#[test]
+fn nested_join_on_right_side_is_collapsed() -> Result<()> {
+    let p = TestPipeline::new();
+    let a = from_vec(&p, vec![("k".to_string(), 1u8)]);
+    let b = from_vec(&p, vec![("k".to_string(), 2u8)]);
+    let c = from_vec(&p, vec![("k".to_string(), 3u8)]);
+
+    let joined = a.join_inner(&b.join_inner(&c));
+    let explain = build_plan(&p, joined.node_id())?.explain().to_string();
+    assert!(
+        explain.contains("Multi-Way Join Collapsing"),
+        "explain output should mention the collapsed join chain: {explain}"
+    );
+    assert_eq!(
+        joined.collect_seq()?,
+        vec![("k".to_string(), (1u8, (2u8, 3u8)))]
+    );
+    Ok(())
+}
+
+#[test]
+fn cross_join_pairs_every_element_seq_par() -> Result<()> {
+    let p = TestPipeline::new();
+    let rates = from_vec(&p, vec![1u8, 2]);
+    let depths = from_vec(&p, vec!['a', 'b', 'c']);
+
+    let expected = vec![(1u8, 'a'), (1, 'b'), (1, 'c'), (2, 'a'), (2, 'b'), (2, 'c')];
+    // The right side is larger here, so it is the one split across workers.
+    let grid = rates.cross_join(&depths);
+    assert_eq!(sorted(grid.clone().collect_seq()?), expected);
+    assert_eq!(sorted(grid.collect_par(None, Some(3))?), expected);
+    assert_eq!(sorted(depths.cross_join(&rates).collect_seq()?).len(), 6);
+
+    let explanation = build_plan(&p, rates.cross_join(&depths).node_id())?.explain_estimated();
+    assert_eq!(explanation.cost_estimate.estimated_output_elements, Some(6));
+
+    let none = from_vec(&p, Vec::<u8>::new());
+    assert!(depths.cross_join(&none).collect_seq()?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn cross_join_output_feeds_keyed_joins() -> Result<()> {
+    let p = TestPipeline::new();
+    let users = from_vec(&p, vec!["ann".to_string(), "bob".to_string()]);
+    let days = from_vec(&p, vec![1u32, 2]);
+    let visits = from_vec(&p, vec![("ann".to_string(), 'x')]);
+
+    let joined = users.cross_join(&days).join_inner(&visits);
+    assert_eq!(
+        sorted(joined.collect_seq()?),
+        vec![
+            ("ann".to_string(), (1u32, 'x')),
+            ("ann".to_string(), (2u32, 'x')),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn cross_join_size_guard_rejects_large_estimates() -> Result<()> {
+    let p = TestPipeline::new();
+    let left = from_vec(&p, (0..4_000u32).collect::<Vec<_>>());
+    let right = from_vec(&p, (0..3_000u32).collect::<Vec<_>>());
+
+    let Err(err) = left.cross_join(&right).collect_seq() else {
+        panic!("a 12M-pair cross join should be rejected");
+    };
+    let message = format!("{err:#}");
+    assert!(
+        message.contains("~4000 x ~3000") && message.contains("allow_large()"),
+        "{message}"
+    );
+
+    // The guard also applies when the cross join is an input to another join.
+    let keyed = left.cross_join(&right).map(|(l, r): &(u32, u32)| (*l, *r));
+    let other = from_vec(&p, vec![(1u32, 'x')]);
+    assert!(build_plan(&p, keyed.join_inner(&other).node_id()).is_err());
+
+    // Filters shrink the estimate below the limit; `allow_large` lifts the guard.
+    let small = left.clone().take(100);
+    assert!(build_plan(&p, small.cross_join(&right).node_id()).is_ok());
+    let allowed = left.cross_join(&right).allow_large();
+    assert!(build_plan(&p, allowed.node_id()).is_ok());
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "not the output of `cross_join`")]
+fn allow_large_requires_a_cross_join() {
+    let p = TestPipeline::new();
+    let _ = from_vec(&p, vec![1u8]).allow_large();
+}
@@ -0,0 +1,100 @@
+use anyhow::Result;
+use ironbeam::combiners::Sum;
+use ironbeam::testing::*;
+use ironbeam::*;
+
+fn sorted<T: Ord>(mut v: Vec<T>) -> Vec<T> {
+    v.sort();
+    v
+}
+
+fn words(p: &Pipeline) -> KeyedPCollection<char, String> {
+    from_vec(
+        p,
+        vec![
+            "apple".to_string(),
+            "avocado".to_string(),
+            "banana".to_string(),
+            "cherry".to_string(),
+            "blueberry".to_string(),
+        ],
+    )
+    .keyed_by(|w: &String| w.chars().next().unwrap())
+}
+
+#[test]
+fn keyed_by_matches_key_by() -> Result<()> {
+    let p = TestPipeline::new();
+    let keyed = words(&p).collect_seq()?;
+    let plain = from_vec(&p, vec!["apple".to_string(), "avocado".to_string()])
+        .key_by(|w: &String| w.chars().next().unwrap())
+        .collect_seq()?;
+    assert_eq!(&keyed[..2], plain.as_slice());
+    Ok(())
+}
+
+#[test]
+fn keyed_ops_stay_keyed() -> Result<()> {
+    let p = TestPipeline::new();
+    let lengths: KeyedPCollection<char, usize> = words(&p)
+        .filter_values(|w: &String| w.len() > 5)
+        .map_values(String::len)
+        .with_name("lengths");
+
+    let totals = lengths.clone().combine_values(Sum::<usize>::new());
+    assert_eq!(
+        sorted(totals.collect_par(Some(2), Some(3))?),
+        vec![('a', 7), ('b', 15), ('c', 6)]
+    );
+
+    let counts = lengths.count_per_key();
+    assert_eq!(
+        sorted(counts.collect_seq()?),
+        vec![('a', 1), ('b', 2), ('c', 1)]
+    );
+    Ok(())
+}
+
+#[test]
+fn keyed_group_and_join() -> Result<()> {
+    let p = TestPipeline::new();
+    let groups = words(&p)
+        .group_by_key()
+        .map_values(|ws: &Vec<String>| ws.len());
+    let colors = from_vec(
+        &p,
+        vec![('a', "red".to_string()), ('b', "yellow".to_string())],
+    )
+    .keyed();
+
+    let inner = groups.join_inner(&colors);
+    assert_eq!(
+        sorted(inner.collect_seq()?),
+        vec![
+            ('a', (2, "red".to_string())),
+            ('b', (2, "yellow".to_string()))
+        ]
+    );
+
+    let left = groups
+        .join_left(&colors)
+        .filter_values(|(_, c)| c.is_none());
+    assert_eq!(left.keys().collect_seq()?, vec!['c']);
+    Ok(())
+}
+
+#[test]
+fn into_pcollection_round_trips() -> Result<()> {
+    let p = TestPipeline::new();
+    let keyed = words(&p);
+    let id = keyed.node_id();
+    let plain: PCollection<(char, String)> = keyed.into();
+    assert_eq!(plain.node_id(), id);
+
+    let swapped = plain.kv_swap().keyed().values();
+    assert_eq!(
+        sorted(swapped.collect_seq()?),
+        vec!['a', 'a', 'b', 'b', 'c']
+    );
+    Ok(())
+}
@@ -2,14 +2,24 @@
 mod basic;
 mod batching;
 mod cloud;
+mod columnar;
 mod distinct;
 mod joins;
+mod keyed_collection;
 mod parquet;
+mod pattern;
 mod regex;
 mod reify;
+mod repartition;
 mod reshuffle;
 mod side_input;
+mod skewed_combine;
+mod sort;
+mod stateful;
 mod statistical;
+mod time_series;
+mod try_process;
 mod value_ops;
 mod windowed_combine;
 mod windowing;
+mod with_metrics;
@@ -0,0 +1,127 @@
+//! Tests for `match_pattern`: ordered event sequences per key.
+
+use anyhow::Result;
+use ironbeam::testing::*;
+use ironbeam::*;
+
+fn clicks(events: &[(&str, u64, &str)]) -> Vec<(String, Timestamped<String>)> {
+    events
+        .iter()
+        .map(|&(user, ts, kind)| (user.to_string(), Timestamped::new(ts, kind.to_string())))
+        .collect()
+}
+
+fn is(kind: &'static str) -> impl Fn(&String) -> bool + Send + Sync + 'static {
+    move |e: &String| e == kind
+}
+
+fn spans(matches: &[(String, PatternMatch<String>)]) -> Vec<(String, u64, u64)> {
+    let mut out: Vec<_> = matches
+        .iter()
+        .map(|(k, m)| (k.clone(), m.start, m.end))
+        .collect();
+    out.sort();
+    out
+}
+
+#[test]
+fn match_pattern_finds_ordered_steps_and_skips_noise() -> Result<()> {
+    let p = TestPipeline::new();
+    let events = clicks(&[
+        ("a", 30, "purchase"),
+        ("a", 0, "view"),
+        ("a", 10, "search"),
+        ("a", 20, "cart"),
+        ("b", 0, "cart"),
+        ("b", 5, "view"),
+    ]);
+    let funnel = Pattern::begin("view", is("view"))
+        .followed_by("cart", is("cart"))
+        .followed_by("purchase", is("purchase"));
+    assert_eq!(funnel.step_names(), ["view", "cart", "purchase"]);
+
+    let matches = from_vec(&p, events)
+        .match_pattern(&funnel)
+        .collect_par(Some(2), Some(2))?;
+    assert_eq!(matches.len(), 1);
+    let (user, m) = &matches[0];
+    assert_eq!(user, "a");
+    let kinds: Vec<&str> = m.events.iter().map(|e| e.value.as_str()).collect();
+    assert_eq!(kinds, ["view", "cart", "purchase"]);
+    assert_eq!((m.start, m.end), (0, 30));
+    Ok(())
+}
+
+#[test]
+fn match_pattern_trailing_absence_and_time_limit() -> Result<()> {
+    let p = TestPipeline::new();
+    let events = clicks(&[
+        // Bought within the limit: not abandoned.
+        ("a", 0, "view"),
+        ("a", 10, "cart"),
+        ("a", 90, "purchase"),
+        // Bought after the limit ran out: abandoned.
+        ("b", 0, "view"),
+        ("b", 10, "cart"),
+        ("b", 101, "purchase"),
+        // Cart too late after the view: no match at all.
+        ("c", 0, "view"),
+        ("c", 150, "cart"),
+        // Never bought before the data ended: abandoned.
+        ("d", 0, "view"),
+        ("d", 100, "cart"),
+    ]);
+    let abandoned = Pattern::begin("view", is("view"))
+        .followed_by("cart", is("cart"))
+        .not_followed_by("purchase", is("purchase"))
+        .within(100);
+
+    let matches = from_vec(&p, events)
+        .match_pattern(&abandoned)
+        .collect_seq()?;
+    assert_eq!(
+        spans(&matches),
+        vec![("b".into(), 0, 10), ("d".into(), 0, 100)]
+    );
+    Ok(())
+}
+
+#[test]
+fn match_pattern_absence_between_steps_cancels_attempt() -> Result<()> {
+    let p = TestPipeline::new();
+    let events = clicks(&[
+        ("a", 0, "login"),
+        ("a", 5, "logout"),
+        ("a", 9, "transfer"),
+        ("b", 0, "login"),
+        ("b", 9, "transfer"),
+    ]);
+    let pattern = Pattern::begin("login", is("login"))
+        .not_followed_by("logout", is("logout"))
+        .followed_by("transfer", is("transfer"));
+
+    let matches = from_vec(&p, events).match_pattern(&pattern).collect_seq()?;
+    assert_eq!(spans(&matches), vec![("b".into(), 0, 9)]);
+    Ok(())
+}
+
+#[test]
+fn match_pattern_consumes_each_event_once() -> Result<()> {
+    let p = TestPipeline::new();
+    let events = clicks(&[
+        ("a", 0, "view"),
+        ("a", 1, "view"),
+        ("a", 2, "cart"),
+        ("a", 3, "cart"),
+        ("a", 4, "cart"),
+    ]);
+    let pattern = Pattern::begin("view", is("view")).followed_by("cart", is("cart"));
+
+    let matches = from_vec(&p, events).match_pattern(&pattern).collect_seq()?;
+    // The older view takes the first cart; the third cart has no view left.
+    assert_eq!(
+        spans(&matches),
+        vec![("a".into(), 0, 2), ("a".into(), 1, 3)]
+    );
+    Ok(())
+}
@@ -612,3 +612,87 @@ fn regex_split_then_flat_map() -> Result<()> {
     );
     Ok(())
 }
+
+// ─────────────────────────────────── extract_regex ───────────────────────────────────
+
+#[test]
+fn extract_regex_into_tuples_and_rows() -> Result<()> {
+    let p = Pipeline::default();
+    let lines = from_vec(
+        &p,
+        vec![
+            "GET /index.html 200".to_string(),
+            "POST /login".to_string(),
+            "garbage".to_string(),
+        ],
+    );
+
+    let requests = lines
+        .clone()
+        .extract_regex::<(String, String)>(r"^(\w+) (\S+)")
+        .collect_seq()?;
+    assert_eq!(
+        requests,
+        vec![
+            ("GET".to_string(), "/index.html".to_string()),
+            ("POST".into(), "/login".into()),
+        ]
+    );
+
+    let rows = lines
+        .extract_regex::<Row>(r"^(?P<method>\w+) (\S+)(?: (?P<status>\d{3}))?$")
+        .collect_par(Some(2), Some(2))?;
+    assert_eq!(rows.len(), 2);
+    let login = rows
+        .iter()
+        .find(|r| r.get("2") == Some(&serde_json::json!("/login")))
+        .expect("the login row");
+    assert_eq!(login.get("method"), Some(&serde_json::json!("POST")));
+    assert_eq!(login.get("status"), Some(&serde_json::Value::Null));
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "extract_regex: pattern has 1 capture groups, but 2 are needed")]
+fn extract_regex_rejects_missing_groups() {
+    let p = Pipeline::default();
+    let _ = from_vec(&p, vec![String::new()]).extract_regex::<(String, String)>(r"(\w+)");
+}
+
+// ─────────────────────────────────── parse_key_value ─────────────────────────────────
+
+#[test]
+fn parse_key_value_logfmt_and_custom_delimiters() -> Result<()> {
+    let p = Pipeline::default();
+    let rows = from_vec(
+        &p,
+        vec![
+            r#"level=info  msg="said \"hi\" twice" debug dur=5ms"#.to_string(),
+            "   ".to_string(),
+        ],
+    )
+    .parse_key_value(KeyValueFormat::logfmt())
+    .collect_seq()?;
+    assert_eq!(rows.len(), 1);
+    let columns: Vec<_> = rows[0].columns().map(|(k, v)| (k, v.clone())).collect();
+    assert_eq!(
+        columns,
+        vec![
+            ("level", serde_json::json!("info")),
+            ("msg", serde_json::json!("said \"hi\" twice")),
+            ("debug", serde_json::json!(true)),
+            ("dur", serde_json::json!("5ms")),
+        ]
+    );
+
+    let format = KeyValueFormat::new(',', ':').with_quote(None);
+    assert_eq!(
+        format.parse("a: 1, b:\"x\",,c"),
+        vec![
+            ("a".to_string(), Some("1".to_string())),
+            ("b".into(), Some("\"x\"".into())),
+            ("c".into(), None),
+        ]
+    );
+    Ok(())
+}
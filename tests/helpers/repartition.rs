@@ -0,0 +1,201 @@
+//! Tests for [`PCollection::repartition`], [`PCollection::repartition_by_key`],
+//! [`PCollection::repartition_by_range`] (including range-partitioned sorting), and
+//! [`PCollection::with_partitions`] hints.
+
+use anyhow::Result;
+use ironbeam::*;
+
+/// Collect one `Vec<T>` per partition (a single batch spans a whole partition).
+fn partitions_of(pc: PCollection<u32>, parts: usize) -> Result<Vec<Vec<u32>>> {
+    pc.batch_elements(usize::MAX).collect_par(None, Some(parts))
+}
+
+#[test]
+fn range_partitions_are_contiguous_and_balanced() -> Result<()> {
+    let p = Pipeline::default();
+    let data: Vec<u32> = (0..4_000u32).map(|i| (i * 7919) % 4_000).collect();
+    let ranges = partitions_of(from_vec(&p, data).repartition_by_range(4), 4)?;
+
+    assert_eq!(ranges.len(), 4);
+    for pair in ranges.windows(2) {
+        let max_left = pair[0].iter().max().unwrap();
+        let min_right = pair[1].iter().min().unwrap();
+        assert!(max_left < min_right);
+    }
+    for r in &ranges {
+        assert!(
+            (600..=1_400).contains(&r.len()),
+            "unbalanced range: {}",
+            r.len()
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn range_partition_balances_skewed_values() -> Result<()> {
+    let p = Pipeline::default();
+    // Power-law-ish: most values are tiny, a long tail is large.
+    let data: Vec<u32> = (1..=5_000u32).map(|i| 1_000_000 / i).collect();
+    let ranges = partitions_of(from_vec(&p, data).repartition_by_range(5), 5)?;
+    let largest = ranges.iter().map(Vec::len).max().unwrap();
+    assert!(largest < 2_500, "largest range holds {largest} of 5000");
+    Ok(())
+}
+
+#[test]
+fn repeated_key_stays_in_one_range() -> Result<()> {
+    let p = Pipeline::default();
+    let mut data = vec![7u32; 1_000];
+    data.extend(0..100);
+    let ranges = partitions_of(from_vec(&p, data).repartition_by_range(4), 4)?;
+    let holding_seven = ranges.iter().filter(|r| r.contains(&7)).count();
+    assert_eq!(holding_seven, 1);
+    assert_eq!(ranges.iter().map(Vec::len).sum::<usize>(), 1_100);
+    Ok(())
+}
+
+#[test]
+fn repartition_by_range_sequential_is_single_partition() -> Result<()> {
+    let p = Pipeline::default();
+    let out = from_vec(&p, vec![3u32, 1, 2])
+        .repartition_by_range(8)
+        .batch_elements(usize::MAX)
+        .collect_seq()?;
+    assert_eq!(out, vec![vec![3, 1, 2]]);
+    Ok(())
+}
+
+#[test]
+fn parallel_sort_uses_ordered_ranges() -> Result<()> {
+    let p = Pipeline::default();
+    let data: Vec<u32> = (0..2_000u32).map(|i| (i * 104_729) % 2_000).collect();
+    let ranges = partitions_of(from_vec(&p, data).sort_by_key(|x| *x), 4)?;
+    assert!(ranges.len() > 1);
+    let flat: Vec<u32> = ranges.into_iter().flatten().collect();
+    assert_eq!(flat, (0..2_000u32).collect::<Vec<_>>());
+    Ok(())
+}
+
+#[test]
+fn repartition_sets_exact_partition_count_and_keeps_order() -> Result<()> {
+    let p = Pipeline::default();
+    let shards = partitions_of(
+        from_vec(&p, (0..1_000u32).collect::<Vec<_>>())
+            .filter(|x| x % 10 == 0)
+            .repartition(3),
+        8,
+    )?;
+    assert_eq!(shards.len(), 3);
+    let flat: Vec<u32> = shards.into_iter().flatten().collect();
+    assert_eq!(flat, (0..100u32).map(|x| x * 10).collect::<Vec<_>>());
+    Ok(())
+}
+
+#[test]
+fn repartition_fans_out_after_collapse() -> Result<()> {
+    let p = Pipeline::default();
+    let total = from_vec(&p, (0..100u32).collect::<Vec<_>>())
+        .combine_globally(Sum::<u32>::default(), None)
+        .flat_map(|s| (0..*s % 97 + 40).collect::<Vec<u32>>())
+        .repartition(4);
+    let shards = partitions_of(total, 4)?;
+    assert_eq!(shards.len(), 4);
+    Ok(())
+}
+
+#[test]
+fn repartition_by_key_colocates_keys() -> Result<()> {
+    let p = Pipeline::default();
+    let data: Vec<(u32, u32)> = (0..500u32).map(|i| (i % 17, i)).collect();
+    let shards = from_vec(&p, data)
+        .repartition_by_key(4, |k| u64::from(*k))
+        .batch_elements(usize::MAX)
+        .collect_par(None, Some(4))?;
+    assert!(shards.len() <= 4);
+    for shard in &shards {
+        let bucket = shard[0].0 % 4;
+        assert!(shard.iter().all(|(k, _)| k % 4 == bucket));
+    }
+    assert_eq!(shards.iter().map(Vec::len).sum::<usize>(), 500);
+    Ok(())
+}
+
+#[test]
+fn repartition_sequential_is_single_partition() -> Result<()> {
+    let p = Pipeline::default();
+    let out = from_vec(&p, vec![(1u32, 'a'), (2, 'b')])
+        .repartition_by_key(4, |k| u64::from(*k))
+        .repartition(3)
+        .batch_elements(usize::MAX)
+        .collect_seq()?;
+    assert_eq!(out, vec![vec![(1, 'a'), (2, 'b')]]);
+    Ok(())
+}
+
+#[test]
+fn with_partitions_sets_source_split() -> Result<()> {
+    let p = Pipeline::default();
+    let hinted = from_vec(&p, (0..100u32).collect::<Vec<_>>())
+        .map(|x| x + 1)
+        .with_partitions(5);
+    let parts = partitions_of(hinted, 2)?;
+    assert_eq!(parts.len(), 5);
+    assert_eq!(parts.concat(), (1..=100).collect::<Vec<_>>());
+    Ok(())
+}
+
+#[test]
+fn with_partitions_overrides_repartition_target() -> Result<()> {
+    let p = Pipeline::default();
+    let hinted = from_vec(&p, (0..90u32).collect::<Vec<_>>())
+        .repartition(2)
+        .with_partitions(3);
+    let parts = partitions_of(hinted, 4)?;
+    assert_eq!(
+        parts.iter().map(Vec::len).collect::<Vec<_>>(),
+        vec![30, 30, 30]
+    );
+    Ok(())
+}
+
+#[test]
+fn with_partitions_after_grouping_is_reported_and_ignored() -> Result<()> {
+    use ironbeam::validation::DiagnosticKind;
+
+    let p = Pipeline::default();
+    let sums = from_vec(&p, vec![("a".to_string(), 1u32), ("b".to_string(), 2)])
+        .combine_values(Sum::<u32>::default())
+        .map(|(k, v): &(String, u32)| format!("{k}={v}"))
+        .with_partitions(8);
+
+    let plan = build_plan(&p, sums.node_id())?;
+    assert!(plan.stage_partitions.iter().all(Option::is_none));
+    let ignored: Vec<_> = plan
+        .diagnostics
+        .iter()
+        .filter(|d| d.kind == DiagnosticKind::IgnoredPartitionHint)
+        .collect();
+    assert_eq!(ignored.len(), 1, "{:?}", plan.diagnostics);
+    assert_eq!(ignored[0].node, Some(sums.node_id()));
+
+    let mut out = sums.collect_par(None, Some(4))?;
+    out.sort();
+    assert_eq!(out, vec!["a=1".to_string(), "b=2".to_string()]);
+    Ok(())
+}
+
+#[test]
+fn with_partitions_shows_in_explain() -> Result<()> {
+    let p = Pipeline::default();
+    let hinted = from_vec(&p, (0..10u32).collect::<Vec<_>>()).with_partitions(4);
+    let plan = build_plan(&p, hinted.node_id())?;
+    assert_eq!(plan.stage_partitions[0], Some(4));
+    let explanation = plan.explain();
+    assert!(
+        explanation.steps[0].description.contains("[partitions=4]"),
+        "{}",
+        explanation.steps[0].description
+    );
+    Ok(())
+}
@@ -1,9 +1,15 @@
-use anyhow::Result;
+use anyhow::{Result, bail};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
 
 use ironbeam::testing::*;
-use ironbeam::{from_vec, side_hashmap, side_multimap, side_singleton, side_vec};
+use ironbeam::{
+    IronbeamError, RefreshableSideInput, from_vec, side_hashmap, side_multimap, side_singleton,
+    side_vec,
+};
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 struct Product {
@@ -367,3 +373,85 @@ fn side_multimap_single_key_many_values() -> Result<()> {
     assert_eq!(out, vec![("k".to_string(), 15u32)]);
     Ok(())
 }
+
+#[cfg(feature = "io-jsonl")]
+#[test]
+fn side_from_jsonl_reads_file_at_execution_time() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let path = tmp.path().join("names.jsonl");
+    let names = ironbeam::side_from_jsonl::<u32, String>(&path);
+
+    let p = TestPipeline::new();
+    let labelled = from_vec(&p, vec![1u32, 2, 3])
+        .map_with_refreshable(&names, |id, m| m.get(id).cloned().unwrap_or_default());
+
+    // The file only has to exist once the pipeline runs.
+    std::fs::write(&path, "[1, \"one\"]\n[2, \"two\"]\n")?;
+    let out = labelled.collect_seq()?;
+    assert_eq!(
+        out,
+        vec!["one".to_string(), "two".to_string(), String::new()]
+    );
+    Ok(())
+}
+
+#[cfg(feature = "io-csv")]
+#[test]
+fn side_from_csv_builds_lookup_table() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let path = tmp.path().join("prices.csv");
+    std::fs::write(&path, "sku,price\nA,100\nB,250\n")?;
+    let prices = ironbeam::side_from_csv::<String, u32>(&path, true);
+
+    let p = TestPipeline::new();
+    let priced = from_vec(&p, vec!["A".to_string(), "B".to_string(), "C".to_string()])
+        .filter_with_refreshable(&prices, |sku, m| m.contains_key(sku));
+    let mut out = priced.collect_par(Some(2), Some(3))?;
+    out.sort();
+    assert_eq!(out, vec!["A".to_string(), "B".to_string()]);
+    Ok(())
+}
+
+#[test]
+fn refreshable_side_input_reloads_after_interval() -> Result<()> {
+    let loads = Arc::new(AtomicU32::new(0));
+    let counter = Arc::clone(&loads);
+    let clock = Arc::new(FakeClock::new(0));
+    let version = RefreshableSideInput::new(move || Ok(counter.fetch_add(1, Ordering::SeqCst) + 1))
+        .refresh_every(Duration::from_secs(60))
+        .with_clock(clock.clone());
+
+    let p = TestPipeline::new();
+    let tagged = from_vec(&p, vec![0u32; 8]).map_with_refreshable(&version, |_, v| *v);
+
+    assert_eq!(tagged.clone().collect_par(Some(2), Some(4))?, vec![1; 8]);
+    clock.advance(59_000);
+    assert_eq!(tagged.clone().collect_seq()?, vec![1; 8]);
+    assert_eq!(loads.load(Ordering::SeqCst), 1);
+
+    clock.advance(1_000);
+    assert_eq!(tagged.collect_seq()?, vec![2; 8]);
+    assert_eq!(loads.load(Ordering::SeqCst), 2);
+
+    version.refresh()?;
+    assert_eq!(*version.get()?, 3);
+    Ok(())
+}
+
+#[test]
+fn refreshable_side_input_load_error_fails_consuming_step() {
+    let missing = RefreshableSideInput::<u32>::new(|| bail!("reference table unavailable"));
+
+    let p = TestPipeline::new();
+    let out = from_vec(&p, vec![1u32, 2])
+        .map_with_refreshable(&missing, |x, m| x + m)
+        .with_name("enrich");
+    let err = out.collect_seq().unwrap_err();
+    match err.downcast_ref() {
+        Some(IronbeamError::PanicInTransform { node, payload, .. }) => {
+            assert_eq!(node.name.as_deref(), Some("enrich"));
+            assert!(payload.contains("reference table unavailable"), "{payload}");
+        }
+        other => panic!("unexpected error: {other:?} ({err})"),
+    }
+}
@@ -0,0 +1,75 @@
+//! Tests for [`PCollection::combine_values_skewed`] and [`PCollection::combine_values_with_hint`].
+
+use anyhow::Result;
+use ironbeam::*;
+
+/// Power-law keyed input: key 0 carries ~90% of the rows.
+fn hot_key_input() -> Vec<(u32, u64)> {
+    (0..20_000u64)
+        .map(|i| {
+            let k = if i % 10 == 0 { (i % 97) as u32 + 1 } else { 0 };
+            (k, i)
+        })
+        .collect()
+}
+
+#[test]
+fn skewed_sum_matches_plain_combine() -> Result<()> {
+    let p = Pipeline::default();
+    let input = from_vec(&p, hot_key_input());
+    let plain = input
+        .clone()
+        .combine_values(Sum::<u64>::default())
+        .collect_par_sorted_by_key(None, Some(8))?;
+    let skewed = input
+        .combine_values_skewed(Sum::<u64>::default(), 16)
+        .collect_par_sorted_by_key(None, Some(8))?;
+    assert_eq!(plain, skewed);
+    Ok(())
+}
+
+#[test]
+fn skewed_combine_with_non_trivial_finish() -> Result<()> {
+    let p = Pipeline::default();
+    let means = from_vec(&p, hot_key_input())
+        .map(|(k, v)| (*k, *v as f64))
+        .combine_values_skewed(AverageF64, 4)
+        .collect_seq()?;
+    let plain = from_vec(&p, hot_key_input())
+        .map(|(k, v)| (*k, *v as f64))
+        .combine_values(AverageF64)
+        .collect_seq()?;
+    let plain: std::collections::HashMap<u32, f64> = plain.into_iter().collect();
+    assert_eq!(means.len(), plain.len());
+    for (k, m) in means {
+        assert!((m - plain[&k]).abs() < 1e-9);
+    }
+    Ok(())
+}
+
+#[test]
+fn salt_factor_one_is_plain_combine() -> Result<()> {
+    let p = Pipeline::default();
+    let out = from_vec(&p, vec![("a".to_string(), 1u64), ("a".into(), 2)])
+        .combine_values_skewed(Count, 1)
+        .collect_seq()?;
+    assert_eq!(out, vec![("a".to_string(), 2)]);
+    Ok(())
+}
+
+#[test]
+fn hint_selects_strategy() -> Result<()> {
+    let p = Pipeline::default();
+    let input = from_vec(&p, hot_key_input());
+    let uniform = input
+        .clone()
+        .combine_values_with_hint(Count, SkewHint::Uniform)
+        .collect_par_sorted_by_key(None, None)?;
+    let hot = input
+        .combine_values_with_hint(Count, SkewHint::HotKeys { salt_factor: 8 })
+        .collect_par_sorted_by_key(None, None)?;
+    assert_eq!(uniform, hot);
+    assert_eq!(hot[0], (0, 18_000));
+    assert_eq!(SkewHint::default(), SkewHint::Uniform);
+    Ok(())
+}
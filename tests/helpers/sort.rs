@@ -0,0 +1,81 @@
+//! Tests for [`PCollection::sort_by`] and [`PCollection::sort_by_key`].
+
+use anyhow::Result;
+use ironbeam::*;
+
+fn scrambled(n: u32) -> Vec<u32> {
+    (0..n).map(|i| (i * 7919) % n).collect()
+}
+
+#[test]
+fn sort_by_empty_collection() -> Result<()> {
+    let p = Pipeline::default();
+    let out = from_vec::<u32>(&p, vec![])
+        .sort_by(Ord::cmp)
+        .collect_par(None, Some(4))?;
+    assert!(out.is_empty());
+    Ok(())
+}
+
+#[test]
+fn sort_by_seq_and_par_match_std_sort() -> Result<()> {
+    let p = Pipeline::default();
+    let sorted = from_vec(&p, scrambled(1_000)).sort_by(|a, b| b.cmp(a));
+    let mut expected = scrambled(1_000);
+    expected.sort_unstable_by(|a, b| b.cmp(a));
+    assert_eq!(sorted.clone().collect_seq()?, expected);
+    assert_eq!(sorted.collect_par(None, Some(8))?, expected);
+    Ok(())
+}
+
+#[test]
+fn sort_by_key_is_stable_across_partitions() -> Result<()> {
+    let p = Pipeline::default();
+    // Key is i % 3; ties must keep their input order even when split across partitions.
+    let data: Vec<(u32, u32)> = (0..90).map(|i| (i % 3, i)).collect();
+    let out = from_vec(&p, data.clone())
+        .sort_by_key(|(k, _)| *k)
+        .collect_par(None, Some(6))?;
+    let mut expected = data;
+    expected.sort_by_key(|(k, _)| *k);
+    assert_eq!(out, expected);
+    Ok(())
+}
+
+#[test]
+fn sort_order_survives_downstream_stateless_ops() -> Result<()> {
+    let p = Pipeline::default();
+    let out = from_vec(&p, scrambled(500))
+        .sort_by_key(|x| *x)
+        .map(|x| x * 2)
+        .filter(|x| x % 3 == 0)
+        .collect_par(None, Some(4))?;
+    assert!(out.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(out.len(), 167);
+    Ok(())
+}
+
+#[test]
+fn sort_then_take_returns_leading_elements() -> Result<()> {
+    let p = Pipeline::default();
+    let out = from_vec(&p, scrambled(200))
+        .sort_by_key(|x| std::cmp::Reverse(*x))
+        .take(3)
+        .collect_par(None, Some(4))?;
+    assert_eq!(out, vec![199, 198, 197]);
+    Ok(())
+}
+
+#[test]
+fn sort_shows_up_as_barrier_in_explain() -> Result<()> {
+    let p = Pipeline::default();
+    let sorted = from_vec(&p, vec![3u8, 1, 2]).sort_by(Ord::cmp);
+    let explanation = build_plan(&p, sorted.node_id())?.explain();
+    assert!(
+        explanation
+            .steps
+            .iter()
+            .any(|s| s.node_type == "Sort" && s.is_barrier)
+    );
+    Ok(())
+}
@@ -0,0 +1,182 @@
+//! Tests for keyed state: `map_values_stateful`, its sorted variant, and timers.
+
+use anyhow::Result;
+use ironbeam::testing::*;
+use ironbeam::*;
+use std::collections::HashSet;
+
+#[test]
+fn stateful_map_shares_one_state_per_key() -> Result<()> {
+    let p = TestPipeline::new();
+    let data: Vec<(u32, u64)> = (0..1_000).map(|i| (i % 7, u64::from(i))).collect();
+
+    // Running sum per key; the last value per key must equal the key's total.
+    let sums = from_vec(&p, data.clone())
+        .map_values_stateful(|v, total: &mut u64| {
+            *total += v;
+            *total
+        })
+        .collect_par(Some(4), Some(50))?;
+    assert_eq!(sums.len(), 1_000);
+    for key in 0..7 {
+        let expected: u64 = data.iter().filter(|(k, _)| *k == key).map(|(_, v)| v).sum();
+        let max = sums
+            .iter()
+            .filter(|(k, _)| *k == key)
+            .map(|(_, s)| *s)
+            .max();
+        assert_eq!(max, Some(expected));
+    }
+    Ok(())
+}
+
+#[test]
+fn stateful_map_sorted_is_deterministic_across_modes() -> Result<()> {
+    let p = TestPipeline::new();
+    // (sensor, (ts, reading)), shuffled in time.
+    let data: Vec<(char, (u64, i32))> = (0..200u64)
+        .map(|i| {
+            let ts = (i * 37) % 200;
+            let sensor = if ts % 2 == 0 { 'x' } else { 'y' };
+            (sensor, (ts, i32::try_from(ts % 5).unwrap()))
+        })
+        .collect();
+
+    // Emit (ts, reading changed since previous?) per sensor.
+    let changes = |pc: PCollection<(char, (u64, i32))>| {
+        pc.map_values_stateful_sorted_by_key(
+            |(ts, _)| *ts,
+            |&(ts, reading), last: &mut Option<i32>| {
+                let changed = last.is_some_and(|l| l != reading);
+                *last = Some(reading);
+                (ts, changed)
+            },
+        )
+    };
+    let seq = changes(from_vec(&p, data.clone())).collect_seq_sorted()?;
+    let par = changes(from_vec(&p, data)).collect_par_sorted(Some(5), Some(7))?;
+    assert_eq!(seq, par);
+    assert_eq!(seq.len(), 200);
+    assert!(seq.contains(&('x', (0, false))));
+    assert!(seq.contains(&('x', (2, true))));
+    Ok(())
+}
+
+#[test]
+fn stateful_map_supports_dedup_sets() -> Result<()> {
+    let p = TestPipeline::new();
+    let data = vec![
+        ("a".to_string(), (1u32, 5u32)),
+        ("a".to_string(), (2, 5)),
+        ("a".to_string(), (3, 6)),
+        ("b".to_string(), (1, 5)),
+    ];
+    let firsts = from_vec(&p, data)
+        .map_values_stateful_sorted_by_key(
+            |(seq, _)| *seq,
+            |&(_, v), seen: &mut HashSet<u32>| seen.insert(v).then_some(v),
+        )
+        .filter_map(|(k, v): &(String, Option<u32>)| v.map(|v| (k.clone(), v)))
+        .collect_seq_sorted()?;
+    assert_eq!(
+        firsts,
+        vec![
+            ("a".to_string(), 5),
+            ("a".to_string(), 6),
+            ("b".to_string(), 5)
+        ]
+    );
+    Ok(())
+}
+
+/// Inactivity timeout: emit the last activity time once a key is idle for `gap` ms.
+fn idle_after(
+    pc: PCollection<(u32, Timestamped<u32>)>,
+    gap: u64,
+) -> PCollection<(u32, Timestamped<u64>)> {
+    pc.process_with_timers(
+        move |e, ctx: &mut TimerContext<u64, u64>| {
+            ctx.state = e.ts;
+            ctx.set_timer(e.ts + gap);
+        },
+        |ctx| {
+            let last = ctx.state;
+            ctx.emit(last);
+        },
+    )
+}
+
+#[test]
+fn timers_fire_on_inactivity_and_at_end_of_input() -> Result<()> {
+    let p = TestPipeline::new();
+    let data = vec![
+        (1u32, Timestamped::new(0, 0u32)),
+        (1, Timestamped::new(50, 0)),
+        (1, Timestamped::new(500, 0)),
+        (1, Timestamped::new(550, 0)),
+        (2, Timestamped::new(10, 0)),
+    ];
+    let mut fired: Vec<(u32, u64, u64)> = idle_after(from_vec(&p, data), 100)
+        .map(|(k, t): &(u32, Timestamped<u64>)| (*k, t.ts, t.value))
+        .collect_seq()?;
+    fired.sort_unstable();
+    // Key 1 goes idle after 50 (fires at 150) and after 550 (fires at end, at 650).
+    assert_eq!(fired, vec![(1, 150, 50), (1, 650, 550), (2, 110, 10)]);
+    Ok(())
+}
+
+#[test]
+fn timers_fire_before_events_at_the_same_time() -> Result<()> {
+    let p = TestPipeline::new();
+    // The second event arrives exactly when the timer is due: the timer wins.
+    let data = vec![
+        (7u32, Timestamped::new(0, 0u32)),
+        (7, Timestamped::new(100, 0)),
+    ];
+    let fired = idle_after(from_vec(&p, data), 100)
+        .map(|(_, t): &(u32, Timestamped<u64>)| t.ts)
+        .collect_seq_sorted()?;
+    assert_eq!(fired, vec![100, 200]);
+    Ok(())
+}
+
+#[test]
+fn timers_can_be_cleared_and_rearmed() -> Result<()> {
+    let p = TestPipeline::new();
+    // Heartbeat every 10ms for three ticks, then stop; a cleared timer never fires.
+    let data = vec![
+        ("hb".to_string(), Timestamped::new(0, true)),
+        ("off".to_string(), Timestamped::new(0, true)),
+        ("off".to_string(), Timestamped::new(5, false)),
+    ];
+    let ticks = from_vec(&p, data)
+        .process_with_timers(
+            |e, ctx: &mut TimerContext<u32, u32>| {
+                if e.value {
+                    ctx.set_timer(e.ts + 10);
+                } else {
+                    ctx.clear_timer();
+                }
+                assert_eq!(ctx.now(), e.ts);
+            },
+            |ctx| {
+                ctx.state += 1;
+                let n = ctx.state;
+                ctx.emit(n);
+                if n < 3 {
+                    ctx.set_timer(ctx.now() + 10);
+                }
+            },
+        )
+        .map(|(k, t): &(String, Timestamped<u32>)| (k.clone(), t.ts, t.value))
+        .collect_par_sorted(Some(2), Some(1))?;
+    assert_eq!(
+        ticks,
+        vec![
+            ("hb".to_string(), 10, 1),
+            ("hb".to_string(), 20, 2),
+            ("hb".to_string(), 30, 3),
+        ]
+    );
+    Ok(())
+}
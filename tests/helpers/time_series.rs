@@ -0,0 +1,165 @@
+//! Tests for per-key time-series helpers: lag, deltas, rates, and running totals.
+
+use anyhow::Result;
+use ironbeam::testing::*;
+use ironbeam::*;
+
+fn series(points: &[(&str, u64, u32)]) -> Vec<(String, Timestamped<u32>)> {
+    points
+        .iter()
+        .map(|&(k, ts, v)| (k.to_string(), Timestamped::new(ts, v)))
+        .collect()
+}
+
+/// Flatten `(K, Timestamped<f64>)` into sortable `(K, ts, value)` triples.
+fn triples(out: Vec<(String, Timestamped<f64>)>) -> Vec<(String, u64, f64)> {
+    let mut v: Vec<_> = out.into_iter().map(|(k, t)| (k, t.ts, t.value)).collect();
+    v.sort_by(|a, b| (&a.0, a.1).cmp(&(&b.0, b.1)));
+    v
+}
+
+#[test]
+fn map_values_with_previous_sees_time_ordered_predecessor() -> Result<()> {
+    let p = TestPipeline::new();
+    let data = series(&[("a", 30, 3), ("a", 10, 1), ("b", 5, 9), ("a", 20, 2)]);
+
+    let mut out = from_vec(&p, data)
+        .map_values_with_previous(|prev, cur| (prev.map(|p| p.ts), cur.value))
+        .collect_par(Some(3), Some(1))?;
+    out.sort_by_key(|(k, t)| (k.clone(), t.ts));
+    let lags: Vec<(String, u64, Option<u64>, u32)> = out
+        .into_iter()
+        .map(|(k, t)| (k, t.ts, t.value.0, t.value.1))
+        .collect();
+    assert_eq!(
+        lags,
+        vec![
+            ("a".into(), 10, None, 1),
+            ("a".into(), 20, Some(10), 2),
+            ("a".into(), 30, Some(20), 3),
+            ("b".into(), 5, None, 9),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn deltas_and_rates_skip_the_first_event() -> Result<()> {
+    let p = TestPipeline::new();
+    let data = series(&[
+        ("a", 0, 10),
+        ("a", 2_000, 14),
+        ("a", 2_500, 4),
+        ("a", 2_500, 4),
+        ("b", 7, 1),
+    ]);
+
+    let deltas = from_vec(&p, data.clone()).deltas().collect_seq()?;
+    assert_eq!(
+        triples(deltas),
+        vec![
+            ("a".into(), 2_000, 4.0),
+            ("a".into(), 2_500, -10.0),
+            ("a".into(), 2_500, 0.0),
+        ]
+    );
+
+    // The repeated timestamp has no elapsed time and is skipped.
+    let rates = from_vec(&p, data).rates_per_second().collect_seq()?;
+    assert_eq!(
+        triples(rates),
+        vec![("a".into(), 2_000, 2.0), ("a".into(), 2_500, -20.0)]
+    );
+    Ok(())
+}
+
+#[test]
+fn cumulative_sum_runs_per_key_in_time_order() -> Result<()> {
+    let p = TestPipeline::new();
+    let data = series(&[("a", 3, 30), ("b", 1, 5), ("a", 1, 10), ("a", 2, 20)]);
+    let sums = from_vec(&p, data).cumulative_sum().collect_seq()?;
+    assert_eq!(
+        triples(sums),
+        vec![
+            ("a".into(), 1, 10.0),
+            ("a".into(), 2, 30.0),
+            ("a".into(), 3, 60.0),
+            ("b".into(), 1, 5.0),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn rolling_statistics_follow_time_order_per_key() -> Result<()> {
+    let p = TestPipeline::new();
+    let data = series(&[
+        ("a", 4, 2),
+        ("a", 1, 4),
+        ("a", 3, 6),
+        ("a", 2, 8),
+        ("b", 1, 10),
+    ]);
+
+    // In time order, a's values are 4, 8, 6, 2.
+    let means = from_vec(&p, data.clone())
+        .rolling(combiners::RollingMean::new(2))
+        .collect_par(Some(2), Some(1))?;
+    assert_eq!(
+        triples(means),
+        vec![
+            ("a".into(), 1, 4.0),
+            ("a".into(), 2, 6.0),
+            ("a".into(), 3, 7.0),
+            ("a".into(), 4, 4.0),
+            ("b".into(), 1, 10.0),
+        ]
+    );
+
+    let mut maxes: Vec<(String, u64, u32)> = from_vec(&p, data.clone())
+        .rolling(combiners::RollingMax::new(3))
+        .collect_seq()?
+        .into_iter()
+        .map(|(k, t)| (k, t.ts, t.value))
+        .collect();
+    maxes.sort();
+    assert_eq!(
+        maxes,
+        vec![
+            ("a".into(), 1, 4),
+            ("a".into(), 2, 8),
+            ("a".into(), 3, 8),
+            ("a".into(), 4, 8),
+            ("b".into(), 1, 10),
+        ]
+    );
+
+    // alpha = 0.5: 4, then 6, then 6, then 4.
+    let ewma = from_vec(&p, data).ewma(0.5).collect_seq()?;
+    let a: Vec<f64> = triples(ewma)
+        .into_iter()
+        .filter(|(k, _, _)| k == "a")
+        .map(|(_, _, v)| v)
+        .collect();
+    assert_eq!(a, vec![4.0, 6.0, 6.0, 4.0]);
+    Ok(())
+}
+
+#[test]
+fn rolling_max_window_expires_old_peaks() {
+    use ironbeam::combiners::{RollingFn, RollingMax};
+
+    let max = RollingMax::new(2);
+    let mut state = max.create();
+    let out: Vec<f64> = [5.0, f64::NAN, 1.0, 3.0, 2.0]
+        .into_iter()
+        .map(|v| max.push(&mut state, v))
+        .collect();
+    assert_eq!(out, vec![5.0, 5.0, 1.0, 3.0, 3.0]);
+}
+
+#[test]
+#[should_panic(expected = "Ewma::new: alpha must be in (0, 1]")]
+fn ewma_rejects_out_of_range_alpha() {
+    let _ = combiners::Ewma::new(0.0);
+}
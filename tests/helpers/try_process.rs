@@ -0,0 +1,82 @@
+//! Tests for the fallible transforms in `helpers::try_process`.
+
+use anyhow::Result;
+use ironbeam::*;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+
+// --- RetryPolicy ------------------------------------------------------------
+
+#[test]
+fn retry_policy_backoff_doubles_and_caps() {
+    let policy = RetryPolicy::new(10, Duration::from_millis(100));
+    assert_eq!(policy.delay_after(1), Duration::from_millis(100));
+    assert_eq!(policy.delay_after(2), Duration::from_millis(200));
+    assert_eq!(policy.delay_after(4), Duration::from_millis(800));
+    assert_eq!(policy.delay_after(40), RetryPolicy::MAX_BACKOFF);
+}
+
+// --- try_map_with_retry -----------------------------------------------------
+
+/// Transient failures are retried until the call succeeds.
+#[test]
+fn try_map_with_retry_recovers_transient_failures() -> Result<()> {
+    let p = Pipeline::default();
+    let calls = Arc::new(AtomicU32::new(0));
+    let counter = Arc::clone(&calls);
+
+    // Every element fails twice before succeeding.
+    let out = from_vec(&p, vec![1u32, 2, 3])
+        .try_map_with_retry::<u32, String, _>(
+            move |x| {
+                let n = counter.fetch_add(1, Ordering::SeqCst);
+                if n % 3 < 2 {
+                    Err(format!("transient {n}"))
+                } else {
+                    Ok(x * 10)
+                }
+            },
+            RetryPolicy::new(3, Duration::ZERO),
+        )
+        .collect_fail_fast()?;
+
+    assert_eq!(out, vec![10, 20, 30]);
+    assert_eq!(calls.load(Ordering::SeqCst), 9);
+    Ok(())
+}
+
+/// Elements that exhaust their attempts surface the last error.
+#[test]
+fn try_map_with_retry_gives_up_after_max_attempts() -> Result<()> {
+    let p = Pipeline::default();
+    let calls = Arc::new(AtomicU32::new(0));
+    let counter = Arc::clone(&calls);
+
+    let out = from_vec(&p, vec![7u32])
+        .try_map_with_retry::<u32, String, _>(
+            move |_| {
+                Err(format!(
+                    "attempt {}",
+                    counter.fetch_add(1, Ordering::SeqCst) + 1
+                ))
+            },
+            RetryPolicy::new(4, Duration::from_millis(1)),
+        )
+        .collect_seq()?;
+
+    assert_eq!(out, vec![Err("attempt 4".to_string())]);
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+    Ok(())
+}
+
+/// `max_attempts = 0` still calls the function once.
+#[test]
+fn try_map_with_retry_zero_attempts_calls_once() -> Result<()> {
+    let p = Pipeline::default();
+    let out = from_vec(&p, vec![1u8, 2])
+        .try_map_with_retry::<u8, String, _>(|x| Ok(*x), RetryPolicy::new(0, Duration::ZERO))
+        .collect_fail_fast()?;
+    assert_eq!(out, vec![1, 2]);
+    Ok(())
+}
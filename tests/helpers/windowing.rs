@@ -100,3 +100,62 @@ fn attach_timestamps_then_window() -> Result<()> {
     assert_eq!(out, expected);
     Ok(())
 }
+
+#[test]
+fn sessionize_splits_on_inactivity_gaps() -> Result<()> {
+    let p = TestPipeline::new();
+    // Out of order on purpose; user 1 has a gap of exactly 10 between 25 and 35.
+    let events = vec![
+        (1u32, Timestamped::new(35, 'd')),
+        (1, Timestamped::new(0, 'a')),
+        (2, Timestamped::new(5, 'x')),
+        (1, Timestamped::new(25, 'c')),
+        (1, Timestamped::new(9, 'b')),
+        (2, Timestamped::new(100, 'y')),
+    ];
+
+    let mut sessions = from_vec(&p, events)
+        .sessionize(10)
+        .collect_par(Some(3), Some(2))?;
+    sessions.sort_by_key(|(k, s)| (*k, s.start));
+
+    let summary: Vec<(u32, u64, u64, String)> = sessions
+        .iter()
+        .map(|(k, s)| {
+            (
+                *k,
+                s.start,
+                s.end,
+                s.events.iter().map(|e| e.value).collect(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (1, 0, 9, "ab".to_string()),
+            (1, 25, 25, "c".to_string()),
+            (1, 35, 35, "d".to_string()),
+            (2, 5, 5, "x".to_string()),
+            (2, 100, 100, "y".to_string()),
+        ]
+    );
+    assert_eq!(sessions[0].1.duration_ms(), 9);
+    assert_eq!(sessions[0].1.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn sessionize_chains_events_into_one_long_session() -> Result<()> {
+    let p = TestPipeline::new();
+    // One event every 50ms for 10s: never idle for a full second.
+    let events: Vec<(String, Timestamped<u64>)> = (0..200u64)
+        .map(|i| ("k".to_string(), Timestamped::new(i * 50, i)))
+        .collect();
+    let sessions = from_vec(&p, events).sessionize(1_000).collect_seq()?;
+    assert_eq!(sessions.len(), 1);
+    let session = &sessions[0].1;
+    assert_eq!((session.start, session.end), (0, 9_950));
+    assert!(session.events.windows(2).all(|w| w[0].ts < w[1].ts));
+    Ok(())
+}
@@ -0,0 +1,101 @@
+//! Tests for [`PCollection::map_with_metrics`] and [`PCollection::filter_with_metrics`].
+
+#![cfg(feature = "metrics")]
+
+use anyhow::Result;
+use ironbeam::metrics::{CounterMetric, HistogramMetric, MetricsCollector};
+use ironbeam::*;
+use serde_json::json;
+
+fn parsed(p: &Pipeline) -> PCollection<u32> {
+    let rows: Vec<String> = (0..100u32)
+        .map(|i| {
+            if i % 10 == 0 {
+                format!("bad-{i}")
+            } else {
+                i.to_string()
+            }
+        })
+        .collect();
+    from_vec(p, rows)
+        .filter_with_metrics(|row, m| {
+            let ok = row.parse::<u32>().is_ok();
+            if !ok {
+                m.counter("bad_rows").inc();
+            }
+            ok
+        })
+        .map_with_metrics(|row, m| {
+            let n: u32 = row.parse().unwrap();
+            m.distribution("value").record(f64::from(n));
+            m.gauge("last_seen").set(f64::from(n));
+            n
+        })
+}
+
+#[test]
+fn user_metrics_aggregate_across_partitions() -> Result<()> {
+    for parts in [1, 4] {
+        let p = Pipeline::default();
+        p.set_metrics(MetricsCollector::new());
+        let out = parsed(&p).collect_par(Some(2), Some(parts))?;
+        assert_eq!(out.len(), 90);
+
+        let report = p.get_metrics().unwrap().report();
+        assert_eq!(report.values()["bad_rows"], json!(10));
+        assert_eq!(report.values()["value"]["count"], json!(90));
+        assert_eq!(report.values()["value"]["max"], json!(99.0));
+        assert!(report.values().contains_key("last_seen"));
+    }
+    Ok(())
+}
+
+#[test]
+fn user_metrics_reset_between_runs_but_counters_accumulate() -> Result<()> {
+    let p = Pipeline::default();
+    p.set_metrics(MetricsCollector::new());
+    let values = parsed(&p);
+    values.clone().collect_seq()?;
+    values.collect_seq()?;
+
+    let report = p.get_metrics().unwrap().report();
+    assert_eq!(report.values()["bad_rows"], json!(20));
+    assert_eq!(report.values()["value"]["count"], json!(180));
+    Ok(())
+}
+
+#[test]
+fn user_metrics_merge_into_registered_metrics() -> Result<()> {
+    let p = Pipeline::default();
+    let mut collector = MetricsCollector::new();
+    collector.register(Box::new(CounterMetric::with_value("bad_rows", 5)));
+    collector.register(Box::new(HistogramMetric::with_values(
+        "value",
+        vec![1_000.0],
+    )));
+    p.set_metrics(collector);
+    parsed(&p).collect_seq()?;
+
+    let report = p.get_metrics().unwrap().report();
+    assert_eq!(report.values()["bad_rows"], json!(15));
+    assert_eq!(report.values()["value"]["count"], json!(91));
+    assert_eq!(report.values()["value"]["max"], json!(1_000.0));
+    Ok(())
+}
+
+#[test]
+fn user_metrics_without_collector_are_discarded() -> Result<()> {
+    let p = Pipeline::default();
+    assert_eq!(parsed(&p).collect_seq()?.len(), 90);
+
+    p.set_metrics(MetricsCollector::new());
+    from_vec(&p, vec![1u32]).collect_seq()?;
+    assert!(
+        !p.get_metrics()
+            .unwrap()
+            .report()
+            .values()
+            .contains_key("bad_rows")
+    );
+    Ok(())
+}
@@ -0,0 +1,315 @@
+//! Tests for the paginated JSON REST source and the batched POST sink.
+#![cfg(feature = "io-http")]
+
+use anyhow::Result;
+use ironbeam::io::cloud::utils::RetryConfig;
+use ironbeam::io::cloud::{ConfigIO, ConfigResolver, FakeConfigIO};
+use ironbeam::io::http::{HttpSinkConfig, HttpSourceConfig, Pagination};
+use ironbeam::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Item {
+    id: u32,
+}
+
+/// A request as the test server saw it.
+#[derive(Clone, Debug)]
+struct Seen {
+    method: String,
+    /// Path and query string.
+    target: String,
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl Seen {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// `(status, extra headers, body)` of a response.
+type Reply = (u16, Vec<(String, String)>, String);
+
+/// Serve every connection with `handler`, one request per connection, and record the
+/// requests. Returns the base URL and the record.
+fn serve<H>(handler: H) -> (String, Arc<Mutex<Vec<Seen>>>)
+where
+    H: Fn(&Seen, &str) -> Reply + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let record = Arc::clone(&seen);
+    let own_base = base.clone();
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let mut parts = line.split_whitespace();
+            let method = parts.next().unwrap_or_default().to_string();
+            let target = parts.next().unwrap_or_default().to_string();
+            let mut headers = Vec::new();
+            loop {
+                line.clear();
+                reader.read_line(&mut line).unwrap();
+                let header = line.trim_end();
+                if header.is_empty() {
+                    break;
+                }
+                let (name, value) = header.split_once(':').unwrap();
+                headers.push((name.trim().to_string(), value.trim().to_string()));
+            }
+            let length = headers
+                .iter()
+                .find(|(n, _)| n.eq_ignore_ascii_case("content-length"))
+                .map_or(0, |(_, v)| v.parse().unwrap());
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            let request = Seen {
+                method,
+                target,
+                headers,
+                body: String::from_utf8(body).unwrap(),
+            };
+
+            let (status, extra, body) = handler(&request, &own_base);
+            record.lock().unwrap().push(request);
+            let mut response = format!(
+                "HTTP/1.1 {status} X\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n",
+                body.len()
+            );
+            for (name, value) in extra {
+                response.push_str(&format!("{name}: {value}\r\n"));
+            }
+            response.push_str("\r\n");
+            response.push_str(&body);
+            stream.write_all(response.as_bytes()).unwrap();
+        }
+    });
+    (base, seen)
+}
+
+fn ok(body: &Value) -> Reply {
+    (200, Vec::new(), body.to_string())
+}
+
+fn items(ids: impl IntoIterator<Item = u32>) -> Value {
+    Value::Array(ids.into_iter().map(|id| json!({ "id": id })).collect())
+}
+
+fn fast_retry() -> RetryConfig {
+    RetryConfig {
+        max_attempts: 3,
+        initial_delay_ms: 1,
+        max_delay_ms: 1,
+        backoff_multiplier: 2.0,
+    }
+}
+
+fn query_param(target: &str, name: &str) -> Option<u32> {
+    let (_, query) = target.split_once('?')?;
+    query
+        .split('&')
+        .find_map(|pair| pair.strip_prefix(&format!("{name}=")))
+        .map(|v| v.parse().unwrap())
+}
+
+#[test]
+fn page_numbers_stop_at_an_empty_page() -> Result<()> {
+    let (base, seen) = serve(|req, _| {
+        let page = query_param(&req.target, "page").unwrap();
+        let ids = if page <= 2 {
+            vec![page * 10, page * 10 + 1]
+        } else {
+            vec![]
+        };
+        ok(&json!({ "data": items(ids) }))
+    });
+    let p = Pipeline::default();
+    let config = HttpSourceConfig::new(format!("{base}/items"))
+        .with_pagination(Pagination::PageNumber {
+            param: "page".into(),
+            start: 1,
+        })
+        .with_records("/data");
+
+    let got = read_http_json::<Item>(&p, &config)?.collect_seq()?;
+    assert_eq!(
+        got.iter().map(|i| i.id).collect::<Vec<_>>(),
+        vec![10, 11, 20, 21]
+    );
+    assert_eq!(seen.lock().unwrap().len(), 3);
+    Ok(())
+}
+
+#[test]
+fn offsets_stop_at_a_short_page() -> Result<()> {
+    let (base, seen) = serve(|req, _| {
+        let offset = query_param(&req.target, "offset").unwrap();
+        let limit = query_param(&req.target, "limit").unwrap();
+        ok(&items((offset..offset + limit).filter(|id| *id < 5)))
+    });
+    let config =
+        HttpSourceConfig::new(format!("{base}/items")).with_pagination(Pagination::Offset {
+            offset_param: "offset".into(),
+            limit_param: "limit".into(),
+            limit: 2,
+        });
+
+    let got: Vec<Item> = read_http_json_vec(&config)?;
+    assert_eq!(got.len(), 5);
+    assert_eq!(seen.lock().unwrap().len(), 3);
+    Ok(())
+}
+
+#[test]
+fn cursors_and_link_headers_are_followed() -> Result<()> {
+    let (base, _) = serve(|req, _| match query_param(&req.target, "after") {
+        None => ok(&json!({ "items": items([1]), "next": "2" })),
+        Some(2) => ok(&json!({ "items": items([2]), "next": null })),
+        Some(_) => (400, Vec::new(), "{}".into()),
+    });
+    let config = HttpSourceConfig::new(format!("{base}/c"))
+        .with_pagination(Pagination::Cursor {
+            param: "after".into(),
+            next: "/next".into(),
+        })
+        .with_records("/items");
+    let got: Vec<Item> = read_http_json_vec(&config)?;
+    assert_eq!(got, vec![Item { id: 1 }, Item { id: 2 }]);
+
+    let (base, seen) = serve(|req, base| {
+        if req.target == "/l" {
+            let link = format!("<{base}/l2>; rel=\"next\", <{base}/l2>; rel=\"last\"");
+            (200, vec![("Link".into(), link)], items([1]).to_string())
+        } else {
+            ok(&items([2, 3]))
+        }
+    });
+    let config = HttpSourceConfig::new(format!("{base}/l")).with_pagination(Pagination::LinkHeader);
+    let got: Vec<Item> = read_http_json_vec(&config)?;
+    assert_eq!(got.len(), 3);
+    assert_eq!(seen.lock().unwrap()[1].target, "/l2");
+    Ok(())
+}
+
+#[test]
+fn max_pages_caps_the_read() -> Result<()> {
+    let (base, seen) = serve(|req, _| ok(&items([query_param(&req.target, "page").unwrap()])));
+    let config = HttpSourceConfig::new(base)
+        .with_pagination(Pagination::PageNumber {
+            param: "page".into(),
+            start: 0,
+        })
+        .with_max_pages(4);
+    let got: Vec<Item> = read_http_json_vec(&config)?;
+    assert_eq!(got.len(), 4);
+    assert_eq!(seen.lock().unwrap().len(), 4);
+    Ok(())
+}
+
+#[test]
+fn transient_failures_are_retried() -> Result<()> {
+    let calls = Arc::new(Mutex::new(0));
+    let counter = Arc::clone(&calls);
+    let (base, _) = serve(move |_, _| {
+        let mut calls = counter.lock().unwrap();
+        *calls += 1;
+        match *calls {
+            1 => (503, Vec::new(), "{}".into()),
+            2 => (429, Vec::new(), "{}".into()),
+            _ => ok(&items([7])),
+        }
+    });
+    let config = HttpSourceConfig::new(base).with_retry(fast_retry());
+    let got: Vec<Item> = read_http_json_vec(&config)?;
+    assert_eq!(got, vec![Item { id: 7 }]);
+    assert_eq!(*calls.lock().unwrap(), 3);
+    Ok(())
+}
+
+#[test]
+fn client_errors_fail_without_retrying() {
+    let (base, seen) = serve(|_, _| (404, Vec::new(), r#"{"error":"no such thing"}"#.into()));
+    let config = HttpSourceConfig::new(format!("{base}/missing")).with_retry(fast_retry());
+    let err = read_http_json_vec::<Item>(&config).unwrap_err();
+    let msg = format!("{err:#}");
+    assert!(
+        msg.contains("HTTP 404") && msg.contains("no such thing"),
+        "{msg}"
+    );
+    assert_eq!(seen.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn pages_without_records_are_errors() {
+    let (base, _) = serve(|_, _| ok(&json!({ "data": { "id": 1 } })));
+    let config = HttpSourceConfig::new(base).with_records("/data");
+    let err = read_http_json_vec::<Item>(&config).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("expected an array of records at /data, found an object"),
+        "{err:#}"
+    );
+}
+
+#[test]
+fn write_http_json_posts_batches() -> Result<()> {
+    let (base, seen) = serve(|_, _| (201, Vec::new(), "{}".into()));
+    let p = Pipeline::default();
+    let config = HttpSinkConfig::new(format!("{base}/ingest"))
+        .with_header("X-Api-Key", "k")
+        .with_batch_size(2);
+
+    let sent =
+        from_vec(&p, (0..5).map(|id| Item { id }).collect::<Vec<_>>()).write_http_json(&config)?;
+    assert_eq!(sent, 5);
+
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.len(), 3);
+    assert!(
+        seen.iter()
+            .all(|r| r.method == "POST" && r.target == "/ingest")
+    );
+    assert_eq!(seen[0].header("x-api-key"), Some("k"));
+    assert_eq!(seen[0].header("content-type"), Some("application/json"));
+    let batches: Vec<Vec<Item>> = seen
+        .iter()
+        .map(|r| serde_json::from_str(&r.body).unwrap())
+        .collect();
+    assert_eq!(
+        batches.iter().map(Vec::len).collect::<Vec<_>>(),
+        vec![2, 2, 1]
+    );
+    assert_eq!(batches[2], vec![Item { id: 4 }]);
+    Ok(())
+}
+
+#[test]
+fn url_and_headers_resolve_config_references() -> Result<()> {
+    let (base, seen) = serve(|_, _| ok(&items([1])));
+    let config = FakeConfigIO::new();
+    config.set("api/base", &base, false)?;
+    config.set("api/token", "s3cret", true)?;
+    let p = Pipeline::default();
+    p.set_config_resolver(ConfigResolver::new(Arc::new(config)));
+
+    let source = HttpSourceConfig::new("${config://api/base}/items")
+        .with_header("Authorization", "Bearer ${config://api/token}");
+    assert_eq!(read_http_json::<Item>(&p, &source)?.collect_seq()?.len(), 1);
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen[0].target, "/items");
+    assert_eq!(seen[0].header("authorization"), Some("Bearer s3cret"));
+    Ok(())
+}
@@ -0,0 +1,206 @@
+//! Tests for batched model inference with `predict_with`.
+
+use anyhow::Result;
+use ironbeam::helpers::inference::PredictOptions;
+use ironbeam::io::cloud::utils::RetryConfig;
+use ironbeam::io::cloud::{
+    CloudIOError, CloudResult, ErrorKind, FakeIntelligenceIO, InferenceInput, InferenceOutput,
+    IntelligenceIO,
+};
+use ironbeam::*;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+fn encode(x: &u32) -> Result<InferenceInput> {
+    Ok(InferenceInput {
+        data: x.to_string().into_bytes(),
+        content_type: "text/plain".to_string(),
+    })
+}
+
+fn decode(x: u32, out: InferenceOutput) -> Result<(u32, u32)> {
+    Ok((x, String::from_utf8(out.data)?.parse()?))
+}
+
+fn doubler() -> FakeIntelligenceIO {
+    let model = FakeIntelligenceIO::new();
+    model.register_model("double", |input| {
+        let x: u32 = std::str::from_utf8(input).unwrap().parse().unwrap();
+        (x * 2).to_string().into_bytes()
+    });
+    model
+}
+
+/// Wraps the doubling model, fails the first `failures` batch calls with `kind`, and
+/// records the size of every batch it serves.
+struct Flaky {
+    inner: FakeIntelligenceIO,
+    kind: ErrorKind,
+    failures: Mutex<u32>,
+    batches: Mutex<Vec<usize>>,
+}
+
+impl Flaky {
+    fn new(kind: ErrorKind, failures: u32) -> Self {
+        Self {
+            inner: doubler(),
+            kind,
+            failures: Mutex::new(failures),
+            batches: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl IntelligenceIO for Flaky {
+    fn predict(&self, model_name: &str, input: InferenceInput) -> CloudResult<InferenceOutput> {
+        self.inner.predict(model_name, input)
+    }
+    fn predict_batch(
+        &self,
+        model_name: &str,
+        inputs: Vec<InferenceInput>,
+    ) -> CloudResult<Vec<InferenceOutput>> {
+        let mut failures = self.failures.lock().unwrap();
+        if *failures > 0 {
+            *failures -= 1;
+            return Err(CloudIOError::new(self.kind.clone(), "injected failure"));
+        }
+        drop(failures);
+        self.batches.lock().unwrap().push(inputs.len());
+        self.inner.predict_batch(model_name, inputs)
+    }
+    fn list_models(&self) -> CloudResult<Vec<String>> {
+        self.inner.list_models()
+    }
+    fn get_model_info(&self, model_name: &str) -> CloudResult<HashMap<String, String>> {
+        self.inner.get_model_info(model_name)
+    }
+}
+
+const fn fast_retry() -> RetryConfig {
+    RetryConfig {
+        max_attempts: 3,
+        initial_delay_ms: 1,
+        max_delay_ms: 1,
+        backoff_multiplier: 2.0,
+    }
+}
+
+#[test]
+fn predictions_keep_input_order_across_batches() -> Result<()> {
+    let service = Flaky::new(ErrorKind::Network, 0);
+    let p = Pipeline::default();
+    let out = from_vec(&p, (0u32..25).collect::<Vec<_>>())
+        .predict_with(&service, "double", 10, encode, decode)?
+        .collect_seq()?;
+    assert_eq!(out, (0u32..25).map(|x| (x, x * 2)).collect::<Vec<_>>());
+    assert_eq!(*service.batches.lock().unwrap(), vec![10, 10, 5]);
+    Ok(())
+}
+
+#[test]
+fn transient_failures_are_retried() -> Result<()> {
+    let service = Flaky::new(ErrorKind::RateLimited, 2);
+    let p = Pipeline::default();
+    let opts = PredictOptions::default().with_retry(fast_retry());
+    let out = from_vec(&p, vec![1u32, 2, 3])
+        .predict_with_options(&service, "double", 2, encode, decode, &opts)?
+        .collect_seq()?;
+    assert_eq!(out, vec![(1, 2), (2, 4), (3, 6)]);
+    Ok(())
+}
+
+#[test]
+fn permanent_failures_fail_the_transform() {
+    let service = Flaky::new(ErrorKind::InvalidInput, 1);
+    let p = Pipeline::default();
+    let opts = PredictOptions::default().with_retry(fast_retry());
+    let Err(err) =
+        from_vec(&p, vec![1u32]).predict_with_options(&service, "double", 2, encode, decode, &opts)
+    else {
+        panic!("the batch request should fail");
+    };
+    assert!(format!("{err:#}").contains("injected failure"), "{err:#}");
+    assert!(service.batches.lock().unwrap().is_empty());
+}
+
+#[test]
+fn requests_are_rate_limited() -> Result<()> {
+    let service = doubler();
+    let p = Pipeline::default();
+    let opts = PredictOptions::default().with_max_requests_per_second(20.0);
+    let start = Instant::now();
+    let out = from_vec(&p, (0u32..4).collect::<Vec<_>>())
+        .predict_with_options(&service, "double", 1, encode, decode, &opts)?
+        .collect_seq()?;
+    assert_eq!(out.len(), 4);
+    // Four requests 50ms apart take at least 150ms.
+    assert!(start.elapsed() >= Duration::from_millis(150));
+    Ok(())
+}
+
+#[test]
+fn decode_errors_name_the_element() {
+    let service = doubler();
+    let p = Pipeline::default();
+    let Err(err) =
+        from_vec(&p, vec![1u32, 2, 3]).predict_with(&service, "double", 2, encode, |x: u32, _| {
+            anyhow::ensure!(x != 3, "cannot decode {x}");
+            Ok(x)
+        })
+    else {
+        panic!("decoding should fail");
+    };
+    let msg = format!("{err:#}");
+    assert!(
+        msg.contains("decode output #2") && msg.contains("cannot decode 3"),
+        "{msg}"
+    );
+}
+
+#[test]
+fn zero_batch_size_is_rejected() {
+    let p = Pipeline::default();
+    let Err(err) = from_vec(&p, vec![1u32]).predict_with(&doubler(), "double", 0, encode, decode)
+    else {
+        panic!("a zero batch size should be rejected");
+    };
+    assert!(err.to_string().contains("batch_size"), "{err}");
+}
+
+#[test]
+fn shared_predictions_are_lazy_and_batched_per_partition() -> Result<()> {
+    let service = Arc::new(Flaky::new(ErrorKind::Network, 0));
+    let p = Pipeline::default();
+    let scored = from_vec(&p, (0u32..12).collect::<Vec<_>>()).predict_with_shared(
+        service.clone(),
+        "double",
+        4,
+        encode,
+        decode,
+    )?;
+    assert!(service.batches.lock().unwrap().is_empty());
+
+    let mut out = scored.collect_par(None, Some(2))?;
+    out.sort_unstable();
+    assert_eq!(out, (0u32..12).map(|x| (x, x * 2)).collect::<Vec<_>>());
+    // Each six-element partition goes out as a batch of four and a batch of two.
+    let mut batches = service.batches.lock().unwrap().clone();
+    batches.sort_unstable();
+    assert_eq!(batches, vec![2, 2, 4, 4]);
+    Ok(())
+}
+
+#[test]
+fn shared_permanent_failures_fail_the_step() -> Result<()> {
+    let service = Arc::new(Flaky::new(ErrorKind::InvalidInput, 1));
+    let p = Pipeline::default();
+    let opts = PredictOptions::default().with_retry(fast_retry());
+    let err = from_vec(&p, vec![1u32])
+        .predict_with_options_shared(service, "double", 2, encode, decode, &opts)?
+        .collect_seq()
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("injected failure"), "{err:#}");
+    Ok(())
+}
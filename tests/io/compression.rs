@@ -7,8 +7,12 @@
 mod compression_tests {
     use anyhow::Result;
     use ironbeam::io::compression::{
-        CompressionCodec, auto_detect_reader, auto_detect_writer, register_codec,
+        Compression, CompressionCodec, WriteOptions, auto_detect_reader, auto_detect_writer,
+        compressed_writer, register_codec,
     };
+    #[cfg(feature = "io-csv")]
+    use ironbeam::io::csv::{read_csv_vec, write_csv_vec_with};
+    use ironbeam::io::jsonl::{read_jsonl_vec, write_jsonl_vec_with};
     use serde::{Deserialize, Serialize};
     use std::io::{Read, Write};
     use std::sync::Arc;
@@ -336,6 +340,132 @@ mod compression_tests {
         let result = auto_detect_writer(buffer, "test.txt");
         assert!(result.is_ok());
     }
+
+    #[test]
+    #[cfg(feature = "compression-gzip")]
+    fn test_mislabeled_gz_extension_reads_plain() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("already_inflated.jsonl.gz");
+        let data = sample_data();
+        write_jsonl_vec_with(
+            &path,
+            &data,
+            &WriteOptions::default().with_compression(Compression::None),
+        )?;
+
+        let raw = std::fs::read(&path)?;
+        assert_eq!(raw.first(), Some(&b'{'));
+        let loaded: Vec<TestRecord> = read_jsonl_vec(&path)?;
+        assert_eq!(data, loaded);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "compression-gzip")]
+    fn test_concatenated_gzip_members() -> Result<()> {
+        use flate2::write::GzEncoder;
+
+        let data = sample_data();
+        let mut bytes = Vec::new();
+        for record in &data {
+            let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+            serde_json::to_writer(&mut encoder, record)?;
+            encoder.write_all(b"\n")?;
+            bytes.extend(encoder.finish()?);
+        }
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("members.jsonl.gz");
+        std::fs::write(&path, bytes)?;
+
+        let loaded: Vec<TestRecord> = read_jsonl_vec(&path)?;
+        assert_eq!(data, loaded);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "compression-zstd")]
+    fn test_explicit_compression_overrides_extension() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("out.jsonl");
+        let data = sample_data();
+        let opts = WriteOptions::default().with_compression(Compression::Zstd { level: 19 });
+        assert_eq!(write_jsonl_vec_with(&path, &data, &opts)?, 3);
+
+        let raw = std::fs::read(&path)?;
+        assert!(raw.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]));
+        // Sniffed from the content despite the plain extension.
+        let loaded: Vec<TestRecord> = read_jsonl_vec(&path)?;
+        assert_eq!(data, loaded);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "compression-bzip2", feature = "io-csv"))]
+    fn test_write_csv_with_explicit_level() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("out.csv");
+        let data = sample_data();
+        let opts = WriteOptions::default().with_compression(Compression::Bzip2 { level: 1 });
+        write_csv_vec_with(&path, true, &data, &opts)?;
+
+        assert!(std::fs::read(&path)?.starts_with(b"BZh1"));
+        let loaded: Vec<TestRecord> = read_csv_vec(&path, true)?;
+        assert_eq!(data, loaded);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "compression-gzip")]
+    fn test_invalid_compression_level() {
+        let err = compressed_writer(Vec::new(), "out.gz", Compression::Gzip { level: 12 })
+            .err()
+            .expect("level 12 is out of range");
+        assert!(err.to_string().contains("0..=9"));
+    }
+
+    #[test]
+    #[cfg(all(feature = "archive-tar", feature = "compression-gzip"))]
+    fn test_tar_gz_of_shards() -> Result<()> {
+        use flate2::write::GzEncoder;
+
+        let data = sample_data();
+        let encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        let mut dir_header = tar::Header::new_gnu();
+        dir_header.set_entry_type(tar::EntryType::Directory);
+        dir_header.set_size(0);
+        builder.append_data(&mut dir_header, "shards/", std::io::empty())?;
+        for (i, record) in data.iter().enumerate() {
+            let mut line = serde_json::to_vec(record)?;
+            line.push(b'\n');
+            let mut header = tar::Header::new_gnu();
+            header.set_size(line.len() as u64);
+            header.set_mode(0o644);
+            builder.append_data(
+                &mut header,
+                format!("shards/part-{i:05}.jsonl"),
+                line.as_slice(),
+            )?;
+            // macOS resource forks ride along in tarballs and must be ignored.
+            let mut fork = tar::Header::new_gnu();
+            fork.set_size(4);
+            builder.append_data(
+                &mut fork,
+                format!("shards/._part-{i:05}.jsonl"),
+                &b"\0\0\0\0"[..],
+            )?;
+        }
+        let bytes = builder.into_inner()?.finish()?;
+
+        let dir = tempfile::tempdir()?;
+        for name in ["shards.tar.gz", "shards.tgz"] {
+            let path = dir.path().join(name);
+            std::fs::write(&path, &bytes)?;
+            let loaded: Vec<TestRecord> = read_jsonl_vec(&path)?;
+            assert_eq!(data, loaded, "{name}");
+        }
+        Ok(())
+    }
 }
 
 #[cfg(not(any(
@@ -202,3 +202,104 @@ fn write_csv_par_auto_shards() -> Result<()> {
     assert_eq!(back, data);
     Ok(())
 }
+
+#[test]
+fn read_csv_vec_with_dialect_options() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let path = tmp.path().join("dialect.csv");
+    fs::write(
+        &path,
+        "# exported rows\n id ; name \n1;'Smith; J'\n# trailing note\n 2 ; Bob \n",
+    )?;
+
+    let opts = CsvReadOptions::new()
+        .with_delimiter(b';')
+        .with_quote(b'\'')
+        .with_comment(Some(b'#'))
+        .with_trim(true);
+    let (rows, bad): (Vec<Record>, _) = read_csv_vec_with(&path, &opts)?;
+    assert!(bad.is_empty());
+    assert_eq!(
+        rows,
+        vec![
+            Record {
+                id: 1,
+                name: "Smith; J".into()
+            },
+            Record {
+                id: 2,
+                name: "Bob".into()
+            },
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn read_csv_vec_with_latin1_encoding() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let path = tmp.path().join("latin1.csv");
+    // "José" and "Müller" in ISO-8859-1.
+    fs::write(&path, b"id,name\n1,Jos\xe9\n2,M\xfcller\n")?;
+
+    // Not valid UTF-8, so the default options fail.
+    assert!(read_csv_vec::<Record>(&path, true).is_err());
+
+    let opts = CsvReadOptions::new().with_encoding(CsvEncoding::Latin1);
+    let (rows, _): (Vec<Record>, _) = read_csv_vec_with(&path, &opts)?;
+    let names: Vec<&str> = rows.iter().map(|r| r.name.as_str()).collect();
+    assert_eq!(names, vec!["José", "Müller"]);
+    Ok(())
+}
+
+#[test]
+fn read_csv_vec_with_malformed_row_policies() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let path = tmp.path().join("malformed.csv");
+    fs::write(&path, "id,name\n1,a\nnot-a-number,b\n3,c,extra\n4,d\n")?;
+
+    let fail = CsvReadOptions::new();
+    let err = read_csv_vec_with::<Record>(&path, &fail).unwrap_err();
+    assert!(format!("{err:#}").contains("record #2"));
+
+    let skip = CsvReadOptions::new().with_malformed_policy(MalformedRowPolicy::Skip);
+    let (rows, bad): (Vec<Record>, _) = read_csv_vec_with(&path, &skip)?;
+    assert_eq!(rows.iter().map(|r| r.id).collect::<Vec<_>>(), vec![1, 4]);
+    assert!(bad.is_empty());
+
+    let dead = skip.with_malformed_policy(MalformedRowPolicy::DeadLetter);
+    let (rows, bad): (Vec<Record>, _) = read_csv_vec_with(&path, &dead)?;
+    assert_eq!(rows.len(), 2);
+    let raw: Vec<&str> = bad.iter().map(|d| d.element.as_str()).collect();
+    assert_eq!(raw, vec!["not-a-number,b", "3,c,extra"]);
+    assert!(bad[0].error.contains("record #2"));
+
+    // Flexible rows tolerate the extra column.
+    let flexible = dead.with_flexible(true);
+    let (rows, bad): (Vec<Record>, _) = read_csv_vec_with(&path, &flexible)?;
+    assert_eq!(rows.iter().map(|r| r.id).collect::<Vec<_>>(), vec![1, 3, 4]);
+    assert_eq!(bad.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn csv_shards_apply_read_options() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let path = tmp.path().join("shards.csv");
+    fs::write(&path, "1|a\n2|b\nx|c\n4|d\n5|e\n")?;
+
+    let opts = CsvReadOptions::new()
+        .with_headers(false)
+        .with_delimiter(b'|')
+        .with_malformed_policy(MalformedRowPolicy::DeadLetter);
+    let shards = build_csv_shards_with(&path, &opts, 2)?;
+    // Malformed rows still count toward the shard layout.
+    assert_eq!(shards.total_rows, 5);
+    assert_eq!(shards.ranges, vec![(0, 2), (2, 4), (4, 5)]);
+
+    let (rows, bad) = read_csv_range_with::<(u32, String)>(&shards, 2, 4)?;
+    assert_eq!(rows, vec![(4, "d".to_string())]);
+    assert_eq!(bad.len(), 1);
+    assert_eq!(bad[0].element, "x|c");
+    Ok(())
+}
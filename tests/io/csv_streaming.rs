@@ -1,8 +1,12 @@
 #![cfg(feature = "io-csv")]
 
 use anyhow::Result;
+use ironbeam::io::csv::{CsvReadOptions, MalformedRowPolicy};
 use ironbeam::testing::*;
-use ironbeam::{from_vec, read_csv, read_csv_streaming, read_csv_vec, write_csv_vec};
+use ironbeam::{
+    from_vec, read_csv, read_csv_streaming, read_csv_streaming_with, read_csv_vec, read_csv_with,
+    write_csv_vec,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
@@ -115,3 +119,61 @@ fn write_csv_par_helper_function() -> Result<()> {
     assert_eq!(back, data);
     Ok(())
 }
+
+#[test]
+fn read_csv_with_routes_malformed_rows_to_dead_letters() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    std::fs::write(tmp.path().join("a.csv"), "id\tname\n1\ta\nbad\tb\n")?;
+    std::fs::write(tmp.path().join("b.csv"), "id\tname\n2\tc\n3\n")?;
+    let pattern = tmp.path().join("*.csv");
+
+    let p = TestPipeline::new();
+    let opts = CsvReadOptions::new()
+        .with_delimiter(b'\t')
+        .with_malformed_policy(MalformedRowPolicy::DeadLetter);
+    let (rows, bad) = read_csv_with::<Rec>(&p, &pattern, &opts)?;
+    let ids = rows.map(|r: &Rec| r.id).collect_seq_sorted()?;
+    assert_eq!(ids, vec![1, 2]);
+    let raw = bad.map(|d| d.element.clone()).collect_seq_sorted()?;
+    assert_eq!(raw, vec!["3".to_string(), "bad\tb".to_string()]);
+    Ok(())
+}
+
+#[test]
+fn read_csv_streaming_with_dead_letters_matches_vector_read() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let file = tmp.path().join("mixed.csv");
+    let mut text = String::from("id,name\n");
+    for i in 0..50 {
+        if i % 7 == 0 {
+            text.push_str(&format!("oops{i},x\n"));
+        } else {
+            text.push_str(&format!("{i},n{i}\n"));
+        }
+    }
+    std::fs::write(&file, text)?;
+
+    let opts = CsvReadOptions::new().with_malformed_policy(MalformedRowPolicy::DeadLetter);
+    let p = TestPipeline::new();
+    let (rows, bad) = read_csv_streaming_with::<Rec>(&p, &file, &opts, 6)?;
+    let streamed = rows.map(|r: &Rec| r.id).collect_par_sorted(Some(4), None)?;
+    let bad_rows = bad
+        .map(|d| d.element.clone())
+        .collect_par_sorted(Some(4), None)?;
+
+    let (expected, expected_bad): (Vec<Rec>, _) = ironbeam::read_csv_vec_with(&file, &opts)?;
+    let mut expected: Vec<u32> = expected.into_iter().map(|r| r.id).collect();
+    expected.sort_unstable();
+    let mut expected_bad: Vec<String> = expected_bad.into_iter().map(|d| d.element).collect();
+    expected_bad.sort();
+    assert_eq!(streamed, expected);
+    assert_eq!(bad_rows, expected_bad);
+    assert_eq!(bad_rows.len(), 8);
+
+    // Skip drops malformed rows and leaves the dead-letter side empty.
+    let skip = opts.with_malformed_policy(MalformedRowPolicy::Skip);
+    let (rows, bad) = read_csv_streaming_with::<Rec>(&p, &file, &skip, 6)?;
+    assert_eq!(rows.collect_seq()?.len(), 42);
+    assert!(bad.collect_seq()?.is_empty());
+    Ok(())
+}
@@ -365,6 +365,140 @@ fn test_csv_deterministic_order() -> Result<()> {
     Ok(())
 }
 
+#[cfg(feature = "io-csv")]
+#[test]
+fn test_csv_glob_header_drift_fails_by_default() -> Result<()> {
+    use ironbeam::io::drift::SchemaDriftError;
+
+    let dir = TempDir::new()?;
+    let base = dir.path();
+    std::fs::write(base.join("a.csv"), "id,name\n1,Alice\n")?;
+    std::fs::write(base.join("b.csv"), "id,name\n2,Bob\n")?;
+    std::fs::write(base.join("c.csv"), "name,id,email\nCy,3,cy@x\n")?;
+    std::fs::write(base.join("d.csv"), "id\n4\n")?;
+
+    let p = TestPipeline::new();
+    let pattern = format!("{}/*.csv", base.display());
+    let Err(err) = read_csv::<Record>(&p, &pattern, true) else {
+        panic!("drifted headers should fail the read");
+    };
+    let drift = err
+        .downcast_ref::<SchemaDriftError>()
+        .expect("structured drift error");
+    assert!(drift.reference.ends_with("a.csv"));
+    assert_eq!(drift.columns, vec!["id", "name"]);
+    assert_eq!(drift.drifts.len(), 2);
+    assert!(drift.drifts[0].path.ends_with("c.csv"));
+    assert_eq!(drift.drifts[0].added, vec!["email"]);
+    assert!(drift.drifts[0].missing.is_empty());
+    assert!(drift.drifts[0].reordered);
+    assert!(drift.drifts[1].path.ends_with("d.csv"));
+    assert_eq!(drift.drifts[1].missing, vec!["name"]);
+    assert!(!drift.drifts[1].reordered);
+    assert!(
+        format!("{err:#}").contains("added [email], reordered"),
+        "{err:#}"
+    );
+    Ok(())
+}
+
+#[cfg(feature = "io-csv")]
+#[test]
+fn test_csv_glob_header_drift_policies() -> Result<()> {
+    use ironbeam::io::csv::CsvReadOptions;
+    use ironbeam::io::drift::SchemaDriftPolicy;
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    struct Contact {
+        id: u32,
+        name: Option<String>,
+        email: Option<String>,
+    }
+
+    let dir = TempDir::new()?;
+    let base = dir.path();
+    std::fs::write(base.join("a.csv"), "id,name\n1,Alice\n")?;
+    std::fs::write(base.join("b.csv"), "email,id,name\nb@x,2,Bob\n")?;
+    std::fs::write(base.join("c.csv"), "id\n3\n")?;
+    let pattern = format!("{}/*.csv", base.display());
+
+    let p = TestPipeline::new();
+    let opts = CsvReadOptions::new().with_drift_policy(SchemaDriftPolicy::AlignByName);
+    let (rows, _) = read_csv_with::<Contact>(&p, &pattern, &opts)?;
+    let contact = |id, name: Option<&str>, email: Option<&str>| Contact {
+        id,
+        name: name.map(Into::into),
+        email: email.map(Into::into),
+    };
+    assert_eq!(
+        rows.collect_seq()?,
+        vec![
+            contact(1, Some("Alice"), None),
+            contact(2, Some("Bob"), Some("b@x")),
+            contact(3, None, None),
+        ]
+    );
+
+    // Tuples follow column positions, so only alignment keeps them straight.
+    let p = TestPipeline::new();
+    let (rows, _) = read_csv_with::<(String, String, String)>(&p, &pattern, &opts)?;
+    assert_eq!(
+        rows.collect_seq()?[1],
+        ("2".to_string(), "Bob".to_string(), "b@x".to_string())
+    );
+
+    let p = TestPipeline::new();
+    let opts = CsvReadOptions::new().with_drift_policy(SchemaDriftPolicy::Ignore);
+    let (rows, _) = read_csv_with::<Contact>(&p, &pattern, &opts)?;
+    assert_eq!(rows.collect_seq()?.len(), 3);
+    Ok(())
+}
+
+#[cfg(feature = "io-jsonl")]
+#[test]
+fn test_jsonl_glob_key_drift() -> Result<()> {
+    use ironbeam::io::drift::{SchemaDriftError, SchemaDriftPolicy};
+
+    #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+    struct Event {
+        id: u32,
+        tag: Option<String>,
+    }
+
+    let dir = TempDir::new()?;
+    let base = dir.path();
+    // Key order and keys only some records carry are not drift.
+    std::fs::write(
+        base.join("a.jsonl"),
+        "{\"id\":1,\"tag\":\"x\"}\n{\"id\":2}\n",
+    )?;
+    std::fs::write(base.join("b.jsonl"), "{\"tag\":null,\"id\":3}\n")?;
+    let pattern = format!("{}/*.jsonl", base.display());
+    let p = TestPipeline::new();
+    let rows = read_jsonl_checked::<Event>(&p, &pattern, SchemaDriftPolicy::Fail)?;
+    assert_eq!(rows.collect_seq()?.len(), 3);
+
+    std::fs::write(base.join("c.jsonl"), "{\"id\":4,\"source\":\"api\"}\n")?;
+    let p = TestPipeline::new();
+    let Err(err) = read_jsonl_checked::<Event>(&p, &pattern, SchemaDriftPolicy::Fail) else {
+        panic!("drifted keys should fail the read");
+    };
+    let drift = err
+        .downcast_ref::<SchemaDriftError>()
+        .expect("structured drift error");
+    assert_eq!(drift.drifts.len(), 1);
+    assert_eq!(drift.drifts[0].added, vec!["source"]);
+    assert_eq!(drift.drifts[0].missing, vec!["tag"]);
+
+    let p = TestPipeline::new();
+    let rows = read_jsonl_checked::<Event>(&p, &pattern, SchemaDriftPolicy::AlignByName)?;
+    assert_eq!(
+        rows.collect_seq()?.last(),
+        Some(&Event { id: 4, tag: None })
+    );
+    Ok(())
+}
+
 // Unit tests from src/io/glob.rs
 mod glob_unit_tests {
     use anyhow::Result;
@@ -3,7 +3,7 @@
 use anyhow::Result;
 use ironbeam::io::jsonl::*;
 use ironbeam::testing::*;
-use ironbeam::{Count, from_vec, read_jsonl};
+use ironbeam::{Count, from_vec, read_jsonl, read_jsonl_lenient};
 use serde::{Deserialize, Serialize};
 use std::fs;
 
@@ -312,3 +312,50 @@ invalid json line
     assert!(err_msg.contains("parse JSONL line"));
     Ok(())
 }
+
+#[test]
+fn jsonl_lenient_collects_bad_lines() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let file = tmp.path().join("feed.jsonl");
+    let mut bytes = b"{\"id\":1,\"word\":\"a\"}\n\n{\"id\":2,\"word\"\n".to_vec();
+    bytes.extend_from_slice(
+        b"{\"id\":\"three\",\"word\":\"c\"}\r\n\xff\xfe\n{\"id\":4,\"word\":\"d\"}",
+    );
+    fs::write(&file, bytes)?;
+
+    // The strict reader gives up on line 3.
+    assert!(read_jsonl_vec::<Rec>(&file).is_err());
+
+    let p = TestPipeline::new();
+    let (rows, errors) = read_jsonl_lenient::<Rec>(&p, &file)?;
+    let ids: Vec<u32> = rows.collect_seq()?.into_iter().map(|r| r.id).collect();
+    assert_eq!(ids, vec![1, 4]);
+
+    let errors = errors.collect_seq_sorted()?;
+    let lines: Vec<usize> = errors.iter().map(|(line, _, _)| *line).collect();
+    assert_eq!(lines, vec![3, 4, 5]);
+    assert_eq!(errors[0].1, "{\"id\":2,\"word\"");
+    assert_eq!(errors[1].1, "{\"id\":\"three\",\"word\":\"c\"}");
+    assert!(errors[1].2.contains("invalid type"));
+    assert!(errors[2].2.contains("invalid UTF-8"));
+    Ok(())
+}
+
+#[test]
+fn jsonl_lenient_glob_prefixes_errors_with_file() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    fs::write(
+        tmp.path().join("a.jsonl"),
+        "{\"id\":1,\"word\":\"a\"}\nnope\n",
+    )?;
+    fs::write(tmp.path().join("b.jsonl"), "{\"id\":2,\"word\":\"b\"}\n")?;
+
+    let p = TestPipeline::new();
+    let (rows, errors) = read_jsonl_lenient::<Rec>(&p, tmp.path().join("*.jsonl"))?;
+    assert_eq!(rows.collect_seq()?.len(), 2);
+    let errors = errors.collect_seq()?;
+    assert_eq!(errors.len(), 1);
+    assert_eq!((errors[0].0, errors[0].1.as_str()), (2, "nope"));
+    assert!(errors[0].2.contains("a.jsonl"));
+    Ok(())
+}
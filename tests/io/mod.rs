@@ -13,4 +13,6 @@ mod jsonl_streaming;
 mod msgpack;
 mod parquet;
 mod parquet_streaming;
+mod proto;
+mod timestamped;
 mod xml;
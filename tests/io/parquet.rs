@@ -172,3 +172,217 @@ fn read_parquet_row_group_range_file_error() -> Result<()> {
     assert!(err_msg.contains("open") || err_msg.contains("No such file"));
     Ok(())
 }
+
+fn rows(n: u32) -> Vec<Row> {
+    (0..n)
+        .map(|i| Row {
+            id: i,
+            name: format!("name{i}"),
+            score: Some(f64::from(i)),
+            tags: vec!["tag".into()],
+        })
+        .collect()
+}
+
+#[test]
+fn write_options_set_row_groups_compression_and_encodings() -> Result<()> {
+    use ::parquet::basic::{Compression, Encoding};
+    use ::parquet::file::reader::{FileReader, SerializedFileReader};
+
+    let tmp = tempfile::tempdir()?;
+    let path = tmp.path().join("tuned.parquet");
+    let opts = ParquetWriteOptions::default()
+        .with_row_group_size(10)
+        .with_compression(ParquetCompression::Zstd { level: 5 })
+        .with_dictionary(false)
+        .with_statistics(false)
+        .with_column_encoding("id", ParquetEncoding::DeltaBinaryPacked);
+    assert_eq!(write_parquet_vec_with(&path, &rows(25), &opts)?, 25);
+
+    let reader = SerializedFileReader::new(std::fs::File::open(&path)?)?;
+    let meta = reader.metadata();
+    assert_eq!(meta.num_row_groups(), 3);
+    let group = meta.row_group(0);
+    let id = group.column(0);
+    assert_eq!(id.column_path().string(), "id");
+    assert!(matches!(id.compression(), Compression::ZSTD(_)));
+    assert!(id.encodings().any(|e| e == Encoding::DELTA_BINARY_PACKED));
+    assert!(id.dictionary_page_offset().is_none());
+    assert!(id.statistics().is_none());
+
+    // The tuned file reads back unchanged, and shards by its smaller row groups.
+    assert_eq!(read_parquet_vec::<Row>(&path)?, rows(25));
+    assert_eq!(build_parquet_shards(&path, 1)?.group_ranges.len(), 3);
+
+    // Defaults keep dictionaries and statistics, uncompressed, in one row group.
+    let plain = tmp.path().join("plain.parquet");
+    write_parquet_vec(&plain, &rows(25))?;
+    let reader = SerializedFileReader::new(std::fs::File::open(&plain)?)?;
+    let group = reader.metadata().row_group(0);
+    assert_eq!(reader.metadata().num_row_groups(), 1);
+    assert_eq!(group.column(0).compression(), Compression::UNCOMPRESSED);
+    assert!(group.column(0).statistics().is_some());
+    Ok(())
+}
+
+#[test]
+fn write_parquet_with_threads_options_through_the_pipeline() -> Result<()> {
+    use ::parquet::basic::Compression;
+    use ::parquet::file::reader::{FileReader, SerializedFileReader};
+
+    let tmp = tempfile::tempdir()?;
+    let path = tmp.path().join("pipeline.parquet");
+    let p = TestPipeline::new();
+    let opts = ParquetWriteOptions::default()
+        .with_row_group_size(4)
+        .with_compression(ParquetCompression::Snappy);
+    assert_eq!(from_vec(&p, rows(10)).write_parquet_with(&path, &opts)?, 10);
+
+    let reader = SerializedFileReader::new(std::fs::File::open(&path)?)?;
+    assert_eq!(reader.metadata().num_row_groups(), 3);
+    assert_eq!(
+        reader.metadata().row_group(0).column(0).compression(),
+        Compression::SNAPPY
+    );
+    assert_eq!(read_parquet_vec::<Row>(&path)?, rows(10));
+    Ok(())
+}
+
+#[test]
+fn invalid_write_options_are_rejected() {
+    let tmp = tempfile::tempdir().unwrap();
+    let path = tmp.path().join("bad.parquet");
+    let bad_group = ParquetWriteOptions::default().with_row_group_size(0);
+    let err = write_parquet_vec_with(&path, &rows(1), &bad_group).unwrap_err();
+    assert!(format!("{err:#}").contains("row group size"), "{err:#}");
+
+    let bad_level =
+        ParquetWriteOptions::default().with_compression(ParquetCompression::Gzip { level: 42 });
+    let err = write_parquet_vec_with(&path, &rows(1), &bad_level).unwrap_err();
+    assert!(format!("{err:#}").contains("gzip level"), "{err:#}");
+    assert!(!path.exists());
+}
+
+/// An older layout of [`Evolved`]: narrower `id` and `score`, no `label` or `seen`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Legacy {
+    id: i32,
+    score: f32,
+    big: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Evolved {
+    id: i64,
+    score: f64,
+    label: Option<String>,
+    seen: u32,
+}
+
+fn legacy_file(dir: &std::path::Path) -> Result<std::path::PathBuf> {
+    let path = dir.join("legacy.parquet");
+    let rows = vec![
+        Legacy {
+            id: 1,
+            score: 0.5,
+            big: 1 << 40,
+        },
+        Legacy {
+            id: 2,
+            score: 2.0,
+            big: 7,
+        },
+    ];
+    write_parquet_vec(&path, &rows)?;
+    Ok(path)
+}
+
+#[test]
+fn coercion_fills_missing_columns_and_widens() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let path = legacy_file(tmp.path())?;
+
+    // Without a policy the non-optional `seen` column is missing.
+    assert!(read_parquet_vec::<Evolved>(&path).is_err());
+
+    let rows: Vec<Evolved> = read_parquet_vec_with(&path, &SchemaCoercion::default())?;
+    assert_eq!(
+        rows,
+        vec![
+            Evolved {
+                id: 1,
+                score: 0.5,
+                label: None,
+                seen: 0,
+            },
+            Evolved {
+                id: 2,
+                score: 2.0,
+                label: None,
+                seen: 0,
+            },
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn coercion_policies_reject_what_they_disable() -> Result<()> {
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    struct Narrow {
+        big: i32,
+    }
+
+    let tmp = tempfile::tempdir()?;
+    let path = legacy_file(tmp.path())?;
+
+    let err = read_parquet_vec_with::<Evolved>(&path, &SchemaCoercion::strict()).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("widening is disabled"),
+        "{err:#}"
+    );
+    let no_fill = SchemaCoercion::default().with_fill_missing(false);
+    let err = read_parquet_vec_with::<Evolved>(&path, &no_fill).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("column `label` is missing"),
+        "{err:#}"
+    );
+
+    let err = read_parquet_vec_with::<Narrow>(&path, &SchemaCoercion::default()).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("narrowing is disabled"),
+        "{err:#}"
+    );
+    // Allowed narrowing still checks every value: 1 << 40 does not fit an i32.
+    let narrowing = SchemaCoercion::default().with_allow_narrowing(true);
+    let err = read_parquet_vec_with::<Narrow>(&path, &narrowing).unwrap_err();
+    assert!(format!("{err:#}").contains("cast column `big`"), "{err:#}");
+    Ok(())
+}
+
+#[test]
+fn streaming_reads_apply_coercion_per_shard() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let path = legacy_file(tmp.path())?;
+    let p = TestPipeline::new();
+
+    let rows =
+        ironbeam::read_parquet_streaming_with::<Evolved>(&p, &path, 1, &SchemaCoercion::default())?
+            .collect_seq()?;
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[1].id, 2);
+
+    // Policy violations surface when the source is built, not when it runs.
+    let Err(err) =
+        ironbeam::read_parquet_streaming_with::<Evolved>(&p, &path, 1, &SchemaCoercion::strict())
+    else {
+        panic!("a strict read of the legacy file should fail");
+    };
+    assert!(format!("{err:#}").contains("legacy.parquet"), "{err:#}");
+
+    let shards = build_parquet_shards(&path, 1)?;
+    let range: Vec<Evolved> =
+        read_parquet_row_group_range_with(&shards, 0, 1, &SchemaCoercion::default())?;
+    assert_eq!(range.len(), 2);
+    Ok(())
+}
@@ -0,0 +1,101 @@
+//! Tests for the length-delimited protobuf connector (feature `io-proto`).
+
+#![cfg(feature = "io-proto")]
+
+use anyhow::Result;
+use ironbeam::io::proto::*;
+use ironbeam::testing::*;
+use ironbeam::type_token::VecOps;
+use ironbeam::{from_vec, read_proto, read_proto_streaming};
+use prost::Message;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+#[derive(Clone, PartialEq, Eq, Message, Serialize, Deserialize)]
+struct Click {
+    #[prost(string, tag = "1")]
+    user: String,
+    #[prost(uint64, tag = "2")]
+    ts: u64,
+}
+
+fn clicks(n: u64) -> Vec<Click> {
+    (0..n)
+        .map(|ts| Click {
+            user: format!("u{}", ts % 3),
+            ts,
+        })
+        .collect()
+}
+
+#[test]
+fn proto_vec_roundtrip() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let path = tmp.path().join("nested/clicks.pb");
+    let data = clicks(50);
+    assert_eq!(write_proto_vec(&path, &data)?, 50);
+    let back: Vec<Click> = read_proto_vec(&path)?;
+    assert_eq!(back, data);
+    Ok(())
+}
+
+#[test]
+fn proto_reads_delimited_bytes_from_other_writers() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let path = tmp.path().join("dump.pb");
+    // Hand-framed, as a Kafka dump tool would write them; include an empty message.
+    let mut bytes = Vec::new();
+    for click in [Click::default(), clicks(300).pop().unwrap()] {
+        click.encode_length_delimited(&mut bytes)?;
+    }
+    fs::write(&path, &bytes)?;
+    let back: Vec<Click> = read_proto_vec(&path)?;
+    assert_eq!(back.len(), 2);
+    assert_eq!(back[1].ts, 299);
+
+    // A truncated final message is an error, not a silent drop.
+    fs::write(&path, &bytes[..bytes.len() - 1])?;
+    let err = read_proto_vec::<Click>(&path).unwrap_err();
+    assert!(format!("{err:#}").contains("message #2"));
+    Ok(())
+}
+
+#[test]
+fn proto_shards_and_streaming_source() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let path = tmp.path().join("clicks.pb");
+    write_proto_vec(&path, &clicks(23))?;
+
+    let shards = build_proto_shards(&path, 10)?;
+    assert_eq!(shards.total_records, 23);
+    assert_eq!(shards.ranges, vec![(0, 10), (10, 20), (20, 23)]);
+    let tail: Vec<Click> = read_proto_range(&shards, 20, 23)?;
+    assert_eq!(
+        tail.iter().map(|c| c.ts).collect::<Vec<_>>(),
+        vec![20, 21, 22]
+    );
+
+    let ops = ProtoVecOps::<Click>::new();
+    assert_eq!(ops.len(&shards), Some(23));
+    assert_eq!(ops.split(&shards, 3).map(|parts| parts.len()), Some(3));
+
+    let p = TestPipeline::new();
+    let ts = read_proto_streaming::<Click>(&p, &path, 4)?
+        .map(|c: &Click| c.ts)
+        .collect_par_sorted(Some(3), None)?;
+    assert_eq!(ts, (0..23).collect::<Vec<_>>());
+    Ok(())
+}
+
+#[test]
+fn proto_pipeline_write_and_glob_read() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let p = TestPipeline::new();
+    from_vec(&p, clicks(5)).write_proto(tmp.path().join("a.pb"))?;
+    from_vec(&p, clicks(3)).write_proto(tmp.path().join("b.pb"))?;
+    fs::write(tmp.path().join("empty.pb"), b"")?;
+
+    let all = read_proto::<Click>(&p, tmp.path().join("*.pb"))?.collect_seq()?;
+    assert_eq!(all.len(), 8);
+    Ok(())
+}
@@ -0,0 +1,95 @@
+#![cfg(all(feature = "io-jsonl", feature = "io-csv", feature = "io-parquet"))]
+
+use anyhow::Result;
+use ironbeam::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Click {
+    ts_ms: u64,
+    user: String,
+}
+
+fn click(ts_ms: u64, user: &str) -> Click {
+    Click {
+        ts_ms,
+        user: user.into(),
+    }
+}
+
+#[test]
+fn sources_stamp_records_by_field_or_closure() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let clicks = vec![click(1_000, "a"), click(61_000, "b"), click(62_000, "a")];
+    let jsonl = tmp.path().join("clicks.jsonl");
+    let csv = tmp.path().join("clicks.csv");
+    let parquet = tmp.path().join("clicks.parquet");
+    write_jsonl_vec(&jsonl, &clicks)?;
+    write_csv_vec(&csv, true, &clicks)?;
+    write_parquet_vec(&parquet, &clicks)?;
+
+    let p = Pipeline::default();
+    let by_field = EventTime::field("ts_ms");
+    let by_closure = EventTime::extract(|c: &Click| c.ts_ms + 1);
+    let expected: Vec<Timestamped<Click>> = clicks
+        .iter()
+        .map(|c| Timestamped::new(c.ts_ms, c.clone()))
+        .collect();
+
+    assert_eq!(
+        read_jsonl_timestamped(&p, &jsonl, &by_field)?.collect_seq()?,
+        expected
+    );
+    assert_eq!(
+        read_csv_timestamped(&p, &csv, true, &by_field)?.collect_seq()?,
+        expected
+    );
+    let stamped = read_parquet_timestamped(&p, &parquet, &by_closure)?;
+    assert_eq!(
+        stamped
+            .clone()
+            .collect_seq()?
+            .iter()
+            .map(|t| t.ts)
+            .collect::<Vec<_>>(),
+        vec![1_001, 61_001, 62_001]
+    );
+    let mut per_minute = stamped.count_per_window(60_000, 0).collect_seq()?;
+    per_minute.sort();
+    assert_eq!(
+        per_minute
+            .iter()
+            .map(|(w, n)| (w.start, *n))
+            .collect::<Vec<_>>(),
+        vec![(0, 1), (60_000, 2)]
+    );
+    Ok(())
+}
+
+#[test]
+fn field_errors_name_the_record() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let path = tmp.path().join("events.jsonl");
+    fs::write(&path, "{\"ts\":\"5\",\"n\":1}\n{\"ts\":\"soon\",\"n\":2}\n")?;
+
+    #[derive(Clone, Serialize, Deserialize)]
+    struct Event {
+        ts: String,
+        n: u32,
+    }
+
+    let p = Pipeline::default();
+    let Err(err) = read_jsonl_timestamped::<Event>(&p, &path, &EventTime::field("ts")) else {
+        panic!("expected a bad timestamp to fail the read");
+    };
+    let message = format!("{err:#}");
+    assert!(message.contains("event time of record #2"), "{message}");
+    assert!(message.contains("not a millisecond timestamp"), "{message}");
+
+    let Err(err) = read_jsonl_timestamped::<Event>(&p, &path, &EventTime::field("when")) else {
+        panic!("expected a missing field to fail the read");
+    };
+    assert!(format!("{err:#}").contains("record has no field `when`"));
+    Ok(())
+}
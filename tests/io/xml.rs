@@ -5,11 +5,15 @@
 //! - Streaming read operations (single-shard)
 //! - Parallel write operations
 //! - Glob pattern support
+//! - Record extraction by tag path
 //! - Compression support
 //! - Error handling
 
 use anyhow::Result;
-use ironbeam::io::xml::{XmlVecOps, build_xml_shards, read_xml_range, read_xml_vec, write_xml_vec};
+use ironbeam::io::xml::{
+    XmlVecOps, build_xml_record_shards, build_xml_shards, read_xml_range, read_xml_records_vec,
+    read_xml_vec, write_xml_vec,
+};
 use ironbeam::*;
 use serde::{Deserialize, Serialize};
 use std::fs::File;
@@ -883,3 +887,80 @@ fn test_read_xml_single_record() -> Result<()> {
 
     Ok(())
 }
+
+// ── Record paths ─────────────────────────────────────────────────────────────
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+struct Book {
+    #[serde(rename = "@id")]
+    id: u32,
+    title: String,
+}
+
+const CATALOG: &str = r#"<?xml version="1.0"?>
+<lib:catalog xmlns:lib="urn:lib">
+  <meta><title>not a book</title></meta>
+  <shelf name="a">
+    <lib:book id="1"><title>Dune</title></lib:book>
+    <book id="2"><title>Emma &amp; Co</title></book>
+  </shelf>
+  <shelf name="b">
+    <book id="3"><title>Ulysses</title></book>
+    <pamphlet id="9"><title>skip me</title></pamphlet>
+  </shelf>
+  <book id="99"><title>wrong depth</title></book>
+</lib:catalog>"#;
+
+#[test]
+fn test_read_xml_records_by_path() -> Result<()> {
+    let tmp = TempDir::new()?;
+    let path = tmp.path().join("catalog.xml");
+    std::fs::write(&path, CATALOG)?;
+
+    let books: Vec<Book> = read_xml_records_vec(&path, "catalog/shelf/book")?;
+    let ids: Vec<u32> = books.iter().map(|b| b.id).collect();
+    assert_eq!(ids, vec![1, 2, 3]);
+    assert_eq!(books[1].title, "Emma & Co");
+
+    // `*` matches any element name at that level.
+    let any: Vec<Book> = read_xml_records_vec(&path, "/catalog/shelf/*")?;
+    assert_eq!(any.len(), 4);
+
+    assert!(read_xml_records_vec::<Book>(&path, "/").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_xml_record_shards_split_by_count() -> Result<()> {
+    let tmp = TempDir::new()?;
+    let path = tmp.path().join("feed.xml");
+    let mut xml = String::from("<feed>");
+    for i in 0..25 {
+        xml.push_str(&format!(r#"<entry id="{i}"><title>t{i}</title></entry>"#));
+    }
+    xml.push_str("<entry id=\"25\" title=\"empty\"/></feed>");
+    std::fs::write(&path, xml)?;
+
+    #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+    struct Entry {
+        #[serde(rename = "@id")]
+        id: u32,
+    }
+
+    let shards = build_xml_record_shards(&path, "feed/entry", 10)?;
+    assert_eq!(shards.total_records, 26);
+    assert_eq!(shards.ranges, vec![(0, 10), (10, 20), (20, 26)]);
+    let middle: Vec<Entry> = read_xml_range(&shards, 10, 20)?;
+    assert_eq!(middle.first().map(|e| e.id), Some(10));
+    assert_eq!(middle.len(), 10);
+
+    let p = Pipeline::default();
+    let streamed = read_xml_records_streaming::<Entry>(&p, &path, "feed/entry", 7)?
+        .map(|e: &Entry| e.id)
+        .collect_par_sorted(Some(4), None)?;
+    assert_eq!(streamed, (0..26).collect::<Vec<_>>());
+
+    let vector = read_xml_records::<Entry>(&p, tmp.path().join("*.xml"), "feed/entry")?;
+    assert_eq!(vector.collect_seq()?.len(), 26);
+    Ok(())
+}
@@ -0,0 +1,118 @@
+//! Tests for per-key file writes.
+#![cfg(all(feature = "io-jsonl", feature = "io-csv", feature = "compression-gzip"))]
+
+use anyhow::Result;
+use ironbeam::io::compression::Compression;
+use ironbeam::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+struct Row {
+    id: u32,
+    name: String,
+}
+
+fn keyed(p: &Pipeline, n: u32) -> PCollection<(String, Row)> {
+    from_vec(p, (0..n).collect::<Vec<_>>()).map(|id: &u32| {
+        let tenant = ["acme", "globex", "initech"][(*id % 3) as usize].to_string();
+        (
+            tenant,
+            Row {
+                id: *id,
+                name: format!("row-{id}"),
+            },
+        )
+    })
+}
+
+#[test]
+fn jsonl_per_key_keeps_partition_order() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let p = Pipeline::default();
+    let opts = PerKeyWriteOptions::default()
+        .with_shards(5)
+        .with_max_open_files(2)
+        .with_success_marker(true);
+    let files = keyed(&p, 30).write_jsonl_per_key(tmp.path(), &opts)?;
+
+    let keys: Vec<&str> = files.iter().map(|f| f.key.as_str()).collect();
+    assert_eq!(keys, vec!["acme", "globex", "initech"]);
+    assert!(files.iter().all(|f| f.rows == 10));
+    assert_eq!(files[1].path, tmp.path().join("globex.jsonl"));
+
+    let globex: Vec<Row> = read_jsonl_vec(&files[1].path)?;
+    let ids: Vec<u32> = globex.iter().map(|r| r.id).collect();
+    assert_eq!(ids, (1..30).step_by(3).collect::<Vec<_>>());
+    assert!(tmp.path().join("_SUCCESS").exists());
+    Ok(())
+}
+
+#[test]
+fn csv_per_key_sanitizes_names_and_writes_one_header() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let p = Pipeline::default();
+    let row = |id| Row {
+        id,
+        name: format!("row-{id}"),
+    };
+    let data = vec![
+        ("../escape".to_string(), row(1)),
+        ("north/east".to_string(), row(2)),
+        ("con".to_string(), row(3)),
+        (String::new(), row(4)),
+        ("north/east".to_string(), row(5)),
+    ];
+    let opts = PerKeyWriteOptions::default()
+        .with_shards(2)
+        .with_extension("csv.gz");
+    let files = from_vec(&p, data).write_csv_per_key(tmp.path(), true, &opts)?;
+
+    let names: Vec<String> = files
+        .iter()
+        .map(|f| f.path.file_name().unwrap().to_string_lossy().into_owned())
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "_.csv.gz",
+            "_.._escape.csv.gz",
+            "_con.csv.gz",
+            "north_east.csv.gz"
+        ]
+    );
+    assert!(files.iter().all(|f| f.path.parent() == Some(tmp.path())));
+
+    let rows: Vec<Row> = read_csv_vec(&files[3].path, true)?;
+    assert_eq!(rows, vec![row(2), row(5)]);
+    Ok(())
+}
+
+#[test]
+fn colliding_keys_fail_before_writing() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let out = tmp.path().join("out");
+    let p = Pipeline::default();
+    let row = Row {
+        id: 1,
+        name: "a".into(),
+    };
+    let data = vec![
+        ("eu west".to_string(), row.clone()),
+        ("EU_WEST".to_string(), row),
+    ];
+    let opts = PerKeyWriteOptions::default().with_compression(Compression::None);
+    let err = from_vec(&p, data)
+        .write_jsonl_per_key(&out, &opts)
+        .unwrap_err();
+    assert!(
+        format!("{err:#}").contains("both map to the file name"),
+        "{err:#}"
+    );
+    assert!(!out.exists());
+
+    let p = Pipeline::default();
+    let files = from_vec(&p, Vec::<(u32, Row)>::new()).write_jsonl_per_key(&out, &opts)?;
+    assert!(files.is_empty());
+    assert_eq!(std::fs::read_dir(&out)?.count(), 0);
+    Ok(())
+}
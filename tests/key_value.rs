@@ -0,0 +1,136 @@
+//! Tests for the `KeyValueIO` sink and enrichment.
+
+use anyhow::Result;
+use ironbeam::helpers::key_value::{ENRICH_BATCH_SIZE, KeyValueDocument};
+use ironbeam::io::cloud::{FakeKeyValueIO, KeyValueIO};
+use ironbeam::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+fn doc(name: &str) -> KeyValueDocument {
+    HashMap::from([("name".to_string(), name.to_string())])
+}
+
+#[test]
+fn write_key_value_puts_in_batches() -> Result<()> {
+    let store = FakeKeyValueIO::new();
+    let p = Pipeline::default();
+    let docs = (0..25)
+        .map(|i| (format!("k{i}"), doc(&format!("n{i}"))))
+        .collect::<Vec<_>>();
+
+    assert_eq!(from_vec(&p, docs).write_key_value(&store, "c", 10)?, 25);
+    assert_eq!(store.batch_put_sizes(), vec![10, 10, 5]);
+    assert_eq!(store.get("c", "k24")?.unwrap().data, doc("n24"));
+    Ok(())
+}
+
+#[test]
+fn last_document_for_a_key_wins() -> Result<()> {
+    let store = FakeKeyValueIO::new();
+    let p = Pipeline::default();
+    let docs = vec![
+        ("k".to_string(), doc("first")),
+        ("k".to_string(), doc("second")),
+    ];
+    from_vec(&p, docs).write_key_value(&store, "c", 1)?;
+    assert_eq!(store.get("c", "k")?.unwrap().data, doc("second"));
+    Ok(())
+}
+
+#[test]
+fn zero_batch_size_is_rejected() {
+    let p = Pipeline::default();
+    let err = from_vec(&p, vec![("k".to_string(), doc("v"))])
+        .write_key_value(&FakeKeyValueIO::new(), "c", 0)
+        .unwrap_err();
+    assert!(err.to_string().contains("batch_size"), "{err}");
+}
+
+#[test]
+fn enrich_pairs_elements_with_documents() -> Result<()> {
+    let store = FakeKeyValueIO::new();
+    store.put("users", "a", doc("Ada"))?;
+    store.put("users", "b", doc("Bob"))?;
+    let p = Pipeline::default();
+    let orders = from_vec(
+        &p,
+        vec![
+            ("a".to_string(), 1u32),
+            ("x".to_string(), 2),
+            ("b".to_string(), 3),
+        ],
+    );
+
+    let enriched = orders
+        .enrich_from_key_value(Arc::new(store), "users", |(user, _): &(String, u32)| {
+            user.clone()
+        })?
+        .map(|((_, n), d): &((String, u32), Option<KeyValueDocument>)| {
+            (*n, d.as_ref().map(|d| d["name"].clone()))
+        })
+        .collect_seq()?;
+    assert_eq!(
+        enriched,
+        vec![
+            (1, Some("Ada".to_string())),
+            (2, None),
+            (3, Some("Bob".to_string()))
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn enrich_fetches_each_key_once_in_batches() -> Result<()> {
+    let store = FakeKeyValueIO::new();
+    let p = Pipeline::default();
+    let n = u32::try_from(ENRICH_BATCH_SIZE).unwrap() + 20;
+    // Every key appears three times.
+    let elements = (0..3 * n).map(|i| i % n).collect::<Vec<_>>();
+
+    let enriched = from_vec(&p, elements)
+        .enrich_from_key_value(Arc::new(store.clone()), "c", |i: &u32| format!("k{i}"))?
+        .collect_seq()?;
+    assert_eq!(enriched.len(), 3 * n as usize);
+    assert_eq!(store.batch_get_sizes(), vec![ENRICH_BATCH_SIZE, 20]);
+    Ok(())
+}
+
+#[test]
+fn enrich_is_lazy_and_fetches_per_partition() -> Result<()> {
+    let store = FakeKeyValueIO::new();
+    store.put("users", "a", doc("Ada"))?;
+    let p = Pipeline::default();
+    let elements = (0..12u32).map(|i| if i % 2 == 0 { "a" } else { "x" }.to_string());
+
+    let enriched = from_vec(&p, elements.collect::<Vec<_>>()).enrich_from_key_value(
+        Arc::new(store.clone()),
+        "users",
+        String::clone,
+    )?;
+    assert!(store.batch_get_sizes().is_empty());
+
+    let out = enriched.collect_par(None, Some(3))?;
+    assert_eq!(out.len(), 12);
+    for (key, found) in &out {
+        assert_eq!(found.is_some(), key == "a", "{key}");
+    }
+    // Each of the three partitions fetches its own two distinct keys.
+    assert_eq!(store.batch_get_sizes(), vec![2, 2, 2]);
+    Ok(())
+}
+
+#[test]
+fn enrich_collection_reference_without_resolver_is_rejected() {
+    let p = Pipeline::default();
+    let err = from_vec(&p, vec![1u32])
+        .enrich_from_key_value(
+            Arc::new(FakeKeyValueIO::new()),
+            "config://kv/users",
+            |i: &u32| i.to_string(),
+        )
+        .err()
+        .expect("unresolvable reference");
+    assert!(err.to_string().contains("no config resolver"), "{err}");
+}
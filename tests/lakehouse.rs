@@ -0,0 +1,295 @@
+//! Tests for Delta Lake snapshot reads.
+#![cfg(feature = "io-parquet")]
+
+use ::parquet::arrow::ArrowWriter;
+use anyhow::Result;
+use arrow::datatypes::FieldRef;
+use ironbeam::io::lakehouse::{DeltaReadOptions, DeltaSnapshot, PartitionFilter};
+use ironbeam::*;
+use serde::{Deserialize, Serialize};
+use serde_arrow::schema::{SchemaLike, TracingOptions};
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+use std::fs::{File, create_dir_all, write};
+use std::path::Path;
+use tempfile::tempdir;
+
+/// A row as stored in a data file: the partition column `date` is not in it.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Stored {
+    user: String,
+    clicks: i64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Event {
+    date: String,
+    day: i64,
+    user: String,
+    clicks: i64,
+}
+
+fn schema_string() -> String {
+    json!({
+        "type": "struct",
+        "fields": [
+            { "name": "date", "type": "date", "nullable": true, "metadata": {} },
+            { "name": "day", "type": "integer", "nullable": true, "metadata": {} },
+            { "name": "user", "type": "string", "nullable": true, "metadata": {} },
+            { "name": "clicks", "type": "long", "nullable": true, "metadata": {} },
+        ]
+    })
+    .to_string()
+}
+
+fn metadata() -> Value {
+    json!({ "metaData": {
+        "id": "t",
+        "format": { "provider": "parquet", "options": {} },
+        "schemaString": schema_string(),
+        "partitionColumns": ["date", "day"],
+        "configuration": {},
+    }})
+}
+
+fn protocol() -> Value {
+    json!({ "protocol": { "minReaderVersion": 1, "minWriterVersion": 2 } })
+}
+
+/// Write a data file under `table` and return its `add` action.
+fn data_file(table: &Path, date: &str, day: i64, name: &str, rows: &[Stored]) -> Result<Value> {
+    let relative = format!("date={date}/day={day}/{name}");
+    let path = table.join(&relative);
+    create_dir_all(path.parent().unwrap())?;
+    write_parquet_vec(&path, &rows.to_vec())?;
+    Ok(json!({ "add": {
+        "path": relative.replace(' ', "%20"),
+        "partitionValues": { "date": date, "day": day.to_string() },
+        "size": std::fs::metadata(&path)?.len(),
+        "modificationTime": 0,
+        "dataChange": true,
+        "stats": json!({ "numRecords": rows.len() }).to_string(),
+    }}))
+}
+
+fn commit(table: &Path, version: u64, actions: &[Value]) -> Result<()> {
+    let log = table.join("_delta_log");
+    create_dir_all(&log)?;
+    let lines: Vec<String> = actions.iter().map(Value::to_string).collect();
+    write(log.join(format!("{version:020}.json")), lines.join("\n"))?;
+    Ok(())
+}
+
+fn stored(user: &str, clicks: i64) -> Stored {
+    Stored {
+        user: user.into(),
+        clicks,
+    }
+}
+
+/// A three-version table: two files, then a third, then the first one replaced.
+fn sample_table(table: &Path) -> Result<()> {
+    let a = data_file(table, "2024-06-01", 1, "a.parquet", &[stored("ann", 3)])?;
+    let b = data_file(
+        table,
+        "2024-06-02",
+        2,
+        "b file.parquet",
+        &[stored("bob", 5), stored("cy", 1)],
+    )?;
+    commit(table, 0, &[protocol(), metadata(), a])?;
+    commit(table, 1, &[b])?;
+    let c = data_file(table, "2024-06-01", 1, "c.parquet", &[stored("ann", 4)])?;
+    commit(
+        table,
+        2,
+        &[
+            json!({ "remove": { "path": "date=2024-06-01/day=1/a.parquet" } }),
+            c,
+        ],
+    )?;
+    Ok(())
+}
+
+fn event(date: &str, day: i64, user: &str, clicks: i64) -> Event {
+    Event {
+        date: date.into(),
+        day,
+        user: user.into(),
+        clicks,
+    }
+}
+
+#[test]
+fn latest_snapshot_replays_adds_and_removes() -> Result<()> {
+    let dir = tempdir()?;
+    sample_table(dir.path())?;
+    let p = Pipeline::default();
+
+    let mut rows = read_delta_table::<Event>(&p, dir.path(), &DeltaReadOptions::default())?
+        .collect_par(Some(4), None)?;
+    rows.sort_by(|a, b| a.user.cmp(&b.user));
+    assert_eq!(
+        rows,
+        vec![
+            event("2024-06-01", 1, "ann", 4),
+            event("2024-06-02", 2, "bob", 5),
+            event("2024-06-02", 2, "cy", 1),
+        ]
+    );
+
+    let snapshot = DeltaSnapshot::load(dir.path(), None)?;
+    assert_eq!(snapshot.version, 2);
+    assert_eq!(snapshot.partition_columns, vec!["date", "day"]);
+    assert_eq!(snapshot.files.len(), 2);
+    assert_eq!(snapshot.num_records(), Some(3));
+    Ok(())
+}
+
+#[test]
+fn versions_travel_back_in_time() -> Result<()> {
+    let dir = tempdir()?;
+    sample_table(dir.path())?;
+
+    let opts = DeltaReadOptions::default().with_version(0);
+    let rows: Vec<Event> = read_delta_vec(dir.path(), &opts)?;
+    assert_eq!(rows, vec![event("2024-06-01", 1, "ann", 3)]);
+
+    let err = DeltaSnapshot::load(dir.path(), Some(9)).unwrap_err();
+    assert!(format!("{err:#}").contains("has no version 9"), "{err:#}");
+    Ok(())
+}
+
+#[test]
+fn partition_filters_prune_files() -> Result<()> {
+    let dir = tempdir()?;
+    sample_table(dir.path())?;
+
+    let opts = DeltaReadOptions::default()
+        .with_filter(PartitionFilter::Eq("date".into(), "2024-06-02".into()));
+    let rows: Vec<Event> = read_delta_vec(dir.path(), &opts)?;
+    assert_eq!(rows.len(), 2);
+
+    let opts = DeltaReadOptions::default().with_filter(PartitionFilter::In(
+        "day".into(),
+        vec!["1".into(), "3".into()],
+    ));
+    let rows: Vec<Event> = read_delta_vec(dir.path(), &opts)?;
+    assert_eq!(rows, vec![event("2024-06-01", 1, "ann", 4)]);
+
+    let opts = DeltaReadOptions::default().with_filter(PartitionFilter::IsNull("date".into()));
+    assert!(read_delta_vec::<Event>(dir.path(), &opts)?.is_empty());
+    Ok(())
+}
+
+/// One row of a checkpoint file, in the Delta checkpoint schema.
+#[derive(Serialize)]
+struct CheckpointRow {
+    add: Option<CheckpointAdd>,
+    #[serde(rename = "metaData")]
+    metadata: Option<CheckpointMetadata>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CheckpointAdd {
+    path: String,
+    partition_values: BTreeMap<String, Option<String>>,
+    size: i64,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CheckpointMetadata {
+    schema_string: String,
+    partition_columns: Vec<String>,
+}
+
+#[test]
+fn checkpoints_replace_cleaned_up_commits() -> Result<()> {
+    let dir = tempdir()?;
+    let table = dir.path();
+    sample_table(table)?;
+
+    // Checkpoint version 1 (files a and b), then drop the commits it covers.
+    let add = |path: &str, date: &str, day: &str| CheckpointRow {
+        add: Some(CheckpointAdd {
+            path: path.into(),
+            partition_values: BTreeMap::from([
+                ("date".into(), Some(date.into())),
+                ("day".into(), Some(day.into())),
+            ]),
+            size: 1,
+        }),
+        metadata: None,
+    };
+    let rows = vec![
+        CheckpointRow {
+            add: None,
+            metadata: Some(CheckpointMetadata {
+                schema_string: schema_string(),
+                partition_columns: vec!["date".into(), "day".into()],
+            }),
+        },
+        add("date=2024-06-01/day=1/a.parquet", "2024-06-01", "1"),
+        add("date=2024-06-02/day=2/b%20file.parquet", "2024-06-02", "2"),
+    ];
+    let fields =
+        Vec::<FieldRef>::from_samples(&rows, TracingOptions::default().map_as_struct(false))?;
+    let batch = serde_arrow::to_record_batch(&fields, &rows)?;
+    let log = table.join("_delta_log");
+    let mut writer = ArrowWriter::try_new(
+        File::create(log.join(format!("{:020}.checkpoint.parquet", 1)))?,
+        batch.schema(),
+        None,
+    )?;
+    writer.write(&batch)?;
+    writer.close()?;
+    std::fs::remove_file(log.join(format!("{:020}.json", 0)))?;
+    std::fs::remove_file(log.join(format!("{:020}.json", 1)))?;
+
+    let snapshot = DeltaSnapshot::load(table, None)?;
+    assert_eq!(snapshot.files.len(), 2);
+    // Row counts are unknown for files added by the checkpoint without stats.
+    assert_eq!(snapshot.num_records(), None);
+    let mut rows: Vec<Event> = read_delta_vec(table, &DeltaReadOptions::default())?;
+    rows.sort_by(|a, b| a.user.cmp(&b.user));
+    assert_eq!(
+        rows.iter().map(|e| e.clicks).collect::<Vec<_>>(),
+        vec![4, 5, 1]
+    );
+
+    let err = DeltaSnapshot::load(table, Some(0)).unwrap_err();
+    assert!(format!("{err:#}").contains("commit 0"), "{err:#}");
+    Ok(())
+}
+
+#[test]
+fn unsupported_features_are_rejected() -> Result<()> {
+    let dir = tempdir()?;
+    let table = dir.path();
+    let mut meta = metadata();
+    meta["metaData"]["configuration"] = json!({ "delta.columnMapping.mode": "name" });
+    commit(table, 0, &[protocol(), meta])?;
+    let err = DeltaSnapshot::load(table, None).unwrap_err();
+    assert!(format!("{err:#}").contains("column mapping"), "{err:#}");
+
+    let dir = tempdir()?;
+    let table = dir.path();
+    let mut a = data_file(table, "2024-06-01", 1, "a.parquet", &[stored("ann", 3)])?;
+    a["add"]["deletionVector"] = json!({ "storageType": "u", "pathOrInlineDv": "x" });
+    commit(table, 0, &[protocol(), metadata(), a])?;
+    let err = DeltaSnapshot::load(table, None).unwrap_err();
+    assert!(format!("{err:#}").contains("deletion vector"), "{err:#}");
+
+    let dir = tempdir()?;
+    let proto = json!({ "protocol": {
+        "minReaderVersion": 3,
+        "minWriterVersion": 7,
+        "readerFeatures": ["variantType"],
+    }});
+    commit(dir.path(), 0, &[proto, metadata()])?;
+    let err = DeltaSnapshot::load(dir.path(), None).unwrap_err();
+    assert!(format!("{err:#}").contains("variantType"), "{err:#}");
+    Ok(())
+}
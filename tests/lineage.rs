@@ -0,0 +1,91 @@
+//! Tests for per-record lineage tracking.
+#![cfg(all(feature = "io-jsonl", feature = "io-csv"))]
+
+use anyhow::Result;
+use ironbeam::*;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+struct Order {
+    region: String,
+    cents: i64,
+}
+
+#[test]
+fn jsonl_lineage_survives_transforms_and_errors() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    fs::write(
+        tmp.path().join("a.jsonl"),
+        "{\"region\":\"eu\",\"cents\":5}\n\n{\"region\":\"us\",\"cents\":-3}\n",
+    )?;
+    fs::write(
+        tmp.path().join("b.jsonl"),
+        "{\"region\":\"eu\",\"cents\":-7}\n",
+    )?;
+    let a = tmp.path().join("a.jsonl").display().to_string();
+    let b = tmp.path().join("b.jsonl").display().to_string();
+
+    let p = Pipeline::default();
+    let orders =
+        read_jsonl_with_lineage::<Order>(&p, tmp.path().join("*.jsonl").to_str().unwrap())?;
+    let (doubled, bad) = orders.clone().map_traced_catching(|o: &Order| {
+        u64::try_from(o.cents)
+            .map(|c| c * 2)
+            .map_err(|e| e.to_string())
+    });
+    assert_eq!(
+        doubled.collect_seq()?,
+        vec![Traced::new(10, vec![Provenance::new(a.clone(), 0, 1)])]
+    );
+    let mut bad: Vec<_> = bad
+        .collect_seq()?
+        .into_iter()
+        .map(|d| d.element.lineage[0].to_string())
+        .collect();
+    bad.sort();
+    assert_eq!(bad, vec![format!("{a}:3"), format!("{b}:1")]);
+
+    let out = tmp.path().join("out.jsonl");
+    let refunds = orders
+        .filter_traced(|o: &Order| o.cents < 0)
+        .map_traced(|o: &Order| o.region.clone());
+    assert_eq!(refunds.clone().write_jsonl_with_lineage(&out)?, 2);
+    let mut written: Vec<Traced<String>> = read_jsonl_vec(&out)?;
+    written.sort_by_key(|t| t.lineage[0].shard);
+    assert_eq!(written[1].value, "eu");
+    assert_eq!(written[1].lineage, vec![Provenance::new(b, 1, 1)]);
+    assert_eq!(
+        refunds.strip_lineage().collect_seq_sorted()?,
+        vec!["eu", "us"]
+    );
+    Ok(())
+}
+
+#[test]
+fn csv_lineage_merges_on_grouping() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let path = tmp.path().join("orders.csv");
+    fs::write(&path, "region,cents\neu,5\nus,2\neu,4\n")?;
+    let source = path.display().to_string();
+
+    let p = Pipeline::default();
+    let mut totals = read_csv_with_lineage::<Order>(&p, &path, true)?
+        .flat_map_traced(|o: &Order| vec![o.clone(); 2])
+        .key_by(|t: &Traced<Order>| t.value.region.clone())
+        .group_by_key_traced()
+        .collect_seq()?;
+    totals.sort_by(|a, b| a.0.cmp(&b.0));
+    let (region, eu) = &totals[0];
+    assert_eq!(region, "eu");
+    assert_eq!(eu.value.len(), 4);
+    let mut records: Vec<u64> = eu.lineage.iter().map(|l| l.record).collect();
+    records.sort_unstable();
+    assert_eq!(records, vec![1, 1, 3, 3]);
+    assert!(
+        eu.lineage
+            .iter()
+            .all(|l| l.source == source && l.shard == 0)
+    );
+    Ok(())
+}
@@ -0,0 +1,116 @@
+//! Tests for pipeline manifests.
+#![cfg(feature = "io-jsonl")]
+
+use anyhow::Result;
+use ironbeam::manifest::{MANIFEST_VERSION, PipelineManifest};
+use ironbeam::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Order {
+    region: String,
+    cents: u64,
+}
+
+#[test]
+fn describes_inputs_outputs_and_transforms() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let input = tmp.path().join("orders.jsonl");
+    let order = |region: &str, cents| Order {
+        region: region.into(),
+        cents,
+    };
+    write_jsonl_vec(&input, &[order("eu", 1), order("us", 2), order("eu", 3)])?;
+
+    let p = Pipeline::default();
+    let orders = read_jsonl::<Order>(&p, &input)?.with_name("orders");
+    let totals = orders
+        .map(|o: &Order| (o.region.clone(), o.cents))
+        .sum_per_key()
+        .declare_output("jsonl", "out/totals.jsonl");
+
+    let manifest = p.describe()?;
+    assert_eq!(manifest.version, MANIFEST_VERSION);
+    assert!(manifest.features.iter().any(|f| f == "io-jsonl"));
+
+    assert_eq!(manifest.inputs.len(), 1);
+    let source = &manifest.inputs[0];
+    assert_eq!(source.name.as_deref(), Some("orders"));
+    assert_eq!(source.format.as_deref(), Some("jsonl"));
+    assert_eq!(source.location, Some(input.display().to_string()));
+    assert_eq!(source.estimated_elements, Some(3));
+
+    assert_eq!(manifest.outputs.len(), 1);
+    let output = &manifest.outputs[0];
+    assert_eq!(output.node, totals.node_id().raw());
+    assert_eq!(output.location.as_deref(), Some("out/totals.jsonl"));
+    assert_eq!(output.inputs, vec![source.node]);
+    assert!(output.suggested_partitions.is_some());
+
+    let kinds: Vec<&str> = manifest
+        .transforms
+        .iter()
+        .map(|t| t.kind.as_str())
+        .collect();
+    assert_eq!(kinds, vec!["Source", "Stateless", "CombineValues"]);
+    assert_eq!(
+        manifest.transforms[2].upstream,
+        vec![manifest.transforms[1].node]
+    );
+    assert_eq!(manifest.resources.barriers, 1);
+    assert_eq!(manifest.resources.input_elements, Some(3));
+
+    assert_eq!(PipelineManifest::from_json(&manifest.to_json()?)?, manifest);
+    Ok(())
+}
+
+#[test]
+fn follows_joins_and_lists_unconsumed_collections() -> Result<()> {
+    let p = Pipeline::default();
+    let left = from_vec(&p, vec![("a".to_string(), 1u32), ("b".to_string(), 2)]);
+    let right = from_vec(&p, vec![("a".to_string(), 'x')]);
+    let joined = left.clone().join_inner(&right);
+    let counted = left.clone().count_globally();
+    let extra = from_vec(&p, vec![7u8]).materialize();
+
+    let manifest = p.describe()?;
+    let outputs: Vec<u64> = manifest.outputs.iter().map(|o| o.node).collect();
+    assert_eq!(
+        outputs,
+        vec![
+            joined.node_id().raw(),
+            counted.node_id().raw(),
+            extra.node_id().raw()
+        ]
+    );
+    assert_eq!(
+        manifest.outputs[0].inputs,
+        vec![left.node_id().raw(), right.node_id().raw()]
+    );
+    assert_eq!(manifest.outputs[1].inputs, vec![left.node_id().raw()]);
+
+    // The join's placeholder source is neither an input nor a transform.
+    assert_eq!(manifest.inputs.len(), 3);
+    assert!(manifest.inputs.iter().all(|i| i.location.is_none()));
+    let join = manifest
+        .transforms
+        .iter()
+        .find(|t| t.node == joined.node_id().raw())
+        .unwrap();
+    assert_eq!(join.kind, "CoGroup");
+    assert_eq!(
+        join.upstream,
+        vec![left.node_id().raw(), right.node_id().raw()]
+    );
+
+    let path = tempfile::tempdir()?
+        .path()
+        .join("missing")
+        .join("manifest.json");
+    let err = p.save_manifest(&path).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("write pipeline manifest"),
+        "{err:#}"
+    );
+    Ok(())
+}
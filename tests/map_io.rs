@@ -0,0 +1,93 @@
+//! Tests for `map_io` and `map_io_unordered`.
+
+use anyhow::Result;
+use ironbeam::*;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// Tracks how many calls are running at once.
+#[derive(Clone, Default)]
+struct Gauge {
+    now: Arc<AtomicUsize>,
+    peak: Arc<AtomicUsize>,
+}
+
+impl Gauge {
+    fn call(&self, x: u64, delay: Duration) -> u64 {
+        let current = self.now.fetch_add(1, Ordering::SeqCst) + 1;
+        self.peak.fetch_max(current, Ordering::SeqCst);
+        std::thread::sleep(delay);
+        self.now.fetch_sub(1, Ordering::SeqCst);
+        x * 10
+    }
+
+    fn peak(&self) -> usize {
+        self.peak.load(Ordering::SeqCst)
+    }
+}
+
+#[test]
+fn map_io_keeps_order_and_bounds_concurrency() -> Result<()> {
+    let gauge = Gauge::default();
+    let g = gauge.clone();
+    let p = Pipeline::default();
+    let out = from_vec(&p, (0..40u64).collect::<Vec<_>>())
+        .map_io(4, move |x: &u64| {
+            g.call(
+                *x,
+                Duration::from_millis(if x.is_multiple_of(3) { 6 } else { 1 }),
+            )
+        })
+        .collect_seq()?;
+
+    assert_eq!(out, (0..40u64).map(|x| x * 10).collect::<Vec<_>>());
+    assert!(gauge.peak() <= 4);
+    assert!(gauge.peak() >= 2, "calls should overlap");
+    Ok(())
+}
+
+#[test]
+fn map_io_limit_is_shared_across_partitions() -> Result<()> {
+    let gauge = Gauge::default();
+    let g = gauge.clone();
+    let p = Pipeline::default();
+    let mut out = from_vec(&p, (0..64u64).collect::<Vec<_>>())
+        .map_io(3, move |x: &u64| g.call(*x, Duration::from_millis(2)))
+        .collect_par(Some(4), Some(8))?;
+    out.sort_unstable();
+
+    assert_eq!(out, (0..64u64).map(|x| x * 10).collect::<Vec<_>>());
+    assert!(gauge.peak() <= 3);
+    Ok(())
+}
+
+#[test]
+fn map_io_unordered_delivers_in_completion_order() -> Result<()> {
+    let p = Pipeline::default();
+    let out = from_vec(&p, vec![0u64, 1, 2, 3])
+        .map_io_unordered(4, |x: &u64| {
+            // Element 0 is much slower than the rest.
+            let delay = if *x == 0 { 100 } else { 1 };
+            std::thread::sleep(Duration::from_millis(delay));
+            *x
+        })
+        .collect_seq()?;
+
+    assert_eq!(out.len(), 4);
+    assert_eq!(out.last(), Some(&0));
+    Ok(())
+}
+
+#[test]
+fn map_io_panics_surface_as_errors() {
+    let p = Pipeline::default();
+    let result = from_vec(&p, vec![1u32, 2, 3])
+        .map_io(2, |x: &u32| {
+            assert!(*x != 2, "lookup failed");
+            *x
+        })
+        .collect_par(None, Some(1));
+    let err = result.unwrap_err();
+    assert!(format!("{err:#}").contains("lookup failed"));
+}
@@ -0,0 +1,102 @@
+//! Tests for `map_with_resource`.
+
+use anyhow::Result;
+use ironbeam::*;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Counts how many resources were built and how many have been dropped.
+#[derive(Clone, Default)]
+struct Lifecycle {
+    opened: Arc<AtomicUsize>,
+    closed: Arc<AtomicUsize>,
+}
+
+impl Lifecycle {
+    fn open(&self, offset: u32) -> Handle {
+        self.opened.fetch_add(1, Ordering::SeqCst);
+        Handle {
+            offset,
+            closed: Arc::clone(&self.closed),
+        }
+    }
+
+    fn counts(&self) -> (usize, usize) {
+        (
+            self.opened.load(Ordering::SeqCst),
+            self.closed.load(Ordering::SeqCst),
+        )
+    }
+}
+
+struct Handle {
+    offset: u32,
+    closed: Arc<AtomicUsize>,
+}
+
+impl Drop for Handle {
+    fn drop(&mut self) {
+        self.closed.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn pipeline(life: &Lifecycle, n: u32) -> (Pipeline, PCollection<u32>) {
+    let p = Pipeline::default();
+    let life = life.clone();
+    let out = from_vec(&p, (0..n).collect::<Vec<_>>())
+        .map_with_resource(move || life.open(100), |h: &Handle, x: &u32| x + h.offset);
+    (p, out)
+}
+
+#[test]
+fn resource_is_built_once_per_partition_and_dropped() -> Result<()> {
+    let life = Lifecycle::default();
+    let (_p, out) = pipeline(&life, 1_000);
+    let mut values = out.collect_par(None, Some(4))?;
+    values.sort_unstable();
+    assert_eq!(values, (100..1_100).collect::<Vec<_>>());
+    assert_eq!(life.counts(), (4, 4));
+    Ok(())
+}
+
+#[test]
+fn sequential_run_builds_one_resource() -> Result<()> {
+    let life = Lifecycle::default();
+    let (_p, out) = pipeline(&life, 10);
+    assert_eq!(out.collect_seq()?, (100..110).collect::<Vec<_>>());
+    assert_eq!(life.counts(), (1, 1));
+    Ok(())
+}
+
+#[test]
+fn streaming_drops_resource_when_stream_ends_early() -> Result<()> {
+    let life = Lifecycle::default();
+    let (p, out) = pipeline(&life, 1_000);
+    let first = out.take(3);
+    let runner = DirectRunner {
+        mode: ExecMode::Streaming,
+        ..DirectRunner::default()
+    };
+    let values: Vec<u32> = runner.run_collect(&p, first.node_id())?;
+    assert_eq!(values, vec![100, 101, 102]);
+    assert_eq!(life.counts(), (1, 1));
+    Ok(())
+}
+
+#[test]
+fn resource_is_dropped_when_stage_panics() {
+    let life = Lifecycle::default();
+    let p = Pipeline::default();
+    let l = life.clone();
+    let out = from_vec(&p, (0u32..100).collect::<Vec<_>>()).map_with_resource(
+        move || l.open(0),
+        |_: &Handle, x: &u32| {
+            assert!(*x != 50, "bad element");
+            *x
+        },
+    );
+    assert!(out.collect_par(None, Some(2)).is_err());
+    let (opened, closed) = life.counts();
+    assert!(opened >= 1);
+    assert_eq!(opened, closed);
+}
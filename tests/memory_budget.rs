@@ -0,0 +1,106 @@
+//! Tests for `DirectRunner::max_memory_bytes`.
+
+use anyhow::Result;
+use ironbeam::memory::estimate_bytes;
+use ironbeam::*;
+
+fn budgeted(mode: ExecMode, budget: usize) -> DirectRunner {
+    DirectRunner {
+        mode,
+        max_memory_bytes: Some(budget),
+        ..DirectRunner::default()
+    }
+}
+
+#[test]
+fn estimate_covers_shallow_size() {
+    let xs: Vec<u64> = (0..100).collect();
+    assert!(estimate_bytes(&xs) >= 100 * size_of::<u64>());
+    assert_eq!(estimate_bytes::<u64>(&[]), 0);
+}
+
+#[test]
+fn budget_raises_source_partitioning() -> Result<()> {
+    let input: Vec<u64> = (0..10_000).collect();
+    let budget = estimate_bytes(&input) / 8;
+
+    let p = Pipeline::default();
+    // One output element per partition.
+    let sizes = from_vec(&p, input).map_batches(usize::MAX, |batch: &[u64]| vec![batch.len()]);
+    let runner = budgeted(
+        ExecMode::Parallel {
+            threads: None,
+            partitions: Some(1),
+        },
+        budget,
+    );
+    let sizes: Vec<usize> = runner.run_collect(&p, sizes.node_id())?;
+    assert!(sizes.len() >= 16, "{sizes:?}");
+    assert_eq!(sizes.iter().sum::<usize>(), 10_000);
+    Ok(())
+}
+
+#[test]
+fn oversized_stage_reports_step_and_partition() {
+    let p = Pipeline::default();
+    let exploded = from_vec(&p, (0u32..64).collect::<Vec<_>>())
+        .flat_map(|x: &u32| vec![u64::from(*x); 1_000])
+        .with_name("explode");
+
+    let err = budgeted(ExecMode::Sequential, 10_000)
+        .run_collect::<u64>(&p, exploded.node_id())
+        .unwrap_err();
+    match err.downcast_ref() {
+        Some(IronbeamError::MemoryBudgetExceeded {
+            node,
+            partition,
+            estimated_bytes,
+            budget,
+        }) => {
+            assert_eq!(node.name.as_deref(), Some("explode"));
+            assert_eq!(*partition, None);
+            assert!(*estimated_bytes > *budget);
+            assert_eq!(*budget, 10_000);
+        }
+        other => panic!("unexpected error: {other:?} ({err})"),
+    }
+
+    let err = budgeted(
+        ExecMode::Parallel {
+            threads: None,
+            partitions: Some(4),
+        },
+        10_000,
+    )
+    .run_collect::<u64>(&p, exploded.node_id())
+    .unwrap_err();
+    assert!(
+        matches!(
+            err.downcast_ref(),
+            Some(IronbeamError::MemoryBudgetExceeded {
+                partition: Some(_),
+                ..
+            })
+        ),
+        "{err}"
+    );
+    assert!(err.to_string().contains("'explode'"), "{err}");
+}
+
+#[test]
+fn generous_budget_changes_nothing() -> Result<()> {
+    let p = Pipeline::default();
+    let doubled = from_vec(&p, (0u32..1_000).collect::<Vec<_>>()).map(|x: &u32| x * 2);
+    let expected = doubled.clone().collect_seq()?;
+    for mode in [
+        ExecMode::Sequential,
+        ExecMode::Parallel {
+            threads: None,
+            partitions: Some(3),
+        },
+    ] {
+        let out: Vec<u32> = budgeted(mode, 1 << 30).run_collect(&p, doubled.node_id())?;
+        assert_eq!(out, expected);
+    }
+    Ok(())
+}
@@ -5,6 +5,7 @@
 use ironbeam::metrics::{
     CounterMetric, GaugeMetric, HistogramMetric, HistogramStats, Metric, MetricsCollector,
 };
+use ironbeam::*;
 use serde_json::json;
 
 #[macro_use]
@@ -384,3 +385,100 @@ fn test_json_sorted_metrics() {
     assert_eq!(json["a_first"]["value"], json!(2));
     assert_eq!(json["m_middle"]["value"], json!(3));
 }
+
+fn keyed_pipeline() -> (Pipeline, PCollection<(u32, u64)>) {
+    let p = Pipeline::default();
+    p.set_metrics(MetricsCollector::new());
+    let sums = from_vec(&p, (0..1_000u32).collect::<Vec<_>>())
+        .filter(|x| x % 4 == 0)
+        .with_name("quarter")
+        .key_by(|x| x % 10)
+        .map_values(|x| u64::from(*x))
+        .combine_values(Sum::<u64>::default())
+        .with_name("sum");
+    (p, sums)
+}
+
+#[test]
+fn test_per_transform_sequential() -> anyhow::Result<()> {
+    let (p, sums) = keyed_pipeline();
+    let id = sums.node_id();
+    assert_eq!(sums.collect_seq()?.len(), 5);
+
+    let report = p.get_metrics().unwrap().report();
+    let steps = report.per_transform();
+    assert_eq!(steps.first().unwrap().node.kind, "Source");
+    assert_eq!(steps.first().unwrap().elements_out, Some(1_000));
+    assert_eq!(steps.first().unwrap().elements_in, None);
+    for (i, t) in steps.iter().enumerate() {
+        assert_eq!(t.node.step, i + 1);
+    }
+
+    let quarter = report
+        .per_transform()
+        .iter()
+        .find(|t| {
+            t.node
+                .name
+                .as_deref()
+                .is_some_and(|n| n.contains("quarter"))
+        })
+        .unwrap();
+    assert_eq!(quarter.elements_in, Some(1_000));
+    assert_eq!(quarter.elements_out, Some(250));
+    assert_eq!(
+        quarter.bytes_out,
+        Some(250 * std::mem::size_of::<(u32, u64)>() as u64)
+    );
+
+    let sum = report.transform("sum").unwrap();
+    assert_eq!(sum.elements_out, Some(5));
+    assert_eq!(report.transform_for_node(id), Some(sum));
+    Ok(())
+}
+
+#[test]
+fn test_per_transform_parallel_matches_sequential_counts() -> anyhow::Result<()> {
+    let (p, sums) = keyed_pipeline();
+    sums.clone().collect_seq()?;
+    let seq: Vec<_> = p
+        .get_metrics()
+        .unwrap()
+        .report()
+        .per_transform()
+        .iter()
+        .map(|t| (t.node.clone(), t.elements_in, t.elements_out))
+        .collect();
+
+    sums.collect_par(Some(2), Some(4))?;
+    let par: Vec<_> = p
+        .get_metrics()
+        .unwrap()
+        .report()
+        .per_transform()
+        .iter()
+        .map(|t| (t.node.clone(), t.elements_in, t.elements_out))
+        .collect();
+    assert_eq!(seq, par);
+    Ok(())
+}
+
+#[test]
+fn test_per_transform_in_json() -> anyhow::Result<()> {
+    let (p, sums) = keyed_pipeline();
+    sums.collect_seq()?;
+
+    let json = p.get_metrics().unwrap().to_json();
+    let transforms = json["transforms"]["value"].as_array().unwrap();
+    assert_eq!(transforms[0]["kind"], json!("Source"));
+    assert_eq!(transforms[0]["elements_out"], json!(1_000));
+    assert!(transforms.iter().any(|t| t["name"] == json!("sum")));
+    Ok(())
+}
+
+#[test]
+fn test_per_transform_absent_without_run() {
+    let collector = MetricsCollector::new();
+    assert!(collector.report().per_transform().is_empty());
+    assert!(collector.to_json().get("transforms").is_none());
+}
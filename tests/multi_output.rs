@@ -6,6 +6,8 @@
 use anyhow::Result;
 use ironbeam::*;
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 // Test fixtures and helper types
 
@@ -665,3 +667,186 @@ fn test_partition_parallel_execution() -> Result<()> {
 
     Ok(())
 }
+
+// Tests for flat_map_multi / partition_by
+
+#[test]
+fn test_flat_map_multi_routes_main_and_side() -> Result<()> {
+    let p = Pipeline::default();
+    let lines = from_vec(
+        &p,
+        vec!["1,2".to_string(), "oops".to_string(), "3,x".to_string()],
+    );
+
+    let (numbers, errors) = lines.flat_map_multi(|line: &String, out| {
+        for tok in line.split(',') {
+            match tok.parse::<u32>() {
+                Ok(n) => out.emit(n),
+                Err(_) => out.emit_side(tok.to_string()),
+            }
+        }
+    });
+
+    assert_eq!(numbers.clone().collect_seq_sorted()?, vec![1, 2, 3]);
+    assert_eq!(
+        errors.clone().collect_seq_sorted()?,
+        vec!["oops".to_string(), "x".to_string()]
+    );
+
+    let mut par = numbers.collect_par(None, Some(3))?;
+    par.sort_unstable();
+    assert_eq!(par, vec![1, 2, 3]);
+    Ok(())
+}
+
+#[test]
+fn test_partition_by_index() -> Result<()> {
+    let p = Pipeline::default();
+    let parts = from_vec(&p, (0u32..10).collect::<Vec<_>>()).partition_by(3, |x| *x as usize % 3);
+    assert_eq!(parts.len(), 3);
+    assert_eq!(parts[0].clone().collect_seq()?, vec![0, 3, 6, 9]);
+    assert_eq!(parts[1].clone().collect_seq()?, vec![1, 4, 7]);
+    let mut last = parts[2].clone().collect_par(None, Some(4))?;
+    last.sort_unstable();
+    assert_eq!(last, vec![2, 5, 8]);
+    Ok(())
+}
+
+#[test]
+fn test_multi_outputs_route_each_element_once() -> Result<()> {
+    let p = Pipeline::default();
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    let counted = Arc::clone(&calls);
+    let (evens, odds) =
+        from_vec(&p, (0u32..10).collect::<Vec<_>>()).flat_map_multi(move |x: &u32, out| {
+            counted.fetch_add(1, Ordering::Relaxed);
+            if x.is_multiple_of(2) {
+                out.emit(*x);
+            } else {
+                out.emit_side(*x);
+            }
+        });
+    assert_eq!(evens.collect_seq()?.len(), 5);
+    assert_eq!(odds.collect_par(None, Some(4))?.len(), 5);
+    assert_eq!(calls.load(Ordering::Relaxed), 10);
+
+    calls.store(0, Ordering::Relaxed);
+    let counted = Arc::clone(&calls);
+    let parts = from_vec(&p, (0u32..10).collect::<Vec<_>>()).partition_by(3, move |x| {
+        counted.fetch_add(1, Ordering::Relaxed);
+        *x as usize % 3
+    });
+    let sizes: Vec<usize> = parts
+        .into_iter()
+        .map(|part| part.collect_seq().map(|v| v.len()))
+        .collect::<Result<_>>()?;
+    assert_eq!(sizes, vec![4, 3, 3]);
+    assert_eq!(calls.load(Ordering::Relaxed), 10);
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "partition_by requires at least one output")]
+fn test_partition_by_zero_outputs_panics() {
+    let p = Pipeline::default();
+    let _ = from_vec(&p, vec![1u8]).partition_by(0, |_| 0);
+}
+
+#[test]
+fn test_split_randomly_is_disjoint_proportional_and_reproducible() -> Result<()> {
+    let p = Pipeline::default();
+    let data: Vec<u32> = (0..10_000).collect();
+    let splits = from_vec(&p, data.clone()).split_randomly(&[0.8, 0.1, 0.1], 7);
+    assert_eq!(splits.len(), 3);
+
+    let seq: Vec<Vec<u32>> = splits
+        .iter()
+        .map(|s| s.clone().collect_seq_sorted())
+        .collect::<Result<_>>()?;
+    let mut all: Vec<u32> = seq.concat();
+    all.sort_unstable();
+    assert_eq!(all, data);
+    assert!((7_700..=8_300).contains(&seq[0].len()));
+    assert!((850..=1_150).contains(&seq[1].len()));
+    assert!((850..=1_150).contains(&seq[2].len()));
+
+    // The same seed reproduces the assignment, in parallel too; another seed changes it.
+    let again = from_vec(&p, data.clone()).split_randomly(&[0.8, 0.1, 0.1], 7);
+    assert_eq!(
+        again[1].clone().collect_par_sorted(Some(4), Some(64))?,
+        seq[1]
+    );
+    let other = from_vec(&p, data).split_randomly(&[0.8, 0.1, 0.1], 8);
+    assert_ne!(other[1].clone().collect_seq_sorted()?, seq[1]);
+    Ok(())
+}
+
+#[test]
+fn test_split_randomly_by_keeps_keys_together() -> Result<()> {
+    let p = Pipeline::default();
+    // (user, score); f64 is not Hash, so split by user instead.
+    let events: Vec<(u32, f64)> = (0..600u32).map(|i| (i % 60, f64::from(i))).collect();
+    let splits = from_vec(&p, events).split_randomly_by(|e| e.0, &[0.0, 1.0, 1.0], 3);
+
+    assert!(splits[0].clone().collect_seq()?.is_empty());
+    let left = splits[1].clone().collect_seq()?;
+    let right = splits[2].clone().collect_seq()?;
+    assert_eq!(left.len() + right.len(), 600);
+    assert!(left.iter().all(|(u, _)| right.iter().all(|(v, _)| u != v)));
+    assert_eq!(left.len() % 10, 0);
+    Ok(())
+}
+
+#[test]
+#[should_panic(expected = "split_randomly: weights must be finite and non-negative")]
+fn test_split_randomly_rejects_negative_weights() {
+    let p = Pipeline::default();
+    let _ = from_vec(&p, vec![1u8]).split_randomly(&[0.5, -0.5], 0);
+}
+
+#[test]
+fn test_flat_map_multi_routes_on_the_callers_runner() -> Result<()> {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let p = Pipeline::default();
+    let counted = Arc::clone(&calls);
+    let (evens, odds) = from_vec(&p, (0u32..20).collect::<Vec<_>>()).flat_map_multi(
+        move |x: &u32, out: &mut MultiOutput<u32, u32>| {
+            counted.fetch_add(1, Ordering::SeqCst);
+            if x.is_multiple_of(2) {
+                out.emit(*x);
+            } else {
+                out.emit_side(*x);
+            }
+        },
+    );
+
+    // The routing pass runs under the caller's token, so a cancelled run never routes.
+    let token = CancellationToken::new();
+    token.cancel();
+    let err = DirectRunner::default()
+        .run_with_cancellation::<u32>(&p, evens.node_id(), &token)
+        .unwrap_err();
+    assert!(
+        matches!(
+            err.downcast_ref::<IronbeamError>(),
+            Some(IronbeamError::Cancelled { .. })
+        ),
+        "{err:#}"
+    );
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+    let runner = DirectRunner {
+        mode: ExecMode::Parallel {
+            threads: Some(2),
+            partitions: Some(4),
+        },
+        ..DirectRunner::default()
+    };
+    let mut out = runner.run_collect::<u32>(&p, odds.node_id())?;
+    out.sort_unstable();
+    assert_eq!(out, (1..20).step_by(2).collect::<Vec<_>>());
+    assert_eq!(evens.collect_seq()?.len(), 10);
+    assert_eq!(calls.load(Ordering::SeqCst), 20);
+    Ok(())
+}
@@ -0,0 +1,189 @@
+//! Tests for runtime attribution of plan steps: typed transform-panic errors and
+//! named-stage metrics.
+
+use ironbeam::helpers::key_value::KeyValueDocument;
+use ironbeam::io::cloud::{CloudIOError, CloudResult, Document, ErrorKind, KeyValueIO};
+use ironbeam::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+fn failing_pipeline(p: &Pipeline) -> PCollection<u32> {
+    from_vec(p, vec!["1".to_string(), "x".to_string()])
+        .map(|s: &String| s.parse::<u32>().expect("not a number"))
+        .with_name("parse_events")
+}
+
+#[test]
+fn panic_in_named_stage_is_reported_as_error() {
+    let p = Pipeline::default();
+    let err = failing_pipeline(&p).collect_seq().unwrap_err();
+    match err.downcast_ref::<IronbeamError>() {
+        Some(IronbeamError::PanicInTransform {
+            node,
+            partition,
+            payload,
+        }) => {
+            assert_eq!(node.step, 2);
+            assert_eq!(node.kind, "Stateless");
+            assert_eq!(node.name.as_deref(), Some("parse_events"));
+            assert_eq!(*partition, None);
+            assert!(payload.starts_with("not a number"), "{payload}");
+        }
+        other => panic!("unexpected error: {other:?}"),
+    }
+    assert!(
+        err.to_string()
+            .starts_with("step 2 (Stateless 'parse_events') panicked: not a number")
+    );
+}
+
+#[test]
+fn parallel_panic_reports_failing_partition() {
+    let p = Pipeline::default();
+    let err = failing_pipeline(&p).collect_par(None, Some(2)).unwrap_err();
+    match err.downcast_ref::<IronbeamError>() {
+        Some(IronbeamError::PanicInTransform {
+            node, partition, ..
+        }) => {
+            assert_eq!(node.name.as_deref(), Some("parse_events"));
+            assert_eq!(*partition, Some(1));
+        }
+        other => panic!("unexpected error: {other:?}"),
+    }
+}
+
+#[test]
+fn unnamed_stage_panic_reports_step() {
+    let p = Pipeline::default();
+    let err = from_vec(&p, vec![0u32, 1])
+        .map(|x| 10 / x)
+        .collect_seq()
+        .unwrap_err();
+    match err.downcast_ref::<IronbeamError>() {
+        Some(IronbeamError::PanicInTransform { node, .. }) => {
+            assert_eq!(node.step, 2);
+            assert_eq!(node.name, None);
+        }
+        other => panic!("unexpected error: {other:?}"),
+    }
+}
+
+#[test]
+fn terminal_type_mismatch_is_typed() {
+    let p = Pipeline::default();
+    let out = from_vec(&p, vec![1u32, 2]).map(|x| x + 1);
+    let err = DirectRunner::default()
+        .run_collect::<String>(&p, out.node_id())
+        .unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<IronbeamError>(),
+        Some(IronbeamError::TypeMismatch { node: None, .. })
+    ));
+}
+
+#[test]
+fn names_do_not_change_results() -> anyhow::Result<()> {
+    let p = Pipeline::default();
+    let out = from_vec(&p, vec![1u32, 2, 3])
+        .map(|x| x * 2)
+        .with_name("double")
+        .filter(|x| *x > 2)
+        .with_name("drop_small")
+        .collect_seq()?;
+    assert_eq!(out, vec![4, 6]);
+    Ok(())
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn named_stage_time_is_recorded_in_metrics() -> anyhow::Result<()> {
+    use ironbeam::metrics::MetricsCollector;
+
+    let p = Pipeline::default();
+    p.set_metrics(MetricsCollector::new());
+    from_vec(&p, (0..1_000u32).collect::<Vec<_>>())
+        .map(|x| x + 1)
+        .with_name("bump")
+        .collect_par(None, Some(4))?;
+
+    let metrics = p.take_metrics().expect("metrics were set");
+    assert!(metrics.snapshot().contains_key("stage.bump.micros"));
+    Ok(())
+}
+
+/// A key-value store whose every call fails, so a step looking documents up fails.
+struct UnavailableStore;
+
+impl KeyValueIO for UnavailableStore {
+    fn put(&self, _: &str, _: &str, _: HashMap<String, String>) -> CloudResult<()> {
+        Err(unavailable())
+    }
+    fn get(&self, _: &str, _: &str) -> CloudResult<Option<Document>> {
+        Err(unavailable())
+    }
+    fn delete(&self, _: &str, _: &str) -> CloudResult<()> {
+        Err(unavailable())
+    }
+    fn query(&self, _: &str, _: HashMap<String, String>) -> CloudResult<Vec<Document>> {
+        Err(unavailable())
+    }
+    fn batch_get(&self, _: &str, _: Vec<String>) -> CloudResult<Vec<Option<Document>>> {
+        Err(unavailable())
+    }
+    fn batch_put(&self, _: &str, _: Vec<(String, HashMap<String, String>)>) -> CloudResult<()> {
+        Err(unavailable())
+    }
+    fn exists(&self, _: &str, _: &str) -> CloudResult<bool> {
+        Err(unavailable())
+    }
+}
+
+fn unavailable() -> CloudIOError {
+    CloudIOError::new(ErrorKind::ServiceUnavailable, "store is down")
+}
+
+fn failing_lookup(p: &Pipeline) -> PCollection<(String, u32)> {
+    from_vec(p, vec!["a".to_string(), "b".to_string()])
+        .enrich_from_key_value(Arc::new(UnavailableStore), "users", String::clone)
+        .unwrap()
+        .map(|(k, d): &(String, Option<KeyValueDocument>)| (k.clone(), u32::from(d.is_some())))
+        .with_name("lookup")
+}
+
+fn assert_lookup_failed(err: &anyhow::Error) {
+    match err.downcast_ref::<IronbeamError>() {
+        Some(IronbeamError::TransformFailed { message, .. }) => {
+            assert!(message.contains("store is down"), "{message}");
+        }
+        other => panic!("unexpected error: {other:?} ({err:#})"),
+    }
+}
+
+#[test]
+fn step_failure_in_streaming_mode_is_reported_as_error() {
+    let p = Pipeline::default();
+    let looked_up = failing_lookup(&p);
+    let runner = DirectRunner {
+        mode: ExecMode::Streaming,
+        ..DirectRunner::default()
+    };
+    let err = runner
+        .run_collect::<(String, u32)>(&p, looked_up.node_id())
+        .unwrap_err();
+    assert_lookup_failed(&err);
+}
+
+#[test]
+fn step_failure_in_join_input_is_reported_as_error() {
+    let p = Pipeline::default();
+    let other = from_vec(&p, vec![("a".to_string(), 'x')]);
+    for parallel in [false, true] {
+        let joined = failing_lookup(&p).join_inner(&other);
+        let err = if parallel {
+            joined.collect_par(None, Some(2)).unwrap_err()
+        } else {
+            joined.collect_seq().unwrap_err()
+        };
+        assert_lookup_failed(&err);
+    }
+}
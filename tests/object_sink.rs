@@ -0,0 +1,266 @@
+//! Tests for uploads to object storage and the `write_*_object` sinks.
+#![cfg(all(feature = "io-jsonl", feature = "io-csv"))]
+
+use anyhow::Result;
+use ironbeam::io::cloud::upload::{ObjectWriteOptions, upload_object};
+use ironbeam::io::cloud::utils::RetryConfig;
+use ironbeam::io::cloud::{
+    CloudIOError, CloudResult, ErrorKind, FakeObjectIO, ObjectIO, ObjectMetadata,
+};
+use ironbeam::*;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+#[derive(Clone, Serialize, Deserialize, PartialEq, Debug)]
+struct Row {
+    id: u32,
+    name: String,
+}
+
+fn rows(p: &Pipeline, n: u32) -> PCollection<Row> {
+    from_vec(p, (0..n).collect::<Vec<_>>()).map(|id: &u32| Row {
+        id: *id,
+        name: format!("row-{id}"),
+    })
+}
+
+fn keys(storage: &dyn ObjectIO, bucket: &str) -> CloudResult<Vec<String>> {
+    Ok(storage
+        .list_objects(bucket, None)?
+        .into_iter()
+        .map(|o| o.key)
+        .collect())
+}
+
+const fn fast_retry() -> RetryConfig {
+    RetryConfig {
+        max_attempts: 3,
+        initial_delay_ms: 1,
+        max_delay_ms: 1,
+        backoff_multiplier: 2.0,
+    }
+}
+
+/// Wraps a [`FakeObjectIO`] and fails the first `failures` part uploads with `kind`.
+struct Flaky {
+    inner: FakeObjectIO,
+    multipart: bool,
+    kind: ErrorKind,
+    failures: AtomicU32,
+}
+
+impl Flaky {
+    fn new(multipart: bool, kind: ErrorKind, failures: u32) -> Self {
+        Self {
+            inner: FakeObjectIO::new(),
+            multipart,
+            kind,
+            failures: AtomicU32::new(failures),
+        }
+    }
+
+    fn fail(&self) -> CloudResult<()> {
+        let left = self.failures.load(Ordering::SeqCst);
+        if left == 0 {
+            return Ok(());
+        }
+        self.failures.store(left - 1, Ordering::SeqCst);
+        Err(CloudIOError::new(self.kind.clone(), "injected failure"))
+    }
+}
+
+impl ObjectIO for Flaky {
+    fn put_object(&self, bucket: &str, key: &str, data: &[u8]) -> CloudResult<()> {
+        if !self.multipart {
+            self.fail()?;
+        }
+        self.inner.put_object(bucket, key, data)
+    }
+    fn get_object(&self, bucket: &str, key: &str) -> CloudResult<Vec<u8>> {
+        self.inner.get_object(bucket, key)
+    }
+    fn delete_object(&self, bucket: &str, key: &str) -> CloudResult<()> {
+        self.inner.delete_object(bucket, key)
+    }
+    fn list_objects(&self, bucket: &str, prefix: Option<&str>) -> CloudResult<Vec<ObjectMetadata>> {
+        self.inner.list_objects(bucket, prefix)
+    }
+    fn object_exists(&self, bucket: &str, key: &str) -> CloudResult<bool> {
+        self.inner.object_exists(bucket, key)
+    }
+    fn get_metadata(&self, bucket: &str, key: &str) -> CloudResult<ObjectMetadata> {
+        self.inner.get_metadata(bucket, key)
+    }
+    fn copy_object(&self, sb: &str, sk: &str, db: &str, dk: &str) -> CloudResult<()> {
+        self.inner.copy_object(sb, sk, db, dk)
+    }
+    fn supports_multipart(&self) -> bool {
+        self.multipart
+    }
+    fn create_multipart_upload(&self, bucket: &str, key: &str) -> CloudResult<String> {
+        self.inner.create_multipart_upload(bucket, key)
+    }
+    fn upload_part(
+        &self,
+        bucket: &str,
+        key: &str,
+        upload_id: &str,
+        part_number: u32,
+        data: &[u8],
+    ) -> CloudResult<String> {
+        self.fail()?;
+        self.inner
+            .upload_part(bucket, key, upload_id, part_number, data)
+    }
+    fn complete_multipart_upload(
+        &self,
+        bucket: &str,
+        key: &str,
+        upload_id: &str,
+        etags: &[String],
+    ) -> CloudResult<()> {
+        self.inner
+            .complete_multipart_upload(bucket, key, upload_id, etags)
+    }
+    fn abort_multipart_upload(&self, bucket: &str, key: &str, upload_id: &str) -> CloudResult<()> {
+        self.inner.abort_multipart_upload(bucket, key, upload_id)
+    }
+}
+
+#[test]
+fn large_objects_are_uploaded_in_parts() -> CloudResult<()> {
+    let storage = FakeObjectIO::new();
+    let data: Vec<u8> = (0..=255u8).cycle().take(10_000).collect();
+    let opts = ObjectWriteOptions::default().with_part_size(1_024);
+    upload_object(&storage, "b", "big.bin", &data, &opts)?;
+    assert_eq!(storage.get_object("b", "big.bin")?, data);
+    assert_eq!(storage.pending_uploads(), 0);
+    Ok(())
+}
+
+#[test]
+fn transient_part_failures_are_retried() -> CloudResult<()> {
+    let storage = Flaky::new(true, ErrorKind::Network, 2);
+    let data = vec![7u8; 100];
+    let opts = ObjectWriteOptions::default()
+        .with_part_size(10)
+        .with_retry(fast_retry());
+    upload_object(&storage, "b", "k", &data, &opts)?;
+    assert_eq!(storage.get_object("b", "k")?, data);
+    assert_eq!(storage.failures.load(Ordering::SeqCst), 0);
+    Ok(())
+}
+
+#[test]
+fn failed_multipart_upload_is_aborted() {
+    let storage = Flaky::new(true, ErrorKind::InvalidInput, 1);
+    let opts = ObjectWriteOptions::default()
+        .with_part_size(10)
+        .with_retry(fast_retry());
+    let err = upload_object(&storage, "b", "k", &[1u8; 50], &opts).unwrap_err();
+    assert_eq!(err.kind, ErrorKind::InvalidInput);
+    assert!(!storage.object_exists("b", "k").unwrap());
+    assert_eq!(storage.inner.pending_uploads(), 0);
+}
+
+#[test]
+fn stores_without_multipart_get_single_retried_puts() -> CloudResult<()> {
+    let storage = Flaky::new(false, ErrorKind::Timeout, 2);
+    let data = vec![1u8; 100];
+    let opts = ObjectWriteOptions::default()
+        .with_part_size(10)
+        .with_retry(fast_retry());
+    upload_object(&storage, "b", "k", &data, &opts)?;
+    assert_eq!(storage.get_object("b", "k")?, data);
+    Ok(())
+}
+
+#[test]
+fn jsonl_object_per_shard() -> Result<()> {
+    let storage = FakeObjectIO::new();
+    let p = Pipeline::default();
+    let opts = ObjectWriteOptions::default()
+        .with_shards(3)
+        .with_part_size(256)
+        .with_success_marker(true);
+
+    let n = rows(&p, 90).write_jsonl_object(&storage, "out", "run/", &opts)?;
+    assert_eq!(n, 90);
+    assert_eq!(
+        keys(&storage, "out")?,
+        vec![
+            "run/_SUCCESS",
+            "run/part-00000.jsonl",
+            "run/part-00001.jsonl",
+            "run/part-00002.jsonl"
+        ]
+    );
+
+    let mut back = Vec::new();
+    for i in 0..3 {
+        let bytes = storage.get_object("out", &format!("run/part-{i:05}.jsonl"))?;
+        for line in String::from_utf8(bytes)?.lines() {
+            back.push(serde_json::from_str::<Row>(line)?);
+        }
+    }
+    assert_eq!(back, rows(&p, 90).collect_seq()?);
+    Ok(())
+}
+
+#[test]
+fn csv_objects_are_self_contained() -> Result<()> {
+    let storage = FakeObjectIO::new();
+    let p = Pipeline::default();
+    let opts = ObjectWriteOptions::default().with_shards(2);
+
+    assert_eq!(
+        rows(&p, 10).write_csv_object(&storage, "out", "csv/", true, &opts)?,
+        10
+    );
+    assert_eq!(
+        keys(&storage, "out")?,
+        vec!["csv/part-00000.csv", "csv/part-00001.csv"]
+    );
+    for key in keys(&storage, "out")? {
+        let text = String::from_utf8(storage.get_object("out", &key)?)?;
+        assert!(text.starts_with("id,name\n"), "{key}: {text}");
+        assert_eq!(text.lines().count(), 6);
+    }
+    Ok(())
+}
+
+#[test]
+fn failed_upload_fails_the_write() {
+    let storage = Flaky::new(false, ErrorKind::Authorization, 1);
+    let p = Pipeline::default();
+    let opts = ObjectWriteOptions::default()
+        .with_shards(1)
+        .with_success_marker(true);
+    let err = rows(&p, 5)
+        .write_jsonl_object(&storage, "out", "", &opts)
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("injected failure"), "{err:#}");
+    assert!(!storage.object_exists("out", "_SUCCESS").unwrap());
+}
+
+#[cfg(feature = "io-parquet")]
+#[test]
+fn parquet_object_per_shard() -> Result<()> {
+    let storage = FakeObjectIO::new();
+    let p = Pipeline::default();
+    let opts = ObjectWriteOptions::default().with_shards(2);
+    assert_eq!(
+        rows(&p, 40).write_parquet_object(&storage, "out", "pq/", &opts)?,
+        40
+    );
+
+    let tmp = tempfile::tempdir()?;
+    let mut back = Vec::new();
+    for key in keys(&storage, "out")? {
+        let file = tmp.path().join(key.replace('/', "_"));
+        std::fs::write(&file, storage.get_object("out", &key)?)?;
+        back.extend(read_parquet_vec::<Row>(&file)?);
+    }
+    assert_eq!(back, rows(&p, 40).collect_seq()?);
+    Ok(())
+}
@@ -0,0 +1,159 @@
+//! Tests for the `tracing` spans emitted by the runner under the `otel` feature.
+
+#![cfg(feature = "otel")]
+
+use anyhow::Result;
+use ironbeam::*;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+#[derive(Debug, Clone)]
+struct CapturedSpan {
+    name: &'static str,
+    parent: Option<u64>,
+    fields: HashMap<&'static str, String>,
+}
+
+/// Minimal subscriber that records every span with its parent and fields.
+#[derive(Clone, Default)]
+struct Capture {
+    next_id: Arc<AtomicU64>,
+    spans: Arc<Mutex<HashMap<u64, CapturedSpan>>>,
+    stack: Arc<Mutex<Vec<u64>>>,
+}
+
+struct Fields<'a>(&'a mut HashMap<&'static str, String>);
+
+impl Visit for Fields<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.0.insert(field.name(), format!("{value:?}"));
+    }
+}
+
+impl Subscriber for Capture {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let parent = attrs.parent().map(Id::into_u64).or_else(|| {
+            attrs
+                .is_contextual()
+                .then(|| self.stack.lock().unwrap().last().copied())
+                .flatten()
+        });
+        let mut fields = HashMap::new();
+        attrs.record(&mut Fields(&mut fields));
+        self.spans.lock().unwrap().insert(
+            id,
+            CapturedSpan {
+                name: attrs.metadata().name(),
+                parent,
+                fields,
+            },
+        );
+        Id::from_u64(id)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        if let Some(s) = self.spans.lock().unwrap().get_mut(&span.into_u64()) {
+            values.record(&mut Fields(&mut s.fields));
+        }
+    }
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, _: &Event<'_>) {}
+
+    fn enter(&self, span: &Id) {
+        self.stack.lock().unwrap().push(span.into_u64());
+    }
+
+    fn exit(&self, _: &Id) {
+        self.stack.lock().unwrap().pop();
+    }
+}
+
+/// Run `f` under a fresh capturing subscriber and return the recorded spans by id.
+fn capture(f: impl FnOnce() -> Result<()>) -> Result<Vec<(u64, CapturedSpan)>> {
+    let sub = Capture::default();
+    tracing::subscriber::with_default(sub.clone(), f)?;
+    let mut spans: Vec<_> = sub.spans.lock().unwrap().clone().into_iter().collect();
+    spans.sort_by_key(|(id, _)| *id);
+    Ok(spans)
+}
+
+#[test]
+fn run_and_stage_spans_are_nested() -> Result<()> {
+    let spans = capture(|| {
+        let p = Pipeline::default();
+        from_vec(&p, (0..100u32).collect::<Vec<_>>())
+            .map(|x| x * 2)
+            .with_name("double")
+            .key_by(|x| x % 3)
+            .group_by_key()
+            .collect_seq()?;
+        Ok(())
+    })?;
+
+    let runs: Vec<_> = spans
+        .iter()
+        .filter(|(_, s)| s.name == "ironbeam.run")
+        .collect();
+    assert_eq!(runs.len(), 1);
+    let (run_id, run) = runs[0];
+    assert_eq!(run.fields["mode"], "Sequential");
+
+    let stages: Vec<_> = spans
+        .iter()
+        .map(|(_, s)| s)
+        .filter(|s| s.name == "ironbeam.stage")
+        .collect();
+    assert_eq!(run.fields["steps"], stages.len().to_string());
+    assert!(stages.iter().all(|s| s.parent == Some(*run_id)));
+    assert!(stages.iter().all(|s| s.fields.contains_key("duration_us")));
+
+    assert_eq!(stages[0].fields["kind"], "Source");
+    assert!(!stages[0].fields.contains_key("partitions_in"));
+    assert_eq!(stages[0].fields["partitions_out"], "1");
+    assert!(
+        stages
+            .iter()
+            .any(|s| s.fields.get("name").is_some_and(|n| n.contains("double")))
+    );
+    assert_eq!(stages.last().unwrap().fields["kind"], "GroupByKey");
+    Ok(())
+}
+
+#[test]
+fn parallel_stage_spans_report_partition_counts() -> Result<()> {
+    let spans = capture(|| {
+        let p = Pipeline::default();
+        from_vec(&p, (0..1_000u32).collect::<Vec<_>>())
+            .filter(|x| x % 2 == 0)
+            .collect_par(None, Some(4))?;
+        Ok(())
+    })?;
+
+    let stages: Vec<_> = spans
+        .iter()
+        .map(|(_, s)| s)
+        .filter(|s| s.name == "ironbeam.stage")
+        .collect();
+    assert_eq!(stages.len(), 2);
+    assert_eq!(stages[0].fields["partitions_out"], "4");
+    assert_eq!(stages[1].fields["kind"], "Stateless");
+    assert_eq!(stages[1].fields["partitions_in"], "4");
+    assert_eq!(stages[1].fields["partitions_out"], "4");
+    Ok(())
+}
@@ -0,0 +1,67 @@
+//! Tests for `bench::PipelineBench`.
+#![cfg(feature = "bench")]
+
+use anyhow::Result;
+use ironbeam::bench::PipelineBench;
+use ironbeam::*;
+
+fn squares(p: &Pipeline) -> PCollection<u64> {
+    from_vec(p, (0u64..10_000).collect::<Vec<_>>())
+        .map_batches(512, |xs: &[u64]| xs.iter().map(|x| x * x).collect())
+        .filter(|x: &u64| x.is_multiple_of(2))
+}
+
+#[test]
+fn bench_covers_thread_partition_matrix() -> Result<()> {
+    let report = PipelineBench::new("squares", squares)
+        .iterations(3)
+        .warmup(0)
+        .threads([1, 2])
+        .partitions([2, 4, 8])
+        .run()?;
+
+    assert_eq!(report.results.len(), 6);
+    let point = report.result(Some(2), Some(8)).expect("matrix point");
+    assert_eq!(point.times.len(), 3);
+    assert_eq!(point.elements, 10_000);
+    assert_eq!(point.bytes, 80_000);
+    assert!(point.min() <= point.median() && point.median() <= point.max());
+    assert!(report.fastest().is_some());
+    Ok(())
+}
+
+#[test]
+fn bench_reports_per_stage_timings() -> Result<()> {
+    let report = PipelineBench::new("squares", squares).iterations(2).run()?;
+    let point = &report.results[0];
+    assert_eq!(point.threads, None);
+    assert_eq!(point.partitions, None);
+    assert!(point.stages.len() >= 2);
+    assert_eq!(point.stages[0].step.step, 1);
+    assert!(
+        point
+            .stages
+            .iter()
+            .all(|s| s.min <= s.mean && s.mean <= s.max)
+    );
+
+    let text = report.to_string();
+    assert!(text.starts_with("bench 'squares' (2 iterations)"), "{text}");
+    assert!(text.contains("default"), "{text}");
+    assert!(text.contains("step 1"), "{text}");
+    Ok(())
+}
+
+#[test]
+fn bench_uses_explicit_input_size() -> Result<()> {
+    let report = PipelineBench::new("words", |p: &Pipeline| {
+        from_vec(p, vec!["alpha".to_string(), "beta".to_string()]).map(|w: &String| w.len())
+    })
+    .iterations(1)
+    .input_size(2, 9)
+    .run()?;
+    let point = &report.results[0];
+    assert_eq!((point.elements, point.bytes), (2, 9));
+    assert!(point.elements_per_sec() > 0.0);
+    Ok(())
+}
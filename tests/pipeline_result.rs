@@ -0,0 +1,105 @@
+//! Tests for `PipelineResult` and the `collect_*_result` methods.
+
+use anyhow::Result;
+use ironbeam::*;
+
+fn multiples_of_three(p: &Pipeline) -> PCollection<u32> {
+    from_vec(p, (0u32..300).collect::<Vec<_>>())
+        .filter(|x: &u32| x.is_multiple_of(3))
+        .with_name("multiples")
+}
+
+#[test]
+fn sequential_result_matches_collect_seq() -> Result<()> {
+    let p = Pipeline::default();
+    let expected = multiples_of_three(&p).collect_seq()?;
+    let result = multiples_of_three(&p).collect_seq_result()?;
+    assert_eq!(result.data, expected);
+    assert!(matches!(result.mode, ExecMode::Sequential));
+    assert_eq!(result.partitions, 1);
+    assert!(!result.stages.is_empty());
+    assert!(
+        result
+            .stages
+            .iter()
+            .all(|s| s.wall_time <= result.wall_time)
+    );
+    assert_eq!(result.into_data(), expected);
+    Ok(())
+}
+
+#[test]
+fn parallel_result_reports_partitions_and_counts() -> Result<()> {
+    let p = Pipeline::default();
+    let result = multiples_of_three(&p).collect_par_result(Some(2), Some(4))?;
+    assert_eq!(result.data.len(), 100);
+    assert_eq!(result.partitions, 4);
+
+    let stage = result.stage("multiples").expect("named stage is reported");
+    assert_eq!(stage.elements, Some(100));
+    assert_eq!(stage.partitions, 4);
+    assert_eq!(result.stages.last().unwrap().elements, Some(100));
+    Ok(())
+}
+
+#[test]
+fn empty_source_result_has_no_stages() -> Result<()> {
+    let p = Pipeline::default();
+    let out = from_vec(&p, Vec::<u32>::new()).map(|x: &u32| x + 1);
+    let result = DirectRunner::default().run_with_result::<u32>(&p, out.node_id())?;
+    assert!(result.data.is_empty());
+    assert_eq!(result.partitions, 0);
+    assert!(result.stages.is_empty());
+    Ok(())
+}
+
+#[test]
+fn ignored_partition_hint_is_reported_as_warning() -> Result<()> {
+    use ironbeam::combiners::Sum;
+    use ironbeam::validation::DiagnosticKind;
+
+    let p = Pipeline::default();
+    let sums = from_vec(&p, vec![("a".to_string(), 1u32), ("b".to_string(), 2)])
+        .combine_values(Sum::<u32>::default())
+        .map(|(k, v): &(String, u32)| format!("{k}={v}"))
+        .with_partitions(8);
+    let result = sums.collect_par_result(None, Some(4))?;
+    assert_eq!(result.data.len(), 2);
+    assert!(
+        result
+            .warnings
+            .iter()
+            .any(|d| d.kind == DiagnosticKind::IgnoredPartitionHint)
+    );
+    Ok(())
+}
+
+#[cfg(feature = "checkpointing")]
+#[test]
+fn checkpoint_activity_is_summarized() -> Result<()> {
+    use ironbeam::checkpoint::{CheckpointConfig, CheckpointPolicy};
+
+    let dir = tempfile::tempdir()?;
+    let p = Pipeline::default();
+    let out = from_vec(&p, (0u32..100).collect::<Vec<_>>())
+        .key_by(|x: &u32| x % 5)
+        .group_by_key()
+        .map_values(Vec::len);
+    let runner = DirectRunner {
+        mode: ExecMode::Sequential,
+        checkpoint_config: Some(CheckpointConfig {
+            enabled: true,
+            directory: dir.path().to_path_buf(),
+            policy: CheckpointPolicy::AfterEveryBarrier,
+            auto_recover: false,
+            max_checkpoints: Some(10),
+            ..CheckpointConfig::default()
+        }),
+        ..DirectRunner::default()
+    };
+    let result = runner.run_with_result::<(u32, usize)>(&p, out.node_id())?;
+    let summary = result.checkpoints.expect("checkpointing was enabled");
+    assert!(summary.saved >= 1);
+    assert_eq!(summary.resumed_after, None);
+    Ok(())
+}
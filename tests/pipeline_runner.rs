@@ -0,0 +1,101 @@
+//! Tests for the `PipelineRunner` backend trait.
+
+use anyhow::{Result, bail};
+use ironbeam::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// A backend that records how often it runs and delegates to the direct engine.
+#[derive(Default)]
+struct CountingRunner {
+    runs: AtomicUsize,
+}
+
+impl PipelineRunner for CountingRunner {
+    fn run_collect<T: Element>(&self, p: &Pipeline, terminal: NodeId) -> Result<Vec<T>> {
+        self.runs.fetch_add(1, Ordering::SeqCst);
+        let direct = DirectRunner {
+            mode: ExecMode::Sequential,
+            ..DirectRunner::default()
+        };
+        direct.run_collect(p, terminal)
+    }
+}
+
+/// A backend that cannot run anything.
+struct Unavailable;
+
+impl PipelineRunner for Unavailable {
+    fn run_collect<T: Element>(&self, _: &Pipeline, _: NodeId) -> Result<Vec<T>> {
+        bail!("backend unavailable")
+    }
+}
+
+#[derive(Default)]
+struct Collect {
+    items: Vec<u32>,
+    closed: bool,
+}
+
+impl Sink<u32> for Collect {
+    fn write_batch(&mut self, batch: &[u32]) -> Result<()> {
+        self.items.extend_from_slice(batch);
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<()> {
+        self.closed = true;
+        Ok(())
+    }
+}
+
+#[test]
+fn collect_with_uses_the_given_backend() -> Result<()> {
+    let runner = CountingRunner::default();
+    let p = Pipeline::default();
+    let out = from_vec(&p, vec![1u32, 2, 3])
+        .map(|x: &u32| x * 10)
+        .collect_with(&runner)?;
+    assert_eq!(out, vec![10, 20, 30]);
+    assert_eq!(runner.runs.load(Ordering::SeqCst), 1);
+    Ok(())
+}
+
+#[test]
+fn default_run_to_sink_drives_the_sink() -> Result<()> {
+    let runner = CountingRunner::default();
+    let p = Pipeline::default();
+    let evens = from_vec(&p, (1..=6u32).collect::<Vec<_>>()).filter(|x: &u32| x.is_multiple_of(2));
+
+    let mut sink = Collect::default();
+    let written = evens.write_to_with(&runner, &mut sink)?;
+    assert_eq!(written, 3);
+    assert_eq!(sink.items, vec![2, 4, 6]);
+    assert!(sink.closed);
+    assert_eq!(runner.runs.load(Ordering::SeqCst), 1);
+    Ok(())
+}
+
+#[test]
+fn backend_errors_propagate() {
+    let p = Pipeline::default();
+    let data = from_vec(&p, vec![1u32]);
+    let err = data.clone().collect_with(&Unavailable).unwrap_err();
+    assert_eq!(err.to_string(), "backend unavailable");
+    assert!(
+        data.write_to_with(&Unavailable, Collect::default())
+            .is_err()
+    );
+}
+
+#[test]
+fn direct_runner_matches_the_runner_alias() -> Result<()> {
+    let p = Pipeline::default();
+    let data = from_vec(&p, vec![3u32, 1, 2]);
+    let mut direct: Vec<u32> =
+        PipelineRunner::run_collect(&DirectRunner::default(), &p, data.node_id())?;
+    let mut legacy = DirectRunner::default().run_collect::<u32>(&p, data.node_id())?;
+    direct.sort_unstable();
+    legacy.sort_unstable();
+    assert_eq!(direct, legacy);
+    Ok(())
+}
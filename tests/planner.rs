@@ -3,7 +3,7 @@ use ironbeam::from_vec;
 use ironbeam::node::Node;
 use ironbeam::testing::*;
 use ironbeam::{
-    OptimizationDecision, PCollection, Pipeline, Runner, SharedCSECache, build_plan,
+    DirectRunner, OptimizationDecision, PCollection, Pipeline, SharedCSECache, build_plan,
     cogroup_by_key, flatten,
 };
 
@@ -95,9 +95,9 @@ fn cse_shared_prefix_executes_once() -> Result<()> {
     let b = mapped.map(|x: &u32| x + 1);
 
     let cache = SharedCSECache::default();
-    let runner = Runner {
+    let runner = DirectRunner {
         mode: ironbeam::ExecMode::Sequential,
-        ..Runner::default()
+        ..DirectRunner::default()
     };
 
     let mut out_a = runner.run_collect_cached::<u32>(&p, a.node_id(), &cache)?;
@@ -1471,9 +1471,9 @@ fn cse_fanout_still_works_with_dominator_approach() -> Result<()> {
     let b = mapped.map(|x: &u32| x + 1);
 
     let cache = SharedCSECache::default();
-    let runner = Runner {
+    let runner = DirectRunner {
         mode: ironbeam::ExecMode::Sequential,
-        ..Runner::default()
+        ..DirectRunner::default()
     };
 
     let mut out_a = runner.run_collect_cached::<u32>(&p, a.node_id(), &cache)?;
@@ -1508,9 +1508,9 @@ fn cse_flatten_pipeline_correct_results() -> Result<()> {
     let terminal = joined.map(|x: &u32| x + 1); // [12,13,14,3,5,7]
 
     let cache = SharedCSECache::default();
-    let runner = Runner {
+    let runner = DirectRunner {
         mode: ironbeam::ExecMode::Sequential,
-        ..Runner::default()
+        ..DirectRunner::default()
     };
 
     let mut out1 = runner.run_collect_cached::<u32>(&p, terminal.node_id(), &cache)?;
@@ -1544,9 +1544,9 @@ fn cse_linear_pipeline_caches_on_repeated_calls() -> Result<()> {
         .map(|x: &u32| x * 2);
 
     let cache = SharedCSECache::default();
-    let runner = Runner {
+    let runner = DirectRunner {
         mode: ironbeam::ExecMode::Sequential,
-        ..Runner::default()
+        ..DirectRunner::default()
     };
 
     // First call: executes the full pipeline and caches at idom(terminal).
@@ -1690,7 +1690,7 @@ fn empty_source_runner_returns_empty_vec() -> Result<()> {
         *x * 2
     });
 
-    let runner = Runner::default();
+    let runner = DirectRunner::default();
     let out = runner.run_collect::<u32>(&p, result.node_id())?;
     assert!(out.is_empty(), "result must be empty for empty source");
     assert_eq!(
@@ -1750,7 +1750,7 @@ fn singleton_source_runner_correct_result() -> Result<()> {
     let p = Pipeline::default();
     let data = from_vec(&p, vec![7u32]);
     let result = data.map(|x| x * 3).filter(|x| *x > 0);
-    let runner = Runner::default();
+    let runner = DirectRunner::default();
     let out = runner.run_collect::<u32>(&p, result.node_id())?;
     assert_eq!(out, vec![21u32]);
     Ok(())
@@ -1863,12 +1863,16 @@ fn display_unreachable_branches_render() {
         is_barrier: false,
         cost_hint: 1,
         name: None,
+        estimated_elements: None,
+        estimated_bytes: None,
     };
     let cost = CostEstimate {
         barriers: 0,
         total_ops: 1,
         stateless_ops: 0,
         source_size: Some(1),
+        estimated_output_elements: None,
+        estimated_peak_bytes: None,
     };
 
     let dropped = ExecutionExplanation {
@@ -1939,7 +1943,7 @@ fn adaptive_partition_count_correctness_gbk() -> Result<()> {
         ],
     );
     let grouped = data.group_by_key();
-    let runner = Runner::default();
+    let runner = DirectRunner::default();
     let mut result = runner.run_collect::<(String, Vec<u32>)>(&p, grouped.node_id())?;
     result.sort_by_key(|(k, _)| k.clone());
     for (_, v) in &mut result {
@@ -1954,3 +1958,339 @@ fn adaptive_partition_count_correctness_gbk() -> Result<()> {
     );
     Ok(())
 }
+
+/// `filter_keys` after a `map_values` runs first, so the mapping closure only sees
+/// rows whose key survives the filter.
+#[test]
+fn key_filter_hoisted_ahead_of_map_values() -> Result<()> {
+    use std::sync::Arc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    let p = TestPipeline::new();
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&calls);
+    let data: Vec<(u32, u32)> = (0..100).map(|i| (i % 10, i)).collect();
+
+    let out = from_vec(&p, data)
+        .map_values(move |v: &u32| {
+            counter.fetch_add(1, Ordering::Relaxed);
+            v * 2
+        })
+        .filter_keys(|k: &u32| *k == 3);
+
+    let plan = build_plan(&p, out.node_id())?;
+    assert!(
+        plan.optimizations
+            .iter()
+            .any(|o| matches!(o, OptimizationDecision::HoistedKeyFilters { filters: 1 }))
+    );
+
+    let result = out.collect_seq_sorted()?;
+    let expected: Vec<(u32, u32)> = (0..100)
+        .filter(|i| i % 10 == 3)
+        .map(|i| (3, i * 2))
+        .collect();
+    assert_eq!(result, expected);
+    assert_eq!(calls.load(Ordering::Relaxed), 10);
+    Ok(())
+}
+
+/// A key filter hops over several key-preserving maps, rebuilt for each one's input.
+#[test]
+fn key_filter_hoisted_across_value_type_changes() -> Result<()> {
+    let p = TestPipeline::new();
+    let out = from_vec(&p, vec![("a".to_string(), 1u32), ("b".to_string(), 2)])
+        .map_values(|v: &u32| u64::from(*v) + 1)
+        .map_values(|v: &u64| format!("#{v}"))
+        .filter_keys(|k: &String| k == "b");
+
+    let plan = build_plan(&p, out.node_id())?;
+    let Some(Node::Stateless(ops)) = plan.chain.get(1) else {
+        panic!("expected a fused stateless stage after the source");
+    };
+    assert!(ops[0].key_predicate().is_some(), "filter should run first");
+
+    assert_eq!(
+        out.collect_par_sorted(None, None)?,
+        vec![("b".to_string(), "#3".to_string())]
+    );
+    Ok(())
+}
+
+/// `take(usize::MAX)` is a pass-through and is removed before execution.
+#[test]
+fn planner_drops_noop_take() -> Result<()> {
+    let p = TestPipeline::new();
+    let out = from_vec(&p, (1..=5).collect::<Vec<u32>>()).take(usize::MAX);
+
+    let plan = build_plan(&p, out.node_id())?;
+    assert!(
+        plan.optimizations
+            .iter()
+            .any(|o| matches!(o, OptimizationDecision::DroppedNoOps { count: 1 }))
+    );
+    assert!(
+        !plan
+            .chain
+            .iter()
+            .any(|n| matches!(n, Node::Stateless(ops) if ops.is_empty())),
+        "no empty stateless stage should remain"
+    );
+    assert!(
+        !plan
+            .optimizations
+            .iter()
+            .any(|o| matches!(o, OptimizationDecision::LimitPushdown { .. }))
+    );
+
+    assert_eq!(out.collect_seq()?, vec![1, 2, 3, 4, 5]);
+    Ok(())
+}
+
+/// Sums each group's values; adding a group in pieces gives the same total.
+struct SumGroups;
+
+impl ironbeam::CombineFn<Vec<u64>, u64, u64> for SumGroups {
+    fn create(&self) -> u64 {
+        0
+    }
+    fn add_input(&self, acc: &mut u64, vs: Vec<u64>) {
+        *acc += vs.iter().sum::<u64>();
+    }
+    fn merge(&self, acc: &mut u64, other: u64) {
+        *acc += other;
+    }
+    fn finish(&self, acc: u64) -> u64 {
+        acc
+    }
+    fn accepts_partial_groups(&self) -> bool {
+        true
+    }
+}
+
+/// Counts groups; splitting a group changes the count, so it must not be lifted.
+struct CountGroups;
+
+impl ironbeam::CombineFn<Vec<u64>, u64, u64> for CountGroups {
+    fn create(&self) -> u64 {
+        0
+    }
+    fn add_input(&self, acc: &mut u64, _vs: Vec<u64>) {
+        *acc += 1;
+    }
+    fn merge(&self, acc: &mut u64, other: u64) {
+        *acc += other;
+    }
+    fn finish(&self, acc: u64) -> u64 {
+        acc
+    }
+}
+
+fn keyed_u64(p: &Pipeline) -> PCollection<(u32, u64)> {
+    from_vec(
+        p,
+        (0..1_000u64)
+            .map(|i| ((i % 7) as u32, i))
+            .collect::<Vec<_>>(),
+    )
+}
+
+fn expected_sums() -> Vec<(u32, u64)> {
+    (0..7u32)
+        .map(|k| (k, (0..1_000u64).filter(|i| i % 7 == u64::from(k)).sum()))
+        .collect()
+}
+
+/// `group_by_key().combine_values(c)` is lifted when `c` accepts partial groups.
+#[test]
+fn planner_lifts_gbk_then_splittable_combine() -> Result<()> {
+    let p = TestPipeline::new();
+    let sums = keyed_u64(&p).group_by_key().combine_values(SumGroups);
+
+    let plan = build_plan(&p, sums.node_id())?;
+    assert!(plan.optimizations.iter().any(|o| matches!(
+        o,
+        OptimizationDecision::LiftedGroupAggregation {
+            combines: 1,
+            aggregations: 0
+        }
+    )));
+    assert!(
+        !plan
+            .chain
+            .iter()
+            .any(|n| matches!(n, Node::GroupByKey { .. }))
+    );
+
+    assert_eq!(sums.collect_par_sorted(Some(8), None)?, expected_sums());
+    Ok(())
+}
+
+/// The associative built-in combiners combine groups value by value, so an ordinary
+/// `group_by_key().combine_values(Sum::new())` gets a pre-shuffle combine step.
+#[test]
+fn planner_lifts_gbk_then_builtin_combines() -> Result<()> {
+    use ironbeam::combiners::{Count, DistinctCount, Max, Min, Sum};
+
+    fn assert_lifted(p: &Pipeline, id: ironbeam::NodeId) -> Result<()> {
+        let plan = build_plan(p, id)?;
+        assert!(plan.optimizations.iter().any(|o| matches!(
+            o,
+            OptimizationDecision::LiftedGroupAggregation { combines: 1, .. }
+        )));
+        assert!(
+            !plan
+                .chain
+                .iter()
+                .any(|n| matches!(n, Node::GroupByKey { .. }))
+        );
+        Ok(())
+    }
+
+    let p = TestPipeline::new();
+    let sums = keyed_u64(&p).group_by_key().combine_values(Sum::new());
+    assert_lifted(&p, sums.node_id())?;
+    assert_eq!(sums.collect_par_sorted(None, Some(8))?, expected_sums());
+
+    let counts = keyed_u64(&p)
+        .group_by_key()
+        .combine_values(Count::<u64>::new());
+    assert_lifted(&p, counts.node_id())?;
+    let expected: Vec<(u32, u64)> = (0..7u64)
+        .map(|k| {
+            (
+                k as u32,
+                (0..1_000u64).filter(|i| i % 7 == k).count() as u64,
+            )
+        })
+        .collect();
+    assert_eq!(counts.collect_par_sorted(None, Some(8))?, expected);
+
+    let mins = keyed_u64(&p)
+        .group_by_key()
+        .combine_values(Min::<u64>::new());
+    let maxes = keyed_u64(&p)
+        .group_by_key()
+        .combine_values(Max::<u64>::new());
+    let distinct = keyed_u64(&p)
+        .map_values(|v: &u64| v % 3)
+        .group_by_key()
+        .combine_values(DistinctCount::<u64>::new());
+    for id in [mins.node_id(), maxes.node_id(), distinct.node_id()] {
+        assert_lifted(&p, id)?;
+    }
+    assert_eq!(
+        mins.collect_par_sorted(None, Some(8))?,
+        (0..7u32).map(|k| (k, u64::from(k))).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        maxes.collect_par_sorted(None, Some(8))?,
+        (0..7u32)
+            .map(|k| (
+                k,
+                (0..1_000u64)
+                    .filter(|i| i % 7 == u64::from(k))
+                    .max()
+                    .unwrap()
+            ))
+            .collect::<Vec<_>>()
+    );
+    assert_eq!(
+        distinct.collect_par_sorted(None, Some(8))?,
+        (0..7u32).map(|k| (k, 3u64)).collect::<Vec<_>>()
+    );
+    Ok(())
+}
+
+/// A combiner that does not accept partial groups keeps the full `group_by_key`.
+#[test]
+fn planner_keeps_gbk_for_group_sensitive_combine() -> Result<()> {
+    let p = TestPipeline::new();
+    let counts = keyed_u64(&p).group_by_key().combine_values(CountGroups);
+
+    let plan = build_plan(&p, counts.node_id())?;
+    assert!(
+        !plan
+            .optimizations
+            .iter()
+            .any(|o| matches!(o, OptimizationDecision::LiftedGroupAggregation { .. }))
+    );
+    assert_eq!(
+        counts.collect_par_sorted(Some(8), None)?,
+        (0..7u32).map(|k| (k, 1)).collect::<Vec<_>>()
+    );
+    Ok(())
+}
+
+/// The untyped `Count` treats each group as one element, so it is not lifted:
+/// splitting a group across partitions would change the count.
+#[test]
+fn planner_keeps_gbk_for_builtin_combine_over_groups() -> Result<()> {
+    let p = TestPipeline::new();
+    let groups = keyed_u64(&p).group_by_key().combine_values(Count);
+
+    let plan = build_plan(&p, groups.node_id())?;
+    assert!(
+        !plan
+            .optimizations
+            .iter()
+            .any(|o| matches!(o, OptimizationDecision::LiftedGroupAggregation { .. }))
+    );
+    assert!(
+        plan.chain
+            .iter()
+            .any(|n| matches!(n, Node::GroupByKey { .. }))
+    );
+    assert_eq!(
+        groups.collect_par_sorted(None, Some(8))?,
+        (0..7u32).map(|k| (k, 1)).collect::<Vec<_>>()
+    );
+    Ok(())
+}
+
+/// `group_by_key().map_values(f).with_partial_merge(g)` is lifted, and ops after the
+/// aggregation still run.
+#[test]
+fn planner_lifts_gbk_then_merged_map_values() -> Result<()> {
+    let p = TestPipeline::new();
+    let doubled = keyed_u64(&p)
+        .group_by_key()
+        .map_values(|vs: &Vec<u64>| vs.iter().sum::<u64>())
+        .with_partial_merge(|a, b| a + b)
+        .map_values(|s: &u64| s * 2);
+
+    let plan = build_plan(&p, doubled.node_id())?;
+    assert!(plan.optimizations.iter().any(|o| matches!(
+        o,
+        OptimizationDecision::LiftedGroupAggregation {
+            combines: 0,
+            aggregations: 1
+        }
+    )));
+
+    let expected: Vec<(u32, u64)> = expected_sums()
+        .into_iter()
+        .map(|(k, s)| (k, s * 2))
+        .collect();
+    assert_eq!(doubled.clone().collect_par_sorted(Some(8), None)?, expected);
+    assert_eq!(doubled.collect_seq_sorted()?, expected);
+    Ok(())
+}
+
+/// Without a merge hint, `group_by_key().map_values(f)` is left alone.
+#[test]
+fn planner_does_not_lift_unhinted_map_values() -> Result<()> {
+    let p = TestPipeline::new();
+    let sums = keyed_u64(&p)
+        .group_by_key()
+        .map_values(|vs: &Vec<u64>| vs.iter().sum::<u64>());
+
+    let plan = build_plan(&p, sums.node_id())?;
+    assert!(
+        plan.chain
+            .iter()
+            .any(|n| matches!(n, Node::GroupByKey { .. }))
+    );
+    assert_eq!(sums.collect_par_sorted(Some(8), None)?, expected_sums());
+    Ok(())
+}
@@ -0,0 +1,138 @@
+//! Tests for the Prometheus / OpenMetrics exporter.
+
+#![cfg(feature = "metrics")]
+
+use anyhow::Result;
+use ironbeam::metrics::{
+    CounterMetric, GaugeMetric, HistogramMetric, MetricsCollector, PrometheusExporter,
+};
+use ironbeam::*;
+
+fn sample_lines(text: &str) -> Vec<&str> {
+    text.lines().filter(|l| !l.starts_with('#')).collect()
+}
+
+#[test]
+fn renders_registered_metrics() {
+    let mut collector = MetricsCollector::new();
+    collector.register(Box::new(CounterMetric::with_value("rows.read", 7)));
+    collector.register(Box::new(
+        GaugeMetric::new("queue depth", 2.5).with_description("Pending work"),
+    ));
+    collector.register(Box::new(HistogramMetric::with_values(
+        "latency",
+        vec![1.0, 2.0, 3.0, 4.0],
+    )));
+
+    let text = PrometheusExporter::new(collector).render();
+    assert!(text.contains("# TYPE ironbeam_rows_read_total counter\n"));
+    assert!(text.contains("ironbeam_rows_read_total 7\n"));
+    assert!(text.contains("# HELP ironbeam_queue_depth Pending work\n"));
+    assert!(text.contains("ironbeam_queue_depth 2.5\n"));
+    assert!(text.contains("# TYPE ironbeam_latency summary\n"));
+    assert!(text.contains("ironbeam_latency{quantile=\"0.5\"} 3\n"));
+    assert!(text.contains("ironbeam_latency_sum 10\n"));
+    assert!(text.contains("ironbeam_latency_count 4\n"));
+    assert!(!text.contains("# EOF"));
+}
+
+#[test]
+fn openmetrics_uses_family_names_and_eof() {
+    let mut collector = MetricsCollector::new();
+    collector.register(Box::new(CounterMetric::with_value("events_total", 3)));
+
+    let text = PrometheusExporter::new(collector)
+        .with_namespace("")
+        .render_openmetrics();
+    assert!(text.contains("# TYPE events counter\n"));
+    assert!(text.contains("events_total 3\n"));
+    assert!(text.ends_with("# EOF\n"));
+}
+
+#[test]
+fn renders_per_transform_metrics_after_a_run() -> Result<()> {
+    let p = Pipeline::default();
+    let collector = MetricsCollector::new();
+    p.set_metrics(collector.clone());
+    from_vec(&p, (0..10u32).collect::<Vec<_>>())
+        .filter(|x| x % 2 == 0)
+        .with_name("even \"ones\"")
+        .collect_seq()?;
+
+    let text = PrometheusExporter::new(collector)
+        .with_namespace("etl")
+        .render();
+    assert!(text.contains("# TYPE etl_pipeline_execution_seconds gauge\n"));
+    assert!(text.contains("etl_transform_elements_out{step=\"1\",kind=\"Source\"} 10\n"));
+    assert!(text.contains(
+        "etl_transform_elements_out{step=\"2\",kind=\"Stateless\",name=\"even \\\"ones\\\"\"} 5\n"
+    ));
+    assert!(
+        sample_lines(&text)
+            .iter()
+            .any(|l| l.starts_with("etl_transform_wall_seconds{step=\"2\""))
+    );
+    Ok(())
+}
+
+#[test]
+fn write_to_file_replaces_contents() -> Result<()> {
+    let dir = tempfile::TempDir::new()?;
+    let path = dir.path().join("ironbeam.prom");
+    let collector = MetricsCollector::new();
+    let exporter = PrometheusExporter::new(collector.clone());
+
+    collector.set_counter("runs", 1);
+    exporter.write_to_file(&path)?;
+    collector.set_counter("runs", 2);
+    exporter.write_to_file(&path)?;
+
+    let text = std::fs::read_to_string(&path)?;
+    assert!(text.contains("ironbeam_runs_total 2\n"));
+    assert_eq!(std::fs::read_dir(dir.path())?.count(), 1);
+    Ok(())
+}
+
+#[cfg(feature = "metrics-http")]
+mod http {
+    use super::*;
+    use ironbeam::metrics::{OPENMETRICS_CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE};
+    use std::io::{Read, Write};
+    use std::net::{SocketAddr, TcpStream};
+
+    fn get(addr: SocketAddr, path: &str, accept: &str) -> Result<String> {
+        let mut stream = TcpStream::connect(addr)?;
+        write!(
+            stream,
+            "GET {path} HTTP/1.1\r\nHost: localhost\r\nAccept: {accept}\r\n\r\n"
+        )?;
+        let mut response = String::new();
+        stream.read_to_string(&mut response)?;
+        Ok(response)
+    }
+
+    #[test]
+    fn serves_live_metrics() -> Result<()> {
+        let collector = MetricsCollector::new();
+        let server = PrometheusExporter::new(collector.clone()).serve("127.0.0.1:0")?;
+        let addr = server.local_addr();
+
+        collector.set_counter("scrapes", 1);
+        let first = get(addr, "/metrics", "text/plain")?;
+        assert!(first.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(first.contains(&format!("Content-Type: {PROMETHEUS_CONTENT_TYPE}")));
+        assert!(first.contains("ironbeam_scrapes_total 1\n"));
+
+        collector.set_counter("scrapes", 2);
+        let second = get(addr, "/metrics", "application/openmetrics-text")?;
+        assert!(second.contains(&format!("Content-Type: {OPENMETRICS_CONTENT_TYPE}")));
+        assert!(second.contains("ironbeam_scrapes_total 2\n"));
+        assert!(second.ends_with("# EOF\n"));
+
+        assert!(get(addr, "/nope", "*/*")?.starts_with("HTTP/1.1 404 Not Found\r\n"));
+
+        server.shutdown();
+        assert!(TcpStream::connect(addr).is_err());
+        Ok(())
+    }
+}
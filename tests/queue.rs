@@ -0,0 +1,104 @@
+//! Tests for draining a `QueueIO` work queue with `read_queue`.
+
+use anyhow::{Result, bail};
+use ironbeam::io::cloud::{FakeQueueIO, QueueIO};
+use ironbeam::*;
+use std::collections::HashMap;
+
+fn queue_with(n: u32) -> Result<FakeQueueIO> {
+    let queue = FakeQueueIO::new();
+    for i in 0..n {
+        queue.send("jobs", &i.to_string(), HashMap::new())?;
+    }
+    Ok(queue)
+}
+
+fn parse(body: &str) -> Result<u32> {
+    Ok(body.parse()?)
+}
+
+#[test]
+fn successful_run_deletes_the_messages() -> Result<()> {
+    let queue = queue_with(25)?;
+    let p = Pipeline::default();
+    let batch = read_queue(&p, &queue, "jobs", 100, 30, parse)?;
+    assert_eq!(batch.len(), 25);
+    assert_eq!(queue.in_flight("jobs"), 25);
+
+    let out = batch.run(|jobs| jobs.map(|n: &u32| n + 1).collect_seq())?;
+    assert_eq!(out, (1..=25).collect::<Vec<_>>());
+    assert_eq!(queue.in_flight("jobs"), 0);
+    assert_eq!(queue.queue_size("jobs")?, 0);
+    Ok(())
+}
+
+#[test]
+fn max_messages_caps_the_batch() -> Result<()> {
+    let queue = queue_with(25)?;
+    let p = Pipeline::default();
+    let batch = read_queue(&p, &queue, "jobs", 12, 30, parse)?;
+    assert_eq!(
+        batch.collection().collect_seq()?,
+        (0..12).collect::<Vec<_>>()
+    );
+    assert_eq!(queue.queue_size("jobs")?, 13);
+    Ok(())
+}
+
+#[test]
+fn failed_run_leaves_messages_in_flight() -> Result<()> {
+    let queue = queue_with(3)?;
+    let p = Pipeline::default();
+    let err = read_queue(&p, &queue, "jobs", 10, 30, parse)?
+        .run(|_| -> Result<()> { bail!("worker crashed") })
+        .unwrap_err();
+    assert_eq!(err.to_string(), "worker crashed");
+    assert_eq!(queue.in_flight("jobs"), 3);
+    assert_eq!(queue.queue_size("jobs")?, 0);
+    Ok(())
+}
+
+#[test]
+fn failed_run_can_redeliver_at_once() -> Result<()> {
+    let queue = queue_with(3)?;
+    let p = Pipeline::default();
+    let err = read_queue(&p, &queue, "jobs", 10, 30, parse)?
+        .with_redelivery_on_failure(true)
+        .run(|_| -> Result<()> { bail!("worker crashed") })
+        .unwrap_err();
+    assert_eq!(err.to_string(), "worker crashed");
+    assert_eq!(queue.in_flight("jobs"), 0);
+
+    let redelivered = queue.receive("jobs", 10, 30)?;
+    assert_eq!(
+        redelivered
+            .iter()
+            .map(|m| m.body.as_str())
+            .collect::<Vec<_>>(),
+        vec!["0", "1", "2"]
+    );
+    assert!(redelivered.iter().all(|m| m.receive_count == 2));
+    Ok(())
+}
+
+#[test]
+fn bad_messages_fail_the_read() -> Result<()> {
+    let queue = queue_with(2)?;
+    let bad = queue.send("jobs", "not a number", HashMap::new())?;
+    let p = Pipeline::default();
+    let Err(err) = read_queue(&p, &queue, "jobs", 10, 30, parse) else {
+        panic!("the last message should not deserialize");
+    };
+    assert!(format!("{err:#}").contains(&bad), "{err:#}");
+    Ok(())
+}
+
+#[test]
+fn empty_queue_gives_an_empty_batch() -> Result<()> {
+    let queue = FakeQueueIO::new();
+    let p = Pipeline::default();
+    let batch = read_queue(&p, &queue, "jobs", 10, 30, parse)?;
+    assert!(batch.is_empty());
+    assert_eq!(batch.run(PCollection::collect_seq)?, Vec::<u32>::new());
+    Ok(())
+}
@@ -0,0 +1,116 @@
+//! Tests for `PCollection::cache_persistent` and `ResultCache`.
+#![cfg(feature = "result-cache")]
+
+use anyhow::Result;
+use ironbeam::result_cache::ResultCache;
+use ironbeam::*;
+use std::fs;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tempfile::TempDir;
+
+/// Build `data -> map(x * 10) -> cache_persistent -> map(x + 1)` on a fresh pipeline,
+/// counting calls to the cached stage, and collect it.
+fn run(cache: &ResultCache, data: Vec<u64>, calls: &Arc<AtomicUsize>) -> Result<Vec<u64>> {
+    let calls = Arc::clone(calls);
+    let p = Pipeline::default();
+    from_vec(&p, data)
+        .map(move |x: &u64| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            x * 10
+        })
+        .cache_persistent(cache)
+        .map(|x: &u64| x + 1)
+        .collect_seq()
+}
+
+#[test]
+fn unchanged_upstream_is_loaded_from_disk() -> Result<()> {
+    let dir = TempDir::new()?;
+    let cache = ResultCache::new(dir.path());
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    assert_eq!(run(&cache, vec![1, 2, 3], &calls)?, vec![11, 21, 31]);
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+    // A new pipeline with the same upstream skips the cached stage.
+    assert_eq!(run(&cache, vec![1, 2, 3], &calls)?, vec![11, 21, 31]);
+    assert_eq!(calls.load(Ordering::SeqCst), 3);
+    Ok(())
+}
+
+#[test]
+fn changed_source_data_or_salt_recomputes() -> Result<()> {
+    let dir = TempDir::new()?;
+    let cache = ResultCache::new(dir.path());
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    run(&cache, vec![1, 2, 3], &calls)?;
+    assert_eq!(run(&cache, vec![1, 2, 4], &calls)?, vec![11, 21, 41]);
+    assert_eq!(calls.load(Ordering::SeqCst), 6);
+
+    run(&cache.clone().with_salt("v2"), vec![1, 2, 4], &calls)?;
+    assert_eq!(calls.load(Ordering::SeqCst), 9);
+    Ok(())
+}
+
+#[test]
+fn declared_inputs_invalidate_on_change() -> Result<()> {
+    let dir = TempDir::new()?;
+    let input = dir.path().join("lookup.txt");
+    fs::write(&input, "a")?;
+    let cache = ResultCache::new(dir.path().join("cache")).with_input(&input);
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    run(&cache, vec![1], &calls)?;
+    run(&cache, vec![1], &calls)?;
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    fs::write(&input, "a longer value")?;
+    run(&cache, vec![1], &calls)?;
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    Ok(())
+}
+
+#[test]
+fn key_ignores_unrelated_nodes_but_tracks_upstream_shape() -> Result<()> {
+    let cache = ResultCache::new("unused");
+
+    let p = Pipeline::default();
+    let nums = from_vec(&p, vec![1u32, 2, 3]).map(|x: &u32| x * 2);
+    let before = cache.key(&nums)?;
+    let _unrelated = from_vec(&p, vec![9u32]).map(|x: &u32| x + 1);
+    assert_eq!(cache.key(&nums)?, before);
+
+    let q = Pipeline::default();
+    let _built_first = from_vec(&q, vec!["x".to_string()]);
+    let same = from_vec(&q, vec![1u32, 2, 3]).map(|x: &u32| x * 2);
+    assert_eq!(cache.key(&same)?, before);
+
+    let longer = nums.filter(|x: &u32| *x > 2);
+    assert_ne!(cache.key(&longer)?, before);
+    Ok(())
+}
+
+#[test]
+fn corrupt_results_are_recomputed_and_clear_removes_them() -> Result<()> {
+    let dir = TempDir::new()?;
+    let cache = ResultCache::new(dir.path());
+    let calls = Arc::new(AtomicUsize::new(0));
+
+    run(&cache, vec![5, 6], &calls)?;
+    let stored: Vec<_> = fs::read_dir(dir.path())?.collect::<Result<_, _>>()?;
+    assert_eq!(stored.len(), 1);
+    let path = stored[0].path();
+    let mut bytes = fs::read(&path)?;
+    *bytes.last_mut().unwrap() ^= 0xff;
+    fs::write(&path, bytes)?;
+
+    assert_eq!(run(&cache, vec![5, 6], &calls)?, vec![51, 61]);
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+    assert_eq!(cache.clear()?, 1);
+    run(&cache, vec![5, 6], &calls)?;
+    assert_eq!(calls.load(Ordering::SeqCst), 6);
+    Ok(())
+}
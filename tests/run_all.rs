@@ -0,0 +1,198 @@
+//! Tests for `Pipeline::run_all` with `materialize` and `write_to_sink` outputs.
+
+use anyhow::Result;
+use ironbeam::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Words from two lines, counting how many lines the tokenizer has processed.
+fn words(p: &Pipeline, calls: &Arc<AtomicUsize>) -> PCollection<String> {
+    let calls = Arc::clone(calls);
+    from_vec(p, vec!["a b c".to_string(), "b c".to_string()]).flat_map(move |line: &String| {
+        calls.fetch_add(1, Ordering::SeqCst);
+        line.split(' ').map(str::to_string).collect::<Vec<_>>()
+    })
+}
+
+/// A sink that appends into shared storage.
+#[derive(Clone, Default)]
+struct Shared(Arc<Mutex<Vec<String>>>);
+
+impl Sink<String> for Shared {
+    fn write_batch(&mut self, batch: &[String]) -> Result<()> {
+        self.0.lock().unwrap().extend_from_slice(batch);
+        Ok(())
+    }
+}
+
+#[test]
+fn run_all_delivers_every_output_and_shares_the_prefix() -> Result<()> {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let p = Pipeline::default();
+    let words = words(&p, &calls);
+
+    let sink = Shared::default();
+    let written = words.clone().write_to_sink(sink.clone());
+    let upper = words
+        .clone()
+        .map(|w: &String| w.to_uppercase())
+        .materialize();
+    let counts = words
+        .key_by(|w: &String| w.clone())
+        .map_values(|_| 1u64)
+        .combine_values(Count)
+        .materialize();
+
+    let delivered = p.run_all(&[written, upper.node_id(), counts.node_id()])?;
+
+    assert_eq!(delivered, vec![5, 5, 3]);
+    assert_eq!(*sink.0.lock().unwrap(), vec!["a", "b", "c", "b", "c"]);
+    let mut upper = upper.take()?;
+    upper.sort();
+    assert_eq!(upper, vec!["A", "B", "B", "C", "C"]);
+    let mut counts = counts.take()?;
+    counts.sort();
+    assert_eq!(
+        counts,
+        vec![
+            ("a".to_string(), 1),
+            ("b".to_string(), 2),
+            ("c".to_string(), 2)
+        ]
+    );
+    assert_eq!(
+        calls.load(Ordering::SeqCst),
+        2,
+        "tokenizer ran once per line"
+    );
+    Ok(())
+}
+
+#[test]
+fn separate_collects_recompute_the_prefix() -> Result<()> {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let p = Pipeline::default();
+    let words = words(&p, &calls);
+
+    words.clone().collect_seq()?;
+    words.count_globally().collect_seq()?;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+    Ok(())
+}
+
+#[test]
+fn nested_forks_are_each_computed_once() -> Result<()> {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let later = Arc::new(AtomicUsize::new(0));
+    let p = Pipeline::default();
+    let l = Arc::clone(&later);
+    let words = words(&p, &calls);
+    let long = words.clone().filter(move |w: &String| {
+        l.fetch_add(1, Ordering::SeqCst);
+        w != "a"
+    });
+
+    let all = words.materialize();
+    let long_words = long.clone().materialize();
+    let long_total = long.count_globally().materialize();
+    p.run_all(&[all.node_id(), long_words.node_id(), long_total.node_id()])?;
+
+    assert_eq!(all.take()?.len(), 5);
+    assert_eq!(long_words.take()?.len(), 4);
+    assert_eq!(long_total.take()?, vec![4]);
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+    assert_eq!(later.load(Ordering::SeqCst), 5);
+    Ok(())
+}
+
+#[test]
+fn run_all_rejects_unregistered_and_already_run_outputs() -> Result<()> {
+    let p = Pipeline::default();
+    let nums = from_vec(&p, vec![1u32, 2, 3]);
+    let out = nums.clone().materialize();
+
+    assert!(p.run_all(&[nums.node_id()]).is_err());
+    assert!(out.take().is_err(), "nothing has run yet");
+
+    assert_eq!(p.run_all(&[out.node_id()])?, vec![3]);
+    assert_eq!(out.take()?, vec![1, 2, 3]);
+    assert!(p.run_all(&[out.node_id()]).is_err());
+    Ok(())
+}
+
+#[cfg(all(feature = "io-jsonl", feature = "io-csv", feature = "io-parquet"))]
+#[test]
+fn file_sinks_write_every_file_in_one_pass() -> Result<()> {
+    #[derive(Clone, serde::Serialize, serde::Deserialize, PartialEq, Debug)]
+    struct Word {
+        word: String,
+    }
+
+    let tmp = tempfile::tempdir()?;
+    let calls = Arc::new(AtomicUsize::new(0));
+    let p = Pipeline::default();
+    let words = words(&p, &calls).map(|w: &String| Word { word: w.clone() });
+
+    let jsonl = words
+        .clone()
+        .write_to_sink(JsonlSink::new(tmp.path().join("out/words.jsonl")));
+    let csv = words
+        .clone()
+        .write_to_sink(CsvSink::new(tmp.path().join("words.csv"), true).with_partitions(3));
+    let parquet = words
+        .clone()
+        .write_to_sink(ParquetSink::new(tmp.path().join("words.parquet")).with_partitions(2));
+    assert_eq!(p.run_all(&[jsonl, csv, parquet])?, vec![5, 5, 5]);
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+    let expected = words.collect_seq()?;
+    assert_eq!(
+        read_jsonl_vec::<Word>(tmp.path().join("out/words.jsonl"))?,
+        expected
+    );
+    assert_eq!(
+        read_csv_vec::<Word>(tmp.path().join("words.csv"), true)?,
+        expected
+    );
+    assert_eq!(
+        read_parquet_vec::<Word>(tmp.path().join("words.parquet"))?,
+        expected
+    );
+    // Only the outputs are left; the part files are gone.
+    let mut left: Vec<_> = std::fs::read_dir(tmp.path())?
+        .map(|e| e.map(|e| e.file_name()))
+        .collect::<Result<_, _>>()?;
+    left.sort();
+    assert_eq!(left, vec!["out", "words.csv", "words.parquet"]);
+    Ok(())
+}
+
+#[cfg(feature = "io-jsonl")]
+#[test]
+fn run_all_with_runs_on_the_given_configuration() -> Result<()> {
+    let tmp = tempfile::tempdir()?;
+    let file = tmp.path().join("words.jsonl");
+    let calls = Arc::new(AtomicUsize::new(0));
+    let p = Pipeline::default();
+    let words = words(&p, &calls);
+    let written = words.clone().write_to_sink(JsonlSink::new(&file));
+    let total = words.count_globally().materialize();
+
+    let token = CancellationToken::new();
+    token.cancel();
+    let config = RunnerConfig::builder().cancellation(token).build();
+    let err = p
+        .run_all_with(&config, &[written, total.node_id()])
+        .unwrap_err();
+    assert!(
+        matches!(
+            err.downcast_ref::<IronbeamError>(),
+            Some(IronbeamError::Cancelled { .. })
+        ),
+        "expected Cancelled, got {err:#}"
+    );
+    assert!(!file.exists());
+    assert_eq!(calls.load(Ordering::SeqCst), 0);
+    Ok(())
+}
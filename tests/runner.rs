@@ -2,7 +2,7 @@ use anyhow::Result;
 use ironbeam::collection::{CombineFn, Count};
 use ironbeam::flatten;
 use ironbeam::from_vec;
-use ironbeam::runner::{ExecMode, Runner};
+use ironbeam::runner::{DirectRunner, ExecMode};
 use ironbeam::testing::*;
 
 fn sorted<T: Ord>(mut v: Vec<T>) -> Vec<T> {
@@ -361,13 +361,15 @@ fn parallel_multi_partition_collection() -> Result<()> {
     Ok(())
 }
 
-/// Test `Runner::default()` uses parallel mode
+/// Test `DirectRunner::default()` uses parallel mode
 #[test]
 fn runner_default_is_parallel() {
-    let runner = Runner::default();
+    let runner = DirectRunner::default();
     match runner.mode {
         ExecMode::Parallel { .. } => (),
-        ExecMode::Sequential => panic!("Default runner should use parallel mode"),
+        ExecMode::Sequential | ExecMode::Streaming => {
+            panic!("Default runner should use parallel mode")
+        }
     }
     assert!(runner.default_partitions >= 2);
 }
@@ -512,17 +514,18 @@ fn combine_global_unlimited_fanout() -> Result<()> {
 /// Test runner with custom mode configuration
 #[test]
 fn custom_runner_mode() -> Result<()> {
-    use ironbeam::runner::{ExecMode, Runner};
+    use ironbeam::runner::{DirectRunner, ExecMode};
 
     let p = TestPipeline::new();
     let data = from_vec(&p, vec![1u32, 2, 3, 4, 5]);
     let mapped = data.map(|x: &u32| x * 2);
 
-    let runner = Runner {
+    let runner = DirectRunner {
         mode: ExecMode::Sequential,
         default_partitions: 4,
         #[cfg(feature = "checkpointing")]
         checkpoint_config: None,
+        ..DirectRunner::default()
     };
 
     let result = runner.run_collect::<u32>(&p, mapped.node_id())?;
@@ -749,7 +752,9 @@ fn exec_par_limit_truncate_single_partition_result() -> Result<()> {
 mod checkpointing_tests {
     use super::*;
     use ironbeam::checkpoint::{CheckpointConfig, CheckpointPolicy};
-    use ironbeam::runner::{ExecMode, Runner};
+    use ironbeam::runner::{DirectRunner, ExecMode};
+    #[cfg(feature = "coders")]
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
     use tempfile::TempDir;
 
     /// Test sequential execution with checkpointing enabled
@@ -771,12 +776,14 @@ mod checkpointing_tests {
             policy: CheckpointPolicy::EveryNNodes(2),
             auto_recover: false,
             max_checkpoints: Some(5),
+            ..Default::default()
         };
 
-        let runner = Runner {
+        let runner = DirectRunner {
             mode: ExecMode::Sequential,
             default_partitions: 4,
             checkpoint_config: Some(config),
+            ..DirectRunner::default()
         };
 
         let result = runner.run_collect::<(String, Vec<u32>)>(&p, mapped.node_id())?;
@@ -802,15 +809,17 @@ mod checkpointing_tests {
             policy: CheckpointPolicy::EveryNNodes(3),
             auto_recover: false,
             max_checkpoints: Some(10),
+            ..Default::default()
         };
 
-        let runner = Runner {
+        let runner = DirectRunner {
             mode: ExecMode::Parallel {
                 threads: Some(2),
                 partitions: Some(4),
             },
             default_partitions: 4,
             checkpoint_config: Some(config),
+            ..DirectRunner::default()
         };
 
         let result = runner.run_collect::<u32>(&p, pcoll.node_id())?;
@@ -835,12 +844,14 @@ mod checkpointing_tests {
             policy: CheckpointPolicy::EveryNNodes(1),
             auto_recover: true,
             max_checkpoints: Some(5),
+            ..Default::default()
         };
 
-        let runner = Runner {
+        let runner = DirectRunner {
             mode: ExecMode::Sequential,
             default_partitions: 4,
             checkpoint_config: Some(config.clone()),
+            ..DirectRunner::default()
         };
 
         let _result = runner.run_collect::<u32>(&p, pcoll.node_id())?;
@@ -850,10 +861,11 @@ mod checkpointing_tests {
         let data2: Vec<u32> = (1..=20).collect();
         let pcoll2 = from_vec(&p2, data2).map(|x: &u32| x + 1);
 
-        let runner2 = Runner {
+        let runner2 = DirectRunner {
             mode: ExecMode::Sequential,
             default_partitions: 4,
             checkpoint_config: Some(config),
+            ..DirectRunner::default()
         };
 
         let result2 = runner2.run_collect::<u32>(&p2, pcoll2.node_id())?;
@@ -885,12 +897,14 @@ mod checkpointing_tests {
             policy: CheckpointPolicy::AfterEveryBarrier,
             auto_recover: false,
             max_checkpoints: Some(5),
+            ..Default::default()
         };
 
-        let runner = Runner {
+        let runner = DirectRunner {
             mode: ExecMode::Sequential,
             default_partitions: 4,
             checkpoint_config: Some(config),
+            ..DirectRunner::default()
         };
 
         let result = runner.run_collect::<(String, u64)>(&p, combined.node_id())?;
@@ -915,12 +929,14 @@ mod checkpointing_tests {
             policy: CheckpointPolicy::AfterEveryBarrier,
             auto_recover: false,
             max_checkpoints: Some(5),
+            ..Default::default()
         };
 
-        let runner = Runner {
+        let runner = DirectRunner {
             mode: ExecMode::Sequential,
             default_partitions: 4,
             checkpoint_config: Some(config),
+            ..DirectRunner::default()
         };
 
         let result = runner.run_collect::<u64>(&p, combined.node_id())?;
@@ -946,21 +962,114 @@ mod checkpointing_tests {
             policy: CheckpointPolicy::EveryNNodes(2),
             auto_recover: false,
             max_checkpoints: Some(10),
+            ..Default::default()
         };
 
-        let runner = Runner {
+        let runner = DirectRunner {
             mode: ExecMode::Parallel {
                 threads: Some(4),
                 partitions: Some(8),
             },
             default_partitions: 8,
             checkpoint_config: Some(config),
+            ..DirectRunner::default()
         };
 
         let result = runner.run_collect::<(String, u64)>(&p, pcoll.node_id())?;
         assert_eq!(result.len(), 5);
         Ok(())
     }
+
+    /// Build `source -> map (counted) -> group_by_key -> map (fails while `fail` is set)`.
+    #[cfg(feature = "coders")]
+    fn resumable_pipeline(
+        calls: &'static AtomicUsize,
+        fail: &'static AtomicBool,
+    ) -> (TestPipeline, ironbeam::NodeId) {
+        let p = TestPipeline::new();
+        let sums = from_vec(&p, (1..=30u32).collect::<Vec<_>>())
+            .map(move |x: &u32| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                *x
+            })
+            .key_by(|x: &u32| format!("k{}", x % 3))
+            .group_by_key()
+            .map(move |(k, vs): &(String, Vec<u32>)| {
+                assert!(!fail.load(Ordering::SeqCst), "injected failure");
+                (k.clone(), vs.iter().sum::<u32>())
+            });
+        let id = sums.node_id();
+        (p, id)
+    }
+
+    #[cfg(feature = "coders")]
+    fn assert_resumes_after_barrier(
+        mode: ExecMode,
+        calls: &'static AtomicUsize,
+        fail: &'static AtomicBool,
+    ) -> Result<()> {
+        let temp_dir = TempDir::new()?;
+        let runner = DirectRunner {
+            mode,
+            checkpoint_config: Some(CheckpointConfig {
+                enabled: true,
+                directory: temp_dir.path().to_path_buf(),
+                policy: CheckpointPolicy::AfterEveryBarrier,
+                auto_recover: true,
+                max_checkpoints: Some(5),
+                ..Default::default()
+            }),
+            ..DirectRunner::default()
+        };
+
+        // First run: fails after the GroupByKey checkpoint was written.
+        fail.store(true, Ordering::SeqCst);
+        let (p, id) = resumable_pipeline(calls, fail);
+        assert!(runner.run_collect::<(String, u32)>(&p, id).is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 30);
+        assert_eq!(std::fs::read_dir(temp_dir.path())?.count(), 1);
+
+        // Second run: resumes from the stored barrier output.
+        fail.store(false, Ordering::SeqCst);
+        let (p, id) = resumable_pipeline(calls, fail);
+        let result = sorted(runner.run_collect::<(String, u32)>(&p, id)?);
+        assert_eq!(calls.load(Ordering::SeqCst), 30, "pre-barrier work reran");
+        assert_eq!(
+            result,
+            vec![
+                ("k0".to_string(), 165),
+                ("k1".to_string(), 145),
+                ("k2".to_string(), 155),
+            ]
+        );
+        assert_eq!(std::fs::read_dir(temp_dir.path())?.count(), 0);
+        Ok(())
+    }
+
+    /// `auto_recover` resumes after the last checkpointed barrier in sequential mode.
+    #[test]
+    #[cfg(feature = "coders")]
+    fn sequential_resumes_after_barrier() -> Result<()> {
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        static FAIL: AtomicBool = AtomicBool::new(false);
+        assert_resumes_after_barrier(ExecMode::Sequential, &CALLS, &FAIL)
+    }
+
+    /// `auto_recover` resumes after the last checkpointed barrier in parallel mode.
+    #[test]
+    #[cfg(feature = "coders")]
+    fn parallel_resumes_after_barrier() -> Result<()> {
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        static FAIL: AtomicBool = AtomicBool::new(false);
+        assert_resumes_after_barrier(
+            ExecMode::Parallel {
+                threads: None,
+                partitions: Some(4),
+            },
+            &CALLS,
+            &FAIL,
+        )
+    }
 }
 
 // ── run_subplan_par CombineGlobal arm coverage ──────────────────────────────
@@ -1038,3 +1147,30 @@ fn combine_global_non_ac_parallel() -> Result<()> {
     assert_eq!(result, vec![1275u64]); // sum 1..=50
     Ok(())
 }
+
+/// Each parallel run gets its own pool, so runs in one process can use different
+/// thread counts.
+#[test]
+fn parallel_runs_use_their_own_thread_counts() -> Result<()> {
+    for threads in [1, 3, 2] {
+        let p = TestPipeline::new();
+        let seen = from_vec(&p, (0u32..64).collect::<Vec<_>>()).map(|_: &u32| {
+            let name = std::thread::current().name().map(str::to_string);
+            (rayon::current_num_threads(), name)
+        });
+        let runner = DirectRunner {
+            mode: ExecMode::Parallel {
+                threads: Some(threads),
+                partitions: Some(4),
+            },
+            ..DirectRunner::default()
+        };
+        let out: Vec<(usize, Option<String>)> = runner.run_collect(&p, seen.node_id())?;
+        assert_eq!(out.len(), 64);
+        for (n, name) in out {
+            assert_eq!(n, threads);
+            assert!(name.is_some_and(|n| n.starts_with("ironbeam-worker-")));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,136 @@
+//! Tests for `RunnerConfig::builder`.
+
+use anyhow::Result;
+use ironbeam::*;
+use std::time::Duration;
+
+fn doubled(p: &Pipeline) -> PCollection<u32> {
+    from_vec(p, (0u32..100).collect::<Vec<_>>()).map(|x: &u32| x * 2)
+}
+
+#[cfg(feature = "spilling")]
+#[test]
+fn builder_sets_runner_fields() {
+    let config = RunnerConfig::builder()
+        .threads(3)
+        .partitions(7)
+        .default_partitions(5)
+        .memory_budget(1 << 20)
+        .max_memory_bytes(1 << 30)
+        .timeout(Duration::from_secs(30))
+        .build();
+    let runner = config.runner();
+    assert!(matches!(
+        runner.mode,
+        ExecMode::Parallel {
+            threads: Some(3),
+            partitions: Some(7)
+        }
+    ));
+    assert_eq!(runner.default_partitions, 5);
+    assert_eq!(runner.memory_budget, Some(1 << 20));
+    assert_eq!(runner.max_memory_bytes, Some(1 << 30));
+    assert_eq!(runner.timeout, Some(Duration::from_secs(30)));
+}
+
+#[cfg(all(feature = "spilling", feature = "coders"))]
+#[test]
+fn builder_memory_budget_spills_instead_of_failing() -> Result<()> {
+    let dir = tempfile::TempDir::new()?;
+    let p = Pipeline::default();
+    let pairs = (0u32..5_000).map(|i| (i % 37, i)).collect::<Vec<_>>();
+    let grouped = from_vec(&p, pairs).group_by_key();
+    let mut expected = grouped.clone().collect_seq()?;
+    expected.sort_unstable();
+
+    let spilling = RunnerConfig::builder()
+        .partitions(4)
+        .memory_budget(1_024)
+        .temp_dir(dir.path())
+        .build();
+    let mut out = grouped.clone().collect_with(&spilling)?;
+    out.iter_mut().for_each(|(_, vs)| vs.sort_unstable());
+    out.sort_unstable();
+    assert_eq!(out, expected);
+
+    let limited = RunnerConfig::builder()
+        .partitions(4)
+        .max_memory_bytes(1_024)
+        .build();
+    let err = grouped.collect_with(&limited).unwrap_err();
+    assert!(
+        matches!(
+            err.downcast_ref::<IronbeamError>(),
+            Some(IronbeamError::MemoryBudgetExceeded { .. })
+        ),
+        "{err:#}"
+    );
+    Ok(())
+}
+
+#[test]
+fn parallel_options_override_sequential_mode() {
+    let config = RunnerConfig::builder()
+        .mode(ExecMode::Sequential)
+        .partitions(4)
+        .build();
+    assert!(matches!(
+        config.runner().mode,
+        ExecMode::Parallel {
+            threads: None,
+            partitions: Some(4)
+        }
+    ));
+
+    let runner: DirectRunner = RunnerConfig::builder()
+        .threads(2)
+        .mode(ExecMode::Streaming)
+        .build()
+        .into();
+    assert!(matches!(runner.mode, ExecMode::Streaming));
+}
+
+#[test]
+fn collect_with_config_matches_collect_seq() -> Result<()> {
+    let p = Pipeline::default();
+    let expected = doubled(&p).collect_seq()?;
+    for config in [
+        RunnerConfig::builder().mode(ExecMode::Sequential).build(),
+        RunnerConfig::builder().partitions(4).build(),
+        RunnerConfig::builder().mode(ExecMode::Streaming).build(),
+    ] {
+        let mut out = doubled(&p).collect_with(&config)?;
+        out.sort_unstable();
+        assert_eq!(out, expected);
+    }
+    Ok(())
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn builder_installs_metrics_collector() -> Result<()> {
+    use ironbeam::metrics::MetricsCollector;
+
+    let metrics = MetricsCollector::new();
+    let config = RunnerConfig::builder()
+        .metrics(metrics.clone())
+        .mode(ExecMode::Sequential)
+        .build();
+    let p = Pipeline::default();
+    doubled(&p).collect_with(&config)?;
+    assert!(metrics.elapsed().is_some());
+    Ok(())
+}
+
+#[test]
+#[allow(deprecated)]
+fn deprecated_runner_alias_still_works() -> Result<()> {
+    let p = Pipeline::default();
+    let out = doubled(&p);
+    let runner = Runner {
+        mode: ExecMode::Sequential,
+        ..Runner::default()
+    };
+    assert_eq!(runner.run_collect::<u32>(&p, out.node_id())?.len(), 100);
+    Ok(())
+}
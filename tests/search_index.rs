@@ -0,0 +1,82 @@
+//! Tests for the `SearchIO` indexing sink.
+
+use anyhow::Result;
+use ironbeam::io::cloud::{FakeSearchIO, SearchIO};
+use ironbeam::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Product {
+    sku: String,
+    name: String,
+    price: u32,
+    tags: Vec<String>,
+    discount: Option<u32>,
+}
+
+fn product(i: u32) -> Product {
+    Product {
+        sku: format!("p-{i}"),
+        name: format!("Product {i}"),
+        price: i * 100,
+        tags: vec!["new".to_string()],
+        discount: None,
+    }
+}
+
+fn sku(p: &Product) -> String {
+    p.sku.clone()
+}
+
+#[test]
+fn documents_are_indexed_in_batches() -> Result<()> {
+    let search = FakeSearchIO::new();
+    let p = Pipeline::default();
+    let products = from_vec(&p, (0..25).map(product).collect::<Vec<_>>());
+
+    assert_eq!(
+        products.write_search_index(&search, "products", sku, 10)?,
+        25
+    );
+    assert_eq!(search.batch_index_sizes(), vec![10, 10, 5]);
+    assert!(search.get("products", "p-24")?.is_some());
+    Ok(())
+}
+
+#[test]
+fn fields_are_flattened_to_strings() -> Result<()> {
+    let search = FakeSearchIO::new();
+    let p = Pipeline::default();
+    let mut discounted = product(2);
+    discounted.discount = Some(15);
+    from_vec(&p, vec![product(1), discounted]).write_search_index(&search, "products", sku, 100)?;
+
+    let doc = search.get("products", "p-1")?.unwrap();
+    assert_eq!(doc["name"], "Product 1");
+    assert_eq!(doc["price"], "100");
+    assert_eq!(doc["tags"], r#"["new"]"#);
+    assert!(!doc.contains_key("discount"));
+    assert_eq!(search.get("products", "p-2")?.unwrap()["discount"], "15");
+    Ok(())
+}
+
+#[test]
+fn non_map_elements_are_rejected() {
+    let search = FakeSearchIO::new();
+    let p = Pipeline::default();
+    let err = from_vec(&p, vec![1u32, 2])
+        .write_search_index(&search, "numbers", u32::to_string, 10)
+        .unwrap_err();
+    let msg = format!("{err:#}");
+    assert!(msg.contains("element #0") && msg.contains("map"), "{msg}");
+    assert!(!search.index_exists("numbers").unwrap());
+}
+
+#[test]
+fn zero_batch_size_is_rejected() {
+    let p = Pipeline::default();
+    let err = from_vec(&p, vec![product(1)])
+        .write_search_index(&FakeSearchIO::new(), "products", sku, 0)
+        .unwrap_err();
+    assert!(err.to_string().contains("batch_size"), "{err}");
+}
@@ -0,0 +1,185 @@
+//! Tests for the HNSW index and `similarity_join`.
+
+use anyhow::Result;
+use ironbeam::hnsw::HnswIndex;
+use ironbeam::*;
+
+/// Deterministic pseudo-random vectors with components in `[-1, 1)`.
+fn random_vectors(n: usize, dim: usize, mut seed: u64) -> Vec<Vec<f32>> {
+    let mut next = || {
+        seed = seed
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        (seed >> 40) as f32 / (1u64 << 23) as f32 - 1.0
+    };
+    (0..n).map(|_| (0..dim).map(|_| next()).collect()).collect()
+}
+
+#[test]
+fn metrics_measure_distance() {
+    let (a, b) = ([1.0, 0.0], [0.0, 2.0]);
+    assert!((VectorMetric::Cosine.distance(&a, &b) - 1.0).abs() < 1e-6);
+    assert!(VectorMetric::Cosine.distance(&a, &[3.0, 0.0]).abs() < 1e-6);
+    assert!((VectorMetric::Cosine.distance(&a, &[-1.0, 0.0]) - 2.0).abs() < 1e-6);
+    assert!((VectorMetric::Cosine.distance(&a, &[0.0, 0.0]) - 1.0).abs() < 1e-6);
+    assert!((VectorMetric::Euclidean.distance(&a, &b) - 5f32.sqrt()).abs() < 1e-6);
+    assert!((VectorMetric::DotProduct.distance(&[1.0, 2.0], &[3.0, 4.0]) + 11.0).abs() < 1e-6);
+}
+
+#[test]
+#[should_panic(expected = "vector dimensions differ")]
+fn metrics_reject_mismatched_dimensions() {
+    let _ = VectorMetric::Euclidean.distance(&[1.0], &[1.0, 2.0]);
+}
+
+#[test]
+fn hnsw_search_is_exact_when_small_and_accurate_when_large() {
+    let mut small = HnswIndex::new(VectorMetric::Euclidean);
+    assert!(small.search(&[0.0], 3).is_empty());
+    for x in [5.0, 1.0, 3.0, 2.0] {
+        small.insert(x as u32, vec![x]);
+    }
+    let found: Vec<(u32, f32)> = small
+        .search(&[2.2], 3)
+        .into_iter()
+        .map(|(id, d)| (*id, d))
+        .collect();
+    assert_eq!(
+        found.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
+        vec![2, 3, 1]
+    );
+    assert!((found[0].1 - 0.2).abs() < 1e-6);
+    assert!(small.search(&[2.2], 0).is_empty());
+
+    let vectors = random_vectors(2_000, 8, 7);
+    let mut index = HnswIndex::new(VectorMetric::Euclidean);
+    for (i, v) in vectors.iter().enumerate() {
+        index.insert(i, v.clone());
+    }
+    assert_eq!(index.len(), 2_000);
+
+    let k = 10;
+    let mut hits = 0;
+    for query in random_vectors(50, 8, 99) {
+        let mut exact: Vec<(f32, usize)> = vectors
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (VectorMetric::Euclidean.distance(&query, v), i))
+            .collect();
+        exact.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let found = index.search(&query, k);
+        assert_eq!(found.len(), k);
+        assert!(found.windows(2).all(|w| w[0].1 <= w[1].1));
+        hits += exact[..k]
+            .iter()
+            .filter(|(_, i)| found.iter().any(|(id, _)| *id == i))
+            .count();
+    }
+    // At least 90% of the true neighbors are found.
+    assert!(
+        hits * 10 >= 9 * 50 * k,
+        "{hits} of {} neighbors found",
+        50 * k
+    );
+}
+
+#[test]
+fn similarity_join_pairs_each_element_with_its_nearest_neighbors() -> Result<()> {
+    let p = Pipeline::default();
+    let products = from_vec(
+        &p,
+        vec![
+            ("east".to_string(), vec![1.0f32, 0.0]),
+            ("north".to_string(), vec![0.0, 1.0]),
+        ],
+    );
+    let listings = from_vec(
+        &p,
+        vec![
+            (1u32, vec![0.9f32, 0.1]),
+            (2, vec![0.2, 0.8]),
+            (3, vec![-1.0, 0.1]),
+        ],
+    );
+
+    // Every listing is paired with its closest product.
+    let mut best = listings
+        .similarity_join(&products, 1, VectorMetric::Cosine)
+        .map(|(id, (product, _))| (*id, product.clone()))
+        .collect_seq()?;
+    best.sort();
+    assert_eq!(
+        best,
+        vec![
+            (1, "east".to_string()),
+            (2, "north".into()),
+            (3, "north".into())
+        ]
+    );
+
+    // Reversed, the product side is the smaller one, so it is indexed: every listing
+    // is still paired with its closest product, with the pairs oriented product first.
+    let mut reversed = products
+        .similarity_join(&listings, 1, VectorMetric::Euclidean)
+        .collect_seq()?;
+    reversed.sort_by_key(|(_, (listing, _))| *listing);
+    let ids: Vec<(String, u32)> = reversed.iter().map(|(p, (l, _))| (p.clone(), *l)).collect();
+    assert_eq!(
+        ids,
+        vec![
+            ("east".to_string(), 1),
+            ("north".into(), 2),
+            ("north".into(), 3)
+        ]
+    );
+    assert!((reversed[0].1.1 - 0.02f32.sqrt()).abs() < 1e-6);
+
+    // The same holds for a larger k: each element of the larger side gets k pairs.
+    assert_eq!(
+        products
+            .similarity_join(&listings, 5, VectorMetric::DotProduct)
+            .count_globally()
+            .collect_seq()?,
+        vec![6]
+    );
+
+    // k larger than the right side returns every pairing.
+    assert_eq!(
+        listings
+            .similarity_join(&products, 5, VectorMetric::DotProduct)
+            .count_globally()
+            .collect_seq()?,
+        vec![6]
+    );
+
+    let none = from_vec(&p, Vec::<(String, Vec<f32>)>::new());
+    assert!(
+        listings
+            .similarity_join(&none, 3, VectorMetric::Cosine)
+            .collect_seq()?
+            .is_empty()
+    );
+    Ok(())
+}
+
+#[test]
+fn similarity_self_join_finds_near_duplicates() -> Result<()> {
+    let mut docs: Vec<(usize, Vec<f32>)> =
+        random_vectors(400, 16, 3).into_iter().enumerate().collect();
+    // Document 400 is a slightly perturbed copy of document 123.
+    let mut copy = docs[123].1.clone();
+    copy[0] += 0.001;
+    docs.push((400, copy));
+
+    let p = Pipeline::default();
+    let docs = from_vec(&p, docs);
+    let mut duplicates = docs
+        .similarity_join(&docs, 2, VectorMetric::Cosine)
+        .filter(|(a, (b, distance))| a < b && *distance < 1e-3)
+        .map(|(a, (b, _))| (*a, *b))
+        .collect_seq()?;
+    duplicates.sort_unstable();
+    duplicates.dedup();
+    assert_eq!(duplicates, vec![(123, 400)]);
+    Ok(())
+}
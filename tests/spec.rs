@@ -0,0 +1,154 @@
+//! Tests for pipeline specs built through a `DoFnRegistry`.
+
+use anyhow::Result;
+use ironbeam::spec::{DoFnRegistry, PipelineSpec, SPEC_VERSION};
+use ironbeam::*;
+use serde_json::json;
+use tempfile::TempDir;
+
+fn registry() -> DoFnRegistry {
+    let mut registry = DoFnRegistry::new();
+    registry
+        .register_source("range", |p, cfg| {
+            let n = cfg["n"].as_u64().unwrap_or(0);
+            Ok(from_vec(p, (0..n).collect::<Vec<u64>>()))
+        })
+        .register_map("times", |cfg| {
+            let k = cfg.as_u64().unwrap_or(1);
+            Ok(move |x: &u64| x * k)
+        })
+        .register_filter("odd", |_| Ok(|x: &u64| x % 2 == 1))
+        .register_transform("sum_by_mod", |input: PCollection<u64>, cfg| {
+            let m = cfg["mod"].as_u64().unwrap_or(1);
+            Ok(input
+                .key_by(move |x: &u64| x % m)
+                .combine_values(Sum::<u64>::default()))
+        })
+        .register_binary(
+            "union",
+            |a: PCollection<(u64, u64)>, b: PCollection<(u64, u64)>, _| Ok(flatten(&[&a, &b])),
+        );
+    registry
+}
+
+fn build(registry: &DoFnRegistry) -> Result<(Pipeline, NodeId)> {
+    let p = Pipeline::default();
+    let nums = registry.source::<u64>(&p, "range", json!({ "n": 20 }))?;
+    let odd = registry
+        .apply::<u64, u64>(nums.clone(), "odd", ())?
+        .with_name("odd numbers");
+    let tripled = registry.apply::<u64, u64>(odd, "times", 3)?;
+    let left = registry.apply::<u64, (u64, u64)>(tripled, "sum_by_mod", json!({ "mod": 4 }))?;
+    let right = registry.apply::<u64, (u64, u64)>(nums, "sum_by_mod", json!({ "mod": 2 }))?;
+    let out = registry.apply_binary::<_, _, (u64, u64)>(left, right, "union", ())?;
+    Ok((p, out.node_id()))
+}
+
+fn run(p: &Pipeline, id: NodeId) -> Result<Vec<(u64, u64)>> {
+    let mut out = DirectRunner::default().run_collect::<(u64, u64)>(p, id)?;
+    out.sort_unstable();
+    Ok(out)
+}
+
+#[test]
+fn spec_round_trips_through_json_and_rebuilds() -> Result<()> {
+    let registry = registry();
+    let (p, terminal) = build(&registry)?;
+    let spec = p.spec()?;
+
+    assert_eq!(spec.version, SPEC_VERSION);
+    let transforms: Vec<&str> = spec.steps.iter().map(|s| s.transform.as_str()).collect();
+    assert_eq!(
+        transforms,
+        ["range", "odd", "times", "sum_by_mod", "sum_by_mod", "union"]
+    );
+    assert_eq!(spec.steps[1].name.as_deref(), Some("odd numbers"));
+    assert_eq!(spec.steps[3].kind, "CombineValues");
+    assert_eq!(spec.steps[5].inputs, vec![3, 4]);
+    assert_eq!(spec.steps[4].config, json!({ "mod": 2 }));
+
+    let reloaded = PipelineSpec::from_json(&spec.to_json()?)?;
+    assert_eq!(reloaded, spec);
+
+    let (p2, nodes) = Pipeline::from_spec(&reloaded, &registry)?;
+    assert_eq!(nodes.len(), spec.steps.len());
+    assert_eq!(p2.spec()?, spec);
+    assert_eq!(p2.node_name(nodes[1]).as_deref(), Some("odd numbers"));
+    assert_eq!(run(&p2, nodes[5])?, run(&p, terminal)?);
+    Ok(())
+}
+
+#[test]
+fn save_spec_writes_a_loadable_file() -> Result<()> {
+    let dir = TempDir::new()?;
+    let path = dir.path().join("pipeline.json");
+    let registry = registry();
+    let (p, _) = build(&registry)?;
+
+    p.save_spec(&path)?;
+    assert_eq!(PipelineSpec::load(&path)?, p.spec()?);
+    Ok(())
+}
+
+#[test]
+fn apply_checks_registration_and_types() -> Result<()> {
+    let registry = registry();
+    let p = Pipeline::default();
+    let nums = registry.source::<u64>(&p, "range", json!({ "n": 3 }))?;
+
+    let missing = registry.apply::<u64, u64>(nums.clone(), "nope", ());
+    assert!(
+        missing
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("not registered")
+    );
+
+    let wrong_output = registry.apply::<u64, String>(nums, "times", 2);
+    assert!(wrong_output.is_err());
+
+    let wrong_source = registry.source::<String>(&p, "range", json!({ "n": 3 }));
+    assert!(wrong_source.is_err());
+    Ok(())
+}
+
+#[test]
+fn spec_rejects_unregistered_inputs() -> Result<()> {
+    let registry = registry();
+    let p = Pipeline::default();
+    let doubled = from_vec(&p, vec![1u64, 2, 3]).map(|x: &u64| x * 2);
+    registry.apply::<u64, u64>(doubled, "odd", ())?;
+
+    let err = p.spec().err().unwrap();
+    assert!(err.to_string().contains("not built through a DoFnRegistry"));
+    Ok(())
+}
+
+#[test]
+fn from_spec_validates_the_spec() -> Result<()> {
+    let registry = registry();
+    let (p, _) = build(&registry)?;
+    let spec = p.spec()?;
+
+    let mut future = spec.clone();
+    future.version = SPEC_VERSION + 1;
+    assert!(Pipeline::from_spec(&future, &registry).is_err());
+
+    let mut unknown = spec.clone();
+    unknown.steps[2].transform = "missing".to_string();
+    assert!(Pipeline::from_spec(&unknown, &registry).is_err());
+
+    let mut forward = spec.clone();
+    forward.steps[1].inputs = vec![3];
+    assert!(Pipeline::from_spec(&forward, &registry).is_err());
+
+    // A registry whose "times" now builds a barrier no longer matches the spec.
+    let mut drifted = registry.clone();
+    drifted.register_transform("times", |input: PCollection<u64>, _| {
+        Ok(input.map(|x: &u64| x % 7).reshuffle())
+    });
+    let err = Pipeline::from_spec(&spec, &drifted).err().unwrap();
+    assert!(format!("{err:#}").contains("spec recorded Stateless"));
+    Ok(())
+}
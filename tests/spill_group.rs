@@ -0,0 +1,165 @@
+//! Tests for external (spill-to-disk) group-by-key selected by `DirectRunner::memory_budget`.
+
+#![cfg(all(feature = "spilling", feature = "coders"))]
+
+use anyhow::Result;
+use ironbeam::spill_group::{ExternalGroup, group_pairs_external};
+use ironbeam::*;
+use tempfile::TempDir;
+
+fn keyed_input(n: u32) -> Vec<(String, u32)> {
+    (0..n).map(|i| (format!("key-{}", i % 37), i)).collect()
+}
+
+fn budgeted(mode: ExecMode, budget: usize, dir: &TempDir) -> DirectRunner {
+    DirectRunner {
+        mode,
+        memory_budget: Some(budget),
+        spill_dir: Some(dir.path().to_path_buf()),
+        ..DirectRunner::default()
+    }
+}
+
+fn sorted_groups(mut groups: Vec<(String, Vec<u32>)>) -> Vec<(String, Vec<u32>)> {
+    for (_, vs) in &mut groups {
+        vs.sort_unstable();
+    }
+    groups.sort();
+    groups
+}
+
+#[test]
+fn tiny_budget_matches_in_memory_grouping() -> Result<()> {
+    let dir = TempDir::new()?;
+    let p = Pipeline::default();
+    let grouped = from_vec(&p, keyed_input(5_000)).group_by_key();
+
+    let expected = sorted_groups(grouped.clone().collect_seq()?);
+    for mode in [
+        ExecMode::Sequential,
+        ExecMode::Parallel {
+            threads: None,
+            partitions: Some(4),
+        },
+    ] {
+        let runner = budgeted(mode, 1_024, &dir);
+        let out = runner.run_collect::<(String, Vec<u32>)>(&p, grouped.node_id())?;
+        assert_eq!(sorted_groups(out), expected);
+    }
+    assert_eq!(std::fs::read_dir(dir.path())?.count(), 0);
+    Ok(())
+}
+
+#[test]
+fn spilled_sorted_groups_keep_value_order() -> Result<()> {
+    let dir = TempDir::new()?;
+    let p = Pipeline::default();
+    let grouped =
+        from_vec(&p, keyed_input(2_000)).group_by_key_sorted_by_key(|v| std::cmp::Reverse(*v));
+
+    let runner = budgeted(
+        ExecMode::Parallel {
+            threads: None,
+            partitions: Some(3),
+        },
+        512,
+        &dir,
+    );
+    let out = runner.run_collect::<(String, Vec<u32>)>(&p, grouped.node_id())?;
+    assert_eq!(out.len(), 37);
+    for (_, vs) in &out {
+        assert!(vs.windows(2).all(|w| w[0] > w[1]));
+    }
+    Ok(())
+}
+
+#[test]
+fn grouping_within_budget_does_not_spill() -> Result<()> {
+    let dir = TempDir::new()?;
+    let parts = vec![vec![(1u32, 'a'), (2, 'b')], vec![(1, 'c')]];
+    let mut groups = group_pairs_external(parts, usize::MAX, dir.path())?;
+    groups.sort_unstable();
+    assert_eq!(groups, vec![(1, vec!['a', 'c']), (2, vec!['b'])]);
+    assert_eq!(std::fs::read_dir(dir.path())?.count(), 0);
+    Ok(())
+}
+
+#[test]
+fn external_grouping_keeps_input_order_within_group() -> Result<()> {
+    let dir = TempDir::new()?;
+    let parts: Vec<Vec<(u32, u32)>> = (0..4u32)
+        .map(|p| (0..500u32).map(|i| (i % 11, p * 500 + i)).collect())
+        .collect();
+    let groups = group_pairs_external(parts, 256, dir.path())?;
+    assert_eq!(groups.len(), 11);
+    for (k, vs) in &groups {
+        assert!(vs.iter().all(|v| v % 500 % 11 == *k));
+        assert!(vs.windows(2).all(|w| w[0] < w[1]));
+    }
+    Ok(())
+}
+
+#[test]
+fn scattered_partitions_gather_whole_groups_in_input_order() -> Result<()> {
+    let dir = TempDir::new()?;
+    // Room for about one partition: the rest are spilled as they are scattered.
+    let group = ExternalGroup::new(8_192, dir.path());
+    let parts = (0..4u32)
+        .map(|p| group.scatter((0..500u32).map(|i| (i % 11, p * 500 + i)).collect()))
+        .collect::<Result<Vec<_>>>()?;
+    let groups = group.gather(parts)?;
+    assert_eq!(groups.len(), 11);
+    for (k, vs) in &groups {
+        assert_eq!(
+            vs.len(),
+            (0..2_000u32).filter(|v| v % 500 % 11 == *k).count()
+        );
+        assert!(vs.windows(2).all(|w| w[0] < w[1]));
+    }
+    assert_eq!(std::fs::read_dir(dir.path())?.count(), 0);
+    Ok(())
+}
+
+#[test]
+fn hot_key_over_budget_is_grouped_whole() -> Result<()> {
+    let dir = TempDir::new()?;
+    let group = ExternalGroup::new(64, dir.path());
+    let parts = (0..3u32)
+        .map(|p| {
+            let mut pairs: Vec<(u32, u32)> = (0..1_000).map(|i| (0, p * 1_000 + i)).collect();
+            pairs.push((p + 1, p));
+            group.scatter(pairs)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let mut groups = group.gather(parts)?;
+    groups.sort_unstable();
+    assert_eq!(groups[0].1, (0..3_000).collect::<Vec<u32>>());
+    assert_eq!(&groups[1..], [(1, vec![0]), (2, vec![1]), (3, vec![2])]);
+    Ok(())
+}
+
+#[test]
+fn spill_failure_is_reported_as_a_step_error() -> Result<()> {
+    let dir = TempDir::new()?;
+    let blocker = dir.path().join("not-a-dir");
+    std::fs::write(&blocker, b"")?;
+    let p = Pipeline::default();
+    let grouped = from_vec(&p, keyed_input(1_000)).group_by_key();
+
+    let runner = DirectRunner {
+        memory_budget: Some(16),
+        spill_dir: Some(blocker.join("spill")),
+        ..DirectRunner::default()
+    };
+    let err = runner
+        .run_collect::<(String, Vec<u32>)>(&p, grouped.node_id())
+        .unwrap_err();
+    match err.downcast_ref::<IronbeamError>() {
+        Some(IronbeamError::TransformFailed { node, message, .. }) => {
+            assert_eq!(node.kind, "GroupByKey");
+            assert!(message.contains("spill directory"), "{message}");
+        }
+        other => panic!("unexpected error: {other:?} ({err:#})"),
+    }
+    Ok(())
+}
@@ -0,0 +1,270 @@
+//! Tests for the SQL frontend, `Pipeline::sql`.
+#![cfg(feature = "sql")]
+
+use anyhow::Result;
+use ironbeam::sql::SqlTable;
+use ironbeam::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Sale {
+    region: String,
+    product: String,
+    units: u64,
+    price: f64,
+}
+
+fn sales(p: &Pipeline) -> PCollection<Sale> {
+    let sale = |region: &str, product: &str, units, price| Sale {
+        region: region.into(),
+        product: product.into(),
+        units,
+        price,
+    };
+    from_vec(
+        p,
+        vec![
+            sale("eu", "apple", 3, 1.5),
+            sale("eu", "pear", 1, 2.0),
+            sale("us", "apple", 4, 1.0),
+            sale("us", "plum", 0, 3.0),
+        ],
+    )
+}
+
+/// Collect `rows` as JSON objects, sorted by their text for comparison.
+fn sorted(rows: PCollection<Row>) -> Result<Vec<Value>> {
+    let mut out: Vec<Value> = rows
+        .collect_seq()?
+        .into_iter()
+        .map(Row::into_value)
+        .collect();
+    out.sort_by_key(Value::to_string);
+    Ok(out)
+}
+
+#[test]
+fn group_by_aggregates_keyed_collections() -> Result<()> {
+    let p = Pipeline::default();
+    let kv = from_vec(
+        &p,
+        vec![
+            ("a".to_string(), 1u64),
+            ("b".to_string(), 2),
+            ("a".to_string(), 3),
+        ],
+    );
+
+    let out = p.sql(
+        "SELECT key, SUM(value), COUNT(*) AS n FROM t GROUP BY key",
+        [("t", kv)],
+    )?;
+
+    assert_eq!(
+        sorted(out)?,
+        vec![
+            json!({"key": "b", "SUM(value)": 2, "n": 1}),
+            json!({"key": "a", "SUM(value)": 4, "n": 2}),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn projection_filter_and_arithmetic() -> Result<()> {
+    let p = Pipeline::default();
+    let out = p.sql(
+        "select product, units * price as revenue from sales \
+         where region = 'eu' and not units < 2 or product = 'plum'",
+        [("sales", sales(&p))],
+    )?;
+
+    assert_eq!(
+        sorted(out)?,
+        vec![
+            json!({"product": "apple", "revenue": 4.5}),
+            json!({"product": "plum", "revenue": 0.0}),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn aggregates_without_group_by_return_one_row() -> Result<()> {
+    let p = Pipeline::default();
+    let out = p.sql(
+        "SELECT COUNT(*) AS n, MIN(price) AS lo, MAX(product) AS hi, AVG(units) AS mean, \
+         SUM(units) / COUNT(*) AS per_sale FROM sales",
+        [("sales", sales(&p))],
+    )?;
+    assert_eq!(
+        out.collect_seq()?[0].clone().into_value(),
+        json!({"n": 4, "lo": 1.0, "hi": "plum", "mean": 2.0, "per_sale": 2})
+    );
+
+    let empty = p.sql(
+        "SELECT COUNT(*) AS n, SUM(units) AS total FROM sales WHERE units > 100",
+        [("sales", sales(&p))],
+    )?;
+    assert_eq!(
+        empty.collect_seq()?[0].clone().into_value(),
+        json!({"n": 0, "total": null})
+    );
+    Ok(())
+}
+
+#[test]
+fn inner_and_left_joins() -> Result<()> {
+    let p = Pipeline::default();
+    let regions = from_vec(
+        &p,
+        vec![
+            ("eu".to_string(), "Europe".to_string()),
+            ("apac".to_string(), "Asia".to_string()),
+        ],
+    );
+
+    let inner = p.sql(
+        "SELECT s.product, r.value AS name FROM sales s JOIN regions r ON s.region = r.key \
+         WHERE s.units > 1",
+        [
+            ("sales", SqlTable::from(sales(&p))),
+            ("regions", regions.clone().into()),
+        ],
+    )?;
+    assert_eq!(
+        sorted(inner)?,
+        vec![json!({"product": "apple", "name": "Europe"})]
+    );
+
+    let left = p.sql(
+        "SELECT r.key, COUNT(s.product) AS sold FROM regions r \
+         LEFT JOIN sales s ON r.key = s.region GROUP BY r.key",
+        [
+            ("sales", SqlTable::from(sales(&p))),
+            ("regions", regions.into()),
+        ],
+    )?;
+    assert_eq!(
+        sorted(left)?,
+        vec![
+            json!({"key": "apac", "sold": 0}),
+            json!({"key": "eu", "sold": 2})
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn null_semantics() -> Result<()> {
+    let p = Pipeline::default();
+    let rows = from_vec(
+        &p,
+        vec![
+            json!({"k": 1, "v": 10}),
+            json!({"k": null, "v": 20}),
+            json!({"k": 1}),
+        ],
+    );
+
+    let out = p.sql(
+        "SELECT COUNT(*) AS n, COUNT(v) AS with_v, SUM(v) AS total FROM t WHERE k = 1",
+        [("t", rows.clone())],
+    )?;
+    assert_eq!(
+        out.collect_seq()?[0].clone().into_value(),
+        json!({"n": 2, "with_v": 1, "total": 10})
+    );
+
+    let nulls = p.sql("SELECT v FROM t WHERE k IS NULL", [("t", rows)])?;
+    assert_eq!(sorted(nulls)?, vec![json!({"v": 20})]);
+    Ok(())
+}
+
+#[test]
+fn select_star_and_typed_results() -> Result<()> {
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Out {
+        product: String,
+        units: u64,
+    }
+
+    let p = Pipeline::default();
+    let out = p.sql(
+        "SELECT * FROM sales WHERE units >= 4",
+        [("sales", sales(&p))],
+    )?;
+    let rows = out.collect_seq()?;
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].len(), 4);
+    assert_eq!(
+        rows[0].deserialize::<Out>()?,
+        Out {
+            product: "apple".into(),
+            units: 4
+        }
+    );
+    Ok(())
+}
+
+#[test]
+fn planning_errors_are_reported() {
+    let p = Pipeline::default();
+    let err = |q: &str| {
+        p.sql(q, [("sales", sales(&p))])
+            .err()
+            .map(|e| e.to_string())
+            .unwrap_or_default()
+    };
+
+    assert!(err("SELECT FROM sales").contains("expected expression"));
+    assert!(err("SELECT units FROM missing").contains("unknown table missing"));
+    assert!(err("SELECT product, SUM(units) FROM sales").contains("must appear in GROUP BY"));
+    assert!(err("SELECT units FROM sales WHERE SUM(units) > 1").contains("not allowed in WHERE"));
+    assert!(err("SELECT x.units FROM sales").contains("unknown table x"));
+    assert!(err("SELECT units FROM sales ORDER BY units").contains("end of query"));
+    assert!(err("SELECT MEDIAN(units) FROM sales").contains("unknown function"));
+}
+
+#[test]
+fn evaluation_errors_fail_the_run() -> Result<()> {
+    let p = Pipeline::default();
+    let out = p.sql(
+        "SELECT product FROM sales WHERE product > 3",
+        [("sales", sales(&p))],
+    )?;
+    assert!(out.collect_seq().is_err());
+    Ok(())
+}
+
+#[test]
+fn rows_keep_column_order_through_json() -> Result<()> {
+    let row: Row = [("z", json!(1)), ("a", json!({"b": [true, null]}))]
+        .into_iter()
+        .collect();
+    let text = serde_json::to_string(&row)?;
+    assert_eq!(text, r#"{"z":1,"a":{"b":[true,null]}}"#);
+    assert_eq!(serde_json::from_str::<Row>(&text)?, row);
+    Ok(())
+}
+
+#[cfg(feature = "coders")]
+#[test]
+fn rows_round_trip_through_the_element_coder() -> Result<()> {
+    let rows = vec![
+        Row::from_serialize(&json!({"k": 1.5, "v": "x"}))?,
+        Row::from_serialize(&("key", json!(null)))?,
+    ];
+    let coder = PostcardCoder::<Row>::new();
+    let encoded = coder.encode_all(Box::new(rows.clone()))?;
+    let decoded: Vec<Row> = encoded
+        .iter()
+        .map(|bytes| {
+            let part = coder.decode_one(bytes).expect("decode_one");
+            part.downcast::<Vec<Row>>().expect("Vec<Row>").remove(0)
+        })
+        .collect();
+    assert_eq!(decoded, rows);
+    Ok(())
+}
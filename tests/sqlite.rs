@@ -0,0 +1,135 @@
+//! Tests for SQLite query sources and table sinks.
+#![cfg(feature = "io-sqlite")]
+
+use anyhow::Result;
+use ironbeam::io::cloud::{ConfigIO, ConfigResolver, FakeConfigIO};
+use ironbeam::*;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tempfile::tempdir;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Sale {
+    region: String,
+    cents: i64,
+    rate: f64,
+    note: Option<String>,
+    tags: Vec<String>,
+}
+
+fn sale(region: &str, cents: i64) -> Sale {
+    Sale {
+        region: region.into(),
+        cents,
+        rate: 0.5,
+        note: None,
+        tags: vec!["a".into()],
+    }
+}
+
+#[test]
+fn write_table_then_query_round_trips() -> Result<()> {
+    let dir = tempdir()?;
+    let db = dir.path().join("nested/sales.db");
+    let p = Pipeline::default();
+
+    let written = from_vec(&p, vec![sale("eu", 100), sale("us", 250), sale("eu", 40)])
+        .write_table(&db, "sales")?;
+    assert_eq!(written, 3);
+
+    let eu = read_sql_query::<Sale>(
+        &p,
+        &db,
+        "SELECT * FROM sales WHERE region = 'eu' ORDER BY cents",
+    )?
+    .collect_seq()?;
+    assert_eq!(eu, vec![sale("eu", 40), sale("eu", 100)]);
+    Ok(())
+}
+
+#[test]
+fn writes_append_and_create_columns_from_every_record() -> Result<()> {
+    let dir = tempdir()?;
+    let db = dir.path().join("kv.db");
+    let first = BTreeMap::from([("k".to_string(), serde_json::json!(1))]);
+    let second = BTreeMap::from([
+        ("k".to_string(), serde_json::json!(2)),
+        ("extra".to_string(), serde_json::json!("x")),
+    ]);
+    assert_eq!(write_table_vec(&db, "t", &[first, second])?, 2);
+    assert_eq!(write_table_vec(&db, "t", &[BTreeMap::from([("k", 3)])])?, 1);
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Row {
+        k: i64,
+        extra: Option<String>,
+    }
+    let rows: Vec<Row> = read_sql_query_vec(&db, "SELECT k, extra FROM t ORDER BY k")?;
+    assert_eq!(
+        rows,
+        vec![
+            Row { k: 1, extra: None },
+            Row {
+                k: 2,
+                extra: Some("x".into())
+            },
+            Row { k: 3, extra: None },
+        ]
+    );
+
+    #[derive(Deserialize)]
+    struct Count {
+        n: i64,
+    }
+    let types: Vec<Count> = read_sql_query_vec(
+        &db,
+        "SELECT count(*) AS n FROM pragma_table_info('t') WHERE type = 'INTEGER'",
+    )?;
+    assert_eq!(types[0].n, 1);
+    Ok(())
+}
+
+#[test]
+fn empty_writes_create_nothing() -> Result<()> {
+    let dir = tempdir()?;
+    let db = dir.path().join("empty.db");
+    assert_eq!(write_table_vec::<Sale>(&db, "sales", &[])?, 0);
+    assert!(!db.exists());
+    Ok(())
+}
+
+#[test]
+fn errors_are_reported() -> Result<()> {
+    let dir = tempdir()?;
+    let db = dir.path().join("e.db");
+
+    let err = write_table_vec(&db, "nums", &[1, 2]).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("record #0 does not serialize to a struct or map"),
+        "{err:#}"
+    );
+
+    let err = read_sql_query_vec::<Sale>(dir.path().join("missing.db"), "SELECT 1").unwrap_err();
+    assert!(format!("{err:#}").contains("missing.db"), "{err:#}");
+
+    write_table_vec(&db, "sales", &[sale("eu", 1)])?;
+    let err = read_sql_query_vec::<Sale>(&db, "SELECT region FROM sales").unwrap_err();
+    assert!(format!("{err:#}").contains("deserialize row #0"), "{err:#}");
+    Ok(())
+}
+
+#[test]
+fn paths_resolve_config_references() -> Result<()> {
+    let dir = tempdir()?;
+    let db = dir.path().join("cfg.db");
+    let config = FakeConfigIO::new();
+    config.set("db/path", db.to_str().unwrap(), false)?;
+    let p = Pipeline::default();
+    p.set_config_resolver(ConfigResolver::new(Arc::new(config)));
+
+    from_vec(&p, vec![sale("eu", 1)]).write_table("config://db/path", "sales")?;
+    let got = read_sql_query::<Sale>(&p, "config://db/path", "SELECT * FROM sales")?;
+    assert_eq!(got.collect_seq()?.len(), 1);
+    Ok(())
+}
@@ -0,0 +1,78 @@
+//! Tests for [`ExecMode::Streaming`] and lazy stateless execution.
+
+use anyhow::Result;
+use ironbeam::*;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[test]
+fn streaming_matches_sequential_for_stateless_chain() -> Result<()> {
+    let p = Pipeline::default();
+    let out = from_vec(&p, (0..500u32).collect::<Vec<_>>())
+        .map(|x| x * 3)
+        .filter(|x| x % 2 == 0)
+        .flat_map(|x| vec![*x, x + 1])
+        .take(100);
+
+    assert_eq!(out.clone().collect_streaming()?, out.collect_seq()?);
+    Ok(())
+}
+
+#[test]
+fn streaming_materializes_at_barriers() -> Result<()> {
+    let p = Pipeline::default();
+    let grouped = from_vec(&p, (0..100u32).collect::<Vec<_>>())
+        .map(|x| (x % 5, *x))
+        .filter_values(|v| v % 2 == 1)
+        .map_values(|v| v * 10)
+        .group_by_key()
+        .map_values(|vs| vs.iter().sum::<u32>());
+
+    let mut streamed = grouped.clone().collect_streaming()?;
+    streamed.sort_unstable();
+    assert_eq!(streamed, grouped.collect_seq_sorted()?);
+    Ok(())
+}
+
+#[test]
+fn streaming_pulls_only_what_take_needs() -> Result<()> {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&calls);
+    let p = Pipeline::default();
+    let out = from_vec(&p, (0..10_000u32).collect::<Vec<_>>())
+        .map(move |x| {
+            counter.fetch_add(1, Ordering::Relaxed);
+            x + 1
+        })
+        .take(3)
+        .collect_streaming()?;
+
+    assert_eq!(out, vec![1, 2, 3]);
+    assert_eq!(calls.load(Ordering::Relaxed), 3);
+    Ok(())
+}
+
+/// A custom op without a streaming override falls back to `apply`.
+struct Reverse;
+
+impl DynOp for Reverse {
+    fn apply(&self, input: Partition) -> Partition {
+        let mut v = *input
+            .downcast::<Vec<u32>>()
+            .expect("Reverse expects Vec<u32>");
+        v.reverse();
+        Box::new(v)
+    }
+}
+
+#[test]
+fn custom_ops_fall_back_to_eager_apply() -> Result<()> {
+    let p = Pipeline::default();
+    let out = from_vec(&p, vec![1u32, 2, 3, 4])
+        .map(|x| x * 2)
+        .apply_transform::<u32>(Arc::new(Reverse))
+        .filter(|x| *x > 2)
+        .collect_streaming()?;
+    assert_eq!(out, vec![8, 6, 4]);
+    Ok(())
+}
@@ -354,3 +354,50 @@ fn test_flatten_then_distinct() -> Result<()> {
     assert_eq!(result, vec![1, 2, 3, 4, 5]);
     Ok(())
 }
+
+/// `union` concatenates two collections, keeping duplicates.
+#[test]
+fn test_union_two_collections() -> Result<()> {
+    let p = Pipeline::default();
+    let a = from_vec(&p, vec![1, 2, 3]);
+    let b = from_vec(&p, vec![3, 4]);
+
+    let unioned = a.union(&b);
+    assert_eq!(unioned.clone().collect_seq_sorted()?, vec![1, 2, 3, 3, 4]);
+    let mut par = unioned.collect_par(Some(2), None)?;
+    par.sort_unstable();
+    assert_eq!(par, vec![1, 2, 3, 3, 4]);
+    Ok(())
+}
+
+/// Chained unions (with element-wise ops in between) splice into a single
+/// N-way Flatten instead of nesting subplans.
+#[test]
+fn test_chained_union_splices_into_one_flatten() -> Result<()> {
+    let p = Pipeline::default();
+    let a = from_vec(&p, vec![1u32, 2]);
+    let b = from_vec(&p, vec![3u32]);
+    let c = from_vec(&p, vec![40u32, 50]);
+
+    let unioned = a.union(&b).map(|x| x * 10).union(&c);
+
+    let plan = build_plan(&p, unioned.node_id())?;
+    let flatten_inputs: Vec<usize> = plan
+        .chain
+        .iter()
+        .filter_map(|n| match n {
+            ironbeam::node::Node::Flatten { chains, .. } => Some(chains.len()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(flatten_inputs, vec![3]);
+
+    assert_eq!(
+        unioned.clone().collect_seq_sorted()?,
+        vec![10, 20, 30, 40, 50]
+    );
+    let mut par = unioned.collect_par(Some(3), None)?;
+    par.sort_unstable();
+    assert_eq!(par, vec![10, 20, 30, 40, 50]);
+    Ok(())
+}
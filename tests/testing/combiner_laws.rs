@@ -0,0 +1,156 @@
+use anyhow::Result;
+use ironbeam::testing::combiner_laws::*;
+use ironbeam::*;
+
+/// Keeps the first value seen; merge keeps the left side, so it is not commutative.
+struct First;
+
+impl CombineFn<u32, Option<u32>, Option<u32>> for First {
+    fn create(&self) -> Option<u32> {
+        None
+    }
+    fn add_input(&self, acc: &mut Option<u32>, v: u32) {
+        acc.get_or_insert(v);
+    }
+    fn merge(&self, acc: &mut Option<u32>, other: Option<u32>) {
+        if acc.is_none() {
+            *acc = other;
+        }
+    }
+    fn finish(&self, acc: Option<u32>) -> Option<u32> {
+        acc
+    }
+    fn is_associative_commutative(&self) -> bool {
+        true
+    }
+}
+
+/// Starts every accumulator at one, so `create()` is not an identity.
+struct OffByOne;
+
+impl CombineFn<u32, u32, u32> for OffByOne {
+    fn create(&self) -> u32 {
+        1
+    }
+    fn add_input(&self, acc: &mut u32, v: u32) {
+        *acc += v;
+    }
+    fn merge(&self, acc: &mut u32, other: u32) {
+        *acc += other;
+    }
+    fn finish(&self, acc: u32) -> u32 {
+        acc
+    }
+}
+
+/// Counts groups; adding a group in pieces counts it twice.
+struct CountGroups;
+
+impl CombineFn<Vec<u32>, u32, u32> for CountGroups {
+    fn create(&self) -> u32 {
+        0
+    }
+    fn add_input(&self, acc: &mut u32, _group: Vec<u32>) {
+        *acc += 1;
+    }
+    fn merge(&self, acc: &mut u32, other: u32) {
+        *acc += other;
+    }
+    fn finish(&self, acc: u32) -> u32 {
+        acc
+    }
+}
+
+/// Sums the values of each group.
+struct SumGroups;
+
+impl CombineFn<Vec<u32>, u32, u32> for SumGroups {
+    fn create(&self) -> u32 {
+        0
+    }
+    fn add_input(&self, acc: &mut u32, group: Vec<u32>) {
+        *acc += group.iter().sum::<u32>();
+    }
+    fn merge(&self, acc: &mut u32, other: u32) {
+        *acc += other;
+    }
+    fn finish(&self, acc: u32) -> u32 {
+        acc
+    }
+    fn accepts_partial_groups(&self) -> bool {
+        true
+    }
+}
+
+fn parts() -> Vec<Vec<u32>> {
+    vec![vec![5, 1, 9], vec![], vec![7, 3], vec![2]]
+}
+
+#[test]
+fn builtin_combiners_obey_laws() -> Result<()> {
+    check_combiner_laws(&Sum::<u32>::new(), &parts())?;
+    check_combiner_laws(&Min::<u32>::new(), &parts())?;
+    check_combiner_laws(&Max::<u32>::new(), &parts())?;
+    check_combiner_laws(&Count, &parts())?;
+    Ok(())
+}
+
+#[test]
+fn empty_inputs_skip_finish() -> Result<()> {
+    // `Min::finish` panics on an empty accumulator; the checks must not call it.
+    let none: &[u32] = &[];
+    check_combiner_laws(&Min::<u32>::new(), &[vec![], vec![]] as &[Vec<u32>])?;
+    check_merge_associative(&Min::<u32>::new(), none, none, none)?;
+    Ok(())
+}
+
+#[test]
+fn non_commutative_merge_is_reported() {
+    let err = check_merge_commutative(&First, &[1], &[2]).unwrap_err();
+    assert!(err.to_string().contains("not commutative"), "{err}");
+    assert!(check_combiner_laws(&First, &[vec![1], vec![2]]).is_err());
+}
+
+#[test]
+fn non_identity_create_is_reported() {
+    let err = check_empty_identity(&OffByOne, &[1, 2]).unwrap_err();
+    assert!(err.to_string().contains("identity"), "{err}");
+}
+
+#[test]
+fn lifted_equivalence_catches_per_partition_state() {
+    let err = check_lifted_equivalence(&OffByOne, &[vec![1], vec![2]]).unwrap_err();
+    assert!(err.to_string().contains("partitioned combine"), "{err}");
+}
+
+#[test]
+fn partial_groups_law() -> Result<()> {
+    check_partial_groups(&SumGroups, &[1, 2], &[3])?;
+    assert!(check_partial_groups(&CountGroups, &[1, 2], &[3]).is_err());
+    Ok(())
+}
+
+#[cfg(feature = "proptest")]
+mod properties {
+    use super::SumGroups;
+    use ironbeam::testing::combiner_laws::*;
+    use ironbeam::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn sum_obeys_laws(parts in partitioned(0u64..1_000, 16, 6)) {
+            prop_assert!(check_combiner_laws(&Sum::<u64>::new(), &parts).is_ok());
+        }
+
+        #[test]
+        fn max_is_associative((a, b, c) in three_parts(any::<i32>(), 8)) {
+            prop_assert!(check_merge_associative(&Max::<i32>::new(), &a, &b, &c).is_ok());
+        }
+
+        #[test]
+        fn sum_groups_accepts_partial_groups((a, b, _c) in three_parts(0u32..100, 8)) {
+            prop_assert!(check_partial_groups(&SumGroups, &a, &b).is_ok());
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use anyhow::Result;
+use ironbeam::testing::*;
+use ironbeam::*;
+
+#[test]
+fn processing_time_follows_fake_clock() -> Result<()> {
+    let ctx = TestContext::new().with_start_time(5_000);
+    let p = ctx.pipeline();
+    let stamped = from_vec(&p, vec![1u32, 2, 3]).attach_processing_time();
+
+    let out = stamped.clone().collect_par(None, Some(3))?;
+    assert!(out.iter().all(|t| t.ts == 5_000));
+
+    ctx.clock().advance(250);
+    let out = stamped.collect_seq()?;
+    assert_eq!(out.iter().map(|t| t.ts).collect::<Vec<_>>(), vec![5_250; 3]);
+    Ok(())
+}
+
+#[test]
+fn ticking_clock_gives_distinct_times() -> Result<()> {
+    let ctx = TestContext::new().with_clock(FakeClock::with_tick(100, 10));
+    let p = ctx.pipeline();
+    let out = from_vec(&p, vec!["a".to_string(), "b".to_string(), "c".to_string()])
+        .attach_processing_time()
+        .collect_seq()?;
+    assert_eq!(
+        out.iter().map(|t| t.ts).collect::<Vec<_>>(),
+        vec![100, 110, 120]
+    );
+    assert_eq!(ctx.clock().peek(), 130);
+    Ok(())
+}
+
+#[test]
+fn processing_time_windows_are_deterministic() -> Result<()> {
+    let ctx = TestContext::new().with_start_time(12_345);
+    let p = ctx.pipeline();
+    let windows = from_vec(&p, vec!["x".to_string(), "y".to_string()])
+        .attach_processing_time()
+        .group_by_window(10_000, 0)
+        .collect_seq()?;
+    assert_eq!(windows.len(), 1);
+    assert_eq!(windows[0].0, Window::new(10_000, 20_000));
+    assert_eq!(windows[0].1.len(), 2);
+    Ok(())
+}
+
+#[test]
+fn sampling_uses_context_seed() -> Result<()> {
+    let sample = |ctx: &TestContext| -> Result<Vec<u32>> {
+        let p = ctx.pipeline();
+        let mut out = from_vec(&p, (0u32..1_000).collect::<Vec<_>>())
+            .sample_globally(10)
+            .collect_seq()?
+            .remove(0);
+        out.sort_unstable();
+        Ok(out)
+    };
+
+    let ctx = TestContext::new().with_seed(42);
+    assert_eq!(sample(&ctx)?, sample(&ctx)?);
+    assert_eq!(ctx.seed(), 42);
+
+    let p = TestPipeline::new();
+    let mut explicit = from_vec(&p, (0u32..1_000).collect::<Vec<_>>())
+        .sample_globally_with_seed(10, 42)
+        .collect_seq()?
+        .remove(0);
+    explicit.sort_unstable();
+    assert_eq!(sample(&ctx)?, explicit);
+    Ok(())
+}
+
+#[test]
+fn install_sets_clock_and_seed_on_existing_pipeline() {
+    let ctx = TestContext::new().with_seed(7).with_start_time(99);
+    let p = Pipeline::default();
+    ctx.install(&p);
+    assert_eq!(p.sample_seed(), 7);
+    assert_eq!(p.clock().now_ms(), 99);
+}
@@ -0,0 +1,100 @@
+use anyhow::Result;
+use ironbeam::extensions::CompositeTransform;
+use ironbeam::testing::*;
+use ironbeam::*;
+use std::fs::{read_to_string, write};
+
+struct WordCounts;
+
+impl CompositeTransform<String, (String, u64)> for WordCounts {
+    fn expand(&self, input: PCollection<String>) -> PCollection<(String, u64)> {
+        input
+            .flat_map(|line: &String| line.split_whitespace().map(str::to_string).collect())
+            .key_by(|w: &String| w.clone())
+            .count_per_key()
+    }
+}
+
+fn lines() -> Vec<String> {
+    vec![
+        "the quick brown fox".to_string(),
+        "the lazy dog".to_string(),
+        "the end".to_string(),
+    ]
+}
+
+#[test]
+fn tester_runs_closure_in_both_modes() -> Result<()> {
+    let out = TransformTester::new(|xs: PCollection<u32>| xs.map(|x: &u32| x * 3))
+        .input((1..=50).collect())
+        .partitions(7)
+        .run()?;
+    assert_eq!(out, (1..=50).map(|x| x * 3).collect::<Vec<u32>>());
+    Ok(())
+}
+
+#[test]
+fn tester_runs_composite_unordered() -> Result<()> {
+    let mut out = TransformTester::composite(WordCounts)
+        .input(lines())
+        .run()?;
+    out.sort();
+    assert_eq!(out.len(), 7);
+    assert_eq!(out[6], ("the".to_string(), 3));
+    Ok(())
+}
+
+#[test]
+fn tester_records_then_checks_golden_file() -> Result<()> {
+    let dir = TempDirPath::new()?;
+    let golden = dir.file_path("snapshots/word_counts.jsonl");
+
+    TransformTester::composite(WordCounts)
+        .input(lines())
+        .golden(&golden)
+        .run()?;
+    let recorded = read_to_string(&golden)?;
+    assert_eq!(recorded.lines().count(), 7);
+    assert!(recorded.contains(r#"["the",3]"#));
+
+    // A second run compares against the recorded snapshot.
+    TransformTester::composite(WordCounts)
+        .input(lines())
+        .golden(&golden)
+        .run()?;
+    Ok(())
+}
+
+#[test]
+fn tester_reports_golden_diff() -> Result<()> {
+    let dir = TempDirPath::new()?;
+    let golden = dir.file_path("evens.jsonl");
+    write(&golden, "2\n4\n5\n")?;
+
+    let err = TransformTester::new(|xs: PCollection<u32>| xs.filter(|x: &u32| x.is_multiple_of(2)))
+        .input(vec![1, 2, 3, 4, 6])
+        .ordered()
+        .golden(&golden)
+        .run()
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("differs from golden file"), "{err}");
+    assert!(err.contains("  2\n  4\n- 5\n+ 6\n"), "{err}");
+    Ok(())
+}
+
+#[test]
+fn tester_detects_partition_dependent_output() {
+    // Keeping only the first element of each partition depends on partitioning.
+    let err = TransformTester::new(|xs: PCollection<u32>| {
+        xs.map_batches(1_000_000, |batch: &[u32]| {
+            batch.iter().take(1).copied().collect()
+        })
+    })
+    .input((0..100).collect())
+    .partitions(4)
+    .run()
+    .unwrap_err()
+    .to_string();
+    assert!(err.contains("sequential and parallel"), "{err}");
+}
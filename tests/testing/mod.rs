@@ -1,5 +1,8 @@
 mod assertions;
 mod builders;
+mod combiner_laws;
+mod context;
 mod debug;
 mod fixtures;
+mod harness;
 mod mock_io;
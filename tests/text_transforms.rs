@@ -0,0 +1,63 @@
+//! Tests for the text preprocessing composites.
+
+use anyhow::Result;
+use ironbeam::transforms::text::{NGrams, NormalizeCase, RemoveStopwords, Tokenize};
+use ironbeam::*;
+
+#[test]
+fn word_count_pipeline() -> Result<()> {
+    let p = Pipeline::default();
+    let lines = from_vec(
+        &p,
+        vec![
+            "The cat sat on the mat.".to_string(),
+            "A cat, a HAT!".into(),
+        ],
+    );
+    let counts = lines
+        .apply_composite(&Tokenize::regex(r"\w+")?)
+        .apply_composite(&NormalizeCase::Lower)
+        .apply_composite(&RemoveStopwords::english())
+        .flat_map(|doc: &Vec<String>| doc.clone())
+        .count_per_element()
+        .collect_seq_sorted()?;
+    assert_eq!(
+        counts,
+        vec![
+            ("cat".to_string(), 2),
+            ("hat".into(), 1),
+            ("mat".into(), 1),
+            ("sat".into(), 1),
+        ]
+    );
+    assert!(Tokenize::regex("(").is_err());
+    Ok(())
+}
+
+#[test]
+fn tokens_stopwords_and_ngrams() -> Result<()> {
+    let p = Pipeline::default();
+    let docs = from_vec(&p, vec!["to be or  not to be".to_string(), "x".into()]);
+    let stop = side_vec(vec!["or".to_string(), "not".into()]);
+    let tokens = docs
+        .apply_composite(&Tokenize::whitespace().with_min_len(2))
+        .apply_composite(&RemoveStopwords::from_side(&stop));
+    assert_eq!(
+        tokens.clone().collect_seq()?,
+        vec![vec!["to", "be", "to", "be"], vec![]]
+    );
+
+    let bigrams = tokens
+        .clone()
+        .apply_composite(&NGrams::new(2).with_separator("_"))
+        .collect_seq()?;
+    assert_eq!(bigrams, vec![vec!["to_be", "be_to", "to_be"], vec![]]);
+    assert!(NGrams::new(5).ngrams(&["a".to_string()]).is_empty());
+
+    let upper = from_vec(&p, vec!["be".to_string(), "Or".into(), "or".into()])
+        .apply_composite(&RemoveStopwords::from_side(&stop))
+        .apply_composite(&NormalizeCase::Upper)
+        .collect_seq()?;
+    assert_eq!(upper, vec!["BE", "OR"]);
+    Ok(())
+}
@@ -0,0 +1,127 @@
+//! Tests for calendar-aware timestamp helpers.
+#![cfg(feature = "time")]
+
+use anyhow::Result;
+use ironbeam::helpers::time::{
+    TimeBucket, TimeUnit, TimestampFormat, parse_timestamp, truncate_timestamp,
+};
+use ironbeam::*;
+use std::time::Duration;
+
+// 2024-02-29T13:45:30.250Z, a Thursday.
+const LEAP_DAY: TimestampMs = 1_709_214_330_250;
+
+#[test]
+fn parses_every_format() -> Result<()> {
+    for (text, format) in [
+        ("2024-02-29T13:45:30.250Z", TimestampFormat::Rfc3339),
+        ("2024-02-29T15:45:30.250+02:00", TimestampFormat::Rfc3339),
+        ("1709214330.25", TimestampFormat::UnixSeconds),
+        ("1709214330250", TimestampFormat::UnixMillis),
+        (
+            "29/02/2024 13:45:30.250",
+            TimestampFormat::pattern("%d/%m/%Y %H:%M:%S%.3f"),
+        ),
+        (
+            "2024-02-29 14:45:30.250 +0100",
+            TimestampFormat::pattern("%Y-%m-%d %H:%M:%S%.3f %z"),
+        ),
+    ] {
+        assert_eq!(parse_timestamp(text, &format)?, LEAP_DAY, "{text}");
+    }
+    assert_eq!(
+        parse_timestamp("Thu, 29 Feb 2024 13:45:30 +0000", &TimestampFormat::Rfc2822)?,
+        LEAP_DAY - 250
+    );
+    assert_eq!(
+        parse_timestamp("2024-02-29", &TimestampFormat::pattern("%Y-%m-%d"))?,
+        truncate_timestamp(LEAP_DAY, TimeUnit::Day)
+    );
+
+    let err = parse_timestamp("1969-12-31T00:00:00Z", &TimestampFormat::Rfc3339).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("before the Unix epoch"),
+        "{err:#}"
+    );
+    let err = parse_timestamp("yesterday", &TimestampFormat::Rfc3339).unwrap_err();
+    assert!(format!("{err:#}").starts_with("parse \"yesterday\" as RFC 3339"));
+    Ok(())
+}
+
+#[test]
+fn truncates_and_buckets_by_calendar_units() {
+    let day = 1_709_164_800_000; // 2024-02-29T00:00:00Z
+    assert_eq!(
+        truncate_timestamp(LEAP_DAY, TimeUnit::Second),
+        LEAP_DAY - 250
+    );
+    assert_eq!(
+        truncate_timestamp(LEAP_DAY, TimeUnit::Hour),
+        day + 13 * 3_600_000
+    );
+    assert_eq!(truncate_timestamp(LEAP_DAY, TimeUnit::Day), day);
+    assert_eq!(
+        TimeUnit::Week.window(LEAP_DAY),
+        Window::new(day - 3 * 86_400_000, day + 4 * 86_400_000)
+    );
+    assert_eq!(
+        TimeUnit::Month.window(LEAP_DAY),
+        Window::new(1_706_745_600_000, day + 86_400_000)
+    );
+    assert_eq!(
+        TimeUnit::Year.window(LEAP_DAY),
+        Window::new(1_704_067_200_000, 1_735_689_600_000)
+    );
+    // 1970-01-01 was a Thursday; its week is clamped at the epoch.
+    assert_eq!(TimeUnit::Week.window(5), Window::new(0, 4 * 86_400_000));
+    assert_eq!(
+        TimeBucket::from(Duration::from_secs(90)).window(LEAP_DAY),
+        Window::tumble(LEAP_DAY, 90_000, 0)
+    );
+}
+
+#[test]
+fn collection_helpers_stamp_truncate_and_key() -> Result<()> {
+    let p = Pipeline::default();
+    let rows = from_vec(
+        &p,
+        vec![
+            ("2024-01-31T23:59:59Z".to_string(), 1u64),
+            ("2024-02-01T00:00:00Z".to_string(), 2),
+            ("2024-02-29T13:45:30Z".to_string(), 3),
+            ("not a time".to_string(), 4),
+        ],
+    );
+    let (stamped, bad) =
+        rows.parse_timestamp(TimestampFormat::Rfc3339, |r: &(String, u64)| r.0.as_str());
+
+    let bad = bad.collect_seq()?;
+    assert_eq!(bad.len(), 1);
+    assert_eq!(bad[0].element.1, 4);
+    assert!(bad[0].error.contains("as RFC 3339"), "{}", bad[0].error);
+
+    let mut days: Vec<TimestampMs> = stamped
+        .clone()
+        .truncate_to(TimeUnit::Day)
+        .collect_seq()?
+        .into_iter()
+        .map(|t| t.ts)
+        .collect();
+    days.sort_unstable();
+    assert_eq!(days[1], 1_706_745_600_000);
+
+    let mut per_month = stamped
+        .key_by_time_bucket(TimeUnit::Month)
+        .map_values(|r: &(String, u64)| r.1)
+        .sum_per_key()
+        .collect_seq()?;
+    per_month.sort_by_key(|(w, _)| w.start);
+    assert_eq!(
+        per_month
+            .iter()
+            .map(|(w, n)| (w.start, *n))
+            .collect::<Vec<_>>(),
+        vec![(1_704_067_200_000, 1), (1_706_745_600_000, 5)]
+    );
+    Ok(())
+}
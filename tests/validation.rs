@@ -560,3 +560,232 @@ fn test_multiple_field_errors() {
     assert!(errors.iter().any(|e| e.field == Some("age".to_string())));
     assert!(errors.iter().any(|e| e.field == Some("name".to_string())));
 }
+
+// ── Schema validation ───────────────────────────────────────────────────────
+
+fn schema_test_users() -> Vec<User> {
+    let user = |id, email: &str, age| User {
+        id,
+        email: email.into(),
+        age,
+    };
+    vec![
+        user(1, "alice@example.com", 30),
+        user(2, "invalid", 25),
+        user(3, "bob@example.com", -5),
+        user(4, "charlie@example.com", 40),
+    ]
+}
+
+fn user_schema() -> Schema {
+    Schema::new()
+        .field(FieldRule::new("id").required().of_type(FieldType::Integer))
+        .field(
+            FieldRule::new("email")
+                .required()
+                .pattern(r"^[^@\s]+@[^@\s]+$"),
+        )
+        .field(FieldRule::new("age").range(0.0, 150.0))
+}
+
+#[test]
+fn test_validate_schema_splits_structs() -> anyhow::Result<()> {
+    let p = Pipeline::default();
+    let (valid, violations) = from_vec(&p, schema_test_users()).validate_schema(&user_schema());
+
+    let mut ids: Vec<u32> = valid.collect_seq()?.iter().map(|u| u.id).collect();
+    ids.sort_unstable();
+    assert_eq!(ids, vec![1, 4]);
+
+    let mut violations = violations.collect_seq()?;
+    violations.sort_by(|a, b| a.field.cmp(&b.field));
+    assert_eq!(violations.len(), 2);
+    assert_eq!(violations[0].field, "age");
+    assert_eq!(violations[0].kind, ViolationKind::OutOfRange);
+    assert_eq!(violations[0].value, serde_json::json!(-5));
+    assert_eq!(violations[0].record["id"], serde_json::json!(3));
+    assert_eq!(violations[1].field, "email");
+    assert_eq!(violations[1].kind, ViolationKind::Pattern);
+    Ok(())
+}
+
+#[test]
+fn test_schema_nullability_types_and_membership() {
+    let schema = Schema::new()
+        .field(FieldRule::new("id").required())
+        .field(FieldRule::new("name").required().of_type(FieldType::String))
+        .field(FieldRule::new("tier").one_of(["gold", "silver"]))
+        .field(FieldRule::new("address.zip").pattern(r"^\d{5}$"));
+    let kinds = |record: serde_json::Value| {
+        schema
+            .check(&record)
+            .into_iter()
+            .map(|v| (v.field, v.kind))
+            .collect::<Vec<_>>()
+    };
+
+    assert!(kinds(serde_json::json!({"id": 1, "name": "a", "tier": null})).is_empty());
+    assert_eq!(
+        kinds(serde_json::json!({"name": null, "tier": "bronze", "address": {"zip": "1234"}})),
+        vec![
+            ("id".to_string(), ViolationKind::Missing),
+            ("name".to_string(), ViolationKind::Null),
+            ("tier".to_string(), ViolationKind::NotAllowed),
+            ("address.zip".to_string(), ViolationKind::Pattern),
+        ]
+    );
+    assert_eq!(
+        kinds(serde_json::json!({"id": 1, "name": 7, "address": {"zip": 12345}})),
+        vec![
+            ("name".to_string(), ViolationKind::Type),
+            ("address.zip".to_string(), ViolationKind::Type),
+        ]
+    );
+}
+
+#[test]
+fn test_validate_schema_on_rows() -> anyhow::Result<()> {
+    let p = Pipeline::default();
+    let rows = from_vec(
+        &p,
+        vec![
+            Row::from_serialize(&serde_json::json!({"id": 1, "email": "a@b.c"}))?,
+            Row::from_serialize(&serde_json::json!({"id": "x", "email": "a@b.c"}))?,
+        ],
+    );
+    let (valid, violations) = rows.validate_schema(&user_schema());
+    assert_eq!(valid.collect_seq()?.len(), 1);
+    let violations = violations.collect_seq()?;
+    assert_eq!(violations.len(), 1);
+    assert_eq!(violations[0].kind, ViolationKind::Type);
+    assert_eq!(
+        violations[0].to_string(),
+        r#"[id] Type: expected Integer (value: "x")"#
+    );
+    Ok(())
+}
+
+#[cfg(feature = "metrics")]
+#[test]
+fn test_validate_schema_records_metrics() -> anyhow::Result<()> {
+    use ironbeam::metrics::MetricsCollector;
+
+    let p = Pipeline::default();
+    p.set_metrics(MetricsCollector::new());
+    let (valid, _violations) = from_vec(&p, schema_test_users()).validate_schema(&user_schema());
+    valid.collect_par(Some(2), Some(2))?;
+
+    let report = p.get_metrics().unwrap().report();
+    let values = report.values();
+    assert_eq!(values["schema.records"], serde_json::json!(4));
+    assert_eq!(values["schema.invalid_records"], serde_json::json!(2));
+    assert_eq!(values["schema.violations"], serde_json::json!(2));
+    assert_eq!(values["schema.violations.email"], serde_json::json!(1));
+    assert_eq!(values["schema.violations.age"], serde_json::json!(1));
+    Ok(())
+}
+
+#[cfg(feature = "coders")]
+#[test]
+fn test_violation_round_trips_through_the_element_coder() {
+    let violations = user_schema().check(&User {
+        id: 9,
+        email: String::new(),
+        age: 200,
+    });
+    assert_eq!(violations.len(), 2);
+
+    let coder = PostcardCoder::<Violation>::new();
+    let encoded = coder.encode_all(Box::new(violations.clone())).unwrap();
+    let decoded: Vec<Violation> = encoded
+        .iter()
+        .map(|bytes| {
+            let part = coder.decode_one(bytes).unwrap();
+            part.downcast::<Vec<Violation>>().unwrap().remove(0)
+        })
+        .collect();
+    assert_eq!(decoded, violations);
+}
+
+// ───────────────────────────── Pre-flight checks ─────────────────────────────
+
+#[test]
+fn test_preflight_clean_pipeline_has_no_diagnostics() -> anyhow::Result<()> {
+    let p = Pipeline::default();
+    let counts = from_vec(&p, vec!["a".to_string(), "b".to_string(), "a".to_string()])
+        .key_by(Clone::clone)
+        .count_per_key()
+        .reshuffle();
+
+    assert!(p.validate().is_empty());
+    assert!(build_plan(&p, counts.node_id())?.diagnostics.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_preflight_empty_pipeline() {
+    let p = Pipeline::default();
+    let diagnostics = p.validate();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].kind, DiagnosticKind::EmptyPipeline);
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+
+    let err = DirectRunner::default()
+        .run_collect::<u32>(&p, NodeId::new(0))
+        .expect_err("an empty pipeline cannot run");
+    assert!(err.to_string().contains("validation failed"), "{err}");
+}
+
+#[test]
+fn test_preflight_warns_on_regrouped_expansion() -> anyhow::Result<()> {
+    let p = Pipeline::default();
+    let flattened = from_vec(&p, vec![("k".to_string(), 1u32), ("k".to_string(), 2)])
+        .group_by_key()
+        .with_name("group")
+        .flat_map(|(k, vs): &(String, Vec<u32>)| {
+            vs.iter().map(|v| (k.clone(), *v)).collect::<Vec<_>>()
+        });
+
+    let diagnostics = p.validate();
+    assert_eq!(diagnostics.len(), 1);
+    let warning = &diagnostics[0];
+    assert_eq!(warning.kind, DiagnosticKind::RegroupedExpansion);
+    assert_eq!(warning.node, Some(flattened.node_id()));
+    assert!(warning.message.contains("`group`"), "{}", warning.message);
+    assert!(warning.to_string().starts_with("warning: "));
+
+    // Warnings do not block execution; they travel with the plan.
+    let plan = build_plan(&p, flattened.node_id())?;
+    assert_eq!(plan.diagnostics, diagnostics);
+    assert_eq!(flattened.collect_seq()?.len(), 2);
+    Ok(())
+}
+
+#[cfg(feature = "coders")]
+#[test]
+fn test_preflight_rejects_type_mismatch() {
+    use std::sync::Arc;
+
+    let p = Pipeline::default();
+    let numbers = from_vec(&p, vec![3u64, 1, 2]);
+    let good = numbers.clone().map(|x| x + 1);
+    let shuffled = numbers.reshuffle();
+    p.set_coder_override(shuffled.node_id(), Arc::new(PostcardCoder::<String>::new()));
+
+    let diagnostics = p.validate();
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].kind, DiagnosticKind::TypeMismatch);
+    assert!(diagnostics[0].is_error());
+    assert!(diagnostics[0].message.contains("alloc::string::String"));
+
+    let err = shuffled
+        .collect_seq()
+        .expect_err("mismatch blocks planning");
+    assert!(
+        err.to_string()
+            .contains("passes elements through unchanged"),
+        "{err}"
+    );
+    // Only the subgraph feeding the executed collection is checked.
+    assert_eq!(good.collect_seq_sorted().unwrap(), vec![2, 3, 4]);
+}
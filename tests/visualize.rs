@@ -0,0 +1,77 @@
+//! Tests for DOT and Mermaid export of pipelines and plans.
+
+use anyhow::Result;
+use ironbeam::*;
+
+fn word_count(p: &Pipeline) -> PCollection<(String, u64)> {
+    from_vec(p, vec!["a b".to_string(), "b c".to_string()])
+        .flat_map(|l: &String| l.split_whitespace().map(String::from).collect())
+        .with_name("Split")
+        .key_by(|w: &String| w.clone())
+        .map_values(|_| 1u64)
+        .with_name("One")
+        .group_by_key()
+        .map_values(|vs: &Vec<u64>| vs.iter().sum::<u64>())
+}
+
+#[test]
+fn pipeline_dot_lists_every_node_and_edge() {
+    let p = Pipeline::default();
+    let _ = word_count(&p);
+    let dot = p.to_dot();
+
+    assert!(dot.starts_with("digraph pipeline {"));
+    assert!(dot.trim_end().ends_with('}'));
+    let (nodes, edges) = p.snapshot();
+    assert_eq!(dot.matches("[label=").count(), nodes.len());
+    assert_eq!(dot.matches(" -> ").count(), edges.len());
+    assert!(dot.contains("Stateless (1 op)\\nSplit"));
+    assert!(dot.contains("GroupByKey\", style=\"filled,bold\""));
+}
+
+#[test]
+fn plan_dot_shows_fused_stages() -> Result<()> {
+    let p = Pipeline::default();
+    let out = word_count(&p);
+    let plan = build_plan(&p, out.node_id())?;
+    let dot = plan.to_dot();
+
+    assert!(dot.starts_with("digraph plan {"));
+    assert!(dot.contains("(3 ops fused)\\nSplit + One"));
+    assert_eq!(dot.matches(" -> ").count(), plan.chain.len() - 1);
+    Ok(())
+}
+
+#[test]
+fn mermaid_marks_barriers_and_escapes_labels() -> Result<()> {
+    let p = Pipeline::default();
+    let out = from_vec(&p, vec![(1u32, 2u32)])
+        .group_by_key()
+        .with_name("say \"hi\"");
+    let mermaid = build_plan(&p, out.node_id())?.to_mermaid();
+
+    assert!(mermaid.starts_with("flowchart TD\n"));
+    assert!(mermaid.contains(":::barrier"));
+    assert!(mermaid.contains("classDef barrier"));
+    assert!(mermaid.contains("say #quot;hi#quot;"));
+    assert!(!p.to_mermaid().contains("\"hi\""));
+    Ok(())
+}
+
+#[test]
+fn plan_export_draws_join_inputs_as_clusters() -> Result<()> {
+    let p = Pipeline::default();
+    let left = from_vec(&p, vec![(1u32, "a".to_string())]);
+    let right = from_vec(&p, vec![(1u32, 10u64)]).filter(|(_, v)| *v > 0);
+    let joined = left.join_inner(&right);
+    let plan = build_plan(&p, joined.node_id())?;
+
+    let dot = plan.to_dot();
+    assert_eq!(dot.matches("subgraph cluster_").count(), 2);
+    assert!(dot.contains("label=\"input 1\""));
+
+    let mermaid = plan.to_mermaid();
+    assert_eq!(mermaid.matches("subgraph ").count(), 2);
+    assert_eq!(mermaid.lines().filter(|l| l.trim() == "end").count(), 2);
+    Ok(())
+}